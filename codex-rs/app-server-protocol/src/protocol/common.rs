@@ -105,6 +105,10 @@ client_request_definitions! {
         params: v2::ThreadStartParams,
         response: v2::ThreadStartResponse,
     },
+    ThreadStartFromTemplate => "thread/startFromTemplate" {
+        params: v2::ThreadStartFromTemplateParams,
+        response: v2::ThreadStartResponse,
+    },
     ThreadResume => "thread/resume" {
         params: v2::ThreadResumeParams,
         response: v2::ThreadResumeResponse,
@@ -117,10 +121,32 @@ client_request_definitions! {
         params: v2::ThreadArchiveParams,
         response: v2::ThreadArchiveResponse,
     },
+    ThreadUnarchive => "thread/unarchive" {
+        params: v2::ThreadUnarchiveParams,
+        response: v2::ThreadUnarchiveResponse,
+    },
+    ThreadMetadataUpdate => "thread/metadata/update" {
+        params: v2::ThreadMetadataUpdateParams,
+        response: v2::ThreadMetadataUpdateResponse,
+    },
     ThreadRollback => "thread/rollback" {
         params: v2::ThreadRollbackParams,
         response: v2::ThreadRollbackResponse,
     },
+    ThreadTrim => "thread/trim" {
+        params: v2::ThreadTrimParams,
+        response: v2::ThreadTrimResponse,
+    },
+    /// Merge the outcome of another thread into a loaded thread.
+    ThreadMerge => "thread/merge" {
+        params: v2::ThreadMergeParams,
+        response: v2::ThreadMergeResponse,
+    },
+    /// Switch a feature of a loaded thread for the rest of the conversation.
+    ThreadFeatureSet => "thread/feature/set" {
+        params: v2::ThreadFeatureSetParams,
+        response: v2::ThreadFeatureSetResponse,
+    },
     ThreadList => "thread/list" {
         params: v2::ThreadListParams,
         response: v2::ThreadListResponse,
@@ -133,10 +159,29 @@ client_request_definitions! {
         params: v2::ThreadReadParams,
         response: v2::ThreadReadResponse,
     },
+    /// Diff the environment recorded when a thread was created against the current one.
+    ThreadEnvironmentDiff => "thread/environment/diff" {
+        params: v2::ThreadEnvironmentDiffParams,
+        response: v2::ThreadEnvironmentDiffResponse,
+    },
+    /// Split the workspace changes of two threads over the same repository by thread.
+    ThreadWorkspaceDiff => "thread/workspace/diff" {
+        params: v2::ThreadWorkspaceDiffParams,
+        response: v2::ThreadWorkspaceDiffResponse,
+    },
     SkillsList => "skills/list" {
         params: v2::SkillsListParams,
         response: v2::SkillsListResponse,
     },
+    /// Resolve the AGENTS.md files that apply to a working directory.
+    InstructionsRead => "instructions/read" {
+        params: v2::InstructionsReadParams,
+        response: v2::InstructionsReadResponse,
+    },
+    TemplateList => "template/list" {
+        params: v2::TemplateListParams,
+        response: v2::TemplateListResponse,
+    },
     AppsList => "app/list" {
         params: v2::AppsListParams,
         response: v2::AppsListResponse,
@@ -153,6 +198,26 @@ client_request_definitions! {
         params: v2::TurnInterruptParams,
         response: v2::TurnInterruptResponse,
     },
+    /// Return what was sent to the model for a turn of a loaded thread.
+    TurnContextRead => "turn/context/read" {
+        params: v2::TurnContextReadParams,
+        response: v2::TurnContextReadResponse,
+    },
+    /// List the files tool calls returned in a thread.
+    ArtifactList => "artifact/list" {
+        params: v2::ArtifactListParams,
+        response: v2::ArtifactListResponse,
+    },
+    /// Copy all artifacts of a thread into a directory.
+    ArtifactExport => "artifact/export" {
+        params: v2::ArtifactExportParams,
+        response: v2::ArtifactExportResponse,
+    },
+    /// Download a file a tool call returned in a thread.
+    ArtifactDownload => "artifact/download" {
+        params: v2::ArtifactDownloadParams,
+        response: v2::ArtifactDownloadResponse,
+    },
     ReviewStart => "review/start" {
         params: v2::ReviewStartParams,
         response: v2::ReviewStartResponse,
@@ -227,6 +292,46 @@ client_request_definitions! {
         response: v2::ConfigWriteResponse,
     },
 
+    SandboxPolicyRead => "sandbox/policy/read" {
+        params: v2::SandboxPolicyReadParams,
+        response: v2::SandboxPolicyReadResponse,
+    },
+
+    SandboxPolicyEvaluate => "sandbox/policy/evaluate" {
+        params: v2::SandboxPolicyEvaluateParams,
+        response: v2::SandboxPolicyEvaluateResponse,
+    },
+
+    StatsRead => "stats/read" {
+        params: v2::StatsReadParams,
+        response: v2::StatsReadResponse,
+    },
+
+    ChangesQuery => "changes/query" {
+        params: v2::ChangesQueryParams,
+        response: v2::ChangesQueryResponse,
+    },
+
+    ProvenanceExport => "provenance/export" {
+        params: v2::ProvenanceExportParams,
+        response: v2::ProvenanceExportResponse,
+    },
+
+    ApprovalsList => "approvals/list" {
+        params: v2::ApprovalsListParams,
+        response: v2::ApprovalsListResponse,
+    },
+
+    ApprovalsRevoke => "approvals/revoke" {
+        params: v2::ApprovalsRevokeParams,
+        response: v2::ApprovalsRevokeResponse,
+    },
+
+    DoctorRun => "doctor/run" {
+        params: #[ts(type = "undefined")] #[serde(skip_serializing_if = "Option::is_none")] Option<()>,
+        response: v2::DoctorRunResponse,
+    },
+
     ConfigRequirementsRead => "configRequirements/read" {
         params: #[ts(type = "undefined")] #[serde(skip_serializing_if = "Option::is_none")] Option<()>,
         response: v2::ConfigRequirementsReadResponse,
@@ -569,8 +674,13 @@ server_notification_definitions! {
     Error => "error" (v2::ErrorNotification),
     ThreadStarted => "thread/started" (v2::ThreadStartedNotification),
     ThreadTokenUsageUpdated => "thread/tokenUsage/updated" (v2::ThreadTokenUsageUpdatedNotification),
+    ThreadTokenForecast => "thread/tokenUsage/forecast" (v2::ThreadTokenForecastNotification),
+    ThreadResumeContext => "thread/resumeContext" (v2::ThreadResumeContextNotification),
+    ThreadModelSwitched => "thread/modelSwitched" (v2::ThreadModelSwitchedNotification),
     TurnStarted => "turn/started" (v2::TurnStartedNotification),
     TurnCompleted => "turn/completed" (v2::TurnCompletedNotification),
+    TurnInterrupted => "turn/interrupted" (v2::TurnInterruptedNotification),
+    TurnHeartbeat => "turn/heartbeat" (v2::TurnHeartbeatNotification),
     TurnDiffUpdated => "turn/diff/updated" (v2::TurnDiffUpdatedNotification),
     TurnPlanUpdated => "turn/plan/updated" (v2::TurnPlanUpdatedNotification),
     ItemStarted => "item/started" (v2::ItemStartedNotification),
@@ -582,6 +692,7 @@ server_notification_definitions! {
     TerminalInteraction => "item/commandExecution/terminalInteraction" (v2::TerminalInteractionNotification),
     FileChangeOutputDelta => "item/fileChange/outputDelta" (v2::FileChangeOutputDeltaNotification),
     McpToolCallProgress => "item/mcpToolCall/progress" (v2::McpToolCallProgressNotification),
+    McpToolCallArtifact => "item/mcpToolCall/artifact" (v2::McpToolCallArtifactNotification),
     McpServerOauthLoginCompleted => "mcpServer/oauthLogin/completed" (v2::McpServerOauthLoginCompletedNotification),
     AccountUpdated => "account/updated" (v2::AccountUpdatedNotification),
     AccountRateLimitsUpdated => "account/rateLimits/updated" (v2::AccountRateLimitsUpdatedNotification),
@@ -663,6 +774,58 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn initialize_from_a_client_without_protocol_versions() -> Result<()> {
+        // What clients built before the capability handshake send.
+        let request: ClientRequest = serde_json::from_value(json!({
+            "method": "initialize",
+            "id": 1,
+            "params": {
+                "clientInfo": { "name": "codex_vscode", "title": null, "version": "0.1.0" }
+            }
+        }))?;
+        let ClientRequest::Initialize { params, .. } = request else {
+            anyhow::bail!("expected initialize, got {request:?}");
+        };
+        assert_eq!(params.protocol_versions, None);
+        assert_eq!(v1::negotiate_protocol_version(None), Some("v2"));
+        Ok(())
+    }
+
+    #[test]
+    fn negotiate_protocol_version_prefers_the_clients_order() {
+        let versions = |versions: &[&str]| -> Vec<String> {
+            versions.iter().map(ToString::to_string).collect()
+        };
+        assert_eq!(
+            v1::negotiate_protocol_version(Some(&versions(&["v3", "v1", "v2"]))),
+            Some("v1")
+        );
+        assert_eq!(
+            v1::negotiate_protocol_version(Some(&versions(&["v3"]))),
+            None
+        );
+    }
+
+    #[test]
+    fn initialize_response_from_an_older_server_deserializes() -> Result<()> {
+        let response: v1::InitializeResponse =
+            serde_json::from_value(json!({ "userAgent": "codex_vscode/0.1.0" }))?;
+        assert_eq!(
+            response,
+            v1::InitializeResponse {
+                user_agent: "codex_vscode/0.1.0".to_string(),
+                server_version: String::new(),
+                protocol_versions: Vec::new(),
+                protocol_version: String::new(),
+                enabled_features: Vec::new(),
+                tools: Vec::new(),
+                auth: v1::InitializeAuthState::default(),
+            }
+        );
+        Ok(())
+    }
+
     #[test]
     fn conversation_id_serializes_as_plain_string() -> Result<()> {
         let id = ThreadId::from_string("67e55044-10b1-426f-9247-bb680e5fe0c8")?;
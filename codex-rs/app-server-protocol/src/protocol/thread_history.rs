@@ -3,6 +3,7 @@ use crate::protocol::v2::Turn;
 use crate::protocol::v2::TurnError;
 use crate::protocol::v2::TurnStatus;
 use crate::protocol::v2::UserInput;
+use codex_protocol::protocol::AgentMessageEvent;
 use codex_protocol::protocol::AgentReasoningEvent;
 use codex_protocol::protocol::AgentReasoningRawContentEvent;
 use codex_protocol::protocol::EventMsg;
@@ -50,7 +51,7 @@ impl ThreadHistoryBuilder {
     fn handle_event(&mut self, event: &EventMsg) {
         match event {
             EventMsg::UserMessage(payload) => self.handle_user_message(payload),
-            EventMsg::AgentMessage(payload) => self.handle_agent_message(payload.message.clone()),
+            EventMsg::AgentMessage(payload) => self.handle_agent_message(payload),
             EventMsg::AgentReasoning(payload) => self.handle_agent_reasoning(payload),
             EventMsg::AgentReasoningRawContent(payload) => {
                 self.handle_agent_reasoning_raw_content(payload)
@@ -59,7 +60,28 @@ impl ThreadHistoryBuilder {
             EventMsg::EnteredReviewMode(_) => {}
             EventMsg::ExitedReviewMode(_) => {}
             EventMsg::ThreadRolledBack(payload) => self.handle_thread_rollback(payload),
+            // The trimmed turns are gone from the rollout; nothing to rebuild.
+            EventMsg::TurnsTrimmed(_) => {}
             EventMsg::UndoCompleted(_) => {}
+            // Artifacts are looked up by id through `artifact/download`.
+            EventMsg::ToolArtifact(_) => {}
+            // Clips live outside the transcript.
+            EventMsg::ClipStored(_) => {}
+            // Feature changes affect later turns, not the transcript.
+            EventMsg::FeatureOverridden(_) => {}
+            // The snippet is already part of the `repl` function call.
+            EventMsg::ReplSnippet(_) => {}
+            // Findings are pinned context for later turns, not a transcript item.
+            EventMsg::ExplorationFindings(_) | EventMsg::ExplorationFindingsCleared => {}
+            // Snippets are context for the model, not a transcript item.
+            EventMsg::KnowledgeInjected(_) => {}
+            // Merged outcomes are context for the model, not a transcript item.
+            EventMsg::ConversationMerged(_) => {}
+            // Diffs are kept so the conversation can be merged into another one.
+            EventMsg::TurnDiff(_) => {}
+            // Resume-time settings changes are not part of any turn.
+            EventMsg::ResumeContext(_) => {}
+            EventMsg::ModelSwitch(_) => {}
             EventMsg::TurnAborted(payload) => self.handle_turn_aborted(payload),
             _ => {}
         }
@@ -68,21 +90,31 @@ impl ThreadHistoryBuilder {
     fn handle_user_message(&mut self, payload: &UserMessageEvent) {
         self.finish_current_turn();
         let mut turn = self.new_turn();
-        let id = self.next_item_id();
+        let id = self.item_id(payload.item_id.as_ref());
         let content = self.build_user_inputs(payload);
         turn.items.push(ThreadItem::UserMessage { id, content });
         self.current_turn = Some(turn);
     }
 
-    fn handle_agent_message(&mut self, text: String) {
-        if text.is_empty() {
+    fn handle_agent_message(&mut self, payload: &AgentMessageEvent) {
+        if payload.message.is_empty() {
             return;
         }
 
-        let id = self.next_item_id();
-        self.ensure_turn()
-            .items
-            .push(ThreadItem::AgentMessage { id, text });
+        // An agent message item with several content parts is recorded as one
+        // event per part.
+        if let Some(ThreadItem::AgentMessage { id, text }) = self.ensure_turn().items.last_mut()
+            && payload.item_id.as_deref() == Some(id.as_str())
+        {
+            text.push_str(&payload.message);
+            return;
+        }
+
+        let id = self.item_id(payload.item_id.as_ref());
+        self.ensure_turn().items.push(ThreadItem::AgentMessage {
+            id,
+            text: payload.message.clone(),
+        });
     }
 
     fn handle_agent_reasoning(&mut self, payload: &AgentReasoningEvent) {
@@ -90,14 +122,16 @@ impl ThreadHistoryBuilder {
             return;
         }
 
-        // If the last item is a reasoning item, add the new text to the summary.
-        if let Some(ThreadItem::Reasoning { summary, .. }) = self.ensure_turn().items.last_mut() {
+        // If the last item is the same reasoning item, add the new text to the summary.
+        if let Some(ThreadItem::Reasoning { id, summary, .. }) = self.ensure_turn().items.last_mut()
+            && same_item(id, payload.item_id.as_ref())
+        {
             summary.push(payload.text.clone());
             return;
         }
 
         // Otherwise, create a new reasoning item.
-        let id = self.next_item_id();
+        let id = self.item_id(payload.item_id.as_ref());
         self.ensure_turn().items.push(ThreadItem::Reasoning {
             id,
             summary: vec![payload.text.clone()],
@@ -110,14 +144,16 @@ impl ThreadHistoryBuilder {
             return;
         }
 
-        // If the last item is a reasoning item, add the new text to the content.
-        if let Some(ThreadItem::Reasoning { content, .. }) = self.ensure_turn().items.last_mut() {
+        // If the last item is the same reasoning item, add the new text to the content.
+        if let Some(ThreadItem::Reasoning { id, content, .. }) = self.ensure_turn().items.last_mut()
+            && same_item(id, payload.item_id.as_ref())
+        {
             content.push(payload.text.clone());
             return;
         }
 
         // Otherwise, create a new reasoning item.
-        let id = self.next_item_id();
+        let id = self.item_id(payload.item_id.as_ref());
         self.ensure_turn().items.push(ThreadItem::Reasoning {
             id,
             summary: Vec::new(),
@@ -192,6 +228,14 @@ impl ThreadHistoryBuilder {
         id
     }
 
+    /// The recorded item id, or a synthetic one for events without it.
+    fn item_id(&mut self, recorded: Option<&String>) -> String {
+        match recorded {
+            Some(id) => id.clone(),
+            None => self.next_item_id(),
+        }
+    }
+
     fn build_user_inputs(&self, payload: &UserMessageEvent) -> Vec<UserInput> {
         let mut content = Vec::new();
         if !payload.message.trim().is_empty() {
@@ -217,6 +261,12 @@ impl ThreadHistoryBuilder {
     }
 }
 
+/// Whether an event for `recorded` continues the item `id`. Events recorded
+/// without an id continue whatever item came last, as they always have.
+fn same_item(id: &str, recorded: Option<&String>) -> bool {
+    recorded.is_none_or(|recorded| recorded == id)
+}
+
 struct PendingTurn {
     id: String,
     items: Vec<ThreadItem>,
@@ -247,6 +297,58 @@ mod tests {
     use codex_protocol::protocol::UserMessageEvent;
     use pretty_assertions::assert_eq;
 
+    #[test]
+    fn uses_recorded_item_ids_and_groups_events_by_item() {
+        let events = vec![
+            EventMsg::UserMessage(UserMessageEvent {
+                message: "Hello".into(),
+                images: None,
+                text_elements: Vec::new(),
+                local_images: Vec::new(),
+                item_id: Some("user-1".into()),
+            }),
+            EventMsg::AgentReasoning(AgentReasoningEvent {
+                text: "first".into(),
+                item_id: Some("rs-1".into()),
+            }),
+            EventMsg::AgentReasoning(AgentReasoningEvent {
+                text: "second".into(),
+                item_id: Some("rs-2".into()),
+            }),
+            EventMsg::AgentMessage(AgentMessageEvent {
+                message: "Hi ".into(),
+                item_id: Some("msg-1".into()),
+            }),
+            EventMsg::AgentMessage(AgentMessageEvent {
+                message: "there".into(),
+                item_id: Some("msg-1".into()),
+            }),
+        ];
+
+        let turns = build_turns_from_event_msgs(&events);
+        assert_eq!(turns.len(), 1);
+        assert_eq!(
+            turns[0].items[1..].to_vec(),
+            vec![
+                ThreadItem::Reasoning {
+                    id: "rs-1".into(),
+                    summary: vec!["first".into()],
+                    content: Vec::new(),
+                },
+                ThreadItem::Reasoning {
+                    id: "rs-2".into(),
+                    summary: vec!["second".into()],
+                    content: Vec::new(),
+                },
+                ThreadItem::AgentMessage {
+                    id: "msg-1".into(),
+                    text: "Hi there".into(),
+                },
+            ]
+        );
+        assert!(matches!(&turns[0].items[0], ThreadItem::UserMessage { id, .. } if id == "user-1"));
+    }
+
     #[test]
     fn builds_multiple_turns_with_reasoning_items() {
         let events = vec![
@@ -255,24 +357,30 @@ mod tests {
                 images: Some(vec!["https://example.com/one.png".into()]),
                 text_elements: Vec::new(),
                 local_images: Vec::new(),
+                item_id: None,
             }),
             EventMsg::AgentMessage(AgentMessageEvent {
                 message: "Hi there".into(),
+                item_id: None,
             }),
             EventMsg::AgentReasoning(AgentReasoningEvent {
                 text: "thinking".into(),
+                item_id: None,
             }),
             EventMsg::AgentReasoningRawContent(AgentReasoningRawContentEvent {
                 text: "full reasoning".into(),
+                item_id: None,
             }),
             EventMsg::UserMessage(UserMessageEvent {
                 message: "Second turn".into(),
                 images: None,
                 text_elements: Vec::new(),
                 local_images: Vec::new(),
+                item_id: None,
             }),
             EventMsg::AgentMessage(AgentMessageEvent {
                 message: "Reply two".into(),
+                item_id: None,
             }),
         ];
 
@@ -344,18 +452,23 @@ mod tests {
                 images: None,
                 text_elements: Vec::new(),
                 local_images: Vec::new(),
+                item_id: None,
             }),
             EventMsg::AgentReasoning(AgentReasoningEvent {
                 text: "first summary".into(),
+                item_id: None,
             }),
             EventMsg::AgentReasoningRawContent(AgentReasoningRawContentEvent {
                 text: "first content".into(),
+                item_id: None,
             }),
             EventMsg::AgentMessage(AgentMessageEvent {
                 message: "interlude".into(),
+                item_id: None,
             }),
             EventMsg::AgentReasoning(AgentReasoningEvent {
                 text: "second summary".into(),
+                item_id: None,
             }),
         ];
 
@@ -390,9 +503,11 @@ mod tests {
                 images: None,
                 text_elements: Vec::new(),
                 local_images: Vec::new(),
+                item_id: None,
             }),
             EventMsg::AgentMessage(AgentMessageEvent {
                 message: "Working...".into(),
+                item_id: None,
             }),
             EventMsg::TurnAborted(TurnAbortedEvent {
                 reason: TurnAbortReason::Replaced,
@@ -402,9 +517,11 @@ mod tests {
                 images: None,
                 text_elements: Vec::new(),
                 local_images: Vec::new(),
+                item_id: None,
             }),
             EventMsg::AgentMessage(AgentMessageEvent {
                 message: "Second attempt complete.".into(),
+                item_id: None,
             }),
         ];
 
@@ -462,28 +579,37 @@ mod tests {
                 images: None,
                 text_elements: Vec::new(),
                 local_images: Vec::new(),
+                item_id: None,
             }),
             EventMsg::AgentMessage(AgentMessageEvent {
                 message: "A1".into(),
+                item_id: None,
             }),
             EventMsg::UserMessage(UserMessageEvent {
                 message: "Second".into(),
                 images: None,
                 text_elements: Vec::new(),
                 local_images: Vec::new(),
+                item_id: None,
             }),
             EventMsg::AgentMessage(AgentMessageEvent {
                 message: "A2".into(),
+                item_id: None,
+            }),
+            EventMsg::ThreadRolledBack(ThreadRolledBackEvent {
+                num_turns: 1,
+                edited_item_id: None,
             }),
-            EventMsg::ThreadRolledBack(ThreadRolledBackEvent { num_turns: 1 }),
             EventMsg::UserMessage(UserMessageEvent {
                 message: "Third".into(),
                 images: None,
                 text_elements: Vec::new(),
                 local_images: Vec::new(),
+                item_id: None,
             }),
             EventMsg::AgentMessage(AgentMessageEvent {
                 message: "A3".into(),
+                item_id: None,
             }),
         ];
 
@@ -537,20 +663,27 @@ mod tests {
                 images: None,
                 text_elements: Vec::new(),
                 local_images: Vec::new(),
+                item_id: None,
             }),
             EventMsg::AgentMessage(AgentMessageEvent {
                 message: "A1".into(),
+                item_id: None,
             }),
             EventMsg::UserMessage(UserMessageEvent {
                 message: "Two".into(),
                 images: None,
                 text_elements: Vec::new(),
                 local_images: Vec::new(),
+                item_id: None,
             }),
             EventMsg::AgentMessage(AgentMessageEvent {
                 message: "A2".into(),
+                item_id: None,
+            }),
+            EventMsg::ThreadRolledBack(ThreadRolledBackEvent {
+                num_turns: 99,
+                edited_item_id: None,
             }),
-            EventMsg::ThreadRolledBack(ThreadRolledBackEvent { num_turns: 99 }),
         ];
 
         let turns = build_turns_from_event_msgs(&events);
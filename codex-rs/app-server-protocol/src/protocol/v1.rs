@@ -29,10 +29,33 @@ use uuid::Uuid;
 use crate::protocol::common::AuthMode;
 use crate::protocol::common::GitSha;
 
+/// Protocol versions this server speaks, oldest first. `v2` is the
+/// thread/turn API; `v1` the conversation API it replaces.
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["v1", "v2"];
+
+/// Picks the first of the client's `protocol_versions` the server speaks, or
+/// the newest supported version when the client sent none. Returns `None`
+/// when there is no version in common.
+pub fn negotiate_protocol_version(protocol_versions: Option<&[String]>) -> Option<&'static str> {
+    match protocol_versions {
+        None => SUPPORTED_PROTOCOL_VERSIONS.last().copied(),
+        Some(requested) => requested.iter().find_map(|version| {
+            SUPPORTED_PROTOCOL_VERSIONS
+                .iter()
+                .copied()
+                .find(|supported| supported == version)
+        }),
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct InitializeParams {
     pub client_info: ClientInfo,
+    /// Protocol versions the client speaks, in order of preference. Clients
+    /// that omit it are assumed to speak every version the server does.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol_versions: Option<Vec<String>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
@@ -47,6 +70,34 @@ pub struct ClientInfo {
 #[serde(rename_all = "camelCase")]
 pub struct InitializeResponse {
     pub user_agent: String,
+    /// Version of the server build, e.g. `0.80.0`.
+    #[serde(default)]
+    pub server_version: String,
+    /// Protocol versions the server speaks, oldest first.
+    #[serde(default)]
+    pub protocol_versions: Vec<String>,
+    /// Version picked from the client's `protocolVersions`, or the newest
+    /// one the server speaks when the client sent none.
+    #[serde(default)]
+    pub protocol_version: String,
+    /// Keys of the features enabled in the server's config.
+    #[serde(default)]
+    pub enabled_features: Vec<String>,
+    /// Built-in tools offered to the model by default; MCP tools are not
+    /// included.
+    #[serde(default)]
+    pub tools: Vec<String>,
+    #[serde(default)]
+    pub auth: InitializeAuthState,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct InitializeAuthState {
+    /// Whether the configured model provider needs an OpenAI login.
+    pub requires_openai_auth: bool,
+    /// How the server is logged in, if it is.
+    pub auth_mode: Option<AuthMode>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
@@ -119,6 +170,8 @@ pub struct ListConversationsParams {
     pub page_size: Option<usize>,
     pub cursor: Option<String>,
     pub model_providers: Option<Vec<String>>,
+    /// When set, only conversations carrying all of these tags are returned.
+    pub tags: Option<Vec<String>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -134,6 +187,10 @@ pub struct ConversationSummary {
     pub cli_version: String,
     pub source: SessionSource,
     pub git_info: Option<ConversationGitInfo>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
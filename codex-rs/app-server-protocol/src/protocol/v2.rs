@@ -1,16 +1,20 @@
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
 use crate::protocol::common::AuthMode;
 use codex_protocol::account::PlanType;
 use codex_protocol::approvals::ExecPolicyAmendment as CoreExecPolicyAmendment;
+use codex_protocol::approvals::MatchedApprovalRule as CoreMatchedApprovalRule;
 use codex_protocol::config_types::CollaborationMode;
 use codex_protocol::config_types::ForcedLoginMethod;
 use codex_protocol::config_types::ReasoningSummary;
+use codex_protocol::config_types::ResumeContextOptions as CoreResumeContextOptions;
 use codex_protocol::config_types::SandboxMode as CoreSandboxMode;
 use codex_protocol::config_types::Verbosity;
 use codex_protocol::config_types::WebSearchMode;
 use codex_protocol::items::AgentMessageContent as CoreAgentMessageContent;
+use codex_protocol::items::ProviderTool;
 use codex_protocol::items::TurnItem as CoreTurnItem;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::openai_models::ReasoningEffort;
@@ -20,17 +24,30 @@ use codex_protocol::plan_tool::StepStatus as CorePlanStepStatus;
 use codex_protocol::protocol::AgentStatus as CoreAgentStatus;
 use codex_protocol::protocol::AskForApproval as CoreAskForApproval;
 use codex_protocol::protocol::CodexErrorInfo as CoreCodexErrorInfo;
+use codex_protocol::protocol::CompactionDroppedItems as CoreCompactionDroppedItems;
 use codex_protocol::protocol::CreditsSnapshot as CoreCreditsSnapshot;
+use codex_protocol::protocol::EnvironmentChange as CoreEnvironmentChange;
+use codex_protocol::protocol::ErrorFingerprint as CoreErrorFingerprint;
 use codex_protocol::protocol::NetworkAccess as CoreNetworkAccess;
 use codex_protocol::protocol::RateLimitSnapshot as CoreRateLimitSnapshot;
 use codex_protocol::protocol::RateLimitWindow as CoreRateLimitWindow;
+use codex_protocol::protocol::ResumeContextChange as CoreResumeContextChange;
+use codex_protocol::protocol::ReviewPaths as CoreReviewPaths;
+use codex_protocol::protocol::SandboxEvaluation as CoreSandboxEvaluation;
+use codex_protocol::protocol::SandboxEvaluationTarget as CoreSandboxEvaluationTarget;
+use codex_protocol::protocol::SandboxPolicyReport as CoreSandboxPolicyReport;
+use codex_protocol::protocol::SandboxRule as CoreSandboxRule;
 use codex_protocol::protocol::SessionSource as CoreSessionSource;
+use codex_protocol::protocol::SessionStats as CoreSessionStats;
 use codex_protocol::protocol::SkillErrorInfo as CoreSkillErrorInfo;
 use codex_protocol::protocol::SkillInterface as CoreSkillInterface;
 use codex_protocol::protocol::SkillMetadata as CoreSkillMetadata;
 use codex_protocol::protocol::SkillScope as CoreSkillScope;
 use codex_protocol::protocol::TokenUsage as CoreTokenUsage;
 use codex_protocol::protocol::TokenUsageInfo as CoreTokenUsageInfo;
+use codex_protocol::protocol::ToolArtifact as CoreToolArtifact;
+use codex_protocol::protocol::TurnPhase as CoreTurnPhase;
+use codex_protocol::protocol::TurnRequestSnapshot as CoreTurnRequestSnapshot;
 use codex_protocol::user_input::ByteRange as CoreByteRange;
 use codex_protocol::user_input::TextElement as CoreTextElement;
 use codex_protocol::user_input::UserInput as CoreUserInput;
@@ -80,6 +97,21 @@ macro_rules! v2_enum_from_core {
 #[ts(export_to = "v2/")]
 pub enum CodexErrorInfo {
     ContextWindowExceeded,
+    /// The request was not sent because it would not fit in the model's
+    /// context window.
+    ContextOverflow {
+        #[serde(rename = "estimatedTokens")]
+        #[ts(rename = "estimatedTokens")]
+        estimated_tokens: i64,
+        #[serde(rename = "contextWindow")]
+        #[ts(rename = "contextWindow")]
+        context_window: i64,
+        /// Largest parts of the prompt, biggest first.
+        #[serde(rename = "largestContributors")]
+        #[ts(rename = "largestContributors")]
+        largest_contributors: Vec<ContextOverflowContributor>,
+        remedies: Vec<ContextOverflowRemedy>,
+    },
     UsageLimitExceeded,
     HttpConnectionFailed {
         #[serde(rename = "httpStatusCode")]
@@ -96,6 +128,7 @@ pub enum CodexErrorInfo {
     Unauthorized,
     BadRequest,
     ThreadRollbackFailed,
+    ConversationMergeFailed,
     SandboxError,
     /// The response SSE stream disconnected in the middle of a turn before completion.
     ResponseStreamDisconnected {
@@ -116,6 +149,23 @@ impl From<CoreCodexErrorInfo> for CodexErrorInfo {
     fn from(value: CoreCodexErrorInfo) -> Self {
         match value {
             CoreCodexErrorInfo::ContextWindowExceeded => CodexErrorInfo::ContextWindowExceeded,
+            CoreCodexErrorInfo::ContextOverflow {
+                estimated_tokens,
+                context_window,
+                largest_contributors,
+                remedies,
+            } => CodexErrorInfo::ContextOverflow {
+                estimated_tokens,
+                context_window,
+                largest_contributors: largest_contributors
+                    .into_iter()
+                    .map(ContextOverflowContributor::from)
+                    .collect(),
+                remedies: remedies
+                    .into_iter()
+                    .map(ContextOverflowRemedy::from)
+                    .collect(),
+            },
             CoreCodexErrorInfo::UsageLimitExceeded => CodexErrorInfo::UsageLimitExceeded,
             CoreCodexErrorInfo::HttpConnectionFailed { http_status_code } => {
                 CodexErrorInfo::HttpConnectionFailed { http_status_code }
@@ -127,6 +177,7 @@ impl From<CoreCodexErrorInfo> for CodexErrorInfo {
             CoreCodexErrorInfo::Unauthorized => CodexErrorInfo::Unauthorized,
             CoreCodexErrorInfo::BadRequest => CodexErrorInfo::BadRequest,
             CoreCodexErrorInfo::ThreadRollbackFailed => CodexErrorInfo::ThreadRollbackFailed,
+            CoreCodexErrorInfo::ConversationMergeFailed => CodexErrorInfo::ConversationMergeFailed,
             CoreCodexErrorInfo::SandboxError => CodexErrorInfo::SandboxError,
             CoreCodexErrorInfo::ResponseStreamDisconnected { http_status_code } => {
                 CodexErrorInfo::ResponseStreamDisconnected { http_status_code }
@@ -139,6 +190,44 @@ impl From<CoreCodexErrorInfo> for CodexErrorInfo {
     }
 }
 
+v2_enum_from_core!(
+    pub enum ContextItemCategory from codex_protocol::protocol::ContextItemCategory {
+        Instructions,
+        ToolDefinitions,
+        UserMessages,
+        AssistantMessages,
+        Reasoning,
+        ToolCalls,
+        ToolOutputs
+    }
+);
+
+v2_enum_from_core!(
+    pub enum ContextOverflowRemedy from codex_protocol::protocol::ContextOverflowRemedy {
+        Compact, DropToolOutputs, SwitchModel
+    }
+);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ContextOverflowContributor {
+    pub category: ContextItemCategory,
+    /// File the tokens are attributed to. `null` for the category as a whole.
+    pub path: Option<String>,
+    pub estimated_tokens: i64,
+}
+
+impl From<codex_protocol::protocol::ContextOverflowContributor> for ContextOverflowContributor {
+    fn from(value: codex_protocol::protocol::ContextOverflowContributor) -> Self {
+        Self {
+            category: value.category.into(),
+            path: value.path,
+            estimated_tokens: value.estimated_tokens,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
 #[serde(rename_all = "kebab-case")]
 #[ts(rename_all = "kebab-case", export_to = "v2/")]
@@ -311,6 +400,12 @@ pub struct SandboxWorkspaceWrite {
     pub exclude_tmpdir_env_var: bool,
     #[serde(default)]
     pub exclude_slash_tmp: bool,
+    #[serde(default)]
+    pub max_memory_bytes: Option<u64>,
+    #[serde(default)]
+    pub cpu_rate_percent: Option<u8>,
+    #[serde(default)]
+    pub max_processes: Option<u32>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -470,6 +565,8 @@ pub struct ConfigReadResponse {
 pub struct ConfigRequirements {
     pub allowed_approval_policies: Option<Vec<AskForApproval>>,
     pub allowed_sandbox_modes: Option<Vec<SandboxMode>>,
+    pub allowed_models: Option<Vec<String>>,
+    pub forbidden_tools: Option<Vec<String>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -519,6 +616,9 @@ pub enum CommandExecutionApprovalDecision {
     Accept,
     /// User approved the command and future identical commands should run without prompting.
     AcceptForSession,
+    /// User approved the command and future commands with the same prefix should run without
+    /// prompting for as long as `scope` lasts.
+    AcceptAlways { scope: ApprovalScope },
     /// User approved the command, and wants to apply the proposed execpolicy amendment so future
     /// matching commands can run without prompting.
     AcceptWithExecpolicyAmendment {
@@ -538,6 +638,9 @@ pub enum FileChangeApprovalDecision {
     Accept,
     /// User approved the file changes and future changes to the same files should run without prompting.
     AcceptForSession,
+    /// User approved the file changes and future changes to the same files should run without
+    /// prompting for as long as `scope` lasts.
+    AcceptAlways { scope: ApprovalScope },
     /// User denied the file changes. The agent will continue the turn.
     Decline,
     /// User denied the file changes. The turn will also be immediately interrupted.
@@ -577,6 +680,15 @@ pub enum SandboxPolicy {
         exclude_tmpdir_env_var: bool,
         #[serde(default)]
         exclude_slash_tmp: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        max_memory_bytes: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        cpu_rate_percent: Option<u8>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        max_processes: Option<u32>,
     },
 }
 
@@ -600,11 +712,17 @@ impl SandboxPolicy {
                 network_access,
                 exclude_tmpdir_env_var,
                 exclude_slash_tmp,
+                max_memory_bytes,
+                cpu_rate_percent,
+                max_processes,
             } => codex_protocol::protocol::SandboxPolicy::WorkspaceWrite {
                 writable_roots: writable_roots.clone(),
                 network_access: *network_access,
                 exclude_tmpdir_env_var: *exclude_tmpdir_env_var,
                 exclude_slash_tmp: *exclude_slash_tmp,
+                max_memory_bytes: *max_memory_bytes,
+                cpu_rate_percent: *cpu_rate_percent,
+                max_processes: *max_processes,
             },
         }
     }
@@ -630,11 +748,17 @@ impl From<codex_protocol::protocol::SandboxPolicy> for SandboxPolicy {
                 network_access,
                 exclude_tmpdir_env_var,
                 exclude_slash_tmp,
+                max_memory_bytes,
+                cpu_rate_percent,
+                max_processes,
             } => SandboxPolicy::WorkspaceWrite {
                 writable_roots,
                 network_access,
                 exclude_tmpdir_env_var,
                 exclude_slash_tmp,
+                max_memory_bytes,
+                cpu_rate_percent,
+                max_processes,
             },
         }
     }
@@ -661,6 +785,36 @@ impl From<CoreExecPolicyAmendment> for ExecPolicyAmendment {
     }
 }
 
+v2_enum_from_core!(
+    pub enum ApprovalRuleDecision from codex_protocol::approvals::ApprovalRuleDecision {
+        Allow, Prompt, Forbidden
+    }
+);
+
+v2_enum_from_core!(
+    pub enum ApprovalScope from codex_protocol::approvals::ApprovalScope {
+        Session, Project, Global
+    }
+);
+
+/// Config-defined `[[approval_rules]]` entry that decided how a command is approved.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct MatchedApprovalRule {
+    pub name: String,
+    pub decision: ApprovalRuleDecision,
+}
+
+impl From<CoreMatchedApprovalRule> for MatchedApprovalRule {
+    fn from(value: CoreMatchedApprovalRule) -> Self {
+        Self {
+            name: value.name,
+            decision: value.decision.into(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(tag = "type", rename_all = "camelCase")]
 #[ts(tag = "type")]
@@ -1065,6 +1219,157 @@ pub struct CommandExecResponse {
     pub stderr: String,
 }
 
+v2_enum_from_core!(
+    pub enum SandboxBackend from codex_protocol::protocol::SandboxBackend {
+        None, MacosSeatbelt, LinuxSeccomp, WindowsRestrictedToken
+    }
+);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SandboxPolicyReadParams {
+    /// Directory to resolve writable roots against. Defaults to the server cwd.
+    pub cwd: Option<PathBuf>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SandboxWritableRoot {
+    pub root: AbsolutePathBuf,
+    pub read_only_subpaths: Vec<AbsolutePathBuf>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SandboxPolicyReadResponse {
+    pub sandbox_policy: SandboxPolicy,
+    pub cwd: PathBuf,
+    pub full_disk_write_access: bool,
+    /// Writable roots after applying cwd, tmpdir and read-only exclusions.
+    pub writable_roots: Vec<SandboxWritableRoot>,
+    pub network_access: bool,
+    pub backend: SandboxBackend,
+}
+
+impl From<CoreSandboxPolicyReport> for SandboxPolicyReadResponse {
+    fn from(value: CoreSandboxPolicyReport) -> Self {
+        Self {
+            sandbox_policy: value.sandbox_policy.into(),
+            cwd: value.cwd,
+            full_disk_write_access: value.full_disk_write_access,
+            writable_roots: value
+                .writable_roots
+                .into_iter()
+                .map(|root| SandboxWritableRoot {
+                    root: root.root,
+                    read_only_subpaths: root.read_only_subpaths,
+                })
+                .collect(),
+            network_access: value.network_access,
+            backend: value.backend.into(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "camelCase")]
+#[ts(tag = "type")]
+#[ts(export_to = "v2/")]
+pub enum SandboxEvaluationTarget {
+    /// Writing to `path`, resolved against `cwd` when relative.
+    Path { path: PathBuf },
+    /// Running `command` (argv). `bash -lc` scripts are split into their
+    /// plain commands and each one is checked.
+    Command { command: Vec<String> },
+}
+
+impl SandboxEvaluationTarget {
+    pub fn to_core(self) -> CoreSandboxEvaluationTarget {
+        match self {
+            SandboxEvaluationTarget::Path { path } => CoreSandboxEvaluationTarget::Path { path },
+            SandboxEvaluationTarget::Command { command } => {
+                CoreSandboxEvaluationTarget::Command { command }
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SandboxPolicyEvaluateParams {
+    pub target: SandboxEvaluationTarget,
+    /// Directory to resolve paths and writable roots against. Defaults to the
+    /// server cwd.
+    pub cwd: Option<PathBuf>,
+}
+
+v2_enum_from_core!(
+    pub enum SandboxDecision from codex_protocol::protocol::SandboxDecision {
+        Allow, Deny
+    }
+);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "camelCase")]
+#[ts(tag = "type")]
+#[ts(export_to = "v2/")]
+pub enum SandboxRule {
+    FullDiskWriteAccess,
+    ReadOnly,
+    WritableRoot {
+        root: AbsolutePathBuf,
+    },
+    ReadOnlySubpath {
+        root: AbsolutePathBuf,
+        subpath: AbsolutePathBuf,
+    },
+    OutsideWritableRoots,
+    NetworkAccess,
+    NetworkRestricted,
+    NoRestrictedEffects,
+}
+
+impl From<CoreSandboxRule> for SandboxRule {
+    fn from(value: CoreSandboxRule) -> Self {
+        match value {
+            CoreSandboxRule::FullDiskWriteAccess => SandboxRule::FullDiskWriteAccess,
+            CoreSandboxRule::ReadOnly => SandboxRule::ReadOnly,
+            CoreSandboxRule::WritableRoot { root } => SandboxRule::WritableRoot { root },
+            CoreSandboxRule::ReadOnlySubpath { root, subpath } => {
+                SandboxRule::ReadOnlySubpath { root, subpath }
+            }
+            CoreSandboxRule::OutsideWritableRoots => SandboxRule::OutsideWritableRoots,
+            CoreSandboxRule::NetworkAccess => SandboxRule::NetworkAccess,
+            CoreSandboxRule::NetworkRestricted => SandboxRule::NetworkRestricted,
+            CoreSandboxRule::NoRestrictedEffects => SandboxRule::NoRestrictedEffects,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SandboxPolicyEvaluateResponse {
+    pub decision: SandboxDecision,
+    /// The part of the policy that decided the outcome.
+    pub rule: SandboxRule,
+    pub reason: String,
+}
+
+impl From<CoreSandboxEvaluation> for SandboxPolicyEvaluateResponse {
+    fn from(value: CoreSandboxEvaluation) -> Self {
+        Self {
+            decision: value.decision.into(),
+            rule: value.rule.into(),
+            reason: value.reason,
+        }
+    }
+}
+
 // === Threads, Turns, and Items ===
 // Thread APIs
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
@@ -1133,6 +1438,163 @@ pub struct ThreadResumeParams {
     pub config: Option<HashMap<String, serde_json::Value>>,
     pub base_instructions: Option<String>,
     pub developer_instructions: Option<String>,
+    /// Whether to keep the model, environment and sandbox settings recorded
+    /// in the thread or use the current ones. Unset dimensions follow the
+    /// `[resume_context]` config section.
+    #[serde(default)]
+    pub resume_context: Option<ResumeContextOptions>,
+}
+
+v2_enum_from_core!(
+    pub enum ResumeContextSource from codex_protocol::config_types::ResumeContextSource {
+        Recorded, Current
+    }
+);
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ResumeContextOptions {
+    /// Model and reasoning effort.
+    pub model: Option<ResumeContextSource>,
+    /// Working directory.
+    pub environment: Option<ResumeContextSource>,
+    /// Approval and sandbox policies.
+    pub sandbox: Option<ResumeContextSource>,
+}
+
+impl ResumeContextOptions {
+    /// Overlays the dimensions set here onto `base`.
+    pub fn apply_to(self, base: CoreResumeContextOptions) -> CoreResumeContextOptions {
+        CoreResumeContextOptions {
+            model: self.model.map_or(base.model, ResumeContextSource::to_core),
+            environment: self
+                .environment
+                .map_or(base.environment, ResumeContextSource::to_core),
+            sandbox: self
+                .sandbox
+                .map_or(base.sandbox, ResumeContextSource::to_core),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(tag = "field", rename_all = "camelCase")]
+#[ts(tag = "field")]
+#[ts(export_to = "v2/")]
+pub enum ResumeContextChange {
+    Model {
+        recorded: String,
+        current: String,
+        applied: ResumeContextSource,
+    },
+    ReasoningEffort {
+        recorded: Option<ReasoningEffort>,
+        current: Option<ReasoningEffort>,
+        applied: ResumeContextSource,
+    },
+    Cwd {
+        recorded: PathBuf,
+        current: PathBuf,
+        applied: ResumeContextSource,
+    },
+    ApprovalPolicy {
+        recorded: AskForApproval,
+        current: AskForApproval,
+        applied: ResumeContextSource,
+    },
+    SandboxPolicy {
+        recorded: SandboxPolicy,
+        current: SandboxPolicy,
+        applied: ResumeContextSource,
+    },
+}
+
+impl From<CoreResumeContextChange> for ResumeContextChange {
+    fn from(value: CoreResumeContextChange) -> Self {
+        match value {
+            CoreResumeContextChange::Model {
+                recorded,
+                current,
+                applied,
+            } => ResumeContextChange::Model {
+                recorded,
+                current,
+                applied: applied.into(),
+            },
+            CoreResumeContextChange::ReasoningEffort {
+                recorded,
+                current,
+                applied,
+            } => ResumeContextChange::ReasoningEffort {
+                recorded,
+                current,
+                applied: applied.into(),
+            },
+            CoreResumeContextChange::Cwd {
+                recorded,
+                current,
+                applied,
+            } => ResumeContextChange::Cwd {
+                recorded,
+                current,
+                applied: applied.into(),
+            },
+            CoreResumeContextChange::ApprovalPolicy {
+                recorded,
+                current,
+                applied,
+            } => ResumeContextChange::ApprovalPolicy {
+                recorded: recorded.into(),
+                current: current.into(),
+                applied: applied.into(),
+            },
+            CoreResumeContextChange::SandboxPolicy {
+                recorded,
+                current,
+                applied,
+            } => ResumeContextChange::SandboxPolicy {
+                recorded: recorded.into(),
+                current: current.into(),
+                applied: applied.into(),
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadResumeContextNotification {
+    pub thread_id: String,
+    pub changes: Vec<ResumeContextChange>,
+}
+
+/// Emitted when a turn switches the thread to a different model, describing
+/// how the thread context was adapted to it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadModelSwitchedNotification {
+    pub thread_id: String,
+    pub previous_model: String,
+    pub model: String,
+    /// Whether the base instructions were re-rendered for the new model.
+    pub instructions_updated: bool,
+    /// Built-in tools the new model offers that the previous one did not.
+    pub tools_added: Vec<String>,
+    /// Built-in tools the previous model offered that the new one does not.
+    pub tools_removed: Vec<String>,
+    #[ts(type = "number | null")]
+    pub previous_context_window: Option<i64>,
+    #[ts(type = "number | null")]
+    pub context_window: Option<i64>,
+    /// Tokens the conversation currently uses.
+    #[ts(type = "number")]
+    pub tokens_in_context: i64,
+    /// Whether the next turn compacts the conversation first to fit the new
+    /// model's budget.
+    pub compaction_pending: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -1201,6 +1663,38 @@ pub struct ThreadArchiveParams {
 #[ts(export_to = "v2/")]
 pub struct ThreadArchiveResponse {}
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadUnarchiveParams {
+    pub thread_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadUnarchiveResponse {
+    pub thread: Thread,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadMetadataUpdateParams {
+    pub thread_id: String,
+    /// New title for the thread. An empty string clears the title; null leaves it unchanged.
+    pub title: Option<String>,
+    /// Replacement tags for the thread; null leaves them unchanged.
+    pub tags: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadMetadataUpdateResponse {
+    pub thread: Thread,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -1225,6 +1719,70 @@ pub struct ThreadRollbackResponse {
     pub thread: Thread,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadTrimParams {
+    pub thread_id: String,
+    /// Zero-based index of the first turn to remove from the rollout.
+    ///
+    /// Turns are counted over every user turn recorded for the thread,
+    /// including turns that were later rolled back.
+    pub start_turn: u32,
+    /// The number of consecutive turns to remove. Must be >= 1.
+    pub num_turns: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadTrimResponse {
+    /// The thread after trimming, with `turns` populated.
+    pub thread: Thread,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadMergeParams {
+    /// The loaded thread to merge into.
+    pub thread_id: String,
+    /// The thread whose outcome is merged: its final agent message, the diff
+    /// of every turn that changed files and the artifacts its tools returned.
+    pub source_thread_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadMergeResponse {
+    pub source_thread_id: String,
+    pub final_message: Option<String>,
+    /// 1-based numbers of the source thread's turns whose diff was merged.
+    pub diff_turns: Vec<u32>,
+    pub artifacts: Vec<ToolArtifact>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadFeatureSetParams {
+    pub thread_id: String,
+    /// `[features]` key of a feature that can change while a thread runs.
+    pub feature: String,
+    pub enabled: bool,
+    /// Who or what made the change, recorded with it in the rollout.
+    /// Defaults to the client name given at initialization.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub changed_by: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadFeatureSetResponse {}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -1241,6 +1799,8 @@ pub struct ThreadListParams {
     /// Optional archived filter; when set to true, only archived threads are returned.
     /// If false or null, only non-archived threads are returned.
     pub archived: Option<bool>,
+    /// Optional tag filter; when set, only threads carrying all of these tags are returned.
+    pub tags: Option<Vec<String>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, JsonSchema, TS)]
@@ -1302,43 +1862,549 @@ pub struct ThreadReadResponse {
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
-pub struct SkillsListParams {
-    /// When empty, defaults to the current session working directory.
-    #[serde(default, skip_serializing_if = "Vec::is_empty")]
-    pub cwds: Vec<PathBuf>,
+pub struct ThreadEnvironmentDiffParams {
+    pub thread_id: String,
+}
 
-    /// When true, bypass the skills cache and re-scan skills from disk.
-    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
-    pub force_reload: bool,
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadEnvironmentDiffResponse {
+    /// False when the thread's rollout predates environment snapshots, in
+    /// which case `changes` is empty.
+    pub recorded: bool,
+    pub changes: Vec<EnvironmentChange>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
-pub struct SkillsListResponse {
-    pub data: Vec<SkillsListEntry>,
+pub struct ThreadWorkspaceDiffParams {
+    pub thread_id: String,
+    /// The thread to compare with; it must have run in the same repository.
+    pub other_thread_id: String,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
-#[serde(rename_all = "snake_case")]
-#[ts(rename_all = "snake_case")]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
-pub enum SkillScope {
-    User,
-    Repo,
-    System,
-    Admin,
+pub struct ThreadWorkspaceDiffResponse {
+    /// Root of the repository both threads ran in.
+    pub repo: PathBuf,
+    /// `threadId` first, then `otherThreadId`.
+    pub threads: Vec<ThreadWorkspaceDelta>,
+    /// Files changed by both threads.
+    pub shared: Vec<String>,
+    /// Files that changed while the threads were active without either of
+    /// them recording a diff for them.
+    pub unattributed: Vec<String>,
+    /// Plain-text report of the above, followed by each thread's diffs.
+    pub rendered: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
-pub struct SkillMetadata {
-    pub name: String,
-    pub description: String,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    #[ts(optional)]
-    /// Legacy short_description from SKILL.md. Prefer SKILL.toml interface.short_description.
+pub struct ThreadWorkspaceDelta {
+    pub thread_id: String,
+    /// Ghost commits recorded at the start of the thread's turns, oldest first.
+    pub checkpoints: Vec<String>,
+    pub files: Vec<ThreadWorkspaceFileDelta>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadWorkspaceFileDelta {
+    /// Relative to the repository root.
+    pub path: String,
+    /// 1-based turns that changed the file.
+    pub turns: Vec<u32>,
+    /// The file's sections of those turns' diffs, in turn order.
+    pub diff: String,
+}
+
+/// A difference between the environment recorded when a thread was created
+/// and the current one. Values of variables that are not allowlisted are
+/// SHA-256 digests; `null` means unset or not installed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+#[ts(tag = "kind")]
+#[ts(export_to = "v2/")]
+pub enum EnvironmentChange {
+    Platform {
+        recorded: String,
+        current: String,
+    },
+    EnvVar {
+        name: String,
+        recorded: Option<String>,
+        current: Option<String>,
+    },
+    Path {
+        recorded: Vec<String>,
+        current: Vec<String>,
+    },
+    Tool {
+        name: String,
+        recorded: Option<String>,
+        current: Option<String>,
+    },
+}
+
+impl From<CoreEnvironmentChange> for EnvironmentChange {
+    fn from(value: CoreEnvironmentChange) -> Self {
+        match value {
+            CoreEnvironmentChange::Platform { recorded, current } => {
+                EnvironmentChange::Platform { recorded, current }
+            }
+            CoreEnvironmentChange::EnvVar {
+                name,
+                recorded,
+                current,
+            } => EnvironmentChange::EnvVar {
+                name,
+                recorded,
+                current,
+            },
+            CoreEnvironmentChange::Path { recorded, current } => {
+                EnvironmentChange::Path { recorded, current }
+            }
+            CoreEnvironmentChange::Tool {
+                name,
+                recorded,
+                current,
+            } => EnvironmentChange::Tool {
+                name,
+                recorded,
+                current,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub enum StatsGrouping {
+    /// One group per `YYYY-MM-DD` day the sessions started on.
+    Day,
+    /// One group per session working directory.
+    Project,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct StatsReadParams {
+    /// First day (`YYYY-MM-DD`) to include, by session start date.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub from: Option<String>,
+    /// Last day (`YYYY-MM-DD`) to include, by session start date.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub to: Option<String>,
+    /// Only sessions that ran in this directory or below it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub cwd: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub group_by: Option<StatsGrouping>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct StatsReadResponse {
+    pub sessions: u64,
+    /// Matching sessions without recorded stats, e.g. still running or
+    /// recorded by an older version.
+    pub sessions_without_stats: u64,
+    pub totals: SessionStats,
+    /// Present when `groupBy` was set, sorted by key.
+    pub groups: Vec<StatsGroup>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct StatsGroup {
+    pub key: String,
+    pub sessions: u64,
+    pub stats: SessionStats,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SessionStats {
+    pub turns: u64,
+    /// Tool calls keyed by tool name.
+    pub tool_calls: BTreeMap<String, u64>,
+    pub token_usage: TokenUsageBreakdown,
+    /// Estimated USD cost; `null` unless `model_pricing` covers every model used.
+    pub cost_usd: Option<f64>,
+    #[ts(type = "number")]
+    pub wall_time_ms: u64,
+    pub approvals_requested: u64,
+    pub approvals_granted: u64,
+}
+
+impl From<CoreSessionStats> for SessionStats {
+    fn from(value: CoreSessionStats) -> Self {
+        Self {
+            turns: value.turns,
+            tool_calls: value.tool_calls,
+            token_usage: value.token_usage.into(),
+            cost_usd: value.cost_usd,
+            wall_time_ms: value.wall_time_ms,
+            approvals_requested: value.approvals_requested,
+            approvals_granted: value.approvals_granted,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ChangesQueryParams {
+    /// Only changes in the repository containing this directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub cwd: Option<PathBuf>,
+    /// Only changes to this file: a path relative to the repository root, a
+    /// trailing part of one, or an absolute path inside the repository.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub path: Option<String>,
+    /// First day (`YYYY-MM-DD`) to include.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub since: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ChangesQueryResponse {
+    /// Newest first.
+    pub changes: Vec<RecordedFileChange>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct RecordedFileChange {
+    /// Root of the repository the session ran in.
+    pub repo: PathBuf,
+    /// Changed file, relative to `repo` when it is inside it.
+    pub path: String,
+    pub thread_id: String,
+    pub rollout_path: PathBuf,
+    /// 1-based turn of the thread that changed the file.
+    pub turn: u32,
+    /// When the turn's diff was recorded (RFC 3339).
+    pub timestamp: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ProvenanceExportParams {
+    pub thread_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ProvenanceExportResponse {
+    /// One record per patch applied in the thread, oldest first.
+    pub records: Vec<PatchProvenance>,
+}
+
+/// Signed record of where one applied patch came from.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct PatchProvenance {
+    pub version: u32,
+    pub thread_id: String,
+    pub turn_id: String,
+    pub call_id: String,
+    pub model: String,
+    pub model_provider: String,
+    /// SHA-256 (hex) of every user prompt in the thread when the patch was
+    /// applied, oldest first.
+    pub prompt_sha256: Vec<String>,
+    /// SHA-256 (hex) of the patch.
+    pub diff_sha256: String,
+    pub files: Vec<PathBuf>,
+    /// When the patch was applied (RFC 3339).
+    pub timestamp: String,
+    /// Identifies the signing key without revealing it.
+    pub key_id: String,
+    /// `hmac-sha256:<hex>` over the record.
+    pub signature: String,
+    /// Whether the signature checks out against the server's signing key.
+    pub verified: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ApprovalsListParams {
+    /// Also list the session approvals of this loaded thread.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub thread_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ApprovalsListResponse {
+    /// Session approvals first, then project and global ones, oldest first.
+    pub approvals: Vec<RememberedApproval>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ApprovalsRevokeParams {
+    pub id: String,
+    /// Thread whose session approvals may hold `id`; project and global
+    /// approvals are revoked without one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub thread_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ApprovalsRevokeResponse {
+    /// Whether an approval with that id existed.
+    pub revoked: bool,
+}
+
+/// An "always allow" approval Codex consults before prompting.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct RememberedApproval {
+    pub id: String,
+    pub scope: ApprovalScope,
+    /// Project root a `project` approval applies to.
+    pub project: Option<PathBuf>,
+    /// Tool the approval was given for, e.g. `shell` or `apply_patch`.
+    pub tool: String,
+    pub target: ApprovalTarget,
+    pub created_at: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "camelCase")]
+#[ts(tag = "type")]
+#[ts(export_to = "v2/")]
+pub enum ApprovalTarget {
+    /// Exactly `command`.
+    Command { command: Vec<String> },
+    /// Commands whose words start with `prefix`.
+    CommandPrefix { prefix: Vec<String> },
+    /// Changes to `path`, or to anything below it.
+    Path { path: PathBuf },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct DoctorRunResponse {
+    /// Most severe status of any check.
+    pub status: DoctorStatus,
+    pub checks: Vec<DoctorCheck>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct DoctorCheck {
+    pub kind: DoctorCheckKind,
+    /// What was checked, e.g. the name of an MCP server. Empty when the kind
+    /// says it all.
+    pub subject: String,
+    pub status: DoctorStatus,
+    pub summary: String,
+    /// How to resolve a warning or error.
+    pub fix: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub enum DoctorStatus {
+    Skipped,
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub enum DoctorCheckKind {
+    Config,
+    CodexHome,
+    Auth,
+    Provider,
+    Sandbox,
+    McpServer,
+    Git,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SkillsListParams {
+    /// When empty, defaults to the current session working directory.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cwds: Vec<PathBuf>,
+
+    /// When true, bypass the skills cache and re-scan skills from disk.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub force_reload: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SkillsListResponse {
+    pub data: Vec<SkillsListEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct InstructionsReadParams {
+    /// Working directory to resolve instructions for. Defaults to the
+    /// server's working directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub cwd: Option<PathBuf>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct InstructionsReadResponse {
+    /// Instructions from `$CODEX_HOME/AGENTS.md`, which apply everywhere.
+    pub user_instructions: Option<String>,
+    /// Project docs ordered from the repository root to `cwd`. Later entries
+    /// take precedence over earlier ones.
+    pub project_docs: Vec<ProjectDoc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ProjectDoc {
+    pub path: PathBuf,
+    /// Directory whose files (including subdirectories) the doc applies to.
+    pub scope: PathBuf,
+    pub content: String,
+    /// Whether `content` was cut short by `project_doc_max_bytes`.
+    pub truncated: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct TemplateListParams {
+    /// Directory whose project templates (`.codex/templates` at the repo
+    /// root) are included. Defaults to the server's working directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub cwd: Option<PathBuf>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadStartFromTemplateParams {
+    /// Name of a template returned by `template/list`.
+    pub template: String,
+    /// Values for the template's `$NAME` placeholders.
+    #[serde(default)]
+    pub arguments: HashMap<String, String>,
+    /// Working directory of the thread; also where project templates are
+    /// looked up. Defaults to the server's working directory.
+    pub cwd: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct TemplateListResponse {
+    pub data: Vec<SessionTemplate>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+#[ts(export_to = "v2/")]
+pub enum SessionTemplateScope {
+    User,
+    Project,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SessionTemplate {
+    pub name: String,
+    pub path: PathBuf,
+    pub scope: SessionTemplateScope,
+    pub description: Option<String>,
+    /// Initial prompt with `$NAME` placeholders.
+    pub prompt: String,
+    /// Config profile the session starts with.
+    pub profile: Option<String>,
+    pub required_tools: Vec<String>,
+    pub context_files: Vec<PathBuf>,
+    pub arguments: Vec<SessionTemplateArgument>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SessionTemplateArgument {
+    pub name: String,
+    pub description: Option<String>,
+    /// Arguments without a default are required.
+    pub default: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+#[ts(export_to = "v2/")]
+pub enum SkillScope {
+    User,
+    Repo,
+    System,
+    Admin,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SkillMetadata {
+    pub name: String,
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    /// Legacy short_description from SKILL.md. Prefer SKILL.toml interface.short_description.
     pub short_description: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[ts(optional)]
@@ -1470,6 +2536,10 @@ pub struct Thread {
     pub source: SessionSource,
     /// Optional Git metadata captured when the thread was created.
     pub git_info: Option<GitInfo>,
+    /// User-assigned title, if any.
+    pub title: Option<String>,
+    /// User-assigned tags.
+    pub tags: Vec<String>,
     /// Only populated on `thread/resume`, `thread/rollback`, `thread/fork`, and `thread/read`
     /// (when `includeTurns` is true) responses.
     /// For all other responses and notifications returning a Thread,
@@ -1493,6 +2563,32 @@ pub struct ThreadTokenUsageUpdatedNotification {
     pub token_usage: ThreadTokenUsage,
 }
 
+/// Estimated while a response streams; `thread/tokenUsage/updated` reports
+/// the actual usage once it completes.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadTokenForecastNotification {
+    pub thread_id: String,
+    pub turn_id: String,
+    /// Tokens of the request input plus the output streamed so far.
+    #[ts(type = "number")]
+    pub estimated_tokens: i64,
+    /// Tokens the context is projected to hold once the response completes.
+    #[ts(type = "number")]
+    pub projected_tokens: i64,
+    #[ts(type = "number | null")]
+    pub model_context_window: Option<i64>,
+    #[ts(type = "number | null")]
+    pub auto_compact_token_limit: Option<i64>,
+    /// Whether the conversation is projected to be compacted before the turn
+    /// continues.
+    pub will_compact: bool,
+    /// Whether the model's tokenizer is unknown and the estimates come from
+    /// byte counts instead.
+    pub heuristic: bool,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -1577,6 +2673,36 @@ pub struct ErrorNotification {
     pub will_retry: bool,
     pub thread_id: String,
     pub turn_id: String,
+    /// Identifies recurrences of the same failure; unset for errors that will
+    /// be retried.
+    #[serde(default)]
+    pub fingerprint: Option<ErrorFingerprint>,
+}
+
+/// Stable identity of a failure, so recurrences can be recognized.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ErrorFingerprint {
+    /// Hex digest over the error code, the failing component and the message
+    /// with volatile parts (numbers, ids, paths) masked.
+    pub hash: String,
+    /// Part of Codex that failed, e.g. `model_client` or `sandbox`.
+    pub component: String,
+    /// Times this failure was seen on this machine in the last 7 days,
+    /// including this one.
+    #[ts(type = "number")]
+    pub occurrences_last_7_days: u64,
+}
+
+impl From<CoreErrorFingerprint> for ErrorFingerprint {
+    fn from(value: CoreErrorFingerprint) -> Self {
+        Self {
+            hash: value.hash,
+            component: value.component,
+            occurrences_last_7_days: value.occurrences_last_7_days,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -1627,6 +2753,41 @@ pub struct ReviewStartParams {
     /// detached on a new thread (returned in `reviewThreadId`).
     #[serde(default)]
     pub delivery: Option<ReviewDelivery>,
+
+    /// Limit the review to these files, whichever changes `target` selects.
+    #[serde(default)]
+    pub paths: Option<ReviewPaths>,
+}
+
+/// Files a review is limited to: those matching `include` (every file when
+/// empty) and not matching `exclude`. Patterns are git pathspec globs
+/// relative to the repository root, e.g. `src/api/**`.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ReviewPaths {
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+impl ReviewPaths {
+    pub fn into_core(self) -> CoreReviewPaths {
+        CoreReviewPaths {
+            include: self.include,
+            exclude: self.exclude,
+        }
+    }
+}
+
+impl From<CoreReviewPaths> for ReviewPaths {
+    fn from(value: CoreReviewPaths) -> Self {
+        Self {
+            include: value.include,
+            exclude: value.exclude,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -1688,6 +2849,75 @@ pub struct TurnInterruptParams {
 #[ts(export_to = "v2/")]
 pub struct TurnInterruptResponse {}
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct TurnContextReadParams {
+    pub thread_id: String,
+    /// Turn to inspect. Defaults to the most recent turn of the thread.
+    pub turn_id: Option<String>,
+}
+
+v2_enum_from_core!(
+    pub enum InstructionLayerKind from codex_protocol::protocol::InstructionLayerKind {
+        BaseInstructions, DeveloperInstructions, UserInstructions, SkillInstructions, KnowledgeSnippet, EnvironmentContext
+    }
+);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct InstructionLayer {
+    pub kind: InstructionLayerKind,
+    pub text: String,
+    /// Approximate number of tokens in `text`.
+    pub token_count: u64,
+}
+
+/// The payload of the last model request made for a turn.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct TurnContextReadResponse {
+    pub turn_id: String,
+    pub model: String,
+    /// Number of model requests the turn has made; the payload below is the
+    /// last one.
+    pub request_count: u32,
+    pub instructions: Vec<InstructionLayer>,
+    /// Conversation history after compaction, excluding instruction layers.
+    pub history: Vec<ResponseItem>,
+    pub history_token_count: u64,
+    /// Tool definitions exactly as sent to the model.
+    pub tools: Vec<JsonValue>,
+    pub tools_token_count: u64,
+    pub total_token_count: u64,
+}
+
+impl From<CoreTurnRequestSnapshot> for TurnContextReadResponse {
+    fn from(value: CoreTurnRequestSnapshot) -> Self {
+        Self {
+            turn_id: value.turn_id,
+            model: value.model,
+            request_count: value.request_count,
+            instructions: value
+                .instructions
+                .into_iter()
+                .map(|layer| InstructionLayer {
+                    kind: layer.kind.into(),
+                    text: layer.text,
+                    token_count: layer.token_count,
+                })
+                .collect(),
+            history: value.history,
+            history_token_count: value.history_token_count,
+            tools: value.tools,
+            tools_token_count: value.tools_token_count,
+            total_token_count: value.total_token_count,
+        }
+    }
+}
+
 // User input types
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
@@ -1903,13 +3133,37 @@ pub enum ThreadItem {
     WebSearch { id: String, query: String },
     #[serde(rename_all = "camelCase")]
     #[ts(rename_all = "camelCase")]
+    ProviderToolCall {
+        id: String,
+        tool: ProviderTool,
+        /// The code that was run, or the queries that were searched for.
+        input: String,
+        /// What the code printed, or the files that matched.
+        output: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
     ImageView { id: String, path: String },
     #[serde(rename_all = "camelCase")]
     #[ts(rename_all = "camelCase")]
-    EnteredReviewMode { id: String, review: String },
+    EnteredReviewMode {
+        id: String,
+        review: String,
+        /// Files the review is limited to, if it is.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        paths: Option<ReviewPaths>,
+    },
     #[serde(rename_all = "camelCase")]
     #[ts(rename_all = "camelCase")]
-    ExitedReviewMode { id: String, review: String },
+    ExitedReviewMode {
+        id: String,
+        review: String,
+        /// Files the review was limited to, if it was.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        paths: Option<ReviewPaths>,
+    },
 }
 
 impl From<CoreTurnItem> for ThreadItem {
@@ -1938,6 +3192,12 @@ impl From<CoreTurnItem> for ThreadItem {
                 id: search.id,
                 query: search.query,
             },
+            CoreTurnItem::ProviderToolCall(call) => ThreadItem::ProviderToolCall {
+                id: call.id,
+                tool: call.tool,
+                input: call.input,
+                output: call.output,
+            },
         }
     }
 }
@@ -1969,6 +3229,10 @@ pub struct FileUpdateChange {
     pub path: String,
     pub kind: PatchChangeKind,
     pub diff: String,
+    /// Mode the file is left with, when the change sets one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub mode: Option<FileMode>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -1979,8 +3243,15 @@ pub enum PatchChangeKind {
     Add,
     Delete,
     Update { move_path: Option<PathBuf> },
+    Symlink { target: PathBuf },
 }
 
+v2_enum_from_core!(
+    pub enum FileMode from codex_protocol::protocol::FileMode {
+        Regular, Executable
+    }
+);
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -2073,8 +3344,24 @@ pub struct McpToolCallResult {
 #[ts(export_to = "v2/")]
 pub struct McpToolCallError {
     pub message: String,
+    /// Class of the failure; `null` for failures recorded before errors were
+    /// classified.
+    #[serde(default)]
+    pub kind: Option<McpToolCallErrorKind>,
+    /// JSON-RPC error code returned by the server, if any.
+    #[serde(default)]
+    pub code: Option<i64>,
+    /// Whether calling the tool again unchanged may succeed.
+    #[serde(default)]
+    pub retryable: bool,
 }
 
+v2_enum_from_core!(
+    pub enum McpToolCallErrorKind from codex_protocol::protocol::McpToolCallErrorKind {
+        InvalidParams, NotFound, ServerBusy, Timeout, Internal
+    }
+);
+
 // === Server Notifications ===
 // Thread/Turn lifecycle notifications and item progress events
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -2109,6 +3396,62 @@ pub struct TurnCompletedNotification {
     pub turn: Turn,
 }
 
+/// Sent while a turn is running but has produced no other notification for
+/// a while, describing what it is waiting on.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct TurnHeartbeatNotification {
+    pub thread_id: String,
+    pub turn_id: String,
+    pub phase: TurnPhase,
+    /// Milliseconds since the turn started.
+    #[ts(type = "number")]
+    pub turn_elapsed_ms: u64,
+    /// Milliseconds since the last event before this heartbeat.
+    #[ts(type = "number")]
+    pub silent_ms: u64,
+    /// Tool calls that are still running, oldest first.
+    pub active_tools: Vec<ActiveToolCall>,
+}
+
+v2_enum_from_core!(
+    pub enum TurnPhase from CoreTurnPhase { WaitingForModel, RunningTools }
+);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ActiveToolCall {
+    pub call_id: String,
+    pub tool_name: String,
+    /// Milliseconds since the tool call started.
+    #[ts(type = "number")]
+    pub elapsed_ms: u64,
+}
+
+/// Sent before `turn/completed` when a turn is interrupted, describing what
+/// was cut short. Items finished before the interrupt are kept as usual.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct TurnInterruptedNotification {
+    pub thread_id: String,
+    pub turn_id: String,
+    /// Agent text that was still streaming; it is kept in the thread history.
+    pub partial_agent_message: Option<String>,
+    /// Tool calls that were cancelled and recorded as aborted.
+    pub cancelled_tool_calls: Vec<CancelledToolCall>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct CancelledToolCall {
+    pub call_id: String,
+    pub tool_name: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -2280,6 +3623,91 @@ pub struct McpToolCallProgressNotification {
     pub message: String,
 }
 
+/// A file returned by a tool call and stored with the thread.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ToolArtifact {
+    /// Pass to `artifact/download` to fetch the file contents.
+    pub id: String,
+    pub name: String,
+    pub mime_type: String,
+    pub size_bytes: u64,
+    pub path: PathBuf,
+}
+
+impl From<CoreToolArtifact> for ToolArtifact {
+    fn from(value: CoreToolArtifact) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            mime_type: value.mime_type,
+            size_bytes: value.size_bytes,
+            path: value.path,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct McpToolCallArtifactNotification {
+    pub thread_id: String,
+    pub turn_id: String,
+    pub item_id: String,
+    pub artifact: ToolArtifact,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ArtifactListParams {
+    pub thread_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ArtifactListResponse {
+    /// Artifacts of the thread, oldest first.
+    pub data: Vec<ToolArtifact>,
+    pub total_bytes: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ArtifactExportParams {
+    pub thread_id: String,
+    /// Directory to copy the artifacts into; created if missing.
+    pub destination: PathBuf,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ArtifactExportResponse {
+    /// Paths of the exported files.
+    pub paths: Vec<PathBuf>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ArtifactDownloadParams {
+    pub thread_id: String,
+    pub artifact_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ArtifactDownloadResponse {
+    pub artifact: ToolArtifact,
+    /// File contents, base64-encoded.
+    pub data_base64: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -2306,6 +3734,47 @@ pub struct WindowsWorldWritableWarningNotification {
 pub struct ContextCompactedNotification {
     pub thread_id: String,
     pub turn_id: String,
+    /// Estimated tokens in context before compacting.
+    #[ts(type = "number | null")]
+    pub tokens_before: Option<i64>,
+    /// Estimated tokens in context after compacting.
+    #[ts(type = "number | null")]
+    pub tokens_after: Option<i64>,
+    pub strategy: Option<CompactionStrategy>,
+    pub items_dropped: CompactionDroppedItems,
+    /// Ids of the dropped messages, reasoning items and tool calls.
+    pub summarized_item_ids: Vec<String>,
+}
+
+v2_enum_from_core!(
+    pub enum CompactionStrategy from codex_protocol::protocol::CompactionStrategy {
+        Summary, Remote, Background
+    }
+);
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct CompactionDroppedItems {
+    pub user_messages: u32,
+    pub assistant_messages: u32,
+    pub reasoning: u32,
+    pub tool_calls: u32,
+    pub tool_outputs: u32,
+    pub other: u32,
+}
+
+impl From<CoreCompactionDroppedItems> for CompactionDroppedItems {
+    fn from(value: CoreCompactionDroppedItems) -> Self {
+        Self {
+            user_messages: value.user_messages,
+            assistant_messages: value.assistant_messages,
+            reasoning: value.reasoning,
+            tool_calls: value.tool_calls,
+            tool_outputs: value.tool_outputs,
+            other: value.other,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -2331,6 +3800,10 @@ pub struct CommandExecutionRequestApprovalParams {
     pub command_actions: Option<Vec<CommandAction>>,
     /// Optional proposed execpolicy amendment to allow similar commands without prompting.
     pub proposed_execpolicy_amendment: Option<ExecPolicyAmendment>,
+    /// Config-defined approval rule that requires this approval, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub matched_approval_rule: Option<MatchedApprovalRule>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
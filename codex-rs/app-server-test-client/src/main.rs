@@ -419,6 +419,7 @@ impl CodexClient {
                     title: Some("Codex Toy App Server".to_string()),
                     version: env!("CARGO_PKG_VERSION").to_string(),
                 },
+                protocol_versions: None,
             },
         };
 
@@ -846,6 +847,7 @@ impl CodexClient {
             cwd,
             command_actions,
             proposed_execpolicy_amendment,
+            ..
         } = params;
 
         println!(
@@ -1,5 +1,6 @@
 use crate::codex_message_processor::ApiVersion;
 use crate::codex_message_processor::PendingInterrupts;
+use crate::codex_message_processor::PendingMerges;
 use crate::codex_message_processor::PendingRollbacks;
 use crate::codex_message_processor::TurnSummary;
 use crate::codex_message_processor::TurnSummaryStore;
@@ -10,9 +11,11 @@ use crate::error_code::INTERNAL_ERROR_CODE;
 use crate::error_code::INVALID_REQUEST_ERROR_CODE;
 use crate::outgoing_message::OutgoingMessageSender;
 use codex_app_server_protocol::AccountRateLimitsUpdatedNotification;
+use codex_app_server_protocol::ActiveToolCall as V2ActiveToolCall;
 use codex_app_server_protocol::AgentMessageDeltaNotification;
 use codex_app_server_protocol::ApplyPatchApprovalParams;
 use codex_app_server_protocol::ApplyPatchApprovalResponse;
+use codex_app_server_protocol::CancelledToolCall as V2CancelledToolCall;
 use codex_app_server_protocol::CodexErrorInfo as V2CodexErrorInfo;
 use codex_app_server_protocol::CollabAgentState as V2CollabAgentStatus;
 use codex_app_server_protocol::CollabAgentTool;
@@ -25,6 +28,7 @@ use codex_app_server_protocol::CommandExecutionRequestApprovalResponse;
 use codex_app_server_protocol::CommandExecutionStatus;
 use codex_app_server_protocol::ContextCompactedNotification;
 use codex_app_server_protocol::DeprecationNoticeNotification;
+use codex_app_server_protocol::ErrorFingerprint as V2ErrorFingerprint;
 use codex_app_server_protocol::ErrorNotification;
 use codex_app_server_protocol::ExecCommandApprovalParams;
 use codex_app_server_protocol::ExecCommandApprovalResponse;
@@ -38,6 +42,8 @@ use codex_app_server_protocol::InterruptConversationResponse;
 use codex_app_server_protocol::ItemCompletedNotification;
 use codex_app_server_protocol::ItemStartedNotification;
 use codex_app_server_protocol::JSONRPCErrorError;
+use codex_app_server_protocol::MatchedApprovalRule as V2MatchedApprovalRule;
+use codex_app_server_protocol::McpToolCallArtifactNotification;
 use codex_app_server_protocol::McpToolCallError;
 use codex_app_server_protocol::McpToolCallResult;
 use codex_app_server_protocol::McpToolCallStatus;
@@ -51,7 +57,11 @@ use codex_app_server_protocol::ServerNotification;
 use codex_app_server_protocol::ServerRequestPayload;
 use codex_app_server_protocol::TerminalInteractionNotification;
 use codex_app_server_protocol::ThreadItem;
+use codex_app_server_protocol::ThreadMergeResponse;
+use codex_app_server_protocol::ThreadModelSwitchedNotification;
+use codex_app_server_protocol::ThreadResumeContextNotification;
 use codex_app_server_protocol::ThreadRollbackResponse;
+use codex_app_server_protocol::ThreadTokenForecastNotification;
 use codex_app_server_protocol::ThreadTokenUsage;
 use codex_app_server_protocol::ThreadTokenUsageUpdatedNotification;
 use codex_app_server_protocol::ToolRequestUserInputOption;
@@ -62,7 +72,9 @@ use codex_app_server_protocol::Turn;
 use codex_app_server_protocol::TurnCompletedNotification;
 use codex_app_server_protocol::TurnDiffUpdatedNotification;
 use codex_app_server_protocol::TurnError;
+use codex_app_server_protocol::TurnHeartbeatNotification;
 use codex_app_server_protocol::TurnInterruptResponse;
+use codex_app_server_protocol::TurnInterruptedNotification;
 use codex_app_server_protocol::TurnPlanStep;
 use codex_app_server_protocol::TurnPlanUpdatedNotification;
 use codex_app_server_protocol::TurnStatus;
@@ -106,6 +118,7 @@ pub(crate) async fn apply_bespoke_event_handling(
     outgoing: Arc<OutgoingMessageSender>,
     pending_interrupts: PendingInterrupts,
     pending_rollbacks: PendingRollbacks,
+    pending_merges: PendingMerges,
     turn_summary_store: TurnSummaryStore,
     api_version: ApiVersion,
     fallback_model_provider: String,
@@ -205,6 +218,7 @@ pub(crate) async fn apply_bespoke_event_handling(
             cwd,
             reason,
             proposed_execpolicy_amendment,
+            matched_approval_rule,
             parsed_cmd,
         }) => match api_version {
             ApiVersion::V1 => {
@@ -245,6 +259,7 @@ pub(crate) async fn apply_bespoke_event_handling(
                     cwd: Some(cwd.clone()),
                     command_actions: Some(command_actions.clone()),
                     proposed_execpolicy_amendment: proposed_execpolicy_amendment_v2,
+                    matched_approval_rule: matched_approval_rule.map(V2MatchedApprovalRule::from),
                 };
                 let rx = outgoing
                     .send_request(ServerRequestPayload::CommandExecutionRequestApproval(
@@ -341,6 +356,86 @@ pub(crate) async fn apply_bespoke_event_handling(
                 .send_server_notification(ServerNotification::ItemCompleted(notification))
                 .await;
         }
+        EventMsg::ToolArtifact(artifact_event) => {
+            let notification = McpToolCallArtifactNotification {
+                thread_id: conversation_id.to_string(),
+                turn_id: event_turn_id.clone(),
+                item_id: artifact_event.call_id,
+                artifact: artifact_event.artifact.into(),
+            };
+            outgoing
+                .send_server_notification(ServerNotification::McpToolCallArtifact(notification))
+                .await;
+        }
+        EventMsg::ResumeContext(resume_context_event) => {
+            let notification = ThreadResumeContextNotification {
+                thread_id: conversation_id.to_string(),
+                changes: resume_context_event
+                    .changes
+                    .into_iter()
+                    .map(Into::into)
+                    .collect(),
+            };
+            outgoing
+                .send_server_notification(ServerNotification::ThreadResumeContext(notification))
+                .await;
+        }
+        EventMsg::TurnInterrupted(interrupted_event) => {
+            let notification = TurnInterruptedNotification {
+                thread_id: conversation_id.to_string(),
+                turn_id: interrupted_event.turn_id,
+                partial_agent_message: interrupted_event.partial_agent_message,
+                cancelled_tool_calls: interrupted_event
+                    .cancelled_tool_calls
+                    .into_iter()
+                    .map(|call| V2CancelledToolCall {
+                        call_id: call.call_id,
+                        tool_name: call.tool_name,
+                    })
+                    .collect(),
+            };
+            outgoing
+                .send_server_notification(ServerNotification::TurnInterrupted(notification))
+                .await;
+        }
+        EventMsg::Heartbeat(heartbeat_event) => {
+            let notification = TurnHeartbeatNotification {
+                thread_id: conversation_id.to_string(),
+                turn_id: event_turn_id.clone(),
+                phase: heartbeat_event.phase.into(),
+                turn_elapsed_ms: heartbeat_event.turn_elapsed_ms,
+                silent_ms: heartbeat_event.silent_ms,
+                active_tools: heartbeat_event
+                    .active_tools
+                    .into_iter()
+                    .map(|tool| V2ActiveToolCall {
+                        call_id: tool.call_id,
+                        tool_name: tool.tool_name,
+                        elapsed_ms: tool.elapsed_ms,
+                    })
+                    .collect(),
+            };
+            outgoing
+                .send_server_notification(ServerNotification::TurnHeartbeat(notification))
+                .await;
+        }
+        EventMsg::ModelSwitch(model_switch_event) => {
+            let notification = ThreadModelSwitchedNotification {
+                thread_id: conversation_id.to_string(),
+                previous_model: model_switch_event.previous_model,
+                model: model_switch_event.model,
+                instructions_updated: model_switch_event.instructions_updated,
+                tools_added: model_switch_event.tools_added,
+                tools_removed: model_switch_event.tools_removed,
+                previous_context_window: model_switch_event.previous_context_window,
+                context_window: model_switch_event.context_window,
+                tokens_in_context: model_switch_event.tokens_in_context,
+                compaction_pending: model_switch_event.compaction_pending,
+            };
+            outgoing
+                .send_server_notification(ServerNotification::ThreadModelSwitched(notification))
+                .await;
+        }
         EventMsg::CollabAgentSpawnBegin(begin_event) => {
             let item = ThreadItem::CollabAgentToolCall {
                 id: begin_event.call_id,
@@ -564,10 +659,15 @@ pub(crate) async fn apply_bespoke_event_handling(
                 .send_server_notification(ServerNotification::AgentMessageDelta(notification))
                 .await;
         }
-        EventMsg::ContextCompacted(..) => {
+        EventMsg::ContextCompacted(event) => {
             let notification = ContextCompactedNotification {
                 thread_id: conversation_id.to_string(),
                 turn_id: event_turn_id.clone(),
+                tokens_before: event.tokens_before,
+                tokens_after: event.tokens_after,
+                strategy: event.strategy.map(Into::into),
+                items_dropped: event.items_dropped.into(),
+                summarized_item_ids: event.summarized_item_ids,
             };
             outgoing
                 .send_server_notification(ServerNotification::ContextCompacted(notification))
@@ -625,6 +725,23 @@ pub(crate) async fn apply_bespoke_event_handling(
             handle_token_count_event(conversation_id, event_turn_id, token_count_event, &outgoing)
                 .await;
         }
+        EventMsg::TokenForecast(forecast) => {
+            if matches!(api_version, ApiVersion::V2) {
+                let notification = ThreadTokenForecastNotification {
+                    thread_id: conversation_id.to_string(),
+                    turn_id: event_turn_id.clone(),
+                    estimated_tokens: forecast.estimated_tokens,
+                    projected_tokens: forecast.projected_tokens,
+                    model_context_window: forecast.model_context_window,
+                    auto_compact_token_limit: forecast.auto_compact_token_limit,
+                    will_compact: forecast.will_compact,
+                    heuristic: forecast.heuristic,
+                };
+                outgoing
+                    .send_server_notification(ServerNotification::ThreadTokenForecast(notification))
+                    .await;
+            }
+        }
         EventMsg::Error(ev) => {
             let message = ev.message.clone();
             let codex_error_info = ev.codex_error_info.clone();
@@ -637,7 +754,7 @@ pub(crate) async fn apply_bespoke_event_handling(
                 codex_error_info,
                 Some(CoreCodexErrorInfo::ThreadRollbackFailed)
             ) {
-                return handle_thread_rollback_failed(
+                return fail_pending_request(
                     conversation_id,
                     message,
                     &pending_rollbacks,
@@ -645,6 +762,14 @@ pub(crate) async fn apply_bespoke_event_handling(
                 )
                 .await;
             };
+            // Likewise for an in-flight `thread/merge` request.
+            if matches!(
+                codex_error_info,
+                Some(CoreCodexErrorInfo::ConversationMergeFailed)
+            ) {
+                return fail_pending_request(conversation_id, message, &pending_merges, &outgoing)
+                    .await;
+            };
 
             let turn_error = TurnError {
                 message: ev.message,
@@ -658,6 +783,7 @@ pub(crate) async fn apply_bespoke_event_handling(
                     will_retry: false,
                     thread_id: conversation_id.to_string(),
                     turn_id: event_turn_id.clone(),
+                    fingerprint: ev.fingerprint.map(V2ErrorFingerprint::from),
                 }))
                 .await;
         }
@@ -675,6 +801,7 @@ pub(crate) async fn apply_bespoke_event_handling(
                     will_retry: true,
                     thread_id: conversation_id.to_string(),
                     turn_id: event_turn_id.clone(),
+                    fingerprint: None,
                 }))
                 .await;
         }
@@ -701,12 +828,16 @@ pub(crate) async fn apply_bespoke_event_handling(
                 .await;
         }
         EventMsg::EnteredReviewMode(review_request) => {
-            let review = review_request
-                .user_facing_hint
-                .unwrap_or_else(|| review_prompts::user_facing_hint(&review_request.target));
+            let review = review_request.user_facing_hint.unwrap_or_else(|| {
+                review_prompts::scoped_user_facing_hint(
+                    &review_request.target,
+                    review_request.paths.as_ref(),
+                )
+            });
             let item = ThreadItem::EnteredReviewMode {
                 id: event_turn_id.clone(),
                 review,
+                paths: review_request.paths.map(Into::into),
             };
             let started = ItemStartedNotification {
                 thread_id: conversation_id.to_string(),
@@ -755,6 +886,7 @@ pub(crate) async fn apply_bespoke_event_handling(
             let item = ThreadItem::ExitedReviewMode {
                 id: event_turn_id.clone(),
                 review,
+                paths: review_event.paths.map(Into::into),
             };
             let started = ItemStartedNotification {
                 thread_id: conversation_id.to_string(),
@@ -999,6 +1131,18 @@ pub(crate) async fn apply_bespoke_event_handling(
             )
             .await;
         }
+        EventMsg::ConversationMerged(merged_event) => {
+            let pending = pending_merges.lock().await.remove(&conversation_id);
+            if let Some(request_id) = pending {
+                let response = ThreadMergeResponse {
+                    source_thread_id: merged_event.source_thread_id.to_string(),
+                    final_message: merged_event.final_message,
+                    diff_turns: merged_event.diff_turns,
+                    artifacts: merged_event.artifacts.into_iter().map(Into::into).collect(),
+                };
+                outgoing.send_response(request_id, response).await;
+            }
+        }
         EventMsg::ThreadRolledBack(_rollback_event) => {
             let pending = {
                 let mut map = pending_rollbacks.lock().await;
@@ -1267,18 +1411,20 @@ async fn handle_turn_interrupted(
     .await;
 }
 
-async fn handle_thread_rollback_failed(
+/// Fails the `thread/rollback` or `thread/merge` request pending for
+/// `conversation_id`, if any.
+async fn fail_pending_request(
     conversation_id: ThreadId,
     message: String,
-    pending_rollbacks: &PendingRollbacks,
+    pending_requests: &PendingRollbacks,
     outgoing: &OutgoingMessageSender,
 ) {
-    let pending_rollback = {
-        let mut map = pending_rollbacks.lock().await;
+    let pending_request = {
+        let mut map = pending_requests.lock().await;
         map.remove(&conversation_id)
     };
 
-    if let Some(request_id) = pending_rollback {
+    if let Some(request_id) = pending_request {
         outgoing
             .send_error(
                 request_id,
@@ -1495,6 +1641,12 @@ fn convert_patch_changes(changes: &HashMap<PathBuf, CoreFileChange>) -> Vec<File
             path: path.to_string_lossy().into_owned(),
             kind: map_patch_change_kind(change),
             diff: format_file_change_diff(change),
+            mode: match change {
+                CoreFileChange::Add { mode, .. } | CoreFileChange::Update { mode, .. } => {
+                    mode.map(Into::into)
+                }
+                CoreFileChange::Delete { .. } | CoreFileChange::Symlink { .. } => None,
+            },
         })
         .collect();
     converted.sort_by(|a, b| a.path.cmp(&b.path));
@@ -1508,16 +1660,21 @@ fn map_patch_change_kind(change: &CoreFileChange) -> V2PatchChangeKind {
         CoreFileChange::Update { move_path, .. } => V2PatchChangeKind::Update {
             move_path: move_path.clone(),
         },
+        CoreFileChange::Symlink { target } => V2PatchChangeKind::Symlink {
+            target: target.clone(),
+        },
     }
 }
 
 fn format_file_change_diff(change: &CoreFileChange) -> String {
     match change {
-        CoreFileChange::Add { content } => content.clone(),
+        CoreFileChange::Add { content, .. } => content.clone(),
         CoreFileChange::Delete { content } => content.clone(),
+        CoreFileChange::Symlink { target } => target.display().to_string(),
         CoreFileChange::Update {
             unified_diff,
             move_path,
+            ..
         } => {
             if let Some(path) = move_path {
                 format!("{unified_diff}\n\nMoved to: {}", path.display())
@@ -1534,6 +1691,12 @@ fn map_file_change_approval_decision(
     match decision {
         FileChangeApprovalDecision::Accept => (ReviewDecision::Approved, None),
         FileChangeApprovalDecision::AcceptForSession => (ReviewDecision::ApprovedForSession, None),
+        FileChangeApprovalDecision::AcceptAlways { scope } => (
+            ReviewDecision::ApprovedAlways {
+                scope: scope.to_core(),
+            },
+            None,
+        ),
         FileChangeApprovalDecision::Decline => {
             (ReviewDecision::Denied, Some(PatchApplyStatus::Declined))
         }
@@ -1631,6 +1794,12 @@ async fn on_command_execution_request_approval_response(
                 CommandExecutionApprovalDecision::AcceptForSession => {
                     (ReviewDecision::ApprovedForSession, None)
                 }
+                CommandExecutionApprovalDecision::AcceptAlways { scope } => (
+                    ReviewDecision::ApprovedAlways {
+                        scope: scope.to_core(),
+                    },
+                    None,
+                ),
                 CommandExecutionApprovalDecision::AcceptWithExecpolicyAmendment {
                     execpolicy_amendment,
                 } => (
@@ -1730,6 +1899,12 @@ async fn construct_mcp_tool_call_end_notification(
             None,
             Some(McpToolCallError {
                 message: message.clone(),
+                kind: end_event.error.as_ref().map(|error| error.kind.into()),
+                code: end_event.error.as_ref().and_then(|error| error.code),
+                retryable: end_event
+                    .error
+                    .as_ref()
+                    .is_some_and(|error| error.retryable),
             }),
         ),
     };
@@ -1760,9 +1935,12 @@ mod tests {
     use anyhow::Result;
     use anyhow::anyhow;
     use anyhow::bail;
+    use codex_app_server_protocol::McpToolCallErrorKind;
     use codex_app_server_protocol::TurnPlanStepStatus;
     use codex_core::protocol::CreditsSnapshot;
     use codex_core::protocol::McpInvocation;
+    use codex_core::protocol::McpToolCallErrorInfo;
+    use codex_core::protocol::McpToolCallErrorKind as CoreMcpToolCallErrorKind;
     use codex_core::protocol::RateLimitSnapshot;
     use codex_core::protocol::RateLimitWindow;
     use codex_core::protocol::TokenUsage;
@@ -2320,6 +2498,7 @@ mod tests {
             },
             duration: Duration::from_nanos(92708),
             result: Ok(result),
+            error: None,
         };
 
         let thread_id = ThreadId::new().to_string();
@@ -2363,6 +2542,13 @@ mod tests {
             },
             duration: Duration::from_millis(1),
             result: Err("boom".to_string()),
+            error: Some(McpToolCallErrorInfo {
+                kind: CoreMcpToolCallErrorKind::ServerBusy,
+                code: None,
+                message: "boom".to_string(),
+                retryable: true,
+                attempts: 3,
+            }),
         };
 
         let thread_id = ThreadId::new().to_string();
@@ -2386,6 +2572,9 @@ mod tests {
                 result: None,
                 error: Some(McpToolCallError {
                     message: "boom".to_string(),
+                    kind: Some(McpToolCallErrorKind::ServerBusy),
+                    code: None,
+                    retryable: true,
                 }),
                 duration_ms: Some(1),
             },
@@ -5,6 +5,7 @@ use crate::fuzzy_file_search::run_fuzzy_file_search;
 use crate::models::supported_models;
 use crate::outgoing_message::OutgoingMessageSender;
 use crate::outgoing_message::OutgoingNotification;
+use base64::Engine;
 use chrono::DateTime;
 use chrono::SecondsFormat;
 use chrono::Utc;
@@ -14,10 +15,21 @@ use codex_app_server_protocol::AccountUpdatedNotification;
 use codex_app_server_protocol::AddConversationListenerParams;
 use codex_app_server_protocol::AddConversationSubscriptionResponse;
 use codex_app_server_protocol::AppInfo as ApiAppInfo;
+use codex_app_server_protocol::ApprovalTarget as V2ApprovalTarget;
+use codex_app_server_protocol::ApprovalsListParams;
+use codex_app_server_protocol::ApprovalsListResponse;
+use codex_app_server_protocol::ApprovalsRevokeParams;
+use codex_app_server_protocol::ApprovalsRevokeResponse;
 use codex_app_server_protocol::AppsListParams;
 use codex_app_server_protocol::AppsListResponse;
 use codex_app_server_protocol::ArchiveConversationParams;
 use codex_app_server_protocol::ArchiveConversationResponse;
+use codex_app_server_protocol::ArtifactDownloadParams;
+use codex_app_server_protocol::ArtifactDownloadResponse;
+use codex_app_server_protocol::ArtifactExportParams;
+use codex_app_server_protocol::ArtifactExportResponse;
+use codex_app_server_protocol::ArtifactListParams;
+use codex_app_server_protocol::ArtifactListResponse;
 use codex_app_server_protocol::AskForApproval;
 use codex_app_server_protocol::AuthMode;
 use codex_app_server_protocol::AuthStatusChangeNotification;
@@ -25,12 +37,18 @@ use codex_app_server_protocol::CancelLoginAccountParams;
 use codex_app_server_protocol::CancelLoginAccountResponse;
 use codex_app_server_protocol::CancelLoginAccountStatus;
 use codex_app_server_protocol::CancelLoginChatGptResponse;
+use codex_app_server_protocol::ChangesQueryParams;
+use codex_app_server_protocol::ChangesQueryResponse;
 use codex_app_server_protocol::ClientRequest;
 use codex_app_server_protocol::CollaborationModeListParams;
 use codex_app_server_protocol::CollaborationModeListResponse;
 use codex_app_server_protocol::CommandExecParams;
 use codex_app_server_protocol::ConversationGitInfo;
 use codex_app_server_protocol::ConversationSummary;
+use codex_app_server_protocol::DoctorCheck;
+use codex_app_server_protocol::DoctorCheckKind;
+use codex_app_server_protocol::DoctorRunResponse;
+use codex_app_server_protocol::DoctorStatus;
 use codex_app_server_protocol::ExecOneOffCommandResponse;
 use codex_app_server_protocol::FeedbackUploadParams;
 use codex_app_server_protocol::FeedbackUploadResponse;
@@ -50,6 +68,8 @@ use codex_app_server_protocol::GetUserSavedConfigResponse;
 use codex_app_server_protocol::GitDiffToRemoteResponse;
 use codex_app_server_protocol::GitInfo as ApiGitInfo;
 use codex_app_server_protocol::InputItem as WireInputItem;
+use codex_app_server_protocol::InstructionsReadParams;
+use codex_app_server_protocol::InstructionsReadResponse;
 use codex_app_server_protocol::InterruptConversationParams;
 use codex_app_server_protocol::JSONRPCErrorError;
 use codex_app_server_protocol::ListConversationsParams;
@@ -72,31 +92,55 @@ use codex_app_server_protocol::ModelListParams;
 use codex_app_server_protocol::ModelListResponse;
 use codex_app_server_protocol::NewConversationParams;
 use codex_app_server_protocol::NewConversationResponse;
+use codex_app_server_protocol::PatchProvenance;
+use codex_app_server_protocol::ProjectDoc;
+use codex_app_server_protocol::ProvenanceExportParams;
+use codex_app_server_protocol::ProvenanceExportResponse;
+use codex_app_server_protocol::RecordedFileChange;
+use codex_app_server_protocol::RememberedApproval as V2RememberedApproval;
 use codex_app_server_protocol::RemoveConversationListenerParams;
 use codex_app_server_protocol::RemoveConversationSubscriptionResponse;
 use codex_app_server_protocol::RequestId;
 use codex_app_server_protocol::ResumeConversationParams;
 use codex_app_server_protocol::ResumeConversationResponse;
 use codex_app_server_protocol::ReviewDelivery as ApiReviewDelivery;
+use codex_app_server_protocol::ReviewPaths as ApiReviewPaths;
 use codex_app_server_protocol::ReviewStartParams;
 use codex_app_server_protocol::ReviewStartResponse;
 use codex_app_server_protocol::ReviewTarget as ApiReviewTarget;
 use codex_app_server_protocol::SandboxMode;
+use codex_app_server_protocol::SandboxPolicyEvaluateParams;
+use codex_app_server_protocol::SandboxPolicyEvaluateResponse;
+use codex_app_server_protocol::SandboxPolicyReadParams;
+use codex_app_server_protocol::SandboxPolicyReadResponse;
 use codex_app_server_protocol::SendUserMessageParams;
 use codex_app_server_protocol::SendUserMessageResponse;
 use codex_app_server_protocol::SendUserTurnParams;
 use codex_app_server_protocol::SendUserTurnResponse;
 use codex_app_server_protocol::ServerNotification;
 use codex_app_server_protocol::SessionConfiguredNotification;
+use codex_app_server_protocol::SessionTemplate;
+use codex_app_server_protocol::SessionTemplateArgument;
+use codex_app_server_protocol::SessionTemplateScope;
 use codex_app_server_protocol::SetDefaultModelParams;
 use codex_app_server_protocol::SetDefaultModelResponse;
 use codex_app_server_protocol::SkillsConfigWriteParams;
 use codex_app_server_protocol::SkillsConfigWriteResponse;
 use codex_app_server_protocol::SkillsListParams;
 use codex_app_server_protocol::SkillsListResponse;
+use codex_app_server_protocol::StatsGroup;
+use codex_app_server_protocol::StatsGrouping;
+use codex_app_server_protocol::StatsReadParams;
+use codex_app_server_protocol::StatsReadResponse;
+use codex_app_server_protocol::TemplateListParams;
+use codex_app_server_protocol::TemplateListResponse;
 use codex_app_server_protocol::Thread;
 use codex_app_server_protocol::ThreadArchiveParams;
 use codex_app_server_protocol::ThreadArchiveResponse;
+use codex_app_server_protocol::ThreadEnvironmentDiffParams;
+use codex_app_server_protocol::ThreadEnvironmentDiffResponse;
+use codex_app_server_protocol::ThreadFeatureSetParams;
+use codex_app_server_protocol::ThreadFeatureSetResponse;
 use codex_app_server_protocol::ThreadForkParams;
 use codex_app_server_protocol::ThreadForkResponse;
 use codex_app_server_protocol::ThreadItem;
@@ -104,16 +148,30 @@ use codex_app_server_protocol::ThreadListParams;
 use codex_app_server_protocol::ThreadListResponse;
 use codex_app_server_protocol::ThreadLoadedListParams;
 use codex_app_server_protocol::ThreadLoadedListResponse;
+use codex_app_server_protocol::ThreadMergeParams;
+use codex_app_server_protocol::ThreadMetadataUpdateParams;
+use codex_app_server_protocol::ThreadMetadataUpdateResponse;
 use codex_app_server_protocol::ThreadReadParams;
 use codex_app_server_protocol::ThreadReadResponse;
 use codex_app_server_protocol::ThreadResumeParams;
 use codex_app_server_protocol::ThreadResumeResponse;
 use codex_app_server_protocol::ThreadRollbackParams;
 use codex_app_server_protocol::ThreadSortKey;
+use codex_app_server_protocol::ThreadStartFromTemplateParams;
 use codex_app_server_protocol::ThreadStartParams;
 use codex_app_server_protocol::ThreadStartResponse;
 use codex_app_server_protocol::ThreadStartedNotification;
+use codex_app_server_protocol::ThreadTrimParams;
+use codex_app_server_protocol::ThreadTrimResponse;
+use codex_app_server_protocol::ThreadUnarchiveParams;
+use codex_app_server_protocol::ThreadUnarchiveResponse;
+use codex_app_server_protocol::ThreadWorkspaceDelta;
+use codex_app_server_protocol::ThreadWorkspaceDiffParams;
+use codex_app_server_protocol::ThreadWorkspaceDiffResponse;
+use codex_app_server_protocol::ThreadWorkspaceFileDelta;
 use codex_app_server_protocol::Turn;
+use codex_app_server_protocol::TurnContextReadParams;
+use codex_app_server_protocol::TurnContextReadResponse;
 use codex_app_server_protocol::TurnError;
 use codex_app_server_protocol::TurnInterruptParams;
 use codex_app_server_protocol::TurnStartParams;
@@ -134,10 +192,19 @@ use codex_core::InitialHistory;
 use codex_core::NewThread;
 use codex_core::RolloutRecorder;
 use codex_core::SessionMeta;
+use codex_core::SessionMetadataUpdate;
 use codex_core::ThreadManager;
 use codex_core::ThreadSortKey as CoreThreadSortKey;
+use codex_core::approval_memory::ApprovalTarget as CoreApprovalTarget;
+use codex_core::approval_memory::list_persisted_approvals;
+use codex_core::approval_memory::revoke_persisted_approval;
+use codex_core::artifacts::export_artifacts;
+use codex_core::artifacts::list_artifacts;
+use codex_core::artifacts::load_artifact;
 use codex_core::auth::CLIENT_ID;
 use codex_core::auth::login_with_api_key;
+use codex_core::change_index::ChangeQuery;
+use codex_core::change_index::query_changes;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 use codex_core::config::ConfigService;
@@ -145,24 +212,44 @@ use codex_core::config::edit::ConfigEdit;
 use codex_core::config::edit::ConfigEditsBuilder;
 use codex_core::config::types::McpServerTransportConfig;
 use codex_core::default_client::get_codex_user_agent;
+use codex_core::default_client::originator;
+use codex_core::doctor::DoctorCheckKind as CoreDoctorCheckKind;
+use codex_core::doctor::DoctorStatus as CoreDoctorStatus;
+use codex_core::doctor::run_doctor;
+use codex_core::environment_snapshot::diff_rollout_environment;
 use codex_core::error::CodexErr;
 use codex_core::exec::ExecParams;
 use codex_core::exec_env::create_env;
 use codex_core::features::Feature;
+use codex_core::find_archived_thread_path_by_id_str;
 use codex_core::find_thread_path_by_id_str;
 use codex_core::git_info::git_diff_to_remote;
 use codex_core::mcp::collect_mcp_snapshot;
 use codex_core::mcp::group_tools_by_server;
 use codex_core::parse_cursor;
+use codex_core::project_doc::load_project_docs;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::Op;
 use codex_core::protocol::ReviewDelivery as CoreReviewDelivery;
 use codex_core::protocol::ReviewRequest;
 use codex_core::protocol::ReviewTarget as CoreReviewTarget;
 use codex_core::protocol::SessionConfiguredEvent;
+use codex_core::provenance::move_provenance;
+use codex_core::provenance::read_provenance;
+use codex_core::provenance::read_verification_key;
 use codex_core::read_head_for_summary;
 use codex_core::read_session_meta_line;
 use codex_core::sandboxing::SandboxPermissions;
+use codex_core::session_day_dir_for_file_name;
+use codex_core::session_stats::SessionStatsGrouping;
+use codex_core::session_stats::SessionStatsQuery;
+use codex_core::session_stats::parse_stats_day;
+use codex_core::session_stats::query_session_stats;
+use codex_core::session_templates::SessionTemplateScope as CoreSessionTemplateScope;
+use codex_core::session_templates::discover_templates;
+use codex_core::session_templates::find_template;
+use codex_core::update_session_metadata;
+use codex_core::workspace_diff::diff_sessions;
 use codex_feedback::CodexFeedback;
 use codex_login::ServerOptions as LoginServerOptions;
 use codex_login::ShutdownHandle;
@@ -206,6 +293,8 @@ pub(crate) type PendingInterrupts = Arc<Mutex<HashMap<ThreadId, PendingInterrupt
 
 pub(crate) type PendingRollbacks = Arc<Mutex<HashMap<ThreadId, RequestId>>>;
 
+pub(crate) type PendingMerges = Arc<Mutex<HashMap<ThreadId, RequestId>>>;
+
 /// Per-conversation accumulation of the latest states e.g. error message while a turn runs.
 #[derive(Default, Clone)]
 pub(crate) struct TurnSummary {
@@ -251,6 +340,8 @@ pub(crate) struct CodexMessageProcessor {
     pending_interrupts: PendingInterrupts,
     // Queue of pending rollback requests per conversation. We reply when ThreadRollback arrives.
     pending_rollbacks: PendingRollbacks,
+    // Pending merge requests per conversation. We reply when ConversationMerged arrives.
+    pending_merges: PendingMerges,
     turn_summary_store: TurnSummaryStore,
     pending_fuzzy_searches: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>,
     feedback: CodexFeedback,
@@ -307,6 +398,7 @@ impl CodexMessageProcessor {
             active_login: Arc::new(Mutex::new(None)),
             pending_interrupts: Arc::new(Mutex::new(HashMap::new())),
             pending_rollbacks: Arc::new(Mutex::new(HashMap::new())),
+            pending_merges: Arc::new(Mutex::new(HashMap::new())),
             turn_summary_store: Arc::new(Mutex::new(HashMap::new())),
             pending_fuzzy_searches: Arc::new(Mutex::new(HashMap::new())),
             feedback,
@@ -325,6 +417,7 @@ impl CodexMessageProcessor {
 
     fn review_request_from_target(
         target: ApiReviewTarget,
+        paths: Option<ApiReviewPaths>,
     ) -> Result<(ReviewRequest, String), JSONRPCErrorError> {
         fn invalid_request(message: String) -> JSONRPCErrorError {
             JSONRPCErrorError {
@@ -373,10 +466,15 @@ impl CodexMessageProcessor {
             ApiReviewTarget::Custom { instructions } => CoreReviewTarget::Custom { instructions },
         };
 
-        let hint = codex_core::review_prompts::user_facing_hint(&core_target);
+        let paths = paths
+            .map(ApiReviewPaths::into_core)
+            .and_then(codex_core::review_prompts::normalize_review_paths);
+        let hint =
+            codex_core::review_prompts::scoped_user_facing_hint(&core_target, paths.as_ref());
         let review_request = ReviewRequest {
             target: core_target,
             user_facing_hint: Some(hint.clone()),
+            paths,
         };
 
         Ok((review_request, hint))
@@ -391,6 +489,9 @@ impl CodexMessageProcessor {
             ClientRequest::ThreadStart { request_id, params } => {
                 self.thread_start(request_id, params).await;
             }
+            ClientRequest::ThreadStartFromTemplate { request_id, params } => {
+                self.thread_start_from_template(request_id, params).await;
+            }
             ClientRequest::ThreadResume { request_id, params } => {
                 self.thread_resume(request_id, params).await;
             }
@@ -400,9 +501,24 @@ impl CodexMessageProcessor {
             ClientRequest::ThreadArchive { request_id, params } => {
                 self.thread_archive(request_id, params).await;
             }
+            ClientRequest::ThreadUnarchive { request_id, params } => {
+                self.thread_unarchive(request_id, params).await;
+            }
+            ClientRequest::ThreadMetadataUpdate { request_id, params } => {
+                self.thread_metadata_update(request_id, params).await;
+            }
             ClientRequest::ThreadRollback { request_id, params } => {
                 self.thread_rollback(request_id, params).await;
             }
+            ClientRequest::ThreadTrim { request_id, params } => {
+                self.thread_trim(request_id, params).await;
+            }
+            ClientRequest::ThreadMerge { request_id, params } => {
+                self.thread_merge(request_id, params).await;
+            }
+            ClientRequest::ThreadFeatureSet { request_id, params } => {
+                self.thread_feature_set(request_id, params).await;
+            }
             ClientRequest::ThreadList { request_id, params } => {
                 self.thread_list(request_id, params).await;
             }
@@ -412,9 +528,39 @@ impl CodexMessageProcessor {
             ClientRequest::ThreadRead { request_id, params } => {
                 self.thread_read(request_id, params).await;
             }
+            ClientRequest::ThreadEnvironmentDiff { request_id, params } => {
+                self.thread_environment_diff(request_id, params).await;
+            }
+            ClientRequest::ThreadWorkspaceDiff { request_id, params } => {
+                self.thread_workspace_diff(request_id, params).await;
+            }
+            ClientRequest::StatsRead { request_id, params } => {
+                self.stats_read(request_id, params).await;
+            }
+            ClientRequest::ChangesQuery { request_id, params } => {
+                self.changes_query(request_id, params).await;
+            }
+            ClientRequest::ProvenanceExport { request_id, params } => {
+                self.provenance_export(request_id, params).await;
+            }
+            ClientRequest::ApprovalsList { request_id, params } => {
+                self.approvals_list(request_id, params).await;
+            }
+            ClientRequest::ApprovalsRevoke { request_id, params } => {
+                self.approvals_revoke(request_id, params).await;
+            }
+            ClientRequest::DoctorRun { request_id, .. } => {
+                self.doctor_run(request_id).await;
+            }
             ClientRequest::SkillsList { request_id, params } => {
                 self.skills_list(request_id, params).await;
             }
+            ClientRequest::InstructionsRead { request_id, params } => {
+                self.instructions_read(request_id, params).await;
+            }
+            ClientRequest::TemplateList { request_id, params } => {
+                self.template_list(request_id, params).await;
+            }
             ClientRequest::AppsList { request_id, params } => {
                 self.apps_list(request_id, params).await;
             }
@@ -427,6 +573,18 @@ impl CodexMessageProcessor {
             ClientRequest::TurnInterrupt { request_id, params } => {
                 self.turn_interrupt(request_id, params).await;
             }
+            ClientRequest::TurnContextRead { request_id, params } => {
+                self.turn_context_read(request_id, params).await;
+            }
+            ClientRequest::ArtifactList { request_id, params } => {
+                self.artifact_list(request_id, params).await;
+            }
+            ClientRequest::ArtifactExport { request_id, params } => {
+                self.artifact_export(request_id, params).await;
+            }
+            ClientRequest::ArtifactDownload { request_id, params } => {
+                self.artifact_download(request_id, params).await;
+            }
             ClientRequest::ReviewStart { request_id, params } => {
                 self.review_start(request_id, params).await;
             }
@@ -570,6 +728,12 @@ impl CodexMessageProcessor {
             ClientRequest::ConfigRequirementsRead { .. } => {
                 warn!("ConfigRequirementsRead request reached CodexMessageProcessor unexpectedly");
             }
+            ClientRequest::SandboxPolicyRead { request_id, params } => {
+                self.sandbox_policy_read(request_id, params).await;
+            }
+            ClientRequest::SandboxPolicyEvaluate { request_id, params } => {
+                self.sandbox_policy_evaluate(request_id, params).await;
+            }
             ClientRequest::GetAccountRateLimits {
                 request_id,
                 params: _,
@@ -1307,6 +1471,27 @@ impl CodexMessageProcessor {
         });
     }
 
+    async fn sandbox_policy_read(&self, request_id: RequestId, params: SandboxPolicyReadParams) {
+        let cwd = params.cwd.unwrap_or_else(|| self.config.cwd.clone());
+        let report = codex_core::sandboxing::effective_policy_report(&self.config, &cwd);
+        self.outgoing
+            .send_response(request_id, SandboxPolicyReadResponse::from(report))
+            .await;
+    }
+
+    async fn sandbox_policy_evaluate(
+        &self,
+        request_id: RequestId,
+        params: SandboxPolicyEvaluateParams,
+    ) {
+        let cwd = params.cwd.unwrap_or_else(|| self.config.cwd.clone());
+        let evaluation =
+            codex_core::sandboxing::evaluate_policy(&self.config, &params.target.to_core(), &cwd);
+        self.outgoing
+            .send_response(request_id, SandboxPolicyEvaluateResponse::from(evaluation))
+            .await;
+    }
+
     async fn process_new_conversation(
         &mut self,
         request_id: RequestId,
@@ -1425,76 +1610,7 @@ impl CodexMessageProcessor {
 
         match self.thread_manager.start_thread(config).await {
             Ok(new_conv) => {
-                let NewThread {
-                    thread_id,
-                    session_configured,
-                    ..
-                } = new_conv;
-                let rollout_path = session_configured.rollout_path.clone();
-                let fallback_provider = self.config.model_provider_id.as_str();
-
-                // A bit hacky, but the summary contains a lot of useful information for the thread
-                // that unfortunately does not get returned from thread_manager.start_thread().
-                let thread = match read_summary_from_rollout(
-                    rollout_path.as_path(),
-                    fallback_provider,
-                )
-                .await
-                {
-                    Ok(summary) => summary_to_thread(summary),
-                    Err(err) => {
-                        self.send_internal_error(
-                            request_id,
-                            format!(
-                                "failed to load rollout `{}` for thread {thread_id}: {err}",
-                                rollout_path.display()
-                            ),
-                        )
-                        .await;
-                        return;
-                    }
-                };
-
-                let SessionConfiguredEvent {
-                    model,
-                    model_provider_id,
-                    cwd,
-                    approval_policy,
-                    sandbox_policy,
-                    ..
-                } = session_configured;
-                let response = ThreadStartResponse {
-                    thread: thread.clone(),
-                    model,
-                    model_provider: model_provider_id,
-                    cwd,
-                    approval_policy: approval_policy.into(),
-                    sandbox: sandbox_policy.into(),
-                    reasoning_effort: session_configured.reasoning_effort,
-                };
-
-                // Auto-attach a thread listener when starting a thread.
-                // Use the same behavior as the v1 API, with opt-in support for raw item events.
-                if let Err(err) = self
-                    .attach_conversation_listener(
-                        thread_id,
-                        params.experimental_raw_events,
-                        ApiVersion::V2,
-                    )
-                    .await
-                {
-                    tracing::warn!(
-                        "failed to attach listener for thread {}: {}",
-                        thread_id,
-                        err.message
-                    );
-                }
-
-                self.outgoing.send_response(request_id, response).await;
-
-                let notif = ThreadStartedNotification { thread };
-                self.outgoing
-                    .send_server_notification(ServerNotification::ThreadStarted(notif))
+                self.send_thread_started(request_id, new_conv, params.experimental_raw_events)
                     .await;
             }
             Err(err) => {
@@ -1508,6 +1624,138 @@ impl CodexMessageProcessor {
         }
     }
 
+    async fn thread_start_from_template(
+        &mut self,
+        request_id: RequestId,
+        params: ThreadStartFromTemplateParams,
+    ) {
+        let ThreadStartFromTemplateParams {
+            template,
+            arguments,
+            cwd,
+        } = params;
+        let cwd = cwd.map(PathBuf::from);
+        let lookup_cwd = cwd.clone().unwrap_or_else(|| self.config.cwd.clone());
+        let Some(template) = find_template(&self.config.codex_home, &lookup_cwd, &template).await
+        else {
+            self.send_invalid_request_error(
+                request_id,
+                format!("no session template named `{template}`"),
+            )
+            .await;
+            return;
+        };
+
+        let typesafe_overrides = ConfigOverrides {
+            cwd,
+            config_profile: template.profile.clone(),
+            codex_linux_sandbox_exe: self.codex_linux_sandbox_exe.clone(),
+            ..Default::default()
+        };
+        let config =
+            match derive_config_from_params(&self.cli_overrides, None, typesafe_overrides).await {
+                Ok(config) => config,
+                Err(err) => {
+                    self.send_invalid_request_error(
+                        request_id,
+                        format!("error deriving config: {err}"),
+                    )
+                    .await;
+                    return;
+                }
+            };
+
+        match self
+            .thread_manager
+            .start_thread_from_template(config, &template, arguments)
+            .await
+        {
+            Ok(new_conv) => {
+                self.send_thread_started(request_id, new_conv, false).await;
+            }
+            Err(CodexErr::InvalidRequest(message)) => {
+                self.send_invalid_request_error(request_id, message).await;
+            }
+            Err(err) => {
+                self.send_internal_error(request_id, format!("error creating thread: {err}"))
+                    .await;
+            }
+        }
+    }
+
+    /// Responds to a thread/start-style request for `new_conv`, subscribes the
+    /// client to its events and announces it with `thread/started`.
+    async fn send_thread_started(
+        &mut self,
+        request_id: RequestId,
+        new_conv: NewThread,
+        experimental_raw_events: bool,
+    ) {
+        let NewThread {
+            thread_id,
+            session_configured,
+            ..
+        } = new_conv;
+        let rollout_path = session_configured.rollout_path.clone();
+        let fallback_provider = self.config.model_provider_id.as_str();
+
+        // A bit hacky, but the summary contains a lot of useful information for the thread
+        // that unfortunately does not get returned from thread_manager.start_thread().
+        let thread =
+            match read_summary_from_rollout(rollout_path.as_path(), fallback_provider).await {
+                Ok(summary) => summary_to_thread(summary),
+                Err(err) => {
+                    self.send_internal_error(
+                        request_id,
+                        format!(
+                            "failed to load rollout `{}` for thread {thread_id}: {err}",
+                            rollout_path.display()
+                        ),
+                    )
+                    .await;
+                    return;
+                }
+            };
+
+        let SessionConfiguredEvent {
+            model,
+            model_provider_id,
+            cwd,
+            approval_policy,
+            sandbox_policy,
+            ..
+        } = session_configured;
+        let response = ThreadStartResponse {
+            thread: thread.clone(),
+            model,
+            model_provider: model_provider_id,
+            cwd,
+            approval_policy: approval_policy.into(),
+            sandbox: sandbox_policy.into(),
+            reasoning_effort: session_configured.reasoning_effort,
+        };
+
+        // Auto-attach a thread listener when starting a thread.
+        // Use the same behavior as the v1 API, with opt-in support for raw item events.
+        if let Err(err) = self
+            .attach_conversation_listener(thread_id, experimental_raw_events, ApiVersion::V2)
+            .await
+        {
+            tracing::warn!(
+                "failed to attach listener for thread {}: {}",
+                thread_id,
+                err.message
+            );
+        }
+
+        self.outgoing.send_response(request_id, response).await;
+
+        let notif = ThreadStartedNotification { thread };
+        self.outgoing
+            .send_server_notification(ServerNotification::ThreadStarted(notif))
+            .await;
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn build_thread_config_overrides(
         &self,
@@ -1582,77 +1830,500 @@ impl CodexMessageProcessor {
         }
     }
 
-    async fn thread_rollback(&mut self, request_id: RequestId, params: ThreadRollbackParams) {
-        let ThreadRollbackParams {
-            thread_id,
-            num_turns,
-        } = params;
-
-        if num_turns == 0 {
-            self.send_invalid_request_error(request_id, "numTurns must be >= 1".to_string())
-                .await;
-            return;
-        }
-
-        let (thread_id, thread) = match self.load_thread(&thread_id).await {
-            Ok(v) => v,
-            Err(error) => {
-                self.outgoing.send_error(request_id, error).await;
+    async fn thread_unarchive(&self, request_id: RequestId, params: ThreadUnarchiveParams) {
+        let thread_id = match ThreadId::from_string(&params.thread_id) {
+            Ok(id) => id,
+            Err(err) => {
+                self.send_invalid_request_error(request_id, format!("invalid thread id: {err}"))
+                    .await;
                 return;
             }
         };
 
+        let archived_path = match find_archived_thread_path_by_id_str(
+            &self.config.codex_home,
+            &thread_id.to_string(),
+        )
+        .await
         {
-            let mut map = self.pending_rollbacks.lock().await;
-            if map.contains_key(&thread_id) {
+            Ok(Some(path)) => path,
+            Ok(None) => {
                 self.send_invalid_request_error(
                     request_id,
-                    "rollback already in progress for this thread".to_string(),
+                    format!("no archived rollout found for thread id {thread_id}"),
                 )
                 .await;
                 return;
             }
-
-            map.insert(thread_id, request_id.clone());
-        }
-
-        if let Err(err) = thread.submit(Op::ThreadRollback { num_turns }).await {
-            // No ThreadRollback event will arrive if an error occurs.
-            // Clean up and reply immediately.
-            let mut map = self.pending_rollbacks.lock().await;
-            map.remove(&thread_id);
-
-            self.send_internal_error(request_id, format!("failed to start rollback: {err}"))
+            Err(err) => {
+                self.send_invalid_request_error(
+                    request_id,
+                    format!("failed to locate archived thread id {thread_id}: {err}"),
+                )
                 .await;
-        }
-    }
-
-    async fn thread_list(&self, request_id: RequestId, params: ThreadListParams) {
-        let ThreadListParams {
-            cursor,
-            limit,
-            sort_key,
-            model_providers,
-            archived,
-        } = params;
-
-        let requested_page_size = limit
-            .map(|value| value as usize)
-            .unwrap_or(THREAD_LIST_DEFAULT_LIMIT)
-            .clamp(1, THREAD_LIST_MAX_LIMIT);
-        let core_sort_key = match sort_key.unwrap_or(ThreadSortKey::CreatedAt) {
-            ThreadSortKey::CreatedAt => CoreThreadSortKey::CreatedAt,
-            ThreadSortKey::UpdatedAt => CoreThreadSortKey::UpdatedAt,
+                return;
+            }
         };
-        let (summaries, next_cursor) = match self
-            .list_threads_common(
-                requested_page_size,
-                cursor,
-                model_providers,
-                core_sort_key,
-                archived.unwrap_or(false),
+
+        // Archived rollouts are stored flat; restore them into the dated
+        // directory implied by the file name.
+        let Some(file_name) = archived_path.file_name().map(OsStr::to_owned) else {
+            self.send_internal_error(
+                request_id,
+                format!(
+                    "rollout path `{}` missing file name",
+                    archived_path.display()
+                ),
             )
-            .await
+            .await;
+            return;
+        };
+        let Some(day_dir) = session_day_dir_for_file_name(&file_name.to_string_lossy()) else {
+            self.send_internal_error(
+                request_id,
+                format!(
+                    "rollout path `{}` has an unexpected file name",
+                    archived_path.display()
+                ),
+            )
+            .await;
+            return;
+        };
+        let result: std::io::Result<PathBuf> = async {
+            let sessions_day_dir = self
+                .config
+                .codex_home
+                .join(codex_core::SESSIONS_SUBDIR)
+                .join(day_dir);
+            tokio::fs::create_dir_all(&sessions_day_dir).await?;
+            let rollout_path = sessions_day_dir.join(&file_name);
+            tokio::fs::rename(&archived_path, &rollout_path).await?;
+            move_provenance(&archived_path, &rollout_path).await?;
+            Ok(rollout_path)
+        }
+        .await;
+        let rollout_path = match result {
+            Ok(path) => path,
+            Err(err) => {
+                self.send_internal_error(request_id, format!("failed to unarchive thread: {err}"))
+                    .await;
+                return;
+            }
+        };
+
+        let fallback_provider = self.config.model_provider_id.as_str();
+        match read_summary_from_rollout(&rollout_path, fallback_provider).await {
+            Ok(summary) => {
+                let response = ThreadUnarchiveResponse {
+                    thread: summary_to_thread(summary),
+                };
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Err(err) => {
+                self.send_internal_error(
+                    request_id,
+                    format!(
+                        "failed to load rollout `{}` for thread {thread_id}: {err}",
+                        rollout_path.display()
+                    ),
+                )
+                .await;
+            }
+        }
+    }
+
+    async fn thread_metadata_update(
+        &self,
+        request_id: RequestId,
+        params: ThreadMetadataUpdateParams,
+    ) {
+        let ThreadMetadataUpdateParams {
+            thread_id,
+            title,
+            tags,
+        } = params;
+        let thread_id = match ThreadId::from_string(&thread_id) {
+            Ok(id) => id,
+            Err(err) => {
+                self.send_invalid_request_error(request_id, format!("invalid thread id: {err}"))
+                    .await;
+                return;
+            }
+        };
+        let update = SessionMetadataUpdate {
+            title,
+            tags,
+            keep_existing_title: false,
+            stats: None,
+        };
+
+        // A loaded thread owns its rollout file, so route the edit through its
+        // recorder to keep it ordered with pending writes.
+        let (rollout_path, result) = match self.thread_manager.get_thread(thread_id).await {
+            Ok(thread) => (
+                thread.rollout_path(),
+                thread.update_session_metadata(update).await,
+            ),
+            Err(_) => {
+                let codex_home = &self.config.codex_home;
+                let thread_id_str = thread_id.to_string();
+                let found = match find_thread_path_by_id_str(codex_home, &thread_id_str).await {
+                    Ok(None) => {
+                        find_archived_thread_path_by_id_str(codex_home, &thread_id_str).await
+                    }
+                    other => other,
+                };
+                let rollout_path = match found {
+                    Ok(Some(path)) => path,
+                    Ok(None) => {
+                        self.send_invalid_request_error(
+                            request_id,
+                            format!("no rollout found for thread id {thread_id}"),
+                        )
+                        .await;
+                        return;
+                    }
+                    Err(err) => {
+                        self.send_invalid_request_error(
+                            request_id,
+                            format!("failed to locate thread id {thread_id}: {err}"),
+                        )
+                        .await;
+                        return;
+                    }
+                };
+                let result = update_session_metadata(&rollout_path, update).await;
+                (rollout_path, result)
+            }
+        };
+        if let Err(err) = result {
+            self.send_internal_error(
+                request_id,
+                format!("failed to update metadata for thread {thread_id}: {err}"),
+            )
+            .await;
+            return;
+        }
+
+        let fallback_provider = self.config.model_provider_id.as_str();
+        match read_summary_from_rollout(&rollout_path, fallback_provider).await {
+            Ok(summary) => {
+                let response = ThreadMetadataUpdateResponse {
+                    thread: summary_to_thread(summary),
+                };
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Err(err) => {
+                self.send_internal_error(
+                    request_id,
+                    format!(
+                        "failed to load rollout `{}` for thread {thread_id}: {err}",
+                        rollout_path.display()
+                    ),
+                )
+                .await;
+            }
+        }
+    }
+
+    async fn thread_rollback(&mut self, request_id: RequestId, params: ThreadRollbackParams) {
+        let ThreadRollbackParams {
+            thread_id,
+            num_turns,
+        } = params;
+
+        if num_turns == 0 {
+            self.send_invalid_request_error(request_id, "numTurns must be >= 1".to_string())
+                .await;
+            return;
+        }
+
+        let (thread_id, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        {
+            let mut map = self.pending_rollbacks.lock().await;
+            if map.contains_key(&thread_id) {
+                self.send_invalid_request_error(
+                    request_id,
+                    "rollback already in progress for this thread".to_string(),
+                )
+                .await;
+                return;
+            }
+
+            map.insert(thread_id, request_id.clone());
+        }
+
+        if let Err(err) = thread.submit(Op::ThreadRollback { num_turns }).await {
+            // No ThreadRollback event will arrive if an error occurs.
+            // Clean up and reply immediately.
+            let mut map = self.pending_rollbacks.lock().await;
+            map.remove(&thread_id);
+
+            self.send_internal_error(request_id, format!("failed to start rollback: {err}"))
+                .await;
+        }
+    }
+
+    async fn thread_merge(&self, request_id: RequestId, params: ThreadMergeParams) {
+        let ThreadMergeParams {
+            thread_id,
+            source_thread_id,
+        } = params;
+
+        let (thread_id, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+        let source_thread_id = match ThreadId::from_string(&source_thread_id) {
+            Ok(id) => id,
+            Err(err) => {
+                self.send_invalid_request_error(
+                    request_id,
+                    format!("invalid source thread id: {err}"),
+                )
+                .await;
+                return;
+            }
+        };
+        let rollout_path = match self.thread_manager.get_thread(source_thread_id).await {
+            Ok(source) => source.rollout_path(),
+            Err(_) => {
+                let codex_home = &self.config.codex_home;
+                let source_id_str = source_thread_id.to_string();
+                let found = match find_thread_path_by_id_str(codex_home, &source_id_str).await {
+                    Ok(None) => {
+                        find_archived_thread_path_by_id_str(codex_home, &source_id_str).await
+                    }
+                    other => other,
+                };
+                match found {
+                    Ok(Some(path)) => path,
+                    Ok(None) => {
+                        self.send_invalid_request_error(
+                            request_id,
+                            format!("no rollout found for thread id {source_thread_id}"),
+                        )
+                        .await;
+                        return;
+                    }
+                    Err(err) => {
+                        self.send_invalid_request_error(
+                            request_id,
+                            format!("failed to locate thread id {source_thread_id}: {err}"),
+                        )
+                        .await;
+                        return;
+                    }
+                }
+            }
+        };
+
+        {
+            let mut map = self.pending_merges.lock().await;
+            if map.contains_key(&thread_id) {
+                self.send_invalid_request_error(
+                    request_id,
+                    "merge already in progress for this thread".to_string(),
+                )
+                .await;
+                return;
+            }
+
+            map.insert(thread_id, request_id.clone());
+        }
+
+        if let Err(err) = thread.submit(Op::MergeConversation { rollout_path }).await {
+            // No ConversationMerged event will arrive if an error occurs.
+            // Clean up and reply immediately.
+            self.pending_merges.lock().await.remove(&thread_id);
+            self.send_internal_error(request_id, format!("failed to start merge: {err}"))
+                .await;
+        }
+    }
+
+    async fn thread_feature_set(&self, request_id: RequestId, params: ThreadFeatureSetParams) {
+        let ThreadFeatureSetParams {
+            thread_id,
+            feature,
+            enabled,
+            changed_by,
+        } = params;
+
+        let (_, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+        // Checked here as well so the caller learns about a rejected change
+        // from the response rather than from an error notification.
+        if let Err(message) = thread.check_feature_change(&feature, enabled) {
+            self.send_invalid_request_error(request_id, message).await;
+            return;
+        }
+        let changed_by = changed_by.or_else(|| Some(originator().value));
+        if let Err(err) = thread
+            .submit(Op::SetFeature {
+                feature,
+                enabled,
+                changed_by,
+            })
+            .await
+        {
+            self.send_internal_error(request_id, format!("failed to set feature: {err}"))
+                .await;
+            return;
+        }
+        self.outgoing
+            .send_response(request_id, ThreadFeatureSetResponse {})
+            .await;
+    }
+
+    async fn thread_trim(&self, request_id: RequestId, params: ThreadTrimParams) {
+        let ThreadTrimParams {
+            thread_id,
+            start_turn,
+            num_turns,
+        } = params;
+
+        if num_turns == 0 {
+            self.send_invalid_request_error(request_id, "numTurns must be >= 1".to_string())
+                .await;
+            return;
+        }
+        let thread_id = match ThreadId::from_string(&thread_id) {
+            Ok(id) => id,
+            Err(err) => {
+                self.send_invalid_request_error(request_id, format!("invalid thread id: {err}"))
+                    .await;
+                return;
+            }
+        };
+        // A loaded thread keeps the trimmed turns in its in-memory context and
+        // appends to the rollout, so only threads at rest can be trimmed.
+        if self.thread_manager.get_thread(thread_id).await.is_ok() {
+            self.send_invalid_request_error(
+                request_id,
+                format!(
+                    "thread {thread_id} is loaded; only threads that are not loaded can be trimmed"
+                ),
+            )
+            .await;
+            return;
+        }
+
+        let codex_home = &self.config.codex_home;
+        let thread_id_str = thread_id.to_string();
+        let found = match find_thread_path_by_id_str(codex_home, &thread_id_str).await {
+            Ok(None) => find_archived_thread_path_by_id_str(codex_home, &thread_id_str).await,
+            other => other,
+        };
+        let rollout_path = match found {
+            Ok(Some(path)) => path,
+            Ok(None) => {
+                self.send_invalid_request_error(
+                    request_id,
+                    format!("no rollout found for thread id {thread_id}"),
+                )
+                .await;
+                return;
+            }
+            Err(err) => {
+                self.send_invalid_request_error(
+                    request_id,
+                    format!("failed to locate thread id {thread_id}: {err}"),
+                )
+                .await;
+                return;
+            }
+        };
+
+        let start = start_turn as usize;
+        let range = start..start.saturating_add(num_turns as usize);
+        match RolloutRecorder::trim(&rollout_path, range).await {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::InvalidInput => {
+                self.send_invalid_request_error(request_id, err.to_string())
+                    .await;
+                return;
+            }
+            Err(err) => {
+                self.send_internal_error(
+                    request_id,
+                    format!("failed to trim thread {thread_id}: {err}"),
+                )
+                .await;
+                return;
+            }
+        }
+
+        let fallback_provider = self.config.model_provider_id.as_str();
+        let loaded = match read_summary_from_rollout(&rollout_path, fallback_provider).await {
+            Ok(summary) => read_event_msgs_from_rollout(&rollout_path)
+                .await
+                .map(|events| (summary, events)),
+            Err(err) => Err(err),
+        };
+        match loaded {
+            Ok((summary, events)) => {
+                let mut thread = summary_to_thread(summary);
+                thread.turns = build_turns_from_event_msgs(&events);
+                self.outgoing
+                    .send_response(request_id, ThreadTrimResponse { thread })
+                    .await;
+            }
+            Err(err) => {
+                self.send_internal_error(
+                    request_id,
+                    format!(
+                        "failed to load rollout `{}` for thread {thread_id}: {err}",
+                        rollout_path.display()
+                    ),
+                )
+                .await;
+            }
+        }
+    }
+
+    async fn thread_list(&self, request_id: RequestId, params: ThreadListParams) {
+        let ThreadListParams {
+            cursor,
+            limit,
+            sort_key,
+            model_providers,
+            archived,
+            tags,
+        } = params;
+
+        let requested_page_size = limit
+            .map(|value| value as usize)
+            .unwrap_or(THREAD_LIST_DEFAULT_LIMIT)
+            .clamp(1, THREAD_LIST_MAX_LIMIT);
+        let core_sort_key = match sort_key.unwrap_or(ThreadSortKey::CreatedAt) {
+            ThreadSortKey::CreatedAt => CoreThreadSortKey::CreatedAt,
+            ThreadSortKey::UpdatedAt => CoreThreadSortKey::UpdatedAt,
+        };
+        let (summaries, next_cursor) = match self
+            .list_threads_common(
+                requested_page_size,
+                cursor,
+                model_providers,
+                core_sort_key,
+                archived.unwrap_or(false),
+                tags,
+            )
+            .await
         {
             Ok(r) => r,
             Err(error) => {
@@ -1661,64 +2332,471 @@ impl CodexMessageProcessor {
             }
         };
 
-        let data = summaries.into_iter().map(summary_to_thread).collect();
-        let response = ThreadListResponse { data, next_cursor };
+        let data = summaries.into_iter().map(summary_to_thread).collect();
+        let response = ThreadListResponse { data, next_cursor };
+        self.outgoing.send_response(request_id, response).await;
+    }
+
+    async fn thread_loaded_list(&self, request_id: RequestId, params: ThreadLoadedListParams) {
+        let ThreadLoadedListParams { cursor, limit } = params;
+        let mut data = self
+            .thread_manager
+            .list_thread_ids()
+            .await
+            .into_iter()
+            .map(|thread_id| thread_id.to_string())
+            .collect::<Vec<_>>();
+
+        if data.is_empty() {
+            let response = ThreadLoadedListResponse {
+                data,
+                next_cursor: None,
+            };
+            self.outgoing.send_response(request_id, response).await;
+            return;
+        }
+
+        data.sort();
+        let total = data.len();
+        let start = match cursor {
+            Some(cursor) => {
+                let cursor = match ThreadId::from_string(&cursor) {
+                    Ok(id) => id.to_string(),
+                    Err(_) => {
+                        let error = JSONRPCErrorError {
+                            code: INVALID_REQUEST_ERROR_CODE,
+                            message: format!("invalid cursor: {cursor}"),
+                            data: None,
+                        };
+                        self.outgoing.send_error(request_id, error).await;
+                        return;
+                    }
+                };
+                match data.binary_search(&cursor) {
+                    Ok(idx) => idx + 1,
+                    Err(idx) => idx,
+                }
+            }
+            None => 0,
+        };
+
+        let effective_limit = limit.unwrap_or(total as u32).max(1) as usize;
+        let end = start.saturating_add(effective_limit).min(total);
+        let page = data[start..end].to_vec();
+        let next_cursor = page.last().filter(|_| end < total).cloned();
+
+        let response = ThreadLoadedListResponse {
+            data: page,
+            next_cursor,
+        };
+        self.outgoing.send_response(request_id, response).await;
+    }
+
+    async fn thread_environment_diff(
+        &self,
+        request_id: RequestId,
+        params: ThreadEnvironmentDiffParams,
+    ) {
+        let thread_id = match ThreadId::from_string(&params.thread_id) {
+            Ok(id) => id,
+            Err(err) => {
+                self.send_invalid_request_error(request_id, format!("invalid thread id: {err}"))
+                    .await;
+                return;
+            }
+        };
+
+        let rollout_path = match self.locate_rollout(thread_id).await {
+            Ok(path) => path,
+            Err(message) => {
+                self.send_invalid_request_error(request_id, message).await;
+                return;
+            }
+        };
+
+        match diff_rollout_environment(
+            &rollout_path,
+            &self.config.codex_home,
+            &self.config.shell_environment_policy,
+            &self.config.cwd,
+        )
+        .await
+        {
+            Ok(changes) => {
+                let response = ThreadEnvironmentDiffResponse {
+                    recorded: changes.is_some(),
+                    changes: changes
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(Into::into)
+                        .collect(),
+                };
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Err(err) => {
+                self.send_internal_error(
+                    request_id,
+                    format!(
+                        "failed to load rollout `{}` for thread {thread_id}: {err}",
+                        rollout_path.display()
+                    ),
+                )
+                .await;
+            }
+        }
+    }
+
+    /// Rollout of a loaded thread, or of a live or archived one on disk.
+    async fn locate_rollout(&self, thread_id: ThreadId) -> Result<PathBuf, String> {
+        if let Ok(thread) = self.thread_manager.get_thread(thread_id).await {
+            return Ok(thread.rollout_path());
+        }
+        let codex_home = &self.config.codex_home;
+        let thread_id_str = thread_id.to_string();
+        let found = match find_thread_path_by_id_str(codex_home, &thread_id_str).await {
+            Ok(None) => find_archived_thread_path_by_id_str(codex_home, &thread_id_str).await,
+            other => other,
+        };
+        match found {
+            Ok(Some(path)) => Ok(path),
+            Ok(None) => Err(format!("no rollout found for thread id {thread_id}")),
+            Err(err) => Err(format!("failed to locate thread id {thread_id}: {err}")),
+        }
+    }
+
+    async fn thread_workspace_diff(
+        &self,
+        request_id: RequestId,
+        params: ThreadWorkspaceDiffParams,
+    ) {
+        let mut rollout_paths = Vec::new();
+        for id in [&params.thread_id, &params.other_thread_id] {
+            let thread_id = match ThreadId::from_string(id) {
+                Ok(id) => id,
+                Err(err) => {
+                    self.send_invalid_request_error(
+                        request_id,
+                        format!("invalid thread id: {err}"),
+                    )
+                    .await;
+                    return;
+                }
+            };
+            match self.locate_rollout(thread_id).await {
+                Ok(path) => rollout_paths.push(path),
+                Err(message) => {
+                    self.send_invalid_request_error(request_id, message).await;
+                    return;
+                }
+            }
+        }
+
+        match diff_sessions(&rollout_paths[0], &rollout_paths[1]).await {
+            Ok(diff) => {
+                let response = ThreadWorkspaceDiffResponse {
+                    rendered: diff.render(),
+                    repo: diff.repo,
+                    threads: diff
+                        .sessions
+                        .into_iter()
+                        .map(|session| ThreadWorkspaceDelta {
+                            thread_id: session.thread_id.to_string(),
+                            checkpoints: session.checkpoints,
+                            files: session
+                                .files
+                                .into_iter()
+                                .map(|(path, delta)| ThreadWorkspaceFileDelta {
+                                    path,
+                                    turns: delta.turns,
+                                    diff: delta.diff,
+                                })
+                                .collect(),
+                        })
+                        .collect(),
+                    shared: diff.shared,
+                    unattributed: diff.unattributed,
+                };
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::InvalidInput => {
+                self.send_invalid_request_error(request_id, err.to_string())
+                    .await;
+            }
+            Err(err) => {
+                self.send_internal_error(
+                    request_id,
+                    format!("failed to diff thread workspaces: {err}"),
+                )
+                .await;
+            }
+        }
+    }
+
+    async fn stats_read(&self, request_id: RequestId, params: StatsReadParams) {
+        let parse_day = |day: Option<String>| day.as_deref().map(parse_stats_day).transpose();
+        let (from, to) = match (parse_day(params.from), parse_day(params.to)) {
+            (Ok(from), Ok(to)) => (from, to),
+            (Err(message), _) | (_, Err(message)) => {
+                self.send_invalid_request_error(request_id, message).await;
+                return;
+            }
+        };
+        let query = SessionStatsQuery {
+            from,
+            to,
+            cwd: params.cwd,
+            group_by: params.group_by.map(|group_by| match group_by {
+                StatsGrouping::Day => SessionStatsGrouping::Day,
+                StatsGrouping::Project => SessionStatsGrouping::Project,
+            }),
+        };
+
+        match query_session_stats(&self.config.codex_home, &query).await {
+            Ok(report) => {
+                let response = StatsReadResponse {
+                    sessions: report.sessions,
+                    sessions_without_stats: report.sessions_without_stats,
+                    totals: report.totals.into(),
+                    groups: report
+                        .groups
+                        .into_iter()
+                        .map(|group| StatsGroup {
+                            key: group.key,
+                            sessions: group.sessions,
+                            stats: group.stats.into(),
+                        })
+                        .collect(),
+                };
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Err(err) => {
+                self.send_internal_error(
+                    request_id,
+                    format!("failed to read session stats: {err}"),
+                )
+                .await;
+            }
+        }
+    }
+
+    async fn changes_query(&self, request_id: RequestId, params: ChangesQueryParams) {
+        let since = match params.since.as_deref().map(parse_stats_day).transpose() {
+            Ok(since) => since,
+            Err(message) => {
+                self.send_invalid_request_error(request_id, message).await;
+                return;
+            }
+        };
+        let query = ChangeQuery {
+            cwd: params.cwd,
+            path: params.path,
+            since,
+        };
+
+        match query_changes(&self.config.codex_home, &query).await {
+            Ok(matches) => {
+                let response = ChangesQueryResponse {
+                    changes: matches
+                        .into_iter()
+                        .map(|found| RecordedFileChange {
+                            repo: found.repo,
+                            path: found.path,
+                            thread_id: found.change.thread_id.to_string(),
+                            rollout_path: found.change.rollout_path,
+                            turn: found.change.turn,
+                            timestamp: found.change.timestamp,
+                        })
+                        .collect(),
+                };
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Err(err) => {
+                self.send_internal_error(request_id, format!("failed to query changes: {err}"))
+                    .await;
+            }
+        }
+    }
+
+    async fn provenance_export(&self, request_id: RequestId, params: ProvenanceExportParams) {
+        let ProvenanceExportParams { thread_id } = params;
+        let codex_home = &self.config.codex_home;
+        let found = match find_thread_path_by_id_str(codex_home, &thread_id).await {
+            Ok(None) => find_archived_thread_path_by_id_str(codex_home, &thread_id).await,
+            other => other,
+        };
+        let rollout_path = match found {
+            Ok(Some(path)) => path,
+            Ok(None) => {
+                self.send_invalid_request_error(
+                    request_id,
+                    format!("no rollout found for thread id {thread_id}"),
+                )
+                .await;
+                return;
+            }
+            Err(err) => {
+                self.send_invalid_request_error(
+                    request_id,
+                    format!("failed to locate thread id {thread_id}: {err}"),
+                )
+                .await;
+                return;
+            }
+        };
+
+        let records = match read_provenance(&rollout_path).await {
+            Ok(records) => records,
+            Err(err) => {
+                self.send_internal_error(
+                    request_id,
+                    format!("failed to read provenance of thread {thread_id}: {err}"),
+                )
+                .await;
+                return;
+            }
+        };
+        let key = match read_verification_key(codex_home, &self.config.provenance).await {
+            Ok(key) => key,
+            Err(err) => {
+                warn!("failed to load provenance signing key: {err}");
+                None
+            }
+        };
+        let response = ProvenanceExportResponse {
+            records: records
+                .into_iter()
+                .map(|record| PatchProvenance {
+                    verified: key.as_ref().is_some_and(|key| record.verify(key)),
+                    version: record.version,
+                    thread_id: record.thread_id.to_string(),
+                    turn_id: record.turn_id,
+                    call_id: record.call_id,
+                    model: record.model,
+                    model_provider: record.model_provider,
+                    prompt_sha256: record.prompt_sha256,
+                    diff_sha256: record.diff_sha256,
+                    files: record.files,
+                    timestamp: record.timestamp,
+                    key_id: record.key_id,
+                    signature: record.signature,
+                })
+                .collect(),
+        };
         self.outgoing.send_response(request_id, response).await;
     }
 
-    async fn thread_loaded_list(&self, request_id: RequestId, params: ThreadLoadedListParams) {
-        let ThreadLoadedListParams { cursor, limit } = params;
-        let mut data = self
-            .thread_manager
-            .list_thread_ids()
-            .await
-            .into_iter()
-            .map(|thread_id| thread_id.to_string())
-            .collect::<Vec<_>>();
-
-        if data.is_empty() {
-            let response = ThreadLoadedListResponse {
-                data,
-                next_cursor: None,
-            };
-            self.outgoing.send_response(request_id, response).await;
-            return;
+    async fn approvals_list(&self, request_id: RequestId, params: ApprovalsListParams) {
+        let result = match params.thread_id {
+            Some(thread_id) => match self.load_thread(&thread_id).await {
+                Ok((_, thread)) => thread.approval_memory().list().await,
+                Err(error) => {
+                    self.outgoing.send_error(request_id, error).await;
+                    return;
+                }
+            },
+            None => list_persisted_approvals(&self.config.codex_home).await,
+        };
+        match result {
+            Ok(approvals) => {
+                let response = ApprovalsListResponse {
+                    approvals: approvals
+                        .into_iter()
+                        .map(|approval| V2RememberedApproval {
+                            id: approval.id,
+                            scope: approval.scope.into(),
+                            project: approval.project,
+                            tool: approval.tool,
+                            target: match approval.target {
+                                CoreApprovalTarget::Command { command } => {
+                                    V2ApprovalTarget::Command { command }
+                                }
+                                CoreApprovalTarget::CommandPrefix { prefix } => {
+                                    V2ApprovalTarget::CommandPrefix { prefix }
+                                }
+                                CoreApprovalTarget::Path { path } => {
+                                    V2ApprovalTarget::Path { path }
+                                }
+                            },
+                            created_at: approval.created_at,
+                        })
+                        .collect(),
+                };
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Err(err) => {
+                self.send_internal_error(
+                    request_id,
+                    format!("failed to read remembered approvals: {err}"),
+                )
+                .await;
+            }
         }
+    }
 
-        data.sort();
-        let total = data.len();
-        let start = match cursor {
-            Some(cursor) => {
-                let cursor = match ThreadId::from_string(&cursor) {
-                    Ok(id) => id.to_string(),
-                    Err(_) => {
-                        let error = JSONRPCErrorError {
-                            code: INVALID_REQUEST_ERROR_CODE,
-                            message: format!("invalid cursor: {cursor}"),
-                            data: None,
-                        };
-                        self.outgoing.send_error(request_id, error).await;
-                        return;
-                    }
-                };
-                match data.binary_search(&cursor) {
-                    Ok(idx) => idx + 1,
-                    Err(idx) => idx,
+    async fn approvals_revoke(&self, request_id: RequestId, params: ApprovalsRevokeParams) {
+        let ApprovalsRevokeParams { id, thread_id } = params;
+        let result = match thread_id {
+            Some(thread_id) => match self.load_thread(&thread_id).await {
+                Ok((_, thread)) => thread.approval_memory().revoke(&id).await,
+                Err(error) => {
+                    self.outgoing.send_error(request_id, error).await;
+                    return;
                 }
-            }
-            None => 0,
+            },
+            None => revoke_persisted_approval(&self.config.codex_home, &id).await,
         };
+        match result {
+            Ok(revoked) => {
+                self.outgoing
+                    .send_response(request_id, ApprovalsRevokeResponse { revoked })
+                    .await;
+            }
+            Err(err) => {
+                self.send_internal_error(
+                    request_id,
+                    format!("failed to revoke approval {id}: {err}"),
+                )
+                .await;
+            }
+        }
+    }
 
-        let effective_limit = limit.unwrap_or(total as u32).max(1) as usize;
-        let end = start.saturating_add(effective_limit).min(total);
-        let page = data[start..end].to_vec();
-        let next_cursor = page.last().filter(|_| end < total).cloned();
-
-        let response = ThreadLoadedListResponse {
-            data: page,
-            next_cursor,
+    async fn doctor_run(&self, request_id: RequestId) {
+        let outgoing = Arc::clone(&self.outgoing);
+        // An invalid config.toml is reported by the config check, so fall back
+        // to the config the server started with.
+        let config = match self.load_latest_config().await {
+            Ok(config) => config,
+            Err(_) => self.config.as_ref().clone(),
         };
-        self.outgoing.send_response(request_id, response).await;
+
+        // Starting MCP servers and probing the provider can take a while.
+        tokio::spawn(async move {
+            let report = run_doctor(&config).await;
+            let response = DoctorRunResponse {
+                status: doctor_status_to_v2(report.status()),
+                checks: report
+                    .checks
+                    .into_iter()
+                    .map(|check| DoctorCheck {
+                        kind: match check.kind {
+                            CoreDoctorCheckKind::Config => DoctorCheckKind::Config,
+                            CoreDoctorCheckKind::CodexHome => DoctorCheckKind::CodexHome,
+                            CoreDoctorCheckKind::Auth => DoctorCheckKind::Auth,
+                            CoreDoctorCheckKind::Provider => DoctorCheckKind::Provider,
+                            CoreDoctorCheckKind::Sandbox => DoctorCheckKind::Sandbox,
+                            CoreDoctorCheckKind::McpServer => DoctorCheckKind::McpServer,
+                            CoreDoctorCheckKind::Git => DoctorCheckKind::Git,
+                        },
+                        subject: check.subject,
+                        status: doctor_status_to_v2(check.status),
+                        summary: check.summary,
+                        fix: check.fix,
+                    })
+                    .collect(),
+            };
+            outgoing.send_response(request_id, response).await;
+        });
     }
 
     async fn thread_read(&self, request_id: RequestId, params: ThreadReadParams) {
@@ -1836,6 +2914,7 @@ impl CodexMessageProcessor {
             config: request_overrides,
             base_instructions,
             developer_instructions,
+            resume_context,
         } = params;
 
         let thread_history = if let Some(history) = history {
@@ -1924,7 +3003,7 @@ impl CodexMessageProcessor {
         );
 
         // Derive a Config using the same logic as new conversation, honoring overrides if provided.
-        let config = match derive_config_for_cwd(
+        let mut config = match derive_config_for_cwd(
             &self.cli_overrides,
             request_overrides,
             typesafe_overrides,
@@ -1944,6 +3023,10 @@ impl CodexMessageProcessor {
             }
         };
 
+        if let Some(resume_context) = resume_context {
+            config.resume_context = resume_context.apply_to(config.resume_context);
+        }
+
         let fallback_model_provider = config.model_provider_id.clone();
 
         match self
@@ -2283,6 +3366,7 @@ impl CodexMessageProcessor {
             page_size,
             cursor,
             model_providers,
+            tags,
         } = params;
         let requested_page_size = page_size
             .unwrap_or(THREAD_LIST_DEFAULT_LIMIT)
@@ -2295,6 +3379,7 @@ impl CodexMessageProcessor {
                 model_providers,
                 CoreThreadSortKey::UpdatedAt,
                 false,
+                tags,
             )
             .await
         {
@@ -2315,6 +3400,7 @@ impl CodexMessageProcessor {
         model_providers: Option<Vec<String>>,
         sort_key: CoreThreadSortKey,
         archived: bool,
+        tags: Option<Vec<String>>,
     ) -> Result<(Vec<ConversationSummary>, Option<String>), JSONRPCErrorError> {
         let mut cursor_obj: Option<RolloutCursor> = match cursor.as_ref() {
             Some(cursor_str) => {
@@ -2396,6 +3482,10 @@ impl CodexMessageProcessor {
                         updated_at,
                     )
                 })
+                .filter(|summary| {
+                    tags.as_ref()
+                        .is_none_or(|tags| tags.iter().all(|tag| summary.tags.contains(tag)))
+                })
                 .collect::<Vec<_>>();
             if filtered.len() > remaining {
                 filtered.truncate(remaining);
@@ -3299,7 +4389,9 @@ impl CodexMessageProcessor {
                 .codex_home
                 .join(codex_core::ARCHIVED_SESSIONS_SUBDIR);
             tokio::fs::create_dir_all(&archive_folder).await?;
-            tokio::fs::rename(&canonical_rollout_path, &archive_folder.join(&file_name)).await?;
+            let archived_path = archive_folder.join(&file_name);
+            tokio::fs::rename(&canonical_rollout_path, &archived_path).await?;
+            move_provenance(&canonical_rollout_path, &archived_path).await?;
             Ok(())
         }
         .await;
@@ -3534,6 +4626,76 @@ impl CodexMessageProcessor {
             .await;
     }
 
+    async fn instructions_read(&self, request_id: RequestId, params: InstructionsReadParams) {
+        let mut config = (*self.config).clone();
+        if let Some(cwd) = params.cwd {
+            config.cwd = cwd;
+        }
+        match load_project_docs(&config).await {
+            Ok(docs) => {
+                let project_docs = docs
+                    .into_iter()
+                    .map(|doc| ProjectDoc {
+                        path: doc.path,
+                        scope: doc.scope,
+                        content: doc.contents,
+                        truncated: doc.truncated,
+                    })
+                    .collect();
+                self.outgoing
+                    .send_response(
+                        request_id,
+                        InstructionsReadResponse {
+                            user_instructions: config.user_instructions,
+                            project_docs,
+                        },
+                    )
+                    .await;
+            }
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: format!("failed to read project docs: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+            }
+        }
+    }
+
+    async fn template_list(&self, request_id: RequestId, params: TemplateListParams) {
+        let cwd = params.cwd.unwrap_or_else(|| self.config.cwd.clone());
+        let data = discover_templates(&self.config.codex_home, &cwd)
+            .await
+            .into_iter()
+            .map(|template| SessionTemplate {
+                name: template.name,
+                path: template.path,
+                scope: match template.scope {
+                    CoreSessionTemplateScope::User => SessionTemplateScope::User,
+                    CoreSessionTemplateScope::Project => SessionTemplateScope::Project,
+                },
+                description: template.description,
+                prompt: template.prompt,
+                profile: template.profile,
+                required_tools: template.required_tools,
+                context_files: template.context_files,
+                arguments: template
+                    .arguments
+                    .into_iter()
+                    .map(|argument| SessionTemplateArgument {
+                        name: argument.name,
+                        description: argument.description,
+                        default: argument.default,
+                    })
+                    .collect(),
+            })
+            .collect();
+        self.outgoing
+            .send_response(request_id, TemplateListResponse { data })
+            .await;
+    }
+
     async fn skills_config_write(&self, request_id: RequestId, params: SkillsConfigWriteParams) {
         let SkillsConfigWriteParams { path, enabled } = params;
         let edits = vec![ConfigEdit::SetSkillConfig { path, enabled }];
@@ -3842,6 +5004,7 @@ impl CodexMessageProcessor {
             thread_id,
             target,
             delivery,
+            paths,
         } = params;
         let (parent_thread_id, parent_thread) = match self.load_thread(&thread_id).await {
             Ok(v) => v,
@@ -3851,7 +5014,7 @@ impl CodexMessageProcessor {
             }
         };
 
-        let (review_request, display_text) = match Self::review_request_from_target(target) {
+        let (review_request, display_text) = match Self::review_request_from_target(target, paths) {
             Ok(value) => value,
             Err(err) => {
                 self.outgoing.send_error(request_id, err).await;
@@ -3914,6 +5077,148 @@ impl CodexMessageProcessor {
         let _ = thread.submit(Op::Interrupt).await;
     }
 
+    async fn turn_context_read(&self, request_id: RequestId, params: TurnContextReadParams) {
+        let TurnContextReadParams { thread_id, turn_id } = params;
+
+        let (_, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        match thread.turn_request_snapshot(turn_id.as_deref()).await {
+            Some(snapshot) => {
+                let response = TurnContextReadResponse::from(snapshot);
+                self.outgoing.send_response(request_id, response).await;
+            }
+            None => {
+                let message = match turn_id {
+                    Some(turn_id) => format!("no model request recorded for turn {turn_id}"),
+                    None => "no model request recorded for this thread yet".to_string(),
+                };
+                self.send_invalid_request_error(request_id, message).await;
+            }
+        }
+    }
+
+    async fn artifact_list(&self, request_id: RequestId, params: ArtifactListParams) {
+        let ArtifactListParams { thread_id } = params;
+        let Some(thread_uuid) = self.parse_artifact_thread_id(&request_id, &thread_id).await else {
+            return;
+        };
+
+        match list_artifacts(&self.config.codex_home, &thread_uuid).await {
+            Ok(artifacts) => {
+                let total_bytes: u64 = artifacts.iter().map(|artifact| artifact.size_bytes).sum();
+                let response = ArtifactListResponse {
+                    data: artifacts.into_iter().map(Into::into).collect(),
+                    total_bytes,
+                };
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Err(err) => {
+                self.send_internal_error(
+                    request_id,
+                    format!("failed to list artifacts of thread {thread_uuid}: {err}"),
+                )
+                .await;
+            }
+        }
+    }
+
+    async fn artifact_export(&self, request_id: RequestId, params: ArtifactExportParams) {
+        let ArtifactExportParams {
+            thread_id,
+            destination,
+        } = params;
+        let Some(thread_uuid) = self.parse_artifact_thread_id(&request_id, &thread_id).await else {
+            return;
+        };
+        if !destination.is_absolute() {
+            self.send_invalid_request_error(
+                request_id,
+                format!("destination must be absolute: {}", destination.display()),
+            )
+            .await;
+            return;
+        }
+
+        match export_artifacts(&self.config.codex_home, &thread_uuid, &destination).await {
+            Ok(paths) => {
+                self.outgoing
+                    .send_response(request_id, ArtifactExportResponse { paths })
+                    .await;
+            }
+            Err(err) => {
+                self.send_internal_error(
+                    request_id,
+                    format!("failed to export artifacts of thread {thread_uuid}: {err}"),
+                )
+                .await;
+            }
+        }
+    }
+
+    async fn parse_artifact_thread_id(
+        &self,
+        request_id: &RequestId,
+        thread_id: &str,
+    ) -> Option<ThreadId> {
+        match ThreadId::from_string(thread_id) {
+            Ok(id) => Some(id),
+            Err(err) => {
+                self.send_invalid_request_error(
+                    request_id.clone(),
+                    format!("invalid thread id: {err}"),
+                )
+                .await;
+                None
+            }
+        }
+    }
+
+    async fn artifact_download(&self, request_id: RequestId, params: ArtifactDownloadParams) {
+        let ArtifactDownloadParams {
+            thread_id,
+            artifact_id,
+        } = params;
+        let Some(thread_uuid) = self.parse_artifact_thread_id(&request_id, &thread_id).await else {
+            return;
+        };
+
+        let artifact =
+            match load_artifact(&self.config.codex_home, &thread_uuid, &artifact_id).await {
+                Ok(artifact) => artifact,
+                Err(err) => {
+                    self.send_invalid_request_error(
+                        request_id,
+                        format!("artifact {artifact_id} not found in thread {thread_uuid}: {err}"),
+                    )
+                    .await;
+                    return;
+                }
+            };
+
+        match tokio::fs::read(&artifact.path).await {
+            Ok(bytes) => {
+                let response = ArtifactDownloadResponse {
+                    artifact: artifact.into(),
+                    data_base64: base64::engine::general_purpose::STANDARD.encode(bytes),
+                };
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Err(err) => {
+                self.send_internal_error(
+                    request_id,
+                    format!("failed to read artifact {artifact_id}: {err}"),
+                )
+                .await;
+            }
+        }
+    }
+
     async fn add_conversation_listener(
         &mut self,
         request_id: RequestId,
@@ -3994,6 +5299,7 @@ impl CodexMessageProcessor {
         let outgoing_for_task = self.outgoing.clone();
         let pending_interrupts = self.pending_interrupts.clone();
         let pending_rollbacks = self.pending_rollbacks.clone();
+        let pending_merges = self.pending_merges.clone();
         let turn_summary_store = self.turn_summary_store.clone();
         let api_version_for_task = api_version;
         let fallback_model_provider = self.config.model_provider_id.clone();
@@ -4057,6 +5363,7 @@ impl CodexMessageProcessor {
                             outgoing_for_task.clone(),
                             pending_interrupts.clone(),
                             pending_rollbacks.clone(),
+                            pending_merges.clone(),
                             turn_summary_store.clone(),
                             api_version_for_task,
                             fallback_model_provider.clone(),
@@ -4346,6 +5653,7 @@ pub(crate) async fn read_summary_from_rollout(
     let SessionMetaLine {
         meta: session_meta,
         git,
+        ..
     } = session_meta_line;
 
     let created_at = if session_meta.timestamp.is_empty() {
@@ -4388,6 +5696,8 @@ pub(crate) async fn read_summary_from_rollout(
         cli_version: session_meta.cli_version,
         source: session_meta.source,
         git_info,
+        title: session_meta.title,
+        tags: session_meta.tags,
     })
 }
 
@@ -4454,9 +5764,20 @@ fn extract_conversation_summary(
         cli_version: session_meta.cli_version.clone(),
         source: session_meta.source.clone(),
         git_info,
+        title: session_meta.title.clone(),
+        tags: session_meta.tags.clone(),
     })
 }
 
+fn doctor_status_to_v2(status: CoreDoctorStatus) -> DoctorStatus {
+    match status {
+        CoreDoctorStatus::Skipped => DoctorStatus::Skipped,
+        CoreDoctorStatus::Ok => DoctorStatus::Ok,
+        CoreDoctorStatus::Warning => DoctorStatus::Warning,
+        CoreDoctorStatus::Error => DoctorStatus::Error,
+    }
+}
+
 fn map_git_info(git_info: &CoreGitInfo) -> ConversationGitInfo {
     ConversationGitInfo {
         sha: git_info.commit_hash.clone(),
@@ -4497,6 +5818,8 @@ pub(crate) fn summary_to_thread(summary: ConversationSummary) -> Thread {
         cli_version,
         source,
         git_info,
+        title,
+        tags,
     } = summary;
 
     let created_at = parse_datetime(timestamp.as_deref());
@@ -4518,6 +5841,8 @@ pub(crate) fn summary_to_thread(summary: ConversationSummary) -> Thread {
         cli_version,
         source: source.into(),
         git_info,
+        title,
+        tags,
         turns: Vec::new(),
     }
 }
@@ -4587,6 +5912,8 @@ mod tests {
             cli_version: "0.0.0".to_string(),
             source: SessionSource::VSCode,
             git_info: None,
+            title: None,
+            tags: Vec::new(),
         };
 
         assert_eq!(summary, expected);
@@ -4619,6 +5946,8 @@ mod tests {
             item: RolloutItem::SessionMeta(SessionMetaLine {
                 meta: session_meta.clone(),
                 git: None,
+                environment: None,
+                schema_version: None,
             }),
         };
 
@@ -4643,6 +5972,8 @@ mod tests {
             cli_version: String::new(),
             source: SessionSource::VSCode,
             git_info: None,
+            title: None,
+            tags: Vec::new(),
         };
 
         assert_eq!(summary, expected);
@@ -84,6 +84,8 @@ fn map_requirements_toml_to_api(requirements: ConfigRequirementsToml) -> ConfigR
                 .filter_map(map_sandbox_mode_requirement_to_api)
                 .collect()
         }),
+        allowed_models: requirements.allowed_models,
+        forbidden_tools: requirements.forbidden_tools,
     }
 }
 
@@ -136,6 +138,9 @@ mod tests {
                 CoreSandboxModeRequirement::ExternalSandbox,
             ]),
             mcp_servers: None,
+            allowed_models: Some(vec!["gpt-5.1-codex".to_string()]),
+            forbidden_tools: None,
+            otel_exporter: None,
         };
 
         let mapped = map_requirements_toml_to_api(requirements);
@@ -151,5 +156,9 @@ mod tests {
             mapped.allowed_sandbox_modes,
             Some(vec![SandboxMode::ReadOnly]),
         );
+        assert_eq!(
+            mapped.allowed_models,
+            Some(vec!["gpt-5.1-codex".to_string()])
+        );
     }
 }
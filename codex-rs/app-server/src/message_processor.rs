@@ -11,6 +11,7 @@ use codex_app_server_protocol::ConfigBatchWriteParams;
 use codex_app_server_protocol::ConfigReadParams;
 use codex_app_server_protocol::ConfigValueWriteParams;
 use codex_app_server_protocol::ConfigWarningNotification;
+use codex_app_server_protocol::InitializeAuthState;
 use codex_app_server_protocol::InitializeResponse;
 use codex_app_server_protocol::JSONRPCError;
 use codex_app_server_protocol::JSONRPCErrorError;
@@ -18,9 +19,12 @@ use codex_app_server_protocol::JSONRPCNotification;
 use codex_app_server_protocol::JSONRPCRequest;
 use codex_app_server_protocol::JSONRPCResponse;
 use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::SUPPORTED_PROTOCOL_VERSIONS;
 use codex_app_server_protocol::ServerNotification;
+use codex_app_server_protocol::negotiate_protocol_version;
 use codex_core::AuthManager;
 use codex_core::ThreadManager;
+use codex_core::builtin_tool_names;
 use codex_core::config::Config;
 use codex_core::config_loader::LoaderOverrides;
 use codex_core::default_client::SetOriginatorError;
@@ -37,6 +41,8 @@ pub(crate) struct MessageProcessor {
     outgoing: Arc<OutgoingMessageSender>,
     codex_message_processor: CodexMessageProcessor,
     config_api: ConfigApi,
+    config: Arc<Config>,
+    auth_manager: Arc<AuthManager>,
     initialized: bool,
     config_warnings: Vec<ConfigWarningNotification>,
 }
@@ -65,7 +71,7 @@ impl MessageProcessor {
             SessionSource::VSCode,
         ));
         let codex_message_processor = CodexMessageProcessor::new(
-            auth_manager,
+            auth_manager.clone(),
             thread_manager,
             outgoing.clone(),
             codex_linux_sandbox_exe,
@@ -79,6 +85,8 @@ impl MessageProcessor {
             outgoing,
             codex_message_processor,
             config_api,
+            config,
+            auth_manager,
             initialized: false,
             config_warnings,
         }
@@ -130,6 +138,21 @@ impl MessageProcessor {
                         title: _title,
                         version,
                     } = params.client_info;
+                    let Some(protocol_version) =
+                        negotiate_protocol_version(params.protocol_versions.as_deref())
+                    else {
+                        let requested = params.protocol_versions.unwrap_or_default().join(", ");
+                        let supported = SUPPORTED_PROTOCOL_VERSIONS.join(", ");
+                        let error = JSONRPCErrorError {
+                            code: INVALID_REQUEST_ERROR_CODE,
+                            message: format!(
+                                "Unsupported protocol versions: {requested}. This server supports {supported}."
+                            ),
+                            data: None,
+                        };
+                        self.outgoing.send_error(request_id, error).await;
+                        return;
+                    };
                     if let Err(error) = set_default_originator(name.clone()) {
                         match error {
                             SetOriginatorError::InvalidHeaderValue => {
@@ -157,7 +180,27 @@ impl MessageProcessor {
                     }
 
                     let user_agent = get_codex_user_agent();
-                    let response = InitializeResponse { user_agent };
+                    let response = InitializeResponse {
+                        user_agent,
+                        server_version: env!("CARGO_PKG_VERSION").to_string(),
+                        protocol_versions: SUPPORTED_PROTOCOL_VERSIONS
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect(),
+                        protocol_version: protocol_version.to_string(),
+                        enabled_features: self
+                            .config
+                            .features
+                            .enabled_features()
+                            .into_iter()
+                            .map(|feature| feature.key().to_string())
+                            .collect(),
+                        tools: builtin_tool_names(&self.config),
+                        auth: InitializeAuthState {
+                            requires_openai_auth: self.config.model_provider.requires_openai_auth,
+                            auth_mode: self.auth_manager.get_auth_mode(),
+                        },
+                    };
                     self.outgoing.send_response(request_id, response).await;
 
                     self.initialized = true;
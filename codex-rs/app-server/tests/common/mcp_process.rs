@@ -12,10 +12,13 @@ use tokio::process::ChildStdout;
 
 use anyhow::Context;
 use codex_app_server_protocol::AddConversationListenerParams;
+use codex_app_server_protocol::ApprovalsListParams;
+use codex_app_server_protocol::ApprovalsRevokeParams;
 use codex_app_server_protocol::AppsListParams;
 use codex_app_server_protocol::ArchiveConversationParams;
 use codex_app_server_protocol::CancelLoginAccountParams;
 use codex_app_server_protocol::CancelLoginChatGptParams;
+use codex_app_server_protocol::ChangesQueryParams;
 use codex_app_server_protocol::ClientInfo;
 use codex_app_server_protocol::ClientNotification;
 use codex_app_server_protocol::CollaborationModeListParams;
@@ -41,18 +44,26 @@ use codex_app_server_protocol::RemoveConversationListenerParams;
 use codex_app_server_protocol::RequestId;
 use codex_app_server_protocol::ResumeConversationParams;
 use codex_app_server_protocol::ReviewStartParams;
+use codex_app_server_protocol::SandboxPolicyReadParams;
 use codex_app_server_protocol::SendUserMessageParams;
 use codex_app_server_protocol::SendUserTurnParams;
 use codex_app_server_protocol::ServerRequest;
 use codex_app_server_protocol::SetDefaultModelParams;
 use codex_app_server_protocol::ThreadArchiveParams;
+use codex_app_server_protocol::ThreadFeatureSetParams;
 use codex_app_server_protocol::ThreadForkParams;
 use codex_app_server_protocol::ThreadListParams;
 use codex_app_server_protocol::ThreadLoadedListParams;
+use codex_app_server_protocol::ThreadMergeParams;
+use codex_app_server_protocol::ThreadMetadataUpdateParams;
 use codex_app_server_protocol::ThreadReadParams;
 use codex_app_server_protocol::ThreadResumeParams;
 use codex_app_server_protocol::ThreadRollbackParams;
+use codex_app_server_protocol::ThreadStartFromTemplateParams;
 use codex_app_server_protocol::ThreadStartParams;
+use codex_app_server_protocol::ThreadTrimParams;
+use codex_app_server_protocol::ThreadUnarchiveParams;
+use codex_app_server_protocol::TurnContextReadParams;
 use codex_app_server_protocol::TurnInterruptParams;
 use codex_app_server_protocol::TurnStartParams;
 use codex_core::default_client::CODEX_INTERNAL_ORIGINATOR_OVERRIDE_ENV_VAR;
@@ -161,7 +172,19 @@ impl McpProcess {
         &mut self,
         client_info: ClientInfo,
     ) -> anyhow::Result<JSONRPCMessage> {
-        let params = Some(serde_json::to_value(InitializeParams { client_info })?);
+        self.initialize_with_params(InitializeParams {
+            client_info,
+            protocol_versions: None,
+        })
+        .await
+    }
+
+    /// Sends initialize with the provided params and returns the response/error message.
+    pub async fn initialize_with_params(
+        &mut self,
+        params: InitializeParams,
+    ) -> anyhow::Result<JSONRPCMessage> {
+        let params = Some(serde_json::to_value(params)?);
         let request_id = self.send_request("initialize", params).await?;
         let message = self.read_jsonrpc_message().await?;
         match message {
@@ -338,6 +361,15 @@ impl McpProcess {
         self.send_request("thread/start", params).await
     }
 
+    /// Send a `thread/startFromTemplate` JSON-RPC request.
+    pub async fn send_thread_start_from_template_request(
+        &mut self,
+        params: ThreadStartFromTemplateParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("thread/startFromTemplate", params).await
+    }
+
     /// Send a `thread/resume` JSON-RPC request.
     pub async fn send_thread_resume_request(
         &mut self,
@@ -365,6 +397,33 @@ impl McpProcess {
         self.send_request("thread/archive", params).await
     }
 
+    /// Send a `thread/unarchive` JSON-RPC request.
+    pub async fn send_thread_unarchive_request(
+        &mut self,
+        params: ThreadUnarchiveParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("thread/unarchive", params).await
+    }
+
+    /// Send a `thread/metadata/update` JSON-RPC request.
+    pub async fn send_thread_metadata_update_request(
+        &mut self,
+        params: ThreadMetadataUpdateParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("thread/metadata/update", params).await
+    }
+
+    /// Send a `thread/feature/set` JSON-RPC request.
+    pub async fn send_thread_feature_set_request(
+        &mut self,
+        params: ThreadFeatureSetParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("thread/feature/set", params).await
+    }
+
     /// Send a `thread/rollback` JSON-RPC request.
     pub async fn send_thread_rollback_request(
         &mut self,
@@ -374,6 +433,33 @@ impl McpProcess {
         self.send_request("thread/rollback", params).await
     }
 
+    /// Send a `thread/trim` JSON-RPC request.
+    pub async fn send_thread_trim_request(
+        &mut self,
+        params: ThreadTrimParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("thread/trim", params).await
+    }
+
+    /// Send a `changes/query` JSON-RPC request.
+    pub async fn send_changes_query_request(
+        &mut self,
+        params: ChangesQueryParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("changes/query", params).await
+    }
+
+    /// Send a `thread/merge` JSON-RPC request.
+    pub async fn send_thread_merge_request(
+        &mut self,
+        params: ThreadMergeParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("thread/merge", params).await
+    }
+
     /// Send a `thread/list` JSON-RPC request.
     pub async fn send_thread_list_request(
         &mut self,
@@ -425,6 +511,33 @@ impl McpProcess {
         self.send_request("collaborationMode/list", params).await
     }
 
+    /// Send a `sandbox/policy/read` JSON-RPC request.
+    pub async fn send_sandbox_policy_read_request(
+        &mut self,
+        params: SandboxPolicyReadParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("sandbox/policy/read", params).await
+    }
+
+    /// Send an `approvals/list` JSON-RPC request.
+    pub async fn send_approvals_list_request(
+        &mut self,
+        params: ApprovalsListParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("approvals/list", params).await
+    }
+
+    /// Send an `approvals/revoke` JSON-RPC request.
+    pub async fn send_approvals_revoke_request(
+        &mut self,
+        params: ApprovalsRevokeParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("approvals/revoke", params).await
+    }
+
     /// Send a `resumeConversation` JSON-RPC request.
     pub async fn send_resume_conversation_request(
         &mut self,
@@ -475,6 +588,15 @@ impl McpProcess {
         self.send_request("turn/interrupt", params).await
     }
 
+    /// Send a `turn/context/read` JSON-RPC request (v2).
+    pub async fn send_turn_context_read_request(
+        &mut self,
+        params: TurnContextReadParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("turn/context/read", params).await
+    }
+
     /// Send a `review/start` JSON-RPC request (v2).
     pub async fn send_review_start_request(
         &mut self,
@@ -60,10 +60,15 @@ pub fn create_fake_rollout(
         source: SessionSource::Cli,
         model_provider: model_provider.map(str::to_string),
         base_instructions: None,
+        title: None,
+        tags: Vec::new(),
+        stats: None,
     };
     let payload = serde_json::to_value(SessionMetaLine {
         meta,
         git: git_info,
+        environment: None,
+        schema_version: None,
     })?;
 
     let lines = [
@@ -138,10 +143,15 @@ pub fn create_fake_rollout_with_text_elements(
         source: SessionSource::Cli,
         model_provider: model_provider.map(str::to_string),
         base_instructions: None,
+        title: None,
+        tags: Vec::new(),
+        stats: None,
     };
     let payload = serde_json::to_value(SessionMetaLine {
         meta,
         git: git_info,
+        environment: None,
+        schema_version: None,
     })?;
 
     let lines = [
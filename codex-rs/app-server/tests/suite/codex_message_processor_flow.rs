@@ -417,6 +417,9 @@ async fn test_send_user_turn_updates_sandbox_and_cwd_between_turns() -> Result<(
                 network_access: false,
                 exclude_tmpdir_env_var: false,
                 exclude_slash_tmp: false,
+                max_memory_bytes: None,
+                cpu_rate_percent: None,
+                max_processes: None,
             },
             model: model.clone(),
             effort: Some(ReasoningEffort::Medium),
@@ -0,0 +1,122 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::to_response;
+use codex_app_server_protocol::ApprovalScope;
+use codex_app_server_protocol::ApprovalTarget;
+use codex_app_server_protocol::ApprovalsListParams;
+use codex_app_server_protocol::ApprovalsListResponse;
+use codex_app_server_protocol::ApprovalsRevokeParams;
+use codex_app_server_protocol::ApprovalsRevokeResponse;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RememberedApproval;
+use codex_app_server_protocol::RequestId;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[tokio::test]
+async fn approvals_list_and_revoke_persisted_approvals() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    std::fs::write(
+        codex_home.path().join("approvals.json"),
+        serde_json::to_string_pretty(&json!({
+            "approvals": [
+                {
+                    "id": "shell-approval",
+                    "scope": "global",
+                    "tool": "shell",
+                    "target": { "type": "command", "command": ["cargo", "test"] },
+                    "created_at": "2025-06-11T16:02:10.003Z",
+                },
+                {
+                    "id": "patch-approval",
+                    "scope": "project",
+                    "project": "/repo",
+                    "tool": "apply_patch",
+                    "target": { "type": "path", "path": "/repo/src" },
+                    "created_at": "2025-06-12T09:31:44.120Z",
+                },
+            ],
+        }))?,
+    )?;
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_TIMEOUT, mcp.initialize()).await??;
+
+    let patch_approval = RememberedApproval {
+        id: "patch-approval".to_string(),
+        scope: ApprovalScope::Project,
+        project: Some(PathBuf::from("/repo")),
+        tool: "apply_patch".to_string(),
+        target: ApprovalTarget::Path {
+            path: PathBuf::from("/repo/src"),
+        },
+        created_at: "2025-06-12T09:31:44.120Z".to_string(),
+    };
+    assert_eq!(
+        list_approvals(&mut mcp).await?,
+        ApprovalsListResponse {
+            approvals: vec![
+                RememberedApproval {
+                    id: "shell-approval".to_string(),
+                    scope: ApprovalScope::Global,
+                    project: None,
+                    tool: "shell".to_string(),
+                    target: ApprovalTarget::Command {
+                        command: vec!["cargo".to_string(), "test".to_string()],
+                    },
+                    created_at: "2025-06-11T16:02:10.003Z".to_string(),
+                },
+                patch_approval.clone(),
+            ],
+        }
+    );
+
+    let request_id = mcp
+        .send_approvals_revoke_request(ApprovalsRevokeParams {
+            id: "shell-approval".to_string(),
+            thread_id: None,
+        })
+        .await?;
+    let response: JSONRPCResponse = timeout(
+        DEFAULT_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+    assert_eq!(
+        to_response::<ApprovalsRevokeResponse>(response)?,
+        ApprovalsRevokeResponse { revoked: true }
+    );
+
+    assert_eq!(
+        list_approvals(&mut mcp).await?,
+        ApprovalsListResponse {
+            approvals: vec![patch_approval],
+        }
+    );
+
+    // The rewrite goes through a temp file that must not be left behind.
+    let mut entries = std::fs::read_dir(codex_home.path())?
+        .map(|entry| entry.map(|entry| entry.file_name().to_string_lossy().into_owned()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    entries.retain(|name| name.starts_with("approvals"));
+    assert_eq!(entries, vec!["approvals.json".to_string()]);
+    Ok(())
+}
+
+async fn list_approvals(mcp: &mut McpProcess) -> Result<ApprovalsListResponse> {
+    let request_id = mcp
+        .send_approvals_list_request(ApprovalsListParams { thread_id: None })
+        .await?;
+    let response: JSONRPCResponse = timeout(
+        DEFAULT_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+    to_response::<ApprovalsListResponse>(response)
+}
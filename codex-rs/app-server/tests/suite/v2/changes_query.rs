@@ -0,0 +1,123 @@
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::create_fake_rollout;
+use app_test_support::rollout_path;
+use app_test_support::to_response;
+use codex_app_server_protocol::ChangesQueryParams;
+use codex_app_server_protocol::ChangesQueryResponse;
+use codex_app_server_protocol::JSONRPCError;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RecordedFileChange;
+use codex_app_server_protocol::RequestId;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+fn append_turn_diff(rollout: &Path, timestamp: &str, file: &str) -> Result<()> {
+    let diff = format!(
+        "diff --git a/{file} b/{file}\nindex 1..2\n--- a/{file}\n+++ b/{file}\n@@ -1 +1 @@\n-old\n+new\n"
+    );
+    let line = json!({
+        "timestamp": timestamp,
+        "type": "event_msg",
+        "payload": { "type": "turn_diff", "unified_diff": diff },
+    });
+    let mut file = OpenOptions::new().append(true).open(rollout)?;
+    writeln!(file, "{line}")?;
+    Ok(())
+}
+
+async fn query_changes(
+    mcp: &mut McpProcess,
+    params: ChangesQueryParams,
+) -> Result<ChangesQueryResponse> {
+    let request_id = mcp.send_changes_query_request(params).await?;
+    let resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+    to_response::<ChangesQueryResponse>(resp)
+}
+
+#[tokio::test]
+async fn changes_query_finds_the_turns_that_changed_a_file() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let auth_thread = create_fake_rollout(
+        codex_home.path(),
+        "2025-06-01T10-00-00",
+        "2025-06-01T10:00:00Z",
+        "Fix the login",
+        Some("mock_provider"),
+        None,
+    )?;
+    let auth_rollout = rollout_path(codex_home.path(), "2025-06-01T10-00-00", &auth_thread);
+    append_turn_diff(&auth_rollout, "2025-06-01T10:00:05.000Z", "src/auth.rs")?;
+    let docs_thread = create_fake_rollout(
+        codex_home.path(),
+        "2025-06-02T10-00-00",
+        "2025-06-02T10:00:00Z",
+        "Update the docs",
+        Some("mock_provider"),
+        None,
+    )?;
+    let docs_rollout = rollout_path(codex_home.path(), "2025-06-02T10-00-00", &docs_thread);
+    append_turn_diff(&docs_rollout, "2025-06-02T10:00:05.000Z", "docs/auth.md")?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let response = query_changes(
+        &mut mcp,
+        ChangesQueryParams {
+            cwd: None,
+            path: Some("auth.rs".to_string()),
+            since: Some("2025-06-01".to_string()),
+        },
+    )
+    .await?;
+
+    assert_eq!(
+        response,
+        ChangesQueryResponse {
+            changes: vec![RecordedFileChange {
+                repo: PathBuf::from("/"),
+                path: "src/auth.rs".to_string(),
+                thread_id: auth_thread,
+                rollout_path: auth_rollout,
+                turn: 1,
+                timestamp: "2025-06-01T10:00:05.000Z".to_string(),
+            }],
+        }
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn changes_query_rejects_an_invalid_day() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let request_id = mcp
+        .send_changes_query_request(ChangesQueryParams {
+            cwd: None,
+            path: None,
+            since: Some("June 1st".to_string()),
+        })
+        .await?;
+    let err: JSONRPCError = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+    assert_eq!(err.error.code, -32600);
+    Ok(())
+}
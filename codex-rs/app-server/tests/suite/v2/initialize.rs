@@ -3,6 +3,8 @@ use app_test_support::McpProcess;
 use app_test_support::create_mock_responses_server_sequence_unchecked;
 use app_test_support::to_response;
 use codex_app_server_protocol::ClientInfo;
+use codex_app_server_protocol::InitializeAuthState;
+use codex_app_server_protocol::InitializeParams;
 use codex_app_server_protocol::InitializeResponse;
 use codex_app_server_protocol::JSONRPCMessage;
 use pretty_assertions::assert_eq;
@@ -33,7 +35,7 @@ async fn initialize_uses_client_info_name_as_originator() -> Result<()> {
     let JSONRPCMessage::Response(response) = message else {
         anyhow::bail!("expected initialize response, got {message:?}");
     };
-    let InitializeResponse { user_agent } = to_response::<InitializeResponse>(response)?;
+    let InitializeResponse { user_agent, .. } = to_response::<InitializeResponse>(response)?;
 
     assert!(user_agent.starts_with("codex_vscode/"));
     Ok(())
@@ -67,7 +69,7 @@ async fn initialize_respects_originator_override_env_var() -> Result<()> {
     let JSONRPCMessage::Response(response) = message else {
         anyhow::bail!("expected initialize response, got {message:?}");
     };
-    let InitializeResponse { user_agent } = to_response::<InitializeResponse>(response)?;
+    let InitializeResponse { user_agent, .. } = to_response::<InitializeResponse>(response)?;
 
     assert!(user_agent.starts_with("codex_originator_via_env_var/"));
     Ok(())
@@ -108,6 +110,135 @@ async fn initialize_rejects_invalid_client_name() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn initialize_reports_version_features_tools_and_auth() -> Result<()> {
+    let responses = Vec::new();
+    let server = create_mock_responses_server_sequence_unchecked(responses).await;
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri(), "never")?;
+    let config_toml = codex_home.path().join("config.toml");
+    let config = std::fs::read_to_string(&config_toml)?;
+    std::fs::write(&config_toml, format!("{config}\n[features]\nrepl = true\n"))?;
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+
+    let message = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.initialize_with_params(InitializeParams {
+            client_info: client_info(),
+            protocol_versions: Some(vec!["v2".to_string()]),
+        }),
+    )
+    .await??;
+
+    let JSONRPCMessage::Response(response) = message else {
+        anyhow::bail!("expected initialize response, got {message:?}");
+    };
+    let response = to_response::<InitializeResponse>(response)?;
+
+    assert_eq!(response.server_version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(
+        response.protocol_versions,
+        vec!["v1".to_string(), "v2".to_string()]
+    );
+    assert_eq!(response.protocol_version, "v2");
+    assert!(response.enabled_features.contains(&"repl".to_string()));
+    assert!(response.tools.contains(&"repl".to_string()));
+    assert_eq!(
+        response.auth,
+        InitializeAuthState {
+            requires_openai_auth: false,
+            auth_mode: None,
+        }
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn initialize_accepts_clients_that_predate_protocol_versions() -> Result<()> {
+    let responses = Vec::new();
+    let server = create_mock_responses_server_sequence_unchecked(responses).await;
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri(), "never")?;
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+
+    // Without `protocolVersions`, the request is what older clients send.
+    let message = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.initialize_with_client_info(client_info()),
+    )
+    .await??;
+
+    let JSONRPCMessage::Response(response) = message else {
+        anyhow::bail!("expected initialize response, got {message:?}");
+    };
+    // Older clients only read `userAgent`; it must still be there.
+    assert!(response.result.get("userAgent").is_some());
+    let response = to_response::<InitializeResponse>(response)?;
+    assert_eq!(response.protocol_version, "v2");
+    Ok(())
+}
+
+#[tokio::test]
+async fn initialize_negotiates_the_clients_preferred_protocol_version() -> Result<()> {
+    let responses = Vec::new();
+    let server = create_mock_responses_server_sequence_unchecked(responses).await;
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri(), "never")?;
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+
+    let message = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.initialize_with_params(InitializeParams {
+            client_info: client_info(),
+            protocol_versions: Some(vec!["v0".to_string(), "v1".to_string(), "v2".to_string()]),
+        }),
+    )
+    .await??;
+
+    let JSONRPCMessage::Response(response) = message else {
+        anyhow::bail!("expected initialize response, got {message:?}");
+    };
+    let response = to_response::<InitializeResponse>(response)?;
+    assert_eq!(response.protocol_version, "v1");
+    Ok(())
+}
+
+#[tokio::test]
+async fn initialize_rejects_clients_without_a_common_protocol_version() -> Result<()> {
+    let responses = Vec::new();
+    let server = create_mock_responses_server_sequence_unchecked(responses).await;
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri(), "never")?;
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+
+    let message = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.initialize_with_params(InitializeParams {
+            client_info: client_info(),
+            protocol_versions: Some(vec!["v3".to_string()]),
+        }),
+    )
+    .await??;
+
+    let JSONRPCMessage::Error(error) = message else {
+        anyhow::bail!("expected initialize error, got {message:?}");
+    };
+    assert_eq!(error.error.code, -32600);
+    assert_eq!(
+        error.error.message,
+        "Unsupported protocol versions: v3. This server supports v1, v2."
+    );
+    Ok(())
+}
+
+fn client_info() -> ClientInfo {
+    ClientInfo {
+        name: "codex_vscode".to_string(),
+        title: Some("Codex VS Code Extension".to_string()),
+        version: "0.1.0".to_string(),
+    }
+}
+
 // Helper to create a config.toml pointing at the mock model server.
 fn create_config_toml(
     codex_home: &Path,
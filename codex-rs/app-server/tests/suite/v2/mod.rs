@@ -1,6 +1,8 @@
 mod account;
 mod analytics;
 mod app_list;
+mod approvals;
+mod changes_query;
 mod collaboration_mode_list;
 mod config_rpc;
 mod initialize;
@@ -9,13 +11,20 @@ mod output_schema;
 mod rate_limits;
 mod request_user_input;
 mod review;
+mod sandbox_policy_read;
 mod thread_archive;
+mod thread_feature_set;
 mod thread_fork;
 mod thread_list;
 mod thread_loaded_list;
+mod thread_merge;
+mod thread_metadata_update;
 mod thread_read;
 mod thread_resume;
 mod thread_rollback;
 mod thread_start;
+mod thread_start_from_template;
+mod thread_trim;
+mod turn_context_read;
 mod turn_interrupt;
 mod turn_start;
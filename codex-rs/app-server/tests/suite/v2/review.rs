@@ -61,6 +61,7 @@ async fn review_start_runs_review_turn_and_emits_code_review_item() -> Result<()
                 sha: "1234567deadbeef".to_string(),
                 title: Some("Tidy UI colors".to_string()),
             },
+            paths: None,
         })
         .await?;
     let review_resp: JSONRPCResponse = timeout(
@@ -87,7 +88,7 @@ async fn review_start_runs_review_turn_and_emits_code_review_item() -> Result<()
         let started: ItemStartedNotification =
             serde_json::from_value(item_started.params.expect("params must be present"))?;
         match started.item {
-            ThreadItem::EnteredReviewMode { id, review } => {
+            ThreadItem::EnteredReviewMode { id, review, .. } => {
                 assert_eq!(id, turn_id);
                 assert_eq!(review, "commit 1234567: Tidy UI colors");
                 saw_entered_review_mode = true;
@@ -113,7 +114,7 @@ async fn review_start_runs_review_turn_and_emits_code_review_item() -> Result<()
         let completed: ItemCompletedNotification =
             serde_json::from_value(review_notif.params.expect("params must be present"))?;
         match completed.item {
-            ThreadItem::ExitedReviewMode { id, review } => {
+            ThreadItem::ExitedReviewMode { id, review, .. } => {
                 assert_eq!(id, turn_id);
                 review_body = Some(review);
                 break;
@@ -146,6 +147,7 @@ async fn review_start_rejects_empty_base_branch() -> Result<()> {
             target: ReviewTarget::BaseBranch {
                 branch: "   ".to_string(),
             },
+            paths: None,
         })
         .await?;
     let error: JSONRPCError = timeout(
@@ -189,6 +191,7 @@ async fn review_start_with_detached_delivery_returns_new_thread_id() -> Result<(
             target: ReviewTarget::Custom {
                 instructions: "detached review".to_string(),
             },
+            paths: None,
         })
         .await?;
     let review_resp: JSONRPCResponse = timeout(
@@ -228,6 +231,7 @@ async fn review_start_rejects_empty_commit_sha() -> Result<()> {
                 sha: "\t".to_string(),
                 title: None,
             },
+            paths: None,
         })
         .await?;
     let error: JSONRPCError = timeout(
@@ -262,6 +266,7 @@ async fn review_start_rejects_empty_custom_instructions() -> Result<()> {
             target: ReviewTarget::Custom {
                 instructions: "\n\n".to_string(),
             },
+            paths: None,
         })
         .await?;
     let error: JSONRPCError = timeout(
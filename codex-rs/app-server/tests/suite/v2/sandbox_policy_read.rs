@@ -0,0 +1,106 @@
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::to_response;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::SandboxBackend;
+use codex_app_server_protocol::SandboxPolicy;
+use codex_app_server_protocol::SandboxPolicyReadParams;
+use codex_app_server_protocol::SandboxPolicyReadResponse;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[tokio::test]
+async fn sandbox_policy_read_reports_full_access() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), "danger-full-access")?;
+    let workspace = TempDir::new()?;
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_TIMEOUT, mcp.initialize()).await??;
+
+    let request_id = mcp
+        .send_sandbox_policy_read_request(SandboxPolicyReadParams {
+            cwd: Some(workspace.path().to_path_buf()),
+        })
+        .await?;
+    let response: JSONRPCResponse = timeout(
+        DEFAULT_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+    let report = to_response::<SandboxPolicyReadResponse>(response)?;
+
+    assert_eq!(
+        report,
+        SandboxPolicyReadResponse {
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            cwd: workspace.path().to_path_buf(),
+            full_disk_write_access: true,
+            writable_roots: Vec::new(),
+            network_access: true,
+            backend: SandboxBackend::None,
+        }
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn sandbox_policy_read_reports_read_only() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), "read-only")?;
+    let workspace = TempDir::new()?;
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_TIMEOUT, mcp.initialize()).await??;
+
+    let request_id = mcp
+        .send_sandbox_policy_read_request(SandboxPolicyReadParams {
+            cwd: Some(workspace.path().to_path_buf()),
+        })
+        .await?;
+    let response: JSONRPCResponse = timeout(
+        DEFAULT_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+    let report = to_response::<SandboxPolicyReadResponse>(response)?;
+
+    // The Windows sandbox is off unless enabled in the config.
+    let backend = if cfg!(target_os = "macos") {
+        SandboxBackend::MacosSeatbelt
+    } else if cfg!(target_os = "linux") {
+        SandboxBackend::LinuxSeccomp
+    } else {
+        SandboxBackend::None
+    };
+    assert_eq!(
+        report,
+        SandboxPolicyReadResponse {
+            sandbox_policy: SandboxPolicy::ReadOnly,
+            cwd: workspace.path().to_path_buf(),
+            full_disk_write_access: false,
+            writable_roots: Vec::new(),
+            network_access: false,
+            backend,
+        }
+    );
+    Ok(())
+}
+
+fn create_config_toml(codex_home: &Path, sandbox_mode: &str) -> std::io::Result<()> {
+    std::fs::write(
+        codex_home.join("config.toml"),
+        format!(
+            r#"
+model = "mock-model"
+approval_policy = "never"
+sandbox_mode = "{sandbox_mode}"
+"#
+        ),
+    )
+}
@@ -58,6 +58,7 @@ async fn list_threads_with_sort(
             sort_key,
             model_providers: providers,
             archived,
+            tags: None,
         })
         .await?;
     let resp: JSONRPCResponse = timeout(
@@ -879,6 +880,7 @@ async fn thread_list_invalid_cursor_returns_error() -> Result<()> {
             sort_key: None,
             model_providers: Some(vec!["mock_provider".to_string()]),
             archived: None,
+            tags: None,
         })
         .await?;
     let error: JSONRPCError = timeout(
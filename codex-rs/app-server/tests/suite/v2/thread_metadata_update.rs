@@ -0,0 +1,154 @@
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::create_fake_rollout;
+use app_test_support::to_response;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::ThreadListParams;
+use codex_app_server_protocol::ThreadListResponse;
+use codex_app_server_protocol::ThreadMetadataUpdateParams;
+use codex_app_server_protocol::ThreadMetadataUpdateResponse;
+use codex_core::find_thread_path_by_id_str;
+use codex_core::read_session_meta_line;
+use pretty_assertions::assert_eq;
+use std::path::Path;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test]
+async fn thread_metadata_update_persists_title_and_tags() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path())?;
+    let thread_id = create_fake_rollout(
+        codex_home.path(),
+        "2025-01-05T12-00-00",
+        "2025-01-05T12:00:00Z",
+        "Fix the flaky test",
+        Some("mock_provider"),
+        None,
+    )?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let update_id = mcp
+        .send_thread_metadata_update_request(ThreadMetadataUpdateParams {
+            thread_id: thread_id.clone(),
+            title: Some("Flaky test".to_string()),
+            tags: Some(vec!["ci".to_string(), "tests".to_string()]),
+        })
+        .await?;
+    let update_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(update_id)),
+    )
+    .await??;
+    let ThreadMetadataUpdateResponse { thread } =
+        to_response::<ThreadMetadataUpdateResponse>(update_resp)?;
+    assert_eq!(thread.id, thread_id);
+    assert_eq!(thread.title, Some("Flaky test".to_string()));
+    assert_eq!(thread.tags, vec!["ci".to_string(), "tests".to_string()]);
+    assert_eq!(thread.preview, "Fix the flaky test");
+
+    let rollout_path = find_thread_path_by_id_str(codex_home.path(), &thread_id)
+        .await?
+        .expect("rollout path");
+    let meta_line = read_session_meta_line(&rollout_path).await?;
+    assert_eq!(meta_line.meta.title, Some("Flaky test".to_string()));
+    assert_eq!(
+        meta_line.meta.tags,
+        vec!["ci".to_string(), "tests".to_string()]
+    );
+
+    // Leaving tags unset keeps them; an empty title clears it.
+    let clear_id = mcp
+        .send_thread_metadata_update_request(ThreadMetadataUpdateParams {
+            thread_id: thread_id.clone(),
+            title: Some(String::new()),
+            tags: None,
+        })
+        .await?;
+    let clear_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(clear_id)),
+    )
+    .await??;
+    let ThreadMetadataUpdateResponse { thread } =
+        to_response::<ThreadMetadataUpdateResponse>(clear_resp)?;
+    assert_eq!(thread.title, None);
+    assert_eq!(thread.tags, vec!["ci".to_string(), "tests".to_string()]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn thread_list_filters_by_tags() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path())?;
+    let tagged = create_fake_rollout(
+        codex_home.path(),
+        "2025-01-05T12-00-00",
+        "2025-01-05T12:00:00Z",
+        "Tagged",
+        Some("mock_provider"),
+        None,
+    )?;
+    let _untagged = create_fake_rollout(
+        codex_home.path(),
+        "2025-01-06T12-00-00",
+        "2025-01-06T12:00:00Z",
+        "Untagged",
+        Some("mock_provider"),
+        None,
+    )?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let update_id = mcp
+        .send_thread_metadata_update_request(ThreadMetadataUpdateParams {
+            thread_id: tagged.clone(),
+            title: None,
+            tags: Some(vec!["release".to_string(), "ci".to_string()]),
+        })
+        .await?;
+    timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(update_id)),
+    )
+    .await??;
+
+    let list_id = mcp
+        .send_thread_list_request(ThreadListParams {
+            cursor: None,
+            limit: None,
+            sort_key: None,
+            model_providers: Some(Vec::new()),
+            archived: None,
+            tags: Some(vec!["ci".to_string()]),
+        })
+        .await?;
+    let list_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(list_id)),
+    )
+    .await??;
+    let ThreadListResponse { data, .. } = to_response::<ThreadListResponse>(list_resp)?;
+    let ids: Vec<String> = data.into_iter().map(|thread| thread.id).collect();
+    assert_eq!(ids, vec![tagged]);
+
+    Ok(())
+}
+
+fn create_config_toml(codex_home: &Path) -> std::io::Result<()> {
+    let config_toml = codex_home.join("config.toml");
+    std::fs::write(
+        config_toml,
+        r#"model = "mock-model"
+approval_policy = "never"
+sandbox_mode = "read-only"
+"#,
+    )
+}
@@ -0,0 +1,167 @@
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::create_mock_responses_server_repeating_assistant;
+use app_test_support::to_response;
+use codex_app_server_protocol::JSONRPCError;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::ThreadStartFromTemplateParams;
+use codex_app_server_protocol::ThreadStartResponse;
+use pretty_assertions::assert_eq;
+use std::collections::HashMap;
+use std::path::Path;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test]
+async fn start_from_template_submits_rendered_prompt_and_context_files() -> Result<()> {
+    let server = create_mock_responses_server_repeating_assistant("Done").await;
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri())?;
+    write_template(
+        codex_home.path(),
+        "release",
+        "prompt = \"Write notes for $VERSION.\"\ncontext_files = [\"CHANGELOG.md\"]\n",
+    )?;
+    let workspace = TempDir::new()?;
+    std::fs::write(workspace.path().join("CHANGELOG.md"), "- fixed a crash")?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+    let req_id = mcp
+        .send_thread_start_from_template_request(ThreadStartFromTemplateParams {
+            template: "release".to_string(),
+            arguments: HashMap::from([("VERSION".to_string(), "1.2.0".to_string())]),
+            cwd: Some(workspace.path().to_string_lossy().into_owned()),
+        })
+        .await?;
+    let resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(req_id)),
+    )
+    .await??;
+    let ThreadStartResponse { thread, .. } = to_response::<ThreadStartResponse>(resp)?;
+    assert!(!thread.id.is_empty(), "thread id should not be empty");
+
+    timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_notification_message("turn/completed"),
+    )
+    .await??;
+    let requests = server
+        .received_requests()
+        .await
+        .expect("mock server should record requests");
+    let body = String::from_utf8_lossy(&requests[0].body).into_owned();
+    assert!(body.contains("Write notes for 1.2.0."), "{body}");
+    assert!(body.contains("- fixed a crash"), "{body}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn start_from_template_refuses_context_files_outside_the_cwd() -> Result<()> {
+    let server = create_mock_responses_server_repeating_assistant("Done").await;
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri())?;
+    let dir = TempDir::new()?;
+    let workspace = dir.path().join("workspace");
+    std::fs::create_dir(&workspace)?;
+    std::fs::write(dir.path().join("secret.txt"), "secret")?;
+    write_template(
+        codex_home.path(),
+        "leak",
+        "prompt = \"Summarize\"\ncontext_files = [\"../secret.txt\"]\n",
+    )?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+    let req_id = mcp
+        .send_thread_start_from_template_request(ThreadStartFromTemplateParams {
+            template: "leak".to_string(),
+            arguments: HashMap::new(),
+            cwd: Some(workspace.to_string_lossy().into_owned()),
+        })
+        .await?;
+    let err: JSONRPCError = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(req_id)),
+    )
+    .await??;
+    assert_eq!(
+        err.error.message,
+        format!(
+            "template `leak` context file {} is outside {}",
+            workspace.join("../secret.txt").display(),
+            workspace.display()
+        )
+    );
+    assert_eq!(
+        server
+            .received_requests()
+            .await
+            .expect("mock server should record requests")
+            .len(),
+        0
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn start_from_template_ignores_templates_of_untrusted_projects() -> Result<()> {
+    let server = create_mock_responses_server_repeating_assistant("Done").await;
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri())?;
+    let workspace = TempDir::new()?;
+    let templates = workspace.path().join(".codex").join("templates");
+    std::fs::create_dir_all(&templates)?;
+    std::fs::write(templates.join("triage.toml"), "prompt = \"Triage\"\n")?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+    let req_id = mcp
+        .send_thread_start_from_template_request(ThreadStartFromTemplateParams {
+            template: "triage".to_string(),
+            arguments: HashMap::new(),
+            cwd: Some(workspace.path().to_string_lossy().into_owned()),
+        })
+        .await?;
+    let err: JSONRPCError = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(req_id)),
+    )
+    .await??;
+    assert_eq!(err.error.message, "no session template named `triage`");
+    Ok(())
+}
+
+fn write_template(codex_home: &Path, name: &str, contents: &str) -> std::io::Result<()> {
+    let dir = codex_home.join("templates");
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join(format!("{name}.toml")), contents)
+}
+
+// Helper to create a config.toml pointing at the mock model server.
+fn create_config_toml(codex_home: &Path, server_uri: &str) -> std::io::Result<()> {
+    let config_toml = codex_home.join("config.toml");
+    std::fs::write(
+        config_toml,
+        format!(
+            r#"
+model = "mock-model"
+approval_policy = "never"
+sandbox_mode = "read-only"
+
+model_provider = "mock_provider"
+
+[model_providers.mock_provider]
+name = "Mock provider for test"
+base_url = "{server_uri}/v1"
+wire_api = "responses"
+request_max_retries = 0
+stream_max_retries = 0
+"#
+        ),
+    )
+}
@@ -0,0 +1,122 @@
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::create_fake_rollout;
+use app_test_support::to_response;
+use codex_app_server_protocol::JSONRPCError;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::ThreadTrimParams;
+use codex_app_server_protocol::ThreadTrimResponse;
+use codex_core::find_thread_path_by_id_str;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use std::io::Write;
+use std::path::Path;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test]
+async fn thread_trim_removes_turns_from_rollout() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path())?;
+    let thread_id = create_fake_rollout(
+        codex_home.path(),
+        "2025-01-05T12-00-00",
+        "2025-01-05T12:00:00Z",
+        "Set up the deploy script",
+        Some("mock_provider"),
+        None,
+    )?;
+    let rollout_path = find_thread_path_by_id_str(codex_home.path(), &thread_id)
+        .await?
+        .expect("rollout path");
+    append_user_turn(&rollout_path, "my token is sk-pasted-by-mistake")?;
+    append_user_turn(&rollout_path, "now run it")?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let trim_id = mcp
+        .send_thread_trim_request(ThreadTrimParams {
+            thread_id: thread_id.clone(),
+            start_turn: 1,
+            num_turns: 1,
+        })
+        .await?;
+    let trim_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(trim_id)),
+    )
+    .await??;
+    let ThreadTrimResponse { thread } = to_response::<ThreadTrimResponse>(trim_resp)?;
+    assert_eq!(thread.id, thread_id);
+    assert_eq!(thread.preview, "Set up the deploy script");
+    assert_eq!(thread.turns.len(), 2);
+
+    let contents = std::fs::read_to_string(&rollout_path)?;
+    assert!(!contents.contains("sk-pasted-by-mistake"));
+    assert!(contents.contains(r#""type":"turns_trimmed""#));
+
+    // Only two turns are left.
+    let out_of_range_id = mcp
+        .send_thread_trim_request(ThreadTrimParams {
+            thread_id,
+            start_turn: 1,
+            num_turns: 2,
+        })
+        .await?;
+    let err: JSONRPCError = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(out_of_range_id)),
+    )
+    .await??;
+    assert_eq!(
+        err.error.message,
+        "cannot trim turns 1..3: the rollout has 2 turns"
+    );
+
+    Ok(())
+}
+
+fn append_user_turn(rollout_path: &Path, text: &str) -> std::io::Result<()> {
+    let lines = [
+        json!({
+            "timestamp": "2025-01-05T12:01:00Z",
+            "type": "response_item",
+            "payload": {
+                "type": "message",
+                "role": "user",
+                "content": [{"type": "input_text", "text": text}]
+            }
+        }),
+        json!({
+            "timestamp": "2025-01-05T12:01:00Z",
+            "type": "event_msg",
+            "payload": {
+                "type": "user_message",
+                "message": text,
+                "kind": "plain"
+            }
+        }),
+    ];
+    let mut file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(rollout_path)?;
+    for line in lines {
+        writeln!(file, "{line}")?;
+    }
+    Ok(())
+}
+
+fn create_config_toml(codex_home: &Path) -> std::io::Result<()> {
+    let config_toml = codex_home.join("config.toml");
+    std::fs::write(
+        config_toml,
+        r#"model = "mock-model"
+approval_policy = "never"
+sandbox_mode = "read-only"
+"#,
+    )
+}
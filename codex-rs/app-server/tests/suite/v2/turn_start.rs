@@ -772,6 +772,9 @@ async fn turn_start_updates_sandbox_and_cwd_between_turns_v2() -> Result<()> {
                 network_access: false,
                 exclude_tmpdir_env_var: false,
                 exclude_slash_tmp: false,
+                max_memory_bytes: None,
+                cpu_rate_percent: None,
+                max_processes: None,
             }),
             model: Some("mock-model".to_string()),
             effort: Some(ReasoningEffort::Medium),
@@ -957,6 +960,7 @@ async fn turn_start_file_change_approval_v2() -> Result<()> {
             path: expected_readme_path.clone(),
             kind: PatchChangeKind::Add,
             diff: "new line\n".to_string(),
+            mode: None,
         }]
     );
 
@@ -1310,6 +1314,7 @@ async fn turn_start_file_change_approval_decline_v2() -> Result<()> {
             path: expected_readme_path_str.clone(),
             kind: PatchChangeKind::Add,
             diff: "new line\n".to_string(),
+            mode: None,
         }]
     );
 
@@ -1,4 +1,5 @@
 mod invocation;
+mod merge;
 mod parser;
 mod seek_sequence;
 mod standalone_executable;
@@ -9,6 +10,7 @@ use std::path::PathBuf;
 
 use anyhow::Context;
 use anyhow::Result;
+pub use parser::FileMode;
 pub use parser::Hunk;
 pub use parser::ParseError;
 use parser::ParseError::*;
@@ -18,6 +20,12 @@ use similar::TextDiff;
 use thiserror::Error;
 
 pub use invocation::maybe_parse_apply_patch_verified;
+pub use merge::FileMergeConflict;
+pub use merge::MergeConflict;
+pub use merge::MergeResult;
+pub use merge::PatchRebase;
+pub use merge::maybe_rebase_apply_patch;
+pub use merge::three_way_merge;
 pub use standalone_executable::main;
 
 use crate::invocation::ExtractHeredocError;
@@ -86,6 +94,7 @@ pub struct ApplyPatchArgs {
 pub enum ApplyPatchFileChange {
     Add {
         content: String,
+        mode: Option<FileMode>,
     },
     Delete {
         content: String,
@@ -95,6 +104,12 @@ pub enum ApplyPatchFileChange {
         move_path: Option<PathBuf>,
         /// new_content that will result after the unified_diff is applied.
         new_content: String,
+        /// Mode the file is left with, when the patch changes it.
+        mode: Option<FileMode>,
+    },
+    /// A symlink created (or replaced) at the path, pointing to `target`.
+    Symlink {
+        target: PathBuf,
     },
 }
 
@@ -157,7 +172,13 @@ impl ApplyPatchAction {
 + {content}
 *** End Patch"#,
         );
-        let changes = HashMap::from([(path.to_path_buf(), ApplyPatchFileChange::Add { content })]);
+        let changes = HashMap::from([(
+            path.to_path_buf(),
+            ApplyPatchFileChange::Add {
+                content,
+                mode: None,
+            },
+        )]);
         #[expect(clippy::expect_used)]
         Self {
             changes,
@@ -212,7 +233,7 @@ pub fn apply_hunks(
     let _existing_paths: Vec<&Path> = hunks
         .iter()
         .filter_map(|hunk| match hunk {
-            Hunk::AddFile { .. } => {
+            Hunk::AddFile { .. } | Hunk::AddSymlink { .. } => {
                 // The file is being added, so it doesn't exist yet.
                 None
             }
@@ -277,16 +298,17 @@ fn apply_hunks_to_files(hunks: &[Hunk]) -> anyhow::Result<AffectedPaths> {
     let mut deleted: Vec<PathBuf> = Vec::new();
     for hunk in hunks {
         match hunk {
-            Hunk::AddFile { path, contents } => {
-                if let Some(parent) = path.parent()
-                    && !parent.as_os_str().is_empty()
-                {
-                    std::fs::create_dir_all(parent).with_context(|| {
-                        format!("Failed to create parent directories for {}", path.display())
-                    })?;
-                }
+            Hunk::AddFile {
+                path,
+                contents,
+                mode,
+            } => {
+                create_parent_dirs(path)?;
                 std::fs::write(path, contents)
                     .with_context(|| format!("Failed to write file {}", path.display()))?;
+                if let Some(mode) = mode {
+                    set_file_mode(path, *mode)?;
+                }
                 added.push(path.clone());
             }
             Hunk::DeleteFile { path } => {
@@ -297,28 +319,53 @@ fn apply_hunks_to_files(hunks: &[Hunk]) -> anyhow::Result<AffectedPaths> {
             Hunk::UpdateFile {
                 path,
                 move_path,
+                mode,
                 chunks,
             } => {
-                let AppliedPatch { new_contents, .. } =
-                    derive_new_contents_from_chunks(path, chunks)?;
-                if let Some(dest) = move_path {
-                    if let Some(parent) = dest.parent()
-                        && !parent.as_os_str().is_empty()
-                    {
-                        std::fs::create_dir_all(parent).with_context(|| {
-                            format!("Failed to create parent directories for {}", dest.display())
-                        })?;
-                    }
+                let new_contents = if chunks.is_empty() {
+                    // Only the mode changes: keep the contents byte for byte.
+                    std::fs::read(path).with_context(|| {
+                        format!("Failed to read file to update {}", path.display())
+                    })?
+                } else {
+                    derive_new_contents_from_chunks(path, chunks)?
+                        .new_contents
+                        .into_bytes()
+                };
+                let dest = if let Some(dest) = move_path {
+                    create_parent_dirs(dest)?;
                     std::fs::write(dest, new_contents)
                         .with_context(|| format!("Failed to write file {}", dest.display()))?;
                     std::fs::remove_file(path)
                         .with_context(|| format!("Failed to remove original {}", path.display()))?;
-                    modified.push(dest.clone());
+                    dest
                 } else {
                     std::fs::write(path, new_contents)
                         .with_context(|| format!("Failed to write file {}", path.display()))?;
-                    modified.push(path.clone());
+                    path
+                };
+                if let Some(mode) = mode {
+                    set_file_mode(dest, *mode)?;
                 }
+                modified.push(dest.clone());
+            }
+            Hunk::AddSymlink { path, target } => {
+                create_parent_dirs(path)?;
+                if let Ok(metadata) = std::fs::symlink_metadata(path) {
+                    if metadata.is_dir() {
+                        anyhow::bail!("Cannot replace directory {} with a symlink", path.display());
+                    }
+                    std::fs::remove_file(path)
+                        .with_context(|| format!("Failed to replace {}", path.display()))?;
+                }
+                create_symlink(target, path).with_context(|| {
+                    format!(
+                        "Failed to create symlink {} -> {}",
+                        path.display(),
+                        target.display()
+                    )
+                })?;
+                added.push(path.clone());
             }
         }
     }
@@ -329,6 +376,65 @@ fn apply_hunks_to_files(hunks: &[Hunk]) -> anyhow::Result<AffectedPaths> {
     })
 }
 
+fn create_parent_dirs(path: &Path) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        std::fs::create_dir_all(parent).with_context(|| {
+            format!("Failed to create parent directories for {}", path.display())
+        })?;
+    }
+    Ok(())
+}
+
+/// Sets or clears the executable bits, mirroring how git treats `+x`: every
+/// class that can read the file may execute it.
+#[cfg(unix)]
+fn set_file_mode(path: &Path, mode: FileMode) -> anyhow::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut permissions = std::fs::metadata(path)
+        .with_context(|| format!("Failed to read permissions of {}", path.display()))?
+        .permissions();
+    let bits = permissions.mode();
+    let bits = match mode {
+        FileMode::Executable => bits | ((bits & 0o444) >> 2),
+        FileMode::Regular => bits & !0o111,
+    };
+    permissions.set_mode(bits);
+    std::fs::set_permissions(path, permissions)
+        .with_context(|| format!("Failed to set permissions of {}", path.display()))
+}
+
+/// Windows has no executable bit, so mode changes are a no-op there.
+#[cfg(not(unix))]
+fn set_file_mode(_path: &Path, _mode: FileMode) -> anyhow::Result<()> {
+    Ok(())
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &Path, path: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, path)
+}
+
+#[cfg(windows)]
+fn create_symlink(target: &Path, path: &Path) -> std::io::Result<()> {
+    let resolved = path.parent().unwrap_or(Path::new("")).join(target);
+    if resolved.is_dir() {
+        std::os::windows::fs::symlink_dir(target, path)
+    } else {
+        std::os::windows::fs::symlink_file(target, path)
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn create_symlink(_target: &Path, _path: &Path) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "symlinks are not supported on this platform",
+    ))
+}
+
 struct AppliedPatch {
     original_contents: String,
     new_contents: String,
@@ -350,6 +456,20 @@ fn derive_new_contents_from_chunks(
         }
     };
 
+    let new_contents = apply_chunks_to_contents(&original_contents, path, chunks)?;
+    Ok(AppliedPatch {
+        original_contents,
+        new_contents,
+    })
+}
+
+/// Apply the chunks to `original_contents` in memory. `path` is only used to
+/// produce helpful error messages.
+fn apply_chunks_to_contents(
+    original_contents: &str,
+    path: &Path,
+    chunks: &[UpdateFileChunk],
+) -> std::result::Result<String, ApplyPatchError> {
     let mut original_lines: Vec<String> = original_contents.split('\n').map(String::from).collect();
 
     // Drop the trailing empty element that results from the final newline so
@@ -364,11 +484,7 @@ fn derive_new_contents_from_chunks(
     if !new_lines.last().is_some_and(String::is_empty) {
         new_lines.push(String::new());
     }
-    let new_contents = new_lines.join("\n");
-    Ok(AppliedPatch {
-        original_contents,
-        new_contents,
-    })
+    Ok(new_lines.join("\n"))
 }
 
 /// Compute a list of replacements needed to transform `original_lines` into the
@@ -630,6 +746,45 @@ mod tests {
         assert_eq!(contents, "foo\nbaz\n");
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_mode_and_symlink_hunks() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let script = dir.path().join("run.sh");
+        let tool = dir.path().join("tool.sh");
+        let link = dir.path().join("bin/tool");
+        fs::write(&tool, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(&tool, fs::Permissions::from_mode(0o755)).unwrap();
+        let patch = wrap_patch(&format!(
+            r#"*** Add File: {}
+*** Mode: executable
++echo hi
+*** Update File: {}
+*** Mode: regular
+*** Add Symlink: {} -> ../tool.sh"#,
+            script.display(),
+            tool.display(),
+            link.display()
+        ));
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        apply_patch(&patch, &mut stdout, &mut stderr).unwrap();
+
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_ne!(mode(&script) & 0o100, 0);
+        assert_eq!(mode(&tool), 0o644);
+        assert_eq!(fs::read_to_string(&tool).unwrap(), "#!/bin/sh\n");
+        assert_eq!(fs::read_link(&link).unwrap(), PathBuf::from("../tool.sh"));
+        assert_eq!(fs::read_to_string(&link).unwrap(), "#!/bin/sh\n");
+
+        // Re-adding the symlink replaces it.
+        let patch = wrap_patch(&format!("*** Add Symlink: {} -> ../run.sh", link.display()));
+        apply_patch(&patch, &mut stdout, &mut stderr).unwrap();
+        assert_eq!(fs::read_link(&link).unwrap(), PathBuf::from("../run.sh"));
+    }
+
     #[test]
     fn test_update_file_hunk_can_move_file() {
         let dir = tempdir().unwrap();
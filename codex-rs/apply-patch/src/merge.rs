@@ -0,0 +1,551 @@
+//! Three-way merge fallback for `Update File` hunks.
+//!
+//! When a hunk no longer applies because the file changed after the model
+//! read it, we can still recover the model's intent: apply the hunk to the
+//! snapshot the model actually saw (the *base*), then merge that result with
+//! the current file on disk. Non-overlapping edits merge cleanly; overlapping
+//! edits are reported as conflicts instead of being written to disk.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::ops::Range;
+use std::path::Path;
+use std::path::PathBuf;
+
+use similar::Algorithm;
+use similar::DiffTag;
+use similar::capture_diff_slices;
+
+use crate::ApplyPatchArgs;
+use crate::ApplyPatchError;
+use crate::IoError;
+use crate::apply_chunks_to_contents;
+use crate::invocation::MaybeApplyPatch;
+use crate::invocation::effective_cwd;
+use crate::invocation::maybe_parse_apply_patch;
+use crate::parser::FileMode;
+use crate::parser::Hunk;
+use crate::parser::UpdateFileChunk;
+
+const CONFLICT_MARKER_OURS: &str = "<<<<<<< current";
+const CONFLICT_MARKER_BASE: &str = "||||||| base";
+const CONFLICT_MARKER_SEPARATOR: &str = "=======";
+const CONFLICT_MARKER_THEIRS: &str = ">>>>>>> patch";
+
+/// Result of merging two descendants of a common base.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeResult {
+    /// Merged contents. Conflicting regions are rendered with diff3-style
+    /// conflict markers.
+    pub content: String,
+    /// Regions that could not be merged automatically, in file order.
+    pub conflicts: Vec<MergeConflict>,
+}
+
+impl MergeResult {
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// A region where the current file and the patched snapshot disagree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict {
+    /// 1-indexed line in the *current* file where the conflicting region starts.
+    pub current_start_line: usize,
+    pub base: Vec<String>,
+    pub current: Vec<String>,
+    pub patched: Vec<String>,
+}
+
+/// Conflicts found while merging a single file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileMergeConflict {
+    pub path: PathBuf,
+    pub conflicts: Vec<MergeConflict>,
+    /// Merged contents with conflict markers, for callers that want to show
+    /// the user or the model what a manual resolution would start from.
+    pub content_with_markers: String,
+}
+
+/// Outcome of [`maybe_rebase_apply_patch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatchRebase {
+    /// Every update merged cleanly. `patch` is an equivalent patch that
+    /// applies to the files as they exist on disk now.
+    Rebased {
+        patch: String,
+        merged_paths: Vec<PathBuf>,
+    },
+    /// At least one update overlaps with concurrent changes.
+    Conflicted(Vec<FileMergeConflict>),
+}
+
+/// Merge `current` and `patched`, both of which descend from `base`.
+pub fn three_way_merge(base: &str, current: &str, patched: &str) -> MergeResult {
+    let base_lines: Vec<&str> = base.split_inclusive('\n').collect();
+    let current_lines: Vec<&str> = current.split_inclusive('\n').collect();
+    let patched_lines: Vec<&str> = patched.split_inclusive('\n').collect();
+
+    let current_edits = edits(&base_lines, &current_lines);
+    let patched_edits = edits(&base_lines, &patched_lines);
+
+    let mut content = String::new();
+    let mut conflicts = Vec::new();
+    let mut base_pos = 0;
+    let mut i = 0;
+    let mut j = 0;
+
+    loop {
+        let region_start = match (current_edits.get(i), patched_edits.get(j)) {
+            (Some(c), Some(p)) => c.base.start.min(p.base.start),
+            (Some(c), None) => c.base.start,
+            (None, Some(p)) => p.base.start,
+            (None, None) => break,
+        };
+
+        // Grow the region until no edit from either side touches it.
+        let (i_start, j_start) = (i, j);
+        let mut region_end = region_start;
+        loop {
+            if let Some(edit) = current_edits.get(i)
+                && edit.base.start <= region_end
+            {
+                region_end = region_end.max(edit.base.end);
+                i += 1;
+                continue;
+            }
+            if let Some(edit) = patched_edits.get(j)
+                && edit.base.start <= region_end
+            {
+                region_end = region_end.max(edit.base.end);
+                j += 1;
+                continue;
+            }
+            break;
+        }
+
+        for line in &base_lines[base_pos..region_start] {
+            push_line(&mut content, line);
+        }
+
+        let region = region_start..region_end;
+        let current_range = side_range(&current_edits[i_start..i], &region);
+        let patched_range = side_range(&patched_edits[j_start..j], &region);
+        match (current_range, patched_range) {
+            (Some(range), None) => {
+                for line in &current_lines[range] {
+                    push_line(&mut content, line);
+                }
+            }
+            (None, Some(range)) => {
+                for line in &patched_lines[range] {
+                    push_line(&mut content, line);
+                }
+            }
+            (Some(current_range), Some(patched_range)) => {
+                let ours = &current_lines[current_range.clone()];
+                let theirs = &patched_lines[patched_range];
+                if ours == theirs {
+                    for line in ours {
+                        push_line(&mut content, line);
+                    }
+                } else {
+                    let base_region = &base_lines[region.clone()];
+                    push_marker(&mut content, CONFLICT_MARKER_OURS);
+                    for line in ours {
+                        push_line(&mut content, line);
+                    }
+                    push_marker(&mut content, CONFLICT_MARKER_BASE);
+                    for line in base_region {
+                        push_line(&mut content, line);
+                    }
+                    push_marker(&mut content, CONFLICT_MARKER_SEPARATOR);
+                    for line in theirs {
+                        push_line(&mut content, line);
+                    }
+                    push_marker(&mut content, CONFLICT_MARKER_THEIRS);
+                    conflicts.push(MergeConflict {
+                        current_start_line: current_range.start + 1,
+                        base: to_owned_lines(base_region),
+                        current: to_owned_lines(ours),
+                        patched: to_owned_lines(theirs),
+                    });
+                }
+            }
+            (None, None) => {}
+        }
+
+        base_pos = region_end;
+    }
+
+    for line in &base_lines[base_pos..] {
+        push_line(&mut content, line);
+    }
+
+    MergeResult { content, conflicts }
+}
+
+/// Retry an `apply_patch` invocation whose updates no longer apply by merging
+/// each failing update against the snapshot in `bases` (keyed by absolute
+/// path). Returns `None` when `argv` is not an `apply_patch` invocation.
+///
+/// Updates that still apply directly are kept as-is. If an update fails and
+/// there is no base snapshot for its file, the original error is returned.
+pub fn maybe_rebase_apply_patch(
+    argv: &[String],
+    cwd: &Path,
+    bases: &HashMap<PathBuf, String>,
+) -> Option<Result<PatchRebase, ApplyPatchError>> {
+    let MaybeApplyPatch::Body(ApplyPatchArgs { hunks, workdir, .. }) =
+        maybe_parse_apply_patch(argv)
+    else {
+        return None;
+    };
+    let cwd = effective_cwd(workdir.as_deref(), cwd);
+    Some(rebase_hunks(&hunks, &cwd, bases))
+}
+
+fn rebase_hunks(
+    hunks: &[Hunk],
+    cwd: &Path,
+    bases: &HashMap<PathBuf, String>,
+) -> Result<PatchRebase, ApplyPatchError> {
+    let mut patch = String::from("*** Begin Patch\n");
+    let mut merged_paths = Vec::new();
+    let mut file_conflicts = Vec::new();
+
+    for hunk in hunks {
+        let Hunk::UpdateFile {
+            move_path,
+            mode,
+            chunks,
+            ..
+        } = hunk
+        else {
+            write_hunk(&mut patch, hunk, cwd);
+            continue;
+        };
+        let path = hunk.resolve_path(cwd);
+        let current = std::fs::read_to_string(&path).map_err(|source| {
+            ApplyPatchError::IoError(IoError {
+                context: format!("Failed to read file to update {}", path.display()),
+                source,
+            })
+        })?;
+        let err = match apply_chunks_to_contents(&current, &path, chunks) {
+            Ok(_) => {
+                write_hunk(&mut patch, hunk, cwd);
+                continue;
+            }
+            Err(err) => err,
+        };
+        let Some(base) = bases.get(&path) else {
+            return Err(err);
+        };
+        let patched = apply_chunks_to_contents(base, &path, chunks)?;
+        let merged = three_way_merge(base, &current, &patched);
+        if merged.is_clean() {
+            let dest = move_path.as_ref().map(|move_path| cwd.join(move_path));
+            write_full_replacement(&mut patch, &path, dest.as_deref(), *mode, &current, &merged);
+            merged_paths.push(path);
+        } else {
+            file_conflicts.push(FileMergeConflict {
+                path,
+                conflicts: merged.conflicts,
+                content_with_markers: merged.content,
+            });
+        }
+    }
+
+    if !file_conflicts.is_empty() {
+        return Ok(PatchRebase::Conflicted(file_conflicts));
+    }
+    patch.push_str("*** End Patch\n");
+    Ok(PatchRebase::Rebased {
+        patch,
+        merged_paths,
+    })
+}
+
+/// Contiguous change against the base: `base` lines were replaced by `side`
+/// lines in one descendant.
+struct Edit {
+    base: Range<usize>,
+    side: Range<usize>,
+}
+
+fn edits(base: &[&str], side: &[&str]) -> Vec<Edit> {
+    let mut out: Vec<Edit> = Vec::new();
+    for op in capture_diff_slices(Algorithm::Myers, base, side) {
+        let (tag, base_range, side_range) = op.as_tag_tuple();
+        if tag == DiffTag::Equal {
+            continue;
+        }
+        if let Some(last) = out.last_mut()
+            && last.base.end == base_range.start
+            && last.side.end == side_range.start
+        {
+            last.base.end = base_range.end;
+            last.side.end = side_range.end;
+            continue;
+        }
+        out.push(Edit {
+            base: base_range,
+            side: side_range,
+        });
+    }
+    out
+}
+
+/// Map a base region onto one side, given that side's edits inside the
+/// region. Returns `None` when the side left the region untouched.
+fn side_range(edits: &[Edit], region: &Range<usize>) -> Option<Range<usize>> {
+    let first = edits.first()?;
+    let last = edits.last()?;
+    let start = first.side.start - (first.base.start - region.start);
+    let end = last.side.end + (region.end - last.base.end);
+    Some(start..end)
+}
+
+fn push_line(out: &mut String, line: &str) {
+    out.push_str(line);
+}
+
+fn push_marker(out: &mut String, marker: &str) {
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+    out.push_str(marker);
+    out.push('\n');
+}
+
+fn to_owned_lines(lines: &[&str]) -> Vec<String> {
+    lines
+        .iter()
+        .map(|line| line.trim_end_matches('\n').to_string())
+        .collect()
+}
+
+/// Re-serialize `hunk` with paths resolved against `cwd`, so the rebased
+/// patch does not depend on the original `workdir`.
+fn write_hunk(out: &mut String, hunk: &Hunk, cwd: &Path) {
+    let path = hunk.resolve_path(cwd);
+    match hunk {
+        Hunk::AddFile { contents, mode, .. } => {
+            let _ = writeln!(out, "*** Add File: {}", path.display());
+            write_mode(out, *mode);
+            for line in contents.lines() {
+                let _ = writeln!(out, "+{line}");
+            }
+        }
+        Hunk::DeleteFile { .. } => {
+            let _ = writeln!(out, "*** Delete File: {}", path.display());
+        }
+        Hunk::UpdateFile {
+            move_path,
+            mode,
+            chunks,
+            ..
+        } => {
+            let _ = writeln!(out, "*** Update File: {}", path.display());
+            if let Some(move_path) = move_path {
+                let _ = writeln!(out, "*** Move to: {}", cwd.join(move_path).display());
+            }
+            write_mode(out, *mode);
+            for chunk in chunks {
+                write_chunk(out, chunk);
+            }
+        }
+        Hunk::AddSymlink { target, .. } => {
+            let _ = writeln!(
+                out,
+                "*** Add Symlink: {} -> {}",
+                path.display(),
+                target.display()
+            );
+        }
+    }
+}
+
+fn write_mode(out: &mut String, mode: Option<FileMode>) {
+    if let Some(mode) = mode {
+        let _ = writeln!(out, "*** Mode: {mode}");
+    }
+}
+
+fn write_chunk(out: &mut String, chunk: &UpdateFileChunk) {
+    match &chunk.change_context {
+        Some(context) => {
+            let _ = writeln!(out, "@@ {context}");
+        }
+        None => out.push_str("@@\n"),
+    }
+    for line in &chunk.old_lines {
+        let _ = writeln!(out, "-{line}");
+    }
+    for line in &chunk.new_lines {
+        let _ = writeln!(out, "+{line}");
+    }
+    if chunk.is_end_of_file {
+        out.push_str("*** End of File\n");
+    }
+}
+
+/// Emit an update that replaces the whole of `current` with the merged
+/// contents.
+fn write_full_replacement(
+    out: &mut String,
+    path: &Path,
+    move_path: Option<&Path>,
+    mode: Option<FileMode>,
+    current: &str,
+    merged: &MergeResult,
+) {
+    let _ = writeln!(out, "*** Update File: {}", path.display());
+    if let Some(move_path) = move_path {
+        let _ = writeln!(out, "*** Move to: {}", move_path.display());
+    }
+    write_mode(out, mode);
+    out.push_str("@@\n");
+    for line in current.lines() {
+        let _ = writeln!(out, "-{line}");
+    }
+    for line in merged.content.lines() {
+        let _ = writeln!(out, "+{line}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    #[test]
+    fn merges_non_overlapping_edits() {
+        let base = "a\nb\nc\nd\ne\n";
+        let current = "A\nb\nc\nd\ne\n";
+        let patched = "a\nb\nc\nd\nE\n";
+
+        assert_eq!(
+            three_way_merge(base, current, patched),
+            MergeResult {
+                content: "A\nb\nc\nd\nE\n".to_string(),
+                conflicts: Vec::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn identical_edits_are_not_conflicts() {
+        let base = "a\nb\nc\n";
+        let both = "a\nB\nc\n";
+
+        assert_eq!(
+            three_way_merge(base, both, both),
+            MergeResult {
+                content: both.to_string(),
+                conflicts: Vec::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn overlapping_edits_produce_conflict_markers() {
+        let base = "a\nb\nc\n";
+        let current = "a\nmine\nc\n";
+        let patched = "a\ntheirs\nc\n";
+
+        assert_eq!(
+            three_way_merge(base, current, patched),
+            MergeResult {
+                content:
+                    "a\n<<<<<<< current\nmine\n||||||| base\nb\n=======\ntheirs\n>>>>>>> patch\nc\n"
+                        .to_string(),
+                conflicts: vec![MergeConflict {
+                    current_start_line: 2,
+                    base: vec!["b".to_string()],
+                    current: vec!["mine".to_string()],
+                    patched: vec!["theirs".to_string()],
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn rebase_merges_stale_update_against_snapshot() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("file.txt");
+        let base = "fn a() {}\n\nfn b() {}\n".to_string();
+        // The file changed after the model read it, so the patch context no
+        // longer matches.
+        std::fs::write(&path, "// header\nfn a() { todo!() }\n\nfn b() {}\n").unwrap();
+        let patch = "*** Begin Patch\n*** Update File: file.txt\n@@\n fn a() {}\n \n-fn b() {}\n+fn b() { 1 }\n*** End Patch";
+        let argv = vec!["apply_patch".to_string(), patch.to_string()];
+        let bases = HashMap::from([(path.clone(), base)]);
+
+        let rebased = maybe_rebase_apply_patch(&argv, dir.path(), &bases)
+            .expect("apply_patch invocation")
+            .expect("rebase succeeds");
+
+        let expected_patch = format!(
+            "*** Begin Patch\n*** Update File: {}\n@@\n-// header\n-fn a() {{ todo!() }}\n-\n-fn b() {{}}\n+// header\n+fn a() {{ todo!() }}\n+\n+fn b() {{ 1 }}\n*** End Patch\n",
+            path.display()
+        );
+        assert_eq!(
+            rebased,
+            PatchRebase::Rebased {
+                patch: expected_patch,
+                merged_paths: vec![path],
+            }
+        );
+    }
+
+    #[test]
+    fn rebase_reports_conflicts_without_snapshot_error() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("file.txt");
+        std::fs::write(&path, "one\nchanged\nthree\n").unwrap();
+        let patch =
+            "*** Begin Patch\n*** Update File: file.txt\n@@\n one\n-two\n+TWO\n*** End Patch";
+        let argv = vec!["apply_patch".to_string(), patch.to_string()];
+        let bases = HashMap::from([(path.clone(), "one\ntwo\nthree\n".to_string())]);
+
+        let rebased = maybe_rebase_apply_patch(&argv, dir.path(), &bases)
+            .expect("apply_patch invocation")
+            .expect("rebase succeeds");
+
+        assert_eq!(
+            rebased,
+            PatchRebase::Conflicted(vec![FileMergeConflict {
+                path,
+                conflicts: vec![MergeConflict {
+                    current_start_line: 2,
+                    base: vec!["two".to_string()],
+                    current: vec!["changed".to_string()],
+                    patched: vec!["TWO".to_string()],
+                }],
+                content_with_markers:
+                    "one\n<<<<<<< current\nchanged\n||||||| base\ntwo\n=======\nTWO\n>>>>>>> patch\nthree\n"
+                        .to_string(),
+            }])
+        );
+    }
+
+    #[test]
+    fn rebase_without_snapshot_returns_original_error() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("file.txt"), "x\n").unwrap();
+        let patch = "*** Begin Patch\n*** Update File: file.txt\n@@\n-y\n+z\n*** End Patch";
+        let argv = vec!["apply_patch".to_string(), patch.to_string()];
+
+        let result = maybe_rebase_apply_patch(&argv, dir.path(), &HashMap::new())
+            .expect("apply_patch invocation");
+
+        assert!(matches!(
+            result,
+            Err(ApplyPatchError::ComputeReplacements(_))
+        ));
+    }
+}
@@ -7,13 +7,15 @@
 //! begin_patch: "*** Begin Patch" LF
 //! end_patch: "*** End Patch" LF?
 //!
-//! hunk: add_hunk | delete_hunk | update_hunk
-//! add_hunk: "*** Add File: " filename LF add_line+
+//! hunk: add_hunk | delete_hunk | update_hunk | symlink_hunk
+//! add_hunk: "*** Add File: " filename LF file_mode? add_line+
 //! delete_hunk: "*** Delete File: " filename LF
-//! update_hunk: "*** Update File: " filename LF change_move? change?
+//! update_hunk: "*** Update File: " filename LF change_move? file_mode? change?
+//! symlink_hunk: "*** Add Symlink: " filename " -> " filename LF
 //! filename: /(.+)/
 //! add_line: "+" /(.+)/ LF -> line
 //!
+//! file_mode: "*** Mode: " ("executable" | "regular") LF
 //! change_move: "*** Move to: " filename LF
 //! change: (change_context | change_line)+ eof_line?
 //! change_context: ("@@" | "@@ " /(.+)/) LF
@@ -21,7 +23,9 @@
 //! eof_line: "*** End of File" LF
 //!
 //! The parser below is a little more lenient than the explicit spec and allows for
-//! leading/trailing whitespace around patch markers.
+//! leading/trailing whitespace around patch markers, and also accepts `+x`,
+//! `755` and `100755` (`-x`, `644`, `100644`) as file modes. An update hunk
+//! that only renames the file or changes its mode may have no `change`.
 use crate::ApplyPatchArgs;
 use std::path::Path;
 use std::path::PathBuf;
@@ -33,7 +37,10 @@ const END_PATCH_MARKER: &str = "*** End Patch";
 const ADD_FILE_MARKER: &str = "*** Add File: ";
 const DELETE_FILE_MARKER: &str = "*** Delete File: ";
 const UPDATE_FILE_MARKER: &str = "*** Update File: ";
+const ADD_SYMLINK_MARKER: &str = "*** Add Symlink: ";
+const SYMLINK_TARGET_SEPARATOR: &str = " -> ";
 const MOVE_TO_MARKER: &str = "*** Move to: ";
+const MODE_MARKER: &str = "*** Mode: ";
 const EOF_MARKER: &str = "*** End of File";
 const CHANGE_CONTEXT_MARKER: &str = "@@ ";
 const EMPTY_CHANGE_CONTEXT_MARKER: &str = "@@";
@@ -61,6 +68,7 @@ pub enum Hunk {
     AddFile {
         path: PathBuf,
         contents: String,
+        mode: Option<FileMode>,
     },
     DeleteFile {
         path: PathBuf,
@@ -68,11 +76,21 @@ pub enum Hunk {
     UpdateFile {
         path: PathBuf,
         move_path: Option<PathBuf>,
+        /// Mode to leave the (possibly moved) file with. `None` keeps the
+        /// current mode.
+        mode: Option<FileMode>,
 
         /// Chunks should be in order, i.e. the `change_context` of one chunk
         /// should occur later in the file than the previous chunk.
         chunks: Vec<UpdateFileChunk>,
     },
+    /// Creates a symlink at `path` pointing to `target`, replacing an existing
+    /// file or symlink. `target` is stored verbatim, so a relative target is
+    /// relative to the directory containing `path`.
+    AddSymlink {
+        path: PathBuf,
+        target: PathBuf,
+    },
 }
 
 impl Hunk {
@@ -81,6 +99,33 @@ impl Hunk {
             Hunk::AddFile { path, .. } => cwd.join(path),
             Hunk::DeleteFile { path } => cwd.join(path),
             Hunk::UpdateFile { path, .. } => cwd.join(path),
+            Hunk::AddSymlink { path, .. } => cwd.join(path),
+        }
+    }
+}
+
+/// The part of a Unix file mode that a patch can change.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum FileMode {
+    Regular,
+    Executable,
+}
+
+impl std::fmt::Display for FileMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Regular => f.write_str("regular"),
+            Self::Executable => f.write_str("executable"),
+        }
+    }
+}
+
+impl FileMode {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "executable" | "+x" | "755" | "0755" | "100755" => Some(Self::Executable),
+            "regular" | "-x" | "644" | "0644" | "100644" => Some(Self::Regular),
+            _ => None,
         }
     }
 }
@@ -251,8 +296,9 @@ fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), P
     if let Some(path) = first_line.strip_prefix(ADD_FILE_MARKER) {
         // Add File
         let mut contents = String::new();
-        let mut parsed_lines = 1;
-        for add_line in &lines[1..] {
+        let (mode, mut parsed_lines) = parse_mode_line(&lines[1..], line_number + 1)?;
+        parsed_lines += 1;
+        for add_line in &lines[parsed_lines..] {
             if let Some(line_to_add) = add_line.strip_prefix('+') {
                 contents.push_str(line_to_add);
                 contents.push('\n');
@@ -265,9 +311,34 @@ fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), P
             AddFile {
                 path: PathBuf::from(path),
                 contents,
+                mode,
             },
             parsed_lines,
         ));
+    } else if let Some(rest) = first_line.strip_prefix(ADD_SYMLINK_MARKER) {
+        // Add Symlink
+        let Some((path, target)) = rest.split_once(SYMLINK_TARGET_SEPARATOR) else {
+            return Err(InvalidHunkError {
+                message: format!(
+                    "Add symlink hunk '{first_line}' must have the form '*** Add Symlink: {{path}} -> {{target}}'"
+                ),
+                line_number,
+            });
+        };
+        let (path, target) = (path.trim(), target.trim());
+        if path.is_empty() || target.is_empty() {
+            return Err(InvalidHunkError {
+                message: format!("Add symlink hunk '{first_line}' is missing a path or target"),
+                line_number,
+            });
+        }
+        return Ok((
+            AddSymlink {
+                path: PathBuf::from(path),
+                target: PathBuf::from(target),
+            },
+            1,
+        ));
     } else if let Some(path) = first_line.strip_prefix(DELETE_FILE_MARKER) {
         // Delete File
         return Ok((
@@ -291,6 +362,11 @@ fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), P
             parsed_lines += 1;
         }
 
+        // Optional: mode line
+        let (mode, mode_lines) = parse_mode_line(remaining_lines, line_number + parsed_lines)?;
+        remaining_lines = &remaining_lines[mode_lines..];
+        parsed_lines += mode_lines;
+
         let mut chunks = Vec::new();
         // NOTE: we need to know to stop once we reach the next special marker header.
         while !remaining_lines.is_empty() {
@@ -315,7 +391,7 @@ fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), P
             remaining_lines = &remaining_lines[chunk_lines..]
         }
 
-        if chunks.is_empty() {
+        if chunks.is_empty() && mode.is_none() && move_path.is_none() {
             return Err(InvalidHunkError {
                 message: format!("Update file hunk for path '{path}' is empty"),
                 line_number,
@@ -326,6 +402,7 @@ fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), P
             UpdateFile {
                 path: PathBuf::from(path),
                 move_path: move_path.map(PathBuf::from),
+                mode,
                 chunks,
             },
             parsed_lines,
@@ -334,12 +411,36 @@ fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), P
 
     Err(InvalidHunkError {
         message: format!(
-            "'{first_line}' is not a valid hunk header. Valid hunk headers: '*** Add File: {{path}}', '*** Delete File: {{path}}', '*** Update File: {{path}}'"
+            "'{first_line}' is not a valid hunk header. Valid hunk headers: '*** Add File: {{path}}', '*** Delete File: {{path}}', '*** Update File: {{path}}', '*** Add Symlink: {{path}} -> {{target}}'"
         ),
         line_number,
     })
 }
 
+/// Parses an optional `*** Mode: ` line at the start of `lines`, returning
+/// the mode and the number of lines consumed.
+fn parse_mode_line(
+    lines: &[&str],
+    line_number: usize,
+) -> Result<(Option<FileMode>, usize), ParseError> {
+    let Some(value) = lines
+        .first()
+        .and_then(|line| line.trim().strip_prefix(MODE_MARKER))
+    else {
+        return Ok((None, 0));
+    };
+    match FileMode::parse(value) {
+        Some(mode) => Ok((Some(mode), 1)),
+        None => Err(InvalidHunkError {
+            message: format!(
+                "Unsupported file mode '{}', expected 'executable' or 'regular'",
+                value.trim()
+            ),
+            line_number,
+        }),
+    }
+}
+
 fn parse_update_file_chunk(
     lines: &[&str],
     line_number: usize,
@@ -463,7 +564,8 @@ fn test_parse_patch() {
         .hunks,
         vec![AddFile {
             path: PathBuf::from("foo"),
-            contents: "hi\n".to_string()
+            contents: "hi\n".to_string(),
+            mode: None,
         }]
     );
     assert_eq!(
@@ -508,7 +610,8 @@ fn test_parse_patch() {
         vec![
             AddFile {
                 path: PathBuf::from("path/add.py"),
-                contents: "abc\ndef\n".to_string()
+                contents: "abc\ndef\n".to_string(),
+                mode: None,
             },
             DeleteFile {
                 path: PathBuf::from("path/delete.py")
@@ -516,6 +619,7 @@ fn test_parse_patch() {
             UpdateFile {
                 path: PathBuf::from("path/update.py"),
                 move_path: Some(PathBuf::from("path/update2.py")),
+                mode: None,
                 chunks: vec![UpdateFileChunk {
                     change_context: Some("def f():".to_string()),
                     old_lines: vec!["    pass".to_string()],
@@ -543,6 +647,7 @@ fn test_parse_patch() {
             UpdateFile {
                 path: PathBuf::from("file.py"),
                 move_path: None,
+                mode: None,
                 chunks: vec![UpdateFileChunk {
                     change_context: None,
                     old_lines: vec![],
@@ -552,7 +657,8 @@ fn test_parse_patch() {
             },
             AddFile {
                 path: PathBuf::from("other.py"),
-                contents: "content\n".to_string()
+                contents: "content\n".to_string(),
+                mode: None,
             }
         ]
     );
@@ -573,6 +679,7 @@ fn test_parse_patch() {
         vec![UpdateFile {
             path: PathBuf::from("file2.py"),
             move_path: None,
+            mode: None,
             chunks: vec![UpdateFileChunk {
                 change_context: None,
                 old_lines: vec!["import foo".to_string()],
@@ -593,6 +700,7 @@ fn test_parse_patch_lenient() {
     let expected_patch = vec![UpdateFile {
         path: PathBuf::from("file2.py"),
         move_path: None,
+        mode: None,
         chunks: vec![UpdateFileChunk {
             change_context: None,
             old_lines: vec!["import foo".to_string()],
@@ -675,13 +783,81 @@ fn test_parse_one_hunk() {
         parse_one_hunk(&["bad"], 234),
         Err(InvalidHunkError {
             message: "'bad' is not a valid hunk header. \
-            Valid hunk headers: '*** Add File: {path}', '*** Delete File: {path}', '*** Update File: {path}'".to_string(),
+            Valid hunk headers: '*** Add File: {path}', '*** Delete File: {path}', '*** Update File: {path}', \
+            '*** Add Symlink: {path} -> {target}'".to_string(),
             line_number: 234
         })
     );
     // Other edge cases are already covered by tests above/below.
 }
 
+#[test]
+fn test_parse_modes_and_symlinks() {
+    assert_eq!(
+        parse_patch_text(
+            "*** Begin Patch\n\
+             *** Add File: run.sh\n\
+             *** Mode: executable\n\
+             +echo hi\n\
+             *** Update File: build.sh\n\
+             *** Mode: -x\n\
+             *** Add Symlink: latest -> releases/v2\n\
+             *** End Patch",
+            ParseMode::Strict
+        )
+        .map(|args| args.hunks),
+        Ok(vec![
+            AddFile {
+                path: PathBuf::from("run.sh"),
+                contents: "echo hi\n".to_string(),
+                mode: Some(FileMode::Executable),
+            },
+            UpdateFile {
+                path: PathBuf::from("build.sh"),
+                move_path: None,
+                mode: Some(FileMode::Regular),
+                chunks: Vec::new(),
+            },
+            AddSymlink {
+                path: PathBuf::from("latest"),
+                target: PathBuf::from("releases/v2"),
+            },
+        ])
+    );
+    assert_eq!(
+        parse_one_hunk(&["*** Update File: a.sh", "*** Mode: 777"], 5),
+        Err(InvalidHunkError {
+            message: "Unsupported file mode '777', expected 'executable' or 'regular'".to_string(),
+            line_number: 6
+        })
+    );
+    assert_eq!(
+        parse_one_hunk(&["*** Add Symlink: latest"], 7),
+        Err(InvalidHunkError {
+            message: "Add symlink hunk '*** Add Symlink: latest' must have the form \
+                      '*** Add Symlink: {path} -> {target}'"
+                .to_string(),
+            line_number: 7
+        })
+    );
+}
+
+#[test]
+fn test_parse_rename_without_changes() {
+    assert_eq!(
+        parse_one_hunk(&["*** Update File: old.rs", "*** Move to: new.rs"], 2),
+        Ok((
+            UpdateFile {
+                path: PathBuf::from("old.rs"),
+                move_path: Some(PathBuf::from("new.rs")),
+                mode: None,
+                chunks: Vec::new(),
+            },
+            2
+        ))
+    );
+}
+
 #[test]
 fn test_update_file_chunk() {
     assert_eq!(
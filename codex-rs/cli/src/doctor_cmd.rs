@@ -0,0 +1,110 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use codex_common::CliConfigOverrides;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::doctor::DoctorCheck;
+use codex_core::doctor::DoctorCheckKind;
+use codex_core::doctor::DoctorStatus;
+use codex_core::doctor::run_doctor;
+
+#[derive(Debug, clap::Parser)]
+pub struct DoctorCommand {
+    #[clap(skip)]
+    pub config_overrides: CliConfigOverrides,
+
+    /// Print the report as JSON.
+    #[arg(long, default_value_t = false)]
+    pub json: bool,
+}
+
+impl DoctorCommand {
+    /// Prints the report and exits with status 1 when a check failed.
+    pub async fn run(self, codex_linux_sandbox_exe: Option<PathBuf>) -> Result<()> {
+        let cli_overrides = self
+            .config_overrides
+            .parse_overrides()
+            .map_err(anyhow::Error::msg)?;
+        // An invalid config.toml is one of the findings, so fall back to the
+        // defaults instead of failing.
+        let config = match Config::load_with_cli_overrides_and_harness_overrides(
+            cli_overrides.clone(),
+            ConfigOverrides {
+                codex_linux_sandbox_exe: codex_linux_sandbox_exe.clone(),
+                ..Default::default()
+            },
+        )
+        .await
+        {
+            Ok(config) => config,
+            Err(_) => {
+                let mut config = Config::load_default_with_cli_overrides(cli_overrides)?;
+                config.codex_linux_sandbox_exe = codex_linux_sandbox_exe;
+                config
+            }
+        };
+
+        let report = run_doctor(&config).await;
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            for check in &report.checks {
+                println!("{}", format_check(check));
+            }
+        }
+        if report.status() == DoctorStatus::Error {
+            std::process::exit(1);
+        }
+        Ok(())
+    }
+}
+
+fn format_check(check: &DoctorCheck) -> String {
+    let marker = match check.status {
+        DoctorStatus::Ok => "ok  ",
+        DoctorStatus::Skipped => "skip",
+        DoctorStatus::Warning => "warn",
+        DoctorStatus::Error => "FAIL",
+    };
+    let kind = match check.kind {
+        DoctorCheckKind::Config => "config",
+        DoctorCheckKind::CodexHome => "CODEX_HOME",
+        DoctorCheckKind::Auth => "auth",
+        DoctorCheckKind::Provider => "provider",
+        DoctorCheckKind::Sandbox => "sandbox",
+        DoctorCheckKind::McpServer => "MCP server",
+        DoctorCheckKind::Git => "git",
+    };
+    let mut line = if check.subject.is_empty() {
+        format!("[{marker}] {kind}: {}", check.summary)
+    } else {
+        format!("[{marker}] {kind} ({}): {}", check.subject, check.summary)
+    };
+    if let Some(fix) = &check.fix {
+        line.push_str(&format!("\n       fix: {fix}"));
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn format_check_shows_subject_and_fix() {
+        let check = DoctorCheck {
+            kind: DoctorCheckKind::McpServer,
+            subject: "docs".to_string(),
+            status: DoctorStatus::Error,
+            summary: "The server failed to start: connection refused".to_string(),
+            fix: Some("Check `[mcp_servers.docs]` in config.toml, or disable the server with `enabled = false`.".to_string()),
+        };
+
+        assert_eq!(
+            format_check(&check),
+            "[FAIL] MCP server (docs): The server failed to start: connection refused\n       fix: Check `[mcp_servers.docs]` in config.toml, or disable the server with `enabled = false`."
+        );
+    }
+}
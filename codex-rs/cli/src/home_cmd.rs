@@ -0,0 +1,173 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use codex_core::config::find_codex_home;
+use codex_core::home_archive::HomeImportOptions;
+use codex_core::home_archive::HomeImportReport;
+use codex_core::home_archive::HomeSection;
+use codex_core::home_archive::import_home;
+use codex_core::home_archive::read_home_archive;
+use codex_core::home_archive::write_home_archive;
+
+#[derive(Debug, clap::Parser)]
+pub struct HomeCommand {
+    #[command(subcommand)]
+    sub: HomeSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum HomeSubcommand {
+    /// Write config, trusted projects, instructions, prompts, skills and
+    /// templates to a portable archive. Login state and secret config values
+    /// are left out.
+    Export(HomeExportCommand),
+
+    /// Merge an archive into this CODEX_HOME, keeping local files and config
+    /// values that differ unless --overwrite is given.
+    Import(HomeImportCommand),
+}
+
+#[derive(Debug, clap::Parser)]
+struct HomeExportCommand {
+    /// Archive file to write.
+    #[arg(value_name = "ARCHIVE")]
+    archive: PathBuf,
+
+    /// Sections to export instead of the defaults.
+    #[arg(
+        long = "only",
+        value_enum,
+        value_delimiter = ',',
+        value_name = "SECTION"
+    )]
+    only: Vec<Section>,
+
+    /// Also export recorded sessions, which may contain secrets from tool
+    /// output.
+    #[arg(long, default_value_t = false)]
+    sessions: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+struct HomeImportCommand {
+    /// Archive written by `codex home export`.
+    #[arg(value_name = "ARCHIVE")]
+    archive: PathBuf,
+
+    /// Replace local files and config values that differ from the archive.
+    #[arg(long, default_value_t = false)]
+    overwrite: bool,
+
+    /// Show what would change without writing anything.
+    #[arg(long, default_value_t = false)]
+    dry_run: bool,
+
+    /// Print the report as JSON.
+    #[arg(long, default_value_t = false)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum Section {
+    Config,
+    TrustedProjects,
+    Instructions,
+    Prompts,
+    Skills,
+    Templates,
+    Sessions,
+}
+
+impl From<Section> for HomeSection {
+    fn from(section: Section) -> Self {
+        match section {
+            Section::Config => HomeSection::Config,
+            Section::TrustedProjects => HomeSection::TrustedProjects,
+            Section::Instructions => HomeSection::Instructions,
+            Section::Prompts => HomeSection::Prompts,
+            Section::Skills => HomeSection::Skills,
+            Section::Templates => HomeSection::Templates,
+            Section::Sessions => HomeSection::Sessions,
+        }
+    }
+}
+
+impl HomeCommand {
+    pub fn run(self) -> Result<()> {
+        let codex_home = find_codex_home().context("failed to locate CODEX_HOME")?;
+        match self.sub {
+            HomeSubcommand::Export(cmd) => {
+                let mut sections: Vec<HomeSection> = if cmd.only.is_empty() {
+                    HomeSection::DEFAULT.to_vec()
+                } else {
+                    cmd.only.into_iter().map(HomeSection::from).collect()
+                };
+                if cmd.sessions {
+                    sections.push(HomeSection::Sessions);
+                }
+                let archive = write_home_archive(&codex_home, &sections, &cmd.archive)
+                    .with_context(|| format!("failed to export to {}", cmd.archive.display()))?;
+                println!(
+                    "Exported {} files to {}.",
+                    archive.files.len(),
+                    cmd.archive.display()
+                );
+                if !archive.redacted_keys.is_empty() {
+                    println!("Left out secret config values, set them again after importing:");
+                    for key in &archive.redacted_keys {
+                        println!("  {key}");
+                    }
+                }
+            }
+            HomeSubcommand::Import(cmd) => {
+                let archive = read_home_archive(&cmd.archive)
+                    .with_context(|| format!("failed to read {}", cmd.archive.display()))?;
+                let report = import_home(
+                    &codex_home,
+                    &archive,
+                    HomeImportOptions {
+                        overwrite: cmd.overwrite,
+                        dry_run: cmd.dry_run,
+                    },
+                )
+                .with_context(|| format!("failed to import {}", cmd.archive.display()))?;
+                if cmd.json {
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                } else {
+                    print_report(&report, cmd.dry_run);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn print_report(report: &HomeImportReport, dry_run: bool) {
+    let (added, overwritten) = if dry_run {
+        ("Would add", "Would overwrite")
+    } else {
+        ("Added", "Overwrote")
+    };
+    for (label, entries) in [
+        (added, &report.added),
+        (overwritten, &report.overwritten),
+        (
+            "Kept local version (differs from archive; use --overwrite to replace)",
+            &report.conflicts,
+        ),
+        (
+            "Not set locally (secret left out of the archive)",
+            &report.missing_secrets,
+        ),
+    ] {
+        if entries.is_empty() {
+            continue;
+        }
+        println!("{label}:");
+        for entry in entries {
+            println!("  {entry}");
+        }
+    }
+    println!("{} entries already up to date.", report.unchanged);
+}
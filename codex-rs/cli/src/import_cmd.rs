@@ -0,0 +1,91 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use codex_common::CliConfigOverrides;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::rollout::import::TranscriptFormat;
+use codex_core::rollout::import::convert_transcript;
+use codex_core::rollout::import::import_transcript;
+
+#[derive(Debug, clap::Parser)]
+pub struct ImportCommand {
+    #[clap(skip)]
+    pub config_overrides: CliConfigOverrides,
+
+    /// Transcript to import: a Claude Code session log (`.jsonl`) or an Aider
+    /// chat history (`.aider.chat.history.md`).
+    #[arg(value_name = "PATH")]
+    pub path: PathBuf,
+
+    /// Format of the transcript. Detected from the file when omitted.
+    #[arg(long, value_enum)]
+    pub format: Option<ImportFormat>,
+
+    /// Working directory to record for the session. Defaults to the one in
+    /// the transcript when it still exists, otherwise the current directory.
+    #[arg(long = "cd", short = 'C', value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum ImportFormat {
+    ClaudeCode,
+    Aider,
+}
+
+impl From<ImportFormat> for TranscriptFormat {
+    fn from(format: ImportFormat) -> Self {
+        match format {
+            ImportFormat::ClaudeCode => TranscriptFormat::ClaudeCode,
+            ImportFormat::Aider => TranscriptFormat::Aider,
+        }
+    }
+}
+
+impl ImportCommand {
+    pub async fn run(self) -> Result<()> {
+        let contents = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("failed to read {}", self.path.display()))?;
+        let format = match self.format {
+            Some(format) => format.into(),
+            None => TranscriptFormat::detect(&self.path, &contents).with_context(|| {
+                format!(
+                    "could not detect the transcript format of {}; pass --format",
+                    self.path.display()
+                )
+            })?,
+        };
+        let transcript = convert_transcript(format, &contents);
+
+        let cwd = self
+            .cwd
+            .or_else(|| transcript.cwd.clone().filter(|cwd| cwd.is_dir()));
+        let cli_overrides = self
+            .config_overrides
+            .parse_overrides()
+            .map_err(anyhow::Error::msg)?;
+        let config = Config::load_with_cli_overrides_and_harness_overrides(
+            cli_overrides,
+            ConfigOverrides {
+                cwd,
+                ..Default::default()
+            },
+        )
+        .await
+        .context("failed to load configuration")?;
+
+        let messages = transcript.messages;
+        let tool_calls = transcript.tool_calls;
+        let (thread_id, rollout_path) = import_transcript(&config, transcript)
+            .await
+            .with_context(|| format!("failed to import {}", self.path.display()))?;
+        println!(
+            "Imported {messages} messages and {tool_calls} tool calls into {}",
+            rollout_path.display()
+        );
+        println!("Resume it with: codex resume {thread_id}");
+        Ok(())
+    }
+}
@@ -21,6 +21,7 @@ use codex_exec::Cli as ExecCli;
 use codex_exec::Command as ExecCommand;
 use codex_exec::ReviewArgs;
 use codex_execpolicy::ExecPolicyCheckCommand;
+use codex_execpolicy::ExecPolicyTestCommand;
 use codex_responses_api_proxy::Args as ResponsesApiProxyArgs;
 use codex_tui::AppExitInfo;
 use codex_tui::Cli as TuiCli;
@@ -31,10 +32,16 @@ use std::io::IsTerminal;
 use std::path::PathBuf;
 use supports_color::Stream;
 
+mod doctor_cmd;
+mod home_cmd;
+mod import_cmd;
 mod mcp_cmd;
 #[cfg(not(windows))]
 mod wsl_paths;
 
+use crate::doctor_cmd::DoctorCommand;
+use crate::home_cmd::HomeCommand;
+use crate::import_cmd::ImportCommand;
 use crate::mcp_cmd::McpCli;
 
 use codex_core::config::Config;
@@ -64,6 +71,11 @@ struct MultitoolCli {
     #[clap(flatten)]
     pub feature_toggles: FeatureToggles,
 
+    /// Keep the session in memory only: no rollout, message history or
+    /// artifacts are written to disk. Equivalent to `-c incognito=true`.
+    #[arg(long = "incognito", default_value_t = false, global = true)]
+    pub incognito: bool,
+
     #[clap(flatten)]
     interactive: TuiCli,
 
@@ -116,6 +128,15 @@ enum Subcommand {
     /// Fork a previous interactive session (picker by default; use --last to fork the most recent).
     Fork(ForkCommand),
 
+    /// Import a Claude Code or Aider transcript as a Codex session that can be resumed.
+    Import(ImportCommand),
+
+    /// Check auth, provider, sandbox, MCP servers, git and config, and suggest fixes for problems.
+    Doctor(DoctorCommand),
+
+    /// Export CODEX_HOME to a portable archive, or import one when moving to another machine.
+    Home(HomeCommand),
+
     /// [EXPERIMENTAL] Browse tasks from Codex Cloud and apply changes locally.
     #[clap(name = "cloud", alias = "cloud-tasks")]
     Cloud(CloudTasksCli),
@@ -208,6 +229,10 @@ enum ExecpolicySubcommand {
     /// Check execpolicy files against a command.
     #[clap(name = "check")]
     Check(ExecPolicyCheckCommand),
+
+    /// Run a directory of assertion files against execpolicy files.
+    #[clap(name = "test")]
+    Test(ExecPolicyTestCommand),
 }
 
 #[derive(Debug, Parser)]
@@ -473,6 +498,7 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
     let MultitoolCli {
         config_overrides: mut root_config_overrides,
         feature_toggles,
+        incognito,
         mut interactive,
         subcommand,
     } = MultitoolCli::parse();
@@ -480,6 +506,11 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
     // Fold --enable/--disable into config overrides so they flow to all subcommands.
     let toggle_overrides = feature_toggles.to_overrides()?;
     root_config_overrides.raw_overrides.extend(toggle_overrides);
+    if incognito {
+        root_config_overrides
+            .raw_overrides
+            .push("incognito=true".to_string());
+    }
 
     match subcommand {
         None => {
@@ -653,7 +684,25 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
         },
         Some(Subcommand::Execpolicy(ExecpolicyCommand { sub })) => match sub {
             ExecpolicySubcommand::Check(cmd) => run_execpolicycheck(cmd)?,
+            ExecpolicySubcommand::Test(cmd) => cmd.run()?,
         },
+        Some(Subcommand::Import(mut import_cli)) => {
+            prepend_config_flags(
+                &mut import_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            import_cli.run().await?;
+        }
+        Some(Subcommand::Doctor(mut doctor_cli)) => {
+            prepend_config_flags(
+                &mut doctor_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            doctor_cli.run(codex_linux_sandbox_exe).await?;
+        }
+        Some(Subcommand::Home(home_cli)) => {
+            home_cli.run()?;
+        }
         Some(Subcommand::Apply(mut apply_cli)) => {
             prepend_config_flags(
                 &mut apply_cli.config_overrides,
@@ -889,6 +938,7 @@ mod tests {
             config_overrides: root_overrides,
             subcommand,
             feature_toggles: _,
+            incognito: _,
         } = cli;
 
         let Subcommand::Resume(ResumeCommand {
@@ -918,6 +968,7 @@ mod tests {
             config_overrides: root_overrides,
             subcommand,
             feature_toggles: _,
+            incognito: _,
         } = cli;
 
         let Subcommand::Fork(ForkCommand {
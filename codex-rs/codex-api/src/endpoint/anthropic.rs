@@ -0,0 +1,91 @@
+use crate::AnthropicRequest;
+use crate::auth::AuthProvider;
+use crate::common::Prompt as ApiPrompt;
+use crate::common::ResponseStream;
+use crate::endpoint::streaming::StreamingClient;
+use crate::error::ApiError;
+use crate::provider::Provider;
+use crate::response_cache::ResponseCache;
+use crate::sse::anthropic::spawn_anthropic_stream;
+use crate::telemetry::SseTelemetry;
+use codex_client::HttpTransport;
+use codex_client::RequestCompression;
+use codex_client::RequestTelemetry;
+use codex_protocol::protocol::SessionSource;
+use http::HeaderMap;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// Streams turns from a provider speaking Anthropic's Messages API.
+pub struct AnthropicClient<T: HttpTransport, A: AuthProvider> {
+    streaming: StreamingClient<T, A>,
+}
+
+impl<T: HttpTransport, A: AuthProvider> AnthropicClient<T, A> {
+    pub fn new(transport: T, provider: Provider, auth: A) -> Self {
+        Self {
+            streaming: StreamingClient::new(transport, provider, auth),
+        }
+    }
+
+    pub fn with_telemetry(
+        self,
+        request: Option<Arc<dyn RequestTelemetry>>,
+        sse: Option<Arc<dyn SseTelemetry>>,
+    ) -> Self {
+        Self {
+            streaming: self.streaming.with_telemetry(request, sse),
+        }
+    }
+
+    /// Serve identical requests from `cache` instead of the provider.
+    pub fn with_response_cache(self, cache: Option<Arc<ResponseCache>>) -> Self {
+        Self {
+            streaming: self.streaming.with_response_cache(cache),
+        }
+    }
+
+    pub async fn stream_request(
+        &self,
+        request: AnthropicRequest,
+    ) -> Result<ResponseStream, ApiError> {
+        self.stream(request.body, request.headers).await
+    }
+
+    /// `prompt.tools` must already be in the Messages API shape.
+    pub async fn stream_prompt(
+        &self,
+        model: &str,
+        prompt: &ApiPrompt,
+        conversation_id: Option<String>,
+        session_source: Option<SessionSource>,
+    ) -> Result<ResponseStream, ApiError> {
+        use crate::requests::AnthropicRequestBuilder;
+
+        let request =
+            AnthropicRequestBuilder::new(model, &prompt.instructions, &prompt.input, &prompt.tools)
+                .max_tokens(prompt.max_output_tokens)
+                .conversation_id(conversation_id)
+                .session_source(session_source)
+                .build(self.streaming.provider())?;
+
+        self.stream_request(request).await
+    }
+
+    pub async fn stream(
+        &self,
+        body: Value,
+        extra_headers: HeaderMap,
+    ) -> Result<ResponseStream, ApiError> {
+        self.streaming
+            .stream(
+                "messages",
+                body,
+                extra_headers,
+                RequestCompression::None,
+                spawn_anthropic_stream,
+                None,
+            )
+            .await
+    }
+}
@@ -7,6 +7,7 @@ use crate::endpoint::streaming::StreamingClient;
 use crate::error::ApiError;
 use crate::provider::Provider;
 use crate::provider::WireApi;
+use crate::response_cache::ResponseCache;
 use crate::sse::chat::spawn_chat_stream;
 use crate::telemetry::SseTelemetry;
 use codex_client::HttpTransport;
@@ -46,6 +47,13 @@ impl<T: HttpTransport, A: AuthProvider> ChatClient<T, A> {
         }
     }
 
+    /// Serve identical requests from `cache` instead of the provider.
+    pub fn with_response_cache(self, cache: Option<Arc<ResponseCache>>) -> Self {
+        Self {
+            streaming: self.streaming.with_response_cache(cache),
+        }
+    }
+
     pub async fn stream_request(&self, request: ChatRequest) -> Result<ResponseStream, ApiError> {
         self.stream(request.body, request.headers).await
     }
@@ -61,6 +69,7 @@ impl<T: HttpTransport, A: AuthProvider> ChatClient<T, A> {
 
         let request =
             ChatRequestBuilder::new(model, &prompt.instructions, &prompt.input, &prompt.tools)
+                .max_tokens(prompt.max_output_tokens)
                 .conversation_id(conversation_id)
                 .session_source(session_source)
                 .build(self.streaming.provider())?;
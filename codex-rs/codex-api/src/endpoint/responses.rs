@@ -10,6 +10,7 @@ use crate::provider::WireApi;
 use crate::requests::ResponsesRequest;
 use crate::requests::ResponsesRequestBuilder;
 use crate::requests::responses::Compression;
+use crate::response_cache::ResponseCache;
 use crate::sse::spawn_response_stream;
 use crate::telemetry::SseTelemetry;
 use codex_client::HttpTransport;
@@ -57,6 +58,13 @@ impl<T: HttpTransport, A: AuthProvider> ResponsesClient<T, A> {
         }
     }
 
+    /// Serve identical requests from `cache` instead of the provider.
+    pub fn with_response_cache(self, cache: Option<Arc<ResponseCache>>) -> Self {
+        Self {
+            streaming: self.streaming.with_response_cache(cache),
+        }
+    }
+
     pub async fn stream_request(
         &self,
         request: ResponsesRequest,
@@ -98,6 +106,7 @@ impl<T: HttpTransport, A: AuthProvider> ResponsesClient<T, A> {
             .include(include)
             .prompt_cache_key(prompt_cache_key)
             .text(text)
+            .max_output_tokens(prompt.max_output_tokens)
             .conversation(conversation_id)
             .session_source(session_source)
             .store_override(store_override)
@@ -112,6 +121,7 @@ impl<T: HttpTransport, A: AuthProvider> ResponsesClient<T, A> {
         match self.streaming.provider().wire {
             WireApi::Responses | WireApi::Compact => "responses",
             WireApi::Chat => "chat/completions",
+            WireApi::AnthropicMessages => "messages",
         }
     }
 
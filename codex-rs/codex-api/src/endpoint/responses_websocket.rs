@@ -4,6 +4,7 @@ use crate::common::ResponseStream;
 use crate::common::ResponsesWsRequest;
 use crate::error::ApiError;
 use crate::provider::Provider;
+use crate::rate_limits::ProviderRateLimiter;
 use crate::sse::responses::ResponsesStreamEvent;
 use crate::sse::responses::process_responses_event;
 use codex_client::TransportError;
@@ -38,14 +39,21 @@ pub struct ResponsesWebsocketConnection {
     // TODO (pakrym): is this the right place for timeout?
     idle_timeout: Duration,
     server_reasoning_included: bool,
+    rate_limiter: Arc<ProviderRateLimiter>,
 }
 
 impl ResponsesWebsocketConnection {
-    fn new(stream: WsStream, idle_timeout: Duration, server_reasoning_included: bool) -> Self {
+    fn new(
+        stream: WsStream,
+        idle_timeout: Duration,
+        server_reasoning_included: bool,
+        rate_limiter: Arc<ProviderRateLimiter>,
+    ) -> Self {
         Self {
             stream: Arc::new(Mutex::new(Some(stream))),
             idle_timeout,
             server_reasoning_included,
+            rate_limiter,
         }
     }
 
@@ -57,6 +65,8 @@ impl ResponsesWebsocketConnection {
         &self,
         request: ResponsesWsRequest,
     ) -> Result<ResponseStream, ApiError> {
+        // Requests on the socket draw from the same budget as HTTP requests.
+        self.rate_limiter.acquire().await?;
         let (tx_event, rx_event) =
             mpsc::channel::<std::result::Result<ResponseEvent, ApiError>>(1600);
         let stream = Arc::clone(&self.stream);
@@ -124,12 +134,15 @@ impl<A: AuthProvider> ResponsesWebsocketClient<A> {
         headers.extend(extra_headers);
         apply_auth_headers(&mut headers, &self.auth);
 
+        let rate_limiter = ProviderRateLimiter::for_provider(&self.provider);
+        rate_limiter.acquire().await?;
         let (stream, server_reasoning_included) =
-            connect_websocket(ws_url, headers, turn_state).await?;
+            connect_websocket(ws_url, headers, turn_state, &rate_limiter).await?;
         Ok(ResponsesWebsocketConnection::new(
             stream,
             self.provider.stream_idle_timeout,
             server_reasoning_included,
+            rate_limiter,
         ))
     }
 }
@@ -152,6 +165,7 @@ async fn connect_websocket(
     url: Url,
     headers: HeaderMap,
     turn_state: Option<Arc<OnceLock<String>>>,
+    rate_limiter: &ProviderRateLimiter,
 ) -> Result<(WsStream, bool), ApiError> {
     info!("connecting to websocket: {url}");
 
@@ -169,10 +183,16 @@ async fn connect_websocket(
                 "successfully connected to websocket: {url}, headers: {:?}",
                 response.headers()
             );
+            rate_limiter.observe_headers(response.headers());
             (stream, response)
         }
         Err(err) => {
             error!("failed to connect to websocket: {err}, url: {url}");
+            if let WsError::Http(response) = &err
+                && response.status() == http::StatusCode::TOO_MANY_REQUESTS
+            {
+                rate_limiter.observe_throttled(response.headers());
+            }
             return Err(map_ws_error(err, &url));
         }
     };
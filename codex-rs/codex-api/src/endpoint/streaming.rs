@@ -3,14 +3,21 @@ use crate::auth::add_auth_headers;
 use crate::common::ResponseStream;
 use crate::error::ApiError;
 use crate::provider::Provider;
+use crate::rate_limits::ProviderRateLimiter;
+use crate::response_cache::CacheKey;
+use crate::response_cache::ResponseCache;
+use crate::response_cache::record;
+use crate::response_cache::replay;
 use crate::telemetry::SseTelemetry;
 use crate::telemetry::run_with_request_telemetry;
 use codex_client::HttpTransport;
 use codex_client::RequestCompression;
 use codex_client::RequestTelemetry;
 use codex_client::StreamResponse;
+use codex_client::TransportError;
 use http::HeaderMap;
 use http::Method;
+use http::StatusCode;
 use serde_json::Value;
 use std::sync::Arc;
 use std::sync::OnceLock;
@@ -22,6 +29,7 @@ pub(crate) struct StreamingClient<T: HttpTransport, A: AuthProvider> {
     auth: A,
     request_telemetry: Option<Arc<dyn RequestTelemetry>>,
     sse_telemetry: Option<Arc<dyn SseTelemetry>>,
+    response_cache: Option<Arc<ResponseCache>>,
 }
 
 type StreamSpawner = fn(
@@ -39,6 +47,7 @@ impl<T: HttpTransport, A: AuthProvider> StreamingClient<T, A> {
             auth,
             request_telemetry: None,
             sse_telemetry: None,
+            response_cache: None,
         }
     }
 
@@ -52,6 +61,11 @@ impl<T: HttpTransport, A: AuthProvider> StreamingClient<T, A> {
         self
     }
 
+    pub(crate) fn with_response_cache(mut self, cache: Option<Arc<ResponseCache>>) -> Self {
+        self.response_cache = cache;
+        self
+    }
+
     pub(crate) fn provider(&self) -> &Provider {
         &self.provider
     }
@@ -65,6 +79,17 @@ impl<T: HttpTransport, A: AuthProvider> StreamingClient<T, A> {
         spawner: StreamSpawner,
         turn_state: Option<Arc<OnceLock<String>>>,
     ) -> Result<ResponseStream, ApiError> {
+        let cache_key = self
+            .response_cache
+            .as_ref()
+            .and_then(|_| CacheKey::for_request(&self.provider.base_url, path, &body));
+        if let Some(cache) = self.response_cache.clone()
+            && let Some(key) = cache_key.clone()
+            && let Ok(Some(events)) = tokio::task::spawn_blocking(move || cache.load(&key)).await
+        {
+            return Ok(replay(events));
+        }
+
         let builder = || {
             let mut req = self.provider.build_request(Method::POST, path);
             req.headers.extend(extra_headers.clone());
@@ -77,19 +102,53 @@ impl<T: HttpTransport, A: AuthProvider> StreamingClient<T, A> {
             add_auth_headers(&self.auth, req)
         };
 
-        let stream_response = run_with_request_telemetry(
+        // Every attempt, retries after a 429 included, waits for the shared
+        // budget. A budget that will not reset in time ends the retries.
+        let rate_limiter = ProviderRateLimiter::for_provider(&self.provider);
+        let budget_exhausted = OnceLock::new();
+        let result = run_with_request_telemetry(
             self.provider.retry.to_policy(),
             self.request_telemetry.clone(),
             builder,
-            |req| self.transport.stream(req),
+            |req| {
+                let rate_limiter = &rate_limiter;
+                let budget_exhausted = &budget_exhausted;
+                async move {
+                    if let Err(err) = rate_limiter.acquire().await {
+                        let message = err.message.clone();
+                        let _ = budget_exhausted.set(err);
+                        return Err(TransportError::Build(message));
+                    }
+                    let result = self.transport.stream(req).await;
+                    if let Err(TransportError::Http {
+                        status: StatusCode::TOO_MANY_REQUESTS,
+                        headers: Some(headers),
+                        ..
+                    }) = &result
+                    {
+                        rate_limiter.observe_throttled(headers);
+                    }
+                    result
+                }
+            },
         )
-        .await?;
+        .await;
+        let stream_response = match (result, budget_exhausted.into_inner()) {
+            (Ok(stream_response), _) => stream_response,
+            (Err(_), Some(err)) => return Err(err.into()),
+            (Err(err), None) => return Err(err.into()),
+        };
+        rate_limiter.observe_headers(&stream_response.headers);
 
-        Ok(spawner(
+        let stream = spawner(
             stream_response,
             self.provider.stream_idle_timeout,
             self.sse_telemetry.clone(),
             turn_state,
-        ))
+        );
+        match (self.response_cache.clone(), cache_key) {
+            (Some(cache), Some(key)) => Ok(record(stream, cache, key)),
+            _ => Ok(stream),
+        }
     }
 }
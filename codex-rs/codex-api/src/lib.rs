@@ -5,6 +5,7 @@ pub mod error;
 pub mod provider;
 pub mod rate_limits;
 pub mod requests;
+pub mod response_cache;
 pub mod sse;
 pub mod telemetry;
 
@@ -22,6 +23,7 @@ pub use crate::common::ResponseEvent;
 pub use crate::common::ResponseStream;
 pub use crate::common::ResponsesApiRequest;
 pub use crate::common::create_text_param_for_request;
+pub use crate::endpoint::anthropic::AnthropicClient;
 pub use crate::endpoint::chat::AggregateStreamExt;
 pub use crate::endpoint::chat::ChatClient;
 pub use crate::endpoint::compact::CompactClient;
@@ -33,9 +35,12 @@ pub use crate::endpoint::responses_websocket::ResponsesWebsocketConnection;
 pub use crate::error::ApiError;
 pub use crate::provider::Provider;
 pub use crate::provider::WireApi;
+pub use crate::requests::AnthropicRequest;
+pub use crate::requests::AnthropicRequestBuilder;
 pub use crate::requests::ChatRequest;
 pub use crate::requests::ChatRequestBuilder;
 pub use crate::requests::ResponsesRequest;
 pub use crate::requests::ResponsesRequestBuilder;
+pub use crate::response_cache::ResponseCache;
 pub use crate::sse::stream_from_fixture;
 pub use crate::telemetry::SseTelemetry;
@@ -14,6 +14,8 @@ pub enum WireApi {
     Responses,
     Chat,
     Compact,
+    /// Anthropic's Messages API at `/v1/messages`.
+    AnthropicMessages,
 }
 
 /// High-level retry configuration for a provider.
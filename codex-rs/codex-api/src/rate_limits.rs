@@ -1,8 +1,69 @@
+use crate::provider::Provider;
 use codex_protocol::protocol::CreditsSnapshot;
+use codex_protocol::protocol::ProviderRateLimitsEvent;
 use codex_protocol::protocol::RateLimitSnapshot;
 use codex_protocol::protocol::RateLimitWindow;
 use http::HeaderMap;
+use std::collections::HashMap;
 use std::fmt::Display;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+use tokio::time::Instant;
+use tracing::debug;
+
+/// Upper bound on how long a single request is held back by the scheduler.
+/// Requests whose budget resets later than this are rejected up front rather
+/// than parked; otherwise, once this much time has passed, we send anyway and
+/// let the regular retry logic deal with a 429.
+const MAX_SCHEDULER_WAIT: Duration = Duration::from_secs(120);
+
+/// Names of the headers in which a provider advertises its request and token
+/// budget.
+struct BudgetHeaders {
+    limit_requests: &'static str,
+    remaining_requests: &'static str,
+    reset_requests: &'static str,
+    limit_tokens: &'static str,
+    remaining_tokens: &'static str,
+    reset_tokens: &'static str,
+}
+
+impl BudgetHeaders {
+    fn budget(&self) -> impl Iterator<Item = &'static str> {
+        [
+            self.limit_requests,
+            self.remaining_requests,
+            self.limit_tokens,
+            self.remaining_tokens,
+        ]
+        .into_iter()
+    }
+}
+
+const BUDGET_HEADERS: [BudgetHeaders; 2] = [
+    // OpenAI and most compatible providers; resets are durations like `6m0s`.
+    BudgetHeaders {
+        limit_requests: "x-ratelimit-limit-requests",
+        remaining_requests: "x-ratelimit-remaining-requests",
+        reset_requests: "x-ratelimit-reset-requests",
+        limit_tokens: "x-ratelimit-limit-tokens",
+        remaining_tokens: "x-ratelimit-remaining-tokens",
+        reset_tokens: "x-ratelimit-reset-tokens",
+    },
+    // Anthropic; resets are RFC 3339 timestamps.
+    BudgetHeaders {
+        limit_requests: "anthropic-ratelimit-requests-limit",
+        remaining_requests: "anthropic-ratelimit-requests-remaining",
+        reset_requests: "anthropic-ratelimit-requests-reset",
+        limit_tokens: "anthropic-ratelimit-tokens-limit",
+        remaining_tokens: "anthropic-ratelimit-tokens-remaining",
+        reset_tokens: "anthropic-ratelimit-tokens-reset",
+    },
+];
 
 #[derive(Debug)]
 pub struct RateLimitError {
@@ -104,3 +165,417 @@ fn parse_header_bool(headers: &HeaderMap, name: &str) -> Option<bool> {
 fn parse_header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
     headers.get(name)?.to_str().ok()
 }
+
+/// Request and token budget shared by every conversation that talks to the
+/// same provider endpoint within this process.
+///
+/// The budget is learned from the standard `x-ratelimit-*` response headers
+/// (or Anthropic's `anthropic-ratelimit-*`) and from `retry-after` on 429
+/// responses. Before each request, [`ProviderRateLimiter::acquire`] waits
+/// while the budget is exhausted so concurrent conversations back off
+/// together instead of each one hitting the limit and retrying on its own.
+#[derive(Debug)]
+pub struct ProviderRateLimiter {
+    provider: String,
+    state: Mutex<LimiterState>,
+}
+
+#[derive(Debug, Default)]
+struct LimiterState {
+    limit_requests: Option<u64>,
+    remaining_requests: Option<u64>,
+    requests_reset_at: Option<Instant>,
+    limit_tokens: Option<u64>,
+    remaining_tokens: Option<u64>,
+    tokens_reset_at: Option<Instant>,
+    paused_until: Option<Instant>,
+    observed: bool,
+}
+
+impl LimiterState {
+    /// Forget budgets whose window has already reset.
+    fn expire(&mut self, now: Instant) {
+        if self.requests_reset_at.is_some_and(|at| at <= now) {
+            self.remaining_requests = None;
+            self.requests_reset_at = None;
+        }
+        if self.tokens_reset_at.is_some_and(|at| at <= now) {
+            self.remaining_tokens = None;
+            self.tokens_reset_at = None;
+        }
+        if self.paused_until.is_some_and(|at| at <= now) {
+            self.paused_until = None;
+        }
+    }
+
+    /// The instant until which new requests should be held back, if any.
+    fn blocked_until(&self) -> Option<Instant> {
+        let requests = self
+            .requests_reset_at
+            .filter(|_| self.remaining_requests == Some(0));
+        let tokens = self
+            .tokens_reset_at
+            .filter(|_| self.remaining_tokens == Some(0));
+        [self.paused_until, requests, tokens]
+            .into_iter()
+            .flatten()
+            .max()
+    }
+}
+
+impl ProviderRateLimiter {
+    pub fn new(provider: impl Into<String>) -> Self {
+        Self {
+            provider: provider.into(),
+            state: Mutex::new(LimiterState::default()),
+        }
+    }
+
+    /// Shared limiter for `provider`, keyed by its base URL so that every
+    /// client talking to the same endpoint uses the same budget.
+    pub fn for_provider(provider: &Provider) -> Arc<Self> {
+        static LIMITERS: OnceLock<Mutex<HashMap<String, Arc<ProviderRateLimiter>>>> =
+            OnceLock::new();
+        let mut limiters = LIMITERS
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        Arc::clone(
+            limiters
+                .entry(provider.base_url.clone())
+                .or_insert_with(|| Arc::new(Self::new(provider.name.clone()))),
+        )
+    }
+
+    /// Wait until the shared budget allows another request, then reserve it.
+    ///
+    /// Fails without waiting when the budget is exhausted for longer than
+    /// [`MAX_SCHEDULER_WAIT`], e.g. because a daily token limit was hit.
+    pub async fn acquire(&self) -> Result<(), RateLimitError> {
+        let deadline = Instant::now() + MAX_SCHEDULER_WAIT;
+        loop {
+            let wait_until = {
+                let mut state = self.lock();
+                let now = Instant::now();
+                state.expire(now);
+                match state.blocked_until() {
+                    Some(until) if until > now + MAX_SCHEDULER_WAIT => {
+                        let secs = until.saturating_duration_since(now).as_secs();
+                        return Err(RateLimitError {
+                            message: format!(
+                                "{} rate limit exhausted; it resets in {secs}s",
+                                self.provider
+                            ),
+                        });
+                    }
+                    Some(until) if now < deadline => until.min(deadline),
+                    _ => {
+                        if let Some(remaining) = state.remaining_requests.as_mut() {
+                            *remaining = remaining.saturating_sub(1);
+                        }
+                        return Ok(());
+                    }
+                }
+            };
+            debug!(
+                provider = %self.provider,
+                "holding request until the provider rate limit resets"
+            );
+            tokio::time::sleep_until(wait_until).await;
+        }
+    }
+
+    /// Record the budget advertised by a successful response.
+    pub fn observe_headers(&self, headers: &HeaderMap) {
+        let now = Instant::now();
+        let Some(names) = BUDGET_HEADERS
+            .iter()
+            .find(|names| names.budget().any(|name| headers.contains_key(name)))
+        else {
+            return;
+        };
+        let limit_requests = parse_header_u64(headers, names.limit_requests);
+        let remaining_requests = parse_header_u64(headers, names.remaining_requests);
+        let limit_tokens = parse_header_u64(headers, names.limit_tokens);
+        let remaining_tokens = parse_header_u64(headers, names.remaining_tokens);
+        if limit_requests.is_none()
+            && remaining_requests.is_none()
+            && limit_tokens.is_none()
+            && remaining_tokens.is_none()
+        {
+            return;
+        }
+        let mut state = self.lock();
+        state.observed = true;
+        if limit_requests.is_some() {
+            state.limit_requests = limit_requests;
+        }
+        if remaining_requests.is_some() {
+            state.remaining_requests = remaining_requests;
+            state.requests_reset_at =
+                parse_header_reset(headers, names.reset_requests).map(|d| now + d);
+        }
+        if limit_tokens.is_some() {
+            state.limit_tokens = limit_tokens;
+        }
+        if remaining_tokens.is_some() {
+            state.remaining_tokens = remaining_tokens;
+            state.tokens_reset_at =
+                parse_header_reset(headers, names.reset_tokens).map(|d| now + d);
+        }
+    }
+
+    /// Record a 429 response; every conversation waits out `retry-after`
+    /// before its next request.
+    pub fn observe_throttled(&self, headers: &HeaderMap) {
+        let delay = parse_retry_after(headers).unwrap_or(Duration::from_secs(1));
+        let until = Instant::now() + delay.min(MAX_SCHEDULER_WAIT);
+        let mut state = self.lock();
+        state.observed = true;
+        state.paused_until = Some(state.paused_until.map_or(until, |at| at.max(until)));
+    }
+
+    /// Snapshot of the current budget, or `None` if the provider has not
+    /// reported any limits yet.
+    pub fn budget(&self) -> Option<ProviderRateLimitsEvent> {
+        let now = Instant::now();
+        let mut state = self.lock();
+        state.expire(now);
+        if !state.observed {
+            return None;
+        }
+        let to_unix = |at: Option<Instant>| {
+            at.map(|at| {
+                let at = SystemTime::now() + at.saturating_duration_since(now);
+                at.duration_since(UNIX_EPOCH)
+                    .map_or(0, |d| i64::try_from(d.as_secs()).unwrap_or(i64::MAX))
+            })
+        };
+        Some(ProviderRateLimitsEvent {
+            provider: self.provider.clone(),
+            limit_requests: state.limit_requests,
+            remaining_requests: state.remaining_requests,
+            requests_reset_at: to_unix(state.requests_reset_at),
+            limit_tokens: state.limit_tokens,
+            remaining_tokens: state.remaining_tokens,
+            tokens_reset_at: to_unix(state.tokens_reset_at),
+            paused_until: to_unix(state.paused_until),
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LimiterState> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+fn parse_header_u64(headers: &HeaderMap, name: &str) -> Option<u64> {
+    parse_header_str(headers, name)?.trim().parse::<u64>().ok()
+}
+
+/// Time until the reset advertised in `name`, given either as a duration or
+/// as an RFC 3339 timestamp.
+fn parse_header_reset(headers: &HeaderMap, name: &str) -> Option<Duration> {
+    let raw = parse_header_str(headers, name)?;
+    parse_reset_duration(raw).or_else(|| {
+        let reset_at = chrono::DateTime::parse_from_rfc3339(raw.trim()).ok()?;
+        let until = reset_at.with_timezone(&chrono::Utc) - chrono::Utc::now();
+        Some(until.to_std().unwrap_or(Duration::ZERO))
+    })
+}
+
+fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    if let Some(ms) = parse_header_f64(headers, "retry-after-ms")
+        && ms >= 0.0
+    {
+        return Some(Duration::from_secs_f64(ms / 1000.0));
+    }
+    parse_header_f64(headers, "retry-after")
+        .filter(|secs| *secs >= 0.0)
+        .map(Duration::from_secs_f64)
+}
+
+/// Parses reset durations such as `1s`, `6m0s`, `250ms`, or `1h2m3.5s`.
+fn parse_reset_duration(raw: &str) -> Option<Duration> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return None;
+    }
+    let mut total = 0.0_f64;
+    let mut rest = raw;
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let value: f64 = rest[..number_len].parse().ok()?;
+        rest = &rest[number_len..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let seconds_per_unit = match &rest[..unit_len] {
+            "ms" => 0.001,
+            "s" | "" => 1.0,
+            "m" => 60.0,
+            "h" => 3600.0,
+            _ => return None,
+        };
+        rest = &rest[unit_len..];
+        total += value * seconds_per_unit;
+    }
+    Duration::try_from_secs_f64(total).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::HeaderValue;
+    use pretty_assertions::assert_eq;
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    /// Asserts that a reported reset is `secs` from now, give or take a
+    /// second, and returns it.
+    fn reset_in(reset_at: Option<i64>, secs: i64) -> Option<i64> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| i64::try_from(d.as_secs()).unwrap_or(i64::MAX))
+            .expect("time");
+        let at = reset_at.expect("reset time");
+        assert!(
+            (now + secs - 1..=now + secs + 1).contains(&at),
+            "reset at {at}, expected about {}",
+            now + secs
+        );
+        reset_at
+    }
+
+    #[test]
+    fn parses_reset_durations() {
+        assert_eq!(parse_reset_duration("1s"), Some(Duration::from_secs(1)));
+        assert_eq!(parse_reset_duration("6m0s"), Some(Duration::from_secs(360)));
+        assert_eq!(
+            parse_reset_duration("250ms"),
+            Some(Duration::from_millis(250))
+        );
+        assert_eq!(
+            parse_reset_duration("1h2m3.5s"),
+            Some(Duration::from_secs_f64(3723.5))
+        );
+        assert_eq!(parse_reset_duration("soon"), None);
+    }
+
+    #[test]
+    fn budget_reflects_headers_and_reservations() {
+        let limiter = ProviderRateLimiter::new("openai");
+        assert_eq!(limiter.budget(), None);
+
+        limiter.observe_headers(&headers(&[
+            ("x-ratelimit-limit-requests", "500"),
+            ("x-ratelimit-remaining-requests", "2"),
+            ("x-ratelimit-reset-requests", "10s"),
+            ("x-ratelimit-limit-tokens", "30000"),
+            ("x-ratelimit-remaining-tokens", "29000"),
+        ]));
+        let budget = limiter.budget().expect("budget");
+        assert_eq!(
+            budget,
+            ProviderRateLimitsEvent {
+                provider: "openai".to_string(),
+                limit_requests: Some(500),
+                remaining_requests: Some(2),
+                requests_reset_at: reset_in(budget.requests_reset_at, 10),
+                limit_tokens: Some(30000),
+                remaining_tokens: Some(29000),
+                tokens_reset_at: None,
+                paused_until: None,
+            }
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn acquire_waits_for_exhausted_budget() {
+        let limiter = ProviderRateLimiter::new("openai");
+        limiter.observe_headers(&headers(&[
+            ("x-ratelimit-remaining-requests", "1"),
+            ("x-ratelimit-reset-requests", "5s"),
+        ]));
+
+        let start = Instant::now();
+        limiter.acquire().await.expect("acquire");
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        // The only remaining request was reserved above, so the next caller
+        // waits for the window to reset.
+        limiter.acquire().await.expect("acquire");
+        assert_eq!(start.elapsed(), Duration::from_secs(5));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn throttling_pauses_all_callers() {
+        let limiter = ProviderRateLimiter::new("openai");
+        limiter.observe_throttled(&headers(&[("retry-after", "3")]));
+        let budget = limiter.budget().expect("budget");
+        assert_eq!(
+            budget,
+            ProviderRateLimitsEvent {
+                provider: "openai".to_string(),
+                limit_requests: None,
+                remaining_requests: None,
+                requests_reset_at: None,
+                limit_tokens: None,
+                remaining_tokens: None,
+                tokens_reset_at: None,
+                paused_until: reset_in(budget.paused_until, 3),
+            }
+        );
+
+        let start = Instant::now();
+        limiter.acquire().await.expect("acquire");
+        assert_eq!(start.elapsed(), Duration::from_secs(3));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn anthropic_budget_far_from_reset_is_rejected() {
+        let limiter = ProviderRateLimiter::new("anthropic");
+        let mut headers = headers(&[
+            ("anthropic-ratelimit-tokens-limit", "400000"),
+            ("anthropic-ratelimit-tokens-remaining", "0"),
+        ]);
+        let reset_at = (chrono::Utc::now() + chrono::Duration::hours(1)).to_rfc3339();
+        headers.insert(
+            "anthropic-ratelimit-tokens-reset",
+            HeaderValue::from_str(&reset_at).expect("header value"),
+        );
+        limiter.observe_headers(&headers);
+
+        let budget = limiter.budget().expect("budget");
+        assert_eq!(
+            budget,
+            ProviderRateLimitsEvent {
+                provider: "anthropic".to_string(),
+                limit_requests: None,
+                remaining_requests: None,
+                requests_reset_at: None,
+                limit_tokens: Some(400000),
+                remaining_tokens: Some(0),
+                tokens_reset_at: reset_in(budget.tokens_reset_at, 3600),
+                paused_until: None,
+            }
+        );
+
+        let start = Instant::now();
+        let err = limiter
+            .acquire()
+            .await
+            .expect_err("budget resets in an hour");
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        assert!(err.message.starts_with("anthropic rate limit exhausted"));
+    }
+}
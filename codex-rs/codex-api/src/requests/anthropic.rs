@@ -0,0 +1,359 @@
+use crate::error::ApiError;
+use crate::provider::Provider;
+use crate::requests::headers::build_conversation_headers;
+use crate::requests::headers::insert_header;
+use crate::requests::headers::subagent_header;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::FunctionCallOutputContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::SessionSource;
+use codex_utils_image::resize_data_url_to_fit;
+use http::HeaderMap;
+use serde_json::Value;
+use serde_json::json;
+use tracing::warn;
+
+/// `anthropic-version` sent unless the provider configures its own.
+pub const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// The Messages API requires `max_tokens`; this is used when the model does
+/// not declare a limit.
+pub const DEFAULT_ANTHROPIC_MAX_TOKENS: i64 = 8192;
+
+/// Assembled request body plus headers for Anthropic Messages streaming calls.
+pub struct AnthropicRequest {
+    pub body: Value,
+    pub headers: HeaderMap,
+}
+
+pub struct AnthropicRequestBuilder<'a> {
+    model: &'a str,
+    instructions: &'a str,
+    input: &'a [ResponseItem],
+    tools: &'a [Value],
+    max_tokens: Option<i64>,
+    conversation_id: Option<String>,
+    session_source: Option<SessionSource>,
+}
+
+impl<'a> AnthropicRequestBuilder<'a> {
+    /// `tools` must already be in the Messages API shape
+    /// (`{name, description, input_schema}`).
+    pub fn new(
+        model: &'a str,
+        instructions: &'a str,
+        input: &'a [ResponseItem],
+        tools: &'a [Value],
+    ) -> Self {
+        Self {
+            model,
+            instructions,
+            input,
+            tools,
+            max_tokens: None,
+            conversation_id: None,
+            session_source: None,
+        }
+    }
+
+    pub fn max_tokens(mut self, max_tokens: Option<i64>) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    pub fn conversation_id(mut self, id: Option<String>) -> Self {
+        self.conversation_id = id;
+        self
+    }
+
+    pub fn session_source(mut self, source: Option<SessionSource>) -> Self {
+        self.session_source = source;
+        self
+    }
+
+    pub fn build(self, provider: &Provider) -> Result<AnthropicRequest, ApiError> {
+        let mut messages = Vec::<Value>::new();
+        for item in self.input {
+            match item {
+                ResponseItem::Message { role, content, .. } => {
+                    // The Messages API only knows `user` and `assistant`;
+                    // developer and system context is sent as user text.
+                    let role = if role == "assistant" {
+                        "assistant"
+                    } else {
+                        "user"
+                    };
+                    for c in content {
+                        let block = match c {
+                            ContentItem::InputText { text } | ContentItem::OutputText { text } => {
+                                if text.is_empty() {
+                                    continue;
+                                }
+                                json!({"type": "text", "text": text})
+                            }
+                            ContentItem::InputImage { image_url } => image_block(image_url),
+                        };
+                        push_block(&mut messages, role, block);
+                    }
+                }
+                ResponseItem::FunctionCall {
+                    name,
+                    arguments,
+                    call_id,
+                    ..
+                } => {
+                    let input = serde_json::from_str::<Value>(arguments).unwrap_or_else(|err| {
+                        warn!("sending unparsable arguments of {name} as an empty object: {err}");
+                        json!({})
+                    });
+                    push_block(
+                        &mut messages,
+                        "assistant",
+                        json!({"type": "tool_use", "id": call_id, "name": name, "input": input}),
+                    );
+                }
+                ResponseItem::LocalShellCall {
+                    id,
+                    call_id,
+                    action,
+                    ..
+                } => {
+                    let id = call_id.clone().or_else(|| id.clone()).unwrap_or_default();
+                    push_block(
+                        &mut messages,
+                        "assistant",
+                        json!({"type": "tool_use", "id": id, "name": "local_shell", "input": action}),
+                    );
+                }
+                ResponseItem::CustomToolCall {
+                    call_id,
+                    name,
+                    input,
+                    ..
+                } => {
+                    push_block(
+                        &mut messages,
+                        "assistant",
+                        json!({"type": "tool_use", "id": call_id, "name": name, "input": {"input": input}}),
+                    );
+                }
+                ResponseItem::FunctionCallOutput { call_id, output } => {
+                    let content = match &output.content_items {
+                        Some(items) => json!(
+                            items
+                                .iter()
+                                .map(|it| match it {
+                                    FunctionCallOutputContentItem::InputText { text } => {
+                                        json!({"type": "text", "text": text})
+                                    }
+                                    FunctionCallOutputContentItem::InputImage { image_url } => {
+                                        image_block(image_url)
+                                    }
+                                })
+                                .collect::<Vec<_>>()
+                        ),
+                        None => json!(output.content),
+                    };
+                    let mut block =
+                        json!({"type": "tool_result", "tool_use_id": call_id, "content": content});
+                    if output.success == Some(false) {
+                        block["is_error"] = json!(true);
+                    }
+                    push_block(&mut messages, "user", block);
+                }
+                ResponseItem::CustomToolCallOutput { call_id, output } => {
+                    push_block(
+                        &mut messages,
+                        "user",
+                        json!({"type": "tool_result", "tool_use_id": call_id, "content": output}),
+                    );
+                }
+                ResponseItem::Reasoning { .. }
+                | ResponseItem::WebSearchCall { .. }
+                | ResponseItem::CodeInterpreterCall { .. }
+                | ResponseItem::FileSearchCall { .. }
+                | ResponseItem::GhostSnapshot { .. }
+                | ResponseItem::Compaction { .. }
+                | ResponseItem::Other => {}
+            }
+        }
+
+        let mut payload = json!({
+            "model": self.model,
+            "system": self.instructions,
+            "messages": messages,
+            "max_tokens": self.max_tokens.unwrap_or(DEFAULT_ANTHROPIC_MAX_TOKENS),
+            "stream": true,
+        });
+        if !self.tools.is_empty() {
+            payload["tools"] = json!(self.tools);
+        }
+
+        let mut headers = build_conversation_headers(self.conversation_id);
+        if let Some(subagent) = subagent_header(&self.session_source) {
+            insert_header(&mut headers, "x-openai-subagent", &subagent);
+        }
+        if !provider.headers.contains_key("anthropic-version") {
+            insert_header(&mut headers, "anthropic-version", ANTHROPIC_VERSION);
+        }
+
+        Ok(AnthropicRequest {
+            body: payload,
+            headers,
+        })
+    }
+}
+
+/// Appends `block` to the last message if it has `role`, since the Messages
+/// API expects user and assistant turns to alternate.
+fn push_block(messages: &mut Vec<Value>, role: &str, block: Value) {
+    if let Some(Value::Object(last)) = messages.last_mut()
+        && last.get("role").and_then(Value::as_str) == Some(role)
+        && let Some(content) = last.get_mut("content").and_then(Value::as_array_mut)
+    {
+        content.push(block);
+        return;
+    }
+    messages.push(json!({"role": role, "content": [block]}));
+}
+
+/// Encodes an image as a Messages API `image` block. Inline images are
+/// resized first, as for Chat Completions; images that cannot be decoded
+/// become a text block explaining why they were dropped.
+fn image_block(image_url: &str) -> Value {
+    let url = match resize_data_url_to_fit(image_url) {
+        Ok(url) => url,
+        Err(err) => {
+            warn!("dropping image from anthropic request: {err}");
+            return json!({"type": "text", "text": format!("[image omitted: {err}]")});
+        }
+    };
+    let inline = url
+        .strip_prefix("data:")
+        .and_then(|rest| rest.split_once(";base64,"));
+    match inline {
+        Some((media_type, data)) => json!({
+            "type": "image",
+            "source": {"type": "base64", "media_type": media_type, "data": data},
+        }),
+        None => json!({"type": "image", "source": {"type": "url", "url": url}}),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::RetryConfig;
+    use crate::provider::WireApi;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use http::HeaderValue;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    fn provider() -> Provider {
+        Provider {
+            name: "anthropic".to_string(),
+            base_url: "https://api.anthropic.com/v1".to_string(),
+            query_params: None,
+            wire: WireApi::AnthropicMessages,
+            headers: HeaderMap::new(),
+            retry: RetryConfig {
+                max_attempts: 1,
+                base_delay: Duration::from_millis(10),
+                retry_429: false,
+                retry_5xx: true,
+                retry_transport: true,
+            },
+            stream_idle_timeout: Duration::from_secs(1),
+        }
+    }
+
+    fn message(role: &str, text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: role.to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+            end_turn: None,
+        }
+    }
+
+    #[test]
+    fn maps_history_to_alternating_messages() {
+        let prompt_input = vec![
+            message("developer", "be brief"),
+            message("user", "read a.txt"),
+            ResponseItem::FunctionCall {
+                id: None,
+                name: "read_file".to_string(),
+                arguments: r#"{"path":"a.txt"}"#.to_string(),
+                call_id: "toolu_1".to_string(),
+            },
+            ResponseItem::FunctionCallOutput {
+                call_id: "toolu_1".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: "no such file".to_string(),
+                    success: Some(false),
+                    ..Default::default()
+                },
+            },
+        ];
+        let tools = vec![json!({"name": "read_file", "input_schema": {"type": "object"}})];
+
+        let req = AnthropicRequestBuilder::new("claude-test", "inst", &prompt_input, &tools)
+            .build(&provider())
+            .expect("request");
+
+        assert_eq!(
+            req.body,
+            json!({
+                "model": "claude-test",
+                "system": "inst",
+                "messages": [
+                    {"role": "user", "content": [
+                        {"type": "text", "text": "be brief"},
+                        {"type": "text", "text": "read a.txt"},
+                    ]},
+                    {"role": "assistant", "content": [
+                        {"type": "tool_use", "id": "toolu_1", "name": "read_file", "input": {"path": "a.txt"}},
+                    ]},
+                    {"role": "user", "content": [
+                        {"type": "tool_result", "tool_use_id": "toolu_1", "content": "no such file", "is_error": true},
+                    ]},
+                ],
+                "max_tokens": DEFAULT_ANTHROPIC_MAX_TOKENS,
+                "stream": true,
+                "tools": tools,
+            })
+        );
+        assert_eq!(
+            req.headers.get("anthropic-version"),
+            Some(&HeaderValue::from_static(ANTHROPIC_VERSION))
+        );
+    }
+
+    #[test]
+    fn remote_images_become_url_sources() {
+        let prompt_input = vec![ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputImage {
+                image_url: "https://example.com/cat.png".to_string(),
+            }],
+            end_turn: None,
+        }];
+
+        let req = AnthropicRequestBuilder::new("claude-test", "inst", &prompt_input, &[])
+            .max_tokens(Some(1024))
+            .build(&provider())
+            .expect("request");
+
+        assert_eq!(
+            req.body["messages"][0]["content"][0],
+            json!({"type": "image", "source": {"type": "url", "url": "https://example.com/cat.png"}})
+        );
+        assert_eq!(req.body["max_tokens"], json!(1024));
+        assert_eq!(req.body.get("tools"), None);
+    }
+}
@@ -8,10 +8,12 @@ use codex_protocol::models::FunctionCallOutputContentItem;
 use codex_protocol::models::ReasoningItemContent;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::protocol::SessionSource;
+use codex_utils_image::resize_data_url_to_fit;
 use http::HeaderMap;
 use serde_json::Value;
 use serde_json::json;
 use std::collections::HashMap;
+use tracing::warn;
 
 /// Assembled request body plus headers for Chat Completions streaming calls.
 pub struct ChatRequest {
@@ -24,6 +26,7 @@ pub struct ChatRequestBuilder<'a> {
     instructions: &'a str,
     input: &'a [ResponseItem],
     tools: &'a [Value],
+    max_tokens: Option<i64>,
     conversation_id: Option<String>,
     session_source: Option<SessionSource>,
 }
@@ -40,11 +43,17 @@ impl<'a> ChatRequestBuilder<'a> {
             instructions,
             input,
             tools,
+            max_tokens: None,
             conversation_id: None,
             session_source: None,
         }
     }
 
+    pub fn max_tokens(mut self, max_tokens: Option<i64>) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
     pub fn conversation_id(mut self, id: Option<String>) -> Self {
         self.conversation_id = id;
         self
@@ -73,6 +82,8 @@ impl<'a> ChatRequestBuilder<'a> {
                 ResponseItem::CustomToolCall { .. } => {}
                 ResponseItem::CustomToolCallOutput { .. } => {}
                 ResponseItem::WebSearchCall { .. } => {}
+                ResponseItem::CodeInterpreterCall { .. } => {}
+                ResponseItem::FileSearchCall { .. } => {}
                 ResponseItem::GhostSnapshot { .. } => {}
                 ResponseItem::Compaction { .. } => {}
             }
@@ -165,9 +176,7 @@ impl<'a> ChatRequestBuilder<'a> {
                             }
                             ContentItem::InputImage { image_url } => {
                                 saw_image = true;
-                                items.push(
-                                    json!({"type":"image_url","image_url": {"url": image_url}}),
-                                );
+                                items.push(chat_image_part(image_url));
                             }
                         }
                     }
@@ -284,6 +293,8 @@ impl<'a> ChatRequestBuilder<'a> {
                 }
                 ResponseItem::Reasoning { .. }
                 | ResponseItem::WebSearchCall { .. }
+                | ResponseItem::CodeInterpreterCall { .. }
+                | ResponseItem::FileSearchCall { .. }
                 | ResponseItem::Other
                 | ResponseItem::Compaction { .. } => {
                     continue;
@@ -291,12 +302,15 @@ impl<'a> ChatRequestBuilder<'a> {
             }
         }
 
-        let payload = json!({
+        let mut payload = json!({
             "model": self.model,
             "messages": messages,
             "stream": true,
             "tools": self.tools,
         });
+        if let Some(max_tokens) = self.max_tokens {
+            payload["max_tokens"] = json!(max_tokens);
+        }
 
         let mut headers = build_conversation_headers(self.conversation_id);
         if let Some(subagent) = subagent_header(&self.session_source) {
@@ -310,6 +324,22 @@ impl<'a> ChatRequestBuilder<'a> {
     }
 }
 
+/// Encodes an image as a Chat Completions `image_url` content part.
+///
+/// Unlike the Responses API, Chat Completions providers do not downscale
+/// oversized images themselves, so inline images are resized (and re-encoded
+/// as PNG or JPEG) first. Images that cannot be decoded become a text part
+/// explaining why they were dropped.
+fn chat_image_part(image_url: &str) -> Value {
+    match resize_data_url_to_fit(image_url) {
+        Ok(url) => json!({"type":"image_url","image_url": {"url": url}}),
+        Err(err) => {
+            warn!("dropping image from chat request: {err}");
+            json!({"type":"text","text": format!("[image omitted: {err}]")})
+        }
+    }
+}
+
 fn push_tool_call_message(messages: &mut Vec<Value>, tool_call: Value, reasoning: Option<&str>) {
     // Chat Completions requires that tool calls are grouped into a single assistant message
     // (with `tool_calls: [...]`) followed by tool role responses.
@@ -404,6 +434,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn sets_max_tokens_only_when_configured() {
+        let prompt_input = vec![ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: "hi".to_string(),
+            }],
+            end_turn: None,
+        }];
+        let req = ChatRequestBuilder::new("gpt-test", "inst", &prompt_input, &[])
+            .max_tokens(Some(4096))
+            .build(&provider())
+            .expect("request");
+        assert_eq!(req.body.get("max_tokens"), Some(&json!(4096)));
+
+        let req = ChatRequestBuilder::new("gpt-test", "inst", &prompt_input, &[])
+            .build(&provider())
+            .expect("request");
+        assert_eq!(req.body.get("max_tokens"), None);
+    }
+
     #[test]
     fn groups_consecutive_tool_calls_into_a_single_assistant_message() {
         let prompt_input = vec![
@@ -489,4 +541,42 @@ mod tests {
         assert_eq!(messages[5]["role"], "tool");
         assert_eq!(messages[5]["tool_call_id"], "call-c");
     }
+
+    #[test]
+    fn user_images_become_image_url_parts() {
+        let prompt_input = vec![ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![
+                ContentItem::InputText {
+                    text: "what is this?".to_string(),
+                },
+                ContentItem::InputImage {
+                    image_url: "https://example.com/cat.png".to_string(),
+                },
+                ContentItem::InputImage {
+                    image_url: "data:image/png;base64,bm90IGFuIGltYWdl".to_string(),
+                },
+            ],
+            end_turn: None,
+        }];
+
+        let req = ChatRequestBuilder::new("gpt-test", "inst", &prompt_input, &[])
+            .build(&provider())
+            .expect("request");
+
+        let content = &req.body["messages"][1]["content"];
+        assert_eq!(content[0], json!({"type": "text", "text": "what is this?"}));
+        assert_eq!(
+            content[1],
+            json!({"type": "image_url", "image_url": {"url": "https://example.com/cat.png"}})
+        );
+        assert_eq!(content[2]["type"], "text");
+        assert!(
+            content[2]["text"]
+                .as_str()
+                .is_some_and(|text| text.starts_with("[image omitted:")),
+            "{content}"
+        );
+    }
 }
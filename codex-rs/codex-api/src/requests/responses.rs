@@ -36,6 +36,7 @@ pub struct ResponsesRequestBuilder<'a> {
     include: Vec<String>,
     prompt_cache_key: Option<String>,
     text: Option<TextControls>,
+    max_output_tokens: Option<i64>,
     conversation_id: Option<String>,
     session_source: Option<SessionSource>,
     store_override: Option<bool>,
@@ -83,6 +84,11 @@ impl<'a> ResponsesRequestBuilder<'a> {
         self
     }
 
+    pub fn max_output_tokens(mut self, max_output_tokens: Option<i64>) -> Self {
+        self.max_output_tokens = max_output_tokens;
+        self
+    }
+
     pub fn conversation(mut self, conversation_id: Option<String>) -> Self {
         self.conversation_id = conversation_id;
         self
@@ -137,6 +143,7 @@ impl<'a> ResponsesRequestBuilder<'a> {
             include: self.include,
             prompt_cache_key: self.prompt_cache_key,
             text: self.text,
+            max_output_tokens: self.max_output_tokens,
         };
 
         let mut body = serde_json::to_value(&req)
@@ -172,6 +179,8 @@ fn attach_item_ids(payload_json: &mut Value, original_items: &[ResponseItem]) {
         if let ResponseItem::Reasoning { id, .. }
         | ResponseItem::Message { id: Some(id), .. }
         | ResponseItem::WebSearchCall { id: Some(id), .. }
+        | ResponseItem::CodeInterpreterCall { id: Some(id), .. }
+        | ResponseItem::FileSearchCall { id: Some(id), .. }
         | ResponseItem::FunctionCall { id: Some(id), .. }
         | ResponseItem::LocalShellCall { id: Some(id), .. }
         | ResponseItem::CustomToolCall { id: Some(id), .. } = item
@@ -0,0 +1,338 @@
+//! Opt-in exact-match cache of model responses, for eval and batch runs that
+//! send the same request many times. Entries live on disk so repeated runs in
+//! separate processes share them.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use sha2::Digest;
+use sha2::Sha256;
+use tokio::sync::mpsc;
+use tracing::warn;
+
+use crate::common::ResponseEvent;
+use crate::common::ResponseStream;
+use crate::error::ApiError;
+
+/// Request fields that differ between otherwise identical requests, such as
+/// the per-conversation prompt cache key, and so are left out of the key.
+const VOLATILE_REQUEST_FIELDS: &[&str] = &["prompt_cache_key"];
+
+/// Identifies a cached response by model and a hash of the provider's base
+/// URL and the full request, so providers serving the same model names do not
+/// share entries. The hash is SHA-256 so keys stay the same across builds and
+/// processes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CacheKey {
+    model: String,
+    hash: String,
+}
+
+impl CacheKey {
+    /// Key for a request body sent to `path` under `base_url`, or `None` when
+    /// the body names no model.
+    pub(crate) fn for_request(base_url: &str, path: &str, body: &Value) -> Option<Self> {
+        let model = body.get("model")?.as_str()?.to_string();
+        let mut body = body.clone();
+        if let Some(object) = body.as_object_mut() {
+            for field in VOLATILE_REQUEST_FIELDS {
+                object.remove(*field);
+            }
+        }
+        let mut hasher = Sha256::new();
+        hasher.update(base_url.as_bytes());
+        hasher.update([0]);
+        hasher.update(path.as_bytes());
+        hasher.update([0]);
+        hasher.update(body.to_string().as_bytes());
+        let hash = hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        Some(Self { model, hash })
+    }
+
+    fn file_name(&self) -> String {
+        let model: String = self
+            .model
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        format!("{model}-{}.json", self.hash)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    model: String,
+    /// Seconds since the Unix epoch when the response was stored.
+    created_at: u64,
+    events: Vec<ResponseEvent>,
+}
+
+/// Directory of cached responses with an expiry and an entry limit.
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    dir: PathBuf,
+    ttl: Duration,
+    max_entries: usize,
+}
+
+impl ResponseCache {
+    pub fn new(dir: PathBuf, ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            dir,
+            ttl,
+            max_entries,
+        }
+    }
+
+    /// The events of a cached, unexpired response for `key`. This reads the
+    /// disk, so call it off the async runtime.
+    pub(crate) fn load(&self, key: &CacheKey) -> Option<Vec<ResponseEvent>> {
+        let contents = fs::read_to_string(self.dir.join(key.file_name())).ok()?;
+        let entry: CacheEntry = serde_json::from_str(&contents).ok()?;
+        if entry.model != key.model || self.is_expired(entry.created_at) {
+            return None;
+        }
+        Some(entry.events)
+    }
+
+    /// Stores a completed response and prunes expired and excess entries.
+    /// Like [`Self::load`], this blocks on the disk.
+    pub(crate) fn store(&self, key: &CacheKey, events: Vec<ResponseEvent>) {
+        let entry = CacheEntry {
+            model: key.model.clone(),
+            created_at: unix_now(),
+            events,
+        };
+        let result = fs::create_dir_all(&self.dir)
+            .and_then(|()| {
+                let json = serde_json::to_string(&entry).map_err(std::io::Error::other)?;
+                fs::write(self.dir.join(key.file_name()), json)
+            })
+            .and_then(|()| self.prune());
+        if let Err(err) = result {
+            warn!("failed to store cached response: {err}");
+        }
+    }
+
+    fn is_expired(&self, created_at: u64) -> bool {
+        unix_now().saturating_sub(created_at) > self.ttl.as_secs()
+    }
+
+    fn prune(&self) -> std::io::Result<()> {
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let modified = fs::metadata(&path)?.modified()?;
+            let age = SystemTime::now()
+                .duration_since(modified)
+                .unwrap_or_default();
+            if age > self.ttl {
+                fs::remove_file(&path)?;
+            } else {
+                entries.push((modified, path));
+            }
+        }
+        if entries.len() > self.max_entries {
+            entries.sort();
+            let excess = entries.len() - self.max_entries;
+            for (_, path) in entries.into_iter().take(excess) {
+                fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// Replays cached events as if they were streamed by the provider.
+pub(crate) fn replay(events: Vec<ResponseEvent>) -> ResponseStream {
+    let (tx_event, rx_event) = mpsc::channel(events.len().max(1));
+    for event in events {
+        // The channel has room for every event, so this never fails.
+        let _ = tx_event.try_send(Ok(event));
+    }
+    ResponseStream { rx_event }
+}
+
+/// Forwards `stream` unchanged and caches the response once it completes
+/// without errors. Rate limit snapshots and model etags describe the moment
+/// of the request, so they are not cached.
+pub(crate) fn record(
+    mut stream: ResponseStream,
+    cache: Arc<ResponseCache>,
+    key: CacheKey,
+) -> ResponseStream {
+    let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent, ApiError>>(1600);
+    tokio::spawn(async move {
+        let mut events = Vec::new();
+        let mut completed = false;
+        let mut failed = false;
+        while let Some(event) = stream.rx_event.recv().await {
+            match &event {
+                Ok(ResponseEvent::RateLimits(_) | ResponseEvent::ModelsEtag(_)) => {}
+                Ok(event) => {
+                    completed |= matches!(event, ResponseEvent::Completed { .. });
+                    events.push(event.clone());
+                }
+                Err(_) => failed = true,
+            }
+            if tx_event.send(event).await.is_err() {
+                // The consumer went away before the response finished.
+                return;
+            }
+        }
+        if completed && !failed {
+            let _ = tokio::task::spawn_blocking(move || cache.store(&key, events)).await;
+        }
+    });
+    ResponseStream { rx_event }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    const BASE_URL: &str = "https://api.openai.com/v1";
+
+    fn completed(response_id: &str) -> ResponseEvent {
+        ResponseEvent::Completed {
+            response_id: response_id.to_string(),
+            token_usage: None,
+        }
+    }
+
+    #[test]
+    fn key_ignores_volatile_fields_and_includes_the_base_url() {
+        let first = json!({"model": "gpt-5.1", "input": [], "prompt_cache_key": "a"});
+        let second = json!({"model": "gpt-5.1", "input": [], "prompt_cache_key": "b"});
+        let other = json!({"model": "gpt-5.1", "input": ["hi"], "prompt_cache_key": "a"});
+
+        assert_eq!(
+            CacheKey::for_request(BASE_URL, "responses", &first),
+            CacheKey::for_request(BASE_URL, "responses", &second)
+        );
+        assert_ne!(
+            CacheKey::for_request(BASE_URL, "responses", &first),
+            CacheKey::for_request(BASE_URL, "responses", &other)
+        );
+        assert_ne!(
+            CacheKey::for_request(BASE_URL, "responses", &first),
+            CacheKey::for_request("http://localhost:11434/v1", "responses", &first)
+        );
+        assert_eq!(
+            CacheKey::for_request(BASE_URL, "responses", &json!({})),
+            None
+        );
+    }
+
+    #[test]
+    fn key_is_stable_across_builds() {
+        let key = CacheKey::for_request(BASE_URL, "responses", &json!({"model": "gpt-5.1"}))
+            .expect("key");
+        assert_eq!(
+            key,
+            CacheKey {
+                model: "gpt-5.1".to_string(),
+                hash: "5d7fd4bc778d69c4efb9cb7cd3514cdb9b3fd6d67ab8261ee6a687ef42b7557f"
+                    .to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn stores_and_expires_entries() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let key =
+            CacheKey::for_request(BASE_URL, "responses", &json!({"model": "m"})).expect("key");
+
+        let cache = ResponseCache::new(dir.path().to_path_buf(), Duration::from_secs(60), 8);
+        assert!(cache.load(&key).is_none());
+        cache.store(&key, vec![completed("resp-1")]);
+        let events = cache.load(&key).expect("cached events");
+        assert!(matches!(
+            events.as_slice(),
+            [ResponseEvent::Completed { response_id, .. }] if response_id == "resp-1"
+        ));
+
+        let expired = ResponseCache::new(dir.path().to_path_buf(), Duration::ZERO, 8);
+        let mut entry: CacheEntry = serde_json::from_str(
+            &fs::read_to_string(dir.path().join(key.file_name())).expect("entry"),
+        )
+        .expect("parse entry");
+        entry.created_at = entry.created_at.saturating_sub(5);
+        fs::write(
+            dir.path().join(key.file_name()),
+            serde_json::to_string(&entry).expect("serialize entry"),
+        )
+        .expect("write entry");
+        assert!(expired.load(&key).is_none());
+    }
+
+    #[test]
+    fn keeps_at_most_max_entries() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let cache = ResponseCache::new(dir.path().to_path_buf(), Duration::from_secs(60), 2);
+        for index in 0..4 {
+            let key =
+                CacheKey::for_request(BASE_URL, "responses", &json!({"model": "m", "n": index}))
+                    .expect("key");
+            cache.store(&key, vec![completed("resp")]);
+        }
+
+        assert_eq!(fs::read_dir(dir.path()).expect("read dir").count(), 2);
+    }
+
+    #[tokio::test]
+    async fn records_completed_responses_only() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let cache = Arc::new(ResponseCache::new(
+            dir.path().to_path_buf(),
+            Duration::from_secs(60),
+            8,
+        ));
+        let key =
+            CacheKey::for_request(BASE_URL, "responses", &json!({"model": "m"})).expect("key");
+
+        let (tx, rx_event) = mpsc::channel(4);
+        tx.send(Ok(ResponseEvent::Created)).await.expect("send");
+        tx.send(Ok(completed("resp-1"))).await.expect("send");
+        drop(tx);
+        let mut stream = record(ResponseStream { rx_event }, Arc::clone(&cache), key.clone());
+        let mut forwarded = 0;
+        while stream.rx_event.recv().await.is_some() {
+            forwarded += 1;
+        }
+        // Let the recorder finish writing after the channel closes.
+        for _ in 0..50 {
+            if cache.load(&key).is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        assert_eq!(forwarded, 2);
+        assert_eq!(cache.load(&key).map(|events| events.len()), Some(2));
+    }
+}
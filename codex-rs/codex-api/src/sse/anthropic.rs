@@ -0,0 +1,596 @@
+use crate::common::ResponseEvent;
+use crate::common::ResponseStream;
+use crate::error::ApiError;
+use crate::telemetry::SseTelemetry;
+use codex_client::StreamResponse;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ReasoningItemContent;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::TokenUsage;
+use eventsource_stream::Eventsource;
+use futures::Stream;
+use futures::StreamExt;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tokio::time::timeout;
+use tracing::debug;
+use tracing::trace;
+
+pub(crate) fn spawn_anthropic_stream(
+    stream_response: StreamResponse,
+    idle_timeout: Duration,
+    telemetry: Option<Arc<dyn SseTelemetry>>,
+    _turn_state: Option<Arc<OnceLock<String>>>,
+) -> ResponseStream {
+    let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent, ApiError>>(1600);
+    tokio::spawn(async move {
+        process_anthropic_sse(stream_response.bytes, tx_event, idle_timeout, telemetry).await;
+    });
+    ResponseStream { rx_event }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum AnthropicEvent {
+    MessageStart {
+        message: MessageStart,
+    },
+    ContentBlockStart {
+        index: usize,
+        content_block: ContentBlock,
+    },
+    ContentBlockDelta {
+        index: usize,
+        delta: BlockDelta,
+    },
+    ContentBlockStop {
+        index: usize,
+    },
+    MessageDelta {
+        #[serde(default)]
+        delta: Option<MessageDeltaBody>,
+        #[serde(default)]
+        usage: Option<Usage>,
+    },
+    MessageStop,
+    Error {
+        error: ErrorBody,
+    },
+    /// `ping` and event types added after this was written.
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageStart {
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageDeltaBody {
+    #[serde(default)]
+    stop_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ContentBlock {
+    Text {
+        #[serde(default)]
+        text: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        #[serde(default)]
+        input: Value,
+    },
+    Thinking {
+        #[serde(default)]
+        thinking: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum BlockDelta {
+    TextDelta {
+        text: String,
+    },
+    InputJsonDelta {
+        partial_json: String,
+    },
+    ThinkingDelta {
+        thinking: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+/// Token counts. `message_start` carries the input side and `message_delta`
+/// the cumulative output count, so later values replace earlier ones.
+#[derive(Debug, Default, Deserialize)]
+struct Usage {
+    input_tokens: Option<i64>,
+    output_tokens: Option<i64>,
+    cache_read_input_tokens: Option<i64>,
+    cache_creation_input_tokens: Option<i64>,
+}
+
+impl Usage {
+    fn update(&mut self, other: Usage) {
+        self.input_tokens = other.input_tokens.or(self.input_tokens);
+        self.output_tokens = other.output_tokens.or(self.output_tokens);
+        self.cache_read_input_tokens = other
+            .cache_read_input_tokens
+            .or(self.cache_read_input_tokens);
+        self.cache_creation_input_tokens = other
+            .cache_creation_input_tokens
+            .or(self.cache_creation_input_tokens);
+    }
+}
+
+impl From<&Usage> for TokenUsage {
+    fn from(usage: &Usage) -> Self {
+        // Anthropic reports cached and newly cached input apart from
+        // `input_tokens`; Codex counts them all as input.
+        let cached_input_tokens = usage.cache_read_input_tokens.unwrap_or(0);
+        let input_tokens = usage.input_tokens.unwrap_or(0)
+            + cached_input_tokens
+            + usage.cache_creation_input_tokens.unwrap_or(0);
+        let output_tokens = usage.output_tokens.unwrap_or(0);
+        TokenUsage {
+            input_tokens,
+            cached_input_tokens,
+            output_tokens,
+            reasoning_output_tokens: 0,
+            total_tokens: input_tokens + output_tokens,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorBody {
+    #[serde(rename = "type", default)]
+    kind: String,
+    #[serde(default)]
+    message: String,
+}
+
+/// A content block being streamed.
+enum Block {
+    Text(String),
+    Thinking(String),
+    ToolUse {
+        id: String,
+        name: String,
+        /// `input` of `content_block_start`, used when no deltas follow.
+        input: Value,
+        partial_json: String,
+    },
+}
+
+/// Processes Server-Sent Events from the Anthropic Messages streaming API.
+///
+/// Each content block becomes one output item: text blocks an assistant
+/// message, thinking blocks a reasoning item, and `tool_use` blocks a function
+/// call whose arguments are the concatenated `input_json_delta`s. Text and
+/// thinking deltas are forwarded as they arrive. Function calls are held until
+/// `message_stop`, which completes the response with the usage reported along
+/// the way; a `max_tokens` stop reason fails the response instead, since the
+/// last call's arguments may be cut off.
+pub async fn process_anthropic_sse<S>(
+    stream: S,
+    tx_event: mpsc::Sender<Result<ResponseEvent, ApiError>>,
+    idle_timeout: Duration,
+    telemetry: Option<Arc<dyn SseTelemetry>>,
+) where
+    S: Stream<Item = Result<bytes::Bytes, codex_client::TransportError>> + Unpin,
+{
+    let mut stream = stream.eventsource();
+    let mut blocks: HashMap<usize, Block> = HashMap::new();
+    let mut response_id = String::new();
+    let mut usage: Option<Usage> = None;
+    let mut function_calls: Vec<ResponseItem> = Vec::new();
+
+    loop {
+        let start = Instant::now();
+        let response = timeout(idle_timeout, stream.next()).await;
+        if let Some(t) = telemetry.as_ref() {
+            t.on_sse_poll(&response, start.elapsed());
+        }
+        let sse = match response {
+            Ok(Some(Ok(sse))) => sse,
+            Ok(Some(Err(e))) => {
+                let _ = tx_event.send(Err(ApiError::Stream(e.to_string()))).await;
+                return;
+            }
+            Ok(None) => {
+                let _ = tx_event
+                    .send(Err(ApiError::Stream(
+                        "stream closed before message_stop".into(),
+                    )))
+                    .await;
+                return;
+            }
+            Err(_) => {
+                let _ = tx_event
+                    .send(Err(ApiError::Stream("idle timeout waiting for SSE".into())))
+                    .await;
+                return;
+            }
+        };
+
+        trace!("SSE event: {}", sse.data);
+
+        let data = sse.data.trim();
+        if data.is_empty() {
+            continue;
+        }
+        let event: AnthropicEvent = match serde_json::from_str(data) {
+            Ok(event) => event,
+            Err(err) => {
+                debug!("Failed to parse Anthropic SSE event: {err}, data: {data}");
+                continue;
+            }
+        };
+
+        match event {
+            AnthropicEvent::MessageStart { message } => {
+                response_id = message.id;
+                if let Some(start_usage) = message.usage {
+                    usage.get_or_insert_default().update(start_usage);
+                }
+                send(&tx_event, ResponseEvent::Created).await;
+            }
+            AnthropicEvent::ContentBlockStart {
+                index,
+                content_block,
+            } => match content_block {
+                ContentBlock::Text { text } => {
+                    send(
+                        &tx_event,
+                        ResponseEvent::OutputItemAdded(assistant_message(String::new())),
+                    )
+                    .await;
+                    if !text.is_empty() {
+                        send(&tx_event, ResponseEvent::OutputTextDelta(text.clone())).await;
+                    }
+                    blocks.insert(index, Block::Text(text));
+                }
+                ContentBlock::Thinking { thinking } => {
+                    send(
+                        &tx_event,
+                        ResponseEvent::OutputItemAdded(reasoning(String::new())),
+                    )
+                    .await;
+                    if !thinking.is_empty() {
+                        send(
+                            &tx_event,
+                            ResponseEvent::ReasoningContentDelta {
+                                delta: thinking.clone(),
+                                content_index: 0,
+                            },
+                        )
+                        .await;
+                    }
+                    blocks.insert(index, Block::Thinking(thinking));
+                }
+                ContentBlock::ToolUse { id, name, input } => {
+                    blocks.insert(
+                        index,
+                        Block::ToolUse {
+                            id,
+                            name,
+                            input,
+                            partial_json: String::new(),
+                        },
+                    );
+                }
+                ContentBlock::Other => {}
+            },
+            AnthropicEvent::ContentBlockDelta { index, delta } => {
+                match (blocks.get_mut(&index), delta) {
+                    (Some(Block::Text(text)), BlockDelta::TextDelta { text: delta }) => {
+                        text.push_str(&delta);
+                        send(&tx_event, ResponseEvent::OutputTextDelta(delta)).await;
+                    }
+                    (Some(Block::Thinking(text)), BlockDelta::ThinkingDelta { thinking }) => {
+                        text.push_str(&thinking);
+                        send(
+                            &tx_event,
+                            ResponseEvent::ReasoningContentDelta {
+                                delta: thinking,
+                                content_index: 0,
+                            },
+                        )
+                        .await;
+                    }
+                    (
+                        Some(Block::ToolUse { partial_json, .. }),
+                        BlockDelta::InputJsonDelta {
+                            partial_json: delta,
+                        },
+                    ) => partial_json.push_str(&delta),
+                    _ => {}
+                }
+            }
+            AnthropicEvent::ContentBlockStop { index } => {
+                let item = match blocks.remove(&index) {
+                    Some(Block::Text(text)) => assistant_message(text),
+                    Some(Block::Thinking(text)) => reasoning(text),
+                    Some(Block::ToolUse {
+                        id,
+                        name,
+                        input,
+                        partial_json,
+                    }) => {
+                        function_calls.push(ResponseItem::FunctionCall {
+                            id: None,
+                            name,
+                            arguments: if partial_json.is_empty() {
+                                tool_input_json(input)
+                            } else {
+                                partial_json
+                            },
+                            call_id: id,
+                        });
+                        continue;
+                    }
+                    None => continue,
+                };
+                send(&tx_event, ResponseEvent::OutputItemDone(item)).await;
+            }
+            AnthropicEvent::MessageDelta {
+                delta,
+                usage: delta_usage,
+            } => {
+                if let Some(delta_usage) = delta_usage {
+                    usage.get_or_insert_default().update(delta_usage);
+                }
+                if let Some(MessageDeltaBody {
+                    stop_reason: Some(reason),
+                }) = delta
+                    && reason == "max_tokens"
+                {
+                    let _ = tx_event
+                        .send(Err(ApiError::Stream(format!(
+                            "Incomplete response returned, reason: {reason}"
+                        ))))
+                        .await;
+                    return;
+                }
+            }
+            AnthropicEvent::MessageStop => {
+                for call in function_calls.drain(..) {
+                    send(&tx_event, ResponseEvent::OutputItemDone(call)).await;
+                }
+                send(
+                    &tx_event,
+                    ResponseEvent::Completed {
+                        response_id,
+                        token_usage: usage.as_ref().map(TokenUsage::from),
+                    },
+                )
+                .await;
+                return;
+            }
+            AnthropicEvent::Error { error } => {
+                let err = match error.kind.as_str() {
+                    "overloaded_error" | "api_error" => ApiError::Retryable {
+                        message: error.message,
+                        delay: None,
+                    },
+                    _ => ApiError::Stream(format!("{}: {}", error.kind, error.message)),
+                };
+                let _ = tx_event.send(Err(err)).await;
+                return;
+            }
+            AnthropicEvent::Other => {}
+        }
+    }
+}
+
+async fn send(tx_event: &mpsc::Sender<Result<ResponseEvent, ApiError>>, event: ResponseEvent) {
+    let _ = tx_event.send(Ok(event)).await;
+}
+
+fn assistant_message(text: String) -> ResponseItem {
+    ResponseItem::Message {
+        id: None,
+        role: "assistant".to_string(),
+        content: if text.is_empty() {
+            Vec::new()
+        } else {
+            vec![ContentItem::OutputText { text }]
+        },
+        end_turn: None,
+    }
+}
+
+fn reasoning(text: String) -> ResponseItem {
+    ResponseItem::Reasoning {
+        id: String::new(),
+        summary: Vec::new(),
+        content: Some(if text.is_empty() {
+            Vec::new()
+        } else {
+            vec![ReasoningItemContent::ReasoningText { text }]
+        }),
+        encrypted_content: None,
+    }
+}
+
+fn tool_input_json(input: Value) -> String {
+    match input {
+        Value::Null => "{}".to_string(),
+        input => input.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+    use futures::TryStreamExt;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tokio_util::io::ReaderStream;
+
+    fn build_body(events: &[Value]) -> String {
+        let mut body = String::new();
+        for e in events {
+            let kind = e["type"].as_str().unwrap_or("message");
+            body.push_str(&format!("event: {kind}\ndata: {e}\n\n"));
+        }
+        body
+    }
+
+    async fn collect_events(body: &str) -> Vec<Result<ResponseEvent, ApiError>> {
+        let reader = ReaderStream::new(std::io::Cursor::new(body.to_string()))
+            .map_err(|err| codex_client::TransportError::Network(err.to_string()));
+        let (tx, mut rx) = mpsc::channel::<Result<ResponseEvent, ApiError>>(16);
+        tokio::spawn(process_anthropic_sse(
+            reader,
+            tx,
+            Duration::from_millis(1000),
+            None,
+        ));
+
+        let mut out = Vec::new();
+        while let Some(event) = rx.recv().await {
+            out.push(event);
+        }
+        out
+    }
+
+    #[tokio::test]
+    async fn streams_text_and_tool_use_blocks() {
+        let body = build_body(&[
+            json!({"type": "message_start", "message": {"id": "msg_1", "usage": {
+                "input_tokens": 10, "cache_read_input_tokens": 5, "output_tokens": 1,
+            }}}),
+            json!({"type": "content_block_start", "index": 0,
+                "content_block": {"type": "text", "text": ""}}),
+            json!({"type": "ping"}),
+            json!({"type": "content_block_delta", "index": 0,
+                "delta": {"type": "text_delta", "text": "Reading "}}),
+            json!({"type": "content_block_delta", "index": 0,
+                "delta": {"type": "text_delta", "text": "it."}}),
+            json!({"type": "content_block_stop", "index": 0}),
+            json!({"type": "content_block_start", "index": 1,
+                "content_block": {"type": "tool_use", "id": "toolu_1", "name": "read_file", "input": {}}}),
+            json!({"type": "content_block_delta", "index": 1,
+                "delta": {"type": "input_json_delta", "partial_json": "{\"path\":"}}),
+            json!({"type": "content_block_delta", "index": 1,
+                "delta": {"type": "input_json_delta", "partial_json": "\"a.txt\"}"}}),
+            json!({"type": "content_block_stop", "index": 1}),
+            json!({"type": "message_delta", "delta": {"stop_reason": "tool_use"},
+                "usage": {"output_tokens": 7}}),
+            json!({"type": "message_stop"}),
+        ]);
+
+        let events: Vec<ResponseEvent> = collect_events(&body)
+            .await
+            .into_iter()
+            .map(|event| event.expect("event"))
+            .collect();
+
+        assert_matches!(events[0], ResponseEvent::Created);
+        assert_matches!(
+            events[1],
+            ResponseEvent::OutputItemAdded(ResponseItem::Message { .. })
+        );
+        assert_matches!(&events[2], ResponseEvent::OutputTextDelta(delta) if delta == "Reading ");
+        assert_matches!(&events[3], ResponseEvent::OutputTextDelta(delta) if delta == "it.");
+        assert_matches!(
+            &events[4],
+            ResponseEvent::OutputItemDone(item) if *item == assistant_message("Reading it.".to_string())
+        );
+        assert_matches!(
+            &events[5],
+            ResponseEvent::OutputItemDone(ResponseItem::FunctionCall { name, arguments, call_id, .. })
+                if name == "read_file" && arguments == "{\"path\":\"a.txt\"}" && call_id == "toolu_1"
+        );
+        let ResponseEvent::Completed {
+            response_id,
+            token_usage,
+        } = &events[6]
+        else {
+            panic!("expected Completed, got {:?}", events[6]);
+        };
+        assert_eq!(response_id, "msg_1");
+        assert_eq!(
+            token_usage,
+            &Some(TokenUsage {
+                input_tokens: 15,
+                cached_input_tokens: 5,
+                output_tokens: 7,
+                reasoning_output_tokens: 0,
+                total_tokens: 22,
+            })
+        );
+        assert_eq!(events.len(), 7);
+    }
+
+    #[tokio::test]
+    async fn overloaded_errors_are_retryable() {
+        let body = build_body(&[
+            json!({"type": "message_start", "message": {"id": "msg_1"}}),
+            json!({"type": "error", "error": {"type": "overloaded_error", "message": "Overloaded"}}),
+        ]);
+
+        let events = collect_events(&body).await;
+
+        assert_matches!(events[0], Ok(ResponseEvent::Created));
+        assert_matches!(
+            &events[1],
+            Err(ApiError::Retryable { message, delay: None }) if message == "Overloaded"
+        );
+    }
+
+    #[tokio::test]
+    async fn max_tokens_stop_fails_without_emitting_the_cut_off_call() {
+        let body = build_body(&[
+            json!({"type": "message_start", "message": {"id": "msg_1"}}),
+            json!({"type": "content_block_start", "index": 0,
+                "content_block": {"type": "tool_use", "id": "toolu_1", "name": "read_file", "input": {}}}),
+            json!({"type": "content_block_delta", "index": 0,
+                "delta": {"type": "input_json_delta", "partial_json": "{\"path\":\"a."}}),
+            json!({"type": "content_block_stop", "index": 0}),
+            json!({"type": "message_delta", "delta": {"stop_reason": "max_tokens"},
+                "usage": {"output_tokens": 8192}}),
+            json!({"type": "message_stop"}),
+        ]);
+
+        let events = collect_events(&body).await;
+
+        assert_eq!(events.len(), 2);
+        assert_matches!(events[0], Ok(ResponseEvent::Created));
+        assert_matches!(
+            &events[1],
+            Err(ApiError::Stream(message))
+                if message == "Incomplete response returned, reason: max_tokens"
+        );
+    }
+
+    #[tokio::test]
+    async fn stream_ending_without_message_stop_is_an_error() {
+        let body = build_body(&[json!({"type": "message_start", "message": {"id": "msg_1"}})]);
+
+        let events = collect_events(&body).await;
+
+        assert_matches!(events.last(), Some(Err(ApiError::Stream(_))));
+    }
+}
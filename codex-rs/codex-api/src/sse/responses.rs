@@ -22,6 +22,7 @@ use tokio::sync::mpsc;
 use tokio::time::Instant;
 use tokio::time::timeout;
 use tokio_util::io::ReaderStream;
+use tracing::Instrument;
 use tracing::debug;
 use tracing::trace;
 
@@ -44,7 +45,7 @@ pub fn stream_from_fixture(
     let reader = std::io::Cursor::new(content);
     let stream = ReaderStream::new(reader).map_err(|err| TransportError::Network(err.to_string()));
     let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent, ApiError>>(1600);
-    tokio::spawn(process_sse(Box::pin(stream), tx_event, idle_timeout, None));
+    tokio::spawn(process_sse(Box::pin(stream), tx_event, idle_timeout, None).in_current_span());
     Ok(ResponseStream { rx_event })
 }
 
@@ -73,20 +74,23 @@ pub fn spawn_response_stream(
         let _ = turn_state.set(header_value.to_string());
     }
     let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent, ApiError>>(1600);
-    tokio::spawn(async move {
-        if let Some(snapshot) = rate_limits {
-            let _ = tx_event.send(Ok(ResponseEvent::RateLimits(snapshot))).await;
-        }
-        if let Some(etag) = models_etag {
-            let _ = tx_event.send(Ok(ResponseEvent::ModelsEtag(etag))).await;
-        }
-        if reasoning_included {
-            let _ = tx_event
-                .send(Ok(ResponseEvent::ServerReasoningIncluded(true)))
-                .await;
+    tokio::spawn(
+        async move {
+            if let Some(snapshot) = rate_limits {
+                let _ = tx_event.send(Ok(ResponseEvent::RateLimits(snapshot))).await;
+            }
+            if let Some(etag) = models_etag {
+                let _ = tx_event.send(Ok(ResponseEvent::ModelsEtag(etag))).await;
+            }
+            if reasoning_included {
+                let _ = tx_event
+                    .send(Ok(ResponseEvent::ServerReasoningIncluded(true)))
+                    .await;
+            }
+            process_sse(stream_response.bytes, tx_event, idle_timeout, telemetry).await;
         }
-        process_sse(stream_response.bytes, tx_event, idle_timeout, telemetry).await;
-    });
+        .in_current_span(),
+    );
 
     ResponseStream { rx_event }
 }
@@ -195,6 +195,73 @@ data: {"id":"resp-1","output":[{"type":"message","role":"assistant","content":[{
     }
 }
 
+/// Answers the first attempt with a 429 asking to retry after two seconds,
+/// and records when each attempt was made.
+#[derive(Clone, Default)]
+struct ThrottledOnceTransport {
+    attempts: Arc<Mutex<Vec<tokio::time::Instant>>>,
+}
+
+#[async_trait]
+impl HttpTransport for ThrottledOnceTransport {
+    async fn execute(&self, _req: Request) -> Result<Response, TransportError> {
+        Err(TransportError::Build("execute should not run".to_string()))
+    }
+
+    async fn stream(&self, _req: Request) -> Result<StreamResponse, TransportError> {
+        let mut attempts = self
+            .attempts
+            .lock()
+            .unwrap_or_else(|err| panic!("mutex poisoned: {err}"));
+        attempts.push(tokio::time::Instant::now());
+        if attempts.len() == 1 {
+            let mut headers = HeaderMap::new();
+            headers.insert("retry-after", http::HeaderValue::from_static("2"));
+            return Err(TransportError::Http {
+                status: StatusCode::TOO_MANY_REQUESTS,
+                url: None,
+                headers: Some(headers),
+                body: None,
+            });
+        }
+        Ok(StreamResponse {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            bytes: Box::pin(futures::stream::iter(
+                Vec::<Result<Bytes, TransportError>>::new(),
+            )),
+        })
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn retries_after_429_wait_for_the_shared_pause() -> Result<()> {
+    let transport = ThrottledOnceTransport::default();
+    let mut provider = provider("openai", WireApi::Responses);
+    // Limiters are shared per base URL; keep this test's budget to itself.
+    provider.base_url = "https://throttled.example.com/v1".to_string();
+    provider.retry.retry_429 = true;
+    let client = ResponsesClient::new(transport.clone(), provider, NoAuth);
+
+    let _stream = client
+        .stream(
+            serde_json::json!({ "echo": true }),
+            HeaderMap::new(),
+            Compression::None,
+            None,
+        )
+        .await?;
+
+    let attempts = transport
+        .attempts
+        .lock()
+        .unwrap_or_else(|err| panic!("mutex poisoned: {err}"))
+        .clone();
+    assert_eq!(attempts.len(), 2);
+    assert!(attempts[1] - attempts[0] >= Duration::from_secs(2));
+    Ok(())
+}
+
 #[tokio::test]
 async fn chat_client_uses_chat_completions_path_for_chat_wire() -> Result<()> {
     let state = RecordingState::default();
@@ -313,6 +380,7 @@ async fn streaming_client_retries_on_transport_error() -> Result<()> {
         tools: Vec::<Value>::new(),
         parallel_tool_calls: false,
         output_schema: None,
+        max_output_tokens: None,
     };
 
     let options = ResponsesOptions::default();
@@ -33,6 +33,8 @@ pub fn builtin_approval_presets() -> Vec<ApprovalPreset> {
             label: "Default",
             description: "Codex can read and edit files in the current workspace, and run commands. Approval is required to access the internet or edit other files. (Identical to Agent mode)",
             approval: AskForApproval::OnRequest,
+            // Carries no resource limits; the session fills in the configured
+            // `[sandbox_workspace_write]` limits when this preset is applied.
             sandbox: SandboxPolicy::new_workspace_write_policy(),
         },
         ApprovalPreset {
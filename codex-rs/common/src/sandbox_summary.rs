@@ -17,6 +17,7 @@ pub fn summarize_sandbox_policy(sandbox_policy: &SandboxPolicy) -> String {
             network_access,
             exclude_tmpdir_env_var,
             exclude_slash_tmp,
+            ..
         } => {
             let mut summary = "workspace-write".to_string();
 
@@ -74,6 +75,9 @@ mod tests {
             network_access: true,
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
+            max_memory_bytes: None,
+            cpu_rate_percent: None,
+            max_processes: None,
         });
         assert_eq!(
             summary,
@@ -0,0 +1,210 @@
+//! Benchmarks of the agent loop's overhead, measured against a mock model
+//! provider so the numbers do not depend on the network or on a model.
+//!
+//! Run with `cargo bench -p codex-core --features bench --bench agent_loop`.
+//! To compare two commits, save a baseline on the first with
+//! `-- --save-baseline main` and pass `-- --baseline main` on the second.
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use std::path::PathBuf;
+
+use codex_core::RolloutRecorder;
+use codex_core::bench;
+use codex_core::bench::RolloutRecorderParams;
+use codex_core::bench::TruncationPolicy;
+use codex_core::protocol::Event;
+use codex_core::protocol::SandboxPolicy;
+use codex_core::shell::default_user_shell;
+use codex_protocol::ThreadId;
+use codex_protocol::models::BaseInstructions;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::SessionSource;
+use core_test_support::load_default_config_for_test;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::sse;
+use core_test_support::responses::sse_response;
+use core_test_support::responses::start_mock_server;
+use core_test_support::test_codex::test_codex;
+use criterion::BenchmarkId;
+use criterion::Criterion;
+use criterion::Throughput;
+use criterion::criterion_group;
+use criterion::criterion_main;
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::runtime::Runtime;
+use wiremock::Mock;
+use wiremock::matchers::method;
+use wiremock::matchers::path_regex;
+
+/// Sizes of tool output used by the truncation benchmark, in bytes.
+const OUTPUT_SIZES: [usize; 3] = [1024, 64 * 1024, 1024 * 1024];
+
+fn runtime() -> Runtime {
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .expect("tokio runtime")
+}
+
+/// Shell output of roughly `bytes` bytes, in lines like a build log.
+fn tool_output(bytes: usize) -> String {
+    let mut output = String::with_capacity(bytes + 64);
+    let mut line = 0;
+    while output.len() < bytes {
+        output.push_str(&format!("line {line}: compiling crate_{line} v0.1.0\n"));
+        line += 1;
+    }
+    output
+}
+
+fn assistant_message(text: &str) -> ResponseItem {
+    serde_json::from_value(json!({
+        "type": "message",
+        "role": "assistant",
+        "content": [{"type": "output_text", "text": text}],
+    }))
+    .expect("assistant message")
+}
+
+/// A full turn: the user message is submitted, the request is sent to the
+/// mock provider, its stream is parsed and the turn completes.
+fn agent_turn(c: &mut Criterion) {
+    let rt = runtime();
+    let (_server, test) = rt.block_on(async {
+        let server = start_mock_server().await;
+        let body = sse(vec![
+            ev_response_created("resp-1"),
+            ev_assistant_message("msg-1", "done"),
+            ev_completed("resp-1"),
+        ]);
+        Mock::given(method("POST"))
+            .and(path_regex(".*/responses$"))
+            .respond_with(sse_response(body))
+            .mount(&server)
+            .await;
+        let test = test_codex().build(&server).await.expect("build codex");
+        (server, test)
+    });
+
+    c.bench_function("agent_turn", |b| {
+        b.to_async(&rt).iter(|| async {
+            test.submit_turn("say done").await.expect("turn completes");
+        });
+    });
+}
+
+fn event_serialization(c: &mut Criterion) {
+    let events: Vec<Event> = [
+        json!({"id": "1", "msg": {"type": "agent_message_delta", "delta": "Looking at the"}}),
+        json!({"id": "1", "msg": {"type": "agent_message", "message": tool_output(2048)}}),
+        json!({"id": "1", "msg": {"type": "exec_command_output_delta", "call_id": "c1",
+            "stream": "stdout", "chunk": "aGk="}}),
+    ]
+    .into_iter()
+    .map(|value| serde_json::from_value(value).expect("event"))
+    .collect();
+
+    c.bench_function("event_serialization", |b| {
+        b.iter(|| {
+            for event in &events {
+                std::hint::black_box(serde_json::to_string(event).expect("serialize event"));
+            }
+        });
+    });
+}
+
+/// Items queued on the recorder and flushed to disk, as after a tool call.
+fn rollout_write(c: &mut Criterion) {
+    let rt = runtime();
+    let home = TempDir::new().expect("codex home");
+    let recorder = rt.block_on(async {
+        let config = load_default_config_for_test(&home).await;
+        RolloutRecorder::new(
+            &config,
+            RolloutRecorderParams::new(
+                ThreadId::new(),
+                None,
+                SessionSource::Exec,
+                BaseInstructions::default(),
+            ),
+        )
+        .await
+        .expect("rollout recorder")
+    });
+    let items = vec![RolloutItem::ResponseItem(assistant_message(&tool_output(
+        4096,
+    )))];
+
+    c.bench_function("rollout_write", |b| {
+        b.to_async(&rt).iter(|| async {
+            bench::record_rollout_items(&recorder, &items)
+                .await
+                .expect("record items");
+            recorder.flush().await.expect("flush");
+        });
+    });
+}
+
+fn context_rendering(c: &mut Criterion) {
+    let cwd = PathBuf::from("/workspace/project");
+    let shell = default_user_shell();
+
+    c.bench_function("context_rendering", |b| {
+        b.iter(|| {
+            std::hint::black_box(bench::render_environment_context(
+                cwd.clone(),
+                shell.clone(),
+            ))
+        });
+    });
+}
+
+fn truncation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("truncation");
+    for size in OUTPUT_SIZES {
+        let output = tool_output(size);
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("tokens", size), &output, |b, output| {
+            b.iter(|| bench::truncate_text(output, TruncationPolicy::Tokens(10_000)));
+        });
+        group.bench_with_input(BenchmarkId::new("bytes", size), &output, |b, output| {
+            b.iter(|| bench::truncate_text(output, TruncationPolicy::Bytes(10_000)));
+        });
+    }
+    group.finish();
+}
+
+fn sandbox_transform(c: &mut Criterion) {
+    let cwd = PathBuf::from("/workspace/project");
+    let linux_sandbox_exe = PathBuf::from("codex-linux-sandbox");
+    let policy = SandboxPolicy::new_workspace_write_policy();
+    let command = vec![
+        "bash".to_string(),
+        "-lc".to_string(),
+        "cargo test --workspace".to_string(),
+    ];
+
+    c.bench_function("sandbox_transform", |b| {
+        b.iter(|| {
+            std::hint::black_box(
+                bench::sandbox_transform(command.clone(), &cwd, &policy, Some(&linux_sandbox_exe))
+                    .expect("sandbox transform"),
+            )
+        });
+    });
+}
+
+criterion_group!(
+    benches,
+    agent_turn,
+    event_serialization,
+    rollout_write,
+    context_rendering,
+    truncation,
+    sandbox_transform
+);
+criterion_main!(benches);
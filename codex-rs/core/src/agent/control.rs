@@ -2,12 +2,15 @@ use crate::agent::AgentStatus;
 use crate::agent::guards::Guards;
 use crate::error::CodexErr;
 use crate::error::Result as CodexResult;
+use crate::event_subscription::PublishedEvent;
+use crate::protocol::Event;
 use crate::thread_manager::ThreadManagerState;
 use codex_protocol::ThreadId;
 use codex_protocol::protocol::Op;
 use codex_protocol::user_input::UserInput;
 use std::sync::Arc;
 use std::sync::Weak;
+use tokio::sync::broadcast;
 use tokio::sync::watch;
 
 /// Control-plane handle for multi-agent operations.
@@ -121,6 +124,18 @@ impl AgentControl {
         Ok(thread.subscribe_status())
     }
 
+    /// Forward an event emitted by `thread_id` to event subscribers. A no-op
+    /// when the thread is not owned by a [`crate::ThreadManager`].
+    pub(crate) fn publish_event(&self, thread_id: ThreadId, event: &Event) {
+        if let Some(state) = self.manager.upgrade() {
+            state.publish_event(thread_id, event);
+        }
+    }
+
+    pub(crate) fn subscribe_events(&self) -> Option<broadcast::Receiver<PublishedEvent>> {
+        self.manager.upgrade().map(|state| state.subscribe_events())
+    }
+
     fn upgrade(&self) -> CodexResult<Arc<ThreadManagerState>> {
         self.manager
             .upgrade()
@@ -241,6 +256,7 @@ mod tests {
         let status = agent_status_from_event(&EventMsg::Error(ErrorEvent {
             message: "boom".to_string(),
             codex_error_info: None,
+            fingerprint: None,
         }));
 
         let expected = AgentStatus::Errored("boom".to_string());
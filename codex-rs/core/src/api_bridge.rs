@@ -88,7 +88,7 @@ pub(crate) fn map_api_error(err: ApiError) -> CodexErr {
                 CodexErr::Stream(msg, None)
             }
         },
-        ApiError::RateLimit(msg) => CodexErr::Stream(msg, None),
+        ApiError::RateLimit(msg) => CodexErr::ProviderRateLimited(msg),
     }
 }
 
@@ -1,11 +1,18 @@
+use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::function_tool::FunctionCallError;
 use crate::protocol::FileChange;
+use crate::protocol::FileMode;
 use crate::safety::SafetyCheck;
 use crate::safety::assess_patch_safety;
+use crate::safety::match_patch_approval_rule;
+use crate::tools::context::SharedTurnDiffTracker;
 use crate::tools::sandboxing::ExecApprovalRequirement;
 use codex_apply_patch::ApplyPatchAction;
 use codex_apply_patch::ApplyPatchFileChange;
+use codex_apply_patch::FileMergeConflict;
+use codex_protocol::approvals::ApprovalRuleDecision;
+use codex_protocol::protocol::AskForApproval;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
@@ -37,6 +44,54 @@ pub(crate) async fn apply_patch(
     turn_context: &TurnContext,
     action: ApplyPatchAction,
 ) -> InternalApplyPatchInvocation {
+    // Path-scoped approval rules govern patch writes like shell commands.
+    let config = turn_context.client.config();
+    if let Some(rule) =
+        match_patch_approval_rule(&config.approval_rules, &action, &turn_context.cwd)
+    {
+        return match rule.decision {
+            ApprovalRuleDecision::Forbidden => {
+                InternalApplyPatchInvocation::Output(Err(FunctionCallError::RespondToModel(
+                    format!("patch rejected: forbidden by approval rule `{}`", rule.name),
+                )))
+            }
+            ApprovalRuleDecision::Prompt
+                if turn_context.approval_policy == AskForApproval::Never =>
+            {
+                InternalApplyPatchInvocation::Output(Err(FunctionCallError::RespondToModel(
+                    format!(
+                        "patch rejected: approval rule `{}` requires approval, which the approval settings do not allow",
+                        rule.name
+                    ),
+                )))
+            }
+            ApprovalRuleDecision::Prompt => {
+                InternalApplyPatchInvocation::DelegateToExec(ApplyPatchExec {
+                    action,
+                    auto_approved: false,
+                    exec_approval_requirement: ExecApprovalRequirement::NeedsApproval {
+                        reason: Some(format!(
+                            "patch requires approval by approval rule `{}`",
+                            rule.name
+                        )),
+                        proposed_execpolicy_amendment: None,
+                        matched_approval_rule: Some(rule),
+                    },
+                })
+            }
+            ApprovalRuleDecision::Allow => {
+                InternalApplyPatchInvocation::DelegateToExec(ApplyPatchExec {
+                    action,
+                    auto_approved: true,
+                    exec_approval_requirement: ExecApprovalRequirement::Skip {
+                        bypass_sandbox: false,
+                        proposed_execpolicy_amendment: None,
+                    },
+                })
+            }
+        };
+    }
+
     match assess_patch_safety(
         &action,
         turn_context.approval_policy,
@@ -64,6 +119,7 @@ pub(crate) async fn apply_patch(
                 exec_approval_requirement: ExecApprovalRequirement::NeedsApproval {
                     reason: None,
                     proposed_execpolicy_amendment: None,
+                    matched_approval_rule: None,
                 },
             })
         }
@@ -80,8 +136,9 @@ pub(crate) fn convert_apply_patch_to_protocol(
     let mut result = HashMap::with_capacity(changes.len());
     for (path, change) in changes {
         let protocol_change = match change {
-            ApplyPatchFileChange::Add { content } => FileChange::Add {
+            ApplyPatchFileChange::Add { content, mode } => FileChange::Add {
                 content: content.clone(),
+                mode: mode.map(convert_file_mode),
             },
             ApplyPatchFileChange::Delete { content } => FileChange::Delete {
                 content: content.clone(),
@@ -90,9 +147,14 @@ pub(crate) fn convert_apply_patch_to_protocol(
                 unified_diff,
                 move_path,
                 new_content: _new_content,
+                mode,
             } => FileChange::Update {
                 unified_diff: unified_diff.clone(),
                 move_path: move_path.clone(),
+                mode: mode.map(convert_file_mode),
+            },
+            ApplyPatchFileChange::Symlink { target } => FileChange::Symlink {
+                target: target.clone(),
             },
         };
         result.insert(path.clone(), protocol_change);
@@ -100,6 +162,106 @@ pub(crate) fn convert_apply_patch_to_protocol(
     result
 }
 
+fn convert_file_mode(mode: codex_apply_patch::FileMode) -> FileMode {
+    match mode {
+        codex_apply_patch::FileMode::Regular => FileMode::Regular,
+        codex_apply_patch::FileMode::Executable => FileMode::Executable,
+    }
+}
+
+/// Snapshot bookkeeping to perform once a patch has been applied: the new
+/// contents become what the model last saw, and deleted files are forgotten.
+pub(crate) fn read_snapshot_updates(action: &ApplyPatchAction) -> Vec<(PathBuf, Option<String>)> {
+    let mut updates = Vec::new();
+    for (path, change) in action.changes() {
+        match change {
+            ApplyPatchFileChange::Add { content, .. } => {
+                updates.push((path.clone(), Some(content.clone())));
+            }
+            ApplyPatchFileChange::Delete { .. } | ApplyPatchFileChange::Symlink { .. } => {
+                updates.push((path.clone(), None));
+            }
+            ApplyPatchFileChange::Update {
+                move_path,
+                new_content,
+                ..
+            } => match move_path {
+                Some(dest) => {
+                    updates.push((path.clone(), None));
+                    updates.push((dest.clone(), Some(new_content.clone())));
+                }
+                None => updates.push((path.clone(), Some(new_content.clone()))),
+            },
+        }
+    }
+    updates
+}
+
+pub(crate) async fn apply_read_snapshot_updates(
+    session: &Session,
+    tracker: &SharedTurnDiffTracker,
+    updates: Vec<(PathBuf, Option<String>)>,
+) {
+    for (path, contents) in &updates {
+        match contents {
+            Some(contents) => {
+                session
+                    .record_file_read(path.clone(), contents.as_bytes())
+                    .await;
+            }
+            None => session.forget_file_read(path).await,
+        }
+    }
+    let mut tracker = tracker.lock().await;
+    for (path, contents) in updates {
+        match contents {
+            Some(contents) => tracker.record_read_snapshot(path, contents),
+            None => tracker.clear_read_snapshot(&path),
+        }
+    }
+}
+
+pub(crate) fn format_merge_notice(merged_paths: &[PathBuf]) -> String {
+    let paths = merged_paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "Note: {paths} changed after you last read it; your patch was merged with those changes using a 3-way merge."
+    )
+}
+
+/// Render a conflict report the model can act on without retrying blindly.
+pub(crate) fn format_merge_conflicts(conflicts: &[FileMergeConflict]) -> String {
+    let mut out = String::from(
+        "apply_patch verification failed: the following files changed after you last read them and your patch overlaps with those changes. No files were modified.\n",
+    );
+    for FileMergeConflict {
+        path, conflicts, ..
+    } in conflicts
+    {
+        out.push_str(&format!("\n{}:\n", path.display()));
+        for conflict in conflicts {
+            out.push_str(&format!(
+                "  conflict at line {}\n  current file:\n{}  you read:\n{}  your patch:\n{}",
+                conflict.current_start_line,
+                indent_lines(&conflict.current),
+                indent_lines(&conflict.base),
+                indent_lines(&conflict.patched),
+            ));
+        }
+    }
+    out.push_str(
+        "\nRe-read the affected files and regenerate the patch against their current contents.",
+    );
+    out
+}
+
+fn indent_lines(lines: &[String]) -> String {
+    lines.iter().map(|line| format!("    {line}\n")).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -119,8 +281,30 @@ mod tests {
         assert_eq!(
             got.get(&p),
             Some(&FileChange::Add {
-                content: "hello".to_string()
+                content: "hello".to_string(),
+                mode: None,
             })
         );
     }
+
+    #[test]
+    fn format_merge_conflicts_lists_each_region() {
+        let conflicts = vec![FileMergeConflict {
+            path: PathBuf::from("/repo/a.txt"),
+            conflicts: vec![codex_apply_patch::MergeConflict {
+                current_start_line: 2,
+                base: vec!["two".to_string()],
+                current: vec!["changed".to_string()],
+                patched: vec!["TWO".to_string()],
+            }],
+            content_with_markers: String::new(),
+        }];
+
+        assert_eq!(
+            format_merge_conflicts(&conflicts),
+            "apply_patch verification failed: the following files changed after you last read them and your patch overlaps with those changes. No files were modified.\n\
+\n/repo/a.txt:\n  conflict at line 2\n  current file:\n    changed\n  you read:\n    two\n  your patch:\n    TWO\n\
+\nRe-read the affected files and regenerate the patch against their current contents."
+        );
+    }
 }
@@ -0,0 +1,468 @@
+//! Remembered "always allow" approvals.
+//!
+//! When the user answers an approval prompt with
+//! [`ReviewDecision::ApprovedAlways`](codex_protocol::protocol::ReviewDecision),
+//! the command or the files it was asked about are remembered for the chosen
+//! [`ApprovalScope`]: in memory until the session ends, or in
+//! `$CODEX_HOME/approvals.json` for the current project or for every
+//! project. Matching requests of the same tool are then approved without
+//! asking again.
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::approvals::ApprovalScope;
+use serde::Deserialize;
+use serde::Serialize;
+use time::OffsetDateTime;
+use time::macros::format_description;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::bash::extract_bash_command;
+use crate::bash::parse_shell_lc_plain_commands;
+use crate::git_info::resolve_root_git_project_for_trust;
+
+const APPROVALS_FILE: &str = "approvals.json";
+/// Held exclusively while `approvals.json` is read, changed and rewritten.
+const APPROVALS_LOCK_FILE: &str = "approvals.json.lock";
+
+/// What a remembered approval covers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ApprovalTarget {
+    /// Exactly `command`.
+    Command { command: Vec<String> },
+    /// Commands whose words start with `prefix`, for prefixes the user was
+    /// shown as such.
+    CommandPrefix { prefix: Vec<String> },
+    /// Changes to `path`, or to anything below it.
+    Path { path: PathBuf },
+}
+
+impl ApprovalTarget {
+    /// Target covering exactly `command`, after normalization. A `bash -lc`
+    /// script is kept whole so that approving `curl … | sh` does not approve
+    /// other compositions of the same commands.
+    pub(crate) fn for_command(command: &[String]) -> Vec<Self> {
+        vec![Self::Command {
+            command: normalize_command(command),
+        }]
+    }
+
+    /// Target covering every command that starts with `prefix`.
+    pub(crate) fn for_command_prefix(prefix: &[String]) -> Vec<Self> {
+        vec![Self::CommandPrefix {
+            prefix: prefix.to_vec(),
+        }]
+    }
+
+    /// Targets covering changes to `paths`.
+    pub(crate) fn for_paths(paths: impl IntoIterator<Item = PathBuf>) -> Vec<Self> {
+        paths.into_iter().map(|path| Self::Path { path }).collect()
+    }
+
+    fn covers(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Command { command }, Self::Command { command: requested }) => {
+                command == requested
+            }
+            (Self::CommandPrefix { prefix }, Self::Command { command }) => {
+                command.starts_with(prefix)
+            }
+            (Self::CommandPrefix { prefix }, Self::CommandPrefix { prefix: requested }) => {
+                requested.starts_with(prefix)
+            }
+            (Self::Path { path }, Self::Path { path: changed }) => changed.starts_with(path),
+            _ => false,
+        }
+    }
+}
+
+/// One remembered approval.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RememberedApproval {
+    pub id: String,
+    pub scope: ApprovalScope,
+    /// Root of the project a [`ApprovalScope::Project`] approval applies to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<PathBuf>,
+    /// Tool the approval was given for, e.g. `shell` or `apply_patch`.
+    pub tool: String,
+    pub target: ApprovalTarget,
+    /// When the approval was given (`YYYY-MM-DDTHH:MM:SS.sssZ`).
+    pub created_at: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ApprovalsFile {
+    #[serde(default)]
+    approvals: Vec<RememberedApproval>,
+}
+
+/// The approvals a session remembers, across all three scopes.
+#[derive(Debug)]
+pub struct ApprovalMemory {
+    codex_home: PathBuf,
+    project: PathBuf,
+    session: Mutex<Vec<RememberedApproval>>,
+}
+
+impl ApprovalMemory {
+    /// Memory for a session started in `cwd`; project approvals are keyed by
+    /// the root of its git repository, or by `cwd` outside of one.
+    pub fn new(codex_home: PathBuf, cwd: &Path) -> Self {
+        let project = resolve_root_git_project_for_trust(cwd).unwrap_or_else(|| cwd.to_path_buf());
+        Self {
+            codex_home,
+            project,
+            session: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Whether every target is covered by an approval for `tool` that applies
+    /// here.
+    pub(crate) async fn allows(&self, tool: &str, targets: &[ApprovalTarget]) -> bool {
+        if targets.is_empty() {
+            return false;
+        }
+        let mut approvals = self.session.lock().await.clone();
+        match read_approvals_file(&self.codex_home).await {
+            Ok(file) => approvals.extend(file.approvals),
+            Err(err) => tracing::warn!("failed to read remembered approvals: {err}"),
+        }
+        let applicable: Vec<&RememberedApproval> = approvals
+            .iter()
+            .filter(|approval| approval.tool == tool && self.applies_here(approval))
+            .collect();
+        targets.iter().all(|target| {
+            applicable
+                .iter()
+                .any(|approval| approval.target.covers(target))
+        })
+    }
+
+    /// Remembers `targets` for `scope`.
+    pub(crate) async fn remember(
+        &self,
+        scope: ApprovalScope,
+        tool: &str,
+        targets: Vec<ApprovalTarget>,
+    ) -> io::Result<()> {
+        let created_at = now_timestamp();
+        let approvals = targets.into_iter().map(|target| RememberedApproval {
+            id: Uuid::new_v4().to_string(),
+            scope,
+            project: (scope == ApprovalScope::Project).then(|| self.project.clone()),
+            tool: tool.to_string(),
+            target,
+            created_at: created_at.clone(),
+        });
+        if scope == ApprovalScope::Session {
+            self.session.lock().await.extend(approvals);
+            return Ok(());
+        }
+        let approvals: Vec<RememberedApproval> = approvals.collect();
+        update_approvals_file(&self.codex_home, move |file| {
+            file.approvals.extend(approvals);
+            true
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// This session's approvals followed by the persisted ones.
+    pub async fn list(&self) -> io::Result<Vec<RememberedApproval>> {
+        let mut approvals = self.session.lock().await.clone();
+        approvals.extend(list_persisted_approvals(&self.codex_home).await?);
+        Ok(approvals)
+    }
+
+    /// Forgets the approval `id`, whether it belongs to this session or is
+    /// persisted. Returns whether it existed.
+    pub async fn revoke(&self, id: &str) -> io::Result<bool> {
+        {
+            let mut session = self.session.lock().await;
+            let before = session.len();
+            session.retain(|approval| approval.id != id);
+            if session.len() != before {
+                return Ok(true);
+            }
+        }
+        revoke_persisted_approval(&self.codex_home, id).await
+    }
+
+    fn applies_here(&self, approval: &RememberedApproval) -> bool {
+        match approval.scope {
+            ApprovalScope::Session | ApprovalScope::Global => true,
+            ApprovalScope::Project => approval.project.as_ref() == Some(&self.project),
+        }
+    }
+}
+
+/// The project and global approvals remembered under `codex_home`.
+pub async fn list_persisted_approvals(codex_home: &Path) -> io::Result<Vec<RememberedApproval>> {
+    Ok(read_approvals_file(codex_home).await?.approvals)
+}
+
+/// Forgets the persisted approval `id`. Returns whether it existed.
+pub async fn revoke_persisted_approval(codex_home: &Path, id: &str) -> io::Result<bool> {
+    let id = id.to_string();
+    update_approvals_file(codex_home, move |file| {
+        let before = file.approvals.len();
+        file.approvals.retain(|approval| approval.id != id);
+        file.approvals.len() != before
+    })
+    .await
+}
+
+/// A plain command wrapped in `bash -lc` is unwrapped to its words; any other
+/// shell script is kept whole under a canonical `bash -lc`, and everything
+/// else is kept as is.
+fn normalize_command(command: &[String]) -> Vec<String> {
+    if let Some(commands) = parse_shell_lc_plain_commands(command)
+        && let [plain] = commands.as_slice()
+    {
+        return plain.clone();
+    }
+    match extract_bash_command(command) {
+        Some((_, script)) => vec![
+            "bash".to_string(),
+            "-lc".to_string(),
+            script.trim().to_string(),
+        ],
+        None => command.to_vec(),
+    }
+}
+
+async fn read_approvals_file(codex_home: &Path) -> io::Result<ApprovalsFile> {
+    parse_approvals_file(tokio::fs::read_to_string(codex_home.join(APPROVALS_FILE)).await)
+}
+
+fn parse_approvals_file(contents: io::Result<String>) -> io::Result<ApprovalsFile> {
+    match contents {
+        Ok(contents) => serde_json::from_str(&contents).map_err(io::Error::other),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(ApprovalsFile::default()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Applies `update` to the approvals file under an exclusive lock, so that
+/// concurrent sessions do not drop each other's changes. The file is only
+/// rewritten when `update` returns true, which is also what this returns.
+async fn update_approvals_file(
+    codex_home: &Path,
+    update: impl FnOnce(&mut ApprovalsFile) -> bool + Send + 'static,
+) -> io::Result<bool> {
+    let codex_home = codex_home.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        std::fs::create_dir_all(&codex_home)?;
+        let lock = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(codex_home.join(APPROVALS_LOCK_FILE))?;
+        lock.lock()?;
+        let path = codex_home.join(APPROVALS_FILE);
+        let mut file = parse_approvals_file(std::fs::read_to_string(&path))?;
+        if !update(&mut file) {
+            return Ok(false);
+        }
+        // A fixed name would let two writers clobber each other's temp file.
+        let tmp = path.with_extension(format!("json.{}.tmp", Uuid::new_v4()));
+        let contents = serde_json::to_string_pretty(&file).map_err(io::Error::other)?;
+        std::fs::write(&tmp, contents)?;
+        std::fs::rename(&tmp, &path)?;
+        Ok(true)
+    })
+    .await
+    .map_err(io::Error::other)?
+}
+
+fn now_timestamp() -> String {
+    OffsetDateTime::now_utc()
+        .format(format_description!(
+            "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]Z"
+        ))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn words(command: &[&str]) -> Vec<String> {
+        command.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn commands_cover_only_themselves() {
+        let approved = ApprovalTarget::for_command(&words(&["cargo", "test"]));
+
+        let covers = |command: &[&str]| {
+            ApprovalTarget::for_command(&words(command))
+                .iter()
+                .all(|target| approved.iter().any(|approved| approved.covers(target)))
+        };
+        assert!(covers(&["cargo", "test"]));
+        assert!(covers(&["bash", "-lc", "cargo test"]));
+        assert!(!covers(&["bash", "-lc", "cargo test && cargo test"]));
+        assert!(!covers(&["cargo", "test", "--", "--ignored"]));
+        assert!(!covers(&["bash", "-lc", "cargo test && rm -rf target"]));
+        assert!(!covers(&["cargo", "build"]));
+    }
+
+    #[test]
+    fn scripts_cover_only_the_same_composition() {
+        let approved =
+            ApprovalTarget::for_command(&words(&["bash", "-lc", "curl https://x.sh | sh"]));
+
+        let covers = |command: &[&str]| {
+            ApprovalTarget::for_command(&words(command))
+                .iter()
+                .all(|target| approved.iter().any(|approved| approved.covers(target)))
+        };
+        assert!(covers(&["/bin/bash", "-c", " curl https://x.sh | sh"]));
+        assert!(!covers(&["bash", "-lc", "cat x | sh"]));
+        assert!(!covers(&["sh"]));
+        assert!(!covers(&["curl", "https://x.sh"]));
+    }
+
+    #[test]
+    fn explicit_prefixes_cover_longer_commands() {
+        let approved = ApprovalTarget::for_command_prefix(&words(&["python3"]));
+
+        assert!(approved[0].covers(&ApprovalTarget::Command {
+            command: words(&["python3", "-i", "driver.py"]),
+        }));
+        assert!(!approved[0].covers(&ApprovalTarget::Command {
+            command: words(&["node"]),
+        }));
+    }
+
+    #[test]
+    fn paths_cover_themselves_and_their_children() {
+        let approved = ApprovalTarget::Path {
+            path: PathBuf::from("/repo/src"),
+        };
+
+        assert!(approved.covers(&ApprovalTarget::Path {
+            path: PathBuf::from("/repo/src/lib.rs"),
+        }));
+        assert!(!approved.covers(&ApprovalTarget::Path {
+            path: PathBuf::from("/repo/Cargo.toml"),
+        }));
+    }
+
+    #[tokio::test]
+    async fn project_approvals_only_apply_in_their_project() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let first = TempDir::new().expect("tempdir");
+        let second = TempDir::new().expect("tempdir");
+        let targets = ApprovalTarget::for_command(&words(&["make", "lint"]));
+
+        let here = ApprovalMemory::new(codex_home.path().to_path_buf(), first.path());
+        here.remember(ApprovalScope::Project, "shell", targets.clone())
+            .await
+            .expect("remember");
+
+        let there = ApprovalMemory::new(codex_home.path().to_path_buf(), second.path());
+        assert!(here.allows("shell", &targets).await);
+        assert!(!there.allows("shell", &targets).await);
+    }
+
+    #[tokio::test]
+    async fn approvals_only_apply_to_their_tool() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let cwd = TempDir::new().expect("tempdir");
+        let memory = ApprovalMemory::new(codex_home.path().to_path_buf(), cwd.path());
+        memory
+            .remember(
+                ApprovalScope::Global,
+                "repl",
+                ApprovalTarget::for_command_prefix(&words(&["python3"])),
+            )
+            .await
+            .expect("remember");
+
+        let command = ApprovalTarget::for_command(&words(&["python3", "-c", "print(1)"]));
+        assert!(memory.allows("repl", &command).await);
+        assert!(!memory.allows("shell", &command).await);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_approvals_are_all_persisted() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let cwd = TempDir::new().expect("tempdir");
+        let memory = std::sync::Arc::new(ApprovalMemory::new(
+            codex_home.path().to_path_buf(),
+            cwd.path(),
+        ));
+
+        let tasks: Vec<_> = (0..8)
+            .map(|index| {
+                let memory = std::sync::Arc::clone(&memory);
+                tokio::spawn(async move {
+                    let command = words(&["echo", &index.to_string()]);
+                    memory
+                        .remember(
+                            ApprovalScope::Global,
+                            "shell",
+                            ApprovalTarget::for_command(&command),
+                        )
+                        .await
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.expect("join").expect("remember");
+        }
+
+        let persisted = list_persisted_approvals(codex_home.path())
+            .await
+            .expect("list");
+        assert_eq!(persisted.len(), 8);
+    }
+
+    #[tokio::test]
+    async fn revoked_approvals_are_forgotten() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let cwd = TempDir::new().expect("tempdir");
+        let memory = ApprovalMemory::new(codex_home.path().to_path_buf(), cwd.path());
+        let session = ApprovalTarget::for_paths([cwd.path().join("a.rs")]);
+        let global = ApprovalTarget::for_paths([cwd.path().join("b.rs")]);
+        memory
+            .remember(ApprovalScope::Session, "apply_patch", session.clone())
+            .await
+            .expect("remember");
+        memory
+            .remember(ApprovalScope::Global, "apply_patch", global.clone())
+            .await
+            .expect("remember");
+
+        let listed = memory.list().await.expect("list");
+        assert_eq!(
+            listed
+                .iter()
+                .map(|approval| approval.scope)
+                .collect::<Vec<_>>(),
+            vec![ApprovalScope::Session, ApprovalScope::Global]
+        );
+
+        for approval in &listed {
+            assert!(memory.revoke(&approval.id).await.expect("revoke"));
+        }
+        assert!(!memory.revoke(&listed[0].id).await.expect("revoke"));
+        assert!(!memory.allows("apply_patch", &session).await);
+        assert!(!memory.allows("apply_patch", &global).await);
+        assert_eq!(
+            list_persisted_approvals(codex_home.path())
+                .await
+                .expect("list"),
+            Vec::new()
+        );
+    }
+}
@@ -0,0 +1,728 @@
+//! Files returned by tool calls.
+//!
+//! MCP tools can hand back binary output (a rendered chart, a coverage report)
+//! as embedded blob resources. Instead of keeping those bytes in the
+//! conversation and the rollout, each blob is written to
+//! `$CODEX_HOME/artifacts/<thread id>/` and replaced in the tool result with a
+//! `resource_link` to the stored file. A [`ToolArtifact`] records the file in
+//! the rollout so clients can download it later.
+//!
+//! The store is bounded by [`ArtifactQuota`]: files that would push a thread
+//! over its quota are not saved, and [`collect_garbage`] drops the artifacts
+//! of threads whose rollout is gone before evicting the least recently active
+//! threads until the total fits. Sessions run it at most once per
+//! [`GC_INTERVAL`], as decided by [`claim_garbage_collection`].
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use base64::Engine;
+use codex_protocol::ThreadId;
+use mcp_types::BlobResourceContents;
+use mcp_types::CallToolResult;
+use mcp_types::ContentBlock;
+use mcp_types::EmbeddedResource;
+use mcp_types::EmbeddedResourceResource;
+use mcp_types::ResourceLink;
+use mcp_types::TextContent;
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tokio::io::BufWriter;
+use tracing::info;
+use tracing::warn;
+
+use crate::config::types::Artifacts;
+use crate::protocol::ToolArtifact;
+use crate::rollout::find_archived_thread_path_by_id_str;
+use crate::rollout::find_thread_path_by_id_str;
+
+/// Subdirectory of `CODEX_HOME` holding one artifacts directory per thread.
+pub const ARTIFACTS_SUBDIR: &str = "artifacts";
+
+const DEFAULT_MIME_TYPE: &str = "application/octet-stream";
+
+const DEFAULT_MAX_BYTES_PER_THREAD: u64 = 100 * 1024 * 1024;
+
+const DEFAULT_MAX_TOTAL_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Threads with artifacts written this recently are never collected, so a
+/// session whose rollout has not been flushed yet keeps its files.
+const GC_GRACE_PERIOD: Duration = Duration::from_secs(60 * 60);
+
+/// How often session starts clean up `CODEX_HOME`.
+pub const GC_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Records when `CODEX_HOME` was last cleaned up, as seconds since the epoch.
+const GC_STAMP_FILE: &str = ".last_gc";
+
+/// Size limits applied to the artifacts store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArtifactQuota {
+    pub max_bytes_per_thread: u64,
+    pub max_total_bytes: u64,
+}
+
+impl From<&Artifacts> for ArtifactQuota {
+    fn from(config: &Artifacts) -> Self {
+        Self {
+            max_bytes_per_thread: config
+                .max_bytes_per_thread
+                .unwrap_or(DEFAULT_MAX_BYTES_PER_THREAD),
+            max_total_bytes: config.max_total_bytes.unwrap_or(DEFAULT_MAX_TOTAL_BYTES),
+        }
+    }
+}
+
+/// Directory holding the artifacts of `thread_id`.
+pub fn artifacts_dir(codex_home: &Path, thread_id: &ThreadId) -> PathBuf {
+    codex_home
+        .join(ARTIFACTS_SUBDIR)
+        .join(thread_id.to_string())
+}
+
+/// Loads the metadata of a stored artifact.
+pub async fn load_artifact(
+    codex_home: &Path,
+    thread_id: &ThreadId,
+    artifact_id: &str,
+) -> io::Result<ToolArtifact> {
+    // Ids are generated by `persist_blob_resources`; anything else could
+    // escape the thread's directory.
+    if artifact_id.is_empty() || sanitize(artifact_id) != artifact_id {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid artifact id: {artifact_id}"),
+        ));
+    }
+    let metadata_path = artifacts_dir(codex_home, thread_id).join(format!("{artifact_id}.json"));
+    let metadata = fs::read_to_string(&metadata_path).await?;
+    serde_json::from_str(&metadata).map_err(io::Error::other)
+}
+
+/// Lists the artifacts stored for `thread_id`, oldest first.
+pub async fn list_artifacts(
+    codex_home: &Path,
+    thread_id: &ThreadId,
+) -> io::Result<Vec<ToolArtifact>> {
+    let dir = artifacts_dir(codex_home, thread_id);
+    let mut entries = match fs::read_dir(&dir).await {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let mut artifacts = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        let artifact = match fs::read_to_string(&path).await.map(|metadata| {
+            serde_json::from_str::<ToolArtifact>(&metadata).map_err(io::Error::other)
+        }) {
+            Ok(Ok(artifact)) => artifact,
+            Ok(Err(err)) | Err(err) => {
+                warn!(
+                    "skipping unreadable artifact metadata {}: {err}",
+                    path.display()
+                );
+                continue;
+            }
+        };
+        let modified = entry.metadata().await?.modified()?;
+        artifacts.push((modified, artifact));
+    }
+    artifacts.sort_by(|(a_time, a), (b_time, b)| a_time.cmp(b_time).then_with(|| a.id.cmp(&b.id)));
+    Ok(artifacts
+        .into_iter()
+        .map(|(_, artifact)| artifact)
+        .collect())
+}
+
+/// Copies every artifact of `thread_id` into `destination` and returns the
+/// paths written. Name clashes are resolved by prefixing the artifact id.
+pub async fn export_artifacts(
+    codex_home: &Path,
+    thread_id: &ThreadId,
+    destination: &Path,
+) -> io::Result<Vec<PathBuf>> {
+    let artifacts = list_artifacts(codex_home, thread_id).await?;
+    fs::create_dir_all(destination).await?;
+    let mut exported = Vec::with_capacity(artifacts.len());
+    for artifact in artifacts {
+        let mut target = destination.join(&artifact.name);
+        if fs::try_exists(&target).await? {
+            target = destination.join(format!("{}-{}", artifact.id, artifact.name));
+        }
+        fs::copy(&artifact.path, &target).await?;
+        exported.push(target);
+    }
+    Ok(exported)
+}
+
+/// Returns whether the cleanup of `codex_home` is due, i.e. it has not run in
+/// the last [`GC_INTERVAL`], and if so records that it runs now. Claiming
+/// before the cleanup runs keeps concurrent sessions from all running it.
+pub async fn claim_garbage_collection(codex_home: &Path) -> io::Result<bool> {
+    let stamp = codex_home.join(GC_STAMP_FILE);
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    let last_run = match fs::read_to_string(&stamp).await {
+        Ok(contents) => contents.trim().parse::<u64>().ok(),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+        Err(err) => return Err(err),
+    };
+    if last_run
+        .is_some_and(|last_run| now.saturating_sub(Duration::from_secs(last_run)) < GC_INTERVAL)
+    {
+        return Ok(false);
+    }
+    fs::write(&stamp, now.as_secs().to_string()).await?;
+    Ok(true)
+}
+
+/// Deletes the artifacts of threads whose rollout no longer exists, then the
+/// artifacts of the least recently active threads until the store fits
+/// `quota.max_total_bytes`. Returns the number of bytes freed.
+pub async fn collect_garbage(codex_home: &Path, quota: &ArtifactQuota) -> io::Result<u64> {
+    collect_garbage_with_grace(codex_home, quota, GC_GRACE_PERIOD).await
+}
+
+async fn collect_garbage_with_grace(
+    codex_home: &Path,
+    quota: &ArtifactQuota,
+    grace: Duration,
+) -> io::Result<u64> {
+    let root = codex_home.join(ARTIFACTS_SUBDIR);
+    let mut entries = match fs::read_dir(&root).await {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(err) => return Err(err),
+    };
+    let now = SystemTime::now();
+    let mut freed = 0;
+    // (last modified, size, path) of thread directories that may be evicted.
+    let mut candidates = Vec::new();
+    let mut total = 0;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        let Some(thread_id) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if ThreadId::from_string(thread_id).is_err() || !entry.file_type().await?.is_dir() {
+            continue;
+        }
+        let modified = entry.metadata().await?.modified()?;
+        let size = dir_size(&path).await?;
+        total += size;
+        if now.duration_since(modified).unwrap_or_default() < grace {
+            continue;
+        }
+        let has_rollout = find_thread_path_by_id_str(codex_home, thread_id)
+            .await?
+            .is_some()
+            || find_archived_thread_path_by_id_str(codex_home, thread_id)
+                .await?
+                .is_some();
+        if has_rollout {
+            candidates.push((modified, size, path));
+        } else {
+            fs::remove_dir_all(&path).await?;
+            total -= size;
+            freed += size;
+        }
+    }
+
+    candidates.sort_by_key(|(modified, _, _)| *modified);
+    for (_, size, path) in candidates {
+        if total <= quota.max_total_bytes {
+            break;
+        }
+        fs::remove_dir_all(&path).await?;
+        total -= size;
+        freed += size;
+    }
+    if freed > 0 {
+        info!("removed {freed} bytes of tool artifacts");
+    }
+    Ok(freed)
+}
+
+async fn dir_size(dir: &Path) -> io::Result<u64> {
+    let mut entries = match fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(err) => return Err(err),
+    };
+    let mut size = 0;
+    while let Some(entry) = entries.next_entry().await? {
+        let metadata = entry.metadata().await?;
+        if metadata.is_file() {
+            size += metadata.len();
+        }
+    }
+    Ok(size)
+}
+
+/// Writes the blob resources in `result` to the thread's artifacts directory
+/// and replaces them with links to the stored files. Blobs that do not fit
+/// the thread's quota are replaced with a note saying so.
+pub(crate) async fn persist_blob_resources(
+    codex_home: &Path,
+    thread_id: &ThreadId,
+    call_id: &str,
+    quota: &ArtifactQuota,
+    result: &mut CallToolResult,
+) -> Vec<ToolArtifact> {
+    let dir = artifacts_dir(codex_home, thread_id);
+    let mut used = dir_size(&dir).await.unwrap_or_default();
+    let mut artifacts = Vec::new();
+    for (index, block) in result.content.iter_mut().enumerate() {
+        let ContentBlock::EmbeddedResource(EmbeddedResource {
+            resource: EmbeddedResourceResource::BlobResourceContents(blob),
+            ..
+        }) = block
+        else {
+            continue;
+        };
+        let bytes = match base64::engine::general_purpose::STANDARD.decode(blob.blob.trim()) {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                warn!("tool call {call_id} returned a blob that is not base64: {err}");
+                continue;
+            }
+        };
+        let size = bytes.len() as u64;
+        if used.saturating_add(size) > quota.max_bytes_per_thread {
+            *block = ContentBlock::TextContent(TextContent {
+                annotations: None,
+                text: format!(
+                    "The tool returned {} ({size} bytes), but it was not saved because this \
+                     thread's artifacts would exceed {} bytes.",
+                    artifact_name(&blob.uri),
+                    quota.max_bytes_per_thread
+                ),
+                r#type: "text".to_string(),
+            });
+            continue;
+        }
+        let id = format!("{}-{index}", sanitize(call_id));
+        match store_blob(&dir, id, blob, &bytes).await {
+            Ok(artifact) => {
+                used += size;
+                *block = ContentBlock::ResourceLink(resource_link(&artifact));
+                artifacts.push(artifact);
+            }
+            Err(err) => warn!("failed to store artifact from tool call {call_id}: {err}"),
+        }
+    }
+    artifacts
+}
+
+/// Saves `bytes` as the artifact `id` of `thread_id`. Returns `None` when the
+/// file does not fit the thread's quota.
+pub(crate) async fn persist_bytes(
+    codex_home: &Path,
+    thread_id: &ThreadId,
+    quota: &ArtifactQuota,
+    id: &str,
+    name: &str,
+    mime_type: &str,
+    bytes: &[u8],
+) -> io::Result<Option<ToolArtifact>> {
+    let dir = artifacts_dir(codex_home, thread_id);
+    let used = dir_size(&dir).await.unwrap_or_default();
+    if used.saturating_add(bytes.len() as u64) > quota.max_bytes_per_thread {
+        return Ok(None);
+    }
+    store_artifact(
+        &dir,
+        sanitize(id),
+        sanitize(name),
+        mime_type.to_string(),
+        bytes,
+    )
+    .await
+    .map(Some)
+}
+
+/// An artifact written as its content is produced, such as the recording of
+/// a terminal session. Writes past the thread's quota are cut off, so the
+/// file keeps the start of the output instead of being dropped.
+pub(crate) struct ArtifactWriter {
+    dir: PathBuf,
+    path: PathBuf,
+    id: String,
+    name: String,
+    mime_type: String,
+    file: BufWriter<fs::File>,
+    written: u64,
+    remaining: u64,
+}
+
+impl ArtifactWriter {
+    /// Creates the file of artifact `id` of `thread_id`. It may hold what is
+    /// left of the thread's quota when it is created.
+    pub(crate) async fn create(
+        codex_home: &Path,
+        thread_id: &ThreadId,
+        quota: &ArtifactQuota,
+        id: &str,
+        name: &str,
+        mime_type: &str,
+    ) -> io::Result<Self> {
+        let dir = artifacts_dir(codex_home, thread_id);
+        let used = dir_size(&dir).await.unwrap_or_default();
+        fs::create_dir_all(&dir).await?;
+        let id = sanitize(id);
+        let name = sanitize(name);
+        let path = dir.join(format!("{id}-{name}"));
+        let file = fs::File::create(&path).await?;
+        Ok(Self {
+            dir,
+            path,
+            id,
+            name,
+            mime_type: mime_type.to_string(),
+            file: BufWriter::new(file),
+            written: 0,
+            remaining: quota.max_bytes_per_thread.saturating_sub(used),
+        })
+    }
+
+    /// Bytes that can still be written before the quota is reached.
+    pub(crate) fn remaining(&self) -> u64 {
+        self.remaining
+    }
+
+    /// Appends `bytes`, cut at the quota. Returns how many were written.
+    pub(crate) async fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        let len = bytes
+            .len()
+            .min(usize::try_from(self.remaining).unwrap_or(usize::MAX));
+        self.file.write_all(&bytes[..len]).await?;
+        self.written += len as u64;
+        self.remaining -= len as u64;
+        Ok(len)
+    }
+
+    /// Flushes the file and records it as an artifact. A file that nothing
+    /// was written to is removed and `None` is returned.
+    pub(crate) async fn finish(mut self) -> io::Result<Option<ToolArtifact>> {
+        self.file.flush().await?;
+        drop(self.file);
+        if self.written == 0 {
+            fs::remove_file(&self.path).await?;
+            return Ok(None);
+        }
+        let artifact = ToolArtifact {
+            id: self.id,
+            name: self.name,
+            mime_type: self.mime_type,
+            size_bytes: self.written,
+            path: self.path,
+        };
+        write_metadata(&self.dir, &artifact).await?;
+        Ok(Some(artifact))
+    }
+}
+
+async fn store_blob(
+    dir: &Path,
+    id: String,
+    blob: &BlobResourceContents,
+    bytes: &[u8],
+) -> io::Result<ToolArtifact> {
+    let mime_type = blob
+        .mime_type
+        .clone()
+        .unwrap_or_else(|| DEFAULT_MIME_TYPE.to_string());
+    store_artifact(dir, id, artifact_name(&blob.uri), mime_type, bytes).await
+}
+
+async fn store_artifact(
+    dir: &Path,
+    id: String,
+    name: String,
+    mime_type: String,
+    bytes: &[u8],
+) -> io::Result<ToolArtifact> {
+    fs::create_dir_all(dir).await?;
+
+    let path = dir.join(format!("{id}-{name}"));
+    fs::write(&path, bytes).await?;
+
+    let artifact = ToolArtifact {
+        id,
+        name,
+        mime_type,
+        size_bytes: bytes.len() as u64,
+        path,
+    };
+    write_metadata(dir, &artifact).await?;
+    Ok(artifact)
+}
+
+async fn write_metadata(dir: &Path, artifact: &ToolArtifact) -> io::Result<()> {
+    let metadata = serde_json::to_string(artifact).map_err(io::Error::other)?;
+    fs::write(dir.join(format!("{}.json", artifact.id)), metadata).await
+}
+
+fn resource_link(artifact: &ToolArtifact) -> ResourceLink {
+    let uri = url::Url::from_file_path(&artifact.path)
+        .map(String::from)
+        .unwrap_or_else(|()| artifact.path.display().to_string());
+    ResourceLink {
+        annotations: None,
+        description: Some(format!("Saved tool output (artifact {})", artifact.id)),
+        mime_type: Some(artifact.mime_type.clone()),
+        name: artifact.name.clone(),
+        size: i64::try_from(artifact.size_bytes).ok(),
+        title: None,
+        r#type: "resource_link".to_string(),
+        uri,
+    }
+}
+
+/// File name for a blob: the last segment of its URI, made safe for disk.
+fn artifact_name(uri: &str) -> String {
+    let path = uri.split(['?', '#']).next().unwrap_or_default();
+    let name = sanitize(path.rsplit('/').next().unwrap_or_default());
+    if name.trim_matches('.').is_empty() {
+        "artifact".to_string()
+    } else {
+        name
+    }
+}
+
+fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const QUOTA: ArtifactQuota = ArtifactQuota {
+        max_bytes_per_thread: 1024,
+        max_total_bytes: 4096,
+    };
+
+    fn blob_block(uri: &str, mime_type: Option<&str>, data: &[u8]) -> ContentBlock {
+        ContentBlock::EmbeddedResource(EmbeddedResource {
+            annotations: None,
+            resource: EmbeddedResourceResource::BlobResourceContents(BlobResourceContents {
+                blob: base64::engine::general_purpose::STANDARD.encode(data),
+                mime_type: mime_type.map(str::to_string),
+                uri: uri.to_string(),
+            }),
+            r#type: "resource".to_string(),
+        })
+    }
+
+    #[tokio::test]
+    async fn garbage_collection_runs_once_per_interval() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let claims = [
+            claim_garbage_collection(codex_home.path())
+                .await
+                .expect("claim"),
+            claim_garbage_collection(codex_home.path())
+                .await
+                .expect("claim"),
+        ];
+        assert_eq!(claims, [true, false]);
+
+        let two_days_ago = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("time")
+            .saturating_sub(GC_INTERVAL * 2);
+        std::fs::write(
+            codex_home.path().join(GC_STAMP_FILE),
+            two_days_ago.as_secs().to_string(),
+        )
+        .expect("write stamp");
+        let claims = [
+            claim_garbage_collection(codex_home.path())
+                .await
+                .expect("claim"),
+            claim_garbage_collection(codex_home.path())
+                .await
+                .expect("claim"),
+        ];
+        assert_eq!(claims, [true, false]);
+    }
+
+    #[test]
+    fn artifact_names_come_from_the_uri() {
+        assert_eq!(artifact_name("file:///tmp/chart.png?v=2"), "chart.png");
+        assert_eq!(artifact_name("report://coverage/index html"), "index_html");
+        assert_eq!(artifact_name("blob://.."), "artifact");
+    }
+
+    #[tokio::test]
+    async fn blobs_are_stored_and_replaced_with_links() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let thread_id = ThreadId::new();
+        let text = ContentBlock::TextContent(TextContent {
+            annotations: None,
+            text: "rendered".to_string(),
+            r#type: "text".to_string(),
+        });
+        let mut result = CallToolResult {
+            content: vec![
+                text.clone(),
+                blob_block("chart://out/chart.png", Some("image/png"), b"\x89PNG"),
+            ],
+            is_error: None,
+            structured_content: None,
+        };
+
+        let artifacts =
+            persist_blob_resources(codex_home.path(), &thread_id, "call/1", &QUOTA, &mut result)
+                .await;
+
+        assert_eq!(artifacts.len(), 1);
+        let artifact = &artifacts[0];
+        assert_eq!(artifact.id, "call_1-1");
+        assert_eq!(artifact.name, "chart.png");
+        assert_eq!(artifact.mime_type, "image/png");
+        assert_eq!(artifact.size_bytes, 4);
+        assert_eq!(std::fs::read(&artifact.path).expect("read"), b"\x89PNG");
+        assert_eq!(result.content[0], text);
+        let ContentBlock::ResourceLink(link) = &result.content[1] else {
+            panic!("expected a resource link, got {:?}", result.content[1]);
+        };
+        assert_eq!(link.name, "chart.png");
+        assert_eq!(link.size, Some(4));
+
+        let loaded = load_artifact(codex_home.path(), &thread_id, &artifact.id)
+            .await
+            .expect("load");
+        assert_eq!(&loaded, artifact);
+        assert!(
+            load_artifact(codex_home.path(), &thread_id, "../secret")
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn blobs_over_the_thread_quota_are_not_saved() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let thread_id = ThreadId::new();
+        let mut result = CallToolResult {
+            content: vec![
+                blob_block("file:///a.bin", None, &[0; 800]),
+                blob_block("file:///b.bin", None, &[0; 800]),
+            ],
+            is_error: None,
+            structured_content: None,
+        };
+
+        let artifacts =
+            persist_blob_resources(codex_home.path(), &thread_id, "call", &QUOTA, &mut result)
+                .await;
+
+        assert_eq!(artifacts.len(), 1);
+        assert_eq!(artifacts[0].mime_type, DEFAULT_MIME_TYPE);
+        let ContentBlock::TextContent(note) = &result.content[1] else {
+            panic!("expected a note, got {:?}", result.content[1]);
+        };
+        assert!(note.text.contains("b.bin (800 bytes)"), "{}", note.text);
+        assert_eq!(
+            list_artifacts(codex_home.path(), &thread_id)
+                .await
+                .expect("list"),
+            artifacts
+        );
+    }
+
+    #[tokio::test]
+    async fn export_copies_artifacts_under_their_names() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let destination = tempfile::tempdir().expect("tempdir");
+        let thread_id = ThreadId::new();
+        let mut result = CallToolResult {
+            content: vec![
+                blob_block("file:///out/report.txt", Some("text/plain"), b"first"),
+                blob_block("file:///other/report.txt", Some("text/plain"), b"second"),
+            ],
+            is_error: None,
+            structured_content: None,
+        };
+        persist_blob_resources(codex_home.path(), &thread_id, "call", &QUOTA, &mut result).await;
+
+        let exported = export_artifacts(codex_home.path(), &thread_id, destination.path())
+            .await
+            .expect("export");
+
+        let mut contents: Vec<(String, String)> = exported
+            .iter()
+            .map(|path| {
+                (
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                    std::fs::read_to_string(path).expect("read"),
+                )
+            })
+            .collect();
+        contents.sort();
+        assert_eq!(contents.len(), 2);
+        assert!(contents.iter().any(|(name, _)| name == "report.txt"));
+        let mut data: Vec<&str> = contents.iter().map(|(_, data)| data.as_str()).collect();
+        data.sort();
+        assert_eq!(data, vec!["first", "second"]);
+    }
+
+    #[tokio::test]
+    async fn garbage_collection_removes_orphans_and_enforces_total() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let orphan = ThreadId::new();
+        let kept = ThreadId::new();
+        for thread_id in [&orphan, &kept] {
+            let dir = artifacts_dir(codex_home.path(), thread_id);
+            std::fs::create_dir_all(&dir).expect("mkdir");
+            std::fs::write(dir.join("a-out.bin"), [0; 100]).expect("write");
+        }
+        let rollout_dir = codex_home.path().join("sessions/2025/01/01");
+        std::fs::create_dir_all(&rollout_dir).expect("mkdir");
+        std::fs::write(
+            rollout_dir.join(format!("rollout-2025-01-01T00-00-00-{kept}.jsonl")),
+            "",
+        )
+        .expect("write");
+
+        let freed = collect_garbage_with_grace(codex_home.path(), &QUOTA, Duration::ZERO)
+            .await
+            .expect("gc");
+        assert_eq!(freed, 100);
+        assert!(!artifacts_dir(codex_home.path(), &orphan).exists());
+        assert!(artifacts_dir(codex_home.path(), &kept).exists());
+
+        let tight = ArtifactQuota {
+            max_total_bytes: 50,
+            ..QUOTA
+        };
+        let freed = collect_garbage_with_grace(codex_home.path(), &tight, Duration::ZERO)
+            .await
+            .expect("gc");
+        assert_eq!(freed, 100);
+        assert!(!artifacts_dir(codex_home.path(), &kept).exists());
+    }
+}
@@ -0,0 +1,430 @@
+//! Background compaction: summarizing older turns while the user is idle.
+//!
+//! With the `background_compaction` feature, once a turn finishes and the
+//! session stays idle for `[background_compaction] idle_secs`, the turns
+//! before the most recent `keep_recent_turns` are summarized from a snapshot
+//! of the history, provided it has grown past `start_percent` of the
+//! auto-compact limit. The summary is kept warm rather than applied: when
+//! auto-compaction is due, [`apply_warm_summary`] swaps it in for the turns it
+//! covers instead of asking the model to summarize then. Later idle periods
+//! extend a warm summary with only the turns added since it was prepared.
+//!
+//! The history itself is never touched in the background. The warm summary is
+//! applied only if the history still starts with exactly the items that were
+//! summarized, and the check and the replacement happen under the session
+//! state lock, so a turn sees either the full history or the compacted one,
+//! never a mix. Starting a turn stops a summarization still in progress.
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::CompactedItem;
+use codex_protocol::protocol::CompactionStrategy;
+use codex_protocol::protocol::ContextCompactedEvent;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::RolloutItem;
+use futures::StreamExt;
+use tokio_util::task::AbortOnDropHandle;
+use tracing::Instrument;
+use tracing::info_span;
+use tracing::warn;
+
+use crate::Prompt;
+use crate::client_common::ResponseEvent;
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::compact::SUMMARY_PREFIX;
+use crate::compact::build_compacted_history;
+use crate::compact::collect_user_messages;
+use crate::compact::content_items_to_text;
+use crate::compact::describe_compaction;
+use crate::config::types::BackgroundCompaction;
+use crate::context_manager::is_user_turn_boundary;
+use crate::error::CodexErr;
+use crate::error::Result as CodexResult;
+use crate::features::Feature;
+
+/// A summary of the first `prefix.len()` history items.
+struct WarmSummary {
+    prefix: Vec<ResponseItem>,
+    summary_text: String,
+}
+
+#[derive(Default)]
+struct CompactorState {
+    /// The idle timer, or the summarization it started.
+    pending: Option<AbortOnDropHandle<()>>,
+    warm: Option<Arc<WarmSummary>>,
+}
+
+/// The session's pending background summarization and its result.
+#[derive(Default)]
+pub(crate) struct BackgroundCompactor {
+    state: Mutex<CompactorState>,
+}
+
+impl BackgroundCompactor {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stops the idle timer or a summarization in progress, e.g. because a
+    /// turn started. A summary that is already warm is kept.
+    pub(crate) fn cancel_pending(&self) {
+        self.with_state(|state| state.pending = None);
+    }
+
+    fn set_pending(&self, pending: AbortOnDropHandle<()>) {
+        self.with_state(|state| state.pending = Some(pending));
+    }
+
+    fn warm(&self) -> Option<Arc<WarmSummary>> {
+        self.with_state(|state| state.warm.clone())
+    }
+
+    fn set_warm(&self, warm: WarmSummary) {
+        self.with_state(|state| state.warm = Some(Arc::new(warm)));
+    }
+
+    fn take_warm(&self) -> Option<Arc<WarmSummary>> {
+        self.with_state(|state| state.warm.take())
+    }
+
+    fn with_state<T>(&self, f: impl FnOnce(&mut CompactorState) -> T) -> T {
+        f(&mut self.state.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+/// Summarizes older turns once the session has been idle for
+/// `[background_compaction] idle_secs` after the turn of `turn_context`.
+pub(crate) fn schedule(sess: &Arc<Session>, turn_context: Arc<TurnContext>) {
+    if !sess.enabled(Feature::BackgroundCompaction) {
+        return;
+    }
+    let settings = turn_context.client.config().background_compaction.clone();
+    // The timer must not keep a closed session alive.
+    let weak_session = Arc::downgrade(sess);
+    let span = info_span!("background_compaction", conversation_id = %sess.conversation_id);
+    let handle = tokio::spawn(
+        async move {
+            tokio::time::sleep(settings.idle()).await;
+            let Some(sess) = weak_session.upgrade() else {
+                return;
+            };
+            if let Err(err) = prepare(&sess, &turn_context, &settings).await {
+                warn!("background compaction failed: {err}");
+            }
+        }
+        .instrument(span),
+    );
+    sess.services
+        .background_compaction
+        .set_pending(AbortOnDropHandle::new(handle));
+}
+
+/// Replaces the turns covered by the warm summary with it, if the history
+/// still starts with them. Returns whether the history was compacted.
+pub(crate) async fn apply_warm_summary(sess: &Session, turn_context: &TurnContext) -> bool {
+    sess.enabled(Feature::BackgroundCompaction)
+        && replace_with_warm_summary(sess, turn_context).await
+}
+
+async fn replace_with_warm_summary(sess: &Session, turn_context: &TurnContext) -> bool {
+    let Some(warm) = sess.services.background_compaction.take_warm() else {
+        return false;
+    };
+
+    let tokens_before = sess
+        .clone_history()
+        .await
+        .estimate_token_count(turn_context);
+    let initial_context = sess.build_initial_context(turn_context).await;
+    let mut replacement = build_compacted_history(
+        initial_context,
+        &collect_user_messages(&warm.prefix),
+        &warm.summary_text,
+    );
+    // Required to keep `/undo` available after compaction
+    replacement.extend(
+        warm.prefix
+            .iter()
+            .filter(|item| matches!(item, ResponseItem::GhostSnapshot { .. }))
+            .cloned(),
+    );
+    let Some((items_before, new_history)) =
+        sess.replace_history_prefix(&warm.prefix, replacement).await
+    else {
+        // The history changed since the summary was prepared, e.g. it was
+        // compacted or rolled back.
+        return false;
+    };
+
+    sess.recompute_token_usage(turn_context).await;
+    let tokens_after = sess
+        .clone_history()
+        .await
+        .estimate_token_count(turn_context);
+    let event = describe_compaction(
+        CompactionStrategy::Background,
+        &items_before,
+        &new_history,
+        tokens_before,
+    );
+    sess.persist_rollout_items(&[RolloutItem::Compacted(CompactedItem {
+        message: warm.summary_text.clone(),
+        replacement_history: Some(new_history),
+    })])
+    .await;
+    sess.send_event(
+        turn_context,
+        EventMsg::ContextCompacted(ContextCompactedEvent {
+            tokens_after,
+            ..event
+        }),
+    )
+    .await;
+    true
+}
+
+/// Prepares a warm summary of the history's older turns if it is large
+/// enough and not summarized already.
+async fn prepare(
+    sess: &Session,
+    turn_context: &TurnContext,
+    settings: &BackgroundCompaction,
+) -> CodexResult<()> {
+    let Some(auto_compact_limit) = turn_context
+        .client
+        .get_model_info()
+        .auto_compact_token_limit()
+    else {
+        return Ok(());
+    };
+    let history = sess.clone_history().await;
+    let tokens = history.estimate_token_count(turn_context).unwrap_or(0);
+    if tokens.saturating_mul(100)
+        < auto_compact_limit.saturating_mul(i64::from(settings.start_percent()))
+    {
+        return Ok(());
+    }
+    let Some(prefix_len) = summarized_prefix_len(history.raw_items(), settings.keep_recent_turns())
+    else {
+        return Ok(());
+    };
+    let prefix = &history.raw_items()[..prefix_len];
+    let compactor = &sess.services.background_compaction;
+    let warm = compactor.warm();
+    if warm
+        .as_ref()
+        .is_some_and(|warm| warm.prefix.as_slice() == prefix)
+    {
+        return Ok(());
+    }
+
+    let input = summary_input(warm.as_deref(), prefix);
+    let summary = summarize(sess, turn_context, input).await?;
+    compactor.set_warm(WarmSummary {
+        prefix: prefix.to_vec(),
+        summary_text: format!("{SUMMARY_PREFIX}\n{summary}"),
+    });
+    Ok(())
+}
+
+/// Length of the history prefix to summarize: everything before the last
+/// `keep_recent_turns` user turns, or `None` when that leaves no complete
+/// turn to summarize.
+fn summarized_prefix_len(items: &[ResponseItem], keep_recent_turns: usize) -> Option<usize> {
+    let turn_starts: Vec<usize> = items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| is_user_turn_boundary(item))
+        .map(|(idx, _)| idx)
+        .collect();
+    if turn_starts.len() <= keep_recent_turns {
+        return None;
+    }
+    Some(match keep_recent_turns {
+        0 => items.len(),
+        keep => turn_starts[turn_starts.len() - keep],
+    })
+}
+
+/// The items to summarize `prefix` from. When the warm summary covers the
+/// start of `prefix`, only the turns added since are sent, after that summary,
+/// rather than the whole grown prefix.
+fn summary_input(warm: Option<&WarmSummary>, prefix: &[ResponseItem]) -> Vec<ResponseItem> {
+    let Some(warm) = warm.filter(|warm| prefix.starts_with(&warm.prefix)) else {
+        return prefix.to_vec();
+    };
+    let mut input = vec![ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText {
+            text: warm.summary_text.clone(),
+        }],
+        end_turn: None,
+    }];
+    input.extend_from_slice(&prefix[warm.prefix.len()..]);
+    input
+}
+
+/// Asks the model, without tools, to summarize `items` with the session's
+/// compaction prompt. The request's token usage goes to the session stats
+/// only: it does not reflect the size of the conversation's context.
+async fn summarize(
+    sess: &Session,
+    turn_context: &TurnContext,
+    items: Vec<ResponseItem>,
+) -> CodexResult<String> {
+    let mut prefix_history = sess.clone_history().await;
+    prefix_history.replace(items);
+    let mut input = prefix_history.for_prompt();
+    input.push(ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText {
+            text: turn_context.compact_prompt().to_string(),
+        }],
+        end_turn: None,
+    });
+    let prompt = Prompt {
+        input,
+        base_instructions: sess.get_base_instructions().await,
+        personality: turn_context.personality,
+        ..Default::default()
+    };
+
+    let mut client_session = turn_context.client.new_session();
+    let mut stream = client_session.stream(&prompt).await?;
+    let mut summary = String::new();
+    loop {
+        let Some(event) = stream.next().await else {
+            return Err(CodexErr::Stream(
+                "stream closed before response.completed".into(),
+                None,
+            ));
+        };
+        match event? {
+            ResponseEvent::OutputItemDone(ResponseItem::Message { role, content, .. })
+                if role == "assistant" =>
+            {
+                if let Some(text) = content_items_to_text(&content) {
+                    summary = text;
+                }
+            }
+            ResponseEvent::RateLimits(snapshot) => {
+                sess.update_rate_limits(turn_context, snapshot).await;
+            }
+            ResponseEvent::Completed { token_usage, .. } => {
+                if let Some(token_usage) = token_usage {
+                    sess.record_token_usage_stats(turn_context, &token_usage)
+                        .await;
+                }
+                return Ok(summary.trim().to_string());
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn message(role: &str, text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: role.to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+            end_turn: None,
+        }
+    }
+
+    #[test]
+    fn keeps_the_most_recent_turns() {
+        let items = vec![
+            message("developer", "instructions"),
+            message("user", "first"),
+            message("assistant", "one"),
+            message("user", "second"),
+            message("assistant", "two"),
+            message("user", "third"),
+            message("assistant", "three"),
+        ];
+
+        assert_eq!(summarized_prefix_len(&items, 2), Some(3));
+        assert_eq!(summarized_prefix_len(&items, 1), Some(5));
+        assert_eq!(summarized_prefix_len(&items, 0), Some(7));
+        assert_eq!(summarized_prefix_len(&items, 3), None);
+    }
+
+    #[test]
+    fn resummarizes_only_the_turns_after_the_warm_summary() {
+        let prefix = vec![
+            message("user", "first"),
+            message("assistant", "one"),
+            message("user", "second"),
+            message("assistant", "two"),
+        ];
+        let warm = WarmSummary {
+            prefix: prefix[..2].to_vec(),
+            summary_text: format!("{SUMMARY_PREFIX}\nfirst turn"),
+        };
+
+        assert_eq!(
+            summary_input(Some(&warm), &prefix),
+            vec![
+                message("user", &format!("{SUMMARY_PREFIX}\nfirst turn")),
+                message("user", "second"),
+                message("assistant", "two"),
+            ]
+        );
+
+        // A warm summary of a history that has since been rewritten is not
+        // reused.
+        let stale = WarmSummary {
+            prefix: vec![message("user", "zeroth")],
+            summary_text: format!("{SUMMARY_PREFIX}\nstale"),
+        };
+        assert_eq!(summary_input(Some(&stale), &prefix), prefix);
+        assert_eq!(summary_input(None, &prefix), prefix);
+    }
+
+    #[tokio::test]
+    async fn warm_summary_is_dropped_when_the_history_changed() {
+        let (session, turn_context) = crate::codex::make_session_and_context().await;
+        let history = vec![
+            message("user", "first"),
+            message("assistant", "one"),
+            message("user", "second"),
+        ];
+        session.replace_history(history.clone()).await;
+
+        // Summarized before the history was rewritten.
+        let stale = WarmSummary {
+            prefix: vec![message("user", "zeroth"), message("assistant", "zero")],
+            summary_text: format!("{SUMMARY_PREFIX}\nstale"),
+        };
+        session.services.background_compaction.set_warm(stale);
+        assert!(!replace_with_warm_summary(&session, &turn_context).await);
+        assert_eq!(session.clone_history().await.raw_items(), history);
+
+        session
+            .services
+            .background_compaction
+            .set_warm(WarmSummary {
+                prefix: history[..2].to_vec(),
+                summary_text: format!("{SUMMARY_PREFIX}\nfirst turn"),
+            });
+        assert!(replace_with_warm_summary(&session, &turn_context).await);
+        let compacted = session.clone_history().await.raw_items().to_vec();
+        assert_eq!(compacted.last(), Some(&message("user", "second")));
+        assert!(!compacted.contains(&message("assistant", "one")));
+    }
+}
@@ -0,0 +1,76 @@
+//! Entry points into crate-private hot paths of the agent loop, for the
+//! `agent_loop` benchmarks in `core/benches`.
+//!
+//! Each function runs the same code a turn runs, with the inputs a benchmark
+//! can build from public types. This is not a stable API: it follows whatever
+//! the agent loop does today so results stay comparable across commits.
+//!
+//! Available through the `bench` feature.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::models::ResponseItem;
+use codex_protocol::models::SandboxPermissions;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::SandboxPolicy;
+
+use crate::environment_context::EnvironmentContext;
+use crate::error::CodexErr;
+use crate::exec::ExecExpiration;
+use crate::rollout::RolloutRecorder;
+pub use crate::rollout::RolloutRecorderParams;
+use crate::sandboxing::CommandSpec;
+use crate::sandboxing::ExecEnv;
+use crate::sandboxing::SandboxManager;
+use crate::shell::Shell;
+use crate::tools::sandboxing::SandboxablePreference;
+use crate::truncate;
+pub use crate::truncate::TruncationPolicy;
+
+/// Truncates tool output to `policy` the way it is before it reaches the
+/// model.
+pub fn truncate_text(content: &str, policy: TruncationPolicy) -> String {
+    truncate::truncate_text(content, policy)
+}
+
+/// Renders the environment context item sent at the start of a turn.
+pub fn render_environment_context(cwd: PathBuf, shell: Shell) -> ResponseItem {
+    EnvironmentContext::new(Some(cwd), shell).into()
+}
+
+/// Wraps `command` in the sandbox the platform would pick for `policy`.
+/// `codex_linux_sandbox_exe` is only used to build the command line, so it
+/// need not exist.
+pub fn sandbox_transform(
+    command: Vec<String>,
+    cwd: &Path,
+    policy: &SandboxPolicy,
+    codex_linux_sandbox_exe: Option<&PathBuf>,
+) -> Result<ExecEnv, CodexErr> {
+    let Some((program, args)) = command.split_first() else {
+        return Err(CodexErr::InvalidRequest("empty command".to_string()));
+    };
+    let spec = CommandSpec {
+        program: program.clone(),
+        args: args.to_vec(),
+        cwd: cwd.to_path_buf(),
+        env: HashMap::new(),
+        expiration: ExecExpiration::DefaultTimeout,
+        sandbox_permissions: SandboxPermissions::UseDefault,
+        justification: None,
+    };
+    let manager = SandboxManager::new();
+    let sandbox = manager.select_initial(policy, SandboxablePreference::Auto);
+    Ok(manager.transform(spec, policy, sandbox, cwd, codex_linux_sandbox_exe)?)
+}
+
+/// Queues `items` on `recorder` as a turn does; follow with
+/// [`RolloutRecorder::flush`] to include the write to disk.
+pub async fn record_rollout_items(
+    recorder: &RolloutRecorder,
+    items: &[RolloutItem],
+) -> std::io::Result<()> {
+    recorder.record_items(items).await
+}
@@ -0,0 +1,735 @@
+//! Cross-session index of the files Codex changed.
+//!
+//! Rollouts record the final diff of every turn that changed files. Answering
+//! "which session modified `src/auth.rs` last month" from them means opening
+//! every old session, so [`update_change_index`] scans them once and keeps,
+//! per rollout, which files each turn touched in
+//! `$CODEX_HOME/change_index.json`. Rollouts are append-only, so the index
+//! remembers how far it read each one and only scans lines appended since;
+//! rollouts that were deleted drop out of the index. Sessions update the
+//! index concurrently, so updates hold an exclusive lock on
+//! `change_index.lock` and replace the index atomically. [`query_changes`]
+//! refreshes the index and then answers from it.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::ThreadId;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use serde::Deserialize;
+use serde::Serialize;
+use time::Date;
+use time::macros::format_description;
+use tracing::warn;
+
+use crate::git_info::get_git_repo_root;
+use crate::path_utils::write_atomically;
+use crate::rollout::ARCHIVED_SESSIONS_SUBDIR;
+use crate::rollout::SESSIONS_SUBDIR;
+use crate::session_stats::collect_rollout_files;
+
+const CHANGE_INDEX_FILE: &str = "change_index.json";
+
+const CHANGE_INDEX_LOCK_FILE: &str = "change_index.lock";
+
+/// Bumped when the layout changes; an index with another version is rebuilt.
+const CHANGE_INDEX_VERSION: u32 = 2;
+
+/// One turn of a recorded session that changed a file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileChange {
+    pub thread_id: ThreadId,
+    pub rollout_path: PathBuf,
+    /// 1-based turn number within the session.
+    pub turn: u32,
+    /// When the turn's diff was recorded, as written in the rollout
+    /// (`YYYY-MM-DDTHH:MM:SS.sssZ`).
+    pub timestamp: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ChangeIndex {
+    version: u32,
+    rollouts: BTreeMap<PathBuf, ScannedRollout>,
+}
+
+/// What the index knows about one rollout, kept so that lines appended
+/// later can be scanned without reading the rollout again.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct ScannedRollout {
+    /// Bytes of the rollout scanned so far; always the end of a line.
+    offset: u64,
+    /// From the session meta line.
+    session: Option<ScannedSession>,
+    /// Every turn so far, `None` for turns without a diff. Turns undone by a
+    /// rollback are dropped.
+    turns: Vec<Option<TurnChanges>>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct ScannedSession {
+    thread_id: ThreadId,
+    /// Root of the repository the session ran in, or its cwd outside one.
+    repo: PathBuf,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct TurnChanges {
+    timestamp: String,
+    /// Paths as they appear in the diff (relative to the repository root
+    /// when the file is in one).
+    files: BTreeSet<String>,
+}
+
+impl ScannedRollout {
+    /// Applies the complete lines at the start of `bytes`, which continue the
+    /// rollout at `offset`. A trailing partial line is left for next time.
+    fn scan(&mut self, bytes: &[u8]) {
+        let Some(end) = bytes.iter().rposition(|byte| *byte == b'\n') else {
+            return;
+        };
+        for line in bytes[..end].split(|byte| *byte == b'\n') {
+            self.scan_line(&String::from_utf8_lossy(line));
+        }
+        self.offset += (end + 1) as u64;
+    }
+
+    fn scan_line(&mut self, line: &str) {
+        let Ok(RolloutLine { timestamp, item }) = serde_json::from_str::<RolloutLine>(line) else {
+            return;
+        };
+        match item {
+            RolloutItem::SessionMeta(line) if self.session.is_none() => {
+                let repo = get_git_repo_root(&line.meta.cwd).unwrap_or(line.meta.cwd);
+                self.session = Some(ScannedSession {
+                    thread_id: line.meta.id,
+                    repo,
+                });
+            }
+            RolloutItem::EventMsg(EventMsg::UserMessage(_)) => self.turns.push(None),
+            RolloutItem::EventMsg(EventMsg::TurnDiff(event)) => {
+                // Each turn records its final diff once, when it ends.
+                if self.turns.is_empty() {
+                    self.turns.push(None);
+                }
+                if let Some(turn) = self.turns.last_mut() {
+                    *turn = Some(TurnChanges {
+                        timestamp,
+                        files: changed_files(&event.unified_diff),
+                    });
+                }
+            }
+            RolloutItem::EventMsg(EventMsg::ThreadRolledBack(event)) => {
+                let kept = self
+                    .turns
+                    .len()
+                    .saturating_sub(usize::try_from(event.num_turns).unwrap_or(usize::MAX));
+                self.turns.truncate(kept);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Which changes [`query_changes`] returns.
+#[derive(Debug, Clone, Default)]
+pub struct ChangeQuery {
+    /// Only changes in the repository containing this directory.
+    pub cwd: Option<PathBuf>,
+    /// Only changes to this file: a path relative to the repository root,
+    /// any trailing part of one (`auth.rs`, `src/auth.rs`), or an absolute
+    /// path inside the repository of `cwd`.
+    pub path: Option<String>,
+    /// Only changes made on or after this day.
+    pub since: Option<Date>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangeQueryMatch {
+    pub repo: PathBuf,
+    pub path: String,
+    pub change: FileChange,
+}
+
+/// Brings the index under `codex_home` up to date with the live and archived
+/// rollouts. Returns the number of rollouts that were scanned.
+pub async fn update_change_index(codex_home: &Path) -> io::Result<usize> {
+    let mut rollouts = Vec::new();
+    collect_rollout_files(&codex_home.join(SESSIONS_SUBDIR), &mut rollouts).await?;
+    collect_rollout_files(&codex_home.join(ARCHIVED_SESSIONS_SUBDIR), &mut rollouts).await?;
+    let present: BTreeSet<PathBuf> = rollouts.into_iter().map(|(_, path)| path).collect();
+
+    let codex_home = codex_home.to_path_buf();
+    tokio::task::spawn_blocking(move || update_locked(&codex_home, present))
+        .await
+        .map_err(io::Error::other)?
+}
+
+fn update_locked(codex_home: &Path, present: BTreeSet<PathBuf>) -> io::Result<usize> {
+    std::fs::create_dir_all(codex_home)?;
+    let lock = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(codex_home.join(CHANGE_INDEX_LOCK_FILE))?;
+    // Held until `lock` is dropped at the end of the update.
+    lock.lock()?;
+
+    let index_path = codex_home.join(CHANGE_INDEX_FILE);
+    let mut index = read_index(&index_path)?
+        .filter(|index| index.version == CHANGE_INDEX_VERSION)
+        .unwrap_or_default();
+    index.version = CHANGE_INDEX_VERSION;
+
+    let before = index.rollouts.len();
+    index.rollouts.retain(|path, _| present.contains(path));
+    let mut changed = index.rollouts.len() != before;
+
+    let mut scanned = 0;
+    for path in present {
+        let Ok(metadata) = std::fs::metadata(&path) else {
+            continue;
+        };
+        let rollout = index.rollouts.entry(path.clone()).or_default();
+        if metadata.len() == rollout.offset {
+            continue;
+        }
+        if metadata.len() < rollout.offset {
+            // Not appended to but rewritten; start over.
+            *rollout = ScannedRollout::default();
+        }
+        match read_from(&path, rollout.offset) {
+            Ok(bytes) => rollout.scan(&bytes),
+            Err(err) => {
+                warn!("skipping rollout {} in change index: {err}", path.display());
+                continue;
+            }
+        }
+        scanned += 1;
+        changed = true;
+    }
+
+    if changed {
+        let contents = serde_json::to_string(&index).map_err(io::Error::other)?;
+        write_atomically(&index_path, &contents)?;
+    }
+    Ok(scanned)
+}
+
+fn read_index(index_path: &Path) -> io::Result<Option<ChangeIndex>> {
+    match std::fs::read_to_string(index_path) {
+        Ok(contents) => Ok(serde_json::from_str(&contents).ok()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+fn read_from(path: &Path, offset: u64) -> io::Result<Vec<u8>> {
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(offset))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Updates the index and returns the changes matching `query`, newest first.
+pub async fn query_changes(
+    codex_home: &Path,
+    query: &ChangeQuery,
+) -> io::Result<Vec<ChangeQueryMatch>> {
+    update_change_index(codex_home).await?;
+    let contents = match tokio::fs::read_to_string(codex_home.join(CHANGE_INDEX_FILE)).await {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let index: ChangeIndex = serde_json::from_str(&contents).map_err(io::Error::other)?;
+    Ok(select_changes(index, query))
+}
+
+fn select_changes(index: ChangeIndex, query: &ChangeQuery) -> Vec<ChangeQueryMatch> {
+    let repo_filter = query
+        .cwd
+        .as_ref()
+        .map(|cwd| get_git_repo_root(cwd).unwrap_or_else(|| cwd.clone()));
+    let day_format = format_description!("[year]-[month]-[day]");
+    let mut matches = Vec::new();
+    for (rollout_path, rollout) in index.rollouts {
+        let Some(session) = rollout.session else {
+            continue;
+        };
+        if repo_filter
+            .as_ref()
+            .is_some_and(|filter| *filter != session.repo)
+        {
+            continue;
+        }
+        for (turn, number) in rollout.turns.into_iter().zip(1u32..) {
+            let Some(TurnChanges { timestamp, files }) = turn else {
+                continue;
+            };
+            if let Some(since) = query.since
+                && timestamp
+                    .get(..10)
+                    .and_then(|day| Date::parse(day, day_format).ok())
+                    .is_none_or(|day| day < since)
+            {
+                continue;
+            }
+            for path in files {
+                if let Some(wanted) = &query.path
+                    && !path_matches(&session.repo, &path, wanted)
+                {
+                    continue;
+                }
+                matches.push(ChangeQueryMatch {
+                    repo: session.repo.clone(),
+                    path,
+                    change: FileChange {
+                        thread_id: session.thread_id,
+                        rollout_path: rollout_path.clone(),
+                        turn: number,
+                        timestamp: timestamp.clone(),
+                    },
+                });
+            }
+        }
+    }
+    matches.sort_by(|a, b| {
+        b.change
+            .timestamp
+            .cmp(&a.change.timestamp)
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    matches
+}
+
+fn path_matches(repo: &Path, path: &str, wanted: &str) -> bool {
+    let wanted = Path::new(wanted)
+        .strip_prefix(repo)
+        .map(|relative| relative.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| wanted.to_string());
+    let wanted = wanted.trim_start_matches("./");
+    !wanted.is_empty()
+        && (path == wanted
+            || path
+                .strip_suffix(wanted)
+                .is_some_and(|prefix| prefix.ends_with('/')))
+}
+
+/// Files changed by a unified diff; both sides of a rename count.
+///
+/// Names come from the `---`/`+++` and `rename from`/`rename to` lines of
+/// each file's header. A file without them (a mode change, a pure rename
+/// without hunks in some tools) is named by its `diff --git a/<p> b/<p>`
+/// line, which is only unambiguous when both sides are the same path.
+fn changed_files(diff: &str) -> BTreeSet<String> {
+    let mut files = BTreeSet::new();
+    // Header line of the current file and whether its name was found yet;
+    // `None` once the hunks started.
+    let mut header: Option<(&str, bool)> = None;
+    for line in diff.lines() {
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            name_from_header(header.take(), &mut files);
+            header = Some((rest, false));
+            continue;
+        }
+        if header.is_none() {
+            continue;
+        }
+        if line.starts_with("@@") {
+            name_from_header(header.take(), &mut files);
+            continue;
+        }
+        let name = if let Some(name) = line.strip_prefix("--- ") {
+            diff_side(name, "a/")
+        } else if let Some(name) = line.strip_prefix("+++ ") {
+            diff_side(name, "b/")
+        } else if let Some(name) = line
+            .strip_prefix("rename from ")
+            .or_else(|| line.strip_prefix("rename to "))
+        {
+            unquote(name)
+        } else {
+            None
+        };
+        if let Some(name) = name {
+            files.insert(name);
+            if let Some((_, named)) = header.as_mut() {
+                *named = true;
+            }
+        }
+    }
+    name_from_header(header, &mut files);
+    files
+}
+
+/// Names the file of a header that had no `---`/`+++` or rename lines.
+fn name_from_header(header: Option<(&str, bool)>, files: &mut BTreeSet<String>) {
+    if let Some((line, false)) = header
+        && let Some(path) = symmetric_header_path(line)
+    {
+        files.insert(path);
+    }
+}
+
+/// The path of a `---` or `+++` line, without its `a/` or `b/` prefix;
+/// `None` for `/dev/null`.
+fn diff_side(name: &str, prefix: &str) -> Option<String> {
+    // Git ends names containing spaces with a tab.
+    let name = unquote(name.trim_end_matches('\t'))?;
+    if name == "/dev/null" {
+        return None;
+    }
+    Some(match name.strip_prefix(prefix) {
+        Some(path) => path.to_string(),
+        None => name,
+    })
+}
+
+/// The path of a `diff --git` header whose two sides are the same path,
+/// e.g. `a/dir b/x.rs b/dir b/x.rs`.
+fn symmetric_header_path(header: &str) -> Option<String> {
+    if header.starts_with('"') {
+        let (old, rest) = split_quoted(header)?;
+        let (new, _) = split_quoted(rest.trim_start())?;
+        let old = old.strip_prefix("a/")?;
+        return (new.strip_prefix("b/") == Some(old)).then(|| old.to_string());
+    }
+    let rest = header.strip_prefix("a/")?;
+    // `<p> b/<p>`: the path is whatever precedes the middle " b/".
+    let len = rest.len().checked_sub(3)? / 2;
+    let (old, new) = (rest.get(..len)?, rest.get(len..)?);
+    (new.strip_prefix(" b/") == Some(old)).then(|| old.to_string())
+}
+
+/// Decodes a path git may have C-quoted because of special characters.
+fn unquote(name: &str) -> Option<String> {
+    if name.starts_with('"') {
+        split_quoted(name).map(|(name, _)| name)
+    } else {
+        Some(name.to_string())
+    }
+}
+
+/// Splits a leading C-quoted string off `input`, returning it decoded along
+/// with the rest of `input`.
+fn split_quoted(input: &str) -> Option<(String, &str)> {
+    let bytes = input.as_bytes();
+    if bytes.first() != Some(&b'"') {
+        return None;
+    }
+    let mut decoded = Vec::new();
+    let mut i = 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                return Some((
+                    String::from_utf8_lossy(&decoded).into_owned(),
+                    &input[i + 1..],
+                ));
+            }
+            b'\\' => {
+                let escaped = *bytes.get(i + 1)?;
+                i += 2;
+                match escaped {
+                    b'0'..=b'7' => {
+                        let digits = bytes.get(i - 1..i + 2)?;
+                        let value = std::str::from_utf8(digits)
+                            .ok()
+                            .and_then(|digits| u8::from_str_radix(digits, 8).ok())?;
+                        decoded.push(value);
+                        i += 2;
+                    }
+                    b'n' => decoded.push(b'\n'),
+                    b't' => decoded.push(b'\t'),
+                    b'r' => decoded.push(b'\r'),
+                    b'a' => decoded.push(0x07),
+                    b'b' => decoded.push(0x08),
+                    b'f' => decoded.push(0x0c),
+                    b'v' => decoded.push(0x0b),
+                    other => decoded.push(other),
+                }
+            }
+            byte => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    const THREAD_ID: &str = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+
+    fn rollout_text(lines: &[serde_json::Value]) -> String {
+        lines
+            .iter()
+            .map(serde_json::Value::to_string)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn meta_line() -> serde_json::Value {
+        json!({
+            "timestamp": "2025-06-01T10:00:00.000Z",
+            "type": "session_meta",
+            "payload": {
+                "id": THREAD_ID,
+                "timestamp": "2025-06-01T10:00:00.000Z",
+                "cwd": "/repo",
+                "originator": "test",
+                "cli_version": "0.0.0",
+            },
+        })
+    }
+
+    fn user_line(timestamp: &str) -> serde_json::Value {
+        json!({
+            "timestamp": timestamp,
+            "type": "event_msg",
+            "payload": { "type": "user_message", "message": "go" },
+        })
+    }
+
+    fn diff_line(timestamp: &str, files: &[&str]) -> serde_json::Value {
+        let diff = files
+            .iter()
+            .map(|file| format!("diff --git a/{file} b/{file}\n--- a/{file}\n+++ b/{file}\n"))
+            .collect::<String>();
+        json!({
+            "timestamp": timestamp,
+            "type": "event_msg",
+            "payload": { "type": "turn_diff", "unified_diff": diff },
+        })
+    }
+
+    fn scanned(lines: &[serde_json::Value]) -> ScannedRollout {
+        let mut rollout = ScannedRollout::default();
+        rollout.scan(format!("{}\n", rollout_text(lines)).as_bytes());
+        rollout
+    }
+
+    fn turn(timestamp: &str, files: &[&str]) -> Option<TurnChanges> {
+        Some(TurnChanges {
+            timestamp: timestamp.to_string(),
+            files: files.iter().map(|file| file.to_string()).collect(),
+        })
+    }
+
+    #[test]
+    fn scans_the_diff_of_each_kept_turn() {
+        let lines = [
+            meta_line(),
+            user_line("2025-06-01T10:00:01.000Z"),
+            diff_line("2025-06-01T10:00:03.000Z", &["src/auth.rs", "src/lib.rs"]),
+            user_line("2025-06-02T10:00:00.000Z"),
+            diff_line("2025-06-02T10:00:01.000Z", &["README.md"]),
+            json!({
+                "timestamp": "2025-06-02T10:00:02.000Z",
+                "type": "event_msg",
+                "payload": { "type": "thread_rolled_back", "num_turns": 1 },
+            }),
+            user_line("2025-06-03T10:00:00.000Z"),
+        ];
+        let len = rollout_text(&lines).len() + 1;
+
+        assert_eq!(
+            scanned(&lines),
+            ScannedRollout {
+                offset: len as u64,
+                session: Some(ScannedSession {
+                    thread_id: ThreadId::from_string(THREAD_ID).expect("thread id"),
+                    repo: PathBuf::from("/repo"),
+                }),
+                turns: vec![
+                    turn("2025-06-01T10:00:03.000Z", &["src/auth.rs", "src/lib.rs"]),
+                    None,
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn scanning_continues_after_the_last_complete_line() {
+        let first = format!(
+            "{}\n{}\n",
+            meta_line(),
+            user_line("2025-06-01T10:00:01.000Z")
+        );
+        let diff = diff_line("2025-06-01T10:00:03.000Z", &["src/auth.rs"]).to_string();
+        let (head, tail) = diff.split_at(10);
+        let mut rollout = ScannedRollout::default();
+
+        rollout.scan(format!("{first}{head}").as_bytes());
+        assert_eq!(rollout.offset, first.len() as u64);
+        assert_eq!(rollout.turns, vec![None]);
+
+        rollout.scan(format!("{head}{tail}\n").as_bytes());
+        assert_eq!(
+            rollout.turns,
+            vec![turn("2025-06-01T10:00:03.000Z", &["src/auth.rs"])]
+        );
+        assert_eq!(rollout.offset, (first.len() + diff.len() + 1) as u64);
+    }
+
+    #[tokio::test]
+    async fn update_scans_only_appended_lines() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let day_dir = codex_home.path().join("sessions/2025/06/01");
+        std::fs::create_dir_all(&day_dir).expect("sessions dir");
+        let rollout_path = day_dir.join(format!("rollout-2025-06-01T10-00-00-{THREAD_ID}.jsonl"));
+        let turn_one = rollout_text(&[
+            meta_line(),
+            user_line("2025-06-01T10:00:01.000Z"),
+            diff_line("2025-06-01T10:00:02.000Z", &["src/auth.rs"]),
+        ]);
+        std::fs::write(&rollout_path, format!("{turn_one}\n")).expect("write rollout");
+        assert_eq!(
+            update_change_index(codex_home.path())
+                .await
+                .expect("update"),
+            1
+        );
+        assert_eq!(
+            update_change_index(codex_home.path())
+                .await
+                .expect("update"),
+            0
+        );
+
+        let turn_two = rollout_text(&[
+            user_line("2025-06-02T10:00:00.000Z"),
+            diff_line("2025-06-02T10:00:01.000Z", &["src/auth.rs"]),
+        ]);
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(&rollout_path)
+            .expect("open rollout");
+        std::io::Write::write_all(&mut file, format!("{turn_two}\n").as_bytes())
+            .expect("append rollout");
+
+        let query = ChangeQuery {
+            cwd: None,
+            path: Some("auth.rs".to_string()),
+            since: None,
+        };
+        let thread_id = ThreadId::from_string(THREAD_ID).expect("thread id");
+        let found = |turn: u32, timestamp: &str| ChangeQueryMatch {
+            repo: PathBuf::from("/repo"),
+            path: "src/auth.rs".to_string(),
+            change: FileChange {
+                thread_id,
+                rollout_path: rollout_path.clone(),
+                turn,
+                timestamp: timestamp.to_string(),
+            },
+        };
+        assert_eq!(
+            query_changes(codex_home.path(), &query)
+                .await
+                .expect("query"),
+            vec![
+                found(2, "2025-06-02T10:00:01.000Z"),
+                found(1, "2025-06-01T10:00:02.000Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn renames_count_both_paths() {
+        assert_eq!(
+            changed_files(
+                "diff --git a/old/name.rs b/new/name.rs\n--- a/old/name.rs\n+++ b/new/name.rs\n@@ -1 +1 @@\n"
+            ),
+            BTreeSet::from(["new/name.rs".to_string(), "old/name.rs".to_string()])
+        );
+        assert_eq!(
+            changed_files(
+                "diff --git a/old.rs b/new.rs\nsimilarity index 100%\nrename from old.rs\nrename to new.rs\n"
+            ),
+            BTreeSet::from(["new.rs".to_string(), "old.rs".to_string()])
+        );
+    }
+
+    #[test]
+    fn parses_names_that_contain_the_header_separator() {
+        let diff = "diff --git a/dir b/x.rs b/dir b/x.rs\nold mode 100644\nnew mode 100755\n\
+                    diff --git a/notes b/a.md b/notes b/a.md\nindex 1..2\n--- a/notes b/a.md\t\n\
+                    +++ b/notes b/a.md\t\n@@ -1 +1 @@\n--- a/not/a/file\n+++ b/not/a/file\n\
+                    diff --git \"a/tab\\there.rs\" \"b/tab\\there.rs\"\nnew file mode 100644\n\
+                    --- /dev/null\n+++ \"b/tab\\there.rs\"\n";
+
+        assert_eq!(
+            changed_files(diff),
+            BTreeSet::from([
+                "dir b/x.rs".to_string(),
+                "notes b/a.md".to_string(),
+                "tab\there.rs".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn selects_by_path_suffix_and_day_newest_first() {
+        let thread_id = ThreadId::from_string(THREAD_ID).expect("thread id");
+        let rollout_path = PathBuf::from("/home/.codex/sessions/rollout.jsonl");
+        let index = ChangeIndex {
+            version: CHANGE_INDEX_VERSION,
+            rollouts: BTreeMap::from([(
+                rollout_path.clone(),
+                ScannedRollout {
+                    offset: 0,
+                    session: Some(ScannedSession {
+                        thread_id,
+                        repo: PathBuf::from("/repo"),
+                    }),
+                    turns: vec![
+                        turn("2025-05-01T00:00:00.000Z", &["src/auth.rs"]),
+                        turn("2025-06-11T00:00:00.000Z", &["src/oauth.rs"]),
+                        None,
+                        turn("2025-06-10T00:00:00.000Z", &["src/auth.rs"]),
+                    ],
+                },
+            )]),
+        };
+        let query = ChangeQuery {
+            cwd: None,
+            path: Some("/repo/src/auth.rs".to_string()),
+            since: Some(
+                Date::parse("2025-06-01", format_description!("[year]-[month]-[day]"))
+                    .expect("date"),
+            ),
+        };
+
+        assert_eq!(
+            select_changes(index, &query),
+            vec![ChangeQueryMatch {
+                repo: PathBuf::from("/repo"),
+                path: "src/auth.rs".to_string(),
+                change: FileChange {
+                    thread_id,
+                    rollout_path,
+                    turn: 4,
+                    timestamp: "2025-06-10T00:00:00.000Z".to_string(),
+                },
+            }]
+        );
+    }
+}
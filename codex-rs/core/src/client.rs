@@ -6,6 +6,7 @@ use crate::api_bridge::auth_provider_from_auth;
 use crate::api_bridge::map_api_error;
 use crate::auth::UnauthorizedRecovery;
 use codex_api::AggregateStreamExt;
+use codex_api::AnthropicClient as ApiAnthropicClient;
 use codex_api::ChatClient as ApiChatClient;
 use codex_api::CompactClient as ApiCompactClient;
 use codex_api::CompactionInput as ApiCompactionInput;
@@ -13,6 +14,7 @@ use codex_api::Prompt as ApiPrompt;
 use codex_api::RequestTelemetry;
 use codex_api::ReqwestTransport;
 use codex_api::ResponseAppendWsRequest;
+use codex_api::ResponseCache as ApiResponseCache;
 use codex_api::ResponseCreateWsRequest;
 use codex_api::ResponseStream as ApiResponseStream;
 use codex_api::ResponsesClient as ApiResponsesClient;
@@ -26,6 +28,7 @@ use codex_api::common::Reasoning;
 use codex_api::common::ResponsesWsRequest;
 use codex_api::create_text_param_for_request;
 use codex_api::error::ApiError;
+use codex_api::rate_limits::ProviderRateLimiter;
 use codex_api::requests::responses::Compression;
 use codex_app_server_protocol::AuthMode;
 use codex_otel::OtelManager;
@@ -36,6 +39,8 @@ use codex_protocol::config_types::WebSearchMode;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::openai_models::ModelInfo;
 use codex_protocol::openai_models::ReasoningEffort as ReasoningEffortConfig;
+use codex_protocol::protocol::ProviderEndpointEvent;
+use codex_protocol::protocol::ProviderRateLimitsEvent;
 use codex_protocol::protocol::SessionSource;
 use eventsource_stream::Event;
 use eventsource_stream::EventStreamError;
@@ -47,13 +52,16 @@ use reqwest::StatusCode;
 use serde_json::Value;
 use std::time::Duration;
 use tokio::sync::mpsc;
+use tracing::Instrument;
 use tracing::warn;
 
 use crate::AuthManager;
 use crate::auth::RefreshTokenError;
 use crate::client_common::Prompt;
+use crate::client_common::PromptTokenCount;
 use crate::client_common::ResponseEvent;
 use crate::client_common::ResponseStream;
+use crate::client_common::tools::ToolSpec;
 use crate::config::Config;
 use crate::default_client::build_reqwest_client;
 use crate::error::CodexErr;
@@ -63,6 +71,10 @@ use crate::features::Feature;
 use crate::flags::CODEX_RS_SSE_FIXTURE;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::WireApi;
+use crate::provider_endpoints::EndpointFailover;
+use crate::tokenizer::HeuristicTokenizer;
+use crate::tokenizer::tokenizer_for_model;
+use crate::tools::spec::create_tools_json_for_anthropic_messages_api;
 use crate::tools::spec::create_tools_json_for_chat_completions_api;
 use crate::tools::spec::create_tools_json_for_responses_api;
 
@@ -102,6 +114,8 @@ pub struct ModelClientSession {
     /// keep sending it unchanged between turn requests (e.g., for retries, incremental
     /// appends, or continuation requests), and must not send it between different turns.
     turn_state: Arc<OnceLock<String>>,
+    /// Tokens of the prompt last passed to [`ModelClientSession::stream`].
+    prompt_tokens: Option<PromptTokenCount>,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -138,6 +152,7 @@ impl ModelClient {
             connection: None,
             websocket_last_items: Vec::new(),
             turn_state: Arc::new(OnceLock::new()),
+            prompt_tokens: None,
         }
     }
 }
@@ -190,6 +205,34 @@ impl ModelClient {
         self.state.summary
     }
 
+    /// Request/token budget currently advertised by the provider. The budget
+    /// is shared by every conversation that talks to the same endpoint.
+    pub async fn provider_rate_limits(&self) -> Option<ProviderRateLimitsEvent> {
+        let auth = match self.state.auth_manager.as_ref() {
+            Some(manager) => manager.auth().await,
+            None => None,
+        };
+        let api_provider = self
+            .state
+            .provider
+            .to_api_provider(auth.as_ref().map(|a| a.mode))
+            .ok()?;
+        ProviderRateLimiter::for_provider(&api_provider).budget()
+    }
+
+    /// Endpoint currently used by a provider with `fallback_base_urls`, or
+    /// `None` for providers with a single endpoint.
+    pub async fn provider_endpoint(&self) -> Option<ProviderEndpointEvent> {
+        let auth = match self.state.auth_manager.as_ref() {
+            Some(manager) => manager.auth().await,
+            None => None,
+        };
+        self.state
+            .provider
+            .endpoint_pool(auth.as_ref().map(|a| a.mode))
+            .map(|pool| pool.status())
+    }
+
     pub fn get_auth_manager(&self) -> Option<Arc<AuthManager>> {
         self.state.auth_manager.clone()
     }
@@ -207,15 +250,10 @@ impl ModelClient {
             Some(manager) => manager.auth().await,
             None => None,
         };
-        let api_provider = self
-            .state
-            .provider
-            .to_api_provider(auth.as_ref().map(|a| a.mode))?;
+        let auth_mode = auth.as_ref().map(|a| a.mode);
+        let api_provider = self.state.provider.to_api_provider(auth_mode)?;
         let api_auth = auth_provider_from_auth(auth.clone(), &self.state.provider)?;
-        let transport = ReqwestTransport::new(build_reqwest_client());
-        let request_telemetry = self.build_request_telemetry();
-        let client = ApiCompactClient::new(transport, api_provider, api_auth)
-            .with_telemetry(Some(request_telemetry));
+        let mut failover = EndpointFailover::new(self.state.provider.endpoint_pool(auth_mode));
 
         let instructions = prompt.base_instructions.text.clone();
         let payload = ApiCompactionInput {
@@ -238,20 +276,48 @@ impl ModelClient {
                 extra_headers.insert("x-openai-subagent", val);
             }
         }
-        client
-            .compact_input(&payload, extra_headers)
-            .await
-            .map_err(map_api_error)
+        loop {
+            let mut api_provider = api_provider.clone();
+            failover.apply(&mut api_provider);
+            let transport = ReqwestTransport::new(build_reqwest_client());
+            let request_telemetry = self.build_request_telemetry();
+            let client = ApiCompactClient::new(transport, api_provider, api_auth.clone())
+                .with_telemetry(Some(request_telemetry));
+            match client.compact_input(&payload, extra_headers.clone()).await {
+                Ok(items) => {
+                    failover.record_success();
+                    return Ok(items);
+                }
+                Err(err) if failover.should_fail_over(&err) => continue,
+                Err(err) => return Err(map_api_error(err)),
+            }
+        }
     }
 }
 
 impl ModelClientSession {
+    /// Tokens of the prompt of the last request, counted with the model's
+    /// tokenizer.
+    pub(crate) fn prompt_tokens(&self) -> Option<&PromptTokenCount> {
+        self.prompt_tokens.as_ref()
+    }
+
     /// Streams a single model turn using either the Responses or Chat
     /// Completions wire API, depending on the configured provider.
     ///
     /// For Chat providers, the underlying stream is optionally aggregated
     /// based on the `show_raw_agent_reasoning` flag in the config.
     pub async fn stream(&mut self, prompt: &Prompt) -> Result<ResponseStream> {
+        let prompt_tokens = count_prompt_tokens(prompt, &self.state.model_info.slug).await;
+        // Fail before sending rather than letting the provider reject an
+        // oversized request with a generic 400.
+        if let Some(context_window) = self.state.model_info.context_window
+            && let Some(overflow) = prompt_tokens.overflow(context_window)
+        {
+            return Err(CodexErr::ContextOverflow(overflow));
+        }
+        self.prompt_tokens = Some(prompt_tokens);
+
         match self.state.provider.wire_api {
             WireApi::Responses => self.stream_responses_api(prompt).await,
             WireApi::ResponsesWebsocket => self.stream_responses_websocket(prompt).await,
@@ -270,13 +336,25 @@ impl ModelClientSession {
                     ))
                 }
             }
+            WireApi::AnthropicMessages => {
+                let api_stream = self.stream_anthropic_messages(prompt).await?;
+                Ok(map_response_stream(
+                    api_stream,
+                    self.state.otel_manager.clone(),
+                ))
+            }
         }
     }
 
     fn build_responses_request(&self, prompt: &Prompt) -> Result<ApiPrompt> {
         let instructions = prompt.base_instructions.text.clone();
         let tools_json: Vec<Value> = create_tools_json_for_responses_api(&prompt.tools)?;
-        Ok(build_api_prompt(prompt, instructions, tools_json))
+        Ok(build_api_prompt(
+            prompt,
+            instructions,
+            tools_json,
+            self.state.model_info.max_output_tokens,
+        ))
     }
 
     fn build_responses_options(
@@ -300,11 +378,23 @@ impl ModelClientSession {
             None
         };
 
-        let include = if reasoning.is_some() {
+        let mut include = if reasoning.is_some() {
             vec!["reasoning.encrypted_content".to_string()]
         } else {
             Vec::new()
         };
+        // Provider-side tools only return what they produced when asked to.
+        for tool in &prompt.tools {
+            match tool {
+                ToolSpec::CodeInterpreter { .. } => {
+                    include.push("code_interpreter_call.outputs".to_string());
+                }
+                ToolSpec::FileSearch { .. } => {
+                    include.push("file_search_call.results".to_string());
+                }
+                _ => {}
+            }
+        }
 
         let verbosity = if model_info.support_verbosity {
             self.state
@@ -387,6 +477,7 @@ impl ModelClientSession {
             include: include.clone(),
             prompt_cache_key: prompt_cache_key.clone(),
             text: text.clone(),
+            max_output_tokens: api_prompt.max_output_tokens,
         };
 
         ResponsesWsRequest::ResponseCreate(payload)
@@ -447,27 +538,105 @@ impl ModelClientSession {
         let auth_manager = self.state.auth_manager.clone();
         let instructions = prompt.base_instructions.text.clone();
         let tools_json = create_tools_json_for_chat_completions_api(&prompt.tools)?;
-        let api_prompt = build_api_prompt(prompt, instructions, tools_json);
+        let api_prompt = build_api_prompt(
+            prompt,
+            instructions,
+            tools_json,
+            self.state.model_info.max_output_tokens,
+        );
         let conversation_id = self.state.conversation_id.to_string();
         let session_source = self.state.session_source.clone();
 
         let mut auth_recovery = auth_manager
             .as_ref()
             .map(super::auth::AuthManager::unauthorized_recovery);
+        let mut failover = self.endpoint_failover().await;
         loop {
             let auth = match auth_manager.as_ref() {
                 Some(manager) => manager.auth().await,
                 None => None,
             };
-            let api_provider = self
+            let mut api_provider = self
                 .state
                 .provider
                 .to_api_provider(auth.as_ref().map(|a| a.mode))?;
+            failover.apply(&mut api_provider);
             let api_auth = auth_provider_from_auth(auth.clone(), &self.state.provider)?;
             let transport = ReqwestTransport::new(build_reqwest_client());
             let (request_telemetry, sse_telemetry) = self.build_streaming_telemetry();
             let client = ApiChatClient::new(transport, api_provider, api_auth)
-                .with_telemetry(Some(request_telemetry), Some(sse_telemetry));
+                .with_telemetry(Some(request_telemetry), Some(sse_telemetry))
+                .with_response_cache(self.response_cache());
+
+            let stream_result = client
+                .stream_prompt(
+                    &self.state.model_info.slug,
+                    &api_prompt,
+                    Some(conversation_id.clone()),
+                    Some(session_source.clone()),
+                )
+                .await;
+
+            match stream_result {
+                Ok(stream) => {
+                    failover.record_success();
+                    return Ok(stream);
+                }
+                Err(ApiError::Transport(TransportError::Http { status, .. }))
+                    if status == StatusCode::UNAUTHORIZED =>
+                {
+                    handle_unauthorized(status, &mut auth_recovery).await?;
+                    continue;
+                }
+                Err(err) if failover.should_fail_over(&err) => continue,
+                Err(err) => return Err(map_api_error(err)),
+            }
+        }
+    }
+
+    /// Streams a turn via Anthropic's Messages API.
+    ///
+    /// Blocks are already emitted as complete items by the decoder, so unlike
+    /// Chat Completions the stream needs no aggregation.
+    async fn stream_anthropic_messages(&self, prompt: &Prompt) -> Result<ApiResponseStream> {
+        if prompt.output_schema.is_some() {
+            return Err(CodexErr::UnsupportedOperation(
+                "output_schema is not supported for Anthropic Messages API".to_string(),
+            ));
+        }
+
+        let auth_manager = self.state.auth_manager.clone();
+        let instructions = prompt.base_instructions.text.clone();
+        let tools_json = create_tools_json_for_anthropic_messages_api(&prompt.tools)?;
+        let api_prompt = build_api_prompt(
+            prompt,
+            instructions,
+            tools_json,
+            self.state.model_info.max_output_tokens,
+        );
+        let conversation_id = self.state.conversation_id.to_string();
+        let session_source = self.state.session_source.clone();
+
+        let mut auth_recovery = auth_manager
+            .as_ref()
+            .map(super::auth::AuthManager::unauthorized_recovery);
+        let mut failover = self.endpoint_failover().await;
+        loop {
+            let auth = match auth_manager.as_ref() {
+                Some(manager) => manager.auth().await,
+                None => None,
+            };
+            let mut api_provider = self
+                .state
+                .provider
+                .to_api_provider(auth.as_ref().map(|a| a.mode))?;
+            failover.apply(&mut api_provider);
+            let api_auth = auth_provider_from_auth(auth.clone(), &self.state.provider)?;
+            let transport = ReqwestTransport::new(build_reqwest_client());
+            let (request_telemetry, sse_telemetry) = self.build_streaming_telemetry();
+            let client = ApiAnthropicClient::new(transport, api_provider, api_auth)
+                .with_telemetry(Some(request_telemetry), Some(sse_telemetry))
+                .with_response_cache(self.response_cache());
 
             let stream_result = client
                 .stream_prompt(
@@ -479,13 +648,17 @@ impl ModelClientSession {
                 .await;
 
             match stream_result {
-                Ok(stream) => return Ok(stream),
+                Ok(stream) => {
+                    failover.record_success();
+                    return Ok(stream);
+                }
                 Err(ApiError::Transport(TransportError::Http { status, .. }))
                     if status == StatusCode::UNAUTHORIZED =>
                 {
                     handle_unauthorized(status, &mut auth_recovery).await?;
                     continue;
                 }
+                Err(err) if failover.should_fail_over(&err) => continue,
                 Err(err) => return Err(map_api_error(err)),
             }
         }
@@ -510,22 +683,25 @@ impl ModelClientSession {
         let mut auth_recovery = auth_manager
             .as_ref()
             .map(super::auth::AuthManager::unauthorized_recovery);
+        let mut failover = self.endpoint_failover().await;
         loop {
             let auth = match auth_manager.as_ref() {
                 Some(manager) => manager.auth().await,
                 None => None,
             };
-            let api_provider = self
+            let mut api_provider = self
                 .state
                 .provider
                 .to_api_provider(auth.as_ref().map(|a| a.mode))?;
+            failover.apply(&mut api_provider);
             let api_auth = auth_provider_from_auth(auth.clone(), &self.state.provider)?;
             let transport = ReqwestTransport::new(build_reqwest_client());
             let (request_telemetry, sse_telemetry) = self.build_streaming_telemetry();
             let compression = self.responses_request_compression(auth.as_ref());
 
             let client = ApiResponsesClient::new(transport, api_provider, api_auth)
-                .with_telemetry(Some(request_telemetry), Some(sse_telemetry));
+                .with_telemetry(Some(request_telemetry), Some(sse_telemetry))
+                .with_response_cache(self.response_cache());
 
             let options = self.build_responses_options(prompt, compression);
 
@@ -535,6 +711,7 @@ impl ModelClientSession {
 
             match stream_result {
                 Ok(stream) => {
+                    failover.record_success();
                     return Ok(map_response_stream(stream, self.state.otel_manager.clone()));
                 }
                 Err(ApiError::Transport(TransportError::Http { status, .. }))
@@ -543,6 +720,7 @@ impl ModelClientSession {
                     handle_unauthorized(status, &mut auth_recovery).await?;
                     continue;
                 }
+                Err(err) if failover.should_fail_over(&err) => continue,
                 Err(err) => return Err(map_api_error(err)),
             }
         }
@@ -556,15 +734,17 @@ impl ModelClientSession {
         let mut auth_recovery = auth_manager
             .as_ref()
             .map(super::auth::AuthManager::unauthorized_recovery);
+        let mut failover = self.endpoint_failover().await;
         loop {
             let auth = match auth_manager.as_ref() {
                 Some(manager) => manager.auth().await,
                 None => None,
             };
-            let api_provider = self
+            let mut api_provider = self
                 .state
                 .provider
                 .to_api_provider(auth.as_ref().map(|a| a.mode))?;
+            failover.apply(&mut api_provider);
             let api_auth = auth_provider_from_auth(auth.clone(), &self.state.provider)?;
             let compression = self.responses_request_compression(auth.as_ref());
 
@@ -575,13 +755,17 @@ impl ModelClientSession {
                 .websocket_connection(api_provider.clone(), api_auth.clone(), &options)
                 .await
             {
-                Ok(connection) => connection,
+                Ok(connection) => {
+                    failover.record_success();
+                    connection
+                }
                 Err(ApiError::Transport(TransportError::Http { status, .. }))
                     if status == StatusCode::UNAUTHORIZED =>
                 {
                     handle_unauthorized(status, &mut auth_recovery).await?;
                     continue;
                 }
+                Err(err) if failover.should_fail_over(&err) => continue,
                 Err(err) => return Err(map_api_error(err)),
             };
 
@@ -598,6 +782,44 @@ impl ModelClientSession {
         }
     }
 
+    /// Endpoint selection for a request to a provider with
+    /// `fallback_base_urls`.
+    async fn endpoint_failover(&self) -> EndpointFailover {
+        let auth = match self.state.auth_manager.as_ref() {
+            Some(manager) => manager.auth().await,
+            None => None,
+        };
+        EndpointFailover::new(
+            self.state
+                .provider
+                .endpoint_pool(auth.as_ref().map(|a| a.mode)),
+        )
+    }
+
+    /// The on-disk response cache, when enabled for this kind of session.
+    /// Interactive sessions bypass it unless `response_cache.interactive` is set.
+    /// Only `codex exec` and MCP server sessions count as non-interactive;
+    /// anything else, including sub-agents and unknown clients, may have a
+    /// person waiting on it.
+    fn response_cache(&self) -> Option<Arc<ApiResponseCache>> {
+        let settings = &self.state.config.response_cache;
+        let interactive = !matches!(
+            self.state.session_source,
+            SessionSource::Exec | SessionSource::Mcp
+        );
+        if !settings.enabled
+            || self.state.config.incognito
+            || (interactive && !settings.interactive)
+        {
+            return None;
+        }
+        Some(Arc::new(ApiResponseCache::new(
+            self.state.config.codex_home.join("response-cache"),
+            settings.ttl(),
+            settings.max_entries(),
+        )))
+    }
+
     /// Builds request and SSE telemetry for streaming API calls (Chat/Responses).
     fn build_streaming_telemetry(&self) -> (Arc<dyn RequestTelemetry>, Arc<dyn SseTelemetry>) {
         let telemetry = Arc::new(ApiTelemetry::new(self.state.otel_manager.clone()));
@@ -617,13 +839,19 @@ impl ModelClient {
 }
 
 /// Adapts the core `Prompt` type into the `codex-api` payload shape.
-fn build_api_prompt(prompt: &Prompt, instructions: String, tools_json: Vec<Value>) -> ApiPrompt {
+fn build_api_prompt(
+    prompt: &Prompt,
+    instructions: String,
+    tools_json: Vec<Value>,
+    max_output_tokens: Option<i64>,
+) -> ApiPrompt {
     ApiPrompt {
         instructions,
         input: prompt.get_formatted_input(),
         tools: tools_json,
         parallel_tool_calls: prompt.parallel_tool_calls,
         output_schema: prompt.output_schema.clone(),
+        max_output_tokens,
     }
 }
 
@@ -681,53 +909,56 @@ where
 {
     let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
 
-    tokio::spawn(async move {
-        let mut logged_error = false;
-        let mut api_stream = api_stream;
-        while let Some(event) = api_stream.next().await {
-            match event {
-                Ok(ResponseEvent::Completed {
-                    response_id,
-                    token_usage,
-                }) => {
-                    if let Some(usage) = &token_usage {
-                        otel_manager.sse_event_completed(
-                            usage.input_tokens,
-                            usage.output_tokens,
-                            Some(usage.cached_input_tokens),
-                            Some(usage.reasoning_output_tokens),
-                            usage.total_tokens,
-                        );
-                    }
-                    if tx_event
-                        .send(Ok(ResponseEvent::Completed {
-                            response_id,
-                            token_usage,
-                        }))
-                        .await
-                        .is_err()
-                    {
-                        return;
+    tokio::spawn(
+        async move {
+            let mut logged_error = false;
+            let mut api_stream = api_stream;
+            while let Some(event) = api_stream.next().await {
+                match event {
+                    Ok(ResponseEvent::Completed {
+                        response_id,
+                        token_usage,
+                    }) => {
+                        if let Some(usage) = &token_usage {
+                            otel_manager.sse_event_completed(
+                                usage.input_tokens,
+                                usage.output_tokens,
+                                Some(usage.cached_input_tokens),
+                                Some(usage.reasoning_output_tokens),
+                                usage.total_tokens,
+                            );
+                        }
+                        if tx_event
+                            .send(Ok(ResponseEvent::Completed {
+                                response_id,
+                                token_usage,
+                            }))
+                            .await
+                            .is_err()
+                        {
+                            return;
+                        }
                     }
-                }
-                Ok(event) => {
-                    if tx_event.send(Ok(event)).await.is_err() {
-                        return;
-                    }
-                }
-                Err(err) => {
-                    let mapped = map_api_error(err);
-                    if !logged_error {
-                        otel_manager.see_event_completed_failed(&mapped);
-                        logged_error = true;
+                    Ok(event) => {
+                        if tx_event.send(Ok(event)).await.is_err() {
+                            return;
+                        }
                     }
-                    if tx_event.send(Err(mapped)).await.is_err() {
-                        return;
+                    Err(err) => {
+                        let mapped = map_api_error(err);
+                        if !logged_error {
+                            otel_manager.see_event_completed_failed(&mapped);
+                            logged_error = true;
+                        }
+                        if tx_event.send(Err(mapped)).await.is_err() {
+                            return;
+                        }
                     }
                 }
             }
         }
-    });
+        .in_current_span(),
+    );
 
     ResponseStream { rx_event }
 }
@@ -802,3 +1033,22 @@ impl SseTelemetry for ApiTelemetry {
         self.otel_manager.log_sse_event(result, duration);
     }
 }
+
+/// Counts `prompt` with the tokenizer of `model` on a blocking thread, since
+/// tokenizing a long conversation (and loading the encoding the first time)
+/// would stall the runtime.
+async fn count_prompt_tokens(prompt: &Prompt, model: &str) -> PromptTokenCount {
+    let counted = prompt.clone();
+    let model = model.to_string();
+    match tokio::task::spawn_blocking(move || {
+        counted.count_tokens(tokenizer_for_model(&model).as_ref())
+    })
+    .await
+    {
+        Ok(count) => count,
+        Err(err) => {
+            warn!("failed to count prompt tokens, estimating instead: {err}");
+            prompt.count_tokens(&HeuristicTokenizer)
+        }
+    }
+}
@@ -1,12 +1,20 @@
 use crate::client_common::tools::ToolSpec;
 use crate::config::types::Personality;
+use crate::context_manager::estimate_item_token_count;
+use crate::error::ContextOverflowError;
 use crate::error::Result;
+use crate::tokenizer::CountMethod;
+use crate::tokenizer::Tokenizer;
 pub use codex_api::common::ResponseEvent;
 use codex_protocol::models::BaseInstructions;
 use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::ContextItemCategory;
+use codex_protocol::protocol::ContextOverflowContributor;
+use codex_protocol::protocol::ContextOverflowRemedy;
 use futures::Stream;
 use serde::Deserialize;
 use serde_json::Value;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::pin::Pin;
 use std::task::Context;
@@ -21,6 +29,9 @@ pub const REVIEW_EXIT_SUCCESS_TMPL: &str = include_str!("../templates/review/exi
 pub const REVIEW_EXIT_INTERRUPTED_TMPL: &str =
     include_str!("../templates/review/exit_interrupted.xml");
 
+/// Number of contributors listed when a prompt overflows the context window.
+const MAX_CONTEXT_OVERFLOW_CONTRIBUTORS: usize = 5;
+
 /// API request payload for a single model turn
 #[derive(Default, Debug, Clone)]
 pub struct Prompt {
@@ -61,6 +72,197 @@ impl Prompt {
 
         input
     }
+
+    /// Counts the tokens of this prompt with `tokenizer`, by category. Counts
+    /// items the same way as the history token estimate. Tokenizing a whole
+    /// prompt is slow, so callers on the async runtime run this in
+    /// `spawn_blocking`.
+    pub(crate) fn count_tokens(&self, tokenizer: &dyn Tokenizer) -> PromptTokenCount {
+        let mut categories: HashMap<ContextItemCategory, i64> = HashMap::new();
+        // Tool output tokens by the file the call read or edited.
+        let mut files: HashMap<String, i64> = HashMap::new();
+
+        categories.insert(
+            ContextItemCategory::Instructions,
+            approx_tokens(tokenizer, &self.base_instructions.text),
+        );
+        let tool_tokens = self
+            .tools
+            .iter()
+            .map(|tool| approx_tokens(tokenizer, &serde_json::to_string(tool).unwrap_or_default()))
+            .fold(0i64, i64::saturating_add);
+        categories.insert(ContextItemCategory::ToolDefinitions, tool_tokens);
+
+        let call_paths: HashMap<&str, String> = self
+            .input
+            .iter()
+            .filter_map(|item| match item {
+                ResponseItem::FunctionCall {
+                    call_id, arguments, ..
+                } => path_from_arguments(arguments).map(|path| (call_id.as_str(), path)),
+                ResponseItem::CustomToolCall { call_id, input, .. } => {
+                    path_from_patch(input).map(|path| (call_id.as_str(), path))
+                }
+                _ => None,
+            })
+            .collect();
+
+        for item in &self.input {
+            let Some(category) = context_item_category(item) else {
+                continue;
+            };
+            let tokens = estimate_item_token_count(tokenizer, item);
+            let total = categories.entry(category).or_default();
+            *total = total.saturating_add(tokens);
+            if category == ContextItemCategory::ToolOutputs
+                && let Some(call_id) = output_call_id(item)
+                && let Some(path) = call_paths.get(call_id)
+            {
+                let file_tokens = files.entry(path.clone()).or_default();
+                *file_tokens = file_tokens.saturating_add(tokens);
+            }
+        }
+
+        PromptTokenCount {
+            categories,
+            files,
+            method: tokenizer.method(),
+        }
+    }
+}
+
+/// How far, in percent of the context window, a byte-length estimate may
+/// exceed the window before a prompt is rejected without being sent.
+const HEURISTIC_OVERFLOW_HEADROOM_PERCENT: i64 = 25;
+
+/// Tokens a [`Prompt`] takes, counted once per request.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PromptTokenCount {
+    categories: HashMap<ContextItemCategory, i64>,
+    /// Tool output tokens by the file the call read or edited.
+    files: HashMap<String, i64>,
+    pub(crate) method: CountMethod,
+}
+
+impl PromptTokenCount {
+    pub(crate) fn total(&self) -> i64 {
+        self.categories
+            .values()
+            .copied()
+            .fold(0i64, i64::saturating_add)
+    }
+
+    /// When the prompt cannot fit in `context_window` tokens, reports what
+    /// takes up the most room. A count estimated from byte lengths can be off
+    /// either way, so it must exceed the window by
+    /// [`HEURISTIC_OVERFLOW_HEADROOM_PERCENT`] before the prompt is rejected.
+    pub(crate) fn overflow(&self, context_window: i64) -> Option<ContextOverflowError> {
+        let estimated_tokens = self.total();
+        let limit = match self.method {
+            CountMethod::Bpe(_) => context_window,
+            CountMethod::Heuristic => context_window.saturating_add(
+                context_window.saturating_mul(HEURISTIC_OVERFLOW_HEADROOM_PERCENT) / 100,
+            ),
+        };
+        if estimated_tokens <= limit {
+            return None;
+        }
+
+        let tool_output_tokens = self
+            .categories
+            .get(&ContextItemCategory::ToolOutputs)
+            .copied()
+            .unwrap_or_default();
+        let mut largest_contributors: Vec<ContextOverflowContributor> =
+            self.categories
+                .iter()
+                .map(|(category, estimated_tokens)| ContextOverflowContributor {
+                    category: *category,
+                    path: None,
+                    estimated_tokens: *estimated_tokens,
+                })
+                .chain(self.files.iter().map(|(path, estimated_tokens)| {
+                    ContextOverflowContributor {
+                        category: ContextItemCategory::ToolOutputs,
+                        path: Some(path.clone()),
+                        estimated_tokens: *estimated_tokens,
+                    }
+                }))
+                .filter(|contributor| contributor.estimated_tokens > 0)
+                .collect();
+        largest_contributors.sort_by(|a, b| {
+            b.estimated_tokens
+                .cmp(&a.estimated_tokens)
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        largest_contributors.truncate(MAX_CONTEXT_OVERFLOW_CONTRIBUTORS);
+
+        let mut remedies = vec![ContextOverflowRemedy::Compact];
+        if tool_output_tokens.saturating_mul(4) >= estimated_tokens {
+            remedies.push(ContextOverflowRemedy::DropToolOutputs);
+        }
+        remedies.push(ContextOverflowRemedy::SwitchModel);
+
+        Some(ContextOverflowError {
+            estimated_tokens,
+            context_window,
+            largest_contributors,
+            remedies,
+        })
+    }
+}
+
+fn approx_tokens(tokenizer: &dyn Tokenizer, text: &str) -> i64 {
+    i64::try_from(tokenizer.count_tokens(text)).unwrap_or(i64::MAX)
+}
+
+fn context_item_category(item: &ResponseItem) -> Option<ContextItemCategory> {
+    match item {
+        ResponseItem::Message { role, .. } if role == "assistant" => {
+            Some(ContextItemCategory::AssistantMessages)
+        }
+        ResponseItem::Message { .. } => Some(ContextItemCategory::UserMessages),
+        ResponseItem::Reasoning { .. } | ResponseItem::Compaction { .. } => {
+            Some(ContextItemCategory::Reasoning)
+        }
+        ResponseItem::LocalShellCall { .. }
+        | ResponseItem::FunctionCall { .. }
+        | ResponseItem::CustomToolCall { .. }
+        | ResponseItem::WebSearchCall { .. }
+        | ResponseItem::CodeInterpreterCall { .. }
+        | ResponseItem::FileSearchCall { .. } => Some(ContextItemCategory::ToolCalls),
+        ResponseItem::FunctionCallOutput { .. } | ResponseItem::CustomToolCallOutput { .. } => {
+            Some(ContextItemCategory::ToolOutputs)
+        }
+        ResponseItem::GhostSnapshot { .. } | ResponseItem::Other => None,
+    }
+}
+
+fn output_call_id(item: &ResponseItem) -> Option<&str> {
+    match item {
+        ResponseItem::FunctionCallOutput { call_id, .. }
+        | ResponseItem::CustomToolCallOutput { call_id, .. } => Some(call_id),
+        _ => None,
+    }
+}
+
+/// File a function call reads or writes, taken from its `path` or `file_path`
+/// argument.
+fn path_from_arguments(arguments: &str) -> Option<String> {
+    let arguments: Value = serde_json::from_str(arguments).ok()?;
+    ["file_path", "path"]
+        .into_iter()
+        .find_map(|key| arguments.get(key)?.as_str().map(str::to_string))
+}
+
+/// First file touched by a freeform `apply_patch` call.
+fn path_from_patch(input: &str) -> Option<String> {
+    input.lines().find_map(|line| {
+        ["*** Add File: ", "*** Update File: ", "*** Delete File: "]
+            .into_iter()
+            .find_map(|prefix| line.strip_prefix(prefix))
+            .map(|path| path.trim().to_string())
+    })
 }
 
 fn reserialize_shell_outputs(items: &mut [ResponseItem]) {
@@ -178,16 +380,38 @@ pub(crate) mod tools {
             #[serde(skip_serializing_if = "Option::is_none")]
             external_web_access: Option<bool>,
         },
+        // Tools the provider runs on its side; only the Responses API
+        // offers them.
+        // https://platform.openai.com/docs/guides/tools-code-interpreter
+        #[serde(rename = "code_interpreter")]
+        CodeInterpreter { container: CodeInterpreterContainer },
+        // https://platform.openai.com/docs/guides/tools-file-search
+        #[serde(rename = "file_search")]
+        FileSearch {
+            vector_store_ids: Vec<String>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            max_num_results: Option<u32>,
+        },
         #[serde(rename = "custom")]
         Freeform(FreeformTool),
     }
 
+    /// Container the code interpreter runs in; `auto` lets the provider
+    /// create one and reuse it for the rest of the conversation.
+    #[derive(Debug, Clone, Serialize, PartialEq)]
+    #[serde(tag = "type", rename_all = "snake_case")]
+    pub(crate) enum CodeInterpreterContainer {
+        Auto,
+    }
+
     impl ToolSpec {
         pub(crate) fn name(&self) -> &str {
             match self {
                 ToolSpec::Function(tool) => tool.name.as_str(),
                 ToolSpec::LocalShell {} => "local_shell",
                 ToolSpec::WebSearch { .. } => "web_search",
+                ToolSpec::CodeInterpreter { .. } => "code_interpreter",
+                ToolSpec::FileSearch { .. } => "file_search",
                 ToolSpec::Freeform(tool) => tool.name.as_str(),
             }
         }
@@ -237,9 +461,11 @@ mod tests {
     use codex_api::common::OpenAiVerbosity;
     use codex_api::common::TextControls;
     use codex_api::create_text_param_for_request;
+    use codex_protocol::models::FunctionCallOutputPayload;
     use pretty_assertions::assert_eq;
 
     use super::*;
+    use crate::tokenizer::HeuristicTokenizer;
 
     #[test]
     fn serializes_text_verbosity_when_set() {
@@ -261,6 +487,7 @@ mod tests {
                 verbosity: Some(OpenAiVerbosity::Low),
                 format: None,
             }),
+            max_output_tokens: None,
         };
 
         let v = serde_json::to_value(&req).expect("json");
@@ -299,6 +526,7 @@ mod tests {
             include: vec![],
             prompt_cache_key: None,
             text: Some(text_controls),
+            max_output_tokens: None,
         };
 
         let v = serde_json::to_value(&req).expect("json");
@@ -335,9 +563,124 @@ mod tests {
             include: vec![],
             prompt_cache_key: None,
             text: None,
+            max_output_tokens: None,
         };
 
         let v = serde_json::to_value(&req).expect("json");
         assert!(v.get("text").is_none());
     }
+
+    fn read_file_exchange(call_id: &str, path: &str, output: String) -> [ResponseItem; 2] {
+        [
+            ResponseItem::FunctionCall {
+                id: None,
+                name: "read_file".to_string(),
+                arguments: serde_json::json!({ "file_path": path }).to_string(),
+                call_id: call_id.to_string(),
+            },
+            ResponseItem::FunctionCallOutput {
+                call_id: call_id.to_string(),
+                output: FunctionCallOutputPayload {
+                    content: output,
+                    ..Default::default()
+                },
+            },
+        ]
+    }
+
+    #[test]
+    fn context_overflow_is_none_when_prompt_fits() {
+        let prompt = Prompt {
+            input: read_file_exchange("call-1", "src/small.rs", "fn main() {}".to_string())
+                .to_vec(),
+            base_instructions: BaseInstructions {
+                text: String::new(),
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(
+            prompt.count_tokens(&HeuristicTokenizer).overflow(10_000),
+            None
+        );
+    }
+
+    #[test]
+    fn estimated_counts_overflow_only_past_the_headroom() {
+        let count = |method| PromptTokenCount {
+            categories: HashMap::from([(ContextItemCategory::UserMessages, 1_100)]),
+            files: HashMap::new(),
+            method,
+        };
+
+        assert_eq!(count(CountMethod::Heuristic).overflow(1_000), None);
+        assert_eq!(
+            count(CountMethod::Heuristic).overflow(800),
+            Some(ContextOverflowError {
+                estimated_tokens: 1_100,
+                context_window: 800,
+                largest_contributors: vec![ContextOverflowContributor {
+                    category: ContextItemCategory::UserMessages,
+                    path: None,
+                    estimated_tokens: 1_100,
+                }],
+                remedies: vec![
+                    ContextOverflowRemedy::Compact,
+                    ContextOverflowRemedy::SwitchModel,
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn context_overflow_lists_largest_categories_and_files() {
+        let mut input = Vec::new();
+        input.extend(read_file_exchange(
+            "call-1",
+            "src/big.rs",
+            "x".repeat(8_000),
+        ));
+        input.extend(read_file_exchange(
+            "call-2",
+            "src/small.rs",
+            "y".repeat(800),
+        ));
+        let prompt = Prompt {
+            input,
+            base_instructions: BaseInstructions {
+                text: String::new(),
+            },
+            ..Default::default()
+        };
+
+        let overflow = prompt
+            .count_tokens(&HeuristicTokenizer)
+            .overflow(1_000)
+            .expect("prompt should overflow");
+
+        assert!(overflow.estimated_tokens > 2_000);
+        assert_eq!(overflow.context_window, 1_000);
+        let contributors: Vec<(ContextItemCategory, Option<&str>)> = overflow
+            .largest_contributors
+            .iter()
+            .map(|contributor| (contributor.category, contributor.path.as_deref()))
+            .collect();
+        assert_eq!(
+            contributors,
+            vec![
+                (ContextItemCategory::ToolOutputs, None),
+                (ContextItemCategory::ToolOutputs, Some("src/big.rs")),
+                (ContextItemCategory::ToolOutputs, Some("src/small.rs")),
+                (ContextItemCategory::ToolCalls, None),
+            ]
+        );
+        assert_eq!(
+            overflow.remedies,
+            vec![
+                ContextOverflowRemedy::Compact,
+                ContextOverflowRemedy::DropToolOutputs,
+                ContextOverflowRemedy::SwitchModel,
+            ]
+        );
+    }
 }
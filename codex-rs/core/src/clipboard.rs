@@ -0,0 +1,331 @@
+//! The conversation clipboard: named text blobs such as a stack trace or a
+//! long spec, kept outside the model's context. The user stores them with
+//! `Op::SetClip` and the model with the `clipboard` tool. Tool calls refer to
+//! them as `{{clip:<name>}}`, and the reference is only replaced with the
+//! content when the call is dispatched, so a clip costs no prompt tokens
+//! unless the model reads it.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::RolloutItem;
+use serde_json::Value;
+
+use crate::tools::context::ToolPayload;
+
+pub(crate) const CLIPBOARD_TOOL_NAME: &str = "clipboard";
+
+/// Largest single clip.
+pub(crate) const MAX_CLIP_BYTES: usize = 256 * 1024;
+
+/// Largest total size of all clips of a conversation.
+pub(crate) const MAX_CLIPBOARD_BYTES: usize = 4 * 1024 * 1024;
+
+const MAX_CLIP_NAME_LEN: usize = 64;
+
+const REFERENCE_PREFIX: &str = "{{clip:";
+const REFERENCE_SUFFIX: &str = "}}";
+
+#[derive(Debug, Default)]
+pub(crate) struct Clipboard {
+    clips: BTreeMap<String, String>,
+}
+
+impl Clipboard {
+    /// Rebuilds the clipboard from the `ClipStored` events of a rollout.
+    pub(crate) fn from_rollout(items: &[RolloutItem]) -> Self {
+        let mut clipboard = Self::default();
+        for item in items {
+            if let RolloutItem::EventMsg(EventMsg::ClipStored(event)) = item {
+                // Recorded clips passed validation when they were stored.
+                let _ = clipboard.set(&event.name, event.content.clone());
+            }
+        }
+        clipboard
+    }
+
+    /// Stores `content` under `name`, or removes the clip when `content` is
+    /// empty.
+    pub(crate) fn set(&mut self, name: &str, content: String) -> Result<(), String> {
+        validate_clip_name(name)?;
+        if content.is_empty() {
+            self.clips.remove(name);
+            return Ok(());
+        }
+        if content.len() > MAX_CLIP_BYTES {
+            return Err(format!(
+                "clip `{name}` is {} bytes; clips are limited to {MAX_CLIP_BYTES} bytes",
+                content.len()
+            ));
+        }
+        let others: usize = self
+            .clips
+            .iter()
+            .filter(|(existing, _)| existing.as_str() != name)
+            .map(|(_, content)| content.len())
+            .sum();
+        if others + content.len() > MAX_CLIPBOARD_BYTES {
+            return Err(format!(
+                "storing clip `{name}` would exceed the clipboard limit of \
+                 {MAX_CLIPBOARD_BYTES} bytes; remove clips that are no longer needed"
+            ));
+        }
+        self.clips.insert(name.to_string(), content);
+        Ok(())
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Option<&str> {
+        self.clips.get(name).map(String::as_str)
+    }
+
+    /// One line per clip with its size, for the model.
+    pub(crate) fn describe(&self) -> String {
+        if self.clips.is_empty() {
+            return "The clipboard is empty.".to_string();
+        }
+        self.clips
+            .iter()
+            .map(|(name, content)| {
+                format!(
+                    "{name}: {} bytes, {} lines",
+                    content.len(),
+                    content.lines().count()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Replaces every `{{clip:<name>}}` in `text` with the clip's content.
+    /// Fails on references to clips that do not exist; text that merely looks
+    /// similar, such as a reference with an invalid name, is left alone.
+    pub(crate) fn expand<'a>(&self, text: &'a str) -> Result<Cow<'a, str>, String> {
+        if !text.contains(REFERENCE_PREFIX) {
+            return Ok(Cow::Borrowed(text));
+        }
+        let mut expanded = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find(REFERENCE_PREFIX) {
+            let after_prefix = &rest[start + REFERENCE_PREFIX.len()..];
+            let Some(end) = after_prefix.find(REFERENCE_SUFFIX) else {
+                break;
+            };
+            let name = &after_prefix[..end];
+            let reference_len = REFERENCE_PREFIX.len() + end + REFERENCE_SUFFIX.len();
+            if validate_clip_name(name).is_err() {
+                expanded.push_str(&rest[..start + REFERENCE_PREFIX.len()]);
+                rest = after_prefix;
+                continue;
+            }
+            let Some(content) = self.get(name) else {
+                return Err(self.unknown_clip_message(name));
+            };
+            expanded.push_str(&rest[..start]);
+            expanded.push_str(content);
+            rest = &rest[start + reference_len..];
+        }
+        expanded.push_str(rest);
+        Ok(Cow::Owned(expanded))
+    }
+
+    /// Expands clip references in the arguments of a tool call. In JSON
+    /// arguments only string values are expanded, so the content is escaped
+    /// as needed.
+    pub(crate) fn expand_payload(&self, payload: ToolPayload) -> Result<ToolPayload, String> {
+        Ok(match payload {
+            ToolPayload::Function { arguments } => ToolPayload::Function {
+                arguments: self.expand_json_arguments(arguments)?,
+            },
+            ToolPayload::Custom { input } => ToolPayload::Custom {
+                input: self.expand(&input)?.into_owned(),
+            },
+            ToolPayload::LocalShell { mut params } => {
+                for arg in &mut params.command {
+                    self.expand_in_place(arg)?;
+                }
+                ToolPayload::LocalShell { params }
+            }
+            ToolPayload::Mcp {
+                server,
+                tool,
+                raw_arguments,
+            } => ToolPayload::Mcp {
+                server,
+                tool,
+                raw_arguments: self.expand_json_arguments(raw_arguments)?,
+            },
+        })
+    }
+
+    fn expand_json_arguments(&self, arguments: String) -> Result<String, String> {
+        if !arguments.contains(REFERENCE_PREFIX) {
+            return Ok(arguments);
+        }
+        // Malformed arguments are left for the handler to reject.
+        let Ok(mut value) = serde_json::from_str::<Value>(&arguments) else {
+            return Ok(arguments);
+        };
+        self.expand_json_value(&mut value)?;
+        serde_json::to_string(&value).map_err(|err| format!("failed to expand clips: {err}"))
+    }
+
+    fn expand_json_value(&self, value: &mut Value) -> Result<(), String> {
+        match value {
+            Value::String(text) => self.expand_in_place(text)?,
+            Value::Array(items) => {
+                for item in items {
+                    self.expand_json_value(item)?;
+                }
+            }
+            Value::Object(fields) => {
+                for field in fields.values_mut() {
+                    self.expand_json_value(field)?;
+                }
+            }
+            Value::Null | Value::Bool(_) | Value::Number(_) => {}
+        }
+        Ok(())
+    }
+
+    fn expand_in_place(&self, text: &mut String) -> Result<(), String> {
+        if text.contains(REFERENCE_PREFIX) {
+            *text = self.expand(text)?.into_owned();
+        }
+        Ok(())
+    }
+
+    fn unknown_clip_message(&self, name: &str) -> String {
+        if self.clips.is_empty() {
+            format!("unknown clip `{name}`: the clipboard is empty")
+        } else {
+            let names = self.clips.keys().cloned().collect::<Vec<_>>().join(", ");
+            format!("unknown clip `{name}`; stored clips: {names}")
+        }
+    }
+}
+
+/// Clip names are 1-64 ASCII letters, digits, `-`, `_` or `.`.
+pub(crate) fn validate_clip_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_CLIP_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "invalid clip name `{name}`: use 1-{MAX_CLIP_NAME_LEN} letters, digits, `-`, `_` or `.`"
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::ShellToolCallParams;
+    use codex_protocol::protocol::ClipStoredEvent;
+    use pretty_assertions::assert_eq;
+
+    fn clipboard_with(clips: &[(&str, &str)]) -> Clipboard {
+        let mut clipboard = Clipboard::default();
+        for (name, content) in clips {
+            clipboard
+                .set(name, (*content).to_string())
+                .expect("store clip");
+        }
+        clipboard
+    }
+
+    #[test]
+    fn expands_references_and_rejects_unknown_clips() {
+        let clipboard = clipboard_with(&[("trace1", "panicked at src/main.rs:3")]);
+
+        let expand = |text: &str| clipboard.expand(text).map(Cow::into_owned);
+
+        assert_eq!(
+            expand("see {{clip:trace1}} and {{clip:trace1}}"),
+            Ok("see panicked at src/main.rs:3 and panicked at src/main.rs:3".to_string())
+        );
+        assert_eq!(
+            expand("no references {{clip:not a name}}"),
+            Ok("no references {{clip:not a name}}".to_string())
+        );
+        assert_eq!(
+            expand("{{clip:missing}}"),
+            Err("unknown clip `missing`; stored clips: trace1".to_string())
+        );
+    }
+
+    #[test]
+    fn expands_json_string_values_with_escaping() {
+        let clipboard = clipboard_with(&[("spec", "line \"one\"\nline two")]);
+        let payload = ToolPayload::Function {
+            arguments: r#"{"content":"{{clip:spec}}","count":1}"#.to_string(),
+        };
+
+        let ToolPayload::Function { arguments } =
+            clipboard.expand_payload(payload).expect("expand payload")
+        else {
+            panic!("expected function payload");
+        };
+        let value: Value = serde_json::from_str(&arguments).expect("valid json");
+        assert_eq!(
+            value,
+            serde_json::json!({"content": "line \"one\"\nline two", "count": 1})
+        );
+
+        let payload = ToolPayload::LocalShell {
+            params: ShellToolCallParams {
+                command: vec!["grep".to_string(), "{{clip:spec}}".to_string()],
+                workdir: None,
+                timeout_ms: None,
+                sandbox_permissions: None,
+                justification: None,
+            },
+        };
+        let ToolPayload::LocalShell { params } =
+            clipboard.expand_payload(payload).expect("expand payload")
+        else {
+            panic!("expected local shell payload");
+        };
+        assert_eq!(
+            params.command,
+            vec!["grep".to_string(), "line \"one\"\nline two".to_string()]
+        );
+    }
+
+    #[test]
+    fn enforces_size_caps_and_rebuilds_from_rollout() {
+        let mut clipboard = Clipboard::default();
+        assert!(
+            clipboard
+                .set("big", "x".repeat(MAX_CLIP_BYTES + 1))
+                .is_err()
+        );
+        assert!(clipboard.set("bad name", "text".to_string()).is_err());
+
+        let items = vec![
+            RolloutItem::EventMsg(EventMsg::ClipStored(ClipStoredEvent {
+                name: "a".to_string(),
+                content: "first".to_string(),
+                from_model: false,
+            })),
+            RolloutItem::EventMsg(EventMsg::ClipStored(ClipStoredEvent {
+                name: "b".to_string(),
+                content: "second".to_string(),
+                from_model: true,
+            })),
+            RolloutItem::EventMsg(EventMsg::ClipStored(ClipStoredEvent {
+                name: "a".to_string(),
+                content: String::new(),
+                from_model: false,
+            })),
+        ];
+        let clipboard = Clipboard::from_rollout(&items);
+        assert_eq!(clipboard.get("a"), None);
+        assert_eq!(clipboard.get("b"), Some("second"));
+        assert_eq!(clipboard.describe(), "b: 6 bytes, 1 lines");
+    }
+}
@@ -1,11 +1,13 @@
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt::Debug;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering;
+use std::time::Duration;
 
 use crate::AuthManager;
 use crate::CodexAuth;
@@ -13,6 +15,12 @@ use crate::SandboxState;
 use crate::agent::AgentControl;
 use crate::agent::AgentStatus;
 use crate::agent::agent_status_from_event;
+use crate::artifacts::ArtifactQuota;
+use crate::artifacts::claim_garbage_collection;
+use crate::artifacts::collect_garbage;
+use crate::background_compaction;
+use crate::background_compaction::BackgroundCompactor;
+use crate::change_index::update_change_index;
 use crate::compact;
 use crate::compact::run_inline_auto_compact_task;
 use crate::compact::should_use_remote_compact_task;
@@ -21,9 +29,12 @@ use crate::connectors;
 use crate::exec_policy::ExecPolicyManager;
 use crate::features::Feature;
 use crate::features::Features;
+use crate::features::feature_for_key;
 use crate::models_manager::manager::ModelsManager;
 use crate::parse_command::parse_command;
+use crate::parse_command::shlex_join;
 use crate::parse_turn_item;
+use crate::provenance::remove_orphaned_provenance;
 use crate::stream_events_utils::HandleOutputCtx;
 use crate::stream_events_utils::handle_non_tool_response_item;
 use crate::stream_events_utils::handle_output_item_done;
@@ -33,24 +44,34 @@ use crate::user_notification::UserNotifier;
 use crate::util::error_or_panic;
 use async_channel::Receiver;
 use async_channel::Sender;
+use chrono::DateTime;
+use chrono::Utc;
 use codex_protocol::ThreadId;
 use codex_protocol::approvals::ExecPolicyAmendment;
+use codex_protocol::approvals::MatchedApprovalRule;
 use codex_protocol::config_types::Settings;
 use codex_protocol::config_types::WebSearchMode;
+use codex_protocol::items::AgentMessageContent;
+use codex_protocol::items::AgentMessageItem;
 use codex_protocol::items::TurnItem;
 use codex_protocol::items::UserMessageItem;
 use codex_protocol::models::BaseInstructions;
 use codex_protocol::openai_models::ModelInfo;
+use codex_protocol::parse_command::ParsedCommand;
+use codex_protocol::protocol::ClipStoredEvent;
+use codex_protocol::protocol::ExplorationFindingsEvent;
 use codex_protocol::protocol::FileChange;
 use codex_protocol::protocol::HasLegacyEvent;
 use codex_protocol::protocol::ItemCompletedEvent;
 use codex_protocol::protocol::ItemStartedEvent;
 use codex_protocol::protocol::RawResponseItemEvent;
+use codex_protocol::protocol::ResumeContextChange;
 use codex_protocol::protocol::ReviewRequest;
 use codex_protocol::protocol::RolloutItem;
 use codex_protocol::protocol::SessionSource;
 use codex_protocol::protocol::TurnAbortReason;
 use codex_protocol::protocol::TurnContextItem;
+use codex_protocol::protocol::TurnRequestSnapshot;
 use codex_protocol::protocol::TurnStartedEvent;
 use codex_protocol::request_user_input::RequestUserInputArgs;
 use codex_protocol::request_user_input::RequestUserInputResponse;
@@ -73,21 +94,25 @@ use tokio::sync::Mutex;
 use tokio::sync::RwLock;
 use tokio::sync::oneshot;
 use tokio_util::sync::CancellationToken;
+use tokio_util::sync::DropGuard;
 use tracing::Instrument;
 use tracing::debug;
 use tracing::error;
 use tracing::field;
 use tracing::info;
+use tracing::info_span;
 use tracing::instrument;
 use tracing::trace_span;
 use tracing::warn;
 
 use crate::ModelProviderInfo;
 use crate::WireApi;
+use crate::approval_memory::ApprovalMemory;
 use crate::client::ModelClient;
 use crate::client::ModelClientSession;
 use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
+use crate::clipboard::Clipboard;
 use crate::compact::collect_user_messages;
 use crate::config::Config;
 use crate::config::Constrained;
@@ -95,27 +120,41 @@ use crate::config::ConstraintResult;
 use crate::config::GhostSnapshotConfig;
 use crate::config::types::McpServerConfig;
 use crate::config::types::ShellEnvironmentPolicy;
+use crate::config::types::UsageLimitWait;
 use crate::context_manager::ContextManager;
 use crate::environment_context::EnvironmentContext;
 use crate::error::CodexErr;
 use crate::error::Result as CodexResult;
+use crate::error::UsageLimitReachedError;
+use crate::error_fingerprint::ErrorFingerprints;
+use crate::event_socket::EventSocket;
 #[cfg(test)]
 use crate::exec::StreamOutput;
+use crate::exec_env::create_env;
+use crate::exec_env::describe_env;
 use crate::exec_policy::ExecPolicyUpdateError;
 use crate::feedback_tags;
+use crate::heartbeat::TurnActivity;
 use crate::instructions::UserInstructions;
+use crate::knowledge::KnowledgeInjections;
+use crate::knowledge::build_knowledge_injections;
 use crate::mcp::CODEX_APPS_MCP_SERVER_NAME;
 use crate::mcp::auth::compute_auth_statuses;
 use crate::mcp::effective_mcp_servers;
 use crate::mcp::with_codex_apps_mcp;
 use crate::mcp_connection_manager::McpConnectionManager;
 use crate::model_provider_info::CHAT_WIRE_API_DEPRECATION_SUMMARY;
+use crate::model_switch::ModelSwitch;
+use crate::model_switch::describe_model_switch;
+use crate::model_switch::rerender_base_instructions;
 use crate::project_doc::get_user_instructions;
+use crate::project_map::ProjectMapCache;
 use crate::protocol::AgentMessageContentDeltaEvent;
 use crate::protocol::AgentReasoningSectionBreakEvent;
 use crate::protocol::ApplyPatchApprovalRequestEvent;
 use crate::protocol::AskForApproval;
 use crate::protocol::BackgroundEventEvent;
+use crate::protocol::CancelledToolCall;
 use crate::protocol::DeprecationNoticeEvent;
 use crate::protocol::ErrorEvent;
 use crate::protocol::Event;
@@ -123,13 +162,16 @@ use crate::protocol::EventMsg;
 use crate::protocol::ExecApprovalRequestEvent;
 use crate::protocol::McpServerRefreshConfig;
 use crate::protocol::Op;
+use crate::protocol::ProviderEndpointEvent;
 use crate::protocol::RateLimitSnapshot;
 use crate::protocol::ReasoningContentDeltaEvent;
 use crate::protocol::ReasoningRawContentDeltaEvent;
 use crate::protocol::RequestUserInputEvent;
+use crate::protocol::ResumeContextEvent;
 use crate::protocol::ReviewDecision;
 use crate::protocol::SandboxPolicy;
 use crate::protocol::SessionConfiguredEvent;
+use crate::protocol::SessionStats;
 use crate::protocol::SkillErrorInfo;
 use crate::protocol::SkillInterface as ProtocolSkillInterface;
 use crate::protocol::SkillMetadata as ProtocolSkillMetadata;
@@ -140,9 +182,16 @@ use crate::protocol::TokenUsage;
 use crate::protocol::TokenUsageInfo;
 use crate::protocol::TurnDiffEvent;
 use crate::protocol::WarningEvent;
+use crate::repl::ReplManager;
+use crate::resume_context::ResumeContextValues;
+use crate::resume_context::diff_resume_context;
+use crate::resume_context::last_turn_context;
 use crate::rollout::RolloutRecorder;
 use crate::rollout::RolloutRecorderParams;
+use crate::rollout::SessionMeta;
+use crate::rollout::SessionMetadataUpdate;
 use crate::rollout::map_session_init_error;
+use crate::session_title::maybe_spawn_auto_title;
 use crate::shell;
 use crate::shell_snapshot::ShellSnapshot;
 use crate::skills::SkillError;
@@ -150,15 +199,28 @@ use crate::skills::SkillInjections;
 use crate::skills::SkillMetadata;
 use crate::skills::SkillsManager;
 use crate::skills::build_skill_injections;
+use crate::stale_reads::StaleRead;
+use crate::stale_reads::canonical_path;
+use crate::stale_reads::find_stale_reads;
+use crate::stale_reads::shell_reads;
 use crate::state::ActiveTurn;
+use crate::state::InterruptedTurnProgress;
 use crate::state::SessionServices;
 use crate::state::SessionState;
+use crate::state::TurnCheckpoint;
 use crate::tasks::GhostSnapshotTask;
 use crate::tasks::ReviewTask;
 use crate::tasks::SessionTask;
 use crate::tasks::SessionTaskContext;
+use crate::tasks::capture_turn_end_checkpoint;
+use crate::tasks::findings_message;
+use crate::tasks::pinned_findings_from_rollout;
+use crate::token_forecast::TokenForecaster;
+use crate::tokenizer::Tokenizer;
+use crate::tokenizer::tokenizer_for_model;
 use crate::tools::ToolRouter;
 use crate::tools::context::SharedTurnDiffTracker;
+use crate::tools::context::ToolPayload;
 use crate::tools::parallel::ToolCallRuntime;
 use crate::tools::sandboxing::ApprovalStore;
 use crate::tools::spec::ToolsConfig;
@@ -167,11 +229,13 @@ use crate::turn_diff_tracker::TurnDiffTracker;
 use crate::unified_exec::UnifiedExecProcessManager;
 use crate::user_notification::UserNotification;
 use crate::util::backoff;
+use crate::write_quota::TurnWriteQuota;
 use codex_async_utils::OrCancelExt;
 use codex_otel::OtelManager;
 use codex_protocol::config_types::CollaborationMode;
 use codex_protocol::config_types::Personality;
 use codex_protocol::config_types::ReasoningSummary as ReasoningSummaryConfig;
+use codex_protocol::config_types::ResumeContextSource;
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::DeveloperInstructions;
 use codex_protocol::models::ResponseInputItem;
@@ -191,6 +255,7 @@ pub struct Codex {
     pub(crate) rx_event: Receiver<Event>,
     // Last known status of the agent.
     pub(crate) agent_status: watch::Receiver<AgentStatus>,
+    pub(crate) session: Arc<Session>,
 }
 
 /// Wrapper returned by [`Codex::spawn`] containing the spawned [`Codex`],
@@ -265,7 +330,8 @@ impl Codex {
 
         let exec_policy = ExecPolicyManager::load(&config.features, &config.config_layer_stack)
             .await
-            .map_err(|err| CodexErr::Fatal(format!("failed to load rules: {err}")))?;
+            .map_err(|err| CodexErr::Fatal(format!("failed to load rules: {err}")))?
+            .with_approval_rules(config.approval_rules.clone());
 
         let config = Arc::new(config);
         let _ = models_manager
@@ -281,6 +347,11 @@ impl Codex {
                 crate::models_manager::manager::RefreshStrategy::OnlineIfUncached,
             )
             .await;
+        // Frontends may pick the model after the config was loaded, e.g. from
+        // a CLI flag or a model migration prompt.
+        config
+            .ensure_model_allowed(&model)
+            .map_err(|err| CodexErr::Fatal(err.to_string()))?;
 
         // Resolve base instructions for the session. Priority order:
         // 1. config.base_instructions override
@@ -340,13 +411,47 @@ impl Codex {
         })?;
         let thread_id = session.conversation_id;
 
+        let codex_home = config.codex_home.clone();
+        let artifact_quota = ArtifactQuota::from(&config.artifacts);
+        tokio::spawn(async move {
+            match claim_garbage_collection(&codex_home).await {
+                Ok(true) => {}
+                Ok(false) => return,
+                Err(err) => {
+                    warn!("failed to check when CODEX_HOME was last cleaned up: {err}");
+                    return;
+                }
+            }
+            if let Err(err) = collect_garbage(&codex_home, &artifact_quota).await {
+                warn!("failed to clean up tool artifacts: {err}");
+            }
+            if let Err(err) = remove_orphaned_provenance(&codex_home).await {
+                warn!("failed to clean up provenance records: {err}");
+            }
+        });
+        // Keep the cross-session change index current so queries only have
+        // to scan the rollouts written since.
+        if !config.incognito {
+            let codex_home = config.codex_home.clone();
+            tokio::spawn(async move {
+                if let Err(err) = update_change_index(&codex_home).await {
+                    warn!("failed to update the change index: {err}");
+                }
+            });
+        }
+
         // This task will run until Op::Shutdown is received.
-        tokio::spawn(submission_loop(session, config, rx_sub));
+        // Its span is the root of the session → turn → tool call hierarchy.
+        tokio::spawn(
+            submission_loop(Arc::clone(&session), config, rx_sub)
+                .instrument(info_span!("session", conversation_id = %thread_id)),
+        );
         let codex = Codex {
             next_id: AtomicU64::new(0),
             tx_sub,
             rx_event,
             agent_status: agent_status_rx,
+            session,
         };
 
         #[allow(deprecated)]
@@ -390,6 +495,24 @@ impl Codex {
     pub(crate) async fn agent_status(&self) -> AgentStatus {
         self.agent_status.borrow().clone()
     }
+
+    pub(crate) async fn update_session_metadata(
+        &self,
+        update: SessionMetadataUpdate,
+    ) -> std::io::Result<SessionMeta> {
+        self.session.update_session_metadata(update).await
+    }
+
+    pub(crate) async fn turn_request_snapshot(
+        &self,
+        turn_id: Option<&str>,
+    ) -> Option<TurnRequestSnapshot> {
+        self.session.turn_request_snapshot(turn_id).await
+    }
+
+    pub(crate) async fn session_stats(&self) -> SessionStats {
+        self.session.state.lock().await.stats.snapshot()
+    }
 }
 
 /// Context for an initialized model agent
@@ -400,9 +523,9 @@ pub(crate) struct Session {
     tx_event: Sender<Event>,
     agent_status: watch::Sender<AgentStatus>,
     state: Mutex<SessionState>,
-    /// The set of enabled features should be invariant for the lifetime of the
-    /// session.
-    features: Features,
+    /// The set of enabled features. Only features that are read anew for
+    /// every turn change after startup, through `Op::SetFeature`.
+    features: std::sync::RwLock<Features>,
     pending_mcp_server_refresh_config: Mutex<Option<McpServerRefreshConfig>>,
     pub(crate) active_turn: Mutex<Option<ActiveTurn>>,
     pub(crate) services: SessionServices,
@@ -431,6 +554,8 @@ pub(crate) struct TurnContext {
     pub(crate) codex_linux_sandbox_exe: Option<PathBuf>,
     pub(crate) tool_call_gate: Arc<ReadinessFlag>,
     pub(crate) truncation_policy: TruncationPolicy,
+    /// Bytes written by the turn's commands and patches.
+    pub(crate) write_quota: TurnWriteQuota,
 }
 
 impl TurnContext {
@@ -440,6 +565,11 @@ impl TurnContext {
             .map_or_else(|| self.cwd.clone(), |p| self.cwd.join(p))
     }
 
+    /// Tokenizer of the turn's model.
+    pub(crate) fn tokenizer(&self) -> Arc<dyn Tokenizer> {
+        tokenizer_for_model(&self.client.get_model_info().slug)
+    }
+
     pub(crate) fn compact_prompt(&self) -> &str {
         self.compact_prompt
             .as_deref()
@@ -494,6 +624,10 @@ impl SessionConfiguration {
     pub(crate) fn apply(&self, updates: &SessionSettingsUpdate) -> ConstraintResult<Self> {
         let mut next_configuration = self.clone();
         if let Some(collaboration_mode) = updates.collaboration_mode.clone() {
+            if collaboration_mode.model() != self.collaboration_mode.model() {
+                self.original_config_do_not_use
+                    .ensure_model_allowed(collaboration_mode.model())?;
+            }
             next_configuration.collaboration_mode = collaboration_mode;
         }
         if let Some(summary) = updates.reasoning_summary {
@@ -506,6 +640,12 @@ impl SessionConfiguration {
             next_configuration.approval_policy.set(approval_policy)?;
         }
         if let Some(sandbox_policy) = updates.sandbox_policy.clone() {
+            // Mode switches (e.g. `/approvals`) send bare presets; keep the
+            // configured resource limits on them.
+            let sandbox_policy = self
+                .original_config_do_not_use
+                .workspace_write_limits
+                .apply(sandbox_policy);
             next_configuration.sandbox_policy.set(sandbox_policy)?;
         }
         if let Some(cwd) = updates.cwd.clone() {
@@ -513,6 +653,68 @@ impl SessionConfiguration {
         }
         Ok(next_configuration)
     }
+
+    fn resume_context_values(&self) -> ResumeContextValues {
+        ResumeContextValues {
+            model: self.collaboration_mode.model().to_string(),
+            effort: self.collaboration_mode.reasoning_effort(),
+            cwd: self.cwd.clone(),
+            approval_policy: self.approval_policy.value(),
+            sandbox_policy: self.sandbox_policy.get().clone(),
+        }
+    }
+
+    /// Adopts the recorded value for every change resolved to
+    /// [`ResumeContextSource::Recorded`]. Changes the current constraints
+    /// reject are downgraded to [`ResumeContextSource::Current`] in place.
+    fn apply_resume_context(&mut self, changes: &mut [ResumeContextChange]) {
+        for change in changes {
+            if change.applied() != ResumeContextSource::Recorded {
+                continue;
+            }
+            match change {
+                ResumeContextChange::Model {
+                    recorded, applied, ..
+                } => {
+                    if let Err(err) = self
+                        .original_config_do_not_use
+                        .ensure_model_allowed(recorded)
+                    {
+                        warn!("keeping current model on resume: {err}");
+                        *applied = ResumeContextSource::Current;
+                        continue;
+                    }
+                    self.collaboration_mode =
+                        self.collaboration_mode
+                            .with_updates(Some(recorded.clone()), None, None);
+                }
+                ResumeContextChange::ReasoningEffort { recorded, .. } => {
+                    self.collaboration_mode =
+                        self.collaboration_mode
+                            .with_updates(None, Some(*recorded), None);
+                }
+                ResumeContextChange::Cwd { recorded, .. } => {
+                    self.cwd = recorded.clone();
+                }
+                ResumeContextChange::ApprovalPolicy {
+                    recorded, applied, ..
+                } => {
+                    if let Err(err) = self.approval_policy.set(*recorded) {
+                        warn!("keeping current approval policy on resume: {err}");
+                        *applied = ResumeContextSource::Current;
+                    }
+                }
+                ResumeContextChange::SandboxPolicy {
+                    recorded, applied, ..
+                } => {
+                    if let Err(err) = self.sandbox_policy.set(recorded.clone()) {
+                        warn!("keeping current sandbox policy on resume: {err}");
+                        *applied = ResumeContextSource::Current;
+                    }
+                }
+            }
+        }
+    }
 }
 
 #[derive(Default, Clone)]
@@ -551,10 +753,13 @@ impl Session {
         conversation_id: ThreadId,
         sub_id: String,
     ) -> TurnContext {
-        let otel_manager = otel_manager.clone().with_model(
-            session_configuration.collaboration_mode.model(),
-            model_info.slug.as_str(),
-        );
+        let otel_manager = otel_manager
+            .clone()
+            .with_model(
+                session_configuration.collaboration_mode.model(),
+                model_info.slug.as_str(),
+            )
+            .with_turn_id(&sub_id);
         let per_turn_config = Arc::new(per_turn_config);
         let client = ModelClient::new(
             per_turn_config.clone(),
@@ -572,7 +777,9 @@ impl Session {
             model_info: &model_info,
             features: &per_turn_config.features,
             web_search_mode: per_turn_config.web_search_mode,
-        });
+        })
+        .with_forbidden_tools(per_turn_config.forbidden_tools.clone())
+        .with_provider_tools(per_turn_config.provider_tools.clone());
 
         TurnContext {
             sub_id,
@@ -591,12 +798,13 @@ impl Session {
             codex_linux_sandbox_exe: per_turn_config.codex_linux_sandbox_exe.clone(),
             tool_call_gate: Arc::new(ReadinessFlag::new()),
             truncation_policy: model_info.truncation_policy.into(),
+            write_quota: TurnWriteQuota::default(),
         }
     }
 
     #[allow(clippy::too_many_arguments)]
     async fn new(
-        session_configuration: SessionConfiguration,
+        mut session_configuration: SessionConfiguration,
         config: Arc<Config>,
         auth_manager: Arc<AuthManager>,
         models_manager: Arc<ModelsManager>,
@@ -622,6 +830,26 @@ impl Session {
 
         let forked_from_id = initial_history.forked_from_id();
 
+        // When resuming, reconcile the last recorded turn context with the
+        // current config before anything observes the session settings.
+        let resume_context = match &initial_history {
+            InitialHistory::Resumed(resumed) => {
+                last_turn_context(&resumed.history).and_then(|recorded| {
+                    let mut changes = diff_resume_context(
+                        config.resume_context,
+                        recorded,
+                        &session_configuration.resume_context_values(),
+                    );
+                    session_configuration.apply_resume_context(&mut changes);
+                    (!changes.is_empty()).then_some(ResumeContextEvent {
+                        options: config.resume_context,
+                        changes,
+                    })
+                })
+            }
+            InitialHistory::New | InitialHistory::Forked(_) => None,
+        };
+
         let (conversation_id, rollout_params) = match &initial_history {
             InitialHistory::New | InitialHistory::Forked(_) => {
                 let conversation_id = ThreadId::default();
@@ -708,6 +936,12 @@ impl Session {
             });
         }
         maybe_push_chat_wire_api_deprecation(&config, &mut post_session_configured_events);
+        if let Some(resume_context) = resume_context {
+            post_session_configured_events.push(Event {
+                id: INITIAL_SUBMIT_ID.to_owned(),
+                msg: EventMsg::ResumeContext(resume_context),
+            });
+        }
 
         let auth = auth.as_ref();
         let otel_manager = OtelManager::new(
@@ -746,10 +980,14 @@ impl Session {
             mcp_servers.keys().map(String::as_str).collect(),
             config.active_profile.clone(),
         );
+        otel_manager.exec_environment(&describe_env(
+            &config.shell_environment_policy,
+            &create_env(&config.shell_environment_policy),
+        ));
 
         let mut default_shell = shell::default_user_shell();
         // Create the mutable state for the Session.
-        if config.features.enabled(Feature::ShellSnapshot) {
+        if config.features.enabled(Feature::ShellSnapshot) && !config.incognito {
             ShellSnapshot::start_snapshotting(
                 config.codex_home.clone(),
                 conversation_id,
@@ -758,12 +996,30 @@ impl Session {
             );
         }
         let state = SessionState::new(session_configuration.clone());
+        let event_socket = if config.event_socket.enabled && !config.incognito {
+            match EventSocket::start(&config.codex_home, conversation_id).await {
+                Ok(socket) => {
+                    info!("streaming events to {}", socket.socket_path().display());
+                    Some(socket)
+                }
+                Err(err) => {
+                    warn!("failed to open event socket: {err}");
+                    None
+                }
+            }
+        } else {
+            None
+        };
 
         let services = SessionServices {
             mcp_connection_manager: Arc::new(RwLock::new(McpConnectionManager::default())),
             mcp_startup_cancellation_token: Mutex::new(CancellationToken::new()),
             unified_exec_manager: UnifiedExecProcessManager::default(),
-            notifier: UserNotifier::new(config.notify.clone()),
+            repl: ReplManager::default(),
+            notifier: UserNotifier::new(
+                config.notify.clone(),
+                config.notification_channels.clone(),
+            ),
             rollout: Mutex::new(Some(rollout_recorder)),
             user_shell: Arc::new(default_shell),
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
@@ -772,8 +1028,17 @@ impl Session {
             otel_manager,
             models_manager: Arc::clone(&models_manager),
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            approval_memory: ApprovalMemory::new(config.codex_home.clone(), &config.cwd),
+            error_fingerprints: ErrorFingerprints::new(
+                config.codex_home.clone(),
+                !config.incognito,
+            ),
+            background_compaction: BackgroundCompactor::new(),
+            project_map: ProjectMapCache::default(),
             skills_manager,
             agent_control,
+            event_socket,
+            turn_activity: Arc::new(TurnActivity::new()),
         };
 
         let sess = Arc::new(Session {
@@ -781,7 +1046,7 @@ impl Session {
             tx_event: tx_event.clone(),
             agent_status,
             state: Mutex::new(state),
-            features: config.features.clone(),
+            features: std::sync::RwLock::new(config.features.clone()),
             pending_mcp_server_refresh_config: Mutex::new(None),
             active_turn: Mutex::new(None),
             services,
@@ -806,6 +1071,7 @@ impl Session {
                 history_entry_count,
                 initial_messages,
                 rollout_path,
+                incognito: config.incognito,
             }),
         })
         .chain(post_session_configured_events.into_iter());
@@ -929,6 +1195,14 @@ impl Session {
                         .await;
                 }
 
+                {
+                    let mut state = self.state.lock().await;
+                    state.restore_user_messages(&rollout_items);
+                    state.clipboard = Clipboard::from_rollout(&rollout_items);
+                    state.exploration_findings = pinned_findings_from_rollout(&rollout_items);
+                }
+                self.restore_feature_overrides(&rollout_items);
+
                 // Seed usage info from the recorded rollout so UIs can show token counts
                 // immediately on resume/fork.
                 if let Some(info) = Self::last_token_info_from_rollout(&rollout_items) {
@@ -997,6 +1271,7 @@ impl Session {
                         msg: EventMsg::Error(ErrorEvent {
                             message: err.to_string(),
                             codex_error_info: Some(CodexErrorInfo::BadRequest),
+                            fingerprint: None,
                         }),
                     })
                     .await;
@@ -1022,7 +1297,8 @@ impl Session {
         final_output_json_schema: Option<Option<Value>>,
         sandbox_policy_changed: bool,
     ) -> Arc<TurnContext> {
-        let per_turn_config = Self::build_per_turn_config(&session_configuration);
+        let mut per_turn_config = Self::build_per_turn_config(&session_configuration);
+        per_turn_config.features = self.features();
 
         if sandbox_policy_changed {
             let sandbox_state = SandboxState {
@@ -1071,7 +1347,7 @@ impl Session {
             .await
     }
 
-    async fn get_config(&self) -> std::sync::Arc<Config> {
+    pub(crate) async fn get_config(&self) -> std::sync::Arc<Config> {
         let state = self.state.lock().await;
         state
             .session_configuration
@@ -1079,6 +1355,42 @@ impl Session {
             .clone()
     }
 
+    /// Turn context of an explore task: the session's settings with a
+    /// read-only sandbox, no approvals and only the tools `[explore]` allows.
+    pub(crate) async fn new_explore_turn(&self, sub_id: String) -> Arc<TurnContext> {
+        let session_configuration = {
+            let state = self.state.lock().await;
+            state.session_configuration.clone()
+        };
+        let mut per_turn_config = Self::build_per_turn_config(&session_configuration);
+        per_turn_config.features = self.features();
+        let model_info = self
+            .services
+            .models_manager
+            .get_model_info(
+                session_configuration.collaboration_mode.model(),
+                &per_turn_config,
+            )
+            .await;
+        let mut turn_context = Self::make_turn_context(
+            Some(Arc::clone(&self.services.auth_manager)),
+            &self.services.otel_manager,
+            session_configuration.provider.clone(),
+            &session_configuration,
+            per_turn_config,
+            model_info,
+            self.conversation_id,
+            sub_id,
+        );
+        turn_context.approval_policy = AskForApproval::Never;
+        turn_context.sandbox_policy = SandboxPolicy::new_read_only_policy();
+        // MCP tools and tools added later may write outside the sandbox, so
+        // an exploration only gets the tools it is known to be safe with.
+        turn_context.tools_config.allowed_tools =
+            Some(turn_context.client.config().explore.allowed_tools());
+        Arc::new(turn_context)
+    }
+
     pub(crate) async fn new_default_turn_with_sub_id(&self, sub_id: String) -> Arc<TurnContext> {
         let session_configuration = {
             let state = self.state.lock().await;
@@ -1212,6 +1524,54 @@ impl Session {
         update_items
     }
 
+    /// Recomputes the model-derived parts of the session when `current` runs
+    /// a different model than `previous`: base instructions are re-rendered,
+    /// and the token budget moves to the new context window. Emits a model
+    /// switch event describing what changed, including tools.
+    async fn adapt_to_model_switch(&self, previous: &TurnContext, current: &TurnContext) {
+        let previous_model = previous.client.get_model_info();
+        let next_model = current.client.get_model_info();
+        if previous_model.slug == next_model.slug {
+            return;
+        }
+
+        let next_context_window = current.client.get_model_context_window();
+        let (instructions_updated, tokens_in_context) = {
+            let mut state = self.state.lock().await;
+            let rerendered = rerender_base_instructions(
+                &state.session_configuration.base_instructions,
+                &previous_model,
+                previous.personality,
+                &next_model,
+                current.personality,
+            );
+            let instructions_updated = rerendered.is_some();
+            if let Some(base_instructions) = rerendered {
+                state.session_configuration.base_instructions = base_instructions;
+            }
+            if let Some(mut info) = state.token_info() {
+                info.model_context_window = next_context_window;
+                state.set_token_info(Some(info));
+            }
+            let tokens_in_context = state.get_total_token_usage(state.server_reasoning_included());
+            (instructions_updated, tokens_in_context)
+        };
+
+        let event = describe_model_switch(ModelSwitch {
+            previous_model: &previous_model,
+            previous_tools: &previous.tools_config,
+            previous_context_window: previous.client.get_model_context_window(),
+            next_model: &next_model,
+            next_tools: &current.tools_config,
+            next_context_window,
+            instructions_updated,
+            tokens_in_context,
+        });
+        info!("model switch: {}", event.summary());
+        self.send_event(current, EventMsg::ModelSwitch(event)).await;
+        self.send_token_count_event(current).await;
+    }
+
     /// Persist the event to rollout and send it to clients.
     pub(crate) async fn send_event(&self, turn_context: &TurnContext, msg: EventMsg) {
         let legacy_source = msg.clone();
@@ -1231,14 +1591,29 @@ impl Session {
         }
     }
 
-    pub(crate) async fn send_event_raw(&self, event: Event) {
+    pub(crate) async fn send_event_raw(&self, mut event: Event) {
+        if let EventMsg::Error(error) = &mut event.msg {
+            self.services.error_fingerprints.fingerprint(error).await;
+        }
         // Record the last known agent status.
         if let Some(status) = agent_status_from_event(&event.msg) {
             self.agent_status.send_replace(status);
         }
-        // Persist the event into rollout (recorder filters as needed)
-        let rollout_items = vec![RolloutItem::EventMsg(event.msg.clone())];
-        self.persist_rollout_items(&rollout_items).await;
+        // Persist the event into rollout (recorder filters as needed). The
+        // turn persists its final diff itself when it ends.
+        if !matches!(event.msg, EventMsg::TurnDiff(_)) {
+            let rollout_items = vec![RolloutItem::EventMsg(event.msg.clone())];
+            self.persist_rollout_items(&rollout_items).await;
+        }
+        self.services
+            .agent_control
+            .publish_event(self.conversation_id, &event);
+        if let Some(event_socket) = &self.services.event_socket {
+            event_socket.publish(&event);
+        }
+        if !matches!(event.msg, EventMsg::Heartbeat(_)) {
+            self.services.turn_activity.record_event();
+        }
         if let Err(e) = self.tx_event.send(event).await {
             debug!("dropping event because channel is closed: {e}");
         }
@@ -1249,7 +1624,10 @@ impl Session {
     /// Most events can be delivered immediately after queueing the rollout write, but some
     /// clients (e.g. app-server thread/rollback) re-read the rollout file synchronously on
     /// receipt of the event and depend on the marker already being visible on disk.
-    pub(crate) async fn send_event_raw_flushed(&self, event: Event) {
+    pub(crate) async fn send_event_raw_flushed(&self, mut event: Event) {
+        if let EventMsg::Error(error) = &mut event.msg {
+            self.services.error_fingerprints.fingerprint(error).await;
+        }
         // Record the last known agent status.
         if let Some(status) = agent_status_from_event(&event.msg) {
             self.agent_status.send_replace(status);
@@ -1257,6 +1635,15 @@ impl Session {
         self.persist_rollout_items(&[RolloutItem::EventMsg(event.msg.clone())])
             .await;
         self.flush_rollout().await;
+        self.services
+            .agent_control
+            .publish_event(self.conversation_id, &event);
+        if let Some(event_socket) = &self.services.event_socket {
+            event_socket.publish(&event);
+        }
+        if !matches!(event.msg, EventMsg::Heartbeat(_)) {
+            self.services.turn_activity.record_event();
+        }
         if let Err(e) = self.tx_event.send(event).await {
             debug!("dropping event because channel is closed: {e}");
         }
@@ -1296,7 +1683,7 @@ impl Session {
         &self,
         amendment: &ExecPolicyAmendment,
     ) -> Result<(), ExecPolicyUpdateError> {
-        let features = self.features.clone();
+        let features = self.features();
         let codex_home = self
             .state
             .lock()
@@ -1333,6 +1720,7 @@ impl Session {
         cwd: PathBuf,
         reason: Option<String>,
         proposed_execpolicy_amendment: Option<ExecPolicyAmendment>,
+        matched_approval_rule: Option<MatchedApprovalRule>,
     ) -> ReviewDecision {
         let sub_id = turn_context.sub_id.clone();
         // Add the tx_approve callback to the map before sending the request.
@@ -1352,6 +1740,14 @@ impl Session {
             warn!("Overwriting existing pending approval for sub_id: {event_id}");
         }
 
+        self.notifier()
+            .notify(&UserNotification::ApprovalRequested {
+                thread_id: self.conversation_id.to_string(),
+                turn_id: turn_context.sub_id.clone(),
+                cwd: cwd.display().to_string(),
+                call_id: call_id.clone(),
+                summary: format!("Run `{}`", shlex_join(&command)),
+            });
         let parsed_cmd = parse_command(&command);
         let event = EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
             call_id,
@@ -1360,8 +1756,10 @@ impl Session {
             cwd,
             reason,
             proposed_execpolicy_amendment,
+            matched_approval_rule,
             parsed_cmd,
         });
+        self.state.lock().await.stats.record_approval_request();
         self.send_event(turn_context, event).await;
         rx_approve.await.unwrap_or_default()
     }
@@ -1392,6 +1790,19 @@ impl Session {
             warn!("Overwriting existing pending approval for sub_id: {event_id}");
         }
 
+        let mut paths = changes
+            .keys()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>();
+        paths.sort();
+        self.notifier()
+            .notify(&UserNotification::ApprovalRequested {
+                thread_id: self.conversation_id.to_string(),
+                turn_id: turn_context.sub_id.clone(),
+                cwd: turn_context.cwd.display().to_string(),
+                call_id: call_id.clone(),
+                summary: format!("Apply changes to {}", paths.join(", ")),
+            });
         let event = EventMsg::ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent {
             call_id,
             turn_id: turn_context.sub_id.clone(),
@@ -1399,6 +1810,7 @@ impl Session {
             reason,
             grant_root,
         });
+        self.state.lock().await.stats.record_approval_request();
         self.send_event(turn_context, event).await;
         rx_approve
     }
@@ -1473,6 +1885,11 @@ impl Session {
         };
         match entry {
             Some(tx_approve) => {
+                self.state
+                    .lock()
+                    .await
+                    .stats
+                    .record_approval_decision(&decision);
                 tx_approve.send(decision).ok();
             }
             None => {
@@ -1513,12 +1930,14 @@ impl Session {
         rollout_items: &[RolloutItem],
     ) -> Vec<ResponseItem> {
         let mut history = ContextManager::new();
+        let tokenizer = turn_context.tokenizer();
         for item in rollout_items {
             match item {
                 RolloutItem::ResponseItem(response_item) => {
                     history.record_items(
                         std::iter::once(response_item),
                         turn_context.truncation_policy,
+                        tokenizer.as_ref(),
                     );
                 }
                 RolloutItem::Compacted(compacted) => {
@@ -1549,8 +1968,13 @@ impl Session {
         items: &[ResponseItem],
         turn_context: &TurnContext,
     ) {
+        let tokenizer = turn_context.tokenizer();
         let mut state = self.state.lock().await;
-        state.record_items(items.iter(), turn_context.truncation_policy);
+        state.record_items(
+            items.iter(),
+            turn_context.truncation_policy,
+            tokenizer.as_ref(),
+        );
     }
 
     pub(crate) async fn record_model_warning(&self, message: impl Into<String>, ctx: &TurnContext) {
@@ -1574,6 +1998,19 @@ impl Session {
         state.replace_history(items);
     }
 
+    /// Replaces `prefix` at the start of the history with `replacement`
+    /// under one lock, so no item can be recorded between the check and the
+    /// replacement. Returns the history before and after, or `None` when the
+    /// history no longer starts with `prefix`.
+    pub(crate) async fn replace_history_prefix(
+        &self,
+        prefix: &[ResponseItem],
+        replacement: Vec<ResponseItem>,
+    ) -> Option<(Vec<ResponseItem>, Vec<ResponseItem>)> {
+        let mut state = self.state.lock().await;
+        state.replace_history_prefix(prefix, replacement)
+    }
+
     async fn persist_rollout_response_items(&self, items: &[ResponseItem]) {
         let rollout_items: Vec<RolloutItem> = items
             .iter()
@@ -1584,11 +2021,54 @@ impl Session {
     }
 
     pub fn enabled(&self, feature: Feature) -> bool {
-        self.features.enabled(feature)
+        match self.features.read() {
+            Ok(features) => features.enabled(feature),
+            Err(err) => err.into_inner().enabled(feature),
+        }
     }
 
     pub(crate) fn features(&self) -> Features {
-        self.features.clone()
+        match self.features.read() {
+            Ok(features) => features.clone(),
+            Err(err) => err.into_inner().clone(),
+        }
+    }
+
+    /// Turns `feature` on or off for the rest of the session after checking
+    /// that it can change at runtime without breaking its constraints.
+    pub(crate) fn override_feature(&self, feature: Feature, enabled: bool) -> Result<(), String> {
+        let mut features = match self.features.write() {
+            Ok(features) => features,
+            Err(err) => err.into_inner(),
+        };
+        features.check_runtime_change(feature, enabled)?;
+        if enabled {
+            features.enable(feature);
+        } else {
+            features.disable(feature);
+        }
+        Ok(())
+    }
+
+    /// Reapplies the feature changes recorded in a rollout. Changes to
+    /// features that can no longer change at runtime are skipped.
+    fn restore_feature_overrides(&self, rollout_items: &[RolloutItem]) {
+        let mut features = match self.features.write() {
+            Ok(features) => features,
+            Err(err) => err.into_inner(),
+        };
+        for item in rollout_items {
+            if let RolloutItem::EventMsg(EventMsg::FeatureOverridden(event)) = item
+                && let Some(feature) = feature_for_key(&event.feature)
+                && feature.can_change_at_runtime()
+            {
+                if event.enabled {
+                    features.enable(feature);
+                } else {
+                    features.disable(feature);
+                }
+            }
+        }
     }
 
     pub(crate) async fn collaboration_mode(&self) -> CollaborationMode {
@@ -1642,13 +2122,29 @@ impl Session {
                 .into(),
             );
         }
-        items.push(ResponseItem::from(EnvironmentContext::new(
-            Some(turn_context.cwd.clone()),
-            shell.as_ref().clone(),
-        )));
+        items.push(ResponseItem::from(
+            EnvironmentContext::new(Some(turn_context.cwd.clone()), shell.as_ref().clone())
+                .with_locale(&turn_context.client.config().locale),
+        ));
         items
     }
 
+    pub(crate) async fn update_session_metadata(
+        &self,
+        update: SessionMetadataUpdate,
+    ) -> std::io::Result<SessionMeta> {
+        let recorder = {
+            let guard = self.services.rollout.lock().await;
+            guard.clone()
+        };
+        match recorder {
+            Some(rec) => rec.update_session_metadata(update).await,
+            None => Err(std::io::Error::other(
+                "rollout recording is disabled for this thread",
+            )),
+        }
+    }
+
     pub(crate) async fn persist_rollout_items(&self, items: &[RolloutItem]) {
         let recorder = {
             let guard = self.services.rollout.lock().await;
@@ -1666,6 +2162,81 @@ impl Session {
         state.clone_history()
     }
 
+    /// The most recent turn's checkpoint, once its capture has finished.
+    pub(crate) async fn turn_checkpoint(&self) -> Option<TurnCheckpoint> {
+        self.turn_checkpoint_slot().await.lock().await.clone()
+    }
+
+    pub(crate) async fn set_turn_checkpoint(&self, checkpoint: Option<TurnCheckpoint>) {
+        *self.turn_checkpoint_slot().await.lock().await = checkpoint;
+    }
+
+    pub(crate) async fn turn_checkpoint_slot(
+        &self,
+    ) -> Arc<tokio::sync::Mutex<Option<TurnCheckpoint>>> {
+        Arc::clone(&self.state.lock().await.turn_checkpoint)
+    }
+
+    pub(crate) async fn record_cancelled_tool_call(&self, turn_id: &str, call: CancelledToolCall) {
+        self.state
+            .lock()
+            .await
+            .interrupted_turns
+            .entry(turn_id.to_string())
+            .or_default()
+            .cancelled_tool_calls
+            .push(call);
+    }
+
+    /// Commits assistant text that was still streaming when the turn was
+    /// interrupted, so the next user message can refer to it.
+    async fn commit_partial_agent_message(
+        &self,
+        turn_context: &TurnContext,
+        item_id: String,
+        text: String,
+    ) {
+        let message = ResponseItem::Message {
+            id: None,
+            role: "assistant".to_string(),
+            content: vec![ContentItem::OutputText { text: text.clone() }],
+            end_turn: None,
+        };
+        self.record_conversation_items(turn_context, std::slice::from_ref(&message))
+            .await;
+        self.emit_turn_item_completed(
+            turn_context,
+            TurnItem::AgentMessage(AgentMessageItem {
+                id: item_id,
+                content: vec![AgentMessageContent::Text { text: text.clone() }],
+            }),
+        )
+        .await;
+        self.state
+            .lock()
+            .await
+            .interrupted_turns
+            .entry(turn_context.sub_id.clone())
+            .or_default()
+            .partial_agent_message = Some(text);
+    }
+
+    pub(crate) async fn take_interrupted_turn_progress(
+        &self,
+        turn_id: &str,
+    ) -> InterruptedTurnProgress {
+        self.state
+            .lock()
+            .await
+            .interrupted_turns
+            .remove(turn_id)
+            .unwrap_or_default()
+    }
+
+    pub(crate) async fn discard_interrupted_turns(&self) {
+        self.state.lock().await.interrupted_turns.clear();
+    }
+
     pub(crate) async fn update_token_usage_info(
         &self,
         turn_context: &TurnContext,
@@ -1678,11 +2249,203 @@ impl Session {
                     token_usage,
                     turn_context.client.get_model_context_window(),
                 );
+                let config = turn_context.client.config();
+                let pricing = config.model_pricing.get(&turn_context.client.get_model());
+                state.stats.record_token_usage(token_usage, pricing);
             }
         }
         self.send_token_count_event(turn_context).await;
     }
 
+    /// Adds `token_usage` to the session stats without treating it as the
+    /// context's usage, e.g. for usage counted with the model's tokenizer
+    /// when the provider did not report any, or for side requests such as
+    /// background summaries. The context usage comes from turn requests alone.
+    pub(crate) async fn record_token_usage_stats(
+        &self,
+        turn_context: &TurnContext,
+        token_usage: &TokenUsage,
+    ) {
+        let config = turn_context.client.config();
+        let pricing = config.model_pricing.get(&turn_context.client.get_model());
+        self.state
+            .lock()
+            .await
+            .stats
+            .record_token_usage(token_usage, pricing);
+    }
+
+    pub(crate) async fn record_tool_call_stats(&self, tool_name: &str) {
+        self.state.lock().await.stats.record_tool_call(tool_name);
+    }
+
+    /// Remember `contents` as what the model last saw of `path`.
+    pub(crate) async fn record_file_read(&self, path: PathBuf, contents: &[u8]) {
+        let path = canonical_path(&path).await;
+        self.state.lock().await.file_reads.record(path, contents);
+    }
+
+    /// Remember the files that `parsed_cmd`, run from `cwd`, printed.
+    pub(crate) async fn record_shell_reads(&self, parsed_cmd: &[ParsedCommand], cwd: &Path) {
+        for (path, contents) in shell_reads(parsed_cmd, cwd).await {
+            self.record_file_read(path, &contents).await;
+        }
+    }
+
+    pub(crate) async fn forget_file_read(&self, path: &Path) {
+        let path = canonical_path(path).await;
+        self.state.lock().await.file_reads.forget(&path);
+    }
+
+    /// Those of `paths` that changed on disk since the model last saw them.
+    pub(crate) async fn stale_file_reads(&self, paths: &[PathBuf]) -> Vec<StaleRead> {
+        let mut canonical = Vec::with_capacity(paths.len());
+        for path in paths {
+            canonical.push(canonical_path(path).await);
+        }
+        let recorded = self.state.lock().await.file_reads.recorded(&canonical);
+        find_stale_reads(recorded).await
+    }
+
+    pub(crate) async fn store_tool_output_chunks(&self, call_id: String, chunks: Vec<String>) {
+        self.state
+            .lock()
+            .await
+            .tool_output_chunks
+            .insert(call_id, chunks);
+    }
+
+    /// The 1-based `chunk` of a chunked tool output, formatted for the model.
+    pub(crate) async fn tool_output_chunk(
+        &self,
+        call_id: &str,
+        chunk: usize,
+    ) -> Result<String, String> {
+        self.state
+            .lock()
+            .await
+            .tool_output_chunks
+            .get(call_id, chunk)
+    }
+
+    /// Tells clients when the provider moved to another of its endpoints
+    /// since the last report. Starting out on the primary is not reported.
+    async fn report_provider_endpoint(
+        &self,
+        turn_context: &TurnContext,
+        endpoint: ProviderEndpointEvent,
+    ) {
+        {
+            let mut state = self.state.lock().await;
+            let previous = state
+                .reported_provider_endpoint
+                .replace(endpoint.base_url.clone());
+            let unchanged = match previous {
+                Some(previous) => previous == endpoint.base_url,
+                None => endpoint.primary,
+            };
+            if unchanged {
+                return;
+            }
+        }
+        self.send_event(turn_context, EventMsg::ProviderEndpoint(endpoint))
+            .await;
+    }
+
+    /// Stores a clip and records it in the rollout; an empty `content`
+    /// removes the clip.
+    pub(crate) async fn store_clip(
+        &self,
+        turn_context: &TurnContext,
+        name: &str,
+        content: String,
+        from_model: bool,
+    ) -> Result<(), String> {
+        self.state
+            .lock()
+            .await
+            .clipboard
+            .set(name, content.clone())?;
+        self.send_event(
+            turn_context,
+            EventMsg::ClipStored(ClipStoredEvent {
+                name: name.to_string(),
+                content,
+                from_model,
+            }),
+        )
+        .await;
+        Ok(())
+    }
+
+    pub(crate) async fn clip(&self, name: &str) -> Option<String> {
+        self.state
+            .lock()
+            .await
+            .clipboard
+            .get(name)
+            .map(str::to_string)
+    }
+
+    pub(crate) async fn describe_clipboard(&self) -> String {
+        self.state.lock().await.clipboard.describe()
+    }
+
+    /// Pins the findings of an exploration to the prompt of the following
+    /// turns and records them in the rollout.
+    pub(crate) async fn pin_exploration_findings(
+        &self,
+        turn_context: &TurnContext,
+        findings: ExplorationFindingsEvent,
+    ) {
+        self.state.lock().await.exploration_findings = Some(findings.clone());
+        self.send_event(turn_context, EventMsg::ExplorationFindings(findings))
+            .await;
+    }
+
+    /// Unpins the findings of the last exploration and records that in the
+    /// rollout so they stay unpinned on resume.
+    pub(crate) async fn clear_exploration_findings(&self, turn_context: &TurnContext) {
+        self.state.lock().await.exploration_findings = None;
+        self.send_event(turn_context, EventMsg::ExplorationFindingsCleared)
+            .await;
+    }
+
+    /// Tokens billed for the conversation so far, excluding cached input.
+    pub(crate) async fn blended_token_usage(&self) -> i64 {
+        self.state
+            .lock()
+            .await
+            .token_info()
+            .map_or(0, |info| info.total_token_usage.blended_total())
+    }
+
+    /// Context used after the last model response and the output tokens of
+    /// that response.
+    pub(crate) async fn last_response_token_usage(&self) -> (i64, i64) {
+        let state = self.state.lock().await;
+        let output_tokens = state
+            .token_info()
+            .map_or(0, |info| info.last_token_usage.output_tokens);
+        (
+            state.get_total_token_usage(state.server_reasoning_included()),
+            output_tokens,
+        )
+    }
+
+    pub(crate) async fn secret_masking_enabled(&self) -> bool {
+        !self.state.lock().await.secret_masking_disabled
+    }
+
+    /// Substitutes `{{clip:<name>}}` references in the arguments of a tool
+    /// call right before it is dispatched.
+    pub(crate) async fn expand_clip_references(
+        &self,
+        payload: ToolPayload,
+    ) -> Result<ToolPayload, String> {
+        self.state.lock().await.clipboard.expand_payload(payload)
+    }
+
     pub(crate) async fn recompute_token_usage(&self, turn_context: &TurnContext) {
         let Some(estimated_total_tokens) = self
             .clone_history()
@@ -1733,6 +2496,19 @@ impl Session {
         state.set_server_reasoning_included(included);
     }
 
+    pub(crate) async fn turn_request_snapshot(
+        &self,
+        turn_id: Option<&str>,
+    ) -> Option<TurnRequestSnapshot> {
+        let state = self.state.lock().await;
+        state.turn_request(turn_id)
+    }
+
+    pub(crate) async fn take_auto_title_request(&self) -> bool {
+        let mut state = self.state.lock().await;
+        state.take_auto_title_request()
+    }
+
     async fn send_token_count_event(&self, turn_context: &TurnContext) {
         let (info, rate_limits) = {
             let state = self.state.lock().await;
@@ -1777,7 +2553,12 @@ impl Session {
         // those spans, and `record_response_item_and_emit_turn_item` would drop them.
         self.record_conversation_items(turn_context, std::slice::from_ref(&response_item))
             .await;
-        let turn_item = TurnItem::UserMessage(UserMessageItem::new(input));
+        let user_message = UserMessageItem::new(input);
+        self.state
+            .lock()
+            .await
+            .record_user_message(user_message.id.clone(), &response_item);
+        let turn_item = TurnItem::UserMessage(user_message);
         self.emit_turn_item_started(turn_context, &turn_item).await;
         self.emit_turn_item_completed(turn_context, turn_item).await;
     }
@@ -1936,12 +2717,13 @@ impl Session {
         server: &str,
         tool: &str,
         arguments: Option<serde_json::Value>,
+        cancellation_token: CancellationToken,
     ) -> anyhow::Result<CallToolResult> {
         self.services
             .mcp_connection_manager
             .read()
             .await
-            .call_tool(server, tool, arguments)
+            .call_tool(server, tool, arguments, cancellation_token)
             .await
     }
 
@@ -2005,7 +2787,7 @@ impl Session {
         let config = self.get_config().await;
         let mcp_servers = with_codex_apps_mcp(
             mcp_servers,
-            self.features.enabled(Feature::Connectors),
+            self.enabled(Feature::Connectors),
             auth.as_ref(),
             config.as_ref(),
         );
@@ -2053,6 +2835,10 @@ impl Session {
         self.services.show_raw_agent_reasoning
     }
 
+    pub(crate) fn turn_activity(&self) -> Arc<TurnActivity> {
+        Arc::clone(&self.services.turn_activity)
+    }
+
     async fn cancel_mcp_startup(&self) {
         self.services
             .mcp_startup_cancellation_token
@@ -2140,15 +2926,56 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::ListSkills { cwds, force_reload } => {
                 handlers::list_skills(&sess, sub.id.clone(), cwds, force_reload).await;
             }
+            Op::GetSandboxPolicy => {
+                handlers::get_sandbox_policy(&sess, sub.id.clone()).await;
+            }
             Op::Undo => {
                 handlers::undo(&sess, sub.id.clone()).await;
             }
+            Op::UndoTurn => {
+                handlers::undo_turn(&sess, sub.id.clone()).await;
+            }
             Op::Compact => {
                 handlers::compact(&sess, sub.id.clone()).await;
             }
             Op::ThreadRollback { num_turns } => {
                 handlers::thread_rollback(&sess, sub.id.clone(), num_turns).await;
             }
+            Op::EditAndRegenerate { item_id, new_text } => {
+                handlers::edit_and_regenerate(
+                    &sess,
+                    sub.id.clone(),
+                    item_id,
+                    new_text,
+                    &mut previous_context,
+                )
+                .await;
+            }
+            Op::SetClip { name, content } => {
+                handlers::set_clip(&sess, sub.id.clone(), name, content).await;
+            }
+            Op::SetSecretMasking { enabled } => {
+                handlers::set_secret_masking(&sess, sub.id.clone(), enabled).await;
+            }
+            Op::TerminalInput { process_id, input } => {
+                handlers::terminal_input(&sess, sub.id.clone(), process_id, input).await;
+            }
+            Op::SetFeature {
+                feature,
+                enabled,
+                changed_by,
+            } => {
+                handlers::set_feature(&sess, sub.id.clone(), feature, enabled, changed_by).await;
+            }
+            Op::Explore { goal } => {
+                handlers::explore(&sess, sub.id.clone(), goal).await;
+            }
+            Op::ClearExplorationFindings => {
+                handlers::clear_exploration_findings(&sess, sub.id.clone()).await;
+            }
+            Op::MergeConversation { rollout_path } => {
+                handlers::merge_conversation(&sess, sub.id.clone(), rollout_path).await;
+            }
             Op::RunUserShellCommand { command } => {
                 handlers::run_user_shell_command(
                     &sess,
@@ -2187,20 +3014,28 @@ mod handlers {
 
     use crate::codex::spawn_review_thread;
     use crate::config::Config;
+    use crate::conversation_merge;
+    use crate::features::Feature;
+    use crate::features::feature_for_key;
 
     use crate::mcp::auth::compute_auth_statuses;
     use crate::mcp::collect_mcp_snapshot_from_manager;
     use crate::mcp::effective_mcp_servers;
     use crate::review_prompts::resolve_review_request;
     use crate::tasks::CompactTask;
+    use crate::tasks::ExploreTask;
     use crate::tasks::RegularTask;
     use crate::tasks::UndoTask;
+    use crate::tasks::UndoTurnTask;
     use crate::tasks::UserShellCommandTask;
+    use crate::tasks::explore_input;
     use codex_protocol::custom_prompts::CustomPrompt;
+    use codex_protocol::protocol::BackgroundEventEvent;
     use codex_protocol::protocol::CodexErrorInfo;
     use codex_protocol::protocol::ErrorEvent;
     use codex_protocol::protocol::Event;
     use codex_protocol::protocol::EventMsg;
+    use codex_protocol::protocol::FeatureOverriddenEvent;
     use codex_protocol::protocol::ListCustomPromptsResponseEvent;
     use codex_protocol::protocol::ListSkillsResponseEvent;
     use codex_protocol::protocol::McpServerRefreshConfig;
@@ -2252,6 +3087,7 @@ mod handlers {
                 msg: EventMsg::Error(ErrorEvent {
                     message: err.to_string(),
                     codex_error_info: Some(CodexErrorInfo::BadRequest),
+                    fingerprint: None,
                 }),
             })
             .await;
@@ -2259,6 +3095,8 @@ mod handlers {
         }
 
         let current_context = sess.new_default_turn_with_sub_id(sub_id).await;
+        sess.adapt_to_model_switch(&previous_context, &current_context)
+            .await;
         let update_items = sess.build_settings_update_items(
             Some(&previous_context),
             &current_context,
@@ -2331,15 +3169,46 @@ mod handlers {
             .collaboration_mode
             .clone();
         let next_collaboration_mode = updates.collaboration_mode.clone();
+        let model_switch_from = match &next_collaboration_mode {
+            Some(next) if next.model() != previous_collaboration_mode.model() => {
+                Some(sess.new_default_turn().await)
+            }
+            _ => None,
+        };
         let Ok(current_context) = sess.new_turn_with_sub_id(sub_id, updates).await else {
             // new_turn_with_sub_id already emits the error event.
             return;
         };
+        if let Some(previous_turn) = &model_switch_from {
+            sess.adapt_to_model_switch(previous_turn, &current_context)
+                .await;
+        }
         current_context
             .client
             .get_otel_manager()
             .user_prompt(&items);
 
+        let model_info = current_context.client.get_model_info();
+        if !model_info.supports_image_input()
+            && items
+                .iter()
+                .any(|item| matches!(item, UserInput::Image { .. } | UserInput::LocalImage { .. }))
+        {
+            sess.send_event_raw(Event {
+                id: current_context.sub_id.clone(),
+                msg: EventMsg::Error(ErrorEvent {
+                    message: format!(
+                        "{} does not accept image input. Remove the attached images or switch to a model that supports them.",
+                        model_info.slug
+                    ),
+                    codex_error_info: Some(CodexErrorInfo::BadRequest),
+                    fingerprint: None,
+                }),
+            })
+            .await;
+            return;
+        }
+
         // Attempt to inject input into current task
         if let Err(items) = sess.inject_input(items).await {
             let update_items = sess.build_settings_update_items(
@@ -2569,34 +3438,211 @@ mod handlers {
         sess.send_event_raw(event).await;
     }
 
-    pub async fn undo(sess: &Arc<Session>, sub_id: String) {
+    pub async fn get_sandbox_policy(sess: &Session, sub_id: String) {
+        let report = {
+            let state = sess.state.lock().await;
+            crate::sandboxing::policy_report(
+                state.session_configuration.sandbox_policy.get(),
+                &state.session_configuration.cwd,
+            )
+        };
+        let event = Event {
+            id: sub_id,
+            msg: EventMsg::SandboxPolicyReport(report),
+        };
+        sess.send_event_raw(event).await;
+    }
+
+    pub async fn undo(sess: &Arc<Session>, sub_id: String) {
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+        sess.spawn_task(turn_context, Vec::new(), UndoTask::new())
+            .await;
+    }
+
+    pub async fn undo_turn(sess: &Arc<Session>, sub_id: String) {
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+        sess.spawn_task(turn_context, Vec::new(), UndoTurnTask)
+            .await;
+    }
+
+    pub async fn compact(sess: &Arc<Session>, sub_id: String) {
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+
+        sess.spawn_task(
+            Arc::clone(&turn_context),
+            vec![UserInput::Text {
+                text: turn_context.compact_prompt().to_string(),
+                // Compaction prompt is synthesized; no UI element ranges to preserve.
+                text_elements: Vec::new(),
+            }],
+            CompactTask,
+        )
+        .await;
+    }
+
+    pub async fn set_clip(sess: &Arc<Session>, sub_id: String, name: String, content: String) {
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+        let result = if turn_context.tools_config.clipboard {
+            sess.store_clip(&turn_context, &name, content, false).await
+        } else {
+            Err("The clipboard is disabled; enable `clipboard` under [features].".to_string())
+        };
+        if let Err(message) = result {
+            sess.send_event(
+                &turn_context,
+                EventMsg::Error(ErrorEvent {
+                    message,
+                    codex_error_info: None,
+                    fingerprint: None,
+                }),
+            )
+            .await;
+        }
+    }
+
+    pub async fn set_secret_masking(sess: &Arc<Session>, sub_id: String, enabled: bool) {
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+        if !sess.features().enabled(Feature::SecretMasking) {
+            sess.send_event(
+                &turn_context,
+                EventMsg::Error(ErrorEvent {
+                    message:
+                        "Secret masking is disabled; enable `secret_masking` under [features]."
+                            .to_string(),
+                    codex_error_info: None,
+                    fingerprint: None,
+                }),
+            )
+            .await;
+            return;
+        }
+        sess.state.lock().await.secret_masking_disabled = !enabled;
+        let message = if enabled {
+            "Likely secrets in tool outputs are masked again before they reach the model."
+        } else {
+            "Tool outputs are sent to the model unmasked for the rest of this conversation."
+        };
+        sess.send_event(
+            &turn_context,
+            EventMsg::BackgroundEvent(BackgroundEventEvent {
+                message: message.to_string(),
+            }),
+        )
+        .await;
+    }
+
+    pub async fn set_feature(
+        sess: &Arc<Session>,
+        sub_id: String,
+        key: String,
+        enabled: bool,
+        changed_by: Option<String>,
+    ) {
+        let result = match feature_for_key(&key) {
+            Some(feature) => sess
+                .override_feature(feature, enabled)
+                .map(|()| feature.key().to_string()),
+            None => Err(format!("Unknown feature `{key}`.")),
+        };
+        // Build the turn after the change so that the event is sent with the
+        // features the next turn will use.
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+        let msg = match result {
+            Ok(feature) => EventMsg::FeatureOverridden(FeatureOverriddenEvent {
+                feature,
+                enabled,
+                changed_by,
+            }),
+            Err(message) => EventMsg::Error(ErrorEvent {
+                message,
+                codex_error_info: Some(CodexErrorInfo::BadRequest),
+                fingerprint: None,
+            }),
+        };
+        sess.send_event(&turn_context, msg).await;
+    }
+
+    pub async fn terminal_input(
+        sess: &Arc<Session>,
+        sub_id: String,
+        process_id: String,
+        input: String,
+    ) {
+        let result = sess
+            .services
+            .unified_exec_manager
+            .write_terminal_input(&process_id, input.as_bytes())
+            .await;
+        if let Err(err) = result {
+            let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+            sess.send_event(
+                &turn_context,
+                EventMsg::Error(ErrorEvent {
+                    message: err.to_string(),
+                    codex_error_info: None,
+                    fingerprint: None,
+                }),
+            )
+            .await;
+        }
+    }
+
+    pub async fn explore(sess: &Arc<Session>, sub_id: String, goal: String) {
+        let turn_context = sess.new_explore_turn(sub_id).await;
+        if goal.trim().is_empty() {
+            sess.send_event(
+                &turn_context,
+                EventMsg::Error(ErrorEvent {
+                    message: "An exploration needs a goal to investigate.".to_string(),
+                    codex_error_info: Some(CodexErrorInfo::BadRequest),
+                    fingerprint: None,
+                }),
+            )
+            .await;
+            return;
+        }
+        let input = explore_input(&goal, &turn_context.client.config().explore);
+        sess.spawn_task(turn_context, input, ExploreTask::new(goal))
+            .await;
+    }
+
+    pub async fn clear_exploration_findings(sess: &Arc<Session>, sub_id: String) {
         let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
-        sess.spawn_task(turn_context, Vec::new(), UndoTask::new())
-            .await;
+        sess.clear_exploration_findings(&turn_context).await;
     }
 
-    pub async fn compact(sess: &Arc<Session>, sub_id: String) {
+    pub async fn merge_conversation(sess: &Arc<Session>, sub_id: String, rollout_path: PathBuf) {
         let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
-
-        sess.spawn_task(
-            Arc::clone(&turn_context),
-            vec![UserInput::Text {
-                text: turn_context.compact_prompt().to_string(),
-                // Compaction prompt is synthesized; no UI element ranges to preserve.
-                text_elements: Vec::new(),
-            }],
-            CompactTask,
-        )
-        .await;
+        let msg = match conversation_merge::merge_conversation(sess, &turn_context, &rollout_path)
+            .await
+        {
+            Ok(event) => EventMsg::ConversationMerged(event),
+            Err(message) => EventMsg::Error(ErrorEvent {
+                message,
+                codex_error_info: Some(CodexErrorInfo::ConversationMergeFailed),
+                fingerprint: None,
+            }),
+        };
+        sess.send_event(&turn_context, msg).await;
     }
 
     pub async fn thread_rollback(sess: &Arc<Session>, sub_id: String, num_turns: u32) {
+        rollback_turns(sess, sub_id, num_turns, None).await;
+    }
+
+    async fn rollback_turns(
+        sess: &Arc<Session>,
+        sub_id: String,
+        num_turns: u32,
+        edited_item_id: Option<String>,
+    ) {
         if num_turns == 0 {
             sess.send_event_raw(Event {
                 id: sub_id,
                 msg: EventMsg::Error(ErrorEvent {
                     message: "num_turns must be >= 1".to_string(),
                     codex_error_info: Some(CodexErrorInfo::ThreadRollbackFailed),
+                    fingerprint: None,
                 }),
             })
             .await;
@@ -2610,6 +3656,7 @@ mod handlers {
                 msg: EventMsg::Error(ErrorEvent {
                     message: "Cannot rollback while a turn is in progress.".to_string(),
                     codex_error_info: Some(CodexErrorInfo::ThreadRollbackFailed),
+                    fingerprint: None,
                 }),
             })
             .await;
@@ -2628,17 +3675,88 @@ mod handlers {
 
         sess.send_event_raw_flushed(Event {
             id: turn_context.sub_id.clone(),
-            msg: EventMsg::ThreadRolledBack(ThreadRolledBackEvent { num_turns }),
+            msg: EventMsg::ThreadRolledBack(ThreadRolledBackEvent {
+                num_turns,
+                edited_item_id,
+            }),
         })
         .await;
     }
 
+    /// Drop every turn starting at the user message `item_id` and submit
+    /// `new_text` in its place as a fresh user turn.
+    pub async fn edit_and_regenerate(
+        sess: &Arc<Session>,
+        sub_id: String,
+        item_id: String,
+        new_text: String,
+        previous_context: &mut Option<Arc<TurnContext>>,
+    ) {
+        let has_active_turn = { sess.active_turn.lock().await.is_some() };
+        if has_active_turn {
+            sess.send_event_raw(Event {
+                id: sub_id,
+                msg: EventMsg::Error(ErrorEvent {
+                    message: "Cannot edit a message while a turn is in progress.".to_string(),
+                    codex_error_info: Some(CodexErrorInfo::ThreadRollbackFailed),
+                    fingerprint: None,
+                }),
+            })
+            .await;
+            return;
+        }
+
+        let (num_turns, images) = {
+            let state = sess.state.lock().await;
+            (
+                state.user_turns_from_message(&item_id),
+                state.user_message_images(&item_id),
+            )
+        };
+        let Some(num_turns) = num_turns else {
+            sess.send_event_raw(Event {
+                id: sub_id,
+                msg: EventMsg::Error(ErrorEvent {
+                    message: format!(
+                        "Message {item_id} is not part of the current conversation context."
+                    ),
+                    codex_error_info: Some(CodexErrorInfo::ThreadRollbackFailed),
+                    fingerprint: None,
+                }),
+            })
+            .await;
+            return;
+        };
+
+        rollback_turns(sess, sub_id.clone(), num_turns, Some(item_id)).await;
+        let mut items = vec![UserInput::Text {
+            text: new_text,
+            text_elements: Vec::new(),
+        }];
+        items.extend(
+            images
+                .into_iter()
+                .map(|image_url| UserInput::Image { image_url }),
+        );
+        user_input_or_turn(
+            sess,
+            sub_id,
+            Op::UserInput {
+                items,
+                final_output_json_schema: None,
+            },
+            previous_context,
+        )
+        .await;
+    }
+
     pub async fn shutdown(sess: &Arc<Session>, sub_id: String) -> bool {
         sess.abort_all_tasks(TurnAbortReason::Interrupted).await;
         sess.services
             .unified_exec_manager
             .terminate_all_processes()
             .await;
+        sess.services.repl.terminate().await;
         info!("Shutting down Codex instance");
         let history = sess.clone_history().await;
         let turn_count = history
@@ -2658,6 +3776,16 @@ mod handlers {
             let mut guard = sess.services.rollout.lock().await;
             guard.take()
         };
+        if let Some(rec) = recorder_opt.as_ref() {
+            let stats = sess.state.lock().await.stats.snapshot();
+            let update = SessionMetadataUpdate {
+                stats: Some(stats),
+                ..SessionMetadataUpdate::default()
+            };
+            if let Err(e) = rec.update_session_metadata(update).await {
+                warn!("failed to record session stats: {e}");
+            }
+        }
         if let Some(rec) = recorder_opt
             && let Err(e) = rec.shutdown().await
         {
@@ -2667,6 +3795,7 @@ mod handlers {
                 msg: EventMsg::Error(ErrorEvent {
                     message: "Failed to shutdown rollout recorder".to_string(),
                     codex_error_info: Some(CodexErrorInfo::Other),
+                    fingerprint: None,
                 }),
             };
             sess.send_event_raw(event).await;
@@ -2705,6 +3834,7 @@ mod handlers {
                     msg: EventMsg::Error(ErrorEvent {
                         message: err.to_string(),
                         codex_error_info: Some(CodexErrorInfo::Other),
+                        fingerprint: None,
                     }),
                 };
                 sess.send_event(&turn_context, event.msg).await;
@@ -2731,7 +3861,7 @@ async fn spawn_review_thread(
         .get_model_info(&model, &config)
         .await;
     // For reviews, disable web_search and view_image regardless of global settings.
-    let mut review_features = sess.features.clone();
+    let mut review_features = sess.features();
     review_features
         .disable(crate::features::Feature::WebSearchRequest)
         .disable(crate::features::Feature::WebSearchCached);
@@ -2740,7 +3870,8 @@ async fn spawn_review_thread(
         model_info: &review_model_info,
         features: &review_features,
         web_search_mode: Some(review_web_search_mode),
-    });
+    })
+    .with_forbidden_tools(config.forbidden_tools.clone());
 
     let review_prompt = resolved.prompt.clone();
     let provider = parent_turn_context.client.get_provider();
@@ -2756,7 +3887,8 @@ async fn spawn_review_thread(
     let otel_manager = parent_turn_context
         .client
         .get_otel_manager()
-        .with_model(model.as_str(), review_model_info.slug.as_str());
+        .with_model(model.as_str(), review_model_info.slug.as_str())
+        .with_turn_id(&sub_id);
 
     let per_turn_config = Arc::new(per_turn_config);
     let client = ModelClient::new(
@@ -2788,6 +3920,7 @@ async fn spawn_review_thread(
         codex_linux_sandbox_exe: parent_turn_context.codex_linux_sandbox_exe.clone(),
         tool_call_gate: Arc::new(ReadinessFlag::new()),
         truncation_policy: model_info.truncation_policy.into(),
+        write_quota: TurnWriteQuota::default(),
     };
 
     // Seed the child task with the review prompt as the initial user message.
@@ -2797,12 +3930,14 @@ async fn spawn_review_thread(
         text_elements: Vec::new(),
     }];
     let tc = Arc::new(review_turn_context);
-    sess.spawn_task(tc.clone(), input, ReviewTask::new()).await;
+    sess.spawn_task(tc.clone(), input, ReviewTask::new(resolved.paths.clone()))
+        .await;
 
     // Announce entering review mode so UIs can switch modes.
     let review_request = ReviewRequest {
         target: resolved.target,
         user_facing_hint: Some(resolved.user_facing_hint),
+        paths: resolved.paths,
     };
     sess.send_event(&tc, EventMsg::EnteredReviewMode(review_request))
         .await;
@@ -2880,6 +4015,7 @@ pub(crate) async fn run_turn(
         model_context_window: turn_context.client.get_model_context_window(),
     });
     sess.send_event(&turn_context, event).await;
+    sess.state.lock().await.stats.record_turn();
 
     let skills_outcome = Some(
         sess.services
@@ -2899,6 +4035,15 @@ pub(crate) async fn run_turn(
             .await;
     }
 
+    let KnowledgeInjections {
+        items: knowledge_items,
+        event: knowledge_event,
+    } = build_knowledge_injections(&sess, &turn_context, &input).await;
+    if let Some(event) = knowledge_event {
+        sess.send_event(&turn_context, EventMsg::KnowledgeInjected(event))
+            .await;
+    }
+
     let initial_input_for_turn: ResponseInputItem = ResponseInputItem::from(input.clone());
     let response_item: ResponseItem = initial_input_for_turn.clone().into();
     sess.record_user_prompt_and_emit_turn_item(turn_context.as_ref(), &input, response_item)
@@ -2908,6 +4053,10 @@ pub(crate) async fn run_turn(
         sess.record_conversation_items(&turn_context, &skill_items)
             .await;
     }
+    if !knowledge_items.is_empty() {
+        sess.record_conversation_items(&turn_context, &knowledge_items)
+            .await;
+    }
 
     sess.maybe_start_ghost_snapshot(Arc::clone(&turn_context), cancellation_token.child_token())
         .await;
@@ -2915,6 +4064,7 @@ pub(crate) async fn run_turn(
     // Although from the perspective of codex.rs, TurnDiffTracker has the lifecycle of a Task which contains
     // many turns, from the perspective of the user, it is a single turn.
     let turn_diff_tracker = Arc::new(tokio::sync::Mutex::new(TurnDiffTracker::new()));
+    let _milestone_guard = spawn_milestone_notifications(&sess, &turn_context);
 
     let mut client_session = turn_context.client.new_session();
 
@@ -2933,7 +4083,12 @@ pub(crate) async fn run_turn(
         let sampling_request_input: Vec<ResponseItem> = {
             sess.record_conversation_items(&turn_context, &pending_input)
                 .await;
-            sess.clone_history().await.for_prompt()
+            let mut input = sess.clone_history().await.for_prompt();
+            // Pinned outside the history so that compaction keeps the findings.
+            if let Some(findings) = sess.state.lock().await.exploration_findings.as_ref() {
+                input.insert(0, findings_message(findings));
+            }
+            input
         };
 
         let sampling_request_input_messages = sampling_request_input
@@ -2970,6 +4125,7 @@ pub(crate) async fn run_turn(
 
                 if !needs_follow_up {
                     last_agent_message = sampling_request_last_agent_message;
+                    maybe_spawn_auto_title(&sess, &sampling_request_input_messages).await;
                     sess.notifier()
                         .notify(&UserNotification::AgentTurnComplete {
                             thread_id: sess.conversation_id.to_string(),
@@ -2998,12 +4154,19 @@ pub(crate) async fn run_turn(
                     message: "Invalid image in your last message. Please remove it and try again."
                         .to_string(),
                     codex_error_info: Some(CodexErrorInfo::BadRequest),
+                    fingerprint: None,
                 });
                 sess.send_event(&turn_context, event).await;
                 break;
             }
             Err(e) => {
                 info!("Turn error: {e:#}");
+                sess.notifier().notify(&UserNotification::Error {
+                    thread_id: sess.conversation_id.to_string(),
+                    turn_id: turn_context.sub_id.clone(),
+                    cwd: turn_context.cwd.display().to_string(),
+                    message: e.to_string(),
+                });
                 let event = EventMsg::Error(e.to_error_event(None));
                 sess.send_event(&turn_context, event).await;
                 // let the user continue the conversation
@@ -3012,10 +4175,62 @@ pub(crate) async fn run_turn(
         }
     }
 
+    // The rollout keeps only the turn's final diff: the cumulative diff sent
+    // after every patch would make it grow quadratically with the turn.
+    let final_diff = turn_diff_tracker.lock().await.get_unified_diff();
+    if let Ok(Some(unified_diff)) = final_diff {
+        sess.persist_rollout_items(&[RolloutItem::EventMsg(EventMsg::TurnDiff(TurnDiffEvent {
+            unified_diff,
+        }))])
+        .await;
+    }
+    capture_turn_end_checkpoint(&sess, &turn_context).await;
+
     last_agent_message
 }
 
+/// Periodically notifies the user while a turn keeps running. The returned
+/// guard stops the notifications when the turn ends.
+fn spawn_milestone_notifications(
+    sess: &Arc<Session>,
+    turn_context: &Arc<TurnContext>,
+) -> Option<DropGuard> {
+    let interval = sess.notifier().milestone_interval()?;
+    let token = CancellationToken::new();
+    let sess = Arc::clone(sess);
+    let turn_id = turn_context.sub_id.clone();
+    let cwd = turn_context.cwd.display().to_string();
+    let cancelled = token.clone();
+    tokio::spawn(async move {
+        let started = std::time::Instant::now();
+        loop {
+            tokio::select! {
+                _ = cancelled.cancelled() => break,
+                _ = tokio::time::sleep(interval) => {
+                    sess.notifier().notify(&UserNotification::LongRunMilestone {
+                        thread_id: sess.conversation_id.to_string(),
+                        turn_id: turn_id.clone(),
+                        cwd: cwd.clone(),
+                        elapsed_minutes: started.elapsed().as_secs() / 60,
+                    });
+                }
+            }
+        }
+    });
+    Some(token.drop_guard())
+}
+
 async fn run_auto_compact(sess: &Arc<Session>, turn_context: &Arc<TurnContext>) {
+    let auto_compact_limit = turn_context
+        .client
+        .get_model_info()
+        .auto_compact_token_limit()
+        .unwrap_or(i64::MAX);
+    if background_compaction::apply_warm_summary(sess, turn_context).await
+        && sess.get_total_token_usage().await < auto_compact_limit
+    {
+        return;
+    }
     if should_use_remote_compact_task(sess.as_ref(), &turn_context.client.get_provider()) {
         run_inline_remote_auto_compact_task(Arc::clone(sess), Arc::clone(turn_context)).await;
     } else {
@@ -3132,16 +4347,17 @@ async fn run_sampling_request(
 
     let base_instructions = sess.get_base_instructions().await;
 
-    let prompt = Prompt {
+    let prompt = Arc::new(Prompt {
         input,
         tools: router.specs(),
         parallel_tool_calls: model_supports_parallel,
         base_instructions,
         personality: turn_context.personality,
         output_schema: turn_context.final_output_json_schema.clone(),
-    };
+    });
 
     let mut retries = 0;
+    let mut usage_limit_waited = Duration::ZERO;
     loop {
         let err = match try_run_sampling_request(
             Arc::clone(&router),
@@ -3155,15 +4371,27 @@ async fn run_sampling_request(
         .await
         {
             Ok(output) => return Ok(output),
-            Err(CodexErr::ContextWindowExceeded) => {
+            Err(e @ (CodexErr::ContextWindowExceeded | CodexErr::ContextOverflow(_))) => {
                 sess.set_total_tokens_full(&turn_context).await;
-                return Err(CodexErr::ContextWindowExceeded);
+                return Err(e);
             }
             Err(CodexErr::UsageLimitReached(e)) => {
                 let rate_limits = e.rate_limits.clone();
                 if let Some(rate_limits) = rate_limits {
                     sess.update_rate_limits(&turn_context, rate_limits).await;
                 }
+                let config = turn_context.client.config();
+                if let Some(delay) = usage_limit_resume_delay(
+                    &config.usage_limit_wait,
+                    &e,
+                    usage_limit_waited,
+                    Utc::now(),
+                ) {
+                    park_until_usage_limit_resets(&sess, &turn_context, delay, &cancellation_token)
+                        .await?;
+                    usage_limit_waited += delay;
+                    continue;
+                }
                 return Err(CodexErr::UsageLimitReached(e));
             }
             Err(err) => err,
@@ -3204,6 +4432,70 @@ async fn run_sampling_request(
     }
 }
 
+/// Extra time to wait past the advertised reset so the first request after
+/// resuming does not race the provider's window rollover.
+const USAGE_LIMIT_RESUME_GRACE: Duration = Duration::from_secs(30);
+
+/// How long a turn that hit the usage cap should be parked before retrying,
+/// or `None` if it should fail instead. The reset time comes from the error
+/// itself, falling back to the exhausted rate-limit windows.
+fn usage_limit_resume_delay(
+    settings: &UsageLimitWait,
+    err: &UsageLimitReachedError,
+    already_waited: Duration,
+    now: DateTime<Utc>,
+) -> Option<Duration> {
+    if !settings.enabled {
+        return None;
+    }
+    let resets_at = err.resets_at.or_else(|| {
+        let rate_limits = err.rate_limits.as_ref()?;
+        [rate_limits.primary.as_ref(), rate_limits.secondary.as_ref()]
+            .into_iter()
+            .flatten()
+            .filter(|window| window.used_percent >= 100.0)
+            .filter_map(|window| window.resets_at)
+            .max()
+            .and_then(|seconds| DateTime::<Utc>::from_timestamp(seconds, 0))
+    })?;
+    let delay = (resets_at - now).to_std().unwrap_or_default() + USAGE_LIMIT_RESUME_GRACE;
+    (already_waited + delay <= settings.max_wait()).then_some(delay)
+}
+
+async fn park_until_usage_limit_resets(
+    sess: &Session,
+    turn_context: &TurnContext,
+    delay: Duration,
+    cancellation_token: &CancellationToken,
+) -> CodexResult<()> {
+    let resumes_at = Utc::now() + delay;
+    let resumes_at_display = turn_context
+        .client
+        .config()
+        .locale
+        .format_timestamp(resumes_at, "%Y-%m-%d %H:%M");
+    warn!("usage limit reached; parking turn until {resumes_at}");
+    sess.send_event(
+        turn_context,
+        EventMsg::Warning(WarningEvent {
+            message: format!(
+                "Usage limit reached. This turn will resume automatically at {resumes_at_display}."
+            ),
+        }),
+    )
+    .await;
+    sess.notifier().notify(&UserNotification::UsageLimitWait {
+        thread_id: sess.conversation_id.to_string(),
+        turn_id: turn_context.sub_id.clone(),
+        cwd: turn_context.cwd.display().to_string(),
+        resumes_at: resumes_at.to_rfc3339(),
+    });
+    tokio::time::sleep(delay)
+        .or_cancel(cancellation_token)
+        .await?;
+    Ok(())
+}
+
 #[derive(Debug)]
 struct SamplingRequestResult {
     needs_follow_up: bool,
@@ -3243,7 +4535,7 @@ async fn try_run_sampling_request(
     turn_context: Arc<TurnContext>,
     client_session: &mut ModelClientSession,
     turn_diff_tracker: SharedTurnDiffTracker,
-    prompt: &Prompt,
+    prompt: &Arc<Prompt>,
     cancellation_token: CancellationToken,
 ) -> CodexResult<SamplingRequestResult> {
     // TODO: If we need to guarantee the persisted mode always matches the prompt used for this
@@ -3272,15 +4564,30 @@ async fn try_run_sampling_request(
         sandbox_policy = turn_context.sandbox_policy,
         effort = turn_context.client.get_reasoning_effort(),
         auth_mode = sess.services.auth_manager.get_auth_mode(),
-        features = sess.features.enabled_features(),
+        features = sess.features().enabled_features(),
     );
 
     sess.persist_rollout_items(&[rollout_item]).await;
+    {
+        let mut state = sess.state.lock().await;
+        state.record_turn_request(
+            &turn_context.sub_id,
+            turn_context.client.get_model(),
+            Arc::clone(prompt),
+        );
+    }
     let mut stream = client_session
         .stream(prompt)
         .instrument(trace_span!("stream_request"))
         .or_cancel(&cancellation_token)
         .await??;
+    if let Some(rate_limits) = turn_context.client.provider_rate_limits().await {
+        sess.send_event(&turn_context, EventMsg::ProviderRateLimits(rate_limits))
+            .await;
+    }
+    if let Some(endpoint) = turn_context.client.provider_endpoint().await {
+        sess.report_provider_endpoint(&turn_context, endpoint).await;
+    }
 
     let tool_runtime = ToolCallRuntime::new(
         Arc::clone(&router),
@@ -3293,7 +4600,13 @@ async fn try_run_sampling_request(
     let mut needs_follow_up = false;
     let mut last_agent_message: Option<String> = None;
     let mut active_item: Option<TurnItem> = None;
+    // Text streamed so far for the active agent message, kept if the turn is
+    // interrupted before the item completes.
+    let mut partial_agent_text = String::new();
+    let mut interrupted_message: Option<(String, String)> = None;
     let mut should_emit_turn_diff = false;
+    let mut forecaster =
+        TokenForecaster::for_request(&sess, &turn_context, client_session.prompt_tokens()).await;
     let receiving_span = trace_span!("receiving_stream");
     let outcome: CodexResult<SamplingRequestResult> = loop {
         let handle_responses = trace_span!(
@@ -3311,7 +4624,14 @@ async fn try_run_sampling_request(
             .await
         {
             Ok(event) => event,
-            Err(codex_async_utils::CancelErr::Cancelled) => break Err(CodexErr::TurnAborted),
+            Err(codex_async_utils::CancelErr::Cancelled) => {
+                if let Some(TurnItem::AgentMessage(item)) = active_item.take()
+                    && !partial_agent_text.is_empty()
+                {
+                    interrupted_message = Some((item.id, std::mem::take(&mut partial_agent_text)));
+                }
+                break Err(CodexErr::TurnAborted);
+            }
         };
 
         let event = match event {
@@ -3331,7 +4651,9 @@ async fn try_run_sampling_request(
         match event {
             ResponseEvent::Created => {}
             ResponseEvent::OutputItemDone(item) => {
+                forecaster.on_item_done(&item);
                 let previously_active_item = active_item.take();
+                partial_agent_text.clear();
                 let mut ctx = HandleOutputCtx {
                     sess: sess.clone(),
                     turn_context: turn_context.clone(),
@@ -3356,6 +4678,7 @@ async fn try_run_sampling_request(
                     sess.emit_turn_item_started(&turn_context, &turn_item).await;
 
                     active_item = Some(tracked_item);
+                    partial_agent_text.clear();
                 }
             }
             ResponseEvent::ServerReasoningIncluded(included) => {
@@ -3380,6 +4703,10 @@ async fn try_run_sampling_request(
             } => {
                 sess.update_token_usage_info(&turn_context, token_usage.as_ref())
                     .await;
+                if token_usage.is_none() {
+                    sess.record_token_usage_stats(&turn_context, &forecaster.estimated_usage())
+                        .await;
+                }
                 should_emit_turn_diff = true;
 
                 needs_follow_up |= sess.has_pending_input().await;
@@ -3392,7 +4719,11 @@ async fn try_run_sampling_request(
             ResponseEvent::OutputTextDelta(delta) => {
                 // In review child threads, suppress assistant text deltas; the
                 // UI will show a selection popup from the final ReviewOutput.
+                forecaster.on_delta(&delta);
                 if let Some(active) = active_item.as_ref() {
+                    if matches!(active, TurnItem::AgentMessage(_)) {
+                        partial_agent_text.push_str(&delta);
+                    }
                     let event = AgentMessageContentDeltaEvent {
                         thread_id: sess.conversation_id.to_string(),
                         turn_id: turn_context.sub_id.clone(),
@@ -3409,6 +4740,7 @@ async fn try_run_sampling_request(
                 delta,
                 summary_index,
             } => {
+                forecaster.on_delta(&delta);
                 if let Some(active) = active_item.as_ref() {
                     let event = ReasoningContentDeltaEvent {
                         thread_id: sess.conversation_id.to_string(),
@@ -3439,6 +4771,7 @@ async fn try_run_sampling_request(
                 delta,
                 content_index,
             } => {
+                forecaster.on_delta(&delta);
                 if let Some(active) = active_item.as_ref() {
                     let event = ReasoningRawContentDeltaEvent {
                         thread_id: sess.conversation_id.to_string(),
@@ -3454,10 +4787,22 @@ async fn try_run_sampling_request(
                 }
             }
         }
+
+        if let Some(forecast) = forecaster.poll(tokio::time::Instant::now()) {
+            sess.send_event(&turn_context, EventMsg::TokenForecast(forecast))
+                .await;
+        }
     };
 
     drain_in_flight(&mut in_flight, sess.clone(), turn_context.clone()).await?;
 
+    // Committed after the cancelled tool outputs so every call in history is
+    // still directly followed by its output.
+    if let Some((item_id, text)) = interrupted_message {
+        sess.commit_partial_agent_message(&turn_context, item_id, text)
+            .await;
+    }
+
     if should_emit_turn_diff {
         let unified_diff = {
             let mut tracker = turn_diff_tracker.lock().await;
@@ -3870,6 +5215,216 @@ mod tests {
         assert_eq!(initial_context, history.raw_items());
     }
 
+    #[tokio::test]
+    async fn user_turns_from_message_tracks_recorded_user_messages() {
+        let (sess, tc, _rx) = make_session_and_context_with_rx().await;
+
+        let initial_context = sess.build_initial_context(tc.as_ref()).await;
+        sess.record_into_history(&initial_context, tc.as_ref())
+            .await;
+
+        let user_message = |text: &str| ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+            end_turn: None,
+        };
+        let assistant_message = ResponseItem::Message {
+            id: None,
+            role: "assistant".to_string(),
+            content: vec![ContentItem::OutputText {
+                text: "done".to_string(),
+            }],
+            end_turn: None,
+        };
+
+        for (item_id, text) in [("msg-1", "turn 1 user"), ("msg-2", "turn 2 user")] {
+            let item = user_message(text);
+            sess.record_into_history(std::slice::from_ref(&item), tc.as_ref())
+                .await;
+            sess.state
+                .lock()
+                .await
+                .record_user_message(item_id.to_string(), &item);
+            sess.record_into_history(std::slice::from_ref(&assistant_message), tc.as_ref())
+                .await;
+        }
+
+        {
+            let state = sess.state.lock().await;
+            assert_eq!(state.user_turns_from_message("msg-1"), Some(2));
+            assert_eq!(state.user_turns_from_message("msg-2"), Some(1));
+            assert_eq!(state.user_turns_from_message("missing"), None);
+        }
+
+        // Once the message is no longer in history (e.g. compacted away), it
+        // can no longer be edited.
+        sess.replace_history(vec![user_message("summary")]).await;
+        let state = sess.state.lock().await;
+        assert_eq!(state.user_turns_from_message("msg-1"), None);
+    }
+
+    #[tokio::test]
+    async fn restore_user_messages_rebuilds_editable_messages_from_rollout() {
+        let (sess, tc, _rx) = make_session_and_context_with_rx().await;
+
+        let user_message = |content: Vec<ContentItem>| ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content,
+            end_turn: None,
+        };
+        let with_image = user_message(vec![
+            ContentItem::InputText {
+                text: "what is this?".to_string(),
+            },
+            ContentItem::InputImage {
+                image_url: "data:image/png;base64,AAAA".to_string(),
+            },
+        ]);
+        let retried = user_message(vec![ContentItem::InputText {
+            text: "try again".to_string(),
+        }]);
+        let user_event = |item_id: &str| {
+            RolloutItem::EventMsg(EventMsg::UserMessage(crate::protocol::UserMessageEvent {
+                message: String::new(),
+                images: None,
+                local_images: Vec::new(),
+                text_elements: Vec::new(),
+                item_id: Some(item_id.to_string()),
+            }))
+        };
+        let rollout_items = vec![
+            RolloutItem::ResponseItem(with_image.clone()),
+            user_event("msg-1"),
+            RolloutItem::ResponseItem(retried.clone()),
+            user_event("msg-2"),
+            RolloutItem::EventMsg(EventMsg::ThreadRolledBack(
+                crate::protocol::ThreadRolledBackEvent {
+                    num_turns: 1,
+                    edited_item_id: None,
+                },
+            )),
+            RolloutItem::ResponseItem(retried),
+            user_event("msg-3"),
+        ];
+
+        let history = sess
+            .reconstruct_history_from_rollout(tc.as_ref(), &rollout_items)
+            .await;
+        sess.record_into_history(&history, tc.as_ref()).await;
+        let mut state = sess.state.lock().await;
+        state.restore_user_messages(&rollout_items);
+
+        assert_eq!(state.user_turns_from_message("msg-1"), Some(2));
+        assert_eq!(state.user_turns_from_message("msg-2"), None);
+        assert_eq!(state.user_turns_from_message("msg-3"), Some(1));
+        assert_eq!(
+            state.user_message_images("msg-1"),
+            vec!["data:image/png;base64,AAAA".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn edit_and_regenerate_fails_for_unknown_message() {
+        let (sess, tc, rx) = make_session_and_context_with_rx().await;
+
+        let initial_context = sess.build_initial_context(tc.as_ref()).await;
+        sess.record_into_history(&initial_context, tc.as_ref())
+            .await;
+
+        let mut previous_context = None;
+        handlers::edit_and_regenerate(
+            &sess,
+            "sub-1".to_string(),
+            "missing".to_string(),
+            "new text".to_string(),
+            &mut previous_context,
+        )
+        .await;
+
+        let error_event = wait_for_thread_rollback_failed(&rx).await;
+        assert_eq!(
+            error_event.message,
+            "Message missing is not part of the current conversation context."
+        );
+
+        let history = sess.clone_history().await;
+        assert_eq!(initial_context, history.raw_items());
+    }
+
+    #[test]
+    fn usage_limit_resume_delay_respects_settings() {
+        let now = Utc::now();
+        let enabled = UsageLimitWait {
+            enabled: true,
+            max_wait_minutes: Some(60),
+        };
+        let in_ten_minutes = UsageLimitReachedError {
+            plan_type: None,
+            resets_at: Some(now + chrono::Duration::minutes(10)),
+            rate_limits: None,
+        };
+
+        assert_eq!(
+            usage_limit_resume_delay(&enabled, &in_ten_minutes, Duration::ZERO, now),
+            Some(Duration::from_secs(600) + USAGE_LIMIT_RESUME_GRACE)
+        );
+        assert_eq!(
+            usage_limit_resume_delay(
+                &UsageLimitWait::default(),
+                &in_ten_minutes,
+                Duration::ZERO,
+                now
+            ),
+            None
+        );
+        assert_eq!(
+            usage_limit_resume_delay(&enabled, &in_ten_minutes, Duration::from_secs(55 * 60), now),
+            None
+        );
+    }
+
+    #[test]
+    fn usage_limit_resume_delay_falls_back_to_exhausted_window() {
+        let now = Utc::now();
+        let enabled = UsageLimitWait {
+            enabled: true,
+            max_wait_minutes: None,
+        };
+        let resets_at = now.timestamp() + 3600;
+        let err = UsageLimitReachedError {
+            plan_type: None,
+            resets_at: None,
+            rate_limits: Some(RateLimitSnapshot {
+                primary: Some(RateLimitWindow {
+                    used_percent: 40.0,
+                    window_minutes: Some(300),
+                    resets_at: Some(now.timestamp() + 60),
+                }),
+                secondary: Some(RateLimitWindow {
+                    used_percent: 100.0,
+                    window_minutes: Some(10080),
+                    resets_at: Some(resets_at),
+                }),
+                credits: None,
+                plan_type: None,
+            }),
+        };
+
+        let delay = usage_limit_resume_delay(&enabled, &err, Duration::ZERO, now)
+            .expect("should wait for the exhausted window");
+        let expected = DateTime::<Utc>::from_timestamp(resets_at, 0)
+            .expect("timestamp")
+            .signed_duration_since(now)
+            .to_std()
+            .expect("positive")
+            + USAGE_LIMIT_RESUME_GRACE;
+        assert_eq!(delay, expected);
+    }
+
     #[tokio::test]
     async fn set_rate_limits_retains_previous_credits() {
         let codex_home = tempfile::tempdir().expect("create temp dir");
@@ -4256,7 +5811,8 @@ mod tests {
             mcp_connection_manager: Arc::new(RwLock::new(McpConnectionManager::default())),
             mcp_startup_cancellation_token: Mutex::new(CancellationToken::new()),
             unified_exec_manager: UnifiedExecProcessManager::default(),
-            notifier: UserNotifier::new(None),
+            repl: ReplManager::default(),
+            notifier: UserNotifier::default(),
             rollout: Mutex::new(None),
             user_shell: Arc::new(default_user_shell()),
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
@@ -4265,8 +5821,17 @@ mod tests {
             otel_manager: otel_manager.clone(),
             models_manager: Arc::clone(&models_manager),
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            approval_memory: ApprovalMemory::new(config.codex_home.clone(), &config.cwd),
+            error_fingerprints: ErrorFingerprints::new(
+                config.codex_home.clone(),
+                !config.incognito,
+            ),
+            background_compaction: BackgroundCompactor::new(),
+            project_map: ProjectMapCache::default(),
             skills_manager,
             agent_control,
+            event_socket: None,
+            turn_activity: Arc::new(TurnActivity::new()),
         };
 
         let turn_context = Session::make_turn_context(
@@ -4285,7 +5850,7 @@ mod tests {
             tx_event,
             agent_status: agent_status_tx,
             state: Mutex::new(state),
-            features: config.features.clone(),
+            features: std::sync::RwLock::new(config.features.clone()),
             pending_mcp_server_refresh_config: Mutex::new(None),
             active_turn: Mutex::new(None),
             services,
@@ -4361,7 +5926,8 @@ mod tests {
             mcp_connection_manager: Arc::new(RwLock::new(McpConnectionManager::default())),
             mcp_startup_cancellation_token: Mutex::new(CancellationToken::new()),
             unified_exec_manager: UnifiedExecProcessManager::default(),
-            notifier: UserNotifier::new(None),
+            repl: ReplManager::default(),
+            notifier: UserNotifier::default(),
             rollout: Mutex::new(None),
             user_shell: Arc::new(default_user_shell()),
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
@@ -4370,8 +5936,17 @@ mod tests {
             otel_manager: otel_manager.clone(),
             models_manager: Arc::clone(&models_manager),
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            approval_memory: ApprovalMemory::new(config.codex_home.clone(), &config.cwd),
+            error_fingerprints: ErrorFingerprints::new(
+                config.codex_home.clone(),
+                !config.incognito,
+            ),
+            background_compaction: BackgroundCompactor::new(),
+            project_map: ProjectMapCache::default(),
             skills_manager,
             agent_control,
+            event_socket: None,
+            turn_activity: Arc::new(TurnActivity::new()),
         };
 
         let turn_context = Arc::new(Session::make_turn_context(
@@ -4390,7 +5965,7 @@ mod tests {
             tx_event,
             agent_status: agent_status_tx,
             state: Mutex::new(state),
-            features: config.features.clone(),
+            features: std::sync::RwLock::new(config.features.clone()),
             pending_mcp_server_refresh_config: Mutex::new(None),
             active_turn: Mutex::new(None),
             services,
@@ -4442,11 +6017,39 @@ mod tests {
         assert!(!new_token.is_cancelled());
     }
 
+    #[tokio::test]
+    async fn recorded_feature_overrides_are_restored() {
+        let (session, _turn_context) = make_session_and_context().await;
+        let overridden = |feature: &str, enabled: bool| {
+            RolloutItem::EventMsg(EventMsg::FeatureOverridden(
+                codex_protocol::protocol::FeatureOverriddenEvent {
+                    feature: feature.to_string(),
+                    enabled,
+                    changed_by: Some("tests".to_string()),
+                },
+            ))
+        };
+
+        session.restore_feature_overrides(&[
+            overridden("repl", true),
+            overridden("shell_snapshot", true),
+        ]);
+
+        assert!(session.enabled(Feature::Repl));
+        assert!(!session.enabled(Feature::ShellSnapshot));
+        assert_eq!(
+            session.override_feature(Feature::Repl, false),
+            Ok(()),
+            "runtime features can be switched back"
+        );
+        assert!(!session.enabled(Feature::Repl));
+    }
+
     #[tokio::test]
     async fn record_model_warning_appends_user_message() {
         let (mut session, turn_context) = make_session_and_context().await;
         let features = Features::with_defaults();
-        session.features = features;
+        session.features = std::sync::RwLock::new(features);
 
         session
             .record_model_warning("too many unified exec processes", &turn_context)
@@ -4573,7 +6176,7 @@ mod tests {
             text: "start review".to_string(),
             text_elements: Vec::new(),
         }];
-        sess.spawn_task(Arc::clone(&tc), input, ReviewTask::new())
+        sess.spawn_task(Arc::clone(&tc), input, ReviewTask::new(None))
             .await;
 
         sess.abort_all_tasks(TurnAbortReason::Interrupted).await;
@@ -4681,6 +6284,7 @@ mod tests {
                 Arc::clone(&turn_context),
                 tracker,
                 call,
+                CancellationToken::new(),
             )
             .await
             .expect_err("expected fatal error");
@@ -4704,7 +6308,11 @@ mod tests {
         for item in &initial_context {
             rollout_items.push(RolloutItem::ResponseItem(item.clone()));
         }
-        live_history.record_items(initial_context.iter(), turn_context.truncation_policy);
+        live_history.record_items(
+            initial_context.iter(),
+            turn_context.truncation_policy,
+            turn_context.tokenizer().as_ref(),
+        );
 
         let user1 = ResponseItem::Message {
             id: None,
@@ -4714,7 +6322,11 @@ mod tests {
             }],
             end_turn: None,
         };
-        live_history.record_items(std::iter::once(&user1), turn_context.truncation_policy);
+        live_history.record_items(
+            std::iter::once(&user1),
+            turn_context.truncation_policy,
+            turn_context.tokenizer().as_ref(),
+        );
         rollout_items.push(RolloutItem::ResponseItem(user1.clone()));
 
         let assistant1 = ResponseItem::Message {
@@ -4725,7 +6337,11 @@ mod tests {
             }],
             end_turn: None,
         };
-        live_history.record_items(std::iter::once(&assistant1), turn_context.truncation_policy);
+        live_history.record_items(
+            std::iter::once(&assistant1),
+            turn_context.truncation_policy,
+            turn_context.tokenizer().as_ref(),
+        );
         rollout_items.push(RolloutItem::ResponseItem(assistant1.clone()));
 
         let summary1 = "summary one";
@@ -4750,7 +6366,11 @@ mod tests {
             }],
             end_turn: None,
         };
-        live_history.record_items(std::iter::once(&user2), turn_context.truncation_policy);
+        live_history.record_items(
+            std::iter::once(&user2),
+            turn_context.truncation_policy,
+            turn_context.tokenizer().as_ref(),
+        );
         rollout_items.push(RolloutItem::ResponseItem(user2.clone()));
 
         let assistant2 = ResponseItem::Message {
@@ -4761,7 +6381,11 @@ mod tests {
             }],
             end_turn: None,
         };
-        live_history.record_items(std::iter::once(&assistant2), turn_context.truncation_policy);
+        live_history.record_items(
+            std::iter::once(&assistant2),
+            turn_context.truncation_policy,
+            turn_context.tokenizer().as_ref(),
+        );
         rollout_items.push(RolloutItem::ResponseItem(assistant2.clone()));
 
         let summary2 = "summary two";
@@ -4786,7 +6410,11 @@ mod tests {
             }],
             end_turn: None,
         };
-        live_history.record_items(std::iter::once(&user3), turn_context.truncation_policy);
+        live_history.record_items(
+            std::iter::once(&user3),
+            turn_context.truncation_policy,
+            turn_context.tokenizer().as_ref(),
+        );
         rollout_items.push(RolloutItem::ResponseItem(user3));
 
         let assistant3 = ResponseItem::Message {
@@ -4797,7 +6425,11 @@ mod tests {
             }],
             end_turn: None,
         };
-        live_history.record_items(std::iter::once(&assistant3), turn_context.truncation_policy);
+        live_history.record_items(
+            std::iter::once(&assistant3),
+            turn_context.truncation_policy,
+            turn_context.tokenizer().as_ref(),
+        );
         rollout_items.push(RolloutItem::ResponseItem(assistant3));
 
         (rollout_items, live_history.for_prompt())
@@ -4875,6 +6507,7 @@ mod tests {
                     })
                     .to_string(),
                 },
+                cancellation_token: CancellationToken::new(),
             })
             .await;
 
@@ -4912,6 +6545,7 @@ mod tests {
                     })
                     .to_string(),
                 },
+                cancellation_token: CancellationToken::new(),
             })
             .await;
 
@@ -4965,6 +6599,7 @@ mod tests {
                     })
                     .to_string(),
                 },
+                cancellation_token: CancellationToken::new(),
             })
             .await;
 
@@ -1,9 +1,19 @@
 use crate::agent::AgentStatus;
+use crate::approval_memory::ApprovalMemory;
 use crate::codex::Codex;
 use crate::error::Result as CodexResult;
+use crate::event_subscription::EventCategory;
+use crate::event_subscription::thread_subscription_stream;
+use crate::features::feature_for_key;
 use crate::protocol::Event;
 use crate::protocol::Op;
+use crate::protocol::SessionStats;
 use crate::protocol::Submission;
+use crate::protocol::TurnRequestSnapshot;
+use crate::rollout::SessionMeta;
+use crate::rollout::SessionMetadataUpdate;
+use futures::Stream;
+use futures::StreamExt;
 use std::path::PathBuf;
 use tokio::sync::watch;
 
@@ -35,6 +45,20 @@ impl CodexThread {
         self.codex.next_event().await
     }
 
+    /// Subscribe to this thread's events of category `C`, e.g.
+    /// `thread.subscribe::<ExecEvents>()`. Unlike [`Self::next_event`], any
+    /// number of subscribers may listen at once. The stream ends once the
+    /// thread has shut down, and is empty for threads not owned by a
+    /// [`crate::ThreadManager`].
+    pub fn subscribe<C: EventCategory>(&self) -> impl Stream<Item = C::Event> + Send + 'static {
+        let session = &self.codex.session;
+        let rx = session.services.agent_control.subscribe_events();
+        let thread_id = session.conversation_id;
+        futures::stream::iter(rx)
+            .flat_map(move |rx| thread_subscription_stream::<C>(rx, thread_id))
+            .map(|event| event.event)
+    }
+
     pub async fn agent_status(&self) -> AgentStatus {
         self.codex.agent_status().await
     }
@@ -46,4 +70,43 @@ impl CodexThread {
     pub fn rollout_path(&self) -> PathBuf {
         self.rollout_path.clone()
     }
+
+    /// Activity aggregates of this thread so far.
+    pub async fn session_stats(&self) -> SessionStats {
+        self.codex.session_stats().await
+    }
+
+    /// The "always allow" approvals this thread consults before prompting.
+    pub fn approval_memory(&self) -> &ApprovalMemory {
+        &self.codex.session.services.approval_memory
+    }
+
+    /// Checks whether `Op::SetFeature` would accept switching the
+    /// `[features]` key `feature` to `enabled` in this thread right now.
+    pub fn check_feature_change(&self, feature: &str, enabled: bool) -> Result<(), String> {
+        let feature =
+            feature_for_key(feature).ok_or_else(|| format!("Unknown feature `{feature}`."))?;
+        self.codex
+            .session
+            .features()
+            .check_runtime_change(feature, enabled)
+    }
+
+    /// Update the title and/or tags persisted in this thread's rollout.
+    pub async fn update_session_metadata(
+        &self,
+        update: SessionMetadataUpdate,
+    ) -> std::io::Result<SessionMeta> {
+        self.codex.update_session_metadata(update).await
+    }
+
+    /// What was sent to the model for the last request of `turn_id`, or of
+    /// the most recent turn when `turn_id` is `None`. Only the last few turns
+    /// of a loaded thread are retained.
+    pub async fn turn_request_snapshot(
+        &self,
+        turn_id: Option<&str>,
+    ) -> Option<TurnRequestSnapshot> {
+        self.codex.turn_request_snapshot(turn_id).await
+    }
 }
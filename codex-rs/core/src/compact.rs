@@ -1,3 +1,6 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::mem;
 use std::sync::Arc;
 
 use crate::ModelProviderInfo;
@@ -10,6 +13,8 @@ use crate::error::CodexErr;
 use crate::error::Result as CodexResult;
 use crate::features::Feature;
 use crate::protocol::CompactedItem;
+use crate::protocol::CompactionDroppedItems;
+use crate::protocol::CompactionStrategy;
 use crate::protocol::ContextCompactedEvent;
 use crate::protocol::EventMsg;
 use crate::protocol::TurnContextItem;
@@ -74,9 +79,12 @@ async fn run_compact_task_inner(
     let initial_input_for_turn: ResponseInputItem = ResponseInputItem::from(input);
 
     let mut history = sess.clone_history().await;
+    let items_before = history.raw_items().to_vec();
+    let tokens_before = history.estimate_token_count(turn_context.as_ref());
     history.record_items(
         &[initial_input_for_turn.into()],
         turn_context.truncation_policy,
+        turn_context.tokenizer().as_ref(),
     );
 
     let mut truncated_count = 0usize;
@@ -133,7 +141,7 @@ async fn run_compact_task_inner(
             Err(CodexErr::Interrupted) => {
                 return;
             }
-            Err(e @ CodexErr::ContextWindowExceeded) => {
+            Err(e @ (CodexErr::ContextWindowExceeded | CodexErr::ContextOverflow(_))) => {
                 if turn_input_len > 1 {
                     // Trim from the beginning to preserve cache (prefix-based) and keep recent messages intact.
                     error!(
@@ -184,8 +192,18 @@ async fn run_compact_task_inner(
         .cloned()
         .collect();
     new_history.extend(ghost_snapshots);
+    let event = describe_compaction(
+        CompactionStrategy::Summary,
+        &items_before,
+        &new_history,
+        tokens_before,
+    );
     sess.replace_history(new_history).await;
     sess.recompute_token_usage(&turn_context).await;
+    let tokens_after = sess
+        .clone_history()
+        .await
+        .estimate_token_count(turn_context.as_ref());
 
     let rollout_item = RolloutItem::Compacted(CompactedItem {
         message: summary_text.clone(),
@@ -193,7 +211,10 @@ async fn run_compact_task_inner(
     });
     sess.persist_rollout_items(&[rollout_item]).await;
 
-    let event = EventMsg::ContextCompacted(ContextCompactedEvent {});
+    let event = EventMsg::ContextCompacted(ContextCompactedEvent {
+        tokens_after,
+        ..event
+    });
     sess.send_event(&turn_context, event).await;
 
     let warning = EventMsg::Warning(WarningEvent {
@@ -202,6 +223,107 @@ async fn run_compact_task_inner(
     sess.send_event(&turn_context, warning).await;
 }
 
+/// Describes a compaction that replaced the `before` history with `after`.
+/// Items of `before` that `after` no longer contains count as dropped; the
+/// caller fills in `tokens_after` once the new history is installed.
+///
+/// Items are matched by kind and id, so each history is only walked once.
+/// Items without an id are matched by their serialized form instead.
+pub(crate) fn describe_compaction(
+    strategy: CompactionStrategy,
+    before: &[ResponseItem],
+    after: &[ResponseItem],
+    tokens_before: Option<i64>,
+) -> ContextCompactedEvent {
+    let mut kept_ids = HashSet::new();
+    let mut kept_anonymous: HashMap<String, usize> = HashMap::new();
+    for item in after {
+        match item_id(item) {
+            Some(id) => {
+                kept_ids.insert((mem::discriminant(item), id));
+            }
+            None => {
+                if let Ok(json) = serde_json::to_string(item) {
+                    *kept_anonymous.entry(json).or_default() += 1;
+                }
+            }
+        }
+    }
+
+    let mut items_dropped = CompactionDroppedItems::default();
+    let mut summarized_item_ids: Vec<String> = Vec::new();
+    for item in before {
+        let id = item_id(item);
+        let kept = match id {
+            Some(id) => kept_ids.contains(&(mem::discriminant(item), id)),
+            None => serde_json::to_string(item)
+                .ok()
+                .and_then(|json| kept_anonymous.get_mut(&json))
+                .is_some_and(|remaining| {
+                    let kept = *remaining > 0;
+                    *remaining = remaining.saturating_sub(1);
+                    kept
+                }),
+        };
+        if kept {
+            continue;
+        }
+        let counter = match item {
+            ResponseItem::Message { role, .. } => match role.as_str() {
+                "user" => &mut items_dropped.user_messages,
+                "assistant" => &mut items_dropped.assistant_messages,
+                _ => &mut items_dropped.other,
+            },
+            ResponseItem::Reasoning { .. } => &mut items_dropped.reasoning,
+            ResponseItem::LocalShellCall { .. }
+            | ResponseItem::FunctionCall { .. }
+            | ResponseItem::CustomToolCall { .. }
+            | ResponseItem::WebSearchCall { .. }
+            | ResponseItem::CodeInterpreterCall { .. }
+            | ResponseItem::FileSearchCall { .. } => &mut items_dropped.tool_calls,
+            ResponseItem::FunctionCallOutput { .. } | ResponseItem::CustomToolCallOutput { .. } => {
+                &mut items_dropped.tool_outputs
+            }
+            ResponseItem::GhostSnapshot { .. }
+            | ResponseItem::Compaction { .. }
+            | ResponseItem::Other => &mut items_dropped.other,
+        };
+        *counter += 1;
+        if let Some(id) = id
+            && !summarized_item_ids.iter().any(|existing| existing == id)
+        {
+            summarized_item_ids.push(id.to_string());
+        }
+    }
+    ContextCompactedEvent {
+        tokens_before,
+        tokens_after: None,
+        strategy: Some(strategy),
+        items_dropped,
+        summarized_item_ids,
+    }
+}
+
+/// The id a history item is reported and matched by, if it has one.
+fn item_id(item: &ResponseItem) -> Option<&str> {
+    let id = match item {
+        ResponseItem::Message { id, .. } => id.as_deref(),
+        ResponseItem::Reasoning { id, .. } => Some(id.as_str()),
+        ResponseItem::LocalShellCall { id, call_id, .. } => call_id.as_deref().or(id.as_deref()),
+        ResponseItem::FunctionCall { call_id, .. }
+        | ResponseItem::CustomToolCall { call_id, .. }
+        | ResponseItem::FunctionCallOutput { call_id, .. }
+        | ResponseItem::CustomToolCallOutput { call_id, .. } => Some(call_id.as_str()),
+        ResponseItem::WebSearchCall { id, .. }
+        | ResponseItem::CodeInterpreterCall { id, .. }
+        | ResponseItem::FileSearchCall { id, .. } => id.as_deref(),
+        ResponseItem::GhostSnapshot { .. }
+        | ResponseItem::Compaction { .. }
+        | ResponseItem::Other => None,
+    };
+    id.filter(|id| !id.is_empty())
+}
+
 pub fn content_items_to_text(content: &[ContentItem]) -> Option<String> {
     let mut pieces = Vec::new();
     for item in content {
@@ -369,6 +491,7 @@ mod tests {
 
     use super::*;
     use crate::session_prefix::TURN_ABORTED_OPEN_TAG;
+    use codex_protocol::models::FunctionCallOutputPayload;
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -565,4 +688,96 @@ mod tests {
             "expected compacted history to retain <turn_aborted> marker"
         );
     }
+
+    #[test]
+    fn describe_compaction_counts_dropped_items_by_category() {
+        let message = |role: &str, id: &str, text: &str| ResponseItem::Message {
+            id: Some(id.to_string()),
+            role: role.to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+            end_turn: None,
+        };
+        let kept = message("user", "msg-2", "keep me");
+        let before = vec![
+            message("user", "msg-1", "first"),
+            message("assistant", "msg-a", "reply"),
+            ResponseItem::FunctionCall {
+                id: None,
+                name: "shell".to_string(),
+                arguments: "{}".to_string(),
+                call_id: "call-1".to_string(),
+            },
+            ResponseItem::FunctionCallOutput {
+                call_id: "call-1".to_string(),
+                output: FunctionCallOutputPayload::default(),
+            },
+            kept.clone(),
+        ];
+        let after = vec![kept, message("user", "summary", "summary")];
+
+        let event = describe_compaction(CompactionStrategy::Summary, &before, &after, Some(500));
+
+        assert_eq!(
+            event,
+            ContextCompactedEvent {
+                tokens_before: Some(500),
+                tokens_after: None,
+                strategy: Some(CompactionStrategy::Summary),
+                items_dropped: CompactionDroppedItems {
+                    user_messages: 1,
+                    assistant_messages: 1,
+                    tool_calls: 1,
+                    tool_outputs: 1,
+                    ..CompactionDroppedItems::default()
+                },
+                summarized_item_ids: vec![
+                    "msg-1".to_string(),
+                    "msg-a".to_string(),
+                    "call-1".to_string(),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn describe_compaction_matches_items_by_kind_and_id() {
+        let message = |text: &str| ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+            end_turn: None,
+        };
+        let call = ResponseItem::FunctionCall {
+            id: None,
+            name: "shell".to_string(),
+            arguments: "{}".to_string(),
+            call_id: "call-1".to_string(),
+        };
+        let before = vec![
+            message("again"),
+            message("again"),
+            call.clone(),
+            ResponseItem::FunctionCallOutput {
+                call_id: "call-1".to_string(),
+                output: FunctionCallOutputPayload::default(),
+            },
+        ];
+        let after = vec![message("again"), call];
+
+        let event = describe_compaction(CompactionStrategy::Summary, &before, &after, None);
+
+        assert_eq!(
+            event.items_dropped,
+            CompactionDroppedItems {
+                user_messages: 1,
+                tool_outputs: 1,
+                ..CompactionDroppedItems::default()
+            }
+        );
+        assert_eq!(event.summarized_item_ids, vec!["call-1".to_string()]);
+    }
 }
@@ -3,8 +3,10 @@ use std::sync::Arc;
 use crate::Prompt;
 use crate::codex::Session;
 use crate::codex::TurnContext;
+use crate::compact::describe_compaction;
 use crate::error::Result as CodexResult;
 use crate::protocol::CompactedItem;
+use crate::protocol::CompactionStrategy;
 use crate::protocol::ContextCompactedEvent;
 use crate::protocol::EventMsg;
 use crate::protocol::RolloutItem;
@@ -41,6 +43,8 @@ async fn run_remote_compact_task_inner_impl(
     turn_context: &Arc<TurnContext>,
 ) -> CodexResult<()> {
     let history = sess.clone_history().await;
+    let items_before = history.raw_items().to_vec();
+    let tokens_before = history.estimate_token_count(turn_context.as_ref());
 
     // Required to keep `/undo` available after compaction
     let ghost_snapshots: Vec<ResponseItem> = history
@@ -67,8 +71,18 @@ async fn run_remote_compact_task_inner_impl(
     if !ghost_snapshots.is_empty() {
         new_history.extend(ghost_snapshots);
     }
+    let event = describe_compaction(
+        CompactionStrategy::Remote,
+        &items_before,
+        &new_history,
+        tokens_before,
+    );
     sess.replace_history(new_history.clone()).await;
     sess.recompute_token_usage(turn_context).await;
+    let tokens_after = sess
+        .clone_history()
+        .await
+        .estimate_token_count(turn_context.as_ref());
 
     let compacted_item = CompactedItem {
         message: String::new(),
@@ -77,7 +91,10 @@ async fn run_remote_compact_task_inner_impl(
     sess.persist_rollout_items(&[RolloutItem::Compacted(compacted_item)])
         .await;
 
-    let event = EventMsg::ContextCompacted(ContextCompactedEvent {});
+    let event = EventMsg::ContextCompacted(ContextCompactedEvent {
+        tokens_after,
+        ..event
+    });
     sess.send_event(turn_context, event).await;
 
     Ok(())
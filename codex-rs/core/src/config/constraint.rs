@@ -2,6 +2,7 @@ use std::fmt;
 use std::sync::Arc;
 
 use crate::config_loader::RequirementSource;
+use serde::Serialize;
 use thiserror::Error;
 
 #[derive(Debug, Error, PartialEq, Eq)]
@@ -26,6 +27,25 @@ impl ConstraintError {
             field_name: field_name.into(),
         }
     }
+
+    /// Describes a rejected value as a violation of `constraint`. Errors
+    /// that are not about a rejected value are returned unchanged.
+    pub fn into_violation(self, constraint: &str) -> Result<ConstraintViolation, Self> {
+        match self {
+            Self::InvalidValue {
+                field_name,
+                candidate,
+                requirement_source,
+                ..
+            } => Ok(ConstraintViolation::new(
+                constraint,
+                field_name,
+                candidate,
+                &requirement_source,
+            )),
+            err @ Self::EmptyField { .. } => Err(err),
+        }
+    }
 }
 
 pub type ConstraintResult<T> = Result<T, ConstraintError>;
@@ -143,6 +163,69 @@ impl<T: PartialEq> PartialEq for Constrained<T> {
     }
 }
 
+/// A config key rejected by a managed requirement.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConstraintViolation {
+    /// Requirement that blocked the key, e.g. `allowed_models`.
+    pub constraint: String,
+    /// Config key that was rejected, e.g. `model` or `profiles.fast.model`.
+    pub key: String,
+    /// Rejected value.
+    pub value: String,
+    /// Where the requirement was set.
+    pub source: String,
+}
+
+impl ConstraintViolation {
+    pub fn new(
+        constraint: impl Into<String>,
+        key: impl Into<String>,
+        value: impl Into<String>,
+        source: &RequirementSource,
+    ) -> Self {
+        Self {
+            constraint: constraint.into(),
+            key: key.into(),
+            value: value.into(),
+            source: source.to_string(),
+        }
+    }
+}
+
+/// Every violation found while loading a config. Returned from config loading
+/// wrapped in an [`std::io::Error`] of kind `InvalidInput`; use
+/// [`ConstraintReport::from_io_error`] to recover it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Error)]
+pub struct ConstraintReport {
+    pub violations: Vec<ConstraintViolation>,
+}
+
+impl ConstraintReport {
+    pub fn from_io_error(err: &std::io::Error) -> Option<&Self> {
+        err.get_ref()?.downcast_ref()
+    }
+}
+
+impl fmt::Display for ConstraintReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "config violates managed requirements:")?;
+        for violation in &self.violations {
+            write!(
+                f,
+                "\n  - `{}` = `{}` is blocked by `{}` (set by {})",
+                violation.key, violation.value, violation.constraint, violation.source
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl From<ConstraintReport> for std::io::Error {
+    fn from(report: ConstraintReport) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, report)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
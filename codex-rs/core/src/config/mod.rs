@@ -1,22 +1,44 @@
 use crate::auth::AuthCredentialsStoreMode;
 use crate::config::edit::ConfigEdit;
 use crate::config::edit::ConfigEditsBuilder;
+use crate::config::types::ApprovalRule;
+use crate::config::types::Artifacts;
+use crate::config::types::BackgroundCompaction;
 use crate::config::types::DEFAULT_OTEL_ENVIRONMENT;
+use crate::config::types::DelegateLocal;
+use crate::config::types::EventSocket;
+use crate::config::types::Explore;
+use crate::config::types::Heartbeat;
 use crate::config::types::History;
+use crate::config::types::Knowledge;
+use crate::config::types::LocaleToml;
 use crate::config::types::McpServerConfig;
 use crate::config::types::McpServerDisabledReason;
 use crate::config::types::McpServerTransportConfig;
+use crate::config::types::ModelFamily;
+use crate::config::types::ModelPricing;
 use crate::config::types::Notice;
+use crate::config::types::NotificationChannels;
 use crate::config::types::Notifications;
 use crate::config::types::OtelConfig;
 use crate::config::types::OtelConfigToml;
 use crate::config::types::OtelExporterKind;
+use crate::config::types::Provenance;
+use crate::config::types::ProviderTools;
+use crate::config::types::Repl;
+use crate::config::types::ResponseCache;
+use crate::config::types::Rollout;
 use crate::config::types::SandboxWorkspaceWrite;
+use crate::config::types::SecretMasking;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::config::types::ShellEnvironmentPolicyToml;
 use crate::config::types::SkillsConfig;
+use crate::config::types::ToolTimeout;
 use crate::config::types::Tui;
 use crate::config::types::UriBasedFileOpener;
+use crate::config::types::UsageLimitWait;
+use crate::config::types::WorkspaceWriteLimits;
+use crate::config::types::WriteQuota;
 use crate::config_loader::ConfigLayerStack;
 use crate::config_loader::ConfigRequirements;
 use crate::config_loader::LoaderOverrides;
@@ -29,6 +51,7 @@ use crate::features::FeatureOverrides;
 use crate::features::Features;
 use crate::features::FeaturesToml;
 use crate::git_info::resolve_root_git_project_for_trust;
+use crate::locale::LocaleContext;
 use crate::model_provider_info::LMSTUDIO_OSS_PROVIDER_ID;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::OLLAMA_CHAT_PROVIDER_ID;
@@ -45,6 +68,7 @@ use codex_protocol::config_types::ForcedLoginMethod;
 use codex_protocol::config_types::ModeKind;
 use codex_protocol::config_types::Personality;
 use codex_protocol::config_types::ReasoningSummary;
+use codex_protocol::config_types::ResumeContextOptions;
 use codex_protocol::config_types::SandboxMode;
 use codex_protocol::config_types::TrustLevel;
 use codex_protocol::config_types::Verbosity;
@@ -78,7 +102,9 @@ pub mod service;
 pub mod types;
 pub use constraint::Constrained;
 pub use constraint::ConstraintError;
+pub use constraint::ConstraintReport;
 pub use constraint::ConstraintResult;
+pub use constraint::ConstraintViolation;
 
 pub use service::ConfigService;
 pub use service::ConfigServiceError;
@@ -135,6 +161,9 @@ pub struct Config {
     /// Approval policy for executing commands.
     pub approval_policy: Constrained<AskForApproval>,
 
+    /// Command-scoped rules that refine `approval_policy`; the first match wins.
+    pub approval_rules: Vec<ApprovalRule>,
+
     pub sandbox_policy: Constrained<SandboxPolicy>,
 
     /// True if the user passed in an override or set a value in config.toml
@@ -190,6 +219,50 @@ pub struct Config {
     /// If unset the feature is disabled.
     pub notify: Option<Vec<String>>,
 
+    /// Built-in webhook and email channels for user notifications.
+    pub notification_channels: NotificationChannels,
+
+    /// Whether turns that hit the usage cap wait for the limit to reset.
+    pub usage_limit_wait: UsageLimitWait,
+
+    /// Whether identical model requests are answered from a local cache.
+    pub response_cache: ResponseCache,
+
+    /// When true, sessions write nothing to disk: no rollout, message history,
+    /// artifacts, cached responses or other session-scoped files.
+    pub incognito: bool,
+
+    /// Whether sessions tee their events to a local Unix domain socket.
+    pub event_socket: EventSocket,
+
+    /// Heartbeat events sent during long silent stretches of a turn.
+    pub heartbeat: Heartbeat,
+
+    /// Buffering and fsync policy of the session rollout writer.
+    pub rollout: Rollout,
+
+    /// Interpreter and limits of the `repl` tool.
+    pub repl: Repl,
+
+    /// Patterns and thresholds used to mask secrets in tool outputs.
+    pub secret_masking: SecretMasking,
+
+    /// When older turns are summarized while the session is idle.
+    pub background_compaction: BackgroundCompaction,
+
+    /// Local model the `delegate_local` tool hands subtasks to.
+    pub delegate_local: DelegateLocal,
+
+    /// Budget and tools of explore tasks.
+    pub explore: Explore,
+
+    /// Budget of the project knowledge snippets added to a turn.
+    pub knowledge: Knowledge,
+
+    /// Language, timezone and units of the user, shown to the model and used
+    /// to render timestamps.
+    pub locale: LocaleContext,
+
     /// TUI notifications preference. When set, the TUI will send OSC 9 notifications on approvals
     /// and turn completions when not focused.
     pub tui_notifications: Notifications,
@@ -211,6 +284,12 @@ pub struct Config {
     /// - `never`: Never use alternate screen (inline mode, preserves scrollback).
     pub tui_alternate_screen: AltScreenMode,
 
+    /// Show the session status in the terminal title.
+    pub tui_terminal_title: bool,
+
+    /// Show a terminal progress indicator while a turn is running.
+    pub tui_terminal_progress: bool,
+
     /// The directory that should be treated as the current working directory
     /// for the session. All relative paths inside the business-logic layer are
     /// resolved against this path.
@@ -225,6 +304,16 @@ pub struct Config {
     /// Definition for MCP servers that Codex can reach out to for tool calls.
     pub mcp_servers: Constrained<HashMap<String, McpServerConfig>>,
 
+    /// Tools that requirements forbid; they are never offered to the model.
+    pub forbidden_tools: Vec<String>,
+
+    /// Models that requirements allow; switching to any other model is
+    /// rejected for the whole session.
+    pub allowed_models: Option<Sourced<Vec<String>>>,
+
+    /// Resource limits added to every workspace-write sandbox policy.
+    pub workspace_write_limits: WorkspaceWriteLimits,
+
     /// Preferred store for MCP OAuth credentials.
     /// keyring: Use an OS-specific keyring service.
     ///          Credentials stored in the keyring will only be readable by Codex unless the user explicitly grants access via OS-level keyring access.
@@ -261,6 +350,25 @@ pub struct Config {
     /// Settings that govern if and what will be written to `~/.codex/history.jsonl`.
     pub history: History,
 
+    /// Size limits for files saved from tool calls.
+    pub artifacts: Artifacts,
+
+    /// Per-turn limits on bytes written by commands and patches.
+    pub write_quota: WriteQuota,
+
+    /// Signed provenance records for applied patches.
+    pub provenance: Provenance,
+
+    /// Default and maximum timeouts of shell tools, keyed by tool name.
+    pub tool_timeouts: HashMap<String, ToolTimeout>,
+
+    /// Whether a resumed session keeps its recorded model, environment and
+    /// sandbox settings or adopts the current ones.
+    pub resume_context: ResumeContextOptions,
+
+    /// Per-model token prices used to estimate the cost in session stats.
+    pub model_pricing: HashMap<String, ModelPricing>,
+
     /// Optional URI-based file opener. If set, citations to files in the model
     /// output will be hyperlinked using the specified URI scheme.
     pub file_opener: UriBasedFileOpener,
@@ -284,6 +392,13 @@ pub struct Config {
     /// Optional override to force-enable reasoning summaries for the configured model.
     pub model_supports_reasoning_summaries: Option<bool>,
 
+    /// Optional override of whether the configured model accepts image input.
+    pub model_supports_image_input: Option<bool>,
+
+    /// User-defined model families. The first one matching the model slug
+    /// overrides its built-in metadata.
+    pub model_families: Vec<ModelFamily>,
+
     /// Optional verbosity control for GPT-5 models (Responses API `text.verbosity`).
     pub model_verbosity: Option<Verbosity>,
 
@@ -304,6 +419,10 @@ pub struct Config {
     /// Explicit or feature-derived web search mode.
     pub web_search_mode: Option<WebSearchMode>,
 
+    /// Tools the model provider runs on its side, with the profile's
+    /// settings applied.
+    pub provider_tools: ProviderTools,
+
     /// If set to `true`, used only the experimental unified exec tool.
     pub use_experimental_unified_exec_tool: bool,
 
@@ -404,7 +523,7 @@ impl ConfigBuilder {
         let config_layer_stack =
             load_config_layers_state(&codex_home, Some(cwd), &cli_overrides, loader_overrides)
                 .await?;
-        let merged_toml = config_layer_stack.effective_config();
+        let merged_toml = config_layer_stack.resolved_effective_config()?;
 
         // Note that each layer in ConfigLayerStack should have resolved
         // relative paths to absolute paths based on the parent folder of the
@@ -472,6 +591,22 @@ impl Config {
             .build()
             .await
     }
+
+    /// Rejects switching to `model` when requirements restrict the allowed
+    /// models and it is not one of them.
+    pub fn ensure_model_allowed(&self, model: &str) -> ConstraintResult<()> {
+        match &self.allowed_models {
+            Some(allowed) if !allowed.iter().any(|name| name == model) => {
+                Err(ConstraintError::InvalidValue {
+                    field_name: "model",
+                    candidate: model.to_string(),
+                    allowed: format!("{:?}", allowed.value),
+                    requirement_source: allowed.source.clone(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
 /// DEPRECATED: Use [Config::load_with_cli_overrides()] instead because working
@@ -490,7 +625,7 @@ pub async fn load_config_as_toml_with_cli_overrides(
     )
     .await?;
 
-    let merged_toml = config_layer_stack.effective_config();
+    let merged_toml = config_layer_stack.resolved_effective_config()?;
     let cfg = deserialize_config_toml_with_base(merged_toml, codex_home).map_err(|e| {
         tracing::error!("Failed to deserialize overridden config: {e}");
         e
@@ -588,7 +723,7 @@ pub async fn load_global_mcp_servers(
     let config_layer_stack =
         load_config_layers_state(codex_home, cwd, &cli_overrides, LoaderOverrides::default())
             .await?;
-    let merged_toml = config_layer_stack.effective_config();
+    let merged_toml = config_layer_stack.resolved_effective_config()?;
     let Some(servers_value) = merged_toml.get("mcp_servers") else {
         return Ok(BTreeMap::new());
     };
@@ -755,6 +890,11 @@ pub struct ConfigToml {
     /// Default approval policy for executing commands.
     pub approval_policy: Option<AskForApproval>,
 
+    /// Rules that decide approval per command class, target path, or network
+    /// use. Evaluated in order; the first matching rule wins.
+    #[serde(default)]
+    pub approval_rules: Option<Vec<ApprovalRule>>,
+
     #[serde(default)]
     pub shell_environment_policy: ShellEnvironmentPolicyToml,
 
@@ -768,6 +908,52 @@ pub struct ConfigToml {
     #[serde(default)]
     pub notify: Option<Vec<String>>,
 
+    /// Built-in webhook and email channels for end-user notifications.
+    pub notification_channels: Option<NotificationChannels>,
+
+    /// Park turns that hit the usage cap until the limit resets.
+    pub usage_limit_wait: Option<UsageLimitWait>,
+
+    /// Answer identical model requests from a local cache (eval and batch runs).
+    pub response_cache: Option<ResponseCache>,
+
+    /// Keep sessions entirely in memory instead of writing them to disk.
+    pub incognito: Option<bool>,
+
+    /// Tee each session's live event stream to a local Unix domain socket.
+    pub event_socket: Option<EventSocket>,
+
+    /// Periodic heartbeat events while a turn is silent.
+    pub heartbeat: Option<Heartbeat>,
+
+    /// When the session rollout is fsynced.
+    pub rollout: Option<Rollout>,
+
+    /// Interpreter and limits of the `repl` tool.
+    pub repl: Option<Repl>,
+
+    /// Patterns and thresholds used to mask secrets in tool outputs when the
+    /// `secret_masking` feature is enabled.
+    pub secret_masking: Option<SecretMasking>,
+
+    /// Idle delay and thresholds of background compaction when the
+    /// `background_compaction` feature is enabled.
+    pub background_compaction: Option<BackgroundCompaction>,
+
+    /// Local model, token cap and timeout of the `delegate_local` tool when
+    /// the `delegate_local` feature is enabled.
+    pub delegate_local: Option<DelegateLocal>,
+
+    /// Budget and tools of explore tasks started with `/explore`.
+    pub explore: Option<Explore>,
+
+    /// Budget of the `.codex/knowledge` snippets added to a turn when the
+    /// `knowledge` feature is enabled.
+    pub knowledge: Option<Knowledge>,
+
+    /// Language, timezone and measurement units to assume for the user.
+    pub locale: Option<LocaleToml>,
+
     /// System instructions.
     pub instructions: Option<String>,
 
@@ -841,6 +1027,33 @@ pub struct ConfigToml {
     #[serde(default)]
     pub history: Option<History>,
 
+    /// Size limits for files saved from tool calls under `~/.codex/artifacts`.
+    #[serde(default)]
+    pub artifacts: Option<Artifacts>,
+
+    /// Per-turn limits on how much commands and patches write.
+    #[serde(default)]
+    pub write_quota: Option<WriteQuota>,
+
+    /// Signed provenance records for applied patches.
+    #[serde(default)]
+    pub provenance: Option<Provenance>,
+
+    /// Default and maximum timeouts of shell tools, keyed by tool name
+    /// (`shell`, `shell_command`, `local_shell`).
+    #[serde(default)]
+    pub tool_timeouts: Option<HashMap<String, ToolTimeout>>,
+
+    /// Per-dimension choice between the settings recorded in a rollout and the
+    /// current config when resuming a session. Defaults to `current` for all.
+    #[serde(default)]
+    pub resume_context: Option<ResumeContextOptions>,
+
+    /// USD prices per million tokens keyed by model slug. Session stats only
+    /// report a cost when every model a session used has a price here.
+    #[serde(default)]
+    pub model_pricing: Option<HashMap<String, ModelPricing>>,
+
     /// Optional URI-based file opener. If set, citations to files in the model
     /// output will be hyperlinked using the specified URI scheme.
     pub file_opener: Option<UriBasedFileOpener>,
@@ -864,6 +1077,15 @@ pub struct ConfigToml {
     /// Override to force-enable reasoning summaries for the configured model.
     pub model_supports_reasoning_summaries: Option<bool>,
 
+    /// Whether the configured model accepts image input. Set to `false` for
+    /// text-only models so image attachments fail with a clear error.
+    pub model_supports_image_input: Option<bool>,
+
+    /// Metadata for models Codex does not know, such as self-hosted
+    /// fine-tunes, selected by a regular expression over the model slug.
+    #[serde(default)]
+    pub model_families: Option<Vec<ModelFamily>>,
+
     /// EXPERIMENTAL
     /// Optionally specify a personality for the model
     pub model_personality: Option<Personality>,
@@ -876,6 +1098,10 @@ pub struct ConfigToml {
     /// Controls the web search tool mode: disabled, cached, or live.
     pub web_search: Option<WebSearchMode>,
 
+    /// Tools the model provider runs on its side (code interpreter, file
+    /// search).
+    pub provider_tools: Option<ProviderTools>,
+
     /// Nested tools section for feature toggles
     pub tools: Option<ToolsToml>,
 
@@ -1059,11 +1285,17 @@ impl ConfigToml {
                     network_access,
                     exclude_tmpdir_env_var,
                     exclude_slash_tmp,
+                    max_memory_bytes,
+                    cpu_rate_percent,
+                    max_processes,
                 }) => SandboxPolicy::WorkspaceWrite {
                     writable_roots: writable_roots.clone(),
                     network_access: *network_access,
                     exclude_tmpdir_env_var: *exclude_tmpdir_env_var,
                     exclude_slash_tmp: *exclude_slash_tmp,
+                    max_memory_bytes: *max_memory_bytes,
+                    cpu_rate_percent: *cpu_rate_percent,
+                    max_processes: *max_processes,
                 },
                 None => SandboxPolicy::new_workspace_write_policy(),
             },
@@ -1177,6 +1409,64 @@ pub fn resolve_oss_provider(
     }
 }
 
+/// Keys that explicitly turn on a tool that requirements forbid. Tools that
+/// are only enabled implicitly are dropped from the tool list instead.
+fn forbidden_tool_violations(
+    web_search: Option<WebSearchMode>,
+    tools: Option<&ToolsToml>,
+    mcp_servers: &HashMap<String, McpServerConfig>,
+    forbidden: &Sourced<Vec<String>>,
+) -> Vec<ConstraintViolation> {
+    let is_forbidden = |tool: &str| forbidden.iter().any(|name| name == tool);
+    let mut violations = Vec::new();
+
+    if is_forbidden("web_search") {
+        if let Some(mode) = web_search
+            && mode != WebSearchMode::Disabled
+        {
+            violations.push(ConstraintViolation::new(
+                "forbidden_tools",
+                "web_search",
+                format!("{mode:?}"),
+                &forbidden.source,
+            ));
+        }
+        if tools.and_then(|t| t.web_search) == Some(true) {
+            violations.push(ConstraintViolation::new(
+                "forbidden_tools",
+                "tools.web_search",
+                "true",
+                &forbidden.source,
+            ));
+        }
+    }
+    if is_forbidden("view_image") && tools.and_then(|t| t.view_image) == Some(true) {
+        violations.push(ConstraintViolation::new(
+            "forbidden_tools",
+            "tools.view_image",
+            "true",
+            &forbidden.source,
+        ));
+    }
+
+    let mut servers: Vec<_> = mcp_servers.iter().collect();
+    servers.sort_by(|a, b| a.0.cmp(b.0));
+    for (server, server_config) in servers {
+        for tool in server_config.enabled_tools.iter().flatten() {
+            if is_forbidden(&format!("mcp__{server}__{tool}")) {
+                violations.push(ConstraintViolation::new(
+                    "forbidden_tools",
+                    format!("mcp_servers.{server}.enabled_tools"),
+                    tool,
+                    &forbidden.source,
+                ));
+            }
+        }
+    }
+
+    violations
+}
+
 /// Resolve the web search mode from explicit config and feature flags.
 fn resolve_web_search_mode(
     config_toml: &ConfigToml,
@@ -1259,7 +1549,15 @@ impl Config {
         };
 
         let features = Features::from_config(&cfg, &config_profile, feature_overrides);
+        features
+            .check_requirements()
+            .map_err(|message| std::io::Error::new(std::io::ErrorKind::InvalidData, message))?;
         let web_search_mode = resolve_web_search_mode(&cfg, &config_profile, &features);
+        let provider_tools = config_profile
+            .provider_tools
+            .clone()
+            .unwrap_or_default()
+            .or(cfg.provider_tools.clone().unwrap_or_default());
         #[cfg(target_os = "windows")]
         {
             // Base flag controls sandbox on/off; elevated only applies when base is enabled.
@@ -1296,6 +1594,14 @@ impl Config {
             .get_active_project(&resolved_cwd)
             .unwrap_or(ProjectConfig { trust_level: None });
 
+        if let Some(sandbox_workspace_write) = &cfg.sandbox_workspace_write {
+            sandbox_workspace_write.validate_limits()?;
+        }
+        let workspace_write_limits = cfg
+            .sandbox_workspace_write
+            .as_ref()
+            .map(WorkspaceWriteLimits::from)
+            .unwrap_or_default();
         let SandboxPolicyResolution {
             policy: mut sandbox_policy,
             forced_auto_mode_downgraded_on_windows,
@@ -1355,7 +1661,13 @@ impl Config {
             })?
             .clone();
 
-        let shell_environment_policy = cfg.shell_environment_policy.into();
+        let shell_environment_policy = match config_profile.shell_environment_policy {
+            Some(profile_policy) => cfg
+                .shell_environment_policy
+                .with_profile_overrides(profile_policy),
+            None => cfg.shell_environment_policy,
+        }
+        .into();
 
         let history = cfg.history.unwrap_or_default();
 
@@ -1411,6 +1723,10 @@ impl Config {
 
         let forced_login_method = cfg.forced_login_method;
 
+        let model_key = match (&model, &active_profile_name, &config_profile.model) {
+            (None, Some(profile), Some(_)) => format!("profiles.{profile}.model"),
+            _ => "model".to_string(),
+        };
         let model = model.or(config_profile.model).or(cfg.model);
 
         let compact_prompt = compact_prompt.or(cfg.compact_prompt).and_then(|value| {
@@ -1454,14 +1770,112 @@ impl Config {
             approval_policy: mut constrained_approval_policy,
             sandbox_policy: mut constrained_sandbox_policy,
             mcp_servers,
+            allowed_models,
+            forbidden_tools,
+            otel_exporter: required_otel_exporter,
         } = requirements;
 
-        constrained_approval_policy
-            .set(approval_policy)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("{e}")))?;
-        constrained_sandbox_policy
-            .set(sandbox_policy)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("{e}")))?;
+        // Requirements cannot be overridden by user or project config: collect
+        // every key that conflicts with one and fail with the full report.
+        let mut violations = Vec::new();
+        if let Err(err) = constrained_approval_policy.set(approval_policy) {
+            violations.push(err.into_violation("allowed_approval_policies")?);
+        }
+        if let Err(err) = constrained_sandbox_policy.set(sandbox_policy) {
+            violations.push(err.into_violation("allowed_sandbox_modes")?);
+        }
+
+        let mut model = model;
+        if let Some(allowed) = allowed_models.as_ref() {
+            for (key, value) in [
+                (model_key.as_str(), &model),
+                ("review_model", &review_model),
+            ] {
+                if let Some(value) = value
+                    && !allowed.contains(value)
+                {
+                    violations.push(ConstraintViolation::new(
+                        "allowed_models",
+                        key,
+                        value,
+                        &allowed.source,
+                    ));
+                }
+            }
+            if model.is_none() {
+                model = allowed.first().cloned();
+            }
+        }
+
+        let forbidden_tools = match forbidden_tools {
+            Some(forbidden) => {
+                violations.extend(forbidden_tool_violations(
+                    config_profile.web_search.or(cfg.web_search),
+                    cfg.tools.as_ref(),
+                    &cfg.mcp_servers,
+                    &forbidden,
+                ));
+                forbidden.value
+            }
+            None => Vec::new(),
+        };
+
+        let otel_toml: OtelConfigToml = cfg.otel.unwrap_or_default();
+        if let Some(required) = required_otel_exporter.as_ref() {
+            for (key, exporter) in [
+                ("otel.exporter", &otel_toml.exporter),
+                ("otel.trace_exporter", &otel_toml.trace_exporter),
+            ] {
+                if let Some(exporter) = exporter
+                    && exporter != &required.value
+                {
+                    violations.push(ConstraintViolation::new(
+                        "otel_exporter",
+                        key,
+                        format!("{exporter:?}"),
+                        &required.source,
+                    ));
+                }
+            }
+        }
+
+        if !violations.is_empty() {
+            return Err(ConstraintReport { violations }.into());
+        }
+
+        let locale = LocaleContext::resolve(cfg.locale.clone().unwrap_or_default())?;
+
+        let model_families = cfg.model_families.clone().unwrap_or_default();
+        for (index, family) in model_families.iter().enumerate() {
+            if let Err(err) = regex_lite::Regex::new(&family.pattern) {
+                let name = family
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| format!("model_families[{index}]"));
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("invalid `match` pattern for model family {name}: {err}"),
+                ));
+            }
+        }
+
+        let secret_masking = cfg.secret_masking.clone().unwrap_or_default();
+        for pattern in &secret_masking.patterns {
+            if let Err(err) = regex_lite::Regex::new(pattern) {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("invalid pattern `{pattern}` in [secret_masking]: {err}"),
+                ));
+            }
+        }
+
+        let mut delegate_local = cfg.delegate_local.clone().unwrap_or_default();
+        if delegate_local.provider.is_none() {
+            delegate_local.provider = config_profile
+                .oss_provider
+                .clone()
+                .or_else(|| cfg.oss_provider.clone());
+        }
 
         let mcp_servers = constrain_mcp_servers(cfg.mcp_servers.clone(), mcp_servers.as_ref())
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("{e}")))?;
@@ -1475,11 +1889,26 @@ impl Config {
             model_provider,
             cwd: resolved_cwd,
             approval_policy: constrained_approval_policy,
+            approval_rules: cfg.approval_rules.unwrap_or_default(),
             sandbox_policy: constrained_sandbox_policy,
             did_user_set_custom_approval_policy_or_sandbox_mode,
             forced_auto_mode_downgraded_on_windows,
             shell_environment_policy,
             notify: cfg.notify,
+            notification_channels: cfg.notification_channels.unwrap_or_default(),
+            usage_limit_wait: cfg.usage_limit_wait.unwrap_or_default(),
+            response_cache: cfg.response_cache.unwrap_or_default(),
+            incognito: cfg.incognito.unwrap_or(false),
+            event_socket: cfg.event_socket.unwrap_or_default(),
+            heartbeat: cfg.heartbeat.unwrap_or_default(),
+            rollout: cfg.rollout.unwrap_or_default(),
+            repl: cfg.repl.unwrap_or_default(),
+            secret_masking,
+            background_compaction: cfg.background_compaction.unwrap_or_default(),
+            delegate_local,
+            explore: cfg.explore.unwrap_or_default(),
+            knowledge: cfg.knowledge.unwrap_or_default(),
+            locale,
             user_instructions,
             base_instructions,
             model_personality: config_profile.model_personality.or(cfg.model_personality),
@@ -1489,6 +1918,9 @@ impl Config {
             // is important in code to differentiate the mode from the store implementation.
             cli_auth_credentials_store_mode: cfg.cli_auth_credentials_store.unwrap_or_default(),
             mcp_servers,
+            forbidden_tools,
+            allowed_models,
+            workspace_write_limits,
             // The config.toml omits "_mode" because it's a config file. However, "_mode"
             // is important in code to differentiate the mode from the store implementation.
             mcp_oauth_credentials_store_mode: cfg.mcp_oauth_credentials_store.unwrap_or_default(),
@@ -1513,6 +1945,12 @@ impl Config {
             codex_home,
             config_layer_stack,
             history,
+            artifacts: cfg.artifacts.unwrap_or_default(),
+            write_quota: cfg.write_quota.unwrap_or_default(),
+            provenance: cfg.provenance.unwrap_or_default(),
+            tool_timeouts: cfg.tool_timeouts.unwrap_or_default(),
+            resume_context: cfg.resume_context.unwrap_or_default(),
+            model_pricing: cfg.model_pricing.unwrap_or_default(),
             file_opener: cfg.file_opener.unwrap_or(UriBasedFileOpener::VsCode),
             codex_linux_sandbox_exe,
 
@@ -1529,6 +1967,8 @@ impl Config {
                 .or(cfg.model_reasoning_summary)
                 .unwrap_or_default(),
             model_supports_reasoning_summaries: cfg.model_supports_reasoning_summaries,
+            model_supports_image_input: cfg.model_supports_image_input,
+            model_families,
             model_verbosity: config_profile.model_verbosity.or(cfg.model_verbosity),
             chatgpt_base_url: config_profile
                 .chatgpt_base_url
@@ -1538,6 +1978,7 @@ impl Config {
             forced_login_method,
             include_apply_patch_tool: include_apply_patch_tool_flag,
             web_search_mode,
+            provider_tools,
             use_experimental_unified_exec_tool,
             ghost_snapshot,
             features,
@@ -1570,14 +2011,22 @@ impl Config {
                 .as_ref()
                 .map(|t| t.alternate_screen)
                 .unwrap_or_default(),
+            tui_terminal_title: cfg.tui.as_ref().is_some_and(|t| t.terminal_title),
+            tui_terminal_progress: cfg.tui.as_ref().is_some_and(|t| t.terminal_progress),
             otel: {
-                let t: OtelConfigToml = cfg.otel.unwrap_or_default();
+                let t = otel_toml;
                 let log_user_prompt = t.log_user_prompt.unwrap_or(false);
                 let environment = t
                     .environment
                     .unwrap_or(DEFAULT_OTEL_ENVIRONMENT.to_string());
-                let exporter = t.exporter.unwrap_or(OtelExporterKind::None);
-                let trace_exporter = t.trace_exporter.unwrap_or_else(|| exporter.clone());
+                let (exporter, trace_exporter) = match required_otel_exporter {
+                    Some(required) => (required.value.clone(), required.value),
+                    None => {
+                        let exporter = t.exporter.unwrap_or(OtelExporterKind::None);
+                        let trace_exporter = t.trace_exporter.unwrap_or_else(|| exporter.clone());
+                        (exporter, trace_exporter)
+                    }
+                };
                 OtelConfig {
                     log_user_prompt,
                     environment,
@@ -1819,6 +2268,8 @@ persistence = "none"
                 show_tooltips: true,
                 experimental_mode: None,
                 alternate_screen: AltScreenMode::Auto,
+                terminal_title: false,
+                terminal_progress: false,
             }
         );
     }
@@ -1910,6 +2361,9 @@ exclude_slash_tmp = true
                         network_access: false,
                         exclude_tmpdir_env_var: true,
                         exclude_slash_tmp: true,
+                        max_memory_bytes: None,
+                        cpu_rate_percent: None,
+                        max_processes: None,
                     },
                     forced_auto_mode_downgraded_on_windows: false,
                 }
@@ -1958,6 +2412,9 @@ trust_level = "trusted"
                         network_access: false,
                         exclude_tmpdir_env_var: true,
                         exclude_slash_tmp: true,
+                        max_memory_bytes: None,
+                        cpu_rate_percent: None,
+                        max_processes: None,
                     },
                     forced_auto_mode_downgraded_on_windows: false,
                 }
@@ -1965,6 +2422,68 @@ trust_level = "trusted"
         }
     }
 
+    #[test]
+    fn sandbox_workspace_write_rejects_unusable_limits() -> std::io::Result<()> {
+        for (limits, message) in [
+            (
+                "cpu_rate_percent = 0",
+                "sandbox_workspace_write.cpu_rate_percent = 0 is invalid: expected 1 to 100",
+            ),
+            (
+                "cpu_rate_percent = 101",
+                "sandbox_workspace_write.cpu_rate_percent = 101 is invalid: expected 1 to 100",
+            ),
+            (
+                "max_processes = 0",
+                "sandbox_workspace_write.max_processes = 0 is invalid: expected at least 1",
+            ),
+        ] {
+            let codex_home = TempDir::new()?;
+            let cfg =
+                toml::from_str::<ConfigToml>(&format!("[sandbox_workspace_write]\n{limits}\n"))
+                    .expect("TOML deserialization should succeed");
+            let err = Config::load_from_base_config_with_overrides(
+                cfg,
+                ConfigOverrides::default(),
+                codex_home.path().to_path_buf(),
+            )
+            .expect_err("unusable limits should be rejected");
+            assert_eq!(
+                (err.kind(), err.to_string()),
+                (std::io::ErrorKind::InvalidInput, message.to_string())
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn sandbox_workspace_write_limits_are_kept_on_the_config() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[sandbox_workspace_write]
+max_memory_bytes = 1024
+cpu_rate_percent = 50
+max_processes = 8
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+        assert_eq!(
+            config.workspace_write_limits,
+            WorkspaceWriteLimits {
+                max_memory_bytes: Some(1024),
+                cpu_rate_percent: Some(50),
+                max_processes: Some(8),
+            }
+        );
+        Ok(())
+    }
+
     #[test]
     fn filter_mcp_servers_by_allowlist_enforces_identity_rules() {
         const MISMATCHED_COMMAND_SERVER: &str = "mismatched-command-should-disable";
@@ -2191,6 +2710,40 @@ trust_level = "trusted"
         Ok(())
     }
 
+    #[test]
+    fn config_rejects_invalid_model_family_pattern() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg: ConfigToml = toml::from_str(
+            r#"
+[[model_families]]
+name = "acme"
+match = "^acme-("
+context_window = 131072
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        assert_eq!(
+            cfg.model_families
+                .as_ref()
+                .map(|families| families[0].pattern.as_str()),
+            Some("^acme-(")
+        );
+
+        let err = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )
+        .expect_err("invalid pattern should be rejected");
+
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(
+            err.to_string()
+                .contains("invalid `match` pattern for model family acme")
+        );
+        Ok(())
+    }
+
     #[test]
     fn config_defaults_to_auto_oauth_store_mode() -> std::io::Result<()> {
         let codex_home = TempDir::new()?;
@@ -3584,6 +4137,7 @@ model_verbosity = "high"
         let openai_chat_completions_provider = ModelProviderInfo {
             name: "OpenAI using Chat Completions".to_string(),
             base_url: Some("https://api.openai.com/v1".to_string()),
+            fallback_base_urls: None,
             env_key: Some("OPENAI_API_KEY".to_string()),
             wire_api: crate::WireApi::Chat,
             env_key_instructions: None,
@@ -3655,15 +4209,33 @@ model_verbosity = "high"
                 model_provider_id: "openai".to_string(),
                 model_provider: fixture.openai_provider.clone(),
                 approval_policy: Constrained::allow_any(AskForApproval::Never),
+                approval_rules: Vec::new(),
                 sandbox_policy: Constrained::allow_any(SandboxPolicy::new_read_only_policy()),
                 did_user_set_custom_approval_policy_or_sandbox_mode: true,
                 forced_auto_mode_downgraded_on_windows: false,
                 shell_environment_policy: ShellEnvironmentPolicy::default(),
                 user_instructions: None,
                 notify: None,
+                notification_channels: NotificationChannels::default(),
+                usage_limit_wait: UsageLimitWait::default(),
+                response_cache: ResponseCache::default(),
+                incognito: false,
+                event_socket: EventSocket::default(),
+                heartbeat: Heartbeat::default(),
+                rollout: Rollout::default(),
+                repl: Repl::default(),
+                secret_masking: SecretMasking::default(),
+                background_compaction: BackgroundCompaction::default(),
+                delegate_local: DelegateLocal::default(),
+                explore: Explore::default(),
+                knowledge: Knowledge::default(),
+                locale: LocaleContext::default(),
                 cwd: fixture.cwd(),
                 cli_auth_credentials_store_mode: Default::default(),
                 mcp_servers: Constrained::allow_any(HashMap::new()),
+                forbidden_tools: Vec::new(),
+                allowed_models: None,
+                workspace_write_limits: WorkspaceWriteLimits::default(),
                 mcp_oauth_credentials_store_mode: Default::default(),
                 mcp_oauth_callback_port: None,
                 model_providers: fixture.model_provider_map.clone(),
@@ -3674,6 +4246,12 @@ model_verbosity = "high"
                 codex_home: fixture.codex_home(),
                 config_layer_stack: Default::default(),
                 history: History::default(),
+                artifacts: Artifacts::default(),
+                write_quota: WriteQuota::default(),
+                provenance: Provenance::default(),
+                tool_timeouts: HashMap::new(),
+                resume_context: ResumeContextOptions::default(),
+                model_pricing: HashMap::new(),
                 file_opener: UriBasedFileOpener::VsCode,
                 codex_linux_sandbox_exe: None,
                 hide_agent_reasoning: false,
@@ -3681,6 +4259,8 @@ model_verbosity = "high"
                 model_reasoning_effort: Some(ReasoningEffort::High),
                 model_reasoning_summary: ReasoningSummary::Detailed,
                 model_supports_reasoning_summaries: None,
+                model_supports_image_input: None,
+                model_families: Vec::new(),
                 model_verbosity: None,
                 model_personality: None,
                 chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
@@ -3691,6 +4271,7 @@ model_verbosity = "high"
                 forced_login_method: None,
                 include_apply_patch_tool: false,
                 web_search_mode: None,
+                provider_tools: ProviderTools::default(),
                 use_experimental_unified_exec_tool: false,
                 ghost_snapshot: GhostSnapshotConfig::default(),
                 features: Features::with_defaults(),
@@ -3707,6 +4288,8 @@ model_verbosity = "high"
                 analytics_enabled: Some(true),
                 feedback_enabled: true,
                 tui_alternate_screen: AltScreenMode::Auto,
+                tui_terminal_title: false,
+                tui_terminal_progress: false,
                 otel: OtelConfig::default(),
             },
             o3_profile_config
@@ -3736,15 +4319,33 @@ model_verbosity = "high"
             model_provider_id: "openai-chat-completions".to_string(),
             model_provider: fixture.openai_chat_completions_provider.clone(),
             approval_policy: Constrained::allow_any(AskForApproval::UnlessTrusted),
+            approval_rules: Vec::new(),
             sandbox_policy: Constrained::allow_any(SandboxPolicy::new_read_only_policy()),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             forced_auto_mode_downgraded_on_windows: false,
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            notification_channels: NotificationChannels::default(),
+            usage_limit_wait: UsageLimitWait::default(),
+            response_cache: ResponseCache::default(),
+            incognito: false,
+            event_socket: EventSocket::default(),
+            heartbeat: Heartbeat::default(),
+            rollout: Rollout::default(),
+            repl: Repl::default(),
+            secret_masking: SecretMasking::default(),
+            background_compaction: BackgroundCompaction::default(),
+            delegate_local: DelegateLocal::default(),
+            explore: Explore::default(),
+            knowledge: Knowledge::default(),
+            locale: LocaleContext::default(),
             cwd: fixture.cwd(),
             cli_auth_credentials_store_mode: Default::default(),
             mcp_servers: Constrained::allow_any(HashMap::new()),
+            forbidden_tools: Vec::new(),
+            allowed_models: None,
+            workspace_write_limits: WorkspaceWriteLimits::default(),
             mcp_oauth_credentials_store_mode: Default::default(),
             mcp_oauth_callback_port: None,
             model_providers: fixture.model_provider_map.clone(),
@@ -3755,6 +4356,12 @@ model_verbosity = "high"
             codex_home: fixture.codex_home(),
             config_layer_stack: Default::default(),
             history: History::default(),
+            artifacts: Artifacts::default(),
+            write_quota: WriteQuota::default(),
+            provenance: Provenance::default(),
+            tool_timeouts: HashMap::new(),
+            resume_context: ResumeContextOptions::default(),
+            model_pricing: HashMap::new(),
             file_opener: UriBasedFileOpener::VsCode,
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
@@ -3762,6 +4369,8 @@ model_verbosity = "high"
             model_reasoning_effort: None,
             model_reasoning_summary: ReasoningSummary::default(),
             model_supports_reasoning_summaries: None,
+            model_supports_image_input: None,
+            model_families: Vec::new(),
             model_verbosity: None,
             model_personality: None,
             chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
@@ -3772,6 +4381,7 @@ model_verbosity = "high"
             forced_login_method: None,
             include_apply_patch_tool: false,
             web_search_mode: None,
+            provider_tools: ProviderTools::default(),
             use_experimental_unified_exec_tool: false,
             ghost_snapshot: GhostSnapshotConfig::default(),
             features: Features::with_defaults(),
@@ -3788,6 +4398,8 @@ model_verbosity = "high"
             analytics_enabled: Some(true),
             feedback_enabled: true,
             tui_alternate_screen: AltScreenMode::Auto,
+            tui_terminal_title: false,
+            tui_terminal_progress: false,
             otel: OtelConfig::default(),
         };
 
@@ -3832,15 +4444,33 @@ model_verbosity = "high"
             model_provider_id: "openai".to_string(),
             model_provider: fixture.openai_provider.clone(),
             approval_policy: Constrained::allow_any(AskForApproval::OnFailure),
+            approval_rules: Vec::new(),
             sandbox_policy: Constrained::allow_any(SandboxPolicy::new_read_only_policy()),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             forced_auto_mode_downgraded_on_windows: false,
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            notification_channels: NotificationChannels::default(),
+            usage_limit_wait: UsageLimitWait::default(),
+            response_cache: ResponseCache::default(),
+            incognito: false,
+            event_socket: EventSocket::default(),
+            heartbeat: Heartbeat::default(),
+            rollout: Rollout::default(),
+            repl: Repl::default(),
+            secret_masking: SecretMasking::default(),
+            background_compaction: BackgroundCompaction::default(),
+            delegate_local: DelegateLocal::default(),
+            explore: Explore::default(),
+            knowledge: Knowledge::default(),
+            locale: LocaleContext::default(),
             cwd: fixture.cwd(),
             cli_auth_credentials_store_mode: Default::default(),
             mcp_servers: Constrained::allow_any(HashMap::new()),
+            forbidden_tools: Vec::new(),
+            allowed_models: None,
+            workspace_write_limits: WorkspaceWriteLimits::default(),
             mcp_oauth_credentials_store_mode: Default::default(),
             mcp_oauth_callback_port: None,
             model_providers: fixture.model_provider_map.clone(),
@@ -3851,6 +4481,12 @@ model_verbosity = "high"
             codex_home: fixture.codex_home(),
             config_layer_stack: Default::default(),
             history: History::default(),
+            artifacts: Artifacts::default(),
+            write_quota: WriteQuota::default(),
+            provenance: Provenance::default(),
+            tool_timeouts: HashMap::new(),
+            resume_context: ResumeContextOptions::default(),
+            model_pricing: HashMap::new(),
             file_opener: UriBasedFileOpener::VsCode,
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
@@ -3858,6 +4494,8 @@ model_verbosity = "high"
             model_reasoning_effort: None,
             model_reasoning_summary: ReasoningSummary::default(),
             model_supports_reasoning_summaries: None,
+            model_supports_image_input: None,
+            model_families: Vec::new(),
             model_verbosity: None,
             model_personality: None,
             chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
@@ -3868,6 +4506,7 @@ model_verbosity = "high"
             forced_login_method: None,
             include_apply_patch_tool: false,
             web_search_mode: None,
+            provider_tools: ProviderTools::default(),
             use_experimental_unified_exec_tool: false,
             ghost_snapshot: GhostSnapshotConfig::default(),
             features: Features::with_defaults(),
@@ -3884,6 +4523,8 @@ model_verbosity = "high"
             analytics_enabled: Some(false),
             feedback_enabled: true,
             tui_alternate_screen: AltScreenMode::Auto,
+            tui_terminal_title: false,
+            tui_terminal_progress: false,
             otel: OtelConfig::default(),
         };
 
@@ -3914,15 +4555,33 @@ model_verbosity = "high"
             model_provider_id: "openai".to_string(),
             model_provider: fixture.openai_provider.clone(),
             approval_policy: Constrained::allow_any(AskForApproval::OnFailure),
+            approval_rules: Vec::new(),
             sandbox_policy: Constrained::allow_any(SandboxPolicy::new_read_only_policy()),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             forced_auto_mode_downgraded_on_windows: false,
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            notification_channels: NotificationChannels::default(),
+            usage_limit_wait: UsageLimitWait::default(),
+            response_cache: ResponseCache::default(),
+            incognito: false,
+            event_socket: EventSocket::default(),
+            heartbeat: Heartbeat::default(),
+            rollout: Rollout::default(),
+            repl: Repl::default(),
+            secret_masking: SecretMasking::default(),
+            background_compaction: BackgroundCompaction::default(),
+            delegate_local: DelegateLocal::default(),
+            explore: Explore::default(),
+            knowledge: Knowledge::default(),
+            locale: LocaleContext::default(),
             cwd: fixture.cwd(),
             cli_auth_credentials_store_mode: Default::default(),
             mcp_servers: Constrained::allow_any(HashMap::new()),
+            forbidden_tools: Vec::new(),
+            allowed_models: None,
+            workspace_write_limits: WorkspaceWriteLimits::default(),
             mcp_oauth_credentials_store_mode: Default::default(),
             mcp_oauth_callback_port: None,
             model_providers: fixture.model_provider_map.clone(),
@@ -3933,6 +4592,12 @@ model_verbosity = "high"
             codex_home: fixture.codex_home(),
             config_layer_stack: Default::default(),
             history: History::default(),
+            artifacts: Artifacts::default(),
+            write_quota: WriteQuota::default(),
+            provenance: Provenance::default(),
+            tool_timeouts: HashMap::new(),
+            resume_context: ResumeContextOptions::default(),
+            model_pricing: HashMap::new(),
             file_opener: UriBasedFileOpener::VsCode,
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
@@ -3940,6 +4605,8 @@ model_verbosity = "high"
             model_reasoning_effort: Some(ReasoningEffort::High),
             model_reasoning_summary: ReasoningSummary::Detailed,
             model_supports_reasoning_summaries: None,
+            model_supports_image_input: None,
+            model_families: Vec::new(),
             model_verbosity: Some(Verbosity::High),
             model_personality: None,
             chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
@@ -3950,6 +4617,7 @@ model_verbosity = "high"
             forced_login_method: None,
             include_apply_patch_tool: false,
             web_search_mode: None,
+            provider_tools: ProviderTools::default(),
             use_experimental_unified_exec_tool: false,
             ghost_snapshot: GhostSnapshotConfig::default(),
             features: Features::with_defaults(),
@@ -3966,6 +4634,8 @@ model_verbosity = "high"
             analytics_enabled: Some(true),
             feedback_enabled: true,
             tui_alternate_screen: AltScreenMode::Auto,
+            tui_terminal_title: false,
+            tui_terminal_progress: false,
             otel: OtelConfig::default(),
         };
 
@@ -4308,6 +4978,78 @@ mcp_oauth_callback_port = 5678
 
         Ok(())
     }
+
+    fn load_with_allowed_models(
+        cfg: ConfigToml,
+        overrides: ConfigOverrides,
+        codex_home: &TempDir,
+    ) -> std::io::Result<Config> {
+        let requirements = ConfigRequirements {
+            allowed_models: Some(Sourced::new(
+                vec!["gpt-5.1-codex".to_string(), "gpt-5.1".to_string()],
+                RequirementSource::Unknown,
+            )),
+            ..Default::default()
+        };
+        let stack = ConfigLayerStack::new(
+            Vec::new(),
+            requirements,
+            crate::config_loader::ConfigRequirementsToml::default(),
+        )?;
+        Config::load_config_with_layer_stack(cfg, overrides, codex_home.path().to_path_buf(), stack)
+    }
+
+    #[test]
+    fn allowed_models_reject_cli_model_override() -> anyhow::Result<()> {
+        let codex_home = TempDir::new()?;
+
+        let err = load_with_allowed_models(
+            ConfigToml::default(),
+            ConfigOverrides {
+                model: Some("gpt-4o".to_string()),
+                ..Default::default()
+            },
+            &codex_home,
+        )
+        .expect_err("model outside allowed_models");
+
+        assert_eq!(
+            ConstraintReport::from_io_error(&err),
+            Some(&ConstraintReport {
+                violations: vec![ConstraintViolation::new(
+                    "allowed_models",
+                    "model",
+                    "gpt-4o",
+                    &RequirementSource::Unknown,
+                )],
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn allowed_models_default_the_model_and_guard_later_switches() -> anyhow::Result<()> {
+        let codex_home = TempDir::new()?;
+
+        let config = load_with_allowed_models(
+            ConfigToml::default(),
+            ConfigOverrides::default(),
+            &codex_home,
+        )?;
+
+        assert_eq!(config.model, Some("gpt-5.1-codex".to_string()));
+        assert_eq!(config.ensure_model_allowed("gpt-5.1"), Ok(()));
+        assert_eq!(
+            config.ensure_model_allowed("o3"),
+            Err(ConstraintError::InvalidValue {
+                field_name: "model",
+                candidate: "o3".to_string(),
+                allowed: "[\"gpt-5.1-codex\", \"gpt-5.1\"]".to_string(),
+                requirement_source: RequirementSource::Unknown,
+            })
+        );
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -39,7 +39,13 @@ pub struct ConfigProfile {
     pub tools_web_search: Option<bool>,
     pub tools_view_image: Option<bool>,
     pub web_search: Option<WebSearchMode>,
+    /// Tools the model provider runs on its side; settings left unset fall
+    /// back to the top-level `[provider_tools]`.
+    pub provider_tools: Option<crate::config::types::ProviderTools>,
     pub analytics: Option<crate::config::types::AnalyticsConfigToml>,
+    /// Applied on top of the top-level `shell_environment_policy`; `set`
+    /// entries are merged with the top-level ones.
+    pub shell_environment_policy: Option<crate::config::types::ShellEnvironmentPolicyToml>,
     /// Optional feature toggles scoped to this profile.
     #[serde(default)]
     // Injects known feature keys into the schema and forbids unknown keys.
@@ -4,10 +4,14 @@
 // definitions that do not contain business logic.
 
 use crate::config_loader::RequirementSource;
+use crate::model_provider_info::OLLAMA_OSS_PROVIDER_ID;
+pub use codex_protocol::approvals::ApprovalRuleDecision;
 pub use codex_protocol::config_types::AltScreenMode;
 pub use codex_protocol::config_types::ModeKind;
 pub use codex_protocol::config_types::Personality;
 pub use codex_protocol::config_types::WebSearchMode;
+pub use codex_protocol::openai_models::ConfigShellToolType;
+use codex_protocol::protocol::SandboxPolicy;
 use codex_utils_absolute_path::AbsolutePathBuf;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
@@ -307,6 +311,93 @@ pub enum HistoryPersistence {
     None,
 }
 
+/// Limits for files saved from tool calls under `~/.codex/artifacts`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct Artifacts {
+    /// Maximum total size in bytes of the artifacts kept for one thread.
+    /// Files that would exceed it are not saved. Defaults to 100 MiB.
+    pub max_bytes_per_thread: Option<u64>,
+
+    /// Maximum total size in bytes of all artifacts. Once exceeded, artifacts
+    /// of the least recently active threads are deleted. Defaults to 1 GiB.
+    pub max_total_bytes: Option<u64>,
+}
+
+/// Per-turn limits on how much commands and patches write.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct WriteQuota {
+    /// Bytes written in one turn after which the user is warned. `0`
+    /// disables the warning. Defaults to 256 MiB.
+    pub warn_bytes: Option<u64>,
+
+    /// Bytes written in one turn after which every further command or patch
+    /// of the turn needs approval. `0` disables the cap. Defaults to 1 GiB.
+    pub max_bytes: Option<u64>,
+}
+
+/// Tools the model provider runs on its side. Only providers using the
+/// Responses API offer them.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ProviderTools {
+    /// Let the model run Python in a container hosted by the provider.
+    pub code_interpreter: Option<bool>,
+
+    /// Let the model search vector stores hosted by the provider.
+    pub file_search: Option<FileSearchTool>,
+}
+
+impl ProviderTools {
+    /// `self`, with every setting left unset taken from `base`.
+    pub fn or(self, base: ProviderTools) -> ProviderTools {
+        ProviderTools {
+            code_interpreter: self.code_interpreter.or(base.code_interpreter),
+            file_search: self.file_search.or(base.file_search),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct FileSearchTool {
+    /// IDs of the vector stores to search. File search is off when empty.
+    #[serde(default)]
+    pub vector_store_ids: Vec<String>,
+
+    /// Most results one search returns, between 1 and 50.
+    pub max_num_results: Option<u32>,
+}
+
+/// Signed provenance records for the patches Codex applies.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct Provenance {
+    /// Record a signed provenance entry next to the rollout for every patch
+    /// applied. Defaults to false.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// File holding the secret key records are signed with. Defaults to
+    /// `provenance.key` under `CODEX_HOME`, created on first use.
+    pub signing_key_file: Option<AbsolutePathBuf>,
+}
+
+/// Timeout settings for one shell tool (`shell`, `shell_command` or
+/// `local_shell`).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ToolTimeout {
+    /// Timeout in milliseconds for calls that do not set `timeout_ms`.
+    /// Defaults to 10 seconds.
+    pub default_ms: Option<u64>,
+
+    /// Longest timeout in milliseconds a call may request; longer requests
+    /// are clamped. Defaults to 1 hour.
+    pub max_ms: Option<u64>,
+}
+
 // ===== Analytics configuration =====
 
 /// Analytics settings loaded from config.toml. Fields are optional so we can apply defaults.
@@ -420,6 +511,659 @@ impl Default for Notifications {
     }
 }
 
+/// Kinds of events that can be delivered through [`NotificationChannels`].
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum NotificationEventKind {
+    /// The agent finished processing a user submission.
+    TurnComplete,
+    /// The agent is waiting for the user to approve a command or patch.
+    ApprovalRequested,
+    /// A turn ended with an error.
+    Error,
+    /// A turn has been running for another `milestone_interval_minutes`.
+    LongRunMilestone,
+    /// A turn hit the usage cap and is parked until the limit resets.
+    UsageLimitWait,
+}
+
+/// Built-in notification channels that are used in addition to the `notify`
+/// program.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct NotificationChannels {
+    /// POST each notification as JSON to an HTTP endpoint.
+    pub webhook: Option<WebhookNotificationChannel>,
+
+    /// Send each notification as a plain-text email through an SMTP relay.
+    pub email: Option<EmailNotificationChannel>,
+
+    /// How often, in minutes, a long-running turn emits a `long-run-milestone`
+    /// notification. Defaults to 10.
+    pub milestone_interval_minutes: Option<u64>,
+}
+
+/// Webhook notification channel settings.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct WebhookNotificationChannel {
+    /// URL that receives the JSON notification payload.
+    pub url: String,
+
+    /// Extra HTTP headers to send with each request (e.g. `Authorization`).
+    pub headers: Option<HashMap<String, String>>,
+
+    /// Events delivered to this channel. Defaults to all events.
+    pub events: Option<Vec<NotificationEventKind>>,
+
+    /// Number of times a failed delivery is retried. Defaults to 3.
+    pub max_retries: Option<u32>,
+}
+
+/// Email notification channel settings.
+///
+/// Messages include prompts and assistant replies, so they are sent over TLS
+/// unless `smtp_tls = "none"` explicitly opts out (e.g. for a local MTA).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct EmailNotificationChannel {
+    /// Hostname of the SMTP server.
+    pub smtp_host: String,
+
+    /// Port of the SMTP server. Defaults to 587 for `starttls`, 465 for `tls`
+    /// and 25 for `none`.
+    pub smtp_port: Option<u16>,
+
+    /// How the connection is secured. Defaults to `starttls`.
+    #[serde(default)]
+    pub smtp_tls: SmtpTls,
+
+    /// User name to authenticate as. Requires `smtp_password_env` and TLS.
+    pub smtp_username: Option<String>,
+
+    /// Environment variable that holds the SMTP password.
+    pub smtp_password_env: Option<String>,
+
+    /// Envelope and header sender address.
+    pub from: String,
+
+    /// Recipient addresses.
+    pub to: Vec<String>,
+
+    /// Events delivered to this channel. Defaults to all events.
+    pub events: Option<Vec<NotificationEventKind>>,
+}
+
+/// Transport security of the SMTP connection.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpTls {
+    /// Connect in plain text and upgrade with `STARTTLS`; fail if the server
+    /// does not support it.
+    #[default]
+    StartTls,
+    /// Speak TLS from the start (SMTPS).
+    Tls,
+    /// No encryption. Only for trusted relays such as a local MTA.
+    None,
+}
+
+pub const DEFAULT_USAGE_LIMIT_MAX_WAIT_MINUTES: u64 = 24 * 60;
+
+/// Park turns that hit the provider's usage cap until the limit resets
+/// instead of failing them. Useful for long unattended runs.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct UsageLimitWait {
+    /// Wait for the usage limit to reset and then resume the turn.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Longest reset time, in minutes, that a turn will wait for. Limits that
+    /// reset later still fail the turn. Defaults to 1440 (24 hours).
+    pub max_wait_minutes: Option<u64>,
+}
+
+impl UsageLimitWait {
+    pub fn max_wait(&self) -> Duration {
+        let minutes = self
+            .max_wait_minutes
+            .unwrap_or(DEFAULT_USAGE_LIMIT_MAX_WAIT_MINUTES);
+        Duration::from_secs(minutes.saturating_mul(60))
+    }
+}
+
+pub const DEFAULT_RESPONSE_CACHE_TTL_MINUTES: u64 = 24 * 60;
+pub const DEFAULT_RESPONSE_CACHE_MAX_ENTRIES: usize = 1000;
+
+/// Exact-match cache of model responses for eval and batch runs that repeat
+/// the same prompts.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ResponseCache {
+    /// Serve repeated identical requests from `$CODEX_HOME/response-cache`.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// How long, in minutes, a cached response stays valid. Defaults to 1440
+    /// (24 hours).
+    pub ttl_minutes: Option<u64>,
+
+    /// Most responses kept; the oldest are evicted first. Defaults to 1000.
+    pub max_entries: Option<usize>,
+
+    /// Also use the cache in interactive sessions (anything but `codex exec`
+    /// and `codex mcp-server`). Defaults to false, so only those two use it.
+    #[serde(default)]
+    pub interactive: bool,
+}
+
+impl ResponseCache {
+    pub fn ttl(&self) -> Duration {
+        let minutes = self
+            .ttl_minutes
+            .unwrap_or(DEFAULT_RESPONSE_CACHE_TTL_MINUTES);
+        Duration::from_secs(minutes.saturating_mul(60))
+    }
+
+    pub fn max_entries(&self) -> usize {
+        self.max_entries
+            .unwrap_or(DEFAULT_RESPONSE_CACHE_MAX_ENTRIES)
+    }
+}
+
+/// Tee each session's live event stream to a local Unix domain socket so
+/// companion tools can follow along without the app-server.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct EventSocket {
+    /// Open `$CODEX_HOME/event-sockets/<thread id>.sock` for every session.
+    /// Clients authenticate with the token in the matching `.token` file.
+    /// Ignored on platforms without Unix domain sockets.
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+pub const DEFAULT_HEARTBEAT_INTERVAL_SECONDS: u64 = 15;
+
+/// Heartbeat events sent while a turn produces no other events, so clients
+/// can tell a long silent operation from a hung session.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct Heartbeat {
+    /// Send heartbeat events. Defaults to true.
+    pub enabled: Option<bool>,
+    /// Seconds of silence before a heartbeat is sent, and between
+    /// heartbeats while the silence lasts. Defaults to 15.
+    pub interval_seconds: Option<u64>,
+}
+
+impl Heartbeat {
+    /// The silence after which a heartbeat is sent, or `None` when disabled.
+    pub fn interval(&self) -> Option<Duration> {
+        if !self.enabled.unwrap_or(true) {
+            return None;
+        }
+        let seconds = self
+            .interval_seconds
+            .unwrap_or(DEFAULT_HEARTBEAT_INTERVAL_SECONDS)
+            .max(1);
+        Some(Duration::from_secs(seconds))
+    }
+}
+
+pub const DEFAULT_ROLLOUT_FSYNC_INTERVAL_MS: u64 = 1_000;
+
+/// When rollout files are fsynced. Lines are always handed to the OS as soon
+/// as the writer catches up; fsync decides when they survive a power loss or
+/// kernel crash as well.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum RolloutFsync {
+    /// After every batch of lines the writer commits.
+    PerEvent,
+    /// At most once per `fsync_interval_ms` while there are unsynced lines.
+    Interval,
+    /// When a turn ends and when the session shuts down.
+    #[default]
+    OnTurnEnd,
+}
+
+/// How the session rollout is written to disk.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct Rollout {
+    /// When rollout files are fsynced. Defaults to `on-turn-end`.
+    pub fsync: Option<RolloutFsync>,
+    /// Milliseconds between fsyncs with `fsync = "interval"`. Defaults to
+    /// 1000.
+    pub fsync_interval_ms: Option<u64>,
+}
+
+impl Rollout {
+    /// The minimum time between fsyncs with [`RolloutFsync::Interval`].
+    pub fn fsync_interval(&self) -> Duration {
+        Duration::from_millis(
+            self.fsync_interval_ms
+                .unwrap_or(DEFAULT_ROLLOUT_FSYNC_INTERVAL_MS),
+        )
+    }
+}
+
+pub const DEFAULT_REPL_TIMEOUT_MS: u64 = 30_000;
+pub const DEFAULT_REPL_MAX_MEMORY_MB: u64 = 1_024;
+
+/// Interpreter behind the `repl` tool.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReplLanguage {
+    #[default]
+    Python,
+    Node,
+}
+
+/// Settings of the `repl` tool, which keeps one interpreter per conversation.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct Repl {
+    /// Language of the interpreter. Defaults to `python`.
+    pub language: Option<ReplLanguage>,
+    /// Interpreter executable. Defaults to `python3` or `node`.
+    pub interpreter: Option<String>,
+    /// Milliseconds a snippet may run before the interpreter is restarted.
+    /// Defaults to 30000.
+    pub timeout_ms: Option<u64>,
+    /// Memory limit of the interpreter in MiB. Defaults to 1024.
+    pub max_memory_mb: Option<u64>,
+}
+
+impl Repl {
+    pub fn language(&self) -> ReplLanguage {
+        self.language.unwrap_or_default()
+    }
+
+    pub fn interpreter(&self) -> String {
+        self.interpreter.clone().unwrap_or_else(|| {
+            match self.language() {
+                ReplLanguage::Python => "python3",
+                ReplLanguage::Node => "node",
+            }
+            .to_string()
+        })
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms.unwrap_or(DEFAULT_REPL_TIMEOUT_MS))
+    }
+
+    pub fn max_memory_mb(&self) -> u64 {
+        self.max_memory_mb.unwrap_or(DEFAULT_REPL_MAX_MEMORY_MB)
+    }
+}
+
+pub const DEFAULT_EXPLORE_MAX_TOKENS: u64 = 200_000;
+pub const DEFAULT_EXPLORE_MAX_DURATION_SECS: u64 = 300;
+/// Built-in tools that only read, or run commands in the read-only sandbox.
+/// `write_stdin` is left out because it reaches processes started by
+/// earlier turns outside that sandbox.
+pub const DEFAULT_EXPLORE_ALLOWED_TOOLS: &[&str] = &[
+    "shell",
+    "container.exec",
+    "local_shell",
+    "shell_command",
+    "exec_command",
+    "grep_files",
+    "read_file",
+    "list_dir",
+    "project_map",
+    "view_image",
+    "read_output_chunk",
+    "read_artifact",
+    "list_mcp_resources",
+    "list_mcp_resource_templates",
+    "read_mcp_resource",
+    "update_plan",
+    "web_search",
+];
+
+/// Budget and tools of an explore task, which investigates the repository
+/// read-only before its findings are summarized.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct Explore {
+    /// Tokens, excluding cached input, the exploration may spend before it is
+    /// stopped. Defaults to 200000.
+    pub max_tokens: Option<u64>,
+    /// Seconds the exploration may run before it is stopped. Defaults to 300.
+    pub max_duration_secs: Option<u64>,
+    /// Tools the exploration is offered, built-in names or qualified MCP
+    /// names; every other tool is left out. Defaults to the built-in tools
+    /// that only read.
+    pub allowed_tools: Option<Vec<String>>,
+}
+
+impl Explore {
+    pub fn max_tokens(&self) -> u64 {
+        self.max_tokens.unwrap_or(DEFAULT_EXPLORE_MAX_TOKENS)
+    }
+
+    pub fn max_duration(&self) -> Duration {
+        Duration::from_secs(
+            self.max_duration_secs
+                .unwrap_or(DEFAULT_EXPLORE_MAX_DURATION_SECS),
+        )
+    }
+
+    pub fn allowed_tools(&self) -> Vec<String> {
+        match &self.allowed_tools {
+            Some(tools) => tools.clone(),
+            None => DEFAULT_EXPLORE_ALLOWED_TOOLS
+                .iter()
+                .map(|tool| (*tool).to_string())
+                .collect(),
+        }
+    }
+}
+
+pub const DEFAULT_BACKGROUND_COMPACTION_IDLE_SECS: u64 = 30;
+pub const DEFAULT_BACKGROUND_COMPACTION_START_PERCENT: u8 = 60;
+pub const DEFAULT_BACKGROUND_COMPACTION_KEEP_RECENT_TURNS: usize = 2;
+
+/// When older turns are summarized ahead of time, enabled with the
+/// `background_compaction` feature.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct BackgroundCompaction {
+    /// Seconds the session must be idle after a turn before older turns are
+    /// summarized. Defaults to 30.
+    pub idle_secs: Option<u64>,
+    /// Percentage of the auto-compact token limit the history must reach
+    /// before a summary is prepared. Defaults to 60.
+    pub start_percent: Option<u8>,
+    /// Most recent user turns kept verbatim rather than summarized.
+    /// Defaults to 2.
+    pub keep_recent_turns: Option<usize>,
+}
+
+impl BackgroundCompaction {
+    pub fn idle(&self) -> Duration {
+        Duration::from_secs(
+            self.idle_secs
+                .unwrap_or(DEFAULT_BACKGROUND_COMPACTION_IDLE_SECS),
+        )
+    }
+
+    pub fn start_percent(&self) -> u8 {
+        self.start_percent
+            .unwrap_or(DEFAULT_BACKGROUND_COMPACTION_START_PERCENT)
+            .min(100)
+    }
+
+    pub fn keep_recent_turns(&self) -> usize {
+        self.keep_recent_turns
+            .unwrap_or(DEFAULT_BACKGROUND_COMPACTION_KEEP_RECENT_TURNS)
+    }
+}
+
+pub const DEFAULT_DELEGATE_LOCAL_MAX_OUTPUT_TOKENS: u64 = 2_000;
+pub const DEFAULT_DELEGATE_LOCAL_TIMEOUT_SECS: u64 = 120;
+
+/// Local model the `delegate_local` tool hands subtasks to, enabled with the
+/// `delegate_local` feature.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct DelegateLocal {
+    /// Key into `model_providers` of the provider serving the model.
+    /// Defaults to `oss_provider`, then `ollama`.
+    pub provider: Option<String>,
+    /// Model to run, e.g. `gpt-oss:20b`. The tool fails until it is set.
+    pub model: Option<String>,
+    /// Tokens a delegated answer may take before it is cut off. The model
+    /// may ask for fewer. Defaults to 2000.
+    pub max_output_tokens: Option<u64>,
+    /// Seconds a delegated subtask may run. Defaults to 120.
+    pub timeout_secs: Option<u64>,
+}
+
+impl DelegateLocal {
+    pub fn provider(&self) -> &str {
+        self.provider.as_deref().unwrap_or(OLLAMA_OSS_PROVIDER_ID)
+    }
+
+    pub fn max_output_tokens(&self) -> u64 {
+        self.max_output_tokens
+            .unwrap_or(DEFAULT_DELEGATE_LOCAL_MAX_OUTPUT_TOKENS)
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(
+            self.timeout_secs
+                .unwrap_or(DEFAULT_DELEGATE_LOCAL_TIMEOUT_SECS),
+        )
+    }
+}
+
+pub const DEFAULT_KNOWLEDGE_MAX_TOKENS: u64 = 2_000;
+
+/// Budget of the `.codex/knowledge` snippets added to a turn, enabled with
+/// the `knowledge` feature.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct Knowledge {
+    /// Tokens the snippets added to one turn may take. Defaults to 2000.
+    pub max_tokens: Option<u64>,
+}
+
+impl Knowledge {
+    pub fn max_tokens(&self) -> u64 {
+        self.max_tokens.unwrap_or(DEFAULT_KNOWLEDGE_MAX_TOKENS)
+    }
+}
+
+pub const DEFAULT_SECRET_ENTROPY_THRESHOLD: f64 = 4.0;
+
+/// Masking of likely secrets in tool outputs before they are sent to the
+/// model, enabled with the `secret_masking` feature.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct SecretMasking {
+    /// Regular expressions whose matches are masked in addition to the
+    /// built-in credential formats.
+    #[serde(default)]
+    pub patterns: Vec<String>,
+    /// Shannon entropy, in bits per character, from which long random-looking
+    /// tokens and values of secret-looking variables are masked. Defaults to 4.0.
+    pub entropy_threshold: Option<f64>,
+    /// Exact values that are never masked, for false positives.
+    #[serde(default)]
+    pub allow: Vec<String>,
+}
+
+impl SecretMasking {
+    pub fn entropy_threshold(&self) -> f64 {
+        self.entropy_threshold
+            .unwrap_or(DEFAULT_SECRET_ENTROPY_THRESHOLD)
+    }
+}
+
+/// Language, timezone and measurement units to assume for the user. Unset
+/// values are detected from the operating system.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct LocaleToml {
+    /// BCP 47 language tag such as `de-DE`. Defaults to the system locale.
+    pub language: Option<String>,
+
+    /// `local` for the system timezone, `UTC`, or a fixed offset from UTC
+    /// such as `+05:30`. Defaults to `local`.
+    pub timezone: Option<String>,
+
+    /// Measurement system for generated content. Defaults to the convention
+    /// of the language's region.
+    pub units: Option<Units>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Units {
+    Metric,
+    Imperial,
+}
+
+/// USD prices per million tokens for one model, used to estimate session
+/// cost in session stats.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ModelPricing {
+    /// Price per million uncached input tokens.
+    pub input: f64,
+    /// Price per million cached input tokens. Defaults to `input`.
+    pub cached_input: Option<f64>,
+    /// Price per million output tokens, including reasoning tokens.
+    pub output: f64,
+}
+
+/// A user-defined model family, for models Codex has no built-in metadata
+/// for such as self-hosted fine-tunes. The first family whose `match`
+/// pattern matches the model slug overrides the settings it sets.
+///
+/// ```toml
+/// [[model_families]]
+/// name = "acme-coder"
+/// match = "^acme-coder-"
+/// context_window = 131072
+/// max_output_tokens = 8192
+/// instructions = "gpt-5-codex"
+/// shell_type = "shell_command"
+/// apply_patch = "function"
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ModelFamily {
+    /// Label used in error messages. Defaults to `model_families[<index>]`.
+    pub name: Option<String>,
+
+    /// Regular expression matched against the model slug. It is not
+    /// anchored, so use `^` and `$` to match the whole slug.
+    #[serde(rename = "match")]
+    pub pattern: String,
+
+    /// Context window of the model, in tokens.
+    pub context_window: Option<i64>,
+
+    /// Upper bound on the tokens the model may generate per response.
+    pub max_output_tokens: Option<i64>,
+
+    /// Built-in instructions to send to the model.
+    pub instructions: Option<ModelInstructionsPreset>,
+
+    /// Which shell tool the model is given.
+    pub shell_type: Option<ConfigShellToolType>,
+
+    /// How the `apply_patch` tool is offered to the model.
+    pub apply_patch: Option<ApplyPatchToolChoice>,
+
+    /// Whether the model may issue several tool calls in one response.
+    pub supports_parallel_tool_calls: Option<bool>,
+
+    /// Whether to request reasoning summaries from the model.
+    pub supports_reasoning_summaries: Option<bool>,
+
+    /// Whether the model accepts image input.
+    pub supports_image_input: Option<bool>,
+
+    /// Experimental tools to offer the model, e.g. `["read_file", "list_dir"]`.
+    pub experimental_supported_tools: Option<Vec<String>>,
+}
+
+/// Built-in instructions that a [`ModelFamily`] can select.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+pub enum ModelInstructionsPreset {
+    /// The generic prompt, for models that get `apply_patch` as a tool.
+    #[serde(rename = "default")]
+    Default,
+    /// The generic prompt with `apply_patch` usage spelled out, for models
+    /// that call it through the shell.
+    #[serde(rename = "with_apply_patch")]
+    WithApplyPatch,
+    /// The instructions of `gpt-5-codex` and `gpt-5.1-codex`.
+    #[serde(rename = "gpt-5-codex")]
+    Gpt5Codex,
+    /// The instructions of `gpt-5.1`.
+    #[serde(rename = "gpt-5.1")]
+    Gpt51,
+    /// The instructions of `gpt-5.1-codex-max`.
+    #[serde(rename = "gpt-5.1-codex-max")]
+    Gpt51CodexMax,
+    /// The instructions of `gpt-5.2`.
+    #[serde(rename = "gpt-5.2")]
+    Gpt52,
+    /// The instructions of `gpt-5.2-codex`, including its personalities.
+    #[serde(rename = "gpt-5.2-codex")]
+    Gpt52Codex,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ApplyPatchToolChoice {
+    /// A freeform tool whose input is the patch itself.
+    Freeform,
+    /// A JSON function tool.
+    Function,
+    /// No `apply_patch` tool.
+    None,
+}
+
+/// A config-defined rule that decides whether a shell command needs
+/// approval. Every condition that is set must hold for the rule to match.
+///
+/// ```toml
+/// [[approval_rules]]
+/// name = "read-only"
+/// command_classes = ["read", "list_files", "search"]
+/// decision = "allow"
+///
+/// [[approval_rules]]
+/// command_prefix = ["git", "push"]
+/// decision = "prompt"
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ApprovalRule {
+    /// Label reported with approval requests. Defaults to
+    /// `approval_rules[<index>]`.
+    pub name: Option<String>,
+
+    /// Matches when every parsed sub-command belongs to one of these classes.
+    pub command_classes: Option<Vec<CommandClass>>,
+
+    /// Matches when any sub-command starts with these arguments, e.g.
+    /// `["git", "push"]`.
+    pub command_prefix: Option<Vec<String>>,
+
+    /// Matches when any path the command targets matches one of these glob
+    /// patterns. Relative paths are resolved against the command's working
+    /// directory and `~/` expands to the home directory.
+    pub paths: Option<Vec<String>>,
+
+    /// Matches commands that do (`true`) or do not (`false`) appear to use
+    /// the network.
+    pub network: Option<bool>,
+
+    /// What to do with a matching command.
+    pub decision: ApprovalRuleDecision,
+}
+
+/// Command classes reported by `parse_command`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandClass {
+    Read,
+    ListFiles,
+    Search,
+    Unknown,
+}
+
 /// Collection of settings that are specific to the TUI.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
 #[schemars(deny_unknown_fields)]
@@ -454,6 +1198,17 @@ pub struct Tui {
     /// scrollback in terminal multiplexers like Zellij that follow the xterm spec.
     #[serde(default)]
     pub alternate_screen: AltScreenMode,
+
+    /// Show the session status (model, current phase, pending approvals) in
+    /// the terminal title, restoring the original title on exit.
+    /// Defaults to `false`.
+    #[serde(default)]
+    pub terminal_title: bool,
+
+    /// Show a progress indicator (OSC 9;4) in terminals that support one while
+    /// a turn is running. Defaults to `false`.
+    #[serde(default)]
+    pub terminal_progress: bool,
 }
 
 const fn default_true() -> bool {
@@ -512,6 +1267,81 @@ pub struct SandboxWorkspaceWrite {
     pub exclude_tmpdir_env_var: bool,
     #[serde(default)]
     pub exclude_slash_tmp: bool,
+    /// Memory cap, in bytes, for each sandboxed command (Windows only).
+    pub max_memory_bytes: Option<u64>,
+    /// Share of total CPU time (1-100) for each sandboxed command (Windows
+    /// only).
+    pub cpu_rate_percent: Option<u8>,
+    /// Maximum number of concurrently running processes per sandboxed command
+    /// (Windows only).
+    pub max_processes: Option<u32>,
+}
+
+impl SandboxWorkspaceWrite {
+    /// Rejects limits no command could run under.
+    pub(crate) fn validate_limits(&self) -> std::io::Result<()> {
+        let invalid = |key: &str, value: String, expected: &str| {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("sandbox_workspace_write.{key} = {value} is invalid: {expected}"),
+            ))
+        };
+        if let Some(percent) = self.cpu_rate_percent
+            && !(1..=100).contains(&percent)
+        {
+            return invalid("cpu_rate_percent", percent.to_string(), "expected 1 to 100");
+        }
+        if self.max_processes == Some(0) {
+            return invalid("max_processes", "0".to_string(), "expected at least 1");
+        }
+        Ok(())
+    }
+}
+
+/// The resource limits configured under `[sandbox_workspace_write]`. They
+/// apply whenever the session runs workspace-write, including after switching
+/// to it mid-session.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WorkspaceWriteLimits {
+    pub max_memory_bytes: Option<u64>,
+    pub cpu_rate_percent: Option<u8>,
+    pub max_processes: Option<u32>,
+}
+
+impl WorkspaceWriteLimits {
+    /// `policy` with every limit it leaves unset taken from these.
+    pub fn apply(&self, policy: SandboxPolicy) -> SandboxPolicy {
+        match policy {
+            SandboxPolicy::WorkspaceWrite {
+                writable_roots,
+                network_access,
+                exclude_tmpdir_env_var,
+                exclude_slash_tmp,
+                max_memory_bytes,
+                cpu_rate_percent,
+                max_processes,
+            } => SandboxPolicy::WorkspaceWrite {
+                writable_roots,
+                network_access,
+                exclude_tmpdir_env_var,
+                exclude_slash_tmp,
+                max_memory_bytes: max_memory_bytes.or(self.max_memory_bytes),
+                cpu_rate_percent: cpu_rate_percent.or(self.cpu_rate_percent),
+                max_processes: max_processes.or(self.max_processes),
+            },
+            policy => policy,
+        }
+    }
+}
+
+impl From<&SandboxWorkspaceWrite> for WorkspaceWriteLimits {
+    fn from(sandbox_workspace_write: &SandboxWorkspaceWrite) -> Self {
+        Self {
+            max_memory_bytes: sandbox_workspace_write.max_memory_bytes,
+            cpu_rate_percent: sandbox_workspace_write.cpu_rate_percent,
+            max_processes: sandbox_workspace_write.max_processes,
+        }
+    }
 }
 
 impl From<SandboxWorkspaceWrite> for codex_app_server_protocol::SandboxSettings {
@@ -549,6 +1379,10 @@ pub struct ShellEnvironmentPolicyToml {
 
     pub ignore_default_excludes: Option<bool>,
 
+    /// Names (with `*` and `?` wildcards) of parent variables to pass on in
+    /// addition to those of `inherit`, even when a default exclude matches.
+    pub include: Option<Vec<String>>,
+
     /// List of regular expressions.
     pub exclude: Option<Vec<String>>,
 
@@ -560,12 +1394,42 @@ pub struct ShellEnvironmentPolicyToml {
     pub experimental_use_profile: Option<bool>,
 }
 
+impl ShellEnvironmentPolicyToml {
+    /// Applies the `shell_environment_policy` of a profile on top of this
+    /// one: fields the profile sets replace the base values, except `set`,
+    /// whose entries are added to the base entries and override them.
+    pub fn with_profile_overrides(self, profile: ShellEnvironmentPolicyToml) -> Self {
+        let r#set = match (self.r#set, profile.r#set) {
+            (Some(mut base), Some(profile)) => {
+                base.extend(profile);
+                Some(base)
+            }
+            (base, profile) => profile.or(base),
+        };
+        Self {
+            inherit: profile.inherit.or(self.inherit),
+            ignore_default_excludes: profile
+                .ignore_default_excludes
+                .or(self.ignore_default_excludes),
+            include: profile.include.or(self.include),
+            exclude: profile.exclude.or(self.exclude),
+            r#set,
+            include_only: profile.include_only.or(self.include_only),
+            experimental_use_profile: profile
+                .experimental_use_profile
+                .or(self.experimental_use_profile),
+        }
+    }
+}
+
 pub type EnvironmentVariablePattern = WildMatchPattern<'*', '?'>;
 
 /// Deriving the `env` based on this policy works as follows:
-/// 1. Create an initial map based on the `inherit` policy.
+/// 1. Create an initial map based on the `inherit` policy, then add the
+///    parent variables matching `include`.
 /// 2. If `ignore_default_excludes` is false, filter the map using the default
 ///    exclude pattern(s), which are: `"*KEY*"`, `"*SECRET*"`, and `"*TOKEN*"`.
+///    Variables matching `include` are kept.
 /// 3. If `exclude` is not empty, filter the map using the provided patterns.
 /// 4. Insert any entries from `r#set` into the map.
 /// 5. If non-empty, filter the map using the `include_only` patterns.
@@ -578,6 +1442,10 @@ pub struct ShellEnvironmentPolicy {
     /// contain "KEY", "SECRET", or "TOKEN" in their name. Defaults to true.
     pub ignore_default_excludes: bool,
 
+    /// Parent variables passed on regardless of `inherit` and the default
+    /// excludes.
+    pub include: Vec<EnvironmentVariablePattern>,
+
     /// Environment variable names to exclude from the environment.
     pub exclude: Vec<EnvironmentVariablePattern>,
 
@@ -596,6 +1464,12 @@ impl From<ShellEnvironmentPolicyToml> for ShellEnvironmentPolicy {
         // Default to inheriting the full environment when not specified.
         let inherit = toml.inherit.unwrap_or(ShellEnvironmentPolicyInherit::All);
         let ignore_default_excludes = toml.ignore_default_excludes.unwrap_or(true);
+        let include = toml
+            .include
+            .unwrap_or_default()
+            .into_iter()
+            .map(|s| EnvironmentVariablePattern::new_case_insensitive(&s))
+            .collect();
         let exclude = toml
             .exclude
             .unwrap_or_default()
@@ -614,6 +1488,7 @@ impl From<ShellEnvironmentPolicyToml> for ShellEnvironmentPolicy {
         Self {
             inherit,
             ignore_default_excludes,
+            include,
             exclude,
             r#set,
             include_only,
@@ -627,6 +1502,7 @@ impl Default for ShellEnvironmentPolicy {
         Self {
             inherit: ShellEnvironmentPolicyInherit::All,
             ignore_default_excludes: true,
+            include: Vec::new(),
             exclude: Vec::new(),
             r#set: HashMap::new(),
             include_only: Vec::new(),
@@ -640,6 +1516,88 @@ mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
 
+    #[test]
+    fn workspace_write_limits_fill_only_unset_limits() {
+        let limits = WorkspaceWriteLimits {
+            max_memory_bytes: Some(1024),
+            cpu_rate_percent: Some(50),
+            max_processes: Some(8),
+        };
+        let preset = SandboxPolicy::new_workspace_write_policy();
+        let explicit = SandboxPolicy::WorkspaceWrite {
+            writable_roots: Vec::new(),
+            network_access: false,
+            exclude_tmpdir_env_var: false,
+            exclude_slash_tmp: false,
+            max_memory_bytes: None,
+            cpu_rate_percent: Some(25),
+            max_processes: None,
+        };
+
+        assert_eq!(
+            limits.apply(preset),
+            SandboxPolicy::WorkspaceWrite {
+                writable_roots: Vec::new(),
+                network_access: false,
+                exclude_tmpdir_env_var: false,
+                exclude_slash_tmp: false,
+                max_memory_bytes: Some(1024),
+                cpu_rate_percent: Some(50),
+                max_processes: Some(8),
+            }
+        );
+        assert_eq!(
+            limits.apply(explicit),
+            SandboxPolicy::WorkspaceWrite {
+                writable_roots: Vec::new(),
+                network_access: false,
+                exclude_tmpdir_env_var: false,
+                exclude_slash_tmp: false,
+                max_memory_bytes: Some(1024),
+                cpu_rate_percent: Some(25),
+                max_processes: Some(8),
+            }
+        );
+        assert_eq!(
+            limits.apply(SandboxPolicy::ReadOnly),
+            SandboxPolicy::ReadOnly
+        );
+    }
+
+    #[test]
+    fn profile_shell_environment_policy_overrides_fields_and_merges_set() {
+        let base: ShellEnvironmentPolicyToml = toml::from_str(
+            r#"
+            inherit = "core"
+            exclude = ["AWS_*"]
+            set = { CI = "1", RUSTFLAGS = "" }
+        "#,
+        )
+        .expect("base policy");
+        let profile: ShellEnvironmentPolicyToml = toml::from_str(
+            r#"
+            include = ["CARGO_*"]
+            exclude = []
+            set = { RUSTFLAGS = "-D warnings" }
+        "#,
+        )
+        .expect("profile policy");
+
+        assert_eq!(
+            base.with_profile_overrides(profile),
+            ShellEnvironmentPolicyToml {
+                inherit: Some(ShellEnvironmentPolicyInherit::Core),
+                include: Some(vec!["CARGO_*".to_string()]),
+                exclude: Some(Vec::new()),
+                r#set: Some(HashMap::from([
+                    ("CI".to_string(), "1".to_string()),
+                    ("RUSTFLAGS".to_string(), "-D warnings".to_string()),
+                ])),
+                ..Default::default()
+            }
+        );
+    }
+
     #[test]
     fn deserialize_stdio_command_server_config() {
         let cfg: McpServerConfig = toml::from_str(
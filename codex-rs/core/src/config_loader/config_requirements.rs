@@ -8,6 +8,7 @@ use std::fmt;
 
 use crate::config::Constrained;
 use crate::config::ConstraintError;
+use crate::config::types::OtelExporterKind;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum RequirementSource {
@@ -45,6 +46,9 @@ pub struct ConfigRequirements {
     pub approval_policy: Constrained<AskForApproval>,
     pub sandbox_policy: Constrained<SandboxPolicy>,
     pub mcp_servers: Option<Sourced<BTreeMap<String, McpServerRequirement>>>,
+    pub allowed_models: Option<Sourced<Vec<String>>>,
+    pub forbidden_tools: Option<Sourced<Vec<String>>>,
+    pub otel_exporter: Option<Sourced<OtelExporterKind>>,
 }
 
 impl Default for ConfigRequirements {
@@ -53,6 +57,9 @@ impl Default for ConfigRequirements {
             approval_policy: Constrained::allow_any_from_default(),
             sandbox_policy: Constrained::allow_any(SandboxPolicy::ReadOnly),
             mcp_servers: None,
+            allowed_models: None,
+            forbidden_tools: None,
+            otel_exporter: None,
         }
     }
 }
@@ -75,6 +82,14 @@ pub struct ConfigRequirementsToml {
     pub allowed_approval_policies: Option<Vec<AskForApproval>>,
     pub allowed_sandbox_modes: Option<Vec<SandboxModeRequirement>>,
     pub mcp_servers: Option<BTreeMap<String, McpServerRequirement>>,
+    /// Models that `model` and `review_model` may be set to. When `model` is
+    /// unset, the first entry is used.
+    pub allowed_models: Option<Vec<String>>,
+    /// Tool names (built-in, or `mcp__<server>__<tool>`) that are never
+    /// offered to the model.
+    pub forbidden_tools: Option<Vec<String>>,
+    /// OTEL exporter that logs and traces must be sent to.
+    pub otel_exporter: Option<OtelExporterKind>,
 }
 
 /// Value paired with the requirement source it came from, for better error
@@ -104,6 +119,9 @@ pub struct ConfigRequirementsWithSources {
     pub allowed_approval_policies: Option<Sourced<Vec<AskForApproval>>>,
     pub allowed_sandbox_modes: Option<Sourced<Vec<SandboxModeRequirement>>>,
     pub mcp_servers: Option<Sourced<BTreeMap<String, McpServerRequirement>>>,
+    pub allowed_models: Option<Sourced<Vec<String>>>,
+    pub forbidden_tools: Option<Sourced<Vec<String>>>,
+    pub otel_exporter: Option<Sourced<OtelExporterKind>>,
 }
 
 impl ConfigRequirementsWithSources {
@@ -135,6 +153,9 @@ impl ConfigRequirementsWithSources {
                 allowed_approval_policies,
                 allowed_sandbox_modes,
                 mcp_servers,
+                allowed_models,
+                forbidden_tools,
+                otel_exporter,
             }
         );
     }
@@ -144,11 +165,17 @@ impl ConfigRequirementsWithSources {
             allowed_approval_policies,
             allowed_sandbox_modes,
             mcp_servers,
+            allowed_models,
+            forbidden_tools,
+            otel_exporter,
         } = self;
         ConfigRequirementsToml {
             allowed_approval_policies: allowed_approval_policies.map(|sourced| sourced.value),
             allowed_sandbox_modes: allowed_sandbox_modes.map(|sourced| sourced.value),
             mcp_servers: mcp_servers.map(|sourced| sourced.value),
+            allowed_models: allowed_models.map(|sourced| sourced.value),
+            forbidden_tools: forbidden_tools.map(|sourced| sourced.value),
+            otel_exporter: otel_exporter.map(|sourced| sourced.value),
         }
     }
 }
@@ -185,6 +212,9 @@ impl ConfigRequirementsToml {
         self.allowed_approval_policies.is_none()
             && self.allowed_sandbox_modes.is_none()
             && self.mcp_servers.is_none()
+            && self.allowed_models.is_none()
+            && self.forbidden_tools.is_none()
+            && self.otel_exporter.is_none()
     }
 }
 
@@ -196,8 +226,18 @@ impl TryFrom<ConfigRequirementsWithSources> for ConfigRequirements {
             allowed_approval_policies,
             allowed_sandbox_modes,
             mcp_servers,
+            allowed_models,
+            forbidden_tools,
+            otel_exporter,
         } = toml;
 
+        if allowed_models
+            .as_ref()
+            .is_some_and(|models| models.value.is_empty())
+        {
+            return Err(ConstraintError::empty_field("allowed_models"));
+        }
+
         let approval_policy: Constrained<AskForApproval> = match allowed_approval_policies {
             Some(Sourced {
                 value: policies,
@@ -274,6 +314,9 @@ impl TryFrom<ConfigRequirementsWithSources> for ConfigRequirements {
             approval_policy,
             sandbox_policy,
             mcp_servers,
+            allowed_models,
+            forbidden_tools,
+            otel_exporter,
         })
     }
 }
@@ -292,6 +335,9 @@ mod tests {
             allowed_approval_policies,
             allowed_sandbox_modes,
             mcp_servers,
+            allowed_models,
+            forbidden_tools,
+            otel_exporter,
         } = toml;
         ConfigRequirementsWithSources {
             allowed_approval_policies: allowed_approval_policies
@@ -299,6 +345,12 @@ mod tests {
             allowed_sandbox_modes: allowed_sandbox_modes
                 .map(|value| Sourced::new(value, RequirementSource::Unknown)),
             mcp_servers: mcp_servers.map(|value| Sourced::new(value, RequirementSource::Unknown)),
+            allowed_models: allowed_models
+                .map(|value| Sourced::new(value, RequirementSource::Unknown)),
+            forbidden_tools: forbidden_tools
+                .map(|value| Sourced::new(value, RequirementSource::Unknown)),
+            otel_exporter: otel_exporter
+                .map(|value| Sourced::new(value, RequirementSource::Unknown)),
         }
     }
 
@@ -312,6 +364,8 @@ mod tests {
             SandboxModeRequirement::WorkspaceWrite,
             SandboxModeRequirement::DangerFullAccess,
         ];
+        let allowed_models = vec!["gpt-5.1-codex".to_string()];
+        let forbidden_tools = vec!["web_search".to_string()];
 
         // Intentionally constructed without `..Default::default()` so adding a new field to
         // `ConfigRequirementsToml` forces this test to be updated.
@@ -319,6 +373,9 @@ mod tests {
             allowed_approval_policies: Some(allowed_approval_policies.clone()),
             allowed_sandbox_modes: Some(allowed_sandbox_modes.clone()),
             mcp_servers: None,
+            allowed_models: Some(allowed_models.clone()),
+            forbidden_tools: Some(forbidden_tools.clone()),
+            otel_exporter: Some(OtelExporterKind::Statsig),
         };
 
         target.merge_unset_fields(source.clone(), other);
@@ -330,8 +387,11 @@ mod tests {
                     allowed_approval_policies,
                     source.clone()
                 )),
-                allowed_sandbox_modes: Some(Sourced::new(allowed_sandbox_modes, source)),
+                allowed_sandbox_modes: Some(Sourced::new(allowed_sandbox_modes, source.clone())),
                 mcp_servers: None,
+                allowed_models: Some(Sourced::new(allowed_models, source.clone())),
+                forbidden_tools: Some(Sourced::new(forbidden_tools, source.clone())),
+                otel_exporter: Some(Sourced::new(OtelExporterKind::Statsig, source)),
             }
         );
     }
@@ -360,6 +420,9 @@ mod tests {
                 )),
                 allowed_sandbox_modes: None,
                 mcp_servers: None,
+                allowed_models: None,
+                forbidden_tools: None,
+                otel_exporter: None,
             }
         );
         Ok(())
@@ -396,6 +459,9 @@ mod tests {
                 )),
                 allowed_sandbox_modes: None,
                 mcp_servers: None,
+                allowed_models: None,
+                forbidden_tools: None,
+                otel_exporter: None,
             }
         );
         Ok(())
@@ -526,6 +592,9 @@ mod tests {
                     network_access: false,
                     exclude_tmpdir_env_var: false,
                     exclude_slash_tmp: false,
+                    max_memory_bytes: None,
+                    cpu_rate_percent: None,
+                    max_processes: None,
                 })
                 .is_ok()
         );
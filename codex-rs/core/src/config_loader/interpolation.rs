@@ -0,0 +1,137 @@
+//! `${env:VAR}` and `${file:/path}` interpolation in config string values.
+//!
+//! References are resolved in the effective config that [`crate::config::Config`]
+//! is built from, never in the layers themselves, so reading or editing
+//! `config.toml` through the config service keeps the references rather than
+//! the secrets they point at. Any other `${...}` text, e.g. a shell-style
+//! `${HOME}` in `notify`, is kept verbatim. Write `$${env:` or `$${file:` for
+//! a literal reference.
+
+use std::io;
+use std::path::PathBuf;
+
+use toml::Value as TomlValue;
+
+/// Starts of the references that are resolved.
+const REFERENCE_PREFIXES: &[&str] = &["${env:", "${file:"];
+
+/// Resolves every interpolation reference in the string values of `value`.
+pub(super) fn interpolate_config_values(value: &mut TomlValue) -> io::Result<()> {
+    interpolate_with(value, &|name| std::env::var(name).ok())
+}
+
+pub(super) fn interpolate_with(
+    value: &mut TomlValue,
+    lookup_env: &dyn Fn(&str) -> Option<String>,
+) -> io::Result<()> {
+    let mut path = Vec::new();
+    interpolate_value(value, &mut path, lookup_env)
+}
+
+fn interpolate_value(
+    value: &mut TomlValue,
+    path: &mut Vec<String>,
+    lookup_env: &dyn Fn(&str) -> Option<String>,
+) -> io::Result<()> {
+    match value {
+        TomlValue::String(text) => {
+            if text.contains("${") {
+                *text = interpolate_string(text, lookup_env).map_err(|message| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "Error resolving config value `{}`: {message}",
+                            path.join(".")
+                        ),
+                    )
+                })?;
+            }
+        }
+        TomlValue::Array(items) => {
+            for (index, item) in items.iter_mut().enumerate() {
+                path.push(index.to_string());
+                interpolate_value(item, path, lookup_env)?;
+                path.pop();
+            }
+        }
+        TomlValue::Table(table) => {
+            for (key, item) in table.iter_mut() {
+                path.push(key.clone());
+                interpolate_value(item, path, lookup_env)?;
+                path.pop();
+            }
+        }
+        TomlValue::Integer(_)
+        | TomlValue::Float(_)
+        | TomlValue::Boolean(_)
+        | TomlValue::Datetime(_) => {}
+    }
+    Ok(())
+}
+
+fn interpolate_string(
+    text: &str,
+    lookup_env: &dyn Fn(&str) -> Option<String>,
+) -> Result<String, String> {
+    let mut resolved = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = find_reference(rest) {
+        if rest[..start].ends_with('$') {
+            resolved.push_str(&rest[..start - 1]);
+            resolved.push_str("${");
+            rest = &rest[start + 2..];
+            continue;
+        }
+        resolved.push_str(&rest[..start]);
+        let reference = &rest[start + 2..];
+        let Some(end) = reference.find('}') else {
+            return Err(format!("unterminated `${{` in `{text}`"));
+        };
+        resolved.push_str(&resolve_reference(&reference[..end], lookup_env)?);
+        rest = &reference[end + 1..];
+    }
+    resolved.push_str(rest);
+    Ok(resolved)
+}
+
+fn find_reference(text: &str) -> Option<usize> {
+    REFERENCE_PREFIXES
+        .iter()
+        .filter_map(|prefix| text.find(prefix))
+        .min()
+}
+
+fn resolve_reference(
+    reference: &str,
+    lookup_env: &dyn Fn(&str) -> Option<String>,
+) -> Result<String, String> {
+    if let Some(name) = reference.strip_prefix("env:") {
+        if name.is_empty() {
+            return Err("`${env:}` is missing a variable name".to_string());
+        }
+        return lookup_env(name).ok_or_else(|| format!("environment variable `{name}` is not set"));
+    }
+    if let Some(path) = reference.strip_prefix("file:") {
+        let path = expand_home(path)?;
+        if !path.is_absolute() {
+            return Err(format!(
+                "`${{file:{}}}` must use an absolute path",
+                path.display()
+            ));
+        }
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|err| format!("failed to read `{}`: {err}", path.display()))?;
+        // Secret files usually end with a newline that is not part of the secret.
+        return Ok(contents.trim_end_matches(['\n', '\r']).to_string());
+    }
+    Ok(format!("${{{reference}}}"))
+}
+
+fn expand_home(path: &str) -> Result<PathBuf, String> {
+    match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()
+            .map(|home| home.join(rest))
+            .ok_or_else(|| format!("cannot expand `{path}`: home directory not found")),
+        None => Ok(PathBuf::from(path)),
+    }
+}
@@ -1,5 +1,6 @@
 mod config_requirements;
 mod fingerprint;
+mod interpolation;
 mod layer_io;
 #[cfg(target_os = "macos")]
 mod macos;
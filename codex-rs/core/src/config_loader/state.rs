@@ -3,6 +3,7 @@ use crate::config_loader::ConfigRequirementsToml;
 
 use super::fingerprint::record_origins;
 use super::fingerprint::version_for_toml;
+use super::interpolation::interpolate_config_values;
 use super::merge::merge_toml_values;
 use codex_app_server_protocol::ConfigLayer;
 use codex_app_server_protocol::ConfigLayerMetadata;
@@ -10,6 +11,7 @@ use codex_app_server_protocol::ConfigLayerSource;
 use codex_utils_absolute_path::AbsolutePathBuf;
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
+use std::io;
 use std::path::PathBuf;
 use toml::Value as TomlValue;
 
@@ -178,6 +180,15 @@ impl ConfigLayerStack {
         merged
     }
 
+    /// [`Self::effective_config`] with `${env:VAR}` and `${file:/path}`
+    /// references resolved. This is what [`crate::config::Config`] is built
+    /// from; the layers keep the unresolved references.
+    pub fn resolved_effective_config(&self) -> io::Result<TomlValue> {
+        let mut merged = self.effective_config();
+        interpolate_config_values(&mut merged)?;
+        Ok(merged)
+    }
+
     pub fn origins(&self) -> HashMap<String, ConfigLayerMetadata> {
         let mut origins = HashMap::new();
         let mut path = Vec::new();
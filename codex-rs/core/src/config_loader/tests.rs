@@ -9,6 +9,7 @@ use crate::config_loader::ConfigLayerEntry;
 use crate::config_loader::ConfigRequirements;
 use crate::config_loader::config_requirements::ConfigRequirementsWithSources;
 use crate::config_loader::fingerprint::version_for_toml;
+use crate::config_loader::interpolation::interpolate_with;
 use crate::config_loader::load_requirements_toml;
 use codex_protocol::config_types::TrustLevel;
 use codex_protocol::protocol::AskForApproval;
@@ -280,6 +281,9 @@ allowed_sandbox_modes = ["read-only"]
                 network_access: false,
                 exclude_tmpdir_env_var: false,
                 exclude_slash_tmp: false,
+                max_memory_bytes: None,
+                cpu_rate_percent: None,
+                max_processes: None,
             })
             .is_err()
     );
@@ -704,3 +708,116 @@ async fn project_root_markers_supports_alternate_markers() -> std::io::Result<()
 
     Ok(())
 }
+
+#[test]
+fn interpolates_env_and_file_references_in_strings() -> std::io::Result<()> {
+    let tmp = tempdir()?;
+    let token_file = tmp.path().join("token");
+    std::fs::write(&token_file, "file-secret\n")?;
+    let mut value: TomlValue = toml::from_str(&format!(
+        r#"
+model = "${{env:CODEX_TEST_MODEL}}"
+literal = "costs $${{env:NOT_RESOLVED}}"
+
+[model_providers.corp]
+base_url = "https://${{env:CODEX_TEST_HOST}}/v1"
+http_headers = {{ Authorization = 'Bearer ${{file:{}}}' }}
+"#,
+        token_file.display()
+    ))
+    .expect("valid toml");
+
+    interpolate_with(&mut value, &|name| match name {
+        "CODEX_TEST_MODEL" => Some("gpt-test".to_string()),
+        "CODEX_TEST_HOST" => Some("llm.example.com".to_string()),
+        _ => None,
+    })?;
+
+    let expected: TomlValue = toml::from_str(
+        r#"
+model = "gpt-test"
+literal = "costs ${env:NOT_RESOLVED}"
+
+[model_providers.corp]
+base_url = "https://llm.example.com/v1"
+http_headers = { Authorization = "Bearer file-secret" }
+"#,
+    )
+    .expect("valid toml");
+    assert_eq!(value, expected);
+    Ok(())
+}
+
+#[test]
+fn interpolation_keeps_other_dollar_braces_verbatim() -> std::io::Result<()> {
+    let config = r#"
+notify = ["notify-send", "${HOME}/done", "${env"]
+instructions = "Use ${VAR} and $${HOME} as written."
+"#;
+    let mut value: TomlValue = toml::from_str(config).expect("valid toml");
+
+    interpolate_with(&mut value, &|_| None)?;
+
+    let expected: TomlValue = toml::from_str(config).expect("valid toml");
+    assert_eq!(value, expected);
+    Ok(())
+}
+
+#[test]
+fn missing_interpolation_values_name_the_config_key() {
+    let mut value: TomlValue = toml::from_str(
+        r#"
+[model_providers.corp]
+experimental_bearer_token = "${env:CODEX_TEST_UNSET}"
+"#,
+    )
+    .expect("valid toml");
+
+    let err = interpolate_with(&mut value, &|_| None).expect_err("unset variable");
+
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(
+        err.to_string(),
+        "Error resolving config value `model_providers.corp.experimental_bearer_token`: \
+         environment variable `CODEX_TEST_UNSET` is not set"
+    );
+}
+
+#[tokio::test]
+async fn layers_keep_references_that_the_effective_config_resolves() -> std::io::Result<()> {
+    let tmp = tempdir()?;
+    let codex_home = tmp.path().join("home");
+    tokio::fs::create_dir_all(&codex_home).await?;
+    let key_file = tmp.path().join("api-key");
+    tokio::fs::write(&key_file, "sk-from-file").await?;
+    let reference = format!("${{file:{}}}", key_file.display());
+    tokio::fs::write(
+        codex_home.join(CONFIG_TOML_FILE),
+        format!("[model_providers.corp]\nexperimental_bearer_token = {reference:?}\n"),
+    )
+    .await?;
+
+    let layers = load_config_layers_state(
+        &codex_home,
+        None,
+        &[] as &[(String, TomlValue)],
+        LoaderOverrides::default(),
+    )
+    .await?;
+
+    let token_at = |value: &TomlValue| {
+        value
+            .get("model_providers")
+            .and_then(|providers| providers.get("corp"))
+            .and_then(|corp| corp.get("experimental_bearer_token"))
+            .and_then(TomlValue::as_str)
+            .map(str::to_string)
+    };
+    assert_eq!(token_at(&layers.effective_config()), Some(reference));
+    assert_eq!(
+        token_at(&layers.resolved_effective_config()?),
+        Some("sk-from-file".to_string())
+    );
+
+    Ok(())
+}
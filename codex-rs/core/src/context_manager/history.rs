@@ -1,13 +1,15 @@
 use crate::codex::TurnContext;
 use crate::context_manager::normalize;
+use crate::instructions::KnowledgeInstructions;
 use crate::instructions::SkillInstructions;
 use crate::instructions::UserInstructions;
 use crate::session_prefix::is_session_prefix;
+use crate::tokenizer::Tokenizer;
+use crate::tokenizer::tokenizer_for_model;
 use crate::truncate::TruncationPolicy;
-use crate::truncate::approx_token_count;
 use crate::truncate::approx_tokens_from_byte_count;
 use crate::truncate::truncate_function_output_items_with_policy;
-use crate::truncate::truncate_text;
+use crate::truncate::truncate_text_with_tokenizer;
 use crate::user_shell_command::is_user_shell_command_text;
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::FunctionCallOutputContentItem;
@@ -50,9 +52,14 @@ impl ContextManager {
         }
     }
 
-    /// `items` is ordered from oldest to newest.
-    pub(crate) fn record_items<I>(&mut self, items: I, policy: TruncationPolicy)
-    where
+    /// `items` is ordered from oldest to newest. Tool outputs are truncated
+    /// to `policy`, counting tokens with `tokenizer`.
+    pub(crate) fn record_items<I>(
+        &mut self,
+        items: I,
+        policy: TruncationPolicy,
+        tokenizer: &dyn Tokenizer,
+    ) where
         I: IntoIterator,
         I::Item: std::ops::Deref<Target = ResponseItem>,
     {
@@ -63,7 +70,7 @@ impl ContextManager {
                 continue;
             }
 
-            let processed = self.process_item(item_ref, policy);
+            let processed = self.process_item(item_ref, policy, tokenizer);
             self.items.push(processed);
         }
     }
@@ -82,36 +89,20 @@ impl ContextManager {
         &self.items
     }
 
-    // Estimate token usage using byte-based heuristics from the truncation helpers.
-    // This is a coarse lower bound, not a tokenizer-accurate count.
+    // Estimate token usage with the model's tokenizer, or with the byte-based
+    // heuristic for models whose encoding is unknown. Items are counted as
+    // serialized JSON, so this is an upper bound rather than an exact count.
     pub(crate) fn estimate_token_count(&self, turn_context: &TurnContext) -> Option<i64> {
         let model_info = turn_context.client.get_model_info();
+        let tokenizer = tokenizer_for_model(&model_info.slug);
         let personality = turn_context
             .personality
             .or(turn_context.client.config().model_personality);
         let base_instructions = model_info.get_model_instructions(personality);
-        let base_tokens = i64::try_from(approx_token_count(&base_instructions)).unwrap_or(i64::MAX);
+        let base_tokens =
+            i64::try_from(tokenizer.count_tokens(&base_instructions)).unwrap_or(i64::MAX);
 
-        let items_tokens = self.items.iter().fold(0i64, |acc, item| {
-            acc + match item {
-                ResponseItem::GhostSnapshot { .. } => 0,
-                ResponseItem::Reasoning {
-                    encrypted_content: Some(content),
-                    ..
-                }
-                | ResponseItem::Compaction {
-                    encrypted_content: content,
-                } => {
-                    let reasoning_bytes = estimate_reasoning_length(content.len());
-                    i64::try_from(approx_tokens_from_byte_count(reasoning_bytes))
-                        .unwrap_or(i64::MAX)
-                }
-                item => {
-                    let serialized = serde_json::to_string(item).unwrap_or_default();
-                    i64::try_from(approx_token_count(&serialized)).unwrap_or(i64::MAX)
-                }
-            }
-        });
+        let items_tokens = estimate_items_token_count(tokenizer.as_ref(), &self.items);
 
         Some(base_tokens.saturating_add(items_tokens))
     }
@@ -195,6 +186,14 @@ impl ContextManager {
         self.replace(snapshot[..cut_idx].to_vec());
     }
 
+    /// Returns the user turn boundary items in history, oldest first.
+    pub(crate) fn user_turns(&self) -> Vec<&ResponseItem> {
+        self.items
+            .iter()
+            .filter(|item| is_user_turn_boundary(item))
+            .collect()
+    }
+
     pub(crate) fn update_token_info(
         &mut self,
         usage: &TokenUsage,
@@ -265,16 +264,25 @@ impl ContextManager {
         normalize::remove_orphan_outputs(&mut self.items);
     }
 
-    fn process_item(&self, item: &ResponseItem, policy: TruncationPolicy) -> ResponseItem {
+    fn process_item(
+        &self,
+        item: &ResponseItem,
+        policy: TruncationPolicy,
+        tokenizer: &dyn Tokenizer,
+    ) -> ResponseItem {
         let policy_with_serialization_budget = policy.mul(1.2);
         match item {
             ResponseItem::FunctionCallOutput { call_id, output } => {
-                let truncated =
-                    truncate_text(output.content.as_str(), policy_with_serialization_budget);
+                let truncated = truncate_text_with_tokenizer(
+                    output.content.as_str(),
+                    policy_with_serialization_budget,
+                    tokenizer,
+                );
                 let truncated_items = output.content_items.as_ref().map(|items| {
                     truncate_function_output_items_with_policy(
                         items,
                         policy_with_serialization_budget,
+                        tokenizer,
                     )
                 });
                 ResponseItem::FunctionCallOutput {
@@ -287,7 +295,11 @@ impl ContextManager {
                 }
             }
             ResponseItem::CustomToolCallOutput { call_id, output } => {
-                let truncated = truncate_text(output, policy_with_serialization_budget);
+                let truncated = truncate_text_with_tokenizer(
+                    output,
+                    policy_with_serialization_budget,
+                    tokenizer,
+                );
                 ResponseItem::CustomToolCallOutput {
                     call_id: call_id.clone(),
                     output: truncated,
@@ -298,6 +310,8 @@ impl ContextManager {
             | ResponseItem::LocalShellCall { .. }
             | ResponseItem::FunctionCall { .. }
             | ResponseItem::WebSearchCall { .. }
+            | ResponseItem::CodeInterpreterCall { .. }
+            | ResponseItem::FileSearchCall { .. }
             | ResponseItem::CustomToolCall { .. }
             | ResponseItem::Compaction { .. }
             | ResponseItem::GhostSnapshot { .. }
@@ -318,12 +332,47 @@ fn is_api_message(message: &ResponseItem) -> bool {
         | ResponseItem::LocalShellCall { .. }
         | ResponseItem::Reasoning { .. }
         | ResponseItem::WebSearchCall { .. }
+        | ResponseItem::CodeInterpreterCall { .. }
+        | ResponseItem::FileSearchCall { .. }
         | ResponseItem::Compaction { .. } => true,
         ResponseItem::GhostSnapshot { .. } => false,
         ResponseItem::Other => false,
     }
 }
 
+/// Tokens `items` take up. Items are serialized and counted with `tokenizer`
+/// in one batch; encrypted reasoning cannot be tokenized and is estimated
+/// from its length.
+pub(crate) fn estimate_items_token_count(tokenizer: &dyn Tokenizer, items: &[ResponseItem]) -> i64 {
+    let mut reasoning_tokens = 0u64;
+    let mut serialized = Vec::new();
+    for item in items {
+        match item {
+            ResponseItem::GhostSnapshot { .. } => {}
+            ResponseItem::Reasoning {
+                encrypted_content: Some(content),
+                ..
+            }
+            | ResponseItem::Compaction {
+                encrypted_content: content,
+            } => {
+                let reasoning_bytes = estimate_reasoning_length(content.len());
+                reasoning_tokens =
+                    reasoning_tokens.saturating_add(approx_tokens_from_byte_count(reasoning_bytes));
+            }
+            item => serialized.push(serde_json::to_string(item).unwrap_or_default()),
+        }
+    }
+    let texts: Vec<&str> = serialized.iter().map(String::as_str).collect();
+    let tokens = reasoning_tokens.saturating_add(tokenizer.count_total(&texts).tokens);
+    i64::try_from(tokens).unwrap_or(i64::MAX)
+}
+
+/// Tokens a single history item takes up; see [`estimate_items_token_count`].
+pub(crate) fn estimate_item_token_count(tokenizer: &dyn Tokenizer, item: &ResponseItem) -> i64 {
+    estimate_items_token_count(tokenizer, std::slice::from_ref(item))
+}
+
 fn estimate_reasoning_length(encoded_len: usize) -> usize {
     encoded_len
         .saturating_mul(3)
@@ -343,6 +392,7 @@ pub(crate) fn is_user_turn_boundary(item: &ResponseItem) -> bool {
 
     if UserInstructions::is_user_instructions(content)
         || SkillInstructions::is_skill_instructions(content)
+        || KnowledgeInstructions::is_knowledge_instructions(content)
     {
         return false;
     }
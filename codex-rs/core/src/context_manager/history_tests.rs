@@ -1,4 +1,5 @@
 use super::*;
+use crate::tokenizer::HeuristicTokenizer;
 use crate::truncate;
 use crate::truncate::TruncationPolicy;
 use codex_git::GhostCommit;
@@ -31,7 +32,11 @@ fn create_history_with_items(items: Vec<ResponseItem>) -> ContextManager {
     let mut h = ContextManager::new();
     // Use a generous but fixed token budget; tests only rely on truncation
     // behavior, not on a specific model's token limit.
-    h.record_items(items.iter(), TruncationPolicy::Tokens(10_000));
+    h.record_items(
+        items.iter(),
+        TruncationPolicy::Tokens(10_000),
+        &HeuristicTokenizer,
+    );
     h
 }
 
@@ -99,12 +104,16 @@ fn filters_non_api_messages() {
         end_turn: None,
     };
     let reasoning = reasoning_msg("thinking...");
-    h.record_items([&system, &reasoning, &ResponseItem::Other], policy);
+    h.record_items(
+        [&system, &reasoning, &ResponseItem::Other],
+        policy,
+        &HeuristicTokenizer,
+    );
 
     // User and assistant should be retained.
     let u = user_msg("hi");
     let a = assistant_msg("hello");
-    h.record_items([&u, &a], policy);
+    h.record_items([&u, &a], policy, &HeuristicTokenizer);
 
     let items = h.raw_items();
     assert_eq!(
@@ -484,7 +493,7 @@ fn record_items_truncates_function_call_output_content() {
         },
     };
 
-    history.record_items([&item], policy);
+    history.record_items([&item], policy, &HeuristicTokenizer);
 
     assert_eq!(history.items.len(), 1);
     match &history.items[0] {
@@ -516,7 +525,7 @@ fn record_items_truncates_custom_tool_call_output_content() {
         output: long_output.clone(),
     };
 
-    history.record_items([&item], policy);
+    history.record_items([&item], policy, &HeuristicTokenizer);
 
     assert_eq!(history.items.len(), 1);
     match &history.items[0] {
@@ -549,7 +558,7 @@ fn record_items_respects_custom_token_limit() {
         },
     };
 
-    history.record_items([&item], policy);
+    history.record_items([&item], policy, &HeuristicTokenizer);
 
     let stored = match &history.items[0] {
         ResponseItem::FunctionCallOutput { output, .. } => output,
@@ -2,4 +2,6 @@ mod history;
 mod normalize;
 
 pub(crate) use history::ContextManager;
+pub(crate) use history::estimate_item_token_count;
+pub(crate) use history::estimate_items_token_count;
 pub(crate) use history::is_user_turn_boundary;
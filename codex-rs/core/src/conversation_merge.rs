@@ -0,0 +1,321 @@
+//! Folding a side conversation back into the current one.
+//!
+//! A user can start a quick side conversation to investigate something and
+//! then bring what it found into the main thread. [`merge_conversation`]
+//! reads the side conversation's rollout, keeps its final agent message, the
+//! diff of each turn that changed files and the artifacts its tools returned,
+//! and records them as a developer message that names the conversation they
+//! came from.
+
+use std::fmt::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::models::DeveloperInstructions;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::ConversationMergedEvent;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::InitialHistory;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::ToolArtifact;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::rollout::RolloutRecorder;
+use crate::truncate::TruncationPolicy;
+use crate::truncate::truncate_text;
+
+/// Budget for each merged turn diff, so one large change does not crowd out
+/// the rest of the context.
+const TURN_DIFF_MAX_TOKENS: usize = 2_000;
+
+/// What a conversation produced, read back from its rollout.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct ConversationOutcome {
+    pub cwd: Option<PathBuf>,
+    pub final_message: Option<String>,
+    /// Last diff of each turn that changed files, with its 1-based turn
+    /// number.
+    pub turn_diffs: Vec<(u32, String)>,
+    pub artifacts: Vec<ToolArtifact>,
+}
+
+impl ConversationOutcome {
+    fn is_empty(&self) -> bool {
+        self.final_message.is_none() && self.turn_diffs.is_empty() && self.artifacts.is_empty()
+    }
+}
+
+/// Merges the conversation recorded at `rollout_path` into `sess` and
+/// returns the event describing what was merged.
+pub(crate) async fn merge_conversation(
+    sess: &Session,
+    turn_context: &TurnContext,
+    rollout_path: &Path,
+) -> Result<ConversationMergedEvent, String> {
+    if sess.active_turn.lock().await.is_some() {
+        return Err("Cannot merge a conversation while a turn is in progress.".to_string());
+    }
+    let history = RolloutRecorder::get_rollout_history(rollout_path)
+        .await
+        .map_err(|err| format!("Failed to read {}: {err}", rollout_path.display()))?;
+    let InitialHistory::Resumed(resumed) = history else {
+        return Err(format!(
+            "{} does not contain a conversation.",
+            rollout_path.display()
+        ));
+    };
+    if resumed.conversation_id == sess.conversation_id {
+        return Err("A conversation cannot be merged into itself.".to_string());
+    }
+
+    let outcome = conversation_outcome(&resumed.history);
+    if outcome.is_empty() {
+        return Err(format!(
+            "Conversation {} has no final message, diffs or artifacts to merge.",
+            resumed.conversation_id
+        ));
+    }
+    let event = ConversationMergedEvent {
+        source_thread_id: resumed.conversation_id,
+        rollout_path: resumed.rollout_path,
+        cwd: outcome.cwd.clone(),
+        final_message: outcome.final_message.clone(),
+        diff_turns: outcome.turn_diffs.iter().map(|(turn, _)| *turn).collect(),
+        artifacts: outcome.artifacts.clone(),
+    };
+    sess.record_conversation_items(
+        turn_context,
+        &[merged_conversation_message(&event, &outcome)],
+    )
+    .await;
+    Ok(event)
+}
+
+/// What one turn of a conversation produced.
+#[derive(Default)]
+struct TurnOutcome {
+    message: Option<String>,
+    message_item_id: Option<String>,
+    diff: Option<String>,
+    artifacts: Vec<ToolArtifact>,
+}
+
+/// Reads the outcome of a conversation from its rollout items. Turns undone
+/// by a rollback are left out, along with their messages and artifacts.
+pub(crate) fn conversation_outcome(items: &[RolloutItem]) -> ConversationOutcome {
+    let mut cwd = None;
+    let mut turns: Vec<TurnOutcome> = Vec::new();
+    for item in items {
+        match item {
+            RolloutItem::SessionMeta(meta) if cwd.is_none() => {
+                cwd = Some(meta.meta.cwd.clone());
+            }
+            RolloutItem::EventMsg(EventMsg::UserMessage(_)) => turns.push(TurnOutcome::default()),
+            RolloutItem::EventMsg(EventMsg::ThreadRolledBack(event)) => {
+                let kept = turns
+                    .len()
+                    .saturating_sub(usize::try_from(event.num_turns).unwrap_or(usize::MAX));
+                turns.truncate(kept);
+            }
+            RolloutItem::EventMsg(EventMsg::TurnDiff(event)) => {
+                current_turn(&mut turns).diff = Some(event.unified_diff.clone());
+            }
+            RolloutItem::EventMsg(EventMsg::AgentMessage(event)) if !event.message.is_empty() => {
+                let turn = current_turn(&mut turns);
+                // An agent message with several content parts is recorded as
+                // one event per part.
+                match (&mut turn.message, &turn.message_item_id) {
+                    (Some(message), Some(item_id)) if event.item_id.as_ref() == Some(item_id) => {
+                        message.push_str(&event.message);
+                    }
+                    _ => turn.message = Some(event.message.clone()),
+                }
+                turn.message_item_id = event.item_id.clone();
+            }
+            RolloutItem::EventMsg(EventMsg::ToolArtifact(event)) => {
+                current_turn(&mut turns)
+                    .artifacts
+                    .push(event.artifact.clone());
+            }
+            _ => {}
+        }
+    }
+    ConversationOutcome {
+        cwd,
+        final_message: turns.iter().rev().find_map(|turn| turn.message.clone()),
+        turn_diffs: turns
+            .iter()
+            .zip(1u32..)
+            .filter_map(|(turn, number)| Some((number, turn.diff.clone()?)))
+            .collect(),
+        artifacts: turns.into_iter().flat_map(|turn| turn.artifacts).collect(),
+    }
+}
+
+/// The turn rollout items are currently attributed to; items recorded
+/// before the first user message count as a turn of their own.
+fn current_turn(turns: &mut Vec<TurnOutcome>) -> &mut TurnOutcome {
+    if turns.is_empty() {
+        turns.push(TurnOutcome::default());
+    }
+    let last = turns.len() - 1;
+    &mut turns[last]
+}
+
+fn merged_conversation_message(
+    event: &ConversationMergedEvent,
+    outcome: &ConversationOutcome,
+) -> ResponseItem {
+    let mut text = format!(
+        "The user merged the outcome of another conversation into this one. Treat it as the \
+         findings of a side investigation, and re-check details that may have changed \
+         since.\n\n<merged_conversation thread_id=\"{}\" rollout_path=\"{}\"",
+        event.source_thread_id,
+        event.rollout_path.display()
+    );
+    if let Some(cwd) = &outcome.cwd {
+        let _ = write!(text, " cwd=\"{}\"", cwd.display());
+    }
+    text.push_str(">\n");
+    if let Some(message) = &outcome.final_message {
+        let _ = writeln!(text, "<final_message>\n{message}\n</final_message>");
+    }
+    for (turn, diff) in &outcome.turn_diffs {
+        let diff = truncate_text(
+            diff.trim_end(),
+            TruncationPolicy::Tokens(TURN_DIFF_MAX_TOKENS),
+        );
+        let _ = writeln!(text, "<turn_diff turn=\"{turn}\">\n{diff}\n</turn_diff>");
+    }
+    for artifact in &outcome.artifacts {
+        let _ = writeln!(
+            text,
+            "<artifact name=\"{}\" mime_type=\"{}\" path=\"{}\" />",
+            artifact.name,
+            artifact.mime_type,
+            artifact.path.display()
+        );
+    }
+    text.push_str("</merged_conversation>");
+    DeveloperInstructions::new(text).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::protocol::AgentMessageEvent;
+    use codex_protocol::protocol::ThreadRolledBackEvent;
+    use codex_protocol::protocol::ToolArtifactEvent;
+    use codex_protocol::protocol::TurnDiffEvent;
+    use codex_protocol::protocol::UserMessageEvent;
+    use pretty_assertions::assert_eq;
+
+    fn user_message(message: &str) -> RolloutItem {
+        RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
+            message: message.to_string(),
+            images: None,
+            local_images: Vec::new(),
+            text_elements: Vec::new(),
+            item_id: None,
+        }))
+    }
+
+    fn agent_message(message: &str, item_id: &str) -> RolloutItem {
+        RolloutItem::EventMsg(EventMsg::AgentMessage(AgentMessageEvent {
+            message: message.to_string(),
+            item_id: Some(item_id.to_string()),
+        }))
+    }
+
+    fn turn_diff(diff: &str) -> RolloutItem {
+        RolloutItem::EventMsg(EventMsg::TurnDiff(TurnDiffEvent {
+            unified_diff: diff.to_string(),
+        }))
+    }
+
+    fn artifact(name: &str) -> ToolArtifact {
+        ToolArtifact {
+            id: name.to_string(),
+            name: name.to_string(),
+            mime_type: "text/plain".to_string(),
+            size_bytes: 1,
+            path: PathBuf::from(format!("/tmp/{name}")),
+        }
+    }
+
+    #[test]
+    fn keeps_last_diff_per_turn_and_drops_rolled_back_turns() {
+        let items = vec![
+            user_message("investigate"),
+            turn_diff("diff one"),
+            turn_diff("diff one and two"),
+            agent_message("Found it.", "msg-1"),
+            user_message("nothing to change"),
+            agent_message("Nothing changed.", "msg-2"),
+            user_message("try something"),
+            turn_diff("rolled back diff"),
+            RolloutItem::EventMsg(EventMsg::ThreadRolledBack(ThreadRolledBackEvent {
+                num_turns: 1,
+                edited_item_id: None,
+            })),
+            user_message("wrap up"),
+            turn_diff("final diff"),
+            RolloutItem::EventMsg(EventMsg::ToolArtifact(ToolArtifactEvent {
+                call_id: "call-1".to_string(),
+                artifact: artifact("report.txt"),
+            })),
+            agent_message("The bug is in the parser.", "msg-3"),
+            agent_message(" Fixed it.", "msg-3"),
+        ];
+
+        assert_eq!(
+            conversation_outcome(&items),
+            ConversationOutcome {
+                cwd: None,
+                final_message: Some("The bug is in the parser. Fixed it.".to_string()),
+                turn_diffs: vec![
+                    (1, "diff one and two".to_string()),
+                    (3, "final diff".to_string()),
+                ],
+                artifacts: vec![artifact("report.txt")],
+            }
+        );
+    }
+
+    #[test]
+    fn rollback_drops_the_final_message_and_artifacts_of_undone_turns() {
+        let items = vec![
+            user_message("investigate"),
+            agent_message("The bug is in the parser.", "msg-1"),
+            user_message("try a fix"),
+            turn_diff("attempted fix"),
+            RolloutItem::EventMsg(EventMsg::ToolArtifact(ToolArtifactEvent {
+                call_id: "call-1".to_string(),
+                artifact: artifact("attempt.log"),
+            })),
+            agent_message("Fixed it.", "msg-2"),
+            RolloutItem::EventMsg(EventMsg::ThreadRolledBack(ThreadRolledBackEvent {
+                num_turns: 1,
+                edited_item_id: None,
+            })),
+        ];
+
+        assert_eq!(
+            conversation_outcome(&items),
+            ConversationOutcome {
+                cwd: None,
+                final_message: Some("The bug is in the parser.".to_string()),
+                turn_diffs: Vec::new(),
+                artifacts: Vec::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn conversation_without_results_is_empty() {
+        let items = vec![user_message("hello")];
+
+        assert!(conversation_outcome(&items).is_empty());
+    }
+}
@@ -0,0 +1,766 @@
+//! Environment diagnostics behind `codex doctor` and the app-server
+//! `doctor/run` request.
+//!
+//! [`run_doctor`] checks what Codex needs to work in the current environment
+//! and returns a [`DoctorReport`] with one [`DoctorCheck`] per finding. Checks
+//! never fail themselves: problems are reported with a status and, where
+//! there is one, a suggested fix.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+use async_channel::unbounded;
+use chrono::DateTime;
+use chrono::Utc;
+use codex_app_server_protocol::AuthMode;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::McpStartupCompleteEvent;
+use codex_protocol::protocol::SandboxPolicy;
+use serde::Serialize;
+use tokio_util::sync::CancellationToken;
+use tracing::warn;
+
+use crate::AuthManager;
+use crate::CodexAuth;
+use crate::config::Config;
+use crate::config::ConfigBuilder;
+use crate::default_client::build_reqwest_client;
+use crate::exec_policy::check_execpolicy_for_warnings;
+use crate::git_info::get_git_repo_root;
+use crate::mcp::auth::compute_auth_statuses;
+use crate::mcp::effective_mcp_servers;
+use crate::mcp_connection_manager::McpConnectionManager;
+use crate::mcp_connection_manager::SandboxState;
+use crate::token_data::jwt_expiry;
+
+/// How long the provider may take to answer the reachability probe.
+const PROVIDER_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long the sandbox probe command may run.
+const SANDBOX_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Upper bound on MCP server startup, on top of the per-server
+/// `startup_timeout_sec`.
+const MCP_STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DoctorStatus {
+    /// The check does not apply, e.g. no MCP servers are configured.
+    Skipped,
+    Ok,
+    /// Codex works, but some feature may not.
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DoctorCheckKind {
+    Config,
+    CodexHome,
+    Auth,
+    Provider,
+    Sandbox,
+    McpServer,
+    Git,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DoctorCheck {
+    pub kind: DoctorCheckKind,
+    /// What was checked, e.g. the name of an MCP server.
+    pub subject: String,
+    pub status: DoctorStatus,
+    pub summary: String,
+    /// How to resolve a warning or error.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+impl DoctorCheck {
+    fn new(
+        kind: DoctorCheckKind,
+        subject: impl Into<String>,
+        status: DoctorStatus,
+        summary: impl Into<String>,
+    ) -> Self {
+        Self {
+            kind,
+            subject: subject.into(),
+            status,
+            summary: summary.into(),
+            fix: None,
+        }
+    }
+
+    fn with_fix(mut self, fix: impl Into<String>) -> Self {
+        self.fix = Some(fix.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DoctorReport {
+    pub checks: Vec<DoctorCheck>,
+}
+
+impl DoctorReport {
+    /// The most severe status of any check, `Ok` when all were skipped.
+    pub fn status(&self) -> DoctorStatus {
+        self.checks
+            .iter()
+            .map(|check| check.status)
+            .fold(DoctorStatus::Ok, Ord::max)
+    }
+}
+
+/// Runs every check against `config`. MCP servers are started, and stopped
+/// again, to see whether they come up.
+pub async fn run_doctor(config: &Config) -> DoctorReport {
+    let auth = load_fresh_auth(config).await;
+    let auth_mode = auth.as_ref().ok().flatten().map(|auth| auth.mode);
+    let (config_check, provider, sandbox, mcp_servers, git) = tokio::join!(
+        check_config(config),
+        check_provider(config, auth_mode),
+        check_sandbox(config),
+        check_mcp_servers(config),
+        check_git(&config.cwd),
+    );
+
+    let mut checks = vec![
+        config_check,
+        check_codex_home(&config.codex_home),
+        check_auth(config, auth, Utc::now()),
+        provider,
+        sandbox,
+    ];
+    checks.extend(mcp_servers);
+    checks.push(git);
+    DoctorReport { checks }
+}
+
+async fn check_config(config: &Config) -> DoctorCheck {
+    let subject = config.codex_home.join("config.toml").display().to_string();
+    if let Err(err) = ConfigBuilder::default()
+        .codex_home(config.codex_home.clone())
+        .fallback_cwd(Some(config.cwd.clone()))
+        .build()
+        .await
+    {
+        return DoctorCheck::new(
+            DoctorCheckKind::Config,
+            subject,
+            DoctorStatus::Error,
+            format!("The configuration could not be loaded: {err}"),
+        )
+        .with_fix(
+            "Fix the reported setting; until then Codex runs with the default configuration.",
+        );
+    }
+    match check_execpolicy_for_warnings(&config.features, &config.config_layer_stack).await {
+        Ok(None) => DoctorCheck::new(
+            DoctorCheckKind::Config,
+            subject,
+            DoctorStatus::Ok,
+            "The configuration and rules load without errors.",
+        ),
+        Ok(Some(err)) | Err(err) => DoctorCheck::new(
+            DoctorCheckKind::Config,
+            subject,
+            DoctorStatus::Warning,
+            format!("The rules could not be parsed: {err}"),
+        )
+        .with_fix("Fix the reported rule file; until then custom rules are not applied."),
+    }
+}
+
+fn check_codex_home(codex_home: &Path) -> DoctorCheck {
+    let subject = codex_home.display().to_string();
+    if !codex_home.exists() {
+        return DoctorCheck::new(
+            DoctorCheckKind::CodexHome,
+            subject,
+            DoctorStatus::Warning,
+            "The directory does not exist yet.",
+        )
+        .with_fix(format!("mkdir -p {}", codex_home.display()));
+    }
+    let probe = codex_home.join(".doctor-write-probe");
+    if let Err(err) = std::fs::write(&probe, b"").and_then(|()| std::fs::remove_file(&probe)) {
+        return DoctorCheck::new(
+            DoctorCheckKind::CodexHome,
+            subject,
+            DoctorStatus::Error,
+            format!("The directory is not writable: {err}"),
+        )
+        .with_fix("Make the directory writable by your user, or point CODEX_HOME elsewhere.");
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = |path: &Path| {
+            std::fs::metadata(path)
+                .ok()
+                .map(|metadata| metadata.permissions().mode() & 0o777)
+        };
+        let auth_file = codex_home.join("auth.json");
+        if let Some(mode) = mode(&auth_file)
+            && mode & 0o077 != 0
+        {
+            return DoctorCheck::new(
+                DoctorCheckKind::CodexHome,
+                subject,
+                DoctorStatus::Warning,
+                format!("auth.json is readable by other users (mode {mode:o})."),
+            )
+            .with_fix(format!("chmod 600 {}", auth_file.display()));
+        }
+        if let Some(mode) = mode(codex_home)
+            && mode & 0o022 != 0
+        {
+            return DoctorCheck::new(
+                DoctorCheckKind::CodexHome,
+                subject,
+                DoctorStatus::Warning,
+                format!("The directory is writable by other users (mode {mode:o})."),
+            )
+            .with_fix(format!("chmod go-w {}", codex_home.display()));
+        }
+    }
+
+    DoctorCheck::new(
+        DoctorCheckKind::CodexHome,
+        subject,
+        DoctorStatus::Ok,
+        "The directory is writable.",
+    )
+}
+
+/// Loads the stored credentials, refreshing a ChatGPT access token that has
+/// expired the way a request would, so [`check_auth`] only sees an expired
+/// token when it cannot be refreshed.
+async fn load_fresh_auth(config: &Config) -> std::io::Result<Option<CodexAuth>> {
+    let auth =
+        CodexAuth::from_auth_storage(&config.codex_home, config.cli_auth_credentials_store_mode)?;
+    let expired = auth
+        .as_ref()
+        .is_some_and(|auth| access_token_expiry(auth).is_some_and(|expiry| expiry <= Utc::now()));
+    if !expired {
+        return Ok(auth);
+    }
+    let auth_manager = AuthManager::new(
+        config.codex_home.clone(),
+        false,
+        config.cli_auth_credentials_store_mode,
+    );
+    if let Err(err) = auth_manager.refresh_token().await {
+        warn!("failed to refresh the expired access token: {err}");
+    }
+    CodexAuth::from_auth_storage(&config.codex_home, config.cli_auth_credentials_store_mode)
+}
+
+fn access_token_expiry(auth: &CodexAuth) -> Option<DateTime<Utc>> {
+    match auth.mode {
+        AuthMode::ApiKey => None,
+        AuthMode::ChatGPT => jwt_expiry(&auth.get_token_data().ok()?.access_token),
+    }
+}
+
+fn check_auth(
+    config: &Config,
+    auth: std::io::Result<Option<CodexAuth>>,
+    now: DateTime<Utc>,
+) -> DoctorCheck {
+    let provider = &config.model_provider;
+    let subject = config.model_provider_id.clone();
+    if !provider.requires_openai_auth {
+        return match provider.api_key() {
+            Ok(Some(_)) => DoctorCheck::new(
+                DoctorCheckKind::Auth,
+                subject,
+                DoctorStatus::Ok,
+                format!(
+                    "An API key is set in {}.",
+                    provider.env_key.as_deref().unwrap_or_default()
+                ),
+            ),
+            Ok(None) => DoctorCheck::new(
+                DoctorCheckKind::Auth,
+                subject,
+                DoctorStatus::Skipped,
+                "The provider does not need credentials.",
+            ),
+            Err(err) => DoctorCheck::new(
+                DoctorCheckKind::Auth,
+                subject,
+                DoctorStatus::Error,
+                err.to_string(),
+            )
+            .with_fix(format!(
+                "Set {} to an API key for the provider.",
+                provider
+                    .env_key
+                    .as_deref()
+                    .unwrap_or("the provider's `env_key`")
+            )),
+        };
+    }
+
+    let login_fix = "Run `codex login`.";
+    match auth {
+        Err(err) => DoctorCheck::new(
+            DoctorCheckKind::Auth,
+            subject,
+            DoctorStatus::Error,
+            format!("The stored credentials could not be read: {err}"),
+        )
+        .with_fix("Run `codex logout`, then `codex login`."),
+        Ok(None) => DoctorCheck::new(
+            DoctorCheckKind::Auth,
+            subject,
+            DoctorStatus::Error,
+            "Not logged in.",
+        )
+        .with_fix(login_fix),
+        Ok(Some(auth)) => match auth.mode {
+            AuthMode::ApiKey => DoctorCheck::new(
+                DoctorCheckKind::Auth,
+                subject,
+                DoctorStatus::Ok,
+                "Logged in with an API key.",
+            ),
+            AuthMode::ChatGPT => match auth.get_token_data() {
+                Ok(_) => match access_token_expiry(&auth).filter(|expiry| *expiry <= now) {
+                    Some(expiry) => DoctorCheck::new(
+                        DoctorCheckKind::Auth,
+                        subject,
+                        DoctorStatus::Error,
+                        format!(
+                            "The ChatGPT login expired at {} and could not be refreshed.",
+                            expiry.format("%Y-%m-%d %H:%M UTC")
+                        ),
+                    )
+                    .with_fix(login_fix),
+                    None => DoctorCheck::new(
+                        DoctorCheckKind::Auth,
+                        subject,
+                        DoctorStatus::Ok,
+                        match auth.get_account_email() {
+                            Some(email) => format!("Logged in with ChatGPT as {email}."),
+                            None => "Logged in with ChatGPT.".to_string(),
+                        },
+                    ),
+                },
+                Err(err) => DoctorCheck::new(
+                    DoctorCheckKind::Auth,
+                    subject,
+                    DoctorStatus::Error,
+                    format!("The ChatGPT login is incomplete: {err}"),
+                )
+                .with_fix(login_fix),
+            },
+        },
+    }
+}
+
+/// Any HTTP response counts as reachable; authentication is covered by
+/// [`check_auth`].
+async fn check_provider(config: &Config, auth_mode: Option<AuthMode>) -> DoctorCheck {
+    let base_url = config.model_provider.primary_base_url(auth_mode);
+    let subject = config.model_provider_id.clone();
+    let response = build_reqwest_client()
+        .get(&base_url)
+        .timeout(PROVIDER_PROBE_TIMEOUT)
+        .send()
+        .await;
+    match response {
+        Ok(response) => DoctorCheck::new(
+            DoctorCheckKind::Provider,
+            subject,
+            DoctorStatus::Ok,
+            format!("{base_url} answered with HTTP {}.", response.status()),
+        ),
+        Err(err) => DoctorCheck::new(
+            DoctorCheckKind::Provider,
+            subject,
+            DoctorStatus::Error,
+            format!("{base_url} could not be reached: {err}"),
+        )
+        .with_fix(format!(
+            "Check the network connection and proxy settings (HTTPS_PROXY), and the `base_url` of provider `{}`.",
+            config.model_provider_id
+        )),
+    }
+}
+
+async fn check_sandbox(config: &Config) -> DoctorCheck {
+    #[cfg(target_os = "windows")]
+    {
+        if crate::windows_sandbox::sandbox_setup_is_complete(&config.codex_home) {
+            DoctorCheck::new(
+                DoctorCheckKind::Sandbox,
+                "windows",
+                DoctorStatus::Ok,
+                "The Windows sandbox is set up.",
+            )
+        } else {
+            DoctorCheck::new(
+                DoctorCheckKind::Sandbox,
+                "windows",
+                DoctorStatus::Warning,
+                "The Windows sandbox is not set up; commands run without a sandbox.",
+            )
+            .with_fix("Start `codex` and accept the prompt to set up the sandbox.")
+        }
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        probe_sandbox(config).await
+    }
+}
+
+/// Runs `true` under the platform sandbox with a read-only policy.
+#[cfg(not(target_os = "windows"))]
+async fn probe_sandbox(config: &Config) -> DoctorCheck {
+    use crate::spawn::StdioPolicy;
+
+    let cwd = config.cwd.clone();
+    let policy = SandboxPolicy::new_read_only_policy();
+    let env: HashMap<String, String> = std::env::var("PATH")
+        .map(|path| HashMap::from([("PATH".to_string(), path)]))
+        .unwrap_or_default();
+    let command = vec!["true".to_string()];
+
+    #[cfg(target_os = "macos")]
+    let (subject, fix, child) = (
+        "seatbelt",
+        "Codex needs /usr/bin/sandbox-exec, which ships with macOS.",
+        crate::seatbelt::spawn_command_under_seatbelt(
+            command,
+            cwd.clone(),
+            &policy,
+            &cwd,
+            StdioPolicy::RedirectForShellTool,
+            env,
+        )
+        .await,
+    );
+    #[cfg(not(target_os = "macos"))]
+    let (subject, fix, child) = {
+        let fix = "Landlock needs Linux 5.13 or newer with Landlock enabled; in a container, \
+                   allow the landlock and seccomp syscalls.";
+        let Some(exe) = config.codex_linux_sandbox_exe.as_ref() else {
+            return DoctorCheck::new(
+                DoctorCheckKind::Sandbox,
+                "landlock",
+                DoctorStatus::Error,
+                "The codex-linux-sandbox helper was not found.",
+            )
+            .with_fix("Run Codex through the `codex` executable, which provides the helper.");
+        };
+        (
+            "landlock",
+            fix,
+            crate::landlock::spawn_command_under_linux_sandbox(
+                exe,
+                command,
+                cwd.clone(),
+                &policy,
+                &cwd,
+                StdioPolicy::RedirectForShellTool,
+                env,
+            )
+            .await,
+        )
+    };
+
+    let output = match child {
+        Ok(child) => tokio::time::timeout(SANDBOX_PROBE_TIMEOUT, child.wait_with_output())
+            .await
+            .map_err(|_| "the probe command timed out".to_string())
+            .and_then(|output| output.map_err(|err| err.to_string())),
+        Err(err) => Err(err.to_string()),
+    };
+    match output {
+        Ok(output) if output.status.success() => DoctorCheck::new(
+            DoctorCheckKind::Sandbox,
+            subject,
+            DoctorStatus::Ok,
+            "Commands can run in the sandbox.",
+        ),
+        Ok(output) => DoctorCheck::new(
+            DoctorCheckKind::Sandbox,
+            subject,
+            DoctorStatus::Error,
+            format!(
+                "A sandboxed command failed ({}): {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        )
+        .with_fix(fix),
+        Err(err) => DoctorCheck::new(
+            DoctorCheckKind::Sandbox,
+            subject,
+            DoctorStatus::Error,
+            format!("The sandbox could not be started: {err}"),
+        )
+        .with_fix(fix),
+    }
+}
+
+async fn check_mcp_servers(config: &Config) -> Vec<DoctorCheck> {
+    let auth_manager = AuthManager::shared(
+        config.codex_home.clone(),
+        false,
+        config.cli_auth_credentials_store_mode,
+    );
+    let auth = auth_manager.auth().await;
+    let mcp_servers: HashMap<_, _> = effective_mcp_servers(config, auth.as_ref())
+        .into_iter()
+        .filter(|(_, server)| server.enabled)
+        .collect();
+    if mcp_servers.is_empty() {
+        return vec![DoctorCheck::new(
+            DoctorCheckKind::McpServer,
+            "",
+            DoctorStatus::Skipped,
+            "No MCP servers are enabled.",
+        )];
+    }
+
+    let auth_statuses =
+        compute_auth_statuses(mcp_servers.iter(), config.mcp_oauth_credentials_store_mode).await;
+    let (tx_event, rx_event) = unbounded();
+    let cancel_token = CancellationToken::new();
+    let mut mcp_connection_manager = McpConnectionManager::default();
+    mcp_connection_manager
+        .initialize(
+            &mcp_servers,
+            config.mcp_oauth_credentials_store_mode,
+            auth_statuses,
+            tx_event,
+            cancel_token.clone(),
+            SandboxState {
+                sandbox_policy: SandboxPolicy::ReadOnly,
+                codex_linux_sandbox_exe: config.codex_linux_sandbox_exe.clone(),
+                sandbox_cwd: config.cwd.clone(),
+            },
+        )
+        .await;
+    let startup = tokio::time::timeout(MCP_STARTUP_TIMEOUT, async {
+        while let Ok(event) = rx_event.recv().await {
+            if let EventMsg::McpStartupComplete(summary) = event.msg {
+                return summary;
+            }
+        }
+        McpStartupCompleteEvent::default()
+    })
+    .await
+    .unwrap_or_default();
+    cancel_token.cancel();
+
+    let mut names: Vec<&String> = mcp_servers.keys().collect();
+    names.sort();
+    names
+        .into_iter()
+        .map(|name| mcp_server_check(name, &startup))
+        .collect()
+}
+
+fn mcp_server_check(name: &str, startup: &McpStartupCompleteEvent) -> DoctorCheck {
+    if startup.ready.iter().any(|ready| ready == name) {
+        return DoctorCheck::new(
+            DoctorCheckKind::McpServer,
+            name,
+            DoctorStatus::Ok,
+            "The server started.",
+        );
+    }
+    let fix = format!(
+        "Check `[mcp_servers.{name}]` in config.toml, or disable the server with `enabled = false`."
+    );
+    match startup.failed.iter().find(|failure| failure.server == name) {
+        Some(failure) => DoctorCheck::new(
+            DoctorCheckKind::McpServer,
+            name,
+            DoctorStatus::Error,
+            format!("The server failed to start: {}", failure.error),
+        )
+        .with_fix(fix),
+        None => DoctorCheck::new(
+            DoctorCheckKind::McpServer,
+            name,
+            DoctorStatus::Warning,
+            "The server did not finish starting in time.",
+        )
+        .with_fix(fix),
+    }
+}
+
+async fn check_git(cwd: &Path) -> DoctorCheck {
+    let version = tokio::process::Command::new("git")
+        .arg("--version")
+        .output()
+        .await
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+    let Some(version) = version else {
+        return DoctorCheck::new(
+            DoctorCheckKind::Git,
+            "git",
+            DoctorStatus::Warning,
+            "git was not found on PATH; undo, diffs and reviews are unavailable.",
+        )
+        .with_fix("Install git and make sure it is on PATH.");
+    };
+    let repo = match get_git_repo_root(cwd) {
+        Some(root) => format!("{} is a git repository", root.display()),
+        None => format!("{} is not in a git repository", cwd.display()),
+    };
+    DoctorCheck::new(
+        DoctorCheckKind::Git,
+        "git",
+        DoctorStatus::Ok,
+        format!("{version}; {repo}."),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::protocol::McpStartupFailure;
+    use pretty_assertions::assert_eq;
+
+    fn fake_jwt(payload: serde_json::Value) -> String {
+        use base64::Engine;
+        let b64 = |bytes: &[u8]| base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes);
+        format!(
+            "{}.{}.{}",
+            b64(br#"{"alg":"none","typ":"JWT"}"#),
+            b64(payload.to_string().as_bytes()),
+            b64(b"sig")
+        )
+    }
+
+    #[test]
+    fn expired_chatgpt_logins_are_errors() -> std::io::Result<()> {
+        let config = crate::config::test_config();
+        let codex_home = tempfile::tempdir()?;
+        let expiry = DateTime::from_timestamp(1_700_000_000, 0).expect("timestamp");
+        let auth_json = serde_json::json!({
+            "OPENAI_API_KEY": null,
+            "tokens": {
+                "id_token": fake_jwt(serde_json::json!({"email": "user@example.com"})),
+                "access_token": fake_jwt(serde_json::json!({"exp": expiry.timestamp()})),
+                "refresh_token": "refresh",
+            },
+            "last_refresh": "2023-11-14T00:00:00Z",
+        });
+        std::fs::write(codex_home.path().join("auth.json"), auth_json.to_string())?;
+        let load = || {
+            CodexAuth::from_auth_storage(
+                codex_home.path(),
+                crate::auth::AuthCredentialsStoreMode::File,
+            )
+        };
+
+        let subject = config.model_provider_id.clone();
+        assert_eq!(
+            check_auth(&config, load(), expiry - chrono::Duration::minutes(1)),
+            DoctorCheck::new(
+                DoctorCheckKind::Auth,
+                subject.clone(),
+                DoctorStatus::Ok,
+                "Logged in with ChatGPT as user@example.com.",
+            )
+        );
+        assert_eq!(
+            check_auth(&config, load(), expiry),
+            DoctorCheck::new(
+                DoctorCheckKind::Auth,
+                subject,
+                DoctorStatus::Error,
+                "The ChatGPT login expired at 2023-11-14 22:13 UTC and could not be refreshed.",
+            )
+            .with_fix("Run `codex login`.")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn report_status_is_the_most_severe_check() {
+        let check = |status| DoctorCheck::new(DoctorCheckKind::Git, "git", status, "");
+        let mut report = DoctorReport::default();
+        assert_eq!(report.status(), DoctorStatus::Ok);
+
+        report.checks = vec![check(DoctorStatus::Skipped)];
+        assert_eq!(report.status(), DoctorStatus::Ok);
+        report.checks.push(check(DoctorStatus::Error));
+        report.checks.push(check(DoctorStatus::Warning));
+        assert_eq!(report.status(), DoctorStatus::Error);
+    }
+
+    #[test]
+    fn mcp_server_checks_follow_startup_outcome() {
+        let startup = McpStartupCompleteEvent {
+            ready: vec!["docs".to_string()],
+            failed: vec![McpStartupFailure {
+                server: "db".to_string(),
+                error: "connection refused".to_string(),
+            }],
+            cancelled: Vec::new(),
+        };
+
+        let statuses: Vec<(DoctorStatus, String)> = ["docs", "db", "slow"]
+            .into_iter()
+            .map(|name| {
+                let check = mcp_server_check(name, &startup);
+                (check.status, check.summary)
+            })
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                (DoctorStatus::Ok, "The server started.".to_string()),
+                (
+                    DoctorStatus::Error,
+                    "The server failed to start: connection refused".to_string()
+                ),
+                (
+                    DoctorStatus::Warning,
+                    "The server did not finish starting in time.".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn codex_home_flags_credentials_readable_by_others() -> std::io::Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let codex_home = tempfile::tempdir()?;
+        std::fs::set_permissions(codex_home.path(), std::fs::Permissions::from_mode(0o700))?;
+        assert_eq!(check_codex_home(codex_home.path()).status, DoctorStatus::Ok);
+
+        let auth_file = codex_home.path().join("auth.json");
+        std::fs::write(&auth_file, "{}")?;
+        std::fs::set_permissions(&auth_file, std::fs::Permissions::from_mode(0o644))?;
+        let check = check_codex_home(codex_home.path());
+        assert_eq!(check.status, DoctorStatus::Warning);
+        assert_eq!(
+            check.fix,
+            Some(format!("chmod 600 {}", auth_file.display()))
+        );
+
+        let missing = codex_home.path().join("missing");
+        assert_eq!(check_codex_home(&missing).status, DoctorStatus::Warning);
+        Ok(())
+    }
+}
@@ -1,4 +1,6 @@
 use crate::codex::TurnContext;
+use crate::config::types::Units;
+use crate::locale::LocaleContext;
 use crate::shell::Shell;
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::ResponseItem;
@@ -13,11 +15,31 @@ use std::path::PathBuf;
 pub(crate) struct EnvironmentContext {
     pub cwd: Option<PathBuf>,
     pub shell: Shell,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub units: Option<Units>,
 }
 
 impl EnvironmentContext {
     pub fn new(cwd: Option<PathBuf>, shell: Shell) -> Self {
-        Self { cwd, shell }
+        Self {
+            cwd,
+            shell,
+            language: None,
+            timezone: None,
+            units: None,
+        }
+    }
+
+    /// Adds the user's language, timezone and units.
+    pub fn with_locale(mut self, locale: &LocaleContext) -> Self {
+        self.language = locale.language.clone();
+        self.timezone = locale.timezone.as_ref().map(|timezone| timezone.describe());
+        self.units = locale.units;
+        self
     }
 
     /// Compares two environment contexts, ignoring the shell. Useful when
@@ -28,9 +50,15 @@ impl EnvironmentContext {
             cwd,
             // should compare all fields except shell
             shell: _,
+            language,
+            timezone,
+            units,
         } = other;
 
         self.cwd == *cwd
+            && self.language == *language
+            && self.timezone == *timezone
+            && self.units == *units
     }
 
     pub fn diff(before: &TurnContext, after: &TurnContext, shell: &Shell) -> Self {
@@ -39,11 +67,26 @@ impl EnvironmentContext {
         } else {
             None
         };
-        EnvironmentContext::new(cwd, shell.clone())
+        let mut context = EnvironmentContext::new(cwd, shell.clone());
+        let before_locale =
+            Self::new(None, shell.clone()).with_locale(&before.client.config().locale);
+        let after_locale =
+            Self::new(None, shell.clone()).with_locale(&after.client.config().locale);
+        if before_locale.language != after_locale.language {
+            context.language = after_locale.language;
+        }
+        if before_locale.timezone != after_locale.timezone {
+            context.timezone = after_locale.timezone;
+        }
+        if before_locale.units != after_locale.units {
+            context.units = after_locale.units;
+        }
+        context
     }
 
     pub fn from_turn_context(turn_context: &TurnContext, shell: &Shell) -> Self {
         Self::new(Some(turn_context.cwd.clone()), shell.clone())
+            .with_locale(&turn_context.client.config().locale)
     }
 }
 
@@ -56,6 +99,9 @@ impl EnvironmentContext {
     /// <environment_context>
     ///   <cwd>...</cwd>
     ///   <shell>...</shell>
+    ///   <language>...</language>
+    ///   <timezone>...</timezone>
+    ///   <units>...</units>
     /// </environment_context>
     /// ```
     pub fn serialize_to_xml(self) -> String {
@@ -66,6 +112,19 @@ impl EnvironmentContext {
 
         let shell_name = self.shell.name();
         lines.push(format!("  <shell>{shell_name}</shell>"));
+        if let Some(language) = self.language {
+            lines.push(format!("  <language>{language}</language>"));
+        }
+        if let Some(timezone) = self.timezone {
+            lines.push(format!("  <timezone>{timezone}</timezone>"));
+        }
+        if let Some(units) = self.units {
+            let units = match units {
+                Units::Metric => "metric",
+                Units::Imperial => "imperial",
+            };
+            lines.push(format!("  <units>{units}</units>"));
+        }
         lines.push(ENVIRONMENT_CONTEXT_CLOSE_TAG.to_string());
         lines.join("\n")
     }
@@ -116,6 +175,25 @@ mod tests {
         assert_eq!(context.serialize_to_xml(), expected);
     }
 
+    #[test]
+    fn serialize_environment_context_with_locale() {
+        let locale = LocaleContext {
+            language: Some("de-DE".to_string()),
+            timezone: crate::locale::Timezone::parse("+02:00"),
+            units: Some(Units::Metric),
+        };
+        let context = EnvironmentContext::new(None, fake_shell()).with_locale(&locale);
+
+        let expected = r#"<environment_context>
+  <shell>bash</shell>
+  <language>de-DE</language>
+  <timezone>UTC+02:00</timezone>
+  <units>metric</units>
+</environment_context>"#;
+
+        assert_eq!(context.serialize_to_xml(), expected);
+    }
+
     #[test]
     fn serialize_read_only_environment_context() {
         let context = EnvironmentContext::new(None, fake_shell());
@@ -0,0 +1,410 @@
+//! Normalized snapshots of the environment shell commands run with.
+//!
+//! A snapshot is recorded in the rollout's session metadata when a session is
+//! created. When a session is resumed or replayed, [`diff_environment`] lists
+//! what drifted since, so that a reproduction that behaves differently can be
+//! traced back to a changed variable, `PATH` or tool version.
+//!
+//! Values can be secrets, so only the variables in [`ENV_VALUE_ALLOWLIST`] are
+//! recorded verbatim; every other value is replaced with an HMAC-SHA256 under
+//! a random key kept in `CODEX_HOME`, which still shows whether it changed but
+//! cannot be reversed by guessing low-entropy values offline.
+//!
+//! Tool versions are only probed with the `environment_tool_versions`
+//! feature, since that runs each tool once per new session.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::protocol::EnvironmentChange;
+use codex_protocol::protocol::EnvironmentSnapshot;
+use futures::future::join_all;
+use hmac::Hmac;
+use hmac::Mac;
+use sha2::Sha256;
+use tokio::process::Command;
+use tokio::time::Duration;
+use tokio::time::timeout;
+use tracing::warn;
+
+use crate::config::Config;
+use crate::config::types::ShellEnvironmentPolicy;
+use crate::exec_env::create_env;
+use crate::features::Feature;
+use crate::provenance::create_signing_key;
+use crate::provenance::read_signing_key;
+use crate::rollout::list::read_session_meta_line;
+
+/// Variables whose values are recorded verbatim.
+pub const ENV_VALUE_ALLOWLIST: &[&str] = &[
+    "CI",
+    "LANG",
+    "LANGUAGE",
+    "LC_ALL",
+    "LC_CTYPE",
+    "NODE_ENV",
+    "PYTHONHASHSEED",
+    "RUSTUP_TOOLCHAIN",
+    "SHELL",
+    "TERM",
+    "TZ",
+];
+
+/// Tools whose versions are recorded when they are on `PATH` and the
+/// `environment_tool_versions` feature is enabled.
+pub const VERSIONED_TOOLS: &[&str] = &["git", "python3", "node", "rustc", "cargo", "go"];
+
+const HASH_KEY_FILE: &str = "environment.key";
+
+const TOOL_VERSION_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// What a new session needs to record its environment snapshot.
+#[derive(Debug, Clone)]
+pub(crate) struct EnvironmentSnapshotSettings {
+    pub(crate) codex_home: PathBuf,
+    pub(crate) shell_environment_policy: ShellEnvironmentPolicy,
+    pub(crate) probe_tool_versions: bool,
+}
+
+impl EnvironmentSnapshotSettings {
+    pub(crate) fn from_config(config: &Config) -> Self {
+        Self {
+            codex_home: config.codex_home.clone(),
+            shell_environment_policy: config.shell_environment_policy.clone(),
+            probe_tool_versions: config.features.enabled(Feature::EnvironmentToolVersions),
+        }
+    }
+
+    /// Snapshot for a session started in `cwd`, or `None` when the hash key
+    /// cannot be loaded.
+    pub(crate) async fn capture(&self, cwd: &Path) -> Option<EnvironmentSnapshot> {
+        let hash_key = match load_hash_key(&self.codex_home).await {
+            Ok(hash_key) => hash_key,
+            Err(err) => {
+                warn!("not recording an environment snapshot: {err}");
+                return None;
+            }
+        };
+        let tools = if self.probe_tool_versions {
+            VERSIONED_TOOLS
+        } else {
+            &[]
+        };
+        Some(
+            capture_environment_snapshot(&self.shell_environment_policy, cwd, &hash_key, tools)
+                .await,
+        )
+    }
+}
+
+/// Reads the key values are hashed with, creating it under `codex_home` on
+/// first use.
+pub async fn load_hash_key(codex_home: &Path) -> std::io::Result<Vec<u8>> {
+    let path = codex_home.join(HASH_KEY_FILE);
+    match read_signing_key(&path).await {
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => create_signing_key(&path).await,
+        result => result,
+    }
+}
+
+/// Snapshot the environment that shell commands get under `policy`, hashing
+/// values with `hash_key`. Only the versions of `tools` are probed, from `cwd`
+/// so directory-scoped toolchains resolve as they would for the agent.
+pub async fn capture_environment_snapshot(
+    policy: &ShellEnvironmentPolicy,
+    cwd: &Path,
+    hash_key: &[u8],
+    tools: &[&str],
+) -> EnvironmentSnapshot {
+    let env = create_env(policy);
+    let versions = join_all(tools.iter().map(|tool| tool_version(tool, &env, cwd))).await;
+    let tools = tools
+        .iter()
+        .zip(versions)
+        .filter_map(|(tool, version)| Some((tool.to_string(), version?)))
+        .collect();
+    normalize_environment(env, tools, hash_key)
+}
+
+fn normalize_environment(
+    vars: impl IntoIterator<Item = (String, String)>,
+    tools: BTreeMap<String, String>,
+    hash_key: &[u8],
+) -> EnvironmentSnapshot {
+    let mut env = BTreeMap::new();
+    let mut path = Vec::new();
+    for (name, value) in vars {
+        if name.eq_ignore_ascii_case("PATH") {
+            path = std::env::split_paths(&value)
+                .map(|entry| entry.to_string_lossy().into_owned())
+                .collect();
+        } else if ENV_VALUE_ALLOWLIST.contains(&name.as_str()) {
+            env.insert(name, value);
+        } else {
+            env.insert(name, hash_value(hash_key, &value));
+        }
+    }
+    EnvironmentSnapshot {
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        env,
+        path,
+        tools,
+    }
+}
+
+fn hash_value(hash_key: &[u8], value: &str) -> String {
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(hash_key) else {
+        return String::new();
+    };
+    mac.update(value.as_bytes());
+    format!("hmac-sha256:{:x}", mac.finalize().into_bytes())
+}
+
+async fn tool_version(tool: &str, env: &HashMap<String, String>, cwd: &Path) -> Option<String> {
+    let output = timeout(
+        TOOL_VERSION_TIMEOUT,
+        Command::new(tool)
+            .arg("--version")
+            .env_clear()
+            .envs(env)
+            .current_dir(cwd)
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .ok()?
+    .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    // Some tools (older Pythons) print their version on stderr.
+    [output.stdout, output.stderr].iter().find_map(|bytes| {
+        String::from_utf8_lossy(bytes)
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .map(str::to_string)
+    })
+}
+
+/// Lists what differs between `recorded` and `current`, with variables and
+/// tools in name order.
+pub fn diff_environment(
+    recorded: &EnvironmentSnapshot,
+    current: &EnvironmentSnapshot,
+) -> Vec<EnvironmentChange> {
+    let mut changes = Vec::new();
+
+    let recorded_platform = format!("{}-{}", recorded.os, recorded.arch);
+    let current_platform = format!("{}-{}", current.os, current.arch);
+    if recorded_platform != current_platform {
+        changes.push(EnvironmentChange::Platform {
+            recorded: recorded_platform,
+            current: current_platform,
+        });
+    }
+
+    for (name, recorded_value, current_value) in diff_maps(&recorded.env, &current.env) {
+        changes.push(EnvironmentChange::EnvVar {
+            name,
+            recorded: recorded_value,
+            current: current_value,
+        });
+    }
+
+    if recorded.path != current.path {
+        changes.push(EnvironmentChange::Path {
+            recorded: recorded.path.clone(),
+            current: current.path.clone(),
+        });
+    }
+
+    for (name, recorded_version, current_version) in diff_maps(&recorded.tools, &current.tools) {
+        changes.push(EnvironmentChange::Tool {
+            name,
+            recorded: recorded_version,
+            current: current_version,
+        });
+    }
+
+    changes
+}
+
+fn diff_maps(
+    recorded: &BTreeMap<String, String>,
+    current: &BTreeMap<String, String>,
+) -> Vec<(String, Option<String>, Option<String>)> {
+    let names: BTreeSet<&String> = recorded.keys().chain(current.keys()).collect();
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let recorded_value = recorded.get(name);
+            let current_value = current.get(name);
+            (recorded_value != current_value).then(|| {
+                (
+                    name.clone(),
+                    recorded_value.cloned(),
+                    current_value.cloned(),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Diffs the snapshot recorded in the rollout at `rollout_path` against the
+/// environment shell commands would get now. Only the tools the snapshot
+/// recorded are probed again, from the session's recorded working directory,
+/// or from `fallback_cwd` when that no longer exists. Returns `None` when the
+/// rollout predates environment snapshots.
+pub async fn diff_rollout_environment(
+    rollout_path: &Path,
+    codex_home: &Path,
+    policy: &ShellEnvironmentPolicy,
+    fallback_cwd: &Path,
+) -> std::io::Result<Option<Vec<EnvironmentChange>>> {
+    let meta_line = read_session_meta_line(rollout_path).await?;
+    let Some(recorded) = meta_line.environment else {
+        return Ok(None);
+    };
+    let cwd = if meta_line.meta.cwd.is_dir() {
+        meta_line.meta.cwd.as_path()
+    } else {
+        fallback_cwd
+    };
+    let hash_key = load_hash_key(codex_home).await?;
+    let tools: Vec<&str> = recorded.tools.keys().map(String::as_str).collect();
+    let current = capture_environment_snapshot(policy, cwd, &hash_key, &tools).await;
+    Ok(Some(diff_environment(&recorded, &current)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const KEY: &[u8] = b"test-key";
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn hashes_values_outside_the_allowlist_and_splits_path() {
+        let path = std::env::join_paths(["/usr/local/bin", "/usr/bin"])
+            .expect("join paths")
+            .to_string_lossy()
+            .into_owned();
+        let snapshot = normalize_environment(
+            vars(&[
+                ("TERM", "xterm-256color"),
+                ("API_TOKEN", "secret"),
+                ("PATH", path.as_str()),
+            ]),
+            BTreeMap::new(),
+            KEY,
+        );
+
+        assert_eq!(
+            snapshot.env,
+            BTreeMap::from([
+                ("API_TOKEN".to_string(), hash_value(KEY, "secret")),
+                ("TERM".to_string(), "xterm-256color".to_string()),
+            ])
+        );
+        assert!(!snapshot.env["API_TOKEN"].contains("secret"));
+        assert_eq!(
+            snapshot.path,
+            vec!["/usr/local/bin".to_string(), "/usr/bin".to_string()]
+        );
+    }
+
+    #[test]
+    fn diff_reports_changed_added_and_removed_entries() {
+        let recorded = normalize_environment(
+            vars(&[("TERM", "xterm"), ("HOME", "/home/a"), ("PATH", "/bin")]),
+            BTreeMap::from([("git".to_string(), "git version 2.40.0".to_string())]),
+            KEY,
+        );
+        let current = normalize_environment(
+            vars(&[
+                ("TERM", "dumb"),
+                ("HOME", "/home/a"),
+                ("CI", "true"),
+                ("PATH", "/bin"),
+            ]),
+            BTreeMap::from([("node".to_string(), "v20.0.0".to_string())]),
+            KEY,
+        );
+
+        assert_eq!(
+            diff_environment(&recorded, &current),
+            vec![
+                EnvironmentChange::EnvVar {
+                    name: "CI".to_string(),
+                    recorded: None,
+                    current: Some("true".to_string()),
+                },
+                EnvironmentChange::EnvVar {
+                    name: "TERM".to_string(),
+                    recorded: Some("xterm".to_string()),
+                    current: Some("dumb".to_string()),
+                },
+                EnvironmentChange::Tool {
+                    name: "git".to_string(),
+                    recorded: Some("git version 2.40.0".to_string()),
+                    current: None,
+                },
+                EnvironmentChange::Tool {
+                    name: "node".to_string(),
+                    recorded: None,
+                    current: Some("v20.0.0".to_string()),
+                },
+            ]
+        );
+        assert_eq!(diff_environment(&recorded, &recorded), Vec::new());
+    }
+
+    #[test]
+    fn hashes_depend_on_the_install_key() {
+        let unsalted = format!(
+            "{:x}",
+            <Sha256 as sha2::Digest>::digest("secret".as_bytes())
+        );
+
+        assert_eq!(hash_value(KEY, "secret"), hash_value(KEY, "secret"));
+        assert_ne!(
+            hash_value(KEY, "secret"),
+            hash_value(b"other-key", "secret")
+        );
+        assert!(!hash_value(KEY, "secret").contains(&unsalted));
+    }
+
+    #[tokio::test]
+    async fn hash_key_is_created_once_per_codex_home() -> std::io::Result<()> {
+        let codex_home = tempfile::TempDir::new()?;
+
+        let key = load_hash_key(codex_home.path()).await?;
+
+        assert_eq!(load_hash_key(codex_home.path()).await?, key);
+        assert_ne!(load_hash_key(tempfile::TempDir::new()?.path()).await?, key);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn tools_are_only_probed_when_requested() -> std::io::Result<()> {
+        let cwd = tempfile::TempDir::new()?;
+        let policy = ShellEnvironmentPolicy::default();
+
+        let snapshot = capture_environment_snapshot(&policy, cwd.path(), KEY, &[]).await;
+
+        assert_eq!(snapshot.tools, BTreeMap::new());
+        Ok(())
+    }
+}
@@ -10,6 +10,8 @@ use chrono::Utc;
 use codex_async_utils::CancelErr;
 use codex_protocol::ThreadId;
 use codex_protocol::protocol::CodexErrorInfo;
+use codex_protocol::protocol::ContextOverflowContributor;
+use codex_protocol::protocol::ContextOverflowRemedy;
 use codex_protocol::protocol::ErrorEvent;
 use codex_protocol::protocol::RateLimitSnapshot;
 use reqwest::StatusCode;
@@ -75,6 +77,11 @@ pub enum CodexErr {
     )]
     ContextWindowExceeded,
 
+    /// Returned before sending a request whose estimated size exceeds the
+    /// model's context window.
+    #[error("{0}")]
+    ContextOverflow(ContextOverflowError),
+
     #[error("no thread with id: {0}")]
     ThreadNotFound(ThreadId),
 
@@ -127,6 +134,11 @@ pub enum CodexErr {
     )]
     UsageNotIncluded,
 
+    /// The provider's advertised rate limit will not reset soon enough to
+    /// wait for it.
+    #[error("{0}")]
+    ProviderRateLimited(String),
+
     #[error("We're currently experiencing high demand, which may cause temporary errors.")]
     InternalServerError,
 
@@ -193,6 +205,7 @@ impl CodexErr {
             | CodexErr::Fatal(_)
             | CodexErr::UsageNotIncluded
             | CodexErr::QuotaExceeded
+            | CodexErr::ProviderRateLimited(_)
             | CodexErr::InvalidImageRequest()
             | CodexErr::InvalidRequest(_)
             | CodexErr::RefreshTokenFailed(_)
@@ -201,6 +214,7 @@ impl CodexErr {
             | CodexErr::LandlockSandboxExecutableNotProvided
             | CodexErr::RetryLimit(_)
             | CodexErr::ContextWindowExceeded
+            | CodexErr::ContextOverflow(_)
             | CodexErr::ThreadNotFound(_)
             | CodexErr::AgentLimitReached { .. }
             | CodexErr::Spawn
@@ -351,6 +365,53 @@ impl std::fmt::Display for RetryLimitReachedError {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextOverflowError {
+    pub estimated_tokens: i64,
+    pub context_window: i64,
+    /// Largest parts of the prompt, biggest first.
+    pub largest_contributors: Vec<ContextOverflowContributor>,
+    pub remedies: Vec<ContextOverflowRemedy>,
+}
+
+impl std::fmt::Display for ContextOverflowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "This request needs about {} tokens, but the model's context window holds {}.",
+            self.estimated_tokens, self.context_window
+        )?;
+        if !self.largest_contributors.is_empty() {
+            let contributors = self
+                .largest_contributors
+                .iter()
+                .map(|contributor| {
+                    let label = contributor
+                        .path
+                        .as_deref()
+                        .unwrap_or(contributor.category.label());
+                    format!("{label} (~{} tokens)", contributor.estimated_tokens)
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            write!(f, " Largest contributors: {contributors}.")?;
+        }
+        for remedy in &self.remedies {
+            let hint = match remedy {
+                ContextOverflowRemedy::Compact => "Run /compact to summarize earlier history.",
+                ContextOverflowRemedy::DropToolOutputs => {
+                    "Start a new thread to leave the large tool outputs behind."
+                }
+                ContextOverflowRemedy::SwitchModel => {
+                    "Switch to a model with a larger context window with /model."
+                }
+            };
+            write!(f, " {hint}")?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub struct UsageLimitReachedError {
     pub(crate) plan_type: Option<PlanType>,
@@ -485,9 +546,16 @@ impl CodexErr {
     pub fn to_codex_protocol_error(&self) -> CodexErrorInfo {
         match self {
             CodexErr::ContextWindowExceeded => CodexErrorInfo::ContextWindowExceeded,
+            CodexErr::ContextOverflow(err) => CodexErrorInfo::ContextOverflow {
+                estimated_tokens: err.estimated_tokens,
+                context_window: err.context_window,
+                largest_contributors: err.largest_contributors.clone(),
+                remedies: err.remedies.clone(),
+            },
             CodexErr::UsageLimitReached(_)
             | CodexErr::QuotaExceeded
-            | CodexErr::UsageNotIncluded => CodexErrorInfo::UsageLimitExceeded,
+            | CodexErr::UsageNotIncluded
+            | CodexErr::ProviderRateLimited(_) => CodexErrorInfo::UsageLimitExceeded,
             CodexErr::RetryLimit(_) => CodexErrorInfo::ResponseTooManyFailedAttempts {
                 http_status_code: self.http_status_code_value(),
             },
@@ -518,6 +586,7 @@ impl CodexErr {
         ErrorEvent {
             message,
             codex_error_info: Some(self.to_codex_protocol_error()),
+            fingerprint: None,
         }
     }
 
@@ -577,6 +646,7 @@ mod tests {
     use chrono::Duration as ChronoDuration;
     use chrono::TimeZone;
     use chrono::Utc;
+    use codex_protocol::protocol::ContextItemCategory;
     use codex_protocol::protocol::RateLimitWindow;
     use pretty_assertions::assert_eq;
     use reqwest::Response;
@@ -618,6 +688,34 @@ mod tests {
         })
     }
 
+    #[test]
+    fn context_overflow_error_lists_contributors_and_remedies() {
+        let err = ContextOverflowError {
+            estimated_tokens: 300_000,
+            context_window: 272_000,
+            largest_contributors: vec![
+                ContextOverflowContributor {
+                    category: ContextItemCategory::ToolOutputs,
+                    path: None,
+                    estimated_tokens: 200_000,
+                },
+                ContextOverflowContributor {
+                    category: ContextItemCategory::ToolOutputs,
+                    path: Some("logs/build.log".to_string()),
+                    estimated_tokens: 150_000,
+                },
+            ],
+            remedies: vec![
+                ContextOverflowRemedy::Compact,
+                ContextOverflowRemedy::SwitchModel,
+            ],
+        };
+        assert_eq!(
+            err.to_string(),
+            "This request needs about 300000 tokens, but the model's context window holds 272000. Largest contributors: tool outputs (~200000 tokens), logs/build.log (~150000 tokens). Run /compact to summarize earlier history. Switch to a model with a larger context window with /model."
+        );
+    }
+
     #[test]
     fn usage_limit_reached_error_formats_plus_plan() {
         let err = UsageLimitReachedError {
@@ -0,0 +1,346 @@
+//! Fingerprints for error events.
+//!
+//! Every [`ErrorEvent`] a session emits gets an [`ErrorFingerprint`]: a hash
+//! over its error code, the part of Codex that failed and its message with
+//! numbers, ids and paths masked, so the same failure hashes the same way
+//! across sessions and machines. A per-day counter of fingerprints is kept in
+//! `$CODEX_HOME/error_fingerprints.json` so the event can say how often the
+//! failure happened recently, and the latest fingerprint is attached to
+//! feedback uploads as tags. Incognito sessions read the counter but do not
+//! add to it.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use codex_protocol::protocol::CodexErrorInfo;
+use codex_protocol::protocol::ErrorEvent;
+use codex_protocol::protocol::ErrorFingerprint;
+use regex_lite::Regex;
+use serde::Deserialize;
+use serde::Serialize;
+use sha2::Digest;
+use sha2::Sha256;
+use time::Date;
+use time::Duration;
+use time::OffsetDateTime;
+use time::macros::format_description;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::feedback_tags;
+
+const FINGERPRINTS_FILE: &str = "error_fingerprints.json";
+
+/// Days of history kept per fingerprint.
+const RETAINED_DAYS: i64 = 30;
+
+/// Window `occurrences_last_7_days` counts over.
+const RECENT_DAYS: i64 = 7;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct FingerprintCounts {
+    /// Occurrences per fingerprint hash, per day (`YYYY-MM-DD`).
+    #[serde(default)]
+    fingerprints: BTreeMap<String, FingerprintHistory>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct FingerprintHistory {
+    code: String,
+    component: String,
+    #[serde(default)]
+    days: BTreeMap<String, u64>,
+}
+
+/// Computes fingerprints and counts them under `codex_home`.
+#[derive(Debug)]
+pub(crate) struct ErrorFingerprints {
+    codex_home: PathBuf,
+    /// False in incognito sessions, which leave the counts file untouched.
+    persist: bool,
+    // Serializes read-modify-write cycles of the counts file in this process.
+    lock: Mutex<()>,
+}
+
+impl ErrorFingerprints {
+    pub(crate) fn new(codex_home: PathBuf, persist: bool) -> Self {
+        Self {
+            codex_home,
+            persist,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Attaches a fingerprint to `error` (unless it already has one) and
+    /// counts the occurrence.
+    pub(crate) async fn fingerprint(&self, error: &mut ErrorEvent) {
+        if error.fingerprint.is_some() {
+            return;
+        }
+        let code = error_code(error.codex_error_info.as_ref());
+        let component = component(error.codex_error_info.as_ref());
+        let hash = fingerprint_hash(&code, component, &error.message);
+        let occurrences_last_7_days = match self.count(&hash, &code, component).await {
+            Ok(count) => count,
+            Err(err) => {
+                tracing::warn!("failed to count error fingerprint {hash}: {err}");
+                1
+            }
+        };
+        feedback_tags!(
+            last_error_fingerprint = hash.as_str(),
+            last_error_component = component,
+            last_error_occurrences_last_7_days = occurrences_last_7_days
+        );
+        error.fingerprint = Some(ErrorFingerprint {
+            hash,
+            component: component.to_string(),
+            occurrences_last_7_days,
+        });
+    }
+
+    async fn count(&self, hash: &str, code: &str, component: &str) -> io::Result<u64> {
+        let _guard = self.lock.lock().await;
+        let today = OffsetDateTime::now_utc().date();
+        let mut counts = read_counts(&self.codex_home).await?;
+        let history = counts
+            .fingerprints
+            .entry(hash.to_string())
+            .or_insert_with(|| FingerprintHistory {
+                code: code.to_string(),
+                component: component.to_string(),
+                days: BTreeMap::new(),
+            });
+        *history.days.entry(format_day(today)).or_default() += 1;
+        let recent = occurrences_since(history, today - Duration::days(RECENT_DAYS - 1));
+        if !self.persist {
+            return Ok(recent);
+        }
+
+        let oldest_retained = format_day(today - Duration::days(RETAINED_DAYS - 1));
+        counts.fingerprints.retain(|_, history| {
+            history.days.retain(|day, _| *day >= oldest_retained);
+            !history.days.is_empty()
+        });
+        write_counts(&self.codex_home, &counts).await?;
+        Ok(recent)
+    }
+}
+
+fn occurrences_since(history: &FingerprintHistory, since: Date) -> u64 {
+    let since = format_day(since);
+    history
+        .days
+        .iter()
+        .filter(|(day, _)| **day >= since)
+        .map(|(_, count)| count)
+        .sum()
+}
+
+/// Hex digest identifying a failure: the first 16 hex digits of the SHA-256
+/// over its code, component and normalized message.
+fn fingerprint_hash(code: &str, component: &str, message: &str) -> String {
+    let digest = Sha256::digest(format!(
+        "{code}\n{component}\n{}",
+        normalize_message(message)
+    ));
+    format!("{digest:x}")[..16].to_string()
+}
+
+/// `info`'s variant name, e.g. `http_connection_failed`, or `unknown`.
+fn error_code(info: Option<&CodexErrorInfo>) -> String {
+    let value = info.and_then(|info| serde_json::to_value(info).ok());
+    match value {
+        Some(serde_json::Value::String(code)) => code,
+        Some(serde_json::Value::Object(fields)) => fields
+            .keys()
+            .next()
+            .cloned()
+            .unwrap_or_else(|| "unknown".to_string()),
+        _ => "unknown".to_string(),
+    }
+}
+
+fn component(info: Option<&CodexErrorInfo>) -> &'static str {
+    match info {
+        Some(
+            CodexErrorInfo::ContextWindowExceeded
+            | CodexErrorInfo::ContextOverflow { .. }
+            | CodexErrorInfo::UsageLimitExceeded
+            | CodexErrorInfo::HttpConnectionFailed { .. }
+            | CodexErrorInfo::ResponseStreamConnectionFailed { .. }
+            | CodexErrorInfo::InternalServerError
+            | CodexErrorInfo::Unauthorized
+            | CodexErrorInfo::BadRequest
+            | CodexErrorInfo::ResponseStreamDisconnected { .. }
+            | CodexErrorInfo::ResponseTooManyFailedAttempts { .. },
+        ) => "model_client",
+        Some(CodexErrorInfo::SandboxError) => "sandbox",
+        Some(CodexErrorInfo::ThreadRollbackFailed | CodexErrorInfo::ConversationMergeFailed) => {
+            "thread_history"
+        }
+        Some(CodexErrorInfo::Other) | None => "session",
+    }
+}
+
+/// `message` with the parts that vary between occurrences of the same
+/// failure masked: paths, hex ids (request ids, UUIDs) and numbers.
+fn normalize_message(message: &str) -> String {
+    let (path, id, number) = normalization_regexes();
+    let masked = path.replace_all(message, "<path>");
+    let masked = id.replace_all(&masked, "<id>");
+    let masked = number.replace_all(&masked, "#");
+    masked.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn normalization_regexes() -> &'static (Regex, Regex, Regex) {
+    static REGEXES: OnceLock<(Regex, Regex, Regex)> = OnceLock::new();
+    REGEXES.get_or_init(|| {
+        #[expect(clippy::expect_used)]
+        let compile = |pattern: &str| Regex::new(pattern).expect("valid normalization pattern");
+        (
+            compile(r#"(?:[A-Za-z]:\\|~?\B/)[^\s'"`]+"#),
+            compile(r"(?i)\b[0-9a-f]{8,}(?:-[0-9a-f]{4,})*\b"),
+            compile(r"\d+"),
+        )
+    })
+}
+
+fn format_day(day: Date) -> String {
+    day.format(format_description!("[year]-[month]-[day]"))
+        .unwrap_or_default()
+}
+
+async fn read_counts(codex_home: &Path) -> io::Result<FingerprintCounts> {
+    match tokio::fs::read_to_string(codex_home.join(FINGERPRINTS_FILE)).await {
+        Ok(contents) => serde_json::from_str(&contents).map_err(io::Error::other),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(FingerprintCounts::default()),
+        Err(err) => Err(err),
+    }
+}
+
+async fn write_counts(codex_home: &Path, counts: &FingerprintCounts) -> io::Result<()> {
+    let path = codex_home.join(FINGERPRINTS_FILE);
+    // Unique per write, so concurrent sessions never share a temp file.
+    let tmp = path.with_extension(format!("json.{}.tmp", Uuid::new_v4()));
+    let contents = serde_json::to_string_pretty(counts).map_err(io::Error::other)?;
+    tokio::fs::create_dir_all(codex_home).await?;
+    tokio::fs::write(&tmp, contents).await?;
+    tokio::fs::rename(&tmp, &path).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[test]
+    fn volatile_parts_do_not_change_the_fingerprint() {
+        let first = fingerprint_hash(
+            "http_connection_failed",
+            "model_client",
+            "stream error 502 for request 7f3a9c12-4b5d-4e6f-8a9b-0c1d2e3f4a5b reading /Users/ana/repo/a.rs",
+        );
+        let second = fingerprint_hash(
+            "http_connection_failed",
+            "model_client",
+            "stream error 503 for request 0a1b2c3d-4e5f-4a6b-8c7d-9e0f1a2b3c4d reading /home/bo/src/b.rs",
+        );
+        let other_component =
+            fingerprint_hash("http_connection_failed", "sandbox", "stream error 502");
+
+        assert_eq!(first, second);
+        assert_ne!(first, other_component);
+    }
+
+    #[test]
+    fn normalizes_numbers_ids_and_paths() {
+        assert_eq!(
+            normalize_message("exit 137 after 2s in /tmp/x.sh (id deadbeef42)"),
+            "exit # after #s in <path> (id <id>)"
+        );
+    }
+
+    #[test]
+    fn error_codes_name_the_variant() {
+        assert_eq!(
+            error_code(Some(&CodexErrorInfo::SandboxError)),
+            "sandbox_error"
+        );
+        assert_eq!(
+            error_code(Some(&CodexErrorInfo::HttpConnectionFailed {
+                http_status_code: Some(502),
+            })),
+            "http_connection_failed"
+        );
+        assert_eq!(error_code(None), "unknown");
+    }
+
+    #[tokio::test]
+    async fn counts_recurring_fingerprints() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let fingerprints = ErrorFingerprints::new(codex_home.path().to_path_buf(), true);
+        let error = |message: &str| ErrorEvent {
+            message: message.to_string(),
+            codex_error_info: Some(CodexErrorInfo::SandboxError),
+            fingerprint: None,
+        };
+
+        let mut first = error("sandbox denied write to /repo/a.rs");
+        let mut second = error("sandbox denied write to /repo/b.rs");
+        let mut unrelated = error("seatbelt is unavailable");
+        fingerprints.fingerprint(&mut first).await;
+        fingerprints.fingerprint(&mut second).await;
+        fingerprints.fingerprint(&mut unrelated).await;
+
+        let hash = fingerprint_hash(
+            "sandbox_error",
+            "sandbox",
+            "sandbox denied write to /repo/a.rs",
+        );
+        let fingerprint = |occurrences_last_7_days| {
+            Some(ErrorFingerprint {
+                hash: hash.clone(),
+                component: "sandbox".to_string(),
+                occurrences_last_7_days,
+            })
+        };
+        assert_eq!(first.fingerprint, fingerprint(1));
+        assert_eq!(second.fingerprint, fingerprint(2));
+        assert_eq!(
+            unrelated.fingerprint,
+            Some(ErrorFingerprint {
+                hash: fingerprint_hash("sandbox_error", "sandbox", "seatbelt is unavailable"),
+                component: "sandbox".to_string(),
+                occurrences_last_7_days: 1,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn incognito_sessions_do_not_write_counts() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let fingerprints = ErrorFingerprints::new(codex_home.path().to_path_buf(), false);
+        let mut error = ErrorEvent {
+            message: "seatbelt is unavailable".to_string(),
+            codex_error_info: Some(CodexErrorInfo::SandboxError),
+            fingerprint: None,
+        };
+
+        fingerprints.fingerprint(&mut error).await;
+
+        assert_eq!(
+            error.fingerprint,
+            Some(ErrorFingerprint {
+                hash: fingerprint_hash("sandbox_error", "sandbox", "seatbelt is unavailable"),
+                component: "sandbox".to_string(),
+                occurrences_last_7_days: 1,
+            })
+        );
+        assert!(!codex_home.path().join(FINGERPRINTS_FILE).exists());
+    }
+}
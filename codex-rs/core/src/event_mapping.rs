@@ -1,9 +1,12 @@
 use codex_protocol::items::AgentMessageContent;
 use codex_protocol::items::AgentMessageItem;
+use codex_protocol::items::ProviderTool;
+use codex_protocol::items::ProviderToolCallItem;
 use codex_protocol::items::ReasoningItem;
 use codex_protocol::items::TurnItem;
 use codex_protocol::items::UserMessageItem;
 use codex_protocol::items::WebSearchItem;
+use codex_protocol::models::CodeInterpreterOutput;
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::ReasoningItemContent;
 use codex_protocol::models::ReasoningItemReasoningSummary;
@@ -17,6 +20,7 @@ use codex_protocol::user_input::UserInput;
 use tracing::warn;
 use uuid::Uuid;
 
+use crate::instructions::KnowledgeInstructions;
 use crate::instructions::SkillInstructions;
 use crate::instructions::UserInstructions;
 use crate::session_prefix::is_session_prefix;
@@ -25,6 +29,7 @@ use crate::user_shell_command::is_user_shell_command_text;
 fn parse_user_message(message: &[ContentItem]) -> Option<UserMessageItem> {
     if UserInstructions::is_user_instructions(message)
         || SkillInstructions::is_skill_instructions(message)
+        || KnowledgeInstructions::is_knowledge_instructions(message)
     {
         return None;
     }
@@ -135,6 +140,45 @@ pub fn parse_turn_item(item: &ResponseItem) -> Option<TurnItem> {
             id: id.clone().unwrap_or_default(),
             query: query.clone().unwrap_or_default(),
         })),
+        ResponseItem::CodeInterpreterCall {
+            id, code, outputs, ..
+        } => {
+            let output = outputs
+                .iter()
+                .flatten()
+                .filter_map(|output| match output {
+                    CodeInterpreterOutput::Logs { logs } => Some(logs.trim_end().to_string()),
+                    CodeInterpreterOutput::Image { url } => Some(format!("[image: {url}]")),
+                    CodeInterpreterOutput::Other => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            Some(TurnItem::ProviderToolCall(ProviderToolCallItem {
+                id: id.clone().unwrap_or_default(),
+                tool: ProviderTool::CodeInterpreter,
+                input: code.clone().unwrap_or_default(),
+                output,
+            }))
+        }
+        ResponseItem::FileSearchCall {
+            id,
+            queries,
+            results,
+            ..
+        } => {
+            let output = results
+                .iter()
+                .flatten()
+                .filter_map(|result| result.filename.clone().or_else(|| result.file_id.clone()))
+                .collect::<Vec<_>>()
+                .join("\n");
+            Some(TurnItem::ProviderToolCall(ProviderToolCallItem {
+                id: id.clone().unwrap_or_default(),
+                tool: ProviderTool::FileSearch,
+                input: queries.join("\n"),
+                output,
+            }))
+        }
         _ => None,
     }
 }
@@ -143,7 +187,9 @@ pub fn parse_turn_item(item: &ResponseItem) -> Option<TurnItem> {
 mod tests {
     use super::parse_turn_item;
     use codex_protocol::items::AgentMessageContent;
+    use codex_protocol::items::ProviderTool;
     use codex_protocol::items::TurnItem;
+    use codex_protocol::models::CodeInterpreterOutput;
     use codex_protocol::models::ContentItem;
     use codex_protocol::models::ReasoningItemContent;
     use codex_protocol::models::ReasoningItemReasoningSummary;
@@ -434,4 +480,34 @@ mod tests {
             other => panic!("expected TurnItem::WebSearch, got {other:?}"),
         }
     }
+
+    #[test]
+    fn parses_code_interpreter_call() {
+        let item = ResponseItem::CodeInterpreterCall {
+            id: Some("ci_1".to_string()),
+            status: Some("completed".to_string()),
+            container_id: Some("cntr_1".to_string()),
+            code: Some("print(1 + 1)".to_string()),
+            outputs: Some(vec![
+                CodeInterpreterOutput::Logs {
+                    logs: "2\n".to_string(),
+                },
+                CodeInterpreterOutput::Image {
+                    url: "https://example.com/plot.png".to_string(),
+                },
+            ]),
+        };
+
+        let turn_item = parse_turn_item(&item).expect("expected provider tool turn item");
+
+        match turn_item {
+            TurnItem::ProviderToolCall(call) => {
+                assert_eq!(call.id, "ci_1");
+                assert_eq!(call.tool, ProviderTool::CodeInterpreter);
+                assert_eq!(call.input, "print(1 + 1)");
+                assert_eq!(call.output, "2\n[image: https://example.com/plot.png]");
+            }
+            other => panic!("expected TurnItem::ProviderToolCall, got {other:?}"),
+        }
+    }
 }
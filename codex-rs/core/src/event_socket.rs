@@ -0,0 +1,241 @@
+//! Optional read-only tee of a session's live event stream over a Unix domain
+//! socket, for companion UIs (status bar widgets and the like) that do not
+//! need the full app-server.
+//!
+//! Each session listens on `$CODEX_HOME/event-sockets/<thread id>.sock` and
+//! writes a random token to `<thread id>.token` next to it, readable only by
+//! the current user. A client sends that token followed by a newline and then
+//! receives every event as one JSON line. Anything else it sends is ignored.
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use codex_protocol::ThreadId;
+use codex_protocol::protocol::Event;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+#[cfg_attr(not(unix), allow(dead_code))]
+const EVENT_SOCKETS_SUBDIR: &str = "event-sockets";
+
+/// Events buffered per client before a slow client starts missing some.
+#[cfg_attr(not(unix), allow(dead_code))]
+const BUFFERED_EVENTS: usize = 1024;
+
+/// Broadcasts the events of one session to the clients of its socket. The
+/// socket and token files are removed when this is dropped.
+#[cfg_attr(not(unix), allow(dead_code))]
+pub(crate) struct EventSocket {
+    events: broadcast::Sender<Arc<str>>,
+    socket_path: PathBuf,
+    token_path: PathBuf,
+    accept_task: JoinHandle<()>,
+}
+
+impl EventSocket {
+    #[cfg(unix)]
+    pub(crate) async fn start(codex_home: &Path, thread_id: ThreadId) -> io::Result<Self> {
+        use std::os::unix::fs::DirBuilderExt;
+        use std::os::unix::fs::OpenOptionsExt;
+        use tokio::io::AsyncWriteExt;
+
+        let dir = codex_home.join(EVENT_SOCKETS_SUBDIR);
+        tokio::fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(&dir)
+            .await?;
+        let socket_path = dir.join(format!("{thread_id}.sock"));
+        let token_path = dir.join(format!("{thread_id}.token"));
+        // A stale socket from a crashed session with the same id would make
+        // the bind fail.
+        let _ = tokio::fs::remove_file(&socket_path).await;
+
+        let token = uuid::Uuid::new_v4().simple().to_string();
+        let mut token_file = tokio::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(&token_path)
+            .await?;
+        token_file.write_all(token.as_bytes()).await?;
+
+        let listener = tokio::net::UnixListener::bind(&socket_path)?;
+        let (events, _) = broadcast::channel(BUFFERED_EVENTS);
+        let accept_task = tokio::spawn(accept_clients(listener, token, events.clone()));
+        Ok(Self {
+            events,
+            socket_path,
+            token_path,
+            accept_task,
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub(crate) async fn start(_codex_home: &Path, _thread_id: ThreadId) -> io::Result<Self> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "event sockets require Unix domain sockets",
+        ))
+    }
+
+    /// Sends `event` to every connected client. Events are only serialized
+    /// while someone is listening.
+    pub(crate) fn publish(&self, event: &Event) {
+        if self.events.receiver_count() == 0 {
+            return;
+        }
+        match serde_json::to_string(event) {
+            Ok(line) => {
+                let _ = self.events.send(Arc::from(line));
+            }
+            Err(err) => warn!("failed to serialize event for the event socket: {err}"),
+        }
+    }
+
+    pub(crate) fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+}
+
+impl Drop for EventSocket {
+    fn drop(&mut self) {
+        self.accept_task.abort();
+        let _ = std::fs::remove_file(&self.socket_path);
+        let _ = std::fs::remove_file(&self.token_path);
+    }
+}
+
+#[cfg(unix)]
+async fn accept_clients(
+    listener: tokio::net::UnixListener,
+    token: String,
+    events: broadcast::Sender<Arc<str>>,
+) {
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(err) => {
+                warn!("event socket stopped accepting clients: {err}");
+                return;
+            }
+        };
+        tokio::spawn(serve_client(stream, token.clone(), events.downgrade()));
+    }
+}
+
+#[cfg(unix)]
+async fn serve_client(
+    stream: tokio::net::UnixStream,
+    token: String,
+    events: broadcast::WeakSender<Arc<str>>,
+) {
+    use std::time::Duration;
+    use tokio::io::AsyncBufReadExt;
+    use tokio::io::AsyncWriteExt;
+    use tokio::io::BufReader;
+
+    const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    let mut presented = String::new();
+    let handshake = tokio::time::timeout(HANDSHAKE_TIMEOUT, reader.read_line(&mut presented));
+    if !matches!(handshake.await, Ok(Ok(_))) || !tokens_match(presented.trim_end(), &token) {
+        return;
+    }
+    // Subscribe only after the handshake so unauthenticated clients never
+    // hold events back. Clients only keep a receiver, so they disconnect once
+    // the session is gone.
+    let Some(events) = events.upgrade() else {
+        return;
+    };
+    let mut receiver = events.subscribe();
+    drop(events);
+    loop {
+        let line = match receiver.recv().await {
+            Ok(line) => line,
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                warn!("event socket client fell behind and missed {skipped} events");
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => return,
+        };
+        if writer.write_all(line.as_bytes()).await.is_err()
+            || writer.write_all(b"\n").await.is_err()
+        {
+            return;
+        }
+    }
+}
+
+/// Compares a presented token with the expected one in time that depends only
+/// on their lengths, so the token cannot be guessed byte by byte.
+#[cfg(unix)]
+fn tokens_match(presented: &str, expected: &str) -> bool {
+    presented.len() == expected.len()
+        && presented
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use codex_protocol::protocol::EventMsg;
+    use codex_protocol::protocol::WarningEvent;
+    use pretty_assertions::assert_eq;
+    use tokio::io::AsyncBufReadExt;
+    use tokio::io::AsyncWriteExt;
+    use tokio::io::BufReader;
+
+    #[test]
+    fn tokens_must_match_exactly() {
+        let results = [
+            tokens_match("abc123", "abc123"),
+            tokens_match("abc124", "abc123"),
+            tokens_match("abc12", "abc123"),
+            tokens_match("", "abc123"),
+        ];
+        assert_eq!(results, [true, false, false, false]);
+    }
+
+    #[tokio::test]
+    async fn streams_events_to_clients_that_present_the_token() -> anyhow::Result<()> {
+        let codex_home = tempfile::tempdir()?;
+        let socket = EventSocket::start(codex_home.path(), ThreadId::new()).await?;
+        let token = tokio::fs::read_to_string(&socket.token_path).await?;
+
+        let mut rejected = tokio::net::UnixStream::connect(socket.socket_path()).await?;
+        rejected.write_all(b"wrong token\n").await?;
+        let mut client = tokio::net::UnixStream::connect(socket.socket_path()).await?;
+        client.write_all(format!("{token}\n").as_bytes()).await?;
+        // Wait until the handshake has subscribed the client.
+        while socket.events.receiver_count() == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        let event = Event {
+            id: "sub-1".to_string(),
+            msg: EventMsg::Warning(WarningEvent {
+                message: "heads up".to_string(),
+            }),
+        };
+        socket.publish(&event);
+        let mut line = String::new();
+        BufReader::new(client).read_line(&mut line).await?;
+        assert_eq!(line.trim_end(), serde_json::to_string(&event)?);
+        assert_eq!(socket.events.receiver_count(), 1);
+
+        let (socket_path, token_path) = (socket.socket_path.clone(), socket.token_path.clone());
+        drop(socket);
+        assert!(!socket_path.exists() && !token_path.exists());
+        Ok(())
+    }
+}
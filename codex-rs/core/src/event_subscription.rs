@@ -0,0 +1,412 @@
+//! Typed, filtered subscriptions to the events emitted by Codex threads.
+//!
+//! [`crate::CodexThread::next_event`] hands every event to a single consumer,
+//! which then has to match on the whole [`EventMsg`] enum. Subscriptions are
+//! an additional fan-out: any number of subscribers can listen through
+//! [`crate::ThreadManager::subscribe`] or [`crate::CodexThread::subscribe`],
+//! and each one only receives the category of events it asked for, already
+//! narrowed to a small enum, from the agents (threads) it selected.
+//!
+//! Subscribers only see events published after they subscribed. A subscriber
+//! that falls more than [`EVENT_SUBSCRIPTION_CAPACITY`] events behind skips
+//! the oldest ones rather than slowing down the agent.
+
+use std::collections::HashSet;
+
+use codex_protocol::ThreadId;
+use futures::Stream;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::warn;
+
+use crate::protocol::ApplyPatchApprovalRequestEvent;
+use crate::protocol::CollabAgentInteractionBeginEvent;
+use crate::protocol::CollabAgentInteractionEndEvent;
+use crate::protocol::CollabAgentSpawnBeginEvent;
+use crate::protocol::CollabAgentSpawnEndEvent;
+use crate::protocol::CollabCloseBeginEvent;
+use crate::protocol::CollabCloseEndEvent;
+use crate::protocol::CollabWaitingBeginEvent;
+use crate::protocol::CollabWaitingEndEvent;
+use crate::protocol::ElicitationRequestEvent;
+use crate::protocol::ErrorEvent;
+use crate::protocol::Event;
+use crate::protocol::EventMsg;
+use crate::protocol::ExecApprovalRequestEvent;
+use crate::protocol::ExecCommandBeginEvent;
+use crate::protocol::ExecCommandEndEvent;
+use crate::protocol::ExecCommandOutputDeltaEvent;
+use crate::protocol::RequestUserInputEvent;
+use crate::protocol::TerminalInteractionEvent;
+use crate::protocol::TurnAbortedEvent;
+use crate::protocol::TurnCompleteEvent;
+use crate::protocol::TurnStartedEvent;
+
+/// Number of events buffered per subscriber before the oldest are skipped.
+pub const EVENT_SUBSCRIPTION_CAPACITY: usize = 4096;
+
+/// An event published by a thread, as carried on the shared broadcast channel.
+#[derive(Debug, Clone)]
+pub(crate) struct PublishedEvent {
+    pub thread_id: ThreadId,
+    pub event: Event,
+}
+
+/// A category of events that can be subscribed to. Implementations narrow an
+/// [`EventMsg`] down to the category's own event type, returning `None` for
+/// events outside the category.
+pub trait EventCategory: Send + 'static {
+    type Event: Send + 'static;
+
+    fn select(msg: EventMsg) -> Option<Self::Event>;
+}
+
+/// An event delivered to a subscriber.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgentEvent<T> {
+    /// Thread that emitted the event.
+    pub agent_id: ThreadId,
+    /// Submission id the event belongs to (see [`Event::id`]).
+    pub id: String,
+    pub event: T,
+}
+
+/// Which agents (threads) a subscription receives events from.
+#[derive(Debug, Clone, Default)]
+pub struct AgentFilter {
+    agents: Option<HashSet<ThreadId>>,
+}
+
+impl AgentFilter {
+    /// Events from every thread, including sub-agents spawned later.
+    pub fn any() -> Self {
+        Self::default()
+    }
+
+    /// Events from the listed threads only.
+    pub fn only(agents: impl IntoIterator<Item = ThreadId>) -> Self {
+        Self {
+            agents: Some(agents.into_iter().collect()),
+        }
+    }
+
+    pub fn matches(&self, agent_id: ThreadId) -> bool {
+        self.agents
+            .as_ref()
+            .is_none_or(|agents| agents.contains(&agent_id))
+    }
+}
+
+pub(crate) fn subscription_stream<C: EventCategory>(
+    rx: broadcast::Receiver<PublishedEvent>,
+    agents: AgentFilter,
+) -> impl Stream<Item = AgentEvent<C::Event>> + Send + 'static {
+    filtered_stream::<C>(rx, agents, None)
+}
+
+/// Events of one thread, ending after that thread's
+/// [`EventMsg::ShutdownComplete`] (which is still delivered if `C` selects
+/// it).
+pub(crate) fn thread_subscription_stream<C: EventCategory>(
+    rx: broadcast::Receiver<PublishedEvent>,
+    thread_id: ThreadId,
+) -> impl Stream<Item = AgentEvent<C::Event>> + Send + 'static {
+    filtered_stream::<C>(rx, AgentFilter::only([thread_id]), Some(thread_id))
+}
+
+fn filtered_stream<C: EventCategory>(
+    rx: broadcast::Receiver<PublishedEvent>,
+    agents: AgentFilter,
+    ends_with_shutdown_of: Option<ThreadId>,
+) -> impl Stream<Item = AgentEvent<C::Event>> + Send + 'static {
+    let state = (rx, agents, false);
+    futures::stream::unfold(state, move |(mut rx, agents, finished)| async move {
+        if finished {
+            return None;
+        }
+        loop {
+            match rx.recv().await {
+                Ok(PublishedEvent { thread_id, event }) => {
+                    if !agents.matches(thread_id) {
+                        continue;
+                    }
+                    let Event { id, msg } = event;
+                    let finished = ends_with_shutdown_of == Some(thread_id)
+                        && matches!(msg, EventMsg::ShutdownComplete);
+                    match C::select(msg) {
+                        Some(event) => {
+                            let item = AgentEvent {
+                                agent_id: thread_id,
+                                id,
+                                event,
+                            };
+                            return Some((item, (rx, agents, finished)));
+                        }
+                        None if finished => return None,
+                        None => {}
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("event subscriber fell behind; skipped {skipped} events");
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+}
+
+/// Every event, unfiltered.
+pub struct AllEvents;
+
+impl EventCategory for AllEvents {
+    type Event = EventMsg;
+
+    fn select(msg: EventMsg) -> Option<Self::Event> {
+        Some(msg)
+    }
+}
+
+/// Turn lifecycle: start, completion, abort and errors.
+pub struct TurnEvents;
+
+#[derive(Debug, Clone)]
+pub enum TurnEvent {
+    Started(TurnStartedEvent),
+    Completed(TurnCompleteEvent),
+    Aborted(TurnAbortedEvent),
+    Error(ErrorEvent),
+}
+
+impl EventCategory for TurnEvents {
+    type Event = TurnEvent;
+
+    fn select(msg: EventMsg) -> Option<Self::Event> {
+        match msg {
+            EventMsg::TurnStarted(ev) => Some(TurnEvent::Started(ev)),
+            EventMsg::TurnComplete(ev) => Some(TurnEvent::Completed(ev)),
+            EventMsg::TurnAborted(ev) => Some(TurnEvent::Aborted(ev)),
+            EventMsg::Error(ev) => Some(TurnEvent::Error(ev)),
+            _ => None,
+        }
+    }
+}
+
+/// Command execution: begin, streamed output, stdin interaction and end.
+pub struct ExecEvents;
+
+#[derive(Debug, Clone)]
+pub enum ExecEvent {
+    Begin(ExecCommandBeginEvent),
+    OutputDelta(ExecCommandOutputDeltaEvent),
+    TerminalInteraction(TerminalInteractionEvent),
+    End(ExecCommandEndEvent),
+}
+
+impl EventCategory for ExecEvents {
+    type Event = ExecEvent;
+
+    fn select(msg: EventMsg) -> Option<Self::Event> {
+        match msg {
+            EventMsg::ExecCommandBegin(ev) => Some(ExecEvent::Begin(ev)),
+            EventMsg::ExecCommandOutputDelta(ev) => Some(ExecEvent::OutputDelta(ev)),
+            EventMsg::TerminalInteraction(ev) => Some(ExecEvent::TerminalInteraction(ev)),
+            EventMsg::ExecCommandEnd(ev) => Some(ExecEvent::End(ev)),
+            _ => None,
+        }
+    }
+}
+
+/// Requests that wait on the user: approvals, questions and MCP elicitations.
+pub struct ApprovalEvents;
+
+#[derive(Debug, Clone)]
+pub enum ApprovalEvent {
+    Exec(ExecApprovalRequestEvent),
+    Patch(ApplyPatchApprovalRequestEvent),
+    UserInput(RequestUserInputEvent),
+    Elicitation(ElicitationRequestEvent),
+}
+
+impl EventCategory for ApprovalEvents {
+    type Event = ApprovalEvent;
+
+    fn select(msg: EventMsg) -> Option<Self::Event> {
+        match msg {
+            EventMsg::ExecApprovalRequest(ev) => Some(ApprovalEvent::Exec(ev)),
+            EventMsg::ApplyPatchApprovalRequest(ev) => Some(ApprovalEvent::Patch(ev)),
+            EventMsg::RequestUserInput(ev) => Some(ApprovalEvent::UserInput(ev)),
+            EventMsg::ElicitationRequest(ev) => Some(ApprovalEvent::Elicitation(ev)),
+            _ => None,
+        }
+    }
+}
+
+/// Multi-agent coordination: spawning, messaging, waiting on and closing agents.
+pub struct CollabEvents;
+
+#[derive(Debug, Clone)]
+pub enum CollabEvent {
+    SpawnBegin(CollabAgentSpawnBeginEvent),
+    SpawnEnd(CollabAgentSpawnEndEvent),
+    InteractionBegin(CollabAgentInteractionBeginEvent),
+    InteractionEnd(CollabAgentInteractionEndEvent),
+    WaitingBegin(CollabWaitingBeginEvent),
+    WaitingEnd(CollabWaitingEndEvent),
+    CloseBegin(CollabCloseBeginEvent),
+    CloseEnd(CollabCloseEndEvent),
+}
+
+impl EventCategory for CollabEvents {
+    type Event = CollabEvent;
+
+    fn select(msg: EventMsg) -> Option<Self::Event> {
+        match msg {
+            EventMsg::CollabAgentSpawnBegin(ev) => Some(CollabEvent::SpawnBegin(ev)),
+            EventMsg::CollabAgentSpawnEnd(ev) => Some(CollabEvent::SpawnEnd(ev)),
+            EventMsg::CollabAgentInteractionBegin(ev) => Some(CollabEvent::InteractionBegin(ev)),
+            EventMsg::CollabAgentInteractionEnd(ev) => Some(CollabEvent::InteractionEnd(ev)),
+            EventMsg::CollabWaitingBegin(ev) => Some(CollabEvent::WaitingBegin(ev)),
+            EventMsg::CollabWaitingEnd(ev) => Some(CollabEvent::WaitingEnd(ev)),
+            EventMsg::CollabCloseBegin(ev) => Some(CollabEvent::CloseBegin(ev)),
+            EventMsg::CollabCloseEnd(ev) => Some(CollabEvent::CloseEnd(ev)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::WarningEvent;
+    use futures::StreamExt;
+    use pretty_assertions::assert_eq;
+
+    fn published(thread_id: ThreadId, msg: EventMsg) -> PublishedEvent {
+        PublishedEvent {
+            thread_id,
+            event: Event {
+                id: "sub-1".to_string(),
+                msg,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn delivers_only_matching_category_and_agents() {
+        let (tx, rx) = broadcast::channel(16);
+        let watched = ThreadId::new();
+        let other = ThreadId::new();
+        let stream = subscription_stream::<TurnEvents>(rx, AgentFilter::only([watched]));
+
+        let started = TurnStartedEvent {
+            model_context_window: None,
+        };
+        tx.send(published(
+            watched,
+            EventMsg::Warning(WarningEvent {
+                message: "not a turn event".to_string(),
+            }),
+        ))
+        .expect("send");
+        tx.send(published(other, EventMsg::TurnStarted(started.clone())))
+            .expect("send");
+        tx.send(published(watched, EventMsg::TurnStarted(started.clone())))
+            .expect("send");
+        drop(tx);
+
+        let events = stream
+            .map(|event| AgentEvent {
+                agent_id: event.agent_id,
+                id: event.id,
+                event: match event.event {
+                    TurnEvent::Started(started) => started,
+                    other => panic!("unexpected event {other:?}"),
+                },
+            })
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(
+            events,
+            vec![AgentEvent {
+                agent_id: watched,
+                id: "sub-1".to_string(),
+                event: started,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn thread_stream_ends_after_its_shutdown() {
+        let (tx, rx) = broadcast::channel(16);
+        let watched = ThreadId::new();
+        let other = ThreadId::new();
+        let stream = thread_subscription_stream::<AllEvents>(rx, watched);
+
+        tx.send(published(other, EventMsg::ShutdownComplete))
+            .expect("send");
+        tx.send(published(watched, EventMsg::ShutdownComplete))
+            .expect("send");
+        tx.send(published(
+            watched,
+            EventMsg::Warning(WarningEvent {
+                message: "after shutdown".to_string(),
+            }),
+        ))
+        .expect("send");
+
+        // The sender is still alive, so only the shutdown ends the stream.
+        let events = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            stream
+                .map(|event| {
+                    assert!(matches!(event.event, EventMsg::ShutdownComplete));
+                    (event.agent_id, event.id)
+                })
+                .collect::<Vec<_>>(),
+        )
+        .await
+        .expect("stream ends after the shutdown");
+        assert_eq!(events, vec![(watched, "sub-1".to_string())]);
+        drop(tx);
+    }
+
+    #[tokio::test]
+    async fn skips_lagged_events_and_keeps_streaming() {
+        let (tx, rx) = broadcast::channel(1);
+        let thread_id = ThreadId::new();
+        let stream = subscription_stream::<AllEvents>(rx, AgentFilter::any());
+
+        for message in ["dropped", "kept"] {
+            tx.send(published(
+                thread_id,
+                EventMsg::Warning(WarningEvent {
+                    message: message.to_string(),
+                }),
+            ))
+            .expect("send");
+        }
+        drop(tx);
+
+        let events = stream
+            .map(|event| AgentEvent {
+                agent_id: event.agent_id,
+                id: event.id,
+                event: match event.event {
+                    EventMsg::Warning(warning) => warning,
+                    other => panic!("unexpected event {other:?}"),
+                },
+            })
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(
+            events,
+            vec![AgentEvent {
+                agent_id: thread_id,
+                id: "sub-1".to_string(),
+                event: WarningEvent {
+                    message: "kept".to_string(),
+                },
+            }]
+        );
+    }
+}
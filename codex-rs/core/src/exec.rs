@@ -15,6 +15,7 @@ use tokio::io::AsyncReadExt;
 use tokio::io::BufReader;
 use tokio::process::Child;
 use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
 
 use crate::error::CodexErr;
 use crate::error::Result;
@@ -33,6 +34,8 @@ use crate::spawn::StdioPolicy;
 use crate::spawn::spawn_child_async;
 use crate::text_encoding::bytes_to_string_smart;
 use codex_utils_pty::process_group::kill_child_process_group;
+use codex_utils_pty::process_group::kill_process_group;
+use codex_utils_pty::process_group::process_group_exists;
 
 pub const DEFAULT_EXEC_COMMAND_TIMEOUT_MS: u64 = 10_000;
 
@@ -40,7 +43,7 @@ pub const DEFAULT_EXEC_COMMAND_TIMEOUT_MS: u64 = 10_000;
 // for these.
 const SIGKILL_CODE: i32 = 9;
 const TIMEOUT_CODE: i32 = 64;
-const EXIT_CODE_SIGNAL_BASE: i32 = 128; // conventional shell: 128 + signal
+pub(crate) const EXIT_CODE_SIGNAL_BASE: i32 = 128; // conventional shell: 128 + signal
 const EXEC_TIMEOUT_EXIT_CODE: i32 = 124; // conventional timeout exit code
 
 // I/O buffer sizing
@@ -74,6 +77,8 @@ pub enum ExecExpiration {
     Timeout(Duration),
     DefaultTimeout,
     Cancellation(CancellationToken),
+    /// Whichever of the timeout and the cancellation comes first.
+    TimeoutOrCancellation(Duration, CancellationToken),
 }
 
 impl From<Option<u64>> for ExecExpiration {
@@ -100,6 +105,12 @@ impl ExecExpiration {
             ExecExpiration::Cancellation(cancel) => {
                 cancel.cancelled().await;
             }
+            ExecExpiration::TimeoutOrCancellation(duration, cancel) => {
+                tokio::select! {
+                    _ = tokio::time::sleep(duration) => {}
+                    _ = cancel.cancelled() => {}
+                }
+            }
         }
     }
 
@@ -109,6 +120,24 @@ impl ExecExpiration {
             ExecExpiration::Timeout(duration) => Some(duration.as_millis() as u64),
             ExecExpiration::DefaultTimeout => Some(DEFAULT_EXEC_COMMAND_TIMEOUT_MS),
             ExecExpiration::Cancellation(_) => None,
+            ExecExpiration::TimeoutOrCancellation(duration, _) => Some(duration.as_millis() as u64),
+        }
+    }
+
+    /// Also expires when `cancellation_token` is cancelled, so the command is
+    /// killed when the tool call that started it is interrupted. An
+    /// expiration that already has a token keeps it.
+    pub(crate) fn with_cancellation(self, cancellation_token: CancellationToken) -> Self {
+        match self {
+            ExecExpiration::Timeout(duration) => {
+                ExecExpiration::TimeoutOrCancellation(duration, cancellation_token)
+            }
+            ExecExpiration::DefaultTimeout => ExecExpiration::TimeoutOrCancellation(
+                Duration::from_millis(DEFAULT_EXEC_COMMAND_TIMEOUT_MS),
+                cancellation_token,
+            ),
+            expiration @ (ExecExpiration::Cancellation(_)
+            | ExecExpiration::TimeoutOrCancellation(..)) => expiration,
         }
     }
 }
@@ -296,36 +325,19 @@ async fn exec_windows_sandbox(
     };
 
     let exit_status = synthetic_exit_status(capture.exit_code);
-    let mut stdout_text = capture.stdout;
-    if stdout_text.len() > EXEC_OUTPUT_MAX_BYTES {
-        stdout_text.truncate(EXEC_OUTPUT_MAX_BYTES);
-    }
-    let mut stderr_text = capture.stderr;
-    if stderr_text.len() > EXEC_OUTPUT_MAX_BYTES {
-        stderr_text.truncate(EXEC_OUTPUT_MAX_BYTES);
-    }
-    let stdout = StreamOutput {
-        text: stdout_text,
-        truncated_after_lines: None,
-    };
-    let stderr = StreamOutput {
-        text: stderr_text,
-        truncated_after_lines: None,
+    let capped = |mut text: Vec<u8>| {
+        let omitted_bytes = text.len().saturating_sub(EXEC_OUTPUT_MAX_BYTES);
+        text.truncate(EXEC_OUTPUT_MAX_BYTES);
+        StreamOutput {
+            text,
+            truncated_after_lines: None,
+            omitted_bytes,
+        }
     };
+    let stdout = capped(capture.stdout);
+    let stderr = capped(capture.stderr);
     // Best-effort aggregate: stdout then stderr (capped).
-    let mut aggregated = Vec::with_capacity(
-        stdout
-            .text
-            .len()
-            .saturating_add(stderr.text.len())
-            .min(EXEC_OUTPUT_MAX_BYTES),
-    );
-    append_capped(&mut aggregated, &stdout.text, EXEC_OUTPUT_MAX_BYTES);
-    append_capped(&mut aggregated, &stderr.text, EXEC_OUTPUT_MAX_BYTES);
-    let aggregated_output = StreamOutput {
-        text: aggregated,
-        truncated_after_lines: None,
-    };
+    let aggregated_output = aggregate_output(&stdout, &stderr, EXEC_OUTPUT_MAX_BYTES);
 
     Ok(RawExecToolCallOutput {
         exit_status,
@@ -480,6 +492,8 @@ pub(crate) fn is_likely_sandbox_denied(
 pub struct StreamOutput<T: Clone> {
     pub text: T,
     pub truncated_after_lines: Option<u32>,
+    /// Bytes the command printed beyond what was retained in `text`.
+    pub omitted_bytes: usize,
 }
 
 #[derive(Debug)]
@@ -496,6 +510,7 @@ impl StreamOutput<String> {
         Self {
             text,
             truncated_after_lines: None,
+            omitted_bytes: 0,
         }
     }
 }
@@ -505,18 +520,42 @@ impl StreamOutput<Vec<u8>> {
         StreamOutput {
             text: bytes_to_string_smart(&self.text),
             truncated_after_lines: self.truncated_after_lines,
+            omitted_bytes: self.omitted_bytes,
         }
     }
 }
 
+/// Appends as much of `src` as fits under `max_bytes` and returns the number
+/// of bytes dropped.
 #[inline]
-fn append_capped(dst: &mut Vec<u8>, src: &[u8], max_bytes: usize) {
-    if dst.len() >= max_bytes {
-        return;
-    }
+fn append_capped(dst: &mut Vec<u8>, src: &[u8], max_bytes: usize) -> usize {
     let remaining = max_bytes.saturating_sub(dst.len());
     let take = remaining.min(src.len());
     dst.extend_from_slice(&src[..take]);
+    src.len() - take
+}
+
+/// stdout followed by stderr, capped at `max_bytes`. Bytes the streams
+/// dropped themselves count as omitted from the aggregate too.
+fn aggregate_output(
+    stdout: &StreamOutput<Vec<u8>>,
+    stderr: &StreamOutput<Vec<u8>>,
+    max_bytes: usize,
+) -> StreamOutput<Vec<u8>> {
+    let mut aggregated = Vec::with_capacity(
+        stdout
+            .text
+            .len()
+            .saturating_add(stderr.text.len())
+            .min(max_bytes),
+    );
+    let dropped = append_capped(&mut aggregated, &stdout.text, max_bytes)
+        + append_capped(&mut aggregated, &stderr.text, max_bytes);
+    StreamOutput {
+        text: aggregated,
+        truncated_after_lines: None,
+        omitted_bytes: stdout.omitted_bytes + stderr.omitted_bytes + dropped,
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -587,6 +626,38 @@ async fn exec(
     consume_truncated_output(child, expiration, stdout_stream).await
 }
 
+/// How long to wait for a killed command and its process group to go away.
+const KILL_REAP_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Waits for a child whose process group was just killed, so it does not
+/// linger as a zombie, and checks that nothing in the group survived. A
+/// process forked while the kill was being delivered can escape it, so the
+/// group is killed again before giving up with a warning.
+async fn reap_killed_child(child: &mut Child, process_group_id: Option<u32>) {
+    if tokio::time::timeout(KILL_REAP_TIMEOUT, child.wait())
+        .await
+        .is_err()
+    {
+        tracing::warn!("killed command {process_group_id:?} did not exit");
+    }
+    let Some(process_group_id) = process_group_id else {
+        return;
+    };
+    let deadline = Instant::now() + KILL_REAP_TIMEOUT;
+    let mut killed_again = false;
+    while process_group_exists(process_group_id) {
+        if Instant::now() >= deadline {
+            tracing::warn!("process group {process_group_id} survived being killed");
+            return;
+        }
+        if !killed_again && Instant::now() + KILL_REAP_TIMEOUT / 2 >= deadline {
+            let _ = kill_process_group(process_group_id);
+            killed_again = true;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+}
+
 /// Consumes the output of a child process, truncating it so it is suitable for
 /// use as the output of a `shell` tool call. Also enforces specified timeout.
 async fn consume_truncated_output(
@@ -609,17 +680,15 @@ async fn consume_truncated_output(
         ))
     })?;
 
-    let stdout_handle = tokio::spawn(read_capped(
-        BufReader::new(stdout_reader),
-        stdout_stream.clone(),
-        false,
-    ));
-    let stderr_handle = tokio::spawn(read_capped(
-        BufReader::new(stderr_reader),
-        stdout_stream.clone(),
-        true,
-    ));
+    let stdout_handle = tokio::spawn(
+        read_capped(BufReader::new(stdout_reader), stdout_stream.clone(), false).in_current_span(),
+    );
+    let stderr_handle = tokio::spawn(
+        read_capped(BufReader::new(stderr_reader), stdout_stream.clone(), true).in_current_span(),
+    );
 
+    // The child leads its own process group, so its pid is the group id.
+    let process_group_id = child.id();
     let (exit_status, timed_out) = tokio::select! {
         status_result = child.wait() => {
             let exit_status = status_result?;
@@ -628,11 +697,13 @@ async fn consume_truncated_output(
         _ = expiration.wait() => {
             kill_child_process_group(&mut child)?;
             child.start_kill()?;
+            reap_killed_child(&mut child, process_group_id).await;
             (synthetic_exit_status(EXIT_CODE_SIGNAL_BASE + TIMEOUT_CODE), true)
         }
         _ = tokio::signal::ctrl_c() => {
             kill_child_process_group(&mut child)?;
             child.start_kill()?;
+            reap_killed_child(&mut child, process_group_id).await;
             (synthetic_exit_status(EXIT_CODE_SIGNAL_BASE + SIGKILL_CODE), false)
         }
     };
@@ -665,6 +736,7 @@ async fn consume_truncated_output(
                 Ok(StreamOutput {
                     text: Vec::new(),
                     truncated_after_lines: None,
+                    omitted_bytes: 0,
                 })
             }
         }
@@ -684,19 +756,7 @@ async fn consume_truncated_output(
     )
     .await?;
     // Best-effort aggregate: stdout then stderr (capped).
-    let mut aggregated = Vec::with_capacity(
-        stdout
-            .text
-            .len()
-            .saturating_add(stderr.text.len())
-            .min(EXEC_OUTPUT_MAX_BYTES),
-    );
-    append_capped(&mut aggregated, &stdout.text, EXEC_OUTPUT_MAX_BYTES);
-    append_capped(&mut aggregated, &stderr.text, EXEC_OUTPUT_MAX_BYTES * 2);
-    let aggregated_output = StreamOutput {
-        text: aggregated,
-        truncated_after_lines: None,
-    };
+    let aggregated_output = aggregate_output(&stdout, &stderr, EXEC_OUTPUT_MAX_BYTES * 2);
 
     Ok(RawExecToolCallOutput {
         exit_status,
@@ -715,6 +775,7 @@ async fn read_capped<R: AsyncRead + Unpin + Send + 'static>(
     let mut buf = Vec::with_capacity(AGGREGATE_BUFFER_INITIAL_CAPACITY.min(EXEC_OUTPUT_MAX_BYTES));
     let mut tmp = [0u8; READ_CHUNK_SIZE];
     let mut emitted_deltas: usize = 0;
+    let mut omitted_bytes: usize = 0;
 
     loop {
         let n = reader.read(&mut tmp).await?;
@@ -744,13 +805,14 @@ async fn read_capped<R: AsyncRead + Unpin + Send + 'static>(
             emitted_deltas += 1;
         }
 
-        append_capped(&mut buf, &tmp[..n], EXEC_OUTPUT_MAX_BYTES);
+        omitted_bytes += append_capped(&mut buf, &tmp[..n], EXEC_OUTPUT_MAX_BYTES);
         // Continue reading to EOF to avoid back-pressure
     }
 
     Ok(StreamOutput {
         text: buf,
         truncated_after_lines: None,
+        omitted_bytes,
     })
 }
 
@@ -843,7 +905,10 @@ mod tests {
         });
 
         let out = read_capped(reader, None, false).await.expect("read");
-        assert_eq!(out.text.len(), EXEC_OUTPUT_MAX_BYTES);
+        assert_eq!(
+            (out.text.len(), out.omitted_bytes),
+            (EXEC_OUTPUT_MAX_BYTES, 128 * 1024)
+        );
     }
 
     #[cfg(unix)]
@@ -910,6 +975,63 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn cancellation_kills_and_reaps_the_whole_process_group() -> Result<()> {
+        let command = vec![
+            "/bin/sh".to_string(),
+            "-c".to_string(),
+            "sleep 60 & echo $$; sleep 60".to_string(),
+        ];
+        let cancel_token = CancellationToken::new();
+        let cancel_tx = cancel_token.clone();
+        let params = ExecParams {
+            command,
+            cwd: std::env::current_dir()?,
+            expiration: ExecExpiration::Timeout(Duration::from_secs(60))
+                .with_cancellation(cancel_token),
+            env: std::env::vars().collect(),
+            sandbox_permissions: SandboxPermissions::UseDefault,
+            justification: None,
+            arg0: None,
+        };
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            cancel_tx.cancel();
+        });
+
+        let output = exec(params, SandboxType::None, &SandboxPolicy::ReadOnly, None).await?;
+        assert!(output.timed_out);
+
+        let stdout = output.stdout.from_utf8_lossy().text;
+        let pgid_line = stdout.lines().next().unwrap_or("").trim();
+        let pgid: u32 = pgid_line.parse().map_err(|error| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Failed to parse pgid from stdout '{pgid_line}': {error}"),
+            )
+        })?;
+        // Reaping is verified before `exec` returns, so there is no need to
+        // poll here.
+        assert!(
+            !process_group_exists(pgid),
+            "process group {pgid} is still alive"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn with_cancellation_keeps_the_timeout() {
+        let expiration =
+            ExecExpiration::from(Some(1_500)).with_cancellation(CancellationToken::new());
+        assert_eq!(expiration.timeout_ms(), Some(1_500));
+        let expiration = ExecExpiration::DefaultTimeout.with_cancellation(CancellationToken::new());
+        assert_eq!(
+            expiration.timeout_ms(),
+            Some(DEFAULT_EXEC_COMMAND_TIMEOUT_MS)
+        );
+    }
+
     #[tokio::test]
     async fn process_exec_tool_call_respects_cancellation_token() -> Result<()> {
         let command = long_running_command();
@@ -15,38 +15,83 @@ pub fn create_env(policy: &ShellEnvironmentPolicy) -> HashMap<String, String> {
     populate_env(std::env::vars(), policy)
 }
 
+const CORE_VARS: &[&str] = &[
+    "HOME", "LOGNAME", "PATH", "SHELL", "USER", "USERNAME", "TMPDIR", "TEMP", "TMP",
+];
+
+/// Additional core variables on Windows, without which programs fail to
+/// resolve executables or system directories.
+const WINDOWS_CORE_VARS: &[&str] = &[
+    "PATHEXT",
+    "SYSTEMROOT",
+    "SYSTEMDRIVE",
+    "WINDIR",
+    "COMSPEC",
+    "USERPROFILE",
+    "APPDATA",
+    "LOCALAPPDATA",
+    "PROGRAMDATA",
+];
+
+/// One-line description of the environment `policy` produces, for logs:
+/// the inherit mode and the names, never the values, of the variables that
+/// are passed on or set.
+pub fn describe_env(policy: &ShellEnvironmentPolicy, env: &HashMap<String, String>) -> String {
+    let (mut set, mut inherited): (Vec<&str>, Vec<&str>) = env
+        .keys()
+        .map(String::as_str)
+        .partition(|name| policy.r#set.contains_key(*name));
+    set.sort_unstable();
+    inherited.sort_unstable();
+    let inherit = match policy.inherit {
+        ShellEnvironmentPolicyInherit::All => "all",
+        ShellEnvironmentPolicyInherit::Core => "core",
+        ShellEnvironmentPolicyInherit::None => "none",
+    };
+    format!(
+        "inherit={inherit} inherited={} [{}] set={} [{}]",
+        inherited.len(),
+        inherited.join(", "),
+        set.len(),
+        set.join(", ")
+    )
+}
+
 fn populate_env<I>(vars: I, policy: &ShellEnvironmentPolicy) -> HashMap<String, String>
 where
     I: IntoIterator<Item = (String, String)>,
 {
+    // Internal helper – does `name` match **any** pattern in `patterns`?
+    let matches_any = |name: &str, patterns: &[EnvironmentVariablePattern]| -> bool {
+        patterns.iter().any(|pattern| pattern.matches(name))
+    };
+
     // Step 1 – determine the starting set of variables based on the
-    // `inherit` strategy.
+    // `inherit` strategy, plus the explicitly included ones.
     let mut env_map: HashMap<String, String> = match policy.inherit {
         ShellEnvironmentPolicyInherit::All => vars.into_iter().collect(),
-        ShellEnvironmentPolicyInherit::None => HashMap::new(),
+        ShellEnvironmentPolicyInherit::None => vars
+            .into_iter()
+            .filter(|(k, _)| matches_any(k, &policy.include))
+            .collect(),
         ShellEnvironmentPolicyInherit::Core => {
-            const CORE_VARS: &[&str] = &[
-                "HOME", "LOGNAME", "PATH", "SHELL", "USER", "USERNAME", "TMPDIR", "TEMP", "TMP",
-            ];
             let allow: HashSet<&str> = CORE_VARS.iter().copied().collect();
             let is_core_var = |name: &str| {
                 if cfg!(target_os = "windows") {
                     CORE_VARS
                         .iter()
+                        .chain(WINDOWS_CORE_VARS)
                         .any(|allowed| allowed.eq_ignore_ascii_case(name))
                 } else {
                     allow.contains(name)
                 }
             };
-            vars.into_iter().filter(|(k, _)| is_core_var(k)).collect()
+            vars.into_iter()
+                .filter(|(k, _)| is_core_var(k) || matches_any(k, &policy.include))
+                .collect()
         }
     };
 
-    // Internal helper – does `name` match **any** pattern in `patterns`?
-    let matches_any = |name: &str, patterns: &[EnvironmentVariablePattern]| -> bool {
-        patterns.iter().any(|pattern| pattern.matches(name))
-    };
-
     // Step 2 – Apply the default exclude if not disabled.
     if !policy.ignore_default_excludes {
         let default_excludes = vec![
@@ -54,7 +99,8 @@ where
             EnvironmentVariablePattern::new_case_insensitive("*SECRET*"),
             EnvironmentVariablePattern::new_case_insensitive("*TOKEN*"),
         ];
-        env_map.retain(|k, _| !matches_any(k, &default_excludes));
+        env_map
+            .retain(|k, _| !matches_any(k, &default_excludes) || matches_any(k, &policy.include));
     }
 
     // Step 3 – Apply custom excludes.
@@ -248,4 +294,54 @@ mod tests {
         };
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn test_include_extends_inherit_and_beats_default_excludes() {
+        let vars = make_vars(&[
+            ("PATH", "/usr/bin"),
+            ("HOME", "/home"),
+            ("CARGO_HOME", "/cargo"),
+            ("GITHUB_TOKEN", "t"),
+            ("NPM_TOKEN", "n"),
+            ("FOO", "bar"),
+        ]);
+
+        let policy = ShellEnvironmentPolicy {
+            inherit: ShellEnvironmentPolicyInherit::None,
+            ignore_default_excludes: false,
+            include: vec![
+                EnvironmentVariablePattern::new_case_insensitive("cargo_*"),
+                EnvironmentVariablePattern::new_case_insensitive("GITHUB_TOKEN"),
+                EnvironmentVariablePattern::new_case_insensitive("PATH"),
+            ],
+            exclude: vec![EnvironmentVariablePattern::new_case_insensitive("PATH")],
+            ..Default::default()
+        };
+
+        let result = populate_env(vars, &policy);
+        // `exclude` still wins over `include`.
+        let expected: HashMap<String, String> = hashmap! {
+            "CARGO_HOME".to_string() => "/cargo".to_string(),
+            "GITHUB_TOKEN".to_string() => "t".to_string(),
+        };
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_describe_env_lists_names_without_values() {
+        let mut policy = ShellEnvironmentPolicy {
+            inherit: ShellEnvironmentPolicyInherit::Core,
+            ..Default::default()
+        };
+        policy.r#set.insert("CI".to_string(), "1".to_string());
+        let env = populate_env(
+            make_vars(&[("PATH", "/usr/bin"), ("HOME", "/home/user")]),
+            &policy,
+        );
+
+        assert_eq!(
+            describe_env(&policy, &env),
+            "inherit=core inherited=2 [HOME, PATH] set=1 [CI]"
+        );
+    }
 }
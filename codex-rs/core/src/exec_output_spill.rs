@@ -0,0 +1,228 @@
+//! Spilling of oversized command output to the thread's artifacts. Instead of
+//! only cutting out the middle of a long build log, the full output is saved
+//! as a text artifact and the model gets the usual head and tail together
+//! with a reference it can pass to the `read_artifact` tool to read any range
+//! of lines later.
+
+use tracing::warn;
+
+use crate::artifacts::ArtifactQuota;
+use crate::artifacts::load_artifact;
+use crate::artifacts::persist_bytes;
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::exec::StreamOutput;
+use crate::protocol::EventMsg;
+use crate::protocol::ToolArtifact;
+use crate::protocol::ToolArtifactEvent;
+use crate::truncate::TruncationPolicy;
+
+pub(crate) const READ_ARTIFACT_TOOL_NAME: &str = "read_artifact";
+
+const SPILL_MIME_TYPE: &str = "text/plain";
+
+/// Saves `output`, the aggregated output of call `call_id`, to the thread's
+/// artifacts when it is larger than the call's truncation budget `policy` and
+/// reports it with a `ToolArtifact` event. Returns the note to append to the
+/// truncated output, or `None` when nothing was saved. Output past the exec
+/// retention cap was never kept, so an artifact of capped output is named and
+/// described as truncated.
+pub(crate) async fn spill_exec_output(
+    session: &Session,
+    turn: &TurnContext,
+    call_id: &str,
+    output: &StreamOutput<String>,
+    policy: TruncationPolicy,
+) -> Option<String> {
+    let text = &output.text;
+    if !turn.tools_config.exec_output_artifacts || text.len() <= policy.byte_budget() {
+        return None;
+    }
+    let config = turn.client.config();
+    if config.incognito {
+        return None;
+    }
+    let saved = persist_bytes(
+        &config.codex_home,
+        &session.conversation_id,
+        &ArtifactQuota::from(&config.artifacts),
+        &spill_artifact_id(call_id),
+        if output.omitted_bytes > 0 {
+            "output-truncated.log"
+        } else {
+            "output.log"
+        },
+        SPILL_MIME_TYPE,
+        text.as_bytes(),
+    )
+    .await;
+    match saved {
+        Ok(Some(artifact)) => {
+            let note = spill_note(&artifact, text.lines().count(), output.omitted_bytes);
+            let event = EventMsg::ToolArtifact(ToolArtifactEvent {
+                call_id: call_id.to_string(),
+                artifact,
+            });
+            session.send_event(turn, event).await;
+            Some(note)
+        }
+        Ok(None) => {
+            warn!("output of call {call_id} does not fit the artifacts quota");
+            None
+        }
+        Err(err) => {
+            warn!("failed to save output of call {call_id}: {err}");
+            None
+        }
+    }
+}
+
+fn spill_artifact_id(call_id: &str) -> String {
+    format!("{call_id}-output")
+}
+
+/// Full output of call `call_id` if it was spilled to the thread's artifacts.
+pub(crate) async fn read_spilled_output(
+    session: &Session,
+    turn: &TurnContext,
+    call_id: &str,
+) -> Option<String> {
+    let config = turn.client.config();
+    let artifact = load_artifact(
+        &config.codex_home,
+        &session.conversation_id,
+        &spill_artifact_id(call_id),
+    )
+    .await
+    .ok()?;
+    tokio::fs::read_to_string(&artifact.path).await.ok()
+}
+
+fn spill_note(artifact: &ToolArtifact, total_lines: usize, omitted_bytes: usize) -> String {
+    let id = &artifact.id;
+    let saved = if omitted_bytes > 0 {
+        format!(
+            "output ({total_lines} lines) saved as artifact `{id}`; it is truncated: {omitted_bytes} bytes the command printed were not kept"
+        )
+    } else {
+        format!("full output ({total_lines} lines) saved as artifact `{id}`")
+    };
+    format!(
+        "[{saved}; call `{READ_ARTIFACT_TOOL_NAME}` with artifact_id \"{id}\" and a line range to read any part of it]"
+    )
+}
+
+/// Lines `start_line..=end_line` (1-based) of the text artifact `artifact_id`
+/// of this thread, formatted for the model and cut to the turn's budget.
+pub(crate) async fn read_artifact_lines(
+    session: &Session,
+    turn: &TurnContext,
+    artifact_id: &str,
+    start_line: Option<usize>,
+    end_line: Option<usize>,
+) -> Result<String, String> {
+    let config = turn.client.config();
+    let artifact = load_artifact(&config.codex_home, &session.conversation_id, artifact_id)
+        .await
+        .map_err(|err| format!("unknown artifact `{artifact_id}`: {err}"))?;
+    if !artifact.mime_type.starts_with("text/") {
+        return Err(format!(
+            "artifact `{artifact_id}` is not text ({})",
+            artifact.mime_type
+        ));
+    }
+    let text = tokio::fs::read_to_string(&artifact.path)
+        .await
+        .map_err(|err| format!("failed to read artifact `{artifact_id}`: {err}"))?;
+    format_line_range(
+        artifact_id,
+        &text,
+        start_line.unwrap_or(1),
+        end_line,
+        turn.truncation_policy.byte_budget(),
+    )
+}
+
+fn format_line_range(
+    artifact_id: &str,
+    text: &str,
+    start_line: usize,
+    end_line: Option<usize>,
+    max_bytes: usize,
+) -> Result<String, String> {
+    let lines: Vec<&str> = text.lines().collect();
+    let total = lines.len();
+    if start_line == 0 || start_line > total {
+        return Err(format!(
+            "start_line must be between 1 and {total} for artifact `{artifact_id}`"
+        ));
+    }
+    let end_line = end_line.unwrap_or(total).min(total);
+    if end_line < start_line {
+        return Err("end_line must not be before start_line".to_string());
+    }
+
+    let mut body = String::new();
+    let mut last = start_line - 1;
+    for (index, line) in lines[start_line - 1..end_line].iter().enumerate() {
+        let number = start_line + index;
+        let formatted = format!("L{number}: {line}\n");
+        // Always return at least one line, however long.
+        if number > start_line && body.len() + formatted.len() > max_bytes {
+            break;
+        }
+        body.push_str(&formatted);
+        last = number;
+    }
+    let more = if last < end_line {
+        format!("; call again with start_line {} for the rest", last + 1)
+    } else {
+        String::new()
+    };
+    Ok(format!(
+        "[artifact `{artifact_id}`: lines {start_line}-{last} of {total}{more}]\n{body}"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn notes_say_when_the_saved_output_is_truncated() {
+        let artifact = ToolArtifact {
+            id: "call-output".to_string(),
+            name: "output-truncated.log".to_string(),
+            mime_type: SPILL_MIME_TYPE.to_string(),
+            size_bytes: 1024,
+            path: std::path::PathBuf::from("call-output"),
+        };
+
+        assert_eq!(
+            spill_note(&artifact, 20, 4096),
+            "[output (20 lines) saved as artifact `call-output`; it is truncated: 4096 bytes the command printed were not kept; call `read_artifact` with artifact_id \"call-output\" and a line range to read any part of it]"
+        );
+    }
+
+    #[test]
+    fn line_ranges_are_numbered_and_cut_to_the_budget() {
+        let text = "one\ntwo\nthree\nfour\n";
+
+        assert_eq!(
+            format_line_range("call-output", text, 2, Some(3), 1024),
+            Ok("[artifact `call-output`: lines 2-3 of 4]\nL2: two\nL3: three\n".to_string())
+        );
+        assert_eq!(
+            format_line_range("call-output", text, 1, None, 16),
+            Ok(
+                "[artifact `call-output`: lines 1-2 of 4; call again with start_line 3 for the rest]\nL1: one\nL2: two\n"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            format_line_range("call-output", text, 5, None, 1024),
+            Err("start_line must be between 1 and 4 for artifact `call-output`".to_string())
+        );
+    }
+}
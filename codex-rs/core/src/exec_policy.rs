@@ -9,6 +9,7 @@ use crate::config_loader::ConfigLayerStack;
 use crate::config_loader::ConfigLayerStackOrdering;
 use crate::is_dangerous_command::command_might_be_dangerous;
 use crate::is_safe_command::is_known_safe_command;
+use crate::safety::match_approval_rule;
 use codex_execpolicy::AmendError;
 use codex_execpolicy::Decision;
 use codex_execpolicy::Error as ExecPolicyRuleError;
@@ -17,6 +18,7 @@ use codex_execpolicy::Policy;
 use codex_execpolicy::PolicyParser;
 use codex_execpolicy::RuleMatch;
 use codex_execpolicy::blocking_append_allow_prefix_rule;
+use codex_protocol::approvals::ApprovalRuleDecision;
 use codex_protocol::approvals::ExecPolicyAmendment;
 use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::SandboxPolicy;
@@ -25,6 +27,7 @@ use tokio::fs;
 use tokio::task::spawn_blocking;
 
 use crate::bash::parse_shell_lc_plain_commands;
+use crate::config::types::ApprovalRule;
 use crate::features::Feature;
 use crate::features::Features;
 use crate::sandboxing::SandboxPermissions;
@@ -85,15 +88,24 @@ pub enum ExecPolicyUpdateError {
 
 pub(crate) struct ExecPolicyManager {
     policy: ArcSwap<Policy>,
+    approval_rules: Vec<ApprovalRule>,
 }
 
 impl ExecPolicyManager {
     pub(crate) fn new(policy: Arc<Policy>) -> Self {
         Self {
             policy: ArcSwap::from(policy),
+            approval_rules: Vec::new(),
         }
     }
 
+    /// Config-defined `[[approval_rules]]`, consulted before the built-in
+    /// heuristics for every command.
+    pub(crate) fn with_approval_rules(mut self, approval_rules: Vec<ApprovalRule>) -> Self {
+        self.approval_rules = approval_rules;
+        self
+    }
+
     pub(crate) async fn load(
         features: &Features,
         config_stack: &ConfigLayerStack,
@@ -117,6 +129,7 @@ impl ExecPolicyManager {
         approval_policy: AskForApproval,
         sandbox_policy: &SandboxPolicy,
         sandbox_permissions: SandboxPermissions,
+        cwd: &Path,
     ) -> ExecApprovalRequirement {
         let exec_policy = self.current();
         let commands =
@@ -130,16 +143,55 @@ impl ExecPolicyManager {
             )
         };
         let evaluation = exec_policy.check_multiple(commands.iter(), &exec_policy_fallback);
+        let matched_approval_rule = match_approval_rule(&self.approval_rules, command, cwd);
+        // An approval rule replaces the heuristics, but explicit execpolicy
+        // rules still apply: the stricter of the two decisions wins.
+        let (decision, decided_by_rule) = match &matched_approval_rule {
+            Some(rule) => {
+                let rule_decision = approval_rule_decision(rule.decision);
+                let policy_decision = evaluation
+                    .matched_rules
+                    .iter()
+                    .filter(|rule_match| is_policy_match(rule_match))
+                    .map(RuleMatch::decision)
+                    .max();
+                match policy_decision {
+                    Some(policy_decision) if policy_decision > rule_decision => {
+                        (policy_decision, false)
+                    }
+                    _ => (rule_decision, true),
+                }
+            }
+            None => (evaluation.decision, false),
+        };
 
-        match evaluation.decision {
+        match decision {
             Decision::Forbidden => ExecApprovalRequirement::Forbidden {
-                reason: derive_forbidden_reason(command, &evaluation),
+                reason: match &matched_approval_rule {
+                    Some(rule) if decided_by_rule => format!(
+                        "`{}` rejected: forbidden by approval rule `{}`",
+                        render_shlex_command(command),
+                        rule.name
+                    ),
+                    _ => derive_forbidden_reason(command, &evaluation),
+                },
             },
             Decision::Prompt => {
                 if matches!(approval_policy, AskForApproval::Never) {
                     ExecApprovalRequirement::Forbidden {
                         reason: PROMPT_CONFLICT_REASON.to_string(),
                     }
+                } else if let Some(rule) = matched_approval_rule.filter(|_| decided_by_rule) {
+                    ExecApprovalRequirement::NeedsApproval {
+                        reason: Some(format!(
+                            "`{}` requires approval by approval rule `{}`",
+                            render_shlex_command(command),
+                            rule.name
+                        )),
+                        // An execpolicy amendment cannot override the rule.
+                        proposed_execpolicy_amendment: None,
+                        matched_approval_rule: Some(rule),
+                    }
                 } else {
                     ExecApprovalRequirement::NeedsApproval {
                         reason: derive_prompt_reason(command, &evaluation),
@@ -150,6 +202,7 @@ impl ExecPolicyManager {
                         } else {
                             None
                         },
+                        matched_approval_rule: None,
                     }
                 }
             }
@@ -324,6 +377,14 @@ pub fn render_decision_for_unmatched_command(
     }
 }
 
+fn approval_rule_decision(decision: ApprovalRuleDecision) -> Decision {
+    match decision {
+        ApprovalRuleDecision::Allow => Decision::Allow,
+        ApprovalRuleDecision::Prompt => Decision::Prompt,
+        ApprovalRuleDecision::Forbidden => Decision::Forbidden,
+    }
+}
+
 fn default_policy_path(codex_home: &Path) -> PathBuf {
     codex_home.join(RULES_DIR_NAME).join(DEFAULT_POLICY_FILE)
 }
@@ -507,6 +568,7 @@ mod tests {
     use crate::features::Feature;
     use crate::features::Features;
     use codex_app_server_protocol::ConfigLayerSource;
+    use codex_protocol::approvals::MatchedApprovalRule;
     use codex_protocol::protocol::AskForApproval;
     use codex_protocol::protocol::SandboxPolicy;
     use codex_utils_absolute_path::AbsolutePathBuf;
@@ -719,6 +781,7 @@ prefix_rule(pattern=["rm"], decision="forbidden")
                 AskForApproval::OnRequest,
                 &SandboxPolicy::DangerFullAccess,
                 SandboxPermissions::UseDefault,
+                Path::new("/"),
             )
             .await;
 
@@ -757,6 +820,7 @@ prefix_rule(
                 AskForApproval::OnRequest,
                 &SandboxPolicy::DangerFullAccess,
                 SandboxPermissions::UseDefault,
+                Path::new("/"),
             )
             .await;
 
@@ -786,6 +850,7 @@ prefix_rule(
                 AskForApproval::OnRequest,
                 &SandboxPolicy::DangerFullAccess,
                 SandboxPermissions::UseDefault,
+                Path::new("/"),
             )
             .await;
 
@@ -794,6 +859,7 @@ prefix_rule(
             ExecApprovalRequirement::NeedsApproval {
                 reason: Some("`rm` requires approval by policy".to_string()),
                 proposed_execpolicy_amendment: None,
+                matched_approval_rule: None,
             }
         );
     }
@@ -816,6 +882,7 @@ prefix_rule(
                 AskForApproval::Never,
                 &SandboxPolicy::DangerFullAccess,
                 SandboxPermissions::UseDefault,
+                Path::new("/"),
             )
             .await;
 
@@ -827,6 +894,70 @@ prefix_rule(
         );
     }
 
+    #[tokio::test]
+    async fn approval_rules_override_heuristics_but_not_policy_rules() {
+        let policy_src = r#"prefix_rule(pattern=["git", "push", "--force"], decision="forbidden")"#;
+        let mut parser = PolicyParser::new();
+        parser
+            .parse("test.rules", policy_src)
+            .expect("parse policy");
+        let rule = |prefix: &[&str], decision| ApprovalRule {
+            name: None,
+            command_classes: None,
+            command_prefix: Some(prefix.iter().map(ToString::to_string).collect()),
+            paths: None,
+            network: None,
+            decision,
+        };
+        let manager = ExecPolicyManager::new(Arc::new(parser.build())).with_approval_rules(vec![
+            rule(&["git", "push"], ApprovalRuleDecision::Prompt),
+            rule(&["cargo"], ApprovalRuleDecision::Allow),
+        ]);
+        let requirement_for = |command: &[&str], approval_policy| {
+            let command = command.iter().map(ToString::to_string).collect::<Vec<_>>();
+            let manager = &manager;
+            async move {
+                manager
+                    .create_exec_approval_requirement_for_command(
+                        &Features::with_defaults(),
+                        &command,
+                        approval_policy,
+                        &SandboxPolicy::DangerFullAccess,
+                        SandboxPermissions::UseDefault,
+                        Path::new("/"),
+                    )
+                    .await
+            }
+        };
+
+        assert_eq!(
+            requirement_for(&["git", "push"], AskForApproval::OnRequest).await,
+            ExecApprovalRequirement::NeedsApproval {
+                reason: Some(
+                    "`git push` requires approval by approval rule `approval_rules[0]`".to_string()
+                ),
+                proposed_execpolicy_amendment: None,
+                matched_approval_rule: Some(MatchedApprovalRule {
+                    name: "approval_rules[0]".to_string(),
+                    decision: ApprovalRuleDecision::Prompt,
+                }),
+            }
+        );
+        assert_eq!(
+            requirement_for(&["cargo", "build"], AskForApproval::UnlessTrusted).await,
+            ExecApprovalRequirement::Skip {
+                bypass_sandbox: false,
+                proposed_execpolicy_amendment: None,
+            }
+        );
+        assert_eq!(
+            requirement_for(&["git", "push", "--force"], AskForApproval::OnRequest).await,
+            ExecApprovalRequirement::Forbidden {
+                reason: "`git push --force` rejected: policy forbids commands starting with `git push --force`".to_string(),
+            }
+        );
+    }
+
     #[tokio::test]
     async fn exec_approval_requirement_falls_back_to_heuristics() {
         let command = vec!["cargo".to_string(), "build".to_string()];
@@ -839,6 +970,7 @@ prefix_rule(
                 AskForApproval::UnlessTrusted,
                 &SandboxPolicy::ReadOnly,
                 SandboxPermissions::UseDefault,
+                Path::new("/"),
             )
             .await;
 
@@ -846,7 +978,8 @@ prefix_rule(
             requirement,
             ExecApprovalRequirement::NeedsApproval {
                 reason: None,
-                proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(command))
+                proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(command)),
+                matched_approval_rule: None,
             }
         );
     }
@@ -873,13 +1006,15 @@ prefix_rule(
                     AskForApproval::UnlessTrusted,
                     &SandboxPolicy::DangerFullAccess,
                     SandboxPermissions::UseDefault,
+                    Path::new("/"),
                 )
                 .await,
             ExecApprovalRequirement::NeedsApproval {
                 reason: None,
                 proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(vec![
                     "orange".to_string()
-                ]))
+                ])),
+                matched_approval_rule: None,
             }
         );
     }
@@ -947,6 +1082,7 @@ prefix_rule(
                 AskForApproval::UnlessTrusted,
                 &SandboxPolicy::ReadOnly,
                 SandboxPermissions::UseDefault,
+                Path::new("/"),
             )
             .await;
 
@@ -954,7 +1090,8 @@ prefix_rule(
             requirement,
             ExecApprovalRequirement::NeedsApproval {
                 reason: None,
-                proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(command))
+                proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(command)),
+                matched_approval_rule: None,
             }
         );
     }
@@ -974,6 +1111,7 @@ prefix_rule(
                 AskForApproval::UnlessTrusted,
                 &SandboxPolicy::ReadOnly,
                 SandboxPermissions::UseDefault,
+                Path::new("/"),
             )
             .await;
 
@@ -982,6 +1120,7 @@ prefix_rule(
             ExecApprovalRequirement::NeedsApproval {
                 reason: None,
                 proposed_execpolicy_amendment: None,
+                matched_approval_rule: None,
             }
         );
     }
@@ -1004,6 +1143,7 @@ prefix_rule(
                 AskForApproval::OnRequest,
                 &SandboxPolicy::DangerFullAccess,
                 SandboxPermissions::UseDefault,
+                Path::new("/"),
             )
             .await;
 
@@ -1012,6 +1152,7 @@ prefix_rule(
             ExecApprovalRequirement::NeedsApproval {
                 reason: Some("`rm` requires approval by policy".to_string()),
                 proposed_execpolicy_amendment: None,
+                matched_approval_rule: None,
             }
         );
     }
@@ -1031,6 +1172,7 @@ prefix_rule(
                 AskForApproval::UnlessTrusted,
                 &SandboxPolicy::ReadOnly,
                 SandboxPermissions::UseDefault,
+                Path::new("/"),
             )
             .await;
 
@@ -1042,6 +1184,7 @@ prefix_rule(
                     "cargo".to_string(),
                     "build".to_string()
                 ])),
+                matched_approval_rule: None,
             }
         );
     }
@@ -1069,6 +1212,7 @@ prefix_rule(
                     AskForApproval::UnlessTrusted,
                     &SandboxPolicy::ReadOnly,
                     SandboxPermissions::UseDefault,
+                    Path::new("/"),
                 )
                 .await,
             ExecApprovalRequirement::NeedsApproval {
@@ -1076,6 +1220,7 @@ prefix_rule(
                 proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(vec![
                     "apple".to_string()
                 ])),
+                matched_approval_rule: None,
             }
         );
     }
@@ -1092,6 +1237,7 @@ prefix_rule(
                 AskForApproval::OnRequest,
                 &SandboxPolicy::ReadOnly,
                 SandboxPermissions::UseDefault,
+                Path::new("/"),
             )
             .await;
 
@@ -1122,6 +1268,7 @@ prefix_rule(
                 AskForApproval::OnRequest,
                 &SandboxPolicy::ReadOnly,
                 SandboxPermissions::UseDefault,
+                Path::new("/"),
             )
             .await;
 
@@ -1169,6 +1316,7 @@ prefix_rule(
                 ExecApprovalRequirement::NeedsApproval {
                     reason: None,
                     proposed_execpolicy_amendment: expected_amendment.clone(),
+                    matched_approval_rule: None,
                 },
             )
         } else {
@@ -1189,6 +1337,7 @@ prefix_rule(
                     AskForApproval::OnRequest,
                     &SandboxPolicy::ReadOnly,
                     permissions,
+                    Path::new("/"),
                 )
                 .await,
             "{pwsh_approval_reason}"
@@ -1204,6 +1353,7 @@ prefix_rule(
                     "-rf",
                     "/important/data",
                 ]))),
+                matched_approval_rule: None,
             },
             policy
                 .create_exec_approval_requirement_for_command(
@@ -1212,6 +1362,7 @@ prefix_rule(
                     AskForApproval::OnRequest,
                     &SandboxPolicy::ReadOnly,
                     permissions,
+                    Path::new("/"),
                 )
                 .await,
             r#"On all platforms, a forbidden command should require approval
@@ -1231,6 +1382,7 @@ prefix_rule(
                     AskForApproval::Never,
                     &SandboxPolicy::ReadOnly,
                     permissions,
+                    Path::new("/"),
                 )
                 .await,
             r#"On all platforms, a forbidden command should require approval
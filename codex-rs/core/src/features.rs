@@ -109,6 +109,32 @@ pub enum Feature {
     CollaborationModes,
     /// Use the Responses API WebSocket transport for OpenAI by default.
     ResponsesWebsockets,
+    /// Generate a session title from the first exchange.
+    AutoSessionTitle,
+    /// Include the current contents of stale files in apply_patch warnings.
+    RefreshStaleReads,
+    /// Run tool calls that the model wrote out as text instead of invoking.
+    ConvertHallucinatedToolCalls,
+    /// Offer the transactional `bulk_edit` tool.
+    BulkEdit,
+    /// Deliver oversized tool outputs in chunks the model can page through.
+    ChunkedToolOutput,
+    /// Save oversized command output as an artifact the model can read back.
+    ExecOutputArtifacts,
+    /// Conversation clipboard of named text blobs referenced as `{{clip:<name>}}`.
+    Clipboard,
+    /// Offer the `repl` tool backed by a persistent Python or Node interpreter.
+    Repl,
+    /// Mask likely secrets in tool outputs before they are sent to the model.
+    SecretMasking,
+    /// Add `.codex/knowledge` snippets whose keywords appear in the prompt.
+    Knowledge,
+    /// Offer the `delegate_local` tool that hands subtasks to a local model.
+    DelegateLocal,
+    /// Summarize older turns while the session is idle, ahead of auto-compaction.
+    BackgroundCompaction,
+    /// Record tool versions (`git --version`, ...) in environment snapshots.
+    EnvironmentToolVersions,
 }
 
 impl Feature {
@@ -124,6 +150,26 @@ impl Feature {
         self.info().default_enabled
     }
 
+    /// Whether the feature is consulted anew for every turn, so that
+    /// `Op::SetFeature` can change it while a session is running. The others
+    /// are read once when the session starts.
+    pub fn can_change_at_runtime(self) -> bool {
+        matches!(
+            self,
+            Feature::GhostCommit
+                | Feature::RefreshStaleReads
+                | Feature::ConvertHallucinatedToolCalls
+                | Feature::BulkEdit
+                | Feature::ChunkedToolOutput
+                | Feature::ExecOutputArtifacts
+                | Feature::Clipboard
+                | Feature::Repl
+                | Feature::SecretMasking
+                | Feature::Knowledge
+                | Feature::DelegateLocal
+        )
+    }
+
     fn info(self) -> &'static FeatureSpec {
         FEATURES
             .iter()
@@ -287,10 +333,63 @@ impl Features {
     pub fn enabled_features(&self) -> Vec<Feature> {
         self.enabled.iter().copied().collect()
     }
+
+    /// Checks that `feature` may be switched to `enabled` in a running
+    /// session given the features currently enabled.
+    pub fn check_runtime_change(&self, feature: Feature, enabled: bool) -> Result<(), String> {
+        let key = feature.key();
+        if !feature.can_change_at_runtime() {
+            return Err(format!(
+                "`{key}` is only read when a session starts; set it under [features] instead."
+            ));
+        }
+        if enabled {
+            if let Some((_, required)) = FEATURE_REQUIRES
+                .iter()
+                .find(|(dependent, required)| *dependent == feature && !self.enabled(*required))
+            {
+                return Err(format!(
+                    "`{key}` requires `{}` to be enabled.",
+                    required.key()
+                ));
+            }
+        } else if let Some((dependent, _)) = FEATURE_REQUIRES
+            .iter()
+            .find(|(dependent, required)| *required == feature && self.enabled(*dependent))
+        {
+            return Err(format!(
+                "`{}` requires `{key}`; disable it first.",
+                dependent.key()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks the features resolved from the config against
+    /// [`FEATURE_REQUIRES`], the same constraints `Op::SetFeature` enforces.
+    pub fn check_requirements(&self) -> Result<(), String> {
+        match FEATURE_REQUIRES
+            .iter()
+            .find(|(dependent, required)| self.enabled(*dependent) && !self.enabled(*required))
+        {
+            Some((dependent, required)) => Err(format!(
+                "`{}` under [features] requires `{}` to be enabled.",
+                dependent.key(),
+                required.key()
+            )),
+            None => Ok(()),
+        }
+    }
 }
 
+/// `(feature, required)`: the first feature does nothing unless the second
+/// one is enabled as well. Checked when the config is loaded and on every
+/// `Op::SetFeature`.
+const FEATURE_REQUIRES: &[(Feature, Feature)] =
+    &[(Feature::WindowsSandboxElevated, Feature::WindowsSandbox)];
+
 /// Keys accepted in `[features]` tables.
-fn feature_for_key(key: &str) -> Option<Feature> {
+pub(crate) fn feature_for_key(key: &str) -> Option<Feature> {
     for spec in FEATURES {
         if spec.key == key {
             return Some(spec.id);
@@ -469,4 +568,125 @@ pub const FEATURES: &[FeatureSpec] = &[
         stage: Stage::Beta,
         default_enabled: false,
     },
+    FeatureSpec {
+        id: Feature::AutoSessionTitle,
+        key: "auto_session_title",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::RefreshStaleReads,
+        key: "refresh_stale_reads",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::ConvertHallucinatedToolCalls,
+        key: "convert_hallucinated_tool_calls",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::BulkEdit,
+        key: "bulk_edit",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::ChunkedToolOutput,
+        key: "chunked_tool_output",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::ExecOutputArtifacts,
+        key: "exec_output_artifacts",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::Clipboard,
+        key: "clipboard",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::Repl,
+        key: "repl",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::SecretMasking,
+        key: "secret_masking",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::Knowledge,
+        key: "knowledge",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::DelegateLocal,
+        key: "delegate_local",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::BackgroundCompaction,
+        key: "background_compaction",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::EnvironmentToolVersions,
+        key: "environment_tool_versions",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn runtime_changes_respect_startup_only_features() {
+        let mut features = Features::with_defaults();
+        features.enable(Feature::ChunkedToolOutput);
+
+        assert_eq!(features.check_runtime_change(Feature::Repl, true), Ok(()));
+        // Chunked delivery and artifacts work together: output is spilled
+        // only when it is not delivered in chunks.
+        assert_eq!(
+            features.check_runtime_change(Feature::ExecOutputArtifacts, true),
+            Ok(())
+        );
+        assert_eq!(
+            features.check_runtime_change(Feature::ShellSnapshot, true),
+            Err(
+                "`shell_snapshot` is only read when a session starts; set it under [features] instead."
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn requirements_are_checked_on_the_resolved_features() {
+        let mut features = Features::with_defaults();
+        features.disable(Feature::WindowsSandbox);
+        features.enable(Feature::WindowsSandboxElevated);
+        assert_eq!(
+            features.check_requirements(),
+            Err("`elevated_windows_sandbox` under [features] requires \
+                 `experimental_windows_sandbox` to be enabled."
+                .to_string())
+        );
+
+        features.enable(Feature::WindowsSandbox);
+        assert_eq!(features.check_requirements(), Ok(()));
+    }
+}
@@ -0,0 +1,256 @@
+//! Heartbeat events for turns that go quiet, e.g. while a long command runs
+//! or a reasoning model thinks before streaming anything.
+//!
+//! [`TurnActivity`] records when the session last sent an event and which
+//! tool calls are running; [`run_heartbeats`] runs next to each turn and
+//! sends a [`HeartbeatEvent`] every interval while the stream is otherwise
+//! silent. Heartbeats themselves do not count as activity, so `silent_ms`
+//! keeps growing across them.
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use codex_protocol::protocol::ActiveToolCall;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::HeartbeatEvent;
+use codex_protocol::protocol::TurnPhase;
+use indexmap::IndexMap;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+
+/// What the running turn is doing, shared by the session and its tool calls.
+pub(crate) struct TurnActivity {
+    state: Mutex<ActivityState>,
+}
+
+struct ActivityState {
+    turn_started: Instant,
+    /// When the last event other than a heartbeat was sent.
+    last_event: Instant,
+    /// When the last heartbeat was sent, if any since `last_event`.
+    last_heartbeat: Option<Instant>,
+    /// Running tool calls by call id, in the order they started.
+    tools: IndexMap<String, (String, Instant)>,
+}
+
+impl TurnActivity {
+    pub(crate) fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            state: Mutex::new(ActivityState {
+                turn_started: now,
+                last_event: now,
+                last_heartbeat: None,
+                tools: IndexMap::new(),
+            }),
+        }
+    }
+
+    pub(crate) fn turn_started(&self) {
+        let now = Instant::now();
+        self.with_state(|state| {
+            state.turn_started = now;
+            state.last_event = now;
+            state.last_heartbeat = None;
+            state.tools.clear();
+        });
+    }
+
+    pub(crate) fn record_event(&self) {
+        let now = Instant::now();
+        self.with_state(|state| {
+            state.last_event = now;
+            state.last_heartbeat = None;
+        });
+    }
+
+    /// Marks a tool call as running until the returned guard is dropped.
+    pub(crate) fn tool_started(
+        self: &Arc<Self>,
+        call_id: &str,
+        tool_name: &str,
+    ) -> ActiveToolGuard {
+        let now = Instant::now();
+        self.with_state(|state| {
+            state
+                .tools
+                .insert(call_id.to_string(), (tool_name.to_string(), now));
+        });
+        ActiveToolGuard {
+            activity: Arc::clone(self),
+            call_id: call_id.to_string(),
+        }
+    }
+
+    /// The heartbeat to send at `now`, or how long to wait before checking
+    /// again when neither an event nor a heartbeat is `interval` old yet.
+    fn heartbeat_at(&self, now: Instant, interval: Duration) -> Result<HeartbeatEvent, Duration> {
+        self.with_state(|state| {
+            let silent = now.saturating_duration_since(state.last_event);
+            let last_sent = state.last_heartbeat.unwrap_or(state.last_event);
+            let since_last_sent = now.saturating_duration_since(last_sent);
+            if since_last_sent < interval {
+                return Err(interval - since_last_sent);
+            }
+            state.last_heartbeat = Some(now);
+            let active_tools: Vec<ActiveToolCall> = state
+                .tools
+                .iter()
+                .map(|(call_id, (tool_name, started))| ActiveToolCall {
+                    call_id: call_id.clone(),
+                    tool_name: tool_name.clone(),
+                    elapsed_ms: millis(now.saturating_duration_since(*started)),
+                })
+                .collect();
+            Ok(HeartbeatEvent {
+                phase: if active_tools.is_empty() {
+                    TurnPhase::WaitingForModel
+                } else {
+                    TurnPhase::RunningTools
+                },
+                turn_elapsed_ms: millis(now.saturating_duration_since(state.turn_started)),
+                silent_ms: millis(silent),
+                active_tools,
+            })
+        })
+    }
+
+    fn with_state<T>(&self, f: impl FnOnce(&mut ActivityState) -> T) -> T {
+        let mut state = match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        f(&mut state)
+    }
+}
+
+/// Removes its tool call from [`TurnActivity`] when the call finishes or is
+/// cancelled.
+pub(crate) struct ActiveToolGuard {
+    activity: Arc<TurnActivity>,
+    call_id: String,
+}
+
+impl Drop for ActiveToolGuard {
+    fn drop(&mut self) {
+        self.activity.with_state(|state| {
+            state.tools.shift_remove(&self.call_id);
+        });
+    }
+}
+
+/// Sends heartbeats for `turn_context` until the future is dropped.
+pub(crate) async fn run_heartbeats(
+    session: Arc<Session>,
+    turn_context: Arc<TurnContext>,
+    interval: Duration,
+) {
+    let activity = session.turn_activity();
+    loop {
+        match activity.heartbeat_at(Instant::now(), interval) {
+            Ok(heartbeat) => {
+                session
+                    .send_event(turn_context.as_ref(), EventMsg::Heartbeat(heartbeat))
+                    .await;
+            }
+            Err(wait) => tokio::time::sleep(wait).await,
+        }
+    }
+}
+
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn heartbeat_waits_for_the_interval_and_lists_running_tools() {
+        let activity = Arc::new(TurnActivity::new());
+        let interval = Duration::from_secs(15);
+        let start = Instant::now();
+        activity.turn_started();
+
+        assert!(
+            activity
+                .heartbeat_at(start + Duration::from_secs(5), interval)
+                .is_err()
+        );
+
+        let shell = activity.tool_started("call-1", "shell");
+        let _read = activity.tool_started("call-2", "read_file");
+        let heartbeat = activity
+            .heartbeat_at(Instant::now() + Duration::from_secs(20), interval)
+            .expect("silent past the interval");
+        assert_eq!(heartbeat.phase, TurnPhase::RunningTools);
+        assert_eq!(
+            heartbeat
+                .active_tools
+                .iter()
+                .map(|tool| tool.tool_name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["shell", "read_file"]
+        );
+        assert!(heartbeat.silent_ms >= 20_000);
+
+        drop(shell);
+        activity.record_event();
+        assert!(activity.heartbeat_at(Instant::now(), interval).is_err());
+        let heartbeat = activity
+            .heartbeat_at(Instant::now() + interval, interval)
+            .expect("silent for the interval");
+        assert_eq!(heartbeat.active_tools.len(), 1);
+    }
+
+    #[test]
+    fn silent_turn_sends_one_heartbeat_per_interval_with_growing_silence() {
+        let activity = Arc::new(TurnActivity::new());
+        let interval = Duration::from_secs(15);
+        activity.turn_started();
+        let start = Instant::now();
+
+        let first = activity
+            .heartbeat_at(start + Duration::from_secs(15), interval)
+            .expect("silent for the interval");
+        assert_eq!(
+            activity.heartbeat_at(start + Duration::from_secs(20), interval),
+            Err(Duration::from_secs(10))
+        );
+        let second = activity
+            .heartbeat_at(start + Duration::from_secs(30), interval)
+            .expect("another interval passed");
+
+        assert!(first.silent_ms >= 15_000, "{first:?}");
+        assert!(second.silent_ms >= 30_000, "{second:?}");
+        assert!(second.turn_elapsed_ms >= 30_000, "{second:?}");
+
+        activity.record_event();
+        assert!(
+            activity
+                .heartbeat_at(Instant::now() + Duration::from_secs(5), interval)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn heartbeat_without_tools_waits_for_the_model() {
+        let activity = Arc::new(TurnActivity::new());
+        activity.turn_started();
+
+        let heartbeat = activity
+            .heartbeat_at(
+                Instant::now() + Duration::from_secs(30),
+                Duration::from_secs(15),
+            )
+            .expect("silent past the interval");
+
+        assert_eq!(heartbeat.phase, TurnPhase::WaitingForModel);
+        assert_eq!(heartbeat.active_tools, Vec::new());
+    }
+}
@@ -0,0 +1,794 @@
+//! Portable archives of `CODEX_HOME` for moving to another machine.
+//!
+//! [`export_home`] packages the selected [`HomeSection`]s into a
+//! [`HomeArchive`], a single JSON document with one entry per file. Login
+//! state (`auth.json`) is never exported, and the config values known to hold
+//! credentials (bearer tokens, MCP server `env`, header tables, and
+//! `shell_environment_policy.set`) are left out of `config.toml` and listed
+//! in [`HomeArchive::redacted_keys`] so they can be set again on the new
+//! machine.
+//!
+//! [`import_home`] merges an archive into an existing home without
+//! clobbering it: missing files and config keys are added, identical ones are
+//! skipped, and ones that differ are reported as conflicts and kept unless
+//! [`HomeImportOptions::overwrite`] is set.
+
+use std::fs;
+use std::io;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+use base64::Engine;
+use serde::Deserialize;
+use serde::Serialize;
+use toml_edit::DocumentMut;
+use toml_edit::Item as TomlItem;
+use toml_edit::TableLike;
+use walkdir::WalkDir;
+
+use crate::config::CONFIG_TOML_FILE;
+use crate::path_utils::write_atomically;
+use crate::project_doc::DEFAULT_PROJECT_DOC_FILENAME;
+use crate::project_doc::LOCAL_PROJECT_DOC_FILENAME;
+use crate::rollout::ARCHIVED_SESSIONS_SUBDIR;
+use crate::rollout::SESSIONS_SUBDIR;
+use crate::session_templates::TEMPLATES_DIR;
+
+/// Version of the archive format written by [`export_home`].
+pub const HOME_ARCHIVE_VERSION: u32 = 1;
+
+/// Top-level `config.toml` table holding the trust level of each project.
+const PROJECTS_KEY: &str = "projects";
+
+/// Bundled skills, installed again by every Codex version.
+const SYSTEM_SKILLS_DIR: &str = ".system";
+
+/// Config tables whose values are passed to MCP servers, providers, and
+/// commands verbatim and commonly hold credentials. `*` matches any key.
+const SECRET_TABLES: &[&[&str]] = &[
+    &["mcp_servers", "*", "env"],
+    &["mcp_servers", "*", "http_headers"],
+    &["mcp_servers", "*", "env_http_headers"],
+    &["model_providers", "*", "http_headers"],
+    &["model_providers", "*", "env_http_headers"],
+    &["shell_environment_policy", "set"],
+];
+
+/// Config keys holding a credential themselves.
+const SECRET_KEYS: &[&[&str]] = &[
+    &["mcp_servers", "*", "bearer_token"],
+    &["model_providers", "*", "experimental_bearer_token"],
+];
+
+/// Parts of `CODEX_HOME` that can be archived.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HomeSection {
+    /// `config.toml`, including profiles, MCP servers and `[agents]`, but not
+    /// the trusted projects.
+    Config,
+    /// The `[projects]` table of `config.toml`.
+    TrustedProjects,
+    /// `AGENTS.md` and `AGENTS.override.md`.
+    Instructions,
+    /// Custom prompts in `prompts/`.
+    Prompts,
+    /// User skills in `skills/`.
+    Skills,
+    /// Session templates in `templates/`.
+    Templates,
+    /// Rollouts in `sessions/` and `archived_sessions/`.
+    Sessions,
+}
+
+impl HomeSection {
+    /// Sections exported unless others are asked for. Sessions are left out
+    /// since they can be large and may contain secrets from tool output.
+    pub const DEFAULT: &[HomeSection] = &[
+        HomeSection::Config,
+        HomeSection::TrustedProjects,
+        HomeSection::Instructions,
+        HomeSection::Prompts,
+        HomeSection::Skills,
+        HomeSection::Templates,
+    ];
+
+    /// Files and directories, relative to `CODEX_HOME`, that hold the
+    /// section. `config.toml` is handled separately.
+    fn paths(self) -> &'static [&'static str] {
+        match self {
+            HomeSection::Config | HomeSection::TrustedProjects => &[],
+            HomeSection::Instructions => {
+                &[DEFAULT_PROJECT_DOC_FILENAME, LOCAL_PROJECT_DOC_FILENAME]
+            }
+            HomeSection::Prompts => &["prompts"],
+            HomeSection::Skills => &["skills"],
+            HomeSection::Templates => &[TEMPLATES_DIR],
+            HomeSection::Sessions => &[SESSIONS_SUBDIR, ARCHIVED_SESSIONS_SUBDIR],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HomeArchive {
+    pub version: u32,
+    /// RFC 3339 time of the export.
+    pub created_at: String,
+    pub sections: Vec<HomeSection>,
+    pub files: Vec<ArchivedFile>,
+    /// Dotted `config.toml` keys left out because they looked like secrets.
+    pub redacted_keys: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedFile {
+    /// Path relative to `CODEX_HOME`, with `/` separators.
+    pub path: String,
+    pub encoding: FileEncoding,
+    pub data: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileEncoding {
+    Utf8,
+    /// Used for files that are not valid UTF-8.
+    Base64,
+}
+
+impl ArchivedFile {
+    fn new(path: String, contents: Vec<u8>) -> Self {
+        match String::from_utf8(contents) {
+            Ok(data) => Self {
+                path,
+                encoding: FileEncoding::Utf8,
+                data,
+            },
+            Err(err) => Self {
+                path,
+                encoding: FileEncoding::Base64,
+                data: base64::engine::general_purpose::STANDARD.encode(err.into_bytes()),
+            },
+        }
+    }
+
+    fn contents(&self) -> io::Result<Vec<u8>> {
+        match self.encoding {
+            FileEncoding::Utf8 => Ok(self.data.as_bytes().to_vec()),
+            FileEncoding::Base64 => base64::engine::general_purpose::STANDARD
+                .decode(&self.data)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HomeImportOptions {
+    /// Replace local files and config values that differ from the archive
+    /// instead of keeping them.
+    pub overwrite: bool,
+    /// Report what would change without writing anything.
+    pub dry_run: bool,
+}
+
+/// What an import changed. Entries are file paths relative to `CODEX_HOME`,
+/// or `config.toml:<dotted key>` for config values.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct HomeImportReport {
+    pub added: Vec<String>,
+    /// Local entries replaced because [`HomeImportOptions::overwrite`] was set.
+    pub overwritten: Vec<String>,
+    /// Local entries that differ from the archive and were kept.
+    pub conflicts: Vec<String>,
+    /// Entries that were already identical.
+    pub unchanged: usize,
+    /// Redacted config keys the local config does not set, which need to be
+    /// configured again.
+    pub missing_secrets: Vec<String>,
+}
+
+/// Packages `sections` of `codex_home` into an archive.
+pub fn export_home(codex_home: &Path, sections: &[HomeSection]) -> io::Result<HomeArchive> {
+    let mut sections = sections.to_vec();
+    sections.sort();
+    sections.dedup();
+
+    let mut files = Vec::new();
+    let mut redacted_keys = Vec::new();
+    if sections.contains(&HomeSection::Config) || sections.contains(&HomeSection::TrustedProjects) {
+        match fs::read_to_string(codex_home.join(CONFIG_TOML_FILE)) {
+            Ok(contents) => {
+                let (config, redacted) = export_config(&contents, &sections)?;
+                if let Some(config) = config {
+                    files.push(ArchivedFile::new(
+                        CONFIG_TOML_FILE.to_string(),
+                        config.into_bytes(),
+                    ));
+                }
+                redacted_keys = redacted;
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    for section in &sections {
+        for root in section.paths() {
+            files.extend(archive_path(codex_home, root)?);
+        }
+    }
+
+    Ok(HomeArchive {
+        version: HOME_ARCHIVE_VERSION,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        sections,
+        files,
+        redacted_keys,
+    })
+}
+
+/// Exports `sections` of `codex_home` and writes the archive to `dest`.
+pub fn write_home_archive(
+    codex_home: &Path,
+    sections: &[HomeSection],
+    dest: &Path,
+) -> io::Result<HomeArchive> {
+    let archive = export_home(codex_home, sections)?;
+    let json = serde_json::to_vec(&archive).map_err(io::Error::other)?;
+    fs::write(dest, json)?;
+    Ok(archive)
+}
+
+/// Reads an archive written by [`write_home_archive`].
+pub fn read_home_archive(path: &Path) -> io::Result<HomeArchive> {
+    let archive: HomeArchive = serde_json::from_slice(&fs::read(path)?)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    if archive.version > HOME_ARCHIVE_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "archive version {} is newer than the supported version {HOME_ARCHIVE_VERSION}; upgrade Codex to import it",
+                archive.version
+            ),
+        ));
+    }
+    Ok(archive)
+}
+
+/// Merges `archive` into `codex_home`. Entries outside the files of the
+/// sections the archive declares, e.g. `auth.json` or `approvals.json`, and
+/// paths that would escape `codex_home` are rejected before anything is
+/// written.
+pub fn import_home(
+    codex_home: &Path,
+    archive: &HomeArchive,
+    options: HomeImportOptions,
+) -> io::Result<HomeImportReport> {
+    let files = archive
+        .files
+        .iter()
+        .map(|file| Ok((section_path(&file.path, &archive.sections)?, file)))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let mut report = HomeImportReport::default();
+    for (relative, file) in files {
+        let contents = file.contents()?;
+        if file.path == CONFIG_TOML_FILE {
+            let incoming = String::from_utf8(contents)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            import_config(
+                codex_home,
+                &incoming,
+                &archive.sections,
+                options,
+                &mut report,
+            )?;
+            continue;
+        }
+
+        let dest = codex_home.join(relative);
+        match fs::read(&dest) {
+            Ok(existing) if existing == contents => report.unchanged += 1,
+            Ok(_) if options.overwrite => {
+                write_file(&dest, &contents, options)?;
+                report.overwritten.push(file.path.clone());
+            }
+            Ok(_) => report.conflicts.push(file.path.clone()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                write_file(&dest, &contents, options)?;
+                report.added.push(file.path.clone());
+            }
+            Err(err) => return Err(err),
+        }
+    }
+
+    if !archive.redacted_keys.is_empty() {
+        let local = match fs::read_to_string(codex_home.join(CONFIG_TOML_FILE)) {
+            Ok(contents) => parse_config(&contents)?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => DocumentMut::new(),
+            Err(err) => return Err(err),
+        };
+        report.missing_secrets = archive
+            .redacted_keys
+            .iter()
+            .filter(|key| !has_dotted_key(local.as_table(), key))
+            .cloned()
+            .collect();
+    }
+    Ok(report)
+}
+
+/// The exported `config.toml`, `None` when nothing of it was selected, and
+/// the redacted keys.
+fn export_config(
+    contents: &str,
+    sections: &[HomeSection],
+) -> io::Result<(Option<String>, Vec<String>)> {
+    let mut doc = parse_config(contents)?;
+    let table = doc.as_table_mut();
+    retain_config_sections(table, sections);
+    let mut redacted = Vec::new();
+    redact_secrets(table, &[], &mut redacted);
+    if table.is_empty() {
+        return Ok((None, redacted));
+    }
+    Ok((Some(doc.to_string()), redacted))
+}
+
+/// Drops the parts of `config.toml` that belong to none of `sections`.
+fn retain_config_sections(table: &mut toml_edit::Table, sections: &[HomeSection]) {
+    if !sections.contains(&HomeSection::Config) {
+        table.retain(|key, _| key == PROJECTS_KEY);
+    }
+    if !sections.contains(&HomeSection::TrustedProjects) {
+        table.remove(PROJECTS_KEY);
+    }
+}
+
+fn redact_secrets(table: &mut dyn TableLike, prefix: &[String], redacted: &mut Vec<String>) {
+    let in_secret_table = SECRET_TABLES
+        .iter()
+        .any(|pattern| matches_key_path(prefix, pattern));
+    let keys: Vec<String> = table.iter().map(|(key, _)| key.to_string()).collect();
+    for key in keys {
+        let mut path = prefix.to_vec();
+        path.push(key.clone());
+        match table.get_mut(&key).and_then(TomlItem::as_table_like_mut) {
+            Some(child) => {
+                let redacted_before = redacted.len();
+                redact_secrets(child, &path, redacted);
+                // Drop tables emptied by redaction, e.g. an `env` of secrets.
+                if child.is_empty() && redacted.len() > redacted_before {
+                    table.remove(&key);
+                }
+            }
+            None => {
+                if in_secret_table
+                    || SECRET_KEYS
+                        .iter()
+                        .any(|pattern| matches_key_path(&path, pattern))
+                {
+                    table.remove(&key);
+                    redacted.push(path.join("."));
+                }
+            }
+        }
+    }
+}
+
+fn matches_key_path(path: &[String], pattern: &[&str]) -> bool {
+    path.len() == pattern.len()
+        && path
+            .iter()
+            .zip(pattern)
+            .all(|(key, expected)| *expected == "*" || key == expected)
+}
+
+fn import_config(
+    codex_home: &Path,
+    incoming: &str,
+    sections: &[HomeSection],
+    options: HomeImportOptions,
+    report: &mut HomeImportReport,
+) -> io::Result<()> {
+    let mut incoming = parse_config(incoming)?;
+    retain_config_sections(incoming.as_table_mut(), sections);
+    let path = codex_home.join(CONFIG_TOML_FILE);
+    let mut local = match fs::read_to_string(&path) {
+        Ok(contents) => parse_config(&contents)?,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => DocumentMut::new(),
+        Err(err) => return Err(err),
+    };
+
+    let changes = report.added.len() + report.overwritten.len();
+    merge_config_tables(
+        local.as_table_mut(),
+        incoming.as_table(),
+        "",
+        options,
+        report,
+    );
+    if report.added.len() + report.overwritten.len() > changes && !options.dry_run {
+        fs::create_dir_all(codex_home)?;
+        write_atomically(&path, &local.to_string())?;
+    }
+    Ok(())
+}
+
+fn merge_config_tables(
+    local: &mut dyn TableLike,
+    incoming: &dyn TableLike,
+    prefix: &str,
+    options: HomeImportOptions,
+    report: &mut HomeImportReport,
+) {
+    for (key, item) in incoming.iter() {
+        let path = dotted(prefix, key);
+        let entry = format!("{CONFIG_TOML_FILE}:{path}");
+        let Some(existing) = local.get_mut(key) else {
+            local.insert(key, item.clone());
+            report.added.push(entry);
+            continue;
+        };
+        if let (Some(existing), Some(item)) = (existing.as_table_like_mut(), item.as_table_like()) {
+            merge_config_tables(existing, item, &path, options, report);
+        } else if same_item(existing, item) {
+            report.unchanged += 1;
+        } else if options.overwrite {
+            *existing = item.clone();
+            report.overwritten.push(entry);
+        } else {
+            report.conflicts.push(entry);
+        }
+    }
+}
+
+/// Compares values ignoring formatting and comments.
+fn same_item(a: &TomlItem, b: &TomlItem) -> bool {
+    match (a.as_value(), b.as_value()) {
+        (Some(a), Some(b)) => {
+            let parse = |value: &toml_edit::Value| {
+                toml::from_str::<toml::Table>(&format!("v = {value}\n")).ok()
+            };
+            parse(a).is_some_and(|a| Some(a) == parse(b))
+        }
+        _ => a.to_string() == b.to_string(),
+    }
+}
+
+fn has_dotted_key(table: &dyn TableLike, dotted_key: &str) -> bool {
+    let mut table = table;
+    let mut parts = dotted_key.split('.').peekable();
+    while let Some(part) = parts.next() {
+        let Some(item) = table.get(part) else {
+            return false;
+        };
+        if parts.peek().is_none() {
+            return true;
+        }
+        let Some(child) = item.as_table_like() else {
+            return false;
+        };
+        table = child;
+    }
+    false
+}
+
+fn parse_config(contents: &str) -> io::Result<DocumentMut> {
+    contents.parse::<DocumentMut>().map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("failed to parse {CONFIG_TOML_FILE}: {err}"),
+        )
+    })
+}
+
+fn dotted(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{prefix}.{key}")
+    }
+}
+
+/// Every file under `root`, which may also be a single file.
+fn archive_path(codex_home: &Path, root: &str) -> io::Result<Vec<ArchivedFile>> {
+    let mut files = Vec::new();
+    let system_skills = codex_home.join("skills").join(SYSTEM_SKILLS_DIR);
+    for entry in WalkDir::new(codex_home.join(root))
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| entry.path() != system_skills)
+    {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) if err.io_error().map(io::Error::kind) == Some(io::ErrorKind::NotFound) => {
+                continue;
+            }
+            Err(err) => return Err(io::Error::other(err)),
+        };
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Ok(relative) = entry.path().strip_prefix(codex_home) else {
+            continue;
+        };
+        let path = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        files.push(ArchivedFile::new(path, fs::read(entry.path())?));
+    }
+    Ok(files)
+}
+
+/// `path` from an archive as a relative path inside the files of `sections`.
+fn section_path(path: &str, sections: &[HomeSection]) -> io::Result<PathBuf> {
+    let relative = relative_path(path)?;
+    let is_config = path == CONFIG_TOML_FILE
+        && (sections.contains(&HomeSection::Config)
+            || sections.contains(&HomeSection::TrustedProjects));
+    let in_section = sections
+        .iter()
+        .flat_map(|section| section.paths())
+        .any(|root| relative.starts_with(root));
+    if !is_config && !in_section {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("archive entry {path:?} is not part of the archived sections"),
+        ));
+    }
+    Ok(relative)
+}
+
+/// `path` from an archive as a relative path that stays inside `CODEX_HOME`.
+fn relative_path(path: &str) -> io::Result<PathBuf> {
+    let relative = PathBuf::from(path);
+    if path.is_empty()
+        || !relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("archive entry {path:?} is not a relative path inside CODEX_HOME"),
+        ));
+    }
+    Ok(relative)
+}
+
+fn write_file(dest: &Path, contents: &[u8], options: HomeImportOptions) -> io::Result<()> {
+    if options.dry_run {
+        return Ok(());
+    }
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(dest, contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    const CONFIG: &str = r#"model = "gpt-5"
+tool_output_token_limit = 12000
+
+[shell_environment_policy]
+inherit = "core"
+set = { NPM_TOKEN = "npm-789" }
+
+[mcp_servers.docs]
+command = "docs-mcp"
+env = { DOCS_TOKEN = "s3cr3t", DOCS_REGION = "eu" }
+
+[mcp_servers.search]
+url = "https://search.example.com/mcp"
+bearer_token = "tok-123"
+bearer_token_env_var = "SEARCH_TOKEN"
+http_headers = { "X-Api-Key" = "hdr-456" }
+
+[model_providers.acme]
+name = "Acme"
+env_key = "ACME_API_KEY"
+experimental_bearer_token = "abc123"
+env_http_headers = { "X-Team" = "ACME_TEAM" }
+
+[projects."/work/app"]
+trust_level = "trusted"
+"#;
+
+    fn home_with(files: &[(&str, &str)]) -> TempDir {
+        let home = TempDir::new().expect("tempdir");
+        for (path, contents) in files {
+            let path = home.path().join(path);
+            fs::create_dir_all(path.parent().expect("parent")).expect("create dir");
+            fs::write(path, contents).expect("write file");
+        }
+        home
+    }
+
+    fn archived(archive: &HomeArchive, path: &str) -> Option<String> {
+        archive
+            .files
+            .iter()
+            .find(|file| file.path == path)
+            .map(|file| file.data.clone())
+    }
+
+    #[test]
+    fn export_redacts_secrets_and_skips_auth_and_system_skills() {
+        let home = home_with(&[
+            (CONFIG_TOML_FILE, CONFIG),
+            ("auth.json", "{}"),
+            ("prompts/review.md", "Review this."),
+            ("skills/lint/SKILL.md", "lint"),
+            ("skills/.system/bundled/SKILL.md", "bundled"),
+            ("sessions/2025/06/01/rollout.jsonl", "{}"),
+        ]);
+
+        let archive = export_home(home.path(), HomeSection::DEFAULT).expect("export");
+
+        let paths: Vec<&str> = archive
+            .files
+            .iter()
+            .map(|file| file.path.as_str())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                CONFIG_TOML_FILE,
+                "prompts/review.md",
+                "skills/lint/SKILL.md"
+            ]
+        );
+        assert_eq!(
+            archive.redacted_keys,
+            vec![
+                "shell_environment_policy.set.NPM_TOKEN".to_string(),
+                "mcp_servers.docs.env.DOCS_TOKEN".to_string(),
+                "mcp_servers.docs.env.DOCS_REGION".to_string(),
+                "mcp_servers.search.bearer_token".to_string(),
+                "mcp_servers.search.http_headers.X-Api-Key".to_string(),
+                "model_providers.acme.experimental_bearer_token".to_string(),
+                "model_providers.acme.env_http_headers.X-Team".to_string(),
+            ]
+        );
+        // Keys merely named like credentials, e.g. the variable holding one,
+        // are kept.
+        let config = archived(&archive, CONFIG_TOML_FILE).expect("config exported");
+        assert_eq!(
+            config,
+            r#"model = "gpt-5"
+tool_output_token_limit = 12000
+
+[shell_environment_policy]
+inherit = "core"
+
+[mcp_servers.docs]
+command = "docs-mcp"
+
+[mcp_servers.search]
+url = "https://search.example.com/mcp"
+bearer_token_env_var = "SEARCH_TOKEN"
+
+[model_providers.acme]
+name = "Acme"
+env_key = "ACME_API_KEY"
+
+[projects."/work/app"]
+trust_level = "trusted"
+"#
+        );
+    }
+
+    #[test]
+    fn export_of_trusted_projects_only_keeps_the_projects_table() {
+        let home = home_with(&[(CONFIG_TOML_FILE, CONFIG)]);
+
+        let archive = export_home(home.path(), &[HomeSection::TrustedProjects]).expect("export");
+
+        assert_eq!(
+            archived(&archive, CONFIG_TOML_FILE).map(|config| config.trim().to_string()),
+            Some("[projects.\"/work/app\"]\ntrust_level = \"trusted\"".to_string())
+        );
+        assert_eq!(archive.redacted_keys, Vec::<String>::new());
+    }
+
+    #[test]
+    fn import_merges_and_reports_conflicts() {
+        let source = home_with(&[
+            (CONFIG_TOML_FILE, CONFIG),
+            ("prompts/review.md", "Review this."),
+            ("prompts/explain.md", "Explain this."),
+        ]);
+        let archive = export_home(source.path(), HomeSection::DEFAULT).expect("export");
+        let dest = home_with(&[
+            (
+                CONFIG_TOML_FILE,
+                "# mine\nmodel = \"o3\"\n\n[mcp_servers.docs]\ncommand = \"docs-mcp\"\n",
+            ),
+            ("prompts/review.md", "Review this carefully."),
+            ("prompts/explain.md", "Explain this."),
+        ]);
+
+        let report =
+            import_home(dest.path(), &archive, HomeImportOptions::default()).expect("import");
+
+        assert_eq!(
+            report,
+            HomeImportReport {
+                added: vec![
+                    "config.toml:model_providers".to_string(),
+                    "config.toml:projects".to_string(),
+                ],
+                overwritten: Vec::new(),
+                conflicts: vec![
+                    "config.toml:model".to_string(),
+                    "prompts/review.md".to_string(),
+                ],
+                unchanged: 2,
+                missing_secrets: vec![
+                    "mcp_servers.docs.env.DOCS_TOKEN".to_string(),
+                    "mcp_servers.docs.env.DOCS_REGION".to_string(),
+                    "model_providers.acme.experimental_bearer_token".to_string(),
+                ],
+            }
+        );
+        let config = fs::read_to_string(dest.path().join(CONFIG_TOML_FILE)).expect("config");
+        assert!(config.starts_with("# mine\nmodel = \"o3\"\n"));
+        assert!(config.contains("[projects.\"/work/app\"]"));
+        assert_eq!(
+            fs::read_to_string(dest.path().join("prompts/review.md")).expect("prompt"),
+            "Review this carefully."
+        );
+    }
+
+    #[test]
+    fn import_rejects_paths_outside_codex_home() {
+        let home = TempDir::new().expect("tempdir");
+        let archive = HomeArchive {
+            version: HOME_ARCHIVE_VERSION,
+            created_at: String::new(),
+            sections: vec![HomeSection::Prompts],
+            files: vec![ArchivedFile::new(
+                "../outside.md".to_string(),
+                b"x".to_vec(),
+            )],
+            redacted_keys: Vec::new(),
+        };
+
+        let err = import_home(home.path(), &archive, HomeImportOptions::default())
+            .expect_err("path escapes CODEX_HOME");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn import_rejects_files_outside_the_archived_sections() {
+        let home = TempDir::new().expect("tempdir");
+        let archive = HomeArchive {
+            version: HOME_ARCHIVE_VERSION,
+            created_at: String::new(),
+            sections: HomeSection::DEFAULT.to_vec(),
+            files: vec![
+                ArchivedFile::new("prompts/review.md".to_string(), b"Review this.".to_vec()),
+                ArchivedFile::new("approvals.json".to_string(), b"{}".to_vec()),
+            ],
+            redacted_keys: Vec::new(),
+        };
+
+        let err = import_home(home.path(), &archive, HomeImportOptions::default())
+            .expect_err("approvals.json is not part of any section");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            "archive entry \"approvals.json\" is not part of the archived sections"
+        );
+        assert!(!home.path().join("prompts").exists());
+        assert!(!home.path().join("approvals.json").exists());
+    }
+}
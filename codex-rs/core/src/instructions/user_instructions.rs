@@ -7,6 +7,7 @@ use codex_protocol::models::ResponseItem;
 pub const USER_INSTRUCTIONS_OPEN_TAG_LEGACY: &str = "<user_instructions>";
 pub const USER_INSTRUCTIONS_PREFIX: &str = "# AGENTS.md instructions for ";
 pub const SKILL_INSTRUCTIONS_PREFIX: &str = "<skill";
+pub const KNOWLEDGE_INSTRUCTIONS_PREFIX: &str = "<knowledge>";
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename = "user_instructions", rename_all = "snake_case")]
@@ -77,6 +78,47 @@ impl From<SkillInstructions> for ResponseItem {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename = "knowledge_instructions", rename_all = "snake_case")]
+pub(crate) struct KnowledgeInstructions {
+    pub name: String,
+    pub path: String,
+    pub contents: String,
+}
+
+impl KnowledgeInstructions {
+    pub fn is_knowledge_instructions(message: &[ContentItem]) -> bool {
+        Self::injected_name(message).is_some()
+    }
+
+    /// Name of the snippet a history message injected, if it is one.
+    pub fn injected_name(message: &[ContentItem]) -> Option<&str> {
+        let [ContentItem::InputText { text }] = message else {
+            return None;
+        };
+        text.strip_prefix(KNOWLEDGE_INSTRUCTIONS_PREFIX)?
+            .strip_prefix("\n<name>")?
+            .split_once("</name>")
+            .map(|(name, _)| name)
+    }
+}
+
+impl From<KnowledgeInstructions> for ResponseItem {
+    fn from(ki: KnowledgeInstructions) -> Self {
+        ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: format!(
+                    "{KNOWLEDGE_INSTRUCTIONS_PREFIX}\n<name>{}</name>\n<path>{}</path>\n{}\n</knowledge>",
+                    ki.name, ki.path, ki.contents
+                ),
+            }],
+            end_turn: None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
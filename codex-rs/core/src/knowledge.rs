@@ -0,0 +1,387 @@
+//! Project knowledge snippets added to a turn when the prompt mentions them.
+//!
+//! Teams keep short notes on their conventions in `.codex/knowledge/*.md`,
+//! each starting with YAML frontmatter that lists its `keywords`. When the
+//! user prompt contains one of the keywords as a whole word, ignoring case,
+//! the snippet is added to the conversation ahead of the model's reply, up to
+//! the `[knowledge]` token budget. Selection only depends on the prompt and
+//! the files, so the same prompt always brings in the same snippets.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_app_server_protocol::ConfigLayerSource;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::InjectedKnowledgeSnippet;
+use codex_protocol::protocol::KnowledgeInjectedEvent;
+use codex_protocol::user_input::UserInput;
+use serde::Deserialize;
+use tracing::warn;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::config_loader::ConfigLayerStack;
+use crate::features::Feature;
+use crate::instructions::KnowledgeInstructions;
+use crate::truncate::approx_token_count;
+
+const KNOWLEDGE_DIR_NAME: &str = "knowledge";
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct KnowledgeSnippet {
+    /// File name without the `.md` extension.
+    pub name: String,
+    pub path: PathBuf,
+    pub keywords: Vec<String>,
+    pub body: String,
+}
+
+#[derive(Deserialize)]
+struct KnowledgeFrontmatter {
+    keywords: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct KnowledgeInjections {
+    pub(crate) items: Vec<ResponseItem>,
+    pub(crate) event: Option<KnowledgeInjectedEvent>,
+}
+
+/// Snippets to add for `inputs`, leaving out the ones already in the
+/// conversation. Nothing is added unless the `knowledge` feature is enabled.
+pub(crate) async fn build_knowledge_injections(
+    sess: &Session,
+    turn_context: &TurnContext,
+    inputs: &[UserInput],
+) -> KnowledgeInjections {
+    if !sess.features().enabled(Feature::Knowledge) {
+        return KnowledgeInjections::default();
+    }
+    let prompt = inputs
+        .iter()
+        .filter_map(|input| match input {
+            UserInput::Text { text, .. } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    if prompt.trim().is_empty() {
+        return KnowledgeInjections::default();
+    }
+
+    let config = turn_context.client.config();
+    let snippets = load_knowledge(&knowledge_roots(&config.config_layer_stack));
+    if snippets.is_empty() {
+        return KnowledgeInjections::default();
+    }
+    // Snippets dropped by compaction are no longer in the history, so they
+    // are added again the next time they match.
+    let already_injected: HashSet<String> = sess
+        .clone_history()
+        .await
+        .raw_items()
+        .iter()
+        .filter_map(|item| match item {
+            ResponseItem::Message { role, content, .. } if role == "user" => {
+                KnowledgeInstructions::injected_name(content).map(str::to_string)
+            }
+            _ => None,
+        })
+        .collect();
+
+    let selection = select_knowledge(
+        &prompt,
+        &snippets,
+        &already_injected,
+        usize::try_from(config.knowledge.max_tokens()).unwrap_or(usize::MAX),
+    );
+    if selection.selected.is_empty() && selection.over_budget.is_empty() {
+        return KnowledgeInjections::default();
+    }
+
+    let mut injections = KnowledgeInjections::default();
+    let mut injected = Vec::with_capacity(selection.selected.len());
+    for (snippet, matched_keywords) in selection.selected {
+        injections
+            .items
+            .push(ResponseItem::from(KnowledgeInstructions {
+                name: snippet.name.clone(),
+                path: snippet.path.to_string_lossy().into_owned(),
+                contents: snippet.body.clone(),
+            }));
+        injected.push(InjectedKnowledgeSnippet {
+            name: snippet.name.clone(),
+            path: snippet.path.clone(),
+            matched_keywords,
+            token_count: approx_token_count(&snippet.body) as u64,
+        });
+    }
+    injections.event = Some(KnowledgeInjectedEvent {
+        snippets: injected,
+        over_budget: selection.over_budget,
+    });
+    injections
+}
+
+/// `.codex/knowledge` directories of the project layers, closest to the
+/// working directory first.
+fn knowledge_roots(config_layer_stack: &ConfigLayerStack) -> Vec<PathBuf> {
+    config_layer_stack
+        .layers_high_to_low()
+        .into_iter()
+        .filter(|layer| matches!(layer.name, ConfigLayerSource::Project { .. }))
+        .filter_map(|layer| layer.config_folder())
+        .map(|folder| folder.as_path().join(KNOWLEDGE_DIR_NAME))
+        .collect()
+}
+
+/// Reads the snippets under `roots`. A snippet in an earlier root hides one
+/// with the same name in a later root. Files without `keywords` are skipped.
+pub(crate) fn load_knowledge(roots: &[PathBuf]) -> Vec<KnowledgeSnippet> {
+    let mut snippets = Vec::new();
+    let mut seen = HashSet::new();
+    for root in roots {
+        let Ok(entries) = fs::read_dir(root) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "md") && path.is_file())
+            .collect();
+        paths.sort();
+        for path in paths {
+            let Some(name) = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+            else {
+                continue;
+            };
+            if seen.contains(&name) {
+                continue;
+            }
+            match parse_snippet(&path, name.clone()) {
+                Ok(snippet) => {
+                    seen.insert(name);
+                    snippets.push(snippet);
+                }
+                Err(message) => warn!("skipping knowledge snippet {}: {message}", path.display()),
+            }
+        }
+    }
+    snippets
+}
+
+fn parse_snippet(path: &Path, name: String) -> Result<KnowledgeSnippet, String> {
+    let contents = fs::read_to_string(path).map_err(|err| err.to_string())?;
+    let (frontmatter, body) = split_frontmatter(&contents)
+        .ok_or_else(|| "missing YAML frontmatter delimited by ---".to_string())?;
+    let frontmatter: KnowledgeFrontmatter =
+        serde_yaml::from_str(frontmatter).map_err(|err| err.to_string())?;
+    let keywords: Vec<String> = frontmatter
+        .keywords
+        .into_iter()
+        .map(|keyword| keyword.trim().to_string())
+        .filter(|keyword| !normalize(keyword).trim().is_empty())
+        .collect();
+    if keywords.is_empty() {
+        return Err("no keywords".to_string());
+    }
+    Ok(KnowledgeSnippet {
+        name,
+        path: path.to_path_buf(),
+        keywords,
+        body: body.trim().to_string(),
+    })
+}
+
+fn split_frontmatter(contents: &str) -> Option<(&str, &str)> {
+    let rest = contents
+        .strip_prefix("---\n")
+        .or_else(|| contents.strip_prefix("---\r\n"))?;
+    let end = rest.find("\n---")?;
+    let body = &rest[end + "\n---".len()..];
+    let body = body.split_once('\n').map_or("", |(_, body)| body);
+    Some((&rest[..end], body))
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct KnowledgeSelection<'a> {
+    /// Snippets to add with the keywords that matched, most matches first.
+    pub selected: Vec<(&'a KnowledgeSnippet, Vec<String>)>,
+    pub over_budget: Vec<String>,
+}
+
+/// Picks the snippets with a keyword in `prompt`, most matching keywords
+/// first and then by name, until `max_tokens` is used up.
+pub(crate) fn select_knowledge<'a>(
+    prompt: &str,
+    snippets: &'a [KnowledgeSnippet],
+    already_injected: &HashSet<String>,
+    max_tokens: usize,
+) -> KnowledgeSelection<'a> {
+    let prompt = normalize(prompt);
+    let mut candidates: Vec<(&KnowledgeSnippet, Vec<String>)> = snippets
+        .iter()
+        .filter(|snippet| !already_injected.contains(&snippet.name))
+        .filter_map(|snippet| {
+            let matched: Vec<String> = snippet
+                .keywords
+                .iter()
+                .filter(|keyword| prompt.contains(&normalize(keyword)))
+                .cloned()
+                .collect();
+            (!matched.is_empty()).then_some((snippet, matched))
+        })
+        .collect();
+    candidates.sort_by(|(a, a_matched), (b, b_matched)| {
+        b_matched
+            .len()
+            .cmp(&a_matched.len())
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    let mut selection = KnowledgeSelection::default();
+    let mut remaining = max_tokens;
+    for (snippet, matched) in candidates {
+        let tokens = approx_token_count(&snippet.body);
+        if tokens > remaining {
+            selection.over_budget.push(snippet.name.clone());
+            continue;
+        }
+        remaining -= tokens;
+        selection.selected.push((snippet, matched));
+    }
+    selection
+}
+
+/// Lowercases `text` and reduces it to its words separated by single
+/// spaces, with a space on both ends so that `" word "` only matches whole
+/// words.
+fn normalize(text: &str) -> String {
+    let mut normalized = String::from(" ");
+    for word in text
+        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+        .filter(|word| !word.is_empty())
+    {
+        normalized.push_str(&word.to_lowercase());
+        normalized.push(' ');
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn snippet(name: &str, keywords: &[&str], body: &str) -> KnowledgeSnippet {
+        KnowledgeSnippet {
+            name: name.to_string(),
+            path: PathBuf::from(format!(".codex/knowledge/{name}.md")),
+            keywords: keywords.iter().map(|keyword| keyword.to_string()).collect(),
+            body: body.to_string(),
+        }
+    }
+
+    fn names(selection: &KnowledgeSelection<'_>) -> Vec<String> {
+        selection
+            .selected
+            .iter()
+            .map(|(snippet, _)| snippet.name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn matches_whole_words_ignoring_case() {
+        let snippets = vec![
+            snippet("errors", &["error handling"], "Use thiserror."),
+            snippet(
+                "flags",
+                &["feature flag", "flags"],
+                "Gate behind a feature.",
+            ),
+            snippet("logs", &["log"], "Use tracing."),
+        ];
+
+        let selection = select_knowledge(
+            "How is Error Handling done, and where do Feature Flags live? See the logs.",
+            &snippets,
+            &HashSet::new(),
+            1_000,
+        );
+
+        assert_eq!(names(&selection), vec!["errors", "flags"]);
+        assert_eq!(selection.selected[1].1, vec!["flags".to_string()]);
+    }
+
+    #[test]
+    fn ranks_by_matches_and_respects_the_budget() {
+        let snippets = vec![
+            snippet("a-large", &["parser"], &"word ".repeat(400)),
+            snippet("b-small", &["parser"], "Parsers live in codex-parse."),
+            snippet("c-best", &["parser", "lexer"], "Lexer and parser notes."),
+            snippet("d-seen", &["parser"], "Already in the conversation."),
+        ];
+        let already_injected = HashSet::from(["d-seen".to_string()]);
+
+        let selection = select_knowledge(
+            "the lexer feeds the parser",
+            &snippets,
+            &already_injected,
+            100,
+        );
+
+        assert_eq!(names(&selection), vec!["c-best", "b-small"]);
+        assert_eq!(selection.over_budget, vec!["a-large".to_string()]);
+    }
+
+    #[test]
+    fn loads_snippets_with_closer_roots_first() {
+        let dir = TempDir::new().expect("tempdir");
+        let nested = dir.path().join("nested");
+        let outer = dir.path().join("outer");
+        fs::create_dir_all(&nested).expect("create nested");
+        fs::create_dir_all(&outer).expect("create outer");
+        fs::write(
+            nested.join("testing.md"),
+            "---\nkeywords: [tests]\n---\nRun just test.\n",
+        )
+        .expect("write nested");
+        fs::write(
+            outer.join("testing.md"),
+            "---\nkeywords: [tests]\n---\nRun cargo test.\n",
+        )
+        .expect("write outer");
+        fs::write(
+            outer.join("style.md"),
+            "---\nkeywords:\n  - style\n  - formatting\n---\n\nRun cargo fmt.\n",
+        )
+        .expect("write style");
+        fs::write(outer.join("broken.md"), "no frontmatter").expect("write broken");
+        fs::write(outer.join("notes.txt"), "---\nkeywords: [x]\n---\n").expect("write txt");
+
+        let snippets = load_knowledge(&[nested.clone(), outer.clone()]);
+
+        assert_eq!(
+            snippets,
+            vec![
+                KnowledgeSnippet {
+                    name: "testing".to_string(),
+                    path: nested.join("testing.md"),
+                    keywords: vec!["tests".to_string()],
+                    body: "Run just test.".to_string(),
+                },
+                KnowledgeSnippet {
+                    name: "style".to_string(),
+                    path: outer.join("style.md"),
+                    keywords: vec!["style".to_string(), "formatting".to_string()],
+                    body: "Run cargo fmt.".to_string(),
+                },
+            ]
+        );
+    }
+}
@@ -7,33 +7,51 @@
 
 pub mod api_bridge;
 mod apply_patch;
+pub mod approval_memory;
+pub mod artifacts;
 pub mod auth;
+mod background_compaction;
 pub mod bash;
+#[cfg(feature = "bench")]
+pub mod bench;
 mod client;
 mod client_common;
+mod clipboard;
 pub mod codex;
 mod codex_thread;
 mod compact_remote;
 pub use codex_thread::CodexThread;
 mod agent;
+pub mod change_index;
 mod codex_delegate;
 mod command_safety;
 pub mod config;
 pub mod config_loader;
 pub mod connectors;
 mod context_manager;
+mod conversation_merge;
 pub mod custom_prompts;
+pub mod doctor;
 pub mod env;
 mod environment_context;
+pub mod environment_snapshot;
 pub mod error;
+mod error_fingerprint;
+mod event_socket;
+pub mod event_subscription;
 pub mod exec;
 pub mod exec_env;
+mod exec_output_spill;
 mod exec_policy;
 pub mod features;
 mod flags;
 pub mod git_info;
+mod heartbeat;
+pub mod home_archive;
 pub mod instructions;
+mod knowledge;
 pub mod landlock;
+pub mod locale;
 pub mod mcp;
 mod mcp_connection_manager;
 pub mod models_manager;
@@ -42,15 +60,26 @@ pub use mcp_connection_manager::MCP_SANDBOX_STATE_METHOD;
 pub use mcp_connection_manager::SandboxState;
 mod mcp_tool_call;
 mod message_history;
+#[cfg(any(test, feature = "test-support"))]
+pub mod mock_provider;
 mod model_provider_info;
+mod model_switch;
 pub mod parse_command;
 pub mod path_utils;
 pub mod powershell;
+mod provider_endpoints;
 pub mod sandboxing;
+mod secret_masking;
 mod session_prefix;
+pub mod session_stats;
+pub mod session_templates;
+mod session_title;
+mod stale_reads;
 mod stream_events_utils;
 mod text_encoding;
 pub mod token_data;
+mod token_forecast;
+mod tool_output_chunks;
 mod truncate;
 mod unified_exec;
 pub mod windows_sandbox;
@@ -82,6 +111,11 @@ pub use auth::AuthManager;
 pub use auth::CodexAuth;
 pub mod default_client;
 pub mod project_doc;
+mod project_map;
+pub mod provenance;
+mod repl;
+mod response_lint;
+mod resume_context;
 mod rollout;
 pub(crate) mod safety;
 pub mod seatbelt;
@@ -90,13 +124,19 @@ pub mod shell_snapshot;
 pub mod skills;
 pub mod spawn;
 pub mod terminal;
+pub mod tokenizer;
 mod tools;
 pub mod turn_diff_tracker;
+mod turn_snapshot;
 pub use rollout::ARCHIVED_SESSIONS_SUBDIR;
+pub use rollout::CURRENT_ROLLOUT_SCHEMA_VERSION;
 pub use rollout::INTERACTIVE_SESSION_SOURCES;
+pub use rollout::RolloutMigrationReport;
 pub use rollout::RolloutRecorder;
 pub use rollout::SESSIONS_SUBDIR;
 pub use rollout::SessionMeta;
+pub use rollout::SessionMetadataUpdate;
+pub use rollout::find_archived_thread_path_by_id_str;
 #[deprecated(note = "use find_thread_path_by_id_str")]
 pub use rollout::find_conversation_path_by_id_str;
 pub use rollout::find_thread_path_by_id_str;
@@ -107,12 +147,18 @@ pub use rollout::list::ThreadsPage;
 pub use rollout::list::parse_cursor;
 pub use rollout::list::read_head_for_summary;
 pub use rollout::list::read_session_meta_line;
+pub use rollout::list::session_day_dir_for_file_name;
+pub use rollout::migrate_rollout_file;
+pub use rollout::migrate_rollouts;
+pub use rollout::update_session_metadata;
 mod function_tool;
 mod state;
 mod tasks;
 mod user_notification;
 mod user_shell_command;
 pub mod util;
+pub mod workspace_diff;
+mod write_quota;
 
 pub use apply_patch::CODEX_APPLY_PATCH_ARG1;
 pub use client::WEB_SEARCH_ELIGIBLE_HEADER;
@@ -125,6 +171,7 @@ pub use safety::get_platform_sandbox;
 pub use safety::is_windows_elevated_sandbox_enabled;
 pub use safety::set_windows_elevated_sandbox_enabled;
 pub use safety::set_windows_sandbox_enabled;
+pub use tools::spec::builtin_tool_names;
 // Re-export the protocol types from the standalone `codex-protocol` crate so existing
 // `codex_core::protocol::...` references continue to work across the workspace.
 pub use codex_protocol::protocol;
@@ -0,0 +1,260 @@
+//! Language, timezone and measurement units of the person using Codex.
+//!
+//! Resolved once per config load from `[locale]` with the operating system
+//! filling in whatever is not set. The model sees the result in
+//! `<environment_context>` and timestamps Codex renders itself use the same
+//! timezone, so a user in Berlin does not get a mix of UTC and local times.
+
+use std::io;
+
+use chrono::DateTime;
+use chrono::FixedOffset;
+use chrono::Local;
+use chrono::Offset;
+use chrono::Utc;
+
+use crate::config::types::LocaleToml;
+use crate::config::types::Units;
+
+/// Regions that use imperial units for everyday measurements.
+const IMPERIAL_REGIONS: &[&str] = &["US", "LR", "MM"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LocaleContext {
+    /// BCP 47 language tag, e.g. `de-DE`.
+    pub language: Option<String>,
+    /// Timezone for rendered timestamps. `None` falls back to the system
+    /// timezone without advertising it to the model.
+    pub timezone: Option<Timezone>,
+    pub units: Option<Units>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Timezone {
+    /// The system timezone, with its IANA name when it can be determined.
+    Local { name: Option<String> },
+    /// A fixed offset from UTC. UTC itself is an offset of zero.
+    Fixed(FixedOffset),
+}
+
+impl LocaleContext {
+    pub fn resolve(toml: LocaleToml) -> io::Result<Self> {
+        let language = toml
+            .language
+            .or_else(sys_locale::get_locale)
+            .map(|tag| normalize_language_tag(&tag));
+        let timezone = match toml.timezone.as_deref() {
+            None => Timezone::local(),
+            Some(value) => Timezone::parse(value).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "locale.timezone must be `local`, `UTC` or an offset like `+05:30`, got `{value}`"
+                    ),
+                )
+            })?,
+        };
+        let units = toml
+            .units
+            .or_else(|| language.as_deref().map(units_for_language));
+        Ok(Self {
+            language,
+            timezone: Some(timezone),
+            units,
+        })
+    }
+
+    /// Renders `timestamp` in the user's timezone with a chrono `format`
+    /// string.
+    pub fn format_timestamp(&self, timestamp: DateTime<Utc>, format: &str) -> String {
+        match &self.timezone {
+            Some(Timezone::Fixed(offset)) => {
+                timestamp.with_timezone(offset).format(format).to_string()
+            }
+            Some(Timezone::Local { .. }) | None => {
+                timestamp.with_timezone(&Local).format(format).to_string()
+            }
+        }
+    }
+}
+
+impl Timezone {
+    fn local() -> Self {
+        Self::Local {
+            name: detect_timezone_name(),
+        }
+    }
+
+    /// Parses `local`, `UTC`, or an offset such as `+05:30`, `-0800` or
+    /// `UTC+2`.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if value.eq_ignore_ascii_case("local") {
+            return Some(Self::local());
+        }
+        let offset = ["UTC", "GMT"]
+            .into_iter()
+            .find_map(|prefix| {
+                value
+                    .get(..prefix.len())
+                    .filter(|head| head.eq_ignore_ascii_case(prefix))
+                    .map(|_| &value[prefix.len()..])
+            })
+            .unwrap_or(value);
+        if offset.is_empty() || offset.eq_ignore_ascii_case("z") {
+            return FixedOffset::east_opt(0).map(Self::Fixed);
+        }
+        parse_offset(offset).map(Self::Fixed)
+    }
+
+    /// Human-readable description, e.g. `Europe/Berlin (UTC+02:00)` or
+    /// `UTC+05:30`.
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Local { name } => {
+                let offset = describe_offset(Local::now().offset().fix());
+                match name {
+                    Some(name) => format!("{name} ({offset})"),
+                    None => offset,
+                }
+            }
+            Self::Fixed(offset) => describe_offset(*offset),
+        }
+    }
+}
+
+fn describe_offset(offset: FixedOffset) -> String {
+    if offset.local_minus_utc() == 0 {
+        "UTC".to_string()
+    } else {
+        format!("UTC{offset}")
+    }
+}
+
+fn parse_offset(value: &str) -> Option<FixedOffset> {
+    let (sign, rest) = match value.as_bytes().first()? {
+        b'+' => (1, &value[1..]),
+        b'-' => (-1, &value[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = match rest.split_once(':') {
+        Some((hours, minutes)) => (hours, minutes),
+        None if rest.len() > 2 => rest.split_at(rest.len() - 2),
+        None => (rest, "0"),
+    };
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if hours > 14 || minutes >= 60 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Turns POSIX locale names like `en_US.UTF-8` into BCP 47 tags (`en-US`).
+fn normalize_language_tag(tag: &str) -> String {
+    let tag = tag.split(['.', '@']).next().unwrap_or(tag);
+    tag.replace('_', "-")
+}
+
+fn units_for_language(language: &str) -> Units {
+    let imperial = language
+        .split('-')
+        .skip(1)
+        .any(|subtag| IMPERIAL_REGIONS.contains(&subtag));
+    if imperial {
+        Units::Imperial
+    } else {
+        Units::Metric
+    }
+}
+
+/// IANA name of the system timezone, from `TZ` or the `/etc/localtime`
+/// symlink.
+fn detect_timezone_name() -> Option<String> {
+    if let Ok(tz) = std::env::var("TZ") {
+        let tz = tz.trim_start_matches(':');
+        if tz.contains('/') && !tz.starts_with('/') {
+            return Some(tz.to_string());
+        }
+    }
+    #[cfg(unix)]
+    if let Ok(target) = std::fs::read_link("/etc/localtime") {
+        let target = target.to_string_lossy();
+        if let Some((_, name)) = target.split_once("zoneinfo/") {
+            return Some(name.to_string());
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn offset(seconds: i32) -> FixedOffset {
+        FixedOffset::east_opt(seconds).expect("valid offset")
+    }
+
+    #[test]
+    fn parses_utc_and_fixed_offsets() {
+        assert_eq!(Timezone::parse("UTC"), Some(Timezone::Fixed(offset(0))));
+        assert_eq!(
+            Timezone::parse("+05:30"),
+            Some(Timezone::Fixed(offset(5 * 3600 + 30 * 60)))
+        );
+        assert_eq!(
+            Timezone::parse("-0800"),
+            Some(Timezone::Fixed(offset(-8 * 3600)))
+        );
+        assert_eq!(
+            Timezone::parse("utc+2"),
+            Some(Timezone::Fixed(offset(2 * 3600)))
+        );
+        assert_eq!(Timezone::parse("Europe/Berlin"), None);
+        assert_eq!(Timezone::parse("+25:00"), None);
+        assert_eq!(Timezone::Fixed(offset(0)).describe(), "UTC");
+        assert_eq!(
+            Timezone::Fixed(offset(-(3 * 3600 + 30 * 60))).describe(),
+            "UTC-03:30"
+        );
+    }
+
+    #[test]
+    fn resolves_overrides_and_derives_units_from_the_region() -> io::Result<()> {
+        let locale = LocaleContext::resolve(LocaleToml {
+            language: Some("en_US.UTF-8".to_string()),
+            timezone: Some("+09:00".to_string()),
+            units: None,
+        })?;
+        assert_eq!(
+            locale,
+            LocaleContext {
+                language: Some("en-US".to_string()),
+                timezone: Some(Timezone::Fixed(offset(9 * 3600))),
+                units: Some(Units::Imperial),
+            }
+        );
+        let timestamp = DateTime::<Utc>::from_timestamp(0, 0).expect("epoch");
+        assert_eq!(
+            locale.format_timestamp(timestamp, "%Y-%m-%d %H:%M"),
+            "1970-01-01 09:00"
+        );
+
+        let locale = LocaleContext::resolve(LocaleToml {
+            language: Some("de-DE".to_string()),
+            timezone: None,
+            units: None,
+        })?;
+        assert_eq!(locale.units, Some(Units::Metric));
+
+        let err = LocaleContext::resolve(LocaleToml {
+            language: None,
+            timezone: Some("Mars/Olympus".to_string()),
+            units: None,
+        })
+        .expect_err("unknown timezone");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        Ok(())
+    }
+}
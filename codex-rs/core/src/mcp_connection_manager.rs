@@ -588,12 +588,15 @@ impl McpConnectionManager {
         aggregated
     }
 
-    /// Invoke the tool indicated by the (server, tool) pair.
+    /// Invoke the tool indicated by the (server, tool) pair. Cancelling
+    /// `cancellation_token` tells the server to stop and fails the call.
+    #[instrument(level = "info", skip(self, arguments, cancellation_token))]
     pub async fn call_tool(
         &self,
         server: &str,
         tool: &str,
         arguments: Option<serde_json::Value>,
+        cancellation_token: CancellationToken,
     ) -> Result<mcp_types::CallToolResult> {
         let client = self.client_by_name(server).await?;
         if !client.tool_filter.allows(tool) {
@@ -604,7 +607,12 @@ impl McpConnectionManager {
 
         client
             .client
-            .call_tool(tool.to_string(), arguments, client.tool_timeout)
+            .call_tool(
+                tool.to_string(),
+                arguments,
+                client.tool_timeout,
+                cancellation_token,
+            )
             .await
             .with_context(|| format!("tool call failed for `{server}/{tool}`"))
     }
@@ -1,16 +1,30 @@
+use std::future::Future;
 use std::time::Instant;
 
+use codex_rmcp_client::ToolCallFailure;
+use mcp_types::CallToolResult;
+use tokio_util::sync::CancellationToken;
 use tracing::error;
+use tracing::warn;
 
+use crate::artifacts::ArtifactQuota;
+use crate::artifacts::persist_blob_resources;
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::protocol::EventMsg;
 use crate::protocol::McpInvocation;
 use crate::protocol::McpToolCallBeginEvent;
 use crate::protocol::McpToolCallEndEvent;
+use crate::protocol::McpToolCallErrorInfo;
+use crate::protocol::McpToolCallErrorKind;
+use crate::protocol::ToolArtifactEvent;
+use crate::util::backoff;
 use codex_protocol::models::FunctionCallOutputPayload;
 use codex_protocol::models::ResponseInputItem;
 
+/// Automatic retries of a call whose request never reached the server.
+const MAX_TRANSIENT_RETRIES: u32 = 2;
+
 /// Handles the specified tool call dispatches the appropriate
 /// `McpToolCallBegin` and `McpToolCallEnd` events to the `Session`.
 pub(crate) async fn handle_mcp_tool_call(
@@ -20,6 +34,7 @@ pub(crate) async fn handle_mcp_tool_call(
     server: String,
     tool_name: String,
     arguments: String,
+    cancellation_token: CancellationToken,
 ) -> ResponseInputItem {
     // Parse the `arguments` as JSON. An empty string is OK, but invalid JSON
     // is not.
@@ -55,19 +70,63 @@ pub(crate) async fn handle_mcp_tool_call(
     notify_mcp_tool_call_event(sess, turn_context, tool_call_begin_event).await;
 
     let start = Instant::now();
-    // Perform the tool call.
-    let result = sess
-        .call_tool(&server, &tool_name, arguments_value.clone())
-        .await
-        .map_err(|e| format!("tool call error: {e:?}"));
-    if let Err(e) = &result {
-        tracing::warn!("MCP tool call error: {e:?}");
+    let mut result = call_with_retries(&cancellation_token, || {
+        sess.call_tool(
+            &server,
+            &tool_name,
+            arguments_value.clone(),
+            cancellation_token.clone(),
+        )
+    })
+    .await;
+    if let Err(err) = &result
+        && err.attempts > 1
+    {
+        warn!(
+            "MCP tool call {server}/{tool_name} failed after {} attempts: {}",
+            err.attempts, err.message
+        );
     }
+    match &mut result {
+        Ok(call_tool_result) => {
+            let config = sess.get_config().await;
+            // Incognito sessions keep blobs inline instead of saving them.
+            if !config.incognito {
+                let artifacts = persist_blob_resources(
+                    &config.codex_home,
+                    &sess.conversation_id,
+                    &call_id,
+                    &ArtifactQuota::from(&config.artifacts),
+                    call_tool_result,
+                )
+                .await;
+                for artifact in artifacts {
+                    let event = EventMsg::ToolArtifact(ToolArtifactEvent {
+                        call_id: call_id.clone(),
+                        artifact,
+                    });
+                    sess.send_event(turn_context, event).await;
+                }
+            }
+        }
+        Err(e) => warn!("MCP tool call error: {e:?}"),
+    }
+    // Clients show the message; the model gets the structured error so it can
+    // tell whether calling again is worthwhile.
+    let (result, model_result, error) = match result {
+        Ok(result) => (Ok(result.clone()), Ok(result), None),
+        Err(error) => (
+            Err(error.message.clone()),
+            Err(serde_json::to_string(&error).unwrap_or_else(|_| error.message.clone())),
+            Some(error),
+        ),
+    };
     let tool_call_end_event = EventMsg::McpToolCallEnd(McpToolCallEndEvent {
         call_id: call_id.clone(),
         invocation,
         duration: start.elapsed(),
         result: result.clone(),
+        error,
     });
 
     notify_mcp_tool_call_event(sess, turn_context, tool_call_end_event.clone()).await;
@@ -78,9 +137,180 @@ pub(crate) async fn handle_mcp_tool_call(
         .get_otel_manager()
         .counter("codex.mcp.call", 1, &[("status", status)]);
 
-    ResponseInputItem::McpToolCallOutput { call_id, result }
+    ResponseInputItem::McpToolCallOutput {
+        call_id,
+        result: model_result,
+    }
+}
+
+/// Runs `call`, calling again only when the request provably never reached
+/// the server: a call that was sent may already have had side effects.
+async fn call_with_retries<F, Fut>(
+    cancellation_token: &CancellationToken,
+    mut call: F,
+) -> Result<CallToolResult, McpToolCallErrorInfo>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = anyhow::Result<CallToolResult>>,
+{
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let err = match call().await {
+            Ok(result) => return Ok(result),
+            Err(err) => err,
+        };
+        let never_sent = matches!(
+            err.downcast_ref::<ToolCallFailure>(),
+            Some(ToolCallFailure::NotSent(_))
+        );
+        let info = classify_tool_call_error(&err, attempts);
+        if !never_sent || attempts > MAX_TRANSIENT_RETRIES || cancellation_token.is_cancelled() {
+            return Err(info);
+        }
+        tokio::select! {
+            () = tokio::time::sleep(backoff(u64::from(attempts))) => {}
+            () = cancellation_token.cancelled() => return Err(info),
+        }
+    }
 }
 
 async fn notify_mcp_tool_call_event(sess: &Session, turn_context: &TurnContext, event: EventMsg) {
     sess.send_event(turn_context, event).await;
 }
+
+/// Maps a failed `tools/call` to the error taxonomy reported to the model
+/// and to clients.
+fn classify_tool_call_error(err: &anyhow::Error, attempts: u32) -> McpToolCallErrorInfo {
+    let message = format!("{err:#}");
+    let (kind, code) = match err.downcast_ref::<ToolCallFailure>() {
+        Some(ToolCallFailure::Rpc { code, .. }) => {
+            let kind = match code {
+                // Parse error, invalid request, invalid params.
+                -32700 | -32600 | -32602 => McpToolCallErrorKind::InvalidParams,
+                // Method not found; MCP's resource not found.
+                -32601 | -32002 => McpToolCallErrorKind::NotFound,
+                _ => McpToolCallErrorKind::Internal,
+            };
+            (kind, Some(i64::from(*code)))
+        }
+        Some(ToolCallFailure::Timeout(_)) => (McpToolCallErrorKind::Timeout, None),
+        Some(ToolCallFailure::NotSent(_) | ToolCallFailure::Transport(_)) => {
+            (McpToolCallErrorKind::ServerBusy, None)
+        }
+        None => (McpToolCallErrorKind::Internal, None),
+    };
+    McpToolCallErrorInfo {
+        kind,
+        code,
+        message,
+        retryable: matches!(
+            kind,
+            McpToolCallErrorKind::ServerBusy | McpToolCallErrorKind::Timeout
+        ),
+        attempts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    #[test]
+    fn rpc_errors_are_classified_by_code() {
+        let err = anyhow::Error::new(ToolCallFailure::Rpc {
+            code: -32602,
+            message: "missing field `path`".to_string(),
+        })
+        .context("tool call failed for `fs/read`");
+
+        assert_eq!(
+            classify_tool_call_error(&err, 1),
+            McpToolCallErrorInfo {
+                kind: McpToolCallErrorKind::InvalidParams,
+                code: Some(-32602),
+                message: "tool call failed for `fs/read`: tools/call failed: missing field `path` (JSON-RPC error -32602)".to_string(),
+                retryable: false,
+                attempts: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn transport_failures_and_timeouts_are_retryable() {
+        let busy = classify_tool_call_error(
+            &anyhow::Error::new(ToolCallFailure::Transport("Transport closed".to_string())),
+            3,
+        );
+        assert_eq!(busy.kind, McpToolCallErrorKind::ServerBusy);
+        assert!(busy.retryable);
+        assert_eq!(busy.attempts, 3);
+
+        let timeout = classify_tool_call_error(
+            &anyhow::Error::new(ToolCallFailure::Timeout(Duration::from_secs(60))),
+            1,
+        );
+        assert_eq!(timeout.kind, McpToolCallErrorKind::Timeout);
+        assert!(timeout.retryable);
+
+        let unknown = classify_tool_call_error(&anyhow::anyhow!("unknown MCP server 'x'"), 1);
+        assert_eq!(unknown.kind, McpToolCallErrorKind::Internal);
+        assert!(!unknown.retryable);
+    }
+
+    fn ok_result() -> CallToolResult {
+        CallToolResult {
+            content: Vec::new(),
+            is_error: None,
+            structured_content: None,
+        }
+    }
+
+    /// A server whose first call fails with `failure`.
+    async fn call_failing_once(
+        failure: ToolCallFailure,
+    ) -> (Result<CallToolResult, McpToolCallErrorInfo>, u32) {
+        let mut calls = 0;
+        let result = call_with_retries(&CancellationToken::new(), || {
+            calls += 1;
+            let result = if calls == 1 {
+                Err(anyhow::Error::new(failure.clone()))
+            } else {
+                Ok(ok_result())
+            };
+            async move { result }
+        })
+        .await;
+        (result, calls)
+    }
+
+    #[tokio::test]
+    async fn calls_that_never_left_are_retried() {
+        let (result, calls) =
+            call_failing_once(ToolCallFailure::NotSent("Transport closed".to_string())).await;
+
+        assert_eq!((result, calls), (Ok(ok_result()), 2));
+    }
+
+    #[tokio::test]
+    async fn calls_that_were_sent_are_not_retried() {
+        let (result, calls) =
+            call_failing_once(ToolCallFailure::Transport("connection reset".to_string())).await;
+
+        assert_eq!(
+            (result, calls),
+            (
+                Err(McpToolCallErrorInfo {
+                    kind: McpToolCallErrorKind::ServerBusy,
+                    code: None,
+                    message: "tools/call failed: connection reset".to_string(),
+                    retryable: true,
+                    attempts: 1,
+                }),
+                1
+            )
+        );
+    }
+}
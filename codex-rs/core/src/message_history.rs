@@ -74,6 +74,9 @@ pub(crate) async fn append_entry(
     conversation_id: &ThreadId,
     config: &Config,
 ) -> Result<()> {
+    if config.incognito {
+        return Ok(());
+    }
     match config.history.persistence {
         HistoryPersistence::SaveAll => {
             // Save everything: proceed.
@@ -613,4 +616,21 @@ mod tests {
         assert_eq!(pruned_len, long_entry_len);
         assert!(pruned_len <= soft_cap_bytes.max(long_entry_len));
     }
+
+    #[tokio::test]
+    async fn append_entry_writes_nothing_when_incognito() {
+        let codex_home = TempDir::new().expect("create temp dir");
+        let mut config = ConfigBuilder::default()
+            .codex_home(codex_home.path().to_path_buf())
+            .build()
+            .await
+            .expect("load config");
+        config.incognito = true;
+
+        append_entry("secret", &ThreadId::new(), &config)
+            .await
+            .expect("append history entry");
+
+        assert!(!history_filepath(&config).exists());
+    }
 }
@@ -0,0 +1,551 @@
+//! Scriptable local model provider for integration tests.
+//!
+//! [`MockProviderServer`] listens on a loopback port and answers model
+//! requests from a [`MockScenario`]: an ordered list of steps, each consumed
+//! by one request. A step either streams server-sent events or fails with a
+//! status code, and can attach arbitrary response headers (for example the
+//! `x-codex-*` rate-limit headers or `retry-after`). Requests to
+//! `.../responses` are framed for the Responses API and requests to
+//! `.../chat/completions` for Chat Completions, so the same scenario format
+//! drives either wire API.
+//!
+//! Scenarios can be built in code or loaded from a JSON file:
+//!
+//! ```json
+//! {
+//!   "steps": [
+//!     { "type": "error", "status": 429, "headers": { "retry-after": "1" } },
+//!     {
+//!       "type": "sse",
+//!       "headers": { "x-codex-primary-used-percent": "42" },
+//!       "events": [
+//!         { "type": "response.created", "response": { "id": "resp-1" } },
+//!         { "type": "response.completed", "response": { "id": "resp-1" } }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Available to downstream crates through the `test-support` feature.
+
+use std::collections::BTreeMap;
+use std::collections::VecDeque;
+use std::io;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+use tokio::net::TcpStream;
+use tokio::task::JoinHandle;
+
+use crate::model_provider_info::ModelProviderInfo;
+use crate::model_provider_info::WireApi;
+
+/// Largest request head (request line plus headers) the server accepts.
+const MAX_HEAD_BYTES: usize = 64 * 1024;
+
+/// Ordered responses served by a [`MockProviderServer`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MockScenario {
+    #[serde(default)]
+    pub steps: Vec<MockStep>,
+}
+
+impl MockScenario {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads a scenario from a JSON file.
+    pub fn from_file(path: &Path) -> io::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        serde_json::from_str(&contents)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// Appends a step to the scenario.
+    pub fn step(mut self, step: MockStep) -> Self {
+        self.steps.push(step);
+        self
+    }
+}
+
+/// How the server answers a single model request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MockStep {
+    /// Respond with `200 OK` and stream `events` as server-sent events.
+    Sse {
+        events: Vec<Value>,
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        headers: BTreeMap<String, String>,
+        /// Pause between consecutive events.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        event_delay_ms: Option<u64>,
+        /// Close the connection after this many events instead of finishing
+        /// the stream, simulating a dropped connection.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        disconnect_after: Option<usize>,
+    },
+    /// Respond with a non-success status and `body`.
+    Error {
+        status: u16,
+        #[serde(default)]
+        body: String,
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        headers: BTreeMap<String, String>,
+    },
+}
+
+impl MockStep {
+    /// A successful stream of `events` with no extra headers.
+    pub fn sse(events: Vec<Value>) -> Self {
+        Self::Sse {
+            events,
+            headers: BTreeMap::new(),
+            event_delay_ms: None,
+            disconnect_after: None,
+        }
+    }
+
+    /// A failure with the given status and body.
+    pub fn error(status: u16, body: impl Into<String>) -> Self {
+        Self::Error {
+            status,
+            body: body.into(),
+            headers: BTreeMap::new(),
+        }
+    }
+
+    /// A `429 Too Many Requests` that asks the client to retry after
+    /// `retry_after_secs`.
+    pub fn rate_limited(retry_after_secs: u64) -> Self {
+        Self::error(
+            429,
+            r#"{"error":{"type":"rate_limit_exceeded","message":"Rate limit reached"}}"#,
+        )
+        .with_header("retry-after", retry_after_secs.to_string())
+    }
+
+    /// Adds a response header to this step.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        match &mut self {
+            Self::Sse { headers, .. } | Self::Error { headers, .. } => {
+                headers.insert(name.into(), value.into());
+            }
+        }
+        self
+    }
+}
+
+/// A request received by the server, recorded in arrival order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockRequest {
+    pub method: String,
+    pub path: String,
+    /// Header names are lowercased.
+    pub headers: BTreeMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl MockRequest {
+    pub fn body_json(&self) -> Option<Value> {
+        serde_json::from_slice(&self.body).ok()
+    }
+}
+
+#[derive(Default)]
+struct ServerState {
+    steps: VecDeque<MockStep>,
+    requests: Vec<MockRequest>,
+}
+
+/// Loopback HTTP server that replays a [`MockScenario`]. The server stops
+/// when dropped.
+pub struct MockProviderServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<ServerState>>,
+    task: JoinHandle<()>,
+}
+
+impl MockProviderServer {
+    pub async fn start(scenario: MockScenario) -> io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(ServerState {
+            steps: scenario.steps.into(),
+            requests: Vec::new(),
+        }));
+        let task_state = Arc::clone(&state);
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let state = Arc::clone(&task_state);
+                tokio::spawn(async move {
+                    if let Err(err) = serve_connection(stream, state).await {
+                        tracing::debug!("mock provider connection failed: {err}");
+                    }
+                });
+            }
+        });
+        Ok(Self { addr, state, task })
+    }
+
+    /// Base URL to configure as a provider's `base_url`, e.g.
+    /// `http://127.0.0.1:1234/v1`.
+    pub fn base_url(&self) -> String {
+        format!("http://{}/v1", self.addr)
+    }
+
+    /// A provider pointing at this server that speaks `wire_api` and needs no
+    /// credentials or retries.
+    pub fn provider(&self, wire_api: WireApi) -> ModelProviderInfo {
+        ModelProviderInfo {
+            name: "mock".to_string(),
+            base_url: Some(self.base_url()),
+            fallback_base_urls: None,
+            env_key: None,
+            env_key_instructions: None,
+            experimental_bearer_token: None,
+            wire_api,
+            query_params: None,
+            http_headers: None,
+            env_http_headers: None,
+            request_max_retries: Some(0),
+            stream_max_retries: Some(0),
+            stream_idle_timeout_ms: Some(5_000),
+            requires_openai_auth: false,
+        }
+    }
+
+    /// Every request received so far.
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.lock_state().requests.clone()
+    }
+
+    /// Number of scenario steps that have not been consumed yet.
+    pub fn remaining_steps(&self) -> usize {
+        self.lock_state().steps.len()
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, ServerState> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl Drop for MockProviderServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Framing {
+    Responses,
+    Chat,
+}
+
+async fn serve_connection(mut stream: TcpStream, state: Arc<Mutex<ServerState>>) -> io::Result<()> {
+    let request = read_request(&mut stream).await?;
+    let framing = if request.path.contains("/chat/completions") {
+        Some(Framing::Chat)
+    } else if request.path.contains("/responses") {
+        Some(Framing::Responses)
+    } else {
+        None
+    };
+    let step = {
+        let mut state = state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        state.requests.push(request);
+        match framing {
+            Some(_) => state.steps.pop_front(),
+            None => None,
+        }
+    };
+
+    match (framing, step) {
+        (None, _) => write_simple(&mut stream, 404, "not found", &BTreeMap::new()).await,
+        (Some(_), None) => {
+            write_simple(
+                &mut stream,
+                500,
+                "mock scenario exhausted",
+                &BTreeMap::new(),
+            )
+            .await
+        }
+        (
+            Some(_),
+            Some(MockStep::Error {
+                status,
+                body,
+                headers,
+            }),
+        ) => write_simple(&mut stream, status, &body, &headers).await,
+        (
+            Some(framing),
+            Some(MockStep::Sse {
+                events,
+                headers,
+                event_delay_ms,
+                disconnect_after,
+            }),
+        ) => {
+            let mut head = String::from("HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\n");
+            push_headers(&mut head, &headers);
+            head.push_str("connection: close\r\n\r\n");
+            stream.write_all(head.as_bytes()).await?;
+
+            let limit = disconnect_after.unwrap_or(events.len());
+            for (index, event) in events.iter().take(limit).enumerate() {
+                if index > 0
+                    && let Some(delay) = event_delay_ms
+                {
+                    tokio::time::sleep(Duration::from_millis(delay)).await;
+                }
+                stream
+                    .write_all(frame_event(framing, event).as_bytes())
+                    .await?;
+                stream.flush().await?;
+            }
+            if disconnect_after.is_none() && matches!(framing, Framing::Chat) {
+                stream.write_all(b"data: [DONE]\n\n").await?;
+            }
+            stream.shutdown().await
+        }
+    }
+}
+
+fn frame_event(framing: Framing, event: &Value) -> String {
+    match framing {
+        Framing::Responses => {
+            let kind = event
+                .get("type")
+                .and_then(Value::as_str)
+                .unwrap_or("message");
+            format!("event: {kind}\ndata: {event}\n\n")
+        }
+        Framing::Chat => format!("data: {event}\n\n"),
+    }
+}
+
+fn push_headers(head: &mut String, headers: &BTreeMap<String, String>) {
+    for (name, value) in headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+}
+
+async fn write_simple(
+    stream: &mut TcpStream,
+    status: u16,
+    body: &str,
+    headers: &BTreeMap<String, String>,
+) -> io::Result<()> {
+    let mut head = format!(
+        "HTTP/1.1 {status} {}\r\ncontent-length: {}\r\n",
+        reason_phrase(status),
+        body.len()
+    );
+    if !headers
+        .keys()
+        .any(|name| name.eq_ignore_ascii_case("content-type"))
+    {
+        head.push_str("content-type: application/json\r\n");
+    }
+    push_headers(&mut head, headers);
+    head.push_str("connection: close\r\n\r\n");
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        _ => "Status",
+    }
+}
+
+/// Reads one HTTP/1.1 request. Bodies must carry a `content-length`, which is
+/// what the model clients send.
+async fn read_request(stream: &mut TcpStream) -> io::Result<MockRequest> {
+    let mut buf = Vec::new();
+    let head_end = loop {
+        if let Some(pos) = buf.windows(4).position(|window| window == b"\r\n\r\n") {
+            break pos;
+        }
+        if buf.len() > MAX_HEAD_BYTES {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "request head too large",
+            ));
+        }
+        let mut chunk = [0u8; 4096];
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        buf.extend_from_slice(&chunk[..read]);
+    };
+
+    let head = String::from_utf8_lossy(&buf[..head_end]).into_owned();
+    let mut lines = head.split("\r\n");
+    let request_line = lines.next().unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect::<BTreeMap<_, _>>();
+
+    let content_length = headers
+        .get("content-length")
+        .and_then(|value| value.parse::<usize>().ok())
+        .unwrap_or(0);
+    let mut body = buf.split_off(head_end + 4);
+    while body.len() < content_length {
+        let mut chunk = vec![0u8; content_length - body.len()];
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        body.extend_from_slice(&chunk[..read]);
+    }
+    body.truncate(content_length);
+
+    Ok(MockRequest {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn replays_steps_in_order_for_both_wire_apis() {
+        let server = MockProviderServer::start(
+            MockScenario::new()
+                .step(MockStep::rate_limited(2))
+                .step(
+                    MockStep::sse(vec![
+                        json!({"response": {"id": "r1"}, "type": "response.created"}),
+                        json!({"response": {"id": "r1"}, "type": "response.completed"}),
+                    ])
+                    .with_header("x-codex-primary-used-percent", "42"),
+                )
+                .step(MockStep::sse(vec![json!({"choices": []})])),
+        )
+        .await
+        .expect("start server");
+        let client = reqwest::Client::new();
+        let base_url = server.base_url();
+
+        let limited = client
+            .post(format!("{base_url}/responses"))
+            .json(&json!({"model": "mock"}))
+            .send()
+            .await
+            .expect("first request");
+        assert_eq!(limited.status().as_u16(), 429);
+        assert_eq!(
+            limited
+                .headers()
+                .get("retry-after")
+                .and_then(|value| value.to_str().ok()),
+            Some("2")
+        );
+
+        let streamed = client
+            .post(format!("{base_url}/responses"))
+            .json(&json!({"model": "mock"}))
+            .send()
+            .await
+            .expect("second request");
+        assert_eq!(
+            streamed
+                .headers()
+                .get("x-codex-primary-used-percent")
+                .and_then(|value| value.to_str().ok()),
+            Some("42")
+        );
+        assert_eq!(
+            streamed.text().await.expect("body"),
+            "event: response.created\ndata: {\"response\":{\"id\":\"r1\"},\"type\":\"response.created\"}\n\n\
+             event: response.completed\ndata: {\"response\":{\"id\":\"r1\"},\"type\":\"response.completed\"}\n\n"
+        );
+
+        let chat = client
+            .post(format!("{base_url}/chat/completions"))
+            .json(&json!({"model": "mock"}))
+            .send()
+            .await
+            .expect("third request");
+        assert_eq!(
+            chat.text().await.expect("body"),
+            "data: {\"choices\":[]}\n\ndata: [DONE]\n\n"
+        );
+
+        let exhausted = client
+            .post(format!("{base_url}/responses"))
+            .send()
+            .await
+            .expect("fourth request");
+        assert_eq!(exhausted.status().as_u16(), 500);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 4);
+        assert_eq!(requests[0].path, "/v1/responses");
+        assert_eq!(requests[0].body_json(), Some(json!({"model": "mock"})));
+        assert_eq!(server.remaining_steps(), 0);
+    }
+
+    #[test]
+    fn parses_scenario_files() {
+        let scenario: MockScenario = serde_json::from_value(json!({
+            "steps": [
+                {"type": "error", "status": 503},
+                {"type": "sse", "events": [], "disconnect_after": 0},
+            ]
+        }))
+        .expect("valid scenario");
+
+        assert_eq!(
+            scenario,
+            MockScenario::new()
+                .step(MockStep::error(503, ""))
+                .step(MockStep::Sse {
+                    events: Vec::new(),
+                    headers: BTreeMap::new(),
+                    event_delay_ms: None,
+                    disconnect_after: Some(0),
+                })
+        );
+    }
+}
@@ -17,9 +17,11 @@ use serde::Deserialize;
 use serde::Serialize;
 use std::collections::HashMap;
 use std::env::VarError;
+use std::sync::Arc;
 use std::time::Duration;
 
 use crate::error::EnvVarError;
+use crate::provider_endpoints::EndpointPool;
 const DEFAULT_STREAM_IDLE_TIMEOUT_MS: u64 = 300_000;
 const DEFAULT_STREAM_MAX_RETRIES: u64 = 5;
 const DEFAULT_REQUEST_MAX_RETRIES: u64 = 4;
@@ -50,6 +52,10 @@ pub enum WireApi {
     /// Regular Chat Completions compatible with `/v1/chat/completions`.
     #[default]
     Chat,
+
+    /// Anthropic's Messages API at `/v1/messages`, streamed over SSE.
+    #[serde(rename = "anthropic_messages")]
+    AnthropicMessages,
 }
 
 /// Serializable representation of a provider definition.
@@ -60,6 +66,10 @@ pub struct ModelProviderInfo {
     pub name: String,
     /// Base URL for the provider's OpenAI-compatible API.
     pub base_url: Option<String>,
+    /// Additional base URLs serving the same API, tried in order when
+    /// `base_url` fails with connection errors or 5xx responses. The primary
+    /// is retried periodically and used again once it recovers.
+    pub fallback_base_urls: Option<Vec<String>>,
     /// Environment variable that stores the user's API key for this provider.
     pub env_key: Option<String>,
 
@@ -133,19 +143,34 @@ impl ModelProviderInfo {
         Ok(headers)
     }
 
-    pub(crate) fn to_api_provider(
-        &self,
-        auth_mode: Option<AuthMode>,
-    ) -> crate::error::Result<ApiProvider> {
+    pub(crate) fn primary_base_url(&self, auth_mode: Option<AuthMode>) -> String {
         let default_base_url = if matches!(auth_mode, Some(AuthMode::ChatGPT)) {
             "https://chatgpt.com/backend-api/codex"
         } else {
             "https://api.openai.com/v1"
         };
-        let base_url = self
-            .base_url
+        self.base_url
             .clone()
-            .unwrap_or_else(|| default_base_url.to_string());
+            .unwrap_or_else(|| default_base_url.to_string())
+    }
+
+    /// Shared health tracker for the provider's endpoints, or `None` when no
+    /// `fallback_base_urls` are configured.
+    pub(crate) fn endpoint_pool(&self, auth_mode: Option<AuthMode>) -> Option<Arc<EndpointPool>> {
+        let fallbacks = self
+            .fallback_base_urls
+            .as_ref()
+            .filter(|urls| !urls.is_empty())?;
+        let mut base_urls = vec![self.primary_base_url(auth_mode)];
+        base_urls.extend(fallbacks.iter().cloned());
+        Some(EndpointPool::for_provider(&self.name, base_urls))
+    }
+
+    pub(crate) fn to_api_provider(
+        &self,
+        auth_mode: Option<AuthMode>,
+    ) -> crate::error::Result<ApiProvider> {
+        let base_url = self.primary_base_url(auth_mode);
 
         let headers = self.build_header_map()?;
         let retry = ApiRetryConfig {
@@ -164,6 +189,7 @@ impl ModelProviderInfo {
                 WireApi::Responses => ApiWireApi::Responses,
                 WireApi::ResponsesWebsocket => ApiWireApi::Responses,
                 WireApi::Chat => ApiWireApi::Chat,
+                WireApi::AnthropicMessages => ApiWireApi::AnthropicMessages,
             },
             headers,
             retry,
@@ -228,6 +254,7 @@ impl ModelProviderInfo {
             base_url: std::env::var("OPENAI_BASE_URL")
                 .ok()
                 .filter(|v| !v.trim().is_empty()),
+            fallback_base_urls: None,
             env_key: None,
             env_key_instructions: None,
             experimental_bearer_token: None,
@@ -321,6 +348,7 @@ pub fn create_oss_provider_with_base_url(base_url: &str, wire_api: WireApi) -> M
     ModelProviderInfo {
         name: "gpt-oss".into(),
         base_url: Some(base_url.into()),
+        fallback_base_urls: None,
         env_key: None,
         env_key_instructions: None,
         experimental_bearer_token: None,
@@ -349,6 +377,7 @@ base_url = "http://localhost:11434/v1"
         let expected_provider = ModelProviderInfo {
             name: "Ollama".into(),
             base_url: Some("http://localhost:11434/v1".into()),
+            fallback_base_urls: None,
             env_key: None,
             env_key_instructions: None,
             experimental_bearer_token: None,
@@ -377,6 +406,7 @@ query_params = { api-version = "2025-04-01-preview" }
         let expected_provider = ModelProviderInfo {
             name: "Azure".into(),
             base_url: Some("https://xxxxx.openai.azure.com/openai".into()),
+            fallback_base_urls: None,
             env_key: Some("AZURE_OPENAI_API_KEY".into()),
             env_key_instructions: None,
             experimental_bearer_token: None,
@@ -408,6 +438,7 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
         let expected_provider = ModelProviderInfo {
             name: "Example".into(),
             base_url: Some("https://example.com".into()),
+            fallback_base_urls: None,
             env_key: Some("API_KEY".into()),
             env_key_instructions: None,
             experimental_bearer_token: None,
@@ -443,6 +474,7 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
             let provider = ModelProviderInfo {
                 name: "test".into(),
                 base_url: Some(base_url.into()),
+                fallback_base_urls: None,
                 env_key: None,
                 env_key_instructions: None,
                 experimental_bearer_token: None,
@@ -465,6 +497,7 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
         let named_provider = ModelProviderInfo {
             name: "Azure".into(),
             base_url: Some("https://example.com".into()),
+            fallback_base_urls: None,
             env_key: None,
             env_key_instructions: None,
             experimental_bearer_token: None,
@@ -489,6 +522,7 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
             let provider = ModelProviderInfo {
                 name: "test".into(),
                 base_url: Some(base_url.into()),
+                fallback_base_urls: None,
                 env_key: None,
                 env_key_instructions: None,
                 experimental_bearer_token: None,
@@ -508,4 +542,25 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
             );
         }
     }
+
+    #[test]
+    fn fallback_base_urls_follow_the_primary_in_failover_order() {
+        let provider_toml = r#"
+name = "Azure"
+base_url = "https://eastus.example.com/openai"
+fallback_base_urls = ["https://westus.example.com/openai", "https://replica.internal/openai"]
+wire_api = "responses"
+        "#;
+        let provider: ModelProviderInfo = toml::from_str(provider_toml).expect("parse provider");
+
+        let pool = provider.endpoint_pool(None).expect("endpoint pool");
+        assert_eq!(pool.len(), 3);
+        assert_eq!(pool.select(), (0, "https://eastus.example.com/openai"));
+
+        let single = ModelProviderInfo {
+            fallback_base_urls: Some(Vec::new()),
+            ..provider
+        };
+        assert!(single.endpoint_pool(None).is_none());
+    }
 }
@@ -0,0 +1,126 @@
+//! Adapts a running session to a model switch mid-conversation.
+//!
+//! Base instructions, built-in tools, and the token budget are all derived
+//! from the model. When a turn switches models they are recomputed for the new
+//! one and the differences are reported as a [`ModelSwitchEvent`], so that e.g.
+//! moving from a GPT model to an OSS model doesn't silently change the tools.
+
+use std::collections::BTreeSet;
+
+use codex_protocol::config_types::Personality;
+use codex_protocol::openai_models::ModelInfo;
+use codex_protocol::protocol::ModelSwitchEvent;
+
+use crate::tools::router::ToolRouter;
+use crate::tools::spec::ToolsConfig;
+
+/// Returns the new model's base instructions when `current` was rendered for
+/// the previous model. Instructions from config, a resumed recording of
+/// another model, or any other source are left alone.
+pub(crate) fn rerender_base_instructions(
+    current: &str,
+    previous_model: &ModelInfo,
+    previous_personality: Option<Personality>,
+    next_model: &ModelInfo,
+    next_personality: Option<Personality>,
+) -> Option<String> {
+    if current != previous_model.get_model_instructions(previous_personality) {
+        return None;
+    }
+    let next = next_model.get_model_instructions(next_personality);
+    (next != current).then_some(next)
+}
+
+/// Names of the built-in tools offered under `tools_config`. MCP tools do not
+/// depend on the model and are left out.
+pub(crate) fn builtin_tool_names(tools_config: &ToolsConfig) -> BTreeSet<String> {
+    ToolRouter::from_config(tools_config, None)
+        .specs()
+        .iter()
+        .map(|spec| spec.name().to_string())
+        .collect()
+}
+
+/// Inputs for [`describe_model_switch`] taken from the turn contexts and
+/// session state on either side of the switch.
+pub(crate) struct ModelSwitch<'a> {
+    pub previous_model: &'a ModelInfo,
+    pub previous_tools: &'a ToolsConfig,
+    pub previous_context_window: Option<i64>,
+    pub next_model: &'a ModelInfo,
+    pub next_tools: &'a ToolsConfig,
+    pub next_context_window: Option<i64>,
+    pub instructions_updated: bool,
+    pub tokens_in_context: i64,
+}
+
+pub(crate) fn describe_model_switch(switch: ModelSwitch<'_>) -> ModelSwitchEvent {
+    let previous_tools = builtin_tool_names(switch.previous_tools);
+    let next_tools = builtin_tool_names(switch.next_tools);
+    let auto_compact_limit = switch
+        .next_model
+        .auto_compact_token_limit()
+        .unwrap_or(i64::MAX);
+    ModelSwitchEvent {
+        previous_model: switch.previous_model.slug.clone(),
+        model: switch.next_model.slug.clone(),
+        instructions_updated: switch.instructions_updated,
+        tools_added: next_tools.difference(&previous_tools).cloned().collect(),
+        tools_removed: previous_tools.difference(&next_tools).cloned().collect(),
+        previous_context_window: switch.previous_context_window,
+        context_window: switch.next_context_window,
+        tokens_in_context: switch.tokens_in_context,
+        compaction_pending: switch.tokens_in_context >= auto_compact_limit,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::test_config;
+    use crate::features::Features;
+    use crate::models_manager::manager::ModelsManager;
+    use crate::tools::spec::ToolsConfigParams;
+    use pretty_assertions::assert_eq;
+
+    fn tools_config(model_info: &ModelInfo) -> ToolsConfig {
+        ToolsConfig::new(&ToolsConfigParams {
+            model_info,
+            features: &Features::with_defaults(),
+            web_search_mode: None,
+        })
+    }
+
+    #[test]
+    fn switching_to_oss_model_reports_tools_instructions_and_budget() {
+        let config = test_config();
+        let gpt = ModelsManager::construct_model_info_offline("gpt-5.1-codex", &config);
+        let oss = ModelsManager::construct_model_info_offline("gpt-oss-120b", &config);
+
+        let instructions = gpt.get_model_instructions(None);
+        let rerendered = rerender_base_instructions(&instructions, &gpt, None, &oss, None);
+        assert_eq!(rerendered, Some(oss.get_model_instructions(None)));
+        assert_eq!(
+            rerender_base_instructions("custom instructions", &gpt, None, &oss, None),
+            None
+        );
+
+        let event = describe_model_switch(ModelSwitch {
+            previous_model: &gpt,
+            previous_tools: &tools_config(&gpt),
+            previous_context_window: Some(258_400),
+            next_model: &oss,
+            next_tools: &tools_config(&oss),
+            next_context_window: Some(91_200),
+            instructions_updated: true,
+            tokens_in_context: 100_000,
+        });
+        assert_eq!(
+            (event.previous_model.as_str(), event.model.as_str()),
+            ("gpt-5.1-codex", "gpt-oss-120b")
+        );
+        assert!(event.tools_added.contains(&"shell".to_string()));
+        assert!(event.tools_removed.contains(&"shell_command".to_string()));
+        assert!(event.compaction_pending);
+    }
+}
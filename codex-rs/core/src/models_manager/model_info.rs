@@ -1,9 +1,14 @@
 use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::sync::PoisonError;
 
 use codex_protocol::config_types::Personality;
 use codex_protocol::config_types::Verbosity;
 use codex_protocol::openai_models::ApplyPatchToolType;
 use codex_protocol::openai_models::ConfigShellToolType;
+use codex_protocol::openai_models::InputModality;
 use codex_protocol::openai_models::ModelInfo;
 use codex_protocol::openai_models::ModelInstructionsTemplate;
 use codex_protocol::openai_models::ModelVisibility;
@@ -12,8 +17,13 @@ use codex_protocol::openai_models::ReasoningEffort;
 use codex_protocol::openai_models::ReasoningEffortPreset;
 use codex_protocol::openai_models::TruncationMode;
 use codex_protocol::openai_models::TruncationPolicyConfig;
+use codex_protocol::openai_models::default_input_modalities;
+use regex_lite::Regex;
 
 use crate::config::Config;
+use crate::config::types::ApplyPatchToolChoice;
+use crate::config::types::ModelFamily;
+use crate::config::types::ModelInstructionsPreset;
 use crate::truncate::approx_bytes_for_tokens;
 use tracing::warn;
 
@@ -64,7 +74,9 @@ macro_rules! model_info {
             context_window: Some(CONTEXT_WINDOW_272K),
             auto_compact_token_limit: None,
             effective_context_window_percent: 95,
+            max_output_tokens: None,
             experimental_supported_tools: Vec::new(),
+            input_modalities: default_input_modalities(),
         };
 
         $(
@@ -75,9 +87,23 @@ macro_rules! model_info {
 }
 
 pub(crate) fn with_config_overrides(mut model: ModelInfo, config: &Config) -> ModelInfo {
+    if let Some(family) = config
+        .model_families
+        .iter()
+        .find(|family| model_family_matches(family, &model.slug))
+    {
+        apply_model_family(&mut model, family);
+    }
     if let Some(supports_reasoning_summaries) = config.model_supports_reasoning_summaries {
         model.supports_reasoning_summaries = supports_reasoning_summaries;
     }
+    if let Some(supports_image_input) = config.model_supports_image_input {
+        model.input_modalities = if supports_image_input {
+            default_input_modalities()
+        } else {
+            vec![InputModality::Text]
+        };
+    }
     if let Some(context_window) = config.model_context_window {
         model.context_window = Some(context_window);
     }
@@ -105,6 +131,90 @@ pub(crate) fn with_config_overrides(mut model: ModelInfo, config: &Config) -> Mo
     model
 }
 
+/// Compiled `model_families` patterns, keyed by their source. `None` marks a
+/// pattern that does not compile.
+static MODEL_FAMILY_PATTERNS: LazyLock<Mutex<HashMap<String, Option<Regex>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn model_family_matches(family: &ModelFamily, slug: &str) -> bool {
+    let mut patterns = MODEL_FAMILY_PATTERNS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    // Patterns are validated when the config is loaded.
+    patterns
+        .entry(family.pattern.clone())
+        .or_insert_with(|| Regex::new(&family.pattern).ok())
+        .as_ref()
+        .is_some_and(|pattern| pattern.is_match(slug))
+}
+
+fn apply_model_family(model: &mut ModelInfo, family: &ModelFamily) {
+    if let Some(context_window) = family.context_window {
+        model.context_window = Some(context_window);
+    }
+    if let Some(max_output_tokens) = family.max_output_tokens {
+        model.max_output_tokens = Some(max_output_tokens);
+    }
+    if let Some(instructions) = family.instructions {
+        let (base_instructions, template) = instructions_for_preset(instructions);
+        model.base_instructions = base_instructions.to_string();
+        model.model_instructions_template = template;
+    }
+    if let Some(shell_type) = family.shell_type {
+        model.shell_type = shell_type;
+    }
+    if let Some(apply_patch) = family.apply_patch {
+        model.apply_patch_tool_type = match apply_patch {
+            ApplyPatchToolChoice::Freeform => Some(ApplyPatchToolType::Freeform),
+            ApplyPatchToolChoice::Function => Some(ApplyPatchToolType::Function),
+            ApplyPatchToolChoice::None => None,
+        };
+    }
+    if let Some(supports_parallel_tool_calls) = family.supports_parallel_tool_calls {
+        model.supports_parallel_tool_calls = supports_parallel_tool_calls;
+    }
+    if let Some(supports_reasoning_summaries) = family.supports_reasoning_summaries {
+        model.supports_reasoning_summaries = supports_reasoning_summaries;
+    }
+    if let Some(supports_image_input) = family.supports_image_input {
+        model.input_modalities = if supports_image_input {
+            default_input_modalities()
+        } else {
+            vec![InputModality::Text]
+        };
+    }
+    if let Some(tools) = &family.experimental_supported_tools {
+        model.experimental_supported_tools = tools.clone();
+    }
+}
+
+fn instructions_for_preset(
+    preset: ModelInstructionsPreset,
+) -> (&'static str, Option<ModelInstructionsTemplate>) {
+    match preset {
+        ModelInstructionsPreset::Default => (BASE_INSTRUCTIONS, None),
+        ModelInstructionsPreset::WithApplyPatch => (BASE_INSTRUCTIONS_WITH_APPLY_PATCH, None),
+        ModelInstructionsPreset::Gpt5Codex => (GPT_5_CODEX_INSTRUCTIONS, None),
+        ModelInstructionsPreset::Gpt51 => (GPT_5_1_INSTRUCTIONS, None),
+        ModelInstructionsPreset::Gpt51CodexMax => (GPT_5_1_CODEX_MAX_INSTRUCTIONS, None),
+        ModelInstructionsPreset::Gpt52 => (GPT_5_2_INSTRUCTIONS, None),
+        ModelInstructionsPreset::Gpt52Codex => (
+            GPT_5_2_CODEX_INSTRUCTIONS,
+            Some(gpt_5_2_codex_instructions_template()),
+        ),
+    }
+}
+
+fn gpt_5_2_codex_instructions_template() -> ModelInstructionsTemplate {
+    ModelInstructionsTemplate {
+        template: GPT_5_2_CODEX_INSTRUCTIONS_TEMPLATE.to_string(),
+        personality_messages: Some(PersonalityMessages(BTreeMap::from([
+            (Personality::Friendly, PERSONALITY_FRIENDLY.to_string()),
+            (Personality::Pragmatic, PERSONALITY_PRAGMATIC.to_string()),
+        ]))),
+    }
+}
+
 // todo(aibrahim): remove most of the entries here when enabling models.json
 pub(crate) fn find_model_info_for_slug(slug: &str) -> ModelInfo {
     if slug.starts_with("o3") || slug.starts_with("o4-mini") {
@@ -156,6 +266,7 @@ pub(crate) fn find_model_info_for_slug(slug: &str) -> ModelInfo {
             experimental_supported_tools: vec![
                 "grep_files".to_string(),
                 "list_dir".to_string(),
+                "project_map".to_string(),
                 "read_file".to_string(),
                 "test_sync_tool".to_string(),
             ],
@@ -203,16 +314,7 @@ pub(crate) fn find_model_info_for_slug(slug: &str) -> ModelInfo {
             truncation_policy: TruncationPolicyConfig::tokens(10_000),
             context_window: Some(CONTEXT_WINDOW_272K),
             supported_reasoning_levels: supported_reasoning_level_low_medium_high_xhigh(),
-            model_instructions_template: Some(ModelInstructionsTemplate {
-                template: GPT_5_2_CODEX_INSTRUCTIONS_TEMPLATE.to_string(),
-                personality_messages: Some(PersonalityMessages(BTreeMap::from([(
-                    Personality::Friendly,
-                    PERSONALITY_FRIENDLY.to_string(),
-                ), (
-                    Personality::Pragmatic,
-                    PERSONALITY_PRAGMATIC.to_string(),
-                )]))),
-            }),
+            model_instructions_template: Some(gpt_5_2_codex_instructions_template()),
         )
     } else if slug.starts_with("gpt-5.1-codex-max") {
         model_info!(
@@ -6,6 +6,7 @@ use codex_protocol::parse_command::ParsedCommand;
 use shlex::split as shlex_split;
 use shlex::try_join as shlex_try_join;
 use std::path::PathBuf;
+use tree_sitter::Node;
 
 pub fn shlex_join(tokens: &[String]) -> String {
     shlex_try_join(tokens.iter().map(String::as_str))
@@ -40,6 +41,41 @@ pub fn parse_command(command: &[String]) -> Vec<ParsedCommand> {
     deduped
 }
 
+/// A file that a shell command writes with content known before it runs:
+/// a here-doc or an `echo` redirected to the file or piped into `tee`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShellFileWrite {
+    /// The path written to, following any `cd` earlier in the script;
+    /// relative to the command's working directory unless absolute.
+    pub path: String,
+    pub content: String,
+    /// Whether the content is appended (`>>`, `tee -a`) instead of replacing
+    /// the file.
+    pub append: bool,
+}
+
+/// Extracts the file writes of a `bash -lc` script whose written content can
+/// be determined statically, in the order they appear. Writes involving
+/// expansions, substitutions or other redirections are left out, so the
+/// result may be partial but never guesses at content. Writes after a `cd`
+/// whose destination is unknown are left out too.
+pub fn extract_file_writes(command: &[String]) -> Vec<ShellFileWrite> {
+    let Some((_, script)) = extract_bash_command(command) else {
+        return Vec::new();
+    };
+    let Some(tree) = try_parse_shell(script) else {
+        return Vec::new();
+    };
+    let root = tree.root_node();
+    if root.has_error() {
+        return Vec::new();
+    }
+
+    let mut writes = Vec::new();
+    collect_file_writes(root, script, &mut String::new(), &mut writes);
+    writes
+}
+
 #[cfg(test)]
 #[allow(clippy::items_after_test_module)]
 /// Tests are at the top to encourage using TDD + Codex to fix the implementation.
@@ -1328,6 +1364,87 @@ mod tests {
             }],
         );
     }
+
+    fn file_writes(script: &str) -> Vec<ShellFileWrite> {
+        extract_file_writes(&vec_str(&["bash", "-lc", script]))
+    }
+
+    fn write(path: &str, content: &str, append: bool) -> ShellFileWrite {
+        ShellFileWrite {
+            path: path.to_string(),
+            content: content.to_string(),
+            append,
+        }
+    }
+
+    #[test]
+    fn extracts_heredoc_writes() {
+        assert_eq!(
+            file_writes("cat > notes.txt <<'EOF'\nhello $USER\nbye\nEOF"),
+            vec![write("notes.txt", "hello $USER\nbye\n", false)]
+        );
+        assert_eq!(
+            file_writes("mkdir -p logs && cat <<EOF >> logs/run.log\nstarted\nEOF"),
+            vec![write("logs/run.log", "started\n", true)]
+        );
+        assert_eq!(
+            file_writes("cat <<-EOF > indented.txt\n\tone\n\t\ttwo\n\tEOF"),
+            vec![write("indented.txt", "one\ntwo\n", false)]
+        );
+    }
+
+    #[test]
+    fn extracts_echo_redirects_and_tee() {
+        assert_eq!(
+            file_writes("echo 'hello world' > greeting.txt && echo -n done >> greeting.txt"),
+            vec![
+                write("greeting.txt", "hello world\n", false),
+                write("greeting.txt", "done", true),
+            ]
+        );
+        assert_eq!(
+            file_writes("echo enabled | tee -a a.conf b.conf > /dev/null"),
+            vec![
+                write("a.conf", "enabled\n", true),
+                write("b.conf", "enabled\n", true),
+            ]
+        );
+        assert_eq!(
+            file_writes("cat <<'EOF' | tee /tmp/out.txt\nx\nEOF"),
+            vec![write("/tmp/out.txt", "x\n", false)]
+        );
+    }
+
+    #[test]
+    fn skips_writes_with_unknown_content() {
+        assert_eq!(file_writes("cat <<EOF > home.txt\n$HOME\nEOF"), Vec::new());
+        assert_eq!(file_writes("echo \"$PATH\" > path.txt"), Vec::new());
+        assert_eq!(file_writes("cat src.txt > dst.txt"), Vec::new());
+        assert_eq!(file_writes("echo hi 2> err.txt"), Vec::new());
+        assert_eq!(file_writes("echo hi > /dev/null"), Vec::new());
+        assert_eq!(
+            extract_file_writes(&vec_str(&["echo", "hi", ">", "out.txt"])),
+            Vec::new()
+        );
+    }
+
+    #[test]
+    fn file_writes_follow_cd() {
+        assert_eq!(
+            file_writes(
+                "cd src && echo one > a.txt && (cd nested; echo two > b.txt); echo three > c.txt"
+            ),
+            vec![
+                write("src/a.txt", "one\n", false),
+                write("src/nested/b.txt", "two\n", false),
+                write("src/c.txt", "three\n", false),
+            ]
+        );
+        assert_eq!(
+            file_writes("echo one > a.txt && cd \"$DIR\" && echo two > b.txt"),
+            vec![write("a.txt", "one\n", false)]
+        );
+    }
 }
 
 pub fn parse_command_impl(command: &[String]) -> Vec<ParsedCommand> {
@@ -2581,3 +2698,324 @@ fn join_paths(base: &str, rel: &str) -> String {
     buf.push(rel);
     buf.to_string_lossy().to_string()
 }
+
+/// Walks `node` in script order, resolving write paths against the directory
+/// the script has `cd`-ed into so far. A `cd` in a subshell, pipeline or
+/// command substitution does not outlive it.
+/// Returns `false` once a `cd` goes somewhere that cannot be determined.
+fn collect_file_writes(
+    node: Node,
+    src: &str,
+    dir: &mut String,
+    writes: &mut Vec<ShellFileWrite>,
+) -> bool {
+    let found = match node.kind() {
+        "redirected_statement" => redirected_statement_writes(node, src),
+        "pipeline" => tee_pipeline_writes(node, src),
+        "command" => {
+            if !follow_cd(node, src, dir) {
+                return false;
+            }
+            None
+        }
+        _ => None,
+    };
+    writes.extend(found.into_iter().flatten().map(|write| ShellFileWrite {
+        path: join_paths(dir, &write.path),
+        ..write
+    }));
+
+    let mut subshell_dir;
+    let dir = if matches!(
+        node.kind(),
+        "subshell" | "pipeline" | "command_substitution"
+    ) {
+        subshell_dir = dir.clone();
+        &mut subshell_dir
+    } else {
+        dir
+    };
+    let mut cursor = node.walk();
+    let children: Vec<Node> = node.named_children(&mut cursor).collect();
+    children
+        .into_iter()
+        .all(|child| collect_file_writes(child, src, dir, writes))
+}
+
+/// Applies `command` to `dir` if it is a `cd`; `false` when its destination
+/// cannot be determined (no argument, `-`, or expansions).
+fn follow_cd(command: Node, src: &str, dir: &mut String) -> bool {
+    let is_cd = command
+        .child_by_field_name("name")
+        .and_then(|name| name.utf8_text(src.as_bytes()).ok())
+        == Some("cd");
+    if !is_cd {
+        return true;
+    }
+    let target = literal_command_words(command, src).and_then(|words| cd_target(&words[1..]));
+    match target {
+        Some(target) if target != "-" => {
+            *dir = join_paths(dir, &target);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Writes made by `cat <<EOF > file`, `cat <<EOF | tee file` and
+/// `echo ... > file`.
+fn redirected_statement_writes(node: Node, src: &str) -> Option<Vec<ShellFileWrite>> {
+    let body = node.child_by_field_name("body")?;
+    let mut heredoc = None;
+    let mut targets = Vec::new();
+    let mut tee_commands = Vec::new();
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if child.id() == body.id() {
+            continue;
+        }
+        match child.kind() {
+            "file_redirect" => targets.push(child),
+            "heredoc_redirect" if heredoc.is_none() => {
+                heredoc = Some(child);
+                let mut inner_cursor = child.walk();
+                for inner in child.named_children(&mut inner_cursor) {
+                    match inner.kind() {
+                        "file_redirect" => targets.push(inner),
+                        "pipeline" => {
+                            let mut pipeline_cursor = inner.walk();
+                            let commands: Vec<Node> =
+                                inner.named_children(&mut pipeline_cursor).collect();
+                            let [command] = commands.as_slice() else {
+                                return None;
+                            };
+                            tee_commands.push(*command);
+                        }
+                        "heredoc_start" | "heredoc_body" | "heredoc_end" => {}
+                        _ => return None,
+                    }
+                }
+            }
+            _ => return None,
+        }
+    }
+
+    let content = match heredoc {
+        Some(heredoc) if is_bare_cat(body, src) => heredoc_content(heredoc, src)?,
+        Some(_) => return None,
+        None => echo_output(body, src)?,
+    };
+    let mut writes = Vec::new();
+    for target in targets {
+        let (path, append) = stdout_target(target, src)?;
+        if !path.starts_with("/dev/") {
+            writes.push(ShellFileWrite {
+                path,
+                content: content.clone(),
+                append,
+            });
+        }
+    }
+    for command in tee_commands {
+        writes.extend(tee_writes(command, src, &content)?);
+    }
+    Some(writes)
+}
+
+/// Writes made by `echo ... | tee file` and `cat <<EOF | tee file` when the
+/// here-doc is attached to `cat` rather than the pipeline.
+fn tee_pipeline_writes(node: Node, src: &str) -> Option<Vec<ShellFileWrite>> {
+    let mut cursor = node.walk();
+    let stages: Vec<Node> = node.named_children(&mut cursor).collect();
+    let [producer, tee] = stages.as_slice() else {
+        return None;
+    };
+    let content = match producer.kind() {
+        "command" => echo_output(*producer, src)?,
+        "redirected_statement" => {
+            let body = producer.child_by_field_name("body")?;
+            let mut producer_cursor = producer.walk();
+            let redirects: Vec<Node> = producer
+                .named_children(&mut producer_cursor)
+                .filter(|child| child.id() != body.id())
+                .collect();
+            let [heredoc] = redirects.as_slice() else {
+                return None;
+            };
+            if heredoc.kind() != "heredoc_redirect" || !is_bare_cat(body, src) {
+                return None;
+            }
+            heredoc_content(*heredoc, src)?
+        }
+        _ => return None,
+    };
+    tee_writes(*tee, src, &content)
+}
+
+fn tee_writes(command: Node, src: &str, content: &str) -> Option<Vec<ShellFileWrite>> {
+    let words = literal_command_words(command, src)?;
+    let (name, args) = words.split_first()?;
+    if name != "tee" {
+        return None;
+    }
+    let mut append = false;
+    let mut paths = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "-a" | "--append" => append = true,
+            flag if flag.starts_with('-') => return None,
+            path => paths.push(path.to_string()),
+        }
+    }
+    Some(
+        paths
+            .into_iter()
+            .filter(|path| !path.starts_with("/dev/"))
+            .map(|path| ShellFileWrite {
+                path,
+                content: content.to_string(),
+                append,
+            })
+            .collect(),
+    )
+}
+
+/// The text of a here-doc, if it is literal: either the delimiter is quoted or
+/// the body contains nothing the shell would expand.
+fn heredoc_content(heredoc: Node, src: &str) -> Option<String> {
+    let mut cursor = heredoc.walk();
+    let children: Vec<Node> = heredoc.children(&mut cursor).collect();
+    let start = children
+        .iter()
+        .find(|child| child.kind() == "heredoc_start")?;
+    let end = children
+        .iter()
+        .find(|child| child.kind() == "heredoc_end")?;
+    let strip_tabs = children.iter().any(|child| child.kind() == "<<-");
+    let delimiter = start.utf8_text(src.as_bytes()).ok()?;
+    let quoted = delimiter.contains(['\'', '"', '\\']);
+    let body = match children.iter().find(|child| child.kind() == "heredoc_body") {
+        Some(body) => src.get(body.start_byte()..end.start_byte())?,
+        None => "",
+    };
+    if !quoted && body.contains(['$', '`', '\\']) {
+        return None;
+    }
+    if strip_tabs {
+        Some(
+            body.split_inclusive('\n')
+                .map(|line| line.trim_start_matches('\t'))
+                .collect(),
+        )
+    } else {
+        Some(body.to_string())
+    }
+}
+
+/// What `echo` with literal arguments prints; only `-n` is understood.
+fn echo_output(command: Node, src: &str) -> Option<String> {
+    let words = literal_command_words(command, src)?;
+    let (name, mut args) = words.split_first()?;
+    if name != "echo" {
+        return None;
+    }
+    let mut newline = true;
+    if args.first().is_some_and(|arg| arg == "-n") {
+        newline = false;
+        args = &args[1..];
+    }
+    if args.first().is_some_and(|arg| arg.starts_with('-')) {
+        return None;
+    }
+    let mut output = args.join(" ");
+    if newline {
+        output.push('\n');
+    }
+    Some(output)
+}
+
+fn is_bare_cat(command: Node, src: &str) -> bool {
+    literal_command_words(command, src)
+        .is_some_and(|words| matches!(words.as_slice(), [cat] if cat == "cat"))
+}
+
+/// The path a `>`/`>>` redirect of stdout writes to, and whether it appends.
+fn stdout_target(redirect: Node, src: &str) -> Option<(String, bool)> {
+    let mut append = None;
+    let mut path = None;
+    let mut cursor = redirect.walk();
+    for child in redirect.children(&mut cursor) {
+        match child.kind() {
+            ">" | ">|" => append = Some(false),
+            ">>" => append = Some(true),
+            "file_descriptor" => {
+                if child.utf8_text(src.as_bytes()).ok()? != "1" {
+                    return None;
+                }
+            }
+            _ if child.is_named() && path.is_none() => path = Some(literal_word(child, src)?),
+            _ => return None,
+        }
+    }
+    Some((path?, append?))
+}
+
+/// The words of a command made only of literal words, with no assignments or
+/// redirects of its own.
+fn literal_command_words(command: Node, src: &str) -> Option<Vec<String>> {
+    if command.kind() != "command" {
+        return None;
+    }
+    let mut words = Vec::new();
+    let mut cursor = command.walk();
+    for child in command.named_children(&mut cursor) {
+        let word = match child.kind() {
+            "command_name" => literal_word(child.named_child(0)?, src)?,
+            _ => literal_word(child, src)?,
+        };
+        words.push(word);
+    }
+    Some(words)
+}
+
+/// The value of a word the shell passes through unchanged: no expansions,
+/// globs or escapes.
+fn literal_word(node: Node, src: &str) -> Option<String> {
+    let text = node.utf8_text(src.as_bytes()).ok()?;
+    match node.kind() {
+        "word" | "number" => {
+            if text.starts_with('~') || text.contains(['\\', '*', '?', '[', '{']) {
+                return None;
+            }
+            Some(text.to_string())
+        }
+        "raw_string" => text
+            .strip_prefix('\'')
+            .and_then(|text| text.strip_suffix('\''))
+            .map(str::to_string),
+        "string" => {
+            let mut cursor = node.walk();
+            let mut value = String::new();
+            for part in node.named_children(&mut cursor) {
+                if part.kind() != "string_content" {
+                    return None;
+                }
+                let part = part.utf8_text(src.as_bytes()).ok()?;
+                if part.contains(['\\', '$', '`']) {
+                    return None;
+                }
+                value.push_str(part);
+            }
+            Some(value)
+        }
+        "concatenation" => {
+            let mut cursor = node.walk();
+            let mut value = String::new();
+            for part in node.named_children(&mut cursor) {
+                value.push_str(&literal_word(part, src)?);
+            }
+            Some(value)
+        }
+        _ => None,
+    }
+}
@@ -10,7 +10,8 @@
 //!     root is found, only the current working directory is considered.
 //! 2.  Collect every `AGENTS.md` found from the repository root down to the
 //!     current working directory (inclusive) and concatenate their contents in
-//!     that order.
+//!     that order. With more than one file, each is labelled with the
+//!     directory it applies to, and deeper files take precedence.
 //! 3.  We do **not** walk past the Git root.
 
 use crate::config::Config;
@@ -18,6 +19,7 @@ use crate::features::Feature;
 use crate::skills::SkillMetadata;
 use crate::skills::render_skills_section;
 use dunce::canonicalize as normalize_path;
+use std::path::Path;
 use std::path::PathBuf;
 use tokio::io::AsyncReadExt;
 use tracing::error;
@@ -83,26 +85,44 @@ pub(crate) async fn get_user_instructions(
     }
 }
 
+/// One project doc file that contributes to the instructions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectDoc {
+    pub path: PathBuf,
+    /// Directory whose files (including subdirectories) the doc applies to.
+    pub scope: PathBuf,
+    pub contents: String,
+    /// Whether `contents` was cut short by `project_doc_max_bytes`.
+    pub truncated: bool,
+}
+
 /// Attempt to locate and load the project documentation.
 ///
 /// On success returns `Ok(Some(contents))` where `contents` is the
-/// concatenation of all discovered docs. If no documentation file is found the
-/// function returns `Ok(None)`. Unexpected I/O failures bubble up as `Err` so
-/// callers can decide how to handle them.
+/// concatenation of all discovered docs. When more than one doc is found,
+/// each is preceded by a line naming the directory it applies to, and docs
+/// further down the tree are marked as taking precedence. If no
+/// documentation file is found the function returns `Ok(None)`. Unexpected
+/// I/O failures bubble up as `Err` so callers can decide how to handle them.
 pub async fn read_project_docs(config: &Config) -> std::io::Result<Option<String>> {
+    let docs = load_project_docs(config).await?;
+    Ok(render_project_docs(&docs))
+}
+
+/// The docs behind [`read_project_docs`], ordered from the repository root to
+/// the current working directory, so frontends can show where instructions
+/// come from. Empty docs are skipped.
+pub async fn load_project_docs(config: &Config) -> std::io::Result<Vec<ProjectDoc>> {
     let max_total = config.project_doc_max_bytes;
 
     if max_total == 0 {
-        return Ok(None);
+        return Ok(Vec::new());
     }
 
     let paths = discover_project_doc_paths(config)?;
-    if paths.is_empty() {
-        return Ok(None);
-    }
 
     let mut remaining: u64 = max_total as u64;
-    let mut parts: Vec<String> = Vec::new();
+    let mut docs: Vec<ProjectDoc> = Vec::new();
 
     for p in paths {
         if remaining == 0 {
@@ -120,7 +140,8 @@ pub async fn read_project_docs(config: &Config) -> std::io::Result<Option<String
         let mut data: Vec<u8> = Vec::new();
         reader.read_to_end(&mut data).await?;
 
-        if size > remaining {
+        let truncated = size > remaining;
+        if truncated {
             tracing::warn!(
                 "Project doc `{}` exceeds remaining budget ({} bytes) - truncating.",
                 p.display(),
@@ -130,15 +151,42 @@ pub async fn read_project_docs(config: &Config) -> std::io::Result<Option<String
 
         let text = String::from_utf8_lossy(&data).to_string();
         if !text.trim().is_empty() {
-            parts.push(text);
             remaining = remaining.saturating_sub(data.len() as u64);
+            docs.push(ProjectDoc {
+                scope: p.parent().map(Path::to_path_buf).unwrap_or_default(),
+                path: p,
+                contents: text,
+                truncated,
+            });
         }
     }
 
-    if parts.is_empty() {
-        Ok(None)
-    } else {
-        Ok(Some(parts.join("\n\n")))
+    Ok(docs)
+}
+
+fn render_project_docs(docs: &[ProjectDoc]) -> Option<String> {
+    match docs {
+        [] => None,
+        [doc] => Some(doc.contents.clone()),
+        docs => Some(
+            docs.iter()
+                .enumerate()
+                .map(|(index, doc)| {
+                    let scope = doc.scope.display();
+                    let precedence = if index == 0 {
+                        ""
+                    } else {
+                        "; takes precedence over the instructions above"
+                    };
+                    format!(
+                        "--- {} (applies to files under {scope}{precedence}) ---\n\n{}",
+                        doc.path.display(),
+                        doc.contents
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+        ),
     }
 }
 
@@ -423,7 +471,26 @@ mod tests {
         let res = get_user_instructions(&cfg, None)
             .await
             .expect("doc expected");
-        assert_eq!(res, "root doc\n\ncrate doc");
+        let root = normalize_path(repo.path()).unwrap();
+        let crate_dir = root.join("workspace/crate_a");
+        assert_eq!(
+            res,
+            format!(
+                "--- {root_doc} (applies to files under {root}) ---\n\nroot doc\n\n--- {crate_doc} (applies to files under {crate_dir}; takes precedence over the instructions above) ---\n\ncrate doc",
+                root_doc = root.join("AGENTS.md").display(),
+                root = root.display(),
+                crate_doc = crate_dir.join("AGENTS.md").display(),
+                crate_dir = crate_dir.display(),
+            )
+        );
+
+        let docs = load_project_docs(&cfg).await.unwrap();
+        assert_eq!(
+            docs.iter()
+                .map(|doc| (doc.scope.clone(), doc.truncated))
+                .collect::<Vec<_>>(),
+            vec![(root, false), (crate_dir, false)]
+        );
     }
 
     /// AGENTS.override.md is preferred over AGENTS.md when both are present.
@@ -0,0 +1,455 @@
+//! Compact repository outline used by the `project_map` tool.
+//!
+//! The map is a directory tree (pruned by `.gitignore` and friends) annotated
+//! with the top-level symbols of each source file. Symbols are extracted with
+//! tree-sitter and cached per file keyed by size and modification time, so
+//! repeated calls within a session only re-parse files that changed.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use ignore::WalkBuilder;
+use tree_sitter::Language;
+use tree_sitter::Node;
+use tree_sitter::Parser;
+
+/// Files larger than this are listed but never parsed.
+const MAX_PARSE_BYTES: u64 = 1024 * 1024;
+/// Symbols listed per file before the remainder is summarized.
+const MAX_SYMBOLS_PER_FILE: usize = 40;
+const INDENTATION_SPACES: usize = 2;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ProjectMapOptions {
+    pub max_depth: usize,
+    pub max_files: usize,
+    pub include_symbols: bool,
+}
+
+#[derive(Debug)]
+pub(crate) struct ProjectMap {
+    pub text: String,
+    /// Number of files included in the map.
+    pub files: usize,
+    /// Number of files whose symbols had to be (re-)parsed for this map.
+    pub parsed_files: usize,
+    /// True when `max_files` cut the walk short.
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Symbol {
+    kind: &'static str,
+    name: String,
+    line: usize,
+}
+
+struct CachedFile {
+    modified: Option<SystemTime>,
+    len: u64,
+    symbols: Vec<Symbol>,
+}
+
+/// Per-session cache of extracted symbols, keyed by absolute file path.
+#[derive(Default)]
+pub(crate) struct ProjectMapCache {
+    files: Mutex<HashMap<PathBuf, CachedFile>>,
+}
+
+impl ProjectMapCache {
+    /// Walks `root` and renders the map. Blocking; call from a blocking task.
+    pub(crate) fn build(&self, root: &Path, options: ProjectMapOptions) -> ProjectMap {
+        let walker = WalkBuilder::new(root)
+            .max_depth(Some(options.max_depth))
+            .require_git(false)
+            .sort_by_file_name(|a, b| a.cmp(b))
+            .build();
+
+        let mut lines = Vec::new();
+        let mut seen = HashSet::new();
+        let mut files = 0;
+        let mut parsed_files = 0;
+        let mut truncated = false;
+
+        for entry in walker.flatten() {
+            let depth = entry.depth();
+            if depth == 0 {
+                continue;
+            }
+            let indent = " ".repeat((depth - 1) * INDENTATION_SPACES);
+            let name = entry.file_name().to_string_lossy();
+            let Some(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                lines.push(format!("{indent}{name}/"));
+                continue;
+            }
+            if !file_type.is_file() {
+                continue;
+            }
+            if files == options.max_files {
+                truncated = true;
+                break;
+            }
+            files += 1;
+
+            let path = entry.path();
+            let symbols = if options.include_symbols {
+                let (symbols, parsed) = self.symbols_for(path);
+                if parsed {
+                    parsed_files += 1;
+                }
+                seen.insert(path.to_path_buf());
+                symbols
+            } else {
+                Vec::new()
+            };
+            lines.push(format_file_line(&indent, &name, &symbols));
+        }
+
+        if options.include_symbols && !truncated {
+            self.prune(root, &seen);
+        }
+
+        let mut text = format!("Project map for {} ({files} files)", root.display());
+        for line in lines {
+            text.push('\n');
+            text.push_str(&line);
+        }
+        if truncated {
+            let _ = write!(
+                text,
+                "\n[truncated after {} files; narrow `path` or lower `max_depth`]",
+                options.max_files
+            );
+        }
+
+        ProjectMap {
+            text,
+            files,
+            parsed_files,
+            truncated,
+        }
+    }
+
+    /// Returns the cached symbols for `path`, re-parsing when the file's size
+    /// or modification time changed. The flag reports whether a parse happened.
+    fn symbols_for(&self, path: &Path) -> (Vec<Symbol>, bool) {
+        let Some(language) = SourceLanguage::from_path(path) else {
+            return (Vec::new(), false);
+        };
+        let Ok(metadata) = std::fs::metadata(path) else {
+            return (Vec::new(), false);
+        };
+        if metadata.len() > MAX_PARSE_BYTES {
+            return (Vec::new(), false);
+        }
+        let modified = metadata.modified().ok();
+        let len = metadata.len();
+
+        if let Ok(files) = self.files.lock()
+            && let Some(cached) = files.get(path)
+            && cached.len == len
+            && cached.modified.is_some()
+            && cached.modified == modified
+        {
+            return (cached.symbols.clone(), false);
+        }
+
+        let symbols = std::fs::read_to_string(path)
+            .map(|source| extract_symbols(language, &source))
+            .unwrap_or_default();
+        if let Ok(mut files) = self.files.lock() {
+            files.insert(
+                path.to_path_buf(),
+                CachedFile {
+                    modified,
+                    len,
+                    symbols: symbols.clone(),
+                },
+            );
+        }
+        (symbols, true)
+    }
+
+    /// Drops cache entries under `root` for files that no longer exist.
+    fn prune(&self, root: &Path, seen: &HashSet<PathBuf>) {
+        if let Ok(mut files) = self.files.lock() {
+            files.retain(|path, _| !path.starts_with(root) || seen.contains(path));
+        }
+    }
+}
+
+fn format_file_line(indent: &str, name: &str, symbols: &[Symbol]) -> String {
+    if symbols.is_empty() {
+        return format!("{indent}{name}");
+    }
+    let mut listed = symbols
+        .iter()
+        .take(MAX_SYMBOLS_PER_FILE)
+        .map(|symbol| format!("{} {}:{}", symbol.kind, symbol.name, symbol.line))
+        .collect::<Vec<_>>()
+        .join(", ");
+    if symbols.len() > MAX_SYMBOLS_PER_FILE {
+        let _ = write!(listed, ", +{} more", symbols.len() - MAX_SYMBOLS_PER_FILE);
+    }
+    format!("{indent}{name}: {listed}")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SourceLanguage {
+    Rust,
+    Python,
+    TypeScript,
+    Tsx,
+    Go,
+    Bash,
+}
+
+impl SourceLanguage {
+    fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?;
+        match extension {
+            "rs" => Some(Self::Rust),
+            "py" | "pyi" => Some(Self::Python),
+            "ts" | "mts" | "cts" => Some(Self::TypeScript),
+            // The TSX grammar is a superset that also handles plain JavaScript.
+            "tsx" | "js" | "jsx" | "mjs" | "cjs" => Some(Self::Tsx),
+            "go" => Some(Self::Go),
+            "sh" | "bash" => Some(Self::Bash),
+            _ => None,
+        }
+    }
+
+    fn grammar(self) -> Language {
+        match self {
+            Self::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Self::Python => tree_sitter_python::LANGUAGE.into(),
+            Self::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            Self::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+            Self::Go => tree_sitter_go::LANGUAGE.into(),
+            Self::Bash => tree_sitter_bash::LANGUAGE.into(),
+        }
+    }
+
+    /// Maps a top-level node kind to the label shown in the map.
+    fn symbol_kind(self, node_kind: &str) -> Option<&'static str> {
+        let kind = match (self, node_kind) {
+            (Self::Rust, "function_item") => "fn",
+            (Self::Rust, "struct_item") => "struct",
+            (Self::Rust, "enum_item") => "enum",
+            (Self::Rust, "union_item") => "union",
+            (Self::Rust, "trait_item") => "trait",
+            (Self::Rust, "impl_item") => "impl",
+            (Self::Rust, "mod_item") => "mod",
+            (Self::Rust, "const_item") => "const",
+            (Self::Rust, "static_item") => "static",
+            (Self::Rust, "type_item") => "type",
+            (Self::Rust, "macro_definition") => "macro",
+            (Self::Python, "function_definition") => "def",
+            (Self::Python, "class_definition") => "class",
+            (Self::TypeScript | Self::Tsx, "function_declaration")
+            | (Self::TypeScript | Self::Tsx, "generator_function_declaration") => "function",
+            (Self::TypeScript | Self::Tsx, "class_declaration")
+            | (Self::TypeScript | Self::Tsx, "abstract_class_declaration") => "class",
+            (Self::TypeScript | Self::Tsx, "interface_declaration") => "interface",
+            (Self::TypeScript | Self::Tsx, "type_alias_declaration") => "type",
+            (Self::TypeScript | Self::Tsx, "enum_declaration") => "enum",
+            (Self::TypeScript | Self::Tsx, "lexical_declaration") => "const",
+            (Self::Go, "function_declaration") | (Self::Go, "method_declaration") => "func",
+            (Self::Go, "type_declaration") => "type",
+            (Self::Bash, "function_definition") => "function",
+            _ => return None,
+        };
+        Some(kind)
+    }
+}
+
+fn extract_symbols(language: SourceLanguage, source: &str) -> Vec<Symbol> {
+    let mut parser = Parser::new();
+    if parser.set_language(&language.grammar()).is_err() {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(source, None) else {
+        return Vec::new();
+    };
+
+    let root = tree.root_node();
+    let mut cursor = root.walk();
+    let mut symbols = Vec::new();
+    for child in root.named_children(&mut cursor) {
+        let node = unwrap_declaration(child);
+        let Some(kind) = language.symbol_kind(node.kind()) else {
+            continue;
+        };
+        if let Some(name) = symbol_name(node, source) {
+            symbols.push(Symbol {
+                kind,
+                name,
+                line: node.start_position().row + 1,
+            });
+        }
+    }
+    symbols
+}
+
+/// Looks through wrappers that carry no name of their own, such as
+/// `export ...` in TypeScript or decorated definitions in Python.
+fn unwrap_declaration(node: Node<'_>) -> Node<'_> {
+    match node.kind() {
+        "export_statement" => node.child_by_field_name("declaration").unwrap_or(node),
+        "decorated_definition" => node.child_by_field_name("definition").unwrap_or(node),
+        _ => node,
+    }
+}
+
+fn symbol_name(node: Node<'_>, source: &str) -> Option<String> {
+    let text = |node: Node<'_>| node.utf8_text(source.as_bytes()).ok().map(str::to_string);
+    match node.kind() {
+        "impl_item" => {
+            let type_name = text(node.child_by_field_name("type")?)?;
+            match node.child_by_field_name("trait").and_then(text) {
+                Some(trait_name) => Some(format!("{trait_name} for {type_name}")),
+                None => Some(type_name),
+            }
+        }
+        // `const a = ...` / `type Foo struct {...}`: the name lives on the
+        // first declarator/spec rather than on the declaration itself.
+        "lexical_declaration" | "type_declaration" => {
+            let mut cursor = node.walk();
+            let first = node
+                .named_children(&mut cursor)
+                .find(|child| matches!(child.kind(), "variable_declarator" | "type_spec"))?;
+            text(first.child_by_field_name("name")?)
+        }
+        _ => text(node.child_by_field_name("name")?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    const OPTIONS: ProjectMapOptions = ProjectMapOptions {
+        max_depth: 8,
+        max_files: 100,
+        include_symbols: true,
+    };
+
+    #[test]
+    fn extracts_top_level_rust_symbols() {
+        let source = "pub struct Config;\n\nimpl Default for Config {\n    fn default() -> Self { Config }\n}\n\nfn load() {}\n";
+        let names = extract_symbols(SourceLanguage::Rust, source)
+            .into_iter()
+            .map(|symbol| format!("{} {}:{}", symbol.kind, symbol.name, symbol.line))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                "struct Config:1".to_string(),
+                "impl Default for Config:3".to_string(),
+                "fn load:7".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn unwraps_exports_and_decorators() {
+        let ts = extract_symbols(
+            SourceLanguage::TypeScript,
+            "export interface Props {}\nexport const render = () => null;\n",
+        );
+        assert_eq!(
+            ts.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(),
+            vec!["Props", "render"]
+        );
+
+        let py = extract_symbols(
+            SourceLanguage::Python,
+            "@dataclass\nclass Point:\n    x: int\n\ndef main():\n    pass\n",
+        );
+        assert_eq!(
+            py.iter()
+                .map(|s| format!("{} {}", s.kind, s.name))
+                .collect::<Vec<_>>(),
+            vec!["class Point".to_string(), "def main".to_string()]
+        );
+    }
+
+    #[test]
+    fn respects_gitignore_and_renders_tree() {
+        let temp = tempdir().expect("create tempdir");
+        let root = temp.path();
+        std::fs::write(root.join(".gitignore"), "target/\n").expect("write gitignore");
+        std::fs::create_dir_all(root.join("src")).expect("create src");
+        std::fs::create_dir_all(root.join("target")).expect("create target");
+        std::fs::write(root.join("src/lib.rs"), "pub fn run() {}\n").expect("write lib");
+        std::fs::write(root.join("target/out.rs"), "fn hidden() {}\n").expect("write out");
+        std::fs::write(root.join("README.md"), "# readme\n").expect("write readme");
+
+        let map = ProjectMapCache::default().build(root, OPTIONS);
+
+        assert_eq!(
+            map.text,
+            format!(
+                "Project map for {} (2 files)\nREADME.md\nsrc/\n  lib.rs: fn run:1",
+                root.display()
+            )
+        );
+        assert_eq!(map.parsed_files, 1);
+    }
+
+    #[test]
+    fn reparses_only_changed_files() {
+        let temp = tempdir().expect("create tempdir");
+        let root = temp.path();
+        std::fs::write(root.join("a.rs"), "fn a() {}\n").expect("write a");
+        std::fs::write(root.join("b.rs"), "fn b() {}\n").expect("write b");
+
+        let cache = ProjectMapCache::default();
+        assert_eq!(cache.build(root, OPTIONS).parsed_files, 2);
+        assert_eq!(cache.build(root, OPTIONS).parsed_files, 0);
+
+        std::fs::write(root.join("b.rs"), "fn b() {}\nfn b2() {}\n").expect("rewrite b");
+        let map = cache.build(root, OPTIONS);
+        assert_eq!(map.parsed_files, 1);
+        assert!(map.text.contains("b.rs: fn b:1, fn b2:2"));
+
+        std::fs::remove_file(root.join("a.rs")).expect("remove a");
+        cache.build(root, OPTIONS);
+        let cached = cache.files.lock().expect("lock cache").len();
+        assert_eq!(cached, 1);
+    }
+
+    #[test]
+    fn stops_at_max_files() {
+        let temp = tempdir().expect("create tempdir");
+        let root = temp.path();
+        for name in ["a.txt", "b.txt", "c.txt"] {
+            std::fs::write(root.join(name), name).expect("write file");
+        }
+
+        let map = ProjectMapCache::default().build(
+            root,
+            ProjectMapOptions {
+                max_files: 2,
+                ..OPTIONS
+            },
+        );
+
+        assert!(map.truncated);
+        assert_eq!(map.files, 2);
+        assert!(
+            map.text
+                .ends_with("[truncated after 2 files; narrow `path` or lower `max_depth`]")
+        );
+    }
+}
@@ -0,0 +1,494 @@
+//! Signed provenance records for applied patches.
+//!
+//! With `[provenance] enabled = true`, every patch Codex applies appends one
+//! [`ProvenanceRecord`] to a file next to the session's rollout: which
+//! session, turn and model produced it, hashes of the user prompts that led
+//! to it and of the patch itself, and when it was applied. Each record is
+//! signed with HMAC-SHA256 under a key that stays on the machine (or is
+//! provided by the organization), so an exported record can later be checked
+//! against the change it claims to describe.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::ThreadId;
+use codex_protocol::items::TurnItem;
+use codex_protocol::protocol::FileChange;
+use hmac::Hmac;
+use hmac::Mac;
+use rand::RngCore;
+use serde::Deserialize;
+use serde::Serialize;
+use sha2::Digest;
+use sha2::Sha256;
+use time::OffsetDateTime;
+use time::macros::format_description;
+use tokio::io::AsyncWriteExt;
+use tracing::warn;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::config::types::Provenance;
+use crate::event_mapping::parse_turn_item;
+use crate::rollout::ARCHIVED_SESSIONS_SUBDIR;
+use crate::rollout::SESSIONS_SUBDIR;
+
+/// Bumped when the record layout changes.
+pub const PROVENANCE_VERSION: u32 = 1;
+
+const DEFAULT_SIGNING_KEY_FILE: &str = "provenance.key";
+
+const SIGNING_KEY_BYTES: usize = 32;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Where Codex came from for one applied patch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvenanceRecord {
+    pub version: u32,
+    pub thread_id: ThreadId,
+    pub turn_id: String,
+    pub call_id: String,
+    pub model: String,
+    pub model_provider: String,
+    /// SHA-256 of every user prompt in the conversation when the patch was
+    /// applied, oldest first.
+    pub prompt_sha256: Vec<String>,
+    /// SHA-256 of the patch: the changes serialized as JSON, keyed by path.
+    pub diff_sha256: String,
+    pub files: Vec<PathBuf>,
+    /// When the patch was applied (`YYYY-MM-DDTHH:MM:SS.sssZ`).
+    pub timestamp: String,
+    /// Identifies the signing key without revealing it: the first 16 hex
+    /// digits of its SHA-256.
+    pub key_id: String,
+    /// `hmac-sha256:<hex>` over the record serialized with an empty
+    /// signature.
+    #[serde(default)]
+    pub signature: String,
+}
+
+impl ProvenanceRecord {
+    fn signed_payload(&self) -> Vec<u8> {
+        let unsigned = Self {
+            signature: String::new(),
+            ..self.clone()
+        };
+        serde_json::to_vec(&unsigned).unwrap_or_default()
+    }
+
+    fn sign(&mut self, key: &[u8]) {
+        self.key_id = key_id(key);
+        self.signature = format!("hmac-sha256:{}", hmac_hex(key, &self.signed_payload()));
+    }
+
+    /// Whether the record was signed with `key` and not altered since.
+    pub fn verify(&self, key: &[u8]) -> bool {
+        let Some(signature) = self
+            .signature
+            .strip_prefix("hmac-sha256:")
+            .and_then(decode_hex)
+        else {
+            return false;
+        };
+        let Ok(mut mac) = HmacSha256::new_from_slice(key) else {
+            return false;
+        };
+        mac.update(&self.signed_payload());
+        // `verify_slice` compares in constant time.
+        self.key_id == key_id(key) && mac.verify_slice(&signature).is_ok()
+    }
+}
+
+/// File the provenance records of the rollout at `rollout_path` are kept in.
+pub fn provenance_path(rollout_path: &Path) -> PathBuf {
+    rollout_path.with_extension("provenance")
+}
+
+/// Reads the provenance records kept for the rollout at `rollout_path`.
+pub async fn read_provenance(rollout_path: &Path) -> io::Result<Vec<ProvenanceRecord>> {
+    let contents = match tokio::fs::read_to_string(provenance_path(rollout_path)).await {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).map_err(io::Error::other))
+        .collect()
+}
+
+/// Moves the provenance records along with a rollout that moved from `from`
+/// to `to`, e.g. when a thread is archived.
+pub async fn move_provenance(from: &Path, to: &Path) -> io::Result<()> {
+    match tokio::fs::rename(provenance_path(from), provenance_path(to)).await {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+/// Removes the provenance files under `codex_home` whose rollout no longer
+/// exists. Returns the number of files removed.
+pub async fn remove_orphaned_provenance(codex_home: &Path) -> io::Result<usize> {
+    let mut removed = 0;
+    let mut dirs = vec![
+        codex_home.join(SESSIONS_SUBDIR),
+        codex_home.join(ARCHIVED_SESSIONS_SUBDIR),
+    ];
+    while let Some(dir) = dirs.pop() {
+        let mut entries = match tokio::fs::read_dir(&dir).await {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err),
+        };
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if entry.file_type().await?.is_dir() {
+                dirs.push(path);
+            } else if path.extension().is_some_and(|ext| ext == "provenance")
+                && !tokio::fs::try_exists(path.with_extension("jsonl")).await?
+            {
+                tokio::fs::remove_file(&path).await?;
+                removed += 1;
+            }
+        }
+    }
+    Ok(removed)
+}
+
+/// Reads the key records were signed with, without creating one: `None` when
+/// no signing key file is configured and the default key does not exist.
+pub async fn read_verification_key(
+    codex_home: &Path,
+    config: &Provenance,
+) -> io::Result<Option<Vec<u8>>> {
+    let path = match &config.signing_key_file {
+        Some(path) => path.as_path().to_path_buf(),
+        None => codex_home.join(DEFAULT_SIGNING_KEY_FILE),
+    };
+    match read_signing_key(&path).await {
+        Ok(key) => Ok(Some(key)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound && config.signing_key_file.is_none() => {
+            Ok(None)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Reads the key records are signed with, creating the default key under
+/// `codex_home` if it does not exist yet.
+pub async fn load_signing_key(codex_home: &Path, config: &Provenance) -> io::Result<Vec<u8>> {
+    if let Some(path) = &config.signing_key_file {
+        return read_signing_key(path.as_path()).await;
+    }
+    let path = codex_home.join(DEFAULT_SIGNING_KEY_FILE);
+    match read_signing_key(&path).await {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => create_signing_key(&path).await,
+        result => result,
+    }
+}
+
+pub(crate) async fn read_signing_key(path: &Path) -> io::Result<Vec<u8>> {
+    let key = tokio::fs::read_to_string(path).await?.trim().to_string();
+    if key.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("signing key {} is empty", path.display()),
+        ));
+    }
+    Ok(key.into_bytes())
+}
+
+/// Creates a random key at `path`, or reads the one another session created
+/// there first.
+pub(crate) async fn create_signing_key(path: &Path) -> io::Result<Vec<u8>> {
+    let mut bytes = [0u8; SIGNING_KEY_BYTES];
+    rand::rng().fill_bytes(&mut bytes);
+    let key: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+
+    let mut options = tokio::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        options.mode(0o600);
+    }
+    match options.open(path).await {
+        Ok(mut file) => {
+            file.write_all(key.as_bytes()).await?;
+            file.flush().await?;
+            Ok(key.into_bytes())
+        }
+        // Another session created it first.
+        Err(err) if err.kind() == io::ErrorKind::AlreadyExists => read_signing_key(path).await,
+        Err(err) => Err(err),
+    }
+}
+
+/// Appends a signed record for the patch of `call_id` that was just applied.
+/// Does nothing unless provenance is enabled; failures are logged, since the
+/// patch has already been applied.
+pub(crate) async fn record_patch(
+    sess: &Session,
+    turn_context: &TurnContext,
+    call_id: &str,
+    changes: &HashMap<PathBuf, FileChange>,
+) {
+    let config = turn_context.client.config();
+    if !config.provenance.enabled || config.incognito {
+        return;
+    }
+    let Some(rollout_path) = sess
+        .services
+        .rollout
+        .lock()
+        .await
+        .as_ref()
+        .map(|recorder| recorder.rollout_path.clone())
+    else {
+        return;
+    };
+    let prompts: Vec<String> = sess
+        .clone_history()
+        .await
+        .raw_items()
+        .iter()
+        .filter_map(|item| match parse_turn_item(item) {
+            Some(TurnItem::UserMessage(message)) => Some(message.message()),
+            _ => None,
+        })
+        .collect();
+    let mut files: Vec<PathBuf> = changes.keys().cloned().collect();
+    files.sort();
+    let record = ProvenanceRecord {
+        version: PROVENANCE_VERSION,
+        thread_id: sess.conversation_id,
+        turn_id: turn_context.sub_id.clone(),
+        call_id: call_id.to_string(),
+        model: turn_context.client.get_model(),
+        model_provider: turn_context.client.get_provider().name,
+        prompt_sha256: prompts
+            .iter()
+            .map(|prompt| sha256_hex(prompt.as_bytes()))
+            .collect(),
+        diff_sha256: diff_sha256(changes),
+        files,
+        timestamp: now_timestamp(),
+        key_id: String::new(),
+        signature: String::new(),
+    };
+    if let Err(err) = append_record(
+        &config.codex_home,
+        &config.provenance,
+        &rollout_path,
+        record,
+    )
+    .await
+    {
+        warn!("failed to record provenance of patch {call_id}: {err}");
+    }
+}
+
+async fn append_record(
+    codex_home: &Path,
+    config: &Provenance,
+    rollout_path: &Path,
+    mut record: ProvenanceRecord,
+) -> io::Result<()> {
+    let key = load_signing_key(codex_home, config).await?;
+    record.sign(&key);
+    let mut line = serde_json::to_string(&record).map_err(io::Error::other)?;
+    line.push('\n');
+    let mut file = tokio::fs::OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(provenance_path(rollout_path))
+        .await?;
+    file.write_all(line.as_bytes()).await?;
+    file.flush().await
+}
+
+fn diff_sha256(changes: &HashMap<PathBuf, FileChange>) -> String {
+    let sorted: BTreeMap<&PathBuf, &FileChange> = changes.iter().collect();
+    sha256_hex(&serde_json::to_vec(&sorted).unwrap_or_default())
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+fn key_id(key: &[u8]) -> String {
+    sha256_hex(key)[..16].to_string()
+}
+
+fn hmac_hex(key: &[u8], payload: &[u8]) -> String {
+    let Ok(mut mac) = HmacSha256::new_from_slice(key) else {
+        return String::new();
+    };
+    mac.update(payload);
+    format!("{:x}", mac.finalize().into_bytes())
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn now_timestamp() -> String {
+    OffsetDateTime::now_utc()
+        .format(format_description!(
+            "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]Z"
+        ))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn record() -> ProvenanceRecord {
+        ProvenanceRecord {
+            version: PROVENANCE_VERSION,
+            thread_id: ThreadId::new(),
+            turn_id: "turn-1".to_string(),
+            call_id: "call-1".to_string(),
+            model: "gpt-5.1-codex".to_string(),
+            model_provider: "OpenAI".to_string(),
+            prompt_sha256: vec![sha256_hex(b"fix the parser")],
+            diff_sha256: sha256_hex(b"diff"),
+            files: vec![PathBuf::from("/repo/src/parser.rs")],
+            timestamp: "2025-06-12T09:31:44.120Z".to_string(),
+            key_id: String::new(),
+            signature: String::new(),
+        }
+    }
+
+    #[test]
+    fn signature_detects_tampering_and_other_keys() {
+        let mut signed = record();
+        signed.sign(b"secret");
+        assert!(signed.verify(b"secret"));
+        assert!(!signed.verify(b"another secret"));
+
+        let mut tampered = signed.clone();
+        tampered.model = "other-model".to_string();
+        assert!(!tampered.verify(b"secret"));
+
+        let mut malformed = signed.clone();
+        malformed.signature.push('0');
+        assert!(!malformed.verify(b"secret"));
+    }
+
+    #[tokio::test]
+    async fn reading_the_verification_key_does_not_create_one() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let config = Provenance::default();
+
+        let key = read_verification_key(codex_home.path(), &config)
+            .await
+            .expect("read key");
+
+        assert_eq!(key, None);
+        assert!(!codex_home.path().join(DEFAULT_SIGNING_KEY_FILE).exists());
+    }
+
+    #[tokio::test]
+    async fn removes_provenance_of_deleted_rollouts() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let day = codex_home.path().join(SESSIONS_SUBDIR).join("2025/06/12");
+        let archived = codex_home.path().join(ARCHIVED_SESSIONS_SUBDIR);
+        std::fs::create_dir_all(&day).expect("sessions dir");
+        std::fs::create_dir_all(&archived).expect("archived dir");
+        let kept = day.join("rollout-2025-06-12T09-14-03-kept.jsonl");
+        std::fs::write(&kept, "").expect("rollout");
+        for rollout in [
+            &kept,
+            &day.join("rollout-2025-06-12T10-00-00-gone.jsonl"),
+            &archived.join("rollout-2025-06-11T10-00-00-gone.jsonl"),
+        ] {
+            std::fs::write(provenance_path(rollout), "").expect("provenance");
+        }
+
+        let removed = remove_orphaned_provenance(codex_home.path())
+            .await
+            .expect("cleanup");
+
+        assert_eq!(removed, 2);
+        let mut remaining: Vec<PathBuf> = std::fs::read_dir(&day)
+            .expect("read day")
+            .chain(std::fs::read_dir(&archived).expect("read archived"))
+            .map(|entry| entry.expect("entry").path())
+            .collect();
+        remaining.sort();
+        assert_eq!(remaining, vec![kept.clone(), provenance_path(&kept)]);
+    }
+
+    #[test]
+    fn diff_hash_does_not_depend_on_map_order() {
+        let first: HashMap<PathBuf, FileChange> = [
+            (
+                PathBuf::from("a.rs"),
+                FileChange::Add {
+                    content: "a".to_string(),
+                },
+            ),
+            (
+                PathBuf::from("b.rs"),
+                FileChange::Delete {
+                    content: "b".to_string(),
+                },
+            ),
+        ]
+        .into_iter()
+        .collect();
+        let second: HashMap<PathBuf, FileChange> = first
+            .iter()
+            .rev()
+            .map(|(path, change)| (path.clone(), change.clone()))
+            .collect();
+
+        assert_eq!(diff_sha256(&first), diff_sha256(&second));
+    }
+
+    #[tokio::test]
+    async fn appends_signed_records_next_to_the_rollout() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let rollout_path = codex_home
+            .path()
+            .join("rollout-2025-06-12T09-14-03-id.jsonl");
+        let config = Provenance {
+            enabled: true,
+            signing_key_file: None,
+        };
+
+        append_record(codex_home.path(), &config, &rollout_path, record())
+            .await
+            .expect("append");
+        append_record(codex_home.path(), &config, &rollout_path, record())
+            .await
+            .expect("append");
+
+        let key = load_signing_key(codex_home.path(), &config)
+            .await
+            .expect("key");
+        let records = read_provenance(&rollout_path).await.expect("read");
+        assert_eq!(records.len(), 2);
+        assert!(records.iter().all(|record| record.verify(&key)));
+        assert_eq!(
+            provenance_path(&rollout_path),
+            codex_home
+                .path()
+                .join("rollout-2025-06-12T09-14-03-id.provenance")
+        );
+    }
+}
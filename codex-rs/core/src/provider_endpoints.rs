@@ -0,0 +1,256 @@
+//! Endpoint failover for providers configured with `fallback_base_urls`.
+//!
+//! The primary `base_url` is used while it is healthy. When a request fails
+//! with a connection error or a 5xx after its retries, the provider moves to
+//! the next endpoint in the list. While on a fallback, the primary is tried
+//! again every [`PRIMARY_RETRY_INTERVAL`] and taken back as soon as a request
+//! to it succeeds. The health state is shared by every conversation that
+//! talks to the same endpoints.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::OnceLock;
+use std::time::Duration;
+use std::time::Instant;
+
+use codex_api::Provider as ApiProvider;
+use codex_api::TransportError;
+use codex_api::error::ApiError;
+use codex_protocol::protocol::ProviderEndpointEvent;
+use tracing::warn;
+
+/// How long a provider stays on a fallback before probing the primary again.
+pub(crate) const PRIMARY_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug)]
+pub(crate) struct EndpointPool {
+    provider: String,
+    base_urls: Vec<String>,
+    state: Mutex<PoolState>,
+}
+
+#[derive(Debug, Default)]
+struct PoolState {
+    active: usize,
+    retry_primary_at: Option<Instant>,
+    last_failure: Option<String>,
+}
+
+impl EndpointPool {
+    pub(crate) fn new(provider: impl Into<String>, base_urls: Vec<String>) -> Self {
+        Self {
+            provider: provider.into(),
+            base_urls,
+            state: Mutex::new(PoolState::default()),
+        }
+    }
+
+    /// Shared pool for `base_urls`, keyed by the list itself so that every
+    /// client configured with the same endpoints sees the same health.
+    pub(crate) fn for_provider(provider: &str, base_urls: Vec<String>) -> Arc<Self> {
+        static POOLS: OnceLock<Mutex<HashMap<Vec<String>, Arc<EndpointPool>>>> = OnceLock::new();
+        let mut pools = POOLS
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        Arc::clone(
+            pools
+                .entry(base_urls.clone())
+                .or_insert_with(|| Arc::new(Self::new(provider, base_urls))),
+        )
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.base_urls.len()
+    }
+
+    /// Index and base URL of the endpoint the next request should use. This
+    /// is the primary once its retry interval has elapsed.
+    pub(crate) fn select(&self) -> (usize, &str) {
+        self.select_at(Instant::now())
+    }
+
+    fn select_at(&self, now: Instant) -> (usize, &str) {
+        let state = self.lock();
+        let index = match state.retry_primary_at {
+            Some(at) if now >= at => 0,
+            _ => state.active,
+        };
+        (index, &self.base_urls[index])
+    }
+
+    /// Records a successful request to the endpoint at `index`.
+    pub(crate) fn record_success(&self, index: usize) {
+        if index != 0 {
+            return;
+        }
+        let mut state = self.lock();
+        state.active = 0;
+        state.retry_primary_at = None;
+        state.last_failure = None;
+    }
+
+    /// Records that the endpoint at `index` failed with `reason` and moves
+    /// the pool on to the next endpoint.
+    pub(crate) fn record_failure(&self, index: usize, reason: String) {
+        self.record_failure_at(index, reason, Instant::now());
+    }
+
+    fn record_failure_at(&self, index: usize, reason: String, now: Instant) {
+        let mut state = self.lock();
+        if index == state.active {
+            state.active = (index + 1) % self.base_urls.len();
+            warn!(
+                provider = %self.provider,
+                failed = %self.base_urls[index],
+                next = %self.base_urls[state.active],
+                "model provider endpoint failed; failing over"
+            );
+        } else if index != 0 {
+            // Another request already moved the pool past this endpoint.
+            return;
+        }
+        state.retry_primary_at = (state.active != 0).then(|| now + PRIMARY_RETRY_INTERVAL);
+        state.last_failure = Some(reason);
+    }
+
+    /// The endpoint currently in use, for reporting to clients.
+    pub(crate) fn status(&self) -> ProviderEndpointEvent {
+        let state = self.lock();
+        ProviderEndpointEvent {
+            provider: self.provider.clone(),
+            base_url: self.base_urls[state.active].clone(),
+            primary: state.active == 0,
+            reason: if state.active == 0 {
+                None
+            } else {
+                state.last_failure.clone()
+            },
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, PoolState> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Endpoint selection for the attempts of a single request.
+pub(crate) struct EndpointFailover {
+    pool: Option<Arc<EndpointPool>>,
+    current: Option<usize>,
+    failovers: usize,
+}
+
+impl EndpointFailover {
+    pub(crate) fn new(pool: Option<Arc<EndpointPool>>) -> Self {
+        Self {
+            pool,
+            current: None,
+            failovers: 0,
+        }
+    }
+
+    /// Points `provider` at the endpoint the next attempt should use.
+    pub(crate) fn apply(&mut self, provider: &mut ApiProvider) {
+        if let Some(pool) = &self.pool {
+            let (index, base_url) = pool.select();
+            provider.base_url = base_url.to_string();
+            self.current = Some(index);
+        }
+    }
+
+    pub(crate) fn record_success(&self) {
+        if let (Some(pool), Some(index)) = (&self.pool, self.current) {
+            pool.record_success(index);
+        }
+    }
+
+    /// Records a failed attempt. Returns whether the request should be
+    /// retried on another endpoint.
+    pub(crate) fn should_fail_over(&mut self, err: &ApiError) -> bool {
+        let (Some(pool), Some(index)) = (&self.pool, self.current) else {
+            return false;
+        };
+        if !is_endpoint_failure(err) {
+            return false;
+        }
+        pool.record_failure(index, err.to_string());
+        self.failovers += 1;
+        self.failovers < pool.len()
+    }
+}
+
+/// Errors that indicate the endpoint itself is unhealthy, as opposed to the
+/// request being rejected.
+pub(crate) fn is_endpoint_failure(err: &ApiError) -> bool {
+    match err {
+        ApiError::Transport(TransportError::Http { status, .. }) => status.is_server_error(),
+        ApiError::Transport(
+            TransportError::Network(_) | TransportError::Timeout | TransportError::RetryLimit,
+        ) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn pool() -> EndpointPool {
+        EndpointPool::new(
+            "azure",
+            vec![
+                "https://eastus.example.com".to_string(),
+                "https://westus.example.com".to_string(),
+            ],
+        )
+    }
+
+    #[test]
+    fn fails_over_and_returns_to_the_primary_after_the_retry_interval() {
+        let pool = pool();
+        let start = Instant::now();
+        assert_eq!(pool.select_at(start), (0, "https://eastus.example.com"));
+
+        pool.record_failure_at(0, "http 503".to_string(), start);
+        assert_eq!(pool.select_at(start), (1, "https://westus.example.com"));
+        assert_eq!(
+            pool.status(),
+            ProviderEndpointEvent {
+                provider: "azure".to_string(),
+                base_url: "https://westus.example.com".to_string(),
+                primary: false,
+                reason: Some("http 503".to_string()),
+            }
+        );
+
+        let probe = start + PRIMARY_RETRY_INTERVAL;
+        assert_eq!(pool.select_at(probe), (0, "https://eastus.example.com"));
+
+        // A failed probe keeps the fallback and waits another interval.
+        pool.record_failure_at(0, "network error".to_string(), probe);
+        assert_eq!(pool.select_at(probe), (1, "https://westus.example.com"));
+
+        let probe = probe + PRIMARY_RETRY_INTERVAL;
+        assert_eq!(pool.select_at(probe), (0, "https://eastus.example.com"));
+        pool.record_success(0);
+        assert_eq!(pool.select_at(start), (0, "https://eastus.example.com"));
+        assert!(pool.status().primary);
+    }
+
+    #[test]
+    fn wraps_around_and_ignores_failures_of_endpoints_already_left() {
+        let pool = pool();
+        pool.record_failure(0, "timeout".to_string());
+        pool.record_failure(1, "timeout".to_string());
+        // A request that was still on the fallback fails after the pool moved on.
+        pool.record_failure(1, "timeout".to_string());
+        assert_eq!(pool.select(), (0, "https://eastus.example.com"));
+        assert!(pool.status().primary);
+    }
+}
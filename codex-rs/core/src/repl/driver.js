@@ -0,0 +1,126 @@
+// Driver of the `repl` tool. Reads one JSON request per line from stdin,
+// runs the snippet in a context shared by all snippets and answers with a
+// single result line on stdout, prefixed with a marker so it can be told
+// apart from output written directly to the file descriptors.
+"use strict";
+
+const readline = require("readline");
+const util = require("util");
+const vm = require("vm");
+const { Console } = require("console");
+const { Writable } = require("stream");
+
+const MARKER = "\x1ecodex-repl:";
+
+const MAX_OUTPUT = Number(process.env.CODEX_REPL_MAX_OUTPUT_CHARS) || Infinity;
+
+// Keeps the first MAX_OUTPUT characters written to a stream and counts the
+// rest.
+class Capture {
+  constructor() {
+    this.parts = [];
+    this.length = 0;
+    this.omitted = 0;
+  }
+
+  push(text) {
+    const room = MAX_OUTPUT - this.length;
+    if (room < text.length) {
+      this.omitted += text.length - Math.max(room, 0);
+      text = room > 0 ? text.slice(0, room) : "";
+    }
+    this.parts.push(text);
+    this.length += text.length;
+  }
+
+  text() {
+    const text = this.parts.join("");
+    return this.omitted > 0 ? `${text}\n[... ${this.omitted} characters omitted ...]\n` : text;
+  }
+}
+
+const captured = { stdout: new Capture(), stderr: new Capture() };
+const sink = (name) =>
+  new Writable({
+    write(chunk, _encoding, callback) {
+      captured[name].push(chunk.toString());
+      callback();
+    },
+  });
+const snippetConsole = new Console({ stdout: sink("stdout"), stderr: sink("stderr") });
+
+const context = vm.createContext({
+  require,
+  module,
+  exports,
+  process,
+  Buffer,
+  URL,
+  URLSearchParams,
+  TextEncoder,
+  TextDecoder,
+  AbortController,
+  structuredClone,
+  queueMicrotask,
+  setTimeout,
+  clearTimeout,
+  setInterval,
+  clearInterval,
+  setImmediate,
+  clearImmediate,
+  fetch: globalThis.fetch,
+  console: snippetConsole,
+});
+
+function formatError(error) {
+  // Errors thrown in the context come from its own realm, so `instanceof
+  // Error` does not apply.
+  if (error === null || typeof error !== "object" || typeof error.stack !== "string") {
+    return `Uncaught ${util.inspect(error)}\n`;
+  }
+  // Drop the driver's frames so the stack ends in the snippet.
+  const lines = error.stack.split("\n");
+  const end = lines.findIndex((line) => line.includes("node:vm"));
+  return `${(end === -1 ? lines : lines.slice(0, end)).join("\n")}\n`;
+}
+
+async function run(request) {
+  captured.stdout = new Capture();
+  captured.stderr = new Capture();
+  let error = null;
+  try {
+    let value = vm.runInContext(request.code, context, {
+      filename: `repl-${request.id}.js`,
+    });
+    if (value !== null && typeof value === "object" && typeof value.then === "function") {
+      value = await value;
+    }
+    if (value !== undefined) {
+      context._ = value;
+      captured.stdout.push(`${util.inspect(value)}\n`);
+    }
+  } catch (caught) {
+    error = formatError(caught);
+  }
+  return {
+    id: request.id,
+    stdout: captured.stdout.text(),
+    stderr: captured.stderr.text(),
+    error,
+  };
+}
+
+process.on("uncaughtException", (error) => {
+  captured.stderr.push(`Uncaught exception outside a snippet: ${formatError(error)}`);
+});
+process.on("unhandledRejection", (reason) => {
+  captured.stderr.push(`Unhandled rejection outside a snippet: ${formatError(reason)}`);
+});
+
+(async () => {
+  const lines = readline.createInterface({ input: process.stdin, crlfDelay: Infinity });
+  for await (const line of lines) {
+    const result = await run(JSON.parse(line));
+    process.stdout.write(`${MARKER}${JSON.stringify(result)}\n`);
+  }
+})();
@@ -0,0 +1,96 @@
+# Driver of the `repl` tool. Reads one JSON request per line from stdin,
+# runs the snippet in a namespace shared by all snippets and answers with a
+# single result line on stdout, prefixed with a marker so it can be told
+# apart from output written directly to the file descriptors.
+import ast
+import contextlib
+import io
+import json
+import linecache
+import os
+import sys
+import traceback
+
+MARKER = "\x1ecodex-repl:"
+
+_max_memory = int(os.environ.get("CODEX_REPL_MAX_MEMORY_BYTES", "0"))
+if _max_memory > 0:
+    try:
+        import resource
+
+        resource.setrlimit(resource.RLIMIT_AS, (_max_memory, _max_memory))
+    except (ImportError, ValueError, OSError):
+        pass
+
+_max_output = int(os.environ.get("CODEX_REPL_MAX_OUTPUT_CHARS", "0"))
+
+
+class _CappedIO(io.StringIO):
+    """Keeps the first `_max_output` characters written and counts the rest."""
+
+    def __init__(self):
+        super().__init__()
+        self.omitted = 0
+
+    def write(self, s):
+        room = _max_output - self.tell() if _max_output > 0 else len(s)
+        if room < len(s):
+            self.omitted += len(s) - max(room, 0)
+            if room > 0:
+                super().write(s[:room])
+            return len(s)
+        return super().write(s)
+
+    def captured(self):
+        text = self.getvalue()
+        if self.omitted:
+            text += "\n[... %d characters omitted ...]\n" % self.omitted
+        return text
+
+
+_namespace = {"__name__": "__main__", "__builtins__": __builtins__}
+_real_stdout = sys.stdout
+
+
+def _run(code, filename):
+    linecache.cache[filename] = (len(code), None, code.splitlines(True), filename)
+    tree = ast.parse(code, filename, "exec")
+    last = None
+    if tree.body and isinstance(tree.body[-1], ast.Expr):
+        last = ast.Expression(tree.body.pop().value)
+    exec(compile(tree, filename, "exec"), _namespace)
+    if last is not None:
+        value = eval(compile(last, filename, "eval"), _namespace)
+        if value is not None:
+            _namespace["_"] = value
+            print(repr(value))
+
+
+def _format_error(exc):
+    tb = exc.__traceback__
+    # Drop the driver's frames so the traceback starts in the snippet.
+    while tb is not None and not tb.tb_frame.f_code.co_filename.startswith("<repl-"):
+        tb = tb.tb_next
+    if isinstance(exc, SyntaxError) or tb is None:
+        return "".join(traceback.format_exception_only(type(exc), exc))
+    return "".join(traceback.format_exception(type(exc), exc, tb))
+
+
+for line in sys.stdin:
+    request = json.loads(line)
+    stdout = _CappedIO()
+    stderr = _CappedIO()
+    error = None
+    with contextlib.redirect_stdout(stdout), contextlib.redirect_stderr(stderr):
+        try:
+            _run(request["code"], "<repl-%d>" % request["id"])
+        except BaseException as exc:  # noqa: BLE001 - report everything, keep the state
+            error = _format_error(exc)
+    result = {
+        "id": request["id"],
+        "stdout": stdout.captured(),
+        "stderr": stderr.captured(),
+        "error": error,
+    }
+    _real_stdout.write(MARKER + json.dumps(result) + "\n")
+    _real_stdout.flush()
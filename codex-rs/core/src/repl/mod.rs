@@ -0,0 +1,406 @@
+//! The `repl` tool: one Python or Node interpreter per conversation that
+//! keeps its variables, imports and loaded data between snippets, so the
+//! model can explore data step by step instead of re-running a script.
+//!
+//! The interpreter runs a small driver script (`driver.py`, `driver.js`)
+//! that reads one JSON request per line from stdin and answers with a result
+//! line carrying the captured stdout, stderr and formatted error of the
+//! snippet. It is started through the tool orchestrator like any other
+//! command, so it is subject to the approval and sandbox policies of the
+//! turn that starts it. A snippet that exceeds the timeout or takes the
+//! interpreter down restarts it, losing its state.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use codex_protocol::models::SandboxPermissions;
+use codex_utils_pty::ProcessHandle;
+use codex_utils_pty::SpawnedProcess;
+use serde::Deserialize;
+use tokio::sync::Mutex;
+use tokio::sync::MutexGuard;
+use tokio::sync::broadcast;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::config::types::Repl;
+use crate::config::types::ReplLanguage;
+use crate::config::types::ShellEnvironmentPolicy;
+use crate::exec_env::create_env;
+use crate::tools::orchestrator::ToolOrchestrator;
+use crate::tools::runtimes::repl::ReplRequest;
+use crate::tools::runtimes::repl::ReplRuntime;
+use crate::tools::sandboxing::ToolCtx;
+use crate::tools::sandboxing::ToolError;
+
+pub(crate) const REPL_TOOL_NAME: &str = "repl";
+
+const PYTHON_DRIVER: &str = include_str!("driver.py");
+const NODE_DRIVER: &str = include_str!("driver.js");
+
+/// Prefix of the result line the driver writes after each snippet.
+const RESULT_MARKER: &[u8] = b"\x1ecodex-repl:";
+
+/// Output written straight to the interpreter's stdout or stderr, e.g. by a
+/// subprocess, that is kept while waiting for a result line.
+const MAX_STRAY_OUTPUT_BYTES: usize = 1024 * 1024;
+
+/// Characters of stdout and of stderr the driver captures per snippet; the
+/// rest is dropped and replaced with a note saying how much was omitted.
+const MAX_CAPTURED_OUTPUT_CHARS: usize = 256 * 1024;
+
+/// Bytes of stdout and of stderr kept in the [`ReplSnippetEvent`] recorded
+/// in the rollout.
+///
+/// [`ReplSnippetEvent`]: codex_protocol::protocol::ReplSnippetEvent
+pub(crate) const MAX_RECORDED_OUTPUT_BYTES: usize = 16 * 1024;
+
+/// Result line of the driver.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub(crate) struct SnippetResult {
+    pub(crate) id: u64,
+    pub(crate) stdout: String,
+    pub(crate) stderr: String,
+    pub(crate) error: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum ReplError {
+    /// The snippet did not finish within the timeout.
+    TimedOut(Duration),
+    /// The interpreter exited, e.g. because it ran out of memory.
+    Exited {
+        exit_code: Option<i32>,
+        output: String,
+    },
+}
+
+/// A running interpreter.
+pub(crate) struct ReplProcess {
+    handle: ProcessHandle,
+    output_rx: broadcast::Receiver<Vec<u8>>,
+    pending: Vec<u8>,
+    next_id: u64,
+    /// Set while a snippet runs. Still set on the next call when the
+    /// previous one was cancelled mid-snippet, in which case the interpreter
+    /// is in an unknown state.
+    busy: bool,
+    language: ReplLanguage,
+}
+
+impl ReplProcess {
+    pub(crate) fn new(spawned: SpawnedProcess, language: ReplLanguage) -> Self {
+        let SpawnedProcess {
+            session, output_rx, ..
+        } = spawned;
+        Self {
+            handle: session,
+            output_rx,
+            pending: Vec::new(),
+            next_id: 0,
+            busy: false,
+            language,
+        }
+    }
+
+    pub(crate) fn language(&self) -> ReplLanguage {
+        self.language
+    }
+
+    /// Whether the interpreter can take another snippet.
+    pub(crate) fn is_usable(&self) -> bool {
+        !self.busy && !self.handle.has_exited()
+    }
+
+    /// Runs `code` and waits up to `timeout` for its result. Output that
+    /// bypassed the driver's capture is prepended to the snippet's stdout.
+    pub(crate) async fn run(
+        &mut self,
+        code: &str,
+        timeout: Duration,
+    ) -> Result<SnippetResult, ReplError> {
+        self.next_id += 1;
+        let id = self.next_id;
+        let mut request = serde_json::json!({ "id": id, "code": code }).to_string();
+        request.push('\n');
+        self.busy = true;
+        if self
+            .handle
+            .writer_sender()
+            .send(request.into_bytes())
+            .await
+            .is_err()
+        {
+            return Err(self.exited());
+        }
+
+        let deadline = Instant::now() + timeout;
+        let mut stray = Vec::new();
+        loop {
+            while let Some((before, result)) = take_result(&mut self.pending) {
+                stray.extend_from_slice(&before);
+                drop_front(&mut stray, MAX_STRAY_OUTPUT_BYTES);
+                if let Some(mut result) = result
+                    && result.id == id
+                {
+                    self.busy = false;
+                    if !stray.is_empty() {
+                        result
+                            .stdout
+                            .insert_str(0, &String::from_utf8_lossy(&stray));
+                    }
+                    return Ok(result);
+                }
+            }
+            match tokio::time::timeout_at(deadline, self.output_rx.recv()).await {
+                Err(_) => return Err(ReplError::TimedOut(timeout)),
+                Ok(Ok(chunk)) => {
+                    self.pending.extend_from_slice(&chunk);
+                    trim_pending(&mut self.pending);
+                }
+                Ok(Err(broadcast::error::RecvError::Lagged(_))) => {}
+                Ok(Err(broadcast::error::RecvError::Closed)) => {
+                    stray.append(&mut self.pending);
+                    self.pending = stray;
+                    return Err(self.exited());
+                }
+            }
+        }
+    }
+
+    fn exited(&mut self) -> ReplError {
+        ReplError::Exited {
+            exit_code: self.handle.exit_code(),
+            output: String::from_utf8_lossy(&std::mem::take(&mut self.pending)).into_owned(),
+        }
+    }
+}
+
+/// Splits the first complete result line off `pending`. Returns the bytes
+/// before it and the parsed result, or `None` for a line that is not valid
+/// JSON; returns `None` altogether while no complete line has arrived.
+fn take_result(pending: &mut Vec<u8>) -> Option<(Vec<u8>, Option<SnippetResult>)> {
+    let start = pending
+        .windows(RESULT_MARKER.len())
+        .position(|window| window == RESULT_MARKER)?;
+    let body_start = start + RESULT_MARKER.len();
+    let end = body_start + pending[body_start..].iter().position(|b| *b == b'\n')?;
+    let result = serde_json::from_slice(&pending[body_start..end]).ok();
+    let before = pending[..start].to_vec();
+    pending.drain(..=end);
+    Some((before, result))
+}
+
+/// Drops bytes from the front of `buf` until it holds at most `max` bytes.
+fn drop_front(buf: &mut Vec<u8>, max: usize) {
+    if buf.len() > max {
+        let excess = buf.len() - max;
+        buf.drain(..excess);
+    }
+}
+
+/// Bounds the stray output waiting in `pending` without cutting into a
+/// result line that is still arriving, which the driver keeps bounded by
+/// capping what it captures.
+fn trim_pending(pending: &mut Vec<u8>) {
+    let stray_len = pending
+        .windows(RESULT_MARKER.len())
+        .position(|window| window == RESULT_MARKER)
+        .unwrap_or(pending.len());
+    if stray_len > MAX_STRAY_OUTPUT_BYTES {
+        pending.drain(..stray_len - MAX_STRAY_OUTPUT_BYTES);
+    }
+}
+
+/// The conversation's interpreter, started by the first `repl` call.
+#[derive(Default)]
+pub(crate) struct ReplManager {
+    process: Mutex<Option<ReplProcess>>,
+}
+
+impl ReplManager {
+    /// Locks the interpreter for the duration of a snippet.
+    pub(crate) async fn lock(&self) -> MutexGuard<'_, Option<ReplProcess>> {
+        self.process.lock().await
+    }
+
+    pub(crate) async fn terminate(&self) {
+        // Dropping the process handle kills the interpreter.
+        self.process.lock().await.take();
+    }
+}
+
+/// Starts the interpreter configured in `[repl]` in the turn's working
+/// directory, asking for approval and applying the sandbox as for a shell
+/// command.
+pub(crate) async fn start_interpreter(
+    session: &Session,
+    turn: &TurnContext,
+    call_id: &str,
+    cancellation_token: &CancellationToken,
+) -> Result<ReplProcess, String> {
+    let config = turn.client.config();
+    let repl = &config.repl;
+    let cwd = turn.cwd.clone();
+    // Approvals and exec policy rules see the interpreter, not the driver.
+    let display_command = vec![repl.interpreter()];
+    let exec_approval_requirement = session
+        .services
+        .exec_policy
+        .create_exec_approval_requirement_for_command(
+            &session.features(),
+            &display_command,
+            turn.approval_policy,
+            &turn.sandbox_policy,
+            SandboxPermissions::UseDefault,
+            &cwd,
+        )
+        .await;
+    let request = ReplRequest {
+        command: interpreter_command(repl),
+        display_command,
+        cwd,
+        env: interpreter_env(&turn.shell_environment_policy, repl),
+        exec_approval_requirement,
+    };
+    let tool_ctx = ToolCtx {
+        session,
+        turn,
+        call_id: call_id.to_string(),
+        tool_name: REPL_TOOL_NAME.to_string(),
+        cancellation_token: cancellation_token.clone(),
+    };
+    let spawned = ToolOrchestrator::new()
+        .run(
+            &mut ReplRuntime,
+            &request,
+            &tool_ctx,
+            turn,
+            turn.approval_policy,
+        )
+        .await
+        .map_err(|err| match err {
+            ToolError::Rejected(message) => message,
+            ToolError::Codex(err) => err.to_string(),
+        })?;
+    Ok(ReplProcess::new(spawned, repl.language()))
+}
+
+fn interpreter_command(repl: &Repl) -> Vec<String> {
+    match repl.language() {
+        ReplLanguage::Python => vec![
+            repl.interpreter(),
+            "-u".to_string(),
+            "-c".to_string(),
+            PYTHON_DRIVER.to_string(),
+        ],
+        ReplLanguage::Node => vec![
+            repl.interpreter(),
+            format!("--max-old-space-size={}", repl.max_memory_mb()),
+            "-e".to_string(),
+            NODE_DRIVER.to_string(),
+        ],
+    }
+}
+
+fn interpreter_env(policy: &ShellEnvironmentPolicy, repl: &Repl) -> HashMap<String, String> {
+    let mut env = create_env(policy);
+    env.insert("NO_COLOR".to_string(), "1".to_string());
+    env.insert("PYTHONIOENCODING".to_string(), "utf-8".to_string());
+    env.insert("PYTHONUNBUFFERED".to_string(), "1".to_string());
+    env.insert(
+        "CODEX_REPL_MAX_OUTPUT_CHARS".to_string(),
+        MAX_CAPTURED_OUTPUT_CHARS.to_string(),
+    );
+    env.insert(
+        "CODEX_REPL_MAX_MEMORY_BYTES".to_string(),
+        repl.max_memory_mb().saturating_mul(1024 * 1024).to_string(),
+    );
+    env
+}
+
+pub(crate) fn language_name(language: ReplLanguage) -> &'static str {
+    match language {
+        ReplLanguage::Python => "python",
+        ReplLanguage::Node => "node",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn take_result_splits_stray_output_and_partial_lines() {
+        let mut pending = b"from a subprocess\n\x1ecodex-repl:{\"id\":1,\"stdout\":\"42\\n\",\"stderr\":\"\",\"error\":null}\n\x1ecodex-repl:{\"id\":2"
+            .to_vec();
+
+        let (before, result) = take_result(&mut pending).expect("complete result line");
+        assert_eq!(before, b"from a subprocess\n".to_vec());
+        assert_eq!(
+            result,
+            Some(SnippetResult {
+                id: 1,
+                stdout: "42\n".to_string(),
+                stderr: String::new(),
+                error: None,
+            })
+        );
+
+        // The second result line has not been fully written yet.
+        assert_eq!(take_result(&mut pending), None);
+        assert_eq!(pending, b"\x1ecodex-repl:{\"id\":2".to_vec());
+    }
+
+    #[test]
+    fn trim_pending_keeps_a_partial_result_line() {
+        let mut pending = vec![b'x'; MAX_STRAY_OUTPUT_BYTES + 10];
+        pending.extend_from_slice(RESULT_MARKER);
+        pending.extend(std::iter::repeat_n(b'y', MAX_STRAY_OUTPUT_BYTES));
+
+        trim_pending(&mut pending);
+
+        let mut expected = vec![b'x'; MAX_STRAY_OUTPUT_BYTES];
+        expected.extend_from_slice(RESULT_MARKER);
+        expected.extend(std::iter::repeat_n(b'y', MAX_STRAY_OUTPUT_BYTES));
+        assert_eq!(pending, expected);
+    }
+
+    #[test]
+    fn interpreter_command_embeds_the_driver_and_memory_limit() {
+        let node = Repl {
+            language: Some(ReplLanguage::Node),
+            max_memory_mb: Some(512),
+            ..Default::default()
+        };
+        let command = interpreter_command(&node);
+        assert_eq!(
+            command[..2].to_vec(),
+            vec!["node".to_string(), "--max-old-space-size=512".to_string()]
+        );
+        assert_eq!(command[3], NODE_DRIVER);
+
+        let python = Repl {
+            interpreter: Some("/opt/venv/bin/python".to_string()),
+            ..Default::default()
+        };
+        let command = interpreter_command(&python);
+        assert_eq!(
+            command[..3].to_vec(),
+            vec![
+                "/opt/venv/bin/python".to_string(),
+                "-u".to_string(),
+                "-c".to_string()
+            ]
+        );
+        assert_eq!(
+            interpreter_env(&ShellEnvironmentPolicy::default(), &python)
+                .get("CODEX_REPL_MAX_MEMORY_BYTES")
+                .map(String::as_str),
+            Some("1073741824")
+        );
+    }
+}
@@ -0,0 +1,409 @@
+//! Catches assistant messages that spell out a tool call as text instead of
+//! making one, which some models fall back to (Gemini's ```tool_code blocks,
+//! Hermes-style `<tool_call>` tags, `<function_calls>` XML). Nothing runs in
+//! that case, so the user is told why; with
+//! [`crate::features::Feature::ConvertHallucinatedToolCalls`] an unambiguous
+//! call to a known function tool is executed instead.
+
+use std::ops::Range;
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use serde_json::Map;
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PseudoToolSyntax {
+    /// ```tool_code (or tool_call, function_call) fenced blocks.
+    FencedBlock,
+    /// `<tool_call>{"name": ..., "arguments": ...}</tool_call>`.
+    ToolCallTag,
+    /// `<function_calls><invoke name="..."><parameter name="...">`.
+    FunctionCallsXml,
+}
+
+use PseudoToolSyntax::FencedBlock;
+use PseudoToolSyntax::FunctionCallsXml;
+use PseudoToolSyntax::ToolCallTag;
+
+impl PseudoToolSyntax {
+    fn label(self) -> &'static str {
+        match self {
+            FencedBlock => "a ```tool_code block",
+            ToolCallTag => "a <tool_call> tag",
+            FunctionCallsXml => "a <function_calls> block",
+        }
+    }
+}
+
+const ALL_SYNTAXES: &[PseudoToolSyntax] = &[FencedBlock, ToolCallTag, FunctionCallsXml];
+
+/// Syntaxes each model family is known to fall back to, matched against the
+/// model slug. Models outside these families are checked for all of them.
+const FAMILY_SYNTAXES: &[(&str, &[PseudoToolSyntax])] = &[
+    ("gemini", &[FencedBlock, ToolCallTag]),
+    ("qwen", &[ToolCallTag, FencedBlock]),
+    ("gpt-oss", &[ToolCallTag, FencedBlock]),
+    ("llama", &[ToolCallTag, FencedBlock]),
+    ("deepseek", &[ToolCallTag, FencedBlock]),
+    ("claude", &[FunctionCallsXml]),
+];
+
+const FENCE_LANGUAGES: &[&str] = &["tool_code", "tool_call", "tool_calls", "function_call"];
+
+/// What to do about an assistant message that contains pseudo tool calls.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ResponseLint {
+    /// Run `call` in place of the text. `message` is the original message
+    /// with the pseudo call stripped, or `None` when nothing else was said.
+    Convert {
+        message: Option<ResponseItem>,
+        call: ResponseItem,
+    },
+    /// Nothing was executed; tell the user why.
+    Flag { warning: String },
+}
+
+/// Looks for pseudo tool calls in an assistant message. Conversion is only
+/// attempted when `convert` is set, the message holds exactly one call, and
+/// `is_function_tool` recognizes its name.
+pub(crate) fn lint_response_item(
+    item: &ResponseItem,
+    model_slug: &str,
+    convert: bool,
+    is_function_tool: impl Fn(&str) -> bool,
+) -> Option<ResponseLint> {
+    let ResponseItem::Message { role, content, .. } = item else {
+        return None;
+    };
+    if role != "assistant" {
+        return None;
+    }
+    let syntaxes = syntaxes_for_model(model_slug);
+    let blocks = content
+        .iter()
+        .enumerate()
+        .flat_map(|(index, content_item)| match content_item {
+            ContentItem::OutputText { text } => find_blocks(text, syntaxes)
+                .into_iter()
+                .map(|block| (index, block))
+                .collect(),
+            _ => Vec::new(),
+        })
+        .collect::<Vec<_>>();
+    if blocks.is_empty() {
+        return None;
+    }
+
+    if convert
+        && let [(index, block)] = blocks.as_slice()
+        && let [(name, arguments)] = block.calls.as_slice()
+        && is_function_tool(name)
+    {
+        let call = ResponseItem::FunctionCall {
+            id: None,
+            name: name.clone(),
+            arguments: arguments.clone(),
+            call_id: format!("call_{}", uuid::Uuid::new_v4().simple()),
+        };
+        return Some(ResponseLint::Convert {
+            message: strip_block(item, *index, block.range.clone()),
+            call,
+        });
+    }
+
+    let mut labels: Vec<&str> = Vec::new();
+    for (_, block) in &blocks {
+        let label = block.syntax.label();
+        if !labels.contains(&label) {
+            labels.push(label);
+        }
+    }
+    let calls = if blocks.len() == 1 {
+        "a tool call"
+    } else {
+        "tool calls"
+    };
+    Some(ResponseLint::Flag {
+        warning: format!(
+            "The model wrote {calls} as text ({}) instead of invoking a tool, so nothing was executed.",
+            labels.join(", ")
+        ),
+    })
+}
+
+fn syntaxes_for_model(model_slug: &str) -> &'static [PseudoToolSyntax] {
+    let slug = model_slug.to_ascii_lowercase();
+    FAMILY_SYNTAXES
+        .iter()
+        .find(|(family, _)| slug.contains(family))
+        .map_or(ALL_SYNTAXES, |(_, syntaxes)| syntaxes)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct PseudoToolBlock {
+    syntax: PseudoToolSyntax,
+    range: Range<usize>,
+    /// Tool name and JSON arguments of each call the block spells out. Empty
+    /// when the block cannot be parsed.
+    calls: Vec<(String, String)>,
+}
+
+/// Pseudo tool call blocks in `text`, in order and without overlaps.
+fn find_blocks(text: &str, syntaxes: &[PseudoToolSyntax]) -> Vec<PseudoToolBlock> {
+    let mut blocks = Vec::new();
+    for syntax in syntaxes {
+        match syntax {
+            FencedBlock => {
+                for (range, language, body) in fenced_blocks(text) {
+                    if FENCE_LANGUAGES.contains(&language) {
+                        blocks.push(PseudoToolBlock {
+                            syntax: FencedBlock,
+                            range,
+                            calls: parse_json_calls(body).unwrap_or_default(),
+                        });
+                    }
+                }
+            }
+            ToolCallTag => {
+                for (range, body) in tagged_blocks(text, "<tool_call>", "</tool_call>") {
+                    blocks.push(PseudoToolBlock {
+                        syntax: ToolCallTag,
+                        range,
+                        calls: parse_json_calls(body).unwrap_or_default(),
+                    });
+                }
+            }
+            FunctionCallsXml => {
+                for (range, body) in tagged_blocks(text, "<function_calls>", "</function_calls>") {
+                    blocks.push(PseudoToolBlock {
+                        syntax: FunctionCallsXml,
+                        range,
+                        calls: parse_invokes(body).unwrap_or_default(),
+                    });
+                }
+            }
+        }
+    }
+    // Ordinary code blocks are examples, not attempted calls.
+    let code_blocks = fenced_blocks(text)
+        .into_iter()
+        .filter(|(_, language, _)| !FENCE_LANGUAGES.contains(language))
+        .map(|(range, _, _)| range)
+        .collect::<Vec<_>>();
+    blocks.retain(|block| {
+        !code_blocks
+            .iter()
+            .any(|code| code.start <= block.range.start && block.range.end <= code.end)
+    });
+    blocks.sort_by_key(|block| block.range.start);
+    let mut end = 0;
+    blocks.retain(|block| {
+        let keep = block.range.start >= end;
+        if keep {
+            end = block.range.end;
+        }
+        keep
+    });
+    blocks
+}
+
+/// Fenced code blocks as (range, language, body).
+fn fenced_blocks(text: &str) -> Vec<(Range<usize>, &str, &str)> {
+    let mut blocks = Vec::new();
+    let mut offset = 0;
+    while let Some(start) = text[offset..].find("```").map(|i| offset + i) {
+        let Some(header_end) = text[start..].find('\n').map(|i| start + i) else {
+            break;
+        };
+        let language = text[start + 3..header_end].trim();
+        let Some(close) = text[header_end..].find("```").map(|i| header_end + i) else {
+            break;
+        };
+        blocks.push((start..close + 3, language, &text[header_end + 1..close]));
+        offset = close + 3;
+    }
+    blocks
+}
+
+/// Blocks delimited by `open` and `close` as (range, body).
+fn tagged_blocks<'a>(text: &'a str, open: &str, close: &str) -> Vec<(Range<usize>, &'a str)> {
+    let mut blocks = Vec::new();
+    let mut offset = 0;
+    while let Some(start) = text[offset..].find(open).map(|i| offset + i) {
+        let body_start = start + open.len();
+        let Some(end) = text[body_start..].find(close).map(|i| body_start + i) else {
+            break;
+        };
+        blocks.push((start..end + close.len(), &text[body_start..end]));
+        offset = end + close.len();
+    }
+    blocks
+}
+
+/// Parses `{"name": ..., "arguments": {...}}` or an array of those.
+fn parse_json_calls(body: &str) -> Option<Vec<(String, String)>> {
+    let value: Value = serde_json::from_str(body.trim()).ok()?;
+    let calls = match value {
+        Value::Array(calls) => calls,
+        call => vec![call],
+    };
+    calls
+        .into_iter()
+        .map(|call| {
+            let name = call.get("name")?.as_str()?.to_string();
+            let arguments = match call.get("arguments").or_else(|| call.get("parameters"))? {
+                Value::String(raw) => serde_json::from_str::<Value>(raw).ok()?,
+                arguments => arguments.clone(),
+            };
+            arguments.is_object().then(|| (name, arguments.to_string()))
+        })
+        .collect()
+}
+
+/// Parses the `<invoke>` elements of a `<function_calls>` block. Parameter
+/// values that are valid JSON are kept as JSON, anything else as a string.
+fn parse_invokes(body: &str) -> Option<Vec<(String, String)>> {
+    let invokes = tagged_blocks(body, "<invoke name=\"", "</invoke>");
+    if invokes.is_empty() {
+        return None;
+    }
+    invokes
+        .into_iter()
+        .map(|(_, invoke)| {
+            let (name, parameters) = invoke.split_once("\">")?;
+            let mut arguments = Map::new();
+            for (_, parameter) in tagged_blocks(parameters, "<parameter name=\"", "</parameter>") {
+                let (key, value) = parameter.split_once("\">")?;
+                let value = serde_json::from_str(value.trim())
+                    .unwrap_or_else(|_| Value::String(value.to_string()));
+                arguments.insert(key.to_string(), value);
+            }
+            Some((name.to_string(), Value::Object(arguments).to_string()))
+        })
+        .collect()
+}
+
+/// `item` with `range` removed from its content item at `index`, or `None`
+/// when no text would be left.
+fn strip_block(item: &ResponseItem, index: usize, range: Range<usize>) -> Option<ResponseItem> {
+    let ResponseItem::Message {
+        id,
+        role,
+        content,
+        end_turn,
+    } = item
+    else {
+        return None;
+    };
+    let mut content = content.clone();
+    if let Some(ContentItem::OutputText { text }) = content.get_mut(index) {
+        text.replace_range(range, "");
+        *text = text.trim().to_string();
+    }
+    content.retain(
+        |content_item| !matches!(content_item, ContentItem::OutputText { text } if text.is_empty()),
+    );
+    if content.is_empty() {
+        return None;
+    }
+    Some(ResponseItem::Message {
+        id: id.clone(),
+        role: role.clone(),
+        content,
+        end_turn: *end_turn,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn assistant(text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: "assistant".to_string(),
+            content: vec![ContentItem::OutputText {
+                text: text.to_string(),
+            }],
+            end_turn: None,
+        }
+    }
+
+    #[test]
+    fn converts_a_single_call_to_a_known_tool() {
+        let item = assistant(
+            "Let me look.\n<tool_call>{\"name\": \"shell\", \"arguments\": {\"command\": [\"ls\"]}}</tool_call>",
+        );
+        let Some(ResponseLint::Convert { message, call }) =
+            lint_response_item(&item, "qwen3-coder", true, |name| name == "shell")
+        else {
+            panic!("expected a conversion");
+        };
+        assert_eq!(message, Some(assistant("Let me look.")));
+        let ResponseItem::FunctionCall {
+            name, arguments, ..
+        } = call
+        else {
+            panic!("expected a function call");
+        };
+        assert_eq!(
+            (name.as_str(), arguments.as_str()),
+            ("shell", r#"{"command":["ls"]}"#)
+        );
+    }
+
+    #[test]
+    fn flags_calls_that_cannot_be_converted() {
+        let python = assistant("```tool_code\nprint(default_api.shell(command=['ls']))\n```");
+        assert_eq!(
+            lint_response_item(&python, "gemini-2.5-pro", true, |_| true),
+            Some(ResponseLint::Flag {
+                warning: "The model wrote a tool call as text (a ```tool_code block) instead of invoking a tool, so nothing was executed.".to_string(),
+            })
+        );
+
+        let xml = assistant(
+            "<function_calls><invoke name=\"unknown_tool\"><parameter name=\"path\">src</parameter></invoke></function_calls>",
+        );
+        assert!(matches!(
+            lint_response_item(&xml, "claude-sonnet", true, |name| name == "shell"),
+            Some(ResponseLint::Flag { .. })
+        ));
+        // Conversion is opt-in.
+        assert!(matches!(
+            lint_response_item(&xml, "claude-sonnet", false, |_| true),
+            Some(ResponseLint::Flag { .. })
+        ));
+        // Families only get checked for the syntaxes they are known to use.
+        assert_eq!(
+            lint_response_item(&xml, "gemini-2.5-pro", true, |_| true),
+            None
+        );
+        let example = assistant(
+            "Some models emit:\n```text\n<tool_call>{\"name\": \"shell\"}</tool_call>\n```",
+        );
+        assert_eq!(lint_response_item(&example, "qwen3", true, |_| true), None);
+        assert_eq!(
+            lint_response_item(&assistant("All done."), "gpt-5", true, |_| true),
+            None
+        );
+    }
+
+    #[test]
+    fn parses_invoke_parameters_as_json_when_possible() {
+        let calls = parse_invokes(
+            "<invoke name=\"read_file\"><parameter name=\"path\">src/lib.rs</parameter><parameter name=\"limit\">20</parameter></invoke>",
+        )
+        .expect("parsable invoke");
+        let [(name, arguments)] = calls.as_slice() else {
+            panic!("expected one call, got {calls:?}");
+        };
+        assert_eq!(name, "read_file");
+        assert_eq!(
+            serde_json::from_str::<Value>(arguments).expect("json arguments"),
+            serde_json::json!({ "path": "src/lib.rs", "limit": 20 })
+        );
+    }
+}
@@ -0,0 +1,194 @@
+//! Reconciles a resumed rollout's last recorded turn context with the current
+//! configuration.
+//!
+//! Each dimension (model, environment, sandbox) is resolved independently
+//! according to [`ResumeContextOptions`]. The resulting changes are emitted as
+//! a [`ResumeContextEvent`] and persisted so replays know which values the
+//! resumed session actually ran with.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::config_types::ResumeContextOptions;
+use codex_protocol::config_types::ResumeContextSource;
+use codex_protocol::openai_models::ReasoningEffort;
+use codex_protocol::protocol::AskForApproval;
+use codex_protocol::protocol::ResumeContextChange;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::SandboxPolicy;
+use codex_protocol::protocol::TurnContextItem;
+
+/// The turn-context settings a session would start with from the current config.
+#[derive(Debug, Clone)]
+pub(crate) struct ResumeContextValues {
+    pub model: String,
+    pub effort: Option<ReasoningEffort>,
+    pub cwd: PathBuf,
+    pub approval_policy: AskForApproval,
+    pub sandbox_policy: SandboxPolicy,
+}
+
+/// Returns the most recent turn context recorded in `items`, if any.
+pub(crate) fn last_turn_context(items: &[RolloutItem]) -> Option<&TurnContextItem> {
+    items.iter().rev().find_map(|item| match item {
+        RolloutItem::TurnContext(ctx) => Some(ctx),
+        _ => None,
+    })
+}
+
+/// Lists every setting that differs between `recorded` and `current`, tagged
+/// with the source that should win. A recorded working directory that no
+/// longer exists always falls back to the current one.
+pub(crate) fn diff_resume_context(
+    options: ResumeContextOptions,
+    recorded: &TurnContextItem,
+    current: &ResumeContextValues,
+) -> Vec<ResumeContextChange> {
+    let mut changes = Vec::new();
+
+    if recorded.model != current.model {
+        changes.push(ResumeContextChange::Model {
+            recorded: recorded.model.clone(),
+            current: current.model.clone(),
+            applied: options.model,
+        });
+    }
+    if recorded.effort != current.effort {
+        changes.push(ResumeContextChange::ReasoningEffort {
+            recorded: recorded.effort,
+            current: current.effort,
+            applied: options.model,
+        });
+    }
+    if recorded.cwd != current.cwd {
+        let applied = if is_existing_dir(&recorded.cwd) {
+            options.environment
+        } else {
+            ResumeContextSource::Current
+        };
+        changes.push(ResumeContextChange::Cwd {
+            recorded: recorded.cwd.clone(),
+            current: current.cwd.clone(),
+            applied,
+        });
+    }
+    if recorded.approval_policy != current.approval_policy {
+        changes.push(ResumeContextChange::ApprovalPolicy {
+            recorded: recorded.approval_policy,
+            current: current.approval_policy,
+            applied: options.sandbox,
+        });
+    }
+    if recorded.sandbox_policy != current.sandbox_policy {
+        changes.push(ResumeContextChange::SandboxPolicy {
+            recorded: recorded.sandbox_policy.clone(),
+            current: current.sandbox_policy.clone(),
+            applied: options.sandbox,
+        });
+    }
+
+    changes
+}
+
+fn is_existing_dir(path: &Path) -> bool {
+    path.is_absolute() && path.is_dir()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::config_types::ReasoningSummary;
+    use pretty_assertions::assert_eq;
+
+    fn recorded(cwd: PathBuf) -> TurnContextItem {
+        TurnContextItem {
+            cwd,
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: "recorded-model".to_string(),
+            personality: None,
+            collaboration_mode: None,
+            effort: Some(ReasoningEffort::High),
+            summary: ReasoningSummary::Auto,
+            user_instructions: None,
+            developer_instructions: None,
+            final_output_json_schema: None,
+            truncation_policy: None,
+        }
+    }
+
+    fn current(cwd: PathBuf) -> ResumeContextValues {
+        ResumeContextValues {
+            model: "current-model".to_string(),
+            effort: Some(ReasoningEffort::High),
+            cwd,
+            approval_policy: AskForApproval::OnRequest,
+            sandbox_policy: SandboxPolicy::ReadOnly,
+        }
+    }
+
+    #[test]
+    fn reports_only_changed_fields_with_per_dimension_source() {
+        let recorded_dir = tempfile::tempdir().expect("tempdir");
+        let current_dir = tempfile::tempdir().expect("tempdir");
+        let options = ResumeContextOptions {
+            model: ResumeContextSource::Recorded,
+            environment: ResumeContextSource::Recorded,
+            sandbox: ResumeContextSource::Current,
+        };
+
+        let changes = diff_resume_context(
+            options,
+            &recorded(recorded_dir.path().to_path_buf()),
+            &current(current_dir.path().to_path_buf()),
+        );
+
+        assert_eq!(
+            changes,
+            vec![
+                ResumeContextChange::Model {
+                    recorded: "recorded-model".to_string(),
+                    current: "current-model".to_string(),
+                    applied: ResumeContextSource::Recorded,
+                },
+                ResumeContextChange::Cwd {
+                    recorded: recorded_dir.path().to_path_buf(),
+                    current: current_dir.path().to_path_buf(),
+                    applied: ResumeContextSource::Recorded,
+                },
+                ResumeContextChange::ApprovalPolicy {
+                    recorded: AskForApproval::Never,
+                    current: AskForApproval::OnRequest,
+                    applied: ResumeContextSource::Current,
+                },
+                ResumeContextChange::SandboxPolicy {
+                    recorded: SandboxPolicy::DangerFullAccess,
+                    current: SandboxPolicy::ReadOnly,
+                    applied: ResumeContextSource::Current,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_recorded_cwd_falls_back_to_current() {
+        let current_dir = tempfile::tempdir().expect("tempdir");
+        let missing = current_dir.path().join("gone");
+        let options = ResumeContextOptions {
+            environment: ResumeContextSource::Recorded,
+            ..Default::default()
+        };
+
+        let changes = diff_resume_context(
+            options,
+            &recorded(missing.clone()),
+            &current(current_dir.path().to_path_buf()),
+        );
+
+        assert!(changes.contains(&ResumeContextChange::Cwd {
+            recorded: missing,
+            current: current_dir.path().to_path_buf(),
+            applied: ResumeContextSource::Current,
+        }));
+    }
+}
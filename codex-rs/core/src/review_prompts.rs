@@ -1,13 +1,17 @@
 use codex_git::merge_base_with_head;
+use codex_protocol::protocol::ReviewOutputEvent;
+use codex_protocol::protocol::ReviewPaths;
 use codex_protocol::protocol::ReviewRequest;
 use codex_protocol::protocol::ReviewTarget;
 use std::path::Path;
+use wildmatch::WildMatchPattern;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct ResolvedReviewRequest {
     pub target: ReviewTarget,
     pub prompt: String,
     pub user_facing_hint: String,
+    pub paths: Option<ReviewPaths>,
 }
 
 const UNCOMMITTED_PROMPT: &str = "Review the current code changes (staged, unstaged, and untracked files) and provide prioritized findings.";
@@ -24,18 +28,112 @@ pub fn resolve_review_request(
     cwd: &Path,
 ) -> anyhow::Result<ResolvedReviewRequest> {
     let target = request.target;
-    let prompt = review_prompt(&target, cwd)?;
+    let paths = request.paths.and_then(normalize_review_paths);
+    let mut prompt = review_prompt(&target, cwd)?;
+    if let Some(paths) = &paths {
+        prompt.push_str("\n\n");
+        prompt.push_str(&paths_prompt(paths));
+    }
     let user_facing_hint = request
         .user_facing_hint
-        .unwrap_or_else(|| user_facing_hint(&target));
+        .unwrap_or_else(|| scoped_user_facing_hint(&target, paths.as_ref()));
 
     Ok(ResolvedReviewRequest {
         target,
         prompt,
         user_facing_hint,
+        paths,
     })
 }
 
+/// Trims the patterns and drops empty ones; `None` when nothing is left.
+pub fn normalize_review_paths(paths: ReviewPaths) -> Option<ReviewPaths> {
+    let clean = |patterns: Vec<String>| -> Vec<String> {
+        patterns
+            .into_iter()
+            .map(|pattern| pattern.trim().to_string())
+            .filter(|pattern| !pattern.is_empty())
+            .collect()
+    };
+    let paths = ReviewPaths {
+        include: clean(paths.include),
+        exclude: clean(paths.exclude),
+    };
+    (!paths.is_empty()).then_some(paths)
+}
+
+/// Whether `path`, relative to the directory the review runs in, is one of the
+/// files `paths` limits the review to. A pattern also selects everything under
+/// the directory it names, as a git pathspec does.
+pub fn review_paths_match(paths: &ReviewPaths, path: &Path) -> bool {
+    let path = path.to_string_lossy();
+    let matches = |pattern: &String| {
+        let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+        WildMatchPattern::<'*', '?'>::new(pattern).matches(&path)
+            || path
+                .strip_prefix(pattern)
+                .is_some_and(|rest| rest.starts_with('/'))
+    };
+    (paths.include.is_empty() || paths.include.iter().any(matches))
+        && !paths.exclude.iter().any(matches)
+}
+
+/// Drops the findings of `output` located outside `paths`; the model is asked
+/// not to report them, but nothing else enforces it. Locations under `cwd` are
+/// matched relative to it.
+pub fn retain_findings_in_paths(output: &mut ReviewOutputEvent, paths: &ReviewPaths, cwd: &Path) {
+    output.findings.retain(|finding| {
+        let path = &finding.code_location.absolute_file_path;
+        review_paths_match(paths, path.strip_prefix(cwd).unwrap_or(path))
+    });
+}
+
+/// Instructions limiting the review to `paths`, with the git pathspecs that
+/// select them so every diff source can be filtered the same way.
+fn paths_prompt(paths: &ReviewPaths) -> String {
+    let list = |patterns: &[String]| {
+        patterns
+            .iter()
+            .map(|pattern| format!("`{pattern}`"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let mut sentences = Vec::new();
+    if !paths.include.is_empty() {
+        sentences.push(format!(
+            "Only review changes to files matching {}.",
+            list(&paths.include)
+        ));
+    }
+    if !paths.exclude.is_empty() {
+        sentences.push(format!(
+            "Ignore changes to files matching {}.",
+            list(&paths.exclude)
+        ));
+    }
+    let pathspecs = paths
+        .include
+        .iter()
+        .map(|pattern| shell_quote(&format!(":(glob){pattern}")))
+        .chain(
+            paths
+                .exclude
+                .iter()
+                .map(|pattern| shell_quote(&format!(":(glob,exclude){pattern}"))),
+        )
+        .collect::<Vec<_>>()
+        .join(" ");
+    sentences.push(format!(
+        "Limit every `git diff`, `git show`, and `git status` you run to these files by appending `-- {pathspecs}`, and do not report findings outside them."
+    ));
+    sentences.join(" ")
+}
+
+/// Single-quotes `arg` for a POSIX shell.
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
 pub fn review_prompt(target: &ReviewTarget, cwd: &Path) -> anyhow::Result<String> {
     match target {
         ReviewTarget::UncommittedChanges => Ok(UNCOMMITTED_PROMPT.to_string()),
@@ -83,11 +181,154 @@ pub fn user_facing_hint(target: &ReviewTarget) -> String {
     }
 }
 
+/// [`user_facing_hint`] of `target`, followed by the files the review is
+/// limited to.
+pub fn scoped_user_facing_hint(target: &ReviewTarget, paths: Option<&ReviewPaths>) -> String {
+    let hint = user_facing_hint(target);
+    let Some(paths) = paths.filter(|paths| !paths.is_empty()) else {
+        return hint;
+    };
+    let mut scope = Vec::new();
+    if !paths.include.is_empty() {
+        scope.push(format!("in {}", paths.include.join(", ")));
+    }
+    if !paths.exclude.is_empty() {
+        scope.push(format!("excluding {}", paths.exclude.join(", ")));
+    }
+    format!("{hint} ({})", scope.join("; "))
+}
+
 impl From<ResolvedReviewRequest> for ReviewRequest {
     fn from(resolved: ResolvedReviewRequest) -> Self {
         ReviewRequest {
             target: resolved.target,
             user_facing_hint: Some(resolved.user_facing_hint),
+            paths: resolved.paths,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::protocol::ReviewCodeLocation;
+    use codex_protocol::protocol::ReviewFinding;
+    use codex_protocol::protocol::ReviewLineRange;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    #[test]
+    fn path_filter_scopes_prompt_and_hint() {
+        let resolved = resolve_review_request(
+            ReviewRequest {
+                target: ReviewTarget::Commit {
+                    sha: "0123456789".to_string(),
+                    title: None,
+                },
+                user_facing_hint: None,
+                paths: Some(ReviewPaths {
+                    include: vec![" src/api/** ".to_string(), String::new()],
+                    exclude: vec!["src/api/generated/**".to_string()],
+                }),
+            },
+            Path::new("/repo"),
+        )
+        .expect("resolves commit review");
+
+        assert_eq!(
+            resolved.paths,
+            Some(ReviewPaths {
+                include: vec!["src/api/**".to_string()],
+                exclude: vec!["src/api/generated/**".to_string()],
+            })
+        );
+        assert_eq!(
+            resolved.prompt,
+            "Review the code changes introduced by commit 0123456789. Provide prioritized, actionable findings.\n\n\
+             Only review changes to files matching `src/api/**`. Ignore changes to files matching `src/api/generated/**`. \
+             Limit every `git diff`, `git show`, and `git status` you run to these files by appending \
+             `-- ':(glob)src/api/**' ':(glob,exclude)src/api/generated/**'`, and do not report findings outside them."
+        );
+        assert_eq!(
+            resolved.user_facing_hint,
+            "commit 0123456 (in src/api/**; excluding src/api/generated/**)"
+        );
+    }
+
+    #[test]
+    fn pathspecs_quote_single_quotes() {
+        let prompt = paths_prompt(&ReviewPaths {
+            include: vec!["docs/it's/**".to_string()],
+            exclude: Vec::new(),
+        });
+
+        assert!(
+            prompt.contains(r"`-- ':(glob)docs/it'\''s/**'`"),
+            "unexpected prompt: {prompt}"
+        );
+    }
+
+    #[test]
+    fn findings_outside_the_paths_are_dropped() {
+        let finding = |path: &str| ReviewFinding {
+            title: path.to_string(),
+            body: String::new(),
+            confidence_score: 0.5,
+            priority: 1,
+            code_location: ReviewCodeLocation {
+                absolute_file_path: PathBuf::from(path),
+                line_range: ReviewLineRange { start: 1, end: 2 },
+            },
+        };
+        let mut output = ReviewOutputEvent {
+            findings: vec![
+                finding("/repo/src/api/handlers.rs"),
+                finding("/repo/src/api/generated/types.rs"),
+                finding("/repo/src/main.rs"),
+                finding("/repo/src/api"),
+                finding("/elsewhere/src/api/lib.rs"),
+            ],
+            ..Default::default()
+        };
+
+        retain_findings_in_paths(
+            &mut output,
+            &ReviewPaths {
+                include: vec!["src/api".to_string()],
+                exclude: vec!["src/api/generated/**".to_string()],
+            },
+            Path::new("/repo"),
+        );
+
+        assert_eq!(
+            output,
+            ReviewOutputEvent {
+                findings: vec![
+                    finding("/repo/src/api/handlers.rs"),
+                    finding("/repo/src/api"),
+                ],
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn blank_path_filter_is_dropped() {
+        let resolved = resolve_review_request(
+            ReviewRequest {
+                target: ReviewTarget::UncommittedChanges,
+                user_facing_hint: None,
+                paths: Some(ReviewPaths {
+                    include: vec!["  ".to_string()],
+                    exclude: Vec::new(),
+                }),
+            },
+            Path::new("/repo"),
+        )
+        .expect("resolves uncommitted review");
+
+        assert_eq!(resolved.paths, None);
+        assert_eq!(resolved.prompt, UNCOMMITTED_PROMPT);
+        assert_eq!(resolved.user_facing_hint, "current changes");
+    }
+}
@@ -0,0 +1,360 @@
+//! Export of a rollout as one structured JSON document, for analytics that
+//! should not depend on the line format of rollout files.
+//!
+//! [`export_rollout`] reads the lines of a rollout of any version (see
+//! [`super::migrate`]) and collects the session metadata, the messages and
+//! tool calls in the order they were recorded, and the token counts reported
+//! by the provider, each with the timestamp of its line. The document's shape
+//! is versioned by [`ROLLOUT_EXPORT_SCHEMA_VERSION`]: adding fields keeps the
+//! version, renaming or removing them bumps it.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::ThreadId;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use codex_protocol::protocol::SessionSource;
+use codex_protocol::protocol::TokenUsage;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::migrate::migrate_contents;
+use crate::compact::content_items_to_text;
+
+pub const ROLLOUT_EXPORT_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RolloutExport {
+    pub schema_version: u32,
+    /// `None` when the rollout has no session meta line.
+    pub session: Option<ExportedSession>,
+    /// Models the turns ran with, in the order they were first used.
+    pub models: Vec<String>,
+    /// Turns started by a user message.
+    pub user_turns: usize,
+    pub messages: Vec<ExportedMessage>,
+    pub tool_calls: Vec<ExportedToolCall>,
+    pub token_counts: Vec<ExportedTokenCount>,
+    /// Usage of the whole session, from the last token count.
+    pub total_token_usage: Option<TokenUsage>,
+    /// Lines that could not be parsed and were left out.
+    pub skipped_lines: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedSession {
+    pub id: ThreadId,
+    pub forked_from_id: Option<ThreadId>,
+    pub started_at: String,
+    pub cwd: PathBuf,
+    pub originator: String,
+    pub cli_version: String,
+    pub source: SessionSource,
+    pub model_provider: Option<String>,
+    pub title: Option<String>,
+}
+
+/// A message of the model's history: `user` messages include the context
+/// Codex adds (instructions, environment), `assistant` messages are the
+/// model's replies.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedMessage {
+    pub timestamp: String,
+    pub role: String,
+    pub text: String,
+    pub images: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedToolCall {
+    pub timestamp: String,
+    pub call_id: Option<String>,
+    pub name: String,
+    /// The arguments as the model wrote them: JSON for function tools, free
+    /// text for custom tools.
+    pub arguments: String,
+    /// `None` when the call never got an output, e.g. it was interrupted, or
+    /// the tool ran on the provider's side.
+    pub output: Option<String>,
+    pub output_timestamp: Option<String>,
+    pub success: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedTokenCount {
+    pub timestamp: String,
+    /// Usage of the request that was just answered.
+    pub last: TokenUsage,
+    /// Usage of the session so far.
+    pub total: TokenUsage,
+    pub model_context_window: Option<i64>,
+}
+
+/// Exports the rollout file at `path`.
+pub async fn export_rollout_file(path: &Path) -> io::Result<RolloutExport> {
+    let contents = tokio::fs::read_to_string(path).await?;
+    Ok(export_rollout(&contents))
+}
+
+/// Exports the rollout `contents`, of any rollout version.
+pub fn export_rollout(contents: &str) -> RolloutExport {
+    let mut export = RolloutExport {
+        schema_version: ROLLOUT_EXPORT_SCHEMA_VERSION,
+        session: None,
+        models: Vec::new(),
+        user_turns: 0,
+        messages: Vec::new(),
+        tool_calls: Vec::new(),
+        token_counts: Vec::new(),
+        total_token_usage: None,
+        skipped_lines: 0,
+    };
+    // Index into `tool_calls` of each call that has not got its output yet.
+    let mut open_calls: HashMap<String, usize> = HashMap::new();
+
+    for raw in migrate_contents(contents)
+        .lines()
+        .filter(|raw| !raw.trim().is_empty())
+    {
+        let Ok(RolloutLine { timestamp, item }) = serde_json::from_str::<RolloutLine>(raw) else {
+            export.skipped_lines += 1;
+            continue;
+        };
+        match item {
+            RolloutItem::SessionMeta(meta_line) => {
+                // Forked rollouts repeat the source's meta line after their own.
+                if export.session.is_none() {
+                    let meta = meta_line.meta;
+                    export.session = Some(ExportedSession {
+                        id: meta.id,
+                        forked_from_id: meta.forked_from_id,
+                        started_at: meta.timestamp,
+                        cwd: meta.cwd,
+                        originator: meta.originator,
+                        cli_version: meta.cli_version,
+                        source: meta.source,
+                        model_provider: meta.model_provider,
+                        title: meta.title,
+                    });
+                }
+            }
+            RolloutItem::TurnContext(context) => {
+                if !export.models.contains(&context.model) {
+                    export.models.push(context.model);
+                }
+            }
+            RolloutItem::EventMsg(EventMsg::UserMessage(_)) => export.user_turns += 1,
+            RolloutItem::EventMsg(EventMsg::TokenCount(event)) => {
+                if let Some(info) = event.info {
+                    export.total_token_usage = Some(info.total_token_usage.clone());
+                    export.token_counts.push(ExportedTokenCount {
+                        timestamp,
+                        last: info.last_token_usage,
+                        total: info.total_token_usage,
+                        model_context_window: info.model_context_window,
+                    });
+                }
+            }
+            RolloutItem::ResponseItem(item) => {
+                record_response_item(&mut export, &mut open_calls, timestamp, item);
+            }
+            RolloutItem::EventMsg(_) | RolloutItem::Compacted(_) => {}
+        }
+    }
+    export
+}
+
+fn record_response_item(
+    export: &mut RolloutExport,
+    open_calls: &mut HashMap<String, usize>,
+    timestamp: String,
+    item: ResponseItem,
+) {
+    let (call_id, name, arguments) = match item {
+        ResponseItem::Message { role, content, .. } => {
+            let images = content
+                .iter()
+                .filter(|item| matches!(item, ContentItem::InputImage { .. }))
+                .count();
+            export.messages.push(ExportedMessage {
+                timestamp,
+                role,
+                text: content_items_to_text(&content).unwrap_or_default(),
+                images,
+            });
+            return;
+        }
+        ResponseItem::FunctionCall {
+            name,
+            arguments,
+            call_id,
+            ..
+        } => (Some(call_id), name, arguments),
+        ResponseItem::CustomToolCall {
+            call_id,
+            name,
+            input,
+            ..
+        } => (Some(call_id), name, input),
+        ResponseItem::LocalShellCall {
+            call_id, action, ..
+        } => (
+            call_id,
+            "local_shell".to_string(),
+            serde_json::to_string(&action).unwrap_or_default(),
+        ),
+        ResponseItem::WebSearchCall { action, .. } => (
+            None,
+            "web_search".to_string(),
+            serde_json::to_string(&action).unwrap_or_default(),
+        ),
+        ResponseItem::CodeInterpreterCall { code, .. } => (
+            None,
+            "code_interpreter".to_string(),
+            code.unwrap_or_default(),
+        ),
+        ResponseItem::FileSearchCall { queries, .. } => (
+            None,
+            "file_search".to_string(),
+            serde_json::to_string(&queries).unwrap_or_default(),
+        ),
+        ResponseItem::FunctionCallOutput { call_id, output } => {
+            close_call(
+                export,
+                open_calls,
+                &call_id,
+                timestamp,
+                output.content,
+                output.success,
+            );
+            return;
+        }
+        ResponseItem::CustomToolCallOutput { call_id, output } => {
+            close_call(export, open_calls, &call_id, timestamp, output, None);
+            return;
+        }
+        ResponseItem::Reasoning { .. }
+        | ResponseItem::GhostSnapshot { .. }
+        | ResponseItem::Compaction { .. }
+        | ResponseItem::Other => return,
+    };
+    if let Some(call_id) = &call_id {
+        open_calls.insert(call_id.clone(), export.tool_calls.len());
+    }
+    export.tool_calls.push(ExportedToolCall {
+        timestamp,
+        call_id,
+        name,
+        arguments,
+        output: None,
+        output_timestamp: None,
+        success: None,
+    });
+}
+
+fn close_call(
+    export: &mut RolloutExport,
+    open_calls: &mut HashMap<String, usize>,
+    call_id: &str,
+    timestamp: String,
+    output: String,
+    success: Option<bool>,
+) {
+    let Some(call) = open_calls
+        .remove(call_id)
+        .and_then(|idx| export.tool_calls.get_mut(idx))
+    else {
+        return;
+    };
+    call.output = Some(output);
+    call.output_timestamp = Some(timestamp);
+    call.success = success;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn exports_messages_tool_calls_and_token_counts() {
+        let lines = [
+            json!({"timestamp": "t0", "type": "session_meta", "payload": {
+                "id": "67e55044-10b1-426f-9247-bb680e5fe0c8", "timestamp": "t0", "cwd": "/repo",
+                "originator": "codex_cli_rs", "cli_version": "0.1.0", "schema_version": 1,
+            }}),
+            json!({"timestamp": "t1", "type": "event_msg", "payload": {
+                "type": "user_message", "message": "list files",
+            }}),
+            json!({"timestamp": "t1", "type": "response_item", "payload": {
+                "type": "message", "role": "user",
+                "content": [{"type": "input_text", "text": "list files"}],
+            }}),
+            json!({"timestamp": "t2", "type": "response_item", "payload": {
+                "type": "function_call", "name": "shell", "arguments": "{\"command\":[\"ls\"]}",
+                "call_id": "c1",
+            }}),
+            json!({"timestamp": "t3", "type": "response_item", "payload": {
+                "type": "function_call_output", "call_id": "c1", "output": "a.rs",
+            }}),
+            json!({"timestamp": "t4", "type": "event_msg", "payload": {
+                "type": "token_count", "info": {
+                    "total_token_usage": {"input_tokens": 10, "cached_input_tokens": 0,
+                        "output_tokens": 2, "reasoning_output_tokens": 0, "total_tokens": 12},
+                    "last_token_usage": {"input_tokens": 10, "cached_input_tokens": 0,
+                        "output_tokens": 2, "reasoning_output_tokens": 0, "total_tokens": 12},
+                    "model_context_window": null,
+                }, "rate_limits": null,
+            }}),
+        ];
+        let mut contents = lines
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("\n");
+        contents.push_str("\nnot json\n");
+
+        let export = export_rollout(&contents);
+
+        assert_eq!(export.schema_version, ROLLOUT_EXPORT_SCHEMA_VERSION);
+        assert_eq!(
+            export.session.as_ref().map(|session| session.cwd.clone()),
+            Some(PathBuf::from("/repo"))
+        );
+        assert_eq!(export.user_turns, 1);
+        assert_eq!(
+            export.messages,
+            vec![ExportedMessage {
+                timestamp: "t1".to_string(),
+                role: "user".to_string(),
+                text: "list files".to_string(),
+                images: 0,
+            }]
+        );
+        assert_eq!(
+            export.tool_calls,
+            vec![ExportedToolCall {
+                timestamp: "t2".to_string(),
+                call_id: Some("c1".to_string()),
+                name: "shell".to_string(),
+                arguments: "{\"command\":[\"ls\"]}".to_string(),
+                output: Some("a.rs".to_string()),
+                output_timestamp: Some("t3".to_string()),
+                success: None,
+            }]
+        );
+        assert_eq!(export.token_counts.len(), 1);
+        assert_eq!(
+            export.total_token_usage.map(|usage| usage.total_tokens),
+            Some(12)
+        );
+        assert_eq!(export.skipped_lines, 1);
+    }
+}
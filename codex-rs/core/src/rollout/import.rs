@@ -0,0 +1,443 @@
+//! Import of transcripts written by other agent CLIs, so that work started
+//! there can be resumed or referenced from Codex.
+//!
+//! The mapping is best effort. Messages and tool calls are kept; anything
+//! without a Codex counterpart (thinking blocks, sidechains, UI metadata,
+//! Aider's own command output) is dropped. Tool calls keep their original
+//! names and arguments and are paired with their results; calls that never
+//! got a result are left out.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::ThreadId;
+use codex_protocol::models::BaseInstructions;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::FunctionCallOutputPayload;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::AgentMessageEvent;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::SessionSource;
+use codex_protocol::protocol::UserMessageEvent;
+use serde_json::Value;
+
+use crate::config::Config;
+use crate::rollout::RolloutRecorder;
+use crate::rollout::RolloutRecorderParams;
+
+const AIDER_SESSION_HEADER: &str = "# aider chat started at";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscriptFormat {
+    /// Claude Code session log: one JSON entry per line.
+    ClaudeCode,
+    /// Aider chat history (`.aider.chat.history.md`).
+    Aider,
+}
+
+impl TranscriptFormat {
+    /// Guesses the format from the file extension, falling back to the
+    /// first non-empty line of the contents.
+    pub fn detect(path: &Path, contents: &str) -> Option<Self> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("jsonl") => return Some(Self::ClaudeCode),
+            Some("md") => return Some(Self::Aider),
+            _ => {}
+        }
+        let first_line = contents.lines().find(|line| !line.trim().is_empty())?;
+        if first_line.starts_with('{') {
+            Some(Self::ClaudeCode)
+        } else if first_line.starts_with(AIDER_SESSION_HEADER) {
+            Some(Self::Aider)
+        } else {
+            None
+        }
+    }
+}
+
+/// A transcript converted to rollout items.
+#[derive(Debug, Default)]
+pub struct ImportedTranscript {
+    /// Working directory recorded in the transcript, if any.
+    pub cwd: Option<PathBuf>,
+    pub items: Vec<RolloutItem>,
+    pub messages: usize,
+    pub tool_calls: usize,
+}
+
+pub fn convert_transcript(format: TranscriptFormat, contents: &str) -> ImportedTranscript {
+    let mut builder = TranscriptBuilder::default();
+    match format {
+        TranscriptFormat::ClaudeCode => convert_claude_code(contents, &mut builder),
+        TranscriptFormat::Aider => convert_aider(contents, &mut builder),
+    }
+    builder.transcript
+}
+
+/// Records `transcript` as a new interactive session under
+/// `config.codex_home`, with `config.cwd` as its working directory. Returns
+/// the new thread id and the rollout path.
+pub async fn import_transcript(
+    config: &Config,
+    transcript: ImportedTranscript,
+) -> io::Result<(ThreadId, PathBuf)> {
+    if transcript.messages == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "transcript contains no messages",
+        ));
+    }
+    if config.incognito {
+        return Err(io::Error::other(
+            "cannot import a transcript in incognito mode",
+        ));
+    }
+    let thread_id = ThreadId::default();
+    let recorder = RolloutRecorder::new(
+        config,
+        RolloutRecorderParams::new(
+            thread_id,
+            None,
+            SessionSource::Cli,
+            BaseInstructions::default(),
+        ),
+    )
+    .await?;
+    recorder.record_items(&transcript.items).await?;
+    recorder.shutdown().await?;
+    Ok((thread_id, recorder.rollout_path))
+}
+
+#[derive(Default)]
+struct TranscriptBuilder {
+    transcript: ImportedTranscript,
+    /// Tool calls waiting for their result, by call id.
+    pending_calls: HashMap<String, ResponseItem>,
+}
+
+impl TranscriptBuilder {
+    fn message(&mut self, role: &str, text: &str) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        let (content, event) = if role == "user" {
+            (
+                ContentItem::InputText {
+                    text: text.to_string(),
+                },
+                EventMsg::UserMessage(UserMessageEvent {
+                    message: text.to_string(),
+                    images: None,
+                    local_images: Vec::new(),
+                    text_elements: Vec::new(),
+                    item_id: None,
+                }),
+            )
+        } else {
+            (
+                ContentItem::OutputText {
+                    text: text.to_string(),
+                },
+                EventMsg::AgentMessage(AgentMessageEvent {
+                    message: text.to_string(),
+                    item_id: None,
+                }),
+            )
+        };
+        self.transcript
+            .items
+            .push(RolloutItem::ResponseItem(ResponseItem::Message {
+                id: None,
+                role: role.to_string(),
+                content: vec![content],
+                end_turn: None,
+            }));
+        self.transcript.items.push(RolloutItem::EventMsg(event));
+        self.transcript.messages += 1;
+    }
+
+    fn tool_call(&mut self, call_id: &str, name: &str, arguments: String) {
+        self.pending_calls.insert(
+            call_id.to_string(),
+            ResponseItem::FunctionCall {
+                id: None,
+                name: name.to_string(),
+                arguments,
+                call_id: call_id.to_string(),
+            },
+        );
+    }
+
+    fn tool_result(&mut self, call_id: &str, output: String, success: bool) {
+        let Some(call) = self.pending_calls.remove(call_id) else {
+            return;
+        };
+        self.transcript.items.push(RolloutItem::ResponseItem(call));
+        self.transcript.items.push(RolloutItem::ResponseItem(
+            ResponseItem::FunctionCallOutput {
+                call_id: call_id.to_string(),
+                output: FunctionCallOutputPayload {
+                    content: output,
+                    content_items: None,
+                    success: Some(success),
+                },
+            },
+        ));
+        self.transcript.tool_calls += 1;
+    }
+}
+
+fn convert_claude_code(contents: &str, builder: &mut TranscriptBuilder) {
+    for line in contents.lines() {
+        let Ok(entry) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+        if entry["isSidechain"].as_bool() == Some(true) || entry["isMeta"].as_bool() == Some(true) {
+            continue;
+        }
+        if builder.transcript.cwd.is_none()
+            && let Some(cwd) = entry["cwd"].as_str()
+        {
+            builder.transcript.cwd = Some(PathBuf::from(cwd));
+        }
+        let role = match entry["type"].as_str() {
+            Some(role @ ("user" | "assistant")) => role,
+            _ => continue,
+        };
+        match &entry["message"]["content"] {
+            Value::String(text) => builder.message(role, text),
+            Value::Array(blocks) => {
+                for block in blocks {
+                    match block["type"].as_str() {
+                        Some("text") => {
+                            if let Some(text) = block["text"].as_str() {
+                                builder.message(role, text);
+                            }
+                        }
+                        Some("tool_use") => {
+                            if let (Some(call_id), Some(name)) =
+                                (block["id"].as_str(), block["name"].as_str())
+                            {
+                                builder.tool_call(call_id, name, block["input"].to_string());
+                            }
+                        }
+                        Some("tool_result") => {
+                            if let Some(call_id) = block["tool_use_id"].as_str() {
+                                let success = block["is_error"].as_bool() != Some(true);
+                                builder.tool_result(
+                                    call_id,
+                                    claude_tool_result_text(&block["content"]),
+                                    success,
+                                );
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Tool results are either a string or a list of content blocks, of which
+/// only the text ones are kept.
+fn claude_tool_result_text(content: &Value) -> String {
+    match content {
+        Value::String(text) => text.clone(),
+        Value::Array(blocks) => blocks
+            .iter()
+            .filter_map(|block| block["text"].as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// User prompts are the `####` lines; lines starting with `>` are Aider's own
+/// output and everything else is the model's reply.
+fn convert_aider(contents: &str, builder: &mut TranscriptBuilder) {
+    let mut current: Option<(&str, String)> = None;
+    for line in contents.lines() {
+        let next = if line.starts_with(AIDER_SESSION_HEADER) || line.starts_with('>') {
+            None
+        } else if let Some(prompt) = line.strip_prefix("####") {
+            Some(("user", prompt.strip_prefix(' ').unwrap_or(prompt)))
+        } else {
+            Some(("assistant", line))
+        };
+        let same_role = matches!(
+            (&current, next),
+            (Some((role, _)), Some((next_role, _))) if *role == next_role
+        );
+        if !same_role && let Some((role, text)) = current.take() {
+            builder.message(role, &text);
+        }
+        if let Some((role, line)) = next {
+            let (_, text) = current.get_or_insert_with(|| (role, String::new()));
+            text.push_str(line);
+            text.push('\n');
+        }
+    }
+    if let Some((role, text)) = current {
+        builder.message(role, &text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn summarize(items: &[RolloutItem]) -> Vec<String> {
+        items
+            .iter()
+            .filter_map(|item| match item {
+                RolloutItem::ResponseItem(ResponseItem::Message { role, content, .. }) => {
+                    let text = match content.as_slice() {
+                        [ContentItem::InputText { text } | ContentItem::OutputText { text }] => {
+                            text.as_str()
+                        }
+                        _ => "",
+                    };
+                    Some(format!("{role}: {text}"))
+                }
+                RolloutItem::ResponseItem(ResponseItem::FunctionCall {
+                    name, arguments, ..
+                }) => Some(format!("call {name} {arguments}")),
+                RolloutItem::ResponseItem(ResponseItem::FunctionCallOutput { output, .. }) => {
+                    Some(format!(
+                        "output {:?} {}",
+                        output.success.unwrap_or_default(),
+                        output.content
+                    ))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn converts_claude_code_session_log() {
+        let lines = [
+            json!({
+                "type": "summary",
+                "summary": "Fix the build",
+            }),
+            json!({
+                "type": "user",
+                "cwd": "/work/project",
+                "message": { "role": "user", "content": "fix the build" },
+            }),
+            json!({
+                "type": "assistant",
+                "message": { "role": "assistant", "content": [
+                    { "type": "thinking", "thinking": "hmm" },
+                    { "type": "text", "text": "Running the build." },
+                    { "type": "tool_use", "id": "toolu_1", "name": "Bash",
+                      "input": { "command": "cargo build" } },
+                    { "type": "tool_use", "id": "toolu_2", "name": "Read",
+                      "input": { "file_path": "Cargo.toml" } },
+                ] },
+            }),
+            json!({
+                "type": "user",
+                "message": { "role": "user", "content": [
+                    { "type": "tool_result", "tool_use_id": "toolu_1", "is_error": true,
+                      "content": [{ "type": "text", "text": "error: missing crate" }] },
+                ] },
+            }),
+            json!({
+                "type": "user",
+                "isSidechain": true,
+                "message": { "role": "user", "content": "subagent prompt" },
+            }),
+        ];
+        let contents = lines
+            .iter()
+            .map(Value::to_string)
+            .chain(["not json".to_string()])
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let transcript = convert_transcript(TranscriptFormat::ClaudeCode, &contents);
+
+        assert_eq!(transcript.cwd, Some(PathBuf::from("/work/project")));
+        assert_eq!(transcript.messages, 2);
+        assert_eq!(transcript.tool_calls, 1);
+        assert_eq!(
+            summarize(&transcript.items),
+            vec![
+                "user: fix the build".to_string(),
+                "assistant: Running the build.".to_string(),
+                r#"call Bash {"command":"cargo build"}"#.to_string(),
+                "output false error: missing crate".to_string(),
+            ]
+        );
+        assert!(
+            transcript
+                .items
+                .iter()
+                .any(|item| matches!(item, RolloutItem::EventMsg(EventMsg::UserMessage(_))))
+        );
+    }
+
+    #[test]
+    fn converts_aider_chat_history() {
+        let contents = "\
+# aider chat started at 2025-01-01 10:00:00
+
+> Add src/main.rs to the chat? (Y)es/(N)o [Yes]: y
+
+#### add a --verbose flag
+#### and document it
+
+I'll add the flag to the argument parser.
+
+It is documented in the README.
+
+#### thanks
+
+> Tokens: 1k sent, 200 received.
+";
+
+        let transcript = convert_transcript(TranscriptFormat::Aider, contents);
+
+        assert_eq!(transcript.cwd, None);
+        assert_eq!(
+            summarize(&transcript.items),
+            vec![
+                "user: add a --verbose flag\nand document it".to_string(),
+                "assistant: I'll add the flag to the argument parser.\n\n\
+                 It is documented in the README."
+                    .to_string(),
+                "user: thanks".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn detects_format_from_extension_or_contents() {
+        assert_eq!(
+            TranscriptFormat::detect(Path::new("session.jsonl"), ""),
+            Some(TranscriptFormat::ClaudeCode)
+        );
+        assert_eq!(
+            TranscriptFormat::detect(Path::new(".aider.chat.history.md"), ""),
+            Some(TranscriptFormat::Aider)
+        );
+        assert_eq!(
+            TranscriptFormat::detect(
+                Path::new("history"),
+                "\n# aider chat started at 2025-01-01 10:00:00\n"
+            ),
+            Some(TranscriptFormat::Aider)
+        );
+        assert_eq!(TranscriptFormat::detect(Path::new("notes.txt"), "hi"), None);
+    }
+}
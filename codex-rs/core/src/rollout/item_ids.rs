@@ -0,0 +1,85 @@
+//! Compatibility shim for rollouts recorded before legacy message and
+//! reasoning events carried the id of the item they belong to.
+
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::RolloutItem;
+
+/// Gives every persisted legacy event in `items` that has no item id one
+/// derived from its position in the rollout, so replays of old rollouts still
+/// see ids that are unique within the rollout and stable across reads.
+pub(crate) fn backfill_legacy_item_ids(items: &mut [RolloutItem]) {
+    for (index, item) in items.iter_mut().enumerate() {
+        let RolloutItem::EventMsg(event) = item else {
+            continue;
+        };
+        let item_id = match event {
+            EventMsg::UserMessage(event) => &mut event.item_id,
+            EventMsg::AgentMessage(event) => &mut event.item_id,
+            EventMsg::AgentReasoning(event) => &mut event.item_id,
+            EventMsg::AgentReasoningRawContent(event) => &mut event.item_id,
+            _ => continue,
+        };
+        if item_id.is_none() {
+            *item_id = Some(format!("legacy-{index}"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::protocol::AgentMessageEvent;
+    use codex_protocol::protocol::AgentReasoningEvent;
+    use codex_protocol::protocol::TurnAbortReason;
+    use codex_protocol::protocol::TurnAbortedEvent;
+    use pretty_assertions::assert_eq;
+
+    fn agent_message(item_id: Option<&str>) -> RolloutItem {
+        RolloutItem::EventMsg(EventMsg::AgentMessage(AgentMessageEvent {
+            message: "hi".to_string(),
+            item_id: item_id.map(str::to_string),
+        }))
+    }
+
+    fn reasoning(item_id: Option<&str>) -> RolloutItem {
+        RolloutItem::EventMsg(EventMsg::AgentReasoning(AgentReasoningEvent {
+            text: "thinking".to_string(),
+            item_id: item_id.map(str::to_string),
+        }))
+    }
+
+    #[test]
+    fn backfills_only_missing_item_ids() {
+        let mut items = vec![
+            reasoning(None),
+            reasoning(None),
+            agent_message(None),
+            RolloutItem::EventMsg(EventMsg::TurnAborted(TurnAbortedEvent {
+                reason: TurnAbortReason::Interrupted,
+            })),
+            agent_message(Some("msg_1")),
+            agent_message(None),
+        ];
+
+        backfill_legacy_item_ids(&mut items);
+
+        let ids: Vec<Option<&str>> = items
+            .iter()
+            .map(|item| match item {
+                RolloutItem::EventMsg(event) => event.item_id(),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                Some("legacy-0"),
+                Some("legacy-1"),
+                Some("legacy-2"),
+                None,
+                Some("msg_1"),
+                Some("legacy-5"),
+            ]
+        );
+    }
+}
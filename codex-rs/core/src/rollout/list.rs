@@ -8,6 +8,8 @@ use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 
 use async_trait::async_trait;
+use time::Date;
+use time::Month;
 use time::OffsetDateTime;
 use time::PrimitiveDateTime;
 use time::format_description::FormatItem;
@@ -15,7 +17,9 @@ use time::format_description::well_known::Rfc3339;
 use time::macros::format_description;
 use uuid::Uuid;
 
+use super::ARCHIVED_SESSIONS_SUBDIR;
 use super::SESSIONS_SUBDIR;
+use super::migrate::RolloutMigrator;
 use crate::protocol::EventMsg;
 use codex_file_search as file_search;
 use codex_protocol::protocol::RolloutItem;
@@ -70,7 +74,7 @@ struct HeadTailSummary {
 }
 
 /// Hard cap to bound worst‑case work per request.
-const MAX_SCAN_FILES: usize = 10000;
+pub(super) const MAX_SCAN_FILES: usize = 10000;
 const HEAD_RECORD_LIMIT: usize = 10;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -150,7 +154,7 @@ impl AnchorState {
 /// We need to apply different logic if we're ultimately going to be returning
 /// threads ordered by created_at or updated_at.
 #[async_trait]
-trait RolloutFileVisitor {
+pub(super) trait RolloutFileVisitor {
     async fn visit(
         &mut self,
         ts: OffsetDateTime,
@@ -158,6 +162,12 @@ trait RolloutFileVisitor {
         path: PathBuf,
         scanned: usize,
     ) -> ControlFlow<()>;
+
+    /// Whether the day directory for `date` can be passed over without
+    /// reading it. Its files do not count toward `MAX_SCAN_FILES`.
+    fn skip_day(&self, _date: Date) -> bool {
+        false
+    }
 }
 
 /// Collects thread items during directory traversal in created_at order,
@@ -791,7 +801,22 @@ async fn collect_rollout_day_files(
     Ok(day_files)
 }
 
-fn parse_timestamp_uuid_from_filename(name: &str) -> Option<(OffsetDateTime, Uuid)> {
+/// Relative `YYYY/MM/DD` directory under the sessions root where a rollout file
+/// with the given name belongs, derived from the timestamp in the name.
+pub fn session_day_dir_for_file_name(name: &str) -> Option<PathBuf> {
+    let (ts, _) = parse_timestamp_uuid_from_filename(name)?;
+    Some(
+        [
+            ts.year().to_string(),
+            format!("{:02}", u8::from(ts.month())),
+            format!("{:02}", ts.day()),
+        ]
+        .iter()
+        .collect(),
+    )
+}
+
+pub(crate) fn parse_timestamp_uuid_from_filename(name: &str) -> Option<(OffsetDateTime, Uuid)> {
     // Expected: rollout-YYYY-MM-DDThh-mm-ss-<uuid>.jsonl
     let core = name.strip_prefix("rollout-")?.strip_suffix(".jsonl")?;
 
@@ -870,27 +895,33 @@ async fn collect_flat_files_by_updated_at(
     Ok(candidates)
 }
 
-async fn walk_rollout_files(
+pub(super) async fn walk_rollout_files(
     root: &Path,
     scanned_files: &mut usize,
     visitor: &mut impl RolloutFileVisitor,
 ) -> io::Result<()> {
     let year_dirs = collect_dirs_desc(root, |s| s.parse::<u16>().ok()).await?;
 
-    'outer: for (_year, year_path) in year_dirs.iter() {
+    'outer: for (year, year_path) in year_dirs.iter() {
         if *scanned_files >= MAX_SCAN_FILES {
             break;
         }
         let month_dirs = collect_dirs_desc(year_path, |s| s.parse::<u8>().ok()).await?;
-        for (_month, month_path) in month_dirs.iter() {
+        for (month, month_path) in month_dirs.iter() {
             if *scanned_files >= MAX_SCAN_FILES {
                 break 'outer;
             }
             let day_dirs = collect_dirs_desc(month_path, |s| s.parse::<u8>().ok()).await?;
-            for (_day, day_path) in day_dirs.iter() {
+            for (day, day_path) in day_dirs.iter() {
                 if *scanned_files >= MAX_SCAN_FILES {
                     break 'outer;
                 }
+                if let Ok(month) = Month::try_from(*month)
+                    && let Ok(date) = Date::from_calendar_date(i32::from(*year), month, *day)
+                    && visitor.skip_day(date)
+                {
+                    continue;
+                }
                 let day_files = collect_rollout_day_files(day_path).await?;
                 for (ts, id, path) in day_files.into_iter() {
                     *scanned_files += 1;
@@ -943,6 +974,7 @@ async fn read_head_summary(path: &Path, head_limit: usize) -> io::Result<HeadTai
     let reader = tokio::io::BufReader::new(file);
     let mut lines = reader.lines();
     let mut summary = HeadTailSummary::default();
+    let mut migrator = RolloutMigrator::default();
 
     while summary.head.len() < head_limit {
         let line_opt = lines.next_line().await?;
@@ -952,40 +984,45 @@ async fn read_head_summary(path: &Path, head_limit: usize) -> io::Result<HeadTai
             continue;
         }
 
-        let parsed: Result<RolloutLine, _> = serde_json::from_str(trimmed);
-        let Ok(rollout_line) = parsed else { continue };
-
-        match rollout_line.item {
-            RolloutItem::SessionMeta(session_meta_line) => {
-                summary.source = Some(session_meta_line.meta.source.clone());
-                summary.model_provider = session_meta_line.meta.model_provider.clone();
-                summary.created_at = summary
-                    .created_at
-                    .clone()
-                    .or_else(|| Some(rollout_line.timestamp.clone()));
-                if let Ok(val) = serde_json::to_value(session_meta_line) {
-                    summary.head.push(val);
-                    summary.saw_session_meta = true;
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(trimmed) else {
+            continue;
+        };
+        for value in migrator.upgrade(value) {
+            let parsed: Result<RolloutLine, _> = serde_json::from_value(value);
+            let Ok(rollout_line) = parsed else { continue };
+
+            match rollout_line.item {
+                RolloutItem::SessionMeta(session_meta_line) => {
+                    summary.source = Some(session_meta_line.meta.source.clone());
+                    summary.model_provider = session_meta_line.meta.model_provider.clone();
+                    summary.created_at = summary
+                        .created_at
+                        .clone()
+                        .or_else(|| Some(rollout_line.timestamp.clone()));
+                    if let Ok(val) = serde_json::to_value(session_meta_line) {
+                        summary.head.push(val);
+                        summary.saw_session_meta = true;
+                    }
                 }
-            }
-            RolloutItem::ResponseItem(item) => {
-                summary.created_at = summary
-                    .created_at
-                    .clone()
-                    .or_else(|| Some(rollout_line.timestamp.clone()));
-                if let Ok(val) = serde_json::to_value(item) {
-                    summary.head.push(val);
+                RolloutItem::ResponseItem(item) => {
+                    summary.created_at = summary
+                        .created_at
+                        .clone()
+                        .or_else(|| Some(rollout_line.timestamp.clone()));
+                    if let Ok(val) = serde_json::to_value(item) {
+                        summary.head.push(val);
+                    }
                 }
-            }
-            RolloutItem::TurnContext(_) => {
-                // Not included in `head`; skip.
-            }
-            RolloutItem::Compacted(_) => {
-                // Not included in `head`; skip.
-            }
-            RolloutItem::EventMsg(ev) => {
-                if matches!(ev, EventMsg::UserMessage(_)) {
-                    summary.saw_user_event = true;
+                RolloutItem::TurnContext(_) => {
+                    // Not included in `head`; skip.
+                }
+                RolloutItem::Compacted(_) => {
+                    // Not included in `head`; skip.
+                }
+                RolloutItem::EventMsg(ev) => {
+                    if matches!(ev, EventMsg::UserMessage(_)) {
+                        summary.saw_user_event = true;
+                    }
                 }
             }
         }
@@ -1050,13 +1087,24 @@ pub async fn find_thread_path_by_id_str(
     codex_home: &Path,
     id_str: &str,
 ) -> io::Result<Option<PathBuf>> {
+    find_thread_path_in_root(codex_home.join(SESSIONS_SUBDIR), id_str)
+}
+
+/// Locate an archived thread rollout file by its UUID string. Returns `Ok(None)` if it is not
+/// present or the id is invalid.
+pub async fn find_archived_thread_path_by_id_str(
+    codex_home: &Path,
+    id_str: &str,
+) -> io::Result<Option<PathBuf>> {
+    find_thread_path_in_root(codex_home.join(ARCHIVED_SESSIONS_SUBDIR), id_str)
+}
+
+fn find_thread_path_in_root(root: PathBuf, id_str: &str) -> io::Result<Option<PathBuf>> {
     // Validate UUID format early.
     if Uuid::parse_str(id_str).is_err() {
         return Ok(None);
     }
 
-    let mut root = codex_home.to_path_buf();
-    root.push(SESSIONS_SUBDIR);
     if !root.exists() {
         return Ok(None);
     }
@@ -0,0 +1,198 @@
+//! Editing the metadata (title, tags, stats) stored in a rollout's session
+//! meta line.
+
+use std::io;
+use std::path::Path;
+
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use codex_protocol::protocol::SessionMeta;
+use codex_protocol::protocol::SessionStats;
+
+use super::migrate::migrate_contents;
+use crate::session_stats::merge_session_stats;
+
+/// Changes to apply to the metadata of a recorded session. Fields left as
+/// `None` are not modified.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionMetadataUpdate {
+    /// New title. An empty (or whitespace-only) title clears it.
+    pub title: Option<String>,
+    /// Replacement set of tags.
+    pub tags: Option<Vec<String>>,
+    /// When true, `title` is only applied if the session has no title yet.
+    pub keep_existing_title: bool,
+    /// Activity to add to the recorded stats.
+    pub stats: Option<SessionStats>,
+}
+
+impl SessionMetadataUpdate {
+    pub fn apply(self, meta: &mut SessionMeta) {
+        if let Some(title) = self.title
+            && !(self.keep_existing_title && meta.title.is_some())
+        {
+            let title = title.trim();
+            meta.title = (!title.is_empty()).then(|| title.to_string());
+        }
+        if let Some(tags) = self.tags {
+            let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+            for tag in tags {
+                let tag = tag.trim();
+                if !tag.is_empty() && !normalized.iter().any(|existing| existing == tag) {
+                    normalized.push(tag.to_string());
+                }
+            }
+            meta.tags = normalized;
+        }
+        if let Some(stats) = self.stats {
+            match &mut meta.stats {
+                Some(recorded) => merge_session_stats(recorded, &stats),
+                None => meta.stats = Some(stats),
+            }
+        }
+    }
+}
+
+/// Rewrite the leading session meta line of a rollout file's `contents`,
+/// leaving every other line untouched.
+pub(crate) fn rewrite_session_meta(
+    contents: &str,
+    update: SessionMetadataUpdate,
+) -> io::Result<(String, SessionMeta)> {
+    let contents = migrate_contents(contents);
+    let (first_line, rest) = contents.split_once('\n').unwrap_or((contents.as_ref(), ""));
+    let mut rollout_line: RolloutLine = serde_json::from_str(first_line)
+        .map_err(|e| io::Error::other(format!("failed to parse session meta line: {e}")))?;
+    let RolloutItem::SessionMeta(session_meta_line) = &mut rollout_line.item else {
+        return Err(io::Error::other(
+            "rollout does not start with session metadata",
+        ));
+    };
+    update.apply(&mut session_meta_line.meta);
+    let meta = session_meta_line.meta.clone();
+
+    let mut rewritten = serde_json::to_string(&rollout_line)?;
+    rewritten.push('\n');
+    rewritten.push_str(rest);
+    Ok((rewritten, meta))
+}
+
+/// Update the metadata of a rollout file that is not currently being recorded.
+/// Use [`crate::RolloutRecorder::update_session_metadata`] for live sessions so
+/// the edit is serialized with pending writes.
+pub async fn update_session_metadata(
+    path: &Path,
+    update: SessionMetadataUpdate,
+) -> io::Result<SessionMeta> {
+    let contents = tokio::fs::read_to_string(path).await?;
+    let (rewritten, meta) = rewrite_session_meta(&contents, update)?;
+    let tmp_path = path.with_extension("jsonl.tmp");
+    tokio::fs::write(&tmp_path, rewritten).await?;
+    tokio::fs::rename(&tmp_path, path).await?;
+    Ok(meta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::protocol::EventMsg;
+    use codex_protocol::protocol::SessionMetaLine;
+    use codex_protocol::protocol::UserMessageEvent;
+    use pretty_assertions::assert_eq;
+
+    fn rollout_contents() -> String {
+        let meta_line = RolloutLine {
+            timestamp: "2025-01-01T00:00:00.000Z".to_string(),
+            item: RolloutItem::SessionMeta(SessionMetaLine {
+                meta: SessionMeta::default(),
+                git: None,
+                environment: None,
+                schema_version: None,
+            }),
+        };
+        let user_line = RolloutLine {
+            timestamp: "2025-01-01T00:00:01.000Z".to_string(),
+            item: RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
+                message: "hello".to_string(),
+                images: None,
+                local_images: Vec::new(),
+                text_elements: Vec::new(),
+                item_id: None,
+            })),
+        };
+        format!(
+            "{}\n{}\n",
+            serde_json::to_string(&meta_line).expect("serialize meta"),
+            serde_json::to_string(&user_line).expect("serialize user message"),
+        )
+    }
+
+    #[test]
+    fn rewrite_updates_title_and_tags_only() -> io::Result<()> {
+        let contents = rollout_contents();
+        let (rewritten, meta) = rewrite_session_meta(
+            &contents,
+            SessionMetadataUpdate {
+                title: Some("  Fix flaky test ".to_string()),
+                tags: Some(vec![
+                    "ci".to_string(),
+                    " ci ".to_string(),
+                    String::new(),
+                    "tests".to_string(),
+                ]),
+                keep_existing_title: false,
+                stats: None,
+            },
+        )?;
+
+        assert_eq!(meta.title, Some("Fix flaky test".to_string()));
+        assert_eq!(meta.tags, vec!["ci".to_string(), "tests".to_string()]);
+        assert_eq!(
+            rewritten.split_once('\n').map(|(_, rest)| rest),
+            contents.split_once('\n').map(|(_, rest)| rest),
+        );
+
+        let (_, kept) = rewrite_session_meta(
+            &rewritten,
+            SessionMetadataUpdate {
+                title: Some("Generated title".to_string()),
+                tags: None,
+                keep_existing_title: true,
+                stats: None,
+            },
+        )?;
+        assert_eq!(kept.title, Some("Fix flaky test".to_string()));
+
+        let (_, cleared) = rewrite_session_meta(
+            &rewritten,
+            SessionMetadataUpdate {
+                title: Some(" ".to_string()),
+                tags: None,
+                keep_existing_title: false,
+                stats: None,
+            },
+        )?;
+        assert_eq!(cleared.title, None);
+        assert_eq!(cleared.tags, vec!["ci".to_string(), "tests".to_string()]);
+        Ok(())
+    }
+
+    #[test]
+    fn rewrite_adds_stats_to_recorded_stats() -> io::Result<()> {
+        let stats = |turns| SessionMetadataUpdate {
+            stats: Some(SessionStats {
+                turns,
+                cost_usd: Some(0.25),
+                ..SessionStats::default()
+            }),
+            ..SessionMetadataUpdate::default()
+        };
+        let (rewritten, first) = rewrite_session_meta(&rollout_contents(), stats(2))?;
+        assert_eq!(first.stats.map(|stats| stats.turns), Some(2));
+
+        let (_, resumed) = rewrite_session_meta(&rewritten, stats(3))?;
+        let resumed = resumed.stats.expect("stats recorded");
+        assert_eq!((resumed.turns, resumed.cost_usd), (5, Some(0.5)));
+        Ok(())
+    }
+}
@@ -0,0 +1,494 @@
+//! Versioning of the rollout file format and upgrades of older files.
+//!
+//! The version is recorded as `schema_version` in the session meta line that
+//! starts a rollout. Readers pass every line through a [`RolloutMigrator`],
+//! which upgrades lines of an older file one version at a time until they
+//! parse as a current [`codex_protocol::protocol::RolloutLine`].
+//! [`migrate_rollout_file`] and [`migrate_rollouts`] rewrite files in the
+//! current format so the upgrade only happens once.
+//!
+//! Versions:
+//! - 0: untagged lines. The file starts with a bare session header (`id`,
+//!   `timestamp`, `instructions`, `git`), followed by bare response items
+//!   and `{"record_type": "state"}` markers.
+//! - 1: `{"timestamp", "type", "payload"}` lines. Files written before the
+//!   version was recorded are of this version.
+//!
+//! A format change bumps [`CURRENT_ROLLOUT_SCHEMA_VERSION`], appends the
+//! upgrade from the previous version to `UPGRADES` and adds a fixture of the
+//! previous version to the tests below.
+
+use std::borrow::Cow;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde_json::Value;
+use serde_json::json;
+
+use super::ARCHIVED_SESSIONS_SUBDIR;
+use super::SESSIONS_SUBDIR;
+use crate::instructions::USER_INSTRUCTIONS_OPEN_TAG_LEGACY;
+
+pub const CURRENT_ROLLOUT_SCHEMA_VERSION: u32 = 1;
+
+/// Upgrades one line to the next version, into any number of lines.
+type Upgrade = fn(&mut RolloutMigrator, Value) -> Vec<Value>;
+
+/// The upgrade from version `n` to `n + 1` is at index `n`.
+const UPGRADES: [Upgrade; CURRENT_ROLLOUT_SCHEMA_VERSION as usize] = [upgrade_v0_line];
+
+/// Upgrades the lines of one rollout file, in order, to the current version.
+#[derive(Debug, Default)]
+pub(crate) struct RolloutMigrator {
+    /// Version of the file, detected from its first line.
+    version: Option<u32>,
+    /// Timestamp of the session, given to lines of versions that did not
+    /// record one per line.
+    session_timestamp: String,
+}
+
+impl RolloutMigrator {
+    /// Upgrades the next non-empty line of the file. Lines that have no
+    /// counterpart in the current version upgrade to no lines, and some
+    /// upgrade to several.
+    pub(crate) fn upgrade(&mut self, line: Value) -> Vec<Value> {
+        let version = match self.version {
+            Some(version) => version,
+            None => *self.version.insert(detect_version(&line)),
+        };
+        if version >= CURRENT_ROLLOUT_SCHEMA_VERSION {
+            return vec![line];
+        }
+        let mut lines = vec![line];
+        for upgrade in UPGRADES
+            .iter()
+            .skip(usize::try_from(version).unwrap_or(usize::MAX))
+        {
+            lines = lines
+                .into_iter()
+                .flat_map(|line| upgrade(self, line))
+                .collect();
+        }
+        for line in &mut lines {
+            if line["type"] == "session_meta"
+                && let Some(payload) = line.get_mut("payload").and_then(Value::as_object_mut)
+            {
+                payload.insert(
+                    "schema_version".to_string(),
+                    CURRENT_ROLLOUT_SCHEMA_VERSION.into(),
+                );
+            }
+        }
+        lines
+    }
+}
+
+fn is_tagged(line: &Value) -> bool {
+    line.get("type").is_some() && line.get("payload").is_some()
+}
+
+fn detect_version(first_line: &Value) -> u32 {
+    if !is_tagged(first_line) {
+        return 0;
+    }
+    first_line["payload"]["schema_version"]
+        .as_u64()
+        .map_or(1, |version| u32::try_from(version).unwrap_or(u32::MAX))
+}
+
+fn upgrade_v0_line(migrator: &mut RolloutMigrator, line: Value) -> Vec<Value> {
+    // A newer Codex that resumed the file appended tagged lines to it.
+    if is_tagged(&line) {
+        return vec![line];
+    }
+    let Value::Object(mut item) = line else {
+        return Vec::new();
+    };
+    if item.contains_key("record_type") {
+        return Vec::new();
+    }
+    if item.contains_key("type") {
+        return vec![json!({
+            "timestamp": migrator.session_timestamp,
+            "type": "response_item",
+            "payload": item,
+        })];
+    }
+
+    // The session header. Its `instructions` were the user instructions,
+    // which later versions record as a message in the history instead.
+    let Some(id) = item.remove("id") else {
+        return Vec::new();
+    };
+    let timestamp = item
+        .get("timestamp")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    migrator.session_timestamp = timestamp.clone();
+    let mut payload = json!({
+        "id": id,
+        "timestamp": timestamp,
+        "cwd": "",
+        "originator": "",
+        "cli_version": "",
+    });
+    if let Some(git) = item.remove("git").filter(|git| !git.is_null()) {
+        payload["git"] = git;
+    }
+    let mut lines = vec![json!({
+        "timestamp": timestamp,
+        "type": "session_meta",
+        "payload": payload,
+    })];
+    if let Some(instructions) = item
+        .get("instructions")
+        .and_then(Value::as_str)
+        .filter(|instructions| !instructions.trim().is_empty())
+    {
+        // The layout user instructions had in the history at the time.
+        lines.push(json!({
+            "timestamp": timestamp,
+            "type": "response_item",
+            "payload": {
+                "type": "message",
+                "role": "user",
+                "content": [{
+                    "type": "input_text",
+                    "text": format!(
+                        "{USER_INSTRUCTIONS_OPEN_TAG_LEGACY}\n\n{instructions}\n\n</user_instructions>"
+                    ),
+                }],
+            },
+        }));
+    }
+    lines
+}
+
+/// Upgrades the rollout `contents` to the current version, or borrows them
+/// if they already are. Lines an upgrade leaves alone keep their exact text,
+/// as do lines that are not JSON.
+pub(crate) fn migrate_contents(contents: &str) -> Cow<'_, str> {
+    let lines = contents.lines().filter(|raw| !raw.trim().is_empty());
+    let is_current = lines
+        .clone()
+        .next()
+        .and_then(|first| serde_json::from_str::<Value>(first).ok())
+        .is_none_or(|first| detect_version(&first) >= CURRENT_ROLLOUT_SCHEMA_VERSION);
+    if is_current {
+        return Cow::Borrowed(contents);
+    }
+
+    let mut migrator = RolloutMigrator::default();
+    let mut migrated = String::with_capacity(contents.len());
+    for raw in lines {
+        let Ok(line) = serde_json::from_str::<Value>(raw) else {
+            migrated.push_str(raw);
+            migrated.push('\n');
+            continue;
+        };
+        let upgraded = migrator.upgrade(line.clone());
+        if upgraded == [line] {
+            migrated.push_str(raw);
+            migrated.push('\n');
+            continue;
+        }
+        for upgraded in upgraded {
+            migrated.push_str(&upgraded.to_string());
+            migrated.push('\n');
+        }
+    }
+    Cow::Owned(migrated)
+}
+
+/// Rewrites the rollout at `path` in the current format. Returns whether it
+/// had to be upgraded. The rollout must not be recorded by a live session
+/// while it is migrated.
+pub async fn migrate_rollout_file(path: &Path) -> io::Result<bool> {
+    let contents = tokio::fs::read_to_string(path).await?;
+    let Cow::Owned(migrated) = migrate_contents(&contents) else {
+        return Ok(false);
+    };
+    let tmp_path = path.with_extension("jsonl.tmp");
+    tokio::fs::write(&tmp_path, migrated).await?;
+    tokio::fs::rename(&tmp_path, path).await?;
+    Ok(true)
+}
+
+#[derive(Debug, Default)]
+pub struct RolloutMigrationReport {
+    /// Rollouts that were rewritten in the current format.
+    pub migrated: Vec<PathBuf>,
+    /// Number of rollouts that already were in the current format.
+    pub up_to_date: usize,
+    /// Rollouts that could not be migrated, with the error.
+    pub failed: Vec<(PathBuf, io::Error)>,
+}
+
+/// Migrates every active and archived rollout under `codex_home`. Must not
+/// run while sessions are recorded.
+pub async fn migrate_rollouts(codex_home: &Path) -> io::Result<RolloutMigrationReport> {
+    let mut report = RolloutMigrationReport::default();
+    let mut dirs = vec![
+        codex_home.join(SESSIONS_SUBDIR),
+        codex_home.join(ARCHIVED_SESSIONS_SUBDIR),
+    ];
+    while let Some(dir) = dirs.pop() {
+        let mut entries = match tokio::fs::read_dir(&dir).await {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err),
+        };
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if entry.file_type().await?.is_dir() {
+                dirs.push(path);
+                continue;
+            }
+            let is_rollout = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("rollout-") && name.ends_with(".jsonl"));
+            if !is_rollout {
+                continue;
+            }
+            match migrate_rollout_file(&path).await {
+                Ok(true) => report.migrated.push(path),
+                Ok(false) => report.up_to_date += 1,
+                Err(err) => report.failed.push((path, err)),
+            }
+        }
+    }
+    report.migrated.sort();
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RolloutRecorder;
+    use crate::rollout::list::read_session_meta_line;
+    use codex_protocol::models::ResponseItem;
+    use codex_protocol::protocol::RolloutItem;
+    use codex_protocol::protocol::RolloutLine;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    const SESSION_ID: &str = "5973b6c0-94b8-487b-a530-2aeb6098ae0e";
+
+    /// A rollout of every version, in the oldest layout that version allowed.
+    fn fixtures() -> Vec<(u32, String)> {
+        let v0 = [
+            json!({
+                "id": SESSION_ID,
+                "timestamp": "2025-05-07T17:24:21.123Z",
+                "instructions": null,
+                "git": { "commit_hash": "abc123", "branch": "main" },
+            }),
+            json!({ "record_type": "state" }),
+            json!({
+                "type": "message",
+                "role": "user",
+                "content": [{ "type": "input_text", "text": "hello" }],
+            }),
+            json!({
+                "type": "message",
+                "role": "assistant",
+                "content": [{ "type": "output_text", "text": "hi" }],
+            }),
+        ];
+        let v1 = [
+            json!({
+                "timestamp": "2025-09-05T16:53:11.850Z",
+                "type": "session_meta",
+                "payload": {
+                    "id": SESSION_ID,
+                    "timestamp": "2025-09-05T16:53:11.850Z",
+                    "cwd": "/repo",
+                    "originator": "codex_cli_rs",
+                    "cli_version": "0.30.0",
+                    "model_provider": "openai",
+                },
+            }),
+            json!({
+                "timestamp": "2025-09-05T16:53:12.000Z",
+                "type": "response_item",
+                "payload": {
+                    "type": "message",
+                    "role": "user",
+                    "content": [{ "type": "input_text", "text": "hello" }],
+                },
+            }),
+            json!({
+                "timestamp": "2025-09-05T16:53:13.000Z",
+                "type": "response_item",
+                "payload": {
+                    "type": "message",
+                    "role": "assistant",
+                    "content": [{ "type": "output_text", "text": "hi" }],
+                },
+            }),
+        ];
+        let to_contents = |lines: &[Value]| {
+            lines
+                .iter()
+                .map(|line| format!("{line}\n"))
+                .collect::<String>()
+        };
+        assert_eq!(CURRENT_ROLLOUT_SCHEMA_VERSION, 1, "add a fixture");
+        vec![(0, to_contents(&v0)), (1, to_contents(&v1))]
+    }
+
+    fn parse_lines(contents: &str) -> Vec<RolloutLine> {
+        contents
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("current rollout line"))
+            .collect()
+    }
+
+    fn message_texts(items: &[RolloutItem]) -> Vec<String> {
+        items
+            .iter()
+            .filter_map(|item| match item {
+                RolloutItem::ResponseItem(ResponseItem::Message { content, .. }) => {
+                    crate::compact::content_items_to_text(content)
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn every_version_upgrades_to_current_lines() {
+        for (version, contents) in fixtures() {
+            let migrated = migrate_contents(&contents);
+            let lines = parse_lines(&migrated);
+            let RolloutItem::SessionMeta(meta_line) = &lines[0].item else {
+                panic!("version {version} does not start with session meta");
+            };
+            assert_eq!(meta_line.meta.id.to_string(), SESSION_ID);
+            let items: Vec<RolloutItem> = lines.into_iter().map(|line| line.item).collect();
+            assert_eq!(
+                message_texts(&items),
+                vec!["hello".to_string(), "hi".to_string()],
+                "version {version}"
+            );
+
+            // Upgraded contents are current and are left alone from then on.
+            assert!(matches!(migrate_contents(&migrated), Cow::Borrowed(_)));
+            if version == CURRENT_ROLLOUT_SCHEMA_VERSION {
+                assert!(matches!(migrated, Cow::Borrowed(_)));
+            } else {
+                assert_eq!(
+                    meta_line.schema_version,
+                    Some(CURRENT_ROLLOUT_SCHEMA_VERSION)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn v0_keeps_git_and_lines_appended_after_resume() {
+        let (_, mut contents) = fixtures().remove(0);
+        contents.push_str(
+            "{\"timestamp\":\"2025-10-01T00:00:00.000Z\",\"type\":\"response_item\",\"payload\":{\"type\":\"message\",\"role\":\"user\",\"content\":[{\"type\":\"input_text\",\"text\":\"resumed\"}]}}\nnot json\n",
+        );
+
+        let migrated = migrate_contents(&contents);
+        assert!(migrated.ends_with("\"text\":\"resumed\"}]}}\nnot json\n"));
+        let lines = parse_lines(migrated.trim_end_matches("not json\n"));
+        let RolloutItem::SessionMeta(meta_line) = &lines[0].item else {
+            panic!("expected session meta");
+        };
+        assert_eq!(
+            meta_line.git.as_ref().and_then(|git| git.branch.as_deref()),
+            Some("main")
+        );
+        assert_eq!(lines[1].timestamp, "2025-05-07T17:24:21.123Z");
+        let items: Vec<RolloutItem> = lines.into_iter().map(|line| line.item).collect();
+        assert_eq!(
+            message_texts(&items),
+            vec!["hello".to_string(), "hi".to_string(), "resumed".to_string()]
+        );
+    }
+
+    #[test]
+    fn v0_instructions_become_a_user_instructions_message() {
+        let (_, contents) = fixtures().remove(0);
+        let contents = contents.replacen(
+            "\"instructions\":null",
+            "\"instructions\":\"Always run the tests.\"",
+            1,
+        );
+
+        let migrated = migrate_contents(&contents);
+        let lines = parse_lines(&migrated);
+        assert!(matches!(lines[0].item, RolloutItem::SessionMeta(_)));
+        assert_eq!(lines[1].timestamp, "2025-05-07T17:24:21.123Z");
+        let items: Vec<RolloutItem> = lines.into_iter().map(|line| line.item).collect();
+        assert_eq!(
+            message_texts(&items),
+            vec![
+                "<user_instructions>\n\nAlways run the tests.\n\n</user_instructions>".to_string(),
+                "hello".to_string(),
+                "hi".to_string(),
+            ]
+        );
+        let RolloutItem::ResponseItem(ResponseItem::Message { content, .. }) = &items[1] else {
+            panic!("expected the instructions message");
+        };
+        assert!(crate::instructions::UserInstructions::is_user_instructions(
+            content
+        ));
+    }
+
+    #[tokio::test]
+    async fn old_rollouts_resume_and_list_before_and_after_bulk_migration() -> io::Result<()> {
+        let home = TempDir::new()?;
+        let day_dir = home.path().join(SESSIONS_SUBDIR).join("2025/05/07");
+        tokio::fs::create_dir_all(&day_dir).await?;
+        let mut paths = Vec::new();
+        for (version, contents) in fixtures() {
+            let path = day_dir.join(format!(
+                "rollout-2025-05-07T17-24-2{version}-{SESSION_ID}.jsonl"
+            ));
+            tokio::fs::write(&path, contents).await?;
+            paths.push(path);
+        }
+
+        let read_all = |paths: Vec<PathBuf>| async move {
+            let mut read = Vec::new();
+            for path in paths {
+                let history = RolloutRecorder::get_rollout_history(&path).await?;
+                let meta_line = read_session_meta_line(&path).await?;
+                read.push((
+                    message_texts(&history.get_rollout_items()),
+                    meta_line.meta.id.to_string(),
+                ));
+            }
+            io::Result::Ok(read)
+        };
+        let before = read_all(paths.clone()).await?;
+        assert_eq!(
+            before,
+            vec![
+                (
+                    vec!["hello".to_string(), "hi".to_string()],
+                    SESSION_ID.to_string()
+                );
+                2
+            ]
+        );
+
+        let report = migrate_rollouts(home.path()).await?;
+        assert_eq!(report.migrated, vec![paths[0].clone()]);
+        assert_eq!(report.up_to_date, 1);
+        assert!(report.failed.is_empty());
+        assert_eq!(read_all(paths.clone()).await?, before);
+
+        let again = migrate_rollouts(home.path()).await?;
+        assert_eq!((again.migrated.len(), again.up_to_date), (0, 2));
+        Ok(())
+    }
+}
@@ -8,18 +8,40 @@ pub const INTERACTIVE_SESSION_SOURCES: &[SessionSource] =
     &[SessionSource::Cli, SessionSource::VSCode];
 
 pub(crate) mod error;
+pub mod export;
+pub mod import;
+pub(crate) mod item_ids;
 pub mod list;
+pub mod metadata;
+pub mod migrate;
 pub(crate) mod policy;
 pub mod recorder;
+pub mod search;
+pub(crate) mod trim;
 pub(crate) mod truncation;
 
 pub use codex_protocol::protocol::SessionMeta;
 pub(crate) use error::map_session_init_error;
+pub use export::ROLLOUT_EXPORT_SCHEMA_VERSION;
+pub use export::RolloutExport;
+pub use export::export_rollout;
+pub use export::export_rollout_file;
+pub use list::find_archived_thread_path_by_id_str;
 pub use list::find_thread_path_by_id_str;
 #[deprecated(note = "use find_thread_path_by_id_str")]
 pub use list::find_thread_path_by_id_str as find_conversation_path_by_id_str;
+pub use metadata::SessionMetadataUpdate;
+pub use metadata::update_session_metadata;
+pub use migrate::CURRENT_ROLLOUT_SCHEMA_VERSION;
+pub use migrate::RolloutMigrationReport;
+pub use migrate::migrate_rollout_file;
+pub use migrate::migrate_rollouts;
 pub use recorder::RolloutRecorder;
 pub use recorder::RolloutRecorderParams;
+pub use search::SearchFilters;
+pub use search::SearchHit;
+pub use search::SearchResults;
+pub use search::search_conversations;
 
 #[cfg(test)]
 pub mod tests;
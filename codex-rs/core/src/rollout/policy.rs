@@ -27,6 +27,8 @@ pub(crate) fn should_persist_response_item(item: &ResponseItem) -> bool {
         | ResponseItem::CustomToolCall { .. }
         | ResponseItem::CustomToolCallOutput { .. }
         | ResponseItem::WebSearchCall { .. }
+        | ResponseItem::CodeInterpreterCall { .. }
+        | ResponseItem::FileSearchCall { .. }
         | ResponseItem::GhostSnapshot { .. }
         | ResponseItem::Compaction { .. } => true,
         ResponseItem::Other => false,
@@ -46,11 +48,29 @@ pub(crate) fn should_persist_event_msg(ev: &EventMsg) -> bool {
         | EventMsg::EnteredReviewMode(_)
         | EventMsg::ExitedReviewMode(_)
         | EventMsg::ThreadRolledBack(_)
+        | EventMsg::TurnsTrimmed(_)
         | EventMsg::UndoCompleted(_)
-        | EventMsg::TurnAborted(_) => true,
+        | EventMsg::ToolArtifact(_)
+        | EventMsg::ClipStored(_)
+        | EventMsg::FeatureOverridden(_)
+        | EventMsg::ReplSnippet(_)
+        | EventMsg::ExplorationFindings(_)
+        | EventMsg::ExplorationFindingsCleared
+        | EventMsg::KnowledgeInjected(_)
+        | EventMsg::ConversationMerged(_)
+        | EventMsg::ResumeContext(_)
+        | EventMsg::ModelSwitch(_)
+        | EventMsg::TurnAborted(_)
+        | EventMsg::TurnInterrupted(_)
+        // Marks where each turn starts for rollout readers; not replayed on
+        // resume.
+        | EventMsg::TurnStarted(_)
+        // Only each turn's final diff: `Session::send_event_raw` does not
+        // persist the cumulative diffs sent while the turn runs.
+        | EventMsg::TurnDiff(_) => true,
         EventMsg::Error(_)
         | EventMsg::Warning(_)
-        | EventMsg::TurnStarted(_)
+        | EventMsg::Heartbeat(_)
         | EventMsg::TurnComplete(_)
         | EventMsg::AgentMessageDelta(_)
         | EventMsg::AgentReasoningDelta(_)
@@ -62,6 +82,7 @@ pub(crate) fn should_persist_event_msg(ev: &EventMsg) -> bool {
         | EventMsg::McpToolCallEnd(_)
         | EventMsg::WebSearchBegin(_)
         | EventMsg::WebSearchEnd(_)
+        | EventMsg::ProviderToolCallEnd(_)
         | EventMsg::ExecCommandBegin(_)
         | EventMsg::TerminalInteraction(_)
         | EventMsg::ExecCommandOutputDelta(_)
@@ -74,7 +95,6 @@ pub(crate) fn should_persist_event_msg(ev: &EventMsg) -> bool {
         | EventMsg::StreamError(_)
         | EventMsg::PatchApplyBegin(_)
         | EventMsg::PatchApplyEnd(_)
-        | EventMsg::TurnDiff(_)
         | EventMsg::GetHistoryEntryResponse(_)
         | EventMsg::UndoStarted(_)
         | EventMsg::McpListToolsResponse(_)
@@ -82,6 +102,11 @@ pub(crate) fn should_persist_event_msg(ev: &EventMsg) -> bool {
         | EventMsg::McpStartupComplete(_)
         | EventMsg::ListCustomPromptsResponse(_)
         | EventMsg::ListSkillsResponse(_)
+        | EventMsg::SandboxPolicyReport(_)
+        | EventMsg::ProviderRateLimits(_)
+        | EventMsg::TokenForecast(_)
+        | EventMsg::ProviderEndpoint(_)
+        | EventMsg::SecretsMasked(_)
         | EventMsg::PlanUpdate(_)
         | EventMsg::ShutdownComplete
         | EventMsg::ViewImageToolCall(_)
@@ -3,8 +3,10 @@
 use std::fs::File;
 use std::fs::{self};
 use std::io::Error as IoError;
+use std::ops::Range;
 use std::path::Path;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use codex_protocol::ThreadId;
 use codex_protocol::models::BaseInstructions;
@@ -12,15 +14,19 @@ use serde_json::Value;
 use time::OffsetDateTime;
 use time::format_description::FormatItem;
 use time::macros::format_description;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncSeekExt;
 use tokio::io::AsyncWriteExt;
 use tokio::sync::mpsc::Sender;
 use tokio::sync::mpsc::{self};
 use tokio::sync::oneshot;
+use tokio::time::Instant;
 use tracing::info;
 use tracing::warn;
 
 use super::ARCHIVED_SESSIONS_SUBDIR;
 use super::SESSIONS_SUBDIR;
+use super::item_ids::backfill_legacy_item_ids;
 use super::list::Cursor;
 use super::list::ThreadListConfig;
 use super::list::ThreadListLayout;
@@ -28,9 +34,18 @@ use super::list::ThreadSortKey;
 use super::list::ThreadsPage;
 use super::list::get_threads;
 use super::list::get_threads_in_root;
+use super::metadata::SessionMetadataUpdate;
+use super::metadata::rewrite_session_meta;
+use super::migrate::CURRENT_ROLLOUT_SCHEMA_VERSION;
+use super::migrate::RolloutMigrator;
+use super::migrate::migrate_rollout_file;
 use super::policy::is_persisted_response_item;
+use super::trim::trim_rollout_contents;
 use crate::config::Config;
+use crate::config::types::Rollout;
+use crate::config::types::RolloutFsync;
 use crate::default_client::originator;
+use crate::environment_snapshot::EnvironmentSnapshotSettings;
 use crate::git_info::collect_git_info;
 use crate::path_utils;
 use codex_protocol::protocol::InitialHistory;
@@ -41,8 +56,12 @@ use codex_protocol::protocol::SessionMeta;
 use codex_protocol::protocol::SessionMetaLine;
 use codex_protocol::protocol::SessionSource;
 
-/// Records all [`ResponseItem`]s for a session and flushes them to disk after
-/// every update.
+/// Records all [`ResponseItem`]s for a session and writes them to disk as soon
+/// as the writer task catches up.
+///
+/// Lines queued while a write is in progress are committed together with a
+/// single write (group commit). When they are also fsynced is set by
+/// `[rollout] fsync` in the config, see [`RolloutFsync`].
 ///
 /// Rollouts are recorded as JSONL and can be inspected with tools such as:
 ///
@@ -71,10 +90,16 @@ pub enum RolloutRecorderParams {
 
 enum RolloutCmd {
     AddItems(Vec<RolloutItem>),
-    /// Ensure all prior writes are processed; respond when flushed.
+    /// Ensure all prior writes are processed; respond when flushed. Marks the
+    /// end of a turn for [`RolloutFsync::OnTurnEnd`].
     Flush {
         ack: oneshot::Sender<()>,
     },
+    /// Rewrite the session meta line with updated user metadata.
+    UpdateSessionMetadata {
+        update: SessionMetadataUpdate,
+        ack: oneshot::Sender<std::io::Result<SessionMeta>>,
+    },
     Shutdown {
         ack: oneshot::Sender<()>,
     },
@@ -186,20 +211,36 @@ impl RolloutRecorder {
     /// Attempt to create a new [`RolloutRecorder`]. If the sessions directory
     /// cannot be created or the rollout file cannot be opened we return the
     /// error so the caller can decide whether to disable persistence.
+    ///
+    /// With `config.incognito` nothing is written to disk: the rollout is kept
+    /// in memory and dropped with the recorder, and a new session gets an empty
+    /// `rollout_path`.
     pub async fn new(config: &Config, params: RolloutRecorderParams) -> std::io::Result<Self> {
-        let (file, rollout_path, meta) = match params {
+        let (sink, rollout_path, meta) = match params {
             RolloutRecorderParams::Create {
                 conversation_id,
                 forked_from_id,
                 source,
                 base_instructions,
             } => {
-                let LogFileInfo {
-                    file,
-                    path,
-                    conversation_id: session_id,
-                    timestamp,
-                } = create_log_file(config, conversation_id)?;
+                let (sink, path, timestamp) = if config.incognito {
+                    (
+                        RolloutSink::Memory(String::new()),
+                        PathBuf::new(),
+                        OffsetDateTime::now_utc(),
+                    )
+                } else {
+                    let LogFileInfo {
+                        file,
+                        path,
+                        timestamp,
+                    } = create_log_file(config, conversation_id)?;
+                    (
+                        RolloutSink::File(tokio::fs::File::from_std(file)),
+                        path,
+                        timestamp,
+                    )
+                };
 
                 let timestamp_format: &[FormatItem] = format_description!(
                     "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]Z"
@@ -210,10 +251,10 @@ impl RolloutRecorder {
                     .map_err(|e| IoError::other(format!("failed to format timestamp: {e}")))?;
 
                 (
-                    tokio::fs::File::from_std(file),
+                    sink,
                     path,
                     Some(SessionMeta {
-                        id: session_id,
+                        id: conversation_id,
                         forked_from_id,
                         timestamp,
                         cwd: config.cwd.clone(),
@@ -222,21 +263,37 @@ impl RolloutRecorder {
                         source,
                         model_provider: Some(config.model_provider_id.clone()),
                         base_instructions: Some(base_instructions),
+                        title: None,
+                        tags: Vec::new(),
+                        stats: None,
                     }),
                 )
             }
-            RolloutRecorderParams::Resume { path } => (
-                tokio::fs::OpenOptions::new()
-                    .append(true)
-                    .open(&path)
-                    .await?,
-                path,
-                None,
-            ),
+            RolloutRecorderParams::Resume { path } => {
+                // An incognito resume reads the recorded history but leaves the
+                // file as it was.
+                let sink = if config.incognito {
+                    RolloutSink::Memory(String::new())
+                } else {
+                    // New lines are written in the current format, so older
+                    // files are upgraded before they are appended to.
+                    migrate_rollout_file(&path).await?;
+                    let mut file = tokio::fs::OpenOptions::new()
+                        .read(true)
+                        .append(true)
+                        .open(&path)
+                        .await?;
+                    terminate_torn_line(&mut file).await?;
+                    RolloutSink::File(file)
+                };
+                (sink, path, None)
+            }
         };
 
         // Clone the cwd for the spawned task to collect git info asynchronously
         let cwd = config.cwd.clone();
+        let environment_snapshot = EnvironmentSnapshotSettings::from_config(config);
+        let sync_policy = SyncPolicy::from(&config.rollout);
 
         // A reasonably-sized bounded channel. If the buffer fills up the send
         // future will yield, which is fine – we only need to ensure we do not
@@ -246,7 +303,15 @@ impl RolloutRecorder {
         // Spawn a Tokio task that owns the file handle and performs async
         // writes. Using `tokio::fs::File` keeps everything on the async I/O
         // driver instead of blocking the runtime.
-        tokio::task::spawn(rollout_writer(file, rx, meta, cwd));
+        tokio::task::spawn(rollout_writer(
+            sink,
+            sync_policy,
+            rx,
+            meta,
+            cwd,
+            environment_snapshot,
+            rollout_path.clone(),
+        ));
 
         Ok(Self { tx, rollout_path })
     }
@@ -270,7 +335,8 @@ impl RolloutRecorder {
             .map_err(|e| IoError::other(format!("failed to queue rollout items: {e}")))
     }
 
-    /// Flush all queued writes and wait until they are committed by the writer task.
+    /// Flush all queued writes and wait until they are committed by the writer
+    /// task. With [`RolloutFsync::OnTurnEnd`] they are fsynced as well.
     pub async fn flush(&self) -> std::io::Result<()> {
         let (tx, rx) = oneshot::channel();
         self.tx
@@ -281,6 +347,21 @@ impl RolloutRecorder {
             .map_err(|e| IoError::other(format!("failed waiting for rollout flush: {e}")))
     }
 
+    /// Update the title and/or tags stored in this rollout's session meta line.
+    pub async fn update_session_metadata(
+        &self,
+        update: SessionMetadataUpdate,
+    ) -> std::io::Result<SessionMeta> {
+        let (tx, rx) = oneshot::channel();
+        self.tx
+            .send(RolloutCmd::UpdateSessionMetadata { update, ack: tx })
+            .await
+            .map_err(|e| IoError::other(format!("failed to queue rollout metadata update: {e}")))?;
+        rx.await.map_err(|e| {
+            IoError::other(format!("failed waiting for rollout metadata update: {e}"))
+        })?
+    }
+
     pub async fn get_rollout_history(path: &Path) -> std::io::Result<InitialHistory> {
         info!("Resuming rollout from {path:?}");
         let text = tokio::fs::read_to_string(path).await?;
@@ -290,6 +371,7 @@ impl RolloutRecorder {
 
         let mut items: Vec<RolloutItem> = Vec::new();
         let mut thread_id: Option<ThreadId> = None;
+        let mut migrator = RolloutMigrator::default();
         for line in text.lines() {
             if line.trim().is_empty() {
                 continue;
@@ -301,37 +383,40 @@ impl RolloutRecorder {
                     continue;
                 }
             };
-
-            // Parse the rollout line structure
-            match serde_json::from_value::<RolloutLine>(v.clone()) {
-                Ok(rollout_line) => match rollout_line.item {
-                    RolloutItem::SessionMeta(session_meta_line) => {
-                        // Use the FIRST SessionMeta encountered in the file as the canonical
-                        // thread id and main session information. Keep all items intact.
-                        if thread_id.is_none() {
-                            thread_id = Some(session_meta_line.meta.id);
+            for v in migrator.upgrade(v) {
+                // Parse the rollout line structure
+                match serde_json::from_value::<RolloutLine>(v.clone()) {
+                    Ok(rollout_line) => match rollout_line.item {
+                        RolloutItem::SessionMeta(session_meta_line) => {
+                            // Use the FIRST SessionMeta encountered in the file as the canonical
+                            // thread id and main session information. Keep all items intact.
+                            if thread_id.is_none() {
+                                thread_id = Some(session_meta_line.meta.id);
+                            }
+                            items.push(RolloutItem::SessionMeta(session_meta_line));
                         }
-                        items.push(RolloutItem::SessionMeta(session_meta_line));
-                    }
-                    RolloutItem::ResponseItem(item) => {
-                        items.push(RolloutItem::ResponseItem(item));
-                    }
-                    RolloutItem::Compacted(item) => {
-                        items.push(RolloutItem::Compacted(item));
-                    }
-                    RolloutItem::TurnContext(item) => {
-                        items.push(RolloutItem::TurnContext(item));
-                    }
-                    RolloutItem::EventMsg(_ev) => {
-                        items.push(RolloutItem::EventMsg(_ev));
+                        RolloutItem::ResponseItem(item) => {
+                            items.push(RolloutItem::ResponseItem(item));
+                        }
+                        RolloutItem::Compacted(item) => {
+                            items.push(RolloutItem::Compacted(item));
+                        }
+                        RolloutItem::TurnContext(item) => {
+                            items.push(RolloutItem::TurnContext(item));
+                        }
+                        RolloutItem::EventMsg(_ev) => {
+                            items.push(RolloutItem::EventMsg(_ev));
+                        }
+                    },
+                    Err(e) => {
+                        warn!("failed to parse rollout line: {v:?}, error: {e}");
                     }
-                },
-                Err(e) => {
-                    warn!("failed to parse rollout line: {v:?}, error: {e}");
                 }
             }
         }
 
+        backfill_legacy_item_ids(&mut items);
+
         info!(
             "Resumed rollout with {} items, thread ID: {:?}",
             items.len(),
@@ -352,6 +437,18 @@ impl RolloutRecorder {
         }))
     }
 
+    /// Remove the user turns in `range` from the rollout at `path`, leaving a
+    /// `TurnsTrimmed` tombstone in their place. Turns are numbered from zero
+    /// and include turns that were later rolled back. The rollout must not be
+    /// recorded by a live session while it is trimmed.
+    pub async fn trim(path: &Path, range: Range<usize>) -> std::io::Result<()> {
+        let contents = tokio::fs::read_to_string(path).await?;
+        let rewritten = trim_rollout_contents(&contents, range)?;
+        let tmp_path = path.with_extension("jsonl.tmp");
+        tokio::fs::write(&tmp_path, rewritten).await?;
+        tokio::fs::rename(&tmp_path, path).await
+    }
+
     pub async fn shutdown(&self) -> std::io::Result<()> {
         let (tx_done, rx_done) = oneshot::channel();
         match self.tx.send(RolloutCmd::Shutdown { ack: tx_done }).await {
@@ -375,9 +472,6 @@ struct LogFileInfo {
     /// Full path to the rollout file.
     path: PathBuf,
 
-    /// Session ID (also embedded in filename).
-    conversation_id: ThreadId,
-
     /// Timestamp for the start of the session.
     timestamp: OffsetDateTime,
 }
@@ -412,65 +506,157 @@ fn create_log_file(config: &Config, conversation_id: ThreadId) -> std::io::Resul
     Ok(LogFileInfo {
         file,
         path,
-        conversation_id,
         timestamp,
     })
 }
 
+/// Appends a newline when the file ends in the middle of a line, as it does
+/// after a crash during a write, so lines recorded after resuming are not
+/// glued to the torn one. Readers skip the torn line itself.
+async fn terminate_torn_line(file: &mut tokio::fs::File) -> std::io::Result<()> {
+    if file.metadata().await?.len() == 0 {
+        return Ok(());
+    }
+    file.seek(std::io::SeekFrom::End(-1)).await?;
+    let mut last = [0u8; 1];
+    file.read_exact(&mut last).await?;
+    if last[0] != b'\n' {
+        // The handle is in append mode, so this lands at the end of the file.
+        file.write_all(b"\n").await?;
+        file.flush().await?;
+    }
+    Ok(())
+}
+
+/// When the writer task fsyncs, resolved from [`Rollout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SyncPolicy {
+    PerEvent,
+    Interval(Duration),
+    OnTurnEnd,
+}
+
+impl From<&Rollout> for SyncPolicy {
+    fn from(rollout: &Rollout) -> Self {
+        match rollout.fsync.unwrap_or_default() {
+            RolloutFsync::PerEvent => Self::PerEvent,
+            RolloutFsync::Interval => Self::Interval(rollout.fsync_interval()),
+            RolloutFsync::OnTurnEnd => Self::OnTurnEnd,
+        }
+    }
+}
+
 async fn rollout_writer(
-    file: tokio::fs::File,
+    sink: RolloutSink,
+    sync_policy: SyncPolicy,
     mut rx: mpsc::Receiver<RolloutCmd>,
     mut meta: Option<SessionMeta>,
     cwd: std::path::PathBuf,
+    environment_snapshot: EnvironmentSnapshotSettings,
+    rollout_path: PathBuf,
 ) -> std::io::Result<()> {
-    let mut writer = JsonlWriter { file };
+    let mut writer = JsonlWriter::new(sink, sync_policy);
 
-    // If we have a meta, collect git info asynchronously and write meta first
+    // If we have a meta, collect git and environment info asynchronously and
+    // write meta first
     if let Some(session_meta) = meta.take() {
-        let git_info = collect_git_info(&cwd).await;
+        let (git_info, environment) =
+            tokio::join!(collect_git_info(&cwd), environment_snapshot.capture(&cwd),);
         let session_meta_line = SessionMetaLine {
             meta: session_meta,
             git: git_info,
+            environment,
+            schema_version: Some(CURRENT_ROLLOUT_SCHEMA_VERSION),
         };
 
         // Write the SessionMeta as the first item in the file, wrapped in a rollout line
         writer
             .write_rollout_item(RolloutItem::SessionMeta(session_meta_line))
             .await?;
+        writer.commit().await?;
     }
 
     // Process rollout commands
-    while let Some(cmd) = rx.recv().await {
-        match cmd {
-            RolloutCmd::AddItems(items) => {
-                for item in items {
-                    if is_persisted_response_item(&item) {
-                        writer.write_rollout_item(item).await?;
+    loop {
+        let next = match writer.sync_deadline() {
+            Some(deadline) => tokio::select! {
+                cmd = rx.recv() => cmd,
+                _ = tokio::time::sleep_until(deadline) => {
+                    writer.sync().await?;
+                    continue;
+                }
+            },
+            None => rx.recv().await,
+        };
+        let Some(cmd) = next else {
+            break;
+        };
+
+        // Group commit: take everything that queued up while the last batch
+        // was written and commit it with a single write.
+        let mut next = Some(cmd);
+        while let Some(cmd) = next {
+            match cmd {
+                RolloutCmd::AddItems(items) => {
+                    for item in items {
+                        if is_persisted_response_item(&item) {
+                            writer.write_rollout_item(item).await?;
+                        }
                     }
                 }
-            }
-            RolloutCmd::Flush { ack } => {
-                // Ensure underlying file is flushed and then ack.
-                if let Err(e) = writer.file.flush().await {
+                RolloutCmd::Flush { ack } => {
+                    // Ensure underlying file is flushed and then ack.
+                    if let Err(e) = writer.end_turn().await {
+                        let _ = ack.send(());
+                        return Err(e);
+                    }
                     let _ = ack.send(());
-                    return Err(e);
                 }
-                let _ = ack.send(());
-            }
-            RolloutCmd::Shutdown { ack } => {
-                let _ = ack.send(());
+                RolloutCmd::UpdateSessionMetadata { update, ack } => {
+                    let _ = ack.send(writer.rewrite_session_meta(&rollout_path, update).await);
+                }
+                RolloutCmd::Shutdown { ack } => {
+                    let result = writer.commit_and_sync().await;
+                    let _ = ack.send(());
+                    result?;
+                }
             }
+            next = rx.try_recv().ok();
         }
+        writer.commit().await?;
     }
 
-    Ok(())
+    writer.commit_and_sync().await
+}
+
+/// Where rollout lines go.
+enum RolloutSink {
+    File(tokio::fs::File),
+    /// Lines of an incognito session, which never reach the disk.
+    Memory(String),
 }
 
 struct JsonlWriter {
-    file: tokio::fs::File,
+    sink: RolloutSink,
+    sync_policy: SyncPolicy,
+    /// Lines serialized since the last commit.
+    pending: String,
+    /// Whether committed lines may not have been fsynced yet.
+    unsynced: bool,
+    last_sync: Instant,
 }
 
 impl JsonlWriter {
+    fn new(sink: RolloutSink, sync_policy: SyncPolicy) -> Self {
+        Self {
+            sink,
+            sync_policy,
+            pending: String::new(),
+            unsynced: false,
+            last_sync: Instant::now(),
+        }
+    }
+
     async fn write_rollout_item(&mut self, rollout_item: RolloutItem) -> std::io::Result<()> {
         let timestamp_format: &[FormatItem] = format_description!(
             "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]Z"
@@ -483,15 +669,109 @@ impl JsonlWriter {
             timestamp,
             item: rollout_item,
         };
-        self.write_line(&line).await
+        self.write_line(&line)
+    }
+    async fn rewrite_session_meta(
+        &mut self,
+        path: &Path,
+        update: SessionMetadataUpdate,
+    ) -> std::io::Result<SessionMeta> {
+        self.commit().await?;
+        match &mut self.sink {
+            RolloutSink::File(file) => {
+                let contents = tokio::fs::read_to_string(path).await?;
+                let (rewritten, meta) = rewrite_session_meta(&contents, update)?;
+                // Replace the file atomically so a crash or a full disk leaves
+                // either the old or the new rollout, never a truncated one.
+                let tmp_path = path.with_extension("jsonl.tmp");
+                let mut tmp = tokio::fs::File::create(&tmp_path).await?;
+                tmp.write_all(rewritten.as_bytes()).await?;
+                tmp.sync_all().await?;
+                drop(tmp);
+                tokio::fs::rename(&tmp_path, path).await?;
+                // Later writes go to the renamed file, not the replaced one.
+                *file = tokio::fs::OpenOptions::new()
+                    .append(true)
+                    .open(path)
+                    .await?;
+                self.unsynced = false;
+                Ok(meta)
+            }
+            RolloutSink::Memory(contents) => {
+                let (rewritten, meta) = rewrite_session_meta(contents, update)?;
+                *contents = rewritten;
+                Ok(meta)
+            }
+        }
     }
-    async fn write_line(&mut self, item: &impl serde::Serialize) -> std::io::Result<()> {
+
+    fn write_line(&mut self, item: &impl serde::Serialize) -> std::io::Result<()> {
         let mut json = serde_json::to_string(item)?;
         json.push('\n');
-        self.file.write_all(json.as_bytes()).await?;
-        self.file.flush().await?;
+        match &mut self.sink {
+            RolloutSink::File(_) => self.pending.push_str(&json),
+            RolloutSink::Memory(contents) => contents.push_str(&json),
+        }
         Ok(())
     }
+
+    /// Writes the pending lines to the file and fsyncs if the policy asks for
+    /// it after every commit or the interval has elapsed.
+    async fn commit(&mut self) -> std::io::Result<()> {
+        let RolloutSink::File(file) = &mut self.sink else {
+            return Ok(());
+        };
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        // Lines are whole in `pending`, so a crash can at worst tear the
+        // last line of this batch; `terminate_torn_line` repairs that on
+        // resume.
+        file.write_all(self.pending.as_bytes()).await?;
+        file.flush().await?;
+        self.pending.clear();
+        self.unsynced = true;
+
+        match self.sync_policy {
+            SyncPolicy::PerEvent => self.sync().await,
+            SyncPolicy::Interval(interval) if self.last_sync.elapsed() >= interval => {
+                self.sync().await
+            }
+            SyncPolicy::Interval(_) | SyncPolicy::OnTurnEnd => Ok(()),
+        }
+    }
+
+    async fn end_turn(&mut self) -> std::io::Result<()> {
+        self.commit().await?;
+        if self.sync_policy == SyncPolicy::OnTurnEnd {
+            self.sync().await?;
+        }
+        Ok(())
+    }
+
+    async fn commit_and_sync(&mut self) -> std::io::Result<()> {
+        self.commit().await?;
+        self.sync().await
+    }
+
+    async fn sync(&mut self) -> std::io::Result<()> {
+        if let RolloutSink::File(file) = &mut self.sink
+            && self.unsynced
+        {
+            file.sync_data().await?;
+        }
+        self.unsynced = false;
+        self.last_sync = Instant::now();
+        Ok(())
+    }
+
+    /// When the interval policy next owes an fsync for committed lines.
+    fn sync_deadline(&self) -> Option<Instant> {
+        match self.sync_policy {
+            SyncPolicy::Interval(interval) if self.unsynced => Some(self.last_sync + interval),
+            _ => None,
+        }
+    }
 }
 
 fn select_resume_path(page: &ThreadsPage, filter_cwd: Option<&Path>) -> Option<PathBuf> {
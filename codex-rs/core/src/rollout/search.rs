@@ -0,0 +1,528 @@
+//! Full-text search over the transcripts of saved rollouts.
+//!
+//! [`search_conversations`] looks for a query in the messages the user sent
+//! and the replies the model gave, as recorded by the `user_message` and
+//! `agent_message` events of each rollout under `~/.codex/sessions`. Every
+//! hit carries the line and byte offset of the event in its rollout file so
+//! the resume picker can open the session at the matching message.
+//!
+//! Today every search reads the rollout files. A persistent index can plug in
+//! through [`RolloutSearchIndex`]: it narrows the files to read, and the scan
+//! still confirms every hit, so a stale index can miss new sessions but never
+//! report a message that is not there.
+
+use std::cmp::Reverse;
+use std::io;
+use std::ops::ControlFlow;
+use std::path::Path;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use codex_protocol::ThreadId;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use time::Date;
+use time::OffsetDateTime;
+use time::UtcOffset;
+use uuid::Uuid;
+
+use super::SESSIONS_SUBDIR;
+use super::list::MAX_SCAN_FILES;
+use super::list::RolloutFileVisitor;
+use super::list::parse_timestamp_uuid_from_filename;
+use super::list::walk_rollout_files;
+use super::migrate::RolloutMigrator;
+
+/// Longest snippet returned with a hit, in characters.
+const SNIPPET_MAX_CHARS: usize = 160;
+
+/// Restricts which sessions [`search_conversations`] looks at. The default
+/// searches every session.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchFilters {
+    /// Only sessions started at or after this time.
+    pub since: Option<OffsetDateTime>,
+    /// Only sessions started at or before this time.
+    pub until: Option<OffsetDateTime>,
+    /// Only sessions whose working directory is this directory or inside it.
+    pub cwd: Option<PathBuf>,
+    /// Stop after this many hits.
+    pub max_hits: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchHitRole {
+    User,
+    Assistant,
+}
+
+/// Result of a search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResults {
+    pub hits: Vec<SearchHit>,
+    /// True when the scan stopped at the cap on rollout files read per
+    /// search, so older sessions were not searched; narrow the date range to
+    /// reach them.
+    pub reached_scan_cap: bool,
+}
+
+/// A message that contains the query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    pub path: PathBuf,
+    /// `None` when the rollout has no session meta line.
+    pub thread_id: Option<ThreadId>,
+    pub cwd: Option<PathBuf>,
+    /// Start of the session, from the rollout's file name.
+    pub session_started_at: OffsetDateTime,
+    /// 1-based line of the message in the rollout file.
+    pub line: usize,
+    /// Byte offset of the start of that line.
+    pub byte_offset: u64,
+    /// Timestamp of the line.
+    pub timestamp: String,
+    pub role: SearchHitRole,
+    /// The line of the message that matched, shortened to
+    /// `SNIPPET_MAX_CHARS`.
+    pub snippet: String,
+}
+
+/// An on-disk index of rollout contents that [`search_conversations_with_index`]
+/// can consult instead of reading every rollout.
+#[async_trait]
+pub trait RolloutSearchIndex: Send + Sync {
+    /// Rollout files that may contain `query`, or `None` when the index
+    /// cannot answer (e.g. it is not built yet) and every file must be read.
+    async fn candidate_files(
+        &self,
+        codex_home: &Path,
+        query: &str,
+    ) -> io::Result<Option<Vec<PathBuf>>>;
+}
+
+/// Searches the user and assistant messages of the saved rollouts for
+/// `query`, ignoring case. Hits are ordered newest session first, then by
+/// line within a session.
+pub async fn search_conversations(
+    codex_home: &Path,
+    query: &str,
+    filters: &SearchFilters,
+) -> io::Result<SearchResults> {
+    search_conversations_with_index(codex_home, query, filters, None).await
+}
+
+/// Like [`search_conversations`], reading only the files `index` proposes
+/// when it has an answer.
+pub async fn search_conversations_with_index(
+    codex_home: &Path,
+    query: &str,
+    filters: &SearchFilters,
+    index: Option<&dyn RolloutSearchIndex>,
+) -> io::Result<SearchResults> {
+    let mut results = SearchResults {
+        hits: Vec::new(),
+        reached_scan_cap: false,
+    };
+    let needle = query.trim().to_lowercase();
+    if needle.is_empty() {
+        return Ok(results);
+    }
+    let mut visitor = SearchVisitor {
+        needle,
+        filters,
+        hits: Vec::new(),
+    };
+
+    let candidates = match index {
+        Some(index) => index.candidate_files(codex_home, query).await?,
+        None => None,
+    };
+    match candidates {
+        Some(paths) => {
+            let mut files: Vec<(OffsetDateTime, Uuid, PathBuf)> = paths
+                .into_iter()
+                .filter_map(|path| {
+                    let name = path.file_name()?.to_str()?;
+                    let (ts, id) = parse_timestamp_uuid_from_filename(name)?;
+                    Some((ts, id, path))
+                })
+                .collect();
+            files.sort_by_key(|(ts, id, _path)| (Reverse(*ts), Reverse(*id)));
+            for (scanned, (ts, id, path)) in files.into_iter().enumerate() {
+                if let ControlFlow::Break(()) = visitor.visit(ts, id, path, scanned + 1).await {
+                    break;
+                }
+            }
+        }
+        None => {
+            let root = codex_home.join(SESSIONS_SUBDIR);
+            if !root.exists() {
+                return Ok(results);
+            }
+            let mut scanned_files = 0usize;
+            walk_rollout_files(&root, &mut scanned_files, &mut visitor).await?;
+            results.reached_scan_cap = scanned_files >= MAX_SCAN_FILES;
+        }
+    }
+
+    results.hits = visitor.hits;
+    Ok(results)
+}
+
+struct SearchVisitor<'a> {
+    /// The query, lowercased.
+    needle: String,
+    filters: &'a SearchFilters,
+    hits: Vec<SearchHit>,
+}
+
+#[async_trait]
+impl<'a> RolloutFileVisitor for SearchVisitor<'a> {
+    async fn visit(
+        &mut self,
+        ts: OffsetDateTime,
+        _id: Uuid,
+        path: PathBuf,
+        _scanned: usize,
+    ) -> ControlFlow<()> {
+        if let Some(until) = self.filters.until
+            && ts > until
+        {
+            return ControlFlow::Continue(());
+        }
+        // Files come newest first, so every remaining one is older too.
+        if let Some(since) = self.filters.since
+            && ts < since
+        {
+            return ControlFlow::Break(());
+        }
+        let Ok(contents) = tokio::fs::read_to_string(&path).await else {
+            return ControlFlow::Continue(());
+        };
+        let hits = search_rollout_contents(&path, ts, &contents, &self.needle);
+        // Every hit of a file carries the session's cwd.
+        if let Some(cwd) = &self.filters.cwd
+            && let Some(hit) = hits.first()
+            && !hit.cwd.as_deref().is_some_and(|dir| dir.starts_with(cwd))
+        {
+            return ControlFlow::Continue(());
+        }
+        for hit in hits {
+            self.hits.push(hit);
+            if self
+                .filters
+                .max_hits
+                .is_some_and(|max_hits| self.hits.len() >= max_hits)
+            {
+                return ControlFlow::Break(());
+            }
+        }
+        ControlFlow::Continue(())
+    }
+
+    /// Day directories and the times in rollout file names share a clock, so
+    /// a day after `until`'s date only holds sessions started after it.
+    fn skip_day(&self, date: Date) -> bool {
+        self.filters
+            .until
+            .is_some_and(|until| date > until.to_offset(UtcOffset::UTC).date())
+    }
+}
+
+/// Finds the messages of one rollout that contain `needle`, which must be
+/// lowercase.
+fn search_rollout_contents(
+    path: &Path,
+    session_started_at: OffsetDateTime,
+    contents: &str,
+    needle: &str,
+) -> Vec<SearchHit> {
+    let mut migrator = RolloutMigrator::default();
+    let mut thread_id = None;
+    let mut cwd = None;
+    let mut hits = Vec::new();
+    let mut byte_offset = 0u64;
+
+    for (index, raw) in contents.split_inclusive('\n').enumerate() {
+        let line_offset = byte_offset;
+        byte_offset += raw.len() as u64;
+        let trimmed = raw.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(trimmed) else {
+            continue;
+        };
+        for value in migrator.upgrade(value) {
+            let Ok(RolloutLine { timestamp, item }) = serde_json::from_value::<RolloutLine>(value)
+            else {
+                continue;
+            };
+            let (role, message) = match item {
+                // Forked rollouts repeat the source's meta line after their own.
+                RolloutItem::SessionMeta(meta_line) => {
+                    if thread_id.is_none() {
+                        thread_id = Some(meta_line.meta.id);
+                        cwd = Some(meta_line.meta.cwd);
+                    }
+                    continue;
+                }
+                RolloutItem::EventMsg(EventMsg::UserMessage(event)) => {
+                    (SearchHitRole::User, event.message)
+                }
+                RolloutItem::EventMsg(EventMsg::AgentMessage(event)) => {
+                    (SearchHitRole::Assistant, event.message)
+                }
+                _ => continue,
+            };
+            let Some(snippet) = matching_snippet(&message, needle) else {
+                continue;
+            };
+            hits.push(SearchHit {
+                path: path.to_path_buf(),
+                thread_id: None,
+                cwd: None,
+                session_started_at,
+                line: index + 1,
+                byte_offset: line_offset,
+                timestamp,
+                role,
+                snippet,
+            });
+        }
+    }
+
+    for hit in &mut hits {
+        hit.thread_id = thread_id;
+        hit.cwd = cwd.clone();
+    }
+    hits
+}
+
+/// The first line of `message` containing `needle`, shortened to
+/// `SNIPPET_MAX_CHARS`.
+fn matching_snippet(message: &str, needle: &str) -> Option<String> {
+    let line = message
+        .lines()
+        .find(|line| line.to_lowercase().contains(needle))?
+        .trim();
+    if line.chars().count() <= SNIPPET_MAX_CHARS {
+        return Some(line.to_string());
+    }
+    let mut snippet: String = line.chars().take(SNIPPET_MAX_CHARS - 1).collect();
+    snippet.push('…');
+    Some(snippet)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tempfile::TempDir;
+    use time::macros::datetime;
+
+    const SESSION_ID: &str = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+
+    fn write_rollout(codex_home: &Path, day: &str, file_ts: &str, cwd: &str) -> PathBuf {
+        let lines = [
+            json!({"timestamp": "t0", "type": "session_meta", "payload": {
+                "id": SESSION_ID, "timestamp": "t0", "cwd": cwd,
+                "originator": "codex_cli_rs", "cli_version": "0.1.0", "schema_version": 1,
+            }}),
+            json!({"timestamp": "t1", "type": "event_msg", "payload": {
+                "type": "user_message", "message": "Why does the Parser panic?\nsee main.rs",
+            }}),
+            json!({"timestamp": "t1", "type": "response_item", "payload": {
+                "type": "message", "role": "user",
+                "content": [{"type": "input_text", "text": "parser context"}],
+            }}),
+            json!({"timestamp": "t2", "type": "event_msg", "payload": {
+                "type": "agent_message", "message": "The parser unwraps an empty token.",
+            }}),
+        ];
+        let dir = codex_home.join(SESSIONS_SUBDIR).join(day);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(format!("rollout-{file_ts}-{SESSION_ID}.jsonl"));
+        let contents = lines
+            .iter()
+            .map(|line| format!("{line}\n"))
+            .collect::<String>();
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn finds_user_and_assistant_messages_with_line_offsets() {
+        let home = TempDir::new().unwrap();
+        let path = write_rollout(home.path(), "2025/01/02", "2025-01-02T10-00-00", "/repo");
+        let contents = std::fs::read_to_string(&path).unwrap();
+
+        let SearchResults {
+            hits,
+            reached_scan_cap,
+        } = search_conversations(home.path(), "PARSER", &SearchFilters::default())
+            .await
+            .unwrap();
+        assert!(!reached_scan_cap);
+
+        assert_eq!(
+            hits.iter()
+                .map(|hit| (hit.role, hit.line, hit.snippet.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (SearchHitRole::User, 2, "Why does the Parser panic?"),
+                (
+                    SearchHitRole::Assistant,
+                    4,
+                    "The parser unwraps an empty token."
+                ),
+            ]
+        );
+        let second_line_offset = contents.find('\n').unwrap() as u64 + 1;
+        assert_eq!(hits[0].byte_offset, second_line_offset);
+        assert_eq!(hits[0].thread_id, ThreadId::from_string(SESSION_ID).ok());
+        assert_eq!(hits[0].cwd, Some(PathBuf::from("/repo")));
+        assert_eq!(
+            hits[0].session_started_at,
+            datetime!(2025-01-02 10:00:00 UTC)
+        );
+    }
+
+    #[tokio::test]
+    async fn date_and_cwd_filters_exclude_sessions() {
+        let home = TempDir::new().unwrap();
+        write_rollout(
+            home.path(),
+            "2025/01/02",
+            "2025-01-02T10-00-00",
+            "/repo/app",
+        );
+        write_rollout(home.path(), "2025/01/01", "2025-01-01T10-00-00", "/other");
+
+        let recent = SearchFilters {
+            since: Some(datetime!(2025-01-02 00:00:00 UTC)),
+            ..SearchFilters::default()
+        };
+        let in_repo = SearchFilters {
+            cwd: Some(PathBuf::from("/repo")),
+            ..SearchFilters::default()
+        };
+        let old = SearchFilters {
+            until: Some(datetime!(2025-01-01 23:59:59 UTC)),
+            max_hits: Some(1),
+            ..SearchFilters::default()
+        };
+
+        let started = |results: SearchResults| {
+            results
+                .hits
+                .into_iter()
+                .map(|hit| hit.session_started_at)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            started(
+                search_conversations(home.path(), "parser", &recent)
+                    .await
+                    .unwrap()
+            ),
+            vec![datetime!(2025-01-02 10:00:00 UTC); 2]
+        );
+        assert_eq!(
+            started(
+                search_conversations(home.path(), "parser", &in_repo)
+                    .await
+                    .unwrap()
+            ),
+            vec![datetime!(2025-01-02 10:00:00 UTC); 2]
+        );
+        assert_eq!(
+            started(
+                search_conversations(home.path(), "parser", &old)
+                    .await
+                    .unwrap()
+            ),
+            vec![datetime!(2025-01-01 10:00:00 UTC)]
+        );
+    }
+
+    struct FixedIndex(Vec<PathBuf>);
+
+    #[async_trait]
+    impl RolloutSearchIndex for FixedIndex {
+        async fn candidate_files(
+            &self,
+            _codex_home: &Path,
+            _query: &str,
+        ) -> io::Result<Option<Vec<PathBuf>>> {
+            Ok(Some(self.0.clone()))
+        }
+    }
+
+    #[tokio::test]
+    async fn index_narrows_the_files_read() {
+        let home = TempDir::new().unwrap();
+        write_rollout(home.path(), "2025/01/02", "2025-01-02T10-00-00", "/repo");
+        let older = write_rollout(home.path(), "2025/01/01", "2025-01-01T10-00-00", "/repo");
+        let index = FixedIndex(vec![older.clone()]);
+
+        let results = search_conversations_with_index(
+            home.path(),
+            "panic",
+            &SearchFilters::default(),
+            Some(&index),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            results
+                .hits
+                .into_iter()
+                .map(|hit| hit.path)
+                .collect::<Vec<_>>(),
+            vec![older]
+        );
+    }
+
+    #[tokio::test]
+    async fn days_after_until_are_not_read_or_counted() {
+        let home = TempDir::new().unwrap();
+        write_rollout(home.path(), "2025/01/03", "2025-01-03T10-00-00", "/repo");
+        write_rollout(home.path(), "2025/01/02", "2025-01-02T10-00-00", "/repo");
+        write_rollout(home.path(), "2025/01/01", "2025-01-01T10-00-00", "/repo");
+        let filters = SearchFilters {
+            until: Some(datetime!(2025-01-02 12:00:00 UTC)),
+            ..SearchFilters::default()
+        };
+        let mut visitor = SearchVisitor {
+            needle: "panic".to_string(),
+            filters: &filters,
+            hits: Vec::new(),
+        };
+
+        let mut scanned_files = 0usize;
+        walk_rollout_files(
+            &home.path().join(SESSIONS_SUBDIR),
+            &mut scanned_files,
+            &mut visitor,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(scanned_files, 2);
+        assert_eq!(
+            visitor
+                .hits
+                .into_iter()
+                .map(|hit| hit.session_started_at)
+                .collect::<Vec<_>>(),
+            vec![
+                datetime!(2025-01-02 10:00:00 UTC),
+                datetime!(2025-01-01 10:00:00 UTC)
+            ]
+        );
+    }
+}
@@ -779,8 +779,13 @@ async fn test_updated_at_uses_file_mtime() -> Result<()> {
                 source: SessionSource::VSCode,
                 model_provider: Some("test-provider".into()),
                 base_instructions: None,
+                title: None,
+                tags: Vec::new(),
+                stats: None,
             },
             git: None,
+            environment: None,
+            schema_version: None,
         }),
     };
     writeln!(file, "{}", serde_json::to_string(&meta_line)?)?;
@@ -792,6 +797,7 @@ async fn test_updated_at_uses_file_mtime() -> Result<()> {
             images: None,
             text_elements: Vec::new(),
             local_images: Vec::new(),
+            item_id: None,
         })),
     };
     writeln!(file, "{}", serde_json::to_string(&user_event_line)?)?;
@@ -1130,3 +1136,210 @@ async fn test_model_provider_filter_selects_only_matching_sessions() -> Result<(
 
     Ok(())
 }
+
+#[tokio::test]
+async fn incognito_recorder_writes_nothing_to_disk() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let mut config = crate::config::ConfigBuilder::default()
+        .codex_home(codex_home.path().to_path_buf())
+        .build()
+        .await?;
+    config.incognito = true;
+
+    let recorder = crate::rollout::RolloutRecorder::new(
+        &config,
+        crate::rollout::RolloutRecorderParams::new(
+            ThreadId::new(),
+            None,
+            SessionSource::Cli,
+            codex_protocol::models::BaseInstructions::default(),
+        ),
+    )
+    .await?;
+    recorder
+        .record_items(&[RolloutItem::EventMsg(EventMsg::UserMessage(
+            UserMessageEvent {
+                message: "secret".to_string(),
+                images: None,
+                local_images: Vec::new(),
+                text_elements: Vec::new(),
+                item_id: None,
+            },
+        ))])
+        .await?;
+    recorder.flush().await?;
+    let meta = recorder
+        .update_session_metadata(crate::rollout::SessionMetadataUpdate {
+            title: Some("Private".to_string()),
+            ..Default::default()
+        })
+        .await?;
+    recorder.shutdown().await?;
+
+    assert_eq!(meta.title, Some("Private".to_string()));
+    assert_eq!(recorder.rollout_path, std::path::PathBuf::new());
+    assert!(
+        !codex_home
+            .path()
+            .join(crate::rollout::SESSIONS_SUBDIR)
+            .exists()
+    );
+    Ok(())
+}
+
+fn user_message_item(message: &str) -> RolloutItem {
+    RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
+        message: message.to_string(),
+        images: None,
+        local_images: Vec::new(),
+        text_elements: Vec::new(),
+        item_id: None,
+    }))
+}
+
+fn recorded_user_messages(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .filter_map(|line| serde_json::from_str::<RolloutLine>(line).ok())
+        .filter_map(|line| match line.item {
+            RolloutItem::EventMsg(EventMsg::UserMessage(event)) => Some(event.message),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn group_commit_writes_every_queued_line_in_order() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let mut config = crate::config::ConfigBuilder::default()
+        .codex_home(codex_home.path().to_path_buf())
+        .build()
+        .await?;
+    config.rollout.fsync = Some(crate::config::types::RolloutFsync::PerEvent);
+
+    let recorder = crate::rollout::RolloutRecorder::new(
+        &config,
+        crate::rollout::RolloutRecorderParams::new(
+            ThreadId::new(),
+            None,
+            SessionSource::Cli,
+            codex_protocol::models::BaseInstructions::default(),
+        ),
+    )
+    .await?;
+    let expected: Vec<String> = (0..50).map(|i| format!("message {i}")).collect();
+    for message in &expected {
+        recorder.record_items(&[user_message_item(message)]).await?;
+    }
+    recorder.flush().await?;
+
+    let contents = fs::read_to_string(&recorder.rollout_path)?;
+    assert!(contents.ends_with('\n'));
+    assert_eq!(recorded_user_messages(&contents), expected);
+    recorder.shutdown().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn live_metadata_update_replaces_the_file_and_keeps_appending() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let config = crate::config::ConfigBuilder::default()
+        .codex_home(codex_home.path().to_path_buf())
+        .build()
+        .await?;
+
+    let recorder = crate::rollout::RolloutRecorder::new(
+        &config,
+        crate::rollout::RolloutRecorderParams::new(
+            ThreadId::new(),
+            None,
+            SessionSource::Cli,
+            codex_protocol::models::BaseInstructions::default(),
+        ),
+    )
+    .await?;
+    recorder
+        .record_items(&[user_message_item("before")])
+        .await?;
+    recorder
+        .update_session_metadata(crate::rollout::SessionMetadataUpdate {
+            title: Some("Renamed".to_string()),
+            ..Default::default()
+        })
+        .await?;
+    recorder.record_items(&[user_message_item("after")]).await?;
+    recorder.flush().await?;
+
+    let contents = fs::read_to_string(&recorder.rollout_path)?;
+    assert_eq!(
+        recorded_user_messages(&contents),
+        vec!["before".to_string(), "after".to_string()]
+    );
+    let meta = crate::rollout::list::read_session_meta_line(&recorder.rollout_path).await?;
+    assert_eq!(meta.meta.title, Some("Renamed".to_string()));
+    assert!(!recorder.rollout_path.with_extension("jsonl.tmp").exists());
+    recorder.shutdown().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn resume_after_torn_write_keeps_new_lines_intact() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let config = crate::config::ConfigBuilder::default()
+        .codex_home(codex_home.path().to_path_buf())
+        .build()
+        .await?;
+
+    let recorder = crate::rollout::RolloutRecorder::new(
+        &config,
+        crate::rollout::RolloutRecorderParams::new(
+            ThreadId::new(),
+            None,
+            SessionSource::Cli,
+            codex_protocol::models::BaseInstructions::default(),
+        ),
+    )
+    .await?;
+    recorder
+        .record_items(&[user_message_item("before crash")])
+        .await?;
+    recorder.shutdown().await?;
+    let path = recorder.rollout_path.clone();
+    drop(recorder);
+
+    // Simulate a crash in the middle of writing a line.
+    let mut file = fs::OpenOptions::new().append(true).open(&path)?;
+    file.write_all(br#"{"timestamp":"2025-01-01T00:00:00.000Z","type":"event_msg","pay"#)?;
+    drop(file);
+
+    let resumed = crate::rollout::RolloutRecorder::new(
+        &config,
+        crate::rollout::RolloutRecorderParams::resume(path.clone()),
+    )
+    .await?;
+    resumed
+        .record_items(&[user_message_item("after resume")])
+        .await?;
+    resumed.shutdown().await?;
+
+    let contents = fs::read_to_string(&path)?;
+    assert_eq!(
+        recorded_user_messages(&contents),
+        vec!["before crash".to_string(), "after resume".to_string()]
+    );
+    let codex_protocol::protocol::InitialHistory::Resumed(history) =
+        crate::rollout::RolloutRecorder::get_rollout_history(&path).await?
+    else {
+        panic!("expected resumed history");
+    };
+    let history_messages: Vec<&str> = history
+        .history
+        .iter()
+        .filter_map(|item| match item {
+            RolloutItem::EventMsg(EventMsg::UserMessage(event)) => Some(event.message.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(history_messages, vec!["before crash", "after resume"]);
+    Ok(())
+}
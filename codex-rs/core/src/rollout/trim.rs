@@ -0,0 +1,375 @@
+//! Removing a range of user turns from a recorded rollout, so that content
+//! such as an accidentally pasted secret can be scrubbed from history.
+//!
+//! Turns are numbered from zero by their user messages, counting every user
+//! turn recorded in the file, including ones that were later rolled back.
+//! Everything from the user message that starts the first trimmed turn up to
+//! the user message that starts the next kept turn is removed, and a
+//! [`TurnsTrimmedEvent`] tombstone is written in its place.
+
+use std::io;
+use std::ops::Range;
+
+use codex_protocol::items::TurnItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use codex_protocol::protocol::TurnsTrimmedEvent;
+
+use crate::event_mapping;
+use crate::rollout::item_ids::backfill_legacy_item_ids;
+use crate::rollout::migrate::migrate_contents;
+
+/// One line of a rollout file. Lines that fail to parse are kept verbatim,
+/// like [`crate::RolloutRecorder::get_rollout_history`] skips them.
+struct Line<'a> {
+    raw: &'a str,
+    parsed: Option<RolloutLine>,
+    /// Whether `parsed` changed and must be serialized again.
+    dirty: bool,
+    /// Whether the line is left out of the rewritten file.
+    removed: bool,
+    /// Raw index of the user turn the line belongs to, `None` before the
+    /// first user message.
+    turn: Option<usize>,
+}
+
+/// Rewrites rollout `contents` without the user turns in `range`.
+///
+/// The rest of the file stays consistent with what it was before:
+/// - item ids that older rollouts derive from line positions are written out
+///   explicitly, so they do not shift once lines are removed;
+/// - `ThreadRolledBack` markers are kept and their counts re-sequenced so they
+///   drop the same surviving turns as before.
+///
+/// Trimming a turn that contains a compaction is refused, since the history
+/// after it is rebuilt from that compaction.
+pub(crate) fn trim_rollout_contents(contents: &str, range: Range<usize>) -> io::Result<String> {
+    let contents = migrate_contents(contents);
+    let mut lines: Vec<Line<'_>> = contents
+        .lines()
+        .filter(|raw| !raw.trim().is_empty())
+        .map(|raw| Line {
+            raw,
+            parsed: serde_json::from_str(raw).ok(),
+            dirty: false,
+            removed: false,
+            turn: None,
+        })
+        .collect();
+
+    materialize_legacy_item_ids(&mut lines);
+
+    let mut turn_count = 0;
+    for line in &mut lines {
+        if let Some(RolloutLine {
+            item: RolloutItem::ResponseItem(item),
+            ..
+        }) = &line.parsed
+            && is_user_message(item)
+        {
+            turn_count += 1;
+        }
+        line.turn = turn_count.checked_sub(1);
+    }
+    if range.is_empty() || range.end > turn_count {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "cannot trim turns {}..{}: the rollout has {turn_count} turns",
+                range.start, range.end
+            ),
+        ));
+    }
+    let trimmed = |turn: Option<usize>| turn.is_some_and(|turn| range.contains(&turn));
+    if lines.iter().any(|line| {
+        trimmed(line.turn)
+            && matches!(
+                line.parsed,
+                Some(RolloutLine {
+                    item: RolloutItem::Compacted(_),
+                    ..
+                })
+            )
+    }) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "cannot trim turns that contain a compaction",
+        ));
+    }
+
+    for line in &mut lines {
+        let is_rollback = matches!(
+            line.parsed,
+            Some(RolloutLine {
+                item: RolloutItem::EventMsg(EventMsg::ThreadRolledBack(_)),
+                ..
+            })
+        );
+        line.removed = trimmed(line.turn) && !is_rollback;
+    }
+    resequence_rollbacks(&mut lines, &range);
+
+    let mut rewritten = String::with_capacity(contents.len());
+    let mut tombstone_written = false;
+    for line in &lines {
+        if trimmed(line.turn) && !tombstone_written {
+            // Reuse the timestamp of the first removed line so the file
+            // stays in chronological order.
+            let timestamp = line
+                .parsed
+                .as_ref()
+                .map(|parsed| parsed.timestamp.clone())
+                .unwrap_or_default();
+            let tombstone = RolloutLine {
+                timestamp,
+                item: RolloutItem::EventMsg(EventMsg::TurnsTrimmed(TurnsTrimmedEvent {
+                    first_turn: u32::try_from(range.start).unwrap_or(u32::MAX),
+                    num_turns: u32::try_from(range.len()).unwrap_or(u32::MAX),
+                })),
+            };
+            rewritten.push_str(&serde_json::to_string(&tombstone)?);
+            rewritten.push('\n');
+            tombstone_written = true;
+        }
+        if line.removed {
+            continue;
+        }
+        match &line.parsed {
+            Some(parsed) if line.dirty => rewritten.push_str(&serde_json::to_string(parsed)?),
+            _ => rewritten.push_str(line.raw),
+        }
+        rewritten.push('\n');
+    }
+    Ok(rewritten)
+}
+
+fn is_user_message(item: &ResponseItem) -> bool {
+    matches!(item, ResponseItem::Message { .. })
+        && matches!(
+            event_mapping::parse_turn_item(item),
+            Some(TurnItem::UserMessage(_))
+        )
+}
+
+/// Writes the position-derived ids of legacy events into their lines.
+fn materialize_legacy_item_ids(lines: &mut [Line<'_>]) {
+    let mut items: Vec<RolloutItem> = lines
+        .iter()
+        .filter_map(|line| line.parsed.as_ref().map(|parsed| parsed.item.clone()))
+        .collect();
+    backfill_legacy_item_ids(&mut items);
+    let parsed_lines = lines.iter_mut().filter(|line| line.parsed.is_some());
+    for (line, item) in parsed_lines.zip(items) {
+        let Some(parsed) = &mut line.parsed else {
+            continue;
+        };
+        if let (RolloutItem::EventMsg(before), RolloutItem::EventMsg(after)) = (&parsed.item, &item)
+            && before.item_id() != after.item_id()
+        {
+            parsed.item = item;
+            line.dirty = true;
+        }
+    }
+}
+
+/// Replays the rollbacks in `lines` and lowers each count by the number of
+/// trimmed turns it dropped, removing markers that only dropped trimmed turns.
+fn resequence_rollbacks(lines: &mut [Line<'_>], range: &Range<usize>) {
+    let mut live_turns: Vec<usize> = Vec::new();
+    for line in lines.iter_mut() {
+        let Some(parsed) = &mut line.parsed else {
+            continue;
+        };
+        match &mut parsed.item {
+            RolloutItem::ResponseItem(item) if is_user_message(item) => {
+                if let Some(turn) = line.turn {
+                    live_turns.push(turn);
+                }
+            }
+            RolloutItem::EventMsg(EventMsg::ThreadRolledBack(rollback)) => {
+                let num_turns = usize::try_from(rollback.num_turns).unwrap_or(usize::MAX);
+                let keep = live_turns.len().saturating_sub(num_turns);
+                let dropped_trimmed = live_turns
+                    .drain(keep..)
+                    .filter(|turn| range.contains(turn))
+                    .count();
+                if dropped_trimmed == 0 {
+                    continue;
+                }
+                let remaining = num_turns.saturating_sub(dropped_trimmed);
+                if remaining == 0 {
+                    line.removed = true;
+                } else {
+                    rollback.num_turns = u32::try_from(remaining).unwrap_or(u32::MAX);
+                    line.dirty = true;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::ContentItem;
+    use codex_protocol::protocol::AgentMessageEvent;
+    use codex_protocol::protocol::SessionMeta;
+    use codex_protocol::protocol::SessionMetaLine;
+    use codex_protocol::protocol::ThreadRolledBackEvent;
+    use pretty_assertions::assert_eq;
+
+    fn message(role: &str, text: &str) -> RolloutItem {
+        RolloutItem::ResponseItem(ResponseItem::Message {
+            id: None,
+            role: role.to_string(),
+            content: vec![ContentItem::OutputText {
+                text: text.to_string(),
+            }],
+            end_turn: None,
+        })
+    }
+
+    fn agent_event(item_id: Option<&str>) -> RolloutItem {
+        RolloutItem::EventMsg(EventMsg::AgentMessage(AgentMessageEvent {
+            message: "hi".to_string(),
+            item_id: item_id.map(str::to_string),
+        }))
+    }
+
+    fn rollback(num_turns: u32) -> RolloutItem {
+        RolloutItem::EventMsg(EventMsg::ThreadRolledBack(ThreadRolledBackEvent {
+            num_turns,
+            edited_item_id: None,
+        }))
+    }
+
+    fn contents(items: Vec<RolloutItem>) -> String {
+        let meta = RolloutItem::SessionMeta(SessionMetaLine {
+            meta: SessionMeta::default(),
+            git: None,
+            environment: None,
+            schema_version: None,
+        });
+        std::iter::once(meta)
+            .chain(items)
+            .enumerate()
+            .map(|(index, item)| {
+                let line = RolloutLine {
+                    timestamp: format!("2025-01-01T00:00:{index:02}.000Z"),
+                    item,
+                };
+                serde_json::to_string(&line).expect("serialize line") + "\n"
+            })
+            .collect()
+    }
+
+    fn items(contents: &str) -> Vec<serde_json::Value> {
+        contents
+            .lines()
+            .map(|line| {
+                let line: RolloutLine = serde_json::from_str(line).expect("parse line");
+                serde_json::to_value(line.item).expect("serialize item")
+            })
+            .collect()
+    }
+
+    fn values(items: Vec<RolloutItem>) -> Vec<serde_json::Value> {
+        items
+            .into_iter()
+            .map(|item| serde_json::to_value(item).expect("serialize item"))
+            .collect()
+    }
+
+    #[test]
+    fn replaces_trimmed_turns_with_a_tombstone() -> io::Result<()> {
+        let original = contents(vec![
+            message("user", "u0"),
+            message("assistant", "a0"),
+            message("user", "my key is sk-secret"),
+            agent_event(None),
+            message("user", "u2"),
+            agent_event(None),
+        ]);
+
+        let trimmed = trim_rollout_contents(&original, 1..2)?;
+
+        let rewritten = items(&trimmed);
+        assert_eq!(
+            rewritten[1..].to_vec(),
+            values(vec![
+                message("user", "u0"),
+                message("assistant", "a0"),
+                RolloutItem::EventMsg(EventMsg::TurnsTrimmed(TurnsTrimmedEvent {
+                    first_turn: 1,
+                    num_turns: 1,
+                })),
+                message("user", "u2"),
+                // The id derived from the original position is kept.
+                agent_event(Some("legacy-6")),
+            ])
+        );
+        assert!(!trimmed.contains("sk-secret"));
+        let tombstone: RolloutLine =
+            serde_json::from_str(trimmed.lines().nth(3).expect("tombstone line"))
+                .expect("parse tombstone");
+        assert_eq!(tombstone.timestamp, "2025-01-01T00:00:03.000Z");
+        Ok(())
+    }
+
+    #[test]
+    fn resequences_rollbacks_over_trimmed_turns() -> io::Result<()> {
+        let original = contents(vec![
+            message("user", "u0"),
+            message("user", "u1"),
+            message("user", "u2"),
+            rollback(2),
+            message("user", "u3"),
+            rollback(1),
+        ]);
+
+        let trimmed = trim_rollout_contents(&original, 1..2)?;
+
+        assert_eq!(
+            items(&trimmed)[1..].to_vec(),
+            values(vec![
+                message("user", "u0"),
+                RolloutItem::EventMsg(EventMsg::TurnsTrimmed(TurnsTrimmedEvent {
+                    first_turn: 1,
+                    num_turns: 1,
+                })),
+                message("user", "u2"),
+                rollback(1),
+                message("user", "u3"),
+                rollback(1),
+            ])
+        );
+
+        let trimmed = trim_rollout_contents(&original, 3..4)?;
+        assert_eq!(
+            items(&trimmed)[1..].to_vec(),
+            values(vec![
+                message("user", "u0"),
+                message("user", "u1"),
+                message("user", "u2"),
+                rollback(2),
+                RolloutItem::EventMsg(EventMsg::TurnsTrimmed(TurnsTrimmedEvent {
+                    first_turn: 3,
+                    num_turns: 1,
+                })),
+            ])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_out_of_range_turns() {
+        let original = contents(vec![message("user", "u0"), message("user", "u1")]);
+
+        let err = trim_rollout_contents(&original, 1..3).expect_err("range past the end");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(trim_rollout_contents(&original, 1..1).is_err());
+    }
+}
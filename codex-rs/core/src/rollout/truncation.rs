@@ -68,12 +68,58 @@ pub(crate) fn truncate_rollout_before_nth_user_message_from_start(
     items[..cut_idx].to_vec()
 }
 
+/// Return a prefix of `items` that ends with the last item referring to
+/// `item_id`, or `None` when no item does.
+///
+/// Items are matched by their response item id or call id, and events by
+/// [`EventMsg::item_id`], so the id of a tool call keeps the call's output
+/// and the id of a message keeps the event that reported it.
+pub(crate) fn truncate_rollout_after_item_id(
+    items: &[RolloutItem],
+    item_id: &str,
+) -> Option<Vec<RolloutItem>> {
+    let last = items
+        .iter()
+        .rposition(|item| rollout_item_refers_to(item, item_id))?;
+    Some(items[..=last].to_vec())
+}
+
+fn rollout_item_refers_to(item: &RolloutItem, item_id: &str) -> bool {
+    let wanted = Some(item_id);
+    match item {
+        RolloutItem::EventMsg(event) => event.item_id() == wanted,
+        RolloutItem::ResponseItem(item) => match item {
+            ResponseItem::Message { id, .. }
+            | ResponseItem::WebSearchCall { id, .. }
+            | ResponseItem::CodeInterpreterCall { id, .. }
+            | ResponseItem::FileSearchCall { id, .. } => id.as_deref() == wanted,
+            ResponseItem::Reasoning { id, .. } => id == item_id,
+            ResponseItem::LocalShellCall { id, call_id, .. } => {
+                id.as_deref() == wanted || call_id.as_deref() == wanted
+            }
+            ResponseItem::FunctionCall { id, call_id, .. }
+            | ResponseItem::CustomToolCall { id, call_id, .. } => {
+                id.as_deref() == wanted || call_id == item_id
+            }
+            ResponseItem::FunctionCallOutput { call_id, .. }
+            | ResponseItem::CustomToolCallOutput { call_id, .. } => call_id == item_id,
+            ResponseItem::GhostSnapshot { .. }
+            | ResponseItem::Compaction { .. }
+            | ResponseItem::Other => false,
+        },
+        RolloutItem::SessionMeta(_) | RolloutItem::Compacted(_) | RolloutItem::TurnContext(_) => {
+            false
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::codex::make_session_and_context;
     use assert_matches::assert_matches;
     use codex_protocol::models::ContentItem;
+    use codex_protocol::models::FunctionCallOutputPayload;
     use codex_protocol::models::ReasoningItemReasoningSummary;
     use codex_protocol::protocol::ThreadRolledBackEvent;
     use pretty_assertions::assert_eq;
@@ -146,6 +192,41 @@ mod tests {
         assert_matches!(truncated2.as_slice(), []);
     }
 
+    #[test]
+    fn truncates_rollout_after_the_last_item_with_the_id() {
+        let call = ResponseItem::FunctionCall {
+            id: None,
+            name: "tool".to_string(),
+            arguments: "{}".to_string(),
+            call_id: "c1".to_string(),
+        };
+        let output = ResponseItem::FunctionCallOutput {
+            call_id: "c1".to_string(),
+            output: FunctionCallOutputPayload {
+                content: "ok".to_string(),
+                ..Default::default()
+            },
+        };
+        let rollout: Vec<RolloutItem> = [
+            user_msg("u1"),
+            call,
+            output,
+            assistant_msg("a1"),
+            user_msg("u2"),
+        ]
+        .into_iter()
+        .map(RolloutItem::ResponseItem)
+        .collect();
+
+        let truncated = truncate_rollout_after_item_id(&rollout, "c1").expect("c1 is recorded");
+
+        assert_eq!(
+            serde_json::to_value(&truncated).unwrap(),
+            serde_json::to_value(&rollout[..3]).unwrap()
+        );
+        assert_matches!(truncate_rollout_after_item_id(&rollout, "missing"), None);
+    }
+
     #[test]
     fn truncation_max_keeps_full_rollout() {
         let rollout = vec![
@@ -171,6 +252,7 @@ mod tests {
             RolloutItem::ResponseItem(assistant_msg("a2")),
             RolloutItem::EventMsg(EventMsg::ThreadRolledBack(ThreadRolledBackEvent {
                 num_turns: 1,
+                edited_item_id: None,
             })),
             RolloutItem::ResponseItem(user_msg("u3")),
             RolloutItem::ResponseItem(assistant_msg("a3")),
@@ -0,0 +1,259 @@
+//! Explains why a command that failed inside the sandbox was probably blocked.
+//!
+//! [`crate::exec::is_likely_sandbox_denied`] only says *that* the sandbox may
+//! be involved. This pass looks at the failure more closely: it matches
+//! network and permission signatures in the output, pulls the paths those
+//! errors mention, stats them to rule out ordinary file permissions, and then
+//! asks the policy which of its rules applies. The result is appended to the
+//! tool output so the model (and the user reading along) can tell a sandbox
+//! denial from a genuine failure.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::exec::ExecToolCallOutput;
+use crate::protocol::SandboxDecision;
+use crate::protocol::SandboxEvaluationTarget;
+use crate::protocol::SandboxPolicy;
+
+use super::evaluate;
+
+/// Output fragments (lowercase) of a command that could not reach the network.
+const NETWORK_SIGNATURES: &[&str] = &[
+    "could not resolve host",
+    "temporary failure in name resolution",
+    "name or service not known",
+    "nodename nor servname provided",
+    "network is unreachable",
+    "failed to connect",
+    "getaddrinfo",
+];
+
+/// Output fragments (lowercase) of a denied filesystem operation.
+const PERMISSION_SIGNATURES: &[&str] = &[
+    "permission denied",
+    "operation not permitted",
+    "read-only file system",
+];
+
+/// Returns a one-line diagnosis such as "likely blocked by sandbox: write to
+/// /usr/local/bin/tool (/usr/local/bin/tool is outside every writable root)",
+/// or `None` when nothing points at the sandbox.
+pub(crate) fn diagnose_sandbox_failure(
+    command: &[String],
+    output: &ExecToolCallOutput,
+    policy: &SandboxPolicy,
+    cwd: &Path,
+) -> Option<String> {
+    let text = [
+        &output.stderr.text,
+        &output.stdout.text,
+        &output.aggregated_output.text,
+    ]
+    .into_iter()
+    .map(|section| section.to_lowercase())
+    .collect::<Vec<_>>()
+    .join("\n");
+
+    if !policy.has_full_network_access()
+        && NETWORK_SIGNATURES
+            .iter()
+            .any(|signature| text.contains(signature))
+    {
+        return Some(
+            "likely blocked by sandbox: network access (the sandbox policy does not allow outbound network connections)"
+                .to_string(),
+        );
+    }
+
+    let all_output = format!(
+        "{}\n{}\n{}",
+        output.stderr.text, output.stdout.text, output.aggregated_output.text
+    );
+    for path in denied_paths(&all_output) {
+        let path = cwd.join(path);
+        if let Some(reason) = not_the_sandbox(&path) {
+            return Some(reason);
+        }
+        let evaluation = evaluate(
+            policy,
+            &SandboxEvaluationTarget::Path { path: path.clone() },
+            cwd,
+        );
+        if evaluation.decision == SandboxDecision::Deny {
+            return Some(format!(
+                "likely blocked by sandbox: write to {} ({})",
+                path.display(),
+                evaluation.reason
+            ));
+        }
+    }
+
+    let evaluation = evaluate(
+        policy,
+        &SandboxEvaluationTarget::Command {
+            command: command.to_vec(),
+        },
+        cwd,
+    );
+    if evaluation.decision == SandboxDecision::Deny {
+        return Some(format!("likely blocked by sandbox: {}", evaluation.reason));
+    }
+
+    #[cfg(unix)]
+    if output.exit_code == crate::exec::EXIT_CODE_SIGNAL_BASE + libc::SIGSYS {
+        return Some(
+            "likely blocked by sandbox: a system call was denied by the seccomp filter".to_string(),
+        );
+    }
+    None
+}
+
+/// Paths mentioned on output lines that report a denied filesystem
+/// operation, in order of appearance.
+fn denied_paths(output: &str) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for line in output.lines() {
+        let lower = line.to_lowercase();
+        if !PERMISSION_SIGNATURES
+            .iter()
+            .any(|signature| lower.contains(signature))
+        {
+            continue;
+        }
+        let candidates = line
+            .split(|c: char| c.is_whitespace() || matches!(c, '\'' | '"' | '`' | ':' | ','))
+            .map(|token| token.trim_end_matches(['.', ')']))
+            .filter(|token| token.starts_with('/') && token.len() > 1)
+            .map(PathBuf::from);
+        for path in candidates {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+    paths
+}
+
+/// Stats `path` (or its closest existing ancestor) and returns a diagnosis
+/// when ordinary file permissions already explain the failure.
+fn not_the_sandbox(path: &Path) -> Option<String> {
+    let existing = path.ancestors().find(|ancestor| ancestor.exists())?;
+    let metadata = std::fs::metadata(existing).ok()?;
+    if !metadata.permissions().readonly() {
+        return None;
+    }
+    Some(format!(
+        "probably not the sandbox: {} is read-only on disk regardless of the sandbox policy",
+        existing.display()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exec::StreamOutput;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    fn failed_output(stderr: &str) -> ExecToolCallOutput {
+        ExecToolCallOutput {
+            exit_code: 1,
+            stdout: StreamOutput::new(String::new()),
+            stderr: StreamOutput::new(stderr.to_string()),
+            aggregated_output: StreamOutput::new(stderr.to_string()),
+            duration: Duration::from_millis(10),
+            timed_out: false,
+        }
+    }
+
+    fn argv(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn names_the_path_and_rule_behind_a_denied_write() -> std::io::Result<()> {
+        let workspace = tempfile::tempdir()?;
+        let outside = tempfile::tempdir()?;
+        let policy = SandboxPolicy::WorkspaceWrite {
+            writable_roots: Vec::new(),
+            network_access: false,
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+            max_memory_bytes: None,
+            cpu_rate_percent: None,
+            max_processes: None,
+        };
+        let target = outside.path().join("probe");
+        let output = failed_output(&format!(
+            "touch: cannot touch '{}': Operation not permitted\n",
+            target.display()
+        ));
+        let command = argv(&["touch", &target.to_string_lossy()]);
+
+        assert_eq!(
+            diagnose_sandbox_failure(&command, &output, &policy, workspace.path()),
+            Some(format!(
+                "likely blocked by sandbox: write to {path} ({path} is outside every writable root)",
+                path = target.display()
+            ))
+        );
+
+        // A directory that is read-only on disk explains the failure by itself.
+        let original = std::fs::metadata(outside.path())?.permissions();
+        let mut read_only = original.clone();
+        read_only.set_readonly(true);
+        std::fs::set_permissions(outside.path(), read_only)?;
+        let diagnosis = diagnose_sandbox_failure(&command, &output, &policy, workspace.path());
+        std::fs::set_permissions(outside.path(), original)?;
+        assert_eq!(
+            diagnosis,
+            Some(format!(
+                "probably not the sandbox: {} is read-only on disk regardless of the sandbox policy",
+                outside.path().display()
+            ))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn reports_network_failures_when_network_is_restricted() {
+        let workspace = tempfile::tempdir().expect("tempdir");
+        let output = failed_output("curl: (6) Could not resolve host: example.com\n");
+
+        assert_eq!(
+            diagnose_sandbox_failure(
+                &argv(&["curl", "https://example.com"]),
+                &output,
+                &SandboxPolicy::new_workspace_write_policy(),
+                workspace.path(),
+            ),
+            Some(
+                "likely blocked by sandbox: network access (the sandbox policy does not allow outbound network connections)"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            diagnose_sandbox_failure(
+                &argv(&["curl", "https://example.com"]),
+                &output,
+                &SandboxPolicy::DangerFullAccess,
+                workspace.path(),
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn denied_paths_come_from_permission_errors_only() {
+        let output = "\
+mkdir: /opt/tool: Permission denied
+warning: see /etc/tool.conf for details
+cp: cannot create regular file '/opt/tool/bin': Operation not permitted.
+";
+        assert_eq!(
+            denied_paths(output),
+            vec![PathBuf::from("/opt/tool"), PathBuf::from("/opt/tool/bin")]
+        );
+    }
+}
@@ -0,0 +1,317 @@
+//! Dry-run evaluation of a [`SandboxPolicy`] against a single path or
+//! command, without touching the filesystem or spawning anything.
+
+use crate::protocol::SandboxDecision;
+use crate::protocol::SandboxEvaluation;
+use crate::protocol::SandboxEvaluationTarget;
+use crate::protocol::SandboxPolicy;
+use crate::protocol::SandboxRule;
+use codex_utils_absolute_path::AbsolutePathBuf;
+use std::path::Path;
+
+/// Dry-runs `target` against `policy` from `cwd` without touching the
+/// filesystem or spawning anything. Commands are judged by the network
+/// access they obviously need and the paths that well-known file commands
+/// (`touch`, `mkdir`, `rm`, `cp`, ...) would write.
+pub(crate) fn evaluate_plain(
+    policy: &SandboxPolicy,
+    target: &SandboxEvaluationTarget,
+    cwd: &Path,
+) -> SandboxEvaluation {
+    match target {
+        SandboxEvaluationTarget::Path { path } => evaluate_write(policy, path, cwd),
+        SandboxEvaluationTarget::Command { command } => evaluate_command(policy, command, cwd),
+    }
+}
+
+fn evaluate_write(policy: &SandboxPolicy, path: &Path, cwd: &Path) -> SandboxEvaluation {
+    if policy.has_full_disk_write_access() {
+        return allow(
+            SandboxRule::FullDiskWriteAccess,
+            format!(
+                "the policy allows writes anywhere, including {}",
+                path.display()
+            ),
+        );
+    }
+    if matches!(policy, SandboxPolicy::ReadOnly) {
+        return deny(
+            SandboxRule::ReadOnly,
+            format!(
+                "the policy is read-only, so {} is not writable",
+                path.display()
+            ),
+        );
+    }
+    let path = match AbsolutePathBuf::resolve_path_against_base(path, cwd) {
+        Ok(path) => path,
+        Err(err) => {
+            return deny(
+                SandboxRule::OutsideWritableRoots,
+                format!("cannot resolve {}: {err}", path.display()),
+            );
+        }
+    };
+    for writable_root in policy.get_writable_roots_with_cwd(cwd) {
+        if !path.as_path().starts_with(&writable_root.root) {
+            continue;
+        }
+        if let Some(subpath) = writable_root
+            .read_only_subpaths
+            .iter()
+            .find(|subpath| path.as_path().starts_with(subpath))
+        {
+            return deny(
+                SandboxRule::ReadOnlySubpath {
+                    root: writable_root.root.clone(),
+                    subpath: subpath.clone(),
+                },
+                format!(
+                    "{} is inside {}, which stays read-only under writable root {}",
+                    path.display(),
+                    subpath.display(),
+                    writable_root.root.display()
+                ),
+            );
+        }
+        return allow(
+            SandboxRule::WritableRoot {
+                root: writable_root.root.clone(),
+            },
+            format!(
+                "{} is inside writable root {}",
+                path.display(),
+                writable_root.root.display()
+            ),
+        );
+    }
+    deny(
+        SandboxRule::OutsideWritableRoots,
+        format!("{} is outside every writable root", path.display()),
+    )
+}
+
+fn evaluate_command(policy: &SandboxPolicy, command: &[String], cwd: &Path) -> SandboxEvaluation {
+    let program = command.first().map(String::as_str).unwrap_or_default();
+    let uses_network = command_uses_network(command);
+    if uses_network && !policy.has_full_network_access() {
+        return deny(
+            SandboxRule::NetworkRestricted,
+            format!("`{program}` needs network access, which the policy does not allow"),
+        );
+    }
+    let mut last_write = None;
+    for path in command_write_targets(command) {
+        let evaluation = evaluate_write(policy, Path::new(path), cwd);
+        if evaluation.decision == SandboxDecision::Deny {
+            return evaluation;
+        }
+        last_write = Some(evaluation);
+    }
+    if uses_network {
+        return allow(
+            SandboxRule::NetworkAccess,
+            format!("`{program}` needs network access, which the policy allows"),
+        );
+    }
+    last_write.unwrap_or_else(|| {
+        allow(
+            SandboxRule::NoRestrictedEffects,
+            format!(
+                "`{program}` does not obviously write files or use the network; anything it does write is still confined by the policy"
+            ),
+        )
+    })
+}
+
+fn allow(rule: SandboxRule, reason: String) -> SandboxEvaluation {
+    SandboxEvaluation {
+        decision: SandboxDecision::Allow,
+        rule,
+        reason,
+    }
+}
+
+fn deny(rule: SandboxRule, reason: String) -> SandboxEvaluation {
+    SandboxEvaluation {
+        decision: SandboxDecision::Deny,
+        rule,
+        reason,
+    }
+}
+
+/// Programs whose every invocation talks to the network.
+const NETWORK_PROGRAMS: &[&str] = &[
+    "curl", "dig", "ftp", "gh", "host", "nc", "ncat", "nslookup", "ping", "rsync", "scp", "sftp",
+    "ssh", "telnet", "wget",
+];
+
+/// Subcommands that talk to the network for programs that otherwise may not.
+const NETWORK_SUBCOMMANDS: &[(&str, &[&str])] = &[
+    ("apt", &["install", "update", "upgrade"]),
+    ("apt-get", &["install", "update", "upgrade"]),
+    ("brew", &["install", "update", "upgrade"]),
+    (
+        "cargo",
+        &["add", "fetch", "install", "publish", "search", "update"],
+    ),
+    ("docker", &["login", "pull", "push"]),
+    ("git", &["clone", "fetch", "ls-remote", "pull", "push"]),
+    ("go", &["get", "install"]),
+    ("npm", &["add", "ci", "i", "install", "publish", "update"]),
+    ("pip", &["download", "install"]),
+    ("pip3", &["download", "install"]),
+    ("pnpm", &["add", "i", "install", "publish", "update"]),
+    ("uv", &["add", "pip", "sync"]),
+    ("yarn", &["add", "install", "publish", "upgrade"]),
+];
+
+fn program_name(command: &[String]) -> Option<String> {
+    let program = command.first()?;
+    Some(
+        Path::new(program)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+    )
+}
+
+/// Whether the single command `command` (not a shell script) is known to need
+/// outbound network access.
+pub(crate) fn command_uses_network(command: &[String]) -> bool {
+    let Some(program) = program_name(command) else {
+        return false;
+    };
+    if NETWORK_PROGRAMS.contains(&program.as_str()) {
+        return true;
+    }
+    let Some(subcommand) = command.iter().skip(1).find(|arg| !arg.starts_with('-')) else {
+        return false;
+    };
+    NETWORK_SUBCOMMANDS
+        .iter()
+        .any(|(name, subcommands)| *name == program && subcommands.contains(&subcommand.as_str()))
+}
+
+/// Paths that well-known file commands write. Only the destination of `cp`,
+/// `mv` and `ln` is a write; every operand of the others is.
+fn command_write_targets(command: &[String]) -> Vec<&str> {
+    let Some(program) = program_name(command) else {
+        return Vec::new();
+    };
+    let operands: Vec<&str> = command
+        .iter()
+        .skip(1)
+        .map(String::as_str)
+        .filter(|arg| !arg.starts_with('-'))
+        .collect();
+    match program.as_str() {
+        "touch" | "mkdir" | "rm" | "rmdir" | "tee" | "truncate" => operands,
+        "cp" | "mv" | "ln" => operands.last().copied().into_iter().collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn evaluate_reports_matching_rule_for_paths_and_commands() -> Result<()> {
+        let workspace = tempfile::tempdir()?;
+        std::fs::create_dir(workspace.path().join(".git"))?;
+        let cwd = workspace.path();
+        let policy = SandboxPolicy::WorkspaceWrite {
+            writable_roots: Vec::new(),
+            network_access: false,
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+            max_memory_bytes: None,
+            cpu_rate_percent: None,
+            max_processes: None,
+        };
+        let root = AbsolutePathBuf::from_absolute_path(cwd)?;
+        let path = |path: &str| SandboxEvaluationTarget::Path { path: path.into() };
+        let command = |argv: &[&str]| SandboxEvaluationTarget::Command {
+            command: argv.iter().map(ToString::to_string).collect(),
+        };
+
+        let git = root.join(".git")?;
+        let lib = root.join("src/lib.rs")?;
+        let hook = root.join(".git/hooks/pre-commit")?;
+        let elsewhere = AbsolutePathBuf::resolve_path_against_base("../elsewhere", cwd)?;
+
+        let evaluations = [
+            path("src/lib.rs"),
+            path(".git/hooks/pre-commit"),
+            path("../elsewhere"),
+            command(&["curl", "https://example.com"]),
+            command(&["cp", "README.md", "/etc/motd"]),
+            command(&["ls", "-la"]),
+        ]
+        .iter()
+        .map(|target| evaluate_plain(&policy, target, cwd))
+        .collect::<Vec<_>>();
+        assert_eq!(
+            evaluations,
+            vec![
+                SandboxEvaluation {
+                    decision: SandboxDecision::Allow,
+                    rule: SandboxRule::WritableRoot { root: root.clone() },
+                    reason: format!(
+                        "{} is inside writable root {}",
+                        lib.display(),
+                        root.display()
+                    ),
+                },
+                SandboxEvaluation {
+                    decision: SandboxDecision::Deny,
+                    rule: SandboxRule::ReadOnlySubpath {
+                        root: root.clone(),
+                        subpath: git.clone(),
+                    },
+                    reason: format!(
+                        "{} is inside {}, which stays read-only under writable root {}",
+                        hook.display(),
+                        git.display(),
+                        root.display()
+                    ),
+                },
+                SandboxEvaluation {
+                    decision: SandboxDecision::Deny,
+                    rule: SandboxRule::OutsideWritableRoots,
+                    reason: format!("{} is outside every writable root", elsewhere.display()),
+                },
+                SandboxEvaluation {
+                    decision: SandboxDecision::Deny,
+                    rule: SandboxRule::NetworkRestricted,
+                    reason: "`curl` needs network access, which the policy does not allow"
+                        .to_string(),
+                },
+                SandboxEvaluation {
+                    decision: SandboxDecision::Deny,
+                    rule: SandboxRule::OutsideWritableRoots,
+                    reason: "/etc/motd is outside every writable root".to_string(),
+                },
+                SandboxEvaluation {
+                    decision: SandboxDecision::Allow,
+                    rule: SandboxRule::NoRestrictedEffects,
+                    reason: "`ls` does not obviously write files or use the network; anything it does write is still confined by the policy".to_string(),
+                },
+            ]
+        );
+
+        let read_only = evaluate_plain(&SandboxPolicy::ReadOnly, &path("src/lib.rs"), cwd);
+        assert_eq!(
+            read_only,
+            SandboxEvaluation {
+                decision: SandboxDecision::Deny,
+                rule: SandboxRule::ReadOnly,
+                reason: "the policy is read-only, so src/lib.rs is not writable".to_string(),
+            }
+        );
+        Ok(())
+    }
+}
@@ -6,13 +6,24 @@ sandbox placement and transformation of portable CommandSpec into a
 ready‑to‑spawn environment.
 */
 
+pub(crate) mod diagnosis;
+mod evaluation;
+
+use crate::bash::parse_shell_lc_plain_commands;
+use crate::config::Config;
 use crate::exec::ExecExpiration;
 use crate::exec::ExecToolCallOutput;
 use crate::exec::SandboxType;
 use crate::exec::StdoutStream;
 use crate::exec::execute_exec_env;
 use crate::landlock::create_linux_sandbox_command_args;
+use crate::protocol::SandboxBackend;
+use crate::protocol::SandboxDecision;
+use crate::protocol::SandboxEvaluation;
+use crate::protocol::SandboxEvaluationTarget;
 use crate::protocol::SandboxPolicy;
+use crate::protocol::SandboxPolicyReport;
+use crate::protocol::SandboxRule;
 #[cfg(target_os = "macos")]
 use crate::seatbelt::MACOS_PATH_TO_SEATBELT_EXECUTABLE;
 #[cfg(target_os = "macos")]
@@ -22,6 +33,8 @@ use crate::spawn::CODEX_SANDBOX_ENV_VAR;
 use crate::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR;
 use crate::tools::sandboxing::SandboxablePreference;
 pub use codex_protocol::models::SandboxPermissions;
+pub(crate) use evaluation::command_uses_network;
+use evaluation::evaluate_plain;
 use std::collections::HashMap;
 use std::path::Path;
 use std::path::PathBuf;
@@ -171,6 +184,66 @@ impl SandboxManager {
     }
 }
 
+/// Resolve what commands spawned under `config` in `cwd` can touch: the
+/// sandbox policy, writable roots after tmpdir and read-only exclusions,
+/// network access, and the platform backend that will enforce it.
+pub fn effective_policy_report(config: &Config, cwd: &Path) -> SandboxPolicyReport {
+    policy_report(config.sandbox_policy.get(), cwd)
+}
+
+pub(crate) fn policy_report(policy: &SandboxPolicy, cwd: &Path) -> SandboxPolicyReport {
+    let backend = match SandboxManager::new().select_initial(policy, SandboxablePreference::Auto) {
+        SandboxType::None => SandboxBackend::None,
+        SandboxType::MacosSeatbelt => SandboxBackend::MacosSeatbelt,
+        SandboxType::LinuxSeccomp => SandboxBackend::LinuxSeccomp,
+        SandboxType::WindowsRestrictedToken => SandboxBackend::WindowsRestrictedToken,
+    };
+    SandboxPolicyReport {
+        sandbox_policy: policy.clone(),
+        cwd: cwd.to_path_buf(),
+        full_disk_write_access: policy.has_full_disk_write_access(),
+        writable_roots: policy.get_writable_roots_with_cwd(cwd),
+        network_access: policy.has_full_network_access(),
+        backend,
+    }
+}
+
+/// Dry-run `target` against the sandbox policy of `config` from `cwd`.
+/// Commands wrapped in `bash -lc` are split into their plain commands, and the
+/// first one the policy denies decides the result.
+pub fn evaluate_policy(
+    config: &Config,
+    target: &SandboxEvaluationTarget,
+    cwd: &Path,
+) -> SandboxEvaluation {
+    evaluate(config.sandbox_policy.get(), target, cwd)
+}
+
+pub(crate) fn evaluate(
+    policy: &SandboxPolicy,
+    target: &SandboxEvaluationTarget,
+    cwd: &Path,
+) -> SandboxEvaluation {
+    let SandboxEvaluationTarget::Command { command } = target else {
+        return evaluate_plain(policy, target, cwd);
+    };
+    let Some(commands) = parse_shell_lc_plain_commands(command) else {
+        return evaluate_plain(policy, target, cwd);
+    };
+    let mut last = None;
+    for command in commands {
+        let evaluation = evaluate_plain(policy, &SandboxEvaluationTarget::Command { command }, cwd);
+        if evaluation.decision == SandboxDecision::Deny {
+            return evaluation;
+        }
+        // Prefer reporting a command that actually writes or uses the network.
+        if last.is_none() || evaluation.rule != SandboxRule::NoRestrictedEffects {
+            last = Some(evaluation);
+        }
+    }
+    last.unwrap_or_else(|| evaluate_plain(policy, target, cwd))
+}
+
 pub async fn execute_env(
     env: ExecEnv,
     policy: &SandboxPolicy,
@@ -178,3 +251,87 @@ pub async fn execute_env(
 ) -> crate::error::Result<ExecToolCallOutput> {
     execute_exec_env(env, policy, stdout_stream).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_utils_absolute_path::AbsolutePathBuf;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn read_only_report_has_no_writable_roots() {
+        let cwd = std::env::temp_dir();
+        let report = policy_report(&SandboxPolicy::ReadOnly, &cwd);
+
+        let backend = match crate::safety::get_platform_sandbox() {
+            Some(SandboxType::MacosSeatbelt) => SandboxBackend::MacosSeatbelt,
+            Some(SandboxType::LinuxSeccomp) => SandboxBackend::LinuxSeccomp,
+            Some(SandboxType::WindowsRestrictedToken) => SandboxBackend::WindowsRestrictedToken,
+            Some(SandboxType::None) | None => SandboxBackend::None,
+        };
+        assert_eq!(
+            report,
+            SandboxPolicyReport {
+                sandbox_policy: SandboxPolicy::ReadOnly,
+                cwd,
+                full_disk_write_access: false,
+                writable_roots: Vec::new(),
+                network_access: false,
+                backend,
+            }
+        );
+    }
+
+    #[test]
+    fn full_access_report_skips_platform_sandbox() {
+        let cwd = std::env::temp_dir();
+        let report = policy_report(&SandboxPolicy::DangerFullAccess, &cwd);
+
+        assert_eq!(
+            report,
+            SandboxPolicyReport {
+                sandbox_policy: SandboxPolicy::DangerFullAccess,
+                cwd,
+                full_disk_write_access: true,
+                writable_roots: Vec::new(),
+                network_access: true,
+                backend: SandboxBackend::None,
+            }
+        );
+    }
+
+    #[test]
+    fn evaluate_checks_every_command_in_a_shell_script() {
+        let cwd = std::env::temp_dir();
+        let policy = SandboxPolicy::new_workspace_write_policy();
+        let script = |script: &str| SandboxEvaluationTarget::Command {
+            command: vec!["bash".to_string(), "-lc".to_string(), script.to_string()],
+        };
+
+        let denied = evaluate(&policy, &script("ls && curl https://example.com"), &cwd);
+        assert_eq!(
+            denied,
+            SandboxEvaluation {
+                decision: SandboxDecision::Deny,
+                rule: SandboxRule::NetworkRestricted,
+                reason: "`curl` needs network access, which the policy does not allow".to_string(),
+            }
+        );
+
+        let root = AbsolutePathBuf::from_absolute_path(&cwd).expect("absolute cwd");
+        let notes = root.join("notes.txt").expect("notes path");
+        let allowed = evaluate(&policy, &script("touch notes.txt && ls"), &cwd);
+        assert_eq!(
+            allowed,
+            SandboxEvaluation {
+                decision: SandboxDecision::Allow,
+                rule: SandboxRule::WritableRoot { root: root.clone() },
+                reason: format!(
+                    "{} is inside writable root {}",
+                    notes.display(),
+                    root.display()
+                ),
+            }
+        );
+    }
+}
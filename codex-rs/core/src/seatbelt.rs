@@ -210,6 +210,9 @@ mod tests {
             network_access: false,
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
+            max_memory_bytes: None,
+            cpu_rate_percent: None,
+            max_processes: None,
         };
 
         // Create the Seatbelt command to wrap a shell command that tries to
@@ -394,6 +397,9 @@ mod tests {
             network_access: false,
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
+            max_memory_bytes: None,
+            cpu_rate_percent: None,
+            max_processes: None,
         };
 
         let shell_command: Vec<String> = [
@@ -477,6 +483,9 @@ mod tests {
             network_access: false,
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
+            max_memory_bytes: None,
+            cpu_rate_percent: None,
+            max_processes: None,
         };
 
         let shell_command: Vec<String> = [
@@ -0,0 +1,330 @@
+//! Per-session activity aggregates and a query that sums them across recorded
+//! sessions.
+//!
+//! A live session accumulates [`SessionStats`] in its state and writes them to
+//! the session meta line of its rollout on shutdown; resuming adds to the
+//! recorded values. [`query_session_stats`] reads those meta lines back.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use codex_protocol::protocol::ReviewDecision;
+use codex_protocol::protocol::SessionStats;
+use codex_protocol::protocol::TokenUsage;
+use time::Date;
+use time::macros::format_description;
+
+use crate::config::types::ModelPricing;
+use crate::rollout::ARCHIVED_SESSIONS_SUBDIR;
+use crate::rollout::SESSIONS_SUBDIR;
+use crate::rollout::list::parse_timestamp_uuid_from_filename;
+use crate::rollout::list::read_session_meta_line;
+
+const TOKENS_PER_PRICE_UNIT: f64 = 1_000_000.0;
+
+/// Accumulates [`SessionStats`] for the running session.
+#[derive(Debug)]
+pub(crate) struct SessionStatsTracker {
+    started_at: Instant,
+    stats: SessionStats,
+}
+
+impl SessionStatsTracker {
+    pub(crate) fn new() -> Self {
+        Self {
+            started_at: Instant::now(),
+            stats: SessionStats {
+                cost_usd: Some(0.0),
+                ..SessionStats::default()
+            },
+        }
+    }
+
+    pub(crate) fn record_turn(&mut self) {
+        self.stats.turns += 1;
+    }
+
+    pub(crate) fn record_tool_call(&mut self, tool_name: &str) {
+        *self
+            .stats
+            .tool_calls
+            .entry(tool_name.to_string())
+            .or_default() += 1;
+    }
+
+    /// Adds the usage of one model response. Without `pricing` the session
+    /// cost becomes unknown.
+    pub(crate) fn record_token_usage(
+        &mut self,
+        usage: &TokenUsage,
+        pricing: Option<&ModelPricing>,
+    ) {
+        self.stats.token_usage.add_assign(usage);
+        self.stats.cost_usd = match (self.stats.cost_usd, pricing) {
+            (Some(cost), Some(pricing)) => Some(cost + estimate_cost_usd(usage, pricing)),
+            _ => None,
+        };
+    }
+
+    pub(crate) fn record_approval_request(&mut self) {
+        self.stats.approvals_requested += 1;
+    }
+
+    pub(crate) fn record_approval_decision(&mut self, decision: &ReviewDecision) {
+        if matches!(
+            decision,
+            ReviewDecision::Approved
+                | ReviewDecision::ApprovedExecpolicyAmendment { .. }
+                | ReviewDecision::ApprovedForSession
+                | ReviewDecision::ApprovedAlways { .. }
+        ) {
+            self.stats.approvals_granted += 1;
+        }
+    }
+
+    /// The stats so far, with the wall time measured up to now.
+    pub(crate) fn snapshot(&self) -> SessionStats {
+        SessionStats {
+            wall_time_ms: u64::try_from(self.started_at.elapsed().as_millis()).unwrap_or(u64::MAX),
+            ..self.stats.clone()
+        }
+    }
+}
+
+/// Estimated cost in USD of `usage` at `pricing`.
+pub fn estimate_cost_usd(usage: &TokenUsage, pricing: &ModelPricing) -> f64 {
+    let cached = usage.cached_input_tokens.max(0) as f64;
+    let uncached = (usage.input_tokens - usage.cached_input_tokens).max(0) as f64;
+    let output = usage.output_tokens.max(0) as f64;
+    let cached_price = pricing.cached_input.unwrap_or(pricing.input);
+    (uncached * pricing.input + cached * cached_price + output * pricing.output)
+        / TOKENS_PER_PRICE_UNIT
+}
+
+/// Adds `other` into `total`. The cost stays known only if both are known.
+pub fn merge_session_stats(total: &mut SessionStats, other: &SessionStats) {
+    total.turns += other.turns;
+    for (tool, count) in &other.tool_calls {
+        *total.tool_calls.entry(tool.clone()).or_default() += count;
+    }
+    total.token_usage.add_assign(&other.token_usage);
+    total.cost_usd = total.cost_usd.zip(other.cost_usd).map(|(a, b)| a + b);
+    total.wall_time_ms = total.wall_time_ms.saturating_add(other.wall_time_ms);
+    total.approvals_requested += other.approvals_requested;
+    total.approvals_granted += other.approvals_granted;
+}
+
+/// How [`query_session_stats`] breaks down its totals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionStatsGrouping {
+    /// One group per calendar day (`YYYY-MM-DD`) the session started on.
+    Day,
+    /// One group per session working directory.
+    Project,
+}
+
+/// Which recorded sessions [`query_session_stats`] aggregates.
+#[derive(Debug, Clone, Default)]
+pub struct SessionStatsQuery {
+    /// First day to include, by session start date.
+    pub from: Option<Date>,
+    /// Last day to include, by session start date.
+    pub to: Option<Date>,
+    /// Only sessions that ran in this directory or below it.
+    pub cwd: Option<PathBuf>,
+    pub group_by: Option<SessionStatsGrouping>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionStatsReport {
+    /// Sessions that matched the query.
+    pub sessions: u64,
+    /// Matching sessions that have no recorded stats, e.g. because they are
+    /// still running or predate stats recording.
+    pub sessions_without_stats: u64,
+    pub totals: SessionStats,
+    /// Breakdown by [`SessionStatsQuery::group_by`], sorted by key.
+    pub groups: Vec<SessionStatsGroup>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionStatsGroup {
+    pub key: String,
+    pub sessions: u64,
+    pub stats: SessionStats,
+}
+
+/// Parses a `YYYY-MM-DD` day for [`SessionStatsQuery::from`] and
+/// [`SessionStatsQuery::to`].
+pub fn parse_stats_day(day: &str) -> Result<Date, String> {
+    Date::parse(day, format_description!("[year]-[month]-[day]"))
+        .map_err(|err| format!("invalid date `{day}`, expected YYYY-MM-DD: {err}"))
+}
+
+/// Sums the stats recorded in the session meta of every live and archived
+/// rollout under `codex_home` that matches `query`.
+pub async fn query_session_stats(
+    codex_home: &Path,
+    query: &SessionStatsQuery,
+) -> io::Result<SessionStatsReport> {
+    let mut rollouts = Vec::new();
+    collect_rollout_files(&codex_home.join(SESSIONS_SUBDIR), &mut rollouts).await?;
+    collect_rollout_files(&codex_home.join(ARCHIVED_SESSIONS_SUBDIR), &mut rollouts).await?;
+
+    let day_format = format_description!("[year]-[month]-[day]");
+    let mut report = SessionStatsReport {
+        totals: SessionStats {
+            cost_usd: Some(0.0),
+            ..SessionStats::default()
+        },
+        ..SessionStatsReport::default()
+    };
+    let mut groups: BTreeMap<String, SessionStatsGroup> = BTreeMap::new();
+    for (day, path) in rollouts {
+        if query.from.is_some_and(|from| day < from) || query.to.is_some_and(|to| day > to) {
+            continue;
+        }
+        let Ok(meta_line) = read_session_meta_line(&path).await else {
+            continue;
+        };
+        let meta = meta_line.meta;
+        if let Some(cwd) = &query.cwd
+            && !meta.cwd.starts_with(cwd)
+        {
+            continue;
+        }
+
+        report.sessions += 1;
+        let Some(stats) = meta.stats else {
+            report.sessions_without_stats += 1;
+            continue;
+        };
+        merge_session_stats(&mut report.totals, &stats);
+        let key = match query.group_by {
+            Some(SessionStatsGrouping::Day) => day.format(&day_format).map_err(io::Error::other)?,
+            Some(SessionStatsGrouping::Project) => meta.cwd.display().to_string(),
+            None => continue,
+        };
+        let group = groups
+            .entry(key.clone())
+            .or_insert_with(|| SessionStatsGroup {
+                key,
+                sessions: 0,
+                stats: SessionStats {
+                    cost_usd: Some(0.0),
+                    ..SessionStats::default()
+                },
+            });
+        group.sessions += 1;
+        merge_session_stats(&mut group.stats, &stats);
+    }
+    report.groups = groups.into_values().collect();
+    Ok(report)
+}
+
+/// Collects `(start day, path)` for every rollout file below `dir`, which may
+/// not exist.
+pub(crate) async fn collect_rollout_files(
+    dir: &Path,
+    out: &mut Vec<(Date, PathBuf)>,
+) -> io::Result<()> {
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let mut entries = match tokio::fs::read_dir(&dir).await {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err),
+        };
+        while let Some(entry) = entries.next_entry().await? {
+            let file_type = entry.file_type().await?;
+            if file_type.is_dir() {
+                pending.push(entry.path());
+                continue;
+            }
+            let file_name = entry.file_name();
+            if let Some(name) = file_name.to_str()
+                && name.starts_with("rollout-")
+                && name.ends_with(".jsonl")
+                && let Some((timestamp, _)) = parse_timestamp_uuid_from_filename(name)
+            {
+                out.push((timestamp.date(), entry.path()));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn usage(input: i64, cached: i64, output: i64) -> TokenUsage {
+        TokenUsage {
+            input_tokens: input,
+            cached_input_tokens: cached,
+            output_tokens: output,
+            reasoning_output_tokens: 0,
+            total_tokens: input + output,
+        }
+    }
+
+    #[test]
+    fn tracker_estimates_cost_only_while_every_model_is_priced() {
+        let pricing = ModelPricing {
+            input: 2.0,
+            cached_input: Some(0.5),
+            output: 10.0,
+        };
+        let mut tracker = SessionStatsTracker::new();
+        tracker.record_turn();
+        tracker.record_tool_call("shell");
+        tracker.record_tool_call("shell");
+        tracker.record_approval_request();
+        tracker.record_approval_decision(&ReviewDecision::Approved);
+        tracker.record_token_usage(&usage(1_000_000, 200_000, 100_000), Some(&pricing));
+
+        let stats = tracker.snapshot();
+        assert_eq!(stats.turns, 1);
+        assert_eq!(stats.tool_calls, BTreeMap::from([("shell".to_string(), 2)]));
+        assert_eq!((stats.approvals_requested, stats.approvals_granted), (1, 1));
+        assert_eq!(stats.cost_usd, Some(2.7));
+
+        tracker.record_token_usage(&usage(10, 0, 10), None);
+        assert_eq!(tracker.snapshot().cost_usd, None);
+    }
+
+    #[test]
+    fn merge_adds_counters_and_keeps_cost_only_when_both_known() {
+        let mut total = SessionStats {
+            turns: 2,
+            tool_calls: BTreeMap::from([("shell".to_string(), 1)]),
+            cost_usd: Some(1.0),
+            ..SessionStats::default()
+        };
+        merge_session_stats(
+            &mut total,
+            &SessionStats {
+                turns: 3,
+                tool_calls: BTreeMap::from([
+                    ("shell".to_string(), 2),
+                    ("apply_patch".to_string(), 1),
+                ]),
+                cost_usd: Some(0.5),
+                ..SessionStats::default()
+            },
+        );
+        assert_eq!(total.turns, 5);
+        assert_eq!(
+            total.tool_calls,
+            BTreeMap::from([("apply_patch".to_string(), 1), ("shell".to_string(), 3)])
+        );
+        assert_eq!(total.cost_usd, Some(1.5));
+
+        merge_session_stats(&mut total, &SessionStats::default());
+        assert_eq!(total.cost_usd, None);
+    }
+}
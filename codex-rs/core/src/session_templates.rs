@@ -0,0 +1,529 @@
+//! Reusable session blueprints ("triage this issue", "write release notes")
+//! stored as TOML files under `$CODEX_HOME/templates` or
+//! `<repo>/.codex/templates`. A template names the initial prompt, with
+//! `$NAME` placeholders filled from arguments, plus an optional config
+//! profile, tools the session must have, and files to attach to the first
+//! message. Project templates shadow user templates of the same name, and
+//! are only loaded from projects that are trusted, like the rest of a
+//! project's `.codex` folder. Context files must stay inside the directory
+//! they are resolved against.
+//!
+//! ```toml
+//! description = "Triage a GitHub issue"
+//! prompt = "Triage $ISSUE. Reproduce it, find the cause and propose a fix."
+//! profile = "triage"
+//! required_tools = ["github"]
+//! context_files = ["docs/triage.md"]
+//!
+//! [arguments.ISSUE]
+//! description = "Issue URL"
+//! ```
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_app_server_protocol::ConfigLayerSource;
+use codex_utils_absolute_path::AbsolutePathBuf;
+use serde::Deserialize;
+use tracing::warn;
+
+use crate::config_loader::ConfigLayerStackOrdering;
+use crate::config_loader::LoaderOverrides;
+use crate::config_loader::load_config_layers_state;
+use crate::truncate::TruncationPolicy;
+use crate::truncate::truncate_text;
+
+pub const TEMPLATES_DIR: &str = "templates";
+
+/// Upper bound on the contents of each attached context file.
+const CONTEXT_FILE_MAX_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionTemplateScope {
+    User,
+    Project,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionTemplate {
+    pub name: String,
+    pub path: PathBuf,
+    pub scope: SessionTemplateScope,
+    pub description: Option<String>,
+    pub prompt: String,
+    /// Config profile to start the session with.
+    pub profile: Option<String>,
+    /// Tools (built-in tool names or MCP server names) the session must have.
+    pub required_tools: Vec<String>,
+    /// Files attached to the first message, resolved against `context_root`.
+    pub context_files: Vec<PathBuf>,
+    /// The directory that holds the `.codex` folder (user templates: the
+    /// session cwd). Context files outside it are refused.
+    pub context_root: PathBuf,
+    pub arguments: Vec<SessionTemplateArgument>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionTemplateArgument {
+    pub name: String,
+    pub description: Option<String>,
+    /// Used when the caller does not pass the argument. Arguments without a
+    /// default are required.
+    pub default: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TemplateToml {
+    description: Option<String>,
+    prompt: String,
+    profile: Option<String>,
+    #[serde(default)]
+    required_tools: Vec<String>,
+    #[serde(default)]
+    context_files: Vec<PathBuf>,
+    #[serde(default)]
+    arguments: BTreeMap<String, TemplateArgumentToml>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TemplateArgumentToml {
+    description: Option<String>,
+    default: Option<String>,
+}
+
+/// Templates visible from `cwd`, sorted by name. Unreadable or malformed
+/// files are skipped with a warning.
+pub async fn discover_templates(codex_home: &Path, cwd: &Path) -> Vec<SessionTemplate> {
+    let mut sources = vec![(
+        codex_home.join(TEMPLATES_DIR),
+        SessionTemplateScope::User,
+        cwd.to_path_buf(),
+    )];
+    sources.extend(
+        trusted_project_folders(codex_home, cwd)
+            .await
+            .into_iter()
+            .filter_map(|dot_codex| {
+                let base = dot_codex.parent()?.to_path_buf();
+                Some((
+                    dot_codex.join(TEMPLATES_DIR),
+                    SessionTemplateScope::Project,
+                    base,
+                ))
+            }),
+    );
+    let mut templates: BTreeMap<String, SessionTemplate> = BTreeMap::new();
+    for (dir, scope, base) in sources {
+        for template in load_templates_in(&dir, scope, &base).await {
+            templates.insert(template.name.clone(), template);
+        }
+    }
+    templates.into_values().collect()
+}
+
+/// The `.codex` folders between the project root and `cwd`, root first, when
+/// the project is trusted; the config loader only yields project layers then.
+async fn trusted_project_folders(codex_home: &Path, cwd: &Path) -> Vec<PathBuf> {
+    let cwd = match AbsolutePathBuf::try_from(cwd) {
+        Ok(cwd) => cwd,
+        Err(err) => {
+            warn!("not loading project templates for {}: {err}", cwd.display());
+            return Vec::new();
+        }
+    };
+    let layers = match load_config_layers_state(
+        codex_home,
+        Some(cwd),
+        &[],
+        LoaderOverrides::default(),
+    )
+    .await
+    {
+        Ok(layers) => layers,
+        Err(err) => {
+            warn!("not loading project templates: {err}");
+            return Vec::new();
+        }
+    };
+    layers
+        .get_layers(ConfigLayerStackOrdering::LowestPrecedenceFirst)
+        .into_iter()
+        .filter_map(|layer| match &layer.name {
+            ConfigLayerSource::Project { dot_codex_folder } => {
+                Some(dot_codex_folder.as_path().to_path_buf())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+pub async fn find_template(codex_home: &Path, cwd: &Path, name: &str) -> Option<SessionTemplate> {
+    discover_templates(codex_home, cwd)
+        .await
+        .into_iter()
+        .find(|template| template.name == name)
+}
+
+async fn load_templates_in(
+    dir: &Path,
+    scope: SessionTemplateScope,
+    base: &Path,
+) -> Vec<SessionTemplate> {
+    let mut out = Vec::new();
+    let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+        return out;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("toml") {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        let Ok(contents) = tokio::fs::read_to_string(&path).await else {
+            continue;
+        };
+        match parse_template(name, &path, scope, base, &contents) {
+            Ok(template) => out.push(template),
+            Err(err) => warn!("ignoring session template {}: {err}", path.display()),
+        }
+    }
+    out
+}
+
+fn parse_template(
+    name: &str,
+    path: &Path,
+    scope: SessionTemplateScope,
+    base: &Path,
+    contents: &str,
+) -> Result<SessionTemplate, toml::de::Error> {
+    let TemplateToml {
+        description,
+        prompt,
+        profile,
+        required_tools,
+        context_files,
+        arguments: mut declared,
+    } = toml::from_str(contents)?;
+    let mut arguments = placeholders(&prompt)
+        .into_iter()
+        .map(|name| {
+            let declared = declared.remove(&name);
+            SessionTemplateArgument {
+                name,
+                description: declared.as_ref().and_then(|arg| arg.description.clone()),
+                default: declared.and_then(|arg| arg.default),
+            }
+        })
+        .collect::<Vec<_>>();
+    // Declared arguments that the prompt does not use are still listed so
+    // callers can pass them without an error.
+    arguments.extend(
+        declared
+            .into_iter()
+            .map(|(name, arg)| SessionTemplateArgument {
+                name,
+                description: arg.description,
+                default: arg.default,
+            }),
+    );
+    Ok(SessionTemplate {
+        name: name.to_string(),
+        path: path.to_path_buf(),
+        scope,
+        description,
+        prompt,
+        profile,
+        required_tools,
+        context_files: context_files
+            .into_iter()
+            .map(|file| base.join(file))
+            .collect(),
+        context_root: base.to_path_buf(),
+        arguments,
+    })
+}
+
+/// `$NAME` placeholders in `prompt`, in order of first use. `$$NAME` is a
+/// literal `$NAME`.
+fn placeholders(prompt: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    scan_placeholders(prompt, |name| {
+        if !names.iter().any(|known| known == name) {
+            names.push(name.to_string());
+        }
+        None
+    });
+    names
+}
+
+/// Walks `prompt`, replacing each `$NAME` with `replace(NAME)` when it
+/// returns a value and unescaping `$$`.
+fn scan_placeholders(prompt: &str, mut replace: impl FnMut(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(prompt.len());
+    let mut rest = prompt;
+    while let Some(index) = rest.find('$') {
+        out.push_str(&rest[..index]);
+        let after = &rest[index + 1..];
+        if let Some(escaped) = after.strip_prefix('$') {
+            out.push('$');
+            rest = escaped;
+            continue;
+        }
+        let len = after
+            .char_indices()
+            .take_while(|(i, c)| {
+                c.is_ascii_uppercase() || (*i > 0 && (c.is_ascii_digit() || *c == '_'))
+            })
+            .count();
+        let name = &after[..len];
+        match (len > 0).then(|| replace(name)).flatten() {
+            Some(value) => out.push_str(&value),
+            None => {
+                out.push('$');
+                out.push_str(name);
+            }
+        }
+        rest = &after[len..];
+    }
+    out.push_str(rest);
+    out
+}
+
+impl SessionTemplate {
+    /// The prompt with every placeholder filled in from `arguments` or the
+    /// declared defaults.
+    pub fn render_prompt(&self, arguments: &HashMap<String, String>) -> Result<String, String> {
+        if let Some(unknown) = arguments
+            .keys()
+            .find(|name| !self.arguments.iter().any(|arg| &arg.name == *name))
+        {
+            return Err(format!(
+                "template `{}` has no argument `{unknown}`",
+                self.name
+            ));
+        }
+        let mut missing = Vec::new();
+        let mut values = HashMap::new();
+        for arg in &self.arguments {
+            match arguments.get(&arg.name).or(arg.default.as_ref()) {
+                Some(value) => {
+                    values.insert(arg.name.as_str(), value.as_str());
+                }
+                None => missing.push(arg.name.as_str()),
+            }
+        }
+        if !missing.is_empty() {
+            return Err(format!(
+                "template `{}` is missing required arguments: {}",
+                self.name,
+                missing.join(", ")
+            ));
+        }
+        Ok(scan_placeholders(&self.prompt, |name| {
+            values.get(name).map(ToString::to_string)
+        }))
+    }
+
+    /// The context files rendered as text to attach to the first message.
+    /// Fails for a file that resolves, through `..` or symlinks, to a path
+    /// outside `context_root`.
+    pub async fn read_context_files(&self) -> Result<Vec<String>, String> {
+        let root = tokio::fs::canonicalize(&self.context_root)
+            .await
+            .map_err(|err| {
+                format!(
+                    "template `{}` could not resolve {}: {err}",
+                    self.name,
+                    self.context_root.display()
+                )
+            })?;
+        let mut attachments = Vec::new();
+        for path in &self.context_files {
+            let canonical = tokio::fs::canonicalize(path).await.map_err(|err| {
+                format!(
+                    "template `{}` could not read context file {}: {err}",
+                    self.name,
+                    path.display()
+                )
+            })?;
+            if !canonical.starts_with(&root) {
+                return Err(format!(
+                    "template `{}` context file {} is outside {}",
+                    self.name,
+                    path.display(),
+                    self.context_root.display()
+                ));
+            }
+            let contents = tokio::fs::read_to_string(&canonical).await.map_err(|err| {
+                format!(
+                    "template `{}` could not read context file {}: {err}",
+                    self.name,
+                    path.display()
+                )
+            })?;
+            let contents =
+                truncate_text(&contents, TruncationPolicy::Bytes(CONTEXT_FILE_MAX_BYTES));
+            attachments.push(format!(
+                "<context_file path=\"{}\">\n{contents}\n</context_file>",
+                path.display()
+            ));
+        }
+        Ok(attachments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::set_project_trust_level;
+    use codex_protocol::config_types::TrustLevel;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn project_templates_shadow_user_templates() -> anyhow::Result<()> {
+        let codex_home = tempfile::tempdir()?;
+        let project = tempfile::tempdir()?;
+        set_project_trust_level(codex_home.path(), project.path(), TrustLevel::Trusted)?;
+        let user_dir = codex_home.path().join(TEMPLATES_DIR);
+        let project_dir = project.path().join(".codex").join(TEMPLATES_DIR);
+        std::fs::create_dir_all(&user_dir)?;
+        std::fs::create_dir_all(&project_dir)?;
+        std::fs::write(user_dir.join("triage.toml"), "prompt = \"user\"\n")?;
+        std::fs::write(user_dir.join("notes.toml"), "prompt = \"notes\"\n")?;
+        std::fs::write(user_dir.join("broken.toml"), "prompt = 1\n")?;
+        std::fs::write(
+            project_dir.join("triage.toml"),
+            "prompt = \"Triage $ISSUE\"\ncontext_files = [\"docs/triage.md\"]\n",
+        )?;
+
+        let templates = discover_templates(codex_home.path(), project.path()).await;
+        let summary = templates
+            .iter()
+            .map(|template| (template.name.as_str(), template.scope))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                ("notes", SessionTemplateScope::User),
+                ("triage", SessionTemplateScope::Project),
+            ]
+        );
+        assert_eq!(
+            templates[1].context_files,
+            vec![project.path().join("docs/triage.md")]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn untrusted_projects_contribute_no_templates() -> std::io::Result<()> {
+        let codex_home = tempfile::tempdir()?;
+        let project = tempfile::tempdir()?;
+        let project_dir = project.path().join(".codex").join(TEMPLATES_DIR);
+        std::fs::create_dir_all(&project_dir)?;
+        std::fs::write(project_dir.join("triage.toml"), "prompt = \"project\"\n")?;
+
+        assert_eq!(
+            discover_templates(codex_home.path(), project.path()).await,
+            Vec::new()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn context_files_must_stay_inside_the_context_root() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let project = dir.path().join("project");
+        let secret = dir.path().join("secret.txt");
+        std::fs::write(&secret, "secret")?;
+        std::fs::create_dir_all(project.join("docs"))?;
+        std::fs::write(project.join("docs/triage.md"), "steps")?;
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&secret, project.join("docs/link.md"))?;
+
+        let template_with = |file: &Path| {
+            parse_template(
+                "triage",
+                Path::new("triage.toml"),
+                SessionTemplateScope::Project,
+                &project,
+                &format!(
+                    "prompt = \"Triage\"\ncontext_files = [{:?}]\n",
+                    file.to_string_lossy()
+                ),
+            )
+            .expect("valid template")
+        };
+
+        let attachments = template_with(Path::new("docs/triage.md"))
+            .read_context_files()
+            .await
+            .expect("file inside the project");
+        assert_eq!(
+            attachments,
+            vec![format!(
+                "<context_file path=\"{}\">\nsteps\n</context_file>",
+                project.join("docs/triage.md").display()
+            )]
+        );
+
+        let mut escapes = vec![PathBuf::from("../secret.txt"), secret.clone()];
+        if cfg!(unix) {
+            escapes.push(PathBuf::from("docs/link.md"));
+        }
+        for file in escapes {
+            let err = template_with(&file)
+                .read_context_files()
+                .await
+                .expect_err("file outside the project");
+            assert!(err.contains("is outside"), "{err}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn renders_arguments_and_defaults() {
+        let template = parse_template(
+            "release",
+            Path::new("release.toml"),
+            SessionTemplateScope::User,
+            Path::new("/repo"),
+            r#"
+prompt = "Write notes for $VERSION since $SINCE. Costs $$5."
+[arguments.SINCE]
+default = "the last tag"
+"#,
+        )
+        .expect("valid template");
+        assert_eq!(
+            template
+                .arguments
+                .iter()
+                .map(|arg| (arg.name.as_str(), arg.default.as_deref()))
+                .collect::<Vec<_>>(),
+            vec![("VERSION", None), ("SINCE", Some("the last tag"))]
+        );
+
+        let args = HashMap::from([("VERSION".to_string(), "1.2.0".to_string())]);
+        assert_eq!(
+            template.render_prompt(&args),
+            Ok("Write notes for 1.2.0 since the last tag. Costs $5.".to_string())
+        );
+        assert_eq!(
+            template.render_prompt(&HashMap::new()),
+            Err("template `release` is missing required arguments: VERSION".to_string())
+        );
+        let unknown = HashMap::from([("TYPO".to_string(), String::new())]);
+        assert_eq!(
+            template.render_prompt(&unknown),
+            Err("template `release` has no argument `TYPO`".to_string())
+        );
+    }
+}
@@ -0,0 +1,167 @@
+//! Automatic session titles derived from the first exchange of a session.
+//!
+//! Titles are generated locally with a small heuristic over the first user
+//! message so that conversation lists and the resume picker show something
+//! more useful than a raw prompt, without spending an extra model call.
+
+use std::sync::Arc;
+
+use tracing::warn;
+
+use crate::codex::Session;
+use crate::features::Feature;
+use crate::rollout::SessionMetadataUpdate;
+
+const MIN_TITLE_WORDS: usize = 6;
+const MAX_TITLE_WORDS: usize = 10;
+
+/// Leading phrases that carry no information about the task itself.
+const FILLER_PREFIXES: &[&str] = &[
+    "can you please",
+    "could you please",
+    "would you please",
+    "can you",
+    "could you",
+    "would you",
+    "i want you to",
+    "i need you to",
+    "i'd like you to",
+    "help me",
+    "please",
+    "hey",
+    "hi",
+];
+
+/// Request a title for the session once its first turn has completed. Does
+/// nothing when the feature is disabled or a title was already requested.
+pub(crate) async fn maybe_spawn_auto_title(sess: &Arc<Session>, input_messages: &[String]) {
+    if !sess.enabled(Feature::AutoSessionTitle) {
+        return;
+    }
+    if !sess.take_auto_title_request().await {
+        return;
+    }
+    let Some(title) = input_messages
+        .first()
+        .and_then(|message| title_from_first_message(message))
+    else {
+        return;
+    };
+
+    let sess = Arc::clone(sess);
+    tokio::spawn(async move {
+        let update = SessionMetadataUpdate {
+            title: Some(title),
+            tags: None,
+            keep_existing_title: true,
+            stats: None,
+        };
+        if let Err(err) = sess.update_session_metadata(update).await {
+            warn!("failed to store automatic session title: {err}");
+        }
+    });
+}
+
+/// Build a short title ([`MIN_TITLE_WORDS`] to [`MAX_TITLE_WORDS`] words)
+/// from the first line of the user's opening message. A first line that is
+/// too short is continued with the following lines; returns `None` when the
+/// whole message is still too short, leaving the session untitled.
+pub(crate) fn title_from_first_message(message: &str) -> Option<String> {
+    let mut lines = message
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| line.is_empty());
+    let first_line = lines.next()?;
+    let mut text = first_line.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    while let Some(rest) = strip_filler_prefix(&text) {
+        text = rest.to_string();
+    }
+
+    let mut words: Vec<&str> = text.split_whitespace().collect();
+    for line in lines {
+        if words.len() >= MIN_TITLE_WORDS {
+            break;
+        }
+        words.extend(line.split_whitespace());
+    }
+    if words.len() < MIN_TITLE_WORDS {
+        return None;
+    }
+    let mut title = if words.len() > MAX_TITLE_WORDS {
+        format!("{}…", words[..MAX_TITLE_WORDS].join(" "))
+    } else {
+        words.join(" ")
+    };
+    let trimmed_len = title
+        .trim_end_matches(|c: char| matches!(c, '.' | '?' | '!' | ',' | ':' | ';'))
+        .len();
+    title.truncate(trimmed_len);
+    if title.is_empty() {
+        return None;
+    }
+
+    let mut chars = title.chars();
+    let first = chars.next()?;
+    Some(first.to_uppercase().chain(chars).collect())
+}
+
+fn strip_filler_prefix(text: &str) -> Option<&str> {
+    FILLER_PREFIXES.iter().find_map(|prefix| {
+        let head = text.get(..prefix.len())?;
+        let rest = &text[prefix.len()..];
+        if !head.eq_ignore_ascii_case(prefix)
+            || rest.chars().next().is_some_and(char::is_alphanumeric)
+        {
+            return None;
+        }
+        let rest = rest.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+        (!rest.is_empty()).then_some(rest)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn strips_fillers_and_capitalizes() {
+        assert_eq!(
+            title_from_first_message("can you please fix the flaky login test on CI?"),
+            Some("Fix the flaky login test on CI".to_string())
+        );
+        assert_eq!(
+            title_from_first_message("Hi, please add a --json flag to exec"),
+            Some("Add a --json flag to exec".to_string())
+        );
+    }
+
+    #[test]
+    fn uses_first_non_empty_line_and_caps_length() {
+        let message = "\n  refactor the rollout recorder so that writes are batched and flushed on a timer\n\nmore details";
+        assert_eq!(
+            title_from_first_message(message),
+            Some("Refactor the rollout recorder so that writes are batched and…".to_string())
+        );
+    }
+
+    #[test]
+    fn keeps_words_that_only_start_with_a_filler() {
+        assert_eq!(
+            title_from_first_message("highlight syntax errors in the diff view"),
+            Some("Highlight syntax errors in the diff view".to_string())
+        );
+        assert_eq!(title_from_first_message("   \n "), None);
+    }
+
+    #[test]
+    fn continues_short_first_lines_and_skips_short_messages() {
+        assert_eq!(
+            title_from_first_message("Bug:\nthe resume picker shows raw prompts\nthanks"),
+            Some("Bug: the resume picker shows raw prompts".to_string())
+        );
+        assert_eq!(title_from_first_message("please fix the build"), None);
+        assert_eq!(title_from_first_message("please"), None);
+    }
+}
@@ -0,0 +1,254 @@
+//! Remembers which version of each file the model has read so that a later
+//! apply_patch can warn when the file changed on disk in the meantime, e.g.
+//! because the user kept editing it while the agent was working.
+//!
+//! Reads are recorded from `read_file` and from shell commands recognized as
+//! reading a file (`cat`, `sed -n`, `head`, ...). Paths are canonicalized, so
+//! a file reached through a symlink or a relative path is the same entry.
+//!
+//! Unlike the per-turn read snapshots used to rebase patches, these are only
+//! content hashes, so they are cheap to keep for the whole session.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::parse_command::ParsedCommand;
+use sha2::Digest;
+use sha2::Sha256;
+
+use crate::truncate::TruncationPolicy;
+use crate::truncate::truncate_text;
+
+/// Upper bound on the file contents inlined into a staleness warning.
+const REFRESHED_CONTENTS_MAX_BYTES: usize = 16 * 1024;
+
+/// Files larger than this are not hashed after a shell read; a command that
+/// printed them was most likely truncated before the model saw them anyway.
+const SHELL_READ_MAX_BYTES: u64 = 8 * 1024 * 1024;
+
+fn content_hash(contents: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(contents))
+}
+
+/// `path` with symlinks and `..` resolved. A path that no longer exists is
+/// resolved through its parent directory, and kept as is if that fails too.
+pub(crate) async fn canonical_path(path: &Path) -> PathBuf {
+    if let Ok(canonical) = tokio::fs::canonicalize(path).await {
+        return canonical;
+    }
+    if let (Some(parent), Some(name)) = (path.parent(), path.file_name())
+        && let Ok(parent) = tokio::fs::canonicalize(parent).await
+    {
+        return parent.join(name);
+    }
+    path.to_path_buf()
+}
+
+/// Files that shell commands run from `cwd` read, with their current
+/// contents. Files that are missing, not regular or too large are skipped.
+pub(crate) async fn shell_reads(
+    parsed_cmd: &[ParsedCommand],
+    cwd: &Path,
+) -> Vec<(PathBuf, Vec<u8>)> {
+    let mut reads = Vec::new();
+    for parsed in parsed_cmd {
+        let ParsedCommand::Read { path, .. } = parsed else {
+            continue;
+        };
+        let path = cwd.join(path);
+        let Ok(metadata) = tokio::fs::metadata(&path).await else {
+            continue;
+        };
+        if !metadata.is_file() || metadata.len() > SHELL_READ_MAX_BYTES {
+            continue;
+        }
+        if let Ok(contents) = tokio::fs::read(&path).await {
+            reads.push((path, contents));
+        }
+    }
+    reads
+}
+
+/// Content hashes of the files the model has seen, keyed by canonical path.
+#[derive(Debug, Default)]
+pub(crate) struct FileReadHashes {
+    hashes: HashMap<PathBuf, String>,
+}
+
+impl FileReadHashes {
+    /// Records `contents` as what the model last saw of the canonical `path`.
+    pub(crate) fn record(&mut self, path: PathBuf, contents: &[u8]) {
+        self.hashes.insert(path, content_hash(contents));
+    }
+
+    pub(crate) fn forget(&mut self, path: &Path) {
+        self.hashes.remove(path);
+    }
+
+    /// The recorded hashes for those of the canonical `paths` the model has
+    /// seen.
+    pub(crate) fn recorded(&self, paths: &[PathBuf]) -> Vec<(PathBuf, String)> {
+        paths
+            .iter()
+            .filter_map(|path| Some((path.clone(), self.hashes.get(path)?.clone())))
+            .collect()
+    }
+}
+
+/// A file that changed on disk since the model last saw it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct StaleRead {
+    pub(crate) path: PathBuf,
+    /// Current contents, or `None` when the file was deleted or is unreadable.
+    pub(crate) contents: Option<String>,
+}
+
+/// Compares the `recorded` hashes against the files on disk.
+pub(crate) async fn find_stale_reads(recorded: Vec<(PathBuf, String)>) -> Vec<StaleRead> {
+    let mut stale = Vec::new();
+    for (path, hash) in recorded {
+        let contents = tokio::fs::read(&path).await.ok();
+        if contents.as_deref().map(content_hash) == Some(hash) {
+            continue;
+        }
+        stale.push(StaleRead {
+            contents: contents.map(|bytes| String::from_utf8_lossy(&bytes).into_owned()),
+            path,
+        });
+    }
+    stale
+}
+
+/// Warning for the model about `stale` files. With `include_contents` the
+/// current contents are inlined so the model does not have to read them again.
+pub(crate) fn format_stale_read_warning(stale: &[StaleRead], include_contents: bool) -> String {
+    let paths = stale
+        .iter()
+        .map(|read| read.path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let it = if stale.len() == 1 { "it" } else { "them" };
+    let mut warning = format!(
+        "Warning: {paths} changed on disk since you last read {it}. Someone else may be editing {it}; re-read {it} before making further changes."
+    );
+    if !include_contents {
+        return warning;
+    }
+    for read in stale {
+        let path = read.path.display();
+        match &read.contents {
+            Some(contents) => {
+                let contents = truncate_text(
+                    contents,
+                    TruncationPolicy::Bytes(REFRESHED_CONTENTS_MAX_BYTES),
+                );
+                warning.push_str(&format!(
+                    "\n\nCurrent contents of {path} (before this patch):\n{contents}"
+                ));
+            }
+            None => warning.push_str(&format!("\n\n{path} no longer exists.")),
+        }
+    }
+    warning
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn reports_files_that_changed_since_they_were_read() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let unchanged = dir.path().join("unchanged.txt");
+        let edited = dir.path().join("edited.txt");
+        let deleted = dir.path().join("deleted.txt");
+        let never_read = dir.path().join("never_read.txt");
+        for path in [&unchanged, &edited, &deleted, &never_read] {
+            std::fs::write(path, "original\n")?;
+        }
+
+        let mut hashes = FileReadHashes::default();
+        for path in [&unchanged, &edited, &deleted] {
+            hashes.record(path.clone(), b"original\n");
+        }
+        std::fs::write(&edited, "edited by the user\n")?;
+        std::fs::write(&never_read, "edited by the user\n")?;
+        std::fs::remove_file(&deleted)?;
+
+        let paths = vec![unchanged, edited.clone(), deleted.clone(), never_read];
+        let stale = find_stale_reads(hashes.recorded(&paths)).await;
+        assert_eq!(
+            stale,
+            vec![
+                StaleRead {
+                    path: edited.clone(),
+                    contents: Some("edited by the user\n".to_string()),
+                },
+                StaleRead {
+                    path: deleted.clone(),
+                    contents: None,
+                },
+            ]
+        );
+
+        let warning = format_stale_read_warning(&stale, true);
+        assert!(warning.contains(&format!("Current contents of {}", edited.display())));
+        assert!(warning.contains(&format!("{} no longer exists.", deleted.display())));
+        assert!(!format_stale_read_warning(&stale, false).contains("edited by the user"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn canonical_path_resolves_relative_components_and_deleted_files() -> std::io::Result<()>
+    {
+        let dir = tempfile::tempdir()?;
+        let root = tokio::fs::canonicalize(dir.path()).await?;
+        std::fs::create_dir(root.join("src"))?;
+        std::fs::write(root.join("src/lib.rs"), "")?;
+
+        assert_eq!(
+            canonical_path(&root.join("src/../src/lib.rs")).await,
+            root.join("src/lib.rs")
+        );
+        assert_eq!(
+            canonical_path(&root.join("src/../src/deleted.rs")).await,
+            root.join("src/deleted.rs")
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn shell_reads_returns_files_read_by_recognized_commands() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::create_dir(dir.path().join("src"))?;
+        std::fs::write(dir.path().join("src/lib.rs"), "fn main() {}\n")?;
+        let parsed_cmd = vec![
+            ParsedCommand::Read {
+                cmd: "cat src/lib.rs".to_string(),
+                name: "lib.rs".to_string(),
+                path: PathBuf::from("src/lib.rs"),
+            },
+            ParsedCommand::Read {
+                cmd: "cat src".to_string(),
+                name: "src".to_string(),
+                path: PathBuf::from("src"),
+            },
+            ParsedCommand::Read {
+                cmd: "cat missing.rs".to_string(),
+                name: "missing.rs".to_string(),
+                path: PathBuf::from("missing.rs"),
+            },
+            ParsedCommand::Unknown {
+                cmd: "cargo build".to_string(),
+            },
+        ];
+
+        assert_eq!(
+            shell_reads(&parsed_cmd, dir.path()).await,
+            vec![(dir.path().join("src/lib.rs"), b"fn main() {}\n".to_vec())]
+        );
+        Ok(())
+    }
+}
@@ -3,7 +3,9 @@ mod session;
 mod turn;
 
 pub(crate) use service::SessionServices;
+pub(crate) use session::InterruptedTurnProgress;
 pub(crate) use session::SessionState;
+pub(crate) use session::TurnCheckpoint;
 pub(crate) use turn::ActiveTurn;
 pub(crate) use turn::RunningTask;
 pub(crate) use turn::TaskKind;
@@ -3,9 +3,16 @@ use std::sync::Arc;
 use crate::AuthManager;
 use crate::RolloutRecorder;
 use crate::agent::AgentControl;
+use crate::approval_memory::ApprovalMemory;
+use crate::background_compaction::BackgroundCompactor;
+use crate::error_fingerprint::ErrorFingerprints;
+use crate::event_socket::EventSocket;
 use crate::exec_policy::ExecPolicyManager;
+use crate::heartbeat::TurnActivity;
 use crate::mcp_connection_manager::McpConnectionManager;
 use crate::models_manager::manager::ModelsManager;
+use crate::project_map::ProjectMapCache;
+use crate::repl::ReplManager;
 use crate::skills::SkillsManager;
 use crate::tools::sandboxing::ApprovalStore;
 use crate::unified_exec::UnifiedExecProcessManager;
@@ -19,6 +26,8 @@ pub(crate) struct SessionServices {
     pub(crate) mcp_connection_manager: Arc<RwLock<McpConnectionManager>>,
     pub(crate) mcp_startup_cancellation_token: Mutex<CancellationToken>,
     pub(crate) unified_exec_manager: UnifiedExecProcessManager,
+    /// Interpreter behind the `repl` tool, started on first use.
+    pub(crate) repl: ReplManager,
     pub(crate) notifier: UserNotifier,
     pub(crate) rollout: Mutex<Option<RolloutRecorder>>,
     pub(crate) user_shell: Arc<crate::shell::Shell>,
@@ -28,6 +37,17 @@ pub(crate) struct SessionServices {
     pub(crate) models_manager: Arc<ModelsManager>,
     pub(crate) otel_manager: OtelManager,
     pub(crate) tool_approvals: Mutex<ApprovalStore>,
+    /// "Always allow" approvals, consulted before prompting.
+    pub(crate) approval_memory: ApprovalMemory,
+    /// Fingerprints and recurrence counts attached to error events.
+    pub(crate) error_fingerprints: ErrorFingerprints,
+    pub(crate) project_map: ProjectMapCache,
     pub(crate) skills_manager: Arc<SkillsManager>,
     pub(crate) agent_control: AgentControl,
+    /// Read-only tee of the event stream, when `event_socket` is enabled.
+    pub(crate) event_socket: Option<EventSocket>,
+    /// Liveness of the running turn, reported by heartbeat events.
+    pub(crate) turn_activity: Arc<TurnActivity>,
+    /// Summary of older turns prepared while the session was idle.
+    pub(crate) background_compaction: BackgroundCompactor,
 }
@@ -1,13 +1,47 @@
 //! Session-wide mutable state.
 
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use codex_git::GhostCommit;
+use codex_protocol::models::ContentItem;
 use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::CancelledToolCall;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::ExplorationFindingsEvent;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::TurnRequestSnapshot;
 
+use crate::client_common::Prompt;
+use crate::clipboard::Clipboard;
 use crate::codex::SessionConfiguration;
 use crate::context_manager::ContextManager;
+use crate::context_manager::is_user_turn_boundary;
 use crate::protocol::RateLimitSnapshot;
 use crate::protocol::TokenUsage;
 use crate::protocol::TokenUsageInfo;
+use crate::session_stats::SessionStatsTracker;
+use crate::stale_reads::FileReadHashes;
+use crate::tokenizer::Tokenizer;
+use crate::tool_output_chunks::ToolOutputChunks;
 use crate::truncate::TruncationPolicy;
+use crate::turn_snapshot::build_turn_request_snapshot;
+
+/// Number of turns whose model request is kept for debugging.
+const MAX_TURN_REQUEST_SNAPSHOTS: usize = 8;
+
+/// Number of most recent user messages that can be edited.
+const MAX_RECORDED_USER_MESSAGES: usize = 256;
+
+/// The last model request of a turn. The prompt is shared with the request
+/// itself, and the [`TurnRequestSnapshot`] is only built when asked for.
+struct TurnRequest {
+    turn_id: String,
+    model: String,
+    request_count: u32,
+    prompt: Arc<Prompt>,
+}
 
 /// Persistent, session-scoped state previously stored directly on `Session`.
 pub(crate) struct SessionState {
@@ -15,6 +49,57 @@ pub(crate) struct SessionState {
     pub(crate) history: ContextManager,
     pub(crate) latest_rate_limits: Option<RateLimitSnapshot>,
     pub(crate) server_reasoning_included: bool,
+    /// Whether an automatic title has already been requested for this session.
+    pub(crate) auto_title_requested: bool,
+    /// What was sent to the model for the most recent turns, oldest first.
+    turn_requests: VecDeque<TurnRequest>,
+    /// The most recent user messages with their turn item ids, oldest first,
+    /// so they can be edited later.
+    user_messages: VecDeque<(String, RecordedUserMessage)>,
+    /// Workspace snapshots bracketing the most recent turn, used to undo it.
+    /// Captured in the background after the turn ends; locked while the
+    /// capture runs so readers wait for it.
+    pub(crate) turn_checkpoint: Arc<tokio::sync::Mutex<Option<TurnCheckpoint>>>,
+    /// Activity aggregates written to the session meta on shutdown.
+    pub(crate) stats: SessionStatsTracker,
+    /// What the model last saw of each file it read or patched.
+    pub(crate) file_reads: FileReadHashes,
+    /// What interrupted turns cut short, keyed by turn id until the abort is
+    /// reported.
+    pub(crate) interrupted_turns: HashMap<String, InterruptedTurnProgress>,
+    /// Oversized tool outputs the model can page through.
+    pub(crate) tool_output_chunks: ToolOutputChunks,
+    /// Named text blobs that tool calls reference as `{{clip:<name>}}`.
+    pub(crate) clipboard: Clipboard,
+    /// Set with `Op::SetSecretMasking` to stop masking secrets in tool outputs.
+    pub(crate) secret_masking_disabled: bool,
+    /// Findings of the last exploration, added to the prompt of every turn.
+    pub(crate) exploration_findings: Option<ExplorationFindingsEvent>,
+    /// Model provider base URL last reported to clients, when the provider
+    /// has fallback endpoints.
+    pub(crate) reported_provider_endpoint: Option<String>,
+}
+
+/// Partial output and cancelled tool calls collected while an interrupted
+/// turn winds down.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct InterruptedTurnProgress {
+    pub(crate) partial_agent_message: Option<String>,
+    pub(crate) cancelled_tool_calls: Vec<CancelledToolCall>,
+}
+
+/// Ghost commits taken when the most recent turn started and ended.
+#[derive(Clone)]
+pub(crate) struct TurnCheckpoint {
+    pub(crate) start: GhostCommit,
+    pub(crate) end: GhostCommit,
+}
+
+/// Where a user message landed in history when it was recorded.
+struct RecordedUserMessage {
+    /// Zero-based index among the user turns in history.
+    turn_index: usize,
+    item: ResponseItem,
 }
 
 impl SessionState {
@@ -26,16 +111,32 @@ impl SessionState {
             history,
             latest_rate_limits: None,
             server_reasoning_included: false,
+            auto_title_requested: false,
+            turn_requests: VecDeque::new(),
+            user_messages: VecDeque::new(),
+            turn_checkpoint: Arc::default(),
+            stats: SessionStatsTracker::new(),
+            file_reads: FileReadHashes::default(),
+            interrupted_turns: HashMap::new(),
+            tool_output_chunks: ToolOutputChunks::default(),
+            clipboard: Clipboard::default(),
+            secret_masking_disabled: false,
+            exploration_findings: None,
+            reported_provider_endpoint: None,
         }
     }
 
     // History helpers
-    pub(crate) fn record_items<I>(&mut self, items: I, policy: TruncationPolicy)
-    where
+    pub(crate) fn record_items<I>(
+        &mut self,
+        items: I,
+        policy: TruncationPolicy,
+        tokenizer: &dyn Tokenizer,
+    ) where
         I: IntoIterator,
         I::Item: std::ops::Deref<Target = ResponseItem>,
     {
-        self.history.record_items(items, policy);
+        self.history.record_items(items, policy, tokenizer);
     }
 
     pub(crate) fn clone_history(&self) -> ContextManager {
@@ -46,6 +147,139 @@ impl SessionState {
         self.history.replace(items);
     }
 
+    /// Replaces `prefix` at the start of the history with `replacement`, if
+    /// the history still starts with it. Returns the history before and
+    /// after the replacement.
+    pub(crate) fn replace_history_prefix(
+        &mut self,
+        prefix: &[ResponseItem],
+        replacement: Vec<ResponseItem>,
+    ) -> Option<(Vec<ResponseItem>, Vec<ResponseItem>)> {
+        let before = self.history.raw_items();
+        if !before.starts_with(prefix) {
+            return None;
+        }
+        let mut after = replacement;
+        after.extend_from_slice(&before[prefix.len()..]);
+        let before = before.to_vec();
+        self.history.replace(after.clone());
+        Some((before, after))
+    }
+
+    /// Remember the user message identified by `item_id`. Must be called right
+    /// after `item` has been recorded into history.
+    pub(crate) fn record_user_message(&mut self, item_id: String, item: &ResponseItem) {
+        if !is_user_turn_boundary(item) {
+            return;
+        }
+        let Some(turn_index) = self.history.user_turns().len().checked_sub(1) else {
+            return;
+        };
+        self.push_user_message(
+            item_id,
+            RecordedUserMessage {
+                turn_index,
+                item: item.clone(),
+            },
+        );
+    }
+
+    /// Rebuild the editable user messages of a resumed session from the
+    /// `UserMessage` events in its rollout. Must be called once the history
+    /// has been restored from the same rollout.
+    pub(crate) fn restore_user_messages(&mut self, rollout_items: &[RolloutItem]) {
+        let mut recorded = Vec::new();
+        let mut last_user_item = None;
+        for rollout_item in rollout_items {
+            match rollout_item {
+                RolloutItem::ResponseItem(item) if is_user_turn_boundary(item) => {
+                    last_user_item = Some(item);
+                }
+                RolloutItem::EventMsg(EventMsg::UserMessage(event)) => {
+                    if let Some(item_id) = &event.item_id
+                        && let Some(item) = last_user_item.take()
+                    {
+                        recorded.push((item_id.clone(), item));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Match newest first so a message that was rolled back and re-sent
+        // with the same text resolves to the copy still in history.
+        let user_turns = self.history.user_turns();
+        let mut remaining = user_turns.len();
+        let mut restored = Vec::new();
+        for (item_id, item) in recorded.into_iter().rev() {
+            if restored.len() == MAX_RECORDED_USER_MESSAGES {
+                break;
+            }
+            let Some(turn_index) = user_turns[..remaining]
+                .iter()
+                .rposition(|turn| *turn == item)
+            else {
+                continue;
+            };
+            remaining = turn_index;
+            restored.push((
+                item_id,
+                RecordedUserMessage {
+                    turn_index,
+                    item: item.clone(),
+                },
+            ));
+        }
+        for (item_id, message) in restored.into_iter().rev() {
+            self.push_user_message(item_id, message);
+        }
+    }
+
+    fn push_user_message(&mut self, item_id: String, message: RecordedUserMessage) {
+        if self.user_messages.len() == MAX_RECORDED_USER_MESSAGES {
+            self.user_messages.pop_front();
+        }
+        self.user_messages.push_back((item_id, message));
+    }
+
+    fn recorded_user_message(&self, item_id: &str) -> Option<&RecordedUserMessage> {
+        self.user_messages
+            .iter()
+            .rev()
+            .find(|(id, _)| id == item_id)
+            .map(|(_, message)| message)
+    }
+
+    /// Number of user turns to drop so that history ends right before the user
+    /// message identified by `item_id`. Returns `None` when the message is
+    /// unknown or no longer part of history (e.g. after compaction).
+    pub(crate) fn user_turns_from_message(&self, item_id: &str) -> Option<u32> {
+        let recorded = self.recorded_user_message(item_id)?;
+        let user_turns = self.history.user_turns();
+        if user_turns.get(recorded.turn_index) != Some(&&recorded.item) {
+            return None;
+        }
+        u32::try_from(user_turns.len() - recorded.turn_index).ok()
+    }
+
+    /// Image URLs attached to the user message identified by `item_id`.
+    pub(crate) fn user_message_images(&self, item_id: &str) -> Vec<String> {
+        let Some(RecordedUserMessage {
+            item: ResponseItem::Message { content, .. },
+            ..
+        }) = self.recorded_user_message(item_id)
+        else {
+            return Vec::new();
+        };
+        content
+            .iter()
+            .filter_map(|content_item| match content_item {
+                ContentItem::InputImage { image_url } => Some(image_url.clone()),
+                ContentItem::InputText { .. } | ContentItem::OutputText { .. } => None,
+            })
+            .collect()
+    }
+
     pub(crate) fn set_token_info(&mut self, info: Option<TokenUsageInfo>) {
         self.history.set_token_info(info);
     }
@@ -92,6 +326,56 @@ impl SessionState {
     pub(crate) fn server_reasoning_included(&self) -> bool {
         self.server_reasoning_included
     }
+
+    /// Remember the latest model request of a turn. Only the last
+    /// [`MAX_TURN_REQUEST_SNAPSHOTS`] turns are kept.
+    pub(crate) fn record_turn_request(
+        &mut self,
+        turn_id: &str,
+        model: String,
+        prompt: Arc<Prompt>,
+    ) {
+        if let Some(last) = self.turn_requests.back_mut()
+            && last.turn_id == turn_id
+        {
+            last.request_count = last.request_count.saturating_add(1);
+            last.model = model;
+            last.prompt = prompt;
+            return;
+        }
+        if self.turn_requests.len() == MAX_TURN_REQUEST_SNAPSHOTS {
+            self.turn_requests.pop_front();
+        }
+        self.turn_requests.push_back(TurnRequest {
+            turn_id: turn_id.to_string(),
+            model,
+            request_count: 1,
+            prompt,
+        });
+    }
+
+    /// Snapshot for `turn_id`, or for the most recent turn when `None`.
+    pub(crate) fn turn_request(&self, turn_id: Option<&str>) -> Option<TurnRequestSnapshot> {
+        let request = match turn_id {
+            Some(turn_id) => self
+                .turn_requests
+                .iter()
+                .rev()
+                .find(|request| request.turn_id == turn_id),
+            None => self.turn_requests.back(),
+        }?;
+        Some(build_turn_request_snapshot(
+            &request.turn_id,
+            &request.model,
+            request.request_count,
+            &request.prompt,
+        ))
+    }
+
+    /// Returns true the first time it is called for this session.
+    pub(crate) fn take_auto_title_request(&mut self) -> bool {
+        !std::mem::replace(&mut self.auto_title_requested, true)
+    }
 }
 
 // Sometimes new snapshots don't include credits or plan information.
@@ -8,8 +8,13 @@ use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::error::CodexErr;
 use crate::error::Result;
+use crate::features::Feature;
 use crate::function_tool::FunctionCallError;
 use crate::parse_turn_item;
+use crate::protocol::EventMsg;
+use crate::protocol::WarningEvent;
+use crate::response_lint::ResponseLint;
+use crate::response_lint::lint_response_item;
 use crate::tools::parallel::ToolCallRuntime;
 use crate::tools::router::ToolRouter;
 use codex_protocol::models::FunctionCallOutputPayload;
@@ -69,24 +74,62 @@ pub(crate) async fn handle_output_item_done(
         }
         // No tool call: convert messages/reasoning into turn items and mark them as complete.
         Ok(None) => {
-            if let Some(turn_item) = handle_non_tool_response_item(&item).await {
-                if previously_active_item.is_none() {
+            let lint = lint_response_item(
+                &item,
+                &ctx.turn_context.client.get_model(),
+                ctx.sess.enabled(Feature::ConvertHallucinatedToolCalls),
+                |name| ctx.tool_runtime.has_function_tool(name),
+            );
+            let (message, converted_call) = match lint {
+                Some(ResponseLint::Convert { message, call }) => (message, Some(call)),
+                Some(ResponseLint::Flag { warning }) => {
+                    ctx.sess
+                        .send_event(
+                            &ctx.turn_context,
+                            EventMsg::Warning(WarningEvent { message: warning }),
+                        )
+                        .await;
+                    (Some(item), None)
+                }
+                None => (Some(item), None),
+            };
+
+            if let Some(item) = message {
+                if let Some(turn_item) = handle_non_tool_response_item(&item).await {
+                    if previously_active_item.is_none() {
+                        ctx.sess
+                            .emit_turn_item_started(&ctx.turn_context, &turn_item)
+                            .await;
+                    }
+
                     ctx.sess
-                        .emit_turn_item_started(&ctx.turn_context, &turn_item)
+                        .emit_turn_item_completed(&ctx.turn_context, turn_item)
                         .await;
                 }
 
                 ctx.sess
-                    .emit_turn_item_completed(&ctx.turn_context, turn_item)
+                    .record_conversation_items(&ctx.turn_context, std::slice::from_ref(&item))
                     .await;
+                output.last_agent_message = last_assistant_message_from_item(&item);
             }
 
-            ctx.sess
-                .record_conversation_items(&ctx.turn_context, std::slice::from_ref(&item))
-                .await;
-            let last_agent_message = last_assistant_message_from_item(&item);
-
-            output.last_agent_message = last_agent_message;
+            // The model wrote a call out as text; run it as if it had been issued.
+            if let Some(call_item) = converted_call
+                && let Ok(Some(call)) =
+                    ToolRouter::build_tool_call(ctx.sess.as_ref(), call_item.clone()).await
+            {
+                tracing::info!("ToolCall (converted from text): {}", call.tool_name);
+                ctx.sess
+                    .record_conversation_items(&ctx.turn_context, std::slice::from_ref(&call_item))
+                    .await;
+                let cancellation_token = ctx.cancellation_token.child_token();
+                output.tool_future = Some(Box::pin(
+                    ctx.tool_runtime
+                        .clone()
+                        .handle_tool_call(call, cancellation_token),
+                ));
+                output.needs_follow_up = true;
+            }
         }
         // Guardrail: the model issued a LocalShellCall without an id; surface the error back into history.
         Err(FunctionCallError::MissingLocalShellCallId) => {
@@ -156,7 +199,9 @@ pub(crate) async fn handle_non_tool_response_item(item: &ResponseItem) -> Option
     match item {
         ResponseItem::Message { .. }
         | ResponseItem::Reasoning { .. }
-        | ResponseItem::WebSearchCall { .. } => parse_turn_item(item),
+        | ResponseItem::WebSearchCall { .. }
+        | ResponseItem::CodeInterpreterCall { .. }
+        | ResponseItem::FileSearchCall { .. } => parse_turn_item(item),
         ResponseItem::FunctionCallOutput { .. } | ResponseItem::CustomToolCallOutput { .. } => {
             debug!("unexpected tool output from stream");
             None
@@ -0,0 +1,317 @@
+//! Explore tasks: a time-boxed, read-only investigation of the repository.
+//!
+//! The exploration runs as a regular turn in a turn context that has a
+//! read-only sandbox, never asks for approval and only offers the tools
+//! listed in `[explore] allowed_tools`. Once the model is done, or the
+//! `[explore]` token or time budget runs out, a final tool-less request asks
+//! for a structured summary of the findings. The summary is pinned: it is
+//! added to the prompt of every later turn, survives compaction and is
+//! restored on resume from its `ExplorationFindings` event, until
+//! `Op::ClearExplorationFindings` unpins it.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::DeveloperInstructions;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::ErrorEvent;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::ExplorationFindingsEvent;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::user_input::UserInput;
+use futures::prelude::*;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+use crate::Prompt;
+use crate::client_common::ResponseEvent;
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::codex::run_turn;
+use crate::compact::content_items_to_text;
+use crate::config::types::Explore;
+use crate::error::CodexErr;
+use crate::error::Result as CodexResult;
+use crate::state::TaskKind;
+
+use super::SessionTask;
+use super::SessionTaskContext;
+
+const EXPLORE_PROMPT: &str = include_str!("../../templates/explore/prompt.md");
+const FINDINGS_PROMPT: &str = include_str!("../../templates/explore/findings.md");
+
+/// How often token usage is checked against the budget. Usage only changes
+/// when a model response completes, so the budget may be overshot by one
+/// response.
+const BUDGET_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+pub(crate) struct ExploreTask {
+    goal: String,
+}
+
+impl ExploreTask {
+    pub(crate) fn new(goal: String) -> Self {
+        Self { goal }
+    }
+}
+
+#[async_trait]
+impl SessionTask for ExploreTask {
+    fn kind(&self) -> TaskKind {
+        TaskKind::Explore
+    }
+
+    async fn run(
+        self: Arc<Self>,
+        session: Arc<SessionTaskContext>,
+        ctx: Arc<TurnContext>,
+        input: Vec<UserInput>,
+        cancellation_token: CancellationToken,
+    ) -> Option<String> {
+        let sess = session.clone_session();
+        let _ = sess
+            .services
+            .otel_manager
+            .counter("codex.task.explore", 1, &[]);
+        let budget = ctx.client.config().explore.clone();
+
+        let exploration_token = cancellation_token.child_token();
+        let exploration = run_turn(
+            Arc::clone(&sess),
+            Arc::clone(&ctx),
+            input,
+            exploration_token.clone(),
+        );
+        tokio::pin!(exploration);
+        let budget_exhausted = tokio::select! {
+            _ = &mut exploration => false,
+            () = wait_for_budget(&sess, &budget) => {
+                // Stop the turn like an interrupt so what it found so far stays
+                // in the history for the summary.
+                exploration_token.cancel();
+                exploration.await;
+                sess.take_interrupted_turn_progress(&ctx.sub_id).await;
+                true
+            }
+        };
+        if cancellation_token.is_cancelled() {
+            return None;
+        }
+
+        let findings = tokio::select! {
+            _ = cancellation_token.cancelled() => return None,
+            findings = summarize_findings(&sess, &ctx, &self.goal, budget_exhausted) => findings,
+        };
+        let message = match findings {
+            Ok(findings) if !findings.is_empty() => {
+                sess.pin_exploration_findings(
+                    &ctx,
+                    ExplorationFindingsEvent {
+                        goal: self.goal.clone(),
+                        findings: findings.clone(),
+                        budget_exhausted,
+                    },
+                )
+                .await;
+                return Some(findings);
+            }
+            Ok(_) => "the model did not summarize its findings".to_string(),
+            Err(err) => err.to_string(),
+        };
+        sess.send_event(
+            &ctx,
+            EventMsg::Error(ErrorEvent {
+                message: format!("Exploration failed: {message}"),
+                codex_error_info: None,
+                fingerprint: None,
+            }),
+        )
+        .await;
+        None
+    }
+}
+
+/// The user message that starts an exploration of `goal`.
+pub(crate) fn explore_input(goal: &str, budget: &Explore) -> Vec<UserInput> {
+    let text = EXPLORE_PROMPT
+        .replace("{{goal}}", goal.trim())
+        .replace("{{max_tokens}}", &budget.max_tokens().to_string())
+        .replace(
+            "{{max_minutes}}",
+            &budget.max_duration().as_secs().div_ceil(60).to_string(),
+        );
+    vec![UserInput::Text {
+        text,
+        // The exploration prompt is synthesized; no UI element ranges to preserve.
+        text_elements: Vec::new(),
+    }]
+}
+
+/// The pinned findings as they are added to the prompt.
+pub(crate) fn findings_message(pinned: &ExplorationFindingsEvent) -> ResponseItem {
+    let ExplorationFindingsEvent { goal, findings, .. } = pinned;
+    DeveloperInstructions::new(format!(
+        "A read-only exploration of this repository was done for the goal \"{goal}\". Use its \
+         findings as the starting point for the work that follows, and re-check details that may \
+         have changed since.\n\n<exploration_findings>\n{findings}\n</exploration_findings>"
+    ))
+    .into()
+}
+
+/// The findings pinned by the last exploration recorded in a rollout,
+/// unless they were cleared after it.
+pub(crate) fn pinned_findings_from_rollout(
+    items: &[RolloutItem],
+) -> Option<ExplorationFindingsEvent> {
+    items.iter().rev().find_map(|item| match item {
+        RolloutItem::EventMsg(EventMsg::ExplorationFindings(event)) => Some(Some(event.clone())),
+        RolloutItem::EventMsg(EventMsg::ExplorationFindingsCleared) => Some(None),
+        _ => None,
+    })?
+}
+
+/// Resolves once the exploration has used up its token or time budget.
+async fn wait_for_budget(sess: &Session, budget: &Explore) {
+    let deadline = Instant::now() + budget.max_duration();
+    let max_tokens = i64::try_from(budget.max_tokens()).unwrap_or(i64::MAX);
+    let tokens_before = sess.blended_token_usage().await;
+    loop {
+        let now = Instant::now();
+        if now >= deadline
+            || sess
+                .blended_token_usage()
+                .await
+                .saturating_sub(tokens_before)
+                >= max_tokens
+        {
+            return;
+        }
+        tokio::time::sleep(BUDGET_POLL_INTERVAL.min(deadline - now)).await;
+    }
+}
+
+/// Asks the model, without tools, for the structured findings of the
+/// exploration recorded in the history.
+async fn summarize_findings(
+    sess: &Session,
+    turn_context: &TurnContext,
+    goal: &str,
+    budget_exhausted: bool,
+) -> CodexResult<String> {
+    let budget_note = if budget_exhausted {
+        " because its budget ran out; include what you did not get to under open questions"
+    } else {
+        ""
+    };
+    let mut input = sess.clone_history().await.for_prompt();
+    input.push(ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText {
+            text: FINDINGS_PROMPT
+                .replace("{{budget_note}}", budget_note)
+                .replace("{{goal}}", goal.trim()),
+        }],
+        end_turn: None,
+    });
+    let prompt = Prompt {
+        input,
+        base_instructions: sess.get_base_instructions().await,
+        personality: turn_context.personality,
+        ..Default::default()
+    };
+
+    let mut client_session = turn_context.client.new_session();
+    let mut stream = client_session.stream(&prompt).await?;
+    let mut findings = String::new();
+    loop {
+        let Some(event) = stream.next().await else {
+            return Err(CodexErr::Stream(
+                "stream closed before response.completed".into(),
+                None,
+            ));
+        };
+        match event? {
+            ResponseEvent::OutputItemDone(ResponseItem::Message { role, content, .. })
+                if role == "assistant" =>
+            {
+                if let Some(text) = content_items_to_text(&content) {
+                    findings = text;
+                }
+            }
+            ResponseEvent::RateLimits(snapshot) => {
+                sess.update_rate_limits(turn_context, snapshot).await;
+            }
+            ResponseEvent::Completed { token_usage, .. } => {
+                sess.update_token_usage_info(turn_context, token_usage.as_ref())
+                    .await;
+                return Ok(findings.trim().to_string());
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn findings(goal: &str) -> ExplorationFindingsEvent {
+        ExplorationFindingsEvent {
+            goal: goal.to_string(),
+            findings: format!("## Relevant files\n- src/{goal}.rs"),
+            budget_exhausted: false,
+        }
+    }
+
+    #[test]
+    fn rollout_restores_the_last_exploration() {
+        let items = vec![
+            RolloutItem::EventMsg(EventMsg::ExplorationFindings(findings("parser"))),
+            RolloutItem::EventMsg(EventMsg::ExplorationFindings(findings("lexer"))),
+        ];
+
+        assert_eq!(
+            pinned_findings_from_rollout(&items),
+            Some(findings("lexer"))
+        );
+        assert_eq!(pinned_findings_from_rollout(&[]), None);
+    }
+
+    #[test]
+    fn rollout_keeps_cleared_findings_unpinned() {
+        let items = vec![
+            RolloutItem::EventMsg(EventMsg::ExplorationFindings(findings("parser"))),
+            RolloutItem::EventMsg(EventMsg::ExplorationFindingsCleared),
+        ];
+        assert_eq!(pinned_findings_from_rollout(&items), None);
+
+        let items = vec![
+            RolloutItem::EventMsg(EventMsg::ExplorationFindingsCleared),
+            RolloutItem::EventMsg(EventMsg::ExplorationFindings(findings("lexer"))),
+        ];
+        assert_eq!(
+            pinned_findings_from_rollout(&items),
+            Some(findings("lexer"))
+        );
+    }
+
+    #[test]
+    fn explore_input_states_goal_and_budget() {
+        let budget = Explore {
+            max_tokens: Some(50_000),
+            max_duration_secs: Some(90),
+            ..Default::default()
+        };
+
+        let input = explore_input("  add a --json flag\n", &budget);
+        let [UserInput::Text { text, .. }] = input.as_slice() else {
+            panic!("expected a single text input, got {input:?}");
+        };
+        assert!(text.contains("\n\nadd a --json flag\n\n"));
+        assert!(text.contains("about 50000 tokens and 2 minutes"));
+    }
+}
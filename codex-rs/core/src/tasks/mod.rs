@@ -1,8 +1,10 @@
 mod compact;
+mod explore;
 mod ghost_snapshot;
 mod regular;
 mod review;
 mod undo;
+mod undo_turn;
 mod user_shell;
 
 use std::sync::Arc;
@@ -13,17 +15,22 @@ use tokio::select;
 use tokio::sync::Notify;
 use tokio_util::sync::CancellationToken;
 use tokio_util::task::AbortOnDropHandle;
+use tracing::Instrument;
+use tracing::info_span;
 use tracing::trace;
 use tracing::warn;
 
 use crate::AuthManager;
+use crate::background_compaction;
 use crate::codex::Session;
 use crate::codex::TurnContext;
+use crate::heartbeat::run_heartbeats;
 use crate::models_manager::manager::ModelsManager;
 use crate::protocol::EventMsg;
 use crate::protocol::TurnAbortReason;
 use crate::protocol::TurnAbortedEvent;
 use crate::protocol::TurnCompleteEvent;
+use crate::protocol::TurnInterruptedEvent;
 use crate::session_prefix::TURN_ABORTED_OPEN_TAG;
 use crate::state::ActiveTurn;
 use crate::state::RunningTask;
@@ -34,14 +41,20 @@ use codex_protocol::protocol::RolloutItem;
 use codex_protocol::user_input::UserInput;
 
 pub(crate) use compact::CompactTask;
+pub(crate) use explore::ExploreTask;
+pub(crate) use explore::explore_input;
+pub(crate) use explore::findings_message;
+pub(crate) use explore::pinned_findings_from_rollout;
 pub(crate) use ghost_snapshot::GhostSnapshotTask;
 pub(crate) use regular::RegularTask;
 pub(crate) use review::ReviewTask;
 pub(crate) use undo::UndoTask;
+pub(crate) use undo_turn::UndoTurnTask;
+pub(crate) use undo_turn::capture_turn_end_checkpoint;
 pub(crate) use user_shell::UserShellCommandTask;
 
 const GRACEFULL_INTERRUPTION_TIMEOUT_MS: u64 = 100;
-const TURN_ABORTED_INTERRUPTED_GUIDANCE: &str = "The user interrupted the previous turn. Do not continue or repeat work from that turn unless the user explicitly asks. If any tools/commands were aborted, they may have partially executed; verify current state before retrying.";
+const TURN_ABORTED_INTERRUPTED_GUIDANCE: &str = "The user interrupted the previous turn. Do not continue or repeat work from that turn unless the user explicitly asks. Any response text you had already streamed is kept above and may be referred to. If any tools/commands were aborted, they may have partially executed; verify current state before retrying.";
 
 /// Thin wrapper that exposes the parts of [`Session`] task runners need.
 #[derive(Clone)]
@@ -115,6 +128,10 @@ impl Session {
         task: T,
     ) {
         self.abort_all_tasks(TurnAbortReason::Replaced).await;
+        self.services.background_compaction.cancel_pending();
+        // A tool call aborted together with its turn can still report itself
+        // as cancelled after the abort was handled; drop anything left over.
+        self.discard_interrupted_turns().await;
 
         let task: Arc<dyn SessionTask> = Arc::new(task);
         let task_kind = task.kind();
@@ -123,30 +140,47 @@ impl Session {
         let done = Arc::new(Notify::new());
 
         let done_clone = Arc::clone(&done);
+        let turn_span = info_span!(
+            "turn",
+            conversation_id = %self.conversation_id,
+            turn_id = %turn_context.sub_id,
+            kind = ?task_kind,
+        );
         let handle = {
             let session_ctx = Arc::new(SessionTaskContext::new(Arc::clone(self)));
             let ctx = Arc::clone(&turn_context);
             let task_for_run = Arc::clone(&task);
             let task_cancellation_token = cancellation_token.child_token();
-            tokio::spawn(async move {
-                let ctx_for_finish = Arc::clone(&ctx);
-                let last_agent_message = task_for_run
-                    .run(
-                        Arc::clone(&session_ctx),
-                        ctx,
-                        input,
-                        task_cancellation_token.child_token(),
-                    )
-                    .await;
-                session_ctx.clone_session().flush_rollout().await;
-                if !task_cancellation_token.is_cancelled() {
-                    // Emit completion uniformly from spawn site so all tasks share the same lifecycle.
-                    let sess = session_ctx.clone_session();
-                    sess.on_task_finished(ctx_for_finish, last_agent_message)
+            tokio::spawn(
+                async move {
+                    let ctx_for_finish = Arc::clone(&ctx);
+                    let session = session_ctx.clone_session();
+                    session.turn_activity().turn_started();
+                    let heartbeats = ctx.client.config().heartbeat.interval().map(|interval| {
+                        AbortOnDropHandle::new(tokio::spawn(
+                            run_heartbeats(session, Arc::clone(&ctx), interval).in_current_span(),
+                        ))
+                    });
+                    let last_agent_message = task_for_run
+                        .run(
+                            Arc::clone(&session_ctx),
+                            ctx,
+                            input,
+                            task_cancellation_token.child_token(),
+                        )
                         .await;
+                    drop(heartbeats);
+                    session_ctx.clone_session().flush_rollout().await;
+                    if !task_cancellation_token.is_cancelled() {
+                        // Emit completion uniformly from spawn site so all tasks share the same lifecycle.
+                        let sess = session_ctx.clone_session();
+                        sess.on_task_finished(ctx_for_finish, last_agent_message)
+                            .await;
+                    }
+                    done_clone.notify_waiters();
                 }
-                done_clone.notify_waiters();
-            })
+                .instrument(turn_span),
+            )
         };
 
         let timer = turn_context
@@ -157,7 +191,7 @@ impl Session {
 
         let running_task = RunningTask {
             done,
-            handle: Arc::new(AbortOnDropHandle::new(handle)),
+            handle: AbortOnDropHandle::new(handle),
             kind: task_kind,
             task,
             cancellation_token,
@@ -192,8 +226,13 @@ impl Session {
         if should_close_processes {
             self.close_unified_exec_processes().await;
         }
+        // Nothing reports a turn that completes, so drop whatever it recorded
+        // about cancelled tool calls.
+        self.take_interrupted_turn_progress(&turn_context.sub_id)
+            .await;
         let event = EventMsg::TurnComplete(TurnCompleteEvent { last_agent_message });
         self.send_event(turn_context.as_ref(), event).await;
+        background_compaction::schedule(self, turn_context);
     }
 
     async fn register_new_active_task(&self, task: RunningTask) {
@@ -222,7 +261,7 @@ impl Session {
             .await;
     }
 
-    async fn handle_task_abort(self: &Arc<Self>, task: RunningTask, reason: TurnAbortReason) {
+    async fn handle_task_abort(self: &Arc<Self>, mut task: RunningTask, reason: TurnAbortReason) {
         let sub_id = task.turn_context.sub_id.clone();
         if task.cancellation_token.is_cancelled() {
             return;
@@ -241,12 +280,16 @@ impl Session {
         }
 
         task.handle.abort();
+        // Wait for the task to unwind so everything it recorded while winding
+        // down is visible before its progress is taken below.
+        let _ = (&mut task.handle).await;
 
         let session_ctx = Arc::new(SessionTaskContext::new(Arc::clone(self)));
         session_task
             .abort(session_ctx, Arc::clone(&task.turn_context))
             .await;
 
+        let progress = self.take_interrupted_turn_progress(&sub_id).await;
         if reason == TurnAbortReason::Interrupted {
             let marker = ResponseItem::Message {
                 id: None,
@@ -265,6 +308,13 @@ impl Session {
             // Ensure the marker is durably visible before emitting TurnAborted: some clients
             // synchronously re-read the rollout on receipt of the abort event.
             self.flush_rollout().await;
+
+            let event = EventMsg::TurnInterrupted(TurnInterruptedEvent {
+                turn_id: sub_id.clone(),
+                partial_agent_message: progress.partial_agent_message,
+                cancelled_tool_calls: progress.cancelled_tool_calls,
+            });
+            self.send_event(task.turn_context.as_ref(), event).await;
         }
 
         let event = EventMsg::TurnAborted(TurnAbortedEvent { reason });
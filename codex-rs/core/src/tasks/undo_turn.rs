@@ -0,0 +1,351 @@
+//! Undo the most recent turn as a single step: the workspace is restored to
+//! the ghost snapshot taken when the turn started and the turn is dropped
+//! from the conversation history.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::context_manager::is_user_turn_boundary;
+use crate::features::Feature;
+use crate::protocol::EventMsg;
+use crate::protocol::ThreadRolledBackEvent;
+use crate::protocol::UndoCompletedEvent;
+use crate::protocol::UndoStartedEvent;
+use crate::state::TaskKind;
+use crate::state::TurnCheckpoint;
+use crate::tasks::SessionTask;
+use crate::tasks::SessionTaskContext;
+use async_trait::async_trait;
+use codex_git::CreateGhostCommitOptions;
+use codex_git::GhostCommit;
+use codex_git::GitToolingError;
+use codex_git::RestoreGhostCommitOptions;
+use codex_git::changed_paths_between_ghost_commits;
+use codex_git::create_ghost_commit;
+use codex_git::restore_ghost_commit_with_options;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::user_input::UserInput;
+use codex_utils_readiness::Readiness;
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+use tracing::warn;
+
+/// Maximum number of paths listed in the undo summary.
+const MAX_LISTED_PATHS: usize = 5;
+
+pub(crate) struct UndoTurnTask;
+
+enum UndoTurnOutcome {
+    Reverted(Vec<PathBuf>),
+    Diverged(Vec<PathBuf>),
+}
+
+#[async_trait]
+impl SessionTask for UndoTurnTask {
+    fn kind(&self) -> TaskKind {
+        TaskKind::Regular
+    }
+
+    async fn run(
+        self: Arc<Self>,
+        session: Arc<SessionTaskContext>,
+        ctx: Arc<TurnContext>,
+        _input: Vec<UserInput>,
+        cancellation_token: CancellationToken,
+    ) -> Option<String> {
+        let _ = session
+            .session
+            .services
+            .otel_manager
+            .counter("codex.task.undo_turn", 1, &[]);
+        let sess = session.clone_session();
+        sess.send_event(
+            ctx.as_ref(),
+            EventMsg::UndoStarted(UndoStartedEvent {
+                message: Some("Undoing last turn...".to_string()),
+            }),
+        )
+        .await;
+
+        let completed = if cancellation_token.is_cancelled() {
+            UndoCompletedEvent {
+                success: false,
+                message: Some("Undo cancelled.".to_string()),
+            }
+        } else {
+            undo_last_turn(&sess, ctx.as_ref()).await
+        };
+
+        sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
+            .await;
+        None
+    }
+}
+
+async fn undo_last_turn(sess: &Arc<Session>, ctx: &TurnContext) -> UndoCompletedEvent {
+    let failed = |message: String| UndoCompletedEvent {
+        success: false,
+        message: Some(message),
+    };
+
+    let history = sess.clone_history().await;
+    let Some(start) = last_turn_ghost_snapshot(history.raw_items()) else {
+        return failed("No workspace checkpoint is available for the last turn.".to_string());
+    };
+    let Some(checkpoint) = sess
+        .turn_checkpoint()
+        .await
+        .filter(|checkpoint| checkpoint.start.id() == start.id())
+    else {
+        return failed("The last turn did not finish recording its checkpoint.".to_string());
+    };
+
+    let repo_path = ctx.cwd.clone();
+    let ghost_snapshot = ctx.ghost_snapshot.clone();
+    let outcome = tokio::task::spawn_blocking(move || -> Result<_, GitToolingError> {
+        let current = create_ghost_commit(
+            &CreateGhostCommitOptions::new(&repo_path).ghost_snapshot(ghost_snapshot.clone()),
+        )?;
+        let diverged = changed_paths_between_ghost_commits(&repo_path, &checkpoint.end, &current)?;
+        if !diverged.is_empty() {
+            return Ok(UndoTurnOutcome::Diverged(diverged));
+        }
+        let reverted =
+            changed_paths_between_ghost_commits(&repo_path, &checkpoint.start, &checkpoint.end)?;
+        let options = RestoreGhostCommitOptions::new(&repo_path).ghost_snapshot(ghost_snapshot);
+        restore_ghost_commit_with_options(&options, &checkpoint.start)?;
+        Ok(UndoTurnOutcome::Reverted(reverted))
+    })
+    .await;
+
+    match outcome {
+        Ok(Ok(UndoTurnOutcome::Reverted(paths))) => {
+            let mut history = history;
+            history.drop_last_n_user_turns(1);
+            sess.replace_history(history.raw_items().to_vec()).await;
+            sess.recompute_token_usage(ctx).await;
+            sess.set_turn_checkpoint(None).await;
+            sess.send_event(
+                ctx,
+                EventMsg::ThreadRolledBack(ThreadRolledBackEvent {
+                    num_turns: 1,
+                    edited_item_id: None,
+                }),
+            )
+            .await;
+            info!(commit_id = start.id(), "Undo turn restored ghost snapshot");
+            UndoCompletedEvent {
+                success: true,
+                message: Some(format_reverted_summary(&paths)),
+            }
+        }
+        Ok(Ok(UndoTurnOutcome::Diverged(paths))) => failed(format!(
+            "Refusing to undo: the workspace changed after the last turn ({}).",
+            format_paths(&paths)
+        )),
+        Ok(Err(err)) => {
+            let message = format!("Failed to undo the last turn: {err}");
+            warn!("{message}");
+            failed(message)
+        }
+        Err(err) => {
+            let message = format!("Failed to undo the last turn: {err}");
+            warn!("{message}");
+            failed(message)
+        }
+    }
+}
+
+/// Snapshot the workspace as the turn left it so a later [`UndoTurnTask`] can
+/// tell whether anything changed after the turn ended. The snapshot is taken
+/// in the background so the turn does not wait for it; the checkpoint stays
+/// locked until it is recorded, so an undo waits for it instead.
+pub(crate) async fn capture_turn_end_checkpoint(sess: &Arc<Session>, ctx: &Arc<TurnContext>) {
+    if !sess.enabled(Feature::GhostCommit) {
+        return;
+    }
+    // A later turn may start before the snapshot is taken, so remember which
+    // turn this is rather than looking up the last one.
+    let turn = sess
+        .clone_history()
+        .await
+        .raw_items()
+        .iter()
+        .filter(|item| is_user_turn_boundary(item))
+        .count();
+    let mut checkpoint = sess.turn_checkpoint_slot().await.lock_owned().await;
+    let sess = Arc::clone(sess);
+    let ctx = Arc::clone(ctx);
+    tokio::spawn(async move {
+        // The start-of-turn snapshot is recorded in the background; wait for it.
+        ctx.tool_call_gate.wait_ready().await;
+        let history = sess.clone_history().await;
+        let Some(start) = turn_ghost_snapshot(history.raw_items(), turn) else {
+            *checkpoint = None;
+            return;
+        };
+
+        let repo_path = ctx.cwd.clone();
+        let ghost_snapshot = ctx.ghost_snapshot.clone();
+        let end = tokio::task::spawn_blocking(move || {
+            create_ghost_commit(
+                &CreateGhostCommitOptions::new(&repo_path).ghost_snapshot(ghost_snapshot),
+            )
+        })
+        .await;
+        *checkpoint = match end {
+            Ok(Ok(end)) => Some(TurnCheckpoint { start, end }),
+            Ok(Err(err)) => {
+                warn!("failed to capture end-of-turn snapshot: {err}");
+                None
+            }
+            Err(err) => {
+                warn!("end-of-turn snapshot task panicked: {err}");
+                None
+            }
+        };
+    });
+}
+
+/// Returns the ghost snapshot recorded during the `turn`-th user turn (counted
+/// from 1), if any.
+fn turn_ghost_snapshot(items: &[ResponseItem], turn: usize) -> Option<GhostCommit> {
+    let mut boundaries = items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| is_user_turn_boundary(item))
+        .map(|(idx, _)| idx);
+    let turn_start = boundaries.nth(turn.checked_sub(1)?)?;
+    let turn_end = boundaries.next().unwrap_or(items.len());
+    find_ghost_snapshot(&items[turn_start..turn_end])
+}
+
+/// Returns the ghost snapshot recorded during the last user turn, if any.
+fn last_turn_ghost_snapshot(items: &[ResponseItem]) -> Option<GhostCommit> {
+    let last_turn_start = items.iter().rposition(is_user_turn_boundary)?;
+    find_ghost_snapshot(&items[last_turn_start..])
+}
+
+fn find_ghost_snapshot(items: &[ResponseItem]) -> Option<GhostCommit> {
+    items.iter().find_map(|item| match item {
+        ResponseItem::GhostSnapshot { ghost_commit } => Some(ghost_commit.clone()),
+        _ => None,
+    })
+}
+
+fn format_reverted_summary(paths: &[PathBuf]) -> String {
+    match paths.len() {
+        0 => "Reverted the last turn; it had not changed any files.".to_string(),
+        1 => format!(
+            "Reverted the last turn and restored 1 file: {}.",
+            format_paths(paths)
+        ),
+        count => format!(
+            "Reverted the last turn and restored {count} files: {}.",
+            format_paths(paths)
+        ),
+    }
+}
+
+fn format_paths(paths: &[PathBuf]) -> String {
+    let mut listed = paths
+        .iter()
+        .take(MAX_LISTED_PATHS)
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    if paths.len() > MAX_LISTED_PATHS {
+        listed.push_str(&format!(" and {} more", paths.len() - MAX_LISTED_PATHS));
+    }
+    listed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::ContentItem;
+    use pretty_assertions::assert_eq;
+
+    fn user_message(text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+            end_turn: None,
+        }
+    }
+
+    fn ghost_snapshot(id: &str) -> ResponseItem {
+        ResponseItem::GhostSnapshot {
+            ghost_commit: GhostCommit::new(id.to_string(), None, Vec::new(), Vec::new()),
+        }
+    }
+
+    #[test]
+    fn finds_snapshot_recorded_during_last_turn() {
+        let items = vec![
+            user_message("first"),
+            ghost_snapshot("aaa"),
+            user_message("second"),
+            ghost_snapshot("bbb"),
+        ];
+        assert_eq!(
+            last_turn_ghost_snapshot(&items).map(|commit| commit.id().to_string()),
+            Some("bbb".to_string())
+        );
+
+        let without_snapshot = vec![
+            user_message("first"),
+            ghost_snapshot("aaa"),
+            user_message("second"),
+        ];
+        assert!(last_turn_ghost_snapshot(&without_snapshot).is_none());
+    }
+
+    #[test]
+    fn finds_snapshot_recorded_during_a_given_turn() {
+        let items = vec![
+            user_message("first"),
+            ghost_snapshot("aaa"),
+            user_message("second"),
+            user_message("third"),
+            ghost_snapshot("ccc"),
+        ];
+        let ids: Vec<Option<String>> = (0..=4)
+            .map(|turn| turn_ghost_snapshot(&items, turn).map(|commit| commit.id().to_string()))
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                None,
+                Some("aaa".to_string()),
+                None,
+                Some("ccc".to_string()),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn summarizes_reverted_paths() {
+        assert_eq!(
+            format_reverted_summary(&[]),
+            "Reverted the last turn; it had not changed any files."
+        );
+        assert_eq!(
+            format_reverted_summary(&[PathBuf::from("src/lib.rs")]),
+            "Reverted the last turn and restored 1 file: src/lib.rs."
+        );
+        let many: Vec<PathBuf> = (1..=7)
+            .map(|n| PathBuf::from(format!("f{n}.txt")))
+            .collect();
+        assert_eq!(
+            format_reverted_summary(&many),
+            "Reverted the last turn and restored 7 files: f1.txt, f2.txt, f3.txt, f4.txt, f5.txt and 2 more."
+        );
+    }
+}
@@ -1,7 +1,9 @@
 //! Terminal detection utilities.
 //!
 //! This module feeds terminal metadata into OpenTelemetry user-agent logging and into
-//! terminal-specific configuration choices in the TUI.
+//! terminal-specific configuration choices in the TUI. It also builds the OSC sequences the TUI
+//! uses to report session status through the terminal title, progress indicator, and
+//! notifications.
 
 use std::sync::OnceLock;
 
@@ -180,6 +182,70 @@ impl TerminalInfo {
 
         sanitize_header_value(raw)
     }
+
+    /// Returns whether the terminal shows a progress indicator for OSC 9;4 sequences.
+    pub fn supports_progress_indicator(&self) -> bool {
+        matches!(
+            self.name,
+            TerminalName::WindowsTerminal | TerminalName::Ghostty | TerminalName::Iterm2
+        )
+    }
+
+    /// Returns whether the terminal posts notifications for OSC 777 but ignores OSC 9.
+    pub fn prefers_osc777_notifications(&self) -> bool {
+        matches!(self.name, TerminalName::GnomeTerminal | TerminalName::Vte)
+    }
+}
+
+/// State shown by the terminal's progress indicator (OSC 9;4).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TerminalProgress {
+    /// No progress indicator.
+    Hidden,
+    /// Busy, without a known completion percentage.
+    Indeterminate,
+    /// Paused, for example while waiting for the user.
+    Paused,
+    /// The operation failed.
+    Error,
+}
+
+/// Saves the current window title on the terminal's title stack (XTWINOPS 22).
+pub const PUSH_TITLE_SEQUENCE: &str = "\x1b[22;0t";
+
+/// Restores the window title saved by [`PUSH_TITLE_SEQUENCE`] (XTWINOPS 23).
+pub const POP_TITLE_SEQUENCE: &str = "\x1b[23;0t";
+
+/// Builds the OSC 2 sequence that sets the window title.
+pub fn set_title_sequence(title: &str) -> String {
+    format!("\x1b]2;{}\x07", strip_control_chars(title))
+}
+
+/// Builds the OSC 9;4 sequence that updates the terminal's progress indicator.
+pub fn progress_sequence(progress: TerminalProgress) -> String {
+    let (state, value) = match progress {
+        TerminalProgress::Hidden => (0, 0),
+        TerminalProgress::Error => (2, 100),
+        TerminalProgress::Indeterminate => (3, 0),
+        TerminalProgress::Paused => (4, 100),
+    };
+    format!("\x1b]9;4;{state};{value}\x07")
+}
+
+/// Builds the OSC 777 sequence that posts a desktop notification.
+///
+/// The title is a `;`-separated field, so semicolons in it are replaced.
+pub fn osc777_notification_sequence(title: &str, body: &str) -> String {
+    format!(
+        "\x1b]777;notify;{};{}\x07",
+        strip_control_chars(title).replace(';', ","),
+        strip_control_chars(body)
+    )
+}
+
+/// Removes characters that would terminate or corrupt an OSC payload.
+fn strip_control_chars(value: &str) -> String {
+    value.chars().filter(|c| !c.is_control()).collect()
 }
 
 static TERMINAL_INFO: OnceLock<TerminalInfo> = OnceLock::new();
@@ -1163,4 +1229,35 @@ mod tests {
         );
         assert_eq!(terminal.user_agent_token(), "unknown", "unknown_user_agent");
     }
+
+    #[test]
+    fn builds_status_sequences() {
+        assert_eq!(
+            set_title_sequence("codex · working\x07\n"),
+            "\x1b]2;codex · working\x07"
+        );
+        assert_eq!(
+            progress_sequence(TerminalProgress::Indeterminate),
+            "\x1b]9;4;3;0\x07"
+        );
+        assert_eq!(
+            progress_sequence(TerminalProgress::Hidden),
+            "\x1b]9;4;0;0\x07"
+        );
+        assert_eq!(
+            osc777_notification_sequence("Codex; done", "Turn complete"),
+            "\x1b]777;notify;Codex, done;Turn complete\x07"
+        );
+    }
+
+    #[test]
+    fn status_capabilities_follow_the_terminal() {
+        let ghostty = terminal_info(TerminalName::Ghostty, Some("ghostty"), None, None, None);
+        assert!(ghostty.supports_progress_indicator());
+        assert!(!ghostty.prefers_osc777_notifications());
+
+        let vte = terminal_info(TerminalName::Vte, None, Some("7600"), None, None);
+        assert!(!vte.supports_progress_indicator());
+        assert!(vte.prefers_osc777_notifications());
+    }
 }
@@ -9,15 +9,28 @@ use crate::codex::CodexSpawnOk;
 use crate::codex::INITIAL_SUBMIT_ID;
 use crate::codex_thread::CodexThread;
 use crate::config::Config;
+use crate::config::ConfigBuilder;
+use crate::config::ConfigOverrides;
 use crate::error::CodexErr;
 use crate::error::Result as CodexResult;
+use crate::event_subscription::AgentEvent;
+use crate::event_subscription::AgentFilter;
+use crate::event_subscription::EVENT_SUBSCRIPTION_CAPACITY;
+use crate::event_subscription::EventCategory;
+use crate::event_subscription::PublishedEvent;
+use crate::event_subscription::subscription_stream;
 use crate::models_manager::manager::ModelsManager;
+use crate::models_manager::manager::RefreshStrategy;
 use crate::protocol::Event;
 use crate::protocol::EventMsg;
 use crate::protocol::SessionConfiguredEvent;
 use crate::rollout::RolloutRecorder;
 use crate::rollout::truncation;
+use crate::session_templates::SessionTemplate;
 use crate::skills::SkillsManager;
+use crate::tools::router::ToolRouter;
+use crate::tools::spec::ToolsConfig;
+use crate::tools::spec::ToolsConfigParams;
 use codex_protocol::ThreadId;
 use codex_protocol::config_types::CollaborationMode;
 use codex_protocol::openai_models::ModelPreset;
@@ -26,6 +39,8 @@ use codex_protocol::protocol::McpServerRefreshConfig;
 use codex_protocol::protocol::Op;
 use codex_protocol::protocol::RolloutItem;
 use codex_protocol::protocol::SessionSource;
+use codex_protocol::user_input::UserInput;
+use futures::Stream;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -59,6 +74,7 @@ pub struct ThreadManager {
 pub(crate) struct ThreadManagerState {
     threads: Arc<RwLock<HashMap<ThreadId, Arc<CodexThread>>>>,
     thread_created_tx: broadcast::Sender<ThreadId>,
+    events_tx: broadcast::Sender<PublishedEvent>,
     auth_manager: Arc<AuthManager>,
     models_manager: Arc<ModelsManager>,
     skills_manager: Arc<SkillsManager>,
@@ -80,6 +96,7 @@ impl ThreadManager {
             state: Arc::new(ThreadManagerState {
                 threads: Arc::new(RwLock::new(HashMap::new())),
                 thread_created_tx,
+                events_tx: broadcast::channel(EVENT_SUBSCRIPTION_CAPACITY).0,
                 models_manager: Arc::new(ModelsManager::new(
                     codex_home.clone(),
                     auth_manager.clone(),
@@ -120,6 +137,7 @@ impl ThreadManager {
             state: Arc::new(ThreadManagerState {
                 threads: Arc::new(RwLock::new(HashMap::new())),
                 thread_created_tx,
+                events_tx: broadcast::channel(EVENT_SUBSCRIPTION_CAPACITY).0,
                 models_manager: Arc::new(ModelsManager::with_provider(
                     codex_home.clone(),
                     auth_manager.clone(),
@@ -191,6 +209,17 @@ impl ThreadManager {
         self.state.thread_created_tx.subscribe()
     }
 
+    /// Subscribe to the events of category `C` emitted by the threads `agents`
+    /// selects, including threads created after subscribing. This is
+    /// independent of [`CodexThread::next_event`], which still receives every
+    /// event.
+    pub fn subscribe<C: EventCategory>(
+        &self,
+        agents: AgentFilter,
+    ) -> impl Stream<Item = AgentEvent<C::Event>> + Send + 'static {
+        subscription_stream::<C>(self.state.events_tx.subscribe(), agents)
+    }
+
     pub async fn get_thread(&self, thread_id: ThreadId) -> CodexResult<Arc<CodexThread>> {
         self.state.get_thread(thread_id).await
     }
@@ -206,6 +235,111 @@ impl ThreadManager {
             .await
     }
 
+    /// Starts a thread from a session template: loads the template's profile
+    /// (when `config` was not already built with it), checks that its
+    /// required tools are available, and submits the rendered prompt together
+    /// with its context files as the first turn.
+    ///
+    /// Reloading the config for a profile keeps `codex_home` and `cwd` but
+    /// not other harness or CLI overrides, so callers that need those should
+    /// build `config` with [`SessionTemplate::profile`] themselves.
+    pub async fn start_thread_from_template(
+        &self,
+        config: Config,
+        template: &SessionTemplate,
+        arguments: HashMap<String, String>,
+    ) -> CodexResult<NewThread> {
+        let prompt = template
+            .render_prompt(&arguments)
+            .map_err(CodexErr::InvalidRequest)?;
+        let config = match &template.profile {
+            Some(profile) if config.active_profile.as_ref() != Some(profile) => {
+                ConfigBuilder::default()
+                    .codex_home(config.codex_home.clone())
+                    .harness_overrides(ConfigOverrides {
+                        cwd: Some(config.cwd.clone()),
+                        config_profile: Some(profile.clone()),
+                        ..ConfigOverrides::default()
+                    })
+                    .build()
+                    .await?
+            }
+            _ => config,
+        };
+        self.check_template_tools(&config, template).await?;
+        let context_files = template
+            .read_context_files()
+            .await
+            .map_err(CodexErr::InvalidRequest)?;
+
+        let new_thread = self.start_thread(config).await?;
+        let items = std::iter::once(prompt)
+            .chain(context_files)
+            .map(|text| UserInput::Text {
+                text,
+                text_elements: Vec::new(),
+            })
+            .collect();
+        new_thread
+            .thread
+            .submit(Op::UserInput {
+                items,
+                final_output_json_schema: None,
+            })
+            .await?;
+        Ok(new_thread)
+    }
+
+    /// Fails when a tool the template requires is neither a built-in tool
+    /// available to the configured model nor an enabled MCP server.
+    async fn check_template_tools(
+        &self,
+        config: &Config,
+        template: &SessionTemplate,
+    ) -> CodexResult<()> {
+        if template.required_tools.is_empty() {
+            return Ok(());
+        }
+        let models_manager = &self.state.models_manager;
+        let model = models_manager
+            .get_default_model(&config.model, config, RefreshStrategy::OnlineIfUncached)
+            .await;
+        let model_info = models_manager.get_model_info(&model, config).await;
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &config.features,
+            web_search_mode: config.web_search_mode,
+        })
+        .with_forbidden_tools(config.forbidden_tools.clone())
+        .with_provider_tools(config.provider_tools.clone());
+        let builtin_tools = ToolRouter::from_config(&tools_config, None).specs();
+        let missing = template
+            .required_tools
+            .iter()
+            .filter(|tool| {
+                let builtin = builtin_tools
+                    .iter()
+                    .any(|spec| spec.name() == tool.as_str());
+                let mcp_server = config
+                    .mcp_servers
+                    .get()
+                    .get(tool.as_str())
+                    .is_some_and(|server| server.enabled);
+                !builtin && !mcp_server
+            })
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(CodexErr::InvalidRequest(format!(
+                "template `{}` requires tools that are not available: {}",
+                template.name,
+                missing.join(", ")
+            )))
+        }
+    }
+
     pub async fn resume_thread_from_rollout(
         &self,
         config: Config,
@@ -266,6 +400,36 @@ impl ThreadManager {
             .await
     }
 
+    /// Fork an existing thread at an arbitrary point: the new thread starts from the
+    /// rollout up to and including the last item that refers to `item_id` (a message,
+    /// reasoning, or tool call id, as reported in item events). Like [`Self::fork_thread`],
+    /// the new thread gets a fresh id and its session metadata records the thread it was
+    /// forked from.
+    pub async fn fork_thread_after_item(
+        &self,
+        config: Config,
+        path: PathBuf,
+        item_id: &str,
+    ) -> CodexResult<NewThread> {
+        let items = RolloutRecorder::get_rollout_history(&path)
+            .await?
+            .get_rollout_items();
+        let Some(items) = truncation::truncate_rollout_after_item_id(&items, item_id) else {
+            return Err(CodexErr::InvalidRequest(format!(
+                "item {item_id} is not recorded in {}",
+                path.display()
+            )));
+        };
+        self.state
+            .spawn_thread(
+                config,
+                InitialHistory::Forked(items),
+                Arc::clone(&self.state.auth_manager),
+                self.agent_control(),
+            )
+            .await
+    }
+
     pub(crate) fn agent_control(&self) -> AgentControl {
         AgentControl::new(Arc::downgrade(&self.state))
     }
@@ -379,6 +543,21 @@ impl ThreadManagerState {
     pub(crate) fn notify_thread_created(&self, thread_id: ThreadId) {
         let _ = self.thread_created_tx.send(thread_id);
     }
+
+    pub(crate) fn subscribe_events(&self) -> broadcast::Receiver<PublishedEvent> {
+        self.events_tx.subscribe()
+    }
+
+    /// Fan `event` out to subscribers. Skips the clone when nobody listens.
+    pub(crate) fn publish_event(&self, thread_id: ThreadId, event: &Event) {
+        if self.events_tx.receiver_count() == 0 {
+            return;
+        }
+        let _ = self.events_tx.send(PublishedEvent {
+            thread_id,
+            event: event.clone(),
+        });
+    }
 }
 
 /// Return a prefix of `items` obtained by cutting strictly before the nth user message
@@ -1,4 +1,6 @@
 use base64::Engine;
+use chrono::DateTime;
+use chrono::Utc;
 use serde::Deserialize;
 use serde::Serialize;
 use thiserror::Error;
@@ -94,15 +96,7 @@ pub enum IdTokenInfoError {
 }
 
 pub fn parse_id_token(id_token: &str) -> Result<IdTokenInfo, IdTokenInfoError> {
-    // JWT format: header.payload.signature
-    let mut parts = id_token.split('.');
-    let (_header_b64, payload_b64, _sig_b64) = match (parts.next(), parts.next(), parts.next()) {
-        (Some(h), Some(p), Some(s)) if !h.is_empty() && !p.is_empty() && !s.is_empty() => (h, p, s),
-        _ => return Err(IdTokenInfoError::InvalidFormat),
-    };
-
-    let payload_bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(payload_b64)?;
-    let claims: IdClaims = serde_json::from_slice(&payload_bytes)?;
+    let claims: IdClaims = serde_json::from_slice(&decode_jwt_payload(id_token)?)?;
 
     match claims.auth {
         Some(auth) => Ok(IdTokenInfo {
@@ -122,6 +116,30 @@ pub fn parse_id_token(id_token: &str) -> Result<IdTokenInfo, IdTokenInfoError> {
     }
 }
 
+#[derive(Deserialize)]
+struct ExpiryClaims {
+    #[serde(default)]
+    exp: Option<i64>,
+}
+
+/// When `jwt` expires, from its `exp` claim. `None` when the token is not a
+/// JWT or has no expiry.
+pub fn jwt_expiry(jwt: &str) -> Option<DateTime<Utc>> {
+    let payload = decode_jwt_payload(jwt).ok()?;
+    let claims: ExpiryClaims = serde_json::from_slice(&payload).ok()?;
+    DateTime::from_timestamp(claims.exp?, 0)
+}
+
+fn decode_jwt_payload(jwt: &str) -> Result<Vec<u8>, IdTokenInfoError> {
+    // JWT format: header.payload.signature
+    let mut parts = jwt.split('.');
+    let payload_b64 = match (parts.next(), parts.next(), parts.next()) {
+        (Some(h), Some(p), Some(s)) if !h.is_empty() && !p.is_empty() && !s.is_empty() => p,
+        _ => return Err(IdTokenInfoError::InvalidFormat),
+    };
+    Ok(base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(payload_b64)?)
+}
+
 fn deserialize_id_token<'de, D>(deserializer: D) -> Result<IdTokenInfo, D::Error>
 where
     D: serde::Deserializer<'de>,
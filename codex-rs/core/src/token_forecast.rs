@@ -0,0 +1,244 @@
+//! Live forecast of a turn's context usage while a response streams.
+//!
+//! Providers only report usage when a response completes, which is too late
+//! to warn that the turn is about to trigger auto-compaction. While a
+//! response streams, [`TokenForecaster`] adds an estimate of the output so
+//! far to an estimate of the request input, both counted with the model's
+//! tokenizer, and projects where the context will end up. The result is sent as a [`TokenForecastEvent`] at
+//! most every [`FORECAST_INTERVAL`].
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::TokenForecastEvent;
+use codex_protocol::protocol::TokenUsage;
+use tokio::time::Instant;
+
+use crate::client_common::PromptTokenCount;
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::context_manager::estimate_item_token_count;
+use crate::tokenizer::CountMethod;
+use crate::tokenizer::Tokenizer;
+use crate::tokenizer::tokenizer_for_model;
+use crate::truncate::approx_tokens_from_byte_count;
+
+/// Minimum time between two forecasts of the same response.
+pub(crate) const FORECAST_INTERVAL: Duration = Duration::from_millis(500);
+
+pub(crate) struct TokenForecaster {
+    tokenizer: Arc<dyn Tokenizer>,
+    /// Tokens of the request input as counted, before the floor below.
+    prompt_tokens: i64,
+    input_tokens: i64,
+    /// Output of the previous response, taken as the least this one will
+    /// produce.
+    expected_output_tokens: i64,
+    /// Output of the items of this response that are done.
+    completed_output_tokens: i64,
+    /// Bytes streamed for the item in progress, estimated with the byte
+    /// heuristic until the item is done.
+    active_output_bytes: usize,
+    model_context_window: Option<i64>,
+    auto_compact_token_limit: Option<i64>,
+    last_sent: Option<(Instant, i64)>,
+}
+
+impl TokenForecaster {
+    fn new(
+        tokenizer: Arc<dyn Tokenizer>,
+        input_tokens: i64,
+        expected_output_tokens: i64,
+        model_context_window: Option<i64>,
+        auto_compact_token_limit: Option<i64>,
+    ) -> Self {
+        Self {
+            tokenizer,
+            prompt_tokens: input_tokens,
+            input_tokens,
+            expected_output_tokens,
+            completed_output_tokens: 0,
+            active_output_bytes: 0,
+            model_context_window,
+            auto_compact_token_limit,
+            last_sent: None,
+        }
+    }
+
+    /// A forecaster for the request just sent, whose input the client
+    /// counted as `prompt_tokens`.
+    pub(crate) async fn for_request(
+        sess: &Session,
+        turn_context: &TurnContext,
+        prompt_tokens: Option<&PromptTokenCount>,
+    ) -> Self {
+        let tokenizer = tokenizer_for_model(&turn_context.client.get_model_info().slug);
+        let prompt_tokens = prompt_tokens.map_or(0, PromptTokenCount::total);
+        let (previous_total, previous_output) = sess.last_response_token_usage().await;
+        // The request repeats everything the previous one used, so its actual
+        // usage is a floor for the estimate.
+        Self {
+            prompt_tokens,
+            ..Self::new(
+                tokenizer,
+                prompt_tokens.max(previous_total),
+                previous_output,
+                turn_context.client.get_model_context_window(),
+                turn_context
+                    .client
+                    .get_model_info()
+                    .auto_compact_token_limit(),
+            )
+        }
+    }
+
+    pub(crate) fn on_delta(&mut self, delta: &str) {
+        self.active_output_bytes = self.active_output_bytes.saturating_add(delta.len());
+    }
+
+    /// Replaces the streamed estimate of the item with one of the whole item,
+    /// which also covers output that is not streamed, like tool call
+    /// arguments and encrypted reasoning.
+    pub(crate) fn on_item_done(&mut self, item: &ResponseItem) {
+        self.completed_output_tokens = self
+            .completed_output_tokens
+            .saturating_add(estimate_item_token_count(self.tokenizer.as_ref(), item));
+        self.active_output_bytes = 0;
+    }
+
+    fn output_tokens(&self) -> i64 {
+        self.completed_output_tokens.saturating_add(
+            i64::try_from(approx_tokens_from_byte_count(self.active_output_bytes))
+                .unwrap_or(i64::MAX),
+        )
+    }
+
+    /// Usage of the response counted with the tokenizer, for providers that
+    /// do not report it.
+    pub(crate) fn estimated_usage(&self) -> TokenUsage {
+        let output_tokens = self.output_tokens();
+        TokenUsage {
+            input_tokens: self.prompt_tokens,
+            output_tokens,
+            total_tokens: self.prompt_tokens.saturating_add(output_tokens),
+            ..TokenUsage::default()
+        }
+    }
+
+    pub(crate) fn forecast(&self) -> TokenForecastEvent {
+        let output_tokens = self.output_tokens();
+        let estimated_tokens = self.input_tokens.saturating_add(output_tokens);
+        let projected_tokens = self
+            .input_tokens
+            .saturating_add(output_tokens.max(self.expected_output_tokens));
+        TokenForecastEvent {
+            estimated_tokens,
+            projected_tokens,
+            model_context_window: self.model_context_window,
+            auto_compact_token_limit: self.auto_compact_token_limit,
+            will_compact: self
+                .auto_compact_token_limit
+                .is_some_and(|limit| projected_tokens >= limit),
+            heuristic: self.tokenizer.method() == CountMethod::Heuristic,
+        }
+    }
+
+    /// The forecast to send at `now`, if the last one is older than
+    /// [`FORECAST_INTERVAL`] and the estimate has changed since.
+    pub(crate) fn poll(&mut self, now: Instant) -> Option<TokenForecastEvent> {
+        let forecast = self.forecast();
+        if let Some((sent_at, estimated_tokens)) = self.last_sent
+            && (now.duration_since(sent_at) < FORECAST_INTERVAL
+                || estimated_tokens == forecast.estimated_tokens)
+        {
+            return None;
+        }
+        self.last_sent = Some((now, forecast.estimated_tokens));
+        Some(forecast)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::HeuristicTokenizer;
+    use codex_protocol::models::ContentItem;
+    use pretty_assertions::assert_eq;
+
+    fn heuristic() -> Arc<dyn Tokenizer> {
+        Arc::new(HeuristicTokenizer)
+    }
+
+    #[test]
+    fn projects_at_least_the_previous_output() {
+        let mut forecaster =
+            TokenForecaster::new(heuristic(), 9_000, 400, Some(10_000), Some(9_300));
+        forecaster.on_delta(&"a".repeat(400));
+
+        assert_eq!(
+            forecaster.forecast(),
+            TokenForecastEvent {
+                estimated_tokens: 9_100,
+                projected_tokens: 9_400,
+                model_context_window: Some(10_000),
+                auto_compact_token_limit: Some(9_300),
+                will_compact: true,
+                heuristic: true,
+            }
+        );
+    }
+
+    #[test]
+    fn completed_items_replace_the_streamed_estimate() {
+        let mut forecaster = TokenForecaster::new(heuristic(), 1_000, 0, None, None);
+        let item = ResponseItem::Message {
+            id: None,
+            role: "assistant".to_string(),
+            content: vec![ContentItem::OutputText {
+                text: "done".repeat(100),
+            }],
+            end_turn: None,
+        };
+        forecaster.on_delta(&"done".repeat(100));
+        forecaster.on_item_done(&item);
+
+        let forecast = forecaster.forecast();
+        assert_eq!(
+            forecast.estimated_tokens,
+            1_000 + estimate_item_token_count(&HeuristicTokenizer, &item)
+        );
+        assert!(!forecast.will_compact);
+    }
+
+    #[test]
+    fn estimated_usage_counts_the_prompt_without_the_floor() {
+        let mut forecaster = TokenForecaster {
+            prompt_tokens: 800,
+            ..TokenForecaster::new(heuristic(), 1_000, 0, None, None)
+        };
+        forecaster.on_delta(&"a".repeat(40));
+
+        assert_eq!(
+            forecaster.estimated_usage(),
+            TokenUsage {
+                input_tokens: 800,
+                output_tokens: 10,
+                total_tokens: 810,
+                ..TokenUsage::default()
+            }
+        );
+    }
+
+    #[test]
+    fn poll_is_throttled_and_skips_unchanged_estimates() {
+        let mut forecaster = TokenForecaster::new(heuristic(), 1_000, 0, None, Some(2_000));
+        let start = Instant::now();
+
+        assert!(forecaster.poll(start).is_some());
+        forecaster.on_delta(&"a".repeat(40));
+        assert_eq!(forecaster.poll(start + FORECAST_INTERVAL / 2), None);
+        assert!(forecaster.poll(start + FORECAST_INTERVAL).is_some());
+        assert_eq!(forecaster.poll(start + FORECAST_INTERVAL * 3), None);
+    }
+}
@@ -0,0 +1,244 @@
+//! Token counting shared by every subsystem that budgets context.
+//!
+//! [`tokenizer_for_model`] returns the [`Tokenizer`] for a model slug: the
+//! model's BPE encoding when it is known, or [`HeuristicTokenizer`] (bytes
+//! divided by [`HEURISTIC_BYTES_PER_TOKEN`]) otherwise. Encodings are loaded
+//! on first use and shared, and the tokenizer chosen for a slug is cached, so
+//! asking again is cheap. Every [`TokenCount`] records which method produced
+//! it, so callers can tell exact counts from estimates.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+use std::sync::PoisonError;
+
+use tiktoken_rs::CoreBPE;
+use tracing::warn;
+
+/// Bytes per token assumed when the model's tokenizer is unknown.
+pub const HEURISTIC_BYTES_PER_TOKEN: usize = 4;
+
+/// BPE encodings built into Codex.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    /// GPT-4o, GPT-4.1, GPT-5, the o-series and Codex models.
+    O200kBase,
+    /// GPT-4 and GPT-3.5.
+    Cl100kBase,
+}
+
+impl Encoding {
+    /// The encoding `model` uses, if it is one Codex knows.
+    pub fn for_model(model: &str) -> Option<Self> {
+        // Provider-qualified slugs, e.g. `openai/gpt-5`.
+        let model = model.rsplit('/').next().unwrap_or(model);
+        const O200K_PREFIXES: &[&str] = &[
+            "gpt-4o", "gpt-4.1", "gpt-4.5", "gpt-5", "gpt-oss", "o1", "o3", "o4", "codex-",
+        ];
+        if O200K_PREFIXES
+            .iter()
+            .any(|prefix| model.starts_with(prefix))
+        {
+            Some(Self::O200kBase)
+        } else if model.starts_with("gpt-4") || model.starts_with("gpt-3.5") {
+            Some(Self::Cl100kBase)
+        } else {
+            None
+        }
+    }
+
+    /// The loaded encoding, or `None` when it failed to load.
+    fn bpe(self) -> Option<&'static CoreBPE> {
+        static O200K_BASE: OnceLock<Option<CoreBPE>> = OnceLock::new();
+        static CL100K_BASE: OnceLock<Option<CoreBPE>> = OnceLock::new();
+        let (cell, load): (_, fn() -> anyhow::Result<CoreBPE>) = match self {
+            Self::O200kBase => (&O200K_BASE, tiktoken_rs::o200k_base),
+            Self::Cl100kBase => (&CL100K_BASE, tiktoken_rs::cl100k_base),
+        };
+        cell.get_or_init(|| match load() {
+            Ok(bpe) => Some(bpe),
+            Err(err) => {
+                warn!("failed to load {self:?} encoding, estimating tokens instead: {err}");
+                None
+            }
+        })
+        .as_ref()
+    }
+}
+
+/// How a [`TokenCount`] was produced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CountMethod {
+    /// Exact, with the model's encoding.
+    Bpe(Encoding),
+    /// Estimated from the byte length.
+    Heuristic,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenCount {
+    pub tokens: u64,
+    pub method: CountMethod,
+}
+
+impl TokenCount {
+    /// Whether the count is an estimate rather than an exact tokenization.
+    pub fn is_estimate(&self) -> bool {
+        self.method == CountMethod::Heuristic
+    }
+}
+
+pub trait Tokenizer: Send + Sync {
+    fn method(&self) -> CountMethod;
+
+    /// Tokens in `text`.
+    fn count_tokens(&self, text: &str) -> u64;
+
+    fn count(&self, text: &str) -> TokenCount {
+        TokenCount {
+            tokens: self.count_tokens(text),
+            method: self.method(),
+        }
+    }
+
+    /// Counts of `texts`, in order.
+    fn count_batch(&self, texts: &[&str]) -> Vec<TokenCount> {
+        texts.iter().map(|text| self.count(text)).collect()
+    }
+
+    /// Sum of the counts of `texts`.
+    fn count_total(&self, texts: &[&str]) -> TokenCount {
+        TokenCount {
+            tokens: self
+                .count_batch(texts)
+                .iter()
+                .fold(0u64, |total, count| total.saturating_add(count.tokens)),
+            method: self.method(),
+        }
+    }
+}
+
+/// Byte-length estimate for models whose tokenizer is unknown.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeuristicTokenizer;
+
+impl HeuristicTokenizer {
+    pub fn tokens_for_bytes(bytes: usize) -> u64 {
+        (bytes as u64).div_ceil(HEURISTIC_BYTES_PER_TOKEN as u64)
+    }
+
+    pub fn bytes_for_tokens(tokens: usize) -> usize {
+        tokens.saturating_mul(HEURISTIC_BYTES_PER_TOKEN)
+    }
+}
+
+impl Tokenizer for HeuristicTokenizer {
+    fn method(&self) -> CountMethod {
+        CountMethod::Heuristic
+    }
+
+    fn count_tokens(&self, text: &str) -> u64 {
+        Self::tokens_for_bytes(text.len())
+    }
+}
+
+struct BpeTokenizer {
+    encoding: Encoding,
+    bpe: &'static CoreBPE,
+}
+
+impl Tokenizer for BpeTokenizer {
+    fn method(&self) -> CountMethod {
+        CountMethod::Bpe(self.encoding)
+    }
+
+    fn count_tokens(&self, text: &str) -> u64 {
+        self.bpe.encode_ordinary(text).len() as u64
+    }
+}
+
+/// The tokenizer for `model`, falling back to [`HeuristicTokenizer`] when
+/// its encoding is unknown or fails to load. Loading an encoding takes a
+/// while the first time, so call this off the async runtime when the model
+/// may be new.
+pub fn tokenizer_for_model(model: &str) -> Arc<dyn Tokenizer> {
+    static BY_MODEL: OnceLock<Mutex<HashMap<String, Arc<dyn Tokenizer>>>> = OnceLock::new();
+    let by_model = BY_MODEL.get_or_init(Default::default);
+    if let Some(tokenizer) = by_model
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(model)
+    {
+        return Arc::clone(tokenizer);
+    }
+    // Load without holding the lock, so other models are not blocked behind
+    // it; concurrent loads of one encoding wait on its `OnceLock` instead.
+    let tokenizer: Arc<dyn Tokenizer> =
+        match Encoding::for_model(model).and_then(|encoding| Some((encoding, encoding.bpe()?))) {
+            Some((encoding, bpe)) => Arc::new(BpeTokenizer { encoding, bpe }),
+            None => Arc::new(HeuristicTokenizer),
+        };
+    Arc::clone(
+        by_model
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(model.to_string())
+            .or_insert(tokenizer),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn maps_models_to_encodings() {
+        assert_eq!(
+            Encoding::for_model("gpt-5.1-codex"),
+            Some(Encoding::O200kBase)
+        );
+        assert_eq!(Encoding::for_model("openai/o3"), Some(Encoding::O200kBase));
+        assert_eq!(
+            Encoding::for_model("gpt-4-turbo"),
+            Some(Encoding::Cl100kBase)
+        );
+        assert_eq!(Encoding::for_model("llama3.2"), None);
+    }
+
+    #[test]
+    fn unknown_models_are_marked_as_estimates() {
+        let counts = tokenizer_for_model("llama3.2").count_batch(&["abcd", "abcde"]);
+
+        assert_eq!(
+            counts,
+            vec![
+                TokenCount {
+                    tokens: 1,
+                    method: CountMethod::Heuristic,
+                },
+                TokenCount {
+                    tokens: 2,
+                    method: CountMethod::Heuristic,
+                },
+            ]
+        );
+        assert!(counts.iter().all(TokenCount::is_estimate));
+    }
+
+    #[test]
+    fn known_models_count_exactly() {
+        let tokenizer = tokenizer_for_model("gpt-5.1-codex");
+        let count = tokenizer.count("hello world");
+
+        assert_eq!(count.method, CountMethod::Bpe(Encoding::O200kBase));
+        assert_eq!(count.tokens, 2);
+        assert_eq!(
+            tokenizer
+                .count_total(&["hello world", "hello world"])
+                .tokens,
+            4
+        );
+    }
+}
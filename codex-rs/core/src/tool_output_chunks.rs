@@ -0,0 +1,194 @@
+//! Chunked delivery of tool outputs that are too large for a single function
+//! result. Instead of truncating the middle of such an output, the model gets
+//! its first chunk together with ordering metadata and can page through the
+//! rest with the `read_output_chunk` tool, e.g. to read a full test log.
+
+use std::collections::VecDeque;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::tools::context::ToolOutput;
+
+pub(crate) const READ_OUTPUT_CHUNK_TOOL_NAME: &str = "read_output_chunk";
+
+/// Number of chunked outputs kept per session; older ones are dropped first.
+const MAX_CHUNKED_OUTPUTS: usize = 32;
+
+/// Room left in each chunk for the metadata header.
+const CHUNK_HEADER_RESERVE_BYTES: usize = 256;
+
+/// Smallest chunk size, whatever the truncation budget.
+const MIN_CHUNK_BYTES: usize = 1024;
+
+/// Chunks of oversized tool outputs, keyed by the call that produced them.
+#[derive(Debug, Default)]
+pub(crate) struct ToolOutputChunks {
+    outputs: VecDeque<(String, Vec<String>)>,
+}
+
+impl ToolOutputChunks {
+    pub(crate) fn insert(&mut self, call_id: String, chunks: Vec<String>) {
+        self.outputs.retain(|(id, _)| id != &call_id);
+        if self.outputs.len() >= MAX_CHUNKED_OUTPUTS {
+            self.outputs.pop_front();
+        }
+        self.outputs.push_back((call_id, chunks));
+    }
+
+    /// The 1-based `chunk` of the output of `call_id`, formatted for the model.
+    pub(crate) fn get(&self, call_id: &str, chunk: usize) -> Result<String, String> {
+        let Some((_, chunks)) = self.outputs.iter().find(|(id, _)| id == call_id) else {
+            return Err(format!("no chunked output for call_id `{call_id}`"));
+        };
+        match chunk.checked_sub(1).and_then(|index| chunks.get(index)) {
+            Some(text) => Ok(format_chunk(call_id, chunk, chunks.len(), text)),
+            None => Err(format!(
+                "chunk must be between 1 and {} for call_id `{call_id}`",
+                chunks.len()
+            )),
+        }
+    }
+}
+
+/// Replaces a function output larger than the turn's truncation budget with
+/// its first chunk and keeps the rest for `read_output_chunk`. Other outputs
+/// are returned unchanged.
+pub(crate) async fn chunk_oversized_output(
+    session: &Session,
+    turn: &TurnContext,
+    call_id: &str,
+    output: ToolOutput,
+) -> ToolOutput {
+    let ToolOutput::Function {
+        content,
+        content_items: None,
+        success,
+    } = output
+    else {
+        return output;
+    };
+    let budget = turn.truncation_policy.byte_budget();
+    if !turn.tools_config.chunked_tool_output || content.len() <= budget {
+        return ToolOutput::Function {
+            content,
+            content_items: None,
+            success,
+        };
+    }
+
+    let chunk_bytes = budget
+        .saturating_sub(CHUNK_HEADER_RESERVE_BYTES)
+        .max(MIN_CHUNK_BYTES);
+    let chunks = split_into_chunks(&content, chunk_bytes);
+    let first = format_chunk(call_id, 1, chunks.len(), &chunks[0]);
+    session
+        .store_tool_output_chunks(call_id.to_string(), chunks)
+        .await;
+    ToolOutput::Function {
+        content: first,
+        content_items: None,
+        success,
+    }
+}
+
+fn format_chunk(call_id: &str, chunk: usize, total: usize, text: &str) -> String {
+    let next = if chunk < total {
+        format!(
+            "call `{READ_OUTPUT_CHUNK_TOOL_NAME}` with call_id \"{call_id}\" and chunk {} for the next one",
+            chunk + 1
+        )
+    } else {
+        "this is the last one".to_string()
+    };
+    format!("[output of call `{call_id}`: chunk {chunk} of {total}; {next}]\n{text}")
+}
+
+/// Splits `text` into pieces of at most `max_bytes`, breaking after a newline
+/// where possible and never inside a UTF-8 character.
+fn split_into_chunks(text: &str, max_bytes: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut rest = text;
+    while rest.len() > max_bytes {
+        let mut end = max_bytes;
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        if let Some(newline) = rest[..end].rfind('\n') {
+            end = newline + 1;
+        } else if end == 0 {
+            // A single character wider than `max_bytes`.
+            end = rest.chars().next().map_or(rest.len(), char::len_utf8);
+        }
+        chunks.push(rest[..end].to_string());
+        rest = &rest[end..];
+    }
+    if !rest.is_empty() || chunks.is_empty() {
+        chunks.push(rest.to_string());
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn splits_on_line_boundaries_and_keeps_every_byte() {
+        let text = "first line\nsecond line\nthird\n";
+
+        let chunks = split_into_chunks(text, 16);
+
+        assert_eq!(chunks, vec!["first line\n", "second line\n", "third\n"]);
+        assert_eq!(chunks.concat(), text);
+    }
+
+    #[test]
+    fn splits_long_lines_on_char_boundaries() {
+        let text = "ééééé";
+
+        let chunks = split_into_chunks(text, 3);
+
+        assert_eq!(chunks, vec!["é", "é", "é", "é", "é"]);
+    }
+
+    #[test]
+    fn chunks_are_numbered_and_point_at_the_next_one() {
+        let mut store = ToolOutputChunks::default();
+        store.insert(
+            "call-1".to_string(),
+            vec!["one\n".to_string(), "two\n".to_string()],
+        );
+
+        assert_eq!(
+            store.get("call-1", 1),
+            Ok(
+                "[output of call `call-1`: chunk 1 of 2; call `read_output_chunk` with call_id \"call-1\" and chunk 2 for the next one]\none\n"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            store.get("call-1", 2),
+            Ok("[output of call `call-1`: chunk 2 of 2; this is the last one]\ntwo\n".to_string())
+        );
+        assert_eq!(
+            store.get("call-1", 3),
+            Err("chunk must be between 1 and 2 for call_id `call-1`".to_string())
+        );
+        assert_eq!(
+            store.get("call-2", 1),
+            Err("no chunked output for call_id `call-2`".to_string())
+        );
+    }
+
+    #[test]
+    fn drops_the_oldest_output_when_full() {
+        let mut store = ToolOutputChunks::default();
+        for index in 0..=MAX_CHUNKED_OUTPUTS {
+            store.insert(format!("call-{index}"), vec!["x".to_string()]);
+        }
+
+        assert!(store.get("call-0", 1).is_err());
+        assert!(store.get(&format!("call-{MAX_CHUNKED_OUTPUTS}"), 1).is_ok());
+    }
+}
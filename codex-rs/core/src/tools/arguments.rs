@@ -0,0 +1,344 @@
+//! Validation of function tool arguments against the tool's declared
+//! parameters, before the call reaches its handler.
+//!
+//! Models get most arguments right, and the mistakes they do make tend to be
+//! small: a number sent as a string, an explicit `null` for an optional
+//! field. Those are coerced. Everything else is reported with the path of the
+//! offending field, which gives the model far more to go on than a serde error.
+
+use serde_json::Map;
+use serde_json::Number;
+use serde_json::Value;
+
+use crate::tools::spec::AdditionalProperties;
+use crate::tools::spec::JsonSchema;
+
+/// Checks `arguments` against `schema` and returns them with coercions
+/// applied, or a message for the model listing every problem found.
+///
+/// Coercions:
+/// - strings holding a number or `true`/`false` where a number or boolean is
+///   expected are converted;
+/// - `null` for an optional property is dropped, so the handler's default
+///   applies;
+/// - empty arguments for a tool that takes an object are read as `{}`.
+///
+/// The returned string is `arguments` unchanged when nothing was coerced.
+pub(crate) fn validate_arguments(
+    tool_name: &str,
+    schema: &JsonSchema,
+    arguments: &str,
+) -> Result<String, String> {
+    let mut value = if arguments.trim().is_empty() && matches!(schema, JsonSchema::Object { .. }) {
+        Value::Object(Map::new())
+    } else {
+        serde_json::from_str(arguments)
+            .map_err(|err| format!("failed to parse function arguments: {err}"))?
+    };
+
+    let mut validator = Validator::default();
+    validator.check(schema, &mut value, &mut Vec::new());
+    if !validator.errors.is_empty() {
+        let mut message = format!("invalid arguments for `{tool_name}`:");
+        for error in validator.errors {
+            message.push_str("\n- ");
+            message.push_str(&error);
+        }
+        message.push_str("\nFix these arguments and call the tool again.");
+        return Err(message);
+    }
+    if validator.coerced || arguments.trim().is_empty() {
+        Ok(value.to_string())
+    } else {
+        Ok(arguments.to_string())
+    }
+}
+
+#[derive(Default)]
+struct Validator {
+    errors: Vec<String>,
+    coerced: bool,
+}
+
+impl Validator {
+    fn check(&mut self, schema: &JsonSchema, value: &mut Value, path: &mut Vec<String>) {
+        match schema {
+            JsonSchema::Boolean { .. } => match value {
+                Value::Bool(_) => {}
+                Value::String(text) if matches!(text.as_str(), "true" | "false") => {
+                    let coerced = text == "true";
+                    *value = Value::Bool(coerced);
+                    self.coerced = true;
+                }
+                _ => self.mismatch(path, "a boolean", value),
+            },
+            JsonSchema::String { .. } => {
+                if !value.is_string() {
+                    self.mismatch(path, "a string", value);
+                }
+            }
+            JsonSchema::Number { .. } => match value {
+                Value::Number(_) => {}
+                Value::String(text) => match parse_number(text) {
+                    Some(number) => {
+                        *value = Value::Number(number);
+                        self.coerced = true;
+                    }
+                    None => self.mismatch(path, "a number", value),
+                },
+                _ => self.mismatch(path, "a number", value),
+            },
+            JsonSchema::Array { items, .. } => match value {
+                Value::Array(elements) => {
+                    for (index, element) in elements.iter_mut().enumerate() {
+                        path.push(format!("[{index}]"));
+                        self.check(items, element, path);
+                        path.pop();
+                    }
+                }
+                _ => self.mismatch(path, "an array", value),
+            },
+            JsonSchema::Object {
+                properties,
+                required,
+                additional_properties,
+            } => {
+                let Value::Object(object) = value else {
+                    self.mismatch(path, "an object", value);
+                    return;
+                };
+                let required = required.as_deref().unwrap_or_default();
+                let optional_nulls: Vec<String> = object
+                    .iter()
+                    .filter(|(key, field)| {
+                        field.is_null()
+                            && properties.contains_key(key.as_str())
+                            && !required.contains(*key)
+                    })
+                    .map(|(key, _)| key.clone())
+                    .collect();
+                for key in optional_nulls {
+                    object.remove(&key);
+                    self.coerced = true;
+                }
+                for name in required {
+                    if object.get(name).is_none_or(Value::is_null) {
+                        self.errors
+                            .push(format!("missing required field `{}`", join(path, name)));
+                    }
+                }
+                for (key, field) in object.iter_mut() {
+                    if field.is_null() && required.contains(key) {
+                        // Already reported as missing.
+                        continue;
+                    }
+                    let field_schema = match (properties.get(key), additional_properties) {
+                        (Some(schema), _) => schema,
+                        (None, Some(AdditionalProperties::Schema(schema))) => schema.as_ref(),
+                        (None, Some(AdditionalProperties::Boolean(false))) => {
+                            self.errors.push(unknown_field(path, key, properties));
+                            continue;
+                        }
+                        (None, _) => continue,
+                    };
+                    path.push(key.clone());
+                    self.check(field_schema, field, path);
+                    path.pop();
+                }
+            }
+        }
+    }
+
+    fn mismatch(&mut self, path: &[String], expected: &str, actual: &Value) {
+        let field = if path.is_empty() {
+            "the arguments".to_string()
+        } else {
+            format!("`{}`", path_string(path))
+        };
+        self.errors.push(format!(
+            "{field}: expected {expected}, got {}",
+            describe(actual)
+        ));
+    }
+}
+
+fn parse_number(text: &str) -> Option<Number> {
+    let text = text.trim();
+    if let Ok(integer) = text.parse::<i64>() {
+        return Some(Number::from(integer));
+    }
+    text.parse::<f64>().ok().and_then(Number::from_f64)
+}
+
+fn unknown_field(
+    path: &[String],
+    key: &str,
+    properties: &std::collections::BTreeMap<String, JsonSchema>,
+) -> String {
+    let known = properties
+        .keys()
+        .map(|name| format!("`{name}`"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "unknown field `{}`; expected one of {known}",
+        join(path, key)
+    )
+}
+
+fn join(path: &[String], key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{key}", path_string(path))
+    }
+}
+
+fn path_string(path: &[String]) -> String {
+    let mut rendered = String::new();
+    for segment in path {
+        if !rendered.is_empty() && !segment.starts_with('[') {
+            rendered.push('.');
+        }
+        rendered.push_str(segment);
+    }
+    rendered
+}
+
+fn describe(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(value) => format!("a boolean ({value})"),
+        Value::Number(value) => format!("a number ({value})"),
+        Value::String(value) => format!("a string ({value:?})"),
+        Value::Array(_) => "an array".to_string(),
+        Value::Object(_) => "an object".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    fn read_file_schema() -> JsonSchema {
+        JsonSchema::Object {
+            properties: BTreeMap::from([
+                (
+                    "file_path".to_string(),
+                    JsonSchema::String { description: None },
+                ),
+                (
+                    "offset".to_string(),
+                    JsonSchema::Number { description: None },
+                ),
+                (
+                    "indentation".to_string(),
+                    JsonSchema::Object {
+                        properties: BTreeMap::from([(
+                            "include_siblings".to_string(),
+                            JsonSchema::Boolean { description: None },
+                        )]),
+                        required: None,
+                        additional_properties: Some(false.into()),
+                    },
+                ),
+                (
+                    "lines".to_string(),
+                    JsonSchema::Array {
+                        items: Box::new(JsonSchema::Number { description: None }),
+                        description: None,
+                    },
+                ),
+            ]),
+            required: Some(vec!["file_path".to_string()]),
+            additional_properties: Some(false.into()),
+        }
+    }
+
+    #[test]
+    fn passes_valid_arguments_through_unchanged() {
+        let arguments = r#"{ "file_path": "/tmp/a", "offset": 3 }"#;
+
+        assert_eq!(
+            validate_arguments("read_file", &read_file_schema(), arguments),
+            Ok(arguments.to_string())
+        );
+    }
+
+    #[test]
+    fn coerces_strings_and_drops_optional_nulls() {
+        let arguments = json!({
+            "file_path": "/tmp/a",
+            "offset": "10",
+            "indentation": { "include_siblings": "true" },
+            "lines": ["1", 2.5],
+        })
+        .to_string();
+
+        let coerced = validate_arguments("read_file", &read_file_schema(), &arguments)
+            .expect("coercible arguments");
+        assert_eq!(
+            serde_json::from_str::<Value>(&coerced).expect("json"),
+            json!({
+                "file_path": "/tmp/a",
+                "offset": 10,
+                "indentation": { "include_siblings": true },
+                "lines": [1, 2.5],
+            })
+        );
+
+        let coerced = validate_arguments(
+            "read_file",
+            &read_file_schema(),
+            r#"{"file_path": "/tmp/a", "offset": null}"#,
+        )
+        .expect("optional null");
+        assert_eq!(coerced, r#"{"file_path":"/tmp/a"}"#);
+    }
+
+    #[test]
+    fn reports_every_problem_with_its_path() {
+        let arguments = json!({
+            "indentation": { "depth": 2, "include_siblings": 1 },
+            "lines": [1, "x"],
+            "offset": "ten",
+        })
+        .to_string();
+
+        let err = validate_arguments("read_file", &read_file_schema(), &arguments)
+            .expect_err("invalid arguments");
+
+        assert_eq!(
+            err,
+            "invalid arguments for `read_file`:\n\
+             - missing required field `file_path`\n\
+             - unknown field `indentation.depth`; expected one of `include_siblings`\n\
+             - `indentation.include_siblings`: expected a boolean, got a number (1)\n\
+             - `lines[1]`: expected a number, got a string (\"x\")\n\
+             - `offset`: expected a number, got a string (\"ten\")\n\
+             Fix these arguments and call the tool again."
+        );
+    }
+
+    #[test]
+    fn reads_empty_arguments_as_an_empty_object() {
+        let schema = JsonSchema::Object {
+            properties: BTreeMap::new(),
+            required: None,
+            additional_properties: Some(false.into()),
+        };
+
+        assert_eq!(
+            validate_arguments("list_mcp_resources", &schema, ""),
+            Ok("{}".to_string())
+        );
+        assert!(
+            validate_arguments("read_file", &read_file_schema(), "not json")
+                .expect_err("malformed json")
+                .starts_with("failed to parse function arguments: ")
+        );
+    }
+}
@@ -3,6 +3,7 @@ use crate::codex::TurnContext;
 use crate::error::CodexErr;
 use crate::error::SandboxErr;
 use crate::exec::ExecToolCallOutput;
+use crate::exec_output_spill::spill_exec_output;
 use crate::function_tool::FunctionCallError;
 use crate::parse_command::parse_command;
 use crate::protocol::EventMsg;
@@ -13,8 +14,16 @@ use crate::protocol::FileChange;
 use crate::protocol::PatchApplyBeginEvent;
 use crate::protocol::PatchApplyEndEvent;
 use crate::protocol::TurnDiffEvent;
+use crate::protocol::WarningEvent;
+use crate::provenance::record_patch;
+use crate::sandboxing::diagnosis::diagnose_sandbox_failure;
 use crate::tools::context::SharedTurnDiffTracker;
 use crate::tools::sandboxing::ToolError;
+use crate::tools::timeouts::TimeoutBudget;
+use crate::truncate::TruncationPolicy;
+use crate::write_quota::WriteQuotaLimits;
+use crate::write_quota::disk_usage;
+use crate::write_quota::patch_bytes;
 use codex_protocol::parse_command::ParsedCommand;
 use std::collections::HashMap;
 use std::path::Path;
@@ -91,6 +100,7 @@ pub(crate) enum ToolEmitter {
         source: ExecCommandSource,
         parsed_cmd: Vec<ParsedCommand>,
         freeform: bool,
+        timeout_budget: Option<TimeoutBudget>,
     },
     ApplyPatch {
         changes: HashMap<PathBuf, FileChange>,
@@ -119,9 +129,18 @@ impl ToolEmitter {
             source,
             parsed_cmd,
             freeform,
+            timeout_budget: None,
         }
     }
 
+    /// Reports `budget` to the model if the shell command times out.
+    pub fn with_timeout_budget(mut self, budget: TimeoutBudget) -> Self {
+        if let Self::Shell { timeout_budget, .. } = &mut self {
+            *timeout_budget = Some(budget);
+        }
+        self
+    }
+
     pub fn apply_patch(changes: HashMap<PathBuf, FileChange>, auto_approved: bool) -> Self {
         Self::ApplyPatch {
             changes,
@@ -157,6 +176,11 @@ impl ToolEmitter {
                 },
                 stage,
             ) => {
+                if let ToolEventStage::Success(output) = &stage
+                    && output.exit_code == 0
+                {
+                    ctx.session.record_shell_reads(parsed_cmd, cwd).await;
+                }
                 emit_exec_stage(
                     ctx,
                     ExecCommandInput::new(command, cwd.as_path(), parsed_cmd, *source, None, None),
@@ -189,6 +213,10 @@ impl ToolEmitter {
                     .await;
             }
             (Self::ApplyPatch { changes, .. }, ToolEventStage::Success(output)) => {
+                if output.exit_code == 0 {
+                    record_writes(ctx, patch_bytes(changes)).await;
+                    record_patch(ctx.session, ctx.turn, ctx.call_id, changes).await;
+                }
                 emit_patch_end(
                     ctx,
                     changes.clone(),
@@ -234,6 +262,11 @@ impl ToolEmitter {
                 },
                 stage,
             ) => {
+                if let ToolEventStage::Success(output) = &stage
+                    && output.exit_code == 0
+                {
+                    ctx.session.record_shell_reads(parsed_cmd, cwd).await;
+                }
                 emit_exec_stage(
                     ctx,
                     ExecCommandInput::new(
@@ -252,19 +285,68 @@ impl ToolEmitter {
     }
 
     pub async fn begin(&self, ctx: ToolEventCtx<'_>) {
+        if ctx.turn.write_quota.needs_baseline() {
+            let usage = measure_writable_roots(ctx.turn).await;
+            ctx.turn.write_quota.set_baseline(usage);
+        }
         self.emit(ctx, ToolEventStage::Begin).await;
     }
 
-    fn format_exec_output_for_model(
+    async fn format_exec_output_for_model(
         &self,
         output: &ExecToolCallOutput,
         ctx: ToolEventCtx<'_>,
     ) -> String {
+        // With chunked delivery the registry pages through the full output
+        // instead of truncating it here.
+        let truncation_policy = if ctx.turn.tools_config.chunked_tool_output {
+            TruncationPolicy::Bytes(usize::MAX)
+        } else {
+            ctx.turn.truncation_policy
+        };
+        let mut content = match self {
+            Self::Shell {
+                freeform: true,
+                timeout_budget,
+                ..
+            } => super::format_exec_output_for_model_freeform(
+                output,
+                truncation_policy,
+                *timeout_budget,
+            ),
+            Self::Shell { timeout_budget, .. } => super::format_exec_output_for_model_structured(
+                output,
+                truncation_policy,
+                *timeout_budget,
+            ),
+            _ => super::format_exec_output_for_model_structured(output, truncation_policy, None),
+        };
+        if !ctx.turn.tools_config.chunked_tool_output
+            && let Some(note) = spill_exec_output(
+                ctx.session,
+                ctx.turn,
+                ctx.call_id,
+                &output.aggregated_output,
+                truncation_policy,
+            )
+            .await
+        {
+            content.push_str("\n\n");
+            content.push_str(&note);
+        }
+        content
+    }
+
+    fn diagnose_sandbox_denial(
+        &self,
+        output: &ExecToolCallOutput,
+        ctx: ToolEventCtx<'_>,
+    ) -> Option<String> {
         match self {
-            Self::Shell { freeform: true, .. } => {
-                super::format_exec_output_for_model_freeform(output, ctx.turn.truncation_policy)
+            Self::Shell { command, cwd, .. } | Self::UnifiedExec { command, cwd, .. } => {
+                diagnose_sandbox_failure(command, output, &ctx.turn.sandbox_policy, cwd)
             }
-            _ => super::format_exec_output_for_model_structured(output, ctx.turn.truncation_policy),
+            Self::ApplyPatch { .. } => None,
         }
     }
 
@@ -275,7 +357,7 @@ impl ToolEmitter {
     ) -> Result<String, FunctionCallError> {
         let (event, result) = match out {
             Ok(output) => {
-                let content = self.format_exec_output_for_model(&output, ctx);
+                let content = self.format_exec_output_for_model(&output, ctx).await;
                 let exit_code = output.exit_code;
                 let event = ToolEventStage::Success(output);
                 let result = if exit_code == 0 {
@@ -285,9 +367,17 @@ impl ToolEmitter {
                 };
                 (event, result)
             }
-            Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Timeout { output })))
-            | Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied { output }))) => {
-                let response = self.format_exec_output_for_model(&output, ctx);
+            Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Timeout { output }))) => {
+                let response = self.format_exec_output_for_model(&output, ctx).await;
+                let event = ToolEventStage::Failure(ToolEventFailure::Output(*output));
+                let result = Err(FunctionCallError::RespondToModel(response));
+                (event, result)
+            }
+            Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied { output }))) => {
+                let mut response = self.format_exec_output_for_model(&output, ctx).await;
+                if let Some(diagnosis) = self.diagnose_sandbox_denial(&output, ctx) {
+                    response.push_str(&format!("\n\nSandbox diagnosis: {diagnosis}"));
+                }
                 let event = ToolEventStage::Failure(ToolEventFailure::Output(*output));
                 let result = Err(FunctionCallError::RespondToModel(response));
                 (event, result)
@@ -379,6 +469,9 @@ async fn emit_exec_stage(
         }
         ToolEventStage::Success(output)
         | ToolEventStage::Failure(ToolEventFailure::Output(output)) => {
+            let output_bytes =
+                u64::try_from(output.aggregated_output.text.len()).unwrap_or(u64::MAX);
+            record_command_writes(ctx, output_bytes).await;
             let exec_result = ExecCommandResult {
                 stdout: output.stdout.text.clone(),
                 stderr: output.stderr.text.clone(),
@@ -404,6 +497,50 @@ async fn emit_exec_stage(
     }
 }
 
+/// Counts `bytes` toward the turn's write quota, warning the user once the
+/// turn passes the warning threshold.
+async fn record_writes(ctx: ToolEventCtx<'_>, bytes: u64) {
+    let limits = WriteQuotaLimits::from(&ctx.turn.client.config().write_quota);
+    if let Some(message) = ctx.turn.write_quota.record(bytes, limits) {
+        warn_write_quota(ctx, message).await;
+    }
+}
+
+/// Counts a finished command toward the turn's write quota by how much the
+/// writable roots grew, or by `output_bytes` when they cannot be measured.
+async fn record_command_writes(ctx: ToolEventCtx<'_>, output_bytes: u64) {
+    let limits = WriteQuotaLimits::from(&ctx.turn.client.config().write_quota);
+    let usage = measure_writable_roots(ctx.turn).await;
+    if let Some(message) = ctx
+        .turn
+        .write_quota
+        .record_command(usage, output_bytes, limits)
+    {
+        warn_write_quota(ctx, message).await;
+    }
+}
+
+async fn warn_write_quota(ctx: ToolEventCtx<'_>, message: String) {
+    ctx.session
+        .send_event(ctx.turn, EventMsg::Warning(WarningEvent { message }))
+        .await;
+}
+
+/// [`disk_usage`] of the turn's cwd and writable roots.
+async fn measure_writable_roots(turn: &TurnContext) -> Option<u64> {
+    let mut roots = vec![turn.cwd.clone()];
+    roots.extend(
+        turn.sandbox_policy
+            .get_writable_roots_with_cwd(&turn.cwd)
+            .into_iter()
+            .map(|root| root.root.into_path_buf()),
+    );
+    tokio::task::spawn_blocking(move || disk_usage(&roots))
+        .await
+        .ok()
+        .flatten()
+}
+
 async fn emit_exec_end(
     ctx: ToolEventCtx<'_>,
     exec_input: ExecCommandInput<'_>,
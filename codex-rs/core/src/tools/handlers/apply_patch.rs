@@ -1,5 +1,6 @@
 use std::collections::BTreeMap;
 use std::path::Path;
+use std::path::PathBuf;
 
 use crate::apply_patch;
 use crate::apply_patch::InternalApplyPatchInvocation;
@@ -10,7 +11,9 @@ use crate::client_common::tools::ResponsesApiTool;
 use crate::client_common::tools::ToolSpec;
 use crate::codex::Session;
 use crate::codex::TurnContext;
+use crate::features::Feature;
 use crate::function_tool::FunctionCallError;
+use crate::stale_reads::format_stale_read_warning;
 use crate::tools::context::SharedTurnDiffTracker;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
@@ -28,8 +31,12 @@ use crate::tools::spec::ApplyPatchToolArgs;
 use crate::tools::spec::JsonSchema;
 use async_trait::async_trait;
 use codex_apply_patch::ApplyPatchAction;
+use codex_apply_patch::ApplyPatchError;
 use codex_apply_patch::ApplyPatchFileChange;
+use codex_apply_patch::Hunk;
+use codex_apply_patch::PatchRebase;
 use codex_utils_absolute_path::AbsolutePathBuf;
+use tokio_util::sync::CancellationToken;
 
 pub struct ApplyPatchHandler;
 
@@ -59,6 +66,26 @@ fn to_abs_path(cwd: &Path, path: &Path) -> Option<AbsolutePathBuf> {
     AbsolutePathBuf::resolve_path_against_base(path, cwd).ok()
 }
 
+/// Files the patch updates or deletes, i.e. the ones the model must have seen
+/// to write it. Unparseable patches yield nothing.
+fn existing_files_in_patch(patch: &str, cwd: &Path) -> Vec<PathBuf> {
+    let Ok(args) = codex_apply_patch::parse_patch(patch) else {
+        return Vec::new();
+    };
+    args.hunks
+        .iter()
+        .filter(|hunk| !matches!(hunk, Hunk::AddFile { .. } | Hunk::AddSymlink { .. }))
+        .map(|hunk| hunk.resolve_path(cwd))
+        .collect()
+}
+
+fn with_stale_read_warning(content: String, warning: &Option<String>) -> String {
+    match warning {
+        Some(warning) => format!("{warning}\n\n{content}"),
+        None => content,
+    }
+}
+
 #[async_trait]
 impl ToolHandler for ApplyPatchHandler {
     fn kind(&self) -> ToolKind {
@@ -84,6 +111,7 @@ impl ToolHandler for ApplyPatchHandler {
             call_id,
             tool_name,
             payload,
+            cancellation_token,
         } = invocation;
 
         let patch_input = match payload {
@@ -102,12 +130,44 @@ impl ToolHandler for ApplyPatchHandler {
         // Re-parse and verify the patch so we can compute changes and approval.
         // Avoid building temporary ExecParams/command vectors; derive directly from inputs.
         let cwd = turn.cwd.clone();
-        let command = vec!["apply_patch".to_string(), patch_input.clone()];
-        match codex_apply_patch::maybe_parse_apply_patch_verified(&command, &cwd) {
+        let stale_reads = session
+            .stale_file_reads(&existing_files_in_patch(&patch_input, &cwd))
+            .await;
+        let stale_read_warning = (!stale_reads.is_empty()).then(|| {
+            format_stale_read_warning(&stale_reads, session.enabled(Feature::RefreshStaleReads))
+        });
+        let mut command = vec!["apply_patch".to_string(), patch_input.clone()];
+        let mut verified = codex_apply_patch::maybe_parse_apply_patch_verified(&command, &cwd);
+        let mut merged_paths = Vec::new();
+        if let codex_apply_patch::MaybeApplyPatchVerified::CorrectnessError(
+            ApplyPatchError::ComputeReplacements(_),
+        ) = &verified
+        {
+            // The patch no longer applies to the files on disk. If we know what
+            // the model last saw, merge its intent with the concurrent changes.
+            let bases = tracker.lock().await.read_snapshots().clone();
+            match codex_apply_patch::maybe_rebase_apply_patch(&command, &cwd, &bases) {
+                Some(Ok(PatchRebase::Rebased {
+                    patch,
+                    merged_paths: paths,
+                })) => {
+                    command = vec!["apply_patch".to_string(), patch];
+                    verified = codex_apply_patch::maybe_parse_apply_patch_verified(&command, &cwd);
+                    merged_paths = paths;
+                }
+                Some(Ok(PatchRebase::Conflicted(conflicts))) => {
+                    return Err(FunctionCallError::RespondToModel(
+                        apply_patch::format_merge_conflicts(&conflicts),
+                    ));
+                }
+                Some(Err(_)) | None => {}
+            }
+        }
+        match verified {
             codex_apply_patch::MaybeApplyPatchVerified::Body(changes) => {
                 match apply_patch::apply_patch(turn.as_ref(), changes).await {
                     InternalApplyPatchInvocation::Output(item) => {
-                        let content = item?;
+                        let content = with_stale_read_warning(item?, &stale_read_warning);
                         Ok(ToolOutput::Function {
                             content,
                             content_items: None,
@@ -117,6 +177,7 @@ impl ToolHandler for ApplyPatchHandler {
                     InternalApplyPatchInvocation::DelegateToExec(apply) => {
                         let changes = convert_apply_patch_to_protocol(&apply.action);
                         let file_paths = file_paths_for_action(&apply.action);
+                        let snapshot_updates = apply_patch::read_snapshot_updates(&apply.action);
                         let emitter =
                             ToolEmitter::apply_patch(changes.clone(), apply.auto_approved);
                         let event_ctx = ToolEventCtx::new(
@@ -143,6 +204,7 @@ impl ToolHandler for ApplyPatchHandler {
                             turn: turn.as_ref(),
                             call_id: call_id.clone(),
                             tool_name: tool_name.to_string(),
+                            cancellation_token,
                         };
                         let out = orchestrator
                             .run(&mut runtime, &req, &tool_ctx, &turn, turn.approval_policy)
@@ -154,6 +216,21 @@ impl ToolHandler for ApplyPatchHandler {
                             Some(&tracker),
                         );
                         let content = emitter.finish(event_ctx, out).await?;
+                        apply_patch::apply_read_snapshot_updates(
+                            session.as_ref(),
+                            &tracker,
+                            snapshot_updates,
+                        )
+                        .await;
+                        let content = if merged_paths.is_empty() {
+                            content
+                        } else {
+                            format!(
+                                "{}\n{content}",
+                                apply_patch::format_merge_notice(&merged_paths)
+                            )
+                        };
+                        let content = with_stale_read_warning(content, &stale_read_warning);
                         Ok(ToolOutput::Function {
                             content,
                             content_items: None,
@@ -163,8 +240,9 @@ impl ToolHandler for ApplyPatchHandler {
                 }
             }
             codex_apply_patch::MaybeApplyPatchVerified::CorrectnessError(parse_error) => {
-                Err(FunctionCallError::RespondToModel(format!(
-                    "apply_patch verification failed: {parse_error}"
+                Err(FunctionCallError::RespondToModel(with_stale_read_warning(
+                    format!("apply_patch verification failed: {parse_error}"),
+                    &stale_read_warning,
                 )))
             }
             codex_apply_patch::MaybeApplyPatchVerified::ShellParseError(error) => {
@@ -192,6 +270,7 @@ pub(crate) async fn intercept_apply_patch(
     tracker: Option<&SharedTurnDiffTracker>,
     call_id: &str,
     tool_name: &str,
+    cancellation_token: &CancellationToken,
 ) -> Result<Option<ToolOutput>, FunctionCallError> {
     match codex_apply_patch::maybe_parse_apply_patch_verified(command, cwd) {
         codex_apply_patch::MaybeApplyPatchVerified::Body(changes) => {
@@ -234,6 +313,7 @@ pub(crate) async fn intercept_apply_patch(
                         turn,
                         call_id: call_id.to_string(),
                         tool_name: tool_name.to_string(),
+                        cancellation_token: cancellation_token.clone(),
                     };
                     let out = orchestrator
                         .run(&mut runtime, &req, &tool_ctx, turn, turn.approval_policy)
@@ -297,13 +377,15 @@ Your patch language is a stripped‑down, file‑oriented diff format designed t
 
 Within that envelope, you get a sequence of file operations.
 You MUST include a header to specify the action you are taking.
-Each operation starts with one of three headers:
+Each operation starts with one of four headers:
 
 *** Add File: <path> - create a new file. Every following line is a + line (the initial contents).
 *** Delete File: <path> - remove an existing file. Nothing follows.
 *** Update File: <path> - patch an existing file in place (optionally with a rename).
+*** Add Symlink: <path> -> <target> - create or replace a symlink. <target> is relative to the link's directory. Nothing follows.
 
 May be immediately followed by *** Move to: <new path> if you want to rename the file.
+An Add File or Update File header (after any Move to) may be followed by *** Mode: executable or *** Mode: regular to set or clear the executable bit; an Update File that only renames the file or changes its mode needs no hunks.
 Then one or more “hunks”, each introduced by @@ (optionally followed by a hunk header).
 Within a hunk each line starts with:
 
@@ -329,11 +411,13 @@ The full grammar definition is below:
 Patch := Begin { FileOp } End
 Begin := "*** Begin Patch" NEWLINE
 End := "*** End Patch" NEWLINE
-FileOp := AddFile | DeleteFile | UpdateFile
-AddFile := "*** Add File: " path NEWLINE { "+" line NEWLINE }
+FileOp := AddFile | DeleteFile | UpdateFile | AddSymlink
+AddFile := "*** Add File: " path NEWLINE [ Mode ] { "+" line NEWLINE }
 DeleteFile := "*** Delete File: " path NEWLINE
-UpdateFile := "*** Update File: " path NEWLINE [ MoveTo ] { Hunk }
+UpdateFile := "*** Update File: " path NEWLINE [ MoveTo ] [ Mode ] { Hunk }
+AddSymlink := "*** Add Symlink: " path " -> " target NEWLINE
 MoveTo := "*** Move to: " newPath NEWLINE
+Mode := "*** Mode: " ("executable" | "regular") NEWLINE
 Hunk := "@@" [ header ] NEWLINE { HunkLine } [ "*** End of File" NEWLINE ]
 HunkLine := (" " | "-" | "+") text NEWLINE
 
@@ -396,4 +480,27 @@ mod tests {
         let keys = file_paths_for_action(&action);
         assert_eq!(keys.len(), 2);
     }
+
+    #[test]
+    fn existing_files_in_patch_skips_added_files() {
+        let cwd = Path::new("/repo");
+        let patch = r#"*** Begin Patch
+*** Add File: new.txt
++hello
+*** Update File: src/lib.rs
+@@
+-old
++new
+*** Delete File: old.txt
+*** End Patch"#;
+
+        assert_eq!(
+            existing_files_in_patch(patch, cwd),
+            vec![cwd.join("src/lib.rs"), cwd.join("old.txt")]
+        );
+        assert_eq!(
+            existing_files_in_patch("not a patch", cwd),
+            Vec::<PathBuf>::new()
+        );
+    }
 }
@@ -0,0 +1,609 @@
+//! `bulk_edit` applies several file creations, renames, deletions and small
+//! edits as one transaction. The operations are compiled into a single
+//! `apply_patch` call; the touched files are snapshotted in a ghost commit
+//! first so a failed apply can be rolled back completely.
+
+use std::fmt::Write as _;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use codex_apply_patch::MaybeApplyPatchVerified;
+use codex_git::CreateGhostCommitOptions;
+use codex_git::create_ghost_commit;
+use codex_git::restore_paths_from_ghost_commit;
+use serde::Deserialize;
+
+use crate::apply_patch;
+use crate::apply_patch::InternalApplyPatchInvocation;
+use crate::apply_patch::convert_apply_patch_to_protocol;
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::events::ToolEmitter;
+use crate::tools::events::ToolEventCtx;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::orchestrator::ToolOrchestrator;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::tools::runtimes::apply_patch::ApplyPatchRequest;
+use crate::tools::runtimes::apply_patch::ApplyPatchRuntime;
+use crate::tools::sandboxing::ToolCtx;
+use crate::tools::sandboxing::ToolError;
+use codex_utils_absolute_path::AbsolutePathBuf;
+
+pub struct BulkEditHandler;
+
+#[derive(Debug, Deserialize)]
+struct BulkEditArgs {
+    operations: Vec<BulkEditOperation>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum BulkEditOperation {
+    Create {
+        path: String,
+        content: String,
+    },
+    Rename {
+        path: String,
+        new_path: String,
+    },
+    Delete {
+        path: String,
+    },
+    Edit {
+        path: String,
+        old_text: String,
+        new_text: String,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+enum PlannedChange {
+    Add {
+        content: String,
+    },
+    Delete,
+    Update {
+        original: String,
+        content: String,
+        move_path: Option<PathBuf>,
+    },
+}
+
+/// The net effect of every operation on one file, keyed by the path the file
+/// had before the transaction (or the path it is created at).
+#[derive(Debug, PartialEq)]
+struct PlannedFile {
+    path: PathBuf,
+    change: PlannedChange,
+}
+
+impl PlannedFile {
+    /// Where the file lives once the transaction is applied, if it survives.
+    fn live_path(&self) -> Option<&Path> {
+        match &self.change {
+            PlannedChange::Delete => None,
+            PlannedChange::Update {
+                move_path: Some(move_path),
+                ..
+            } => Some(move_path),
+            PlannedChange::Add { .. } | PlannedChange::Update { .. } => Some(&self.path),
+        }
+    }
+
+    fn touches(&self, path: &Path) -> bool {
+        self.path == path
+            || matches!(
+                &self.change,
+                PlannedChange::Update { move_path: Some(move_path), .. } if move_path == path
+            )
+    }
+}
+
+fn relative_path(raw: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(raw);
+    let escapes = path
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_) | Component::CurDir));
+    if raw.is_empty() || escapes {
+        return Err(format!(
+            "`{raw}`: paths must be relative to the working directory and stay inside it"
+        ));
+    }
+    Ok(path)
+}
+
+fn read_existing_file(cwd: &Path, path: &Path) -> Result<String, String> {
+    let full_path = cwd.join(path);
+    if !full_path.is_file() {
+        return Err(format!("{}: no such file", path.display()));
+    }
+    std::fs::read_to_string(&full_path)
+        .map_err(|err| format!("failed to read {}: {err}", path.display()))
+}
+
+fn ensure_untouched(plan: &[PlannedFile], cwd: &Path, path: &Path) -> Result<(), String> {
+    if plan.iter().any(|file| file.touches(path)) {
+        return Err(format!(
+            "{} is already touched by an earlier operation",
+            path.display()
+        ));
+    }
+    if cwd.join(path).symlink_metadata().is_ok() {
+        return Err(format!("{} already exists", path.display()));
+    }
+    Ok(())
+}
+
+/// Returns the index of the planned file currently at `path`, reading it from
+/// disk into the plan if no earlier operation touched it.
+fn live_file_index(plan: &mut Vec<PlannedFile>, cwd: &Path, path: &Path) -> Result<usize, String> {
+    if let Some(index) = plan.iter().position(|file| file.live_path() == Some(path)) {
+        return Ok(index);
+    }
+    if plan.iter().any(|file| file.touches(path)) {
+        return Err(format!("{}: no such file", path.display()));
+    }
+    let original = read_existing_file(cwd, path)?;
+    plan.push(PlannedFile {
+        path: path.to_path_buf(),
+        change: PlannedChange::Update {
+            content: original.clone(),
+            original,
+            move_path: None,
+        },
+    });
+    Ok(plan.len() - 1)
+}
+
+fn replace_once(
+    content: &mut String,
+    path: &Path,
+    old_text: &str,
+    new_text: &str,
+) -> Result<(), String> {
+    if old_text.is_empty() {
+        return Err(format!(
+            "edit of {}: `old_text` must not be empty",
+            path.display()
+        ));
+    }
+    match content.matches(old_text).count() {
+        0 => Err(format!(
+            "edit of {}: `old_text` was not found",
+            path.display()
+        )),
+        1 => {
+            *content = content.replacen(old_text, new_text, 1);
+            Ok(())
+        }
+        count => Err(format!(
+            "edit of {}: `old_text` matches {count} times; include more surrounding text so it matches exactly once",
+            path.display()
+        )),
+    }
+}
+
+/// Folds the operations, in order, into one planned change per file.
+fn plan_operations(
+    cwd: &Path,
+    operations: Vec<BulkEditOperation>,
+) -> Result<Vec<PlannedFile>, String> {
+    let mut plan: Vec<PlannedFile> = Vec::new();
+    for operation in operations {
+        match operation {
+            BulkEditOperation::Create { path, content } => {
+                let path = relative_path(&path)?;
+                ensure_untouched(&plan, cwd, &path)?;
+                plan.push(PlannedFile {
+                    path,
+                    change: PlannedChange::Add { content },
+                });
+            }
+            BulkEditOperation::Delete { path } => {
+                let path = relative_path(&path)?;
+                if plan.iter().any(|file| file.touches(&path)) {
+                    return Err(format!(
+                        "{} is already touched by an earlier operation",
+                        path.display()
+                    ));
+                }
+                read_existing_file(cwd, &path)?;
+                plan.push(PlannedFile {
+                    path,
+                    change: PlannedChange::Delete,
+                });
+            }
+            BulkEditOperation::Rename { path, new_path } => {
+                let path = relative_path(&path)?;
+                let new_path = relative_path(&new_path)?;
+                ensure_untouched(&plan, cwd, &new_path)?;
+                let index = live_file_index(&mut plan, cwd, &path)?;
+                let file = &mut plan[index];
+                match &mut file.change {
+                    PlannedChange::Add { .. } => file.path = new_path,
+                    PlannedChange::Update { move_path, .. } => *move_path = Some(new_path),
+                    PlannedChange::Delete => {
+                        return Err(format!("{}: no such file", path.display()));
+                    }
+                }
+            }
+            BulkEditOperation::Edit {
+                path,
+                old_text,
+                new_text,
+            } => {
+                let path = relative_path(&path)?;
+                let index = live_file_index(&mut plan, cwd, &path)?;
+                match &mut plan[index].change {
+                    PlannedChange::Add { content } | PlannedChange::Update { content, .. } => {
+                        replace_once(content, &path, &old_text, &new_text)?;
+                    }
+                    PlannedChange::Delete => {
+                        return Err(format!("{}: no such file", path.display()));
+                    }
+                }
+            }
+        }
+    }
+    Ok(plan)
+}
+
+/// Renders the plan as an `apply_patch` envelope. Edited files are written as
+/// a single full-replacement hunk, which is exact regardless of context.
+fn patch_for_plan(plan: &[PlannedFile]) -> Option<String> {
+    let mut body = String::new();
+    for file in plan {
+        let path = file.path.display();
+        match &file.change {
+            PlannedChange::Add { content } => {
+                let _ = writeln!(body, "*** Add File: {path}");
+                for line in content.lines() {
+                    let _ = writeln!(body, "+{line}");
+                }
+            }
+            PlannedChange::Delete => {
+                let _ = writeln!(body, "*** Delete File: {path}");
+            }
+            PlannedChange::Update {
+                original,
+                content,
+                move_path,
+            } => {
+                if original == content && move_path.is_none() {
+                    continue;
+                }
+                let _ = writeln!(body, "*** Update File: {path}");
+                if let Some(move_path) = move_path {
+                    let _ = writeln!(body, "*** Move to: {}", move_path.display());
+                }
+                if original != content {
+                    body.push_str("@@\n");
+                    for line in original.lines() {
+                        let _ = writeln!(body, "-{line}");
+                    }
+                    for line in content.lines() {
+                        let _ = writeln!(body, "+{line}");
+                    }
+                }
+            }
+        }
+    }
+    (!body.is_empty()).then(|| format!("*** Begin Patch\n{body}*** End Patch"))
+}
+
+/// Every path the transaction may create, modify or remove.
+fn touched_paths(plan: &[PlannedFile]) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    for file in plan {
+        paths.push(file.path.clone());
+        if let PlannedChange::Update {
+            move_path: Some(move_path),
+            ..
+        } = &file.change
+        {
+            paths.push(move_path.clone());
+        }
+    }
+    paths
+}
+
+#[async_trait]
+impl ToolHandler for BulkEditHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn is_mutating(&self, _invocation: &ToolInvocation) -> bool {
+        true
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            tracker,
+            call_id,
+            tool_name,
+            payload,
+            cancellation_token,
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "bulk_edit handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+        let args: BulkEditArgs = parse_arguments(&arguments)?;
+        if args.operations.is_empty() {
+            return Err(FunctionCallError::RespondToModel(
+                "bulk_edit requires at least one operation".to_string(),
+            ));
+        }
+
+        let cwd = turn.cwd.clone();
+        let plan = plan_operations(&cwd, args.operations).map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "bulk_edit failed, nothing was changed: {err}"
+            ))
+        })?;
+        let Some(patch) = patch_for_plan(&plan) else {
+            return Ok(ToolOutput::Function {
+                content: "bulk_edit: the operations leave every file unchanged.".to_string(),
+                content_items: None,
+                success: Some(true),
+            });
+        };
+        let command = vec!["apply_patch".to_string(), patch];
+        let action = match codex_apply_patch::maybe_parse_apply_patch_verified(&command, &cwd) {
+            MaybeApplyPatchVerified::Body(action) => action,
+            MaybeApplyPatchVerified::CorrectnessError(err) => {
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "bulk_edit failed, nothing was changed: {err}"
+                )));
+            }
+            MaybeApplyPatchVerified::ShellParseError(err) => {
+                tracing::trace!("bulk_edit produced an unparseable patch: {err:?}");
+                return Err(FunctionCallError::RespondToModel(
+                    "bulk_edit failed, nothing was changed: could not build a patch".to_string(),
+                ));
+            }
+            MaybeApplyPatchVerified::NotApplyPatch => {
+                return Err(FunctionCallError::RespondToModel(
+                    "bulk_edit failed, nothing was changed: could not build a patch".to_string(),
+                ));
+            }
+        };
+
+        // Snapshot the touched files before anything is written so a failed
+        // apply can be undone. Paths that do not exist yet are removed on
+        // rollback because the snapshot does not contain them.
+        let touched = touched_paths(&plan);
+        let repo_path = cwd.clone();
+        let ghost_snapshot = turn.ghost_snapshot.clone();
+        let existing = touched
+            .iter()
+            .filter(|path| cwd.join(path).symlink_metadata().is_ok())
+            .cloned()
+            .collect::<Vec<_>>();
+        let snapshot = tokio::task::spawn_blocking(move || {
+            create_ghost_commit(
+                &CreateGhostCommitOptions::new(&repo_path)
+                    .ghost_snapshot(ghost_snapshot)
+                    .force_include(existing),
+            )
+        })
+        .await
+        .map_err(|err| {
+            FunctionCallError::RespondToModel(format!("bulk_edit snapshot task failed: {err}"))
+        })?
+        .map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "bulk_edit needs a git repository to snapshot the working tree ({err}); \
+                 use apply_patch instead"
+            ))
+        })?;
+
+        let apply = match apply_patch::apply_patch(turn.as_ref(), action).await {
+            InternalApplyPatchInvocation::Output(item) => {
+                return Ok(ToolOutput::Function {
+                    content: item?,
+                    content_items: None,
+                    success: Some(true),
+                });
+            }
+            InternalApplyPatchInvocation::DelegateToExec(apply) => apply,
+        };
+
+        let changes = convert_apply_patch_to_protocol(&apply.action);
+        let file_paths = touched
+            .iter()
+            .filter_map(|path| AbsolutePathBuf::resolve_path_against_base(path, &cwd).ok())
+            .collect();
+        let snapshot_updates = apply_patch::read_snapshot_updates(&apply.action);
+        let emitter = ToolEmitter::apply_patch(changes.clone(), apply.auto_approved);
+        let event_ctx =
+            ToolEventCtx::new(session.as_ref(), turn.as_ref(), &call_id, Some(&tracker));
+        emitter.begin(event_ctx).await;
+
+        let req = ApplyPatchRequest {
+            action: apply.action,
+            file_paths,
+            changes,
+            exec_approval_requirement: apply.exec_approval_requirement,
+            timeout_ms: None,
+            codex_exe: turn.codex_linux_sandbox_exe.clone(),
+        };
+        let mut orchestrator = ToolOrchestrator::new();
+        let mut runtime = ApplyPatchRuntime::new();
+        let tool_ctx = ToolCtx {
+            session: session.as_ref(),
+            turn: turn.as_ref(),
+            call_id: call_id.clone(),
+            tool_name: tool_name.to_string(),
+            cancellation_token,
+        };
+        let out = orchestrator
+            .run(&mut runtime, &req, &tool_ctx, &turn, turn.approval_policy)
+            .await;
+        // A rejected patch never ran, so there is nothing to undo.
+        let needs_rollback = match &out {
+            Ok(output) => output.exit_code != 0,
+            Err(ToolError::Rejected(_)) => false,
+            Err(ToolError::Codex(_)) => true,
+        };
+        let event_ctx =
+            ToolEventCtx::new(session.as_ref(), turn.as_ref(), &call_id, Some(&tracker));
+        match emitter.finish(event_ctx, out).await {
+            Ok(content) => {
+                apply_patch::apply_read_snapshot_updates(
+                    session.as_ref(),
+                    &tracker,
+                    snapshot_updates,
+                )
+                .await;
+                Ok(ToolOutput::Function {
+                    content,
+                    content_items: None,
+                    success: Some(true),
+                })
+            }
+            Err(FunctionCallError::RespondToModel(message)) if needs_rollback => {
+                let repo_path = cwd.clone();
+                let rollback = tokio::task::spawn_blocking(move || {
+                    restore_paths_from_ghost_commit(&repo_path, &snapshot, &touched)
+                })
+                .await;
+                let outcome = match rollback {
+                    Ok(Ok(())) => {
+                        "The transaction was rolled back; no files were changed.".to_string()
+                    }
+                    Ok(Err(err)) => format!(
+                        "Rolling back the transaction failed ({err}); some files may be partially modified."
+                    ),
+                    Err(err) => format!(
+                        "Rolling back the transaction failed ({err}); some files may be partially modified."
+                    ),
+                };
+                Err(FunctionCallError::RespondToModel(format!(
+                    "{message}\n\n{outcome}"
+                )))
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn plan(cwd: &Path, operations: serde_json::Value) -> Result<Vec<PlannedFile>, String> {
+        let args: BulkEditArgs =
+            serde_json::from_value(serde_json::json!({ "operations": operations }))
+                .expect("valid arguments");
+        plan_operations(cwd, args.operations)
+    }
+
+    #[test]
+    fn folds_operations_into_one_patch() {
+        let tmp = TempDir::new().expect("tmp");
+        let cwd = tmp.path();
+        std::fs::write(cwd.join("old.rs"), "fn a() {}\nfn b() {}\n").expect("write old.rs");
+        std::fs::write(cwd.join("moved.rs"), "pub mod x;\n").expect("write moved.rs");
+        std::fs::write(cwd.join("gone.rs"), "unused\n").expect("write gone.rs");
+
+        let plan = plan(
+            cwd,
+            serde_json::json!([
+                { "type": "create", "path": "new.rs", "content": "fn c() {}\n" },
+                { "type": "rename", "path": "old.rs", "new_path": "renamed.rs" },
+                { "type": "edit", "path": "renamed.rs", "old_text": "fn b", "new_text": "fn d" },
+                { "type": "rename", "path": "moved.rs", "new_path": "lib.rs" },
+                { "type": "delete", "path": "gone.rs" },
+            ]),
+        )
+        .expect("plan");
+
+        assert_eq!(
+            patch_for_plan(&plan).expect("patch"),
+            "*** Begin Patch\n\
+             *** Add File: new.rs\n\
+             +fn c() {}\n\
+             *** Update File: old.rs\n\
+             *** Move to: renamed.rs\n\
+             @@\n\
+             -fn a() {}\n\
+             -fn b() {}\n\
+             +fn a() {}\n\
+             +fn d() {}\n\
+             *** Update File: moved.rs\n\
+             *** Move to: lib.rs\n\
+             *** Delete File: gone.rs\n\
+             *** End Patch"
+        );
+        assert_eq!(
+            touched_paths(&plan),
+            vec![
+                PathBuf::from("new.rs"),
+                PathBuf::from("old.rs"),
+                PathBuf::from("renamed.rs"),
+                PathBuf::from("moved.rs"),
+                PathBuf::from("lib.rs"),
+                PathBuf::from("gone.rs"),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_conflicting_or_ambiguous_operations() {
+        let tmp = TempDir::new().expect("tmp");
+        let cwd = tmp.path();
+        std::fs::write(cwd.join("a.txt"), "x\nx\n").expect("write a.txt");
+
+        assert_eq!(
+            plan(
+                cwd,
+                serde_json::json!([{ "type": "edit", "path": "a.txt", "old_text": "x", "new_text": "y" }]),
+            ),
+            Err("edit of a.txt: `old_text` matches 2 times; include more surrounding text so it matches exactly once".to_string())
+        );
+        assert_eq!(
+            plan(
+                cwd,
+                serde_json::json!([
+                    { "type": "delete", "path": "a.txt" },
+                    { "type": "edit", "path": "a.txt", "old_text": "x", "new_text": "y" },
+                ]),
+            ),
+            Err("a.txt: no such file".to_string())
+        );
+        assert_eq!(
+            plan(
+                cwd,
+                serde_json::json!([{ "type": "create", "path": "a.txt", "content": "" }]),
+            ),
+            Err("a.txt already exists".to_string())
+        );
+        assert_eq!(
+            plan(
+                cwd,
+                serde_json::json!([{ "type": "delete", "path": "../a.txt" }]),
+            ),
+            Err(
+                "`../a.txt`: paths must be relative to the working directory and stay inside it"
+                    .to_string()
+            )
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct ClipboardHandler;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ClipboardAction {
+    Set,
+    Get,
+    List,
+}
+
+#[derive(Deserialize)]
+struct ClipboardArgs {
+    action: ClipboardAction,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    content: Option<String>,
+}
+
+#[async_trait]
+impl ToolHandler for ClipboardHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "clipboard handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: ClipboardArgs = parse_arguments(&arguments)?;
+        let require_name = || {
+            args.name.clone().ok_or_else(|| {
+                FunctionCallError::RespondToModel("`name` is required for this action".to_string())
+            })
+        };
+        let content = match args.action {
+            ClipboardAction::Set => {
+                let name = require_name()?;
+                let content = args.content.clone().unwrap_or_default();
+                let removed = content.is_empty();
+                session
+                    .store_clip(&turn, &name, content, true)
+                    .await
+                    .map_err(FunctionCallError::RespondToModel)?;
+                if removed {
+                    format!("Removed clip `{name}`.")
+                } else {
+                    format!("Stored clip `{name}`; reference it as {{{{clip:{name}}}}}.")
+                }
+            }
+            ClipboardAction::Get => {
+                let name = require_name()?;
+                session.clip(&name).await.ok_or_else(|| {
+                    FunctionCallError::RespondToModel(format!("unknown clip `{name}`"))
+                })?
+            }
+            ClipboardAction::List => session.describe_clipboard().await,
+        };
+
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
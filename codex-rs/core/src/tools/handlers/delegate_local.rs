@@ -0,0 +1,426 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use codex_protocol::models::BaseInstructions;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use futures::StreamExt;
+use serde::Deserialize;
+
+use crate::Prompt;
+use crate::client::ModelClient;
+use crate::client_common::ResponseEvent;
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::compact::content_items_to_text;
+use crate::error::CodexErr;
+use crate::error::Result as CodexResult;
+use crate::exec_output_spill::read_spilled_output;
+use crate::function_tool::FunctionCallError;
+use crate::models_manager::model_info;
+use crate::tokenizer::Tokenizer;
+use crate::tokenizer::tokenizer_for_model;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::truncate::TruncationPolicy;
+use crate::truncate::truncate_text;
+
+const DELEGATE_INSTRUCTIONS: &str =
+    include_str!("../../../templates/delegate_local/instructions.md");
+
+/// Largest file the tool reads for the local model.
+const MAX_INPUT_FILE_BYTES: u64 = 4 * 1024 * 1024;
+
+pub struct DelegateLocalHandler;
+
+#[derive(Deserialize)]
+struct DelegateLocalArgs {
+    task: String,
+    #[serde(default)]
+    input: Option<String>,
+    #[serde(default)]
+    paths: Vec<String>,
+    #[serde(default)]
+    call_ids: Vec<String>,
+    #[serde(default)]
+    max_output_tokens: Option<u64>,
+}
+
+/// One piece of text the task works on and where it came from, if not from
+/// the `input` argument.
+#[derive(Debug, PartialEq)]
+struct DelegateInput {
+    source: Option<String>,
+    text: String,
+}
+
+/// The local model's answer, cut at the token cap.
+#[derive(Debug, PartialEq)]
+struct Delegated {
+    text: String,
+    truncated: bool,
+}
+
+#[async_trait]
+impl ToolHandler for DelegateLocalHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            payload,
+            cancellation_token,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "delegate_local handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: DelegateLocalArgs = parse_arguments(&arguments)?;
+        if args.task.trim().is_empty() {
+            return Err(FunctionCallError::RespondToModel(
+                "`task` must not be empty".to_string(),
+            ));
+        }
+        let config = turn.client.config();
+        let settings = &config.delegate_local;
+        let max_output_tokens = args
+            .max_output_tokens
+            .map_or(settings.max_output_tokens(), |tokens| {
+                tokens.min(settings.max_output_tokens())
+            })
+            .max(1);
+        let inputs = collect_inputs(session.as_ref(), turn.as_ref(), &args).await?;
+        let client = local_client(session.as_ref(), turn.as_ref())?;
+        let tokenizer = tokenizer_for_model(&client.get_model());
+        let prompt = Prompt {
+            input: vec![ResponseItem::Message {
+                id: None,
+                role: "user".to_string(),
+                content: vec![ContentItem::InputText {
+                    text: request_text(&args.task, &inputs),
+                }],
+                end_turn: None,
+            }],
+            base_instructions: BaseInstructions {
+                text: DELEGATE_INSTRUCTIONS.to_string(),
+            },
+            ..Default::default()
+        };
+
+        let timeout = settings.timeout();
+        let delegated = tokio::select! {
+            delegated = tokio::time::timeout(
+                timeout,
+                run_delegate(&client, &prompt, tokenizer.as_ref(), max_output_tokens),
+            ) => delegated,
+            _ = cancellation_token.cancelled() => {
+                return Err(FunctionCallError::RespondToModel(
+                    "delegate_local call was cancelled".to_string(),
+                ));
+            }
+        };
+        let Delegated { text, truncated } = match delegated {
+            Ok(Ok(delegated)) => delegated,
+            Ok(Err(err)) => {
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "local model {} failed: {err}",
+                    client.get_model()
+                )));
+            }
+            Err(_) => {
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "local model {} did not finish within {}s",
+                    client.get_model(),
+                    timeout.as_secs()
+                )));
+            }
+        };
+
+        let content = if truncated {
+            format!("{text}\n\n[answer cut off at {max_output_tokens} tokens]")
+        } else {
+            text
+        };
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+/// A client for the `[delegate_local]` model, on the turn's session.
+fn local_client(session: &Session, turn: &TurnContext) -> Result<ModelClient, FunctionCallError> {
+    let config = turn.client.config();
+    let settings = &config.delegate_local;
+    let Some(model) = settings.model.clone() else {
+        return Err(FunctionCallError::RespondToModel(
+            "no local model is configured; the user must set `model` under `[delegate_local]`"
+                .to_string(),
+        ));
+    };
+    let provider_id = settings.provider();
+    let Some(provider) = config.model_providers.get(provider_id).cloned() else {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "unknown model provider `{provider_id}` under `[delegate_local]`"
+        )));
+    };
+
+    let mut local_config = (*config).clone();
+    local_config.model = Some(model.clone());
+    local_config.model_provider_id = provider_id.to_string();
+    local_config.model_provider = provider.clone();
+    let model_info =
+        model_info::with_config_overrides(model_info::find_model_info_for_slug(&model), &config);
+    let otel_manager = turn
+        .client
+        .get_otel_manager()
+        .with_model(model.as_str(), model_info.slug.as_str());
+    Ok(ModelClient::new(
+        Arc::new(local_config),
+        // Local providers do not authenticate with the user's account.
+        None,
+        model_info,
+        otel_manager,
+        provider,
+        None,
+        config.model_reasoning_summary,
+        session.conversation_id,
+        turn.client.get_session_source(),
+    ))
+}
+
+/// Gathers what the task works on: the `input` text, the files in `paths`
+/// and the outputs of the earlier tool calls in `call_ids`. Files and
+/// outputs are read here, so they reach the local model without passing
+/// through the main model's output.
+async fn collect_inputs(
+    session: &Session,
+    turn: &TurnContext,
+    args: &DelegateLocalArgs,
+) -> Result<Vec<DelegateInput>, FunctionCallError> {
+    let mut inputs = Vec::new();
+    if let Some(input) = args.input.as_deref()
+        && !input.is_empty()
+    {
+        inputs.push(DelegateInput {
+            source: None,
+            text: input.to_string(),
+        });
+    }
+    for path in &args.paths {
+        let resolved = turn.resolve_path(Some(path.clone()));
+        let metadata = tokio::fs::metadata(&resolved).await.map_err(|err| {
+            FunctionCallError::RespondToModel(format!("failed to read `{path}`: {err}"))
+        })?;
+        if metadata.len() > MAX_INPUT_FILE_BYTES {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "`{path}` is larger than {MAX_INPUT_FILE_BYTES} bytes"
+            )));
+        }
+        let bytes = tokio::fs::read(&resolved).await.map_err(|err| {
+            FunctionCallError::RespondToModel(format!("failed to read `{path}`: {err}"))
+        })?;
+        inputs.push(DelegateInput {
+            source: Some(path.clone()),
+            text: String::from_utf8_lossy(&bytes).into_owned(),
+        });
+    }
+    if !args.call_ids.is_empty() {
+        let history = session.clone_history().await;
+        for call_id in &args.call_ids {
+            // Prefer the full output saved as an artifact over the truncated
+            // copy kept in the history.
+            let text = match read_spilled_output(session, turn, call_id).await {
+                Some(text) => text,
+                None => tool_output_in_history(history.raw_items(), call_id).ok_or_else(|| {
+                    FunctionCallError::RespondToModel(format!(
+                        "no output of a tool call `{call_id}` in this conversation"
+                    ))
+                })?,
+            };
+            inputs.push(DelegateInput {
+                source: Some(format!("output of call {call_id}")),
+                text,
+            });
+        }
+    }
+    Ok(inputs)
+}
+
+/// Output of tool call `call_id` as recorded in `items`.
+fn tool_output_in_history(items: &[ResponseItem], call_id: &str) -> Option<String> {
+    items.iter().rev().find_map(|item| match item {
+        ResponseItem::FunctionCallOutput {
+            call_id: id,
+            output,
+        } if id == call_id => Some(output.content.clone()),
+        ResponseItem::CustomToolCallOutput {
+            call_id: id,
+            output,
+        } if id == call_id => Some(output.clone()),
+        _ => None,
+    })
+}
+
+fn request_text(task: &str, inputs: &[DelegateInput]) -> String {
+    let mut text = task.trim().to_string();
+    for DelegateInput {
+        source,
+        text: input,
+    } in inputs
+    {
+        match source {
+            Some(source) => text.push_str(&format!(
+                "\n\n<input source=\"{source}\">\n{input}\n</input>"
+            )),
+            None => text.push_str(&format!("\n\n<input>\n{input}\n</input>")),
+        }
+    }
+    text
+}
+
+/// Streams the local model's answer, stopping once it passes
+/// `max_output_tokens`.
+async fn run_delegate(
+    client: &ModelClient,
+    prompt: &Prompt,
+    tokenizer: &dyn Tokenizer,
+    max_output_tokens: u64,
+) -> CodexResult<Delegated> {
+    let mut client_session = client.new_session();
+    let mut stream = client_session.stream(prompt).await?;
+    let mut streamed = String::new();
+    let mut streamed_tokens = 0u64;
+    let mut answer = None;
+    loop {
+        let Some(event) = stream.next().await else {
+            return Err(CodexErr::Stream(
+                "stream closed before response.completed".into(),
+                None,
+            ));
+        };
+        match event? {
+            ResponseEvent::OutputTextDelta(delta) => {
+                streamed.push_str(&delta);
+                streamed_tokens = streamed_tokens.saturating_add(tokenizer.count_tokens(&delta));
+                if streamed_tokens > max_output_tokens {
+                    return Ok(cap_answer(&streamed, tokenizer, max_output_tokens));
+                }
+            }
+            ResponseEvent::OutputItemDone(ResponseItem::Message { role, content, .. })
+                if role == "assistant" =>
+            {
+                if let Some(text) = content_items_to_text(&content) {
+                    answer = Some(text);
+                }
+            }
+            ResponseEvent::Completed { .. } => {
+                let answer = answer.unwrap_or(streamed);
+                return Ok(cap_answer(answer.trim(), tokenizer, max_output_tokens));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn cap_answer(text: &str, tokenizer: &dyn Tokenizer, max_output_tokens: u64) -> Delegated {
+    if tokenizer.count_tokens(text) <= max_output_tokens {
+        return Delegated {
+            text: text.to_string(),
+            truncated: false,
+        };
+    }
+    let budget = usize::try_from(max_output_tokens).unwrap_or(usize::MAX);
+    Delegated {
+        text: truncate_text(text, TruncationPolicy::Tokens(budget)),
+        truncated: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::HeuristicTokenizer;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn request_wraps_the_inputs() {
+        let inputs = vec![
+            DelegateInput {
+                source: None,
+                text: "E1 disk full".to_string(),
+            },
+            DelegateInput {
+                source: Some("logs/build.log".to_string()),
+                text: "E2 disk full".to_string(),
+            },
+        ];
+
+        assert_eq!(
+            request_text(" Summarize the errors in these logs. ", &inputs),
+            "Summarize the errors in these logs.\n\n<input>\nE1 disk full\n</input>\n\n<input source=\"logs/build.log\">\nE2 disk full\n</input>"
+        );
+    }
+
+    #[test]
+    fn finds_tool_outputs_in_the_history() {
+        let items = vec![
+            ResponseItem::FunctionCallOutput {
+                call_id: "call-1".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: "build log".to_string(),
+                    ..Default::default()
+                },
+            },
+            ResponseItem::CustomToolCallOutput {
+                call_id: "call-2".to_string(),
+                output: "patch applied".to_string(),
+            },
+        ];
+
+        assert_eq!(
+            (
+                tool_output_in_history(&items, "call-1"),
+                tool_output_in_history(&items, "call-2"),
+                tool_output_in_history(&items, "call-3"),
+            ),
+            (
+                Some("build log".to_string()),
+                Some("patch applied".to_string()),
+                None,
+            )
+        );
+    }
+
+    #[test]
+    fn answers_over_the_cap_are_cut() {
+        let short = cap_answer("abcd", &HeuristicTokenizer, 1);
+        let long = cap_answer(&"word ".repeat(100), &HeuristicTokenizer, 10);
+
+        assert_eq!(
+            short,
+            Delegated {
+                text: "abcd".to_string(),
+                truncated: false,
+            }
+        );
+        assert!(long.truncated);
+        assert!(long.text.len() < 500);
+    }
+}
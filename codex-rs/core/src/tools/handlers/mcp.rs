@@ -22,6 +22,7 @@ impl ToolHandler for McpHandler {
             turn,
             call_id,
             payload,
+            cancellation_token,
             ..
         } = invocation;
 
@@ -48,6 +49,7 @@ impl ToolHandler for McpHandler {
             server,
             tool,
             arguments_str,
+            cancellation_token,
         )
         .await;
 
@@ -1,11 +1,18 @@
 pub mod apply_patch;
+mod bulk_edit;
+mod clipboard;
 pub(crate) mod collab;
+mod delegate_local;
 mod grep_files;
 mod list_dir;
 mod mcp;
 mod mcp_resource;
 mod plan;
+mod project_map;
+mod read_artifact;
 mod read_file;
+mod read_output_chunk;
+mod repl;
 mod request_user_input;
 mod shell;
 mod test_sync;
@@ -17,13 +24,20 @@ use serde::Deserialize;
 
 use crate::function_tool::FunctionCallError;
 pub use apply_patch::ApplyPatchHandler;
+pub use bulk_edit::BulkEditHandler;
+pub use clipboard::ClipboardHandler;
 pub use collab::CollabHandler;
+pub use delegate_local::DelegateLocalHandler;
 pub use grep_files::GrepFilesHandler;
 pub use list_dir::ListDirHandler;
 pub use mcp::McpHandler;
 pub use mcp_resource::McpResourceHandler;
 pub use plan::PlanHandler;
+pub use project_map::ProjectMapHandler;
+pub use read_artifact::ReadArtifactHandler;
 pub use read_file::ReadFileHandler;
+pub use read_output_chunk::ReadOutputChunkHandler;
+pub use repl::ReplHandler;
 pub use request_user_input::RequestUserInputHandler;
 pub use shell::ShellCommandHandler;
 pub use shell::ShellHandler;
@@ -0,0 +1,112 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::project_map::ProjectMapOptions;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct ProjectMapHandler;
+
+const DEFAULT_MAX_DEPTH: usize = 6;
+const DEFAULT_MAX_FILES: usize = 500;
+const MAX_FILES_LIMIT: usize = 5000;
+
+fn default_max_depth() -> usize {
+    DEFAULT_MAX_DEPTH
+}
+
+fn default_max_files() -> usize {
+    DEFAULT_MAX_FILES
+}
+
+fn default_include_symbols() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+struct ProjectMapArgs {
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default = "default_max_depth")]
+    max_depth: usize,
+    #[serde(default = "default_max_files")]
+    max_files: usize,
+    #[serde(default = "default_include_symbols")]
+    include_symbols: bool,
+}
+
+#[async_trait]
+impl ToolHandler for ProjectMapHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "project_map handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: ProjectMapArgs = parse_arguments(&arguments)?;
+
+        if args.max_depth == 0 {
+            return Err(FunctionCallError::RespondToModel(
+                "max_depth must be greater than zero".to_string(),
+            ));
+        }
+
+        if args.max_files == 0 {
+            return Err(FunctionCallError::RespondToModel(
+                "max_files must be greater than zero".to_string(),
+            ));
+        }
+
+        let root = turn.resolve_path(args.path);
+        let metadata = tokio::fs::metadata(&root).await.map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "unable to access `{}`: {err}",
+                root.display()
+            ))
+        })?;
+        if !metadata.is_dir() {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "`{}` is not a directory",
+                root.display()
+            )));
+        }
+
+        let options = ProjectMapOptions {
+            max_depth: args.max_depth,
+            max_files: args.max_files.min(MAX_FILES_LIMIT),
+            include_symbols: args.include_symbols,
+        };
+        let map =
+            tokio::task::spawn_blocking(move || session.services.project_map.build(&root, options))
+                .await
+                .map_err(|err| {
+                    FunctionCallError::RespondToModel(format!("failed to build project map: {err}"))
+                })?;
+
+        Ok(ToolOutput::Function {
+            content: map.text,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
@@ -0,0 +1,64 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::exec_output_spill::read_artifact_lines;
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct ReadArtifactHandler;
+
+#[derive(Deserialize)]
+struct ReadArtifactArgs {
+    artifact_id: String,
+    #[serde(default)]
+    start_line: Option<usize>,
+    #[serde(default)]
+    end_line: Option<usize>,
+}
+
+#[async_trait]
+impl ToolHandler for ReadArtifactHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "read_artifact handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: ReadArtifactArgs = parse_arguments(&arguments)?;
+        let content = read_artifact_lines(
+            &session,
+            &turn,
+            &args.artifact_id,
+            args.start_line,
+            args.end_line,
+        )
+        .await
+        .map_err(FunctionCallError::RespondToModel)?;
+
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
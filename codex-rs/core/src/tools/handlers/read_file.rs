@@ -98,7 +98,12 @@ impl ToolHandler for ReadFileHandler {
     }
 
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
-        let ToolInvocation { payload, .. } = invocation;
+        let ToolInvocation {
+            session,
+            payload,
+            tracker,
+            ..
+        } = invocation;
 
         let arguments = match payload {
             ToolPayload::Function { arguments } => arguments,
@@ -145,6 +150,14 @@ impl ToolHandler for ReadFileHandler {
                 indentation::read_block(&path, offset, limit, indentation).await?
             }
         };
+        // Remember what the model saw so a later apply_patch can merge against
+        // it, or at least warn, if the file changes in the meantime.
+        if let Ok(contents) = tokio::fs::read_to_string(&path).await {
+            session
+                .record_file_read(path.clone(), contents.as_bytes())
+                .await;
+            tracker.lock().await.record_read_snapshot(path, contents);
+        }
         Ok(ToolOutput::Function {
             content: collected.join("\n"),
             content_items: None,
@@ -0,0 +1,52 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct ReadOutputChunkHandler;
+
+#[derive(Deserialize)]
+struct ReadOutputChunkArgs {
+    call_id: String,
+    chunk: usize,
+}
+
+#[async_trait]
+impl ToolHandler for ReadOutputChunkHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session, payload, ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "read_output_chunk handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: ReadOutputChunkArgs = parse_arguments(&arguments)?;
+        let content = session
+            .tool_output_chunk(&args.call_id, args.chunk)
+            .await
+            .map_err(FunctionCallError::RespondToModel)?;
+
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
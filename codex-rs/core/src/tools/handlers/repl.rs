@@ -0,0 +1,182 @@
+use async_trait::async_trait;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::ReplSnippetEvent;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::repl::MAX_RECORDED_OUTPUT_BYTES;
+use crate::repl::ReplError;
+use crate::repl::SnippetResult;
+use crate::repl::language_name;
+use crate::repl::start_interpreter;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::truncate::TruncationPolicy;
+use crate::truncate::formatted_truncate_text;
+use crate::truncate::truncate_text;
+
+pub struct ReplHandler;
+
+#[derive(Deserialize)]
+struct ReplArgs {
+    code: String,
+    #[serde(default)]
+    reset: bool,
+}
+
+#[async_trait]
+impl ToolHandler for ReplHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            call_id,
+            payload,
+            cancellation_token,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "repl handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: ReplArgs = parse_arguments(&arguments)?;
+        let config = turn.client.config();
+        let language = config.repl.language();
+
+        let mut process = session.services.repl.lock().await;
+        // A previous interpreter is replaced when asked to, when it died or
+        // was left mid-snippet by a cancelled call, or when `[repl]` now
+        // names another language.
+        let had_process = process.is_some();
+        if args.reset
+            || process
+                .as_ref()
+                .is_some_and(|process| !process.is_usable() || process.language() != language)
+        {
+            process.take();
+        }
+        let restarted = had_process && process.is_none() && !args.reset;
+        let interpreter = match process.as_mut() {
+            Some(interpreter) => interpreter,
+            None => process.insert(
+                start_interpreter(
+                    session.as_ref(),
+                    turn.as_ref(),
+                    &call_id,
+                    &cancellation_token,
+                )
+                .await
+                .map_err(FunctionCallError::RespondToModel)?,
+            ),
+        };
+
+        let run = tokio::select! {
+            run = interpreter.run(&args.code, config.repl.timeout()) => run,
+            _ = cancellation_token.cancelled() => {
+                // The snippet may still be running; kill the interpreter
+                // rather than leave it busy for the next call.
+                process.take();
+                return Err(FunctionCallError::RespondToModel(
+                    "repl call was cancelled; the interpreter was stopped".to_string(),
+                ));
+            }
+        };
+        let (result, restarted) = match run {
+            Ok(result) => (result, restarted),
+            Err(err) => {
+                // The interpreter is stuck or gone; the next call starts a
+                // fresh one.
+                process.take();
+                let error = match err {
+                    ReplError::TimedOut(timeout) => format!(
+                        "Snippet did not finish within {}s; the interpreter was restarted and \
+                         its state was lost.\n",
+                        timeout.as_secs_f32()
+                    ),
+                    ReplError::Exited { exit_code, output } => {
+                        let status = exit_code
+                            .map(|code| format!(" with code {code}"))
+                            .unwrap_or_default();
+                        format!(
+                            "{output}The interpreter exited{status}, possibly after exceeding its \
+                             memory limit; its state was lost.\n"
+                        )
+                    }
+                };
+                let result = SnippetResult {
+                    id: 0,
+                    stdout: String::new(),
+                    stderr: String::new(),
+                    error: Some(error),
+                };
+                (result, true)
+            }
+        };
+        drop(process);
+
+        let SnippetResult {
+            stdout,
+            stderr,
+            error,
+            ..
+        } = result;
+        let mut content = String::new();
+        if restarted {
+            content.push_str("[note] The interpreter was restarted; earlier state is gone.\n");
+        }
+        for (section, text) in [
+            ("stdout", stdout.as_str()),
+            ("stderr", stderr.as_str()),
+            ("error", error.as_deref().unwrap_or_default()),
+        ] {
+            if !text.is_empty() {
+                content.push_str(&format!("[{section}]\n{text}"));
+                if !text.ends_with('\n') {
+                    content.push('\n');
+                }
+            }
+        }
+        if content.is_empty() {
+            content.push_str("(no output)");
+        }
+        let content = formatted_truncate_text(&content, turn.truncation_policy);
+
+        let success = error.is_none();
+        // The event is kept in the rollout; keep a bounded excerpt there.
+        let recorded = TruncationPolicy::Bytes(MAX_RECORDED_OUTPUT_BYTES);
+        session
+            .send_event(
+                turn.as_ref(),
+                EventMsg::ReplSnippet(ReplSnippetEvent {
+                    call_id,
+                    language: language_name(language).to_string(),
+                    code: args.code,
+                    stdout: truncate_text(&stdout, recorded),
+                    stderr: truncate_text(&stderr, recorded),
+                    error: error.map(|error| truncate_text(&error, recorded)),
+                    restarted,
+                }),
+            )
+            .await;
+
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(success),
+        })
+    }
+}
@@ -23,17 +23,25 @@ use crate::tools::registry::ToolKind;
 use crate::tools::runtimes::shell::ShellRequest;
 use crate::tools::runtimes::shell::ShellRuntime;
 use crate::tools::sandboxing::ToolCtx;
+use crate::tools::timeouts::ToolTimeoutPolicy;
+use tokio_util::sync::CancellationToken;
 
 pub struct ShellHandler;
 
 pub struct ShellCommandHandler;
 
 impl ShellHandler {
-    fn to_exec_params(params: ShellToolCallParams, turn_context: &TurnContext) -> ExecParams {
+    fn to_exec_params(
+        params: ShellToolCallParams,
+        tool_name: &str,
+        turn_context: &TurnContext,
+    ) -> ExecParams {
+        let timeout_ms =
+            ToolTimeoutPolicy::for_turn(turn_context, tool_name).resolve(params.timeout_ms);
         ExecParams {
             command: params.command,
             cwd: turn_context.resolve_path(params.workdir.clone()),
-            expiration: params.timeout_ms.into(),
+            expiration: timeout_ms.into(),
             env: create_env(&turn_context.shell_environment_policy),
             sandbox_permissions: params.sandbox_permissions.unwrap_or_default(),
             justification: params.justification,
@@ -50,16 +58,19 @@ impl ShellCommandHandler {
 
     fn to_exec_params(
         params: ShellCommandToolCallParams,
+        tool_name: &str,
         session: &crate::codex::Session,
         turn_context: &TurnContext,
     ) -> ExecParams {
         let shell = session.user_shell();
         let command = Self::base_command(shell.as_ref(), &params.command, params.login);
+        let timeout_ms =
+            ToolTimeoutPolicy::for_turn(turn_context, tool_name).resolve(params.timeout_ms);
 
         ExecParams {
             command,
             cwd: turn_context.resolve_path(params.workdir.clone()),
-            expiration: params.timeout_ms.into(),
+            expiration: timeout_ms.into(),
             env: create_env(&turn_context.shell_environment_policy),
             sandbox_permissions: params.sandbox_permissions.unwrap_or_default(),
             justification: params.justification,
@@ -101,12 +112,13 @@ impl ToolHandler for ShellHandler {
             call_id,
             tool_name,
             payload,
+            cancellation_token,
         } = invocation;
 
         match payload {
             ToolPayload::Function { arguments } => {
                 let params: ShellToolCallParams = parse_arguments(&arguments)?;
-                let exec_params = Self::to_exec_params(params, turn.as_ref());
+                let exec_params = Self::to_exec_params(params, tool_name.as_str(), turn.as_ref());
                 Self::run_exec_like(
                     tool_name.as_str(),
                     exec_params,
@@ -115,11 +127,12 @@ impl ToolHandler for ShellHandler {
                     tracker,
                     call_id,
                     false,
+                    cancellation_token,
                 )
                 .await
             }
             ToolPayload::LocalShell { params } => {
-                let exec_params = Self::to_exec_params(params, turn.as_ref());
+                let exec_params = Self::to_exec_params(params, tool_name.as_str(), turn.as_ref());
                 Self::run_exec_like(
                     tool_name.as_str(),
                     exec_params,
@@ -128,6 +141,7 @@ impl ToolHandler for ShellHandler {
                     tracker,
                     call_id,
                     false,
+                    cancellation_token,
                 )
                 .await
             }
@@ -170,6 +184,7 @@ impl ToolHandler for ShellCommandHandler {
             call_id,
             tool_name,
             payload,
+            cancellation_token,
         } = invocation;
 
         let ToolPayload::Function { arguments } = payload else {
@@ -179,7 +194,8 @@ impl ToolHandler for ShellCommandHandler {
         };
 
         let params: ShellCommandToolCallParams = parse_arguments(&arguments)?;
-        let exec_params = Self::to_exec_params(params, session.as_ref(), turn.as_ref());
+        let exec_params =
+            Self::to_exec_params(params, tool_name.as_str(), session.as_ref(), turn.as_ref());
         ShellHandler::run_exec_like(
             tool_name.as_str(),
             exec_params,
@@ -194,6 +210,7 @@ impl ToolHandler for ShellCommandHandler {
 }
 
 impl ShellHandler {
+    #[allow(clippy::too_many_arguments)]
     async fn run_exec_like(
         tool_name: &str,
         exec_params: ExecParams,
@@ -202,6 +219,7 @@ impl ShellHandler {
         tracker: crate::tools::context::SharedTurnDiffTracker,
         call_id: String,
         freeform: bool,
+        cancellation_token: CancellationToken,
     ) -> Result<ToolOutput, FunctionCallError> {
         // Approval policy guard for explicit escalation in non-OnRequest modes.
         if exec_params
@@ -228,6 +246,7 @@ impl ShellHandler {
             Some(&tracker),
             &call_id,
             tool_name,
+            &cancellation_token,
         )
         .await?
         {
@@ -235,12 +254,18 @@ impl ShellHandler {
         }
 
         let source = ExecCommandSource::Agent;
+        let timeout_policy = ToolTimeoutPolicy::for_turn(turn.as_ref(), tool_name);
+        let timeout_ms = exec_params
+            .expiration
+            .timeout_ms()
+            .unwrap_or(timeout_policy.default_ms);
         let emitter = ToolEmitter::shell(
             exec_params.command.clone(),
             exec_params.cwd.clone(),
             source,
             freeform,
-        );
+        )
+        .with_timeout_budget(timeout_policy.budget(timeout_ms));
         let event_ctx = ToolEventCtx::new(session.as_ref(), turn.as_ref(), &call_id, None);
         emitter.begin(event_ctx).await;
 
@@ -254,6 +279,7 @@ impl ShellHandler {
                 turn.approval_policy,
                 &turn.sandbox_policy,
                 exec_params.sandbox_permissions,
+                &exec_params.cwd,
             )
             .await;
 
@@ -273,6 +299,7 @@ impl ShellHandler {
             turn: turn.as_ref(),
             call_id: call_id.clone(),
             tool_name: tool_name.to_string(),
+            cancellation_token,
         };
         let out = orchestrator
             .run(&mut runtime, &req, &tool_ctx, &turn, turn.approval_policy)
@@ -378,7 +405,8 @@ mod tests {
             justification: justification.clone(),
         };
 
-        let exec_params = ShellCommandHandler::to_exec_params(params, &session, &turn_context);
+        let exec_params =
+            ShellCommandHandler::to_exec_params(params, "shell_command", &session, &turn_context);
 
         // ExecParams cannot derive Eq due to the CancellationToken field, so we manually compare the fields.
         assert_eq!(exec_params.command, expected_command);
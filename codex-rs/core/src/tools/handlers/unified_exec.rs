@@ -35,6 +35,8 @@ struct ExecCommandArgs {
     login: bool,
     #[serde(default = "default_tty")]
     tty: bool,
+    #[serde(default)]
+    raw: bool,
     #[serde(default = "default_exec_yield_time_ms")]
     yield_time_ms: u64,
     #[serde(default)]
@@ -107,7 +109,7 @@ impl ToolHandler for UnifiedExecHandler {
             call_id,
             tool_name,
             payload,
-            ..
+            cancellation_token,
         } = invocation;
 
         let arguments = match payload {
@@ -120,7 +122,12 @@ impl ToolHandler for UnifiedExecHandler {
         };
 
         let manager: &UnifiedExecProcessManager = &session.services.unified_exec_manager;
-        let context = UnifiedExecContext::new(session.clone(), turn.clone(), call_id.clone());
+        let context = UnifiedExecContext::new(
+            session.clone(),
+            turn.clone(),
+            call_id.clone(),
+            cancellation_token,
+        );
 
         let response = match tool_name.as_str() {
             "exec_command" => {
@@ -131,6 +138,7 @@ impl ToolHandler for UnifiedExecHandler {
                 let ExecCommandArgs {
                     workdir,
                     tty,
+                    raw,
                     yield_time_ms,
                     max_output_tokens,
                     sandbox_permissions,
@@ -165,6 +173,7 @@ impl ToolHandler for UnifiedExecHandler {
                     Some(&tracker),
                     &context.call_id,
                     tool_name.as_str(),
+                    &context.cancellation_token,
                 )
                 .await?
                 {
@@ -180,7 +189,9 @@ impl ToolHandler for UnifiedExecHandler {
                             yield_time_ms,
                             max_output_tokens,
                             workdir,
-                            tty,
+                            // A raw session is always a PTY.
+                            tty: tty || raw,
+                            raw,
                             sandbox_permissions,
                             justification,
                         },
@@ -48,6 +48,14 @@ impl ToolHandler for ViewImageHandler {
 
         let args: ViewImageArgs = parse_arguments(&arguments)?;
 
+        let model_info = turn.client.get_model_info();
+        if !model_info.supports_image_input() {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "{} does not accept image input, so images cannot be viewed",
+                model_info.slug
+            )));
+        }
+
         let abs_path = turn.resolve_path(Some(args.path));
 
         let metadata = fs::metadata(&abs_path).await.map_err(|error| {
@@ -1,3 +1,4 @@
+pub(crate) mod arguments;
 pub mod context;
 pub mod events;
 pub(crate) mod handlers;
@@ -8,8 +9,10 @@ pub mod router;
 pub mod runtimes;
 pub mod sandboxing;
 pub mod spec;
+pub(crate) mod timeouts;
 
 use crate::exec::ExecToolCallOutput;
+use crate::tools::timeouts::TimeoutBudget;
 use crate::truncate::TruncationPolicy;
 use crate::truncate::formatted_truncate_text;
 use crate::truncate::truncate_text;
@@ -23,14 +26,17 @@ pub(crate) const TELEMETRY_PREVIEW_TRUNCATION_NOTICE: &str =
     "[... telemetry preview truncated ...]";
 
 /// Format the combined exec output for sending back to the model.
-/// Includes exit code and duration metadata; truncates large bodies safely.
-pub fn format_exec_output_for_model_structured(
+/// Includes exit code and duration metadata, plus the timeout budget when the
+/// command timed out; truncates large bodies safely.
+pub(crate) fn format_exec_output_for_model_structured(
     exec_output: &ExecToolCallOutput,
     truncation_policy: TruncationPolicy,
+    timeout_budget: Option<TimeoutBudget>,
 ) -> String {
     let ExecToolCallOutput {
         exit_code,
         duration,
+        timed_out,
         ..
     } = exec_output;
 
@@ -38,6 +44,8 @@ pub fn format_exec_output_for_model_structured(
     struct ExecMetadata {
         exit_code: i32,
         duration_seconds: f32,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        timeout: Option<TimeoutBudget>,
     }
 
     #[derive(Serialize)]
@@ -56,6 +64,7 @@ pub fn format_exec_output_for_model_structured(
         metadata: ExecMetadata {
             exit_code: *exit_code,
             duration_seconds,
+            timeout: timeout_budget.filter(|_| *timed_out),
         },
     };
 
@@ -63,9 +72,10 @@ pub fn format_exec_output_for_model_structured(
     serde_json::to_string(&payload).expect("serialize ExecOutput")
 }
 
-pub fn format_exec_output_for_model_freeform(
+pub(crate) fn format_exec_output_for_model_freeform(
     exec_output: &ExecToolCallOutput,
     truncation_policy: TruncationPolicy,
+    timeout_budget: Option<TimeoutBudget>,
 ) -> String {
     // round to 1 decimal place
     let duration_seconds = ((exec_output.duration.as_secs_f32()) * 10.0).round() / 10.0;
@@ -83,6 +93,9 @@ pub fn format_exec_output_for_model_freeform(
     if total_lines != formatted_output.lines().count() {
         sections.push(format!("Total output lines: {total_lines}"));
     }
+    if let Some(budget) = timeout_budget.filter(|_| exec_output.timed_out) {
+        sections.push(format!("Timed out: {}", budget.hint()));
+    }
 
     sections.push("Output:".to_string());
     sections.push(formatted_output);
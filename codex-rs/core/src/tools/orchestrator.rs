@@ -17,6 +17,7 @@ use crate::tools::sandboxing::ToolCtx;
 use crate::tools::sandboxing::ToolError;
 use crate::tools::sandboxing::ToolRuntime;
 use crate::tools::sandboxing::default_exec_approval_requirement;
+use crate::write_quota::WriteQuotaLimits;
 use codex_otel::ToolDecisionSource;
 use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::ReviewDecision;
@@ -55,6 +56,11 @@ impl ToolOrchestrator {
         let requirement = tool.exec_approval_requirement(req).unwrap_or_else(|| {
             default_exec_approval_requirement(approval_policy, &turn_ctx.sandbox_policy)
         });
+        let write_limits = WriteQuotaLimits::from(&turn_ctx.client.config().write_quota);
+        let requirement = match turn_ctx.write_quota.over_cap_reason(write_limits) {
+            Some(reason) => require_approval_over_write_cap(requirement, reason, approval_policy),
+            None => requirement,
+        };
         match requirement {
             ExecApprovalRequirement::Skip { .. } => {
                 otel.tool_decision(otel_tn, otel_ci, &ReviewDecision::Approved, otel_cfg);
@@ -79,7 +85,8 @@ impl ToolOrchestrator {
                     }
                     ReviewDecision::Approved
                     | ReviewDecision::ApprovedExecpolicyAmendment { .. }
-                    | ReviewDecision::ApprovedForSession => {}
+                    | ReviewDecision::ApprovedForSession
+                    | ReviewDecision::ApprovedAlways { .. } => {}
                 }
                 already_approved = true;
             }
@@ -141,7 +148,8 @@ impl ToolOrchestrator {
                         }
                         ReviewDecision::Approved
                         | ReviewDecision::ApprovedExecpolicyAmendment { .. }
-                        | ReviewDecision::ApprovedForSession => {}
+                        | ReviewDecision::ApprovedForSession
+                        | ReviewDecision::ApprovedAlways { .. } => {}
                     }
                 }
 
@@ -161,6 +169,26 @@ impl ToolOrchestrator {
     }
 }
 
+/// Past the turn's write cap, calls that would otherwise run unprompted need
+/// approval, or are refused when approvals cannot be requested.
+fn require_approval_over_write_cap(
+    requirement: ExecApprovalRequirement,
+    reason: String,
+    approval_policy: AskForApproval,
+) -> ExecApprovalRequirement {
+    match requirement {
+        ExecApprovalRequirement::Skip { .. } if approval_policy == AskForApproval::Never => {
+            ExecApprovalRequirement::Forbidden { reason }
+        }
+        ExecApprovalRequirement::Skip { .. } => ExecApprovalRequirement::NeedsApproval {
+            reason: Some(reason),
+            proposed_execpolicy_amendment: None,
+            matched_approval_rule: None,
+        },
+        other => other,
+    }
+}
+
 fn build_denial_reason_from_output(_output: &ExecToolCallOutput) -> String {
     // Keep approval reason terse and stable for UX/tests, but accept the
     // output so we can evolve heuristics later without touching call sites.
@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::time::Duration;
 use std::time::Instant;
 
 use tokio::sync::RwLock;
@@ -6,8 +7,8 @@ use tokio_util::either::Either;
 use tokio_util::sync::CancellationToken;
 use tokio_util::task::AbortOnDropHandle;
 use tracing::Instrument;
+use tracing::info_span;
 use tracing::instrument;
-use tracing::trace_span;
 
 use crate::codex::Session;
 use crate::codex::TurnContext;
@@ -19,6 +20,12 @@ use crate::tools::router::ToolCall;
 use crate::tools::router::ToolRouter;
 use codex_protocol::models::FunctionCallOutputPayload;
 use codex_protocol::models::ResponseInputItem;
+use codex_protocol::protocol::CancelledToolCall;
+
+/// How long a cancelled tool call gets to stop the work it started, such as
+/// killing a process group or telling an MCP server to stop, before it is
+/// reported as aborted anyway.
+const CANCEL_GRACE_PERIOD: Duration = Duration::from_secs(2);
 
 #[derive(Clone)]
 pub(crate) struct ToolCallRuntime {
@@ -45,6 +52,10 @@ impl ToolCallRuntime {
         }
     }
 
+    pub(crate) fn has_function_tool(&self, name: &str) -> bool {
+        self.router.has_function_tool(name)
+    }
+
     #[instrument(level = "trace", skip_all, fields(call = ?call))]
     pub(crate) fn handle_tool_call(
         self,
@@ -55,14 +66,17 @@ impl ToolCallRuntime {
 
         let router = Arc::clone(&self.router);
         let session = Arc::clone(&self.session);
+        let abort_session = Arc::clone(&self.session);
+        let turn_id = self.turn_context.sub_id.clone();
         let turn = Arc::clone(&self.turn_context);
         let tracker = Arc::clone(&self.tracker);
         let lock = Arc::clone(&self.parallel_execution);
         let started = Instant::now();
 
-        let dispatch_span = trace_span!(
+        let dispatch_span = info_span!(
             "dispatch_tool_call",
             otel.name = call.tool_name.as_str(),
+            turn_id = turn_id.as_str(),
             tool_name = call.tool_name.as_str(),
             call_id = call.call_id.as_str(),
             aborted = false,
@@ -70,24 +84,53 @@ impl ToolCallRuntime {
 
         let handle: AbortOnDropHandle<Result<ResponseInputItem, FunctionCallError>> =
             AbortOnDropHandle::new(tokio::spawn(async move {
-                tokio::select! {
-                    _ = cancellation_token.cancelled() => {
-                        let secs = started.elapsed().as_secs_f32().max(0.1);
-                        dispatch_span.record("aborted", true);
-                        Ok(Self::aborted_response(&call, secs))
-                    },
-                    res = async {
+                let _activity = session
+                    .turn_activity()
+                    .tool_started(&call.call_id, &call.tool_name);
+                let dispatch = {
+                    let call = call.clone();
+                    let dispatch_span = dispatch_span.clone();
+                    // The handler sees a child token, so it is cancelled
+                    // along with the turn.
+                    let cancellation_token = cancellation_token.child_token();
+                    async move {
                         let _guard = if supports_parallel {
                             Either::Left(lock.read().await)
                         } else {
                             Either::Right(lock.write().await)
                         };
+                        // Do not start a call that was cancelled while it
+                        // waited for the lock.
+                        if cancellation_token.is_cancelled() {
+                            return Ok(Self::aborted_response(&call, 0.0));
+                        }
 
                         router
-                            .dispatch_tool_call(session, turn, tracker, call.clone())
-                            .instrument(dispatch_span.clone())
+                            .dispatch_tool_call(session, turn, tracker, call, cancellation_token)
+                            .instrument(dispatch_span)
                             .await
-                    } => res,
+                    }
+                };
+                tokio::pin!(dispatch);
+                tokio::select! {
+                    _ = cancellation_token.cancelled() => {
+                        // Let the handler observe the cancellation and clean
+                        // up; its result is discarded either way.
+                        let _ = tokio::time::timeout(CANCEL_GRACE_PERIOD, &mut dispatch).await;
+                        let secs = started.elapsed().as_secs_f32().max(0.1);
+                        dispatch_span.record("aborted", true);
+                        abort_session
+                            .record_cancelled_tool_call(
+                                &turn_id,
+                                CancelledToolCall {
+                                    call_id: call.call_id.clone(),
+                                    tool_name: call.tool_name.clone(),
+                                },
+                            )
+                            .await;
+                        Ok(Self::aborted_response(&call, secs))
+                    },
+                    res = &mut dispatch => res,
                 }
             }));
 
@@ -4,9 +4,15 @@ use std::time::Duration;
 
 use crate::client_common::tools::ToolSpec;
 use crate::function_tool::FunctionCallError;
+use crate::secret_masking::mask_tool_error;
+use crate::secret_masking::mask_tool_output;
+use crate::tool_output_chunks::READ_OUTPUT_CHUNK_TOOL_NAME;
+use crate::tool_output_chunks::chunk_oversized_output;
+use crate::tools::arguments::validate_arguments;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
+use crate::tools::spec::JsonSchema;
 use async_trait::async_trait;
 use codex_protocol::models::ResponseInputItem;
 use codex_utils_readiness::Readiness;
@@ -45,11 +51,16 @@ pub trait ToolHandler: Send + Sync {
 
 pub struct ToolRegistry {
     handlers: HashMap<String, Arc<dyn ToolHandler>>,
+    /// Declared parameters of function tools, checked before dispatch.
+    argument_schemas: HashMap<String, JsonSchema>,
 }
 
 impl ToolRegistry {
     pub fn new(handlers: HashMap<String, Arc<dyn ToolHandler>>) -> Self {
-        Self { handlers }
+        Self {
+            handlers,
+            argument_schemas: HashMap::new(),
+        }
     }
 
     pub fn handler(&self, name: &str) -> Option<Arc<dyn ToolHandler>> {
@@ -66,7 +77,7 @@ impl ToolRegistry {
 
     pub async fn dispatch(
         &self,
-        invocation: ToolInvocation,
+        mut invocation: ToolInvocation,
     ) -> Result<ResponseInputItem, FunctionCallError> {
         let tool_name = invocation.tool_name.clone();
         let call_id_owned = invocation.call_id.clone();
@@ -104,6 +115,28 @@ impl ToolRegistry {
             return Err(FunctionCallError::Fatal(message));
         }
 
+        if let ToolPayload::Function { arguments } = &mut invocation.payload
+            && let Some(schema) = self.argument_schemas.get(tool_name.as_str())
+        {
+            match validate_arguments(tool_name.as_str(), schema, arguments) {
+                Ok(validated) => *arguments = validated,
+                Err(message) => {
+                    otel.tool_result(
+                        tool_name.as_ref(),
+                        &call_id_owned,
+                        log_payload.as_ref(),
+                        Duration::ZERO,
+                        false,
+                        &message,
+                    );
+                    return Err(FunctionCallError::RespondToModel(message));
+                }
+            }
+        }
+
+        invocation.session.record_tool_call_stats(&tool_name).await;
+        let session = Arc::clone(&invocation.session);
+        let turn = Arc::clone(&invocation.turn);
         let output_cell = tokio::sync::Mutex::new(None);
 
         let result = otel
@@ -142,9 +175,18 @@ impl ToolRegistry {
                 let output = guard.take().ok_or_else(|| {
                     FunctionCallError::Fatal("tool produced no output".to_string())
                 })?;
+                let output = if tool_name == READ_OUTPUT_CHUNK_TOOL_NAME {
+                    output
+                } else {
+                    // Mask before chunking so stored chunks never hold secrets.
+                    let output = mask_tool_output(&session, &turn, &call_id_owned, output).await;
+                    chunk_oversized_output(&session, &turn, &call_id_owned, output).await
+                };
                 Ok(output.into_response(&call_id_owned, &payload_for_response))
             }
-            Err(err) => Err(err),
+            // Failed calls (non-zero exit, timeout, sandbox denial) still
+            // show their output to the model.
+            Err(err) => Err(mask_tool_error(&session, &turn, &call_id_owned, err).await),
         }
     }
 }
@@ -201,6 +243,23 @@ impl ToolRegistryBuilder {
         }
     }
 
+    /// Drops the specs and handlers of the tools named in `names`.
+    pub fn remove_tools(&mut self, names: &[String]) {
+        if names.is_empty() {
+            return;
+        }
+        self.specs
+            .retain(|configured| !names.iter().any(|name| name == configured.spec.name()));
+        self.handlers.retain(|name, _| !names.contains(name));
+    }
+
+    /// Drops every tool not named in `names`.
+    pub fn retain_tools(&mut self, names: &[String]) {
+        self.specs
+            .retain(|configured| names.iter().any(|name| name == configured.spec.name()));
+        self.handlers.retain(|name, _| names.contains(name));
+    }
+
     // TODO(jif) for dynamic tools.
     // pub fn register_many<I>(&mut self, names: I, handler: Arc<dyn ToolHandler>)
     // where
@@ -220,7 +279,18 @@ impl ToolRegistryBuilder {
     // }
 
     pub fn build(self) -> (Vec<ConfiguredToolSpec>, ToolRegistry) {
-        let registry = ToolRegistry::new(self.handlers);
+        let argument_schemas = self
+            .specs
+            .iter()
+            .filter_map(|configured| match &configured.spec {
+                ToolSpec::Function(tool) => Some((tool.name.clone(), tool.parameters.clone())),
+                _ => None,
+            })
+            .collect();
+        let registry = ToolRegistry {
+            handlers: self.handlers,
+            argument_schemas,
+        };
         (self.specs, registry)
     }
 }
@@ -16,6 +16,7 @@ use codex_protocol::models::ResponseItem;
 use codex_protocol::models::ShellToolCallParams;
 use std::collections::HashMap;
 use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
 use tracing::instrument;
 
 #[derive(Clone, Debug)]
@@ -55,6 +56,13 @@ impl ToolRouter {
             .any(|config| config.spec.name() == tool_name)
     }
 
+    /// Whether `name` is a JSON-arguments function tool, including MCP tools.
+    pub fn has_function_tool(&self, name: &str) -> bool {
+        self.specs
+            .iter()
+            .any(|config| matches!(&config.spec, ToolSpec::Function(tool) if tool.name == name))
+    }
+
     #[instrument(level = "trace", skip_all, err)]
     pub async fn build_tool_call(
         session: &Session,
@@ -133,6 +141,7 @@ impl ToolRouter {
         turn: Arc<TurnContext>,
         tracker: SharedTurnDiffTracker,
         call: ToolCall,
+        cancellation_token: CancellationToken,
     ) -> Result<ResponseInputItem, FunctionCallError> {
         let ToolCall {
             tool_name,
@@ -142,6 +151,21 @@ impl ToolRouter {
         let payload_outputs_custom = matches!(payload, ToolPayload::Custom { .. });
         let failure_call_id = call_id.clone();
 
+        let payload = if turn.tools_config.clipboard {
+            match session.expand_clip_references(payload).await {
+                Ok(payload) => payload,
+                Err(message) => {
+                    return Ok(Self::failure_response(
+                        failure_call_id,
+                        payload_outputs_custom,
+                        FunctionCallError::RespondToModel(message),
+                    ));
+                }
+            }
+        } else {
+            payload
+        };
+
         let invocation = ToolInvocation {
             session,
             turn,
@@ -149,6 +173,7 @@ impl ToolRouter {
             call_id,
             tool_name,
             payload,
+            cancellation_token,
         };
 
         match self.registry.dispatch(invocation).await {
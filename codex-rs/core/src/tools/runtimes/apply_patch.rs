@@ -5,6 +5,7 @@
 //! `codex --codex-run-as-apply-patch`, and runs under the current
 //! `SandboxAttempt` with a minimal environment.
 use crate::CODEX_APPLY_PATCH_ARG1;
+use crate::approval_memory::ApprovalTarget;
 use crate::exec::ExecToolCallOutput;
 use crate::sandboxing::CommandSpec;
 use crate::sandboxing::SandboxPermissions;
@@ -102,6 +103,8 @@ impl Approvable<ApplyPatchRequest> for ApplyPatchRuntime {
         let call_id = ctx.call_id.to_string();
         let retry_reason = ctx.retry_reason.clone();
         let approval_keys = self.approval_keys(req);
+        let targets =
+            ApprovalTarget::for_paths(req.file_paths.iter().map(AbsolutePathBuf::to_path_buf));
         let changes = req.changes.clone();
         Box::pin(async move {
             if let Some(reason) = retry_reason {
@@ -115,6 +118,7 @@ impl Approvable<ApplyPatchRequest> for ApplyPatchRuntime {
                 &session.services,
                 "apply_patch",
                 approval_keys,
+                targets,
                 || async move {
                     let rx_approve = session
                         .request_patch_approval(turn, call_id, changes, None, None)
@@ -150,6 +154,12 @@ impl ToolRuntime<ApplyPatchRequest, ExecToolCallOutput> for ApplyPatchRuntime {
         ctx: &ToolCtx<'_>,
     ) -> Result<ExecToolCallOutput, ToolError> {
         let spec = Self::build_command_spec(req)?;
+        let spec = CommandSpec {
+            expiration: spec
+                .expiration
+                .with_cancellation(ctx.cancellation_token.clone()),
+            ..spec
+        };
         let env = attempt
             .env_for(spec)
             .map_err(|err| ToolError::Codex(err.into()))?;
@@ -0,0 +1,144 @@
+/*
+Runtime: repl
+
+Starts the interpreter behind the `repl` tool. Approval and sandbox selection
+go through the orchestrator like a shell command; the snippets themselves are
+sent to the running interpreter without further approvals.
+*/
+use crate::approval_memory::ApprovalTarget;
+use crate::exec::ExecExpiration;
+use crate::sandboxing::SandboxPermissions;
+use crate::tools::runtimes::build_command_spec;
+use crate::tools::sandboxing::Approvable;
+use crate::tools::sandboxing::ApprovalCtx;
+use crate::tools::sandboxing::ExecApprovalRequirement;
+use crate::tools::sandboxing::SandboxAttempt;
+use crate::tools::sandboxing::Sandboxable;
+use crate::tools::sandboxing::SandboxablePreference;
+use crate::tools::sandboxing::ToolCtx;
+use crate::tools::sandboxing::ToolError;
+use crate::tools::sandboxing::ToolRuntime;
+use crate::tools::sandboxing::with_cached_approval;
+use codex_protocol::protocol::ReviewDecision;
+use codex_utils_pty::SpawnedProcess;
+use futures::future::BoxFuture;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug)]
+pub struct ReplRequest {
+    /// Interpreter invocation including the driver script.
+    pub command: Vec<String>,
+    /// What approvals show and remember: the interpreter alone.
+    pub display_command: Vec<String>,
+    pub cwd: PathBuf,
+    pub env: HashMap<String, String>,
+    pub exec_approval_requirement: ExecApprovalRequirement,
+}
+
+#[derive(serde::Serialize, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ReplApprovalKey {
+    pub command: Vec<String>,
+    pub cwd: PathBuf,
+}
+
+#[derive(Default)]
+pub struct ReplRuntime;
+
+impl Sandboxable for ReplRuntime {
+    fn sandbox_preference(&self) -> SandboxablePreference {
+        SandboxablePreference::Auto
+    }
+
+    // Sandbox denials surface in individual snippets, long after the
+    // interpreter started, so there is no failed attempt to retry.
+    fn escalate_on_failure(&self) -> bool {
+        false
+    }
+}
+
+impl Approvable<ReplRequest> for ReplRuntime {
+    type ApprovalKey = ReplApprovalKey;
+
+    fn approval_keys(&self, req: &ReplRequest) -> Vec<Self::ApprovalKey> {
+        vec![ReplApprovalKey {
+            command: req.display_command.clone(),
+            cwd: req.cwd.clone(),
+        }]
+    }
+
+    fn start_approval_async<'b>(
+        &'b mut self,
+        req: &'b ReplRequest,
+        ctx: ApprovalCtx<'b>,
+    ) -> BoxFuture<'b, ReviewDecision> {
+        let keys = self.approval_keys(req);
+        let targets = ApprovalTarget::for_command_prefix(&req.display_command);
+        let session = ctx.session;
+        let turn = ctx.turn;
+        let call_id = ctx.call_id.to_string();
+        let command = req.display_command.clone();
+        let cwd = req.cwd.clone();
+        let reason = Some(ctx.retry_reason.clone().unwrap_or_else(|| {
+            "Start an interpreter that keeps its state for this conversation".to_string()
+        }));
+        Box::pin(async move {
+            with_cached_approval(&session.services, "repl", keys, targets, || async move {
+                session
+                    .request_command_approval(
+                        turn,
+                        call_id,
+                        command,
+                        cwd,
+                        reason,
+                        req.exec_approval_requirement
+                            .proposed_execpolicy_amendment()
+                            .cloned(),
+                        req.exec_approval_requirement
+                            .matched_approval_rule()
+                            .cloned(),
+                    )
+                    .await
+            })
+            .await
+        })
+    }
+
+    fn exec_approval_requirement(&self, req: &ReplRequest) -> Option<ExecApprovalRequirement> {
+        Some(req.exec_approval_requirement.clone())
+    }
+}
+
+impl ToolRuntime<ReplRequest, SpawnedProcess> for ReplRuntime {
+    async fn run(
+        &mut self,
+        req: &ReplRequest,
+        attempt: &SandboxAttempt<'_>,
+        _ctx: &ToolCtx<'_>,
+    ) -> Result<SpawnedProcess, ToolError> {
+        let spec = build_command_spec(
+            &req.command,
+            &req.cwd,
+            &req.env,
+            ExecExpiration::DefaultTimeout,
+            SandboxPermissions::UseDefault,
+            None,
+        )?;
+        let exec_env = attempt
+            .env_for(spec)
+            .map_err(|err| ToolError::Codex(err.into()))?;
+        let (program, args) = exec_env
+            .command
+            .split_first()
+            .ok_or_else(|| ToolError::Rejected("missing interpreter command".to_string()))?;
+        codex_utils_pty::spawn_pipe_process(
+            program,
+            args,
+            exec_env.cwd.as_path(),
+            &exec_env.env,
+            &exec_env.arg0,
+        )
+        .await
+        .map_err(|err| ToolError::Rejected(format!("failed to start the interpreter: {err}")))
+    }
+}
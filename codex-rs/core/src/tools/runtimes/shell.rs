@@ -4,6 +4,8 @@ Runtime: shell
 Executes shell requests under the orchestrator: asks for approval when needed,
 builds a CommandSpec, and runs it under the current SandboxAttempt.
 */
+use crate::approval_memory::ApprovalTarget;
+use crate::exec::ExecExpiration;
 use crate::exec::ExecToolCallOutput;
 use crate::features::Feature;
 use crate::powershell::prefix_powershell_script_with_utf8;
@@ -88,6 +90,7 @@ impl Approvable<ShellRequest> for ShellRuntime {
         ctx: ApprovalCtx<'a>,
     ) -> BoxFuture<'a, ReviewDecision> {
         let keys = self.approval_keys(req);
+        let targets = ApprovalTarget::for_command(&req.command);
         let command = req.command.clone();
         let cwd = req.cwd.clone();
         let reason = ctx
@@ -98,20 +101,29 @@ impl Approvable<ShellRequest> for ShellRuntime {
         let turn = ctx.turn;
         let call_id = ctx.call_id.to_string();
         Box::pin(async move {
-            with_cached_approval(&session.services, "shell", keys, move || async move {
-                session
-                    .request_command_approval(
-                        turn,
-                        call_id,
-                        command,
-                        cwd,
-                        reason,
-                        req.exec_approval_requirement
-                            .proposed_execpolicy_amendment()
-                            .cloned(),
-                    )
-                    .await
-            })
+            with_cached_approval(
+                &session.services,
+                "shell",
+                keys,
+                targets,
+                move || async move {
+                    session
+                        .request_command_approval(
+                            turn,
+                            call_id,
+                            command,
+                            cwd,
+                            reason,
+                            req.exec_approval_requirement
+                                .proposed_execpolicy_amendment()
+                                .cloned(),
+                            req.exec_approval_requirement
+                                .matched_approval_rule()
+                                .cloned(),
+                        )
+                        .await
+                },
+            )
             .await
         })
     }
@@ -159,7 +171,7 @@ impl ToolRuntime<ShellRequest, ExecToolCallOutput> for ShellRuntime {
             &command,
             &req.cwd,
             &req.env,
-            req.timeout_ms.into(),
+            ExecExpiration::from(req.timeout_ms).with_cancellation(ctx.cancellation_token.clone()),
             req.sandbox_permissions,
             req.justification.clone(),
         )?;
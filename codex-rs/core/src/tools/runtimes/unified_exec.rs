@@ -4,6 +4,7 @@ Runtime: unified exec
 Handles approval + sandbox orchestration for unified exec requests, delegating to
 the process manager to spawn PTYs once an ExecEnv is prepared.
 */
+use crate::approval_memory::ApprovalTarget;
 use crate::error::CodexErr;
 use crate::error::SandboxErr;
 use crate::exec::ExecExpiration;
@@ -111,6 +112,7 @@ impl Approvable<UnifiedExecRequest> for UnifiedExecRuntime<'_> {
         ctx: ApprovalCtx<'b>,
     ) -> BoxFuture<'b, ReviewDecision> {
         let keys = self.approval_keys(req);
+        let targets = ApprovalTarget::for_command(&req.command);
         let session = ctx.session;
         let turn = ctx.turn;
         let call_id = ctx.call_id.to_string();
@@ -121,20 +123,29 @@ impl Approvable<UnifiedExecRequest> for UnifiedExecRuntime<'_> {
             .clone()
             .or_else(|| req.justification.clone());
         Box::pin(async move {
-            with_cached_approval(&session.services, "unified_exec", keys, || async move {
-                session
-                    .request_command_approval(
-                        turn,
-                        call_id,
-                        command,
-                        cwd,
-                        reason,
-                        req.exec_approval_requirement
-                            .proposed_execpolicy_amendment()
-                            .cloned(),
-                    )
-                    .await
-            })
+            with_cached_approval(
+                &session.services,
+                "unified_exec",
+                keys,
+                targets,
+                || async move {
+                    session
+                        .request_command_approval(
+                            turn,
+                            call_id,
+                            command,
+                            cwd,
+                            reason,
+                            req.exec_approval_requirement
+                                .proposed_execpolicy_amendment()
+                                .cloned(),
+                            req.exec_approval_requirement
+                                .matched_approval_rule()
+                                .cloned(),
+                        )
+                        .await
+                },
+            )
             .await
         })
     }
@@ -4,6 +4,7 @@
 //! `ApprovalCtx`, `Approvable`) together with the sandbox orchestration traits
 //! and helpers (`Sandboxable`, `ToolRuntime`, `SandboxAttempt`, etc.).
 
+use crate::approval_memory::ApprovalTarget;
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::error::CodexErr;
@@ -13,12 +14,14 @@ use crate::sandboxing::SandboxManager;
 use crate::sandboxing::SandboxTransformError;
 use crate::state::SessionServices;
 use codex_protocol::approvals::ExecPolicyAmendment;
+use codex_protocol::approvals::MatchedApprovalRule;
 use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::ReviewDecision;
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::hash::Hash;
 use std::path::Path;
+use tokio_util::sync::CancellationToken;
 
 use futures::Future;
 use futures::future::BoxFuture;
@@ -52,14 +55,17 @@ impl ApprovalStore {
 /// Takes a vector of approval keys and returns a ReviewDecision.
 /// There will be one key in most cases, but apply_patch can modify multiple files at once.
 ///
-/// - If all keys are already approved for session, we skip prompting.
+/// - If all keys are already approved for session, or all `targets` are covered by a
+///   remembered "always allow" approval, we skip prompting.
 /// - If the user approves for session, we store the decision for each key individually
 ///   so future requests touching any subset can also skip prompting.
+/// - If the user approves always, we additionally remember `targets` for the chosen scope.
 pub(crate) async fn with_cached_approval<K, F, Fut>(
     services: &SessionServices,
     // Name of the tool, used for metrics collection.
     tool_name: &str,
     keys: Vec<K>,
+    targets: Vec<ApprovalTarget>,
     fetch: F,
 ) -> ReviewDecision
 where
@@ -78,7 +84,7 @@ where
             .all(|key| matches!(store.get(key), Some(ReviewDecision::ApprovedForSession)))
     };
 
-    if already_approved {
+    if already_approved || services.approval_memory.allows(tool_name, &targets).await {
         return ReviewDecision::ApprovedForSession;
     }
 
@@ -93,13 +99,25 @@ where
         ],
     );
 
-    if matches!(decision, ReviewDecision::ApprovedForSession) {
+    if matches!(
+        decision,
+        ReviewDecision::ApprovedForSession | ReviewDecision::ApprovedAlways { .. }
+    ) {
         let mut store = services.tool_approvals.lock().await;
         for key in keys {
             store.put(key, ReviewDecision::ApprovedForSession);
         }
     }
 
+    if let ReviewDecision::ApprovedAlways { scope } = decision
+        && let Err(err) = services
+            .approval_memory
+            .remember(scope, tool_name, targets)
+            .await
+    {
+        tracing::warn!("failed to remember approval for {tool_name}: {err}");
+    }
+
     decision
 }
 
@@ -129,6 +147,8 @@ pub(crate) enum ExecApprovalRequirement {
         /// Proposed execpolicy amendment to skip future approvals for similar commands
         /// See core/src/exec_policy.rs for more details on how proposed_execpolicy_amendment is determined.
         proposed_execpolicy_amendment: Option<ExecPolicyAmendment>,
+        /// Config-defined approval rule that requires this approval, if any.
+        matched_approval_rule: Option<MatchedApprovalRule>,
     },
     /// Execution forbidden for this tool call.
     Forbidden { reason: String },
@@ -148,6 +168,16 @@ impl ExecApprovalRequirement {
             _ => None,
         }
     }
+
+    pub fn matched_approval_rule(&self) -> Option<&MatchedApprovalRule> {
+        match self {
+            Self::NeedsApproval {
+                matched_approval_rule: Some(rule),
+                ..
+            } => Some(rule),
+            _ => None,
+        }
+    }
 }
 
 /// - Never, OnFailure: do not ask
@@ -170,6 +200,7 @@ pub(crate) fn default_exec_approval_requirement(
         ExecApprovalRequirement::NeedsApproval {
             reason: None,
             proposed_execpolicy_amendment: None,
+            matched_approval_rule: None,
         }
     } else {
         ExecApprovalRequirement::Skip {
@@ -251,6 +282,9 @@ pub(crate) struct ToolCtx<'a> {
     pub turn: &'a TurnContext,
     pub call_id: String,
     pub tool_name: String,
+    /// Cancelled when the tool call is interrupted; runtimes stop the work
+    /// they started when it fires.
+    pub cancellation_token: CancellationToken,
 }
 
 #[derive(Debug)]
@@ -320,6 +354,7 @@ mod tests {
             ExecApprovalRequirement::NeedsApproval {
                 reason: None,
                 proposed_execpolicy_amendment: None,
+                matched_approval_rule: None,
             }
         );
     }
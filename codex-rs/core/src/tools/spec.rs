@@ -1,8 +1,17 @@
 use crate::agent::AgentRole;
+use crate::client_common::tools::CodeInterpreterContainer;
 use crate::client_common::tools::ResponsesApiTool;
 use crate::client_common::tools::ToolSpec;
+use crate::clipboard::CLIPBOARD_TOOL_NAME;
+use crate::config::Config;
+use crate::config::types::ProviderTools;
+use crate::exec_output_spill::READ_ARTIFACT_TOOL_NAME;
 use crate::features::Feature;
 use crate::features::Features;
+use crate::models_manager::manager::OPENAI_DEFAULT_CHATGPT_MODEL;
+use crate::models_manager::model_info;
+use crate::repl::REPL_TOOL_NAME;
+use crate::tool_output_chunks::READ_OUTPUT_CHUNK_TOOL_NAME;
 use crate::tools::handlers::PLAN_TOOL;
 use crate::tools::handlers::apply_patch::create_apply_patch_freeform_tool;
 use crate::tools::handlers::apply_patch::create_apply_patch_json_tool;
@@ -28,7 +37,18 @@ pub(crate) struct ToolsConfig {
     pub web_search_mode: Option<WebSearchMode>,
     pub collab_tools: bool,
     pub collaboration_modes_tools: bool,
+    pub bulk_edit_tool: bool,
+    pub chunked_tool_output: bool,
+    pub exec_output_artifacts: bool,
+    pub clipboard: bool,
+    pub repl: bool,
+    pub delegate_local: bool,
     pub experimental_supported_tools: Vec<String>,
+    pub forbidden_tools: Vec<String>,
+    /// When set, only these tools are offered; applied after
+    /// `forbidden_tools`.
+    pub allowed_tools: Option<Vec<String>>,
+    pub provider_tools: ProviderTools,
 }
 
 pub(crate) struct ToolsConfigParams<'a> {
@@ -47,6 +67,12 @@ impl ToolsConfig {
         let include_apply_patch_tool = features.enabled(Feature::ApplyPatchFreeform);
         let include_collab_tools = features.enabled(Feature::Collab);
         let include_collaboration_modes_tools = features.enabled(Feature::CollaborationModes);
+        let include_bulk_edit_tool = features.enabled(Feature::BulkEdit);
+        let chunked_tool_output = features.enabled(Feature::ChunkedToolOutput);
+        let exec_output_artifacts = features.enabled(Feature::ExecOutputArtifacts);
+        let clipboard = features.enabled(Feature::Clipboard);
+        let repl = features.enabled(Feature::Repl);
+        let delegate_local = features.enabled(Feature::DelegateLocal);
 
         let shell_type = if !features.enabled(Feature::ShellTool) {
             ConfigShellToolType::Disabled
@@ -79,9 +105,56 @@ impl ToolsConfig {
             web_search_mode: *web_search_mode,
             collab_tools: include_collab_tools,
             collaboration_modes_tools: include_collaboration_modes_tools,
+            bulk_edit_tool: include_bulk_edit_tool,
+            chunked_tool_output,
+            exec_output_artifacts,
+            clipboard,
+            repl,
+            delegate_local,
             experimental_supported_tools: model_info.experimental_supported_tools.clone(),
+            forbidden_tools: Vec::new(),
+            allowed_tools: None,
+            provider_tools: ProviderTools::default(),
         }
     }
+
+    /// Never offer `forbidden_tools` (built-in names or qualified MCP names)
+    /// to the model, whatever else enables them.
+    pub fn with_forbidden_tools(mut self, forbidden_tools: Vec<String>) -> Self {
+        self.forbidden_tools = forbidden_tools;
+        self
+    }
+
+    /// Offer the tools the model provider runs on its side that
+    /// `provider_tools` enables.
+    pub fn with_provider_tools(mut self, provider_tools: ProviderTools) -> Self {
+        self.provider_tools = provider_tools;
+        self
+    }
+}
+
+/// Names of the built-in tools a conversation started with `config` offers
+/// the model, before MCP tools are added. Resolved from the bundled model
+/// metadata, so remote model overrides are not reflected.
+pub fn builtin_tool_names(config: &Config) -> Vec<String> {
+    let model = config
+        .model
+        .as_deref()
+        .unwrap_or(OPENAI_DEFAULT_CHATGPT_MODEL);
+    let model_info =
+        model_info::with_config_overrides(model_info::find_model_info_for_slug(model), config);
+    let tools_config = ToolsConfig::new(&ToolsConfigParams {
+        model_info: &model_info,
+        features: &config.features,
+        web_search_mode: config.web_search_mode,
+    })
+    .with_forbidden_tools(config.forbidden_tools.clone())
+    .with_provider_tools(config.provider_tools.clone());
+    let (tools, _) = build_specs(&tools_config, None).build();
+    tools
+        .iter()
+        .map(|tool| tool.spec.name().to_string())
+        .collect()
 }
 
 /// Generic JSON‑Schema subset needed for our tool definitions
@@ -180,6 +253,15 @@ fn create_exec_command_tool() -> ToolSpec {
                 ),
             }
         ),
+        (
+            "raw".to_string(),
+            JsonSchema::Boolean {
+                description: Some(
+                    "Keep full terminal semantics (colors, cursor addressing) for a program the user will watch or take over, and record the session for replay. Implies tty. Defaults to false."
+                        .to_string(),
+                ),
+            },
+        ),
         (
             "yield_time_ms".to_string(),
             JsonSchema::Number {
@@ -905,6 +987,289 @@ fn create_list_dir_tool() -> ToolSpec {
     })
 }
 
+fn create_bulk_edit_tool() -> ToolSpec {
+    let operation_properties = BTreeMap::from([
+        (
+            "type".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "One of \"create\", \"rename\", \"delete\" or \"edit\".".to_string(),
+                ),
+            },
+        ),
+        (
+            "path".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "File to operate on, relative to the working directory.".to_string(),
+                ),
+            },
+        ),
+        (
+            "content".to_string(),
+            JsonSchema::String {
+                description: Some("create: the full contents of the new file.".to_string()),
+            },
+        ),
+        (
+            "new_path".to_string(),
+            JsonSchema::String {
+                description: Some("rename: the destination path.".to_string()),
+            },
+        ),
+        (
+            "old_text".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "edit: text to replace. Must occur exactly once in the file.".to_string(),
+                ),
+            },
+        ),
+        (
+            "new_text".to_string(),
+            JsonSchema::String {
+                description: Some("edit: replacement for `old_text`.".to_string()),
+            },
+        ),
+    ]);
+    let properties = BTreeMap::from([(
+        "operations".to_string(),
+        JsonSchema::Array {
+            items: Box::new(JsonSchema::Object {
+                properties: operation_properties,
+                required: Some(vec!["type".to_string(), "path".to_string()]),
+                additional_properties: Some(false.into()),
+            }),
+            description: Some(
+                "Operations applied in order. Later operations see the effect of earlier ones, \
+                 so a file can be renamed and then edited at its new path."
+                    .to_string(),
+            ),
+        },
+    )]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "bulk_edit".to_string(),
+        description: "Creates, renames, deletes and edits several files as one transaction: \
+                      either every operation is applied or the working tree is left unchanged. \
+                      Prefer it over sequences of shell mv/rm calls when restructuring code."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["operations".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_read_output_chunk_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "call_id".to_string(),
+            JsonSchema::String {
+                description: Some("Call id named in the chunk header.".to_string()),
+            },
+        ),
+        (
+            "chunk".to_string(),
+            JsonSchema::Number {
+                description: Some("1-based number of the chunk to read.".to_string()),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: READ_OUTPUT_CHUNK_TOOL_NAME.to_string(),
+        description: "Reads another chunk of a tool output that was too large to return at once. \
+                      Such outputs start with a header like \"[output of call `<call_id>`: chunk 1 of N; ...]\"; \
+                      only page further when the rest of the output is actually needed."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["call_id".to_string(), "chunk".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_read_artifact_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "artifact_id".to_string(),
+            JsonSchema::String {
+                description: Some("Artifact id named in the truncated output.".to_string()),
+            },
+        ),
+        (
+            "start_line".to_string(),
+            JsonSchema::Number {
+                description: Some("1-based first line to read. Defaults to 1.".to_string()),
+            },
+        ),
+        (
+            "end_line".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "1-based last line to read, inclusive. Defaults to the end.".to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: READ_ARTIFACT_TOOL_NAME.to_string(),
+        description: "Reads a range of lines from the full output of a command whose output was truncated. \
+                      Such outputs end with a note like \"[full output (N lines) saved as artifact `<id>`; ...]\"; \
+                      use it to look at the part of a long log that was cut out, e.g. the first compiler error."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["artifact_id".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_clipboard_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "action".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "`set` to store `content` under `name` (empty content removes the clip), \
+                     `get` to read the clip `name`, or `list` to see every clip with its size."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "name".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Clip name: letters, digits, `-`, `_` or `.`. Required for `set` and `get`."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "content".to_string(),
+            JsonSchema::String {
+                description: Some("Text to store with `set`.".to_string()),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: CLIPBOARD_TOOL_NAME.to_string(),
+        description: "Named text blobs shared by you and the user outside the conversation, such as \
+                      a stack trace or a long spec. Write `{{clip:<name>}}` in the arguments of any \
+                      tool call to have the clip's content substituted when the call runs, which \
+                      saves repeating large text. Only `get` a clip when you need to read it."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["action".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_repl_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "code".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Snippet to run. The value of a trailing expression is printed.".to_string(),
+                ),
+            },
+        ),
+        (
+            "reset".to_string(),
+            JsonSchema::Boolean {
+                description: Some(
+                    "Start a fresh interpreter before running `code`, discarding all state."
+                        .to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: REPL_TOOL_NAME.to_string(),
+        description: "Runs code in an interpreter (Python unless configured otherwise) that \
+                      stays alive for the whole conversation: variables, imports and loaded data \
+                      persist between calls, so explore data step by step instead of re-running \
+                      a script. Returns the snippet's stdout, stderr and error. A snippet that \
+                      runs past the timeout or exhausts memory restarts the interpreter and loses \
+                      its state."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["code".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_project_map_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "path".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Directory to map. Defaults to the session's working directory.".to_string(),
+                ),
+            },
+        ),
+        (
+            "max_depth".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "The maximum directory depth to traverse (defaults to 6).".to_string(),
+                ),
+            },
+        ),
+        (
+            "max_files".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "Maximum number of files to include (defaults to 500).".to_string(),
+                ),
+            },
+        ),
+        (
+            "include_symbols".to_string(),
+            JsonSchema::Boolean {
+                description: Some(
+                    "When false, only the directory tree is returned (defaults to true)."
+                        .to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "project_map".to_string(),
+        description:
+            "Outlines the project as a .gitignore-aware directory tree annotated with the \
+                      top-level symbols (and their line numbers) of each source file. Use it for \
+                      orientation before reaching for many ls/grep calls."
+                .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
 fn create_list_mcp_resources_tool() -> ToolSpec {
     let properties = BTreeMap::from([
         (
@@ -1066,6 +1431,27 @@ pub(crate) fn create_tools_json_for_chat_completions_api(
     Ok(tools_json)
 }
 
+/// Returns JSON values in the tool shape of Anthropic's Messages API
+/// (`{name, description, input_schema}`). As with Chat Completions, only
+/// function tools can be expressed.
+pub(crate) fn create_tools_json_for_anthropic_messages_api(
+    tools: &[ToolSpec],
+) -> crate::error::Result<Vec<serde_json::Value>> {
+    let responses_api_tools_json = create_tools_json_for_responses_api(tools)?;
+    let tools_json = responses_api_tools_json
+        .into_iter()
+        .filter(|tool| tool.get("type").and_then(serde_json::Value::as_str) == Some("function"))
+        .map(|tool| {
+            json!({
+                "name": tool.get("name").cloned().unwrap_or_default(),
+                "description": tool.get("description").cloned().unwrap_or_default(),
+                "input_schema": tool.get("parameters").cloned().unwrap_or_else(|| json!({"type": "object"})),
+            })
+        })
+        .collect::<Vec<serde_json::Value>>();
+    Ok(tools_json)
+}
+
 pub(crate) fn mcp_tool_to_openai_tool(
     fully_qualified_name: String,
     tool: mcp_types::Tool,
@@ -1212,19 +1598,101 @@ fn sanitize_json_schema(value: &mut JsonValue) {
     }
 }
 
+fn create_delegate_local_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "task".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "What the local model should do, stated completely: it cannot see this \
+                     conversation. E.g. \"List the distinct error messages in this log with \
+                     their counts.\""
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "input".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Short text the task works on. Everything passed here is written out by \
+                     you, so pass large inputs with `paths` or `call_ids` instead."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "paths".to_string(),
+            JsonSchema::Array {
+                items: Box::new(JsonSchema::String { description: None }),
+                description: Some(
+                    "Files the task works on, relative to the working directory. They are read \
+                     by the tool and passed to the local model directly."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "call_ids".to_string(),
+            JsonSchema::Array {
+                items: Box::new(JsonSchema::String { description: None }),
+                description: Some(
+                    "Call ids of earlier tool calls in this conversation whose output the task \
+                     works on, such as a long command output. The full output is used when it \
+                     was saved as an artifact."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "max_output_tokens".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "Upper bound on the answer's length; it cannot exceed the configured cap."
+                        .to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "delegate_local".to_string(),
+        description: "Hands a narrowly scoped, mechanical subtask (summarizing a long log, \
+                      extracting fields, classifying lines) to a smaller model running locally, \
+                      and returns its answer. Point it at large inputs with `paths` or `call_ids` \
+                      rather than copying them into `input`: only the answer enters your context. \
+                      The local model is less capable: do not delegate reasoning, code changes or \
+                      decisions, and check answers you rely on. Answers are capped in length."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["task".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
 /// Builds the tool registry builder while collecting tool specs for later serialization.
 pub(crate) fn build_specs(
     config: &ToolsConfig,
     mcp_tools: Option<HashMap<String, mcp_types::Tool>>,
 ) -> ToolRegistryBuilder {
     use crate::tools::handlers::ApplyPatchHandler;
+    use crate::tools::handlers::BulkEditHandler;
+    use crate::tools::handlers::ClipboardHandler;
     use crate::tools::handlers::CollabHandler;
+    use crate::tools::handlers::DelegateLocalHandler;
     use crate::tools::handlers::GrepFilesHandler;
     use crate::tools::handlers::ListDirHandler;
     use crate::tools::handlers::McpHandler;
     use crate::tools::handlers::McpResourceHandler;
     use crate::tools::handlers::PlanHandler;
+    use crate::tools::handlers::ProjectMapHandler;
+    use crate::tools::handlers::ReadArtifactHandler;
     use crate::tools::handlers::ReadFileHandler;
+    use crate::tools::handlers::ReadOutputChunkHandler;
+    use crate::tools::handlers::ReplHandler;
     use crate::tools::handlers::RequestUserInputHandler;
     use crate::tools::handlers::ShellCommandHandler;
     use crate::tools::handlers::ShellHandler;
@@ -1301,6 +1769,42 @@ pub(crate) fn build_specs(
         builder.register_handler("apply_patch", apply_patch_handler);
     }
 
+    if config.bulk_edit_tool {
+        let bulk_edit_handler = Arc::new(BulkEditHandler);
+        builder.push_spec(create_bulk_edit_tool());
+        builder.register_handler("bulk_edit", bulk_edit_handler);
+    }
+
+    if config.chunked_tool_output {
+        let read_output_chunk_handler = Arc::new(ReadOutputChunkHandler);
+        builder.push_spec_with_parallel_support(create_read_output_chunk_tool(), true);
+        builder.register_handler(READ_OUTPUT_CHUNK_TOOL_NAME, read_output_chunk_handler);
+    }
+
+    if config.exec_output_artifacts {
+        let read_artifact_handler = Arc::new(ReadArtifactHandler);
+        builder.push_spec_with_parallel_support(create_read_artifact_tool(), true);
+        builder.register_handler(READ_ARTIFACT_TOOL_NAME, read_artifact_handler);
+    }
+
+    if config.clipboard {
+        let clipboard_handler = Arc::new(ClipboardHandler);
+        builder.push_spec(create_clipboard_tool());
+        builder.register_handler(CLIPBOARD_TOOL_NAME, clipboard_handler);
+    }
+
+    if config.repl {
+        let repl_handler = Arc::new(ReplHandler);
+        builder.push_spec(create_repl_tool());
+        builder.register_handler(REPL_TOOL_NAME, repl_handler);
+    }
+
+    if config.delegate_local {
+        let delegate_local_handler = Arc::new(DelegateLocalHandler);
+        builder.push_spec_with_parallel_support(create_delegate_local_tool(), true);
+        builder.register_handler("delegate_local", delegate_local_handler);
+    }
+
     if config
         .experimental_supported_tools
         .contains(&"grep_files".to_string())
@@ -1329,6 +1833,16 @@ pub(crate) fn build_specs(
         builder.register_handler("list_dir", list_dir_handler);
     }
 
+    if config
+        .experimental_supported_tools
+        .iter()
+        .any(|tool| tool == "project_map")
+    {
+        let project_map_handler = Arc::new(ProjectMapHandler);
+        builder.push_spec_with_parallel_support(create_project_map_tool(), true);
+        builder.register_handler("project_map", project_map_handler);
+    }
+
     if config
         .experimental_supported_tools
         .contains(&"test_sync_tool".to_string())
@@ -1352,6 +1866,20 @@ pub(crate) fn build_specs(
         Some(WebSearchMode::Disabled) | None => {}
     }
 
+    if config.provider_tools.code_interpreter == Some(true) {
+        builder.push_spec(ToolSpec::CodeInterpreter {
+            container: CodeInterpreterContainer::Auto,
+        });
+    }
+    if let Some(file_search) = &config.provider_tools.file_search
+        && !file_search.vector_store_ids.is_empty()
+    {
+        builder.push_spec(ToolSpec::FileSearch {
+            vector_store_ids: file_search.vector_store_ids.clone(),
+            max_num_results: file_search.max_num_results,
+        });
+    }
+
     builder.push_spec_with_parallel_support(create_view_image_tool(), true);
     builder.register_handler("view_image", view_image_handler);
 
@@ -1384,6 +1912,10 @@ pub(crate) fn build_specs(
         }
     }
 
+    builder.remove_tools(&config.forbidden_tools);
+    if let Some(allowed_tools) = &config.allowed_tools {
+        builder.retain_tools(allowed_tools);
+    }
     builder
 }
 
@@ -1391,6 +1923,7 @@ pub(crate) fn build_specs(
 mod tests {
     use crate::client_common::tools::FreeformTool;
     use crate::config::test_config;
+    use crate::config::types::FileSearchTool;
     use crate::models_manager::manager::ModelsManager;
     use crate::tools::registry::ConfiguredToolSpec;
     use mcp_types::ToolInputSchema;
@@ -1403,6 +1936,8 @@ mod tests {
             ToolSpec::Function(ResponsesApiTool { name, .. }) => name,
             ToolSpec::LocalShell {} => "local_shell",
             ToolSpec::WebSearch { .. } => "web_search",
+            ToolSpec::CodeInterpreter { .. } => "code_interpreter",
+            ToolSpec::FileSearch { .. } => "file_search",
             ToolSpec::Freeform(FreeformTool { name, .. }) => name,
         }
     }
@@ -1480,7 +2015,11 @@ mod tests {
             ToolSpec::Function(ResponsesApiTool { parameters, .. }) => {
                 strip_descriptions_schema(parameters);
             }
-            ToolSpec::Freeform(_) | ToolSpec::LocalShell {} | ToolSpec::WebSearch { .. } => {}
+            ToolSpec::Freeform(_)
+            | ToolSpec::LocalShell {}
+            | ToolSpec::WebSearch { .. }
+            | ToolSpec::CodeInterpreter { .. }
+            | ToolSpec::FileSearch { .. } => {}
         }
     }
 
@@ -1594,6 +2133,104 @@ mod tests {
         assert_contains_tool_names(&tools, &["request_user_input"]);
     }
 
+    #[test]
+    fn forbidden_tools_are_removed_from_specs_and_handlers() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
+        let features = Features::with_defaults();
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Live),
+        })
+        .with_forbidden_tools(vec!["web_search".to_string(), "view_image".to_string()]);
+
+        let (tools, registry) = build_specs(&tools_config, None).build();
+
+        let names = tools.iter().map(|t| t.spec.name()).collect::<Vec<_>>();
+        assert!(
+            !names.contains(&"web_search") && !names.contains(&"view_image"),
+            "forbidden tools should be removed; had: {names:?}"
+        );
+        assert!(registry.handler("view_image").is_none());
+    }
+
+    #[test]
+    fn allowed_tools_leave_out_every_other_tool() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
+        let features = Features::with_defaults();
+        let mut tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Live),
+        });
+        tools_config.allowed_tools = Some(vec!["view_image".to_string(), "web_search".to_string()]);
+
+        let (tools, registry) = build_specs(
+            &tools_config,
+            Some(HashMap::from([(
+                "fs/write".to_string(),
+                mcp_types::Tool {
+                    name: "write".to_string(),
+                    input_schema: ToolInputSchema {
+                        properties: None,
+                        required: None,
+                        r#type: "object".to_string(),
+                    },
+                    output_schema: None,
+                    title: None,
+                    annotations: None,
+                    description: Some("Write a file".to_string()),
+                },
+            )])),
+        )
+        .build();
+
+        let names = tools.iter().map(|t| t.spec.name()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["web_search", "view_image"]);
+        assert!(registry.handler("fs/write").is_none());
+        assert!(registry.handler("apply_patch").is_none());
+    }
+
+    #[test]
+    fn provider_tools_are_offered_when_configured() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
+        let features = Features::with_defaults();
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: None,
+        })
+        .with_provider_tools(ProviderTools {
+            code_interpreter: Some(true),
+            file_search: Some(FileSearchTool {
+                vector_store_ids: vec!["vs_1".to_string()],
+                max_num_results: Some(5),
+            }),
+        });
+
+        let (tools, _) = build_specs(&tools_config, None).build();
+
+        let provider_specs = tools
+            .iter()
+            .filter_map(|tool| match &tool.spec {
+                spec @ (ToolSpec::CodeInterpreter { .. } | ToolSpec::FileSearch { .. }) => {
+                    Some(serde_json::to_value(spec).expect("serialize"))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            provider_specs,
+            vec![
+                json!({"type": "code_interpreter", "container": {"type": "auto"}}),
+                json!({"type": "file_search", "vector_store_ids": ["vs_1"], "max_num_results": 5}),
+            ]
+        );
+    }
+
     fn assert_model_tools(
         model_slug: &str,
         features: &Features,
@@ -1922,6 +2559,147 @@ mod tests {
         assert!(find_tool(&tools, "read_file").supports_parallel_tool_calls);
     }
 
+    #[test]
+    fn bulk_edit_tool_requires_feature() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
+        let mut features = Features::with_defaults();
+        let has_bulk_edit = |features: &Features| {
+            let tools_config = ToolsConfig::new(&ToolsConfigParams {
+                model_info: &model_info,
+                features,
+                web_search_mode: None,
+            });
+            let (tools, _) = build_specs(&tools_config, None).build();
+            tools
+                .iter()
+                .any(|tool| tool_name(&tool.spec) == "bulk_edit")
+        };
+
+        assert!(!has_bulk_edit(&features));
+        features.enable(Feature::BulkEdit);
+        assert!(has_bulk_edit(&features));
+    }
+
+    #[test]
+    fn read_output_chunk_tool_requires_feature() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
+        let mut features = Features::with_defaults();
+        let has_read_output_chunk = |features: &Features| {
+            let tools_config = ToolsConfig::new(&ToolsConfigParams {
+                model_info: &model_info,
+                features,
+                web_search_mode: None,
+            });
+            let (tools, _) = build_specs(&tools_config, None).build();
+            tools
+                .iter()
+                .any(|tool| tool_name(&tool.spec) == "read_output_chunk")
+        };
+
+        assert!(!has_read_output_chunk(&features));
+        features.enable(Feature::ChunkedToolOutput);
+        assert!(has_read_output_chunk(&features));
+    }
+
+    #[test]
+    fn read_artifact_tool_requires_feature() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
+        let mut features = Features::with_defaults();
+        let has_read_artifact = |features: &Features| {
+            let tools_config = ToolsConfig::new(&ToolsConfigParams {
+                model_info: &model_info,
+                features,
+                web_search_mode: None,
+            });
+            let (tools, _) = build_specs(&tools_config, None).build();
+            tools
+                .iter()
+                .any(|tool| tool_name(&tool.spec) == READ_ARTIFACT_TOOL_NAME)
+        };
+
+        assert!(!has_read_artifact(&features));
+        features.enable(Feature::ExecOutputArtifacts);
+        assert!(has_read_artifact(&features));
+    }
+
+    #[test]
+    fn clipboard_tool_requires_feature() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
+        let mut features = Features::with_defaults();
+        let has_clipboard = |features: &Features| {
+            let tools_config = ToolsConfig::new(&ToolsConfigParams {
+                model_info: &model_info,
+                features,
+                web_search_mode: None,
+            });
+            let (tools, _) = build_specs(&tools_config, None).build();
+            tools
+                .iter()
+                .any(|tool| tool_name(&tool.spec) == "clipboard")
+        };
+
+        assert!(!has_clipboard(&features));
+        features.enable(Feature::Clipboard);
+        assert!(has_clipboard(&features));
+    }
+
+    #[test]
+    fn delegate_local_tool_requires_feature() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
+        let mut features = Features::with_defaults();
+        let has_delegate_local = |features: &Features| {
+            let tools_config = ToolsConfig::new(&ToolsConfigParams {
+                model_info: &model_info,
+                features,
+                web_search_mode: None,
+            });
+            let (tools, _) = build_specs(&tools_config, None).build();
+            tools
+                .iter()
+                .any(|tool| tool_name(&tool.spec) == "delegate_local")
+        };
+
+        assert!(!has_delegate_local(&features));
+        features.enable(Feature::DelegateLocal);
+        assert!(has_delegate_local(&features));
+    }
+
+    #[test]
+    fn builtin_tool_names_follow_features_and_forbidden_tools() {
+        let mut config = test_config();
+        config.features.enable(Feature::Repl);
+        config.forbidden_tools = vec!["view_image".to_string()];
+
+        let names = builtin_tool_names(&config);
+        assert!(names.iter().any(|name| name == "repl"));
+        assert!(!names.iter().any(|name| name == "view_image"));
+    }
+
+    #[test]
+    fn repl_tool_requires_feature() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
+        let mut features = Features::with_defaults();
+        let has_repl = |features: &Features| {
+            let tools_config = ToolsConfig::new(&ToolsConfigParams {
+                model_info: &model_info,
+                features,
+                web_search_mode: None,
+            });
+            let (tools, _) = build_specs(&tools_config, None).build();
+            tools.iter().any(|tool| tool_name(&tool.spec) == "repl")
+        };
+
+        assert!(!has_repl(&features));
+        features.enable(Feature::Repl);
+        assert!(has_repl(&features));
+    }
+
     #[test]
     fn test_test_model_info_includes_sync_tool() {
         let config = test_config();
@@ -1950,6 +2728,11 @@ mod tests {
                 .any(|tool| tool_name(&tool.spec) == "grep_files")
         );
         assert!(tools.iter().any(|tool| tool_name(&tool.spec) == "list_dir"));
+        assert!(
+            tools
+                .iter()
+                .any(|tool| tool_name(&tool.spec) == "project_map")
+        );
     }
 
     #[test]
@@ -2570,4 +3353,39 @@ Examples of valid command strings:
             })]
         );
     }
+
+    #[test]
+    fn anthropic_tools_use_input_schema() {
+        let properties =
+            BTreeMap::from([("foo".to_string(), JsonSchema::String { description: None })]);
+        let tools = vec![
+            ToolSpec::Function(ResponsesApiTool {
+                name: "demo".to_string(),
+                description: "A demo tool".to_string(),
+                strict: false,
+                parameters: JsonSchema::Object {
+                    properties,
+                    required: None,
+                    additional_properties: None,
+                },
+            }),
+            ToolSpec::LocalShell {},
+        ];
+
+        let tools_json = create_tools_json_for_anthropic_messages_api(&tools).unwrap();
+
+        assert_eq!(
+            tools_json,
+            vec![json!({
+                "name": "demo",
+                "description": "A demo tool",
+                "input_schema": {
+                    "type": "object",
+                    "properties": {
+                        "foo": { "type": "string" }
+                    },
+                },
+            })]
+        );
+    }
 }
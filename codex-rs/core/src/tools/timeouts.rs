@@ -0,0 +1,131 @@
+//! Timeouts of the shell tools: a per-tool default for calls that do not set
+//! `timeout_ms`, and a cap on what a call may request.
+//!
+//! When a call times out the model is told which timeout applied and how much
+//! longer it may ask for, so it can retry with a larger budget or split the
+//! work instead of repeating the same command.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::codex::TurnContext;
+use crate::config::types::ToolTimeout;
+use crate::exec::DEFAULT_EXEC_COMMAND_TIMEOUT_MS;
+
+const DEFAULT_MAX_TIMEOUT_MS: u64 = 60 * 60 * 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ToolTimeoutPolicy {
+    pub(crate) default_ms: u64,
+    pub(crate) max_ms: u64,
+}
+
+impl ToolTimeoutPolicy {
+    pub(crate) fn for_tool(timeouts: &HashMap<String, ToolTimeout>, tool_name: &str) -> Self {
+        let config = timeouts.get(tool_name);
+        let max_ms = config
+            .and_then(|config| config.max_ms)
+            .unwrap_or(DEFAULT_MAX_TIMEOUT_MS);
+        let default_ms = config
+            .and_then(|config| config.default_ms)
+            .unwrap_or(DEFAULT_EXEC_COMMAND_TIMEOUT_MS)
+            .min(max_ms);
+        Self { default_ms, max_ms }
+    }
+
+    pub(crate) fn for_turn(turn: &TurnContext, tool_name: &str) -> Self {
+        Self::for_tool(&turn.client.config().tool_timeouts, tool_name)
+    }
+
+    /// The timeout for a call that asked for `requested_ms`, clamped to the
+    /// cap.
+    pub(crate) fn resolve(self, requested_ms: Option<u64>) -> u64 {
+        requested_ms.unwrap_or(self.default_ms).min(self.max_ms)
+    }
+
+    pub(crate) fn budget(self, timeout_ms: u64) -> TimeoutBudget {
+        TimeoutBudget {
+            timeout_ms,
+            max_timeout_ms: self.max_ms,
+            remaining_budget_ms: self.max_ms.saturating_sub(timeout_ms),
+        }
+    }
+}
+
+/// Reported to the model alongside the output of a call that timed out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub(crate) struct TimeoutBudget {
+    /// The timeout the call ran with.
+    pub(crate) timeout_ms: u64,
+    /// The largest `timeout_ms` a call of this tool may request.
+    pub(crate) max_timeout_ms: u64,
+    /// How much longer than this call a retry may run.
+    pub(crate) remaining_budget_ms: u64,
+}
+
+impl TimeoutBudget {
+    pub(crate) fn hint(self) -> String {
+        if self.remaining_budget_ms == 0 {
+            format!(
+                "The timeout was already at the maximum of {} ms; split the work into shorter \
+                 commands or run it in the background.",
+                self.max_timeout_ms
+            )
+        } else {
+            format!(
+                "Retry with `timeout_ms` up to {} ms ({} ms more than this call had) if the \
+                 command needs longer, or split the work into shorter commands.",
+                self.max_timeout_ms, self.remaining_budget_ms
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn resolves_per_tool_defaults_and_clamps_requests() {
+        let timeouts = HashMap::from([(
+            "shell_command".to_string(),
+            ToolTimeout {
+                default_ms: Some(30_000),
+                max_ms: Some(120_000),
+            },
+        )]);
+
+        let configured = ToolTimeoutPolicy::for_tool(&timeouts, "shell_command");
+        assert_eq!(configured.resolve(None), 30_000);
+        assert_eq!(configured.resolve(Some(5_000)), 5_000);
+        assert_eq!(configured.resolve(Some(600_000)), 120_000);
+
+        let fallback = ToolTimeoutPolicy::for_tool(&timeouts, "shell");
+        assert_eq!(fallback.resolve(None), DEFAULT_EXEC_COMMAND_TIMEOUT_MS);
+        assert_eq!(fallback.resolve(Some(7_200_000)), DEFAULT_MAX_TIMEOUT_MS);
+    }
+
+    #[test]
+    fn budget_reports_remaining_headroom() {
+        let policy = ToolTimeoutPolicy {
+            default_ms: 10_000,
+            max_ms: 60_000,
+        };
+
+        assert_eq!(
+            policy.budget(10_000),
+            TimeoutBudget {
+                timeout_ms: 10_000,
+                max_timeout_ms: 60_000,
+                remaining_budget_ms: 50_000,
+            }
+        );
+        assert_eq!(
+            policy.budget(60_000).hint(),
+            "The timeout was already at the maximum of 60000 ms; split the work into shorter \
+             commands or run it in the background."
+        );
+    }
+}
@@ -7,7 +7,9 @@ use codex_protocol::openai_models::TruncationMode;
 use codex_protocol::openai_models::TruncationPolicyConfig;
 use codex_protocol::protocol::TruncationPolicy as ProtocolTruncationPolicy;
 
-const APPROX_BYTES_PER_TOKEN: usize = 4;
+use crate::tokenizer::CountMethod;
+use crate::tokenizer::HeuristicTokenizer;
+use crate::tokenizer::Tokenizer;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TruncationPolicy {
@@ -91,12 +93,29 @@ pub(crate) fn truncate_text(content: &str, policy: TruncationPolicy) -> String {
         }
     }
 }
+/// Like [`truncate_text`], but counts a `Tokens` budget with `tokenizer`
+/// rather than the byte heuristic, so text that tokenizes densely (code,
+/// non-English text) is cut where the model's budget actually ends.
+pub(crate) fn truncate_text_with_tokenizer(
+    content: &str,
+    policy: TruncationPolicy,
+    tokenizer: &dyn Tokenizer,
+) -> String {
+    match policy {
+        TruncationPolicy::Tokens(max_tokens) if tokenizer.method() != CountMethod::Heuristic => {
+            truncate_with_tokenizer(content, max_tokens, tokenizer)
+        }
+        TruncationPolicy::Tokens(_) | TruncationPolicy::Bytes(_) => truncate_text(content, policy),
+    }
+}
+
 /// Globally truncate function output items to fit within the given
 /// truncation policy's budget, preserving as many text/image items as
 /// possible and appending a summary for any omitted text items.
 pub(crate) fn truncate_function_output_items_with_policy(
     items: &[FunctionCallOutputContentItem],
     policy: TruncationPolicy,
+    tokenizer: &dyn Tokenizer,
 ) -> Vec<FunctionCallOutputContentItem> {
     let mut out: Vec<FunctionCallOutputContentItem> = Vec::with_capacity(items.len());
     let mut remaining_budget = match policy {
@@ -115,7 +134,9 @@ pub(crate) fn truncate_function_output_items_with_policy(
 
                 let cost = match policy {
                     TruncationPolicy::Bytes(_) => text.len(),
-                    TruncationPolicy::Tokens(_) => approx_token_count(text),
+                    TruncationPolicy::Tokens(_) => {
+                        usize::try_from(tokenizer.count_tokens(text)).unwrap_or(usize::MAX)
+                    }
                 };
 
                 if cost <= remaining_budget {
@@ -126,7 +147,7 @@ pub(crate) fn truncate_function_output_items_with_policy(
                         TruncationPolicy::Bytes(_) => TruncationPolicy::Bytes(remaining_budget),
                         TruncationPolicy::Tokens(_) => TruncationPolicy::Tokens(remaining_budget),
                     };
-                    let snippet = truncate_text(text, snippet_policy);
+                    let snippet = truncate_text_with_tokenizer(text, snippet_policy, tokenizer);
                     if snippet.is_empty() {
                         omitted_text_items += 1;
                     } else {
@@ -177,6 +198,34 @@ fn truncate_with_token_budget(s: &str, policy: TruncationPolicy) -> (String, Opt
     }
 }
 
+/// Truncate the middle of `s` to about `max_tokens` tokens as counted by
+/// `tokenizer`, preserving the beginning and the end.
+fn truncate_with_tokenizer(s: &str, max_tokens: usize, tokenizer: &dyn Tokenizer) -> String {
+    // Every token covers at least one byte.
+    if s.len() <= max_tokens {
+        return s.to_string();
+    }
+    let total_tokens = tokenizer.count_tokens(s);
+    if total_tokens <= max_tokens as u64 {
+        return s.to_string();
+    }
+    let policy = TruncationPolicy::Tokens(max_tokens);
+    if max_tokens == 0 {
+        return format_truncation_marker(policy, total_tokens);
+    }
+
+    // Keep the share of the bytes that the budget is of the tokens.
+    let max_bytes = usize::try_from(s.len() as u128 * max_tokens as u128 / total_tokens as u128)
+        .unwrap_or(usize::MAX);
+    let (left_budget, right_budget) = split_budget(max_bytes);
+    let (_, left, right) = split_string(s, left_budget, right_budget);
+    let kept_tokens = tokenizer
+        .count_tokens(left)
+        .saturating_add(tokenizer.count_tokens(right));
+    let marker = format_truncation_marker(policy, total_tokens.saturating_sub(kept_tokens));
+    assemble_truncated_output(left, right, &marker)
+}
+
 /// Truncate a string using a byte budget derived from the token budget, without
 /// performing any real tokenization. This keeps the logic purely byte-based and
 /// uses a bytes placeholder in the truncated output.
@@ -286,19 +335,18 @@ fn assemble_truncated_output(prefix: &str, suffix: &str, marker: &str) -> String
     out
 }
 
+// Estimates for budgets that are not tied to a model's tokenizer, with the
+// same heuristic as `tokenizer::HeuristicTokenizer`.
 pub(crate) fn approx_token_count(text: &str) -> usize {
-    let len = text.len();
-    len.saturating_add(APPROX_BYTES_PER_TOKEN.saturating_sub(1)) / APPROX_BYTES_PER_TOKEN
+    usize::try_from(HeuristicTokenizer.count_tokens(text)).unwrap_or(usize::MAX)
 }
 
 pub(crate) fn approx_bytes_for_tokens(tokens: usize) -> usize {
-    tokens.saturating_mul(APPROX_BYTES_PER_TOKEN)
+    HeuristicTokenizer::bytes_for_tokens(tokens)
 }
 
 pub(crate) fn approx_tokens_from_byte_count(bytes: usize) -> u64 {
-    let bytes_u64 = bytes as u64;
-    bytes_u64.saturating_add((APPROX_BYTES_PER_TOKEN as u64).saturating_sub(1))
-        / (APPROX_BYTES_PER_TOKEN as u64)
+    HeuristicTokenizer::tokens_for_bytes(bytes)
 }
 
 #[cfg(test)]
@@ -310,10 +358,48 @@ mod tests {
     use super::split_string;
     use super::truncate_function_output_items_with_policy;
     use super::truncate_text;
+    use super::truncate_text_with_tokenizer;
     use super::truncate_with_token_budget;
+    use crate::tokenizer::CountMethod;
+    use crate::tokenizer::Encoding;
+    use crate::tokenizer::HeuristicTokenizer;
+    use crate::tokenizer::Tokenizer;
     use codex_protocol::models::FunctionCallOutputContentItem;
     use pretty_assertions::assert_eq;
 
+    /// Counts one token per character, standing in for a model's encoding.
+    struct CharTokenizer;
+
+    impl Tokenizer for CharTokenizer {
+        fn method(&self) -> CountMethod {
+            CountMethod::Bpe(Encoding::O200kBase)
+        }
+
+        fn count_tokens(&self, text: &str) -> u64 {
+            text.chars().count() as u64
+        }
+    }
+
+    #[test]
+    fn token_budgets_are_counted_with_the_tokenizer() {
+        let content = "abcdefghij";
+
+        assert_eq!(
+            (
+                truncate_text_with_tokenizer(content, TruncationPolicy::Tokens(4), &CharTokenizer),
+                truncate_text_with_tokenizer(content, TruncationPolicy::Tokens(10), &CharTokenizer),
+                truncate_text_with_tokenizer(content, TruncationPolicy::Tokens(0), &CharTokenizer),
+                truncate_text_with_tokenizer(content, TruncationPolicy::Bytes(4), &CharTokenizer),
+            ),
+            (
+                "ab…6 tokens truncated…ij".to_string(),
+                content.to_string(),
+                "…10 tokens truncated…".to_string(),
+                truncate_text(content, TruncationPolicy::Bytes(4)),
+            )
+        );
+    }
+
     #[test]
     fn split_string_works() {
         assert_eq!(split_string("hello world", 5, 5), (1, "hello", "world"));
@@ -486,8 +572,11 @@ mod tests {
             FunctionCallOutputContentItem::InputText { text: t5 },
         ];
 
-        let output =
-            truncate_function_output_items_with_policy(&items, TruncationPolicy::Tokens(limit));
+        let output = truncate_function_output_items_with_policy(
+            &items,
+            TruncationPolicy::Tokens(limit),
+            &HeuristicTokenizer,
+        );
 
         // Expect: t1 (full), t2 (full), image, t3 (truncated), summary mentioning 2 omitted.
         assert_eq!(output.len(), 5);
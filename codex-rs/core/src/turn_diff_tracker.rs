@@ -40,6 +40,10 @@ pub struct TurnDiffTracker {
     temp_name_to_current_path: HashMap<String, PathBuf>,
     /// Cache of known git worktree roots to avoid repeated filesystem walks.
     git_root_cache: Vec<PathBuf>,
+    /// External path -> contents as last seen by the model (via a read or a
+    /// successful patch). Used as the merge base when a later patch no longer
+    /// applies because the file changed underneath the model.
+    read_snapshots: HashMap<PathBuf, String>,
 }
 
 impl TurnDiffTracker {
@@ -127,6 +131,21 @@ impl TurnDiffTracker {
         }
     }
 
+    /// Record the contents of `path` as the model last saw them.
+    pub fn record_read_snapshot(&mut self, path: PathBuf, contents: String) {
+        self.read_snapshots.insert(path, contents);
+    }
+
+    /// Forget the snapshot for `path`, e.g. after the file was deleted.
+    pub fn clear_read_snapshot(&mut self, path: &Path) {
+        self.read_snapshots.remove(path);
+    }
+
+    /// All snapshots recorded during this turn, keyed by absolute path.
+    pub fn read_snapshots(&self) -> &HashMap<PathBuf, String> {
+        &self.read_snapshots
+    }
+
     fn get_path_for_internal(&self, internal: &str) -> Option<PathBuf> {
         self.temp_name_to_current_path
             .get(internal)
@@ -294,8 +313,9 @@ impl TurnDiffTracker {
             None
         };
 
-        // Fast path: identical bytes or both missing.
-        if left_bytes == right_bytes.as_deref() {
+        // Fast path: identical bytes (and mode) or both missing.
+        let same_bytes = left_bytes == right_bytes.as_deref();
+        if same_bytes && baseline_mode == current_mode {
             return aggregated;
         }
 
@@ -313,6 +333,11 @@ impl TurnDiffTracker {
             aggregated.push_str(&format!("new mode {current_mode}\n"));
         }
 
+        // Like git, a mode-only change has no index line or hunks.
+        if same_bytes {
+            return aggregated;
+        }
+
         let left_text = left_bytes.and_then(|b| std::str::from_utf8(b).ok());
         let right_text = right_bytes
             .as_deref()
@@ -515,6 +540,7 @@ mod tests {
             file.clone(),
             FileChange::Add {
                 content: "foo\n".to_string(),
+                mode: None,
             },
         )]);
         acc.on_patch_begin(&add_changes);
@@ -545,6 +571,7 @@ index {ZERO_OID}..{right_oid}
             FileChange::Update {
                 unified_diff: "".to_owned(),
                 move_path: None,
+                mode: None,
             },
         )]);
         acc.on_patch_begin(&update_changes);
@@ -571,6 +598,36 @@ index {ZERO_OID}..{right_oid}
         assert_eq!(combined, expected_combined);
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn reports_mode_only_changes() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("run.sh");
+        fs::write(&file, "echo hi\n").unwrap();
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o644)).unwrap();
+
+        let mut acc = TurnDiffTracker::new();
+        acc.on_patch_begin(&HashMap::from([(
+            file.clone(),
+            FileChange::Update {
+                unified_diff: String::new(),
+                move_path: None,
+                mode: Some(codex_protocol::protocol::FileMode::Executable),
+            },
+        )]));
+
+        // Simulate apply: only the executable bit changes.
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o755)).unwrap();
+        let diff = acc.get_unified_diff().unwrap().unwrap();
+        let diff = normalize_diff_for_test(&diff, dir.path());
+        assert_eq!(
+            diff,
+            "diff --git a/<TMP>/run.sh b/<TMP>/run.sh\nold mode 100644\nnew mode 100755\n"
+        );
+    }
+
     #[test]
     fn accumulates_delete() {
         let dir = tempdir().unwrap();
@@ -620,6 +677,7 @@ index {left_oid}..{ZERO_OID}
             FileChange::Update {
                 unified_diff: "".to_owned(),
                 move_path: Some(dest.clone()),
+                mode: None,
             },
         )]);
         acc.on_patch_begin(&mv_changes);
@@ -660,6 +718,7 @@ index {left_oid}..{right_oid}
             FileChange::Update {
                 unified_diff: "".to_owned(),
                 move_path: Some(dest.clone()),
+                mode: None,
             },
         )]);
         acc.on_patch_begin(&mv_changes);
@@ -682,6 +741,7 @@ index {left_oid}..{right_oid}
             FileChange::Update {
                 unified_diff: "".into(),
                 move_path: Some(dest.clone()),
+                mode: None,
             },
         )]);
         acc.on_patch_begin(&mv);
@@ -722,6 +782,7 @@ index {ZERO_OID}..{right_oid}
             FileChange::Update {
                 unified_diff: "".to_owned(),
                 move_path: None,
+                mode: None,
             },
         )]);
         acc.on_patch_begin(&update_a);
@@ -802,6 +863,7 @@ index {left_oid_b}..{ZERO_OID}
             FileChange::Update {
                 unified_diff: "".to_owned(),
                 move_path: None,
+                mode: None,
             },
         )]);
         acc.on_patch_begin(&update_changes);
@@ -838,6 +900,7 @@ Binary files differ
             file.clone(),
             FileChange::Add {
                 content: "foo\n".to_string(),
+                mode: None,
             },
         )]);
         acc.on_patch_begin(&add_changes);
@@ -868,6 +931,7 @@ index {ZERO_OID}..{right_oid}
             FileChange::Update {
                 unified_diff: "".to_owned(),
                 move_path: None,
+                mode: None,
             },
         )]);
         acc.on_patch_begin(&update_changes);
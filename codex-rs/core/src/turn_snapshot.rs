@@ -0,0 +1,185 @@
+//! Snapshots of the exact payload sent to the model for a turn, split into
+//! instruction layers, history, and tools so clients can explain what the
+//! model did (and did not) see.
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::InstructionLayer;
+use codex_protocol::protocol::InstructionLayerKind;
+use codex_protocol::protocol::TurnRequestSnapshot;
+use serde_json::Value;
+
+use crate::client_common::Prompt;
+use crate::instructions::KnowledgeInstructions;
+use crate::instructions::SkillInstructions;
+use crate::instructions::UserInstructions;
+use crate::session_prefix::ENVIRONMENT_CONTEXT_OPEN_TAG;
+use crate::tools::spec::create_tools_json_for_responses_api;
+use crate::truncate::approx_token_count;
+
+pub(crate) fn build_turn_request_snapshot(
+    turn_id: &str,
+    model: &str,
+    request_count: u32,
+    prompt: &Prompt,
+) -> TurnRequestSnapshot {
+    let mut instructions = vec![instruction_layer(
+        InstructionLayerKind::BaseInstructions,
+        prompt.base_instructions.text.clone(),
+    )];
+    let mut history = Vec::new();
+    for item in prompt.get_formatted_input() {
+        match classify_instruction(&item) {
+            Some((kind, text)) => instructions.push(instruction_layer(kind, text)),
+            None => history.push(item),
+        }
+    }
+
+    let tools = create_tools_json_for_responses_api(&prompt.tools).unwrap_or_default();
+    let history_token_count = history.iter().map(approx_json_tokens).sum();
+    let tools_token_count = tools.iter().map(approx_json_tokens).sum();
+    let instructions_token_count: u64 = instructions.iter().map(|layer| layer.token_count).sum();
+
+    TurnRequestSnapshot {
+        turn_id: turn_id.to_string(),
+        model: model.to_string(),
+        request_count,
+        instructions,
+        history,
+        history_token_count,
+        tools,
+        tools_token_count,
+        total_token_count: instructions_token_count + history_token_count + tools_token_count,
+    }
+}
+
+fn instruction_layer(kind: InstructionLayerKind, text: String) -> InstructionLayer {
+    InstructionLayer {
+        kind,
+        token_count: approx_token_count(&text) as u64,
+        text,
+    }
+}
+
+/// Returns the instruction layer an input item belongs to, or `None` for
+/// regular conversation history.
+fn classify_instruction(item: &ResponseItem) -> Option<(InstructionLayerKind, String)> {
+    let ResponseItem::Message { role, content, .. } = item else {
+        return None;
+    };
+    let kind = if role == "developer" {
+        InstructionLayerKind::DeveloperInstructions
+    } else if role != "user" {
+        return None;
+    } else if UserInstructions::is_user_instructions(content) {
+        InstructionLayerKind::UserInstructions
+    } else if SkillInstructions::is_skill_instructions(content) {
+        InstructionLayerKind::SkillInstructions
+    } else if KnowledgeInstructions::is_knowledge_instructions(content) {
+        InstructionLayerKind::KnowledgeSnippet
+    } else if is_environment_context(content) {
+        InstructionLayerKind::EnvironmentContext
+    } else {
+        return None;
+    };
+    let text = content
+        .iter()
+        .filter_map(|content_item| match content_item {
+            ContentItem::InputText { text } | ContentItem::OutputText { text } => {
+                Some(text.as_str())
+            }
+            ContentItem::InputImage { .. } => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some((kind, text))
+}
+
+fn is_environment_context(content: &[ContentItem]) -> bool {
+    matches!(
+        content,
+        [ContentItem::InputText { text }]
+            if text
+                .trim_start()
+                .to_ascii_lowercase()
+                .starts_with(ENVIRONMENT_CONTEXT_OPEN_TAG)
+    )
+}
+
+fn approx_json_tokens<T: serde::Serialize>(value: &T) -> u64 {
+    serde_json::to_string(value)
+        .map(|json| approx_token_count(&json) as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::BaseInstructions;
+    use pretty_assertions::assert_eq;
+
+    fn message(role: &str, text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: role.to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+            end_turn: None,
+        }
+    }
+
+    #[test]
+    fn splits_instruction_layers_from_history() {
+        let prompt = Prompt {
+            input: vec![
+                message("developer", "Be terse."),
+                message(
+                    "user",
+                    "# AGENTS.md instructions for /repo\n\n<INSTRUCTIONS>\nRun tests.\n</INSTRUCTIONS>",
+                ),
+                message(
+                    "user",
+                    "<environment_context>\n  <cwd>/repo</cwd>\n</environment_context>",
+                ),
+                message("user", "Why is main.rs failing?"),
+            ],
+            base_instructions: BaseInstructions {
+                text: "You are Codex.".to_string(),
+            },
+            ..Default::default()
+        };
+
+        let snapshot = build_turn_request_snapshot("turn-1", "gpt-5.1-codex", 1, &prompt);
+
+        let kinds: Vec<InstructionLayerKind> = snapshot
+            .instructions
+            .iter()
+            .map(|layer| layer.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                InstructionLayerKind::BaseInstructions,
+                InstructionLayerKind::DeveloperInstructions,
+                InstructionLayerKind::UserInstructions,
+                InstructionLayerKind::EnvironmentContext,
+            ]
+        );
+        assert_eq!(
+            snapshot.history,
+            vec![message("user", "Why is main.rs failing?")]
+        );
+        assert_eq!(snapshot.instructions[0].token_count, 4);
+        assert_eq!(
+            snapshot.total_token_count,
+            snapshot
+                .instructions
+                .iter()
+                .map(|layer| layer.token_count)
+                .sum::<u64>()
+                + snapshot.history_token_count
+                + snapshot.tools_token_count
+        );
+    }
+}
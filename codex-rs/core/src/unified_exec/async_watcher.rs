@@ -22,6 +22,8 @@ use crate::tools::events::ToolEmitter;
 use crate::tools::events::ToolEventCtx;
 use crate::tools::events::ToolEventStage;
 use crate::unified_exec::head_tail_buffer::HeadTailBuffer;
+use crate::unified_exec::recording::SharedRecording;
+use crate::unified_exec::recording::save_recording;
 
 pub(crate) const TRAILING_OUTPUT_GRACE: Duration = Duration::from_millis(100);
 
@@ -35,11 +37,12 @@ const UNIFIED_EXEC_OUTPUT_DELTA_MAX_BYTES: usize = 8192;
 
 /// Spawn a background task that continuously reads from the PTY, appends to the
 /// shared transcript, and emits ExecCommandOutputDelta events on UTF‑8
-/// boundaries.
+/// boundaries. Raw sessions also append every chunk to their recording.
 pub(crate) fn start_streaming_output(
     process: &UnifiedExecProcess,
     context: &UnifiedExecContext,
     transcript: Arc<Mutex<HeadTailBuffer>>,
+    recording: Option<SharedRecording>,
 ) {
     let mut receiver = process.output_receiver();
     let output_drained = process.output_drained_notify();
@@ -85,6 +88,9 @@ pub(crate) fn start_streaming_output(
                         }
                     };
 
+                    if let Some(recording) = &recording {
+                        recording.lock().await.push(&chunk).await;
+                    }
                     process_chunk(
                         &mut pending,
                         &transcript,
@@ -112,6 +118,7 @@ pub(crate) fn spawn_exit_watcher(
     cwd: PathBuf,
     process_id: String,
     transcript: Arc<Mutex<HeadTailBuffer>>,
+    recording: Option<SharedRecording>,
     started_at: Instant,
 ) {
     let exit_token = process.cancellation_token();
@@ -131,6 +138,7 @@ pub(crate) fn spawn_exit_watcher(
             cwd,
             Some(process_id),
             transcript,
+            recording,
             String::new(),
             exit_code,
             duration,
@@ -173,7 +181,8 @@ async fn process_chunk(
 
 /// Emit an ExecCommandEnd event for a unified exec session, using the transcript
 /// as the primary source of aggregated_output and falling back to the provided
-/// text when the transcript is empty.
+/// text when the transcript is empty. The recording of a raw session is closed
+/// afterwards.
 #[allow(clippy::too_many_arguments)]
 pub(crate) async fn emit_exec_end_for_unified_exec(
     session_ref: Arc<Session>,
@@ -183,16 +192,21 @@ pub(crate) async fn emit_exec_end_for_unified_exec(
     cwd: PathBuf,
     process_id: Option<String>,
     transcript: Arc<Mutex<HeadTailBuffer>>,
+    recording: Option<SharedRecording>,
     fallback_output: String,
     exit_code: i32,
     duration: Duration,
 ) {
     let aggregated_output = resolve_aggregated_output(&transcript, fallback_output).await;
+    let omitted_bytes = transcript.lock().await.omitted_bytes();
     let output = ExecToolCallOutput {
         exit_code,
         stdout: StreamOutput::new(aggregated_output.clone()),
         stderr: StreamOutput::new(String::new()),
-        aggregated_output: StreamOutput::new(aggregated_output),
+        aggregated_output: StreamOutput {
+            omitted_bytes,
+            ..StreamOutput::new(aggregated_output)
+        },
         duration,
         timed_out: false,
     };
@@ -201,11 +215,22 @@ pub(crate) async fn emit_exec_end_for_unified_exec(
         &command,
         cwd,
         ExecCommandSource::UnifiedExecStartup,
-        process_id,
+        process_id.clone(),
     );
     emitter
         .emit(event_ctx, ToolEventStage::Success(output))
         .await;
+
+    if let Some(recording) = &recording {
+        save_recording(
+            session_ref.as_ref(),
+            turn_ref.as_ref(),
+            &call_id,
+            process_id.as_deref().unwrap_or_default(),
+            recording,
+        )
+        .await;
+    }
 }
 
 fn split_valid_utf8_prefix(buffer: &mut Vec<u8>) -> Option<Vec<u8>> {
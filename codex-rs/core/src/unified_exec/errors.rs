@@ -14,8 +14,12 @@ pub(crate) enum UnifiedExecError {
         "stdin is closed for this session; rerun exec_command with tty=true to keep stdin open"
     )]
     StdinClosed,
+    #[error("process {process_id} was not started in raw mode, so it cannot be taken over")]
+    NotRaw { process_id: String },
     #[error("missing command line for unified exec request")]
     MissingCommandLine,
+    #[error("exec_command was cancelled before the process was handed back")]
+    Cancelled,
     #[error("Command denied by sandbox: {message}")]
     SandboxDenied {
         message: String,
@@ -30,6 +30,7 @@ use std::time::Duration;
 use rand::Rng;
 use rand::rng;
 use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
 
 use crate::codex::Session;
 use crate::codex::TurnContext;
@@ -40,6 +41,7 @@ mod errors;
 mod head_tail_buffer;
 mod process;
 mod process_manager;
+mod recording;
 
 pub(crate) use errors::UnifiedExecError;
 pub(crate) use process::UnifiedExecProcess;
@@ -60,14 +62,22 @@ pub(crate) struct UnifiedExecContext {
     pub session: Arc<Session>,
     pub turn: Arc<TurnContext>,
     pub call_id: String,
+    /// Cancelled when the tool call is interrupted.
+    pub cancellation_token: CancellationToken,
 }
 
 impl UnifiedExecContext {
-    pub fn new(session: Arc<Session>, turn: Arc<TurnContext>, call_id: String) -> Self {
+    pub fn new(
+        session: Arc<Session>,
+        turn: Arc<TurnContext>,
+        call_id: String,
+        cancellation_token: CancellationToken,
+    ) -> Self {
         Self {
             session,
             turn,
             call_id,
+            cancellation_token,
         }
     }
 }
@@ -80,6 +90,10 @@ pub(crate) struct ExecCommandRequest {
     pub max_output_tokens: Option<usize>,
     pub workdir: Option<PathBuf>,
     pub tty: bool,
+    /// Keep full terminal semantics (colors, cursor addressing) instead of
+    /// the plain-text environment, and record the output. Only set together
+    /// with `tty`.
+    pub raw: bool,
     pub sandbox_permissions: SandboxPermissions,
     pub justification: Option<String>,
 }
@@ -137,6 +151,8 @@ struct ProcessEntry {
     process_id: String,
     command: Vec<String>,
     tty: bool,
+    /// Started in raw mode, so the user may type into it.
+    raw: bool,
     last_used: tokio::time::Instant,
 }
 
@@ -184,8 +200,23 @@ mod tests {
         cmd: &str,
         yield_time_ms: u64,
     ) -> Result<UnifiedExecResponse, UnifiedExecError> {
-        let context =
-            UnifiedExecContext::new(Arc::clone(session), Arc::clone(turn), "call".to_string());
+        exec_command_with_cancellation(session, turn, cmd, yield_time_ms, CancellationToken::new())
+            .await
+    }
+
+    async fn exec_command_with_cancellation(
+        session: &Arc<Session>,
+        turn: &Arc<TurnContext>,
+        cmd: &str,
+        yield_time_ms: u64,
+        cancellation_token: CancellationToken,
+    ) -> Result<UnifiedExecResponse, UnifiedExecError> {
+        let context = UnifiedExecContext::new(
+            Arc::clone(session),
+            Arc::clone(turn),
+            "call".to_string(),
+            cancellation_token,
+        );
         let process_id = session
             .services
             .unified_exec_manager
@@ -203,6 +234,7 @@ mod tests {
                     max_output_tokens: None,
                     workdir: None,
                     tty: true,
+                    raw: false,
                     sandbox_permissions: SandboxPermissions::UseDefault,
                     justification: None,
                 },
@@ -298,6 +330,25 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn terminal_input_requires_raw_mode() -> anyhow::Result<()> {
+        skip_if_sandbox!(Ok(()));
+
+        let (session, turn) = test_session_and_turn().await;
+
+        let open_shell = exec_command(&session, &turn, "bash -i", 2_500).await?;
+        let process_id = open_shell.process_id.as_ref().expect("expected process_id");
+
+        let result = session
+            .services
+            .unified_exec_manager
+            .write_terminal_input(process_id, b"echo hi\n")
+            .await;
+        assert!(matches!(result, Err(UnifiedExecError::NotRaw { .. })));
+
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     async fn multi_unified_exec_sessions() -> anyhow::Result<()> {
         skip_if_sandbox!(Ok(()));
@@ -477,4 +528,34 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn cancelled_exec_command_terminates_the_process() -> anyhow::Result<()> {
+        skip_if_sandbox!(Ok(()));
+
+        let (session, turn) = test_session_and_turn().await;
+        let cancellation_token = CancellationToken::new();
+        let cancel = cancellation_token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            cancel.cancel();
+        });
+
+        let err =
+            exec_command_with_cancellation(&session, &turn, "sleep 60", 10_000, cancellation_token)
+                .await
+                .expect_err("expected the call to be cancelled");
+
+        assert!(matches!(err, UnifiedExecError::Cancelled));
+        let store = session
+            .services
+            .unified_exec_manager
+            .process_store
+            .lock()
+            .await;
+        assert!(store.processes.is_empty());
+        assert!(store.reserved_process_ids.is_empty());
+
+        Ok(())
+    }
 }
@@ -45,6 +45,8 @@ use crate::unified_exec::head_tail_buffer::HeadTailBuffer;
 use crate::unified_exec::process::OutputBuffer;
 use crate::unified_exec::process::OutputHandles;
 use crate::unified_exec::process::UnifiedExecProcess;
+use crate::unified_exec::recording::SharedRecording;
+use crate::unified_exec::recording::TerminalRecording;
 use crate::unified_exec::resolve_max_tokens;
 
 const UNIFIED_EXEC_ENV: [(&str, &str); 10] = [
@@ -60,8 +62,23 @@ const UNIFIED_EXEC_ENV: [(&str, &str); 10] = [
     ("CODEX_CI", "1"),
 ];
 
-fn apply_unified_exec_env(mut env: HashMap<String, String>) -> HashMap<String, String> {
-    for (key, value) in UNIFIED_EXEC_ENV {
+/// Environment of raw sessions, which a user may watch or take over: colors
+/// and pagers are left as the user configured them.
+const UNIFIED_EXEC_RAW_ENV: [(&str, &str); 5] = [
+    ("TERM", "xterm-256color"),
+    ("LANG", "C.UTF-8"),
+    ("LC_CTYPE", "C.UTF-8"),
+    ("LC_ALL", "C.UTF-8"),
+    ("CODEX_CI", "1"),
+];
+
+fn apply_unified_exec_env(mut env: HashMap<String, String>, raw: bool) -> HashMap<String, String> {
+    let overrides: &[(&str, &str)] = if raw {
+        &UNIFIED_EXEC_RAW_ENV
+    } else {
+        &UNIFIED_EXEC_ENV
+    };
+    for (key, value) in overrides {
         env.insert(key.to_string(), value.to_string());
     }
     env
@@ -129,6 +146,7 @@ impl UnifiedExecProcessManager {
                 request.sandbox_permissions,
                 request.justification,
                 request.tty,
+                request.raw,
                 context,
             )
             .await;
@@ -156,7 +174,23 @@ impl UnifiedExecProcessManager {
         );
         emitter.emit(event_ctx, ToolEventStage::Begin).await;
 
-        start_streaming_output(&process, context, Arc::clone(&transcript));
+        let recording = if request.raw {
+            TerminalRecording::start(
+                context.turn.as_ref(),
+                &context.session.conversation_id,
+                &context.call_id,
+                &request.process_id,
+            )
+            .await
+        } else {
+            None
+        };
+        start_streaming_output(
+            &process,
+            context,
+            Arc::clone(&transcript),
+            recording.clone(),
+        );
 
         let max_tokens = resolve_max_tokens(request.max_output_tokens);
         let yield_time_ms = clamp_yield_time(request.yield_time_ms);
@@ -171,13 +205,21 @@ impl UnifiedExecProcessManager {
             cancellation_token,
         } = process.output_handles();
         let deadline = start + Duration::from_millis(yield_time_ms);
-        let collected = Self::collect_output_until_deadline(
-            &output_buffer,
-            &output_notify,
-            &cancellation_token,
-            deadline,
-        )
-        .await;
+        let collected = tokio::select! {
+            collected = Self::collect_output_until_deadline(
+                &output_buffer,
+                &output_notify,
+                &cancellation_token,
+                deadline,
+            ) => collected,
+            _ = context.cancellation_token.cancelled() => {
+                // The call was interrupted before the model learned the
+                // process id, so nothing could ever poll or stop the process.
+                process.terminate();
+                self.release_process_id(&request.process_id).await;
+                return Err(UnifiedExecError::Cancelled);
+            }
+        };
         let wall_time = Instant::now().saturating_duration_since(start);
 
         let text = String::from_utf8_lossy(&collected).to_string();
@@ -199,6 +241,7 @@ impl UnifiedExecProcessManager {
                 cwd,
                 Some(process_id),
                 Arc::clone(&transcript),
+                recording,
                 output.clone(),
                 exit,
                 wall_time,
@@ -220,7 +263,9 @@ impl UnifiedExecProcessManager {
                 start,
                 process_id,
                 request.tty,
+                request.raw,
                 Arc::clone(&transcript),
+                recording,
             )
             .await;
         };
@@ -334,6 +379,32 @@ impl UnifiedExecProcessManager {
         Ok(response)
     }
 
+    /// Writes input typed by the user into raw process `process_id`. Unlike
+    /// [`Self::write_stdin`] it does not wait for output: the process's
+    /// output keeps streaming through its output delta events.
+    pub(crate) async fn write_terminal_input(
+        &self,
+        process_id: &str,
+        input: &[u8],
+    ) -> Result<(), UnifiedExecError> {
+        let writer_tx = {
+            let mut store = self.process_store.lock().await;
+            let entry = store.processes.get_mut(process_id).ok_or_else(|| {
+                UnifiedExecError::UnknownProcessId {
+                    process_id: process_id.to_string(),
+                }
+            })?;
+            if !entry.raw {
+                return Err(UnifiedExecError::NotRaw {
+                    process_id: process_id.to_string(),
+                });
+            }
+            entry.last_used = Instant::now();
+            entry.process.writer_sender()
+        };
+        Self::send_input(&writer_tx, input).await
+    }
+
     async fn refresh_process_state(&self, process_id: &str) -> ProcessStatus {
         let mut store = self.process_store.lock().await;
         let Some(entry) = store.processes.get(process_id) else {
@@ -410,7 +481,9 @@ impl UnifiedExecProcessManager {
         started_at: Instant,
         process_id: String,
         tty: bool,
+        raw: bool,
         transcript: Arc<tokio::sync::Mutex<HeadTailBuffer>>,
+        recording: Option<SharedRecording>,
     ) {
         let entry = ProcessEntry {
             process: Arc::clone(&process),
@@ -418,6 +491,7 @@ impl UnifiedExecProcessManager {
             process_id: process_id.clone(),
             command: command.to_vec(),
             tty,
+            raw,
             last_used: started_at,
         };
         let number_processes = {
@@ -446,6 +520,7 @@ impl UnifiedExecProcessManager {
             cwd,
             process_id,
             transcript,
+            recording,
             started_at,
         );
     }
@@ -484,6 +559,7 @@ impl UnifiedExecProcessManager {
         UnifiedExecProcess::from_spawned(spawned, env.sandbox).await
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(super) async fn open_session_with_sandbox(
         &self,
         command: &[String],
@@ -491,9 +567,10 @@ impl UnifiedExecProcessManager {
         sandbox_permissions: SandboxPermissions,
         justification: Option<String>,
         tty: bool,
+        raw: bool,
         context: &UnifiedExecContext,
     ) -> Result<UnifiedExecProcess, UnifiedExecError> {
-        let env = apply_unified_exec_env(create_env(&context.turn.shell_environment_policy));
+        let env = apply_unified_exec_env(create_env(&context.turn.shell_environment_policy), raw);
         let features = context.session.features();
         let mut orchestrator = ToolOrchestrator::new();
         let mut runtime = UnifiedExecRuntime::new(self);
@@ -507,6 +584,7 @@ impl UnifiedExecProcessManager {
                 context.turn.approval_policy,
                 &context.turn.sandbox_policy,
                 sandbox_permissions,
+                &cwd,
             )
             .await;
         let req = UnifiedExecToolRequest::new(
@@ -523,6 +601,7 @@ impl UnifiedExecProcessManager {
             turn: context.turn.as_ref(),
             call_id: context.call_id.clone(),
             tool_name: "exec_command".to_string(),
+            cancellation_token: context.cancellation_token.clone(),
         };
         orchestrator
             .run(
@@ -687,7 +766,7 @@ mod tests {
 
     #[test]
     fn unified_exec_env_injects_defaults() {
-        let env = apply_unified_exec_env(HashMap::new());
+        let env = apply_unified_exec_env(HashMap::new(), false);
         let expected = HashMap::from([
             ("NO_COLOR".to_string(), "1".to_string()),
             ("TERM".to_string(), "dumb".to_string()),
@@ -710,12 +789,27 @@ mod tests {
         base.insert("NO_COLOR".to_string(), "0".to_string());
         base.insert("PATH".to_string(), "/usr/bin".to_string());
 
-        let env = apply_unified_exec_env(base);
+        let env = apply_unified_exec_env(base, false);
 
         assert_eq!(env.get("NO_COLOR"), Some(&"1".to_string()));
         assert_eq!(env.get("PATH"), Some(&"/usr/bin".to_string()));
     }
 
+    #[test]
+    fn raw_unified_exec_env_keeps_terminal_features() {
+        let base = HashMap::from([
+            ("COLORTERM".to_string(), "truecolor".to_string()),
+            ("PAGER".to_string(), "less".to_string()),
+        ]);
+
+        let env = apply_unified_exec_env(base, true);
+
+        assert_eq!(env.get("TERM"), Some(&"xterm-256color".to_string()));
+        assert_eq!(env.get("COLORTERM"), Some(&"truecolor".to_string()));
+        assert_eq!(env.get("PAGER"), Some(&"less".to_string()));
+        assert_eq!(env.get("NO_COLOR"), None);
+    }
+
     #[test]
     fn pruning_prefers_exited_processes_outside_recently_used() {
         let now = Instant::now();
@@ -0,0 +1,225 @@
+//! Recordings of raw unified exec sessions.
+//!
+//! A raw session keeps full terminal semantics so a user can watch or take
+//! over the program. Every byte it writes is recorded with its timing in the
+//! ttyrec format (a 12-byte header of seconds, microseconds and length, all
+//! little-endian `u32`, before each chunk) so the session can be replayed
+//! with `ttyplay` or any other ttyrec player. Frames are streamed to the
+//! thread's artifacts as the output arrives; once the artifacts quota is
+//! reached the last frame is shortened to fit and the rest is dropped.
+
+use std::sync::Arc;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use codex_protocol::ThreadId;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use crate::artifacts::ArtifactQuota;
+use crate::artifacts::ArtifactWriter;
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::protocol::EventMsg;
+use crate::protocol::ToolArtifactEvent;
+
+const RECORDING_MIME_TYPE: &str = "application/x-ttyrec";
+
+const FRAME_HEADER_BYTES: usize = 12;
+
+pub(crate) type SharedRecording = Arc<Mutex<TerminalRecording>>;
+
+pub(crate) struct TerminalRecording {
+    /// Taken when the recording is saved; chunks that arrive later are
+    /// dropped.
+    writer: Option<ArtifactWriter>,
+    /// Set once a chunk did not fit in the quota; later chunks are dropped
+    /// so the recording stays a valid prefix of the session.
+    truncated: bool,
+}
+
+impl TerminalRecording {
+    /// Starts the recording of process `process_id`. Incognito sessions keep
+    /// nothing, and a recording that cannot be created is skipped.
+    pub(crate) async fn start(
+        turn: &TurnContext,
+        thread_id: &ThreadId,
+        call_id: &str,
+        process_id: &str,
+    ) -> Option<SharedRecording> {
+        let config = turn.client.config();
+        if config.incognito {
+            return None;
+        }
+        let writer = ArtifactWriter::create(
+            &config.codex_home,
+            thread_id,
+            &ArtifactQuota::from(&config.artifacts),
+            &format!("{call_id}-{process_id}"),
+            &format!("exec-{process_id}.ttyrec"),
+            RECORDING_MIME_TYPE,
+        )
+        .await;
+        match writer {
+            Ok(writer) => Some(Arc::new(Mutex::new(Self {
+                writer: Some(writer),
+                truncated: false,
+            }))),
+            Err(err) => {
+                warn!("failed to start recording of unified exec process {process_id}: {err}");
+                None
+            }
+        }
+    }
+
+    pub(crate) async fn push(&mut self, chunk: &[u8]) {
+        self.push_at(SystemTime::now(), chunk).await;
+    }
+
+    async fn push_at(&mut self, at: SystemTime, chunk: &[u8]) {
+        let Some(writer) = self.writer.as_mut() else {
+            return;
+        };
+        if self.truncated || chunk.is_empty() {
+            return;
+        }
+        let room = usize::try_from(writer.remaining())
+            .unwrap_or(usize::MAX)
+            .saturating_sub(FRAME_HEADER_BYTES)
+            .min(u32::MAX as usize);
+        let data = if chunk.len() > room {
+            self.truncated = true;
+            &chunk[..room]
+        } else {
+            chunk
+        };
+        if data.is_empty() {
+            return;
+        }
+        let since_epoch = at.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let secs = u32::try_from(since_epoch.as_secs()).unwrap_or(u32::MAX);
+        let mut frame = Vec::with_capacity(FRAME_HEADER_BYTES + data.len());
+        frame.extend_from_slice(&secs.to_le_bytes());
+        frame.extend_from_slice(&since_epoch.subsec_micros().to_le_bytes());
+        frame.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        frame.extend_from_slice(data);
+        if let Err(err) = writer.write(&frame).await {
+            warn!("failed to write terminal recording: {err}");
+            self.truncated = true;
+        }
+    }
+}
+
+/// Closes the recording of process `process_id` and reports it with a
+/// `ToolArtifact` event.
+pub(crate) async fn save_recording(
+    session: &Session,
+    turn: &TurnContext,
+    call_id: &str,
+    process_id: &str,
+    recording: &SharedRecording,
+) {
+    let (writer, truncated) = {
+        let mut recording = recording.lock().await;
+        (recording.writer.take(), recording.truncated)
+    };
+    let Some(writer) = writer else {
+        return;
+    };
+    if truncated {
+        warn!("recording of unified exec process {process_id} was cut at the artifacts quota");
+    }
+    match writer.finish().await {
+        Ok(Some(artifact)) => {
+            let event = EventMsg::ToolArtifact(ToolArtifactEvent {
+                call_id: call_id.to_string(),
+                artifact,
+            });
+            session.send_event(turn, event).await;
+        }
+        Ok(None) => {}
+        Err(err) => warn!("failed to save recording of unified exec process {process_id}: {err}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    async fn recording(codex_home: &std::path::Path, max_bytes: u64) -> TerminalRecording {
+        let quota = ArtifactQuota {
+            max_bytes_per_thread: max_bytes,
+            max_total_bytes: max_bytes,
+        };
+        let writer = ArtifactWriter::create(
+            codex_home,
+            &ThreadId::new(),
+            &quota,
+            "call-1000",
+            "exec-1000.ttyrec",
+            RECORDING_MIME_TYPE,
+        )
+        .await
+        .expect("create recording");
+        TerminalRecording {
+            writer: Some(writer),
+            truncated: false,
+        }
+    }
+
+    fn frame(at: SystemTime, data: &[u8]) -> Vec<u8> {
+        let since_epoch = at.duration_since(UNIX_EPOCH).expect("after epoch");
+        let mut frame = Vec::new();
+        frame.extend_from_slice(&(since_epoch.as_secs() as u32).to_le_bytes());
+        frame.extend_from_slice(&since_epoch.subsec_micros().to_le_bytes());
+        frame.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        frame.extend_from_slice(data);
+        frame
+    }
+
+    async fn finish(recording: TerminalRecording) -> Vec<u8> {
+        let artifact = recording
+            .writer
+            .expect("writer")
+            .finish()
+            .await
+            .expect("finish recording")
+            .expect("artifact");
+        let bytes = tokio::fs::read(&artifact.path)
+            .await
+            .expect("read recording");
+        assert_eq!(artifact.size_bytes, bytes.len() as u64);
+        bytes
+    }
+
+    #[tokio::test]
+    async fn streams_chunks_in_ttyrec_format() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let mut recording = recording(codex_home.path(), 1024).await;
+        let at = UNIX_EPOCH + Duration::from_micros(1_700_000_000_250_000);
+        recording.push_at(at, b"\x1b[31mred\x1b[0m").await;
+
+        let mut expected = Vec::new();
+        expected.extend_from_slice(&1_700_000_000u32.to_le_bytes());
+        expected.extend_from_slice(&250_000u32.to_le_bytes());
+        expected.extend_from_slice(&12u32.to_le_bytes());
+        expected.extend_from_slice(b"\x1b[31mred\x1b[0m");
+        assert_eq!(finish(recording).await, expected);
+    }
+
+    #[tokio::test]
+    async fn truncates_the_recording_at_the_quota() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let max_bytes = (2 * FRAME_HEADER_BYTES + 4) as u64;
+        let mut recording = recording(codex_home.path(), max_bytes).await;
+        recording.push_at(UNIX_EPOCH, b"ab").await;
+        recording.push_at(UNIX_EPOCH, b"cdef").await;
+        recording.push_at(UNIX_EPOCH, b"g").await;
+
+        assert!(recording.truncated);
+        let expected = [frame(UNIX_EPOCH, b"ab"), frame(UNIX_EPOCH, b"cd")].concat();
+        assert_eq!(finish(recording).await, expected);
+    }
+}
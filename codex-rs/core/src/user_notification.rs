@@ -1,19 +1,86 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use base64::Engine;
 use serde::Serialize;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncWrite;
+use tokio::io::AsyncWriteExt;
+use tokio::io::BufReader;
+use tokio::net::TcpStream;
+use tokio_rustls::TlsConnector;
+use tokio_rustls::rustls;
 use tracing::error;
 use tracing::warn;
 
+use crate::config::types::EmailNotificationChannel;
+use crate::config::types::NotificationChannels;
+use crate::config::types::NotificationEventKind;
+use crate::config::types::SmtpTls;
+use crate::config::types::WebhookNotificationChannel;
+use crate::default_client::build_reqwest_client;
+
+const DEFAULT_WEBHOOK_MAX_RETRIES: u32 = 3;
+const WEBHOOK_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+const SMTP_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_MILESTONE_INTERVAL_MINUTES: u64 = 10;
+
 #[derive(Debug, Default)]
 pub(crate) struct UserNotifier {
     notify_command: Option<Vec<String>>,
+    channels: NotificationChannels,
 }
 
 impl UserNotifier {
     pub(crate) fn notify(&self, notification: &UserNotification) {
-        if let Some(notify_command) = &self.notify_command
+        // The external program predates the other channels and has only ever
+        // received turn completions, so keep its contract unchanged.
+        if notification.kind() == NotificationEventKind::TurnComplete
+            && let Some(notify_command) = &self.notify_command
             && !notify_command.is_empty()
         {
             self.invoke_notify(notify_command, notification)
         }
+
+        if let Some(webhook) = &self.channels.webhook
+            && wants_event(webhook.events.as_deref(), notification.kind())
+        {
+            spawn_delivery(send_webhook(webhook.clone(), notification.clone()));
+        }
+
+        if let Some(email) = &self.channels.email
+            && wants_event(email.events.as_deref(), notification.kind())
+        {
+            spawn_delivery(send_email(email.clone(), notification.clone()));
+        }
+    }
+
+    /// Interval between `long-run-milestone` notifications, or `None` when no
+    /// channel would receive them.
+    pub(crate) fn milestone_interval(&self) -> Option<Duration> {
+        let webhook_wants = self.channels.webhook.as_ref().is_some_and(|webhook| {
+            wants_event(
+                webhook.events.as_deref(),
+                NotificationEventKind::LongRunMilestone,
+            )
+        });
+        let email_wants = self.channels.email.as_ref().is_some_and(|email| {
+            wants_event(
+                email.events.as_deref(),
+                NotificationEventKind::LongRunMilestone,
+            )
+        });
+        if !webhook_wants && !email_wants {
+            return None;
+        }
+        let minutes = self
+            .channels
+            .milestone_interval_minutes
+            .unwrap_or(DEFAULT_MILESTONE_INTERVAL_MINUTES)
+            .max(1);
+        Some(Duration::from_secs(minutes * 60))
     }
 
     fn invoke_notify(&self, notify_command: &[String], notification: &UserNotification) {
@@ -34,16 +101,288 @@ impl UserNotifier {
         }
     }
 
-    pub(crate) fn new(notify: Option<Vec<String>>) -> Self {
+    pub(crate) fn new(notify: Option<Vec<String>>, channels: NotificationChannels) -> Self {
         Self {
             notify_command: notify,
+            channels,
+        }
+    }
+}
+
+fn wants_event(events: Option<&[NotificationEventKind]>, kind: NotificationEventKind) -> bool {
+    events.is_none_or(|events| events.contains(&kind))
+}
+
+/// Deliveries are fire-and-forget so a slow endpoint never stalls the turn.
+fn spawn_delivery(delivery: impl Future<Output = ()> + Send + 'static) {
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) => {
+            handle.spawn(delivery);
+        }
+        Err(_) => warn!("no async runtime available to deliver notification"),
+    }
+}
+
+async fn send_webhook(channel: WebhookNotificationChannel, notification: UserNotification) {
+    let client = build_reqwest_client();
+    let max_retries = channel.max_retries.unwrap_or(DEFAULT_WEBHOOK_MAX_RETRIES);
+    let mut backoff = WEBHOOK_INITIAL_BACKOFF;
+    for attempt in 0..=max_retries {
+        let mut request = client
+            .post(&channel.url)
+            .timeout(WEBHOOK_TIMEOUT)
+            .json(&notification);
+        for (name, value) in channel.headers.iter().flatten() {
+            request = request.header(name, value);
+        }
+
+        let retryable = match request.send().await {
+            Ok(response) if response.status().is_success() => return,
+            Ok(response) => {
+                let status = response.status();
+                warn!("notification webhook returned {status} (attempt {attempt})");
+                status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+            }
+            Err(e) => {
+                warn!("failed to deliver notification webhook (attempt {attempt}): {e}");
+                true
+            }
+        };
+        if !retryable || attempt == max_retries {
+            break;
+        }
+        tokio::time::sleep(backoff).await;
+        backoff *= 2;
+    }
+    error!("giving up on notification webhook {}", channel.url);
+}
+
+async fn send_email(channel: EmailNotificationChannel, notification: UserNotification) {
+    let port = channel.smtp_port.unwrap_or(match channel.smtp_tls {
+        SmtpTls::StartTls => 587,
+        SmtpTls::Tls => 465,
+        SmtpTls::None => 25,
+    });
+    let result = tokio::time::timeout(SMTP_TIMEOUT, deliver_email(&channel, port, &notification))
+        .await
+        .unwrap_or_else(|_| {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                "SMTP exchange timed out",
+            ))
+        });
+    if let Err(e) = result {
+        warn!(
+            "failed to deliver notification email via {}:{port}: {e}",
+            channel.smtp_host
+        );
+    }
+}
+
+struct SmtpCredentials {
+    username: String,
+    password: String,
+}
+
+fn smtp_credentials(
+    channel: &EmailNotificationChannel,
+) -> std::io::Result<Option<SmtpCredentials>> {
+    let Some(username) = &channel.smtp_username else {
+        return Ok(None);
+    };
+    if channel.smtp_tls == SmtpTls::None {
+        return Err(std::io::Error::other(
+            "refusing to send SMTP credentials without TLS",
+        ));
+    }
+    let Some(password_env) = &channel.smtp_password_env else {
+        return Err(std::io::Error::other(
+            "smtp_username requires smtp_password_env",
+        ));
+    };
+    let password = std::env::var(password_env)
+        .map_err(|_| std::io::Error::other(format!("{password_env} is not set")))?;
+    Ok(Some(SmtpCredentials {
+        username: username.clone(),
+        password,
+    }))
+}
+
+async fn deliver_email(
+    channel: &EmailNotificationChannel,
+    port: u16,
+    notification: &UserNotification,
+) -> std::io::Result<()> {
+    let credentials = smtp_credentials(channel)?;
+    let stream = TcpStream::connect((channel.smtp_host.as_str(), port)).await?;
+    match channel.smtp_tls {
+        SmtpTls::None => smtp_send(stream, channel, None, notification).await,
+        SmtpTls::Tls => {
+            let stream = tls_connect(&channel.smtp_host, stream).await?;
+            smtp_send(stream, channel, credentials.as_ref(), notification).await
+        }
+        SmtpTls::StartTls => {
+            let mut plain = BufReader::new(stream);
+            smtp_expect(&mut plain, 220).await?;
+            smtp_command(&mut plain, "EHLO codex", 250).await?;
+            smtp_command(&mut plain, "STARTTLS", 220).await?;
+            let stream = tls_connect(&channel.smtp_host, plain.into_inner()).await?;
+            let mut stream = BufReader::new(stream);
+            smtp_command(&mut stream, "EHLO codex", 250).await?;
+            smtp_transaction(&mut stream, channel, credentials.as_ref(), notification).await
+        }
+    }
+}
+
+async fn tls_connect(
+    host: &str,
+    stream: TcpStream,
+) -> std::io::Result<tokio_rustls::client::TlsStream<TcpStream>> {
+    let mut roots = rustls::RootCertStore::empty();
+    for cert in rustls_native_certs::load_native_certs().certs {
+        if let Err(e) = roots.add(cert) {
+            warn!("ignoring invalid native root certificate: {e}");
+        }
+    }
+    let config = rustls::ClientConfig::builder()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    let server_name = rustls::pki_types::ServerName::try_from(host.to_string())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    TlsConnector::from(Arc::new(config))
+        .connect(server_name, stream)
+        .await
+}
+
+/// Minimal SMTP client: greets the server on an already secured (or
+/// deliberately plain) `stream` and hands it a single plain-text message.
+async fn smtp_send<S>(
+    stream: S,
+    channel: &EmailNotificationChannel,
+    credentials: Option<&SmtpCredentials>,
+    notification: &UserNotification,
+) -> std::io::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut stream = BufReader::new(stream);
+    smtp_expect(&mut stream, 220).await?;
+    smtp_command(&mut stream, "EHLO codex", 250).await?;
+    smtp_transaction(&mut stream, channel, credentials, notification).await
+}
+
+/// Authenticates if asked to, then sends the message and quits.
+async fn smtp_transaction<S>(
+    stream: &mut BufReader<S>,
+    channel: &EmailNotificationChannel,
+    credentials: Option<&SmtpCredentials>,
+    notification: &UserNotification,
+) -> std::io::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    if let Some(SmtpCredentials { username, password }) = credentials {
+        let token =
+            base64::engine::general_purpose::STANDARD.encode(format!("\0{username}\0{password}"));
+        smtp_command(stream, &format!("AUTH PLAIN {token}"), 235).await?;
+    }
+    smtp_command(
+        stream,
+        &format!("MAIL FROM:<{}>", smtp_address(&channel.from)?),
+        250,
+    )
+    .await?;
+    for recipient in &channel.to {
+        smtp_command(
+            stream,
+            &format!("RCPT TO:<{}>", smtp_address(recipient)?),
+            250,
+        )
+        .await?;
+    }
+    smtp_command(stream, "DATA", 354).await?;
+
+    let mut message = format!(
+        "From: {}\r\nTo: {}\r\nSubject: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n",
+        header_value(&channel.from),
+        header_value(&channel.to.join(", ")),
+        header_value(&notification.subject()),
+    );
+    for line in notification.body().lines() {
+        // Dot-stuffing (RFC 5321 §4.5.2) so a lone "." does not end the message.
+        if line.starts_with('.') {
+            message.push('.');
+        }
+        message.push_str(line);
+        message.push_str("\r\n");
+    }
+    message.push_str(".\r\n");
+    stream.get_mut().write_all(message.as_bytes()).await?;
+    smtp_expect(stream, 250).await?;
+
+    smtp_command(stream, "QUIT", 221).await
+}
+
+/// Rejects addresses that would break out of the `<...>` of an SMTP command.
+fn smtp_address(address: &str) -> std::io::Result<&str> {
+    if address.contains(['\r', '\n', '<', '>']) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("invalid email address {address:?}"),
+        ));
+    }
+    Ok(address)
+}
+
+/// Header values must stay on one line, or they could inject more headers.
+fn header_value(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
+
+async fn smtp_command<S>(
+    stream: &mut BufReader<S>,
+    command: &str,
+    expected: u16,
+) -> std::io::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    stream
+        .get_mut()
+        .write_all(format!("{command}\r\n").as_bytes())
+        .await?;
+    smtp_expect(stream, expected).await
+}
+
+async fn smtp_expect<S>(stream: &mut BufReader<S>, expected: u16) -> std::io::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    loop {
+        let mut line = String::new();
+        if stream.read_line(&mut line).await? == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "SMTP server closed the connection",
+            ));
+        }
+        let code = line.get(..3).and_then(|code| code.parse::<u16>().ok());
+        if code != Some(expected) {
+            return Err(std::io::Error::other(format!(
+                "unexpected SMTP reply (wanted {expected}): {}",
+                line.trim_end()
+            )));
+        }
+        // Multi-line replies use "250-" on every line but the last.
+        if line.as_bytes().get(3) != Some(&b'-') {
+            return Ok(());
         }
     }
 }
 
 /// User can configure a program that will receive notifications. Each
 /// notification is serialized as JSON and passed as an argument to the
-/// program.
+/// program. The same JSON is the body of webhook deliveries.
 #[derive(Debug, Clone, PartialEq, Serialize)]
 #[serde(tag = "type", rename_all = "kebab-case")]
 pub(crate) enum UserNotification {
@@ -59,12 +398,115 @@ pub(crate) enum UserNotification {
         /// The last message sent by the assistant in the turn.
         last_assistant_message: Option<String>,
     },
+
+    #[serde(rename_all = "kebab-case")]
+    ApprovalRequested {
+        thread_id: String,
+        turn_id: String,
+        cwd: String,
+        call_id: String,
+
+        /// Short description of what the agent wants to do.
+        summary: String,
+    },
+
+    #[serde(rename_all = "kebab-case")]
+    Error {
+        thread_id: String,
+        turn_id: String,
+        cwd: String,
+        message: String,
+    },
+
+    #[serde(rename_all = "kebab-case")]
+    LongRunMilestone {
+        thread_id: String,
+        turn_id: String,
+        cwd: String,
+        elapsed_minutes: u64,
+    },
+
+    #[serde(rename_all = "kebab-case")]
+    UsageLimitWait {
+        thread_id: String,
+        turn_id: String,
+        cwd: String,
+
+        /// RFC 3339 timestamp at which the turn resumes.
+        resumes_at: String,
+    },
+}
+
+impl UserNotification {
+    pub(crate) fn kind(&self) -> NotificationEventKind {
+        match self {
+            UserNotification::AgentTurnComplete { .. } => NotificationEventKind::TurnComplete,
+            UserNotification::ApprovalRequested { .. } => NotificationEventKind::ApprovalRequested,
+            UserNotification::Error { .. } => NotificationEventKind::Error,
+            UserNotification::LongRunMilestone { .. } => NotificationEventKind::LongRunMilestone,
+            UserNotification::UsageLimitWait { .. } => NotificationEventKind::UsageLimitWait,
+        }
+    }
+
+    fn subject(&self) -> String {
+        match self {
+            UserNotification::AgentTurnComplete { cwd, .. } => {
+                format!("Codex finished a turn in {cwd}")
+            }
+            UserNotification::ApprovalRequested { cwd, .. } => {
+                format!("Codex needs approval in {cwd}")
+            }
+            UserNotification::Error { cwd, .. } => format!("Codex hit an error in {cwd}"),
+            UserNotification::LongRunMilestone {
+                cwd,
+                elapsed_minutes,
+                ..
+            } => format!("Codex has been working for {elapsed_minutes} minutes in {cwd}"),
+            UserNotification::UsageLimitWait { cwd, .. } => {
+                format!("Codex is waiting for the usage limit to reset in {cwd}")
+            }
+        }
+    }
+
+    fn body(&self) -> String {
+        match self {
+            UserNotification::AgentTurnComplete {
+                input_messages,
+                last_assistant_message,
+                ..
+            } => {
+                let mut body = String::new();
+                for message in input_messages {
+                    body.push_str(&format!("> {message}\n"));
+                }
+                if let Some(last_assistant_message) = last_assistant_message {
+                    body.push('\n');
+                    body.push_str(last_assistant_message);
+                }
+                body
+            }
+            UserNotification::ApprovalRequested { summary, .. } => summary.clone(),
+            UserNotification::Error { message, .. } => message.clone(),
+            UserNotification::LongRunMilestone { turn_id, .. } => {
+                format!("Turn {turn_id} is still running.")
+            }
+            UserNotification::UsageLimitWait {
+                turn_id,
+                resumes_at,
+                ..
+            } => format!("Turn {turn_id} hit the usage limit and will resume at {resumes_at}."),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use anyhow::Result;
+    use core_test_support::skip_if_no_network;
+    use pretty_assertions::assert_eq;
+    use tokio::io::AsyncReadExt;
+    use tokio::io::duplex;
 
     #[test]
     fn test_user_notification() -> Result<()> {
@@ -84,4 +526,193 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn milestone_interval_requires_a_subscribed_channel() {
+        let webhook = WebhookNotificationChannel {
+            url: "http://localhost/hook".to_string(),
+            headers: None,
+            events: Some(vec![NotificationEventKind::TurnComplete]),
+            max_retries: None,
+        };
+        let notifier = UserNotifier::new(
+            None,
+            NotificationChannels {
+                webhook: Some(webhook.clone()),
+                email: None,
+                milestone_interval_minutes: Some(5),
+            },
+        );
+        assert_eq!(notifier.milestone_interval(), None);
+
+        let notifier = UserNotifier::new(
+            None,
+            NotificationChannels {
+                webhook: Some(WebhookNotificationChannel {
+                    events: None,
+                    ..webhook
+                }),
+                email: None,
+                milestone_interval_minutes: Some(5),
+            },
+        );
+        assert_eq!(
+            notifier.milestone_interval(),
+            Some(Duration::from_secs(5 * 60))
+        );
+    }
+
+    #[tokio::test]
+    async fn webhook_retries_until_success() {
+        skip_if_no_network!();
+
+        use wiremock::Mock;
+        use wiremock::MockServer;
+        use wiremock::ResponseTemplate;
+        use wiremock::matchers::header;
+        use wiremock::matchers::method;
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(header("authorization", "Bearer secret"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let notification = UserNotification::Error {
+            thread_id: "thread".to_string(),
+            turn_id: "turn".to_string(),
+            cwd: "/repo".to_string(),
+            message: "stream disconnected".to_string(),
+        };
+        send_webhook(
+            WebhookNotificationChannel {
+                url: server.uri(),
+                headers: Some(
+                    [("authorization".to_string(), "Bearer secret".to_string())]
+                        .into_iter()
+                        .collect(),
+                ),
+                events: None,
+                max_retries: Some(2),
+            },
+            notification.clone(),
+        )
+        .await;
+
+        let requests = server
+            .received_requests()
+            .await
+            .expect("failed to fetch received requests");
+        assert_eq!(requests.len(), 2);
+        let body: serde_json::Value =
+            serde_json::from_slice(&requests[1].body).expect("webhook body should be JSON");
+        assert_eq!(
+            body,
+            serde_json::to_value(&notification).expect("serialize notification")
+        );
+    }
+
+    #[tokio::test]
+    async fn smtp_send_follows_protocol() -> Result<()> {
+        let (client, mut server) = duplex(4096);
+        let channel = EmailNotificationChannel {
+            smtp_host: "localhost".to_string(),
+            smtp_port: None,
+            smtp_tls: SmtpTls::Tls,
+            smtp_username: Some("codex".to_string()),
+            smtp_password_env: None,
+            from: "codex@example.com".to_string(),
+            to: vec!["dev@example.com".to_string()],
+            events: None,
+        };
+        let notification = UserNotification::ApprovalRequested {
+            thread_id: "thread".to_string(),
+            turn_id: "turn".to_string(),
+            cwd: "/repo\r\nBcc: attacker@example.com".to_string(),
+            call_id: "call".to_string(),
+            summary: ".hidden\nRun `rm -rf target`".to_string(),
+        };
+
+        let server_task = tokio::spawn(async move {
+            let replies: [&[u8]; 8] = [
+                b"220 relay ready\r\n",
+                b"250-relay\r\n250 OK\r\n",
+                b"235 authenticated\r\n",
+                b"250 OK\r\n",
+                b"250 OK\r\n",
+                b"354 go ahead\r\n",
+                b"250 queued\r\n",
+                b"221 bye\r\n",
+            ];
+            let mut transcript = Vec::new();
+            server.write_all(replies[0]).await?;
+            for reply in &replies[1..] {
+                let mut buf = [0u8; 1024];
+                loop {
+                    let n = server.read(&mut buf).await?;
+                    transcript.extend_from_slice(&buf[..n]);
+                    if transcript.ends_with(b"\r\n") {
+                        break;
+                    }
+                }
+                server.write_all(reply).await?;
+            }
+            Ok::<_, std::io::Error>(String::from_utf8_lossy(&transcript).into_owned())
+        });
+
+        let credentials = SmtpCredentials {
+            username: "codex".to_string(),
+            password: "secret".to_string(),
+        };
+        smtp_send(client, &channel, Some(&credentials), &notification).await?;
+        let transcript = server_task.await??;
+        assert_eq!(
+            transcript,
+            "EHLO codex\r\n\
+             AUTH PLAIN AGNvZGV4AHNlY3JldA==\r\n\
+             MAIL FROM:<codex@example.com>\r\n\
+             RCPT TO:<dev@example.com>\r\n\
+             DATA\r\n\
+             From: codex@example.com\r\n\
+             To: dev@example.com\r\n\
+             Subject: Codex needs approval in /repo  Bcc: attacker@example.com\r\n\
+             Content-Type: text/plain; charset=utf-8\r\n\
+             \r\n\
+             ..hidden\r\n\
+             Run `rm -rf target`\r\n\
+             .\r\n\
+             QUIT\r\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn smtp_refuses_credentials_without_tls_and_unsafe_addresses() {
+        let channel = EmailNotificationChannel {
+            smtp_host: "localhost".to_string(),
+            smtp_port: None,
+            smtp_tls: SmtpTls::None,
+            smtp_username: Some("codex".to_string()),
+            smtp_password_env: Some("CODEX_TEST_SMTP_PASSWORD".to_string()),
+            from: "codex@example.com".to_string(),
+            to: vec!["dev@example.com".to_string()],
+            events: None,
+        };
+        assert_eq!(
+            smtp_credentials(&channel)
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            Err("refusing to send SMTP credentials without TLS".to_string())
+        );
+        assert_eq!(
+            smtp_address("dev@example.com>\r\nRCPT TO:<attacker@example.com").map_err(|e| e.kind()),
+            Err(std::io::ErrorKind::InvalidInput)
+        );
+    }
 }
@@ -0,0 +1,505 @@
+//! Attribute workspace changes to the sessions that made them.
+//!
+//! When several sessions work in the same checkout, their edits end up mixed
+//! in one working tree. [`diff_sessions`] reads the rollouts of two sessions
+//! and splits the combined delta by session: the turn diffs each one
+//! recorded, grouped per file, and the files both of them changed. The ghost
+//! commits recorded at the start of every turn bound the window the sessions
+//! were active in; files that changed inside that window without either
+//! session recording a diff for them are reported as unattributed.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_git::GhostCommit;
+use codex_git::changed_paths_between_ghost_commits;
+use codex_protocol::ThreadId;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use tracing::warn;
+
+use crate::git_info::get_git_repo_root;
+
+/// What one file went through in one session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDelta {
+    /// 1-based turns that changed the file, oldest first.
+    pub turns: Vec<u32>,
+    /// The file's sections of those turns' diffs, in turn order.
+    pub diff: String,
+}
+
+/// The part of the workspace delta one session is responsible for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionDelta {
+    pub thread_id: ThreadId,
+    /// Ghost commits recorded at the start of the session's turns, oldest
+    /// first.
+    pub checkpoints: Vec<String>,
+    /// Changed files, relative to the repository root.
+    pub files: BTreeMap<String, FileDelta>,
+}
+
+/// The combined delta of two sessions over the same repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceDiff {
+    pub repo: PathBuf,
+    pub sessions: Vec<SessionDelta>,
+    /// Files changed by both sessions.
+    pub shared: Vec<String>,
+    /// Files that changed between the earliest and the latest checkpoint of
+    /// the two sessions without either of them recording a diff for them.
+    pub unattributed: Vec<String>,
+}
+
+impl WorkspaceDiff {
+    /// Plain-text report: a summary of who changed what, followed by each
+    /// session's diffs.
+    pub fn render(&self) -> String {
+        let mut out = format!("Workspace changes in {}\n", self.repo.display());
+        for session in &self.sessions {
+            let _ = write!(
+                out,
+                "\nSession {} changed {} file(s)",
+                session.thread_id,
+                session.files.len()
+            );
+            if let (Some(first), Some(last)) =
+                (session.checkpoints.first(), session.checkpoints.last())
+            {
+                let _ = write!(
+                    out,
+                    " (checkpoints {}..{})",
+                    short_id(first),
+                    short_id(last)
+                );
+            }
+            out.push_str(":\n");
+            for (path, delta) in &session.files {
+                let turns = delta
+                    .turns
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let _ = writeln!(out, "  {path} (turns {turns})");
+            }
+        }
+        if !self.shared.is_empty() {
+            out.push_str("\nChanged by both sessions:\n");
+            for path in &self.shared {
+                let _ = writeln!(out, "  {path}");
+            }
+        }
+        if !self.unattributed.is_empty() {
+            out.push_str("\nChanged outside these sessions:\n");
+            for path in &self.unattributed {
+                let _ = writeln!(out, "  {path}");
+            }
+        }
+        for session in &self.sessions {
+            for (path, delta) in &session.files {
+                let _ = write!(out, "\n# {} {path}\n{}", session.thread_id, delta.diff);
+                if !delta.diff.ends_with('\n') {
+                    out.push('\n');
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Splits the workspace delta of the sessions recorded in `first` and
+/// `second` by session. Fails with [`io::ErrorKind::InvalidInput`] when the
+/// sessions ran in different repositories.
+pub async fn diff_sessions(first: &Path, second: &Path) -> io::Result<WorkspaceDiff> {
+    let first = read_session(first).await?;
+    let second = read_session(second).await?;
+    let repo = repo_root(&first.cwd);
+    let other_repo = repo_root(&second.cwd);
+    if repo != other_repo {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "the sessions ran in different repositories ({} and {})",
+                repo.display(),
+                other_repo.display()
+            ),
+        ));
+    }
+
+    let mut checkpoints: Vec<(String, GhostCommit)> = first
+        .turns
+        .iter()
+        .chain(&second.turns)
+        .filter_map(|turn| Some((turn.timestamp.clone(), turn.checkpoint.clone()?)))
+        .collect();
+    checkpoints.sort_by(|a, b| a.0.cmp(&b.0));
+    let sessions = [first, second].map(session_delta);
+
+    let window = match (checkpoints.first(), checkpoints.last()) {
+        (Some((_, earliest)), Some((_, latest))) if earliest.id() != latest.id() => {
+            Some((earliest.clone(), latest.clone()))
+        }
+        _ => None,
+    };
+    let changed_in_window = match window {
+        Some((earliest, latest)) => {
+            let repo = repo.clone();
+            let changed = tokio::task::spawn_blocking(move || {
+                changed_paths_between_ghost_commits(&repo, &earliest, &latest)
+            })
+            .await
+            .map_err(io::Error::other)?;
+            match changed {
+                Ok(paths) => paths,
+                Err(err) => {
+                    warn!("failed to diff checkpoints in {}: {err}", repo.display());
+                    Vec::new()
+                }
+            }
+        }
+        None => Vec::new(),
+    };
+
+    Ok(attribute(repo, sessions.into(), changed_in_window))
+}
+
+fn attribute(
+    repo: PathBuf,
+    sessions: Vec<SessionDelta>,
+    changed_in_window: Vec<PathBuf>,
+) -> WorkspaceDiff {
+    let mut seen: BTreeMap<&str, usize> = BTreeMap::new();
+    for session in &sessions {
+        for path in session.files.keys() {
+            *seen.entry(path.as_str()).or_default() += 1;
+        }
+    }
+    let shared = seen
+        .iter()
+        .filter(|(_, sessions)| **sessions > 1)
+        .map(|(path, _)| path.to_string())
+        .collect();
+    let unattributed = changed_in_window
+        .into_iter()
+        .map(|path| path.to_string_lossy().into_owned())
+        .filter(|path| !seen.contains_key(path.as_str()))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    WorkspaceDiff {
+        repo,
+        sessions,
+        shared,
+        unattributed,
+    }
+}
+
+fn repo_root(cwd: &Path) -> PathBuf {
+    get_git_repo_root(cwd).unwrap_or_else(|| cwd.to_path_buf())
+}
+
+fn short_id(id: &str) -> &str {
+    id.get(..7).unwrap_or(id)
+}
+
+#[derive(Debug)]
+struct ScannedTurn {
+    /// When the turn started, as written in the rollout.
+    timestamp: String,
+    checkpoint: Option<GhostCommit>,
+    /// The turn's last (cumulative) diff.
+    diff: Option<String>,
+}
+
+#[derive(Debug)]
+struct ScannedSession {
+    thread_id: ThreadId,
+    cwd: PathBuf,
+    turns: Vec<ScannedTurn>,
+}
+
+async fn read_session(rollout_path: &Path) -> io::Result<ScannedSession> {
+    let contents = tokio::fs::read_to_string(rollout_path).await?;
+    scan_rollout(&contents).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("rollout {} has no session metadata", rollout_path.display()),
+        )
+    })
+}
+
+fn scan_rollout(contents: &str) -> Option<ScannedSession> {
+    let mut meta = None;
+    let mut turns: Vec<ScannedTurn> = Vec::new();
+    // Rollouts written before turn starts were persisted only mark a turn
+    // with its user message.
+    let mut saw_turn_started = false;
+    for line in contents.lines() {
+        let Ok(RolloutLine { timestamp, item }) = serde_json::from_str::<RolloutLine>(line) else {
+            continue;
+        };
+        let new_turn = |timestamp: String| ScannedTurn {
+            timestamp,
+            checkpoint: None,
+            diff: None,
+        };
+        match item {
+            RolloutItem::SessionMeta(line) if meta.is_none() => {
+                meta = Some((line.meta.id, line.meta.cwd));
+            }
+            RolloutItem::EventMsg(EventMsg::TurnStarted(_)) => {
+                saw_turn_started = true;
+                turns.push(new_turn(timestamp));
+            }
+            RolloutItem::EventMsg(EventMsg::UserMessage(_)) if !saw_turn_started => {
+                turns.push(new_turn(timestamp));
+            }
+            RolloutItem::ResponseItem(ResponseItem::GhostSnapshot { ghost_commit }) => {
+                if turns.is_empty() {
+                    turns.push(new_turn(timestamp));
+                }
+                if let Some(turn) = turns.last_mut() {
+                    turn.checkpoint.get_or_insert(ghost_commit);
+                }
+            }
+            RolloutItem::EventMsg(EventMsg::TurnDiff(event)) => {
+                if turns.is_empty() {
+                    turns.push(new_turn(timestamp));
+                }
+                if let Some(turn) = turns.last_mut() {
+                    turn.diff = Some(event.unified_diff);
+                }
+            }
+            RolloutItem::EventMsg(EventMsg::ThreadRolledBack(event)) => {
+                let kept = turns
+                    .len()
+                    .saturating_sub(usize::try_from(event.num_turns).unwrap_or(usize::MAX));
+                turns.truncate(kept);
+            }
+            _ => {}
+        }
+    }
+    let (thread_id, cwd) = meta?;
+    Some(ScannedSession {
+        thread_id,
+        cwd,
+        turns,
+    })
+}
+
+fn session_delta(session: ScannedSession) -> SessionDelta {
+    let mut checkpoints = Vec::new();
+    let mut files: BTreeMap<String, FileDelta> = BTreeMap::new();
+    for (turn, number) in session.turns.into_iter().zip(1u32..) {
+        if let Some(checkpoint) = turn.checkpoint {
+            checkpoints.push(checkpoint.id().to_string());
+        }
+        for (path, section) in split_diff_by_file(turn.diff.as_deref().unwrap_or_default()) {
+            let delta = files.entry(path).or_insert_with(|| FileDelta {
+                turns: Vec::new(),
+                diff: String::new(),
+            });
+            delta.turns.push(number);
+            delta.diff.push_str(&section);
+        }
+    }
+    SessionDelta {
+        thread_id: session.thread_id,
+        checkpoints,
+        files,
+    }
+}
+
+/// Splits a unified diff at its `diff --git a/<old> b/<new>` headers, keyed
+/// by the new path.
+fn split_diff_by_file(diff: &str) -> Vec<(String, String)> {
+    let mut sections: Vec<(String, String)> = Vec::new();
+    for line in diff.split_inclusive('\n') {
+        if let Some(header) = line.strip_prefix("diff --git a/")
+            && let Some((_, new)) = header.trim_end().split_once(" b/")
+        {
+            sections.push((new.to_string(), String::new()));
+        }
+        if let Some((_, section)) = sections.last_mut() {
+            section.push_str(line);
+        }
+    }
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn file_diff(file: &str) -> String {
+        format!("diff --git a/{file} b/{file}\n--- a/{file}\n+++ b/{file}\n")
+    }
+
+    fn rollout(thread_id: &str, turns: &[&[&str]]) -> String {
+        let mut lines = vec![json!({
+            "timestamp": "2025-06-01T10:00:00.000Z",
+            "type": "session_meta",
+            "payload": {
+                "id": thread_id,
+                "timestamp": "2025-06-01T10:00:00.000Z",
+                "cwd": "/repo",
+                "originator": "test",
+                "cli_version": "0.0.0",
+            },
+        })];
+        for files in turns {
+            lines.push(json!({
+                "timestamp": "2025-06-01T10:00:01.000Z",
+                "type": "event_msg",
+                "payload": { "type": "task_started", "model_context_window": null },
+            }));
+            // The prompt, then input steering the running turn.
+            for message in ["go", "also update the docs"] {
+                lines.push(json!({
+                    "timestamp": "2025-06-01T10:00:01.000Z",
+                    "type": "event_msg",
+                    "payload": { "type": "user_message", "message": message },
+                }));
+            }
+            let diff = files.iter().map(|file| file_diff(file)).collect::<String>();
+            lines.push(json!({
+                "timestamp": "2025-06-01T10:00:02.000Z",
+                "type": "event_msg",
+                "payload": { "type": "turn_diff", "unified_diff": diff },
+            }));
+        }
+        lines
+            .iter()
+            .map(serde_json::Value::to_string)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn splits_diffs_per_file() {
+        assert_eq!(
+            split_diff_by_file("diff --git a/a.rs b/a.rs\n+a\ndiff --git a/old.rs b/new.rs\n+b\n"),
+            vec![
+                (
+                    "a.rs".to_string(),
+                    "diff --git a/a.rs b/a.rs\n+a\n".to_string()
+                ),
+                (
+                    "new.rs".to_string(),
+                    "diff --git a/old.rs b/new.rs\n+b\n".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn attributes_files_to_the_sessions_that_changed_them() {
+        let first_id = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+        let second_id = "0f8b1c7e-3a52-4d49-9a53-6c1d2e3f4a5b";
+        let first = scan_rollout(&rollout(first_id, &[&["src/auth.rs"], &["src/lib.rs"]]))
+            .expect("session meta");
+        let second = scan_rollout(&rollout(second_id, &[&["src/lib.rs", "README.md"]]))
+            .expect("session meta");
+
+        let diff = attribute(
+            PathBuf::from("/repo"),
+            vec![session_delta(first), session_delta(second)],
+            vec![PathBuf::from("src/auth.rs"), PathBuf::from("Cargo.lock")],
+        );
+
+        let delta = |file: &str, turn: u32| {
+            (
+                file.to_string(),
+                FileDelta {
+                    turns: vec![turn],
+                    diff: file_diff(file),
+                },
+            )
+        };
+        let session = |thread_id: &str, files: Vec<(String, FileDelta)>| SessionDelta {
+            thread_id: ThreadId::from_string(thread_id).expect("thread id"),
+            checkpoints: Vec::new(),
+            files: files.into_iter().collect(),
+        };
+        assert_eq!(
+            diff,
+            WorkspaceDiff {
+                repo: PathBuf::from("/repo"),
+                sessions: vec![
+                    session(
+                        first_id,
+                        vec![delta("src/auth.rs", 1), delta("src/lib.rs", 2)]
+                    ),
+                    session(
+                        second_id,
+                        vec![delta("README.md", 1), delta("src/lib.rs", 1)]
+                    ),
+                ],
+                shared: vec!["src/lib.rs".to_string()],
+                unattributed: vec!["Cargo.lock".to_string()],
+            }
+        );
+        assert_eq!(
+            diff.render(),
+            format!(
+                "Workspace changes in /repo\n\
+                 \nSession {first_id} changed 2 file(s):\n  src/auth.rs (turns 1)\n  src/lib.rs (turns 2)\n\
+                 \nSession {second_id} changed 2 file(s):\n  README.md (turns 1)\n  src/lib.rs (turns 1)\n\
+                 \nChanged by both sessions:\n  src/lib.rs\n\
+                 \nChanged outside these sessions:\n  Cargo.lock\n\
+                 \n# {first_id} src/auth.rs\n{}\
+                 \n# {first_id} src/lib.rs\n{}\
+                 \n# {second_id} README.md\n{}\
+                 \n# {second_id} src/lib.rs\n{}",
+                file_diff("src/auth.rs"),
+                file_diff("src/lib.rs"),
+                file_diff("README.md"),
+                file_diff("src/lib.rs"),
+            )
+        );
+    }
+
+    #[test]
+    fn rollouts_without_turn_starts_count_user_messages() {
+        let legacy = rollout(
+            "67e55044-10b1-426f-9247-bb680e5fe0c8",
+            &[&["a.rs"], &["b.rs"]],
+        )
+        .lines()
+        .filter(|line| !line.contains("task_started") && !line.contains("also update"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+        let delta = session_delta(scan_rollout(&legacy).expect("session meta"));
+
+        assert_eq!(
+            delta.files,
+            BTreeMap::from([
+                (
+                    "a.rs".to_string(),
+                    FileDelta {
+                        turns: vec![1],
+                        diff: file_diff("a.rs"),
+                    },
+                ),
+                (
+                    "b.rs".to_string(),
+                    FileDelta {
+                        turns: vec![2],
+                        diff: file_diff("b.rs"),
+                    },
+                ),
+            ])
+        );
+    }
+}
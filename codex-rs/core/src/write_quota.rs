@@ -0,0 +1,308 @@
+//! Per-turn guardrail on how much commands and patches write, so that a
+//! misbehaving generation cannot fill the disk with generated files or logs.
+//!
+//! Patches count the content they add. Commands are measured by how much
+//! the files under the turn's writable roots grew since the turn's first
+//! tool call, or by the output they produce when those roots are too large
+//! to measure. Past `warn_bytes` the user is warned once per turn; past
+//! `max_bytes` every further command or patch of the turn needs approval.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use codex_protocol::protocol::FileChange;
+use ignore::WalkBuilder;
+
+use crate::config::types::WriteQuota;
+
+const DEFAULT_WARN_BYTES: u64 = 256 * 1024 * 1024;
+
+const DEFAULT_MAX_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Entries walked when measuring the writable roots before giving up.
+const MAX_MEASURED_ENTRIES: usize = 100_000;
+
+/// Thresholds resolved from [`WriteQuota`]; zero disables a threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct WriteQuotaLimits {
+    pub(crate) warn_bytes: u64,
+    pub(crate) max_bytes: u64,
+}
+
+impl From<&WriteQuota> for WriteQuotaLimits {
+    fn from(config: &WriteQuota) -> Self {
+        Self {
+            warn_bytes: config.warn_bytes.unwrap_or(DEFAULT_WARN_BYTES),
+            max_bytes: config.max_bytes.unwrap_or(DEFAULT_MAX_BYTES),
+        }
+    }
+}
+
+/// Bytes written by the commands and patches of one turn.
+#[derive(Debug, Default)]
+pub(crate) struct TurnWriteQuota {
+    /// Patch content, plus command output when the roots cannot be measured.
+    written: AtomicU64,
+    /// [`disk_usage`] of the writable roots at the turn's first tool call.
+    baseline: OnceLock<Option<u64>>,
+    /// Largest growth of the writable roots over `baseline` seen so far.
+    /// It includes the patches applied before the measurement, so the turn's
+    /// total is the larger of this and `written`.
+    disk_growth: AtomicU64,
+    warned: AtomicBool,
+}
+
+impl TurnWriteQuota {
+    /// Whether the writable roots still need to be measured for this turn.
+    pub(crate) fn needs_baseline(&self) -> bool {
+        self.baseline.get().is_none()
+    }
+
+    /// Sets the [`disk_usage`] the turn started from; later calls are ignored.
+    pub(crate) fn set_baseline(&self, usage: Option<u64>) {
+        let _ = self.baseline.set(usage);
+    }
+
+    /// Records a finished command from the [`disk_usage`] of the writable
+    /// roots after it ran, falling back to `output_bytes` when either
+    /// measurement is missing. Returns a warning like [`Self::record`].
+    pub(crate) fn record_command(
+        &self,
+        usage: Option<u64>,
+        output_bytes: u64,
+        limits: WriteQuotaLimits,
+    ) -> Option<String> {
+        match (self.baseline.get().copied().flatten(), usage) {
+            (Some(baseline), Some(usage)) => {
+                self.disk_growth
+                    .fetch_max(usage.saturating_sub(baseline), Ordering::Relaxed);
+                self.warning(limits)
+            }
+            _ => self.record(output_bytes, limits),
+        }
+    }
+
+    /// Adds `bytes` to the turn's total. Returns a warning for the user the
+    /// first time the total reaches the warning threshold.
+    pub(crate) fn record(&self, bytes: u64, limits: WriteQuotaLimits) -> Option<String> {
+        self.written.fetch_add(bytes, Ordering::Relaxed);
+        self.warning(limits)
+    }
+
+    fn total(&self) -> u64 {
+        self.written
+            .load(Ordering::Relaxed)
+            .max(self.disk_growth.load(Ordering::Relaxed))
+    }
+
+    fn warning(&self, limits: WriteQuotaLimits) -> Option<String> {
+        let written = self.total();
+        if limits.warn_bytes == 0
+            || written < limits.warn_bytes
+            || self.warned.swap(true, Ordering::Relaxed)
+        {
+            return None;
+        }
+        let mut message = format!(
+            "This turn has written {} through commands and patches (warning threshold: {}).",
+            format_bytes(written),
+            format_bytes(limits.warn_bytes)
+        );
+        if limits.max_bytes > 0 {
+            message.push_str(&format!(
+                " Commands and patches will need approval once it passes {}.",
+                format_bytes(limits.max_bytes)
+            ));
+        }
+        Some(message)
+    }
+
+    /// Why the next command or patch needs approval, if the turn has
+    /// written more than the hard cap.
+    pub(crate) fn over_cap_reason(&self, limits: WriteQuotaLimits) -> Option<String> {
+        let written = self.total();
+        if limits.max_bytes == 0 || written <= limits.max_bytes {
+            return None;
+        }
+        Some(format!(
+            "this turn has already written {} through commands and patches, over the cap of {}",
+            format_bytes(written),
+            format_bytes(limits.max_bytes)
+        ))
+    }
+}
+
+/// Bytes a patch adds: the content of new files and the added lines of
+/// updated ones.
+pub(crate) fn patch_bytes(changes: &HashMap<PathBuf, FileChange>) -> u64 {
+    changes
+        .values()
+        .map(|change| match change {
+            FileChange::Add { content, .. } => content.len(),
+            FileChange::Update { unified_diff, .. } => unified_diff
+                .lines()
+                .filter(|line| line.starts_with('+') && !line.starts_with("+++"))
+                .map(|line| line.len())
+                .sum(),
+            _ => 0,
+        })
+        .map(|bytes| u64::try_from(bytes).unwrap_or(u64::MAX))
+        .fold(0, u64::saturating_add)
+}
+
+/// Total size of the files under `roots`, or `None` when they hold more
+/// than [`MAX_MEASURED_ENTRIES`] entries. Roots nested in another root are
+/// only counted once. This walks the filesystem, so call it off the async
+/// runtime.
+pub(crate) fn disk_usage(roots: &[PathBuf]) -> Option<u64> {
+    let mut total = 0u64;
+    let mut entries = 0usize;
+    let outermost = roots.iter().filter(|root| {
+        !roots
+            .iter()
+            .any(|other| other != *root && root.starts_with(other))
+    });
+    for root in outermost {
+        let walker = WalkBuilder::new(root)
+            .standard_filters(false)
+            .follow_links(false)
+            .build();
+        for entry in walker.flatten() {
+            entries += 1;
+            if entries > MAX_MEASURED_ENTRIES {
+                return None;
+            }
+            if entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_file())
+                && let Ok(metadata) = entry.metadata()
+            {
+                total = total.saturating_add(metadata.len());
+            }
+        }
+    }
+    Some(total)
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const KIB: u64 = 1024;
+    const MIB: u64 = 1024 * 1024;
+
+    if bytes >= MIB {
+        return format!("{} MiB", bytes / MIB);
+    }
+    if bytes >= KIB {
+        return format!("{} KiB", bytes / KIB);
+    }
+    format!("{bytes} B")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const LIMITS: WriteQuotaLimits = WriteQuotaLimits {
+        warn_bytes: 2 * 1024,
+        max_bytes: 4 * 1024,
+    };
+
+    #[test]
+    fn warns_once_and_requires_approval_past_the_cap() {
+        let quota = TurnWriteQuota::default();
+
+        assert_eq!(quota.record(1024, LIMITS), None);
+        assert_eq!(
+            quota.record(1024, LIMITS),
+            Some(
+                "This turn has written 2 KiB through commands and patches (warning threshold: \
+                 2 KiB). Commands and patches will need approval once it passes 4 KiB."
+                    .to_string()
+            )
+        );
+        assert_eq!(quota.record(2048, LIMITS), None);
+        assert_eq!(quota.over_cap_reason(LIMITS), None);
+
+        quota.record(1, LIMITS);
+        assert_eq!(
+            quota.over_cap_reason(LIMITS),
+            Some(
+                "this turn has already written 4 KiB through commands and patches, over the cap \
+                 of 4 KiB"
+                    .to_string()
+            )
+        );
+        let unlimited = WriteQuotaLimits {
+            warn_bytes: 0,
+            max_bytes: 0,
+        };
+        assert_eq!(quota.over_cap_reason(unlimited), None);
+    }
+
+    #[test]
+    fn commands_count_how_much_the_writable_roots_grew() {
+        let root = tempfile::tempdir().expect("tempdir");
+        std::fs::create_dir(root.path().join("src")).expect("mkdir");
+        std::fs::write(root.path().join("src/lib.rs"), "fn a() {}\n").expect("write");
+        let roots = vec![root.path().to_path_buf(), root.path().join("src")];
+        let quota = TurnWriteQuota::default();
+        quota.set_baseline(disk_usage(&roots));
+        assert_eq!(disk_usage(&roots), Some(10));
+
+        // Large output that writes nothing does not count.
+        std::fs::write(root.path().join("out.log"), vec![b'x'; 3 * 1024]).expect("write");
+        assert_eq!(
+            quota.record_command(disk_usage(&roots), 1024 * 1024, LIMITS),
+            Some(
+                "This turn has written 3 KiB through commands and patches (warning threshold: \
+                 2 KiB). Commands and patches will need approval once it passes 4 KiB."
+                    .to_string()
+            )
+        );
+        assert_eq!(quota.over_cap_reason(LIMITS), None);
+
+        // Patch bytes already part of the measured growth are not added again.
+        quota.record(1024, LIMITS);
+        assert_eq!(quota.total(), 3 * 1024);
+
+        // Without a measurement the command's output is counted instead.
+        let unmeasured = TurnWriteQuota::default();
+        unmeasured.set_baseline(None);
+        unmeasured.record_command(None, 5 * 1024, LIMITS);
+        assert!(unmeasured.over_cap_reason(LIMITS).is_some());
+    }
+
+    #[test]
+    fn patch_bytes_counts_added_content() {
+        let changes = HashMap::from([
+            (
+                PathBuf::from("new.txt"),
+                FileChange::Add {
+                    content: "hello\n".to_string(),
+                    mode: None,
+                },
+            ),
+            (
+                PathBuf::from("old.txt"),
+                FileChange::Update {
+                    unified_diff: "--- a/old.txt\n+++ b/old.txt\n@@ -1 +1 @@\n-a\n+bc\n"
+                        .to_string(),
+                    move_path: None,
+                    mode: None,
+                },
+            ),
+            (
+                PathBuf::from("gone.txt"),
+                FileChange::Delete {
+                    content: "bye\n".to_string(),
+                },
+            ),
+        ]);
+
+        assert_eq!(patch_bytes(&changes), 6 + 3);
+    }
+}
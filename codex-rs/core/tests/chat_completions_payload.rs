@@ -48,6 +48,7 @@ async fn run_request(input: Vec<ResponseItem>) -> Value {
     let provider = ModelProviderInfo {
         name: "mock".into(),
         base_url: Some(format!("{}/v1", server.uri())),
+        fallback_base_urls: None,
         env_key: None,
         env_key_instructions: None,
         experimental_bearer_token: None,
@@ -47,6 +47,7 @@ async fn run_stream_with_bytes(sse_body: &[u8]) -> Vec<ResponseEvent> {
     let provider = ModelProviderInfo {
         name: "mock".into(),
         base_url: Some(format!("{}/v1", server.uri())),
+        fallback_base_urls: None,
         env_key: None,
         env_key_instructions: None,
         experimental_bearer_token: None,
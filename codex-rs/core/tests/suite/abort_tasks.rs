@@ -2,8 +2,10 @@ use assert_matches::assert_matches;
 use std::sync::Arc;
 use std::time::Duration;
 
+use codex_core::protocol::CancelledToolCall;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::Op;
+use codex_core::protocol::TurnInterruptedEvent;
 use codex_protocol::user_input::UserInput;
 use core_test_support::responses::ev_completed;
 use core_test_support::responses::ev_function_call;
@@ -14,6 +16,7 @@ use core_test_support::responses::sse;
 use core_test_support::responses::start_mock_server;
 use core_test_support::test_codex::test_codex;
 use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
 use regex_lite::Regex;
 use serde_json::json;
 
@@ -239,3 +242,68 @@ async fn interrupt_persists_turn_aborted_marker_in_next_request() {
         "expected <turn_aborted> marker in follow-up request"
     );
 }
+
+/// Interrupting a turn reports the cancelled tool call only once the turn has
+/// finished winding down, before the matching `TurnAborted`.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn interrupt_reports_cancelled_tool_calls_before_turn_aborted() {
+    let call_id = "call-interrupted-summary";
+    let args = json!({
+        "command": "sleep 60",
+        "timeout_ms": 60_000
+    })
+    .to_string();
+    let body = sse(vec![
+        ev_response_created("resp-interrupted"),
+        ev_function_call(call_id, "shell_command", &args),
+        ev_completed("resp-interrupted"),
+    ]);
+
+    let server = start_mock_server().await;
+    mount_sse_once(&server, body).await;
+
+    let codex = test_codex()
+        .with_model("gpt-5.1")
+        .build(&server)
+        .await
+        .unwrap()
+        .codex;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "start sleep".into(),
+                text_elements: Vec::new(),
+            }],
+            final_output_json_schema: None,
+        })
+        .await
+        .unwrap();
+
+    let EventMsg::ExecCommandBegin(begin) =
+        wait_for_event(&codex, |ev| matches!(ev, EventMsg::ExecCommandBegin(_))).await
+    else {
+        unreachable!("wait_for_event returned unexpected event");
+    };
+    codex.submit(Op::Interrupt).await.unwrap();
+
+    let event = wait_for_event(&codex, |ev| {
+        matches!(ev, EventMsg::TurnInterrupted(_) | EventMsg::TurnAborted(_))
+    })
+    .await;
+    let EventMsg::TurnInterrupted(interrupted) = event else {
+        panic!("expected TurnInterrupted before TurnAborted, got {event:?}");
+    };
+    assert_eq!(
+        interrupted,
+        TurnInterruptedEvent {
+            turn_id: begin.turn_id,
+            partial_agent_message: None,
+            cancelled_tool_calls: vec![CancelledToolCall {
+                call_id: call_id.to_string(),
+                tool_name: "shell_command".to_string(),
+            }],
+        }
+    );
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TurnAborted(_))).await;
+}
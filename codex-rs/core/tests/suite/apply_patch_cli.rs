@@ -26,6 +26,7 @@ use core_test_support::responses::ev_response_created;
 use core_test_support::responses::mount_sse_sequence;
 use core_test_support::responses::sse;
 use core_test_support::skip_if_no_network;
+use core_test_support::skip_if_windows;
 use core_test_support::test_codex::TestCodexBuilder;
 use core_test_support::test_codex::TestCodexHarness;
 use core_test_support::test_codex::test_codex;
@@ -581,6 +582,9 @@ async fn apply_patch_cli_rejects_path_traversal_outside_workspace(
         network_access: false,
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        max_memory_bytes: None,
+        cpu_rate_percent: None,
+        max_processes: None,
     };
     harness
         .submit_with_policy(
@@ -637,6 +641,9 @@ async fn apply_patch_cli_rejects_move_path_traversal_outside_workspace(
         network_access: false,
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        max_memory_bytes: None,
+        cpu_rate_percent: None,
+        max_processes: None,
     };
     harness
         .submit_with_policy("attempt move traversal via apply_patch", sandbox_policy)
@@ -1405,3 +1412,54 @@ async fn apply_patch_change_context_disambiguates_target(
     assert_eq!(contents, "fn a\nx=10\ny=2\nfn b\nx=11\ny=20\n");
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn apply_patch_warns_when_file_changed_since_shell_read() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+    skip_if_windows!(Ok(()));
+
+    let harness = apply_patch_harness_with(|builder| builder.with_model("gpt-5.1")).await?;
+    let target = harness.path("target.txt");
+    fs::write(&target, "line1\nline2\n")?;
+
+    let read_call_id = "read-target";
+    let apply_call_id = "apply-target";
+    mount_sse_sequence(
+        harness.server(),
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_shell_command_call(read_call_id, "cat ./target.txt"),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-1", "read it"),
+                ev_completed("resp-2"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-3"),
+                ev_apply_patch_function_call(
+                    apply_call_id,
+                    "*** Begin Patch\n*** Update File: target.txt\n@@\n-line2\n+patched\n*** End Patch",
+                ),
+                ev_completed("resp-3"),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-2", "done"),
+                ev_completed("resp-4"),
+            ]),
+        ],
+    )
+    .await;
+
+    harness.submit("read target.txt").await?;
+    fs::write(&target, "line1\nline2\nedited by the user\n")?;
+    harness.submit("patch target.txt").await?;
+
+    let out = harness.function_call_stdout(apply_call_id).await;
+    assert!(
+        out.contains("changed on disk since you last read it"),
+        "{out}"
+    );
+    Ok(())
+}
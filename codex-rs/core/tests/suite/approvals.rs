@@ -631,6 +631,9 @@ fn scenarios() -> Vec<ScenarioSpec> {
         network_access,
         exclude_tmpdir_env_var: false,
         exclude_slash_tmp: false,
+        max_memory_bytes: None,
+        cpu_rate_percent: None,
+        max_processes: None,
     };
 
     vec![
@@ -1576,6 +1579,9 @@ async fn approving_apply_patch_for_session_skips_future_prompts_for_same_file()
         network_access: false,
         exclude_tmpdir_env_var: false,
         exclude_slash_tmp: false,
+        max_memory_bytes: None,
+        cpu_rate_percent: None,
+        max_processes: None,
     };
     let sandbox_policy_for_config = sandbox_policy.clone();
 
@@ -1212,6 +1212,7 @@ async fn azure_responses_request_includes_store_and_reasoning_ids() {
     let provider = ModelProviderInfo {
         name: "azure".into(),
         base_url: Some(format!("{}/openai", server.uri())),
+        fallback_base_urls: None,
         env_key: None,
         env_key_instructions: None,
         experimental_bearer_token: None,
@@ -1729,6 +1730,7 @@ async fn azure_overrides_assign_properties_used_for_responses_url() {
         name: "custom".to_string(),
         base_url: Some(format!("{}/openai", server.uri())),
         // Reuse the existing environment variable to avoid using unsafe code
+        fallback_base_urls: None,
         env_key: Some(existing_env_var_with_random_value.to_string()),
         experimental_bearer_token: None,
         query_params: Some(std::collections::HashMap::from([(
@@ -1813,6 +1815,7 @@ async fn env_var_overrides_loaded_auth() {
         name: "custom".to_string(),
         base_url: Some(format!("{}/openai", server.uri())),
         // Reuse the existing environment variable to avoid using unsafe code
+        fallback_base_urls: None,
         env_key: Some(existing_env_var_with_random_value.to_string()),
         query_params: Some(std::collections::HashMap::from([(
             "api-version".to_string(),
@@ -0,0 +1,76 @@
+#![cfg(not(target_os = "windows"))]
+
+use codex_core::ModelProviderInfo;
+use codex_core::built_in_model_providers;
+use codex_core::features::Feature;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::mount_function_call_agent_response;
+use core_test_support::responses::mount_sse_once;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::test_codex;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn delegate_local_reads_files_for_the_local_model() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let local_server = start_mock_server().await;
+    let local_provider = ModelProviderInfo {
+        base_url: Some(format!("{}/v1", local_server.uri())),
+        requires_openai_auth: false,
+        ..built_in_model_providers()["openai"].clone()
+    };
+    let test = test_codex()
+        .with_config(move |config| {
+            config.features.enable(Feature::DelegateLocal);
+            config
+                .model_providers
+                .insert("local".to_string(), local_provider);
+            config.delegate_local.provider = Some("local".to_string());
+            config.delegate_local.model = Some("gpt-oss:20b".to_string());
+        })
+        .build(&server)
+        .await?;
+    std::fs::write(
+        test.cwd.path().join("build.log"),
+        "E1 disk full\nE2 disk full\n",
+    )?;
+
+    let local = mount_sse_once(
+        &local_server,
+        sse(vec![
+            ev_assistant_message("local-msg", "disk full (2)"),
+            ev_completed("local-resp"),
+        ]),
+    )
+    .await;
+    let call_id = "delegate-call";
+    let arguments = json!({
+        "task": "List the distinct errors with their counts.",
+        "paths": ["build.log"],
+    })
+    .to_string();
+    let mocks =
+        mount_function_call_agent_response(&server, call_id, &arguments, "delegate_local").await;
+
+    test.submit_turn("summarize the build log").await?;
+
+    assert_eq!(
+        local.single_request().message_input_texts("user"),
+        vec![
+            "List the distinct errors with their counts.\n\n<input source=\"build.log\">\nE1 disk full\nE2 disk full\n\n</input>"
+                .to_string()
+        ]
+    );
+    assert_eq!(
+        mocks.completion.function_call_output_text(call_id),
+        Some("disk full (2)".to_string())
+    );
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+#![allow(clippy::expect_used)]
+
+use anyhow::Result;
+use codex_core::config::types::Explore;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::ExplorationFindingsEvent;
+use codex_core::protocol::Op;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_sequence;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+
+fn tool_names(body: &Value) -> Vec<String> {
+    body["tools"]
+        .as_array()
+        .map(|tools| {
+            tools
+                .iter()
+                .filter_map(|tool| {
+                    tool.get("name")
+                        .or_else(|| tool.get("type"))
+                        .and_then(Value::as_str)
+                        .map(str::to_string)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn has_pinned_findings(developer_texts: &[String]) -> bool {
+    developer_texts
+        .iter()
+        .any(|text| text.contains("<exploration_findings>\n## Relevant files\n- src/lib.rs"))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn exploration_offers_only_allowed_tools_and_pins_findings_until_cleared() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let mut builder = test_codex().with_config(|config| {
+        config.explore = Explore {
+            allowed_tools: Some(vec!["view_image".to_string()]),
+            ..Default::default()
+        };
+    });
+    let test = builder.build(&server).await?;
+
+    let responses = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-explore"),
+                ev_assistant_message("msg-explore", "Looked around."),
+                ev_completed("resp-explore"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-findings"),
+                ev_assistant_message("msg-findings", "## Relevant files\n- src/lib.rs"),
+                ev_completed("resp-findings"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-pinned"),
+                ev_assistant_message("msg-pinned", "ok"),
+                ev_completed("resp-pinned"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-cleared"),
+                ev_assistant_message("msg-cleared", "ok"),
+                ev_completed("resp-cleared"),
+            ]),
+        ],
+    )
+    .await;
+
+    test.codex
+        .submit(Op::Explore {
+            goal: "find the parser".to_string(),
+        })
+        .await?;
+    let EventMsg::ExplorationFindings(findings) = wait_for_event(&test.codex, |event| {
+        matches!(event, EventMsg::ExplorationFindings(_))
+    })
+    .await
+    else {
+        unreachable!("waited for exploration findings");
+    };
+    assert_eq!(
+        findings,
+        ExplorationFindingsEvent {
+            goal: "find the parser".to_string(),
+            findings: "## Relevant files\n- src/lib.rs".to_string(),
+            budget_exhausted: false,
+        }
+    );
+    wait_for_event(&test.codex, |event| {
+        matches!(event, EventMsg::TurnComplete(_))
+    })
+    .await;
+
+    test.submit_turn("add a --json flag").await?;
+
+    test.codex.submit(Op::ClearExplorationFindings).await?;
+    wait_for_event(&test.codex, |event| {
+        matches!(event, EventMsg::ExplorationFindingsCleared)
+    })
+    .await;
+
+    test.submit_turn("and a --yaml flag").await?;
+
+    let requests = responses.requests();
+    assert_eq!(requests.len(), 4);
+    assert_eq!(
+        tool_names(&requests[0].body_json()),
+        vec!["view_image".to_string()]
+    );
+    assert!(has_pinned_findings(
+        &requests[2].message_input_texts("developer")
+    ));
+    assert!(!has_pinned_findings(
+        &requests[3].message_input_texts("developer")
+    ));
+
+    Ok(())
+}
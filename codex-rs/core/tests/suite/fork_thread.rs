@@ -170,3 +170,100 @@ async fn fork_thread_twice_drops_to_first_message() {
         serde_json::to_value(&expected_after_second).unwrap()
     );
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn fork_thread_after_item_keeps_rollout_through_that_item() {
+    skip_if_no_network!();
+
+    let server = MockServer::start().await;
+    let sse = sse_completed("resp");
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_raw(sse, "text/event-stream"),
+        )
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let home = TempDir::new().unwrap();
+    let mut config = load_default_config_for_test(&home).await;
+    config.model_provider = ModelProviderInfo {
+        base_url: Some(format!("{}/v1", server.uri())),
+        ..built_in_model_providers()["openai"].clone()
+    };
+    let config_for_fork = config.clone();
+    let thread_manager = ThreadManager::with_models_provider(
+        CodexAuth::from_api_key("dummy"),
+        config.model_provider.clone(),
+    );
+    let NewThread {
+        thread_id: base_id,
+        thread: codex,
+        ..
+    } = thread_manager
+        .start_thread(config)
+        .await
+        .expect("create conversation");
+
+    for text in ["first", "second"] {
+        codex
+            .submit(Op::UserInput {
+                items: vec![UserInput::Text {
+                    text: text.to_string(),
+                    text_elements: Vec::new(),
+                }],
+                final_output_json_schema: None,
+            })
+            .await
+            .unwrap();
+        let _ = wait_for_event(&codex, |ev| matches!(ev, EventMsg::TurnComplete(_))).await;
+    }
+
+    let read_lines = |p: &std::path::Path| -> Vec<RolloutItem> {
+        std::fs::read_to_string(p)
+            .expect("read rollout file")
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                serde_json::from_str::<RolloutLine>(line)
+                    .expect("rollout line")
+                    .item
+            })
+            .collect()
+    };
+    let base_path = codex.rollout_path();
+    let base_items = read_lines(&base_path);
+    let (cut, first_message_id) = base_items
+        .iter()
+        .enumerate()
+        .find_map(|(idx, item)| match item {
+            RolloutItem::EventMsg(EventMsg::UserMessage(ev)) => Some((idx, ev.item_id.clone()?)),
+            _ => None,
+        })
+        .expect("first user message id");
+
+    let NewThread { thread: fork, .. } = thread_manager
+        .fork_thread_after_item(config_for_fork, base_path, &first_message_id)
+        .await
+        .expect("fork");
+
+    let fork_items = read_lines(&fork.rollout_path());
+    let RolloutItem::SessionMeta(meta) = &fork_items[0] else {
+        panic!("fork rollout starts with session meta");
+    };
+    pretty_assertions::assert_eq!(meta.meta.forked_from_id, Some(base_id));
+    // Everything up to and including the message is kept.
+    let to_json = |items: &[RolloutItem]| -> Vec<serde_json::Value> {
+        items
+            .iter()
+            .filter(|item| !matches!(item, RolloutItem::SessionMeta(_)))
+            .map(|item| serde_json::to_value(item).expect("serialize rollout item"))
+            .collect()
+    };
+    let kept = to_json(&base_items[..=cut]);
+    let forked = to_json(&fork_items);
+    pretty_assertions::assert_eq!(forked[..kept.len()], kept[..]);
+}
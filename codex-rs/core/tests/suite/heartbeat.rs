@@ -0,0 +1,71 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use codex_core::config::types::Heartbeat;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_response_once;
+use core_test_support::responses::sse;
+use core_test_support::responses::sse_response;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event_with_timeout;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn silent_turn_reports_growing_silence_in_heartbeats() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    // The model takes a while before streaming anything.
+    mount_response_once(
+        &server,
+        sse_response(sse(vec![
+            ev_response_created("resp-1"),
+            ev_assistant_message("msg-1", "done"),
+            ev_completed("resp-1"),
+        ]))
+        .set_delay(Duration::from_millis(3500)),
+    )
+    .await;
+    let test = test_codex()
+        .with_config(|config| {
+            config.heartbeat = Heartbeat {
+                enabled: Some(true),
+                interval_seconds: Some(1),
+            };
+        })
+        .build(&server)
+        .await?;
+
+    test.codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "think hard".into(),
+                text_elements: Vec::new(),
+            }],
+            final_output_json_schema: None,
+        })
+        .await?;
+
+    let mut silences = Vec::new();
+    loop {
+        match wait_for_event_with_timeout(&test.codex, |_| true, Duration::from_secs(10)).await {
+            EventMsg::Heartbeat(heartbeat) => silences.push(heartbeat.silent_ms),
+            EventMsg::TurnComplete(_) => break,
+            _ => {}
+        }
+    }
+
+    assert!(silences.len() >= 2, "{silences:?}");
+    assert!(
+        silences.windows(2).all(|pair| pair[0] < pair[1]),
+        "silence should keep growing across heartbeats: {silences:?}"
+    );
+    assert!(silences[silences.len() - 1] >= 2000, "{silences:?}");
+    Ok(())
+}
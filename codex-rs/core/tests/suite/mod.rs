@@ -28,11 +28,14 @@ mod collaboration_instructions;
 mod compact;
 mod compact_remote;
 mod compact_resume_fork;
+mod delegate_local;
 mod deprecation_notice;
 mod exec;
 mod exec_policy;
+mod explore;
 mod fork_thread;
 mod grep_files;
+mod heartbeat;
 mod hierarchical_agents;
 mod image_rollout;
 mod items;
@@ -53,6 +56,7 @@ mod prompt_caching;
 mod quota_exceeded;
 mod read_file;
 mod remote_models;
+mod repl;
 mod request_compression;
 mod request_user_input;
 mod resume;
@@ -1,4 +1,9 @@
+use codex_core::config::types::ApplyPatchToolChoice;
+use codex_core::config::types::ModelFamily;
+use codex_core::config::types::ModelInstructionsPreset;
 use codex_core::models_manager::manager::ModelsManager;
+use codex_protocol::openai_models::ApplyPatchToolType;
+use codex_protocol::openai_models::ConfigShellToolType;
 use codex_protocol::openai_models::TruncationPolicyConfig;
 use core_test_support::load_default_config_for_test;
 use pretty_assertions::assert_eq;
@@ -30,3 +35,44 @@ async fn offline_model_info_with_tool_output_override() {
         TruncationPolicyConfig::tokens(123)
     );
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn offline_model_info_with_custom_model_family() {
+    let codex_home = TempDir::new().expect("create temp dir");
+    let mut config = load_default_config_for_test(&codex_home).await;
+    config.model_families = vec![ModelFamily {
+        name: Some("acme-coder".to_string()),
+        pattern: "^acme-coder-".to_string(),
+        context_window: Some(131_072),
+        max_output_tokens: Some(8_192),
+        instructions: Some(ModelInstructionsPreset::Gpt5Codex),
+        shell_type: Some(ConfigShellToolType::ShellCommand),
+        apply_patch: Some(ApplyPatchToolChoice::Function),
+        supports_parallel_tool_calls: Some(true),
+        supports_reasoning_summaries: None,
+        supports_image_input: Some(false),
+        experimental_supported_tools: None,
+    }];
+    // Explicit model settings still win over the family.
+    config.model_context_window = Some(65_536);
+
+    let model_info = ModelsManager::construct_model_info_offline("acme-coder-7b", &config);
+
+    assert_eq!(model_info.context_window, Some(65_536));
+    assert_eq!(model_info.max_output_tokens, Some(8_192));
+    assert_eq!(model_info.shell_type, ConfigShellToolType::ShellCommand);
+    assert_eq!(
+        model_info.apply_patch_tool_type,
+        Some(ApplyPatchToolType::Function)
+    );
+    assert!(model_info.supports_parallel_tool_calls);
+    assert!(!model_info.supports_image_input());
+    assert_eq!(
+        model_info.base_instructions,
+        ModelsManager::construct_model_info_offline("gpt-5-codex", &config).base_instructions
+    );
+
+    let other = ModelsManager::construct_model_info_offline("other-model", &config);
+    assert_eq!(other.max_output_tokens, None);
+    assert_eq!(other.apply_patch_tool_type, None);
+}
@@ -1,8 +1,15 @@
 use codex_core::CodexAuth;
 use codex_core::ThreadManager;
+use codex_core::config_loader::RequirementSource;
+use codex_core::config_loader::Sourced;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::CodexErrorInfo;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::Op;
+use codex_core::protocol::SandboxPolicy;
+use codex_protocol::config_types::ReasoningSummary;
 use codex_protocol::openai_models::ReasoningEffort;
+use codex_protocol::user_input::UserInput;
 use core_test_support::load_default_config_for_test;
 use core_test_support::wait_for_event;
 use pretty_assertions::assert_eq;
@@ -98,3 +105,61 @@ async fn override_turn_context_does_not_create_config_file() {
         "override should not create config.toml"
     );
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn per_turn_model_outside_allowed_models_is_rejected() {
+    let codex_home = TempDir::new().unwrap();
+    let mut config = load_default_config_for_test(&codex_home).await;
+    config.model = Some("gpt-4o".to_string());
+    config.allowed_models = Some(Sourced::new(
+        vec!["gpt-4o".to_string()],
+        RequirementSource::Unknown,
+    ));
+    let cwd = config.cwd.clone();
+
+    let thread_manager = ThreadManager::with_models_provider(
+        CodexAuth::from_api_key("Test API Key"),
+        config.model_provider.clone(),
+    );
+    let codex = thread_manager
+        .start_thread(config)
+        .await
+        .expect("create conversation")
+        .thread;
+
+    codex
+        .submit(Op::UserTurn {
+            items: vec![UserInput::Text {
+                text: "hello".into(),
+                text_elements: Vec::new(),
+            }],
+            final_output_json_schema: None,
+            cwd,
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: "o3".to_string(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+            collaboration_mode: None,
+            personality: None,
+        })
+        .await
+        .expect("submit turn");
+
+    let EventMsg::Error(error) =
+        wait_for_event(&codex, |ev| matches!(ev, EventMsg::Error(_))).await
+    else {
+        unreachable!("wait_for_event returned a non-error event");
+    };
+    assert_eq!(
+        (error.message, error.codex_error_info),
+        (
+            "invalid value for `model`: `o3` is not in the allowed set [\"gpt-4o\"] (set by <unspecified>)"
+                .to_string(),
+            Some(CodexErrorInfo::BadRequest),
+        )
+    );
+
+    codex.submit(Op::Shutdown).await.expect("request shutdown");
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::ShutdownComplete)).await;
+}
@@ -399,6 +399,9 @@ async fn permissions_message_includes_writable_roots() -> Result<()> {
         network_access: false,
         exclude_tmpdir_env_var: false,
         exclude_slash_tmp: false,
+        max_memory_bytes: None,
+        cpu_rate_percent: None,
+        max_processes: None,
     };
 
     let mut builder = test_codex().with_config(move |config| {
@@ -343,6 +343,9 @@ async fn overrides_turn_context_but_keeps_cached_prefix_and_key_constant() -> an
         network_access: true,
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        max_memory_bytes: None,
+        cpu_rate_percent: None,
+        max_processes: None,
     };
     codex
         .submit(Op::OverrideTurnContext {
@@ -570,6 +573,9 @@ async fn per_turn_overrides_keep_cached_prefix_and_key_constant() -> anyhow::Res
         network_access: true,
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        max_memory_bytes: None,
+        cpu_rate_percent: None,
+        max_processes: None,
     };
     codex
         .submit(Op::UserTurn {
@@ -1,10 +1,16 @@
 use anyhow::Result;
+use codex_core::protocol::CodexErrorInfo;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::Op;
 use codex_protocol::user_input::UserInput;
+use core_test_support::assert_regex_match;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
 use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_response_once;
 use core_test_support::responses::mount_sse_once;
 use core_test_support::responses::sse;
+use core_test_support::responses::sse_response;
 use core_test_support::responses::start_mock_server;
 use core_test_support::skip_if_no_network;
 use core_test_support::test_codex::test_codex;
@@ -72,3 +78,67 @@ async fn quota_exceeded_emits_single_error_event() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn exhausted_provider_rate_limit_fails_the_turn_once() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    // The first response spends the last request of a budget that resets in
+    // an hour, far beyond what the scheduler is willing to wait.
+    mount_response_once(
+        &server,
+        sse_response(sse(vec![
+            ev_response_created("resp-1"),
+            ev_assistant_message("msg-1", "done"),
+            ev_completed("resp-1"),
+        ]))
+        .insert_header("x-ratelimit-limit-requests", "100")
+        .insert_header("x-ratelimit-remaining-requests", "0")
+        .insert_header("x-ratelimit-reset-requests", "1h"),
+    )
+    .await;
+
+    let test = test_codex().build(&server).await?;
+    let user_input = |text: &str| Op::UserInput {
+        items: vec![UserInput::Text {
+            text: text.into(),
+            text_elements: Vec::new(),
+        }],
+        final_output_json_schema: None,
+    };
+    test.codex.submit(user_input("first")).await?;
+    wait_for_event(&test.codex, |ev| matches!(ev, EventMsg::TurnComplete(_))).await;
+    test.codex.submit(user_input("second")).await?;
+
+    let mut errors = Vec::new();
+    let mut stream_errors = 0;
+    loop {
+        match wait_for_event(&test.codex, |_| true).await {
+            EventMsg::Error(err) => errors.push(err),
+            EventMsg::StreamError(_) => stream_errors += 1,
+            EventMsg::TurnComplete(_) => break,
+            _ => {}
+        }
+    }
+
+    assert_eq!((errors.len(), stream_errors), (1, 0));
+    assert_regex_match(
+        r"rate limit exhausted; it resets in \d+s$",
+        &errors[0].message,
+    );
+    assert_eq!(
+        errors[0].codex_error_info,
+        Some(CodexErrorInfo::UsageLimitExceeded)
+    );
+    let responses_requests = server
+        .received_requests()
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|request| request.url.path().ends_with("/responses"))
+        .count();
+    assert_eq!(responses_requests, 1);
+
+    Ok(())
+}
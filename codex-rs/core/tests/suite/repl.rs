@@ -0,0 +1,126 @@
+#![cfg(not(target_os = "windows"))]
+
+use std::time::Duration;
+
+use anyhow::Context;
+use anyhow::Result;
+use codex_core::features::Feature;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_core::protocol::SandboxPolicy;
+use codex_protocol::config_types::ReasoningSummary;
+use codex_protocol::user_input::UserInput;
+use core_test_support::assert_regex_match;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_function_call;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_once;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use core_test_support::wait_for_event_with_timeout;
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn large_snippet_output_is_capped_in_the_result_and_the_event() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+    if std::process::Command::new("python3")
+        .arg("--version")
+        .output()
+        .is_err()
+    {
+        eprintln!("python3 not found; skipping test.");
+        return Ok(());
+    }
+
+    let server = start_mock_server().await;
+    let mut builder = test_codex().with_config(|config| {
+        config.features.enable(Feature::Repl);
+    });
+    let fixture = builder.build(&server).await?;
+
+    let call_id = "repl-large";
+    let args = json!({ "code": "print('x' * 2_000_000)" });
+    mount_sse_once(
+        &server,
+        sse(vec![
+            ev_response_created("resp-1"),
+            ev_function_call(call_id, "repl", &serde_json::to_string(&args)?),
+            ev_completed("resp-1"),
+        ]),
+    )
+    .await;
+    let done_mock = mount_sse_once(
+        &server,
+        sse(vec![
+            ev_assistant_message("msg-1", "done"),
+            ev_completed("resp-2"),
+        ]),
+    )
+    .await;
+
+    fixture
+        .codex
+        .submit(Op::UserTurn {
+            items: vec![UserInput::Text {
+                text: "print a lot".into(),
+                text_elements: Vec::new(),
+            }],
+            final_output_json_schema: None,
+            cwd: fixture.cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: fixture.session_configured.model.clone(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+            collaboration_mode: None,
+            personality: None,
+        })
+        .await?;
+
+    let EventMsg::ReplSnippet(snippet) = wait_for_event_with_timeout(
+        &fixture.codex,
+        |event| matches!(event, EventMsg::ReplSnippet(_)),
+        Duration::from_secs(30),
+    )
+    .await
+    else {
+        unreachable!("waited for a repl snippet event");
+    };
+    wait_for_event(&fixture.codex, |event| {
+        matches!(event, EventMsg::TurnComplete(_))
+    })
+    .await;
+
+    assert!(
+        snippet.stdout.len() <= 17 * 1024,
+        "recorded stdout is {} bytes",
+        snippet.stdout.len()
+    );
+    // The driver keeps the first 256 Ki characters and notes the rest; the
+    // event keeps the start and the end of that.
+    assert_regex_match(
+        r"(?s)^x+…\d+ chars truncated…x+\n\[\.\.\. 1737857 characters omitted \.\.\.\]\n$",
+        &snippet.stdout,
+    );
+
+    let output = done_mock
+        .single_request()
+        .function_call_output_text(call_id)
+        .context("repl output present")?;
+    assert!(
+        output.len() < 64 * 1024,
+        "tool output is {} bytes",
+        output.len()
+    );
+    assert_regex_match(
+        r"(?s)^Total output lines: \d+\n\n\[stdout\]\nx+.*truncated.*1737857 characters omitted",
+        &output,
+    );
+
+    Ok(())
+}
@@ -15,6 +15,7 @@ use codex_core::protocol::ReviewCodeLocation;
 use codex_core::protocol::ReviewFinding;
 use codex_core::protocol::ReviewLineRange;
 use codex_core::protocol::ReviewOutputEvent;
+use codex_core::protocol::ReviewPaths;
 use codex_core::protocol::ReviewRequest;
 use codex_core::protocol::ReviewTarget;
 use codex_core::protocol::RolloutItem;
@@ -84,6 +85,7 @@ async fn review_op_emits_lifecycle_and_review_output() {
                     instructions: "Please review my changes".to_string(),
                 },
                 user_facing_hint: None,
+                paths: None,
             },
         })
         .await
@@ -204,6 +206,7 @@ async fn review_op_with_plain_text_emits_review_fallback() {
                     instructions: "Plain text review".to_string(),
                 },
                 user_facing_hint: None,
+                paths: None,
             },
         })
         .await
@@ -229,6 +232,92 @@ async fn review_op_with_plain_text_emits_review_fallback() {
     server.verify().await;
 }
 
+/// A review limited to some files echoes the filter when it enters and exits
+/// review mode, and drops the findings the model reports outside it.
+// Windows CI only: bump to 4 workers to prevent SSE/event starvation and test timeouts.
+#[cfg_attr(windows, tokio::test(flavor = "multi_thread", worker_threads = 4))]
+#[cfg_attr(not(windows), tokio::test(flavor = "multi_thread", worker_threads = 2))]
+async fn review_op_with_paths_echoes_the_filter() {
+    skip_if_no_network!();
+
+    let codex_home = TempDir::new().unwrap();
+    let cwd = codex_home.path().to_path_buf();
+    let finding = |path: &str| ReviewFinding {
+        title: path.to_string(),
+        body: "Check this.".to_string(),
+        confidence_score: 0.5,
+        priority: 1,
+        code_location: ReviewCodeLocation {
+            absolute_file_path: cwd.join(path),
+            line_range: ReviewLineRange { start: 1, end: 2 },
+        },
+    };
+    let review_json = serde_json::json!({
+        "findings": [finding("src/api/handlers.rs"), finding("src/main.rs")],
+        "overall_correctness": "good",
+        "overall_explanation": "Mostly fine.",
+        "overall_confidence_score": 0.5
+    })
+    .to_string();
+    let sse_raw = r#"[
+        {"type":"response.output_item.done", "item":{
+            "type":"message", "role":"assistant",
+            "content":[{"type":"output_text","text":__REVIEW__}]
+        }},
+        {"type":"response.completed", "response": {"id": "__ID__"}}
+    ]"#
+    .replace("__REVIEW__", &serde_json::to_string(&review_json).unwrap());
+    let (server, _request_log) = start_responses_server_with_sse(&sse_raw, 1).await;
+    let codex = new_conversation_for_server(&server, &codex_home, |config| {
+        config.cwd = cwd.clone();
+    })
+    .await;
+    let paths = ReviewPaths {
+        include: vec!["src/api/**".to_string()],
+        exclude: Vec::new(),
+    };
+
+    codex
+        .submit(Op::Review {
+            review_request: ReviewRequest {
+                target: ReviewTarget::UncommittedChanges,
+                user_facing_hint: None,
+                paths: Some(paths.clone()),
+            },
+        })
+        .await
+        .unwrap();
+
+    let entered =
+        match wait_for_event(&codex, |ev| matches!(ev, EventMsg::EnteredReviewMode(_))).await {
+            EventMsg::EnteredReviewMode(request) => request,
+            other => panic!("expected EnteredReviewMode(..), got {other:?}"),
+        };
+    assert_eq!(entered.paths, Some(paths.clone()));
+    assert_eq!(
+        entered.user_facing_hint.as_deref(),
+        Some("current changes (in src/api/**)")
+    );
+    let exited =
+        match wait_for_event(&codex, |ev| matches!(ev, EventMsg::ExitedReviewMode(_))).await {
+            EventMsg::ExitedReviewMode(ev) => ev,
+            other => panic!("expected ExitedReviewMode(..), got {other:?}"),
+        };
+    assert_eq!(
+        exited.review_output,
+        Some(ReviewOutputEvent {
+            findings: vec![finding("src/api/handlers.rs")],
+            overall_correctness: "good".to_string(),
+            overall_explanation: "Mostly fine.".to_string(),
+            overall_confidence_score: 0.5,
+        })
+    );
+    assert_eq!(exited.paths, Some(paths));
+    let _complete = wait_for_event(&codex, |ev| matches!(ev, EventMsg::TurnComplete(_))).await;
+
+    server.verify().await;
+}
+
 /// Ensure review flow suppresses assistant-specific streaming/completion events:
 /// - AgentMessageContentDelta
 /// - AgentMessageDelta (legacy)
@@ -264,6 +353,7 @@ async fn review_filters_agent_message_related_events() {
                     instructions: "Filter streaming events".to_string(),
                 },
                 user_facing_hint: None,
+                paths: None,
             },
         })
         .await
@@ -345,6 +435,7 @@ async fn review_does_not_emit_agent_message_on_structured_output() {
                     instructions: "check structured".to_string(),
                 },
                 user_facing_hint: None,
+                paths: None,
             },
         })
         .await
@@ -404,6 +495,7 @@ async fn review_uses_custom_review_model_from_config() {
                     instructions: "use custom model".to_string(),
                 },
                 user_facing_hint: None,
+                paths: None,
             },
         })
         .await
@@ -415,7 +507,7 @@ async fn review_uses_custom_review_model_from_config() {
         matches!(
             ev,
             EventMsg::ExitedReviewMode(ExitedReviewModeEvent {
-                review_output: None
+                review_output: None, ..,
             })
         )
     })
@@ -456,6 +548,7 @@ async fn review_uses_session_model_when_review_model_unset() {
                     instructions: "use session model".to_string(),
                 },
                 user_facing_hint: None,
+                paths: None,
             },
         })
         .await
@@ -466,7 +559,7 @@ async fn review_uses_session_model_when_review_model_unset() {
         matches!(
             ev,
             EventMsg::ExitedReviewMode(ExitedReviewModeEvent {
-                review_output: None
+                review_output: None, ..,
             })
         )
     })
@@ -575,6 +668,7 @@ async fn review_input_isolated_from_parent_history() {
                     instructions: review_prompt.clone(),
                 },
                 user_facing_hint: None,
+                paths: None,
             },
         })
         .await
@@ -585,7 +679,7 @@ async fn review_input_isolated_from_parent_history() {
         matches!(
             ev,
             EventMsg::ExitedReviewMode(ExitedReviewModeEvent {
-                review_output: None
+                review_output: None, ..,
             })
         )
     })
@@ -686,6 +780,7 @@ async fn review_history_surfaces_in_parent_session() {
                     instructions: "Start a review".to_string(),
                 },
                 user_facing_hint: None,
+                paths: None,
             },
         })
         .await
@@ -695,7 +790,7 @@ async fn review_history_surfaces_in_parent_session() {
         matches!(
             ev,
             EventMsg::ExitedReviewMode(ExitedReviewModeEvent {
-                review_output: Some(_)
+                review_output: Some(_), ..,
             })
         )
     })
@@ -834,6 +929,7 @@ async fn review_uses_overridden_cwd_for_base_branch_merge_base() {
                     branch: "main".to_string(),
                 },
                 user_facing_hint: None,
+                paths: None,
             },
         })
         .await
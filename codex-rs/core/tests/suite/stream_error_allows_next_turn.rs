@@ -62,6 +62,7 @@ async fn continue_after_stream_error() {
     let provider = ModelProviderInfo {
         name: "mock-openai".into(),
         base_url: Some(format!("{}/v1", server.uri())),
+        fallback_base_urls: None,
         env_key: Some("PATH".into()),
         env_key_instructions: None,
         experimental_bearer_token: None,
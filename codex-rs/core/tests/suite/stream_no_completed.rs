@@ -69,6 +69,7 @@ async fn retries_on_early_close() {
         // Environment variable that should exist in the test environment.
         // ModelClientSession will return an error if the environment variable for the
         // provider is not set.
+        fallback_base_urls: None,
         env_key: Some("PATH".into()),
         env_key_instructions: None,
         experimental_bearer_token: None,
@@ -262,8 +262,9 @@ async fn update_plan_tool_rejects_malformed_payload() -> anyhow::Result<()> {
     let req = second_mock.single_request();
     let (output_text, success_flag) = call_output(&req, call_id);
     assert!(
-        output_text.contains("failed to parse function arguments"),
-        "expected parse error message in output text, got {output_text:?}"
+        output_text.contains("invalid arguments for `update_plan`")
+            && output_text.contains("missing required field `plan`"),
+        "expected validation error message in output text, got {output_text:?}"
     );
     if let Some(success_flag) = success_flag {
         assert!(
@@ -5,6 +5,7 @@ use anyhow::Context;
 use anyhow::Result;
 use codex_core::config::types::McpServerConfig;
 use codex_core::config::types::McpServerTransportConfig;
+use codex_core::features::Feature;
 use codex_core::protocol::AskForApproval;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::Op;
@@ -25,6 +26,7 @@ use core_test_support::skip_if_no_network;
 use core_test_support::stdio_server_bin;
 use core_test_support::test_codex::test_codex;
 use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
 use serde_json::Value;
 use serde_json::json;
 use std::collections::HashMap;
@@ -830,3 +832,85 @@ async fn mcp_tool_call_output_not_truncated_with_custom_limit() -> Result<()> {
 
     Ok(())
 }
+
+// With exec output artifacts, a long output is saved in full and can be read
+// back with `read_artifact`.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn long_output_is_spilled_and_read_back() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let mut builder = test_codex()
+        .with_model("gpt-5.1-codex")
+        .with_config(|config| {
+            config.tool_output_token_limit = Some(200);
+            config.features.enable(Feature::ExecOutputArtifacts);
+        });
+    let fixture = builder.build(&server).await?;
+
+    let call_id = "shell-spill";
+    let args = json!({
+        "command": "seq 1 1000",
+        "timeout_ms": 5_000,
+    });
+    let read_call_id = "read-spill";
+    let read_args = json!({
+        "artifact_id": "shell-spill-output",
+        "start_line": 999,
+    });
+
+    mount_sse_once(
+        &server,
+        sse(vec![
+            ev_response_created("resp-1"),
+            ev_function_call(call_id, "shell_command", &serde_json::to_string(&args)?),
+            ev_completed("resp-1"),
+        ]),
+    )
+    .await;
+    let read_mock = mount_sse_once(
+        &server,
+        sse(vec![
+            ev_response_created("resp-2"),
+            ev_function_call(
+                read_call_id,
+                "read_artifact",
+                &serde_json::to_string(&read_args)?,
+            ),
+            ev_completed("resp-2"),
+        ]),
+    )
+    .await;
+    let done_mock = mount_sse_once(
+        &server,
+        sse(vec![
+            ev_assistant_message("msg-1", "done"),
+            ev_completed("resp-3"),
+        ]),
+    )
+    .await;
+
+    fixture
+        .submit_turn_with_policy("run the shell tool", SandboxPolicy::DangerFullAccess)
+        .await?;
+
+    let output = read_mock
+        .single_request()
+        .function_call_output_text(call_id)
+        .context("shell output present")?;
+    assert_regex_match(
+        r"(?s)tokens truncated.*\[full output \(1000 lines\) saved as artifact `shell-spill-output`",
+        &output,
+    );
+    assert_eq!(
+        done_mock
+            .single_request()
+            .function_call_output_text(read_call_id),
+        Some(
+            "[artifact `shell-spill-output`: lines 999-1000 of 1000]\nL999: 999\nL1000: 1000\n"
+                .to_string()
+        )
+    );
+
+    Ok(())
+}
@@ -99,6 +99,7 @@ impl AppServerClient {
                     title: Some("Debug Client".to_string()),
                     version: env!("CARGO_PKG_VERSION").to_string(),
                 },
+                protocol_versions: None,
             },
         };
 
@@ -174,6 +175,7 @@ impl AppServerClient {
                 sort_key: None,
                 model_providers: None,
                 archived: None,
+                tags: None,
             },
         };
         self.send(&request)?;
@@ -115,6 +115,9 @@ where
             // strict about what is writable.
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
+            max_memory_bytes: None,
+            cpu_rate_percent: None,
+            max_processes: None,
         },
         codex_linux_sandbox_exe,
         sandbox_cwd: writable_folder.as_ref().to_path_buf(),
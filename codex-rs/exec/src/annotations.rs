@@ -0,0 +1,309 @@
+//! GitHub Actions workflow commands for `codex exec --annotate github`.
+//!
+//! Review findings, failed patch applications, and `--output-schema`
+//! problems are translated into `::error` / `::warning` / `::notice`
+//! commands so they show up as inline annotations on the pull request.
+//! Commands are written to stderr, which the Actions runner scans just like
+//! stdout, so the stdout contract of the other output modes is unchanged.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::ExitedReviewModeEvent;
+use codex_core::protocol::PatchApplyEndEvent;
+use codex_core::protocol::ReviewFinding;
+use codex_core::protocol::TurnCompleteEvent;
+
+use crate::cli::AnnotationFormat;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AnnotationLevel {
+    Error,
+    Warning,
+    Notice,
+}
+
+impl AnnotationLevel {
+    fn command(self) -> &'static str {
+        match self {
+            AnnotationLevel::Error => "error",
+            AnnotationLevel::Warning => "warning",
+            AnnotationLevel::Notice => "notice",
+        }
+    }
+
+    /// P0/P1 findings fail loudly, P2 warns, anything lower is informational.
+    fn for_priority(priority: i32) -> Self {
+        match priority {
+            i32::MIN..=1 => AnnotationLevel::Error,
+            2 => AnnotationLevel::Warning,
+            _ => AnnotationLevel::Notice,
+        }
+    }
+}
+
+pub(crate) struct GithubAnnotator {
+    /// Annotation paths are reported relative to this directory.
+    workspace_root: PathBuf,
+    output_schema: Option<PathBuf>,
+}
+
+impl GithubAnnotator {
+    pub(crate) fn new(cwd: &Path, output_schema: Option<PathBuf>) -> Self {
+        let workspace_root = std::env::var_os("GITHUB_WORKSPACE")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| cwd.to_path_buf());
+        Self {
+            workspace_root,
+            output_schema,
+        }
+    }
+
+    pub(crate) fn process_event(&self, msg: &EventMsg) {
+        for annotation in self.annotations_for(msg) {
+            eprintln!("{annotation}");
+        }
+    }
+
+    fn annotations_for(&self, msg: &EventMsg) -> Vec<String> {
+        match msg {
+            EventMsg::ExitedReviewMode(ExitedReviewModeEvent {
+                review_output: Some(review),
+                ..
+            }) => {
+                let mut annotations: Vec<String> = review
+                    .findings
+                    .iter()
+                    .map(|finding| self.review_finding(finding))
+                    .collect();
+                if !review.overall_explanation.is_empty() {
+                    annotations.push(workflow_command(
+                        AnnotationLevel::Notice,
+                        &[(
+                            "title",
+                            format!("Codex review: {}", review.overall_correctness),
+                        )],
+                        &review.overall_explanation,
+                    ));
+                }
+                annotations
+            }
+            EventMsg::PatchApplyEnd(event) if !event.success => self.patch_failure(event),
+            EventMsg::Error(event) if self.is_schema_error(&event.message) => {
+                vec![self.schema_error(&event.message)]
+            }
+            EventMsg::TurnComplete(TurnCompleteEvent {
+                last_agent_message: Some(message),
+            }) if self.output_schema.is_some() => {
+                match serde_json::from_str::<serde_json::Value>(message) {
+                    Ok(_) => Vec::new(),
+                    Err(err) => vec![self.schema_error(&format!(
+                        "Final response is not valid JSON for --output-schema: {err}"
+                    ))],
+                }
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn review_finding(&self, finding: &ReviewFinding) -> String {
+        let location = &finding.code_location;
+        let mut properties = vec![
+            ("file", self.relative_path(&location.absolute_file_path)),
+            ("line", location.line_range.start.to_string()),
+        ];
+        if location.line_range.end > location.line_range.start {
+            properties.push(("endLine", location.line_range.end.to_string()));
+        }
+        properties.push((
+            "title",
+            format!("[P{}] {}", finding.priority, finding.title),
+        ));
+        workflow_command(
+            AnnotationLevel::for_priority(finding.priority),
+            &properties,
+            &finding.body,
+        )
+    }
+
+    fn patch_failure(&self, event: &PatchApplyEndEvent) -> Vec<String> {
+        let output = [event.stderr.trim(), event.stdout.trim()]
+            .into_iter()
+            .find(|output| !output.is_empty())
+            .unwrap_or("apply_patch failed");
+        let title = ("title", "Patch apply failed".to_string());
+        let mut paths: Vec<&PathBuf> = event.changes.keys().collect();
+        if paths.is_empty() {
+            return vec![workflow_command(AnnotationLevel::Error, &[title], output)];
+        }
+        paths.sort();
+        paths
+            .into_iter()
+            .map(|path| {
+                workflow_command(
+                    AnnotationLevel::Error,
+                    &[("file", self.relative_path(path)), title.clone()],
+                    output,
+                )
+            })
+            .collect()
+    }
+
+    fn is_schema_error(&self, message: &str) -> bool {
+        self.output_schema.is_some() && message.to_ascii_lowercase().contains("schema")
+    }
+
+    fn schema_error(&self, message: &str) -> String {
+        let mut properties = Vec::new();
+        if let Some(schema) = &self.output_schema {
+            properties.push(("file", self.relative_path(schema)));
+        }
+        properties.push(("title", "Output schema".to_string()));
+        workflow_command(AnnotationLevel::Error, &properties, message)
+    }
+
+    fn relative_path(&self, path: &Path) -> String {
+        path.strip_prefix(&self.workspace_root)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+}
+
+/// Reports a problem with the `--output-schema` file itself, before the session starts.
+pub(crate) fn emit_schema_file_error(format: Option<AnnotationFormat>, path: &Path, message: &str) {
+    if format == Some(AnnotationFormat::Github) {
+        eprintln!(
+            "{}",
+            workflow_command(
+                AnnotationLevel::Error,
+                &[
+                    ("file", path.display().to_string()),
+                    ("title", "Output schema".to_string()),
+                ],
+                message,
+            )
+        );
+    }
+}
+
+fn workflow_command(
+    level: AnnotationLevel,
+    properties: &[(&str, String)],
+    message: &str,
+) -> String {
+    let properties = properties
+        .iter()
+        .map(|(key, value)| format!("{key}={}", escape_property(value)))
+        .collect::<Vec<_>>()
+        .join(",");
+    let separator = if properties.is_empty() { "" } else { " " };
+    format!(
+        "::{}{separator}{properties}::{}",
+        level.command(),
+        escape_data(message)
+    )
+}
+
+fn escape_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::protocol::FileChange;
+    use codex_core::protocol::ReviewCodeLocation;
+    use codex_core::protocol::ReviewLineRange;
+    use codex_core::protocol::ReviewOutputEvent;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+
+    fn annotator(output_schema: Option<PathBuf>) -> GithubAnnotator {
+        GithubAnnotator {
+            workspace_root: PathBuf::from("/repo"),
+            output_schema,
+        }
+    }
+
+    #[test]
+    fn review_findings_become_line_annotations() {
+        let msg = EventMsg::ExitedReviewMode(ExitedReviewModeEvent {
+            review_output: Some(ReviewOutputEvent {
+                findings: vec![ReviewFinding {
+                    title: "Off-by-one, in loop".to_string(),
+                    body: "Index skips the last element.\nUse `..=`.".to_string(),
+                    confidence_score: 0.9,
+                    priority: 1,
+                    code_location: ReviewCodeLocation {
+                        absolute_file_path: PathBuf::from("/repo/src/lib.rs"),
+                        line_range: ReviewLineRange { start: 10, end: 12 },
+                    },
+                }],
+                overall_correctness: "patch is incorrect".to_string(),
+                overall_explanation: "One bug: 100% reproducible.".to_string(),
+                overall_confidence_score: 0.8,
+            }),
+            paths: None,
+        });
+
+        assert_eq!(
+            annotator(None).annotations_for(&msg),
+            vec![
+                "::error file=src/lib.rs,line=10,endLine=12,title=[P1] Off-by-one%2C in loop::Index skips the last element.%0AUse `..=`."
+                    .to_string(),
+                "::notice title=Codex review%3A patch is incorrect::One bug: 100%25 reproducible."
+                    .to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn failed_patches_annotate_each_file() {
+        let msg = EventMsg::PatchApplyEnd(PatchApplyEndEvent {
+            call_id: "call-1".to_string(),
+            turn_id: "turn-1".to_string(),
+            stdout: String::new(),
+            stderr: "hunk did not apply".to_string(),
+            success: false,
+            changes: HashMap::from([(
+                PathBuf::from("/repo/src/main.rs"),
+                FileChange::Delete {
+                    content: String::new(),
+                },
+            )]),
+        });
+
+        assert_eq!(
+            annotator(None).annotations_for(&msg),
+            vec![
+                "::error file=src/main.rs,title=Patch apply failed::hunk did not apply".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_final_json_is_reported_against_the_schema() {
+        let msg = EventMsg::TurnComplete(TurnCompleteEvent {
+            last_agent_message: Some("not json".to_string()),
+        });
+
+        assert!(annotator(None).annotations_for(&msg).is_empty());
+        let annotations = annotator(Some(PathBuf::from("/repo/schema.json"))).annotations_for(&msg);
+        assert_eq!(annotations.len(), 1);
+        assert!(
+            annotations[0].starts_with(
+                "::error file=schema.json,title=Output schema::Final response is not valid JSON"
+            ),
+            "{annotations:?}"
+        );
+    }
+}
@@ -89,6 +89,38 @@ pub struct Cli {
     )]
     pub json: bool,
 
+    /// Re-run the prompt in the same conversation whenever files matching
+    /// this glob (relative to the working directory) change. May be repeated.
+    #[arg(long = "watch", value_name = "GLOB")]
+    pub watch: Vec<String>,
+
+    /// Quiet period in milliseconds to wait after a change before re-running.
+    #[arg(
+        long = "watch-debounce-ms",
+        value_name = "MS",
+        default_value_t = 500,
+        requires = "watch"
+    )]
+    pub watch_debounce_ms: u64,
+
+    /// Emit workflow-command annotations for review findings, failed patches,
+    /// and output schema errors (e.g. `--annotate github`).
+    #[arg(long = "annotate", value_name = "FORMAT", value_enum)]
+    pub annotate: Option<AnnotationFormat>,
+
+    /// Serve a read-only dashboard of the run's progress at this address
+    /// (e.g. `127.0.0.1:8787`).
+    #[cfg(feature = "dashboard")]
+    #[arg(long = "dashboard", value_name = "ADDR")]
+    pub dashboard: Option<std::net::SocketAddr>,
+
+    /// Token the dashboard's API requires, sent as `Authorization: Bearer`
+    /// or `?token=` (open the page as `http://ADDR/?token=TOKEN`). Required
+    /// to serve the dashboard on a non-loopback address.
+    #[cfg(feature = "dashboard")]
+    #[arg(long = "dashboard-token", value_name = "TOKEN", requires = "dashboard")]
+    pub dashboard_token: Option<String>,
+
     /// Specifies file where the last message from the agent should be written.
     #[arg(long = "output-last-message", short = 'o', value_name = "FILE")]
     pub last_message_file: Option<PathBuf>,
@@ -171,6 +203,14 @@ pub struct ReviewArgs {
     /// Custom review instructions. If `-` is used, read from stdin.
     #[arg(value_name = "PROMPT", value_hint = clap::ValueHint::Other)]
     pub prompt: Option<String>,
+
+    /// Only review files matching this glob, e.g. `src/api/**`. Repeatable.
+    #[arg(long = "path", value_name = "GLOB")]
+    pub include_paths: Vec<String>,
+
+    /// Skip files matching this glob. Repeatable.
+    #[arg(long = "exclude", value_name = "GLOB")]
+    pub exclude_paths: Vec<String>,
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
@@ -181,3 +221,10 @@ pub enum Color {
     #[default]
     Auto,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum AnnotationFormat {
+    /// GitHub Actions workflow commands (`::error file=...,line=...::message`).
+    Github,
+}
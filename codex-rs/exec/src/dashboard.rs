@@ -0,0 +1,427 @@
+//! Read-only progress dashboard for `codex exec --dashboard <ADDR>`.
+//!
+//! Serves one HTML page and `GET /api/state`, a JSON snapshot of the threads
+//! loaded in the run's [`ThreadManager`] (status and session stats, including
+//! token usage and estimated cost), the background terminals still running,
+//! and the most recent events. Nothing served here can change the run, but the
+//! snapshot exposes command lines and working directories, so the dashboard
+//! only binds to a non-loopback address when a token is configured, and then
+//! `/api/state` requires it as `Authorization: Bearer <token>` or `?token=`.
+
+use std::collections::BTreeMap;
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Instant;
+
+use axum::Json;
+use axum::Router;
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::http::StatusCode;
+use axum::http::Uri;
+use axum::http::header::AUTHORIZATION;
+use axum::response::Html;
+use axum::routing::get;
+use codex_core::ThreadManager;
+use codex_core::event_subscription::AgentEvent;
+use codex_core::event_subscription::AgentFilter;
+use codex_core::event_subscription::AllEvents;
+use codex_core::protocol::AgentStatus;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::ExecCommandSource;
+use codex_core::protocol::SessionStats;
+use codex_protocol::ThreadId;
+use futures::Stream;
+use futures::StreamExt;
+use serde::Serialize;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+/// Number of events kept for the "recent events" list.
+const RECENT_EVENTS: usize = 200;
+
+const INDEX_HTML: &str = include_str!("dashboard.html");
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct RecentEvent {
+    seq: u64,
+    /// Milliseconds since the dashboard started.
+    elapsed_ms: u64,
+    thread_id: String,
+    /// The event's `type`, e.g. `exec_command_begin`.
+    kind: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct BackgroundTask {
+    process_id: String,
+    thread_id: String,
+    command: Vec<String>,
+    cwd: PathBuf,
+    /// Milliseconds since the dashboard started, when the task started.
+    started_ms: u64,
+}
+
+/// What the dashboard learns from the event stream.
+#[derive(Debug, Default)]
+struct Activity {
+    next_seq: u64,
+    recent_events: VecDeque<RecentEvent>,
+    background_tasks: BTreeMap<String, BackgroundTask>,
+}
+
+impl Activity {
+    fn record(&mut self, thread_id: ThreadId, msg: &EventMsg, elapsed_ms: u64) {
+        match msg {
+            EventMsg::ExecCommandBegin(ev)
+                if ev.source == ExecCommandSource::UnifiedExecStartup =>
+            {
+                if let Some(process_id) = &ev.process_id {
+                    self.background_tasks.insert(
+                        process_id.clone(),
+                        BackgroundTask {
+                            process_id: process_id.clone(),
+                            thread_id: thread_id.to_string(),
+                            command: ev.command.clone(),
+                            cwd: ev.cwd.clone(),
+                            started_ms: elapsed_ms,
+                        },
+                    );
+                }
+            }
+            EventMsg::ExecCommandEnd(ev) => {
+                if let Some(process_id) = &ev.process_id {
+                    self.background_tasks.remove(process_id);
+                }
+            }
+            _ => {}
+        }
+
+        let Some(kind) = event_kind(msg) else {
+            return;
+        };
+        if self.recent_events.len() == RECENT_EVENTS {
+            self.recent_events.pop_front();
+        }
+        self.recent_events.push_back(RecentEvent {
+            seq: self.next_seq,
+            elapsed_ms,
+            thread_id: thread_id.to_string(),
+            kind,
+        });
+        self.next_seq += 1;
+    }
+}
+
+/// The event's `type`, or `None` for streaming deltas, which would push
+/// everything else out of the list.
+fn event_kind(msg: &EventMsg) -> Option<String> {
+    match msg {
+        EventMsg::AgentMessageDelta(_)
+        | EventMsg::AgentReasoningDelta(_)
+        | EventMsg::AgentReasoningRawContentDelta(_)
+        | EventMsg::ExecCommandOutputDelta(_)
+        | EventMsg::AgentMessageContentDelta(_)
+        | EventMsg::ReasoningContentDelta(_)
+        | EventMsg::ReasoningRawContentDelta(_) => None,
+        // The only variants whose `type` is not their snake_case name.
+        EventMsg::TurnStarted(_) => Some("task_started".to_string()),
+        EventMsg::TurnComplete(_) => Some("task_complete".to_string()),
+        msg => Some(msg.to_string()),
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ThreadSnapshot {
+    thread_id: String,
+    status: AgentStatus,
+    stats: SessionStats,
+}
+
+#[derive(Debug, Serialize)]
+struct DashboardSnapshot {
+    elapsed_ms: u64,
+    threads: Vec<ThreadSnapshot>,
+    background_tasks: Vec<BackgroundTask>,
+    /// Newest first.
+    recent_events: Vec<RecentEvent>,
+}
+
+#[derive(Clone)]
+struct Dashboard {
+    thread_manager: Arc<ThreadManager>,
+    activity: Arc<Mutex<Activity>>,
+    started_at: Instant,
+    token: Option<Arc<str>>,
+}
+
+impl Dashboard {
+    fn elapsed_ms(&self) -> u64 {
+        u64::try_from(self.started_at.elapsed().as_millis()).unwrap_or(u64::MAX)
+    }
+
+    async fn snapshot(&self) -> DashboardSnapshot {
+        let mut threads = Vec::new();
+        for thread_id in self.thread_manager.list_thread_ids().await {
+            let Ok(thread) = self.thread_manager.get_thread(thread_id).await else {
+                continue;
+            };
+            threads.push(ThreadSnapshot {
+                thread_id: thread_id.to_string(),
+                status: thread.agent_status().await,
+                stats: thread.session_stats().await,
+            });
+        }
+        let activity = self.activity.lock().await;
+        DashboardSnapshot {
+            elapsed_ms: self.elapsed_ms(),
+            threads,
+            background_tasks: activity.background_tasks.values().cloned().collect(),
+            recent_events: activity.recent_events.iter().rev().cloned().collect(),
+        }
+    }
+}
+
+/// Starts serving the dashboard on `addr` and returns the address it is
+/// bound to. The server runs until the process exits. Addresses other than
+/// loopback are refused unless `token` is set.
+pub(crate) async fn serve(
+    addr: SocketAddr,
+    token: Option<String>,
+    thread_manager: Arc<ThreadManager>,
+) -> anyhow::Result<SocketAddr> {
+    if !addr.ip().is_loopback() && token.is_none() {
+        anyhow::bail!(
+            "refusing to serve the dashboard on {addr} without --dashboard-token; bind it to a loopback address or set a token"
+        );
+    }
+    let dashboard = Dashboard {
+        thread_manager: Arc::clone(&thread_manager),
+        activity: Arc::new(Mutex::new(Activity::default())),
+        started_at: Instant::now(),
+        token: token.map(Arc::from),
+    };
+    let events = thread_manager.subscribe::<AllEvents>(AgentFilter::any());
+    tokio::spawn(record_activity(events, dashboard.clone()));
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    let local_addr = listener.local_addr()?;
+    let app = Router::new()
+        .route("/", get(index))
+        .route("/api/state", get(state))
+        .with_state(dashboard);
+    tokio::spawn(async move {
+        if let Err(err) = axum::serve(listener, app).await {
+            warn!("dashboard server stopped: {err}");
+        }
+    });
+    Ok(local_addr)
+}
+
+async fn record_activity(
+    events: impl Stream<Item = AgentEvent<EventMsg>> + Send + 'static,
+    dashboard: Dashboard,
+) {
+    let mut events = std::pin::pin!(events);
+    while let Some(event) = events.next().await {
+        let elapsed_ms = dashboard.elapsed_ms();
+        dashboard
+            .activity
+            .lock()
+            .await
+            .record(event.agent_id, &event.event, elapsed_ms);
+    }
+}
+
+async fn index() -> Html<&'static str> {
+    Html(INDEX_HTML)
+}
+
+async fn state(
+    State(dashboard): State<Dashboard>,
+    headers: HeaderMap,
+    uri: Uri,
+) -> Result<Json<DashboardSnapshot>, StatusCode> {
+    if !is_authorized(dashboard.token.as_deref(), &headers, &uri) {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    Ok(Json(dashboard.snapshot().await))
+}
+
+/// Whether the request carries `expected` as a bearer token or a `token`
+/// query parameter. Everything is allowed when no token is configured.
+fn is_authorized(expected: Option<&str>, headers: &HeaderMap, uri: &Uri) -> bool {
+    let Some(expected) = expected else {
+        return true;
+    };
+    let bearer = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let query = uri.query().into_iter().flat_map(|query| query.split('&'));
+    bearer
+        .into_iter()
+        .chain(query.filter_map(|pair| pair.strip_prefix("token=")))
+        .any(|candidate| tokens_match(candidate, expected))
+}
+
+/// Compares tokens in time that does not depend on where they differ.
+fn tokens_match(candidate: &str, expected: &str) -> bool {
+    candidate.len() == expected.len()
+        && candidate
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::protocol::AgentMessageDeltaEvent;
+    use codex_core::protocol::ExecCommandBeginEvent;
+    use codex_core::protocol::ExecCommandEndEvent;
+    use codex_core::protocol::TurnCompleteEvent;
+    use codex_core::protocol::TurnStartedEvent;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    fn begin(process_id: &str) -> EventMsg {
+        EventMsg::ExecCommandBegin(ExecCommandBeginEvent {
+            call_id: "call-1".to_string(),
+            process_id: Some(process_id.to_string()),
+            turn_id: "turn-1".to_string(),
+            command: vec!["npm".to_string(), "run".to_string(), "dev".to_string()],
+            cwd: PathBuf::from("/repo"),
+            parsed_cmd: Vec::new(),
+            source: ExecCommandSource::UnifiedExecStartup,
+            interaction_input: None,
+        })
+    }
+
+    fn end(process_id: &str) -> EventMsg {
+        EventMsg::ExecCommandEnd(ExecCommandEndEvent {
+            call_id: "call-1".to_string(),
+            process_id: Some(process_id.to_string()),
+            turn_id: "turn-1".to_string(),
+            command: vec!["npm".to_string(), "run".to_string(), "dev".to_string()],
+            cwd: PathBuf::from("/repo"),
+            parsed_cmd: Vec::new(),
+            source: ExecCommandSource::UnifiedExecStartup,
+            interaction_input: None,
+            stdout: String::new(),
+            stderr: String::new(),
+            aggregated_output: String::new(),
+            exit_code: 0,
+            duration: Duration::from_secs(1),
+            formatted_output: String::new(),
+        })
+    }
+
+    #[test]
+    fn api_requires_the_configured_token() {
+        let uri = |uri: &str| uri.parse::<Uri>().expect("uri");
+        let mut bearer = HeaderMap::new();
+        bearer.insert(AUTHORIZATION, "Bearer s3cret".parse().expect("header"));
+        let mut wrong = HeaderMap::new();
+        wrong.insert(AUTHORIZATION, "Bearer s3crex".parse().expect("header"));
+        let none = HeaderMap::new();
+
+        assert_eq!(
+            [
+                is_authorized(None, &none, &uri("/api/state")),
+                is_authorized(Some("s3cret"), &none, &uri("/api/state")),
+                is_authorized(Some("s3cret"), &bearer, &uri("/api/state")),
+                is_authorized(Some("s3cret"), &wrong, &uri("/api/state")),
+                is_authorized(Some("s3cret"), &none, &uri("/api/state?a=1&token=s3cret")),
+                is_authorized(Some("s3cret"), &none, &uri("/api/state?token=s3c")),
+            ],
+            [true, false, true, false, true, false]
+        );
+    }
+
+    #[test]
+    fn tracks_background_terminals_until_they_exit() {
+        let thread_id = ThreadId::new();
+        let mut activity = Activity::default();
+
+        activity.record(thread_id, &begin("1000"), 5);
+        assert_eq!(
+            activity
+                .background_tasks
+                .values()
+                .cloned()
+                .collect::<Vec<_>>(),
+            vec![BackgroundTask {
+                process_id: "1000".to_string(),
+                thread_id: thread_id.to_string(),
+                command: vec!["npm".to_string(), "run".to_string(), "dev".to_string()],
+                cwd: PathBuf::from("/repo"),
+                started_ms: 5,
+            }]
+        );
+
+        activity.record(thread_id, &end("1000"), 9);
+        assert!(activity.background_tasks.is_empty());
+    }
+
+    #[test]
+    fn event_kinds_match_the_wire_type_and_skip_deltas() {
+        let events = [
+            begin("1000"),
+            end("1000"),
+            EventMsg::TurnStarted(TurnStartedEvent {
+                model_context_window: None,
+            }),
+            EventMsg::TurnComplete(TurnCompleteEvent {
+                last_agent_message: None,
+            }),
+            EventMsg::ShutdownComplete,
+        ];
+        let wire_type = |msg: &EventMsg| {
+            serde_json::to_value(msg).expect("serialize")["type"]
+                .as_str()
+                .map(ToString::to_string)
+        };
+
+        assert_eq!(
+            events.iter().map(event_kind).collect::<Vec<_>>(),
+            events.iter().map(wire_type).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            event_kind(&EventMsg::AgentMessageDelta(AgentMessageDeltaEvent {
+                delta: "hi".to_string(),
+                item_id: None,
+            })),
+            None
+        );
+    }
+
+    #[test]
+    fn keeps_only_the_most_recent_events() {
+        let thread_id = ThreadId::new();
+        let mut activity = Activity::default();
+        let turn_started = EventMsg::TurnStarted(TurnStartedEvent {
+            model_context_window: None,
+        });
+
+        for elapsed_ms in 0..(RECENT_EVENTS as u64 + 5) {
+            activity.record(thread_id, &turn_started, elapsed_ms);
+        }
+
+        assert_eq!(activity.recent_events.len(), RECENT_EVENTS);
+        assert_eq!(
+            activity.recent_events.front().map(|event| event.seq),
+            Some(5)
+        );
+        assert_eq!(
+            activity
+                .recent_events
+                .back()
+                .map(|event| event.kind.as_str()),
+            Some("task_started")
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use std::path::Path;
+use std::path::PathBuf;
 
 use codex_core::config::Config;
 use codex_core::protocol::Event;
@@ -22,6 +23,9 @@ pub(crate) trait EventProcessor {
     /// Handle a single event emitted by the agent.
     fn process_event(&mut self, event: Event) -> CodexStatus;
 
+    /// Report that file changes re-triggered the prompt in `--watch` mode.
+    fn process_watch_trigger(&mut self, changed: &[PathBuf]);
+
     fn print_final_output(&mut self) {}
 }
 
@@ -23,6 +23,7 @@ use codex_core::protocol::TurnCompleteEvent;
 use codex_core::protocol::TurnDiffEvent;
 use codex_core::protocol::WarningEvent;
 use codex_core::protocol::WebSearchEndEvent;
+use codex_protocol::items::ProviderTool;
 use codex_protocol::num_format::format_with_separators;
 use owo_colors::OwoColorize;
 use owo_colors::Style;
@@ -146,6 +147,9 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             "session id",
             session_configured_event.session_id.to_string(),
         ));
+        if session_configured_event.incognito {
+            entries.push(("incognito", "nothing is saved to disk".to_string()));
+        }
 
         for (key, value) in entries {
             eprintln!("{} {}", format!("{key}:").style(self.bold), value);
@@ -162,9 +166,18 @@ impl EventProcessor for EventProcessorWithHumanOutput {
     fn process_event(&mut self, event: Event) -> CodexStatus {
         let Event { id: _, msg } = event;
         match msg {
-            EventMsg::Error(ErrorEvent { message, .. }) => {
+            EventMsg::Error(ErrorEvent {
+                message,
+                fingerprint,
+                ..
+            }) => {
                 let prefix = "ERROR:".style(self.red);
                 ts_msg!(self, "{prefix} {message}");
+                if let Some(note) =
+                    fingerprint.and_then(|fingerprint| fingerprint.recurrence_note())
+                {
+                    ts_msg!(self, "{}", note.style(self.dimmed));
+                }
             }
             EventMsg::Warning(WarningEvent { message }) => {
                 ts_msg!(
@@ -173,6 +186,92 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     "warning:".style(self.yellow).style(self.bold)
                 );
             }
+            EventMsg::ResumeContext(ev) => {
+                for change in &ev.changes {
+                    ts_msg!(
+                        self,
+                        "{} {}",
+                        "resume:".style(self.cyan).style(self.bold),
+                        change.summary()
+                    );
+                }
+            }
+            EventMsg::ModelSwitch(ev) => {
+                ts_msg!(
+                    self,
+                    "{} {}",
+                    "model:".style(self.cyan).style(self.bold),
+                    ev.summary()
+                );
+            }
+            EventMsg::ProviderEndpoint(ev) => {
+                ts_msg!(
+                    self,
+                    "{} {}",
+                    "endpoint:".style(self.cyan).style(self.bold),
+                    ev.summary()
+                );
+            }
+            EventMsg::SecretsMasked(ev) => {
+                ts_msg!(
+                    self,
+                    "{} masked {} likely secret(s) in the output of {} before sending it to the model",
+                    "secrets:".style(self.yellow).style(self.bold),
+                    ev.total(),
+                    ev.call_id
+                );
+            }
+            EventMsg::KnowledgeInjected(ev) => {
+                let names = ev
+                    .snippets
+                    .iter()
+                    .map(|snippet| snippet.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                ts_msg!(
+                    self,
+                    "{} added {names}",
+                    "knowledge:".style(self.cyan).style(self.bold)
+                );
+                if !ev.over_budget.is_empty() {
+                    ts_msg!(
+                        self,
+                        "{} left out {} (over budget)",
+                        "knowledge:".style(self.cyan).style(self.bold),
+                        ev.over_budget.join(", ")
+                    );
+                }
+            }
+            EventMsg::ConversationMerged(ev) => {
+                ts_msg!(
+                    self,
+                    "{} merged {} ({} turn diff(s), {} artifact(s))",
+                    "merge:".style(self.cyan).style(self.bold),
+                    ev.rollout_path.display(),
+                    ev.diff_turns.len(),
+                    ev.artifacts.len()
+                );
+            }
+            EventMsg::ExplorationFindings(ev) => {
+                let outcome = if ev.budget_exhausted {
+                    "stopped by its budget"
+                } else {
+                    "finished"
+                };
+                ts_msg!(
+                    self,
+                    "{} exploration {outcome}; findings pinned for the next turns\n{}",
+                    "explore:".style(self.magenta).style(self.bold),
+                    ev.findings
+                );
+            }
+            EventMsg::ExplorationFindingsCleared => {
+                ts_msg!(
+                    self,
+                    "{} findings unpinned",
+                    "explore:".style(self.magenta).style(self.bold)
+                );
+            }
             EventMsg::DeprecationNotice(DeprecationNoticeEvent { summary, details }) => {
                 ts_msg!(
                     self,
@@ -269,7 +368,7 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                 }
                 eprintln!();
             }
-            EventMsg::AgentReasoningRawContent(AgentReasoningRawContentEvent { text }) => {
+            EventMsg::AgentReasoningRawContent(AgentReasoningRawContentEvent { text, .. }) => {
                 if self.show_raw_agent_reasoning {
                     ts_msg!(
                         self,
@@ -279,7 +378,7 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     );
                 }
             }
-            EventMsg::AgentMessage(AgentMessageEvent { message }) => {
+            EventMsg::AgentMessage(AgentMessageEvent { message, .. }) => {
                 ts_msg!(
                     self,
                     "{}\n{}",
@@ -338,6 +437,7 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     result,
                     invocation,
                     duration,
+                    error: _,
                 } = tool_call_end_event;
 
                 let duration = format!(" in {}", format_duration(duration));
@@ -364,6 +464,21 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             EventMsg::WebSearchEnd(WebSearchEndEvent { call_id: _, query }) => {
                 ts_msg!(self, "🌐 Searched: {query}");
             }
+            EventMsg::ProviderToolCallEnd(ev) => {
+                let label = match ev.tool {
+                    ProviderTool::CodeInterpreter => "code interpreter:",
+                    ProviderTool::FileSearch => "file search:",
+                };
+                ts_msg!(self, "{}", label.style(self.magenta).style(self.bold));
+                for line in ev
+                    .input
+                    .lines()
+                    .chain(ev.output.lines())
+                    .take(MAX_OUTPUT_LINES_FOR_EXEC_TOOL_CALL)
+                {
+                    eprintln!("{}", line.style(self.dimmed));
+                }
+            }
             EventMsg::PatchApplyBegin(PatchApplyBeginEvent {
                 call_id,
                 auto_approved,
@@ -390,13 +505,16 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                 // it's easy to scan in the terminal output.
                 for (path, change) in changes.iter() {
                     match change {
-                        FileChange::Add { content } => {
+                        FileChange::Add { content, mode } => {
                             let header = format!(
                                 "{} {}",
                                 format_file_change(change),
                                 path.to_string_lossy()
                             );
                             eprintln!("{}", header.style(self.magenta));
+                            if let Some(mode) = mode {
+                                eprintln!("mode {mode}");
+                            }
                             for line in content.lines() {
                                 eprintln!("{}", line.style(self.green));
                             }
@@ -415,6 +533,7 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                         FileChange::Update {
                             unified_diff,
                             move_path,
+                            mode,
                         } => {
                             let header = if let Some(dest) = move_path {
                                 format!(
@@ -427,6 +546,9 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                                 format!("{} {}", format_file_change(change), path.to_string_lossy())
                             };
                             eprintln!("{}", header.style(self.magenta));
+                            if let Some(mode) = mode {
+                                eprintln!("mode {mode}");
+                            }
 
                             // Colorize diff lines. We keep file header lines
                             // (--- / +++) without extra coloring so they are
@@ -443,6 +565,15 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                                 }
                             }
                         }
+                        FileChange::Symlink { target } => {
+                            let header = format!(
+                                "{} {} -> {}",
+                                format_file_change(change),
+                                path.to_string_lossy(),
+                                target.to_string_lossy()
+                            );
+                            eprintln!("{}", header.style(self.magenta));
+                        }
                     }
                 }
             }
@@ -568,8 +699,20 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     ts_msg!(self, "task aborted: review ended");
                 }
             },
-            EventMsg::ContextCompacted(_) => {
-                ts_msg!(self, "context compacted");
+            EventMsg::TurnInterrupted(event) => {
+                if let Some(summary) = event.summary() {
+                    ts_msg!(self, "{}", summary.style(self.dimmed));
+                }
+            }
+            EventMsg::Heartbeat(event) => {
+                ts_msg!(
+                    self,
+                    "{}",
+                    format!("still {}", event.summary()).style(self.dimmed)
+                );
+            }
+            EventMsg::ContextCompacted(event) => {
+                ts_msg!(self, "{}", event.summary());
             }
             EventMsg::CollabAgentSpawnBegin(_)
             | EventMsg::CollabAgentSpawnEnd(_)
@@ -591,6 +734,10 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             | EventMsg::McpListToolsResponse(_)
             | EventMsg::ListCustomPromptsResponse(_)
             | EventMsg::ListSkillsResponse(_)
+            | EventMsg::SandboxPolicyReport(_)
+            | EventMsg::ToolArtifact(_)
+            | EventMsg::ProviderRateLimits(_)
+            | EventMsg::TokenForecast(_)
             | EventMsg::RawResponseItem(_)
             | EventMsg::UserMessage(_)
             | EventMsg::EnteredReviewMode(_)
@@ -607,11 +754,29 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             | EventMsg::UndoCompleted(_)
             | EventMsg::UndoStarted(_)
             | EventMsg::ThreadRolledBack(_)
+            | EventMsg::TurnsTrimmed(_)
+            | EventMsg::ClipStored(_)
+            | EventMsg::FeatureOverridden(_)
+            | EventMsg::ReplSnippet(_)
             | EventMsg::RequestUserInput(_) => {}
         }
         CodexStatus::Running
     }
 
+    fn process_watch_trigger(&mut self, changed: &[PathBuf]) {
+        let files = changed
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        ts_msg!(
+            self,
+            "{} {}",
+            "files changed, re-running prompt:".style(self.magenta),
+            files.style(self.dimmed)
+        );
+    }
+
     fn print_final_output(&mut self) {
         if let Some(usage_info) = &self.last_total_token_usage {
             eprintln!(
@@ -642,7 +807,7 @@ fn escape_command(command: &[String]) -> String {
 
 fn format_file_change(change: &FileChange) -> &'static str {
     match change {
-        FileChange::Add { .. } => "A",
+        FileChange::Add { .. } | FileChange::Symlink { .. } => "A",
         FileChange::Delete { .. } => "D",
         FileChange::Update {
             move_path: Some(_), ..
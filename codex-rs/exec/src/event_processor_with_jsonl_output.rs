@@ -32,6 +32,7 @@ use crate::exec_events::TurnCompletedEvent;
 use crate::exec_events::TurnFailedEvent;
 use crate::exec_events::TurnStartedEvent;
 use crate::exec_events::Usage;
+use crate::exec_events::WatchTriggeredEvent;
 use crate::exec_events::WebSearchItem;
 use codex_core::config::Config;
 use codex_core::protocol;
@@ -353,7 +354,9 @@ impl EventProcessorWithJsonOutput {
 
     fn map_change_kind(&self, kind: &protocol::FileChange) -> PatchChangeKind {
         match kind {
-            protocol::FileChange::Add { .. } => PatchChangeKind::Add,
+            protocol::FileChange::Add { .. } | protocol::FileChange::Symlink { .. } => {
+                PatchChangeKind::Add
+            }
             protocol::FileChange::Delete { .. } => PatchChangeKind::Delete,
             protocol::FileChange::Update { .. } => PatchChangeKind::Update,
         }
@@ -520,6 +523,20 @@ impl EventProcessor for EventProcessorWithJsonOutput {
         });
     }
 
+    #[allow(clippy::print_stdout)]
+    fn process_watch_trigger(&mut self, changed: &[PathBuf]) {
+        let event = ThreadEvent::WatchTriggered(WatchTriggeredEvent {
+            paths: changed
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect(),
+        });
+        match serde_json::to_string(&event) {
+            Ok(line) => println!("{line}"),
+            Err(e) => error!("Failed to serialize event: {e:?}"),
+        }
+    }
+
     #[allow(clippy::print_stdout)]
     fn process_event(&mut self, event: protocol::Event) -> CodexStatus {
         let aggregated = self.collect_thread_events(&event);
@@ -0,0 +1,133 @@
+//! TypeScript definitions and JSON Schema for the `codex exec --json` events,
+//! generated from [`crate::exec_events`] so SDKs in other languages can be
+//! typed against the source of truth.
+
+use std::ffi::OsStr;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use schemars::schema_for;
+use ts_rs::TS;
+
+use crate::exec_events::ThreadEvent;
+
+const HEADER: &str = "// GENERATED CODE! DO NOT MODIFY BY HAND!\n\n";
+
+/// Name of the JSON Schema file describing one JSONL line.
+pub const EVENT_SCHEMA_FILE_NAME: &str = "ThreadEvent.json";
+
+/// Writes TypeScript definitions and the JSON Schema of every event to
+/// `out_dir`, formatting the TypeScript with `prettier` when given.
+pub fn generate_types(out_dir: &Path, prettier: Option<&Path>) -> Result<()> {
+    generate_ts(out_dir, prettier)?;
+    generate_json(out_dir)?;
+    Ok(())
+}
+
+/// Writes one `.ts` file per type reachable from [`ThreadEvent`] and an
+/// `index.ts` that re-exports them.
+pub fn generate_ts(out_dir: &Path, prettier: Option<&Path>) -> Result<()> {
+    ensure_dir(out_dir)?;
+    ThreadEvent::export_all_to(out_dir)?;
+
+    let mut index = String::from(HEADER);
+    for file in ts_files_in(out_dir)? {
+        prepend_header_if_missing(&file)?;
+        if let Some(name) = file.file_stem().and_then(OsStr::to_str)
+            && name != "index"
+        {
+            index.push_str(&format!("export type {{ {name} }} from \"./{name}\";\n"));
+        }
+    }
+    let index_path = out_dir.join("index.ts");
+    fs::write(&index_path, index)
+        .with_context(|| format!("Failed to write {}", index_path.display()))?;
+
+    if let Some(prettier_bin) = prettier {
+        let status = Command::new(prettier_bin)
+            .arg("--write")
+            .arg("--log-level")
+            .arg("warn")
+            .args(ts_files_in(out_dir)?)
+            .status()
+            .with_context(|| format!("Failed to invoke Prettier at {}", prettier_bin.display()))?;
+        if !status.success() {
+            return Err(anyhow!("Prettier failed with status {status}"));
+        }
+    }
+    Ok(())
+}
+
+/// Writes the JSON Schema of a JSONL line, with every nested type under
+/// `definitions`.
+pub fn generate_json(out_dir: &Path) -> Result<()> {
+    ensure_dir(out_dir)?;
+    let path = out_dir.join(EVENT_SCHEMA_FILE_NAME);
+    let json = serde_json::to_vec_pretty(&schema_for!(ThreadEvent))?;
+    fs::write(&path, json).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+fn ensure_dir(dir: &Path) -> Result<()> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create output directory {}", dir.display()))
+}
+
+fn prepend_header_if_missing(path: &Path) -> Result<()> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    if content.starts_with(HEADER) {
+        return Ok(());
+    }
+    fs::write(path, format!("{HEADER}{content}"))
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn ts_files_in(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in
+        fs::read_dir(dir).with_context(|| format!("Failed to read dir {}", dir.display()))?
+    {
+        let path = entry?.path();
+        if path.is_file() && path.extension() == Some(OsStr::new("ts")) {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::Value;
+
+    #[test]
+    fn generates_ts_and_json_schema_for_every_event() -> Result<()> {
+        let out_dir = tempfile::tempdir()?;
+        generate_types(out_dir.path(), None)?;
+
+        let thread_event = fs::read_to_string(out_dir.path().join("ThreadEvent.ts"))?;
+        assert!(thread_event.starts_with(HEADER));
+        assert!(thread_event.contains("item.completed"));
+        let index = fs::read_to_string(out_dir.path().join("index.ts"))?;
+        assert!(index.contains("export type { ThreadItemDetails } from \"./ThreadItemDetails\";"));
+
+        let schema: Value =
+            serde_json::from_slice(&fs::read(out_dir.path().join(EVENT_SCHEMA_FILE_NAME))?)?;
+        assert_eq!(schema["title"], "ThreadEvent");
+        for definition in ["ThreadItem", "ThreadErrorEvent", "Usage"] {
+            assert!(
+                schema["definitions"].get(definition).is_some(),
+                "missing {definition}"
+            );
+        }
+        Ok(())
+    }
+}
@@ -4,12 +4,18 @@
 // For both modes, any other output must be written to stderr.
 #![deny(clippy::print_stdout)]
 
+mod annotations;
 mod cli;
+#[cfg(feature = "dashboard")]
+mod dashboard;
 mod event_processor;
 mod event_processor_with_human_output;
 pub mod event_processor_with_jsonl_output;
 pub mod exec_events;
+pub mod export;
+mod watch;
 
+pub use cli::AnnotationFormat;
 pub use cli::Cli;
 pub use cli::Command;
 pub use cli::ReviewArgs;
@@ -34,6 +40,7 @@ use codex_core::protocol::AskForApproval;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::Op;
+use codex_core::protocol::ReviewPaths;
 use codex_core::protocol::ReviewRequest;
 use codex_core::protocol::ReviewTarget;
 use codex_core::protocol::SessionSource;
@@ -47,6 +54,8 @@ use serde_json::Value;
 use std::io::IsTerminal;
 use std::io::Read;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 use supports_color::Stream;
 use tracing::debug;
 use tracing::error;
@@ -57,6 +66,7 @@ use tracing_subscriber::prelude::*;
 use crate::cli::Command as ExecCommand;
 use crate::event_processor::CodexStatus;
 use crate::event_processor::EventProcessor;
+use crate::watch::WorkspaceWatcher;
 use codex_core::default_client::set_default_originator;
 use codex_core::find_thread_path_by_id_str;
 
@@ -89,11 +99,18 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         add_dir,
         color,
         last_message_file,
+        annotate,
+        watch: watch_patterns,
+        watch_debounce_ms,
         json: json_mode,
         sandbox_mode: sandbox_mode_cli_arg,
         prompt,
         output_schema: output_schema_path,
         config_overrides,
+        #[cfg(feature = "dashboard")]
+        dashboard,
+        #[cfg(feature = "dashboard")]
+        dashboard_token,
     } = cli;
 
     let (stdout_with_ansi, stderr_with_ansi) = match color {
@@ -296,6 +313,9 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
     let default_effort = config.model_reasoning_effort;
     let default_summary = config.model_reasoning_summary;
 
+    let annotator = (annotate == Some(AnnotationFormat::Github))
+        .then(|| annotations::GithubAnnotator::new(&default_cwd, output_schema_path.clone()));
+
     if !skip_git_repo_check && get_git_repo_root(&default_cwd).is_none() {
         eprintln!("Not inside a trusted directory and --skip-git-repo-check was not specified.");
         std::process::exit(1);
@@ -306,11 +326,16 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         true,
         config.cli_auth_credentials_store_mode,
     );
-    let thread_manager = ThreadManager::new(
+    let thread_manager = Arc::new(ThreadManager::new(
         config.codex_home.clone(),
         auth_manager.clone(),
         SessionSource::Exec,
-    );
+    ));
+    #[cfg(feature = "dashboard")]
+    if let Some(addr) = dashboard {
+        let addr = dashboard::serve(addr, dashboard_token, Arc::clone(&thread_manager)).await?;
+        eprintln!("Serving the dashboard at http://{addr}/");
+    }
     let default_model = thread_manager
         .get_models_manager()
         .get_default_model(&config.model, &config, RefreshStrategy::OnlineIfUncached)
@@ -337,7 +362,10 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
     let (initial_operation, prompt_summary) = match (command, prompt, images) {
         (Some(ExecCommand::Review(review_cli)), _, _) => {
             let review_request = build_review_request(review_cli)?;
-            let summary = codex_core::review_prompts::user_facing_hint(&review_request.target);
+            let summary = codex_core::review_prompts::scoped_user_facing_hint(
+                &review_request.target,
+                review_request.paths.as_ref(),
+            );
             (InitialOperation::Review { review_request }, summary)
         }
         (Some(ExecCommand::Resume(args)), root_prompt, imgs) => {
@@ -363,7 +391,7 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
                 // CLI input doesn't track UI element ranges, so none are available here.
                 text_elements: Vec::new(),
             });
-            let output_schema = load_output_schema(output_schema_path.clone());
+            let output_schema = load_output_schema(output_schema_path.clone(), annotate);
             (
                 InitialOperation::UserTurn {
                     items,
@@ -383,7 +411,7 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
                 // CLI input doesn't track UI element ranges, so none are available here.
                 text_elements: Vec::new(),
             });
-            let output_schema = load_output_schema(output_schema_path);
+            let output_schema = load_output_schema(output_schema_path.clone(), annotate);
             (
                 InitialOperation::UserTurn {
                     items,
@@ -439,29 +467,46 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         });
     }
 
+    // In watch mode the same turn is resubmitted whenever the workspace changes.
+    let mut watch = None;
     match initial_operation {
         InitialOperation::UserTurn {
             items,
             output_schema,
         } => {
-            let task_id = thread
-                .submit(Op::UserTurn {
-                    items,
-                    cwd: default_cwd,
-                    approval_policy: default_approval_policy,
-                    sandbox_policy: default_sandbox_policy.clone(),
-                    model: default_model,
-                    effort: default_effort,
-                    summary: default_summary,
-                    final_output_json_schema: output_schema,
-                    collaboration_mode: None,
-                    personality: None,
-                })
-                .await?;
+            let user_turn = Op::UserTurn {
+                items,
+                cwd: default_cwd.clone(),
+                approval_policy: default_approval_policy,
+                sandbox_policy: default_sandbox_policy.clone(),
+                model: default_model,
+                effort: default_effort,
+                summary: default_summary,
+                final_output_json_schema: output_schema,
+                collaboration_mode: None,
+                personality: None,
+            };
+            if !watch_patterns.is_empty() {
+                // Files Codex writes itself must not re-trigger the prompt.
+                let codex_paths: Vec<PathBuf> = std::iter::once(config.codex_home.clone())
+                    .chain(last_message_file.clone())
+                    .collect();
+                let watcher = WorkspaceWatcher::start(
+                    &default_cwd,
+                    &watch_patterns,
+                    Duration::from_millis(watch_debounce_ms),
+                    &codex_paths,
+                )?;
+                watch = Some((watcher, user_turn.clone()));
+            }
+            let task_id = thread.submit(user_turn).await?;
             info!("Sent prompt with event ID: {task_id}");
             task_id
         }
         InitialOperation::Review { review_request } => {
+            if !watch_patterns.is_empty() {
+                anyhow::bail!("--watch cannot be used with `codex exec review`");
+            }
             let task_id = thread.submit(Op::Review { review_request }).await?;
             info!("Sent review request with event ID: {task_id}");
             task_id
@@ -472,7 +517,39 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
     // Track whether a fatal error was reported by the server so we can
     // exit with a non-zero status for automation-friendly signaling.
     let mut error_seen = false;
-    while let Some(event) = rx.recv().await {
+    let mut turn_running = true;
+    let mut rerun_pending = false;
+    loop {
+        let event = tokio::select! {
+            event = rx.recv() => match event {
+                Some(event) => event,
+                None => break,
+            },
+            Some(changed) = next_watch_trigger(&mut watch) => {
+                event_processor.process_watch_trigger(&changed);
+                if turn_running {
+                    // Cancel the stale turn; it is resubmitted once the abort lands.
+                    rerun_pending = true;
+                    thread.submit(Op::Interrupt).await?;
+                } else if let Some((_, user_turn)) = watch.as_ref() {
+                    turn_running = true;
+                    thread.submit(user_turn.clone()).await?;
+                }
+                continue;
+            }
+        };
+        if let Some((watcher, _)) = watch.as_mut() {
+            match &event.msg {
+                EventMsg::PatchApplyBegin(ev) => watcher.ignore_agent_edits(ev.changes.keys()),
+                EventMsg::ExecCommandBegin(ev) => watcher.begin_agent_command(&ev.call_id),
+                EventMsg::ExecCommandEnd(ev) => watcher.end_agent_command(&ev.call_id),
+                EventMsg::TurnAborted(_) => {
+                    turn_running = false;
+                    watcher.finish_turn();
+                }
+                _ => {}
+            }
+        }
         if let EventMsg::ElicitationRequest(ev) = &event.msg {
             // Automatically cancel elicitation requests in exec mode.
             thread
@@ -486,16 +563,33 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         if matches!(event.msg, EventMsg::Error(_)) {
             error_seen = true;
         }
+        if let Some(annotator) = &annotator {
+            annotator.process_event(&event.msg);
+        }
         let shutdown: CodexStatus = event_processor.process_event(event);
         match shutdown {
-            CodexStatus::Running => continue,
+            CodexStatus::Running => {}
             CodexStatus::InitiateShutdown => {
-                thread.submit(Op::Shutdown).await?;
+                if let Some((watcher, _)) = watch.as_mut() {
+                    // Stay alive and wait for the next change instead of exiting.
+                    turn_running = false;
+                    watcher.finish_turn();
+                } else {
+                    thread.submit(Op::Shutdown).await?;
+                }
             }
             CodexStatus::Shutdown => {
                 break;
             }
         }
+        if rerun_pending
+            && !turn_running
+            && let Some((_, user_turn)) = watch.as_ref()
+        {
+            rerun_pending = false;
+            turn_running = true;
+            thread.submit(user_turn.clone()).await?;
+        }
     }
     event_processor.print_final_output();
     if error_seen {
@@ -505,6 +599,14 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
     Ok(())
 }
 
+/// Resolves with the next batch of changed files, or never when not watching.
+async fn next_watch_trigger(watch: &mut Option<(WorkspaceWatcher, Op)>) -> Option<Vec<PathBuf>> {
+    match watch {
+        Some((watcher, _)) => watcher.next_trigger().await,
+        None => std::future::pending().await,
+    }
+}
+
 async fn resolve_resume_path(
     config: &Config,
     args: &crate::cli::ResumeArgs,
@@ -542,16 +644,18 @@ async fn resolve_resume_path(
     }
 }
 
-fn load_output_schema(path: Option<PathBuf>) -> Option<Value> {
+fn load_output_schema(path: Option<PathBuf>, annotate: Option<AnnotationFormat>) -> Option<Value> {
     let path = path?;
 
     let schema_str = match std::fs::read_to_string(&path) {
         Ok(contents) => contents,
         Err(err) => {
-            eprintln!(
+            let message = format!(
                 "Failed to read output schema file {}: {err}",
                 path.display()
             );
+            annotations::emit_schema_file_error(annotate, &path, &message);
+            eprintln!("{message}");
             std::process::exit(1);
         }
     };
@@ -559,10 +663,12 @@ fn load_output_schema(path: Option<PathBuf>) -> Option<Value> {
     match serde_json::from_str::<Value>(&schema_str) {
         Ok(value) => Some(value),
         Err(err) => {
-            eprintln!(
+            let message = format!(
                 "Output schema file {} is not valid JSON: {err}",
                 path.display()
             );
+            annotations::emit_schema_file_error(annotate, &path, &message);
+            eprintln!("{message}");
             std::process::exit(1);
         }
     }
@@ -705,9 +811,15 @@ fn build_review_request(args: ReviewArgs) -> anyhow::Result<ReviewRequest> {
         );
     };
 
+    let paths = codex_core::review_prompts::normalize_review_paths(ReviewPaths {
+        include: args.include_paths,
+        exclude: args.exclude_paths,
+    });
+
     Ok(ReviewRequest {
         target,
         user_facing_hint: None,
+        paths,
     })
 }
 
@@ -724,12 +836,15 @@ mod tests {
             commit: None,
             commit_title: None,
             prompt: None,
+            include_paths: Vec::new(),
+            exclude_paths: Vec::new(),
         })
         .expect("builds uncommitted review request");
 
         let expected = ReviewRequest {
             target: ReviewTarget::UncommittedChanges,
             user_facing_hint: None,
+            paths: None,
         };
 
         assert_eq!(request, expected);
@@ -743,6 +858,8 @@ mod tests {
             commit: Some("123456789".to_string()),
             commit_title: Some("Add review command".to_string()),
             prompt: None,
+            include_paths: Vec::new(),
+            exclude_paths: Vec::new(),
         })
         .expect("builds commit review request");
 
@@ -752,6 +869,7 @@ mod tests {
                 title: Some("Add review command".to_string()),
             },
             user_facing_hint: None,
+            paths: None,
         };
 
         assert_eq!(request, expected);
@@ -765,6 +883,8 @@ mod tests {
             commit: None,
             commit_title: None,
             prompt: Some("  custom review instructions  ".to_string()),
+            include_paths: Vec::new(),
+            exclude_paths: Vec::new(),
         })
         .expect("builds custom review request");
 
@@ -773,6 +893,32 @@ mod tests {
                 instructions: "custom review instructions".to_string(),
             },
             user_facing_hint: None,
+            paths: None,
+        };
+
+        assert_eq!(request, expected);
+    }
+
+    #[test]
+    fn builds_review_request_limited_to_paths() {
+        let request = build_review_request(ReviewArgs {
+            uncommitted: true,
+            base: None,
+            commit: None,
+            commit_title: None,
+            prompt: None,
+            include_paths: vec!["src/api/**".to_string()],
+            exclude_paths: vec!["src/api/generated/**".to_string()],
+        })
+        .expect("builds path-limited review request");
+
+        let expected = ReviewRequest {
+            target: ReviewTarget::UncommittedChanges,
+            user_facing_hint: None,
+            paths: Some(ReviewPaths {
+                include: vec!["src/api/**".to_string()],
+                exclude: vec!["src/api/generated/**".to_string()],
+            }),
         };
 
         assert_eq!(request, expected);
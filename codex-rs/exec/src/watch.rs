@@ -0,0 +1,321 @@
+//! Workspace watching for `codex exec --watch`.
+//!
+//! File system notifications under the working directory are filtered
+//! through the user's glob patterns and coalesced until the workspace has
+//! been quiet for the debounce window, so a burst of saves triggers a single
+//! re-run. Codex's own writes never trigger a run: files under its home
+//! directory and the last-message file are ignored outright, and patches and
+//! commands run by the agent are ignored while they run (and for one debounce
+//! window afterwards), so a turn never re-triggers itself.
+
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::Instant;
+
+use notify::EventKind;
+use notify::RecommendedWatcher;
+use notify::RecursiveMode;
+use notify::Watcher;
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::sync::mpsc::unbounded_channel;
+use tracing::warn;
+use wildmatch::WildMatchPattern;
+
+type GlobPattern = WildMatchPattern<'*', '?'>;
+
+/// Glob patterns matched against paths relative to the watched root.
+///
+/// Patterns containing `/` match the whole relative path; other patterns
+/// match the file name in any directory. `*` also matches `/`, so
+/// `src/**/*.rs` covers `src/lib.rs` as well as nested files.
+pub(crate) struct WatchPatterns {
+    patterns: Vec<(GlobPattern, bool)>,
+}
+
+impl WatchPatterns {
+    pub(crate) fn new(patterns: &[String]) -> Self {
+        let patterns = patterns
+            .iter()
+            .map(|pattern| {
+                let pattern = pattern.trim_start_matches("./").replace("**/", "*");
+                let match_full_path = pattern.contains('/');
+                (GlobPattern::new(&pattern), match_full_path)
+            })
+            .collect();
+        Self { patterns }
+    }
+
+    pub(crate) fn matches(&self, relative: &Path) -> bool {
+        let full = relative
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(part) => part.to_str(),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("/");
+        let file_name = full.rsplit('/').next().unwrap_or_default();
+        self.patterns.iter().any(|(pattern, match_full_path)| {
+            if *match_full_path {
+                pattern.matches(&full)
+            } else {
+                pattern.matches(file_name)
+            }
+        })
+    }
+}
+
+pub(crate) struct WorkspaceWatcher {
+    // Dropping the watcher stops notifications.
+    _watcher: RecommendedWatcher,
+    rx: UnboundedReceiver<PathBuf>,
+    roots: Vec<PathBuf>,
+    patterns: WatchPatterns,
+    debounce: Duration,
+    /// Changes seen since the last trigger, relative to the root.
+    pending: BTreeSet<PathBuf>,
+    /// Paths edited by the agent; `None` while the turn is still running.
+    agent_edits: HashMap<PathBuf, Option<Instant>>,
+    /// Paths Codex writes itself, ignored along with everything under them.
+    codex_paths: Vec<PathBuf>,
+    /// Agent commands still running; any change is ignored meanwhile.
+    agent_commands: HashSet<String>,
+    /// Changes are ignored until then, after the last agent command ended.
+    agent_commands_quiet_at: Option<Instant>,
+}
+
+impl WorkspaceWatcher {
+    pub(crate) fn start(
+        root: &Path,
+        patterns: &[String],
+        debounce: Duration,
+        codex_paths: &[PathBuf],
+    ) -> anyhow::Result<Self> {
+        let (tx, rx) = unbounded_channel();
+        let mut watcher =
+            notify::recommended_watcher(move |res: notify::Result<notify::Event>| match res {
+                Ok(event) if !matches!(event.kind, EventKind::Access(_)) => {
+                    for path in event.paths {
+                        let _ = tx.send(path);
+                    }
+                }
+                Ok(_) => {}
+                Err(err) => warn!("file watcher error: {err}"),
+            })?;
+        watcher.watch(root, RecursiveMode::Recursive)?;
+
+        // Notifications may report canonical paths (e.g. `/private/var` on macOS).
+        let mut roots = vec![root.to_path_buf()];
+        if let Ok(canonical) = root.canonicalize()
+            && canonical != root
+        {
+            roots.push(canonical);
+        }
+        let codex_paths = codex_paths
+            .iter()
+            .flat_map(|path| {
+                let path = root.join(path);
+                let canonical = path
+                    .canonicalize()
+                    .ok()
+                    .filter(|canonical| *canonical != path);
+                std::iter::once(path).chain(canonical)
+            })
+            .collect();
+        Ok(Self {
+            _watcher: watcher,
+            rx,
+            roots,
+            patterns: WatchPatterns::new(patterns),
+            debounce,
+            pending: BTreeSet::new(),
+            agent_edits: HashMap::new(),
+            codex_paths,
+            agent_commands: HashSet::new(),
+            agent_commands_quiet_at: None,
+        })
+    }
+
+    /// Waits for matching changes and returns them once no further change has
+    /// arrived for the debounce window. Cancel-safe: changes collected before
+    /// the future is dropped are kept for the next call.
+    pub(crate) async fn next_trigger(&mut self) -> Option<Vec<PathBuf>> {
+        loop {
+            let received = if self.pending.is_empty() {
+                self.rx.recv().await
+            } else {
+                match tokio::time::timeout(self.debounce, self.rx.recv()).await {
+                    Ok(received) => received,
+                    Err(_) => return Some(self.take_pending()),
+                }
+            };
+            let Some(path) = received else {
+                return (!self.pending.is_empty()).then(|| self.take_pending());
+            };
+            if let Some(relative) = self.relevant_change(&path) {
+                self.pending.insert(relative);
+            }
+        }
+    }
+
+    fn take_pending(&mut self) -> Vec<PathBuf> {
+        std::mem::take(&mut self.pending).into_iter().collect()
+    }
+
+    /// Record files the agent is editing so their notifications are ignored.
+    pub(crate) fn ignore_agent_edits<'a>(&mut self, paths: impl IntoIterator<Item = &'a PathBuf>) {
+        for path in paths {
+            let path = if path.is_absolute() {
+                path.clone()
+            } else {
+                self.roots[0].join(path)
+            };
+            self.agent_edits.insert(path, None);
+        }
+    }
+
+    /// The agent started a command; what it writes cannot be known in
+    /// advance, so every change is ignored until it ends.
+    pub(crate) fn begin_agent_command(&mut self, call_id: &str) {
+        self.agent_commands.insert(call_id.to_string());
+    }
+
+    /// An agent command ended; changes count again one debounce window after
+    /// the last running one.
+    pub(crate) fn end_agent_command(&mut self, call_id: &str) {
+        if self.agent_commands.remove(call_id) && self.agent_commands.is_empty() {
+            self.agent_commands_quiet_at = Some(Instant::now() + self.debounce);
+        }
+    }
+
+    /// The agent's turn ended; its edits stop being ignored after one debounce window.
+    pub(crate) fn finish_turn(&mut self) {
+        let expires_at = Instant::now() + self.debounce;
+        for deadline in self.agent_edits.values_mut() {
+            deadline.get_or_insert(expires_at);
+        }
+        // Commands of an aborted turn may never report their end.
+        if !self.agent_commands.is_empty() {
+            self.agent_commands.clear();
+            self.agent_commands_quiet_at = Some(expires_at);
+        }
+    }
+
+    fn relevant_change(&mut self, path: &Path) -> Option<PathBuf> {
+        let now = Instant::now();
+        self.agent_edits
+            .retain(|_, deadline| deadline.is_none_or(|deadline| deadline > now));
+        if self.agent_edits.contains_key(path)
+            || !self.agent_commands.is_empty()
+            || self
+                .agent_commands_quiet_at
+                .is_some_and(|quiet_at| quiet_at > now)
+            || self
+                .codex_paths
+                .iter()
+                .any(|codex_path| path.starts_with(codex_path))
+        {
+            return None;
+        }
+        let relative = self
+            .roots
+            .iter()
+            .find_map(|root| path.strip_prefix(root).ok())?;
+        let in_git_dir = relative
+            .components()
+            .any(|component| component.as_os_str() == ".git");
+        (!in_git_dir && self.patterns.matches(relative)).then(|| relative.to_path_buf())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn recursive_globs_match_top_level_and_nested_files() {
+        let patterns = WatchPatterns::new(&["src/**/*.rs".to_string()]);
+        assert!(patterns.matches(Path::new("src/lib.rs")));
+        assert!(patterns.matches(Path::new("src/a/b/mod.rs")));
+        assert!(!patterns.matches(Path::new("tests/lib.rs")));
+        assert!(!patterns.matches(Path::new("src/lib.rs.orig")));
+    }
+
+    #[test]
+    fn patterns_without_slash_match_file_names() {
+        let patterns = WatchPatterns::new(&["*.toml".to_string(), "./docs/*.md".to_string()]);
+        assert!(patterns.matches(Path::new("Cargo.toml")));
+        assert!(patterns.matches(Path::new("crates/core/Cargo.toml")));
+        assert!(patterns.matches(Path::new("docs/exec.md")));
+        assert!(!patterns.matches(Path::new("README.md")));
+    }
+
+    #[tokio::test]
+    async fn coalesces_changes_and_ignores_agent_edits() -> anyhow::Result<()> {
+        let dir = tempfile::TempDir::new()?;
+        let root = dir.path().canonicalize()?;
+        std::fs::create_dir_all(root.join("src"))?;
+        let mut watcher = WorkspaceWatcher::start(
+            &root,
+            &["src/*.rs".to_string()],
+            Duration::from_millis(200),
+            &[],
+        )?;
+
+        watcher.ignore_agent_edits([&root.join("src/agent.rs")]);
+        std::fs::write(root.join("src/agent.rs"), "// agent")?;
+        std::fs::write(root.join("src/lib.rs"), "// one")?;
+        std::fs::write(root.join("src/main.rs"), "// two")?;
+        std::fs::write(root.join("notes.txt"), "ignored")?;
+
+        let changed = tokio::time::timeout(Duration::from_secs(10), watcher.next_trigger())
+            .await?
+            .expect("watcher closed");
+        assert_eq!(
+            changed,
+            vec![PathBuf::from("src/lib.rs"), PathBuf::from("src/main.rs")]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn ignores_codex_writes_and_agent_commands() -> anyhow::Result<()> {
+        let dir = tempfile::TempDir::new()?;
+        let root = dir.path().canonicalize()?;
+        std::fs::create_dir_all(root.join("src"))?;
+        std::fs::create_dir_all(root.join(".codex/sessions"))?;
+        let debounce = Duration::from_millis(200);
+        let mut watcher = WorkspaceWatcher::start(
+            &root,
+            &[
+                "*.rs".to_string(),
+                "*.md".to_string(),
+                "*.jsonl".to_string(),
+            ],
+            debounce,
+            &[root.join(".codex"), PathBuf::from("last.md")],
+        )?;
+
+        std::fs::write(root.join(".codex/sessions/rollout.jsonl"), "{}")?;
+        std::fs::write(root.join("last.md"), "done")?;
+        watcher.begin_agent_command("call-1");
+        std::fs::write(root.join("src/formatted.rs"), "// by the agent")?;
+        let quiet = tokio::time::timeout(debounce * 4, watcher.next_trigger()).await;
+        assert!(quiet.is_err(), "unexpected trigger: {quiet:?}");
+
+        watcher.end_agent_command("call-1");
+        tokio::time::sleep(debounce * 2).await;
+        std::fs::write(root.join("src/lib.rs"), "// by the user")?;
+        let changed = tokio::time::timeout(Duration::from_secs(10), watcher.next_trigger())
+            .await?
+            .expect("watcher closed");
+        assert_eq!(changed, vec![PathBuf::from("src/lib.rs")]);
+        Ok(())
+    }
+}
@@ -86,6 +86,7 @@ fn session_configured_produces_thread_started_event() {
             history_entry_count: 0,
             initial_messages: None,
             rollout_path,
+            incognito: false,
         }),
     );
     let out = ep.collect_thread_events(&ev);
@@ -295,6 +296,7 @@ fn mcp_tool_call_begin_and_end_emit_item_events() {
                 is_error: None,
                 structured_content: None,
             }),
+            error: None,
         }),
     );
     let end_events = ep.collect_thread_events(&end);
@@ -344,6 +346,7 @@ fn mcp_tool_call_failure_sets_failed_status() {
             invocation,
             duration: Duration::from_millis(5),
             result: Err("tool exploded".to_string()),
+            error: None,
         }),
     );
     let events = ep.collect_thread_events(&end);
@@ -416,6 +419,7 @@ fn mcp_tool_call_defaults_arguments_and_preserves_structured_content() {
                 is_error: None,
                 structured_content: Some(json!({ "status": "ok" })),
             }),
+            error: None,
         }),
     );
     let events = ep.collect_thread_events(&end);
@@ -496,6 +500,7 @@ fn agent_reasoning_produces_item_completed_reasoning() {
         "e1",
         EventMsg::AgentReasoning(AgentReasoningEvent {
             text: "thinking...".to_string(),
+            item_id: None,
         }),
     );
     let out = ep.collect_thread_events(&ev);
@@ -519,6 +524,7 @@ fn agent_message_produces_item_completed_agent_message() {
         "e1",
         EventMsg::AgentMessage(AgentMessageEvent {
             message: "hello".to_string(),
+            item_id: None,
         }),
     );
     let out = ep.collect_thread_events(&ev);
@@ -543,6 +549,7 @@ fn error_event_produces_error() {
         EventMsg::Error(codex_core::protocol::ErrorEvent {
             message: "boom".to_string(),
             codex_error_info: Some(CodexErrorInfo::Other),
+            fingerprint: None,
         }),
     ));
     assert_eq!(
@@ -603,6 +610,7 @@ fn error_followed_by_task_complete_produces_turn_failed() {
         EventMsg::Error(ErrorEvent {
             message: "boom".to_string(),
             codex_error_info: Some(CodexErrorInfo::Other),
+            fingerprint: None,
         }),
     );
     assert_eq!(
@@ -900,6 +908,7 @@ fn patch_apply_success_produces_item_completed_patchapply() {
         PathBuf::from("a/added.txt"),
         FileChange::Add {
             content: "+hello".to_string(),
+            mode: None,
         },
     );
     changes.insert(
@@ -913,6 +922,7 @@ fn patch_apply_success_produces_item_completed_patchapply() {
         FileChange::Update {
             unified_diff: "--- c/modified.txt\n+++ c/modified.txt\n@@\n-old\n+new\n".to_string(),
             move_path: Some(PathBuf::from("c/renamed.txt")),
+            mode: None,
         },
     );
 
@@ -985,6 +995,7 @@ fn patch_apply_failure_produces_item_completed_patchapply_failed() {
         FileChange::Update {
             unified_diff: "--- file.txt\n+++ file.txt\n@@\n-old\n+new\n".to_string(),
             move_path: None,
+            mode: None,
         },
     );
 
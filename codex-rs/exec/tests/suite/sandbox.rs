@@ -74,6 +74,9 @@ async fn python_multiprocessing_lock_works_under_sandbox() {
         network_access: false,
         exclude_tmpdir_env_var: false,
         exclude_slash_tmp: false,
+        max_memory_bytes: None,
+        cpu_rate_percent: None,
+        max_processes: None,
     };
 
     let python_code = r#"import multiprocessing
@@ -173,6 +176,9 @@ async fn sandbox_distinguishes_command_and_policy_cwds() {
         network_access: false,
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        max_memory_bytes: None,
+        cpu_rate_percent: None,
+        max_processes: None,
     };
 
     // Attempt to write inside the command cwd, which is outside of the sandbox policy cwd.
@@ -0,0 +1,239 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use clap::Parser;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::Decision;
+use crate::Policy;
+use crate::RuleMatch;
+use crate::execpolicycheck::load_policies;
+
+/// Arguments for running a directory of assertion files against one or more execpolicy files.
+#[derive(Debug, Parser, Clone)]
+pub struct ExecPolicyTestCommand {
+    /// Paths to execpolicy rule files to evaluate (repeatable).
+    #[arg(short = 'r', long = "rules", value_name = "PATH", required = true)]
+    pub rules: Vec<PathBuf>,
+
+    /// Directory of `*.json` assertion files.
+    #[arg(value_name = "DIR")]
+    pub assertions: PathBuf,
+}
+
+impl ExecPolicyTestCommand {
+    /// Load the policies and assertions, print every mismatch, and fail if there was any.
+    pub fn run(&self) -> Result<()> {
+        let policy = load_policies(&self.rules)?;
+        let assertions = load_assertions(&self.assertions)?;
+        let report = run_assertions(&policy, &assertions);
+
+        for failure in &report.failures {
+            println!("{failure}");
+        }
+        let total = report.passed + report.failures.len();
+        if !report.failures.is_empty() {
+            bail!("{} of {total} assertions failed", report.failures.len());
+        }
+        println!("{total} assertions passed");
+        Ok(())
+    }
+}
+
+/// Decision an assertion expects for its command.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExpectedDecision {
+    Allow,
+    Prompt,
+    Forbidden,
+    /// No rule may match the command.
+    Unmatched,
+}
+
+impl ExpectedDecision {
+    fn matches(self, actual: Option<Decision>) -> bool {
+        match (self, actual) {
+            (ExpectedDecision::Allow, Some(Decision::Allow))
+            | (ExpectedDecision::Prompt, Some(Decision::Prompt))
+            | (ExpectedDecision::Forbidden, Some(Decision::Forbidden))
+            | (ExpectedDecision::Unmatched, None) => true,
+            _ => false,
+        }
+    }
+}
+
+/// A command and the decision the policy must reach for it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PolicyAssertion {
+    /// Assertion file the entry was read from.
+    pub file: PathBuf,
+    /// Zero-based position of the entry within its file.
+    pub index: usize,
+    pub command: Vec<String>,
+    pub decision: ExpectedDecision,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AssertionEntry {
+    command: AssertionCommand,
+    decision: ExpectedDecision,
+}
+
+/// Commands are token arrays or strings, which are tokenized with `shlex`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AssertionCommand {
+    Tokens(Vec<String>),
+    Line(String),
+}
+
+/// Reads every `*.json` file directly inside `dir`, in file name order. Each
+/// file holds an array of `{ "command": [...] | "...", "decision": "allow" |
+/// "prompt" | "forbidden" | "unmatched" }` entries.
+pub fn load_assertions(dir: &Path) -> Result<Vec<PolicyAssertion>> {
+    let mut files = fs::read_dir(dir)
+        .with_context(|| format!("failed to read assertions directory {}", dir.display()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<_>>>()
+        .with_context(|| format!("failed to read assertions directory {}", dir.display()))?;
+    files.retain(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "json"));
+    files.sort();
+
+    let mut assertions = Vec::new();
+    for file in files {
+        let contents = fs::read_to_string(&file)
+            .with_context(|| format!("failed to read assertions at {}", file.display()))?;
+        let entries: Vec<AssertionEntry> = serde_json::from_str(&contents)
+            .with_context(|| format!("failed to parse assertions at {}", file.display()))?;
+        for (index, entry) in entries.into_iter().enumerate() {
+            let command = match entry.command {
+                AssertionCommand::Tokens(tokens) => tokens,
+                AssertionCommand::Line(line) => shlex::split(&line).with_context(|| {
+                    format!(
+                        "invalid command `{line}` in entry {index} of {}",
+                        file.display()
+                    )
+                })?,
+            };
+            if command.is_empty() {
+                bail!("empty command in entry {index} of {}", file.display());
+            }
+            assertions.push(PolicyAssertion {
+                file: file.clone(),
+                index,
+                command,
+                decision: entry.decision,
+            });
+        }
+    }
+    Ok(assertions)
+}
+
+/// An assertion whose command got a different decision than expected.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PolicyAssertionFailure {
+    pub assertion: PolicyAssertion,
+    /// Strictest decision across `matched_rules`, `None` when nothing matched.
+    pub actual: Option<Decision>,
+    pub matched_rules: Vec<RuleMatch>,
+}
+
+impl fmt::Display for PolicyAssertionFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let PolicyAssertion {
+            file,
+            index,
+            command,
+            decision,
+        } = &self.assertion;
+        let command = shlex::try_join(command.iter().map(String::as_str))
+            .unwrap_or_else(|_| command.join(" "));
+        write!(
+            f,
+            "{}[{index}]: `{command}`: expected {}, got {}",
+            file.display(),
+            expected_label(*decision),
+            self.actual.map_or("unmatched", decision_label)
+        )?;
+        for rule in &self.matched_rules {
+            match rule {
+                RuleMatch::PrefixRuleMatch {
+                    matched_prefix,
+                    decision,
+                    justification,
+                } => {
+                    write!(
+                        f,
+                        "\n  matched prefix_rule {matched_prefix:?} ({})",
+                        decision_label(*decision)
+                    )?;
+                    if let Some(justification) = justification {
+                        write!(f, ": {justification}")?;
+                    }
+                }
+                RuleMatch::HeuristicsRuleMatch { decision, .. } => {
+                    write!(f, "\n  matched heuristics ({})", decision_label(*decision))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn decision_label(decision: Decision) -> &'static str {
+    match decision {
+        Decision::Allow => "allow",
+        Decision::Prompt => "prompt",
+        Decision::Forbidden => "forbidden",
+    }
+}
+
+fn expected_label(decision: ExpectedDecision) -> &'static str {
+    match decision {
+        ExpectedDecision::Allow => "allow",
+        ExpectedDecision::Prompt => "prompt",
+        ExpectedDecision::Forbidden => "forbidden",
+        ExpectedDecision::Unmatched => "unmatched",
+    }
+}
+
+/// Outcome of running assertions against a policy.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PolicyTestReport {
+    pub passed: usize,
+    pub failures: Vec<PolicyAssertionFailure>,
+}
+
+impl PolicyTestReport {
+    pub fn is_success(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Checks each assertion against `policy` without heuristics, so only the
+/// policy's own rules decide.
+pub fn run_assertions(policy: &Policy, assertions: &[PolicyAssertion]) -> PolicyTestReport {
+    let mut report = PolicyTestReport::default();
+    for assertion in assertions {
+        let matched_rules = policy.matches_for_command(&assertion.command, None);
+        let actual = matched_rules.iter().map(RuleMatch::decision).max();
+        if assertion.decision.matches(actual) {
+            report.passed += 1;
+        } else {
+            report.failures.push(PolicyAssertionFailure {
+                assertion: assertion.clone(),
+                actual,
+                matched_rules,
+            });
+        }
+    }
+    report
+}
@@ -2,6 +2,7 @@ pub mod amend;
 pub mod decision;
 pub mod error;
 pub mod execpolicycheck;
+pub mod execpolicytest;
 pub mod parser;
 pub mod policy;
 pub mod rule;
@@ -12,6 +13,13 @@ pub use decision::Decision;
 pub use error::Error;
 pub use error::Result;
 pub use execpolicycheck::ExecPolicyCheckCommand;
+pub use execpolicytest::ExecPolicyTestCommand;
+pub use execpolicytest::ExpectedDecision;
+pub use execpolicytest::PolicyAssertion;
+pub use execpolicytest::PolicyAssertionFailure;
+pub use execpolicytest::PolicyTestReport;
+pub use execpolicytest::load_assertions;
+pub use execpolicytest::run_assertions;
 pub use parser::PolicyParser;
 pub use policy::Evaluation;
 pub use policy::Policy;
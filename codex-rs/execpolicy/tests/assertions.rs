@@ -0,0 +1,84 @@
+use std::fs;
+
+use anyhow::Result;
+use codex_execpolicy::Decision;
+use codex_execpolicy::ExpectedDecision;
+use codex_execpolicy::PolicyParser;
+use codex_execpolicy::RuleMatch;
+use codex_execpolicy::load_assertions;
+use codex_execpolicy::run_assertions;
+use pretty_assertions::assert_eq;
+use tempfile::tempdir;
+
+const POLICY: &str = r#"
+prefix_rule(
+    pattern = ["git", "status"],
+)
+
+prefix_rule(
+    pattern = ["git", "push"],
+    decision = "prompt",
+    justification = "pushing publishes work",
+)
+"#;
+
+#[test]
+fn reports_mismatches_with_the_matched_rule() -> Result<()> {
+    let mut parser = PolicyParser::new();
+    parser.parse("org.rules", POLICY)?;
+    let policy = parser.build();
+
+    let dir = tempdir()?;
+    fs::write(
+        dir.path().join("git.json"),
+        r#"[
+            { "command": ["git", "status"], "decision": "allow" },
+            { "command": "git push --force", "decision": "forbidden" },
+            { "command": "make", "decision": "unmatched" }
+        ]"#,
+    )?;
+    fs::write(dir.path().join("notes.txt"), "not an assertion file")?;
+
+    let assertions = load_assertions(dir.path())?;
+    assert_eq!(assertions.len(), 3);
+    assert_eq!(
+        assertions[1].command,
+        vec!["git".to_string(), "push".to_string(), "--force".to_string()]
+    );
+
+    let report = run_assertions(&policy, &assertions);
+    assert_eq!(report.passed, 2);
+    assert_eq!(report.failures.len(), 1);
+    let failure = &report.failures[0];
+    assert_eq!(failure.assertion.decision, ExpectedDecision::Forbidden);
+    assert_eq!(failure.actual, Some(Decision::Prompt));
+    assert_eq!(
+        failure.matched_rules,
+        vec![RuleMatch::PrefixRuleMatch {
+            matched_prefix: vec!["git".to_string(), "push".to_string()],
+            decision: Decision::Prompt,
+            justification: Some("pushing publishes work".to_string()),
+        }]
+    );
+    assert_eq!(
+        failure.to_string(),
+        format!(
+            "{}[1]: `git push --force`: expected forbidden, got prompt\n  matched prefix_rule [\"git\", \"push\"] (prompt): pushing publishes work",
+            dir.path().join("git.json").display()
+        )
+    );
+    Ok(())
+}
+
+#[test]
+fn rejects_malformed_assertions() -> Result<()> {
+    let dir = tempdir()?;
+    fs::write(
+        dir.path().join("bad.json"),
+        r#"[{ "command": [], "decision": "allow" }]"#,
+    )?;
+
+    let err = load_assertions(dir.path()).expect_err("empty command should be rejected");
+    assert!(err.to_string().contains("empty command in entry 0"));
+    Ok(())
+}
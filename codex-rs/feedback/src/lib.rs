@@ -447,4 +447,27 @@ mod tests {
         pretty_assertions::assert_eq!(snap.tags.get("model").map(String::as_str), Some("gpt-5"));
         pretty_assertions::assert_eq!(snap.tags.get("cached").map(String::as_str), Some("true"));
     }
+
+    #[test]
+    fn logger_layer_includes_correlation_ids_from_spans() {
+        let fb = CodexFeedback::new();
+        let _guard = tracing_subscriber::registry()
+            .with(fb.logger_layer())
+            .set_default();
+
+        let turn = tracing::info_span!("turn", conversation_id = "c-1", turn_id = "t-1");
+        let _turn = turn.enter();
+        let tool = tracing::info_span!("dispatch_tool_call", call_id = "call-1");
+        let _tool = tool.enter();
+        tracing::info!("running tool");
+
+        let snap = fb.snapshot(None);
+        let logs = std::str::from_utf8(snap.as_bytes()).unwrap();
+        assert!(
+            logs.contains(
+                "turn{conversation_id=\"c-1\" turn_id=\"t-1\"}:dispatch_tool_call{call_id=\"call-1\"}"
+            ),
+            "{logs}"
+        );
+    }
 }
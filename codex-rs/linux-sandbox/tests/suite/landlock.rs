@@ -75,6 +75,9 @@ async fn run_cmd_output(
         // writing to in the sandbox.
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        max_memory_bytes: None,
+        cpu_rate_percent: None,
+        max_processes: None,
     };
     let sandbox_program = env!("CARGO_BIN_EXE_codex-linux-sandbox");
     let codex_linux_sandbox_exe = Some(PathBuf::from(sandbox_program));
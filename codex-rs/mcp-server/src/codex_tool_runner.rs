@@ -235,6 +235,7 @@ async fn run_codex_tool_session_inner(
                         call_id,
                         reason: _,
                         proposed_execpolicy_amendment: _,
+                        matched_approval_rule: _,
                         parsed_cmd,
                     }) => {
                         handle_exec_approval_request(
@@ -324,6 +325,7 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::AgentReasoningRawContentDelta(_)
                     | EventMsg::TurnStarted(_)
                     | EventMsg::TokenCount(_)
+                    | EventMsg::TokenForecast(_)
                     | EventMsg::AgentReasoning(_)
                     | EventMsg::AgentReasoningSectionBreak(_)
                     | EventMsg::McpToolCallBegin(_)
@@ -331,6 +333,13 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::McpListToolsResponse(_)
                     | EventMsg::ListCustomPromptsResponse(_)
                     | EventMsg::ListSkillsResponse(_)
+                    | EventMsg::SandboxPolicyReport(_)
+                    | EventMsg::ToolArtifact(_)
+                    | EventMsg::ResumeContext(_)
+                    | EventMsg::ModelSwitch(_)
+                    | EventMsg::ProviderRateLimits(_)
+                    | EventMsg::ProviderEndpoint(_)
+                    | EventMsg::SecretsMasked(_)
                     | EventMsg::ExecCommandBegin(_)
                     | EventMsg::TerminalInteraction(_)
                     | EventMsg::ExecCommandOutputDelta(_)
@@ -342,9 +351,11 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::TurnDiff(_)
                     | EventMsg::WebSearchBegin(_)
                     | EventMsg::WebSearchEnd(_)
+                    | EventMsg::ProviderToolCallEnd(_)
                     | EventMsg::GetHistoryEntryResponse(_)
                     | EventMsg::PlanUpdate(_)
                     | EventMsg::TurnAborted(_)
+                    | EventMsg::TurnInterrupted(_)
                     | EventMsg::UserMessage(_)
                     | EventMsg::ShutdownComplete
                     | EventMsg::ViewImageToolCall(_)
@@ -362,6 +373,15 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::RequestUserInput(_)
                     | EventMsg::ContextCompacted(_)
                     | EventMsg::ThreadRolledBack(_)
+                    | EventMsg::TurnsTrimmed(_)
+                    | EventMsg::ClipStored(_)
+                    | EventMsg::FeatureOverridden(_)
+                    | EventMsg::ReplSnippet(_)
+                    | EventMsg::ExplorationFindings(_)
+                    | EventMsg::ExplorationFindingsCleared
+                    | EventMsg::KnowledgeInjected(_)
+                    | EventMsg::ConversationMerged(_)
+                    | EventMsg::Heartbeat(_)
                     | EventMsg::CollabAgentSpawnBegin(_)
                     | EventMsg::CollabAgentSpawnEnd(_)
                     | EventMsg::CollabAgentInteractionBegin(_)
@@ -268,6 +268,7 @@ mod tests {
                 history_entry_count: 1000,
                 initial_messages: None,
                 rollout_path: rollout_file.path().to_path_buf(),
+                incognito: false,
             }),
         };
 
@@ -308,6 +309,7 @@ mod tests {
             history_entry_count: 1000,
             initial_messages: None,
             rollout_path: rollout_file.path().to_path_buf(),
+            incognito: false,
         };
         let event = Event {
             id: "1".to_string(),
@@ -372,6 +374,7 @@ mod tests {
             history_entry_count: 1000,
             initial_messages: None,
             rollout_path: rollout_file.path().to_path_buf(),
+            incognito: false,
         };
         let event = Event {
             id: "1".to_string(),
@@ -36,6 +36,7 @@ pub struct OtelEventMetadata {
     pub(crate) log_user_prompts: bool,
     pub(crate) app_version: &'static str,
     pub(crate) terminal_type: String,
+    pub(crate) turn_id: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -52,6 +53,12 @@ impl OtelManager {
         self
     }
 
+    /// Tags the events this manager emits with the turn they belong to.
+    pub fn with_turn_id(mut self, turn_id: &str) -> Self {
+        self.metadata.turn_id = Some(turn_id.to_owned());
+        self
+    }
+
     pub fn with_metrics(mut self, metrics: MetricsClient) -> Self {
         self.metrics = Some(metrics);
         self.metrics_use_metadata_tags = true;
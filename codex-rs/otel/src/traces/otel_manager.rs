@@ -53,6 +53,7 @@ impl OtelManager {
                 log_user_prompts,
                 app_version: env!("CARGO_PKG_VERSION"),
                 terminal_type,
+                turn_id: None,
             },
             metrics: crate::metrics::global(),
             metrics_use_metadata_tags: true,
@@ -103,6 +104,7 @@ impl OtelManager {
             event.name = "codex.conversation_starts",
             event.timestamp = %timestamp(),
             conversation.id = %self.metadata.conversation_id,
+            turn.id = self.metadata.turn_id,
             app.version = %self.metadata.app_version,
             auth_mode = self.metadata.auth_mode,
             user.account_id = self.metadata.account_id,
@@ -122,6 +124,25 @@ impl OtelManager {
         )
     }
 
+    /// Records which environment variables commands of the conversation get,
+    /// as summarized by the caller; values are never logged.
+    pub fn exec_environment(&self, summary: &str) {
+        tracing::event!(
+            tracing::Level::INFO,
+            event.name = "codex.exec_environment",
+            event.timestamp = %timestamp(),
+            conversation.id = %self.metadata.conversation_id,
+            app.version = %self.metadata.app_version,
+            auth_mode = self.metadata.auth_mode,
+            user.account_id = self.metadata.account_id,
+            user.email = self.metadata.account_email,
+            terminal.type = %self.metadata.terminal_type,
+            model = %self.metadata.model,
+            slug = %self.metadata.slug,
+            summary = %summary,
+        );
+    }
+
     pub async fn log_request<F, Fut>(&self, attempt: u64, f: F) -> Result<Response, Error>
     where
         F: FnOnce() -> Fut,
@@ -152,6 +173,7 @@ impl OtelManager {
             event.name = "codex.api_request",
             event.timestamp = %timestamp(),
             conversation.id = %self.metadata.conversation_id,
+            turn.id = self.metadata.turn_id,
             app.version = %self.metadata.app_version,
             auth_mode = self.metadata.auth_mode,
             user.account_id = self.metadata.account_id,
@@ -220,6 +242,7 @@ impl OtelManager {
             event.timestamp = %timestamp(),
             event.kind = %kind,
             conversation.id = %self.metadata.conversation_id,
+            turn.id = self.metadata.turn_id,
             app.version = %self.metadata.app_version,
             auth_mode = self.metadata.auth_mode,
             user.account_id = self.metadata.account_id,
@@ -242,6 +265,7 @@ impl OtelManager {
                 event.timestamp = %timestamp(),
                 event.kind = %kind,
                 conversation.id = %self.metadata.conversation_id,
+                turn.id = self.metadata.turn_id,
                 app.version = %self.metadata.app_version,
                 auth_mode = self.metadata.auth_mode,
                 user.account_id = self.metadata.account_id,
@@ -257,6 +281,7 @@ impl OtelManager {
                 event.name = "codex.sse_event",
                 event.timestamp = %timestamp(),
                 conversation.id = %self.metadata.conversation_id,
+                turn.id = self.metadata.turn_id,
                 app.version = %self.metadata.app_version,
                 auth_mode = self.metadata.auth_mode,
                 user.account_id = self.metadata.account_id,
@@ -280,6 +305,7 @@ impl OtelManager {
             event.kind = %"response.completed",
             event.timestamp = %timestamp(),
             conversation.id = %self.metadata.conversation_id,
+            turn.id = self.metadata.turn_id,
             app.version = %self.metadata.app_version,
             auth_mode = self.metadata.auth_mode,
             user.account_id = self.metadata.account_id,
@@ -305,6 +331,7 @@ impl OtelManager {
             event.timestamp = %timestamp(),
             event.kind = %"response.completed",
             conversation.id = %self.metadata.conversation_id,
+            turn.id = self.metadata.turn_id,
             app.version = %self.metadata.app_version,
             auth_mode = self.metadata.auth_mode,
             user.account_id = self.metadata.account_id,
@@ -340,6 +367,7 @@ impl OtelManager {
             event.name = "codex.user_prompt",
             event.timestamp = %timestamp(),
             conversation.id = %self.metadata.conversation_id,
+            turn.id = self.metadata.turn_id,
             app.version = %self.metadata.app_version,
             auth_mode = self.metadata.auth_mode,
             user.account_id = self.metadata.account_id,
@@ -364,6 +392,7 @@ impl OtelManager {
             event.name = "codex.tool_decision",
             event.timestamp = %timestamp(),
             conversation.id = %self.metadata.conversation_id,
+            turn.id = self.metadata.turn_id,
             app.version = %self.metadata.app_version,
             auth_mode = self.metadata.auth_mode,
             user.account_id = self.metadata.account_id,
@@ -417,6 +446,7 @@ impl OtelManager {
             event.name = "codex.tool_result",
             event.timestamp = %timestamp(),
             conversation.id = %self.metadata.conversation_id,
+            turn.id = self.metadata.turn_id,
             app.version = %self.metadata.app_version,
             auth_mode = self.metadata.auth_mode,
             user.account_id = self.metadata.account_id,
@@ -456,6 +486,7 @@ impl OtelManager {
             event.name = "codex.tool_result",
             event.timestamp = %timestamp(),
             conversation.id = %self.metadata.conversation_id,
+            turn.id = self.metadata.turn_id,
             app.version = %self.metadata.app_version,
             auth_mode = self.metadata.auth_mode,
             user.account_id = self.metadata.account_id,
@@ -500,6 +531,8 @@ impl OtelManager {
             ResponseItem::CustomToolCall { .. } => "custom_tool_call".into(),
             ResponseItem::CustomToolCallOutput { .. } => "custom_tool_call_output".into(),
             ResponseItem::WebSearchCall { .. } => "web_search_call".into(),
+            ResponseItem::CodeInterpreterCall { .. } => "code_interpreter_call".into(),
+            ResponseItem::FileSearchCall { .. } => "file_search_call".into(),
             ResponseItem::GhostSnapshot { .. } => "ghost_snapshot".into(),
             ResponseItem::Compaction { .. } => "compaction".into(),
             ResponseItem::Other => "other".into(),
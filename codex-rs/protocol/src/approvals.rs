@@ -37,6 +37,38 @@ impl From<Vec<String>> for ExecPolicyAmendment {
     }
 }
 
+/// Outcome of a config-defined `[[approval_rules]]` entry.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum ApprovalRuleDecision {
+    /// Run without asking.
+    Allow,
+    /// Ask the user before running.
+    Prompt,
+    /// Never run.
+    Forbidden,
+}
+
+/// How long an "always allow" approval is remembered.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Hash, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalScope {
+    /// Until the session ends.
+    Session,
+    /// In every session started in the same project.
+    Project,
+    /// In every session.
+    Global,
+}
+
+/// The approval rule that decided how a command is approved.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct MatchedApprovalRule {
+    /// The rule's `name`, or `approval_rules[<index>]` when it has none.
+    pub name: String,
+    pub decision: ApprovalRuleDecision,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ExecApprovalRequestEvent {
     /// Identifier for the associated exec call, if available.
@@ -56,6 +88,10 @@ pub struct ExecApprovalRequestEvent {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[ts(optional)]
     pub proposed_execpolicy_amendment: Option<ExecPolicyAmendment>,
+    /// Config-defined approval rule that required this approval, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub matched_approval_rule: Option<MatchedApprovalRule>,
     pub parsed_cmd: Vec<ParsedCommand>,
 }
 
@@ -48,6 +48,31 @@ pub enum Verbosity {
     High,
 }
 
+/// Which value wins for a turn-context setting when a recorded session is
+/// resumed: the one captured in the rollout or the one from the current config.
+#[derive(
+    Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, Display, JsonSchema, TS,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum ResumeContextSource {
+    Recorded,
+    #[default]
+    Current,
+}
+
+/// Per-dimension choice between recorded and current settings on resume.
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(default)]
+pub struct ResumeContextOptions {
+    /// Model and reasoning effort.
+    pub model: ResumeContextSource,
+    /// Working directory.
+    pub environment: ResumeContextSource,
+    /// Approval and sandbox policies.
+    pub sandbox: ResumeContextSource,
+}
+
 #[derive(
     Deserialize, Debug, Clone, Copy, PartialEq, Default, Serialize, Display, JsonSchema, TS,
 )]
@@ -2,6 +2,7 @@ use crate::protocol::AgentMessageEvent;
 use crate::protocol::AgentReasoningEvent;
 use crate::protocol::AgentReasoningRawContentEvent;
 use crate::protocol::EventMsg;
+use crate::protocol::ProviderToolCallEndEvent;
 use crate::protocol::UserMessageEvent;
 use crate::protocol::WebSearchEndEvent;
 use crate::user_input::ByteRange;
@@ -20,6 +21,7 @@ pub enum TurnItem {
     AgentMessage(AgentMessageItem),
     Reasoning(ReasoningItem),
     WebSearch(WebSearchItem),
+    ProviderToolCall(ProviderToolCallItem),
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, TS, JsonSchema)]
@@ -55,6 +57,27 @@ pub struct WebSearchItem {
     pub query: String,
 }
 
+/// A tool the model provider runs on its side, enabled with
+/// `[provider_tools]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum ProviderTool {
+    CodeInterpreter,
+    FileSearch,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, TS, JsonSchema)]
+pub struct ProviderToolCallItem {
+    pub id: String,
+    pub tool: ProviderTool,
+    /// The code that was run, or the queries that were searched for, one per
+    /// line.
+    pub input: String,
+    /// What the code printed, or the files that matched, one per line.
+    pub output: String,
+}
+
 impl UserMessageItem {
     pub fn new(content: &[UserInput]) -> Self {
         Self {
@@ -71,6 +94,7 @@ impl UserMessageItem {
             images: Some(self.image_urls()),
             local_images: self.local_image_paths(),
             text_elements: self.text_elements(),
+            item_id: Some(self.id.clone()),
         })
     }
 
@@ -147,6 +171,7 @@ impl AgentMessageItem {
             .map(|c| match c {
                 AgentMessageContent::Text { text } => EventMsg::AgentMessage(AgentMessageEvent {
                     message: text.clone(),
+                    item_id: Some(self.id.clone()),
                 }),
             })
             .collect()
@@ -159,6 +184,7 @@ impl ReasoningItem {
         for summary in &self.summary_text {
             events.push(EventMsg::AgentReasoning(AgentReasoningEvent {
                 text: summary.clone(),
+                item_id: Some(self.id.clone()),
             }));
         }
 
@@ -167,6 +193,7 @@ impl ReasoningItem {
                 events.push(EventMsg::AgentReasoningRawContent(
                     AgentReasoningRawContentEvent {
                         text: entry.clone(),
+                        item_id: Some(self.id.clone()),
                     },
                 ));
             }
@@ -185,6 +212,17 @@ impl WebSearchItem {
     }
 }
 
+impl ProviderToolCallItem {
+    pub fn as_legacy_event(&self) -> EventMsg {
+        EventMsg::ProviderToolCallEnd(ProviderToolCallEndEvent {
+            call_id: self.id.clone(),
+            tool: self.tool,
+            input: self.input.clone(),
+            output: self.output.clone(),
+        })
+    }
+}
+
 impl TurnItem {
     pub fn id(&self) -> String {
         match self {
@@ -192,6 +230,7 @@ impl TurnItem {
             TurnItem::AgentMessage(item) => item.id.clone(),
             TurnItem::Reasoning(item) => item.id.clone(),
             TurnItem::WebSearch(item) => item.id.clone(),
+            TurnItem::ProviderToolCall(item) => item.id.clone(),
         }
     }
 
@@ -200,6 +239,7 @@ impl TurnItem {
             TurnItem::UserMessage(item) => vec![item.as_legacy_event()],
             TurnItem::AgentMessage(item) => item.as_legacy_events(),
             TurnItem::WebSearch(item) => vec![item.as_legacy_event()],
+            TurnItem::ProviderToolCall(item) => vec![item.as_legacy_event()],
             TurnItem::Reasoning(item) => item.as_legacy_events(show_raw_agent_reasoning),
         }
     }
@@ -159,6 +159,42 @@ pub enum ResponseItem {
         status: Option<String>,
         action: WebSearchAction,
     },
+    // Emitted by the Responses API when the model runs code with the hosted
+    // code interpreter. `outputs` is only returned when the request includes
+    // `code_interpreter_call.outputs`.
+    CodeInterpreterCall {
+        #[serde(default, skip_serializing)]
+        #[ts(skip)]
+        id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        status: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        container_id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        code: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        outputs: Option<Vec<CodeInterpreterOutput>>,
+    },
+    // Emitted by the Responses API when the model searches vector stores with
+    // the hosted file search. `results` is only returned when the request
+    // includes `file_search_call.results`.
+    FileSearchCall {
+        #[serde(default, skip_serializing)]
+        #[ts(skip)]
+        id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        status: Option<String>,
+        #[serde(default)]
+        queries: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        results: Option<Vec<FileSearchResult>>,
+    },
     // Generated by the harness but considered exactly as a model response.
     GhostSnapshot {
         ghost_commit: GhostCommit,
@@ -555,6 +591,36 @@ pub struct LocalShellExecAction {
     pub user: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CodeInterpreterOutput {
+    Logs {
+        logs: String,
+    },
+    Image {
+        url: String,
+    },
+
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema, TS)]
+pub struct FileSearchResult {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub file_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub filename: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub score: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub text: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum WebSearchAction {
@@ -889,6 +955,9 @@ mod tests {
             network_access: true,
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
+            max_memory_bytes: None,
+            cpu_rate_percent: None,
+            max_processes: None,
         };
 
         let instructions = DeveloperInstructions::from_policy(
@@ -1093,6 +1162,61 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn deserializes_provider_tool_calls() -> Result<()> {
+        let code_interpreter: ResponseItem = serde_json::from_str(
+            r#"{
+                "type": "code_interpreter_call",
+                "id": "ci_1",
+                "status": "completed",
+                "container_id": "cntr_1",
+                "code": "print(1 + 1)",
+                "outputs": [{"type": "logs", "logs": "2\n"}, {"type": "image", "url": "https://example.com/plot.png"}]
+            }"#,
+        )?;
+        assert_eq!(
+            code_interpreter,
+            ResponseItem::CodeInterpreterCall {
+                id: Some("ci_1".into()),
+                status: Some("completed".into()),
+                container_id: Some("cntr_1".into()),
+                code: Some("print(1 + 1)".into()),
+                outputs: Some(vec![
+                    CodeInterpreterOutput::Logs { logs: "2\n".into() },
+                    CodeInterpreterOutput::Image {
+                        url: "https://example.com/plot.png".into(),
+                    },
+                ]),
+            }
+        );
+
+        let file_search: ResponseItem = serde_json::from_str(
+            r#"{
+                "type": "file_search_call",
+                "id": "fs_1",
+                "status": "completed",
+                "queries": ["retry policy"],
+                "results": [{"file_id": "file_1", "filename": "design.md", "score": 0.5, "text": "Retries back off.", "attributes": {}}]
+            }"#,
+        )?;
+        assert_eq!(
+            file_search,
+            ResponseItem::FileSearchCall {
+                id: Some("fs_1".into()),
+                status: Some("completed".into()),
+                queries: vec!["retry policy".into()],
+                results: Some(vec![FileSearchResult {
+                    file_id: Some("file_1".into()),
+                    filename: Some("design.md".into()),
+                    score: Some(0.5),
+                    text: Some("Retries back off.".into()),
+                }]),
+            }
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn deserialize_shell_tool_call_params() -> Result<()> {
         let json = r#"{
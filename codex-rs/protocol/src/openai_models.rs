@@ -100,6 +100,22 @@ pub enum ModelVisibility {
     None,
 }
 
+/// Kind of input a model accepts.
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, TS, JsonSchema, Display,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum InputModality {
+    Text,
+    Image,
+}
+
+/// Models are assumed to accept images unless their metadata says otherwise.
+pub fn default_input_modalities() -> Vec<InputModality> {
+    vec![InputModality::Text, InputModality::Image]
+}
+
 /// Shell execution capability for a model.
 #[derive(
     Debug,
@@ -203,10 +219,20 @@ pub struct ModelInfo {
     /// reserving headroom for system prompts, tool overhead, and model output.
     #[serde(default = "default_effective_context_window_percent")]
     pub effective_context_window_percent: i64,
+    /// Upper bound on the tokens the model may generate per response. When
+    /// omitted, the provider's default applies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<i64>,
     pub experimental_supported_tools: Vec<String>,
+    #[serde(default = "default_input_modalities")]
+    pub input_modalities: Vec<InputModality>,
 }
 
 impl ModelInfo {
+    pub fn supports_image_input(&self) -> bool {
+        self.input_modalities.contains(&InputModality::Image)
+    }
+
     pub fn auto_compact_token_limit(&self) -> Option<i64> {
         self.auto_compact_token_limit.or_else(|| {
             self.context_window
@@ -412,7 +438,9 @@ mod tests {
             context_window: None,
             auto_compact_token_limit: None,
             effective_context_window_percent: 95,
+            max_output_tokens: None,
             experimental_supported_tools: vec![],
+            input_modalities: default_input_modalities(),
         }
     }
 
@@ -3,6 +3,7 @@
 //! Uses a SQ (Submission Queue) / EQ (Event Queue) pattern to asynchronously communicate
 //! between user and agent.
 
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::ffi::OsStr;
 use std::fmt;
@@ -16,7 +17,10 @@ use crate::approvals::ElicitationRequestEvent;
 use crate::config_types::CollaborationMode;
 use crate::config_types::Personality;
 use crate::config_types::ReasoningSummary as ReasoningSummaryConfig;
+use crate::config_types::ResumeContextOptions;
+use crate::config_types::ResumeContextSource;
 use crate::custom_prompts::CustomPrompt;
+use crate::items::ProviderTool;
 use crate::items::TurnItem;
 use crate::message_history::HistoryEntry;
 use crate::models::BaseInstructions;
@@ -44,9 +48,11 @@ use tracing::error;
 use ts_rs::TS;
 
 pub use crate::approvals::ApplyPatchApprovalRequestEvent;
+pub use crate::approvals::ApprovalScope;
 pub use crate::approvals::ElicitationAction;
 pub use crate::approvals::ExecApprovalRequestEvent;
 pub use crate::approvals::ExecPolicyAmendment;
+pub use crate::approvals::MatchedApprovalRule;
 pub use crate::request_user_input::RequestUserInputEvent;
 
 /// Open/close tags for special user-input blocks. Used across crates to avoid
@@ -259,12 +265,79 @@ pub enum Op {
     /// Request Codex to undo a turn (turn are stacked so it is the same effect as CMD + Z).
     Undo,
 
+    /// Undo the most recent turn: restore the workspace to the checkpoint taken
+    /// when the turn started and drop the turn from the conversation history.
+    ///
+    /// Refuses (via `UndoCompleted { success: false }`) when the workspace was
+    /// modified after the turn ended, so unrelated edits are never discarded.
+    UndoTurn,
+
     /// Request Codex to drop the last N user turns from in-memory context.
     ///
     /// This does not attempt to revert local filesystem changes. Clients are
     /// responsible for undoing any edits on disk.
     ThreadRollback { num_turns: u32 },
 
+    /// Replace an earlier user message and regenerate the conversation from
+    /// that point.
+    ///
+    /// Every turn from the message identified by `item_id` onwards is dropped
+    /// from context (recorded in the rollout as a `ThreadRolledBack` event),
+    /// then `new_text` is submitted as a new user turn along with the images
+    /// attached to the original message. Like [`Op::ThreadRollback`], local
+    /// filesystem changes are not reverted.
+    EditAndRegenerate { item_id: String, new_text: String },
+
+    /// Store `content` on the conversation clipboard under `name`, replacing
+    /// any clip of that name. Tool calls reference it as `{{clip:<name>}}`
+    /// and get the content substituted just before they run. An empty
+    /// `content` removes the clip.
+    SetClip { name: String, content: String },
+
+    /// Turn masking of likely secrets in tool outputs on or off for this
+    /// conversation, e.g. after a false positive hid a value the model needs.
+    /// Has no effect unless the `secret_masking` feature is enabled.
+    SetSecretMasking { enabled: bool },
+
+    /// Send input typed by the user to a running unified exec process that
+    /// was started in raw mode, e.g. keys forwarded while the user has taken
+    /// over the session in the TUI. The process's output keeps arriving as
+    /// `ExecCommandOutputDelta` events of the call that started it.
+    TerminalInput { process_id: String, input: String },
+
+    /// Turn the feature with `[features]` key `feature` on or off for the
+    /// rest of this conversation. Only features that are read anew for every
+    /// turn can be changed, and not into a combination that leaves a feature
+    /// without one it requires or enables two that conflict. Accepted changes
+    /// are reported with a [`EventMsg::FeatureOverridden`] event, which is
+    /// recorded in the rollout and reapplied on resume; rejected ones with an
+    /// error.
+    SetFeature {
+        feature: String,
+        enabled: bool,
+        /// Who or what made the change (e.g. a user name or client id), kept
+        /// in the event for auditing.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        changed_by: Option<String>,
+    },
+
+    /// Investigate the repository read-only for `goal` within the `[explore]`
+    /// token and time budget, then summarize the findings. The summary is
+    /// pinned to the context of every later turn of the conversation until
+    /// another exploration replaces it or it is cleared.
+    Explore { goal: String },
+
+    /// Unpin the findings of the last exploration, reported with an
+    /// `ExplorationFindingsCleared` event.
+    ClearExplorationFindings,
+
+    /// Merge the outcome of another conversation, read from its rollout file,
+    /// into this one: its final agent message, the diff of every turn that
+    /// changed files and the artifacts its tools returned. They are recorded
+    /// as context for later turns, tagged with the conversation they came
+    /// from, and reported with a `ConversationMerged` event.
+    MergeConversation { rollout_path: PathBuf },
+
     /// Request a code review from the agent.
     Review { review_request: ReviewRequest },
 
@@ -283,6 +356,10 @@ pub enum Op {
 
     /// Request the list of available models.
     ListModels,
+
+    /// Request the sandbox policy currently in effect for this session.
+    /// Reply is delivered via `EventMsg::SandboxPolicyReport`.
+    GetSandboxPolicy,
 }
 
 /// Determines the conditions under which the user is consulted to approve
@@ -390,6 +467,25 @@ pub enum SandboxPolicy {
         /// writable roots on UNIX. Defaults to `false`.
         #[serde(default)]
         exclude_slash_tmp: bool,
+
+        /// Upper bound, in bytes, on the memory committed by all processes
+        /// of a sandboxed command together. Only enforced by the Windows
+        /// sandbox.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        max_memory_bytes: Option<u64>,
+
+        /// Share of the host's total CPU time (1-100) a sandboxed command may
+        /// use. Only enforced by the Windows sandbox.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        cpu_rate_percent: Option<u8>,
+
+        /// Maximum number of processes a sandboxed command may have running
+        /// at once. Only enforced by the Windows sandbox.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        max_processes: Option<u32>,
     },
 }
 
@@ -398,7 +494,7 @@ pub enum SandboxPolicy {
 /// that folders containing files that could be modified to escalate the
 /// privileges of the agent (e.g. `.codex`, `.git`, notably `.git/hooks`) under
 /// a writable root are not modified by the agent.
-#[derive(Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
 pub struct WritableRoot {
     pub root: AbsolutePathBuf,
 
@@ -447,6 +543,9 @@ impl SandboxPolicy {
             network_access: false,
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
+            max_memory_bytes: None,
+            cpu_rate_percent: None,
+            max_processes: None,
         }
     }
 
@@ -485,7 +584,7 @@ impl SandboxPolicy {
                 writable_roots,
                 exclude_tmpdir_env_var,
                 exclude_slash_tmp,
-                network_access: _,
+                ..
             } => {
                 // Start from explicitly configured writable roots.
                 let mut roots: Vec<AbsolutePathBuf> = writable_roots.clone();
@@ -650,7 +749,11 @@ fn resolve_gitdir_from_file(dot_git: &AbsolutePathBuf) -> Option<AbsolutePathBuf
 }
 
 /// Event Queue Entry - events from agent
-#[derive(Debug, Clone, Deserialize, Serialize)]
+///
+/// Serialized events also carry `item_id` ([`EventMsg::item_id`]) and
+/// `parent_id` ([`Event::parent_id`]) when they have them, so clients can
+/// thread every event without knowing each payload's own id fields.
+#[derive(Debug, Clone, Deserialize)]
 pub struct Event {
     /// Submission `id` that this event is correlated with.
     pub id: String,
@@ -658,6 +761,42 @@ pub struct Event {
     pub msg: EventMsg,
 }
 
+impl Event {
+    /// The item or turn this event hangs off: the parent carried by the
+    /// payload, or for item events whose payload has none, the submission
+    /// (turn) id.
+    pub fn parent_id(&self) -> Option<&str> {
+        self.msg
+            .parent_id()
+            .or_else(|| self.msg.item_id().map(|_| self.id.as_str()))
+    }
+}
+
+impl Serialize for Event {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        #[derive(Serialize)]
+        struct WireEvent<'a> {
+            id: &'a str,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            item_id: Option<&'a str>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            parent_id: Option<&'a str>,
+            msg: &'a EventMsg,
+        }
+
+        WireEvent {
+            id: &self.id,
+            item_id: self.msg.item_id(),
+            parent_id: self.parent_id(),
+            msg: &self.msg,
+        }
+        .serialize(serializer)
+    }
+}
+
 /// Response event from the agent
 /// NOTE: Make sure none of these values have optional types, as it will mess up the extension code-gen.
 #[derive(Debug, Clone, Deserialize, Serialize, Display, JsonSchema, TS)]
@@ -678,6 +817,9 @@ pub enum EventMsg {
     /// Conversation history was rolled back by dropping the last N user turns.
     ThreadRolledBack(ThreadRolledBackEvent),
 
+    /// A range of user turns was removed from the rollout file.
+    TurnsTrimmed(TurnsTrimmedEvent),
+
     /// Agent has started a turn.
     /// v1 wire format uses `task_started`; accept `turn_started` for v2 interop.
     #[serde(rename = "task_started", alias = "turn_started")]
@@ -692,6 +834,18 @@ pub enum EventMsg {
     /// Optional means unknown — UIs should not display when `None`.
     TokenCount(TokenCountEvent),
 
+    /// Periodic estimate of the context a turn will use, sent while a
+    /// response streams.
+    TokenForecast(TokenForecastEvent),
+
+    /// Request/token budget currently advertised by the model provider. The
+    /// budget is shared by every conversation talking to the same provider.
+    ProviderRateLimits(ProviderRateLimitsEvent),
+
+    /// The model provider switched to another of its base URLs, either
+    /// failing over from an unhealthy endpoint or returning to the primary.
+    ProviderEndpoint(ProviderEndpointEvent),
+
     /// Agent text output message
     AgentMessage(AgentMessageEvent),
 
@@ -728,10 +882,45 @@ pub enum EventMsg {
 
     McpToolCallEnd(McpToolCallEndEvent),
 
+    /// A tool call produced a file that was saved to the thread's artifacts directory.
+    ToolArtifact(ToolArtifactEvent),
+
+    /// A clip was stored on or removed from the conversation clipboard.
+    ClipStored(ClipStoredEvent),
+
+    /// A feature was turned on or off for the rest of the conversation.
+    FeatureOverridden(FeatureOverriddenEvent),
+
+    /// The `repl` tool ran a snippet in the conversation's interpreter.
+    ReplSnippet(ReplSnippetEvent),
+
+    /// Likely secrets were masked in a tool output before it was sent to the
+    /// model.
+    SecretsMasked(SecretsMaskedEvent),
+
+    /// An exploration finished and its findings were pinned to the context
+    /// of the following turns.
+    ExplorationFindings(ExplorationFindingsEvent),
+
+    /// The pinned exploration findings were removed from the context of the
+    /// following turns.
+    ExplorationFindingsCleared,
+
+    /// Project knowledge snippets whose keywords appear in the user prompt
+    /// were added to the context of the turn.
+    KnowledgeInjected(KnowledgeInjectedEvent),
+
+    /// The outcome of another conversation was merged into this one.
+    ConversationMerged(ConversationMergedEvent),
+
     WebSearchBegin(WebSearchBeginEvent),
 
     WebSearchEnd(WebSearchEndEvent),
 
+    /// A tool the model provider runs on its side (code interpreter, file
+    /// search) completed.
+    ProviderToolCallEnd(ProviderToolCallEndEvent),
+
     /// Notification that the server is about to execute a command.
     ExecCommandBegin(ExecCommandBeginEvent),
 
@@ -789,6 +978,17 @@ pub enum EventMsg {
     /// List of skills available to the agent.
     ListSkillsResponse(ListSkillsResponseEvent),
 
+    /// Response to `Op::GetSandboxPolicy`.
+    SandboxPolicyReport(SandboxPolicyReport),
+
+    /// Emitted once when a resumed session's recorded turn context differs
+    /// from the current configuration, recording which values were applied.
+    ResumeContext(ResumeContextEvent),
+
+    /// Emitted when a turn switches the session to a different model,
+    /// summarizing how the session context was adapted to it.
+    ModelSwitch(ModelSwitchEvent),
+
     /// Notification that skill data may have been updated and clients may want to reload.
     SkillsUpdateAvailable,
 
@@ -796,6 +996,14 @@ pub enum EventMsg {
 
     TurnAborted(TurnAbortedEvent),
 
+    /// Sent before [`EventMsg::TurnAborted`] when the user interrupts a turn,
+    /// summarizing what of the turn was kept in history.
+    TurnInterrupted(TurnInterruptedEvent),
+
+    /// Sent while a turn is running but has produced no other event for a
+    /// while, so clients can tell a long silent operation from a hang.
+    Heartbeat(HeartbeatEvent),
+
     /// Notification that the agent is shutting down.
     ShutdownComplete,
 
@@ -832,6 +1040,140 @@ pub enum EventMsg {
     CollabCloseEnd(CollabCloseEndEvent),
 }
 
+impl EventMsg {
+    /// Stable id of the item this event creates or updates. The begin, delta,
+    /// and end events of one item, and the approval requests for it, all share
+    /// the same id; for tool calls it is the call id.
+    pub fn item_id(&self) -> Option<&str> {
+        match self {
+            EventMsg::UserMessage(ev) => ev.item_id.as_deref(),
+            EventMsg::AgentMessage(ev) => ev.item_id.as_deref(),
+            EventMsg::AgentMessageDelta(ev) => ev.item_id.as_deref(),
+            EventMsg::AgentReasoning(ev) => ev.item_id.as_deref(),
+            EventMsg::AgentReasoningDelta(ev) => ev.item_id.as_deref(),
+            EventMsg::AgentReasoningRawContent(ev) => ev.item_id.as_deref(),
+            EventMsg::AgentReasoningRawContentDelta(ev) => ev.item_id.as_deref(),
+            EventMsg::AgentReasoningSectionBreak(ev) => Some(&ev.item_id),
+            EventMsg::ItemStarted(ItemStartedEvent { item, .. })
+            | EventMsg::ItemCompleted(ItemCompletedEvent { item, .. }) => Some(match item {
+                TurnItem::UserMessage(item) => &item.id,
+                TurnItem::AgentMessage(item) => &item.id,
+                TurnItem::Reasoning(item) => &item.id,
+                TurnItem::WebSearch(item) => &item.id,
+                TurnItem::ProviderToolCall(item) => &item.id,
+            }),
+            EventMsg::AgentMessageContentDelta(ev) => Some(&ev.item_id),
+            EventMsg::ReasoningContentDelta(ev) => Some(&ev.item_id),
+            EventMsg::ReasoningRawContentDelta(ev) => Some(&ev.item_id),
+            EventMsg::McpToolCallBegin(McpToolCallBeginEvent { call_id, .. })
+            | EventMsg::McpToolCallEnd(McpToolCallEndEvent { call_id, .. })
+            | EventMsg::WebSearchBegin(WebSearchBeginEvent { call_id })
+            | EventMsg::WebSearchEnd(WebSearchEndEvent { call_id, .. })
+            | EventMsg::ProviderToolCallEnd(ProviderToolCallEndEvent { call_id, .. })
+            | EventMsg::ExecCommandBegin(ExecCommandBeginEvent { call_id, .. })
+            | EventMsg::ExecCommandOutputDelta(ExecCommandOutputDeltaEvent { call_id, .. })
+            | EventMsg::TerminalInteraction(TerminalInteractionEvent { call_id, .. })
+            | EventMsg::ExecCommandEnd(ExecCommandEndEvent { call_id, .. })
+            | EventMsg::ViewImageToolCall(ViewImageToolCallEvent { call_id, .. })
+            | EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent { call_id, .. })
+            | EventMsg::RequestUserInput(RequestUserInputEvent { call_id, .. })
+            | EventMsg::ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent {
+                call_id, ..
+            })
+            | EventMsg::PatchApplyBegin(PatchApplyBeginEvent { call_id, .. })
+            | EventMsg::PatchApplyEnd(PatchApplyEndEvent { call_id, .. })
+            | EventMsg::CollabAgentSpawnBegin(CollabAgentSpawnBeginEvent { call_id, .. })
+            | EventMsg::CollabAgentSpawnEnd(CollabAgentSpawnEndEvent { call_id, .. })
+            | EventMsg::CollabAgentInteractionBegin(CollabAgentInteractionBeginEvent {
+                call_id,
+                ..
+            })
+            | EventMsg::CollabAgentInteractionEnd(CollabAgentInteractionEndEvent {
+                call_id, ..
+            })
+            | EventMsg::CollabWaitingBegin(CollabWaitingBeginEvent { call_id, .. })
+            | EventMsg::CollabWaitingEnd(CollabWaitingEndEvent { call_id, .. })
+            | EventMsg::CollabCloseBegin(CollabCloseBeginEvent { call_id, .. })
+            | EventMsg::CollabCloseEnd(CollabCloseEndEvent { call_id, .. }) => Some(call_id),
+            EventMsg::ToolArtifact(ev) => Some(&ev.artifact.id),
+            EventMsg::Error(_)
+            | EventMsg::Warning(_)
+            | EventMsg::ContextCompacted(_)
+            | EventMsg::ThreadRolledBack(_)
+            | EventMsg::TurnsTrimmed(_)
+            | EventMsg::ClipStored(_)
+            | EventMsg::FeatureOverridden(_)
+            | EventMsg::ReplSnippet(_)
+            | EventMsg::SecretsMasked(_)
+            | EventMsg::ExplorationFindings(_)
+            | EventMsg::ExplorationFindingsCleared
+            | EventMsg::KnowledgeInjected(_)
+            | EventMsg::ConversationMerged(_)
+            | EventMsg::TurnStarted(_)
+            | EventMsg::TurnComplete(_)
+            | EventMsg::TokenCount(_)
+            | EventMsg::TokenForecast(_)
+            | EventMsg::ProviderRateLimits(_)
+            | EventMsg::ProviderEndpoint(_)
+            | EventMsg::SessionConfigured(_)
+            | EventMsg::McpStartupUpdate(_)
+            | EventMsg::McpStartupComplete(_)
+            | EventMsg::ElicitationRequest(_)
+            | EventMsg::DeprecationNotice(_)
+            | EventMsg::BackgroundEvent(_)
+            | EventMsg::UndoStarted(_)
+            | EventMsg::UndoCompleted(_)
+            | EventMsg::StreamError(_)
+            | EventMsg::TurnDiff(_)
+            | EventMsg::GetHistoryEntryResponse(_)
+            | EventMsg::McpListToolsResponse(_)
+            | EventMsg::ListCustomPromptsResponse(_)
+            | EventMsg::ListSkillsResponse(_)
+            | EventMsg::SandboxPolicyReport(_)
+            | EventMsg::ResumeContext(_)
+            | EventMsg::ModelSwitch(_)
+            | EventMsg::SkillsUpdateAvailable
+            | EventMsg::PlanUpdate(_)
+            | EventMsg::TurnAborted(_)
+            | EventMsg::TurnInterrupted(_)
+            | EventMsg::Heartbeat(_)
+            | EventMsg::ShutdownComplete
+            | EventMsg::EnteredReviewMode(_)
+            | EventMsg::ExitedReviewMode(_)
+            | EventMsg::RawResponseItem(_) => None,
+        }
+    }
+
+    /// Id of the item or turn that owns [`EventMsg::item_id`], when the
+    /// payload carries it: artifacts hang off their tool call, everything
+    /// else that records a `turn_id` hangs off that turn.
+    pub fn parent_id(&self) -> Option<&str> {
+        match self {
+            EventMsg::ToolArtifact(ev) => Some(&ev.call_id),
+            EventMsg::ItemStarted(ItemStartedEvent { turn_id, .. })
+            | EventMsg::ItemCompleted(ItemCompletedEvent { turn_id, .. })
+            | EventMsg::AgentMessageContentDelta(AgentMessageContentDeltaEvent {
+                turn_id, ..
+            })
+            | EventMsg::ReasoningContentDelta(ReasoningContentDeltaEvent { turn_id, .. })
+            | EventMsg::ReasoningRawContentDelta(ReasoningRawContentDeltaEvent {
+                turn_id, ..
+            })
+            | EventMsg::ExecCommandBegin(ExecCommandBeginEvent { turn_id, .. })
+            | EventMsg::ExecCommandEnd(ExecCommandEndEvent { turn_id, .. })
+            | EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent { turn_id, .. })
+            | EventMsg::RequestUserInput(RequestUserInputEvent { turn_id, .. })
+            | EventMsg::ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent {
+                turn_id, ..
+            })
+            | EventMsg::PatchApplyBegin(PatchApplyBeginEvent { turn_id, .. })
+            | EventMsg::PatchApplyEnd(PatchApplyEndEvent { turn_id, .. })
+            | EventMsg::TurnInterrupted(TurnInterruptedEvent { turn_id, .. }) => Some(turn_id),
+            _ => None,
+        }
+    }
+}
+
 impl From<CollabAgentSpawnBeginEvent> for EventMsg {
     fn from(event: CollabAgentSpawnBeginEvent) -> Self {
         EventMsg::CollabAgentSpawnBegin(event)
@@ -906,6 +1248,15 @@ pub enum AgentStatus {
 #[ts(rename_all = "snake_case")]
 pub enum CodexErrorInfo {
     ContextWindowExceeded,
+    /// The request was not sent because it would not fit in the model's
+    /// context window.
+    ContextOverflow {
+        estimated_tokens: i64,
+        context_window: i64,
+        /// Largest parts of the prompt, biggest first.
+        largest_contributors: Vec<ContextOverflowContributor>,
+        remedies: Vec<ContextOverflowRemedy>,
+    },
     UsageLimitExceeded,
     HttpConnectionFailed {
         http_status_code: Option<u16>,
@@ -927,9 +1278,62 @@ pub enum CodexErrorInfo {
         http_status_code: Option<u16>,
     },
     ThreadRollbackFailed,
+    /// `Op::MergeConversation` could not merge the requested conversation.
+    ConversationMergeFailed,
     Other,
 }
 
+/// Part of a prompt that did not fit in the context window.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema, TS)]
+pub struct ContextOverflowContributor {
+    pub category: ContextItemCategory,
+    /// File the tokens are attributed to. `None` for the category as a whole.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub path: Option<String>,
+    pub estimated_tokens: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum ContextItemCategory {
+    Instructions,
+    ToolDefinitions,
+    UserMessages,
+    AssistantMessages,
+    Reasoning,
+    ToolCalls,
+    ToolOutputs,
+}
+
+impl ContextItemCategory {
+    pub fn label(self) -> &'static str {
+        match self {
+            ContextItemCategory::Instructions => "instructions",
+            ContextItemCategory::ToolDefinitions => "tool definitions",
+            ContextItemCategory::UserMessages => "user messages",
+            ContextItemCategory::AssistantMessages => "assistant messages",
+            ContextItemCategory::Reasoning => "reasoning",
+            ContextItemCategory::ToolCalls => "tool calls",
+            ContextItemCategory::ToolOutputs => "tool outputs",
+        }
+    }
+}
+
+/// Suggested way to get a prompt back under the context window.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum ContextOverflowRemedy {
+    /// Summarize earlier history with `/compact`.
+    Compact,
+    /// Start over without the large tool outputs, e.g. in a new thread.
+    DropToolOutputs,
+    /// Switch to a model with a larger context window.
+    SwitchModel,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, TS, JsonSchema)]
 pub struct RawResponseItemEvent {
     pub item: ResponseItem,
@@ -982,6 +1386,7 @@ impl HasLegacyEvent for AgentMessageContentDeltaEvent {
     fn as_legacy_events(&self, _: bool) -> Vec<EventMsg> {
         vec![EventMsg::AgentMessageDelta(AgentMessageDeltaEvent {
             delta: self.delta.clone(),
+            item_id: Some(self.item_id.clone()),
         })]
     }
 }
@@ -1001,6 +1406,7 @@ impl HasLegacyEvent for ReasoningContentDeltaEvent {
     fn as_legacy_events(&self, _: bool) -> Vec<EventMsg> {
         vec![EventMsg::AgentReasoningDelta(AgentReasoningDeltaEvent {
             delta: self.delta.clone(),
+            item_id: Some(self.item_id.clone()),
         })]
     }
 }
@@ -1021,6 +1427,7 @@ impl HasLegacyEvent for ReasoningRawContentDeltaEvent {
         vec![EventMsg::AgentReasoningRawContentDelta(
             AgentReasoningRawContentDeltaEvent {
                 delta: self.delta.clone(),
+                item_id: Some(self.item_id.clone()),
             },
         )]
     }
@@ -1047,6 +1454,10 @@ impl HasLegacyEvent for EventMsg {
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ExitedReviewModeEvent {
     pub review_output: Option<ReviewOutputEvent>,
+    /// The files the review was limited to, if it was.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub paths: Option<ReviewPaths>,
 }
 
 // Individual event payload types matching each `EventMsg` variant.
@@ -1056,22 +1467,145 @@ pub struct ErrorEvent {
     pub message: String,
     #[serde(default)]
     pub codex_error_info: Option<CodexErrorInfo>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<ErrorFingerprint>,
+}
+
+/// Stable identity of a failure, so recurrences of the same problem can be
+/// recognized instead of looking novel every time.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ErrorFingerprint {
+    /// Hex digest over the error code, the failing component and the message
+    /// with volatile parts (numbers, ids, paths) masked.
+    pub hash: String,
+    /// Part of Codex that failed, e.g. `model_client` or `sandbox`.
+    pub component: String,
+    /// Times this failure was seen on this machine in the last 7 days,
+    /// including this one.
+    pub occurrences_last_7_days: u64,
+}
+
+impl ErrorFingerprint {
+    /// A note for the user when this failure has happened before, e.g.
+    /// "this failure happened 14 times in the last 7 days".
+    pub fn recurrence_note(&self) -> Option<String> {
+        (self.occurrences_last_7_days > 1).then(|| {
+            format!(
+                "this failure happened {} times in the last 7 days",
+                self.occurrences_last_7_days
+            )
+        })
+    }
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
 pub struct WarningEvent {
     pub message: String,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
-pub struct ContextCompactedEvent;
+/// How a compaction rebuilt the conversation history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum CompactionStrategy {
+    /// The model summarized the history, which was replaced by the summary
+    /// and the most recent user messages.
+    Summary,
+    /// The provider's remote compaction endpoint returned the new history.
+    Remote,
+    /// A summary of the older turns, prepared while the session was idle,
+    /// replaced them; the most recent turns were kept as they were.
+    Background,
+}
+
+/// Items a compaction removed from the history, by category.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+#[serde(default)]
+pub struct CompactionDroppedItems {
+    pub user_messages: u32,
+    pub assistant_messages: u32,
+    pub reasoning: u32,
+    pub tool_calls: u32,
+    pub tool_outputs: u32,
+    pub other: u32,
+}
+
+impl CompactionDroppedItems {
+    pub fn total(&self) -> u32 {
+        self.user_messages
+            + self.assistant_messages
+            + self.reasoning
+            + self.tool_calls
+            + self.tool_outputs
+            + self.other
+    }
+}
+
+/// Emitted after the conversation history was compacted. Every field is
+/// empty in rollouts recorded before compactions were described.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
+#[serde(default)]
+pub struct ContextCompactedEvent {
+    /// Estimated tokens in context before compacting.
+    #[ts(type = "number | null")]
+    pub tokens_before: Option<i64>,
+    /// Estimated tokens in context after compacting.
+    #[ts(type = "number | null")]
+    pub tokens_after: Option<i64>,
+    pub strategy: Option<CompactionStrategy>,
+    pub items_dropped: CompactionDroppedItems,
+    /// Ids of the dropped items (message, reasoning or tool call ids), i.e.
+    /// the spans the compacted history now stands in for.
+    pub summarized_item_ids: Vec<String>,
+}
+
+impl ContextCompactedEvent {
+    /// One-line description, e.g. "context compacted (summary): ~120,000 ->
+    /// ~8,000 tokens, dropped 12 items (3 user messages, 9 tool calls)".
+    pub fn summary(&self) -> String {
+        let mut summary = "context compacted".to_string();
+        if let Some(strategy) = self.strategy {
+            let strategy = match strategy {
+                CompactionStrategy::Summary => "summary",
+                CompactionStrategy::Remote => "remote",
+                CompactionStrategy::Background => "background",
+            };
+            summary.push_str(&format!(" ({strategy})"));
+        }
+        if let (Some(before), Some(after)) = (self.tokens_before, self.tokens_after) {
+            summary.push_str(&format!(
+                ": ~{} -> ~{} tokens",
+                format_with_separators(before),
+                format_with_separators(after)
+            ));
+        }
+        let dropped = self.items_dropped;
+        if dropped.total() > 0 {
+            let parts = [
+                (dropped.user_messages, "user messages"),
+                (dropped.assistant_messages, "assistant messages"),
+                (dropped.reasoning, "reasoning items"),
+                (dropped.tool_calls, "tool calls"),
+                (dropped.tool_outputs, "tool outputs"),
+                (dropped.other, "other items"),
+            ]
+            .into_iter()
+            .filter(|(count, _)| *count > 0)
+            .map(|(count, label)| format!("{count} {label}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+            summary.push_str(&format!(", dropped {} items ({parts})", dropped.total()));
+        }
+        summary
+    }
+}
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct TurnCompleteEvent {
     pub last_agent_message: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
 pub struct TurnStartedEvent {
     // TODO(aibrahim): make this not optional
     pub model_context_window: Option<i64>,
@@ -1161,6 +1695,78 @@ pub struct TokenCountEvent {
     pub rate_limits: Option<RateLimitSnapshot>,
 }
 
+/// Estimates are approximations until the provider reports the actual usage
+/// in the next [`TokenCountEvent`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct TokenForecastEvent {
+    /// Tokens of the request input plus the output streamed so far.
+    #[ts(type = "number")]
+    pub estimated_tokens: i64,
+    /// Tokens the context is projected to hold once the response completes.
+    #[ts(type = "number")]
+    pub projected_tokens: i64,
+    #[ts(type = "number | null")]
+    pub model_context_window: Option<i64>,
+    /// Usage at which the conversation is compacted automatically.
+    #[ts(type = "number | null")]
+    pub auto_compact_token_limit: Option<i64>,
+    /// Whether `projected_tokens` reaches `auto_compact_token_limit`, in
+    /// which case the conversation is compacted before the turn continues.
+    pub will_compact: bool,
+    /// Whether the model's tokenizer is unknown and the estimates come from
+    /// byte counts instead.
+    #[serde(default)]
+    pub heuristic: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ProviderRateLimitsEvent {
+    /// Name of the model provider the budget applies to.
+    pub provider: String,
+    pub limit_requests: Option<u64>,
+    pub remaining_requests: Option<u64>,
+    /// Unix timestamp (seconds since epoch) when the request budget resets.
+    #[ts(type = "number | null")]
+    pub requests_reset_at: Option<i64>,
+    pub limit_tokens: Option<u64>,
+    pub remaining_tokens: Option<u64>,
+    /// Unix timestamp (seconds since epoch) when the token budget resets.
+    #[ts(type = "number | null")]
+    pub tokens_reset_at: Option<i64>,
+    /// Unix timestamp (seconds since epoch) until which new requests are held
+    /// back because the provider asked clients to slow down.
+    #[ts(type = "number | null")]
+    pub paused_until: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ProviderEndpointEvent {
+    /// Name of the model provider.
+    pub provider: String,
+    /// Base URL requests are now sent to.
+    pub base_url: String,
+    /// Whether `base_url` is the provider's primary endpoint.
+    pub primary: bool,
+    /// Why the previous endpoint was abandoned, when the switch is a failover.
+    pub reason: Option<String>,
+}
+
+impl ProviderEndpointEvent {
+    /// One-line description for UIs, e.g.
+    /// `azure: failed over to https://westus.example.com/openai (http 503)`.
+    pub fn summary(&self) -> String {
+        let action = if self.primary {
+            "back on primary endpoint"
+        } else {
+            "failed over to"
+        };
+        match &self.reason {
+            Some(reason) => format!("{}: {action} {} ({reason})", self.provider, self.base_url),
+            None => format!("{}: {action} {}", self.provider, self.base_url),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
 pub struct RateLimitSnapshot {
     pub primary: Option<RateLimitWindow>,
@@ -1290,6 +1896,11 @@ impl fmt::Display for FinalOutput {
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct AgentMessageEvent {
     pub message: String,
+    /// Id of the turn item this event belongs to. Absent in rollouts recorded
+    /// before item ids were attached to legacy events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub item_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -1308,26 +1919,51 @@ pub struct UserMessageEvent {
     /// UI-defined spans within `message` used to render or persist special elements.
     #[serde(default)]
     pub text_elements: Vec<crate::user_input::TextElement>,
+    /// Id of the turn item this event belongs to. Absent in rollouts recorded
+    /// before item ids were attached to legacy events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub item_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct AgentMessageDeltaEvent {
     pub delta: String,
+    /// Id of the turn item this event belongs to. Absent in rollouts recorded
+    /// before item ids were attached to legacy events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub item_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct AgentReasoningEvent {
     pub text: String,
+    /// Id of the turn item this event belongs to. Absent in rollouts recorded
+    /// before item ids were attached to legacy events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub item_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct AgentReasoningRawContentEvent {
     pub text: String,
+    /// Id of the turn item this event belongs to. Absent in rollouts recorded
+    /// before item ids were attached to legacy events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub item_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct AgentReasoningRawContentDeltaEvent {
     pub delta: String,
+    /// Id of the turn item this event belongs to. Absent in rollouts recorded
+    /// before item ids were attached to legacy events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub item_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -1342,6 +1978,11 @@ pub struct AgentReasoningSectionBreakEvent {
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct AgentReasoningDeltaEvent {
     pub delta: String,
+    /// Id of the turn item this event belongs to. Absent in rollouts recorded
+    /// before item ids were attached to legacy events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub item_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
@@ -1370,6 +2011,10 @@ pub struct McpToolCallEndEvent {
     pub duration: Duration,
     /// Result of the tool call. Note this could be an error.
     pub result: Result<CallToolResult, String>,
+    /// Classification of the failure when `result` is an error.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub error: Option<McpToolCallErrorInfo>,
 }
 
 impl McpToolCallEndEvent {
@@ -1381,6 +2026,161 @@ impl McpToolCallEndEvent {
     }
 }
 
+/// Class of an MCP tool call failure.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum McpToolCallErrorKind {
+    /// The arguments were rejected (JSON-RPC invalid params or a malformed
+    /// request).
+    InvalidParams,
+    /// The server, the tool or a resource it needs does not exist or is
+    /// disabled.
+    NotFound,
+    /// The server could not be reached or dropped the connection.
+    ServerBusy,
+    /// The server did not answer within the tool timeout. The call may still
+    /// have had an effect.
+    Timeout,
+    /// The server reported an internal error, or the failure is unknown.
+    Internal,
+}
+
+/// Structured description of a failed MCP tool call.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+pub struct McpToolCallErrorInfo {
+    pub kind: McpToolCallErrorKind,
+    /// JSON-RPC error code returned by the server, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub code: Option<i64>,
+    pub message: String,
+    /// Whether calling the tool again unchanged may succeed.
+    pub retryable: bool,
+    /// Number of times the call was sent, including automatic retries.
+    pub attempts: u32,
+}
+
+/// A file returned by a tool call, stored under the thread's artifacts directory.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+pub struct ToolArtifact {
+    /// Identifier unique within the thread, used to download the artifact.
+    pub id: String,
+    /// File name suggested by the tool.
+    pub name: String,
+    pub mime_type: String,
+    pub size_bytes: u64,
+    /// Where the artifact is stored on disk.
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+pub struct ToolArtifactEvent {
+    /// Identifier of the tool call that returned the artifact.
+    pub call_id: String,
+    pub artifact: ToolArtifact,
+}
+
+/// Recorded in the rollout so the clipboard is rebuilt on resume.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+pub struct ClipStoredEvent {
+    pub name: String,
+    /// Content of the clip; empty when the clip was removed.
+    pub content: String,
+    /// Whether the model stored the clip through the `clipboard` tool rather
+    /// than the user.
+    #[serde(default)]
+    pub from_model: bool,
+}
+
+/// Recorded in the rollout so a resumed conversation keeps the features
+/// changed while it ran.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+pub struct FeatureOverriddenEvent {
+    /// `[features]` key of the feature.
+    pub feature: String,
+    pub enabled: bool,
+    /// Who or what made the change, as given in [`Op::SetFeature`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub changed_by: Option<String>,
+}
+
+/// Recorded in the rollout as the snippet history of the `repl` tool.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+pub struct ReplSnippetEvent {
+    pub call_id: String,
+    /// `python` or `node`.
+    pub language: String,
+    pub code: String,
+    /// Captured output, shortened to its start and end when long.
+    pub stdout: String,
+    pub stderr: String,
+    /// Formatted exception when the snippet failed.
+    pub error: Option<String>,
+    /// Whether the interpreter was restarted because of this snippet, for
+    /// example after a timeout, losing its state.
+    #[serde(default)]
+    pub restarted: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+pub struct SecretsMaskedEvent {
+    pub call_id: String,
+    /// Number of masked values by kind, e.g. `aws_access_key` or
+    /// `high_entropy`.
+    pub counts: BTreeMap<String, u64>,
+}
+
+impl SecretsMaskedEvent {
+    pub fn total(&self) -> u64 {
+        self.counts.values().sum()
+    }
+}
+
+/// Recorded in the rollout so the findings stay pinned on resume.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+pub struct ExplorationFindingsEvent {
+    pub goal: String,
+    pub findings: String,
+    /// Whether the exploration was stopped by its token or time budget
+    /// rather than finishing on its own.
+    pub budget_exhausted: bool,
+}
+
+/// Recorded in the rollout so it stays visible which snippets the model saw.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+pub struct KnowledgeInjectedEvent {
+    pub snippets: Vec<InjectedKnowledgeSnippet>,
+    /// Names of matching snippets left out because they did not fit in the
+    /// token budget.
+    pub over_budget: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+pub struct InjectedKnowledgeSnippet {
+    pub name: String,
+    pub path: PathBuf,
+    /// Keywords of the snippet found in the prompt.
+    pub matched_keywords: Vec<String>,
+    /// Approximate number of tokens the snippet added to the context.
+    pub token_count: u64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+pub struct ConversationMergedEvent {
+    pub source_thread_id: ThreadId,
+    pub rollout_path: PathBuf,
+    /// Working directory of the merged conversation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub cwd: Option<PathBuf>,
+    pub final_message: Option<String>,
+    /// 1-based numbers of the merged conversation's turns whose diff was
+    /// merged.
+    pub diff_turns: Vec<u32>,
+    pub artifacts: Vec<ToolArtifact>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct WebSearchBeginEvent {
     pub call_id: String,
@@ -1392,6 +2192,16 @@ pub struct WebSearchEndEvent {
     pub query: String,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ProviderToolCallEndEvent {
+    pub call_id: String,
+    pub tool: ProviderTool,
+    /// The code that was run, or the queries that were searched for.
+    pub input: String,
+    /// What the code printed, or the files that matched.
+    pub output: String,
+}
+
 // Conversation kept for backward compatibility.
 /// Response payload for `Op::GetHistory` containing the current session's
 /// in-memory transcript.
@@ -1456,7 +2266,7 @@ impl InitialHistory {
                     .history
                     .iter()
                     .filter_map(|ri| match ri {
-                        RolloutItem::EventMsg(ev) => Some(ev.clone()),
+                        RolloutItem::EventMsg(ev) if is_replayed_event(ev) => Some(ev.clone()),
                         _ => None,
                     })
                     .collect(),
@@ -1465,7 +2275,7 @@ impl InitialHistory {
                 items
                     .iter()
                     .filter_map(|ri| match ri {
-                        RolloutItem::EventMsg(ev) => Some(ev.clone()),
+                        RolloutItem::EventMsg(ev) if is_replayed_event(ev) => Some(ev.clone()),
                         _ => None,
                     })
                     .collect(),
@@ -1491,6 +2301,13 @@ impl InitialHistory {
     }
 }
 
+/// Whether a persisted event is replayed to clients on resume. `TurnStarted`
+/// is kept only to mark turn boundaries; the matching `TurnComplete` is not
+/// persisted, so replaying it would leave the turn looking unfinished.
+fn is_replayed_event(ev: &EventMsg) -> bool {
+    !matches!(ev, EventMsg::TurnStarted(_))
+}
+
 fn session_cwd_from_items(items: &[RolloutItem]) -> Option<PathBuf> {
     items.iter().find_map(|item| match item {
         RolloutItem::SessionMeta(meta_line) => Some(meta_line.meta.cwd.clone()),
@@ -1565,6 +2382,39 @@ pub struct SessionMeta {
     /// but may be missing for older sessions. If not present, fall back to rendering the base_instructions
     /// from ModelsManager.
     pub base_instructions: Option<BaseInstructions>,
+    /// User-assigned title for the session.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// User-assigned tags used to organize sessions.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Activity aggregates, written when the session shuts down.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<SessionStats>,
+}
+
+/// Per-session activity aggregates. Resuming a session adds to the recorded
+/// values rather than replacing them.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema, TS)]
+pub struct SessionStats {
+    /// Number of turns started.
+    pub turns: u64,
+    /// Tool calls keyed by tool name.
+    #[serde(default)]
+    pub tool_calls: BTreeMap<String, u64>,
+    #[serde(default)]
+    pub token_usage: TokenUsage,
+    /// Estimated cost in USD. Only present when `model_pricing` covers every
+    /// model the session used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+    /// Time from session start to shutdown, in milliseconds.
+    #[ts(type = "number")]
+    pub wall_time_ms: u64,
+    /// Approval requests shown to the user.
+    pub approvals_requested: u64,
+    /// Approval requests the user approved.
+    pub approvals_granted: u64,
 }
 
 impl Default for SessionMeta {
@@ -1579,6 +2429,9 @@ impl Default for SessionMeta {
             source: SessionSource::default(),
             model_provider: None,
             base_instructions: None,
+            title: None,
+            tags: Vec::new(),
+            stats: None,
         }
     }
 }
@@ -1589,6 +2442,14 @@ pub struct SessionMetaLine {
     pub meta: SessionMeta,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub git: Option<GitInfo>,
+    /// Environment shell commands ran with when the session was created.
+    /// Missing for older rollouts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<EnvironmentSnapshot>,
+    /// Version of the rollout file format. Missing for rollouts written
+    /// before the format was versioned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<u32>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, TS)]
@@ -1671,6 +2532,50 @@ pub struct GitInfo {
     pub repository_url: Option<String>,
 }
 
+/// Normalized view of the environment shell commands run with. Every field is
+/// ordered deterministically so that two snapshots of the same environment
+/// serialize identically.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema, TS)]
+pub struct EnvironmentSnapshot {
+    pub os: String,
+    pub arch: String,
+    /// Variable name to value. Only allowlisted values are kept verbatim; all
+    /// others are recorded as `hmac-sha256:<hex>` of the value under a key
+    /// private to the Codex install. `PATH` is recorded separately in `path`.
+    pub env: BTreeMap<String, String>,
+    /// `PATH` entries, in lookup order.
+    pub path: Vec<String>,
+    /// Tool name to the first line of its version output, for the tools that
+    /// were found. Empty unless the `environment_tool_versions` feature was
+    /// enabled.
+    pub tools: BTreeMap<String, String>,
+}
+
+/// A difference between a recorded [`EnvironmentSnapshot`] and the current
+/// environment. `None` means the variable or tool is absent on that side.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EnvironmentChange {
+    Platform {
+        recorded: String,
+        current: String,
+    },
+    EnvVar {
+        name: String,
+        recorded: Option<String>,
+        current: Option<String>,
+    },
+    Path {
+        recorded: Vec<String>,
+        current: Vec<String>,
+    },
+    Tool {
+        name: String,
+        recorded: Option<String>,
+        current: Option<String>,
+    },
+}
+
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
 #[serde(rename_all = "snake_case")]
 pub enum ReviewDelivery {
@@ -1705,6 +2610,23 @@ pub enum ReviewTarget {
     Custom { instructions: String },
 }
 
+/// Limits a review to the files matching `include` (every file when empty)
+/// and not matching `exclude`. Patterns are git pathspec globs relative to
+/// the repository root, e.g. `src/api/**`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct ReviewPaths {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+}
+
+impl ReviewPaths {
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
 /// Review request sent to the review session.
 pub struct ReviewRequest {
@@ -1712,6 +2634,11 @@ pub struct ReviewRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[ts(optional)]
     pub user_facing_hint: Option<String>,
+    /// Files the review is limited to, applied to whichever changes `target`
+    /// selects.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub paths: Option<ReviewPaths>,
 }
 
 /// Structured review result produced by a child review session.
@@ -1904,6 +2831,21 @@ pub struct UndoCompletedEvent {
 pub struct ThreadRolledBackEvent {
     /// Number of user turns that were removed from context.
     pub num_turns: u32,
+    /// Id of the user message that was edited, when the rollback is the
+    /// branch point of an `Op::EditAndRegenerate`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub edited_item_id: Option<String>,
+}
+
+/// Tombstone left in a rollout where user turns were trimmed out of it.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct TurnsTrimmedEvent {
+    /// Zero-based index of the first removed user turn, counting every user
+    /// turn recorded in the rollout at the time of the trim.
+    pub first_turn: u32,
+    /// Number of user turns that were removed.
+    pub num_turns: u32,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -2041,6 +2983,293 @@ pub struct ListCustomPromptsResponseEvent {
     pub custom_prompts: Vec<CustomPrompt>,
 }
 
+/// Platform mechanism used to enforce a [`SandboxPolicy`] on spawned commands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum SandboxBackend {
+    /// Commands run without a platform sandbox, either because the policy
+    /// does not require one or because none is available.
+    None,
+    MacosSeatbelt,
+    LinuxSeccomp,
+    WindowsRestrictedToken,
+}
+
+/// Resolved view of what sandboxed commands can touch, suitable for showing
+/// users an accurate summary of the active policy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct SandboxPolicyReport {
+    pub sandbox_policy: SandboxPolicy,
+    /// Working directory the writable roots were resolved against.
+    pub cwd: PathBuf,
+    pub full_disk_write_access: bool,
+    /// Writable roots after applying cwd, tmpdir and read-only exclusions.
+    /// Empty when the policy is read-only or grants full disk access.
+    pub writable_roots: Vec<WritableRoot>,
+    pub network_access: bool,
+    pub backend: SandboxBackend,
+}
+
+/// What a sandbox policy dry run checks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type")]
+pub enum SandboxEvaluationTarget {
+    /// Writing to `path`, resolved against the cwd when relative.
+    Path { path: PathBuf },
+    /// Running `command` (argv, not a shell script) inside the sandbox.
+    Command { command: Vec<String> },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum SandboxDecision {
+    Allow,
+    Deny,
+}
+
+/// The part of a [`SandboxPolicy`] that decided a [`SandboxEvaluation`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum SandboxRule {
+    /// The policy does not restrict writes.
+    FullDiskWriteAccess,
+    /// The policy does not allow writes anywhere.
+    ReadOnly,
+    /// The path is inside `root`.
+    WritableRoot { root: AbsolutePathBuf },
+    /// The path is inside `subpath` (e.g. `.git`), which stays read-only even
+    /// though `root` is writable.
+    ReadOnlySubpath {
+        root: AbsolutePathBuf,
+        subpath: AbsolutePathBuf,
+    },
+    /// The path is not inside any writable root.
+    OutsideWritableRoots,
+    /// The command needs network access and the policy allows it.
+    NetworkAccess,
+    /// The command needs network access and the policy blocks it.
+    NetworkRestricted,
+    /// The command neither writes known paths nor needs the network.
+    NoRestrictedEffects,
+}
+
+/// Result of dry-running a [`SandboxEvaluationTarget`] against a [`SandboxPolicy`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct SandboxEvaluation {
+    pub decision: SandboxDecision,
+    pub rule: SandboxRule,
+    /// Human-readable explanation of the decision.
+    pub reason: String,
+}
+
+/// How the session adapted when it switched models mid-conversation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct ModelSwitchEvent {
+    pub previous_model: String,
+    pub model: String,
+    /// Whether the base instructions were re-rendered for the new model.
+    /// Instructions set in config or inherited from another model are kept.
+    pub instructions_updated: bool,
+    /// Built-in tools the new model offers that the previous one did not.
+    pub tools_added: Vec<String>,
+    /// Built-in tools the previous model offered that the new one does not.
+    pub tools_removed: Vec<String>,
+    #[ts(type = "number | null")]
+    pub previous_context_window: Option<i64>,
+    #[ts(type = "number | null")]
+    pub context_window: Option<i64>,
+    /// Tokens the conversation currently uses.
+    #[ts(type = "number")]
+    pub tokens_in_context: i64,
+    /// Whether the conversation exceeds the new model's auto-compaction
+    /// limit, so the next turn compacts it first.
+    pub compaction_pending: bool,
+}
+
+impl ModelSwitchEvent {
+    /// One-line description for UIs, e.g.
+    /// `gpt-5.1-codex -> gpt-oss-120b: tools removed: apply_patch; context window 258400 -> 123500`.
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if self.instructions_updated {
+            parts.push("instructions updated".to_string());
+        }
+        if !self.tools_added.is_empty() {
+            parts.push(format!("tools added: {}", self.tools_added.join(", ")));
+        }
+        if !self.tools_removed.is_empty() {
+            parts.push(format!("tools removed: {}", self.tools_removed.join(", ")));
+        }
+        if self.previous_context_window != self.context_window {
+            let window = |value: Option<i64>| {
+                value.map_or_else(|| "unknown".to_string(), |value| value.to_string())
+            };
+            parts.push(format!(
+                "context window {} -> {}",
+                window(self.previous_context_window),
+                window(self.context_window)
+            ));
+        }
+        if self.compaction_pending {
+            parts.push("history will be compacted before the next turn".to_string());
+        }
+        let header = format!("{} -> {}", self.previous_model, self.model);
+        if parts.is_empty() {
+            header
+        } else {
+            format!("{header}: {}", parts.join("; "))
+        }
+    }
+}
+
+/// Settings that differ between a resumed rollout's last recorded turn context
+/// and the current configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct ResumeContextEvent {
+    pub options: ResumeContextOptions,
+    pub changes: Vec<ResumeContextChange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(tag = "field", rename_all = "snake_case")]
+#[ts(tag = "field", rename_all = "snake_case")]
+pub enum ResumeContextChange {
+    Model {
+        recorded: String,
+        current: String,
+        applied: ResumeContextSource,
+    },
+    ReasoningEffort {
+        recorded: Option<ReasoningEffortConfig>,
+        current: Option<ReasoningEffortConfig>,
+        applied: ResumeContextSource,
+    },
+    Cwd {
+        recorded: PathBuf,
+        current: PathBuf,
+        applied: ResumeContextSource,
+    },
+    ApprovalPolicy {
+        recorded: AskForApproval,
+        current: AskForApproval,
+        applied: ResumeContextSource,
+    },
+    SandboxPolicy {
+        recorded: SandboxPolicy,
+        current: SandboxPolicy,
+        applied: ResumeContextSource,
+    },
+}
+
+impl ResumeContextChange {
+    pub fn applied(&self) -> ResumeContextSource {
+        match self {
+            ResumeContextChange::Model { applied, .. }
+            | ResumeContextChange::ReasoningEffort { applied, .. }
+            | ResumeContextChange::Cwd { applied, .. }
+            | ResumeContextChange::ApprovalPolicy { applied, .. }
+            | ResumeContextChange::SandboxPolicy { applied, .. } => *applied,
+        }
+    }
+
+    /// One-line description for UIs, e.g.
+    /// `model: recorded gpt-5, current gpt-5.1 (using current)`.
+    pub fn summary(&self) -> String {
+        fn effort(value: &Option<ReasoningEffortConfig>) -> String {
+            value.map_or_else(|| "default".to_string(), |effort| effort.to_string())
+        }
+        fn sandbox(value: &SandboxPolicy) -> &'static str {
+            match value {
+                SandboxPolicy::DangerFullAccess => "danger-full-access",
+                SandboxPolicy::ReadOnly => "read-only",
+                SandboxPolicy::ExternalSandbox { .. } => "external-sandbox",
+                SandboxPolicy::WorkspaceWrite { .. } => "workspace-write",
+            }
+        }
+
+        let (field, recorded, current) = match self {
+            ResumeContextChange::Model {
+                recorded, current, ..
+            } => ("model", recorded.clone(), current.clone()),
+            ResumeContextChange::ReasoningEffort {
+                recorded, current, ..
+            } => ("reasoning effort", effort(recorded), effort(current)),
+            ResumeContextChange::Cwd {
+                recorded, current, ..
+            } => (
+                "cwd",
+                recorded.display().to_string(),
+                current.display().to_string(),
+            ),
+            ResumeContextChange::ApprovalPolicy {
+                recorded, current, ..
+            } => ("approval policy", recorded.to_string(), current.to_string()),
+            ResumeContextChange::SandboxPolicy {
+                recorded, current, ..
+            } => (
+                "sandbox",
+                sandbox(recorded).to_string(),
+                sandbox(current).to_string(),
+            ),
+        };
+        format!(
+            "{field}: recorded {recorded}, current {current} (using {})",
+            self.applied()
+        )
+    }
+}
+
+/// Where a piece of model-visible instruction text came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum InstructionLayerKind {
+    /// The model's base (system) instructions.
+    BaseInstructions,
+    /// Developer-role messages such as `developer_instructions`.
+    DeveloperInstructions,
+    /// `AGENTS.md` and `user_instructions` content.
+    UserInstructions,
+    /// Instructions injected for a mentioned skill.
+    SkillInstructions,
+    /// A project knowledge snippet matched by the user prompt.
+    KnowledgeSnippet,
+    /// The `<environment_context>` message.
+    EnvironmentContext,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct InstructionLayer {
+    pub kind: InstructionLayerKind,
+    pub text: String,
+    /// Approximate number of tokens in `text`.
+    pub token_count: u64,
+}
+
+/// The fully rendered payload of the last model request made for a turn,
+/// split into the sections the model actually saw.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct TurnRequestSnapshot {
+    pub turn_id: String,
+    pub model: String,
+    /// Number of model requests the turn has made so far; the snapshot
+    /// describes the last one.
+    pub request_count: u32,
+    pub instructions: Vec<InstructionLayer>,
+    /// Conversation history after compaction, excluding instruction layers.
+    pub history: Vec<ResponseItem>,
+    pub history_token_count: u64,
+    /// Tool definitions exactly as sent to the model.
+    pub tools: Vec<serde_json::Value>,
+    pub tools_token_count: u64,
+    /// Approximate size of the whole request, in tokens.
+    pub total_token_count: u64,
+}
+
 /// Response payload for `Op::ListSkills`.
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ListSkillsResponseEvent {
@@ -2139,7 +3368,12 @@ pub struct SessionConfiguredEvent {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub initial_messages: Option<Vec<EventMsg>>,
 
+    /// Rollout file of the session; empty for a new incognito session.
     pub rollout_path: PathBuf,
+
+    /// Whether nothing from this session is written to disk.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub incognito: bool,
 }
 
 /// User's decision in response to an ExecApprovalRequest.
@@ -2160,6 +3394,11 @@ pub enum ReviewDecision {
     /// remainder of the session.
     ApprovedForSession,
 
+    /// User has approved this command and wants the same command (or changes
+    /// to the same files) approved without asking for as long as `scope`
+    /// lasts.
+    ApprovedAlways { scope: ApprovalScope },
+
     /// User has denied this command and the agent should not execute it, but
     /// it should continue the session and try something else.
     #[default]
@@ -2178,6 +3417,7 @@ impl ReviewDecision {
             ReviewDecision::Approved => "approved",
             ReviewDecision::ApprovedExecpolicyAmendment { .. } => "approved_with_amendment",
             ReviewDecision::ApprovedForSession => "approved_for_session",
+            ReviewDecision::ApprovedAlways { .. } => "approved_always",
             ReviewDecision::Denied => "denied",
             ReviewDecision::Abort => "abort",
         }
@@ -2190,6 +3430,10 @@ impl ReviewDecision {
 pub enum FileChange {
     Add {
         content: String,
+        /// Mode the new file is created with, when the patch sets one.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        mode: Option<FileMode>,
     },
     Delete {
         content: String,
@@ -2197,9 +3441,34 @@ pub enum FileChange {
     Update {
         unified_diff: String,
         move_path: Option<PathBuf>,
+        /// Mode the file is left with, when the patch changes it.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        mode: Option<FileMode>,
+    },
+    /// A symlink created (or replaced) at the path, pointing to `target`.
+    Symlink {
+        target: PathBuf,
     },
 }
 
+/// The part of a file's Unix mode that a patch can change.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum FileMode {
+    Regular,
+    Executable,
+}
+
+impl fmt::Display for FileMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileMode::Regular => f.write_str("regular"),
+            FileMode::Executable => f.write_str("executable"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct Chunk {
     /// 1-based line index of the first line in the original file
@@ -2213,6 +3482,120 @@ pub struct TurnAbortedEvent {
     pub reason: TurnAbortReason,
 }
 
+/// What an interrupted turn left behind. Items the model finished before the
+/// interrupt are always kept; this lists what was cut short.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct TurnInterruptedEvent {
+    pub turn_id: String,
+    /// Assistant text that was still streaming, committed to history as a
+    /// partial message the next user message can refer to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub partial_agent_message: Option<String>,
+    /// Tool calls that were cancelled; each was recorded in history with an
+    /// aborted result.
+    #[serde(default)]
+    pub cancelled_tool_calls: Vec<CancelledToolCall>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct CancelledToolCall {
+    pub call_id: String,
+    pub tool_name: String,
+}
+
+impl TurnInterruptedEvent {
+    /// One-line description for UIs, e.g.
+    /// `kept the partial response; cancelled shell, apply_patch`, or `None`
+    /// when nothing was cut short.
+    pub fn summary(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if self.partial_agent_message.is_some() {
+            parts.push("kept the partial response".to_string());
+        }
+        if !self.cancelled_tool_calls.is_empty() {
+            let tools = self
+                .cancelled_tool_calls
+                .iter()
+                .map(|call| call.tool_name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            parts.push(format!("cancelled {tools}"));
+        }
+        (!parts.is_empty()).then(|| parts.join("; "))
+    }
+}
+
+/// What a running turn is doing while it is otherwise silent.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct HeartbeatEvent {
+    pub phase: TurnPhase,
+    /// Milliseconds since the turn started.
+    #[ts(type = "number")]
+    pub turn_elapsed_ms: u64,
+    /// Milliseconds since the last event before this heartbeat.
+    #[ts(type = "number")]
+    pub silent_ms: u64,
+    /// Tool calls that are still running, oldest first.
+    #[serde(default)]
+    pub active_tools: Vec<ActiveToolCall>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum TurnPhase {
+    /// A model request is in flight: the model is thinking or its response
+    /// has not started streaming.
+    WaitingForModel,
+    /// One or more tool calls are running, or waiting for approval.
+    RunningTools,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct ActiveToolCall {
+    pub call_id: String,
+    pub tool_name: String,
+    /// Milliseconds since the tool call started.
+    #[ts(type = "number")]
+    pub elapsed_ms: u64,
+}
+
+impl HeartbeatEvent {
+    /// One-line description for UIs, e.g. `running shell (2m 05s)` or
+    /// `waiting for the model (45s)`.
+    pub fn summary(&self) -> String {
+        match self.phase {
+            TurnPhase::WaitingForModel => format!(
+                "waiting for the model ({})",
+                format_elapsed_ms(self.silent_ms)
+            ),
+            TurnPhase::RunningTools => {
+                let Some(oldest) = self.active_tools.first() else {
+                    return format!("running tools ({})", format_elapsed_ms(self.silent_ms));
+                };
+                let mut summary = format!(
+                    "running {} ({})",
+                    oldest.tool_name,
+                    format_elapsed_ms(oldest.elapsed_ms)
+                );
+                if self.active_tools.len() > 1 {
+                    summary.push_str(&format!(" and {} more", self.active_tools.len() - 1));
+                }
+                summary
+            }
+        }
+    }
+}
+
+fn format_elapsed_ms(elapsed_ms: u64) -> String {
+    let seconds = elapsed_ms / 1000;
+    if seconds < 60 {
+        format!("{seconds}s")
+    } else {
+        format!("{}m {:02}s", seconds / 60, seconds % 60)
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "snake_case")]
 pub enum TurnAbortReason {
@@ -2328,6 +3711,58 @@ mod tests {
     use serde_json::json;
     use tempfile::NamedTempFile;
 
+    #[test]
+    fn heartbeat_summary_names_the_oldest_running_tool() {
+        let mut event = HeartbeatEvent {
+            phase: TurnPhase::WaitingForModel,
+            turn_elapsed_ms: 200_000,
+            silent_ms: 45_500,
+            active_tools: Vec::new(),
+        };
+        assert_eq!(event.summary(), "waiting for the model (45s)");
+
+        event.phase = TurnPhase::RunningTools;
+        event.active_tools = vec![
+            ActiveToolCall {
+                call_id: "call-1".to_string(),
+                tool_name: "shell".to_string(),
+                elapsed_ms: 125_000,
+            },
+            ActiveToolCall {
+                call_id: "call-2".to_string(),
+                tool_name: "read_file".to_string(),
+                elapsed_ms: 1_000,
+            },
+        ];
+        assert_eq!(event.summary(), "running shell (2m 05s) and 1 more");
+    }
+
+    #[test]
+    fn turn_interrupted_summary_lists_what_was_cut_short() {
+        let mut event = TurnInterruptedEvent {
+            turn_id: "turn-1".to_string(),
+            partial_agent_message: None,
+            cancelled_tool_calls: Vec::new(),
+        };
+        assert_eq!(event.summary(), None);
+
+        event.partial_agent_message = Some("Looking at".to_string());
+        event.cancelled_tool_calls = vec![
+            CancelledToolCall {
+                call_id: "call-1".to_string(),
+                tool_name: "shell".to_string(),
+            },
+            CancelledToolCall {
+                call_id: "call-2".to_string(),
+                tool_name: "apply_patch".to_string(),
+            },
+        ];
+        assert_eq!(
+            event.summary().as_deref(),
+            Some("kept the partial response; cancelled shell, apply_patch")
+        );
+    }
+
     #[test]
     fn external_sandbox_reports_full_access_flags() {
         let restricted = SandboxPolicy::ExternalSandbox {
@@ -2456,6 +3891,7 @@ mod tests {
             images: None,
             local_images: Vec::new(),
             text_elements: Vec::new(),
+            item_id: None,
         };
 
         let json_event = serde_json::to_value(event)?;
@@ -2492,6 +3928,7 @@ mod tests {
                 history_entry_count: 0,
                 initial_messages: None,
                 rollout_path: rollout_file.path().to_path_buf(),
+                incognito: false,
             }),
         };
 
@@ -2535,6 +3972,73 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn serialized_events_carry_item_and_parent_ids() -> Result<()> {
+        let patch = Event {
+            id: "sub-1".to_string(),
+            msg: EventMsg::PatchApplyBegin(PatchApplyBeginEvent {
+                call_id: "call-1".to_string(),
+                turn_id: "turn-1".to_string(),
+                auto_approved: true,
+                changes: HashMap::new(),
+            }),
+        };
+        let message = Event {
+            id: "sub-1".to_string(),
+            msg: EventMsg::AgentMessage(AgentMessageEvent {
+                message: "done".to_string(),
+                item_id: Some("msg-1".to_string()),
+            }),
+        };
+        let shutdown = Event {
+            id: "sub-1".to_string(),
+            msg: EventMsg::ShutdownComplete,
+        };
+
+        assert_eq!(
+            [
+                serde_json::to_value(&patch)?,
+                serde_json::to_value(&message)?,
+                serde_json::to_value(&shutdown)?,
+            ],
+            [
+                json!({
+                    "id": "sub-1",
+                    "item_id": "call-1",
+                    "parent_id": "turn-1",
+                    "msg": {
+                        "type": "patch_apply_begin",
+                        "call_id": "call-1",
+                        "turn_id": "turn-1",
+                        "auto_approved": true,
+                        "changes": {},
+                    },
+                }),
+                json!({
+                    "id": "sub-1",
+                    "item_id": "msg-1",
+                    "parent_id": "sub-1",
+                    "msg": {
+                        "type": "agent_message",
+                        "message": "done",
+                        "item_id": "msg-1",
+                    },
+                }),
+                json!({
+                    "id": "sub-1",
+                    "msg": { "type": "shutdown_complete" },
+                }),
+            ]
+        );
+
+        let round_trip: Event = serde_json::from_value(serde_json::to_value(&patch)?)?;
+        assert_eq!(
+            serde_json::to_value(&round_trip)?,
+            serde_json::to_value(&patch)?
+        );
+        Ok(())
+    }
+
     #[test]
     fn serialize_mcp_startup_update_event() -> Result<()> {
         let event = Event {
@@ -25,7 +25,10 @@ use mcp_types::ReadResourceResult;
 use mcp_types::RequestId;
 use mcp_types::Tool;
 use reqwest::header::HeaderMap;
+use rmcp::model::CallToolRequest;
 use rmcp::model::CallToolRequestParam;
+use rmcp::model::CancelledNotification;
+use rmcp::model::CancelledNotificationParam;
 use rmcp::model::ClientNotification;
 use rmcp::model::ClientRequest;
 use rmcp::model::CreateElicitationRequestParam;
@@ -37,8 +40,10 @@ use rmcp::model::InitializeRequestParam;
 use rmcp::model::PaginatedRequestParam;
 use rmcp::model::ReadResourceRequestParam;
 use rmcp::model::ServerResult;
+use rmcp::service::PeerRequestOptions;
 use rmcp::service::RoleClient;
 use rmcp::service::RunningService;
+use rmcp::service::ServiceError;
 use rmcp::service::{self};
 use rmcp::transport::StreamableHttpClientTransport;
 use rmcp::transport::auth::AuthClient;
@@ -51,6 +56,7 @@ use tokio::io::BufReader;
 use tokio::process::Command;
 use tokio::sync::Mutex;
 use tokio::time;
+use tokio_util::sync::CancellationToken;
 use tracing::info;
 use tracing::warn;
 
@@ -110,6 +116,35 @@ pub struct ListToolsWithConnectorIdResult {
 
 /// MCP client implemented on top of the official `rmcp` SDK.
 /// https://github.com/modelcontextprotocol/rust-sdk
+/// Why a `tools/call` request failed, kept typed (rather than flattened into
+/// the `anyhow` message) so callers can tell errors reported by the server
+/// from timeouts and connection problems.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToolCallFailure {
+    /// The server answered with a JSON-RPC error.
+    Rpc { code: i32, message: String },
+    /// The server did not answer within the tool timeout.
+    Timeout(Duration),
+    /// The request could not be sent, so the server never saw it.
+    NotSent(String),
+    /// The connection dropped after the request was sent.
+    Transport(String),
+}
+
+impl std::fmt::Display for ToolCallFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Rpc { code, message } => {
+                write!(f, "tools/call failed: {message} (JSON-RPC error {code})")
+            }
+            Self::Timeout(timeout) => write!(f, "timed out awaiting tools/call after {timeout:?}"),
+            Self::NotSent(err) | Self::Transport(err) => write!(f, "tools/call failed: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ToolCallFailure {}
+
 pub struct RmcpClient {
     state: Mutex<ClientState>,
 }
@@ -401,18 +436,66 @@ impl RmcpClient {
         Ok(converted)
     }
 
+    /// Calls a tool. When `cancellation_token` fires before the server
+    /// answers, the server is sent `notifications/cancelled` for the request,
+    /// as it is when the call times out, and the call fails.
     pub async fn call_tool(
         &self,
         name: String,
         arguments: Option<serde_json::Value>,
         timeout: Option<Duration>,
+        cancellation_token: CancellationToken,
     ) -> Result<CallToolResult> {
         self.refresh_oauth_if_needed().await;
         let service = self.service().await?;
         let params = CallToolRequestParams { arguments, name };
         let rmcp_params: CallToolRequestParam = convert_to_rmcp(params)?;
-        let fut = service.call_tool(rmcp_params);
-        let rmcp_result = run_with_timeout(fut, timeout, "tools/call").await?;
+        let handle = service
+            .send_cancellable_request(
+                ClientRequest::CallToolRequest(CallToolRequest {
+                    method: Default::default(),
+                    params: rmcp_params,
+                    extensions: Extensions::new(),
+                }),
+                PeerRequestOptions {
+                    timeout,
+                    meta: None,
+                },
+            )
+            .await
+            .map_err(|err| ToolCallFailure::NotSent(err.to_string()))?;
+        let peer = handle.peer.clone();
+        let request_id = handle.id.clone();
+        let response = tokio::select! {
+            response = handle.await_response() => response.map_err(|err| match err {
+                ServiceError::McpError(error) => ToolCallFailure::Rpc {
+                    code: error.code.0,
+                    message: error.message.to_string(),
+                },
+                ServiceError::Timeout { timeout } => ToolCallFailure::Timeout(timeout),
+                err => ToolCallFailure::Transport(err.to_string()),
+            })?,
+            _ = cancellation_token.cancelled() => {
+                let notification = CancelledNotification {
+                    method: Default::default(),
+                    params: CancelledNotificationParam {
+                        request_id,
+                        reason: Some("cancelled by the client".to_string()),
+                    },
+                    extensions: Extensions::new(),
+                };
+                if let Err(err) = peer
+                    .send_notification(ClientNotification::CancelledNotification(notification))
+                    .await
+                {
+                    warn!("failed to send notifications/cancelled for tools/call: {err}");
+                }
+                return Err(anyhow!("tools/call was cancelled"));
+            }
+        };
+        let ServerResult::CallToolResult(rmcp_result) = response else {
+            return Err(anyhow!("tools/call returned an unexpected response"));
+        };
         let converted = convert_call_tool_result(rmcp_result)?;
         self.persist_oauth_tokens().await;
         Ok(converted)
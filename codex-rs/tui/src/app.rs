@@ -6,12 +6,14 @@ use crate::app_event::WindowsSandboxEnableMode;
 #[cfg(target_os = "windows")]
 use crate::app_event::WindowsSandboxFallbackReason;
 use crate::app_event_sender::AppEventSender;
+use crate::app_tabs::TabsState;
 use crate::bottom_pane::ApprovalRequest;
 use crate::chatwidget::ChatWidget;
 use crate::chatwidget::ExternalEditorState;
 use crate::diff_render::DiffSummary;
 use crate::exec_command::strip_bash_lc_and_escape;
 use crate::external_editor;
+use crate::file_preview;
 use crate::file_search::FileSearchManager;
 use crate::history_cell;
 use crate::history_cell::HistoryCell;
@@ -58,6 +60,8 @@ use color_eyre::eyre::WrapErr;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
+use ratatui::layout::Constraint;
+use ratatui::layout::Layout;
 use ratatui::style::Stylize;
 use ratatui::text::Line;
 use ratatui::widgets::Paragraph;
@@ -78,6 +82,7 @@ use tokio::sync::broadcast;
 use tokio::sync::mpsc::unbounded_channel;
 
 const EXTERNAL_EDITOR_HINT: &str = "Save and close external editor to continue.";
+const DASHBOARD_TITLE: &str = "D A S H B O A R D";
 
 #[derive(Debug, Clone)]
 pub struct AppExitInfo {
@@ -332,6 +337,8 @@ pub(crate) struct App {
     pub(crate) otel_manager: OtelManager,
     pub(crate) app_event_tx: AppEventSender,
     pub(crate) chat_widget: ChatWidget,
+    /// Conversation tabs; the active tab's widget and transcript live on `App`.
+    pub(crate) tabs: TabsState,
     pub(crate) auth_manager: Arc<AuthManager>,
     /// Config is stored here so we can recreate ChatWidgets as needed.
     pub(crate) config: Config,
@@ -344,7 +351,7 @@ pub(crate) struct App {
     // Pager overlay state (Transcript or Static like Diff)
     pub(crate) overlay: Option<Overlay>,
     pub(crate) deferred_history_lines: Vec<Line<'static>>,
-    has_emitted_history_lines: bool,
+    pub(crate) has_emitted_history_lines: bool,
 
     pub(crate) enhanced_keys_supported: bool,
 
@@ -392,7 +399,7 @@ impl App {
         crate::chatwidget::ChatWidgetInit {
             config: cfg,
             frame_requester: tui.frame_requester(),
-            app_event_tx: self.app_event_tx.clone(),
+            app_event_tx: self.active_tab_event_tx(),
             // Fork/resume bootstraps here don't carry any prefilled message content.
             initial_user_message: None,
             enhanced_keys_supported: self.enhanced_keys_supported,
@@ -433,6 +440,7 @@ impl App {
         let app_event_tx = AppEventSender::new(app_event_tx);
         emit_deprecation_notice(&app_event_tx, ollama_chat_support_notice);
 
+        let tabs = TabsState::new();
         let thread_manager = Arc::new(ThreadManager::new(
             config.codex_home.clone(),
             auth_manager.clone(),
@@ -485,7 +493,7 @@ impl App {
                 let init = crate::chatwidget::ChatWidgetInit {
                     config: config.clone(),
                     frame_requester: tui.frame_requester(),
-                    app_event_tx: app_event_tx.clone(),
+                    app_event_tx: app_event_tx.for_tab(tabs.active_id()),
                     initial_user_message: crate::chatwidget::create_initial_user_message(
                         initial_prompt.clone(),
                         initial_images.clone(),
@@ -513,7 +521,7 @@ impl App {
                 let init = crate::chatwidget::ChatWidgetInit {
                     config: config.clone(),
                     frame_requester: tui.frame_requester(),
-                    app_event_tx: app_event_tx.clone(),
+                    app_event_tx: app_event_tx.for_tab(tabs.active_id()),
                     initial_user_message: crate::chatwidget::create_initial_user_message(
                         initial_prompt.clone(),
                         initial_images.clone(),
@@ -541,7 +549,7 @@ impl App {
                 let init = crate::chatwidget::ChatWidgetInit {
                     config: config.clone(),
                     frame_requester: tui.frame_requester(),
-                    app_event_tx: app_event_tx.clone(),
+                    app_event_tx: app_event_tx.for_tab(tabs.active_id()),
                     initial_user_message: crate::chatwidget::create_initial_user_message(
                         initial_prompt.clone(),
                         initial_images.clone(),
@@ -571,6 +579,7 @@ impl App {
             otel_manager: otel_manager.clone(),
             app_event_tx,
             chat_widget,
+            tabs,
             auth_manager: auth_manager.clone(),
             config,
             active_profile,
@@ -629,7 +638,10 @@ impl App {
             if let AppRunControl::Exit(exit_reason) = control {
                 return Ok(AppExitInfo {
                     token_usage: app.token_usage(),
-                    thread_id: app.chat_widget.thread_id(),
+                    thread_id: app
+                        .chat_widget
+                        .thread_id()
+                        .filter(|_| !app.config.incognito),
                     update_action: app.pending_update_action,
                     exit_reason,
                 });
@@ -676,7 +688,10 @@ impl App {
         tui.terminal.clear()?;
         Ok(AppExitInfo {
             token_usage: app.token_usage(),
-            thread_id: app.chat_widget.thread_id(),
+            thread_id: app
+                .chat_widget
+                .thread_id()
+                .filter(|_| !app.config.incognito),
             update_action: app.pending_update_action,
             exit_reason,
         })
@@ -708,17 +723,27 @@ impl App {
                         self.render_transcript_once(tui);
                     }
                     self.chat_widget.maybe_post_pending_notification(tui);
+                    tui.set_session_status(&self.chat_widget.session_status());
                     if self
                         .chat_widget
                         .handle_paste_burst_tick(tui.frame_requester())
                     {
                         return Ok(AppRunControl::Continue);
                     }
+                    let tab_strip = self.tab_strip_line();
+                    let tab_strip_height = u16::from(tab_strip.is_some());
                     tui.draw(
-                        self.chat_widget.desired_height(tui.terminal.size()?.width),
+                        self.chat_widget.desired_height(tui.terminal.size()?.width)
+                            + tab_strip_height,
                         |frame| {
-                            self.chat_widget.render(frame.area(), frame.buffer);
-                            if let Some((x, y)) = self.chat_widget.cursor_pos(frame.area()) {
+                            let [strip_area, chat_area] = Layout::vertical([
+                                Constraint::Length(tab_strip_height),
+                                Constraint::Fill(1),
+                            ])
+                            .areas(frame.area());
+                            tab_strip.render(strip_area, frame.buffer);
+                            self.chat_widget.render(chat_area, frame.buffer);
+                            if let Some((x, y)) = self.chat_widget.cursor_pos(chat_area) {
                                 frame.set_cursor_position((x, y));
                             }
                         },
@@ -747,7 +772,7 @@ impl App {
                 let init = crate::chatwidget::ChatWidgetInit {
                     config: self.config.clone(),
                     frame_requester: tui.frame_requester(),
-                    app_event_tx: self.app_event_tx.clone(),
+                    app_event_tx: self.active_tab_event_tx(),
                     // New sessions start without prefilled message content.
                     initial_user_message: None,
                     enhanced_keys_supported: self.enhanced_keys_supported,
@@ -938,6 +963,12 @@ impl App {
                     tui.frame_requester().schedule_frame();
                 }
             }
+            AppEvent::TabEvent { tab, event } => {
+                if tab == self.tabs.active_id() {
+                    return Box::pin(self.handle_event(tui, *event)).await;
+                }
+                self.handle_background_tab_event(tab, *event);
+            }
             AppEvent::ExternalApprovalRequest { thread_id, event } => {
                 self.handle_external_approval_request(thread_id, event);
             }
@@ -1032,6 +1063,42 @@ impl App {
                 ));
                 tui.frame_requester().schedule_frame();
             }
+            AppEvent::OpenFilePreview(reference) => {
+                let config = self.chat_widget.config_ref();
+                match file_preview::preview_lines(
+                    &reference,
+                    &config.cwd,
+                    config.sandbox_policy.get(),
+                ) {
+                    Ok(lines) => {
+                        let _ = tui.enter_alt_screen();
+                        self.overlay = Some(Overlay::new_static_with_lines(
+                            lines,
+                            "P R E V I E W".to_string(),
+                        ));
+                        tui.frame_requester().schedule_frame();
+                    }
+                    Err(message) => self.chat_widget.add_error_message(message),
+                }
+            }
+            AppEvent::ShowDashboard(lines) => {
+                let _ = tui.enter_alt_screen();
+                self.overlay = Some(Overlay::new_static_with_lines(
+                    lines,
+                    DASHBOARD_TITLE.to_string(),
+                ));
+                tui.frame_requester().schedule_frame();
+            }
+            AppEvent::RefreshDashboard(lines) => {
+                if let Some(Overlay::Static(overlay)) = &mut self.overlay
+                    && overlay.title() == DASHBOARD_TITLE
+                {
+                    overlay.set_lines(lines);
+                    tui.frame_requester().schedule_frame();
+                } else {
+                    self.chat_widget.on_dashboard_closed();
+                }
+            }
             AppEvent::StartFileSearch(query) => {
                 if !query.is_empty() {
                     self.file_search.on_user_query(query);
@@ -1311,6 +1378,7 @@ impl App {
                 self.chat_widget.set_approval_policy(policy);
             }
             AppEvent::UpdateSandboxPolicy(policy) => {
+                let policy = self.config.workspace_write_limits.apply(policy);
                 #[cfg(target_os = "windows")]
                 let policy_is_workspace_write_or_ro = matches!(
                     &policy,
@@ -1530,6 +1598,9 @@ impl App {
             AppEvent::ManageSkillsClosed => {
                 self.chat_widget.handle_manage_skills_closed();
             }
+            AppEvent::ExecApprovalPreviewed(request) => {
+                self.chat_widget.show_exec_approval(request);
+            }
             AppEvent::FullScreenApprovalRequest(request) => match request {
                 ApprovalRequest::ApplyPatch { cwd, changes, .. } => {
                     let _ = tui.enter_alt_screen();
@@ -1783,6 +1854,22 @@ impl App {
                     self.request_external_editor_launch(tui);
                 }
             }
+            KeyEvent {
+                code: KeyCode::Char('t'),
+                modifiers: crossterm::event::KeyModifiers::ALT,
+                kind: KeyEventKind::Press,
+                ..
+            } => {
+                self.open_new_tab(tui);
+            }
+            KeyEvent {
+                code: KeyCode::Char(code @ ('.' | ',')),
+                modifiers: crossterm::event::KeyModifiers::ALT,
+                kind: KeyEventKind::Press,
+                ..
+            } => {
+                self.cycle_tab(tui, code == '.');
+            }
             // Esc primes/advances backtracking only in normal (not working) mode
             // with the composer focused and empty. In any other state, forward
             // Esc so the active UI (e.g. status indicator, modals, popups)
@@ -1861,7 +1948,7 @@ impl App {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use crate::app_backtrack::BacktrackState;
     use crate::app_backtrack::user_count;
@@ -1910,6 +1997,7 @@ mod tests {
             otel_manager,
             app_event_tx,
             chat_widget,
+            tabs: TabsState::new(),
             auth_manager,
             config,
             active_profile: None,
@@ -1931,7 +2019,7 @@ mod tests {
         }
     }
 
-    async fn make_test_app_with_channels() -> (
+    pub(crate) async fn make_test_app_with_channels() -> (
         App,
         tokio::sync::mpsc::UnboundedReceiver<AppEvent>,
         tokio::sync::mpsc::UnboundedReceiver<Op>,
@@ -1954,6 +2042,7 @@ mod tests {
                 otel_manager,
                 app_event_tx,
                 chat_widget,
+                tabs: TabsState::new(),
                 auth_manager,
                 config,
                 active_profile: None,
@@ -2204,6 +2293,7 @@ mod tests {
                 history_entry_count: 0,
                 initial_messages: None,
                 rollout_path: PathBuf::new(),
+                incognito: false,
             };
             Arc::new(new_session_info(
                 app.chat_widget.config_ref(),
@@ -2251,6 +2341,7 @@ mod tests {
                 history_entry_count: 0,
                 initial_messages: None,
                 rollout_path: PathBuf::new(),
+                incognito: false,
             }),
         });
 
@@ -2294,6 +2385,7 @@ mod tests {
             history_entry_count: 0,
             initial_messages: None,
             rollout_path: PathBuf::new(),
+            incognito: false,
         };
 
         app.chat_widget.handle_codex_event(Event {
@@ -17,7 +17,9 @@ use codex_file_search::FileMatch;
 use codex_protocol::ThreadId;
 use codex_protocol::openai_models::ModelPreset;
 
+use crate::app_tabs::TabId;
 use crate::bottom_pane::ApprovalRequest;
+use crate::file_preview::FileReference;
 use crate::history_cell::HistoryCell;
 
 use codex_core::features::Feature;
@@ -43,6 +45,11 @@ pub(crate) enum WindowsSandboxFallbackReason {
 #[derive(Debug)]
 pub(crate) enum AppEvent {
     CodexEvent(Event),
+    /// An event emitted on behalf of a specific conversation tab.
+    TabEvent {
+        tab: TabId,
+        event: Box<AppEvent>,
+    },
     ExternalApprovalRequest {
         thread_id: ThreadId,
         event: Event,
@@ -91,6 +98,15 @@ pub(crate) enum AppEvent {
     /// Result of computing a `/diff` command.
     DiffResult(String),
 
+    /// Open the read-only preview overlay for a file mentioned in the session.
+    OpenFilePreview(FileReference),
+
+    /// Open the `/dashboard` overlay with pre-rendered lines.
+    ShowDashboard(Vec<ratatui::text::Line<'static>>),
+
+    /// Replace the lines of the `/dashboard` overlay if it is still open.
+    RefreshDashboard(Vec<ratatui::text::Line<'static>>),
+
     InsertHistoryCell(Box<dyn HistoryCell>),
 
     StartCommitAnimation,
@@ -249,6 +265,10 @@ pub(crate) enum AppEvent {
     /// Open the approval popup.
     FullScreenApprovalRequest(ApprovalRequest),
 
+    /// An exec approval whose shell-write preview was built off the UI
+    /// thread and is ready to show.
+    ExecApprovalPreviewed(ApprovalRequest),
+
     /// Open the feedback note entry overlay after the user selects a category.
     OpenFeedbackNote {
         category: FeedbackCategory,
@@ -0,0 +1,362 @@
+//! Multiple conversations ("tabs") within a single TUI process.
+//!
+//! Every tab owns its own `ChatWidget`, and therefore its own `CodexThread`, while sharing the
+//! `ThreadManager`, `AuthManager`, and `Config` held by `App`. The active tab's widget and
+//! transcript live directly on `App` so the rest of the app keeps working unchanged; inactive
+//! tabs are parked in [`TabsState`] together with their transcript cells.
+//!
+//! Chat widgets send their events through an `AppEventSender` scoped with
+//! [`AppEventSender::for_tab`], so the app receives them wrapped in `AppEvent::TabEvent`. Events
+//! for the active tab are handled as usual. Events for a parked tab keep its conversation moving
+//! in the background (codex events, ops, history cells) and anything that needs the screen is
+//! deferred until the tab is activated again. New history in a parked tab marks it unread.
+//!
+//! Switching tabs appends a short header plus the activated tab's transcript to the terminal
+//! scrollback, the same way a confirmed backtrack re-renders the trimmed transcript.
+
+use std::mem;
+use std::sync::Arc;
+
+use codex_core::protocol::EventMsg;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::text::Span;
+
+use crate::app::App;
+use crate::app_event::AppEvent;
+use crate::app_event_sender::AppEventSender;
+use crate::chatwidget::ChatWidget;
+use crate::history_cell::HistoryCell;
+use crate::history_cell::UserHistoryCell;
+use crate::text_formatting::truncate_text;
+use crate::tui;
+
+/// Maximum number of graphemes shown for a tab title.
+const MAX_TITLE_GRAPHEMES: usize = 24;
+
+/// Identifies a conversation tab for the lifetime of the process.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct TabId(u64);
+
+/// The set of open tabs, in display order.
+pub(crate) struct TabsState {
+    tabs: Vec<Tab>,
+    active: usize,
+    next_id: u64,
+}
+
+struct Tab {
+    id: TabId,
+    unread: bool,
+    /// `None` for the active tab, whose state lives on `App`.
+    parked: Option<ParkedTab>,
+}
+
+struct ParkedTab {
+    chat_widget: ChatWidget,
+    transcript_cells: Vec<Arc<dyn HistoryCell>>,
+    /// UI events emitted while parked, replayed once the tab is active again.
+    pending_events: Vec<AppEvent>,
+}
+
+impl TabsState {
+    pub(crate) fn new() -> Self {
+        Self {
+            tabs: vec![Tab {
+                id: TabId(0),
+                unread: false,
+                parked: None,
+            }],
+            active: 0,
+            next_id: 1,
+        }
+    }
+
+    pub(crate) fn active_id(&self) -> TabId {
+        self.tabs[self.active].id
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.tabs.len()
+    }
+
+    fn allocate_id(&mut self) -> TabId {
+        let id = TabId(self.next_id);
+        self.next_id += 1;
+        id
+    }
+
+    fn parked_mut(&mut self, id: TabId) -> Option<(&mut bool, &mut ParkedTab)> {
+        self.tabs
+            .iter_mut()
+            .find(|tab| tab.id == id)
+            .and_then(|tab| Some((&mut tab.unread, tab.parked.as_mut()?)))
+    }
+}
+
+impl App {
+    /// Event sender for chat widgets created in the active tab.
+    pub(crate) fn active_tab_event_tx(&self) -> AppEventSender {
+        self.app_event_tx.for_tab(self.tabs.active_id())
+    }
+
+    /// Start a fresh conversation in a new tab placed after the active one.
+    pub(crate) fn open_new_tab(&mut self, tui: &mut tui::Tui) {
+        let id = self.tabs.allocate_id();
+        let init = crate::chatwidget::ChatWidgetInit {
+            config: self.config.clone(),
+            frame_requester: tui.frame_requester(),
+            app_event_tx: self.app_event_tx.for_tab(id),
+            initial_user_message: None,
+            enhanced_keys_supported: self.enhanced_keys_supported,
+            auth_manager: self.auth_manager.clone(),
+            models_manager: self.server.get_models_manager(),
+            feedback: self.feedback.clone(),
+            is_first_run: false,
+            model: Some(self.chat_widget.current_model().to_string()),
+            otel_manager: self.otel_manager.clone(),
+        };
+        let chat_widget = ChatWidget::new(init, self.server.clone());
+        let index = self.park_new_tab(id, chat_widget);
+        self.activate_tab(tui, index);
+    }
+
+    /// Place a new, parked tab after the active one and return its index.
+    fn park_new_tab(&mut self, id: TabId, chat_widget: ChatWidget) -> usize {
+        let index = self.tabs.active + 1;
+        self.tabs.tabs.insert(
+            index,
+            Tab {
+                id,
+                unread: false,
+                parked: Some(ParkedTab {
+                    chat_widget,
+                    transcript_cells: Vec::new(),
+                    pending_events: Vec::new(),
+                }),
+            },
+        );
+        index
+    }
+
+    /// Switch to the next (or previous) tab, wrapping around at either end.
+    pub(crate) fn cycle_tab(&mut self, tui: &mut tui::Tui, forward: bool) {
+        let len = self.tabs.len();
+        if len < 2 {
+            return;
+        }
+        let index = if forward {
+            (self.tabs.active + 1) % len
+        } else {
+            (self.tabs.active + len - 1) % len
+        };
+        self.activate_tab(tui, index);
+    }
+
+    fn activate_tab(&mut self, tui: &mut tui::Tui, index: usize) {
+        let Some(incoming) = self.tabs.tabs[index].parked.take() else {
+            return;
+        };
+        self.reset_backtrack_state();
+        self.backtrack.pending_rollback = None;
+
+        let ParkedTab {
+            chat_widget,
+            transcript_cells,
+            pending_events,
+        } = incoming;
+        let outgoing = ParkedTab {
+            chat_widget: mem::replace(&mut self.chat_widget, chat_widget),
+            transcript_cells: mem::replace(&mut self.transcript_cells, transcript_cells),
+            pending_events: Vec::new(),
+        };
+        self.tabs.tabs[self.tabs.active].parked = Some(outgoing);
+        self.tabs.active = index;
+        self.tabs.tabs[index].unread = false;
+
+        let title = tab_title(&self.transcript_cells);
+        tui.insert_history_lines(vec![
+            Line::from(""),
+            Line::from(vec![
+                "── ".dim(),
+                format!("Tab {}: {title}", index + 1).bold(),
+                " ──".dim(),
+            ]),
+        ]);
+        self.has_emitted_history_lines = true;
+        self.backtrack_render_pending = true;
+        for event in pending_events {
+            self.app_event_tx.send(event);
+        }
+        tui.frame_requester().schedule_frame();
+    }
+
+    /// Keep a parked tab's conversation running while it is not on screen.
+    pub(crate) fn handle_background_tab_event(&mut self, tab: TabId, event: AppEvent) {
+        let Some((unread, parked)) = self.tabs.parked_mut(tab) else {
+            // The tab became active since the event was sent.
+            if tab == self.tabs.active_id() {
+                self.app_event_tx.send(event);
+            }
+            return;
+        };
+        match event {
+            AppEvent::CodexEvent(event) => {
+                if matches!(
+                    event.msg,
+                    EventMsg::ExecApprovalRequest(_)
+                        | EventMsg::ApplyPatchApprovalRequest(_)
+                        | EventMsg::RequestUserInput(_)
+                ) {
+                    *unread = true;
+                }
+                parked.chat_widget.handle_codex_event(event);
+            }
+            AppEvent::ExecApprovalPreviewed(request) => {
+                *unread = true;
+                parked.chat_widget.show_exec_approval(request);
+            }
+            AppEvent::CodexOp(op) => parked.chat_widget.submit_op(op),
+            AppEvent::InsertHistoryCell(cell) => {
+                parked.transcript_cells.push(cell.into());
+                *unread = true;
+            }
+            // Streaming output is flushed when the turn completes, so the shared
+            // commit animation only needs to drive the active tab.
+            AppEvent::StartCommitAnimation
+            | AppEvent::StopCommitAnimation
+            | AppEvent::CommitTick => {}
+            event => parked.pending_events.push(event),
+        }
+    }
+
+    /// One-line tab strip shown above the chat when more than one tab is open.
+    pub(crate) fn tab_strip_line(&self) -> Option<Line<'static>> {
+        if self.tabs.len() < 2 {
+            return None;
+        }
+        let mut spans: Vec<Span<'static>> = Vec::new();
+        for (index, tab) in self.tabs.tabs.iter().enumerate() {
+            if index > 0 {
+                spans.push(" │ ".dim());
+            }
+            let cells = match &tab.parked {
+                Some(parked) => &parked.transcript_cells,
+                None => &self.transcript_cells,
+            };
+            let label = format!("{} {}", index + 1, tab_title(cells));
+            if index == self.tabs.active {
+                spans.push(label.cyan().bold());
+            } else {
+                spans.push(label.dim());
+            }
+            if tab.unread {
+                spans.push(" •".magenta());
+            }
+        }
+        Some(Line::from(spans))
+    }
+}
+
+/// Title for a tab: the first line of its first user message.
+fn tab_title(transcript_cells: &[Arc<dyn HistoryCell>]) -> String {
+    transcript_cells
+        .iter()
+        .find_map(|cell| cell.as_any().downcast_ref::<UserHistoryCell>())
+        .and_then(|cell| cell.message.lines().find(|line| !line.trim().is_empty()))
+        .map(|line| truncate_text(line.trim(), MAX_TITLE_GRAPHEMES))
+        .unwrap_or_else(|| "New chat".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::tests::make_test_app_with_channels;
+    use crate::chatwidget::tests::make_chatwidget_manual_with_sender;
+    use crate::history_cell::AgentMessageCell;
+    use pretty_assertions::assert_eq;
+
+    fn user_cell(message: &str) -> Arc<dyn HistoryCell> {
+        Arc::new(UserHistoryCell {
+            message: message.to_string(),
+            text_elements: Vec::new(),
+            local_image_paths: Vec::new(),
+        })
+    }
+
+    #[test]
+    fn tab_title_uses_first_user_message() {
+        assert_eq!(tab_title(&[]), "New chat");
+
+        let cells = vec![
+            Arc::new(AgentMessageCell::new(vec![Line::from("hello")], true))
+                as Arc<dyn HistoryCell>,
+            user_cell("\nfix the flaky test\nin core"),
+            user_cell("second message"),
+        ];
+        assert_eq!(tab_title(&cells), "fix the flaky test");
+    }
+
+    #[test]
+    fn tab_title_is_truncated() {
+        let cells = vec![user_cell(
+            "refactor the markdown renderer to support tables",
+        )];
+        assert_eq!(tab_title(&cells), "refactor the markdown...");
+    }
+
+    fn tab_strip_text(app: &App) -> Option<String> {
+        app.tab_strip_line().map(|line| {
+            line.spans
+                .iter()
+                .map(|span| span.content.as_ref())
+                .collect()
+        })
+    }
+
+    #[tokio::test]
+    async fn parked_tabs_collect_history_and_are_marked_unread() {
+        let (mut app, mut app_event_rx, _op_rx) = make_test_app_with_channels().await;
+        assert_eq!(tab_strip_text(&app), None);
+
+        let (chat_widget, _tx, _rx, _op_rx) = make_chatwidget_manual_with_sender().await;
+        let parked_id = app.tabs.allocate_id();
+        app.park_new_tab(parked_id, chat_widget);
+        assert_eq!(
+            tab_strip_text(&app),
+            Some("1 New chat │ 2 New chat".to_string())
+        );
+
+        let user_message = |message: &str| {
+            AppEvent::InsertHistoryCell(Box::new(UserHistoryCell {
+                message: message.to_string(),
+                text_elements: Vec::new(),
+                local_image_paths: Vec::new(),
+            }))
+        };
+        app.handle_background_tab_event(parked_id, user_message("fix the flaky test"));
+        assert_eq!(
+            tab_strip_text(&app),
+            Some("1 New chat │ 2 fix the flaky test •".to_string())
+        );
+        assert!(app.transcript_cells.is_empty());
+
+        // Events for the active tab are not parked but handled as usual.
+        app.handle_background_tab_event(app.tabs.active_id(), user_message("hello"));
+        assert!(matches!(
+            app_event_rx.try_recv(),
+            Ok(AppEvent::InsertHistoryCell(_))
+        ));
+    }
+
+    #[test]
+    fn tab_ids_are_unique() {
+        let mut tabs = TabsState::new();
+        let first = tabs.active_id();
+        let second = tabs.allocate_id();
+        let third = tabs.allocate_id();
+        assert_ne!(first, second);
+        assert_ne!(second, third);
+        assert_eq!(tabs.len(), 1);
+    }
+}
@@ -1,4 +1,6 @@
 use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
 use std::path::PathBuf;
 
 use crate::app_event::AppEvent;
@@ -18,6 +20,8 @@ use crate::render::renderable::ColumnRenderable;
 use crate::render::renderable::Renderable;
 use codex_core::features::Feature;
 use codex_core::features::Features;
+use codex_core::parse_command::ShellFileWrite;
+use codex_core::protocol::ApprovalScope;
 use codex_core::protocol::ElicitationAction;
 use codex_core::protocol::ExecPolicyAmendment;
 use codex_core::protocol::FileChange;
@@ -44,6 +48,10 @@ pub(crate) enum ApprovalRequest {
         command: Vec<String>,
         reason: Option<String>,
         proposed_execpolicy_amendment: Option<ExecPolicyAmendment>,
+        cwd: PathBuf,
+        /// Files the command writes through here-docs, redirects or `tee`,
+        /// previewed like a patch.
+        changes: HashMap<PathBuf, FileChange>,
     },
     ApplyPatch {
         id: String,
@@ -307,6 +315,13 @@ impl BottomPaneView for ApprovalOverlay {
         self.done
     }
 
+    fn pending_approval_count(&self) -> usize {
+        if self.done {
+            return 0;
+        }
+        self.queue.len() + usize::from(self.current_request.is_some() && !self.current_complete)
+    }
+
     fn try_consume_approval_request(
         &mut self,
         request: ApprovalRequest,
@@ -343,25 +358,33 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                 command,
                 reason,
                 proposed_execpolicy_amendment,
+                cwd,
+                changes,
             } => {
-                let mut header: Vec<Line<'static>> = Vec::new();
+                let mut lines: Vec<Line<'static>> = Vec::new();
                 if let Some(reason) = reason {
-                    header.push(Line::from(vec!["Reason: ".into(), reason.italic()]));
-                    header.push(Line::from(""));
+                    lines.push(Line::from(vec!["Reason: ".into(), reason.italic()]));
+                    lines.push(Line::from(""));
                 }
                 let full_cmd = strip_bash_lc_and_escape(&command);
                 let mut full_cmd_lines = highlight_bash_to_lines(&full_cmd);
                 if let Some(first) = full_cmd_lines.first_mut() {
                     first.spans.insert(0, Span::from("$ "));
                 }
-                header.extend(full_cmd_lines);
+                lines.extend(full_cmd_lines);
+                let mut header: Vec<Box<dyn Renderable>> =
+                    vec![Box::new(Paragraph::new(lines).wrap(Wrap { trim: false }))];
+                if !changes.is_empty() {
+                    header.push(Box::new(Line::from("")));
+                    header.push(DiffSummary::new(changes, cwd).into());
+                }
                 Self {
                     variant: ApprovalVariant::Exec {
                         id,
                         command,
                         proposed_execpolicy_amendment,
                     },
-                    header: Box::new(Paragraph::new(header).wrap(Wrap { trim: false })),
+                    header: Box::new(ColumnRenderable::with(header)),
                 }
             }
             ApprovalRequest::ApplyPatch {
@@ -504,6 +527,14 @@ fn patch_options() -> Vec<ApprovalOption> {
             display_shortcut: None,
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('a'))],
         },
+        ApprovalOption {
+            label: "Yes, and don't ask again for these files in this project".to_string(),
+            decision: ApprovalDecision::Review(ReviewDecision::ApprovedAlways {
+                scope: ApprovalScope::Project,
+            }),
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('r'))],
+        },
         ApprovalOption {
             label: "No, and tell Codex what to do differently".to_string(),
             decision: ApprovalDecision::Review(ReviewDecision::Abort),
@@ -536,10 +567,81 @@ fn elicitation_options() -> Vec<ApprovalOption> {
     ]
 }
 
+/// Files larger than this are left out of shell-write previews.
+const MAX_PREVIEW_FILE_BYTES: u64 = 1024 * 1024;
+
+/// The changes `writes` would make against the current contents of the files
+/// under `cwd`. This reads from disk, so call it off the UI thread. Targets
+/// that are not regular files, or are too large to diff, are left out.
+pub(crate) fn shell_write_changes(
+    writes: Vec<ShellFileWrite>,
+    cwd: &Path,
+) -> HashMap<PathBuf, FileChange> {
+    let mut files: HashMap<PathBuf, Option<(Option<String>, String)>> = HashMap::new();
+    for write in writes {
+        let path = cwd.join(&write.path);
+        let entry = files.entry(path).or_insert_with_key(|path| {
+            let original = read_preview_original(path)?;
+            let content = original.clone().unwrap_or_default();
+            Some((original, content))
+        });
+        let Some((_, content)) = entry else {
+            continue;
+        };
+        if write.append {
+            content.push_str(&write.content);
+        } else {
+            *content = write.content;
+        }
+    }
+    files
+        .into_iter()
+        .filter_map(|(path, file)| {
+            let (original, content) = file?;
+            let change = match original {
+                Some(original) => FileChange::Update {
+                    unified_diff: diffy::create_patch(&original, &content).to_string(),
+                    move_path: None,
+                    mode: None,
+                },
+                None => FileChange::Add {
+                    content,
+                    mode: None,
+                },
+            };
+            Some((path, change))
+        })
+        .collect()
+}
+
+/// `Some(None)` when `path` does not exist yet, `Some(Some(text))` for a
+/// regular text file within the preview limit, and `None` for anything that
+/// cannot be previewed.
+fn read_preview_original(path: &Path) -> Option<Option<String>> {
+    let metadata = match std::fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Some(None),
+        Err(_) => return None,
+    };
+    // Checked before opening: opening a FIFO blocks until a writer shows up.
+    if !metadata.is_file() || metadata.len() > MAX_PREVIEW_FILE_BYTES {
+        return None;
+    }
+    let mut text = String::new();
+    std::fs::File::open(path)
+        .ok()?
+        .take(MAX_PREVIEW_FILE_BYTES + 1)
+        .read_to_string(&mut text)
+        .ok()?;
+    (text.len() as u64 <= MAX_PREVIEW_FILE_BYTES).then_some(Some(text))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::app_event::AppEvent;
+    use codex_core::parse_command::extract_file_writes;
+    use insta::assert_snapshot;
     use pretty_assertions::assert_eq;
     use tokio::sync::mpsc::unbounded_channel;
 
@@ -549,6 +651,8 @@ mod tests {
             command: vec!["echo".to_string(), "hi".to_string()],
             reason: Some("reason".to_string()),
             proposed_execpolicy_amendment: None,
+            cwd: PathBuf::new(),
+            changes: HashMap::new(),
         }
     }
 
@@ -563,6 +667,18 @@ mod tests {
         assert!(view.is_complete());
     }
 
+    #[test]
+    fn counts_current_and_queued_requests_as_pending() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx, Features::with_defaults());
+        assert_eq!(view.pending_approval_count(), 1);
+        view.enqueue_request(make_exec_request());
+        assert_eq!(view.pending_approval_count(), 2);
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        assert_eq!(view.pending_approval_count(), 1);
+    }
+
     #[test]
     fn shortcut_triggers_selection() {
         let (tx, mut rx) = unbounded_channel::<AppEvent>();
@@ -593,6 +709,8 @@ mod tests {
                 proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(vec![
                     "echo".to_string(),
                 ])),
+                cwd: PathBuf::new(),
+                changes: HashMap::new(),
             },
             tx,
             Features::with_defaults(),
@@ -631,6 +749,8 @@ mod tests {
                 proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(vec![
                     "echo".to_string(),
                 ])),
+                cwd: PathBuf::new(),
+                changes: HashMap::new(),
             },
             tx,
             {
@@ -655,6 +775,8 @@ mod tests {
             command,
             reason: None,
             proposed_execpolicy_amendment: None,
+            cwd: PathBuf::new(),
+            changes: HashMap::new(),
         };
 
         let view = ApprovalOverlay::new(exec_request, tx, Features::with_defaults());
@@ -676,6 +798,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn shell_writes_are_previewed_as_file_changes() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::write(dir.path().join("existing.txt"), "one\n").expect("write");
+        std::fs::create_dir_all(dir.path().join("sub/out")).expect("mkdir");
+        let big = "x".repeat(MAX_PREVIEW_FILE_BYTES as usize + 1);
+        std::fs::write(dir.path().join("sub/big.log"), big).expect("write");
+        let command = vec![
+            "bash".to_string(),
+            "-lc".to_string(),
+            "echo two >> existing.txt && cat > new.txt <<'EOF'\nhello\nEOF\ncd sub && echo three > nested.txt && echo more >> big.log && echo x > out".to_string(),
+        ];
+
+        let changes = shell_write_changes(extract_file_writes(&command), dir.path());
+
+        let mut rendered: Vec<String> = changes
+            .iter()
+            .map(|(path, change)| {
+                let path = path
+                    .strip_prefix(dir.path())
+                    .unwrap_or(path)
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                let text = match change {
+                    FileChange::Add { content, .. } => format!("add {path}\n{content}"),
+                    FileChange::Update { unified_diff, .. } => {
+                        format!("update {path}\n{unified_diff}")
+                    }
+                    other => format!("{path}: {other:?}"),
+                };
+                text.trim_end().to_string()
+            })
+            .collect();
+        rendered.sort();
+        assert_snapshot!("shell_write_changes", rendered.join("\n\n"));
+    }
+
     #[test]
     fn exec_history_cell_wraps_with_two_space_indent() {
         let command = vec![
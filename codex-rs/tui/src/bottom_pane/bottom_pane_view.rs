@@ -21,12 +21,23 @@ pub(crate) trait BottomPaneView: Renderable {
         CancellationEvent::NotHandled
     }
 
+    /// Return `true` if Esc should reach `handle_key_event` instead of
+    /// cancelling the view.
+    fn passes_through_esc(&self) -> bool {
+        false
+    }
+
     /// Optional paste handler. Return true if the view modified its state and
     /// needs a redraw.
     fn handle_paste(&mut self, _pasted: String) -> bool {
         false
     }
 
+    /// Number of approval requests the view is waiting on the user for.
+    fn pending_approval_count(&self) -> usize {
+        0
+    }
+
     /// Try to handle approval request; return the original value if not
     /// consumed.
     fn try_consume_approval_request(
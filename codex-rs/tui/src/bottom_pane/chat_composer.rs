@@ -1819,10 +1819,32 @@ impl ChatComposer {
                 && let Some((_n, cmd)) =
                     Self::built_in_slash_commands_for_input(self.collaboration_modes_enabled)
                         .find(|(command_name, _)| *command_name == name)
-                && cmd == SlashCommand::Review
+                && matches!(
+                    cmd,
+                    SlashCommand::Review
+                        | SlashCommand::Explore
+                        | SlashCommand::Clip
+                        | SlashCommand::Secrets
+                        | SlashCommand::Feature
+                )
             {
+                // `/clip` stores large pastes, so hand over their full text
+                // rather than the placeholders.
+                let args = if cmd == SlashCommand::Clip && !self.pending_pastes.is_empty() {
+                    let (expanded, _) = Self::expand_pending_pastes(
+                        &text,
+                        self.textarea.text_elements(),
+                        &self.pending_pastes,
+                    );
+                    parse_slash_name(&expanded)
+                        .map(|(_, rest, _)| rest.to_string())
+                        .unwrap_or_default()
+                } else {
+                    rest.to_string()
+                };
                 self.textarea.set_text_clearing_elements("");
-                return Some(InputResult::CommandWithArgs(cmd, rest.to_string()));
+                self.pending_pastes.clear();
+                return Some(InputResult::CommandWithArgs(cmd, args));
             }
         }
         None
@@ -40,6 +40,7 @@ mod approval_overlay;
 mod request_user_input;
 pub(crate) use approval_overlay::ApprovalOverlay;
 pub(crate) use approval_overlay::ApprovalRequest;
+pub(crate) use approval_overlay::shell_write_changes;
 pub(crate) use request_user_input::RequestUserInputOverlay;
 mod bottom_pane_view;
 
@@ -72,6 +73,10 @@ pub mod popup_consts;
 mod queued_user_messages;
 mod scroll_state;
 mod selection_popup_common;
+mod terminal_takeover_view;
+pub(crate) use terminal_takeover_view::SharedTerminalScreen;
+pub(crate) use terminal_takeover_view::TerminalScreen;
+pub(crate) use terminal_takeover_view::TerminalTakeoverView;
 mod textarea;
 mod unified_exec_footer;
 pub(crate) use feedback_view::FeedbackNoteView;
@@ -247,6 +252,7 @@ impl BottomPane {
         // If a modal/view is active, handle it here; otherwise forward to composer.
         if let Some(view) = self.view_stack.last_mut() {
             if key_event.code == KeyCode::Esc
+                && !view.passes_through_esc()
                 && matches!(view.on_ctrl_c(), CancellationEvent::Handled)
                 && view.is_complete()
             {
@@ -560,6 +566,14 @@ impl BottomPane {
         self.composer.is_empty()
     }
 
+    /// Approval requests shown or queued in the bottom pane.
+    pub(crate) fn pending_approval_count(&self) -> usize {
+        self.view_stack
+            .iter()
+            .map(|view| view.pending_approval_count())
+            .sum()
+    }
+
     pub(crate) fn is_task_running(&self) -> bool {
         self.is_task_running
     }
@@ -762,6 +776,7 @@ mod tests {
     use insta::assert_snapshot;
     use ratatui::buffer::Buffer;
     use ratatui::layout::Rect;
+    use std::collections::HashMap;
     use std::path::PathBuf;
     use tokio::sync::mpsc::unbounded_channel;
 
@@ -789,6 +804,8 @@ mod tests {
             command: vec!["echo".into(), "ok".into()],
             reason: None,
             proposed_execpolicy_amendment: None,
+            cwd: PathBuf::new(),
+            changes: HashMap::new(),
         }
     }
 
@@ -0,0 +1,328 @@
+//! Split pane for taking over a raw unified exec session.
+//!
+//! The pane renders the session's output through a terminal emulator and
+//! forwards every key the user types to the process as `Op::TerminalInput`.
+//! Ctrl+] hands the session back to the agent.
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+
+use codex_core::protocol::Op;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyEventKind;
+use crossterm::event::KeyModifiers;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::widgets::Widget;
+
+use crate::app_event::AppEvent;
+use crate::app_event_sender::AppEventSender;
+use crate::render::renderable::Renderable;
+
+use super::CancellationEvent;
+use super::bottom_pane_view::BottomPaneView;
+
+/// Size of the PTY unified exec sessions are started with.
+const SCREEN_ROWS: u16 = 24;
+const SCREEN_COLS: u16 = 80;
+
+/// Emulated screen of a session, fed by the chat widget with the session's
+/// output deltas and drawn by [`TerminalTakeoverView`].
+pub(crate) struct TerminalScreen {
+    parser: vt100::Parser,
+    exited: bool,
+}
+
+pub(crate) type SharedTerminalScreen = Arc<Mutex<TerminalScreen>>;
+
+impl TerminalScreen {
+    pub(crate) fn new_shared() -> SharedTerminalScreen {
+        Arc::new(Mutex::new(Self {
+            parser: vt100::Parser::new(SCREEN_ROWS, SCREEN_COLS, 0),
+            exited: false,
+        }))
+    }
+
+    pub(crate) fn process(&mut self, output: &[u8]) {
+        self.parser.process(output);
+    }
+
+    pub(crate) fn mark_exited(&mut self) {
+        self.exited = true;
+    }
+}
+
+pub(crate) struct TerminalTakeoverView {
+    process_id: String,
+    command_display: String,
+    screen: SharedTerminalScreen,
+    app_event_tx: AppEventSender,
+    complete: bool,
+}
+
+impl TerminalTakeoverView {
+    pub(crate) fn new(
+        process_id: String,
+        command_display: String,
+        screen: SharedTerminalScreen,
+        app_event_tx: AppEventSender,
+    ) -> Self {
+        Self {
+            process_id,
+            command_display,
+            screen,
+            app_event_tx,
+            complete: false,
+        }
+    }
+
+    fn send_input(&self, input: String) {
+        self.app_event_tx.send(AppEvent::CodexOp(Op::TerminalInput {
+            process_id: self.process_id.clone(),
+            input,
+        }));
+    }
+
+    fn exited(&self) -> bool {
+        self.screen
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .exited
+    }
+}
+
+impl BottomPaneView for TerminalTakeoverView {
+    fn handle_key_event(&mut self, key_event: KeyEvent) {
+        if key_event.kind == KeyEventKind::Release {
+            return;
+        }
+        let detach = key_event.code == KeyCode::Char(']')
+            && key_event.modifiers.contains(KeyModifiers::CONTROL);
+        if detach || self.exited() {
+            self.complete = true;
+            return;
+        }
+        if let Some(input) = key_input(key_event) {
+            self.send_input(input);
+        }
+    }
+
+    fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    fn on_ctrl_c(&mut self) -> CancellationEvent {
+        if self.exited() {
+            self.complete = true;
+        } else {
+            self.send_input("\u{3}".to_string());
+        }
+        CancellationEvent::Handled
+    }
+
+    fn passes_through_esc(&self) -> bool {
+        true
+    }
+
+    fn handle_paste(&mut self, pasted: String) -> bool {
+        if pasted.is_empty() || self.exited() {
+            return false;
+        }
+        self.send_input(pasted);
+        false
+    }
+}
+
+impl Renderable for TerminalTakeoverView {
+    fn desired_height(&self, _width: u16) -> u16 {
+        SCREEN_ROWS + 1
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+        let screen = self.screen.lock().unwrap_or_else(PoisonError::into_inner);
+        let hint = if screen.exited {
+            "exited · press any key to close".dim()
+        } else {
+            "ctrl + ] to detach".dim()
+        };
+        Line::from(vec![
+            "Taking over ".into(),
+            self.command_display.clone().cyan(),
+            format!(" (process {}) · ", self.process_id).dim(),
+            hint,
+        ])
+        .render(Rect { height: 1, ..area }, buf);
+
+        let vt = screen.parser.screen();
+        let rows = SCREEN_ROWS.min(area.height.saturating_sub(1));
+        let cols = SCREEN_COLS.min(area.width);
+        for row in 0..rows {
+            for col in 0..cols {
+                let Some(cell) = vt.cell(row, col) else {
+                    continue;
+                };
+                let contents = cell.contents();
+                let symbol = if contents.is_empty() { " " } else { contents };
+                buf[(area.x + col, area.y + 1 + row)]
+                    .set_symbol(symbol)
+                    .set_style(cell_style(cell));
+            }
+        }
+    }
+
+    fn cursor_pos(&self, area: Rect) -> Option<(u16, u16)> {
+        let screen = self.screen.lock().unwrap_or_else(PoisonError::into_inner);
+        let vt = screen.parser.screen();
+        if screen.exited || vt.hide_cursor() {
+            return None;
+        }
+        let (row, col) = vt.cursor_position();
+        (row + 1 < area.height && col < area.width).then_some((area.x + col, area.y + 1 + row))
+    }
+}
+
+fn cell_style(cell: &vt100::Cell) -> Style {
+    let mut style = Style::default()
+        .fg(color(cell.fgcolor()))
+        .bg(color(cell.bgcolor()));
+    if cell.bold() {
+        style = style.add_modifier(Modifier::BOLD);
+    }
+    if cell.italic() {
+        style = style.add_modifier(Modifier::ITALIC);
+    }
+    if cell.underline() {
+        style = style.add_modifier(Modifier::UNDERLINED);
+    }
+    if cell.inverse() {
+        style = style.add_modifier(Modifier::REVERSED);
+    }
+    style
+}
+
+fn color(color: vt100::Color) -> Color {
+    match color {
+        vt100::Color::Default => Color::Reset,
+        vt100::Color::Idx(index) => Color::Indexed(index),
+        vt100::Color::Rgb(r, g, b) => Color::Rgb(r, g, b),
+    }
+}
+
+/// Bytes a terminal would send for `key_event`, or `None` for keys that have
+/// no encoding.
+fn key_input(key_event: KeyEvent) -> Option<String> {
+    let KeyEvent {
+        code, modifiers, ..
+    } = key_event;
+    let input = match code {
+        KeyCode::Char(c) if modifiers.contains(KeyModifiers::CONTROL) => {
+            if !c.is_ascii() {
+                return None;
+            }
+            char::from(c.to_ascii_lowercase() as u8 & 0x1f).to_string()
+        }
+        KeyCode::Char(c) if modifiers.contains(KeyModifiers::ALT) => format!("\u{1b}{c}"),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Enter => "\r".to_string(),
+        KeyCode::Backspace => "\u{7f}".to_string(),
+        KeyCode::Tab => "\t".to_string(),
+        KeyCode::BackTab => "\u{1b}[Z".to_string(),
+        KeyCode::Esc => "\u{1b}".to_string(),
+        KeyCode::Up => "\u{1b}[A".to_string(),
+        KeyCode::Down => "\u{1b}[B".to_string(),
+        KeyCode::Right => "\u{1b}[C".to_string(),
+        KeyCode::Left => "\u{1b}[D".to_string(),
+        KeyCode::Home => "\u{1b}[H".to_string(),
+        KeyCode::End => "\u{1b}[F".to_string(),
+        KeyCode::PageUp => "\u{1b}[5~".to_string(),
+        KeyCode::PageDown => "\u{1b}[6~".to_string(),
+        KeyCode::Insert => "\u{1b}[2~".to_string(),
+        KeyCode::Delete => "\u{1b}[3~".to_string(),
+        _ => return None,
+    };
+    Some(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_snapshot;
+    use pretty_assertions::assert_eq;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    fn render_lines(view: &TerminalTakeoverView, width: u16, height: u16) -> String {
+        let area = Rect::new(0, 0, width, height);
+        let mut buf = Buffer::empty(area);
+        view.render(area, &mut buf);
+        (0..area.height)
+            .map(|row| {
+                (0..area.width)
+                    .map(|col| buf[(col, row)].symbol().to_string())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn renders_the_emulated_screen() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let screen = TerminalScreen::new_shared();
+        screen
+            .lock()
+            .expect("screen")
+            .process(b"\x1b[2J\x1b[H\x1b[31mtop\x1b[0m - 10:00\r\n\x1b[3;5Hload 0.42");
+        let view = TerminalTakeoverView::new(
+            "1000".to_string(),
+            "top".to_string(),
+            screen,
+            AppEventSender::new(tx),
+        );
+
+        assert_snapshot!(render_lines(&view, 60, 4));
+    }
+
+    #[test]
+    fn forwards_keys_until_detached() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let mut view = TerminalTakeoverView::new(
+            "1000".to_string(),
+            "vim".to_string(),
+            TerminalScreen::new_shared(),
+            AppEventSender::new(tx),
+        );
+
+        view.handle_key_event(KeyEvent::from(KeyCode::Char('i')));
+        view.handle_key_event(KeyEvent::from(KeyCode::Esc));
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL));
+        view.handle_key_event(KeyEvent::new(KeyCode::Char(']'), KeyModifiers::CONTROL));
+
+        let mut inputs = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            if let AppEvent::CodexOp(Op::TerminalInput { process_id, input }) = event {
+                inputs.push((process_id, input));
+            }
+        }
+        assert_eq!(
+            inputs,
+            vec![
+                ("1000".to_string(), "i".to_string()),
+                ("1000".to_string(), "\u{1b}".to_string()),
+                ("1000".to_string(), "\u{4}".to_string()),
+            ]
+        );
+        assert!(view.is_complete());
+    }
+}
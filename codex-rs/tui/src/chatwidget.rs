@@ -39,6 +39,7 @@ use codex_core::features::Feature;
 use codex_core::git_info::current_branch_name;
 use codex_core::git_info::local_git_branches;
 use codex_core::models_manager::manager::ModelsManager;
+use codex_core::parse_command::extract_file_writes;
 use codex_core::project_doc::DEFAULT_PROJECT_DOC_FILENAME;
 use codex_core::protocol::AgentMessageDeltaEvent;
 use codex_core::protocol::AgentMessageEvent;
@@ -48,6 +49,8 @@ use codex_core::protocol::AgentReasoningRawContentDeltaEvent;
 use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::ClipStoredEvent;
+use codex_core::protocol::ConversationMergedEvent;
 use codex_core::protocol::CreditsSnapshot;
 use codex_core::protocol::DeprecationNoticeEvent;
 use codex_core::protocol::ErrorEvent;
@@ -59,6 +62,9 @@ use codex_core::protocol::ExecCommandEndEvent;
 use codex_core::protocol::ExecCommandOutputDeltaEvent;
 use codex_core::protocol::ExecCommandSource;
 use codex_core::protocol::ExitedReviewModeEvent;
+use codex_core::protocol::ExplorationFindingsEvent;
+use codex_core::protocol::FeatureOverriddenEvent;
+use codex_core::protocol::KnowledgeInjectedEvent;
 use codex_core::protocol::ListCustomPromptsResponseEvent;
 use codex_core::protocol::ListSkillsResponseEvent;
 use codex_core::protocol::McpListToolsResponseEvent;
@@ -67,19 +73,30 @@ use codex_core::protocol::McpStartupStatus;
 use codex_core::protocol::McpStartupUpdateEvent;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
+use codex_core::protocol::ModelSwitchEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
+use codex_core::protocol::ProviderEndpointEvent;
+use codex_core::protocol::ProviderToolCallEndEvent;
 use codex_core::protocol::RateLimitSnapshot;
+use codex_core::protocol::ReplSnippetEvent;
+use codex_core::protocol::ResumeContextChange;
+use codex_core::protocol::ResumeContextEvent;
 use codex_core::protocol::ReviewRequest;
 use codex_core::protocol::ReviewTarget;
+use codex_core::protocol::SecretsMaskedEvent;
 use codex_core::protocol::SkillMetadata as ProtocolSkillMetadata;
 use codex_core::protocol::StreamErrorEvent;
 use codex_core::protocol::TerminalInteractionEvent;
+use codex_core::protocol::TokenForecastEvent;
 use codex_core::protocol::TokenUsage;
 use codex_core::protocol::TokenUsageInfo;
+use codex_core::protocol::ToolArtifact;
+use codex_core::protocol::ToolArtifactEvent;
 use codex_core::protocol::TurnAbortReason;
 use codex_core::protocol::TurnCompleteEvent;
 use codex_core::protocol::TurnDiffEvent;
+use codex_core::protocol::TurnInterruptedEvent;
 use codex_core::protocol::UndoCompletedEvent;
 use codex_core::protocol::UndoStartedEvent;
 use codex_core::protocol::UserMessageEvent;
@@ -144,8 +161,12 @@ use crate::bottom_pane::QUIT_SHORTCUT_TIMEOUT;
 use crate::bottom_pane::SelectionAction;
 use crate::bottom_pane::SelectionItem;
 use crate::bottom_pane::SelectionViewParams;
+use crate::bottom_pane::SharedTerminalScreen;
+use crate::bottom_pane::TerminalScreen;
+use crate::bottom_pane::TerminalTakeoverView;
 use crate::bottom_pane::custom_prompt_view::CustomPromptView;
 use crate::bottom_pane::popup_consts::standard_popup_hint_line;
+use crate::bottom_pane::shell_write_changes;
 use crate::clipboard_paste::paste_image_to_temp_png;
 use crate::collab;
 use crate::collaboration_modes;
@@ -154,6 +175,10 @@ use crate::exec_cell::CommandOutput;
 use crate::exec_cell::ExecCell;
 use crate::exec_cell::new_active_exec_command;
 use crate::exec_command::strip_bash_lc_and_escape;
+use crate::file_preview::FileReference;
+use crate::file_preview::MAX_FILE_REFERENCES;
+use crate::file_preview::find_file_references;
+use crate::file_preview::patch_file_references;
 use crate::get_git_diff::get_git_diff;
 use crate::history_cell;
 use crate::history_cell::AgentMessageCell;
@@ -170,9 +195,14 @@ use crate::render::renderable::Renderable;
 use crate::render::renderable::RenderableExt;
 use crate::render::renderable::RenderableItem;
 use crate::slash_command::SlashCommand;
+use crate::status::DashboardContext;
+use crate::status::DashboardState;
 use crate::status::RateLimitSnapshotDisplay;
+use crate::status::format_tokens_compact;
 use crate::text_formatting::truncate_text;
 use crate::tui::FrameRequester;
+use crate::tui::terminal_status::SessionPhase;
+use crate::tui::terminal_status::SessionStatus;
 mod interrupts;
 use self::interrupts::InterruptManager;
 mod agent;
@@ -211,9 +241,17 @@ struct RunningCommand {
 
 struct UnifiedExecProcessSummary {
     key: String,
+    call_id: String,
     command_display: String,
 }
 
+/// A background terminal the user has taken over with `/attach`.
+struct TerminalTakeover {
+    process_id: String,
+    call_id: String,
+    screen: SharedTerminalScreen,
+}
+
 struct UnifiedExecWaitState {
     command_display: String,
 }
@@ -441,6 +479,7 @@ pub(crate) struct ChatWidget {
     unified_exec_wait_streak: Option<UnifiedExecWaitStreak>,
     task_complete_pending: bool,
     unified_exec_processes: Vec<UnifiedExecProcessSummary>,
+    terminal_takeover: Option<TerminalTakeover>,
     /// Tracks whether codex-core currently considers an agent turn to be in progress.
     ///
     /// This is kept separate from `mcp_startup_status` so that MCP startup progress (or completion)
@@ -452,6 +491,12 @@ pub(crate) struct ChatWidget {
     /// bottom pane is treated as "running" while this is populated, even if no agent turn is
     /// currently executing.
     mcp_startup_status: Option<HashMap<String, McpStartupStatus>>,
+    /// Per-turn usage, provider budgets, and MCP health shown by `/dashboard`.
+    dashboard: DashboardState,
+    /// Whether the `/dashboard` overlay may still be open, so updates re-render it.
+    dashboard_open: bool,
+    /// Files mentioned in agent messages and patches, most recent first.
+    file_references: VecDeque<FileReference>,
     // Queue of interruptive UI events deferred during an active write cycle
     interrupts: InterruptManager,
     // Accumulates the current reasoning block text to extract a header
@@ -499,6 +544,8 @@ pub(crate) struct ChatWidget {
     had_work_activity: bool,
     // Whether the current turn emitted a plan update.
     saw_plan_update_this_turn: bool,
+    // Whether the user was already warned that the current turn will trigger auto-compaction.
+    warned_compaction_this_turn: bool,
     // Status-indicator elapsed seconds captured at the last emitted final-message separator.
     //
     // This lets the separator show per-chunk work time (since the previous separator) rather than
@@ -726,7 +773,9 @@ impl ChatWidget {
         self.set_skills(None);
         self.thread_id = Some(event.session_id);
         self.forked_from = event.forked_from_id;
-        self.current_rollout_path = Some(event.rollout_path.clone());
+        // An incognito session's turns only live in memory, so there is no
+        // rollout to show or fork from.
+        self.current_rollout_path = (!event.incognito).then(|| event.rollout_path.clone());
         let initial_messages = event.initial_messages.clone();
         let model_for_header = event.model.clone();
         self.session_header.set_model(&model_for_header);
@@ -805,6 +854,7 @@ impl ChatWidget {
     }
 
     fn on_agent_message(&mut self, message: String) {
+        self.remember_file_references(find_file_references(&message, &self.config.cwd));
         // If we have a stream_controller, then the final agent message is redundant and will be a
         // duplicate of what has already been streamed.
         if self.stream_controller.is_none() {
@@ -865,6 +915,7 @@ impl ChatWidget {
     fn on_task_started(&mut self) {
         self.agent_turn_running = true;
         self.saw_plan_update_this_turn = false;
+        self.warned_compaction_this_turn = false;
         self.bottom_pane.clear_quit_shortcut_hint();
         self.quit_shortcut_expires_at = None;
         self.quit_shortcut_key = None;
@@ -874,6 +925,9 @@ impl ChatWidget {
         self.set_status_header(String::from("Working"));
         self.full_reasoning_buffer.clear();
         self.reasoning_buffer.clear();
+        self.dashboard
+            .on_turn_started(self.token_info.as_ref().map(|info| &info.total_token_usage));
+        self.refresh_dashboard();
         self.request_redraw();
     }
 
@@ -889,6 +943,7 @@ impl ChatWidget {
         self.last_unified_wait = None;
         self.unified_exec_wait_streak = None;
         self.clear_unified_exec_processes();
+        self.finish_dashboard_turn();
         self.request_redraw();
 
         if !from_replay && self.queued_user_messages.is_empty() {
@@ -1106,6 +1161,7 @@ impl ChatWidget {
         self.unified_exec_wait_streak = None;
         self.clear_unified_exec_processes();
         self.stream_controller = None;
+        self.finish_dashboard_turn();
         self.maybe_show_pending_rate_limit_prompt();
     }
 
@@ -1128,6 +1184,8 @@ impl ChatWidget {
         if let McpStartupStatus::Failed { error } = &ev.status {
             self.on_warning(error);
         }
+        self.dashboard
+            .on_mcp_server_status(ev.server.clone(), ev.status.clone());
         status.insert(ev.server, ev.status);
         self.mcp_startup_status = Some(status);
         self.update_task_running_state();
@@ -1194,6 +1252,12 @@ impl ChatWidget {
     /// Handle a turn aborted due to user interrupt (Esc).
     /// When there are queued user messages, restore them into the composer
     /// separated by newlines rather than auto‑submitting the next one.
+    fn on_turn_interrupted(&mut self, ev: TurnInterruptedEvent) {
+        if let Some(summary) = ev.summary() {
+            self.add_info_message(format!("Interrupted: {summary}"), None);
+        }
+    }
+
     fn on_interrupted_turn(&mut self, reason: TurnAbortReason) {
         // Finalize, log a gentle prompt, and clear running state.
         self.finalize_turn();
@@ -1326,6 +1390,16 @@ impl ChatWidget {
     }
 
     fn on_exec_command_output_delta(&mut self, ev: ExecCommandOutputDeltaEvent) {
+        if let Some(takeover) = &self.terminal_takeover
+            && takeover.call_id == ev.call_id
+        {
+            takeover
+                .screen
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .process(&ev.chunk);
+            self.request_redraw();
+        }
         let Some(cell) = self
             .active_cell
             .as_mut()
@@ -1392,6 +1466,7 @@ impl ChatWidget {
     }
 
     fn on_patch_apply_begin(&mut self, event: PatchApplyBeginEvent) {
+        self.remember_file_references(patch_file_references(&event.changes, &self.config.cwd));
         self.add_to_history(history_cell::new_patch_event(
             event.changes,
             &self.config.cwd,
@@ -1449,6 +1524,7 @@ impl ChatWidget {
         } else {
             self.unified_exec_processes.push(UnifiedExecProcessSummary {
                 key,
+                call_id: ev.call_id.clone(),
                 command_display,
             });
         }
@@ -1457,6 +1533,13 @@ impl ChatWidget {
 
     fn track_unified_exec_process_end(&mut self, ev: &ExecCommandEndEvent) {
         let key = ev.process_id.clone().unwrap_or(ev.call_id.to_string());
+        if self
+            .terminal_takeover
+            .as_ref()
+            .is_some_and(|takeover| takeover.process_id == key)
+        {
+            self.end_terminal_takeover();
+        }
         let before = self.unified_exec_processes.len();
         self.unified_exec_processes
             .retain(|process| process.key != key);
@@ -1475,6 +1558,7 @@ impl ChatWidget {
     }
 
     fn clear_unified_exec_processes(&mut self) {
+        self.end_terminal_takeover();
         if self.unified_exec_processes.is_empty() {
             return;
         }
@@ -1482,6 +1566,51 @@ impl ChatWidget {
         self.sync_unified_exec_footer();
     }
 
+    /// Takes over the background terminal `process_id`, or the most recently
+    /// started one, in a split pane that forwards keys to it. Only processes
+    /// the model started in raw mode accept the input.
+    fn attach_terminal(&mut self, process_id: Option<&str>) {
+        let process = match process_id {
+            Some(process_id) => self
+                .unified_exec_processes
+                .iter()
+                .find(|process| process.key == process_id),
+            None => self.unified_exec_processes.last(),
+        };
+        let Some(process) = process else {
+            self.add_info_message(
+                "No background terminal to take over.".to_string(),
+                Some("Run /ps to list background terminals.".to_string()),
+            );
+            return;
+        };
+        let screen = TerminalScreen::new_shared();
+        let view = TerminalTakeoverView::new(
+            process.key.clone(),
+            process.command_display.clone(),
+            Arc::clone(&screen),
+            self.app_event_tx.clone(),
+        );
+        self.terminal_takeover = Some(TerminalTakeover {
+            process_id: process.key.clone(),
+            call_id: process.call_id.clone(),
+            screen,
+        });
+        self.bottom_pane.show_view(Box::new(view));
+        self.request_redraw();
+    }
+
+    fn end_terminal_takeover(&mut self) {
+        if let Some(takeover) = self.terminal_takeover.take() {
+            takeover
+                .screen
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .mark_exited();
+            self.request_redraw();
+        }
+    }
+
     fn on_mcp_tool_call_begin(&mut self, ev: McpToolCallBeginEvent) {
         let ev2 = ev.clone();
         self.defer_or_handle(|q| q.push_mcp_begin(ev), |s| s.handle_mcp_begin_now(ev2));
@@ -1501,6 +1630,13 @@ impl ChatWidget {
         self.add_to_history(history_cell::new_web_search_call(ev.query));
     }
 
+    fn on_provider_tool_call_end(&mut self, ev: ProviderToolCallEndEvent) {
+        self.flush_answer_stream_with_separator();
+        self.add_to_history(history_cell::new_provider_tool_call(
+            ev.tool, ev.input, ev.output,
+        ));
+    }
+
     fn on_collab_event(&mut self, cell: PlainHistoryCell) {
         self.flush_answer_stream_with_separator();
         self.add_to_history(cell);
@@ -1743,12 +1879,35 @@ impl ChatWidget {
             .unwrap_or_else(|_| ev.command.join(" "));
         self.notify(Notification::ExecApprovalRequested { command });
 
-        let request = ApprovalRequest::Exec {
-            id,
-            command: ev.command,
-            reason: ev.reason,
-            proposed_execpolicy_amendment: ev.proposed_execpolicy_amendment,
-        };
+        let writes = extract_file_writes(&ev.command);
+        if writes.is_empty() {
+            self.show_exec_approval(ApprovalRequest::Exec {
+                id,
+                command: ev.command,
+                reason: ev.reason,
+                proposed_execpolicy_amendment: ev.proposed_execpolicy_amendment,
+                cwd: ev.cwd,
+                changes: HashMap::new(),
+            });
+            return;
+        }
+        // Previewing the writes reads the target files, which can block, so
+        // the approval is shown once that is done off the UI thread.
+        let tx = self.app_event_tx.clone();
+        tokio::task::spawn_blocking(move || {
+            let changes = shell_write_changes(writes, &ev.cwd);
+            tx.send(AppEvent::ExecApprovalPreviewed(ApprovalRequest::Exec {
+                id,
+                command: ev.command,
+                reason: ev.reason,
+                proposed_execpolicy_amendment: ev.proposed_execpolicy_amendment,
+                cwd: ev.cwd,
+                changes,
+            }));
+        });
+    }
+
+    pub(crate) fn show_exec_approval(&mut self, request: ApprovalRequest) {
         self.bottom_pane
             .push_approval_request(request, &self.config.features);
         self.request_redraw();
@@ -1881,6 +2040,7 @@ impl ChatWidget {
             invocation,
             duration,
             result,
+            error: _,
         } = ev;
 
         let extra_cell = match self
@@ -1991,8 +2151,12 @@ impl ChatWidget {
             unified_exec_wait_streak: None,
             task_complete_pending: false,
             unified_exec_processes: Vec::new(),
+            terminal_takeover: None,
             agent_turn_running: false,
             mcp_startup_status: None,
+            dashboard: DashboardState::default(),
+            dashboard_open: false,
+            file_references: VecDeque::new(),
             interrupts: InterruptManager::new(),
             reasoning_buffer: String::new(),
             full_reasoning_buffer: String::new(),
@@ -2011,6 +2175,7 @@ impl ChatWidget {
             needs_final_message_separator: false,
             had_work_activity: false,
             saw_plan_update_this_turn: false,
+            warned_compaction_this_turn: false,
             last_separator_elapsed_secs: None,
             last_rendered_width: std::cell::Cell::new(None),
             feedback,
@@ -2111,8 +2276,12 @@ impl ChatWidget {
             unified_exec_wait_streak: None,
             task_complete_pending: false,
             unified_exec_processes: Vec::new(),
+            terminal_takeover: None,
             agent_turn_running: false,
             mcp_startup_status: None,
+            dashboard: DashboardState::default(),
+            dashboard_open: false,
+            file_references: VecDeque::new(),
             interrupts: InterruptManager::new(),
             reasoning_buffer: String::new(),
             full_reasoning_buffer: String::new(),
@@ -2131,6 +2300,7 @@ impl ChatWidget {
             needs_final_message_separator: false,
             had_work_activity: false,
             saw_plan_update_this_turn: false,
+            warned_compaction_this_turn: false,
             last_separator_elapsed_secs: None,
             last_rendered_width: std::cell::Cell::new(None),
             feedback,
@@ -2466,6 +2636,32 @@ impl ChatWidget {
                     tx.send(AppEvent::DiffResult(text));
                 });
             }
+            SlashCommand::Open => {
+                self.open_file_reference_picker();
+            }
+            SlashCommand::Clip => {
+                self.add_info_message(
+                    "Usage: /clip <name> <text>".to_string(),
+                    Some(
+                        "Paste a stack trace or spec after the name; tool calls can then \
+                         reference it as {{clip:<name>}}. /clip <name> alone removes the clip."
+                            .to_string(),
+                    ),
+                );
+            }
+            SlashCommand::Secrets => self.show_secrets_usage(),
+            SlashCommand::Feature => self.show_feature_usage(),
+            SlashCommand::Explore => {
+                self.add_info_message(
+                    "Usage: /explore <goal> | /explore clear".to_string(),
+                    Some(
+                        "Codex investigates the repository read-only within the [explore] \
+                         budget, then pins a summary of its findings for the next turns; \
+                         `/explore clear` unpins them."
+                            .to_string(),
+                    ),
+                );
+            }
             SlashCommand::Mention => {
                 self.insert_str("@");
             }
@@ -2475,9 +2671,15 @@ impl ChatWidget {
             SlashCommand::Status => {
                 self.add_status_output();
             }
+            SlashCommand::Dashboard => {
+                self.open_dashboard();
+            }
             SlashCommand::Ps => {
                 self.add_ps_output();
             }
+            SlashCommand::Attach => {
+                self.attach_terminal(None);
+            }
             SlashCommand::Mcp => {
                 self.add_mcp_output();
             }
@@ -2514,6 +2716,7 @@ impl ChatWidget {
                                 PathBuf::from("/tmp/test.txt"),
                                 FileChange::Add {
                                     content: "test".to_string(),
+                                    mode: None,
                                 },
                             ),
                             (
@@ -2521,6 +2724,7 @@ impl ChatWidget {
                                 FileChange::Update {
                                     unified_diff: "+test\n-test2".to_string(),
                                     move_path: None,
+                                    mode: None,
                                 },
                             ),
                         ]),
@@ -2551,6 +2755,41 @@ impl ChatWidget {
                     self.open_collaboration_modes_popup();
                 }
             }
+            SlashCommand::Clip => {
+                let (name, content) = trimmed
+                    .split_once(char::is_whitespace)
+                    .map_or((trimmed, ""), |(name, content)| {
+                        (name, content.trim_start())
+                    });
+                self.submit_op(Op::SetClip {
+                    name: name.to_string(),
+                    content: content.to_string(),
+                });
+            }
+            SlashCommand::Secrets => match trimmed {
+                "on" => self.submit_op(Op::SetSecretMasking { enabled: true }),
+                "off" => self.submit_op(Op::SetSecretMasking { enabled: false }),
+                _ => self.show_secrets_usage(),
+            },
+            SlashCommand::Feature => match trimmed.split_whitespace().collect::<Vec<_>>()[..] {
+                [feature, state @ ("on" | "off")] => self.submit_op(Op::SetFeature {
+                    feature: feature.to_string(),
+                    enabled: state == "on",
+                    changed_by: Some("tui".to_string()),
+                }),
+                _ => self.show_feature_usage(),
+            },
+            SlashCommand::Attach if !trimmed.is_empty() => {
+                self.attach_terminal(Some(trimmed));
+            }
+            SlashCommand::Explore if trimmed == "clear" => {
+                self.submit_op(Op::ClearExplorationFindings);
+            }
+            SlashCommand::Explore if !trimmed.is_empty() => {
+                self.submit_op(Op::Explore {
+                    goal: trimmed.to_string(),
+                });
+            }
             SlashCommand::Review if !trimmed.is_empty() => {
                 self.submit_op(Op::Review {
                     review_request: ReviewRequest {
@@ -2558,6 +2797,7 @@ impl ChatWidget {
                             instructions: trimmed.to_string(),
                         },
                         user_facing_hint: None,
+                        paths: None,
                     },
                 });
             }
@@ -2771,16 +3011,19 @@ impl ChatWidget {
 
         match msg {
             EventMsg::SessionConfigured(e) => self.on_session_configured(e),
-            EventMsg::AgentMessage(AgentMessageEvent { message }) => self.on_agent_message(message),
-            EventMsg::AgentMessageDelta(AgentMessageDeltaEvent { delta }) => {
+            EventMsg::AgentMessage(AgentMessageEvent { message, .. }) => {
+                self.on_agent_message(message)
+            }
+            EventMsg::AgentMessageDelta(AgentMessageDeltaEvent { delta, .. }) => {
                 self.on_agent_message_delta(delta)
             }
-            EventMsg::AgentReasoningDelta(AgentReasoningDeltaEvent { delta })
+            EventMsg::AgentReasoningDelta(AgentReasoningDeltaEvent { delta, .. })
             | EventMsg::AgentReasoningRawContentDelta(AgentReasoningRawContentDeltaEvent {
                 delta,
+                ..
             }) => self.on_agent_reasoning_delta(delta),
             EventMsg::AgentReasoning(AgentReasoningEvent { .. }) => self.on_agent_reasoning_final(),
-            EventMsg::AgentReasoningRawContent(AgentReasoningRawContentEvent { text }) => {
+            EventMsg::AgentReasoningRawContent(AgentReasoningRawContentEvent { text, .. }) => {
                 self.on_agent_reasoning_delta(text);
                 self.on_agent_reasoning_final();
             }
@@ -2792,9 +3035,17 @@ impl ChatWidget {
             EventMsg::TokenCount(ev) => {
                 self.set_token_info(ev.info);
                 self.on_rate_limit_snapshot(ev.rate_limits);
+                self.refresh_dashboard();
             }
             EventMsg::Warning(WarningEvent { message }) => self.on_warning(message),
-            EventMsg::Error(ErrorEvent { message, .. }) => self.on_error(message),
+            EventMsg::Error(ErrorEvent {
+                message,
+                fingerprint,
+                ..
+            }) => match fingerprint.and_then(|fingerprint| fingerprint.recurrence_note()) {
+                Some(note) => self.on_error(format!("{message} ({note})")),
+                None => self.on_error(message),
+            },
             EventMsg::McpStartupUpdate(ev) => self.on_mcp_startup_update(ev),
             EventMsg::McpStartupComplete(ev) => self.on_mcp_startup_complete(ev),
             EventMsg::TurnAborted(ev) => match ev.reason {
@@ -2833,10 +3084,35 @@ impl ChatWidget {
             EventMsg::McpToolCallEnd(ev) => self.on_mcp_tool_call_end(ev),
             EventMsg::WebSearchBegin(ev) => self.on_web_search_begin(ev),
             EventMsg::WebSearchEnd(ev) => self.on_web_search_end(ev),
+            EventMsg::ProviderToolCallEnd(ev) => self.on_provider_tool_call_end(ev),
             EventMsg::GetHistoryEntryResponse(ev) => self.on_get_history_entry_response(ev),
             EventMsg::McpListToolsResponse(ev) => self.on_list_mcp_tools(ev),
             EventMsg::ListCustomPromptsResponse(ev) => self.on_list_custom_prompts(ev),
             EventMsg::ListSkillsResponse(ev) => self.on_list_skills(ev),
+            EventMsg::ProviderRateLimits(ev) => {
+                self.dashboard.on_provider_rate_limits(ev);
+                self.refresh_dashboard();
+            }
+            EventMsg::SandboxPolicyReport(_) => {}
+            EventMsg::ToolArtifact(ev) => self.on_tool_artifact(ev),
+            EventMsg::ClipStored(ev) => self.on_clip_stored(ev),
+            EventMsg::FeatureOverridden(ev) => self.on_feature_overridden(ev),
+            EventMsg::ReplSnippet(ev) => self.on_repl_snippet(ev),
+            EventMsg::ResumeContext(ev) => self.on_resume_context(ev),
+            EventMsg::ModelSwitch(ev) => self.on_model_switch(ev),
+            EventMsg::ProviderEndpoint(ev) => self.on_provider_endpoint(ev),
+            EventMsg::SecretsMasked(ev) => self.on_secrets_masked(ev),
+            EventMsg::TokenForecast(ev) => self.on_token_forecast(ev),
+            EventMsg::ExplorationFindings(ev) => self.on_exploration_findings(ev),
+            EventMsg::ExplorationFindingsCleared => {
+                self.add_info_message(
+                    "Exploration findings are no longer pinned".to_string(),
+                    None,
+                );
+            }
+            EventMsg::KnowledgeInjected(ev) => self.on_knowledge_injected(ev),
+            EventMsg::ConversationMerged(ev) => self.on_conversation_merged(ev),
+            EventMsg::TurnInterrupted(ev) => self.on_turn_interrupted(ev),
             EventMsg::SkillsUpdateAvailable => {
                 self.submit_op(Op::ListSkills {
                     cwds: Vec::new(),
@@ -2876,7 +3152,9 @@ impl ChatWidget {
             EventMsg::CollabWaitingEnd(ev) => self.on_collab_event(collab::waiting_end(ev)),
             EventMsg::CollabCloseBegin(_) => {}
             EventMsg::CollabCloseEnd(ev) => self.on_collab_event(collab::close_end(ev)),
-            EventMsg::ThreadRolledBack(_) => {}
+            EventMsg::ThreadRolledBack(_) | EventMsg::TurnsTrimmed(_) => {}
+            // The status indicator already shows that the turn is running.
+            EventMsg::Heartbeat(_) => {}
             EventMsg::RawResponseItem(_)
             | EventMsg::ItemStarted(_)
             | EventMsg::ItemCompleted(_)
@@ -2993,6 +3271,23 @@ impl ChatWidget {
         }
     }
 
+    /// Status shown in the terminal title and progress indicator.
+    pub(crate) fn session_status(&self) -> SessionStatus {
+        let pending_approvals = self.bottom_pane.pending_approval_count();
+        let phase = if pending_approvals > 0 {
+            SessionPhase::WaitingForApproval
+        } else if self.bottom_pane.is_task_running() {
+            SessionPhase::Working
+        } else {
+            SessionPhase::Idle
+        };
+        SessionStatus {
+            model: self.current_model().to_string(),
+            phase,
+            pending_approvals,
+        }
+    }
+
     /// Mark the active cell as failed (✗) and flush it into history.
     fn finalize_active_cell_as_failed(&mut self) {
         if let Some(mut cell) = self.active_cell.take() {
@@ -3060,6 +3355,314 @@ impl ChatWidget {
         ));
     }
 
+    pub(crate) fn open_dashboard(&mut self) {
+        self.dashboard_open = true;
+        let lines = self.dashboard_lines();
+        self.app_event_tx.send(AppEvent::ShowDashboard(lines));
+    }
+
+    /// Re-render the `/dashboard` overlay while it is open.
+    fn refresh_dashboard(&mut self) {
+        if self.dashboard_open {
+            let lines = self.dashboard_lines();
+            self.app_event_tx.send(AppEvent::RefreshDashboard(lines));
+        }
+    }
+
+    pub(crate) fn on_dashboard_closed(&mut self) {
+        self.dashboard_open = false;
+    }
+
+    fn finish_dashboard_turn(&mut self) {
+        let pricing = self.config.model_pricing.get(self.current_model()).cloned();
+        self.dashboard.on_turn_finished(
+            self.token_info.as_ref().map(|info| &info.total_token_usage),
+            pricing.as_ref(),
+        );
+        self.refresh_dashboard();
+    }
+
+    fn dashboard_lines(&self) -> Vec<Line<'static>> {
+        let background_processes = self
+            .unified_exec_processes
+            .iter()
+            .map(|process| process.command_display.clone())
+            .collect();
+        crate::status::dashboard_lines(
+            &self.dashboard,
+            DashboardContext {
+                model: self.model_display_name(),
+                cwd: &self.config.cwd,
+                sandbox_policy: self.config.sandbox_policy.get(),
+                token_info: self.token_info.as_ref(),
+                rate_limits: self.rate_limit_snapshot.as_ref(),
+                turn_running: self.agent_turn_running,
+                background_processes,
+            },
+            Local::now(),
+        )
+    }
+
+    fn on_tool_artifact(&mut self, ev: ToolArtifactEvent) {
+        let ToolArtifact {
+            name,
+            mime_type,
+            size_bytes,
+            path,
+            ..
+        } = ev.artifact;
+        self.add_info_message(
+            format!("Tool returned {name} ({mime_type}, {size_bytes} bytes)"),
+            Some(format!("Saved to {}", path.display())),
+        );
+    }
+
+    fn on_clip_stored(&mut self, ev: ClipStoredEvent) {
+        let ClipStoredEvent {
+            name,
+            content,
+            from_model,
+        } = ev;
+        let who = if from_model { "Codex" } else { "You" };
+        if content.is_empty() {
+            self.add_info_message(format!("{who} removed clip {name}"), None);
+        } else {
+            self.add_info_message(
+                format!("{who} stored clip {name} ({} bytes)", content.len()),
+                Some(format!(
+                    "Tool calls can reference it as {{{{clip:{name}}}}}"
+                )),
+            );
+        }
+    }
+
+    fn on_feature_overridden(&mut self, ev: FeatureOverriddenEvent) {
+        let FeatureOverriddenEvent {
+            feature,
+            enabled,
+            changed_by,
+        } = ev;
+        let state = if enabled { "enabled" } else { "disabled" };
+        self.add_info_message(
+            format!("Feature {feature} {state} for this session"),
+            changed_by.map(|who| format!("Changed by {who}")),
+        );
+    }
+
+    fn on_repl_snippet(&mut self, ev: ReplSnippetEvent) {
+        let lines = ev.code.lines().count();
+        let first_line = ev.code.lines().next().unwrap_or_default();
+        let title = if lines > 1 {
+            format!("Ran {} snippet ({lines} lines): {first_line}", ev.language)
+        } else {
+            format!("Ran {} snippet: {first_line}", ev.language)
+        };
+        let hint = if ev.restarted {
+            Some("The interpreter was restarted; its state was lost".to_string())
+        } else {
+            ev.error
+                .as_deref()
+                .and_then(|error| error.trim_end().lines().last())
+                .map(str::to_string)
+        };
+        self.add_info_message(title, hint);
+    }
+
+    fn on_resume_context(&mut self, ev: ResumeContextEvent) {
+        let summary = ev
+            .changes
+            .iter()
+            .map(ResumeContextChange::summary)
+            .collect::<Vec<_>>()
+            .join("; ");
+        self.add_info_message(
+            "Resumed session settings differ from the recording".to_string(),
+            Some(summary),
+        );
+    }
+
+    fn on_provider_endpoint(&mut self, ev: ProviderEndpointEvent) {
+        if ev.primary {
+            self.add_info_message(ev.summary(), None);
+        } else {
+            self.on_warning(ev.summary());
+        }
+    }
+
+    fn on_secrets_masked(&mut self, ev: SecretsMaskedEvent) {
+        let kinds = ev
+            .counts
+            .iter()
+            .map(|(kind, count)| format!("{kind} ×{count}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.add_info_message(
+            format!(
+                "Masked {} likely secret(s) in tool output before sending it to the model",
+                ev.total()
+            ),
+            Some(format!(
+                "{kinds}. Use /secrets off if this hid something Codex needs."
+            )),
+        );
+    }
+
+    fn on_knowledge_injected(&mut self, ev: KnowledgeInjectedEvent) {
+        let names = ev
+            .snippets
+            .iter()
+            .map(|snippet| snippet.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let hint = (!ev.over_budget.is_empty()).then(|| {
+            format!(
+                "Left out to stay within the knowledge budget: {}",
+                ev.over_budget.join(", ")
+            )
+        });
+        if names.is_empty() {
+            if let Some(hint) = hint {
+                self.add_info_message(
+                    "No project knowledge fit in the budget".to_string(),
+                    Some(hint),
+                );
+            }
+            return;
+        }
+        self.add_info_message(format!("Added project knowledge: {names}"), hint);
+    }
+
+    fn on_conversation_merged(&mut self, ev: ConversationMergedEvent) {
+        self.add_info_message(
+            format!("Merged conversation {}", ev.source_thread_id),
+            Some(format!(
+                "{} turn diff(s) and {} artifact(s) from {}",
+                ev.diff_turns.len(),
+                ev.artifacts.len(),
+                ev.rollout_path.display()
+            )),
+        );
+    }
+
+    fn on_token_forecast(&mut self, ev: TokenForecastEvent) {
+        if !ev.will_compact || self.warned_compaction_this_turn {
+            return;
+        }
+        self.warned_compaction_this_turn = true;
+        let limit = ev
+            .auto_compact_token_limit
+            .map(|limit| format!(" of the {} limit", format_tokens_compact(limit)))
+            .unwrap_or_default();
+        self.on_warning(format!(
+            "This turn will likely trigger auto-compaction: about {} tokens projected{limit}. Press Esc to interrupt it.",
+            format_tokens_compact(ev.projected_tokens)
+        ));
+    }
+
+    fn on_exploration_findings(&mut self, ev: ExplorationFindingsEvent) {
+        self.flush_answer_stream_with_separator();
+        self.flush_active_cell();
+        let outcome = if ev.budget_exhausted {
+            "Exploration stopped by its budget"
+        } else {
+            "Exploration finished"
+        };
+        self.add_info_message(
+            format!("{outcome}: {}", ev.goal),
+            Some("The findings below stay pinned to the context of the next turns".to_string()),
+        );
+        let mut rendered: Vec<ratatui::text::Line<'static>> = vec!["".into()];
+        append_markdown(ev.findings.trim(), None, &mut rendered);
+        self.app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
+            AgentMessageCell::new(rendered, false),
+        )));
+        self.request_redraw();
+    }
+
+    fn show_feature_usage(&mut self) {
+        self.add_info_message(
+            "Usage: /feature <key> on|off".to_string(),
+            Some(
+                "Switches a [features] key for the rest of this conversation; only features \
+                 read anew for every turn can change."
+                    .to_string(),
+            ),
+        );
+    }
+
+    fn show_secrets_usage(&mut self) {
+        self.add_info_message(
+            "Usage: /secrets on|off".to_string(),
+            Some(
+                "Turns masking of likely secrets in tool outputs on or off for this \
+                 conversation; requires the secret_masking feature."
+                    .to_string(),
+            ),
+        );
+    }
+
+    fn on_model_switch(&mut self, ev: ModelSwitchEvent) {
+        // The model change itself is already announced when it is selected;
+        // only surface what the session had to adapt.
+        if !ev.instructions_updated
+            && ev.tools_added.is_empty()
+            && ev.tools_removed.is_empty()
+            && !ev.compaction_pending
+        {
+            return;
+        }
+        self.add_info_message(
+            format!("Adapted session to {}", ev.model),
+            Some(ev.summary()),
+        );
+    }
+
+    fn remember_file_references(&mut self, references: Vec<FileReference>) {
+        for reference in references.into_iter().rev() {
+            self.file_references
+                .retain(|existing| existing != &reference);
+            self.file_references.push_front(reference);
+        }
+        self.file_references.truncate(MAX_FILE_REFERENCES);
+    }
+
+    /// Open a popup listing files mentioned in this session; selecting one
+    /// opens a read-only preview centered on the referenced line.
+    pub(crate) fn open_file_reference_picker(&mut self) {
+        if self.file_references.is_empty() {
+            self.add_info_message(
+                "No file paths have been mentioned in this session yet.".to_string(),
+                None,
+            );
+            return;
+        }
+        let items = self
+            .file_references
+            .iter()
+            .map(|reference| {
+                let name = reference.display(&self.config.cwd);
+                let reference = reference.clone();
+                SelectionItem {
+                    search_value: Some(name.clone()),
+                    name,
+                    actions: vec![Box::new(move |tx: &AppEventSender| {
+                        tx.send(AppEvent::OpenFilePreview(reference.clone()));
+                    })],
+                    dismiss_on_select: true,
+                    ..Default::default()
+                }
+            })
+            .collect();
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Open a mentioned file".to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            is_searchable: true,
+            search_placeholder: Some("Type to filter files".to_string()),
+            ..Default::default()
+        });
+    }
+
     pub(crate) fn add_ps_output(&mut self) {
         let processes = self
             .unified_exec_processes
@@ -4837,6 +5440,7 @@ impl ChatWidget {
                     review_request: ReviewRequest {
                         target: ReviewTarget::UncommittedChanges,
                         user_facing_hint: None,
+                        paths: None,
                     },
                 }));
             })],
@@ -4892,6 +5496,7 @@ impl ChatWidget {
                                 branch: branch.clone(),
                             },
                             user_facing_hint: None,
+                            paths: None,
                         },
                     }));
                 })],
@@ -4930,6 +5535,7 @@ impl ChatWidget {
                                 title: Some(subject.clone()),
                             },
                             user_facing_hint: None,
+                            paths: None,
                         },
                     }));
                 })],
@@ -4966,6 +5572,7 @@ impl ChatWidget {
                             instructions: trimmed,
                         },
                         user_facing_hint: None,
+                        paths: None,
                     },
                 }));
             }),
@@ -5230,6 +5837,7 @@ pub(crate) fn show_review_commit_picker_with_entries(
                             title: Some(subject.clone()),
                         },
                         user_facing_hint: None,
+                        paths: None,
                     },
                 }));
             })],
@@ -29,6 +29,7 @@ use codex_core::protocol::AgentReasoningDeltaEvent;
 use codex_core::protocol::AgentReasoningEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::CancelledToolCall;
 use codex_core::protocol::CreditsSnapshot;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
@@ -52,9 +53,11 @@ use codex_core::protocol::SessionSource;
 use codex_core::protocol::StreamErrorEvent;
 use codex_core::protocol::TerminalInteractionEvent;
 use codex_core::protocol::TokenCountEvent;
+use codex_core::protocol::TokenForecastEvent;
 use codex_core::protocol::TokenUsage;
 use codex_core::protocol::TokenUsageInfo;
 use codex_core::protocol::TurnCompleteEvent;
+use codex_core::protocol::TurnInterruptedEvent;
 use codex_core::protocol::TurnStartedEvent;
 use codex_core::protocol::UndoCompletedEvent;
 use codex_core::protocol::UndoStartedEvent;
@@ -153,12 +156,15 @@ async fn resumed_initial_messages_render_history() {
                 images: None,
                 text_elements: Vec::new(),
                 local_images: Vec::new(),
+                item_id: None,
             }),
             EventMsg::AgentMessage(AgentMessageEvent {
                 message: "assistant reply".to_string(),
+                item_id: None,
             }),
         ]),
         rollout_path: rollout_file.path().to_path_buf(),
+        incognito: false,
     };
 
     chat.handle_codex_event(Event {
@@ -218,8 +224,10 @@ async fn replayed_user_message_preserves_text_elements_and_local_images() {
             images: None,
             text_elements: text_elements.clone(),
             local_images: local_images.clone(),
+            item_id: None,
         })]),
         rollout_path: rollout_file.path().to_path_buf(),
+        incognito: false,
     };
 
     chat.handle_codex_event(Event {
@@ -267,6 +275,7 @@ async fn submission_preserves_text_elements_and_local_images() {
         history_entry_count: 0,
         initial_messages: None,
         rollout_path: rollout_file.path().to_path_buf(),
+        incognito: false,
     };
     chat.handle_codex_event(Event {
         id: "initial".into(),
@@ -553,6 +562,7 @@ async fn entered_review_mode_uses_request_hint() {
                 branch: "feature".to_string(),
             },
             user_facing_hint: Some("feature branch".to_string()),
+            paths: None,
         }),
     });
 
@@ -572,6 +582,7 @@ async fn entered_review_mode_defaults_to_current_changes_banner() {
         msg: EventMsg::EnteredReviewMode(ReviewRequest {
             target: ReviewTarget::UncommittedChanges,
             user_facing_hint: None,
+            paths: None,
         }),
     });
 
@@ -606,6 +617,7 @@ async fn review_restores_context_window_indicator() {
                 branch: "feature".to_string(),
             },
             user_facing_hint: Some("feature branch".to_string()),
+            paths: None,
         }),
     });
 
@@ -622,6 +634,7 @@ async fn review_restores_context_window_indicator() {
         id: "review-end".into(),
         msg: EventMsg::ExitedReviewMode(ExitedReviewModeEvent {
             review_output: None,
+            paths: None,
         }),
     });
     let _ = drain_insert_history(&mut rx);
@@ -820,8 +833,12 @@ async fn make_chatwidget_manual(
         unified_exec_wait_streak: None,
         task_complete_pending: false,
         unified_exec_processes: Vec::new(),
+        terminal_takeover: None,
         agent_turn_running: false,
         mcp_startup_status: None,
+        dashboard: DashboardState::default(),
+        dashboard_open: false,
+        file_references: VecDeque::new(),
         interrupts: InterruptManager::new(),
         reasoning_buffer: String::new(),
         full_reasoning_buffer: String::new(),
@@ -841,6 +858,7 @@ async fn make_chatwidget_manual(
         needs_final_message_separator: false,
         had_work_activity: false,
         saw_plan_update_this_turn: false,
+        warned_compaction_this_turn: false,
         last_separator_elapsed_secs: None,
         last_rendered_width: std::cell::Cell::new(None),
         feedback: codex_feedback::CodexFeedback::new(),
@@ -1350,6 +1368,7 @@ async fn exec_approval_emits_proposed_command_and_decision_history() {
             "this is a test reason such as one that would be produced by the model".into(),
         ),
         proposed_execpolicy_amendment: None,
+        matched_approval_rule: None,
         parsed_cmd: vec![],
     };
     chat.handle_codex_event(Event {
@@ -1394,6 +1413,7 @@ async fn exec_approval_decision_truncates_multiline_and_long_commands() {
             "this is a test reason such as one that would be produced by the model".into(),
         ),
         proposed_execpolicy_amendment: None,
+        matched_approval_rule: None,
         parsed_cmd: vec![],
     };
     chat.handle_codex_event(Event {
@@ -1444,6 +1464,7 @@ async fn exec_approval_decision_truncates_multiline_and_long_commands() {
         cwd: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
         reason: None,
         proposed_execpolicy_amendment: None,
+        matched_approval_rule: None,
         parsed_cmd: vec![],
     };
     chat.handle_codex_event(Event {
@@ -1969,6 +1990,7 @@ async fn unified_exec_wait_after_final_agent_message_snapshot() {
         id: "turn-1".into(),
         msg: EventMsg::AgentMessage(AgentMessageEvent {
             message: "Final response.".into(),
+            item_id: None,
         }),
     });
     chat.handle_codex_event(Event {
@@ -2008,6 +2030,7 @@ async fn unified_exec_wait_before_streamed_agent_message_snapshot() {
         id: "turn-1".into(),
         msg: EventMsg::AgentMessageDelta(AgentMessageDeltaEvent {
             delta: "Streaming response.".into(),
+            item_id: None,
         }),
     });
     chat.handle_codex_event(Event {
@@ -2025,12 +2048,66 @@ async fn unified_exec_wait_before_streamed_agent_message_snapshot() {
     assert_snapshot!("unified_exec_wait_before_streamed_agent_message", combined);
 }
 
+#[tokio::test]
+async fn feature_command_submits_set_feature() {
+    let (mut chat, _rx, mut op_rx) = make_chatwidget_manual(None).await;
+
+    chat.dispatch_command_with_args(SlashCommand::Feature, "repl on".to_string());
+
+    assert_eq!(
+        op_rx.try_recv(),
+        Ok(Op::SetFeature {
+            feature: "repl".to_string(),
+            enabled: true,
+            changed_by: Some("tui".to_string()),
+        })
+    );
+}
+
+#[tokio::test]
+async fn attach_forwards_keys_to_the_background_terminal() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+    chat.on_task_started();
+    chat.unified_exec_processes.push(UnifiedExecProcessSummary {
+        key: "1000".to_string(),
+        call_id: "call-1".to_string(),
+        command_display: "htop".to_string(),
+    });
+
+    chat.dispatch_command(SlashCommand::Attach);
+    chat.handle_key_event(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE));
+    chat.handle_key_event(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+    chat.handle_key_event(KeyEvent::new(KeyCode::Char(']'), KeyModifiers::CONTROL));
+    chat.handle_key_event(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE));
+
+    let mut ops = Vec::new();
+    while let Ok(app_ev) = rx.try_recv() {
+        if let AppEvent::CodexOp(op @ Op::TerminalInput { .. }) = app_ev {
+            ops.push(op);
+        }
+    }
+    assert_eq!(
+        ops,
+        vec![
+            Op::TerminalInput {
+                process_id: "1000".to_string(),
+                input: "q".to_string(),
+            },
+            Op::TerminalInput {
+                process_id: "1000".to_string(),
+                input: "\u{1b}".to_string(),
+            },
+        ]
+    );
+}
+
 #[tokio::test]
 async fn unified_exec_wait_status_header_updates_on_late_command_display() {
     let (mut chat, _rx, _op_rx) = make_chatwidget_manual(None).await;
     chat.on_task_started();
     chat.unified_exec_processes.push(UnifiedExecProcessSummary {
         key: "proc-1".to_string(),
+        call_id: "call-1".to_string(),
         command_display: "sleep 5".to_string(),
     });
 
@@ -2548,6 +2625,7 @@ async fn custom_prompt_submit_sends_review_op() {
                         instructions: "please audit dependencies".to_string(),
                     },
                     user_facing_hint: None,
+                    paths: None,
                 }
             );
         }
@@ -2647,6 +2725,38 @@ async fn interrupted_turn_error_message_snapshot() {
     assert_snapshot!("interrupted_turn_error_message", last);
 }
 
+// Snapshot test: the interrupt summary lists what the turn kept and which tool
+// calls were cancelled.
+#[tokio::test]
+async fn turn_interrupted_summary_snapshot() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+
+    chat.handle_codex_event(Event {
+        id: "task-1".into(),
+        msg: EventMsg::TurnInterrupted(TurnInterruptedEvent {
+            turn_id: "task-1".to_string(),
+            partial_agent_message: Some("I started by reading".to_string()),
+            cancelled_tool_calls: vec![
+                CancelledToolCall {
+                    call_id: "call-1".to_string(),
+                    tool_name: "shell_command".to_string(),
+                },
+                CancelledToolCall {
+                    call_id: "call-2".to_string(),
+                    tool_name: "apply_patch".to_string(),
+                },
+            ],
+        }),
+    });
+
+    let cells = drain_insert_history(&mut rx);
+    assert_eq!(cells.len(), 1, "expected one interrupt summary cell");
+    assert_snapshot!(
+        "turn_interrupted_summary",
+        lines_to_single_string(&cells[0])
+    );
+}
+
 /// Opening custom prompt from the review popup, pressing Esc returns to the
 /// parent popup, pressing Esc again dismisses all panels (back to normal mode).
 #[tokio::test]
@@ -2938,6 +3048,9 @@ async fn preset_matching_ignores_extra_writable_roots() {
         network_access: false,
         exclude_tmpdir_env_var: false,
         exclude_slash_tmp: false,
+        max_memory_bytes: None,
+        cpu_rate_percent: None,
+        max_processes: None,
     };
 
     assert!(
@@ -3343,6 +3456,7 @@ async fn approval_modal_exec_snapshot() -> anyhow::Result<()> {
             "hello".into(),
             "world".into(),
         ])),
+        matched_approval_rule: None,
         parsed_cmd: vec![],
     };
     chat.handle_codex_event(Event {
@@ -3396,6 +3510,7 @@ async fn approval_modal_exec_without_reason_snapshot() -> anyhow::Result<()> {
             "hello".into(),
             "world".into(),
         ])),
+        matched_approval_rule: None,
         parsed_cmd: vec![],
     };
     chat.handle_codex_event(Event {
@@ -3436,6 +3551,7 @@ async fn approval_modal_exec_multiline_prefix_hides_execpolicy_option_snapshot()
         cwd: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
         reason: None,
         proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(command)),
+        matched_approval_rule: None,
         parsed_cmd: vec![],
     };
     chat.handle_codex_event(Event {
@@ -3473,6 +3589,7 @@ async fn approval_modal_patch_snapshot() -> anyhow::Result<()> {
         PathBuf::from("README.md"),
         FileChange::Add {
             content: "hello\nworld\n".into(),
+            mode: None,
         },
     );
     let ev = ApplyPatchApprovalRequestEvent {
@@ -3683,6 +3800,7 @@ async fn ui_snapshots_small_heights_task_running() {
         id: "task-1".into(),
         msg: EventMsg::AgentReasoningDelta(AgentReasoningDeltaEvent {
             delta: "**Thinking**".into(),
+            item_id: None,
         }),
     });
     for h in [1u16, 2, 3] {
@@ -3715,6 +3833,7 @@ async fn status_widget_and_approval_modal_snapshot() {
         id: "task-1".into(),
         msg: EventMsg::AgentReasoningDelta(AgentReasoningDeltaEvent {
             delta: "**Analyzing**".into(),
+            item_id: None,
         }),
     });
 
@@ -3731,6 +3850,7 @@ async fn status_widget_and_approval_modal_snapshot() {
             "echo".into(),
             "hello world".into(),
         ])),
+        matched_approval_rule: None,
         parsed_cmd: vec![],
     };
     chat.handle_codex_event(Event {
@@ -3767,6 +3887,7 @@ async fn status_widget_active_snapshot() {
         id: "task-1".into(),
         msg: EventMsg::AgentReasoningDelta(AgentReasoningDeltaEvent {
             delta: "**Analyzing**".into(),
+            item_id: None,
         }),
     });
     // Render and snapshot.
@@ -3853,6 +3974,7 @@ async fn apply_patch_events_emit_history_cells() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     let ev = ApplyPatchApprovalRequestEvent {
@@ -3894,6 +4016,7 @@ async fn apply_patch_events_emit_history_cells() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     let begin = PatchApplyBeginEvent {
@@ -3920,6 +4043,7 @@ async fn apply_patch_events_emit_history_cells() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     let end = PatchApplyEndEvent {
@@ -3950,6 +4074,7 @@ async fn apply_patch_manual_approval_adjusts_header() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     chat.handle_codex_event(Event {
@@ -3969,6 +4094,7 @@ async fn apply_patch_manual_approval_adjusts_header() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     chat.handle_codex_event(Event {
@@ -3999,6 +4125,7 @@ async fn apply_patch_manual_flow_snapshot() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     chat.handle_codex_event(Event {
@@ -4022,6 +4149,7 @@ async fn apply_patch_manual_flow_snapshot() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     chat.handle_codex_event(Event {
@@ -4052,6 +4180,7 @@ async fn apply_patch_approval_sends_op_with_submission_id() {
         PathBuf::from("file.rs"),
         FileChange::Add {
             content: "fn main(){}\n".into(),
+            mode: None,
         },
     );
     let ev = ApplyPatchApprovalRequestEvent {
@@ -4090,7 +4219,10 @@ async fn apply_patch_full_flow_integration_like() {
     let mut changes = HashMap::new();
     changes.insert(
         PathBuf::from("pkg.rs"),
-        FileChange::Add { content: "".into() },
+        FileChange::Add {
+            content: "".into(),
+            mode: None,
+        },
     );
     chat.handle_codex_event(Event {
         id: "sub-xyz".into(),
@@ -4131,7 +4263,10 @@ async fn apply_patch_full_flow_integration_like() {
     let mut changes2 = HashMap::new();
     changes2.insert(
         PathBuf::from("pkg.rs"),
-        FileChange::Add { content: "".into() },
+        FileChange::Add {
+            content: "".into(),
+            mode: None,
+        },
     );
     chat.handle_codex_event(Event {
         id: "sub-xyz".into(),
@@ -4145,7 +4280,10 @@ async fn apply_patch_full_flow_integration_like() {
     let mut end_changes = HashMap::new();
     end_changes.insert(
         PathBuf::from("pkg.rs"),
-        FileChange::Add { content: "".into() },
+        FileChange::Add {
+            content: "".into(),
+            mode: None,
+        },
     );
     chat.handle_codex_event(Event {
         id: "sub-xyz".into(),
@@ -4170,7 +4308,10 @@ async fn apply_patch_untrusted_shows_approval_modal() -> anyhow::Result<()> {
     let mut changes = HashMap::new();
     changes.insert(
         PathBuf::from("a.rs"),
-        FileChange::Add { content: "".into() },
+        FileChange::Add {
+            content: "".into(),
+            mode: None,
+        },
     );
     chat.handle_codex_event(Event {
         id: "sub-1".into(),
@@ -4221,6 +4362,7 @@ async fn apply_patch_request_shows_diff_summary() -> anyhow::Result<()> {
         FileChange::Add {
             // Two lines (no trailing empty line counted)
             content: "line one\nline two\n".into(),
+            mode: None,
         },
     );
     chat.handle_codex_event(Event {
@@ -4358,6 +4500,34 @@ async fn warning_event_adds_warning_history_cell() {
     );
 }
 
+#[tokio::test]
+async fn token_forecast_warns_once_per_turn_about_compaction() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+    let forecast = |projected_tokens, will_compact| Event {
+        id: "sub-1".into(),
+        msg: EventMsg::TokenForecast(TokenForecastEvent {
+            estimated_tokens: 180_000,
+            projected_tokens,
+            model_context_window: Some(200_000),
+            auto_compact_token_limit: Some(180_000),
+            will_compact,
+            heuristic: false,
+        }),
+    };
+
+    chat.handle_codex_event(forecast(170_000, false));
+    chat.handle_codex_event(forecast(185_000, true));
+    chat.handle_codex_event(forecast(190_000, true));
+
+    let cells = drain_insert_history(&mut rx);
+    assert_eq!(cells.len(), 1, "expected a single compaction warning");
+    let rendered = lines_to_single_string(&cells[0]);
+    assert!(
+        rendered.contains("will likely trigger auto-compaction"),
+        "warning cell missing content: {rendered}"
+    );
+}
+
 #[tokio::test]
 async fn stream_recovery_restores_previous_status_header() {
     let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
@@ -4381,6 +4551,7 @@ async fn stream_recovery_restores_previous_status_header() {
         id: "delta".into(),
         msg: EventMsg::AgentMessageDelta(AgentMessageDeltaEvent {
             delta: "hello".to_string(),
+            item_id: None,
         }),
     });
 
@@ -4410,6 +4581,7 @@ async fn multiple_agent_messages_in_single_turn_emit_multiple_headers() {
         id: "s1".into(),
         msg: EventMsg::AgentMessage(AgentMessageEvent {
             message: "First message".into(),
+            item_id: None,
         }),
     });
 
@@ -4418,6 +4590,7 @@ async fn multiple_agent_messages_in_single_turn_emit_multiple_headers() {
         id: "s1".into(),
         msg: EventMsg::AgentMessage(AgentMessageEvent {
             message: "Second message".into(),
+            item_id: None,
         }),
     });
 
@@ -4456,12 +4629,14 @@ async fn final_reasoning_then_message_without_deltas_are_rendered() {
         id: "s1".into(),
         msg: EventMsg::AgentReasoning(AgentReasoningEvent {
             text: "I will first analyze the request.".into(),
+            item_id: None,
         }),
     });
     chat.handle_codex_event(Event {
         id: "s1".into(),
         msg: EventMsg::AgentMessage(AgentMessageEvent {
             message: "Here is the result.".into(),
+            item_id: None,
         }),
     });
 
@@ -4483,24 +4658,28 @@ async fn deltas_then_same_final_message_are_rendered_snapshot() {
         id: "s1".into(),
         msg: EventMsg::AgentReasoningDelta(AgentReasoningDeltaEvent {
             delta: "I will ".into(),
+            item_id: None,
         }),
     });
     chat.handle_codex_event(Event {
         id: "s1".into(),
         msg: EventMsg::AgentReasoningDelta(AgentReasoningDeltaEvent {
             delta: "first analyze the ".into(),
+            item_id: None,
         }),
     });
     chat.handle_codex_event(Event {
         id: "s1".into(),
         msg: EventMsg::AgentReasoningDelta(AgentReasoningDeltaEvent {
             delta: "request.".into(),
+            item_id: None,
         }),
     });
     chat.handle_codex_event(Event {
         id: "s1".into(),
         msg: EventMsg::AgentReasoning(AgentReasoningEvent {
             text: "request.".into(),
+            item_id: None,
         }),
     });
 
@@ -4509,12 +4688,14 @@ async fn deltas_then_same_final_message_are_rendered_snapshot() {
         id: "s1".into(),
         msg: EventMsg::AgentMessageDelta(AgentMessageDeltaEvent {
             delta: "Here is the ".into(),
+            item_id: None,
         }),
     });
     chat.handle_codex_event(Event {
         id: "s1".into(),
         msg: EventMsg::AgentMessageDelta(AgentMessageDeltaEvent {
             delta: "result.".into(),
+            item_id: None,
         }),
     });
 
@@ -4522,6 +4703,7 @@ async fn deltas_then_same_final_message_are_rendered_snapshot() {
         id: "s1".into(),
         msg: EventMsg::AgentMessage(AgentMessageEvent {
             message: "Here is the result.".into(),
+            item_id: None,
         }),
     });
 
@@ -4543,7 +4725,7 @@ async fn chatwidget_exec_and_status_layout_vt100_snapshot() {
     let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
     chat.handle_codex_event(Event {
         id: "t1".into(),
-        msg: EventMsg::AgentMessage(AgentMessageEvent { message: "I’m going to search the repo for where “Change Approved” is rendered to update that view.".into() }),
+        msg: EventMsg::AgentMessage(AgentMessageEvent { message: "I’m going to search the repo for where “Change Approved” is rendered to update that view.".into(), item_id: None }),
     });
 
     let command = vec!["bash".into(), "-lc".into(), "rg \"Change Approved\"".into()];
@@ -4602,6 +4784,7 @@ async fn chatwidget_exec_and_status_layout_vt100_snapshot() {
         id: "t1".into(),
         msg: EventMsg::AgentReasoningDelta(AgentReasoningDeltaEvent {
             delta: "**Investigating rendering code**".into(),
+            item_id: None,
         }),
     });
     chat.bottom_pane.set_composer_text(
@@ -4689,7 +4872,10 @@ printf 'fenced within fenced\n'
 
         chat.handle_codex_event(Event {
             id: "t1".into(),
-            msg: EventMsg::AgentMessageDelta(AgentMessageDeltaEvent { delta }),
+            msg: EventMsg::AgentMessageDelta(AgentMessageDeltaEvent {
+                delta,
+                item_id: None,
+            }),
         });
         // Drive commit ticks and drain emitted history lines into the vt100 buffer.
         loop {
@@ -4760,6 +4946,7 @@ async fn review_queues_user_messages_snapshot() {
         msg: EventMsg::EnteredReviewMode(ReviewRequest {
             target: ReviewTarget::UncommittedChanges,
             user_facing_hint: Some("current changes".to_string()),
+            paths: None,
         }),
     });
     let _ = drain_insert_history(&mut rx);
@@ -100,9 +100,10 @@ fn collect_rows(changes: &HashMap<PathBuf, FileChange>) -> Vec<Row> {
     let mut rows: Vec<Row> = Vec::new();
     for (path, change) in changes.iter() {
         let (added, removed) = match change {
-            FileChange::Add { content } => (content.lines().count(), 0),
+            FileChange::Add { content, .. } => (content.lines().count(), 0),
             FileChange::Delete { content } => (0, content.lines().count()),
             FileChange::Update { unified_diff, .. } => calculate_add_remove_from_diff(unified_diff),
+            FileChange::Symlink { .. } => (0, 0),
         };
         let move_path = match change {
             FileChange::Update {
@@ -155,6 +156,7 @@ fn render_changes_block(rows: Vec<Row>, wrap_cols: usize, cwd: &Path) -> Vec<RtL
         let verb = match &row.change {
             FileChange::Add { .. } => "Added",
             FileChange::Delete { .. } => "Deleted",
+            FileChange::Symlink { .. } => "Linked",
             _ => "Edited",
         };
         header_spans.push(verb.bold());
@@ -195,7 +197,22 @@ fn render_changes_block(rows: Vec<Row>, wrap_cols: usize, cwd: &Path) -> Vec<RtL
 
 fn render_change(change: &FileChange, out: &mut Vec<RtLine<'static>>, width: usize) {
     match change {
-        FileChange::Add { content } => {
+        FileChange::Add {
+            mode: Some(mode), ..
+        }
+        | FileChange::Update {
+            mode: Some(mode), ..
+        } => out.push(RtLine::from(format!("mode → {mode}").dim())),
+        _ => {}
+    }
+    match change {
+        FileChange::Symlink { target } => {
+            out.push(RtLine::from(vec![
+                "symlink → ".dim(),
+                target.display().to_string().into(),
+            ]));
+        }
+        FileChange::Add { content, .. } => {
             let line_number_width = line_number_width(content.lines().count());
             for (i, raw) in content.lines().enumerate() {
                 out.extend(push_wrapped_diff_line(
@@ -430,6 +447,7 @@ fn style_del() -> Style {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use codex_core::protocol::FileMode;
     use insta::assert_snapshot;
     use pretty_assertions::assert_eq;
     use ratatui::Terminal;
@@ -516,6 +534,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: None,
+                mode: None,
             },
         );
 
@@ -536,6 +555,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: Some(PathBuf::from("new_name.rs")),
+                mode: None,
             },
         );
 
@@ -556,6 +576,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch_a,
                 move_path: None,
+                mode: None,
             },
         );
 
@@ -564,6 +585,7 @@ mod tests {
             PathBuf::from("b.txt"),
             FileChange::Add {
                 content: "new\n".to_string(),
+                mode: None,
             },
         );
 
@@ -579,6 +601,7 @@ mod tests {
             PathBuf::from("new_file.txt"),
             FileChange::Add {
                 content: "alpha\nbeta\n".to_string(),
+                mode: None,
             },
         );
 
@@ -587,6 +610,46 @@ mod tests {
         snapshot_lines("apply_add_block", lines, 80, 10);
     }
 
+    #[test]
+    fn mode_changes_and_symlinks_are_listed() {
+        let mut changes: HashMap<PathBuf, FileChange> = HashMap::new();
+        changes.insert(
+            PathBuf::from("run.sh"),
+            FileChange::Update {
+                unified_diff: String::new(),
+                move_path: None,
+                mode: Some(FileMode::Executable),
+            },
+        );
+        changes.insert(
+            PathBuf::from("latest"),
+            FileChange::Symlink {
+                target: PathBuf::from("releases/v2"),
+            },
+        );
+
+        let text = diff_summary_for_tests(&changes)
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            text,
+            vec![
+                "• Edited 2 files (+0 -0)",
+                "  └ latest (+0 -0)",
+                "    symlink → releases/v2",
+                "",
+                "  └ run.sh (+0 -0)",
+                "    mode → executable",
+            ]
+        );
+    }
+
     #[test]
     fn ui_snapshot_apply_delete_block() {
         let mut changes: HashMap<PathBuf, FileChange> = HashMap::new();
@@ -614,6 +677,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: None,
+                mode: None,
             },
         );
 
@@ -637,6 +701,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: None,
+                mode: None,
             },
         );
 
@@ -664,6 +729,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: None,
+                mode: None,
             },
         );
 
@@ -687,6 +753,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: Some(abs_new),
+                mode: None,
             },
         );
 
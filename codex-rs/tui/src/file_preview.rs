@@ -0,0 +1,360 @@
+//! File references mentioned in agent messages and patches, and the read-only
+//! preview shown by `/open`.
+//!
+//! References are collected from plain text (`src/lib.rs:42`, `path/to/file.rs#L7`,
+//! markdown links) and only kept when they resolve to an existing file, so the
+//! picker never lists prose that merely looks like a path.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_core::protocol::FileChange;
+use codex_core::protocol::SandboxPolicy;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::text::Span;
+
+use crate::exec_command::relativize_to_home;
+use crate::render::highlight::highlight_code_to_lines;
+
+/// Lines shown above and below the referenced line.
+const CONTEXT_LINES: usize = 20;
+
+/// Files larger than this are not previewed.
+const MAX_PREVIEW_BYTES: u64 = 1024 * 1024;
+
+/// Maximum number of references remembered per session.
+pub(crate) const MAX_FILE_REFERENCES: usize = 50;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct FileReference {
+    /// Absolute path of an existing file.
+    pub path: PathBuf,
+    /// 1-based line number, when the mention included one.
+    pub line: Option<usize>,
+}
+
+impl FileReference {
+    /// Label for pickers: the path relative to `cwd` when possible, plus `:line`.
+    pub(crate) fn display(&self, cwd: &Path) -> String {
+        let path = match self.path.strip_prefix(cwd) {
+            Ok(relative) => relative.display().to_string(),
+            Err(_) => relativize_to_home(&self.path)
+                .map(|relative| format!("~/{}", relative.display()))
+                .unwrap_or_else(|| self.path.display().to_string()),
+        };
+        match self.line {
+            Some(line) => format!("{path}:{line}"),
+            None => path,
+        }
+    }
+}
+
+/// Finds file paths mentioned in `text` that exist relative to `cwd`.
+pub(crate) fn find_file_references(text: &str, cwd: &Path) -> Vec<FileReference> {
+    let mut seen = HashSet::new();
+    text.split(|c: char| {
+        c.is_whitespace() || matches!(c, '`' | '"' | '\'' | '(' | ')' | '[' | ']' | '<' | '>')
+    })
+    .filter_map(|token| parse_reference(token, cwd))
+    .filter(|reference| seen.insert(reference.clone()))
+    .collect()
+}
+
+/// References for the files touched by a patch, pointing at the first changed line.
+pub(crate) fn patch_file_references(
+    changes: &std::collections::HashMap<PathBuf, FileChange>,
+    cwd: &Path,
+) -> Vec<FileReference> {
+    let mut references: Vec<FileReference> = changes
+        .iter()
+        .filter_map(|(path, change)| {
+            let (path, line) = match change {
+                FileChange::Add { .. } => (path, Some(1)),
+                FileChange::Delete { .. } | FileChange::Symlink { .. } => return None,
+                FileChange::Update {
+                    unified_diff,
+                    move_path,
+                    ..
+                } => (
+                    move_path.as_ref().unwrap_or(path),
+                    first_changed_line(unified_diff),
+                ),
+            };
+            let path = cwd.join(path);
+            path.is_file().then_some(FileReference { path, line })
+        })
+        .collect();
+    references.sort_by(|a, b| a.path.cmp(&b.path));
+    references
+}
+
+fn parse_reference(token: &str, cwd: &Path) -> Option<FileReference> {
+    let token = token.trim_end_matches(['.', ',', ';', ':', '!', '?']);
+    let (path, line) = split_line_suffix(token);
+    if path.is_empty() || path.contains("://") {
+        return None;
+    }
+    // Require something path-like so ordinary words are never probed on disk.
+    let file_name = Path::new(path).file_name()?.to_str()?;
+    if !path.contains('/') && !file_name.contains('.') {
+        return None;
+    }
+    let path = match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()?.join(rest),
+        None => cwd.join(path),
+    };
+    path.is_file().then_some(FileReference { path, line })
+}
+
+/// Splits `path:42`, `path:42:7`, `path:42-50`, and `path#L42` into path and line.
+fn split_line_suffix(token: &str) -> (&str, Option<usize>) {
+    if let Some((path, anchor)) = token.rsplit_once("#L") {
+        let digits = anchor.split(['-', 'C']).next().unwrap_or_default();
+        if let Ok(line) = digits.parse() {
+            return (path, Some(line));
+        }
+    }
+    let mut path = token;
+    let mut line = None;
+    // Peel off up to two numeric suffixes (`:line` and `:column`).
+    for _ in 0..2 {
+        let Some((head, tail)) = path.rsplit_once(':') else {
+            break;
+        };
+        let digits = tail.split('-').next().unwrap_or_default();
+        let Ok(number) = digits.parse::<usize>() else {
+            break;
+        };
+        line = Some(number);
+        path = head;
+    }
+    (path, line)
+}
+
+/// New-file line of the first hunk in a unified diff.
+fn first_changed_line(unified_diff: &str) -> Option<usize> {
+    unified_diff.lines().find_map(|line| {
+        let rest = line.strip_prefix("@@ -")?;
+        let (_, new_range) = rest.split_once(" +")?;
+        new_range
+            .split([',', ' '])
+            .next()?
+            .parse::<usize>()
+            .ok()
+            .map(|start| start.max(1))
+    })
+}
+
+/// Renders a read-only window of the referenced file centered on its line.
+pub(crate) fn preview_lines(
+    reference: &FileReference,
+    cwd: &Path,
+    sandbox_policy: &SandboxPolicy,
+) -> Result<Vec<Line<'static>>, String> {
+    let display = reference.display(cwd);
+    if !is_readable(&reference.path, cwd, sandbox_policy) {
+        return Err(format!(
+            "{display} is outside the sandbox's readable roots."
+        ));
+    }
+    let metadata = std::fs::metadata(&reference.path)
+        .map_err(|err| format!("Failed to read {display}: {err}"))?;
+    if metadata.len() > MAX_PREVIEW_BYTES {
+        return Err(format!("{display} is too large to preview."));
+    }
+    let contents = std::fs::read_to_string(&reference.path)
+        .map_err(|err| format!("Failed to read {display}: {err}"))?;
+
+    let source: Vec<&str> = contents.lines().collect();
+    let target = reference
+        .line
+        .map(|line| line.clamp(1, source.len().max(1)));
+    let center = target.unwrap_or(1);
+    let start = center.saturating_sub(CONTEXT_LINES + 1);
+    let end = (center + CONTEXT_LINES).min(source.len());
+    let window = source.get(start..end).unwrap_or_default();
+
+    let language = reference
+        .path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default();
+    let code = window.join("\n");
+    let highlighted = highlight_code_to_lines(language, &code).unwrap_or_else(|| {
+        window
+            .iter()
+            .map(|line| Line::from(line.to_string()))
+            .collect()
+    });
+
+    let gutter_width = end.max(1).to_string().len();
+    let mut lines = vec![Line::from(display.bold()), Line::from("")];
+    if window.is_empty() {
+        lines.push(Line::from("(empty file)".dim().italic()));
+    }
+    for (offset, code_line) in highlighted.into_iter().enumerate() {
+        let number = start + offset + 1;
+        let gutter = format!("{number:>gutter_width$} ");
+        let mut spans: Vec<Span<'static>> = if Some(number) == target {
+            vec!["› ".cyan().bold(), gutter.cyan().bold()]
+        } else {
+            vec!["  ".into(), gutter.dim()]
+        };
+        spans.extend(code_line.spans);
+        lines.push(Line::from(spans));
+    }
+    Ok(lines)
+}
+
+/// Reads are unrestricted today; if a policy ever limits them, only the
+/// workspace roots remain previewable.
+fn is_readable(path: &Path, cwd: &Path, sandbox_policy: &SandboxPolicy) -> bool {
+    if sandbox_policy.has_full_disk_read_access() {
+        return true;
+    }
+    let roots = sandbox_policy
+        .get_writable_roots_with_cwd(cwd)
+        .into_iter()
+        .map(|root| root.root.as_path().to_path_buf());
+    is_inside_roots(path, std::iter::once(cwd.to_path_buf()).chain(roots))
+}
+
+/// Compares canonical paths so that `..` components and symlinks cannot
+/// lead out of a root that the path merely starts with lexically.
+fn is_inside_roots(path: &Path, roots: impl IntoIterator<Item = PathBuf>) -> bool {
+    let Ok(path) = path.canonicalize() else {
+        return false;
+    };
+    roots
+        .into_iter()
+        .any(|root| root.canonicalize().is_ok_and(|root| path.starts_with(root)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    fn workspace() -> TempDir {
+        let dir = TempDir::new().expect("tempdir");
+        std::fs::create_dir_all(dir.path().join("src")).expect("mkdir");
+        std::fs::write(dir.path().join("src/lib.rs"), "fn a() {}\nfn b() {}\n").expect("write");
+        std::fs::write(dir.path().join("README.md"), "# readme\n").expect("write");
+        dir
+    }
+
+    #[test]
+    fn finds_existing_paths_with_optional_lines() {
+        let dir = workspace();
+        let cwd = dir.path();
+        let text = "See `src/lib.rs:2` and [readme](README.md), also src/missing.rs:4, \
+                    src/lib.rs#L1, and https://example.com/src/lib.rs.";
+
+        let references = find_file_references(text, cwd);
+
+        assert_eq!(
+            references,
+            vec![
+                FileReference {
+                    path: cwd.join("src/lib.rs"),
+                    line: Some(2),
+                },
+                FileReference {
+                    path: cwd.join("README.md"),
+                    line: None,
+                },
+                FileReference {
+                    path: cwd.join("src/lib.rs"),
+                    line: Some(1),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parent_components_do_not_escape_the_roots() {
+        let dir = workspace();
+        let src = dir.path().join("src");
+
+        assert!(is_inside_roots(
+            &src.join("../README.md"),
+            [dir.path().to_path_buf()]
+        ));
+        assert!(!is_inside_roots(&src.join("../README.md"), [src.clone()]));
+        assert!(!is_inside_roots(&dir.path().join("src/missing.rs"), [src]));
+    }
+
+    #[test]
+    fn splits_line_suffixes() {
+        assert_eq!(split_line_suffix("a.rs:12:5"), ("a.rs", Some(12)));
+        assert_eq!(split_line_suffix("a.rs:12-20"), ("a.rs", Some(12)));
+        assert_eq!(split_line_suffix("a.rs#L7-L9"), ("a.rs", Some(7)));
+        assert_eq!(split_line_suffix("a.rs"), ("a.rs", None));
+    }
+
+    #[test]
+    fn patch_references_point_at_first_hunk() {
+        let dir = workspace();
+        let cwd = dir.path();
+        let changes = HashMap::from([
+            (
+                PathBuf::from("src/lib.rs"),
+                FileChange::Update {
+                    unified_diff: "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1,2 +2,3 @@\n"
+                        .to_string(),
+                    move_path: None,
+                    mode: None,
+                },
+            ),
+            (
+                PathBuf::from("gone.rs"),
+                FileChange::Delete {
+                    content: String::new(),
+                },
+            ),
+        ]);
+
+        assert_eq!(
+            patch_file_references(&changes, cwd),
+            vec![FileReference {
+                path: cwd.join("src/lib.rs"),
+                line: Some(2),
+            }]
+        );
+    }
+
+    #[test]
+    fn preview_marks_referenced_line() {
+        let dir = workspace();
+        let cwd = dir.path();
+        let reference = FileReference {
+            path: cwd.join("src/lib.rs"),
+            line: Some(2),
+        };
+
+        let lines = preview_lines(&reference, cwd, &SandboxPolicy::ReadOnly).expect("preview");
+        let text: Vec<String> = lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect::<String>()
+            })
+            .collect();
+
+        assert_eq!(
+            text,
+            vec![
+                "src/lib.rs:2".to_string(),
+                String::new(),
+                "  1 fn a() {}".to_string(),
+                "› 2 fn b() {}".to_string(),
+            ]
+        );
+    }
+}
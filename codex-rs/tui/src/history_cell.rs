@@ -45,6 +45,7 @@ use codex_core::protocol::McpAuthStatus;
 use codex_core::protocol::McpInvocation;
 use codex_core::protocol::SessionConfiguredEvent;
 use codex_protocol::config_types::CollaborationMode;
+use codex_protocol::items::ProviderTool;
 use codex_protocol::openai_models::ReasoningEffort as ReasoningEffortConfig;
 use codex_protocol::plan_tool::PlanItemArg;
 use codex_protocol::plan_tool::StepStatus;
@@ -675,6 +676,7 @@ pub fn new_approval_decision_cell(
     command: Vec<String>,
     decision: codex_core::protocol::ReviewDecision,
 ) -> Box<dyn HistoryCell> {
+    use codex_core::protocol::ApprovalScope;
     use codex_core::protocol::ReviewDecision::*;
 
     let (symbol, summary): (Span<'static>, Vec<Span<'static>>) = match decision {
@@ -717,6 +719,24 @@ pub fn new_approval_decision_cell(
                 ],
             )
         }
+        ApprovedAlways { scope } => {
+            let snippet = Span::from(exec_snippet(&command)).dim();
+            let until = match scope {
+                ApprovalScope::Session => " every time this session",
+                ApprovalScope::Project => " every time in this project",
+                ApprovalScope::Global => " every time",
+            };
+            (
+                "✔ ".green(),
+                vec![
+                    "You ".into(),
+                    "approved".bold(),
+                    " codex to run ".into(),
+                    snippet,
+                    until.bold(),
+                ],
+            )
+        }
         Denied => {
             let snippet = Span::from(exec_snippet(&command)).dim();
             (
@@ -912,6 +932,7 @@ pub(crate) fn new_session_info(
     let SessionConfiguredEvent {
         model,
         reasoning_effort,
+        incognito,
         ..
     } = event;
     // Header box rendered as history (so it appears at the very top)
@@ -924,6 +945,15 @@ pub(crate) fn new_session_info(
         collaboration_mode,
     );
     let mut parts: Vec<Box<dyn HistoryCell>> = vec![Box::new(header)];
+    if incognito {
+        parts.push(Box::new(PlainHistoryCell {
+            lines: vec![
+                "  Incognito session: nothing is saved to disk and it cannot be resumed."
+                    .magenta()
+                    .into(),
+            ],
+        }));
+    }
 
     if is_first_event {
         // Help lines below the header (new copy and list)
@@ -1398,6 +1428,26 @@ pub(crate) fn new_web_search_call(query: String) -> PrefixedWrappedHistoryCell {
     PrefixedWrappedHistoryCell::new(text, "• ".dim(), "  ")
 }
 
+pub(crate) fn new_provider_tool_call(
+    tool: ProviderTool,
+    input: String,
+    output: String,
+) -> PrefixedWrappedHistoryCell {
+    let title = match tool {
+        ProviderTool::CodeInterpreter => "Ran code",
+        ProviderTool::FileSearch => "Searched files",
+    };
+    let mut lines = vec![Line::from(title.bold())];
+    lines.extend(
+        input
+            .lines()
+            .chain(output.lines())
+            .take(TOOL_CALL_MAX_LINES)
+            .map(|line| Line::from(line.to_string().dim())),
+    );
+    PrefixedWrappedHistoryCell::new(Text::from(lines), "• ".dim(), "  ")
+}
+
 /// If the first content is an image, return a new cell with the image.
 /// TODO(rgwood-dd): Handle images properly even if they're not the first result.
 fn try_new_completed_mcp_tool_call_with_image_output(
@@ -43,6 +43,7 @@ mod app;
 mod app_backtrack;
 mod app_event;
 mod app_event_sender;
+mod app_tabs;
 mod ascii_animation;
 mod bottom_pane;
 mod chatwidget;
@@ -56,6 +57,7 @@ mod diff_render;
 mod exec_cell;
 mod exec_command;
 mod external_editor;
+mod file_preview;
 mod file_search;
 mod frames;
 mod get_git_diff;
@@ -600,6 +602,7 @@ async fn run_ratatui_app(
 
     let use_alt_screen = determine_alt_screen_mode(no_alt_screen, config.tui_alternate_screen);
     tui.set_alt_screen_enabled(use_alt_screen);
+    tui.set_terminal_status_enabled(config.tui_terminal_title, config.tui_terminal_progress);
 
     let app_result = App::run(
         &mut tui,
@@ -615,6 +618,7 @@ async fn run_ratatui_app(
     )
     .await;
 
+    tui.restore_terminal_status();
     restore();
     // Mark the end of the recorded session.
     session_log::log_session_end();
@@ -1,6 +1,8 @@
+use crate::render::highlight::highlight_code_to_lines;
 use crate::render::line_utils::line_to_static;
 use crate::wrapping::RtOptions;
 use crate::wrapping::word_wrap_line;
+use pulldown_cmark::Alignment;
 use pulldown_cmark::CodeBlockKind;
 use pulldown_cmark::CowStr;
 use pulldown_cmark::Event;
@@ -13,6 +15,7 @@ use ratatui::style::Style;
 use ratatui::text::Line;
 use ratatui::text::Span;
 use ratatui::text::Text;
+use unicode_width::UnicodeWidthStr;
 
 struct MarkdownStyles {
     h1: Style,
@@ -29,6 +32,8 @@ struct MarkdownStyles {
     unordered_list_marker: Style,
     link: Style,
     blockquote: Style,
+    table_header: Style,
+    table_rule: Style,
 }
 
 impl Default for MarkdownStyles {
@@ -50,6 +55,8 @@ impl Default for MarkdownStyles {
             unordered_list_marker: Style::new(),
             link: Style::new().cyan().underlined(),
             blockquote: Style::new().green(),
+            table_header: Style::new().bold(),
+            table_rule: Style::new().dim(),
         }
     }
 }
@@ -78,6 +85,7 @@ pub fn render_markdown_text(input: &str) -> Text<'static> {
 pub(crate) fn render_markdown_text_with_width(input: &str, width: Option<usize>) -> Text<'static> {
     let mut options = Options::empty();
     options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TABLES);
     let parser = Parser::new_ext(input, options);
     let mut w = Writer::new(parser, width);
     w.run();
@@ -105,6 +113,106 @@ where
     current_subsequent_indent: Vec<Span<'static>>,
     current_line_style: Style,
     current_line_in_code_block: bool,
+    /// Language and buffered body of a fenced code block that is highlighted
+    /// as a whole once the block ends.
+    code_block_source: Option<(String, String)>,
+    table: Option<TableBuilder>,
+}
+
+/// Cells of a table collected while its events are parsed; column widths are
+/// only known once every row has been seen.
+struct TableBuilder {
+    alignments: Vec<Alignment>,
+    rows: Vec<Vec<Vec<Span<'static>>>>,
+    header_rows: usize,
+}
+
+impl TableBuilder {
+    fn new(alignments: Vec<Alignment>) -> Self {
+        Self {
+            alignments,
+            rows: Vec::new(),
+            header_rows: 0,
+        }
+    }
+
+    fn start_row(&mut self) {
+        self.rows.push(Vec::new());
+    }
+
+    fn start_cell(&mut self) {
+        if let Some(row) = self.rows.last_mut() {
+            row.push(Vec::new());
+        }
+    }
+
+    fn push_span(&mut self, span: Span<'static>) {
+        if let Some(cell) = self.rows.last_mut().and_then(|row| row.last_mut()) {
+            cell.push(span);
+        }
+    }
+
+    fn into_lines(self, styles: &MarkdownStyles) -> Vec<Line<'static>> {
+        let column_count = self.rows.iter().map(Vec::len).max().unwrap_or(0);
+        let mut widths = vec![0; column_count];
+        for row in &self.rows {
+            for (column, cell) in row.iter().enumerate() {
+                widths[column] = widths[column].max(cell_width(cell));
+            }
+        }
+
+        let mut lines = Vec::with_capacity(self.rows.len() + 1);
+        for (row_index, row) in self.rows.into_iter().enumerate() {
+            let is_header = row_index < self.header_rows;
+            let mut cells = row.into_iter();
+            let mut spans = Vec::new();
+            for (column, &width) in widths.iter().enumerate() {
+                let cell = cells.next().unwrap_or_default();
+                let padding = width.saturating_sub(cell_width(&cell));
+                let alignment = self
+                    .alignments
+                    .get(column)
+                    .copied()
+                    .unwrap_or(Alignment::None);
+                let (before, after) = match alignment {
+                    Alignment::Right => (padding, 0),
+                    Alignment::Center => (padding / 2, padding - padding / 2),
+                    Alignment::Left | Alignment::None => (0, padding),
+                };
+                let is_last = column + 1 == column_count;
+                if column > 0 {
+                    spans.push(Span::from("  "));
+                }
+                if before > 0 {
+                    spans.push(Span::from(" ".repeat(before)));
+                }
+                spans.extend(cell.into_iter().map(|span| {
+                    if is_header {
+                        span.patch_style(styles.table_header)
+                    } else {
+                        span
+                    }
+                }));
+                if after > 0 && !is_last {
+                    spans.push(Span::from(" ".repeat(after)));
+                }
+            }
+            lines.push(Line::from(spans));
+            if row_index + 1 == self.header_rows {
+                let rule = widths
+                    .iter()
+                    .map(|width| "─".repeat((*width).max(1)))
+                    .collect::<Vec<_>>()
+                    .join("  ");
+                lines.push(Line::from(Span::styled(rule, styles.table_rule)));
+            }
+        }
+        lines
+    }
+}
+
+fn cell_width(cell: &[Span<'_>]) -> usize {
+    cell.iter().map(|span| span.content.width()).sum()
 }
 
 impl<'a, I> Writer<'a, I>
@@ -130,6 +238,8 @@ where
             current_subsequent_indent: Vec::new(),
             current_line_style: Style::default(),
             current_line_in_code_block: false,
+            code_block_source: None,
+            table: None,
         }
     }
 
@@ -185,12 +295,19 @@ where
             Tag::Strong => self.push_inline_style(self.styles.strong),
             Tag::Strikethrough => self.push_inline_style(self.styles.strikethrough),
             Tag::Link { dest_url, .. } => self.push_link(dest_url.to_string()),
+            Tag::Table(alignments) => self.start_table(alignments),
+            Tag::TableHead | Tag::TableRow => {
+                if let Some(table) = self.table.as_mut() {
+                    table.start_row();
+                }
+            }
+            Tag::TableCell => {
+                if let Some(table) = self.table.as_mut() {
+                    table.start_cell();
+                }
+            }
             Tag::HtmlBlock
             | Tag::FootnoteDefinition(_)
-            | Tag::Table(_)
-            | Tag::TableHead
-            | Tag::TableRow
-            | Tag::TableCell
             | Tag::Image { .. }
             | Tag::MetadataBlock(_) => {}
         }
@@ -209,10 +326,14 @@ where
             }
             TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough => self.pop_inline_style(),
             TagEnd::Link => self.pop_link(),
+            TagEnd::Table => self.end_table(),
+            TagEnd::TableHead => {
+                if let Some(table) = self.table.as_mut() {
+                    table.header_rows = table.rows.len();
+                }
+            }
             TagEnd::HtmlBlock
             | TagEnd::FootnoteDefinition
-            | TagEnd::Table
-            | TagEnd::TableHead
             | TagEnd::TableRow
             | TagEnd::TableCell
             | TagEnd::Image
@@ -274,6 +395,15 @@ where
     }
 
     fn text(&mut self, text: CowStr<'a>) {
+        if let Some((_, source)) = self.code_block_source.as_mut() {
+            source.push_str(&text);
+            return;
+        }
+        if let Some(table) = self.table.as_mut() {
+            let style = self.inline_styles.last().copied().unwrap_or_default();
+            table.push_span(Span::styled(text.into_string(), style));
+            return;
+        }
         if self.pending_marker_line {
             self.push_line(Line::default());
         }
@@ -394,7 +524,7 @@ where
         self.needs_newline = false;
     }
 
-    fn start_codeblock(&mut self, _lang: Option<String>, indent: Option<Span<'static>>) {
+    fn start_codeblock(&mut self, lang: Option<String>, indent: Option<Span<'static>>) {
         self.flush_current_line();
         if !self.text.lines.is_empty() {
             self.push_blank_line();
@@ -406,14 +536,60 @@ where
             false,
         ));
         self.needs_newline = true;
+        self.code_block_source = lang
+            .filter(|lang| !lang.is_empty())
+            .map(|lang| (lang, String::new()));
     }
 
     fn end_codeblock(&mut self) {
+        if let Some((lang, source)) = self.code_block_source.take()
+            && !source.is_empty()
+        {
+            let code = source.strip_suffix('\n').unwrap_or(&source);
+            match highlight_code_to_lines(&lang, code) {
+                Some(lines) => self.push_highlighted_code(lines),
+                None => self.text(CowStr::from(source)),
+            }
+        }
         self.needs_newline = true;
         self.in_code_block = false;
         self.indent_stack.pop();
     }
 
+    fn push_highlighted_code(&mut self, lines: Vec<Line<'static>>) {
+        if self.pending_marker_line {
+            self.push_line(Line::default());
+        }
+        self.pending_marker_line = false;
+        for line in lines {
+            self.push_line(line);
+        }
+        self.needs_newline = false;
+    }
+
+    fn start_table(&mut self, alignments: Vec<Alignment>) {
+        self.flush_current_line();
+        if self.needs_newline {
+            self.push_blank_line();
+        }
+        self.needs_newline = false;
+        self.table = Some(TableBuilder::new(alignments));
+    }
+
+    /// Tables are laid out once complete and never wrapped, like code blocks.
+    fn end_table(&mut self) {
+        let Some(table) = self.table.take() else {
+            return;
+        };
+        for (index, line) in table.into_lines(&self.styles).into_iter().enumerate() {
+            let mut spans = self.prefix_spans(index == 0 && self.pending_marker_line);
+            spans.extend(line.spans);
+            self.text.lines.push(Line::from(spans));
+        }
+        self.pending_marker_line = false;
+        self.needs_newline = true;
+    }
+
     fn push_inline_style(&mut self, style: Style) {
         let current = self.inline_styles.last().copied().unwrap_or_default();
         let merged = current.patch(style);
@@ -485,6 +661,10 @@ where
     }
 
     fn push_span(&mut self, span: Span<'static>) {
+        if let Some(table) = self.table.as_mut() {
+            table.push_span(span);
+            return;
+        }
         if let Some(line) = self.current_line_content.as_mut() {
             line.push_span(span);
         } else {
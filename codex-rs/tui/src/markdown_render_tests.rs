@@ -1,4 +1,6 @@
 use pretty_assertions::assert_eq;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
 use ratatui::style::Stylize;
 use ratatui::text::Line;
 use ratatui::text::Span;
@@ -658,6 +660,67 @@ fn code_block_unhighlighted() {
     assert_eq!(text, expected);
 }
 
+#[test]
+fn code_block_shell_is_highlighted() {
+    let text = render_markdown_text("```bash\necho \"hi\" | wc -c\n```\n");
+    assert_eq!(text.lines.len(), 1);
+    let line = &text.lines[0];
+    let content: String = line.spans.iter().map(|s| s.content.clone()).collect();
+    assert_eq!(content, "echo \"hi\" | wc -c");
+    let dimmed: Vec<String> = line
+        .spans
+        .iter()
+        .filter(|s| s.style.add_modifier.contains(Modifier::DIM))
+        .map(|s| s.content.trim().to_string())
+        .collect();
+    assert!(dimmed.contains(&"\"hi\"".to_string()), "{dimmed:?}");
+    assert!(dimmed.contains(&"|".to_string()), "{dimmed:?}");
+}
+
+#[test]
+fn table_aligns_columns() {
+    let md = "| Left | Center | Right |\n|:-----|:------:|------:|\n| a | b | c |\n| long cell | x | yy |\n";
+    let text = render_markdown_text(md);
+    let lines: Vec<String> = text
+        .lines
+        .iter()
+        .map(|l| {
+            l.spans
+                .iter()
+                .map(|s| s.content.clone())
+                .collect::<String>()
+        })
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            "Left       Center  Right",
+            "─────────  ──────  ─────",
+            "a            b         c",
+            "long cell    x        yy",
+        ]
+    );
+    assert_eq!(text.lines[0].spans[0], "Left".bold());
+    assert_eq!(text.lines[1].spans[0].style, Style::new().dim());
+}
+
+#[test]
+fn table_inside_list_item_keeps_indent() {
+    let md = "- Item\n\n  | a | b |\n  |---|---|\n  | 1 | 2 |\n";
+    let text = render_markdown_text(md);
+    let lines: Vec<String> = text
+        .lines
+        .iter()
+        .map(|l| {
+            l.spans
+                .iter()
+                .map(|s| s.content.clone())
+                .collect::<String>()
+        })
+        .collect();
+    assert_eq!(lines, vec!["- Item", "", "  a  b", "  ─  ─", "  1  2"]);
+}
+
 #[test]
 fn code_block_multiple_lines_root() {
     let md = "```\nfirst\nsecond\n```\n";
@@ -36,7 +36,8 @@ impl MarkdownStreamCollector {
         let source = self.buffer.clone();
         let last_newline_idx = source.rfind('\n');
         let source = if let Some(last_newline_idx) = last_newline_idx {
-            source[..=last_newline_idx].to_string()
+            let complete = &source[..=last_newline_idx];
+            complete[..stable_prefix_len(complete)].to_string()
         } else {
             return Vec::new();
         };
@@ -96,6 +97,29 @@ impl MarkdownStreamCollector {
     }
 }
 
+/// Length of the prefix of `source` (complete lines only) whose rendering
+/// will not change as more deltas arrive. A trailing run of table rows is
+/// held back: whether it is a table at all, and its column widths, depend on
+/// rows that have not been received yet.
+fn stable_prefix_len(source: &str) -> usize {
+    let mut in_fence = false;
+    let mut offset = 0;
+    let mut table_start = None;
+    for line in source.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            table_start = None;
+        } else if !in_fence && trimmed.contains('|') {
+            table_start.get_or_insert(offset);
+        } else {
+            table_start = None;
+        }
+        offset += line.len();
+    }
+    table_start.unwrap_or(source.len())
+}
+
 #[cfg(test)]
 pub(crate) fn simulate_stream_markdown_for_tests(
     deltas: &[&str],
@@ -131,6 +155,39 @@ mod tests {
         assert_eq!(out2.len(), 1, "one completed line after newline");
     }
 
+    #[tokio::test]
+    async fn table_rows_are_held_back_until_the_table_ends() {
+        let mut c = super::MarkdownStreamCollector::new(None);
+        c.push_delta("Results:\n\n| Name | Count |\n");
+        let out = c.commit_complete_lines();
+        assert_eq!(lines_to_plain_strings(&out), vec!["Results:".to_string()]);
+
+        c.push_delta("|------|------:|\n| a | 1 |\n| bb | 22 |\n");
+        assert!(c.commit_complete_lines().is_empty());
+
+        c.push_delta("\nDone.\n");
+        let out = c.commit_complete_lines();
+        assert_eq!(
+            lines_to_plain_strings(&out),
+            vec![
+                String::new(),
+                "Name  Count".to_string(),
+                "────  ─────".to_string(),
+                "a         1".to_string(),
+                "bb       22".to_string(),
+                String::new(),
+                "Done.".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn stable_prefix_ignores_pipes_inside_code_fences() {
+        let source = "```sh\nls | wc -l\n";
+        assert_eq!(stable_prefix_len(source), source.len());
+        assert_eq!(stable_prefix_len("Intro\n| a | b |\n"), "Intro\n".len());
+    }
+
     #[tokio::test]
     async fn finalize_commits_partial_line() {
         let mut c = super::MarkdownStreamCollector::new(None);
@@ -1,3 +1,4 @@
+mod osc777;
 mod osc9;
 mod windows_toast;
 
@@ -5,18 +6,23 @@ use std::env;
 use std::io;
 
 use codex_core::env::is_wsl;
+use codex_core::terminal::TerminalInfo;
+use codex_core::terminal::terminal_info;
 use osc9::Osc9Backend;
+use osc777::Osc777Backend;
 use windows_toast::WindowsToastBackend;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum NotificationBackendKind {
     Osc9,
+    Osc777,
     WindowsToast,
 }
 
 #[derive(Debug)]
 pub enum DesktopNotificationBackend {
     Osc9(Osc9Backend),
+    Osc777(Osc777Backend),
     WindowsToast(WindowsToastBackend),
 }
 
@@ -25,6 +31,10 @@ impl DesktopNotificationBackend {
         Self::Osc9(Osc9Backend)
     }
 
+    pub fn osc777() -> Self {
+        Self::Osc777(Osc777Backend)
+    }
+
     pub fn windows_toast() -> Self {
         Self::WindowsToast(WindowsToastBackend::default())
     }
@@ -32,6 +42,7 @@ impl DesktopNotificationBackend {
     pub fn kind(&self) -> NotificationBackendKind {
         match self {
             DesktopNotificationBackend::Osc9(_) => NotificationBackendKind::Osc9,
+            DesktopNotificationBackend::Osc777(_) => NotificationBackendKind::Osc777,
             DesktopNotificationBackend::WindowsToast(_) => NotificationBackendKind::WindowsToast,
         }
     }
@@ -39,17 +50,24 @@ impl DesktopNotificationBackend {
     pub fn notify(&mut self, message: &str) -> io::Result<()> {
         match self {
             DesktopNotificationBackend::Osc9(backend) => backend.notify(message),
+            DesktopNotificationBackend::Osc777(backend) => backend.notify(message),
             DesktopNotificationBackend::WindowsToast(backend) => backend.notify(message),
         }
     }
 }
 
 pub fn detect_backend() -> DesktopNotificationBackend {
+    detect_backend_for(&terminal_info())
+}
+
+fn detect_backend_for(terminal: &TerminalInfo) -> DesktopNotificationBackend {
     if should_use_windows_toasts() {
         tracing::info!(
             "Windows Terminal session detected under WSL; using Windows toast notifications"
         );
         DesktopNotificationBackend::windows_toast()
+    } else if terminal.prefers_osc777_notifications() {
+        DesktopNotificationBackend::osc777()
     } else {
         DesktopNotificationBackend::osc9()
     }
@@ -62,7 +80,9 @@ fn should_use_windows_toasts() -> bool {
 #[cfg(test)]
 mod tests {
     use super::NotificationBackendKind;
-    use super::detect_backend;
+    use super::detect_backend_for;
+    use codex_core::terminal::TerminalInfo;
+    use codex_core::terminal::TerminalName;
     use serial_test::serial;
     use std::ffi::OsString;
 
@@ -89,6 +109,16 @@ mod tests {
         }
     }
 
+    fn terminal(name: TerminalName) -> TerminalInfo {
+        TerminalInfo {
+            name,
+            term_program: None,
+            version: None,
+            term: None,
+            multiplexer: None,
+        }
+    }
+
     impl Drop for EnvVarGuard {
         fn drop(&mut self) {
             unsafe {
@@ -105,7 +135,21 @@ mod tests {
     fn defaults_to_osc9_outside_wsl() {
         let _wsl_guard = EnvVarGuard::remove("WSL_DISTRO_NAME");
         let _wt_guard = EnvVarGuard::remove("WT_SESSION");
-        assert_eq!(detect_backend().kind(), NotificationBackendKind::Osc9);
+        assert_eq!(
+            detect_backend_for(&terminal(TerminalName::Unknown)).kind(),
+            NotificationBackendKind::Osc9
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn uses_osc777_in_vte_terminals() {
+        let _wsl_guard = EnvVarGuard::remove("WSL_DISTRO_NAME");
+        let _wt_guard = EnvVarGuard::remove("WT_SESSION");
+        assert_eq!(
+            detect_backend_for(&terminal(TerminalName::GnomeTerminal)).kind(),
+            NotificationBackendKind::Osc777
+        );
     }
 
     #[test]
@@ -113,7 +157,10 @@ mod tests {
     fn waits_for_windows_terminal() {
         let _wsl_guard = EnvVarGuard::set("WSL_DISTRO_NAME", "Ubuntu");
         let _wt_guard = EnvVarGuard::remove("WT_SESSION");
-        assert_eq!(detect_backend().kind(), NotificationBackendKind::Osc9);
+        assert_eq!(
+            detect_backend_for(&terminal(TerminalName::Unknown)).kind(),
+            NotificationBackendKind::Osc9
+        );
     }
 
     #[cfg(target_os = "linux")]
@@ -123,7 +170,7 @@ mod tests {
         let _wsl_guard = EnvVarGuard::set("WSL_DISTRO_NAME", "Ubuntu");
         let _wt_guard = EnvVarGuard::set("WT_SESSION", "abc");
         assert_eq!(
-            detect_backend().kind(),
+            detect_backend_for(&terminal(TerminalName::Unknown)).kind(),
             NotificationBackendKind::WindowsToast
         );
     }
@@ -134,6 +181,9 @@ mod tests {
     fn stays_on_osc9_outside_linux_even_with_wsl_env() {
         let _wsl_guard = EnvVarGuard::set("WSL_DISTRO_NAME", "Ubuntu");
         let _wt_guard = EnvVarGuard::set("WT_SESSION", "abc");
-        assert_eq!(detect_backend().kind(), NotificationBackendKind::Osc9);
+        assert_eq!(
+            detect_backend_for(&terminal(TerminalName::Unknown)).kind(),
+            NotificationBackendKind::Osc9
+        );
     }
 }
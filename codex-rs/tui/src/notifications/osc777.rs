@@ -0,0 +1,41 @@
+use std::fmt;
+use std::io;
+use std::io::stdout;
+
+use codex_core::terminal::osc777_notification_sequence;
+use crossterm::Command;
+use ratatui::crossterm::execute;
+
+/// Title shown above OSC 777 notifications.
+const NOTIFICATION_TITLE: &str = "Codex";
+
+#[derive(Debug, Default)]
+pub struct Osc777Backend;
+
+impl Osc777Backend {
+    pub fn notify(&mut self, message: &str) -> io::Result<()> {
+        execute!(stdout(), PostNotification(message.to_string()))
+    }
+}
+
+/// Command that emits an OSC 777 desktop notification with a message.
+#[derive(Debug, Clone)]
+pub struct PostNotification(pub String);
+
+impl Command for PostNotification {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        f.write_str(&osc777_notification_sequence(NOTIFICATION_TITLE, &self.0))
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> io::Result<()> {
+        Err(std::io::Error::other(
+            "tried to execute PostNotification using WinAPI; use ANSI instead",
+        ))
+    }
+
+    #[cfg(windows)]
+    fn is_ansi_code_supported(&self) -> bool {
+        true
+    }
+}
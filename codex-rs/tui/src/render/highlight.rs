@@ -8,9 +8,11 @@ use tree_sitter_highlight::HighlightConfiguration;
 use tree_sitter_highlight::HighlightEvent;
 use tree_sitter_highlight::Highlighter;
 
-// Ref: https://github.com/tree-sitter/tree-sitter-bash/blob/master/queries/highlights.scm
+// Capture names shared by the bundled grammars' highlight queries, e.g.
+// https://github.com/tree-sitter/tree-sitter-bash/blob/master/queries/highlights.scm
+// Dotted captures such as `function.method` fall back to their prefix.
 #[derive(Copy, Clone)]
-enum BashHighlight {
+enum HighlightKind {
     Comment,
     Constant,
     Embedded,
@@ -22,7 +24,7 @@ enum BashHighlight {
     String,
 }
 
-impl BashHighlight {
+impl HighlightKind {
     const ALL: [Self; 9] = [
         Self::Comment,
         Self::Constant,
@@ -57,34 +59,77 @@ impl BashHighlight {
     }
 }
 
-static HIGHLIGHT_CONFIG: OnceLock<HighlightConfiguration> = OnceLock::new();
+/// Languages with a bundled tree-sitter grammar and highlight query.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Grammar {
+    Bash,
+    Go,
+    Python,
+    Rust,
+}
+
+impl Grammar {
+    /// Maps the first word of a fenced code block's info string to a grammar.
+    fn for_lang(lang: &str) -> Option<Self> {
+        match lang.to_ascii_lowercase().as_str() {
+            "bash" | "sh" | "shell" | "zsh" => Some(Self::Bash),
+            "go" | "golang" => Some(Self::Go),
+            "py" | "python" | "python3" => Some(Self::Python),
+            "rs" | "rust" => Some(Self::Rust),
+            _ => None,
+        }
+    }
+
+    /// The highlight configuration, or `None` if the query fails to load.
+    fn config(self) -> Option<&'static HighlightConfiguration> {
+        static BASH: OnceLock<Option<HighlightConfiguration>> = OnceLock::new();
+        static GO: OnceLock<Option<HighlightConfiguration>> = OnceLock::new();
+        static PYTHON: OnceLock<Option<HighlightConfiguration>> = OnceLock::new();
+        static RUST: OnceLock<Option<HighlightConfiguration>> = OnceLock::new();
+        let (cell, language, name, query) = match self {
+            Self::Bash => (
+                &BASH,
+                tree_sitter_bash::LANGUAGE.into(),
+                "bash",
+                tree_sitter_bash::HIGHLIGHT_QUERY,
+            ),
+            Self::Go => (
+                &GO,
+                tree_sitter_go::LANGUAGE.into(),
+                "go",
+                tree_sitter_go::HIGHLIGHTS_QUERY,
+            ),
+            Self::Python => (
+                &PYTHON,
+                tree_sitter_python::LANGUAGE.into(),
+                "python",
+                tree_sitter_python::HIGHLIGHTS_QUERY,
+            ),
+            Self::Rust => (
+                &RUST,
+                tree_sitter_rust::LANGUAGE.into(),
+                "rust",
+                tree_sitter_rust::HIGHLIGHTS_QUERY,
+            ),
+        };
+        cell.get_or_init(|| {
+            let mut config = HighlightConfiguration::new(language, name, query, "", "").ok()?;
+            config.configure(highlight_names());
+            Some(config)
+        })
+        .as_ref()
+    }
+}
 
 fn highlight_names() -> &'static [&'static str] {
-    static NAMES: OnceLock<[&'static str; BashHighlight::ALL.len()]> = OnceLock::new();
+    static NAMES: OnceLock<[&'static str; HighlightKind::ALL.len()]> = OnceLock::new();
     NAMES
-        .get_or_init(|| BashHighlight::ALL.map(BashHighlight::as_str))
+        .get_or_init(|| HighlightKind::ALL.map(HighlightKind::as_str))
         .as_slice()
 }
 
-fn highlight_config() -> &'static HighlightConfiguration {
-    HIGHLIGHT_CONFIG.get_or_init(|| {
-        let language = tree_sitter_bash::LANGUAGE.into();
-        #[expect(clippy::expect_used)]
-        let mut config = HighlightConfiguration::new(
-            language,
-            "bash",
-            tree_sitter_bash::HIGHLIGHT_QUERY,
-            "",
-            "",
-        )
-        .expect("load bash highlight query");
-        config.configure(highlight_names());
-        config
-    })
-}
-
-fn highlight_for(highlight: Highlight) -> BashHighlight {
-    BashHighlight::ALL[highlight.0]
+fn highlight_for(highlight: Highlight) -> HighlightKind {
+    HighlightKind::ALL[highlight.0]
 }
 
 fn push_segment(lines: &mut Vec<Line<'static>>, segment: &str, style: Option<Style>) {
@@ -109,12 +154,29 @@ fn push_segment(lines: &mut Vec<Line<'static>>, segment: &str, style: Option<Sty
 /// bash highlight query. The highlighter is streamed so multi-line content is
 /// split into `Line`s while preserving style boundaries.
 pub(crate) fn highlight_bash_to_lines(script: &str) -> Vec<Line<'static>> {
+    highlight_with_grammar(Grammar::Bash, script)
+}
+
+/// Highlight the body of a fenced code block whose info string starts with
+/// `lang`. Only bash, Go, Python and Rust have a bundled grammar; `None` is
+/// returned for every other language, which callers render unstyled.
+pub(crate) fn highlight_code_to_lines(lang: &str, code: &str) -> Option<Vec<Line<'static>>> {
+    let lang = lang
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .next()
+        .unwrap_or_default();
+    Grammar::for_lang(lang).map(|grammar| highlight_with_grammar(grammar, code))
+}
+
+fn highlight_with_grammar(grammar: Grammar, source: &str) -> Vec<Line<'static>> {
+    let Some(config) = grammar.config() else {
+        return vec![source.to_string().into()];
+    };
     let mut highlighter = Highlighter::new();
-    let iterator =
-        match highlighter.highlight(highlight_config(), script.as_bytes(), None, |_| None) {
-            Ok(iter) => iter,
-            Err(_) => return vec![script.to_string().into()],
-        };
+    let iterator = match highlighter.highlight(config, source.as_bytes(), None, |_| None) {
+        Ok(iter) => iter,
+        Err(_) => return vec![source.to_string().into()],
+    };
 
     let mut lines: Vec<Line<'static>> = vec![Line::from("")];
     let mut highlight_stack: Vec<Highlight> = Vec::new();
@@ -130,9 +192,9 @@ pub(crate) fn highlight_bash_to_lines(script: &str) -> Vec<Line<'static>> {
                     continue;
                 }
                 let style = highlight_stack.last().map(|h| highlight_for(*h).style());
-                push_segment(&mut lines, &script[start..end], style);
+                push_segment(&mut lines, &source[start..end], style);
             }
-            Err(_) => return vec![script.to_string().into()],
+            Err(_) => return vec![source.to_string().into()],
         }
     }
 
@@ -219,6 +281,34 @@ mod tests {
         assert!(string_style.add_modifier.contains(Modifier::DIM));
     }
 
+    #[test]
+    fn highlights_code_blocks_by_language() {
+        let lines = highlight_code_to_lines("sh", "echo \"hi\"").expect("sh is highlighted");
+        assert_eq!(reconstructed(&lines), "echo \"hi\"");
+        assert_eq!(dimmed_tokens(&lines), vec!["\"hi\"".to_string()]);
+
+        assert!(highlight_code_to_lines("Bash title=\"setup\"", "ls").is_some());
+        assert!(highlight_code_to_lines("haskell", "main = pure ()").is_none());
+    }
+
+    #[test]
+    fn highlights_bundled_grammars() {
+        for (lang, code) in [
+            ("rust", "// entry\nfn main() { let s = \"text\"; }"),
+            ("py", "# entry\ndef main():\n    return 'text'"),
+            ("go", "// entry\nfunc main() { s := \"text\" }"),
+        ] {
+            let lines = highlight_code_to_lines(lang, code).expect("grammar is bundled");
+            assert_eq!(reconstructed(&lines), code);
+
+            // Comments and strings are dimmed; identifiers are not.
+            let dimmed = dimmed_tokens(&lines).join(" ");
+            assert!(dimmed.contains("entry"), "{lang}: {dimmed:?}");
+            assert!(dimmed.contains("text"), "{lang}: {dimmed:?}");
+            assert!(!dimmed.contains("main"), "{lang}: {dimmed:?}");
+        }
+    }
+
     #[test]
     fn highlights_heredoc_body_as_string() {
         let s = "cat <<EOF\nheredoc body\nEOF";
@@ -20,6 +20,7 @@ pub enum SlashCommand {
     Experimental,
     Skills,
     Review,
+    Explore,
     New,
     Resume,
     Fork,
@@ -29,7 +30,12 @@ pub enum SlashCommand {
     // Undo,
     Diff,
     Mention,
+    Open,
+    Clip,
+    Secrets,
+    Feature,
     Status,
+    Dashboard,
     Mcp,
     Logout,
     Quit,
@@ -37,6 +43,7 @@ pub enum SlashCommand {
     Feedback,
     Rollout,
     Ps,
+    Attach,
     TestApproval,
 }
 
@@ -49,15 +56,24 @@ impl SlashCommand {
             SlashCommand::Init => "create an AGENTS.md file with instructions for Codex",
             SlashCommand::Compact => "summarize conversation to prevent hitting the context limit",
             SlashCommand::Review => "review my current changes and find issues",
+            SlashCommand::Explore => "explore the repository read-only and pin the findings",
             SlashCommand::Resume => "resume a saved chat",
             SlashCommand::Fork => "fork the current chat",
             // SlashCommand::Undo => "ask Codex to undo a turn",
             SlashCommand::Quit | SlashCommand::Exit => "exit Codex",
             SlashCommand::Diff => "show git diff (including untracked files)",
             SlashCommand::Mention => "mention a file",
+            SlashCommand::Open => "preview a file mentioned in this session",
+            SlashCommand::Clip => "store text on the clipboard for tools to use as {{clip:<name>}}",
+            SlashCommand::Secrets => "turn masking of secrets in tool outputs on or off",
+            SlashCommand::Feature => "turn a feature on or off for this conversation",
             SlashCommand::Skills => "use skills to improve how Codex performs specific tasks",
             SlashCommand::Status => "show current session configuration and token usage",
+            SlashCommand::Dashboard => {
+                "show live session stats (tokens, rate limits, sandbox, MCP)"
+            }
             SlashCommand::Ps => "list background terminals",
+            SlashCommand::Attach => "take over a background terminal the model started in raw mode",
             SlashCommand::Model => "choose what model and reasoning effort to use",
             SlashCommand::Collab => "change collaboration mode (experimental)",
             SlashCommand::Approvals => "choose what Codex can do without approval",
@@ -92,12 +108,19 @@ impl SlashCommand {
             | SlashCommand::ElevateSandbox
             | SlashCommand::Experimental
             | SlashCommand::Review
+            | SlashCommand::Explore
             | SlashCommand::Logout => false,
             SlashCommand::Diff
             | SlashCommand::Mention
+            | SlashCommand::Open
+            | SlashCommand::Clip
+            | SlashCommand::Secrets
+            | SlashCommand::Feature
             | SlashCommand::Skills
             | SlashCommand::Status
+            | SlashCommand::Dashboard
             | SlashCommand::Ps
+            | SlashCommand::Attach
             | SlashCommand::Mcp
             | SlashCommand::Feedback
             | SlashCommand::Quit
@@ -0,0 +1,475 @@
+//! Full-screen session dashboard opened with `/dashboard`.
+//!
+//! The chat widget feeds [`DashboardState`] from the events it already
+//! receives; [`dashboard_lines`] turns that state plus the current session
+//! configuration into lines for a pager overlay, which the chat widget
+//! re-renders while it is open whenever token usage or the turn changes.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use chrono::DateTime;
+use chrono::Local;
+use chrono::Utc;
+use codex_common::summarize_sandbox_policy;
+use codex_core::config::types::ModelPricing;
+use codex_core::protocol::McpStartupStatus;
+use codex_core::protocol::ProviderRateLimitsEvent;
+use codex_core::protocol::SandboxPolicy;
+use codex_core::protocol::TokenUsage;
+use codex_core::protocol::TokenUsageInfo;
+use codex_core::session_stats::estimate_cost_usd;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+
+use super::helpers::format_directory_display;
+use super::helpers::format_reset_timestamp;
+use super::helpers::format_tokens_compact;
+use super::rate_limits::RateLimitSnapshotDisplay;
+use super::rate_limits::StatusRateLimitData;
+use super::rate_limits::StatusRateLimitValue;
+use super::rate_limits::compose_rate_limit_data;
+use super::rate_limits::format_status_limit_summary;
+use super::rate_limits::render_status_limit_progress_bar;
+
+/// Number of most recent turns listed in the token usage table.
+const MAX_TURN_ROWS: usize = 10;
+
+/// Token usage of one completed turn.
+#[derive(Debug, Clone, PartialEq)]
+struct TurnUsage {
+    usage: TokenUsage,
+    /// Estimated cost, unknown when the model has no `model_pricing` entry.
+    cost_usd: Option<f64>,
+}
+
+/// Session statistics accumulated from events for the dashboard.
+#[derive(Debug, Default)]
+pub(crate) struct DashboardState {
+    /// Usage of each completed turn, oldest first.
+    turns: Vec<TurnUsage>,
+    /// Cumulative usage when the running turn started.
+    turn_start_usage: Option<TokenUsage>,
+    provider_rate_limits: Option<ProviderRateLimitsEvent>,
+    mcp_servers: BTreeMap<String, McpStartupStatus>,
+}
+
+impl DashboardState {
+    pub(crate) fn on_turn_started(&mut self, total_usage: Option<&TokenUsage>) {
+        self.turn_start_usage = Some(total_usage.cloned().unwrap_or_default());
+    }
+
+    pub(crate) fn on_turn_finished(
+        &mut self,
+        total_usage: Option<&TokenUsage>,
+        pricing: Option<&ModelPricing>,
+    ) {
+        let Some(start) = self.turn_start_usage.take() else {
+            return;
+        };
+        let end = total_usage.cloned().unwrap_or_default();
+        let usage = TokenUsage {
+            input_tokens: end.input_tokens - start.input_tokens,
+            cached_input_tokens: end.cached_input_tokens - start.cached_input_tokens,
+            output_tokens: end.output_tokens - start.output_tokens,
+            reasoning_output_tokens: end.reasoning_output_tokens - start.reasoning_output_tokens,
+            total_tokens: end.total_tokens - start.total_tokens,
+        };
+        let cost_usd = pricing.map(|pricing| estimate_cost_usd(&usage, pricing));
+        self.turns.push(TurnUsage { usage, cost_usd });
+    }
+
+    pub(crate) fn on_provider_rate_limits(&mut self, event: ProviderRateLimitsEvent) {
+        self.provider_rate_limits = Some(event);
+    }
+
+    pub(crate) fn on_mcp_server_status(&mut self, server: String, status: McpStartupStatus) {
+        self.mcp_servers.insert(server, status);
+    }
+}
+
+/// Session configuration and live values owned by the chat widget.
+pub(crate) struct DashboardContext<'a> {
+    pub model: &'a str,
+    pub cwd: &'a Path,
+    pub sandbox_policy: &'a SandboxPolicy,
+    pub token_info: Option<&'a TokenUsageInfo>,
+    pub rate_limits: Option<&'a RateLimitSnapshotDisplay>,
+    pub turn_running: bool,
+    pub background_processes: Vec<String>,
+}
+
+pub(crate) fn dashboard_lines(
+    state: &DashboardState,
+    context: DashboardContext<'_>,
+    now: DateTime<Local>,
+) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+
+    section(&mut lines, "Session");
+    field(&mut lines, "Model", context.model.to_string());
+    field(
+        &mut lines,
+        "Directory",
+        format_directory_display(context.cwd, None),
+    );
+    field(
+        &mut lines,
+        "Turn",
+        if context.turn_running {
+            "running".to_string()
+        } else {
+            "idle".to_string()
+        },
+    );
+
+    section(&mut lines, "Token usage");
+    push_token_usage(&mut lines, state, context.token_info);
+
+    section(&mut lines, "Rate limits");
+    push_rate_limits(&mut lines, state, context.rate_limits, now);
+
+    section(&mut lines, "Sandbox");
+    field(
+        &mut lines,
+        "Policy",
+        summarize_sandbox_policy(context.sandbox_policy),
+    );
+    let writable_roots = context
+        .sandbox_policy
+        .get_writable_roots_with_cwd(context.cwd);
+    if writable_roots.is_empty() {
+        field(&mut lines, "Writable roots", "none".to_string());
+    }
+    for (index, root) in writable_roots.iter().enumerate() {
+        let label = if index == 0 { "Writable roots" } else { "" };
+        field(
+            &mut lines,
+            label,
+            format_directory_display(root.root.as_path(), None),
+        );
+    }
+
+    section(&mut lines, "MCP servers");
+    if state.mcp_servers.is_empty() {
+        lines.push(dim_line("No MCP servers configured."));
+    }
+    for (server, status) in &state.mcp_servers {
+        let status = match status {
+            McpStartupStatus::Starting => "starting".cyan(),
+            McpStartupStatus::Ready => "ready".green(),
+            McpStartupStatus::Failed { error } => format!("failed: {error}").red(),
+            McpStartupStatus::Cancelled => "cancelled".dim(),
+        };
+        lines.push(Line::from(vec![format!("  {server:<16} ").into(), status]));
+    }
+
+    section(&mut lines, "Background tasks");
+    if context.background_processes.is_empty() {
+        lines.push(dim_line("No background terminals running."));
+    }
+    for process in context.background_processes {
+        lines.push(Line::from(vec!["  • ".dim(), process.into()]));
+    }
+
+    lines
+}
+
+fn push_token_usage(
+    lines: &mut Vec<Line<'static>>,
+    state: &DashboardState,
+    token_info: Option<&TokenUsageInfo>,
+) {
+    let Some(info) = token_info else {
+        lines.push(dim_line("No tokens used yet."));
+        return;
+    };
+    let total = &info.total_token_usage;
+    field(
+        lines,
+        "Session total",
+        format!(
+            "{} (in {}, cached {}, out {})",
+            format_tokens_compact(total.total_tokens),
+            format_tokens_compact(total.input_tokens),
+            format_tokens_compact(total.cached_input_tokens),
+            format_tokens_compact(total.output_tokens),
+        ),
+    );
+    if let Some(window) = info.model_context_window {
+        let used = info.last_token_usage.tokens_in_context_window();
+        field(
+            lines,
+            "Context window",
+            format!(
+                "{} / {} ({}% left)",
+                format_tokens_compact(used),
+                format_tokens_compact(window),
+                info.last_token_usage
+                    .percent_of_context_window_remaining(window),
+            ),
+        );
+    }
+
+    let skipped = state.turns.len().saturating_sub(MAX_TURN_ROWS);
+    for (index, turn) in state.turns.iter().enumerate().skip(skipped) {
+        let cost = turn
+            .cost_usd
+            .map(|cost| format!("${cost:.4}"))
+            .unwrap_or_else(|| "-".to_string());
+        lines.push(Line::from(vec![
+            format!("  Turn {:<3} ", index + 1).dim(),
+            format!(
+                "{:>7} total  {:>7} in  {:>7} out  {cost:>9}",
+                format_tokens_compact(turn.usage.total_tokens),
+                format_tokens_compact(turn.usage.input_tokens),
+                format_tokens_compact(turn.usage.output_tokens),
+            )
+            .into(),
+        ]));
+    }
+}
+
+fn push_rate_limits(
+    lines: &mut Vec<Line<'static>>,
+    state: &DashboardState,
+    rate_limits: Option<&RateLimitSnapshotDisplay>,
+    now: DateTime<Local>,
+) {
+    match compose_rate_limit_data(rate_limits, now) {
+        StatusRateLimitData::Available(rows) | StatusRateLimitData::Stale(rows) => {
+            for row in rows {
+                let value = match row.value {
+                    StatusRateLimitValue::Window {
+                        percent_used,
+                        resets_at,
+                    } => {
+                        let remaining = (100.0 - percent_used).max(0.0);
+                        let mut value = format!(
+                            "{} {}",
+                            render_status_limit_progress_bar(remaining),
+                            format_status_limit_summary(remaining)
+                        );
+                        if let Some(resets_at) = resets_at {
+                            value.push_str(&format!(" (resets {resets_at})"));
+                        }
+                        value
+                    }
+                    StatusRateLimitValue::Text(text) => text,
+                };
+                field(lines, &row.label, value);
+            }
+        }
+        StatusRateLimitData::Missing => {}
+    }
+
+    let Some(provider) = state.provider_rate_limits.as_ref() else {
+        if rate_limits.is_none() {
+            lines.push(dim_line("No rate-limit data received yet."));
+        }
+        return;
+    };
+    if let Some(remaining) = provider.remaining_requests {
+        field(
+            lines,
+            &format!("{} requests", provider.provider),
+            budget_summary(
+                remaining,
+                provider.limit_requests,
+                provider.requests_reset_at,
+                now,
+            ),
+        );
+    }
+    if let Some(remaining) = provider.remaining_tokens {
+        field(
+            lines,
+            &format!("{} tokens", provider.provider),
+            budget_summary(
+                remaining,
+                provider.limit_tokens,
+                provider.tokens_reset_at,
+                now,
+            ),
+        );
+    }
+    if let Some(paused_until) = provider.paused_until.and_then(local_time)
+        && paused_until > now
+    {
+        field(
+            lines,
+            "Throttled until",
+            format_reset_timestamp(paused_until, now),
+        );
+    }
+}
+
+fn budget_summary(
+    remaining: u64,
+    limit: Option<u64>,
+    reset_at: Option<i64>,
+    now: DateTime<Local>,
+) -> String {
+    let remaining_display = format_tokens_compact(i64::try_from(remaining).unwrap_or(i64::MAX));
+    let mut summary = match limit {
+        Some(limit) => format!(
+            "{remaining_display} / {} left",
+            format_tokens_compact(i64::try_from(limit).unwrap_or(i64::MAX))
+        ),
+        None => format!("{remaining_display} left"),
+    };
+    if let Some(reset_at) = reset_at.and_then(local_time) {
+        summary.push_str(&format!(
+            " (resets {})",
+            format_reset_timestamp(reset_at, now)
+        ));
+    }
+    summary
+}
+
+fn local_time(seconds: i64) -> Option<DateTime<Local>> {
+    DateTime::<Utc>::from_timestamp(seconds, 0).map(|dt| dt.with_timezone(&Local))
+}
+
+fn section(lines: &mut Vec<Line<'static>>, title: &str) {
+    if !lines.is_empty() {
+        lines.push(Line::default());
+    }
+    lines.push(Line::from(title.to_string().bold()));
+}
+
+fn field(lines: &mut Vec<Line<'static>>, label: &str, value: String) {
+    let label = if label.is_empty() {
+        String::new()
+    } else {
+        format!("{label}:")
+    };
+    lines.push(Line::from(vec![
+        format!("  {label:<22}").dim(),
+        value.into(),
+    ]));
+}
+
+fn dim_line(text: &str) -> Line<'static> {
+    Line::from(format!("  {text}").dim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn plain(lines: &[Line<'static>]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    fn usage(input: i64, output: i64) -> TokenUsage {
+        TokenUsage {
+            input_tokens: input,
+            cached_input_tokens: 0,
+            output_tokens: output,
+            reasoning_output_tokens: 0,
+            total_tokens: input + output,
+        }
+    }
+
+    #[test]
+    fn records_usage_per_turn() {
+        let mut state = DashboardState::default();
+        let pricing = ModelPricing {
+            input: 2.0,
+            cached_input: None,
+            output: 10.0,
+        };
+        state.on_turn_started(None);
+        state.on_turn_finished(Some(&usage(1_000, 200)), None);
+        state.on_turn_started(Some(&usage(1_000, 200)));
+        state.on_turn_finished(Some(&usage(3_500, 700)), Some(&pricing));
+        // A turn that never started is ignored.
+        state.on_turn_finished(Some(&usage(9_000, 900)), Some(&pricing));
+
+        assert_eq!(
+            state.turns,
+            vec![
+                TurnUsage {
+                    usage: usage(1_000, 200),
+                    cost_usd: None,
+                },
+                TurnUsage {
+                    usage: usage(2_500, 500),
+                    cost_usd: Some(0.01),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn renders_sections_from_state_and_context() {
+        let mut state = DashboardState::default();
+        state.on_turn_started(None);
+        state.on_turn_finished(
+            Some(&usage(1_000, 200)),
+            Some(&ModelPricing {
+                input: 2.0,
+                cached_input: None,
+                output: 10.0,
+            }),
+        );
+        state.on_mcp_server_status("docs".to_string(), McpStartupStatus::Ready);
+        state.on_provider_rate_limits(ProviderRateLimitsEvent {
+            provider: "OpenAI".to_string(),
+            limit_requests: Some(500),
+            remaining_requests: Some(42),
+            requests_reset_at: None,
+            limit_tokens: None,
+            remaining_tokens: None,
+            tokens_reset_at: None,
+            paused_until: None,
+        });
+        let token_info = TokenUsageInfo {
+            total_token_usage: usage(1_000, 200),
+            last_token_usage: usage(1_000, 200),
+            model_context_window: None,
+        };
+
+        let lines = plain(&dashboard_lines(
+            &state,
+            DashboardContext {
+                model: "gpt-5.1-codex",
+                cwd: Path::new("/repo"),
+                sandbox_policy: &SandboxPolicy::ReadOnly,
+                token_info: Some(&token_info),
+                rate_limits: None,
+                turn_running: false,
+                background_processes: vec!["npm run dev".to_string()],
+            },
+            Local::now(),
+        ));
+
+        for expected in [
+            "  Model:                gpt-5.1-codex",
+            "  Session total:        1.2K (in 1K, cached 0, out 200)",
+            "  Turn 1      1.2K total       1K in      200 out    $0.0040",
+            "  OpenAI requests:      42 / 500 left",
+            "  Policy:               read-only",
+            "  Writable roots:       none",
+            "  docs             ready",
+            "  • npm run dev",
+        ] {
+            assert!(
+                lines.iter().any(|line| line == expected),
+                "missing {expected:?} in {lines:#?}"
+            );
+        }
+    }
+}
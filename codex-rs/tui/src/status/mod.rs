@@ -1,10 +1,14 @@
 mod account;
 mod card;
+mod dashboard;
 mod format;
 mod helpers;
 mod rate_limits;
 
 pub(crate) use card::new_status_output;
+pub(crate) use dashboard::DashboardContext;
+pub(crate) use dashboard::DashboardState;
+pub(crate) use dashboard::dashboard_lines;
 pub(crate) use helpers::format_tokens_compact;
 pub(crate) use rate_limits::RateLimitSnapshotDisplay;
 pub(crate) use rate_limits::rate_limit_snapshot_display;
@@ -103,6 +103,9 @@ async fn status_snapshot_includes_reasoning_details() {
             network_access: false,
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
+            max_memory_bytes: None,
+            cpu_rate_percent: None,
+            max_processes: None,
         })
         .expect("set sandbox policy");
 
@@ -45,12 +45,15 @@ use crate::tui::event_stream::EventBroker;
 use crate::tui::event_stream::TuiEventStream;
 #[cfg(unix)]
 use crate::tui::job_control::SuspendContext;
+use crate::tui::terminal_status::SessionStatus;
+use crate::tui::terminal_status::TerminalStatus;
 
 mod event_stream;
 mod frame_rate_limiter;
 mod frame_requester;
 #[cfg(unix)]
 mod job_control;
+pub(crate) mod terminal_status;
 
 /// A type alias for the terminal type used in this application
 pub type Terminal = CustomTerminal<CrosstermBackend<Stdout>>;
@@ -249,6 +252,7 @@ pub struct Tui {
     notification_backend: Option<DesktopNotificationBackend>,
     // When false, enter_alt_screen() becomes a no-op (for Zellij scrollback support)
     alt_screen_enabled: bool,
+    terminal_status: TerminalStatus,
 }
 
 impl Tui {
@@ -277,6 +281,7 @@ impl Tui {
             enhanced_keys_supported,
             notification_backend: Some(detect_backend()),
             alt_screen_enabled: true,
+            terminal_status: TerminalStatus::default(),
         }
     }
 
@@ -285,6 +290,29 @@ impl Tui {
         self.alt_screen_enabled = enabled;
     }
 
+    /// Enable reporting the session status through the terminal title and
+    /// progress indicator (`tui.terminal_title` / `tui.terminal_progress`).
+    pub fn set_terminal_status_enabled(&mut self, title: bool, progress: bool) {
+        self.terminal_status = TerminalStatus::new(title, progress);
+    }
+
+    /// Update the terminal title and progress indicator, if enabled.
+    pub(crate) fn set_session_status(&mut self, status: &SessionStatus) {
+        if let Err(err) = self
+            .terminal_status
+            .update(self.terminal.backend_mut(), status)
+        {
+            tracing::warn!("failed to update terminal status: {err}");
+        }
+    }
+
+    /// Clear the progress indicator and restore the original terminal title.
+    pub fn restore_terminal_status(&mut self) {
+        if let Err(err) = self.terminal_status.restore(self.terminal.backend_mut()) {
+            tracing::warn!("failed to restore terminal status: {err}");
+        }
+    }
+
     pub fn frame_requester(&self) -> FrameRequester {
         self.frame_requester.clone()
     }
@@ -382,10 +410,10 @@ impl Tui {
                     }
                     false
                 }
-                NotificationBackendKind::Osc9 => {
+                NotificationBackendKind::Osc9 | NotificationBackendKind::Osc777 => {
                     tracing::warn!(
                         error = %err,
-                        "Failed to emit OSC 9 notification; disabling future notifications"
+                        "Failed to emit OSC notification; disabling future notifications"
                     );
                     self.notification_backend = None;
                     false
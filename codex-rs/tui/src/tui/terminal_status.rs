@@ -0,0 +1,195 @@
+//! Session status reported through the terminal itself: the window title and
+//! the progress indicator, each enabled by its own `[tui]` setting.
+//!
+//! Sequences are only written when the status changes. The original title is
+//! saved on the terminal's title stack before the first update and restored by
+//! [`TerminalStatus::restore`].
+
+use std::io;
+use std::io::Write;
+
+use codex_core::terminal::POP_TITLE_SEQUENCE;
+use codex_core::terminal::PUSH_TITLE_SEQUENCE;
+use codex_core::terminal::TerminalProgress;
+use codex_core::terminal::progress_sequence;
+use codex_core::terminal::set_title_sequence;
+use codex_core::terminal::terminal_info;
+
+/// What the session is doing, as shown in the title and progress indicator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SessionPhase {
+    Idle,
+    Working,
+    WaitingForApproval,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SessionStatus {
+    pub(crate) model: String,
+    pub(crate) phase: SessionPhase,
+    pub(crate) pending_approvals: usize,
+}
+
+impl SessionStatus {
+    fn title(&self) -> String {
+        let phase = match self.phase {
+            SessionPhase::Idle => "idle",
+            SessionPhase::Working => "working",
+            SessionPhase::WaitingForApproval => "waiting for approval",
+        };
+        let mut title = format!("codex · {phase} · {}", self.model);
+        match self.pending_approvals {
+            0 => {}
+            1 => title.push_str(" · 1 approval pending"),
+            count => title.push_str(&format!(" · {count} approvals pending")),
+        }
+        title
+    }
+
+    fn progress(&self) -> TerminalProgress {
+        match self.phase {
+            SessionPhase::Idle => TerminalProgress::Hidden,
+            SessionPhase::Working => TerminalProgress::Indeterminate,
+            SessionPhase::WaitingForApproval => TerminalProgress::Paused,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct TerminalStatus {
+    title_enabled: bool,
+    progress_enabled: bool,
+    title_saved: bool,
+    last_title: Option<String>,
+    last_progress: Option<TerminalProgress>,
+}
+
+impl TerminalStatus {
+    /// The progress indicator is only used in terminals known to support it.
+    pub(crate) fn new(title_enabled: bool, progress_enabled: bool) -> Self {
+        Self {
+            title_enabled,
+            progress_enabled: progress_enabled && terminal_info().supports_progress_indicator(),
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn update(
+        &mut self,
+        out: &mut impl Write,
+        status: &SessionStatus,
+    ) -> io::Result<()> {
+        if self.title_enabled {
+            let title = status.title();
+            if self.last_title.as_ref() != Some(&title) {
+                if !self.title_saved {
+                    out.write_all(PUSH_TITLE_SEQUENCE.as_bytes())?;
+                    self.title_saved = true;
+                }
+                out.write_all(set_title_sequence(&title).as_bytes())?;
+                self.last_title = Some(title);
+            }
+        }
+        if self.progress_enabled {
+            let progress = status.progress();
+            // Nothing to clear before the first turn starts.
+            let unchanged = match self.last_progress {
+                Some(last) => last == progress,
+                None => progress == TerminalProgress::Hidden,
+            };
+            if !unchanged {
+                out.write_all(progress_sequence(progress).as_bytes())?;
+                self.last_progress = Some(progress);
+            }
+        }
+        out.flush()
+    }
+
+    /// Clears the progress indicator and restores the title the terminal had
+    /// before the first update.
+    pub(crate) fn restore(&mut self, out: &mut impl Write) -> io::Result<()> {
+        if self
+            .last_progress
+            .take()
+            .is_some_and(|progress| progress != TerminalProgress::Hidden)
+        {
+            out.write_all(progress_sequence(TerminalProgress::Hidden).as_bytes())?;
+        }
+        if self.title_saved {
+            out.write_all(POP_TITLE_SEQUENCE.as_bytes())?;
+            self.title_saved = false;
+            self.last_title = None;
+        }
+        out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn status(phase: SessionPhase, pending_approvals: usize) -> SessionStatus {
+        SessionStatus {
+            model: "gpt-5.1-codex".to_string(),
+            phase,
+            pending_approvals,
+        }
+    }
+
+    fn written(terminal_status: &mut TerminalStatus, status: Option<&SessionStatus>) -> String {
+        let mut out = Vec::new();
+        match status {
+            Some(status) => terminal_status.update(&mut out, status),
+            None => terminal_status.restore(&mut out),
+        }
+        .expect("write to buffer");
+        String::from_utf8(out).expect("utf-8")
+    }
+
+    #[test]
+    fn sets_title_on_change_and_restores_it() {
+        let mut terminal_status = TerminalStatus {
+            title_enabled: true,
+            ..TerminalStatus::default()
+        };
+
+        assert_eq!(
+            written(&mut terminal_status, Some(&status(SessionPhase::Idle, 0))),
+            "\x1b[22;0t\x1b]2;codex · idle · gpt-5.1-codex\x07"
+        );
+        assert_eq!(
+            written(&mut terminal_status, Some(&status(SessionPhase::Idle, 0))),
+            ""
+        );
+        assert_eq!(
+            written(
+                &mut terminal_status,
+                Some(&status(SessionPhase::WaitingForApproval, 2))
+            ),
+            "\x1b]2;codex · waiting for approval · gpt-5.1-codex · 2 approvals pending\x07"
+        );
+        assert_eq!(written(&mut terminal_status, None), "\x1b[23;0t");
+    }
+
+    #[test]
+    fn reports_progress_while_working() {
+        let mut terminal_status = TerminalStatus {
+            progress_enabled: true,
+            ..TerminalStatus::default()
+        };
+
+        assert_eq!(
+            written(&mut terminal_status, Some(&status(SessionPhase::Idle, 0))),
+            ""
+        );
+        assert_eq!(
+            written(
+                &mut terminal_status,
+                Some(&status(SessionPhase::Working, 0))
+            ),
+            "\x1b]9;4;3;0\x07"
+        );
+        assert_eq!(written(&mut terminal_status, None), "\x1b]9;4;0;0\x07");
+    }
+}
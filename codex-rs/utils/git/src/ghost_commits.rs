@@ -453,6 +453,35 @@ pub fn restore_ghost_commit_with_options(
     )
 }
 
+/// List the repository-relative paths whose contents differ between two ghost
+/// commits. An empty list means both snapshots captured the same tree.
+pub fn changed_paths_between_ghost_commits(
+    repo_path: &Path,
+    from: &GhostCommit,
+    to: &GhostCommit,
+) -> Result<Vec<PathBuf>, GitToolingError> {
+    ensure_git_repository(repo_path)?;
+
+    let repo_root = resolve_repository_root(repo_path)?;
+    let output = run_git_for_stdout_all(
+        repo_root.as_path(),
+        vec![
+            OsString::from("diff"),
+            OsString::from("--name-only"),
+            OsString::from("--no-renames"),
+            OsString::from("-z"),
+            OsString::from(from.id()),
+            OsString::from(to.id()),
+        ],
+        None,
+    )?;
+    Ok(output
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .collect())
+}
+
 /// Restore the working tree to match the given commit ID.
 pub fn restore_to_commit(repo_path: &Path, commit_id: &str) -> Result<(), GitToolingError> {
     ensure_git_repository(repo_path)?;
@@ -462,6 +491,64 @@ pub fn restore_to_commit(repo_path: &Path, commit_id: &str) -> Result<(), GitToo
     restore_to_commit_inner(repo_root.as_path(), repo_prefix.as_deref(), commit_id)
 }
 
+/// Restore only `paths` (relative to `repo_path`) to their state in the ghost
+/// commit. Paths the snapshot does not contain are deleted, so files created
+/// after the snapshot disappear again. Everything else in the working tree is
+/// left untouched.
+pub fn restore_paths_from_ghost_commit(
+    repo_path: &Path,
+    commit: &GhostCommit,
+    paths: &[PathBuf],
+) -> Result<(), GitToolingError> {
+    ensure_git_repository(repo_path)?;
+
+    let repo_root = resolve_repository_root(repo_path)?;
+    let repo_prefix = repo_subdir(repo_root.as_path(), repo_path);
+    let paths = dedupe_paths(prepare_force_include(repo_prefix.as_deref(), paths)?);
+    if paths.is_empty() {
+        return Ok(());
+    }
+
+    // Example:
+    //   git ls-tree -r --name-only -z <commit> -- <paths>
+    let mut ls_tree_args = vec![
+        OsString::from("ls-tree"),
+        OsString::from("-r"),
+        OsString::from("--name-only"),
+        OsString::from("-z"),
+        OsString::from(commit.id()),
+        OsString::from("--"),
+    ];
+    ls_tree_args.extend(paths.iter().map(|path| path.as_os_str().to_os_string()));
+    let listing = run_git_for_stdout_all(repo_root.as_path(), ls_tree_args, None)?;
+    let snapshot_paths = listing
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .collect::<Vec<_>>();
+
+    let (present, absent): (Vec<_>, Vec<_>) = paths.into_iter().partition(|path| {
+        snapshot_paths
+            .iter()
+            .any(|entry| entry.starts_with(path.as_path()))
+    });
+    for path in absent {
+        remove_path(&repo_root.join(path))?;
+    }
+    if !present.is_empty() {
+        let mut restore_args = vec![
+            OsString::from("restore"),
+            OsString::from("--source"),
+            OsString::from(commit.id()),
+            OsString::from("--worktree"),
+            OsString::from("--"),
+        ];
+        restore_args.extend(present.iter().map(|path| path.as_os_str().to_os_string()));
+        run_git_for_status(repo_root.as_path(), restore_args, None)?;
+    }
+    Ok(())
+}
+
 /// Restores the working tree and index to the given commit using `git restore`.
 /// The repository root and optional repository-relative prefix limit the restore scope.
 fn restore_to_commit_inner(
@@ -946,6 +1033,105 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    /// Lists files that differ between two snapshots, including new and deleted files.
+    fn changed_paths_between_ghost_commits_lists_differences() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        let repo = temp.path();
+        init_test_repo(repo);
+        std::fs::write(repo.join("tracked.txt"), "initial\n")?;
+        std::fs::write(repo.join("delete-me.txt"), "to be removed\n")?;
+        run_git_in(repo, &["add", "tracked.txt", "delete-me.txt"]);
+        run_git_in(
+            repo,
+            &[
+                "-c",
+                "user.name=Tester",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "-m",
+                "init",
+            ],
+        );
+
+        let options = CreateGhostCommitOptions::new(repo);
+        let before = create_ghost_commit(&options)?;
+        assert_eq!(
+            changed_paths_between_ghost_commits(repo, &before, &create_ghost_commit(&options)?)?,
+            Vec::<PathBuf>::new()
+        );
+
+        std::fs::write(repo.join("tracked.txt"), "modified\n")?;
+        std::fs::remove_file(repo.join("delete-me.txt"))?;
+        std::fs::write(repo.join("new-file.txt"), "hello\n")?;
+        let after = create_ghost_commit(&options)?;
+
+        assert_eq!(
+            changed_paths_between_ghost_commits(repo, &before, &after)?,
+            vec![
+                PathBuf::from("delete-me.txt"),
+                PathBuf::from("new-file.txt"),
+                PathBuf::from("tracked.txt"),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    /// Restores only the requested paths, deleting ones the snapshot lacks.
+    fn restore_paths_from_ghost_commit_limits_scope() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        let repo = temp.path();
+        init_test_repo(repo);
+        std::fs::write(repo.join("edited.txt"), "original\n")?;
+        std::fs::write(repo.join("renamed.txt"), "moving\n")?;
+        std::fs::write(repo.join("untouched.txt"), "before\n")?;
+        run_git_in(repo, &["add", "."]);
+        run_git_in(
+            repo,
+            &[
+                "-c",
+                "user.name=Tester",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "-m",
+                "init",
+            ],
+        );
+
+        let snapshot = create_ghost_commit(&CreateGhostCommitOptions::new(repo))?;
+        std::fs::write(repo.join("edited.txt"), "changed\n")?;
+        std::fs::rename(repo.join("renamed.txt"), repo.join("moved.txt"))?;
+        std::fs::write(repo.join("untouched.txt"), "after\n")?;
+
+        restore_paths_from_ghost_commit(
+            repo,
+            &snapshot,
+            &[
+                PathBuf::from("edited.txt"),
+                PathBuf::from("renamed.txt"),
+                PathBuf::from("moved.txt"),
+            ],
+        )?;
+
+        assert_eq!(
+            std::fs::read_to_string(repo.join("edited.txt"))?,
+            "original\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(repo.join("renamed.txt"))?,
+            "moving\n"
+        );
+        assert!(!repo.join("moved.txt").exists());
+        assert_eq!(
+            std::fs::read_to_string(repo.join("untouched.txt"))?,
+            "after\n"
+        );
+        Ok(())
+    }
+
     #[test]
     /// Verifies a ghost commit can be created and restored end to end.
     fn create_and_restore_roundtrip() -> Result<(), GitToolingError> {
@@ -23,10 +23,12 @@ pub use ghost_commits::IgnoredUntrackedFile;
 pub use ghost_commits::LargeUntrackedDir;
 pub use ghost_commits::RestoreGhostCommitOptions;
 pub use ghost_commits::capture_ghost_snapshot_report;
+pub use ghost_commits::changed_paths_between_ghost_commits;
 pub use ghost_commits::create_ghost_commit;
 pub use ghost_commits::create_ghost_commit_with_report;
 pub use ghost_commits::restore_ghost_commit;
 pub use ghost_commits::restore_ghost_commit_with_options;
+pub use ghost_commits::restore_paths_from_ghost_commit;
 pub use ghost_commits::restore_to_commit;
 pub use platform::create_symlink;
 use schemars::JsonSchema;
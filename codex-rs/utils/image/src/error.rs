@@ -17,6 +17,13 @@ pub enum ImageProcessingError {
         #[source]
         source: image::ImageError,
     },
+    #[error("failed to decode image data URL: {source}")]
+    DecodeDataUrl {
+        #[source]
+        source: image::ImageError,
+    },
+    #[error("invalid image data URL: {0}")]
+    InvalidDataUrl(String),
     #[error("failed to encode image as {format:?}: {source}")]
     Encode {
         format: ImageFormat,
@@ -32,7 +39,9 @@ impl ImageProcessingError {
             ImageProcessingError::Decode {
                 source: ImageError::Decoding(_),
                 ..
-            }
+            } | ImageProcessingError::DecodeDataUrl {
+                source: ImageError::Decoding(_),
+            } | ImageProcessingError::InvalidDataUrl(_)
         )
     }
 }
@@ -45,6 +45,42 @@ pub fn load_and_resize_to_fit(path: &Path) -> Result<EncodedImage, ImageProcessi
 
     let file_bytes = read_file_bytes(path, &path_buf)?;
 
+    resize_bytes_to_fit(file_bytes, |source| ImageProcessingError::Decode {
+        path: path_buf,
+        source,
+    })
+}
+
+/// Downscales an image given as a base64 `data:` URL the same way as
+/// [`load_and_resize_to_fit`], returning a new data URL. Other URLs are
+/// returned unchanged since their content is fetched by the provider.
+pub fn resize_data_url_to_fit(url: &str) -> Result<String, ImageProcessingError> {
+    let Some(rest) = url.strip_prefix("data:") else {
+        return Ok(url.to_string());
+    };
+    let Some((header, data)) = rest.split_once(',') else {
+        return Err(ImageProcessingError::InvalidDataUrl(
+            "missing `,` separator".to_string(),
+        ));
+    };
+    if !header.ends_with(";base64") {
+        return Err(ImageProcessingError::InvalidDataUrl(
+            "only base64-encoded data URLs are supported".to_string(),
+        ));
+    }
+    let bytes = BASE64_STANDARD
+        .decode(data.trim())
+        .map_err(|err| ImageProcessingError::InvalidDataUrl(err.to_string()))?;
+    let encoded = resize_bytes_to_fit(bytes, |source| ImageProcessingError::DecodeDataUrl {
+        source,
+    })?;
+    Ok(encoded.into_data_url())
+}
+
+fn resize_bytes_to_fit(
+    file_bytes: Vec<u8>,
+    decode_error: impl FnOnce(image::ImageError) -> ImageProcessingError,
+) -> Result<EncodedImage, ImageProcessingError> {
     let key = sha1_digest(&file_bytes);
 
     IMAGE_CACHE.get_or_try_insert_with(key, move || {
@@ -54,12 +90,7 @@ pub fn load_and_resize_to_fit(path: &Path) -> Result<EncodedImage, ImageProcessi
             _ => None,
         };
 
-        let dynamic = image::load_from_memory(&file_bytes).map_err(|source| {
-            ImageProcessingError::Decode {
-                path: path_buf.clone(),
-                source,
-            }
-        })?;
+        let dynamic = image::load_from_memory(&file_bytes).map_err(decode_error)?;
 
         let (width, height) = dynamic.dimensions();
 
@@ -249,4 +280,30 @@ mod tests {
         assert_eq!(second.height, 48);
         assert_ne!(second.bytes, first.bytes);
     }
+
+    #[test]
+    fn downscales_large_data_url_and_keeps_remote_urls() {
+        let image = ImageBuffer::from_pixel(4096, 1024, Rgba([1u8, 2, 3, 255]));
+        let mut bytes = Vec::new();
+        image
+            .write_to(&mut std::io::Cursor::new(&mut bytes), ImageFormat::Png)
+            .expect("encode png");
+        let data_url = format!("data:image/png;base64,{}", BASE64_STANDARD.encode(&bytes));
+
+        let resized = resize_data_url_to_fit(&data_url).expect("resize data url");
+        let encoded = resized
+            .strip_prefix("data:image/png;base64,")
+            .expect("png data url");
+        let decoded = image::load_from_memory(&BASE64_STANDARD.decode(encoded).expect("base64"))
+            .expect("decode resized");
+        assert!(decoded.width() <= MAX_WIDTH);
+        assert!(decoded.height() <= MAX_HEIGHT);
+
+        let remote = "https://example.com/cat.png";
+        assert_eq!(resize_data_url_to_fit(remote).expect("remote url"), remote);
+        assert!(matches!(
+            resize_data_url_to_fit("data:image/png,raw"),
+            Err(ImageProcessingError::InvalidDataUrl(_))
+        ));
+    }
 }
@@ -9,6 +9,8 @@
 //! - `kill_process_group_by_pid` targets the whole group (children/grandchildren)
 //! - `kill_process_group` targets a known process group ID directly
 //!   instead of a single PID.
+//! - `process_group_exists` checks whether anything in a group survived a
+//!   kill.
 //! - `set_parent_death_signal` (Linux only) arranges for the child to receive a
 //!   `SIGTERM` when the parent exits, and re-checks the parent PID to avoid
 //!   races during fork/exec.
@@ -140,6 +142,23 @@ pub fn kill_process_group(_process_group_id: u32) -> io::Result<()> {
     Ok(())
 }
 
+#[cfg(unix)]
+/// Whether any process, including an unreaped zombie, is still in the group.
+pub fn process_group_exists(process_group_id: u32) -> bool {
+    let pgid = process_group_id as libc::pid_t;
+    if unsafe { libc::killpg(pgid, 0) } == 0 {
+        return true;
+    }
+    // EPERM means the group exists but belongs to someone else.
+    io::Error::last_os_error().raw_os_error() != Some(libc::ESRCH)
+}
+
+#[cfg(not(unix))]
+/// Always false on non-Unix platforms.
+pub fn process_group_exists(_process_group_id: u32) -> bool {
+    false
+}
+
 #[cfg(unix)]
 /// Kill the process group for a tokio child (best-effort).
 pub fn kill_child_process_group(child: &mut Child) -> io::Result<()> {
@@ -4,6 +4,7 @@ use anyhow::Context;
 use anyhow::Result;
 use codex_windows_sandbox::allow_null_device;
 use codex_windows_sandbox::convert_string_sid_to_sid;
+use codex_windows_sandbox::create_job_object;
 use codex_windows_sandbox::create_process_as_user;
 use codex_windows_sandbox::create_readonly_token_with_cap_from;
 use codex_windows_sandbox::create_workspace_write_token_with_cap_from;
@@ -12,6 +13,7 @@ use codex_windows_sandbox::hide_current_user_profile_dir;
 use codex_windows_sandbox::log_note;
 use codex_windows_sandbox::parse_policy;
 use codex_windows_sandbox::to_wide;
+use codex_windows_sandbox::JobLimits;
 use codex_windows_sandbox::SandboxPolicy;
 use serde::Deserialize;
 use std::collections::HashMap;
@@ -25,11 +27,7 @@ use windows_sys::Win32::Storage::FileSystem::FILE_GENERIC_READ;
 use windows_sys::Win32::Storage::FileSystem::FILE_GENERIC_WRITE;
 use windows_sys::Win32::Storage::FileSystem::OPEN_EXISTING;
 use windows_sys::Win32::System::JobObjects::AssignProcessToJobObject;
-use windows_sys::Win32::System::JobObjects::CreateJobObjectW;
-use windows_sys::Win32::System::JobObjects::JobObjectExtendedLimitInformation;
-use windows_sys::Win32::System::JobObjects::SetInformationJobObject;
-use windows_sys::Win32::System::JobObjects::JOBOBJECT_EXTENDED_LIMIT_INFORMATION;
-use windows_sys::Win32::System::JobObjects::JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+use windows_sys::Win32::System::Threading::ResumeThread;
 use windows_sys::Win32::System::Threading::TerminateProcess;
 use windows_sys::Win32::System::Threading::WaitForSingleObject;
 use windows_sys::Win32::System::Threading::INFINITE;
@@ -59,25 +57,6 @@ struct RunnerRequest {
 
 const WAIT_TIMEOUT: u32 = 0x0000_0102;
 
-unsafe fn create_job_kill_on_close() -> Result<HANDLE> {
-    let h = CreateJobObjectW(std::ptr::null_mut(), std::ptr::null());
-    if h == 0 {
-        return Err(anyhow::anyhow!("CreateJobObjectW failed"));
-    }
-    let mut limits: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
-    limits.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
-    let ok = SetInformationJobObject(
-        h,
-        JobObjectExtendedLimitInformation,
-        &mut limits as *mut _ as *mut _,
-        std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
-    );
-    if ok == 0 {
-        return Err(anyhow::anyhow!("SetInformationJobObject failed"));
-    }
-    Ok(h)
-}
-
 pub fn main() -> Result<()> {
     let mut input = String::new();
     let mut args = std::env::args().skip(1);
@@ -191,7 +170,9 @@ pub fn main() -> Result<()> {
         log_dir,
     );
 
-    // Build command and env, spawn with CreateProcessAsUserW.
+    // Build command and env, spawn with CreateProcessAsUserW. The command
+    // starts suspended so that it cannot start children before it is in the
+    // job.
     let spawn_result = unsafe {
         create_process_as_user(
             h_token,
@@ -200,6 +181,7 @@ pub fn main() -> Result<()> {
             &req.env_map,
             Some(&req.codex_home),
             stdio,
+            true,
         )
     };
     let (proc_info, _si) = match spawn_result {
@@ -216,12 +198,37 @@ pub fn main() -> Result<()> {
         }
     };
 
-    // Optional job kill on close.
-    let h_job = unsafe { create_job_kill_on_close().ok() };
-    if let Some(job) = h_job {
+    // Job kill on close, plus the policy's resource limits. Without limits the
+    // job is best-effort; with them, a command that escapes the job is killed.
+    let job_limits = JobLimits::from_policy(&policy);
+    let h_job = match unsafe { create_job_object(&job_limits, true) } {
+        Ok(job) => Some(job),
+        Err(e) => {
+            log_note(&format!("runner: job object failed: {e:?}"), log_dir);
+            None
+        }
+    };
+    let assigned =
+        h_job.is_some_and(|job| unsafe { AssignProcessToJobObject(job, proc_info.hProcess) } != 0);
+    if !assigned && !job_limits.is_empty() {
         unsafe {
-            let _ = AssignProcessToJobObject(job, proc_info.hProcess);
+            let _ = TerminateProcess(proc_info.hProcess, 1);
+            CloseHandle(proc_info.hThread);
+            CloseHandle(proc_info.hProcess);
+            CloseHandle(h_stdin);
+            CloseHandle(h_stdout);
+            CloseHandle(h_stderr);
+            CloseHandle(h_token);
+            if let Some(job) = h_job {
+                CloseHandle(job);
+            }
         }
+        return Err(anyhow::anyhow!(
+            "runner: could not apply job limits {job_limits:?}"
+        ));
+    }
+    unsafe {
+        ResumeThread(proc_info.hThread);
     }
 
     // Wait for process.
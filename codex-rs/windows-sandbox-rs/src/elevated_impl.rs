@@ -4,7 +4,6 @@ mod windows_impl {
     use crate::allow::AllowDenyPaths;
     use crate::cap::load_or_create_cap_sids;
     use crate::env::ensure_non_interactive_pager;
-    use crate::env::inherit_path_env;
     use crate::env::normalize_null_device_env;
     use crate::identity::require_logon_sandbox_creds;
     use crate::logging::log_failure;
@@ -220,7 +219,6 @@ mod windows_impl {
         let policy = parse_policy(policy_json_or_preset)?;
         normalize_null_device_env(&mut env_map);
         ensure_non_interactive_pager(&mut env_map);
-        inherit_path_env(&mut env_map);
         inject_git_safe_directory(&mut env_map, cwd, None);
         let current_dir = cwd.to_path_buf();
         // Use a temp-based log dir that the sandbox user can write.
@@ -469,6 +467,9 @@ mod windows_impl {
                 network_access,
                 exclude_tmpdir_env_var: false,
                 exclude_slash_tmp: false,
+                max_memory_bytes: None,
+                cpu_rate_percent: None,
+                max_processes: None,
             }
         }
 
@@ -28,20 +28,6 @@ pub fn ensure_non_interactive_pager(env_map: &mut HashMap<String, String>) {
     env_map.entry("LESS".into()).or_insert_with(|| "".into());
 }
 
-// Keep PATH and PATHEXT stable for callers that rely on inheriting the parent process env.
-pub fn inherit_path_env(env_map: &mut HashMap<String, String>) {
-    if !env_map.contains_key("PATH") {
-        if let Ok(path) = env::var("PATH") {
-            env_map.insert("PATH".into(), path);
-        }
-    }
-    if !env_map.contains_key("PATHEXT") {
-        if let Ok(pathext) = env::var("PATHEXT") {
-            env_map.insert("PATHEXT".into(), pathext);
-        }
-    }
-}
-
 fn prepend_path(env_map: &mut HashMap<String, String>, prefix: &str) {
     let existing = env_map
         .get("PATH")
@@ -0,0 +1,153 @@
+use crate::policy::SandboxPolicy;
+use anyhow::Result;
+use windows_sys::Win32::Foundation::CloseHandle;
+use windows_sys::Win32::Foundation::GetLastError;
+use windows_sys::Win32::Foundation::HANDLE;
+use windows_sys::Win32::System::JobObjects::CreateJobObjectW;
+use windows_sys::Win32::System::JobObjects::JobObjectCpuRateControlInformation;
+use windows_sys::Win32::System::JobObjects::JobObjectExtendedLimitInformation;
+use windows_sys::Win32::System::JobObjects::SetInformationJobObject;
+use windows_sys::Win32::System::JobObjects::JOBOBJECT_CPU_RATE_CONTROL_INFORMATION;
+use windows_sys::Win32::System::JobObjects::JOBOBJECT_EXTENDED_LIMIT_INFORMATION;
+use windows_sys::Win32::System::JobObjects::JOB_OBJECT_CPU_RATE_CONTROL_ENABLE;
+use windows_sys::Win32::System::JobObjects::JOB_OBJECT_CPU_RATE_CONTROL_HARD_CAP;
+use windows_sys::Win32::System::JobObjects::JOB_OBJECT_LIMIT_ACTIVE_PROCESS;
+use windows_sys::Win32::System::JobObjects::JOB_OBJECT_LIMIT_JOB_MEMORY;
+use windows_sys::Win32::System::JobObjects::JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+
+/// Resource limits applied to the job object that holds a sandboxed command
+/// and every process it starts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JobLimits {
+    pub max_memory_bytes: Option<u64>,
+    pub cpu_rate_percent: Option<u8>,
+    pub max_processes: Option<u32>,
+}
+
+impl JobLimits {
+    pub fn from_policy(policy: &SandboxPolicy) -> Self {
+        match policy {
+            SandboxPolicy::WorkspaceWrite {
+                max_memory_bytes,
+                cpu_rate_percent,
+                max_processes,
+                ..
+            } => Self {
+                max_memory_bytes: *max_memory_bytes,
+                cpu_rate_percent: *cpu_rate_percent,
+                max_processes: *max_processes,
+            },
+            _ => Self::default(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// CPU rate in the unit `JOBOBJECT_CPU_RATE_CONTROL_INFORMATION` expects:
+/// hundredths of a percent of all processors, between 1 and 10000.
+fn cpu_rate(percent: u8) -> u32 {
+    u32::from(percent.clamp(1, 100)) * 100
+}
+
+/// Creates a job object enforcing `limits`. With `kill_on_close`, every
+/// process in the job is terminated once the last handle to it is closed.
+pub unsafe fn create_job_object(limits: &JobLimits, kill_on_close: bool) -> Result<HANDLE> {
+    let h = CreateJobObjectW(std::ptr::null_mut(), std::ptr::null());
+    if h == 0 {
+        return Err(anyhow::anyhow!(
+            "CreateJobObjectW failed: {}",
+            GetLastError()
+        ));
+    }
+    if let Err(err) = apply_limits(h, limits, kill_on_close) {
+        CloseHandle(h);
+        return Err(err);
+    }
+    Ok(h)
+}
+
+unsafe fn apply_limits(h: HANDLE, limits: &JobLimits, kill_on_close: bool) -> Result<()> {
+    let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+    if kill_on_close {
+        info.BasicLimitInformation.LimitFlags |= JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+    }
+    if let Some(bytes) = limits.max_memory_bytes {
+        info.BasicLimitInformation.LimitFlags |= JOB_OBJECT_LIMIT_JOB_MEMORY;
+        info.JobMemoryLimit = usize::try_from(bytes).unwrap_or(usize::MAX);
+    }
+    if let Some(count) = limits.max_processes {
+        info.BasicLimitInformation.LimitFlags |= JOB_OBJECT_LIMIT_ACTIVE_PROCESS;
+        info.BasicLimitInformation.ActiveProcessLimit = count;
+    }
+    if info.BasicLimitInformation.LimitFlags != 0 {
+        let ok = SetInformationJobObject(
+            h,
+            JobObjectExtendedLimitInformation,
+            &mut info as *mut _ as *mut _,
+            std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+        );
+        if ok == 0 {
+            return Err(anyhow::anyhow!(
+                "SetInformationJobObject(limits) failed: {}",
+                GetLastError()
+            ));
+        }
+    }
+    if let Some(percent) = limits.cpu_rate_percent {
+        let mut cpu: JOBOBJECT_CPU_RATE_CONTROL_INFORMATION = std::mem::zeroed();
+        cpu.ControlFlags =
+            JOB_OBJECT_CPU_RATE_CONTROL_ENABLE | JOB_OBJECT_CPU_RATE_CONTROL_HARD_CAP;
+        cpu.Anonymous.CpuRate = cpu_rate(percent);
+        let ok = SetInformationJobObject(
+            h,
+            JobObjectCpuRateControlInformation,
+            &mut cpu as *mut _ as *mut _,
+            std::mem::size_of::<JOBOBJECT_CPU_RATE_CONTROL_INFORMATION>() as u32,
+        );
+        if ok == 0 {
+            return Err(anyhow::anyhow!(
+                "SetInformationJobObject(cpu rate) failed: {}",
+                GetLastError()
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn cpu_rate_is_clamped_to_hundredths_of_a_percent() {
+        assert_eq!(cpu_rate(0), 100);
+        assert_eq!(cpu_rate(25), 2500);
+        assert_eq!(cpu_rate(200), 10000);
+    }
+
+    #[test]
+    fn limits_come_from_workspace_write_only() {
+        let policy = SandboxPolicy::WorkspaceWrite {
+            writable_roots: Vec::new(),
+            network_access: false,
+            exclude_tmpdir_env_var: false,
+            exclude_slash_tmp: false,
+            max_memory_bytes: Some(1 << 30),
+            cpu_rate_percent: Some(50),
+            max_processes: None,
+        };
+        assert_eq!(
+            JobLimits::from_policy(&policy),
+            JobLimits {
+                max_memory_bytes: Some(1 << 30),
+                cpu_rate_percent: Some(50),
+                max_processes: None,
+            }
+        );
+        assert!(JobLimits::from_policy(&SandboxPolicy::ReadOnly).is_empty());
+    }
+}
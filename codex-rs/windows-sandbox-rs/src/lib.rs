@@ -5,7 +5,7 @@ macro_rules! windows_modules {
 }
 
 windows_modules!(
-    acl, allow, audit, cap, dpapi, env, hide_users, identity, logging, policy, process, token,
+    acl, allow, audit, cap, dpapi, env, hide_users, identity, job, logging, policy, process, token,
     winutil
 );
 
@@ -47,6 +47,10 @@ pub use identity::require_logon_sandbox_creds;
 #[cfg(target_os = "windows")]
 pub use identity::sandbox_setup_is_complete;
 #[cfg(target_os = "windows")]
+pub use job::create_job_object;
+#[cfg(target_os = "windows")]
+pub use job::JobLimits;
+#[cfg(target_os = "windows")]
 pub use logging::log_note;
 #[cfg(target_os = "windows")]
 pub use logging::LOG_FILE_NAME;
@@ -102,6 +106,8 @@ mod windows_impl {
     use super::env::apply_no_network_to_env;
     use super::env::ensure_non_interactive_pager;
     use super::env::normalize_null_device_env;
+    use super::job::create_job_object;
+    use super::job::JobLimits;
     use super::logging::debug_log;
     use super::logging::log_failure;
     use super::logging::log_start;
@@ -125,10 +131,14 @@ mod windows_impl {
     use windows_sys::Win32::Foundation::SetHandleInformation;
     use windows_sys::Win32::Foundation::HANDLE;
     use windows_sys::Win32::Foundation::HANDLE_FLAG_INHERIT;
+    use windows_sys::Win32::System::JobObjects::AssignProcessToJobObject;
     use windows_sys::Win32::System::Pipes::CreatePipe;
     use windows_sys::Win32::System::Threading::CreateProcessAsUserW;
     use windows_sys::Win32::System::Threading::GetExitCodeProcess;
+    use windows_sys::Win32::System::Threading::ResumeThread;
+    use windows_sys::Win32::System::Threading::TerminateProcess;
     use windows_sys::Win32::System::Threading::WaitForSingleObject;
+    use windows_sys::Win32::System::Threading::CREATE_SUSPENDED;
     use windows_sys::Win32::System::Threading::CREATE_UNICODE_ENVIRONMENT;
     use windows_sys::Win32::System::Threading::INFINITE;
     use windows_sys::Win32::System::Threading::PROCESS_INFORMATION;
@@ -204,6 +214,7 @@ mod windows_impl {
         let logs_base_dir = Some(sandbox_base.as_path());
         log_start(&command, logs_base_dir);
         let is_workspace_write = matches!(&policy, SandboxPolicy::WorkspaceWrite { .. });
+        let job_limits = JobLimits::from_policy(&policy);
 
         if matches!(
             &policy,
@@ -269,6 +280,29 @@ mod windows_impl {
             allow_null_device(psid_to_use);
         }
 
+        // Resource limits live on a job object; the command starts suspended
+        // so that it and every process it spawns are inside the job. Closing
+        // the job kills whatever is left, so children cannot outlive the
+        // command and keep using the budget.
+        let h_job = if job_limits.is_empty() {
+            None
+        } else {
+            match unsafe { create_job_object(&job_limits, true) } {
+                Ok(job) => Some(job),
+                Err(err) => {
+                    unsafe {
+                        CloseHandle(h_token);
+                    }
+                    return Err(err);
+                }
+            }
+        };
+        let creation_flags = if h_job.is_some() {
+            CREATE_UNICODE_ENVIRONMENT | CREATE_SUSPENDED
+        } else {
+            CREATE_UNICODE_ENVIRONMENT
+        };
+
         let (stdin_pair, stdout_pair, stderr_pair) = unsafe { setup_stdio_pipes()? };
         let ((in_r, in_w), (out_r, out_w), (err_r, err_w)) = (stdin_pair, stdout_pair, stderr_pair);
         let mut si: STARTUPINFOW = unsafe { std::mem::zeroed() };
@@ -296,7 +330,7 @@ mod windows_impl {
                 ptr::null_mut(),
                 ptr::null_mut(),
                 1,
-                CREATE_UNICODE_ENVIRONMENT,
+                creation_flags,
                 env_block.as_ptr() as *mut c_void,
                 to_wide(cwd).as_ptr(),
                 &si,
@@ -323,10 +357,42 @@ mod windows_impl {
                 CloseHandle(err_r);
                 CloseHandle(err_w);
                 CloseHandle(h_token);
+                if let Some(job) = h_job {
+                    CloseHandle(job);
+                }
             }
             return Err(anyhow::anyhow!("CreateProcessAsUserW failed: {}", err));
         }
 
+        if let Some(job) = h_job {
+            unsafe {
+                if AssignProcessToJobObject(job, pi.hProcess) == 0 {
+                    let err = GetLastError() as i32;
+                    TerminateProcess(pi.hProcess, 1);
+                    for h in [
+                        pi.hThread,
+                        pi.hProcess,
+                        in_r,
+                        in_w,
+                        out_r,
+                        out_w,
+                        err_r,
+                        err_w,
+                    ] {
+                        CloseHandle(h);
+                    }
+                    CloseHandle(h_token);
+                    CloseHandle(job);
+                    return Err(anyhow::anyhow!(
+                        "AssignProcessToJobObject failed: {} ({})",
+                        err,
+                        format_last_error(err)
+                    ));
+                }
+                ResumeThread(pi.hThread);
+            }
+        }
+
         unsafe {
             CloseHandle(in_r);
             // Close the parent's stdin write end so the child sees EOF immediately.
@@ -390,7 +456,7 @@ mod windows_impl {
             }
         } else {
             unsafe {
-                windows_sys::Win32::System::Threading::TerminateProcess(pi.hProcess, 1);
+                TerminateProcess(pi.hProcess, 1);
             }
         }
 
@@ -402,6 +468,9 @@ mod windows_impl {
                 CloseHandle(pi.hProcess);
             }
             CloseHandle(h_token);
+            if let Some(job) = h_job {
+                CloseHandle(job);
+            }
         }
         let _ = t_out.join();
         let _ = t_err.join();
@@ -446,6 +515,9 @@ mod windows_impl {
                 network_access,
                 exclude_tmpdir_env_var: false,
                 exclude_slash_tmp: false,
+                max_memory_bytes: None,
+                cpu_rate_percent: None,
+                max_processes: None,
             }
         }
 
@@ -17,6 +17,7 @@ use windows_sys::Win32::System::Console::STD_ERROR_HANDLE;
 use windows_sys::Win32::System::Console::STD_INPUT_HANDLE;
 use windows_sys::Win32::System::Console::STD_OUTPUT_HANDLE;
 use windows_sys::Win32::System::Threading::CreateProcessAsUserW;
+use windows_sys::Win32::System::Threading::CREATE_SUSPENDED;
 use windows_sys::Win32::System::Threading::CREATE_UNICODE_ENVIRONMENT;
 use windows_sys::Win32::System::Threading::PROCESS_INFORMATION;
 use windows_sys::Win32::System::Threading::STARTF_USESTDHANDLES;
@@ -68,6 +69,7 @@ pub unsafe fn create_process_as_user(
     env_map: &HashMap<String, String>,
     logs_base_dir: Option<&Path>,
     stdio: Option<(HANDLE, HANDLE, HANDLE)>,
+    suspended: bool,
 ) -> Result<(PROCESS_INFORMATION, STARTUPINFOW)> {
     let cmdline_str = argv
         .iter()
@@ -114,7 +116,11 @@ pub unsafe fn create_process_as_user(
         std::ptr::null_mut(),
         std::ptr::null_mut(),
         inherit_handles as i32,
-        CREATE_UNICODE_ENVIRONMENT,
+        if suspended {
+            CREATE_UNICODE_ENVIRONMENT | CREATE_SUSPENDED
+        } else {
+            CREATE_UNICODE_ENVIRONMENT
+        },
         env_block.as_ptr() as *mut c_void,
         to_wide(cwd).as_ptr(),
         &si,
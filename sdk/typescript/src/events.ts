@@ -68,6 +68,13 @@ export type ThreadErrorEvent = {
   message: string;
 };
 
+/** Emitted in `--watch` mode when file changes trigger a re-run of the prompt. */
+export type WatchTriggeredEvent = {
+  type: "watch.triggered";
+  /** Changed files, relative to the working directory. */
+  paths: string[];
+};
+
 /** Top-level JSONL events emitted by codex exec. */
 export type ThreadEvent =
   | ThreadStartedEvent
@@ -77,4 +84,5 @@ export type ThreadEvent =
   | ItemStartedEvent
   | ItemUpdatedEvent
   | ItemCompletedEvent
-  | ThreadErrorEvent;
+  | ThreadErrorEvent
+  | WatchTriggeredEvent;
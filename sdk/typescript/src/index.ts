@@ -10,6 +10,7 @@ export type {
   ThreadError,
   ThreadErrorEvent,
   Usage,
+  WatchTriggeredEvent,
 } from "./events";
 export type {
   ThreadItem,